@@ -313,7 +313,9 @@ val cargoNdkBuild = tasks.register<Exec>("cargoNdkBuild") {
         "-o", cargoNdkOutput.absolutePath,
         "build",
         "--manifest-path", rootProject.layout.projectDirectory.file("Cargo.toml").asFile.absolutePath,
-        "--release"
+        "--release",
+        // Route proxy diagnostics to logcat
+        "--features", "letterbox-proxy/android_logger"
     )
     onlyIf { rustBuildEnabled }
 }
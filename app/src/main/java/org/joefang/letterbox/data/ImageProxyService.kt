@@ -9,6 +9,7 @@ import org.joefang.letterbox.ffi.proxy.HttpFetchResponse
 import org.joefang.letterbox.ffi.proxy.ImageResponse
 import org.joefang.letterbox.ffi.proxy.ProxyException
 import org.joefang.letterbox.ffi.proxy.ProxyStatus
+import org.joefang.letterbox.ffi.proxy.RequestPriority
 import org.joefang.letterbox.ffi.proxy.TlsSelfTestOutcome
 import org.joefang.letterbox.ffi.proxy.UpdateResult
 import org.joefang.letterbox.ffi.proxy.WarpDiagnostics
@@ -303,7 +304,8 @@ class ImageProxyService private constructor(private val context: Context) {
         }
 
         try {
-            val results = proxyFetchImagesBatch(urls, maxConcurrent)
+            val results = proxyFetchImagesBatch(urls, maxConcurrent, RequestPriority.VISIBLE)
+                .use { it.results() }
             results.associate { result ->
                 val fetchResult = if (result.success && result.response != null) {
                     ImageFetchResult.Success(
@@ -637,55 +637,147 @@ internal object IntegrityCheckingUniffiLib {
         uniffiCheckApiChecksums(this)
     }
     external fun uniffi_letterbox_core_checksum_func_extract_remote_images(
-    ): Short
+    ): Int
+    external fun uniffi_letterbox_core_checksum_func_plain_text_to_html(
+    ): Int
     external fun uniffi_letterbox_core_checksum_func_parse_eml(
-    ): Short
+    ): Int
+    external fun uniffi_letterbox_core_checksum_func_parse_eml_file(
+    ): Int
     external fun uniffi_letterbox_core_checksum_func_parse_eml_from_path(
-    ): Short
-    external fun uniffi_letterbox_core_checksum_method_emailhandle_attachment_count(
-    ): Short
+    ): Int
+    external fun uniffi_letterbox_core_checksum_func_parse_eml_headers(
+    ): Int
     external fun uniffi_letterbox_core_checksum_method_emailhandle_body_html(
-    ): Short
+    ): Int
     external fun uniffi_letterbox_core_checksum_method_emailhandle_body_preview(
-    ): Short
+    ): Int
     external fun uniffi_letterbox_core_checksum_method_emailhandle_body_text(
-    ): Short
+    ): Int
     external fun uniffi_letterbox_core_checksum_method_emailhandle_cc(
-    ): Short
+    ): Int
     external fun uniffi_letterbox_core_checksum_method_emailhandle_date(
-    ): Short
+    ): Int
     external fun uniffi_letterbox_core_checksum_method_emailhandle_date_timestamp(
-    ): Short
+    ): Int
     external fun uniffi_letterbox_core_checksum_method_emailhandle_from(
-    ): Short
-    external fun uniffi_letterbox_core_checksum_method_emailhandle_get_attachment_content(
-    ): Short
-    external fun uniffi_letterbox_core_checksum_method_emailhandle_get_attachments(
-    ): Short
+    ): Int
     external fun uniffi_letterbox_core_checksum_method_emailhandle_get_resource(
-    ): Short
+    ): Int
     external fun uniffi_letterbox_core_checksum_method_emailhandle_get_resource_content_type(
-    ): Short
+    ): Int
     external fun uniffi_letterbox_core_checksum_method_emailhandle_get_resource_ids(
-    ): Short
+    ): Int
     external fun uniffi_letterbox_core_checksum_method_emailhandle_get_resource_metadata(
-    ): Short
+    ): Int
     external fun uniffi_letterbox_core_checksum_method_emailhandle_message_id(
-    ): Short
-    external fun uniffi_letterbox_core_checksum_method_emailhandle_recipient_info(
-    ): Short
+    ): Int
     external fun uniffi_letterbox_core_checksum_method_emailhandle_reply_to(
-    ): Short
-    external fun uniffi_letterbox_core_checksum_method_emailhandle_sender_info(
-    ): Short
+    ): Int
     external fun uniffi_letterbox_core_checksum_method_emailhandle_subject(
-    ): Short
+    ): Int
     external fun uniffi_letterbox_core_checksum_method_emailhandle_to(
-    ): Short
-    external fun uniffi_letterbox_core_checksum_method_emailhandle_write_attachment_to_path(
-    ): Short
+    ): Int
     external fun uniffi_letterbox_core_checksum_method_emailhandle_write_resource_to_path(
-    ): Short
+    ): Int
+    external fun uniffi_letterbox_core_checksum_method_emailhandle_attachment_count(
+    ): Int
+    external fun uniffi_letterbox_core_checksum_method_emailhandle_get_attachment_content(
+    ): Int
+    external fun uniffi_letterbox_core_checksum_method_emailhandle_get_attachments(
+    ): Int
+    external fun uniffi_letterbox_core_checksum_method_emailhandle_read_attachment_chunk(
+    ): Int
+    external fun uniffi_letterbox_core_checksum_method_emailhandle_save_attachment(
+    ): Int
+    external fun uniffi_letterbox_core_checksum_method_emailhandle_write_attachment_to_path(
+    ): Int
+    external fun uniffi_letterbox_core_checksum_method_emailhandle_body_html_dark_mode(
+    ): Int
+    external fun uniffi_letterbox_core_checksum_method_emailhandle_body_html_with_cid_scheme(
+    ): Int
+    external fun uniffi_letterbox_core_checksum_method_emailhandle_body_text_derived(
+    ): Int
+    external fun uniffi_letterbox_core_checksum_method_emailhandle_remote_resources(
+    ): Int
+    external fun uniffi_letterbox_core_checksum_method_emailhandle_rewrite_remote_resources(
+    ): Int
+    external fun uniffi_letterbox_core_checksum_method_emailhandle_sanitized_body_html(
+    ): Int
+    external fun uniffi_letterbox_core_checksum_method_emailhandle_strip_attachments(
+    ): Int
+    external fun uniffi_letterbox_core_checksum_method_emailhandle_to_builder(
+    ): Int
+    external fun uniffi_letterbox_core_checksum_method_emailhandle_to_eml_bytes(
+    ): Int
+    external fun uniffi_letterbox_core_checksum_method_emailhandle_all_headers(
+    ): Int
+    external fun uniffi_letterbox_core_checksum_method_emailhandle_bcc_addresses(
+    ): Int
+    external fun uniffi_letterbox_core_checksum_method_emailhandle_cc_addresses(
+    ): Int
+    external fun uniffi_letterbox_core_checksum_method_emailhandle_from_addresses(
+    ): Int
+    external fun uniffi_letterbox_core_checksum_method_emailhandle_get_header(
+    ): Int
+    external fun uniffi_letterbox_core_checksum_method_emailhandle_get_headers(
+    ): Int
+    external fun uniffi_letterbox_core_checksum_method_emailhandle_in_reply_to(
+    ): Int
+    external fun uniffi_letterbox_core_checksum_method_emailhandle_recipient_info(
+    ): Int
+    external fun uniffi_letterbox_core_checksum_method_emailhandle_references(
+    ): Int
+    external fun uniffi_letterbox_core_checksum_method_emailhandle_reply_to_addresses(
+    ): Int
+    external fun uniffi_letterbox_core_checksum_method_emailhandle_sender_info(
+    ): Int
+    external fun uniffi_letterbox_core_checksum_method_emailhandle_thread_message(
+    ): Int
+    external fun uniffi_letterbox_core_checksum_method_emailhandle_to_addresses(
+    ): Int
+    external fun uniffi_letterbox_core_checksum_method_messagebuilder_add_attachment(
+    ): Int
+    external fun uniffi_letterbox_core_checksum_method_messagebuilder_add_header(
+    ): Int
+    external fun uniffi_letterbox_core_checksum_method_messagebuilder_add_inline(
+    ): Int
+    external fun uniffi_letterbox_core_checksum_method_messagebuilder_set_bcc(
+    ): Int
+    external fun uniffi_letterbox_core_checksum_method_messagebuilder_set_cc(
+    ): Int
+    external fun uniffi_letterbox_core_checksum_method_messagebuilder_set_date_timestamp(
+    ): Int
+    external fun uniffi_letterbox_core_checksum_method_messagebuilder_set_from(
+    ): Int
+    external fun uniffi_letterbox_core_checksum_method_messagebuilder_set_html_body(
+    ): Int
+    external fun uniffi_letterbox_core_checksum_method_messagebuilder_set_in_reply_to(
+    ): Int
+    external fun uniffi_letterbox_core_checksum_method_messagebuilder_set_message_id(
+    ): Int
+    external fun uniffi_letterbox_core_checksum_method_messagebuilder_set_references(
+    ): Int
+    external fun uniffi_letterbox_core_checksum_method_messagebuilder_set_reply_to(
+    ): Int
+    external fun uniffi_letterbox_core_checksum_method_messagebuilder_set_subject(
+    ): Int
+    external fun uniffi_letterbox_core_checksum_method_messagebuilder_set_text_body(
+    ): Int
+    external fun uniffi_letterbox_core_checksum_method_messagebuilder_set_to(
+    ): Int
+    external fun uniffi_letterbox_core_checksum_method_messagebuilder_to_eml_bytes(
+    ): Int
+    external fun uniffi_letterbox_core_checksum_method_threadbuilder_add(
+    ): Int
+    external fun uniffi_letterbox_core_checksum_method_threadbuilder_add_all(
+    ): Int
+    external fun uniffi_letterbox_core_checksum_method_threadbuilder_build(
+    ): Int
+    external fun uniffi_letterbox_core_checksum_constructor_messagebuilder_new(
+    ): Int
+    external fun uniffi_letterbox_core_checksum_constructor_threadbuilder_new(
+    ): Int
     external fun ffi_letterbox_core_uniffi_contract_version(
     ): Int
 
@@ -705,165 +797,265 @@ internal object UniffiLib {
         
     }
     external fun uniffi_letterbox_core_fn_clone_emailhandle(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
-    ): Long
-    external fun uniffi_letterbox_core_fn_free_emailhandle(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
-    ): Unit
-    external fun uniffi_letterbox_core_fn_method_emailhandle_attachment_count(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
-    ): Int
-    external fun uniffi_letterbox_core_fn_method_emailhandle_body_html(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
-    ): RustBuffer.ByValue
-    external fun uniffi_letterbox_core_fn_method_emailhandle_body_preview(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
-    ): RustBuffer.ByValue
-    external fun uniffi_letterbox_core_fn_method_emailhandle_body_text(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
-    ): RustBuffer.ByValue
-    external fun uniffi_letterbox_core_fn_method_emailhandle_cc(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
-    ): RustBuffer.ByValue
-    external fun uniffi_letterbox_core_fn_method_emailhandle_date(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
-    ): RustBuffer.ByValue
-    external fun uniffi_letterbox_core_fn_method_emailhandle_date_timestamp(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
-    ): Long
-    external fun uniffi_letterbox_core_fn_method_emailhandle_from(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
-    ): RustBuffer.ByValue
-    external fun uniffi_letterbox_core_fn_method_emailhandle_get_attachment_content(`ptr`: Long,`index`: Int,uniffi_out_err: UniffiRustCallStatus, 
-    ): RustBuffer.ByValue
-    external fun uniffi_letterbox_core_fn_method_emailhandle_get_attachments(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
-    ): RustBuffer.ByValue
-    external fun uniffi_letterbox_core_fn_method_emailhandle_get_resource(`ptr`: Long,`cid`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
-    ): RustBuffer.ByValue
-    external fun uniffi_letterbox_core_fn_method_emailhandle_get_resource_content_type(`ptr`: Long,`cid`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
-    ): RustBuffer.ByValue
-    external fun uniffi_letterbox_core_fn_method_emailhandle_get_resource_ids(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
-    ): RustBuffer.ByValue
-    external fun uniffi_letterbox_core_fn_method_emailhandle_get_resource_metadata(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
-    ): RustBuffer.ByValue
-    external fun uniffi_letterbox_core_fn_method_emailhandle_message_id(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
-    ): RustBuffer.ByValue
-    external fun uniffi_letterbox_core_fn_method_emailhandle_recipient_info(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
-    ): RustBuffer.ByValue
-    external fun uniffi_letterbox_core_fn_method_emailhandle_reply_to(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
-    ): RustBuffer.ByValue
-    external fun uniffi_letterbox_core_fn_method_emailhandle_sender_info(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
-    ): RustBuffer.ByValue
-    external fun uniffi_letterbox_core_fn_method_emailhandle_subject(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
-    ): RustBuffer.ByValue
-    external fun uniffi_letterbox_core_fn_method_emailhandle_to(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
-    ): RustBuffer.ByValue
-    external fun uniffi_letterbox_core_fn_method_emailhandle_write_attachment_to_path(`ptr`: Long,`index`: Int,`path`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
-    ): Byte
-    external fun uniffi_letterbox_core_fn_method_emailhandle_write_resource_to_path(`ptr`: Long,`cid`: RustBuffer.ByValue,`path`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
-    ): Byte
-    external fun uniffi_letterbox_core_fn_func_extract_remote_images(`html`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
-    ): RustBuffer.ByValue
-    external fun uniffi_letterbox_core_fn_func_parse_eml(`data`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
-    ): Long
-    external fun uniffi_letterbox_core_fn_func_parse_eml_from_path(`path`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
-    ): Long
-    external fun ffi_letterbox_core_rustbuffer_alloc(`size`: Long,uniffi_out_err: UniffiRustCallStatus, 
-    ): RustBuffer.ByValue
-    external fun ffi_letterbox_core_rustbuffer_from_bytes(`bytes`: ForeignBytes.ByValue,uniffi_out_err: UniffiRustCallStatus, 
-    ): RustBuffer.ByValue
-    external fun ffi_letterbox_core_rustbuffer_free(`buf`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
-    ): Unit
-    external fun ffi_letterbox_core_rustbuffer_reserve(`buf`: RustBuffer.ByValue,`additional`: Long,uniffi_out_err: UniffiRustCallStatus, 
-    ): RustBuffer.ByValue
-    external fun ffi_letterbox_core_rust_future_poll_u8(`handle`: Long,`callback`: UniffiRustFutureContinuationCallback,`callbackData`: Long,
-    ): Unit
-    external fun ffi_letterbox_core_rust_future_cancel_u8(`handle`: Long,
-    ): Unit
-    external fun ffi_letterbox_core_rust_future_free_u8(`handle`: Long,
-    ): Unit
-    external fun ffi_letterbox_core_rust_future_complete_u8(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
-    ): Byte
-    external fun ffi_letterbox_core_rust_future_poll_i8(`handle`: Long,`callback`: UniffiRustFutureContinuationCallback,`callbackData`: Long,
-    ): Unit
-    external fun ffi_letterbox_core_rust_future_cancel_i8(`handle`: Long,
-    ): Unit
-    external fun ffi_letterbox_core_rust_future_free_i8(`handle`: Long,
-    ): Unit
-    external fun ffi_letterbox_core_rust_future_complete_i8(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
-    ): Byte
-    external fun ffi_letterbox_core_rust_future_poll_u16(`handle`: Long,`callback`: UniffiRustFutureContinuationCallback,`callbackData`: Long,
-    ): Unit
-    external fun ffi_letterbox_core_rust_future_cancel_u16(`handle`: Long,
-    ): Unit
-    external fun ffi_letterbox_core_rust_future_free_u16(`handle`: Long,
-    ): Unit
-    external fun ffi_letterbox_core_rust_future_complete_u16(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
-    ): Short
-    external fun ffi_letterbox_core_rust_future_poll_i16(`handle`: Long,`callback`: UniffiRustFutureContinuationCallback,`callbackData`: Long,
-    ): Unit
-    external fun ffi_letterbox_core_rust_future_cancel_i16(`handle`: Long,
-    ): Unit
-    external fun ffi_letterbox_core_rust_future_free_i16(`handle`: Long,
-    ): Unit
-    external fun ffi_letterbox_core_rust_future_complete_i16(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
-    ): Short
-    external fun ffi_letterbox_core_rust_future_poll_u32(`handle`: Long,`callback`: UniffiRustFutureContinuationCallback,`callbackData`: Long,
-    ): Unit
-    external fun ffi_letterbox_core_rust_future_cancel_u32(`handle`: Long,
-    ): Unit
-    external fun ffi_letterbox_core_rust_future_free_u32(`handle`: Long,
-    ): Unit
-    external fun ffi_letterbox_core_rust_future_complete_u32(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
-    ): Int
-    external fun ffi_letterbox_core_rust_future_poll_i32(`handle`: Long,`callback`: UniffiRustFutureContinuationCallback,`callbackData`: Long,
-    ): Unit
-    external fun ffi_letterbox_core_rust_future_cancel_i32(`handle`: Long,
-    ): Unit
-    external fun ffi_letterbox_core_rust_future_free_i32(`handle`: Long,
-    ): Unit
-    external fun ffi_letterbox_core_rust_future_complete_i32(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
-    ): Int
-    external fun ffi_letterbox_core_rust_future_poll_u64(`handle`: Long,`callback`: UniffiRustFutureContinuationCallback,`callbackData`: Long,
-    ): Unit
-    external fun ffi_letterbox_core_rust_future_cancel_u64(`handle`: Long,
-    ): Unit
-    external fun ffi_letterbox_core_rust_future_free_u64(`handle`: Long,
-    ): Unit
-    external fun ffi_letterbox_core_rust_future_complete_u64(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
-    ): Long
-    external fun ffi_letterbox_core_rust_future_poll_i64(`handle`: Long,`callback`: UniffiRustFutureContinuationCallback,`callbackData`: Long,
-    ): Unit
-    external fun ffi_letterbox_core_rust_future_cancel_i64(`handle`: Long,
-    ): Unit
-    external fun ffi_letterbox_core_rust_future_free_i64(`handle`: Long,
-    ): Unit
-    external fun ffi_letterbox_core_rust_future_complete_i64(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
-    ): Long
-    external fun ffi_letterbox_core_rust_future_poll_f32(`handle`: Long,`callback`: UniffiRustFutureContinuationCallback,`callbackData`: Long,
-    ): Unit
-    external fun ffi_letterbox_core_rust_future_cancel_f32(`handle`: Long,
-    ): Unit
-    external fun ffi_letterbox_core_rust_future_free_f32(`handle`: Long,
-    ): Unit
-    external fun ffi_letterbox_core_rust_future_complete_f32(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
-    ): Float
-    external fun ffi_letterbox_core_rust_future_poll_f64(`handle`: Long,`callback`: UniffiRustFutureContinuationCallback,`callbackData`: Long,
-    ): Unit
-    external fun ffi_letterbox_core_rust_future_cancel_f64(`handle`: Long,
-    ): Unit
-    external fun ffi_letterbox_core_rust_future_free_f64(`handle`: Long,
-    ): Unit
-    external fun ffi_letterbox_core_rust_future_complete_f64(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
-    ): Double
-    external fun ffi_letterbox_core_rust_future_poll_rust_buffer(`handle`: Long,`callback`: UniffiRustFutureContinuationCallback,`callbackData`: Long,
-    ): Unit
-    external fun ffi_letterbox_core_rust_future_cancel_rust_buffer(`handle`: Long,
-    ): Unit
-    external fun ffi_letterbox_core_rust_future_free_rust_buffer(`handle`: Long,
-    ): Unit
-    external fun ffi_letterbox_core_rust_future_complete_rust_buffer(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
-    ): RustBuffer.ByValue
-    external fun ffi_letterbox_core_rust_future_poll_void(`handle`: Long,`callback`: UniffiRustFutureContinuationCallback,`callbackData`: Long,
-    ): Unit
-    external fun ffi_letterbox_core_rust_future_cancel_void(`handle`: Long,
-    ): Unit
-    external fun ffi_letterbox_core_rust_future_free_void(`handle`: Long,
-    ): Unit
-    external fun ffi_letterbox_core_rust_future_complete_void(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
-    ): Unit
+): Long
+external fun uniffi_letterbox_core_fn_free_emailhandle(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_core_fn_method_emailhandle_body_html(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_core_fn_method_emailhandle_body_preview(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_core_fn_method_emailhandle_body_text(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_core_fn_method_emailhandle_cc(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_core_fn_method_emailhandle_date(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_core_fn_method_emailhandle_date_timestamp(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Long
+external fun uniffi_letterbox_core_fn_method_emailhandle_from(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_core_fn_method_emailhandle_get_resource(`ptr`: Long,`cid`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_core_fn_method_emailhandle_get_resource_content_type(`ptr`: Long,`cid`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_core_fn_method_emailhandle_get_resource_ids(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_core_fn_method_emailhandle_get_resource_metadata(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_core_fn_method_emailhandle_message_id(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_core_fn_method_emailhandle_reply_to(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_core_fn_method_emailhandle_subject(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_core_fn_method_emailhandle_to(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_core_fn_method_emailhandle_write_resource_to_path(`ptr`: Long,`cid`: RustBuffer.ByValue,`path`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Byte
+external fun uniffi_letterbox_core_fn_method_emailhandle_attachment_count(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Int
+external fun uniffi_letterbox_core_fn_method_emailhandle_get_attachment_content(`ptr`: Long,`index`: Int,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_core_fn_method_emailhandle_get_attachments(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_core_fn_method_emailhandle_read_attachment_chunk(`ptr`: Long,`index`: Int,`offset`: Long,`len`: Int,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_core_fn_method_emailhandle_save_attachment(`ptr`: Long,`index`: Int,`destPath`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_core_fn_method_emailhandle_write_attachment_to_path(`ptr`: Long,`index`: Int,`path`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Byte
+external fun uniffi_letterbox_core_fn_method_emailhandle_body_html_dark_mode(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_core_fn_method_emailhandle_body_html_with_cid_scheme(`ptr`: Long,`prefix`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_core_fn_method_emailhandle_body_text_derived(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_core_fn_method_emailhandle_remote_resources(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_core_fn_method_emailhandle_rewrite_remote_resources(`ptr`: Long,`schemePrefix`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_core_fn_method_emailhandle_sanitized_body_html(`ptr`: Long,`options`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_core_fn_method_emailhandle_strip_attachments(`ptr`: Long,`indices`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_core_fn_method_emailhandle_to_builder(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Long
+external fun uniffi_letterbox_core_fn_method_emailhandle_to_eml_bytes(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_core_fn_method_emailhandle_all_headers(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_core_fn_method_emailhandle_bcc_addresses(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_core_fn_method_emailhandle_cc_addresses(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_core_fn_method_emailhandle_from_addresses(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_core_fn_method_emailhandle_get_header(`ptr`: Long,`name`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_core_fn_method_emailhandle_get_headers(`ptr`: Long,`name`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_core_fn_method_emailhandle_in_reply_to(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_core_fn_method_emailhandle_recipient_info(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_core_fn_method_emailhandle_references(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_core_fn_method_emailhandle_reply_to_addresses(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_core_fn_method_emailhandle_sender_info(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_core_fn_method_emailhandle_thread_message(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_core_fn_method_emailhandle_to_addresses(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_core_fn_clone_messagebuilder(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Long
+external fun uniffi_letterbox_core_fn_free_messagebuilder(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_core_fn_constructor_messagebuilder_new(uniffi_out_err: UniffiRustCallStatus, 
+): Long
+external fun uniffi_letterbox_core_fn_method_messagebuilder_add_attachment(`ptr`: Long,`name`: RustBuffer.ByValue,`contentType`: RustBuffer.ByValue,`data`: RustBuffer.ByValue,`encoding`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_core_fn_method_messagebuilder_add_header(`ptr`: Long,`name`: RustBuffer.ByValue,`value`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_core_fn_method_messagebuilder_add_inline(`ptr`: Long,`cid`: RustBuffer.ByValue,`contentType`: RustBuffer.ByValue,`data`: RustBuffer.ByValue,`encoding`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_core_fn_method_messagebuilder_set_bcc(`ptr`: Long,`addresses`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_core_fn_method_messagebuilder_set_cc(`ptr`: Long,`addresses`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_core_fn_method_messagebuilder_set_date_timestamp(`ptr`: Long,`timestampMs`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_core_fn_method_messagebuilder_set_from(`ptr`: Long,`address`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_core_fn_method_messagebuilder_set_html_body(`ptr`: Long,`html`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_core_fn_method_messagebuilder_set_in_reply_to(`ptr`: Long,`messageId`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_core_fn_method_messagebuilder_set_message_id(`ptr`: Long,`messageId`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_core_fn_method_messagebuilder_set_references(`ptr`: Long,`messageIds`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_core_fn_method_messagebuilder_set_reply_to(`ptr`: Long,`addresses`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_core_fn_method_messagebuilder_set_subject(`ptr`: Long,`subject`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_core_fn_method_messagebuilder_set_text_body(`ptr`: Long,`text`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_core_fn_method_messagebuilder_set_to(`ptr`: Long,`addresses`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_core_fn_method_messagebuilder_to_eml_bytes(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_core_fn_clone_threadbuilder(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Long
+external fun uniffi_letterbox_core_fn_free_threadbuilder(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_core_fn_constructor_threadbuilder_new(uniffi_out_err: UniffiRustCallStatus, 
+): Long
+external fun uniffi_letterbox_core_fn_method_threadbuilder_add(`ptr`: Long,`message`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_core_fn_method_threadbuilder_add_all(`ptr`: Long,`messages`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_core_fn_method_threadbuilder_build(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_core_fn_func_extract_remote_images(`html`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_core_fn_func_plain_text_to_html(`text`: RustBuffer.ByValue,`emphasis`: Byte,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_core_fn_func_parse_eml(`data`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Long
+external fun uniffi_letterbox_core_fn_func_parse_eml_file(`path`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Long
+external fun uniffi_letterbox_core_fn_func_parse_eml_from_path(`path`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Long
+external fun uniffi_letterbox_core_fn_func_parse_eml_headers(`data`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Long
+external fun ffi_letterbox_core_rustbuffer_alloc(`size`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun ffi_letterbox_core_rustbuffer_from_bytes(`bytes`: ForeignBytes.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun ffi_letterbox_core_rustbuffer_free(`buf`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun ffi_letterbox_core_rustbuffer_reserve(`buf`: RustBuffer.ByValue,`additional`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun ffi_letterbox_core_rust_future_poll_u8(`handle`: Long,`callback`: UniffiRustFutureContinuationCallback,`callbackData`: Long,
+): Unit
+external fun ffi_letterbox_core_rust_future_cancel_u8(`handle`: Long,
+): Unit
+external fun ffi_letterbox_core_rust_future_free_u8(`handle`: Long,
+): Unit
+external fun ffi_letterbox_core_rust_future_complete_u8(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Int
+external fun ffi_letterbox_core_rust_future_poll_i8(`handle`: Long,`callback`: UniffiRustFutureContinuationCallback,`callbackData`: Long,
+): Unit
+external fun ffi_letterbox_core_rust_future_cancel_i8(`handle`: Long,
+): Unit
+external fun ffi_letterbox_core_rust_future_free_i8(`handle`: Long,
+): Unit
+external fun ffi_letterbox_core_rust_future_complete_i8(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Byte
+external fun ffi_letterbox_core_rust_future_poll_u16(`handle`: Long,`callback`: UniffiRustFutureContinuationCallback,`callbackData`: Long,
+): Unit
+external fun ffi_letterbox_core_rust_future_cancel_u16(`handle`: Long,
+): Unit
+external fun ffi_letterbox_core_rust_future_free_u16(`handle`: Long,
+): Unit
+external fun ffi_letterbox_core_rust_future_complete_u16(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Int
+external fun ffi_letterbox_core_rust_future_poll_i16(`handle`: Long,`callback`: UniffiRustFutureContinuationCallback,`callbackData`: Long,
+): Unit
+external fun ffi_letterbox_core_rust_future_cancel_i16(`handle`: Long,
+): Unit
+external fun ffi_letterbox_core_rust_future_free_i16(`handle`: Long,
+): Unit
+external fun ffi_letterbox_core_rust_future_complete_i16(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Short
+external fun ffi_letterbox_core_rust_future_poll_u32(`handle`: Long,`callback`: UniffiRustFutureContinuationCallback,`callbackData`: Long,
+): Unit
+external fun ffi_letterbox_core_rust_future_cancel_u32(`handle`: Long,
+): Unit
+external fun ffi_letterbox_core_rust_future_free_u32(`handle`: Long,
+): Unit
+external fun ffi_letterbox_core_rust_future_complete_u32(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Int
+external fun ffi_letterbox_core_rust_future_poll_i32(`handle`: Long,`callback`: UniffiRustFutureContinuationCallback,`callbackData`: Long,
+): Unit
+external fun ffi_letterbox_core_rust_future_cancel_i32(`handle`: Long,
+): Unit
+external fun ffi_letterbox_core_rust_future_free_i32(`handle`: Long,
+): Unit
+external fun ffi_letterbox_core_rust_future_complete_i32(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Int
+external fun ffi_letterbox_core_rust_future_poll_u64(`handle`: Long,`callback`: UniffiRustFutureContinuationCallback,`callbackData`: Long,
+): Unit
+external fun ffi_letterbox_core_rust_future_cancel_u64(`handle`: Long,
+): Unit
+external fun ffi_letterbox_core_rust_future_free_u64(`handle`: Long,
+): Unit
+external fun ffi_letterbox_core_rust_future_complete_u64(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Long
+external fun ffi_letterbox_core_rust_future_poll_i64(`handle`: Long,`callback`: UniffiRustFutureContinuationCallback,`callbackData`: Long,
+): Unit
+external fun ffi_letterbox_core_rust_future_cancel_i64(`handle`: Long,
+): Unit
+external fun ffi_letterbox_core_rust_future_free_i64(`handle`: Long,
+): Unit
+external fun ffi_letterbox_core_rust_future_complete_i64(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Long
+external fun ffi_letterbox_core_rust_future_poll_f32(`handle`: Long,`callback`: UniffiRustFutureContinuationCallback,`callbackData`: Long,
+): Unit
+external fun ffi_letterbox_core_rust_future_cancel_f32(`handle`: Long,
+): Unit
+external fun ffi_letterbox_core_rust_future_free_f32(`handle`: Long,
+): Unit
+external fun ffi_letterbox_core_rust_future_complete_f32(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Float
+external fun ffi_letterbox_core_rust_future_poll_f64(`handle`: Long,`callback`: UniffiRustFutureContinuationCallback,`callbackData`: Long,
+): Unit
+external fun ffi_letterbox_core_rust_future_cancel_f64(`handle`: Long,
+): Unit
+external fun ffi_letterbox_core_rust_future_free_f64(`handle`: Long,
+): Unit
+external fun ffi_letterbox_core_rust_future_complete_f64(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Double
+external fun ffi_letterbox_core_rust_future_poll_rust_buffer(`handle`: Long,`callback`: UniffiRustFutureContinuationCallback,`callbackData`: Long,
+): Unit
+external fun ffi_letterbox_core_rust_future_cancel_rust_buffer(`handle`: Long,
+): Unit
+external fun ffi_letterbox_core_rust_future_free_rust_buffer(`handle`: Long,
+): Unit
+external fun ffi_letterbox_core_rust_future_complete_rust_buffer(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun ffi_letterbox_core_rust_future_poll_void(`handle`: Long,`callback`: UniffiRustFutureContinuationCallback,`callbackData`: Long,
+): Unit
+external fun ffi_letterbox_core_rust_future_cancel_void(`handle`: Long,
+): Unit
+external fun ffi_letterbox_core_rust_future_free_void(`handle`: Long,
+): Unit
+external fun ffi_letterbox_core_rust_future_complete_void(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
 
-        
+    
 }
 
 private fun uniffiCheckContractApiVersion(lib: IntegrityCheckingUniffiLib) {
@@ -877,79 +1069,217 @@ private fun uniffiCheckContractApiVersion(lib: IntegrityCheckingUniffiLib) {
 }
 @Suppress("UNUSED_PARAMETER")
 private fun uniffiCheckApiChecksums(lib: IntegrityCheckingUniffiLib) {
-    if (lib.uniffi_letterbox_core_checksum_func_extract_remote_images() != 33308.toShort()) {
+    if (lib.uniffi_letterbox_core_checksum_func_extract_remote_images() != 32108) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_core_checksum_func_plain_text_to_html() != 34686) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_core_checksum_func_parse_eml() != 13677) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_core_checksum_func_parse_eml_file() != 54584) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_core_checksum_func_parse_eml_from_path() != 37922) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_core_checksum_func_parse_eml_headers() != 21737) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_core_checksum_method_emailhandle_body_html() != 37584) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_core_checksum_method_emailhandle_body_preview() != 56708) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_core_checksum_method_emailhandle_body_text() != 49627) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_core_checksum_method_emailhandle_cc() != 21092) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_core_checksum_method_emailhandle_date() != 5435) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_core_checksum_method_emailhandle_date_timestamp() != 22451) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_core_checksum_method_emailhandle_from() != 17732) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_core_checksum_method_emailhandle_get_resource() != 30043) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_core_checksum_method_emailhandle_get_resource_content_type() != 29102) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_core_checksum_method_emailhandle_get_resource_ids() != 36234) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_core_checksum_method_emailhandle_get_resource_metadata() != 14885) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_core_checksum_method_emailhandle_message_id() != 52741) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_core_checksum_method_emailhandle_reply_to() != 59962) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_core_checksum_method_emailhandle_subject() != 19561) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_core_checksum_method_emailhandle_to() != 837) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_core_checksum_method_emailhandle_write_resource_to_path() != 46693) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_core_checksum_method_emailhandle_attachment_count() != 15676) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_core_checksum_method_emailhandle_get_attachment_content() != 7376) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_core_checksum_method_emailhandle_get_attachments() != 24020) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_core_checksum_method_emailhandle_read_attachment_chunk() != 17915) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_core_checksum_method_emailhandle_save_attachment() != 42808) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_core_checksum_method_emailhandle_write_attachment_to_path() != 47884) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_core_checksum_method_emailhandle_body_html_dark_mode() != 16953) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_core_checksum_method_emailhandle_body_html_with_cid_scheme() != 1572) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_core_checksum_method_emailhandle_body_text_derived() != 40025) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_core_checksum_method_emailhandle_remote_resources() != 27599) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_core_checksum_method_emailhandle_rewrite_remote_resources() != 26679) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_core_checksum_method_emailhandle_sanitized_body_html() != 4933) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_core_checksum_method_emailhandle_strip_attachments() != 50152) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_core_checksum_method_emailhandle_to_builder() != 59173) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_core_checksum_method_emailhandle_to_eml_bytes() != 3439) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_core_checksum_method_emailhandle_all_headers() != 61151) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_core_checksum_method_emailhandle_bcc_addresses() != 6041) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_core_checksum_method_emailhandle_cc_addresses() != 50941) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_core_checksum_method_emailhandle_from_addresses() != 39817) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_core_checksum_method_emailhandle_get_header() != 29149) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_core_checksum_method_emailhandle_get_headers() != 50599) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_core_checksum_method_emailhandle_in_reply_to() != 63980) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_core_checksum_method_emailhandle_recipient_info() != 50252) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_core_checksum_method_emailhandle_references() != 41379) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_core_checksum_method_emailhandle_reply_to_addresses() != 53433) {
         throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-    if (lib.uniffi_letterbox_core_checksum_func_parse_eml() != 48112.toShort()) {
+    if (lib.uniffi_letterbox_core_checksum_method_emailhandle_sender_info() != 17502) {
         throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-    if (lib.uniffi_letterbox_core_checksum_func_parse_eml_from_path() != 36307.toShort()) {
+    if (lib.uniffi_letterbox_core_checksum_method_emailhandle_thread_message() != 54679) {
         throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-    if (lib.uniffi_letterbox_core_checksum_method_emailhandle_attachment_count() != 2946.toShort()) {
+    if (lib.uniffi_letterbox_core_checksum_method_emailhandle_to_addresses() != 45301) {
         throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-    if (lib.uniffi_letterbox_core_checksum_method_emailhandle_body_html() != 37584.toShort()) {
+    if (lib.uniffi_letterbox_core_checksum_method_messagebuilder_add_attachment() != 46309) {
         throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-    if (lib.uniffi_letterbox_core_checksum_method_emailhandle_body_preview() != 56708.toShort()) {
+    if (lib.uniffi_letterbox_core_checksum_method_messagebuilder_add_header() != 10282) {
         throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-    if (lib.uniffi_letterbox_core_checksum_method_emailhandle_body_text() != 49627.toShort()) {
+    if (lib.uniffi_letterbox_core_checksum_method_messagebuilder_add_inline() != 22911) {
         throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-    if (lib.uniffi_letterbox_core_checksum_method_emailhandle_cc() != 21092.toShort()) {
+    if (lib.uniffi_letterbox_core_checksum_method_messagebuilder_set_bcc() != 37461) {
         throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-    if (lib.uniffi_letterbox_core_checksum_method_emailhandle_date() != 5435.toShort()) {
+    if (lib.uniffi_letterbox_core_checksum_method_messagebuilder_set_cc() != 62455) {
         throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-    if (lib.uniffi_letterbox_core_checksum_method_emailhandle_date_timestamp() != 22451.toShort()) {
+    if (lib.uniffi_letterbox_core_checksum_method_messagebuilder_set_date_timestamp() != 18470) {
         throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-    if (lib.uniffi_letterbox_core_checksum_method_emailhandle_from() != 17732.toShort()) {
+    if (lib.uniffi_letterbox_core_checksum_method_messagebuilder_set_from() != 26458) {
         throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-    if (lib.uniffi_letterbox_core_checksum_method_emailhandle_get_attachment_content() != 16894.toShort()) {
+    if (lib.uniffi_letterbox_core_checksum_method_messagebuilder_set_html_body() != 34751) {
         throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-    if (lib.uniffi_letterbox_core_checksum_method_emailhandle_get_attachments() != 4360.toShort()) {
+    if (lib.uniffi_letterbox_core_checksum_method_messagebuilder_set_in_reply_to() != 24509) {
         throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-    if (lib.uniffi_letterbox_core_checksum_method_emailhandle_get_resource() != 30043.toShort()) {
+    if (lib.uniffi_letterbox_core_checksum_method_messagebuilder_set_message_id() != 30519) {
         throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-    if (lib.uniffi_letterbox_core_checksum_method_emailhandle_get_resource_content_type() != 29102.toShort()) {
+    if (lib.uniffi_letterbox_core_checksum_method_messagebuilder_set_references() != 26499) {
         throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-    if (lib.uniffi_letterbox_core_checksum_method_emailhandle_get_resource_ids() != 36234.toShort()) {
+    if (lib.uniffi_letterbox_core_checksum_method_messagebuilder_set_reply_to() != 61629) {
         throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-    if (lib.uniffi_letterbox_core_checksum_method_emailhandle_get_resource_metadata() != 14885.toShort()) {
+    if (lib.uniffi_letterbox_core_checksum_method_messagebuilder_set_subject() != 5149) {
         throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-    if (lib.uniffi_letterbox_core_checksum_method_emailhandle_message_id() != 52741.toShort()) {
+    if (lib.uniffi_letterbox_core_checksum_method_messagebuilder_set_text_body() != 10157) {
         throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-    if (lib.uniffi_letterbox_core_checksum_method_emailhandle_recipient_info() != 35618.toShort()) {
+    if (lib.uniffi_letterbox_core_checksum_method_messagebuilder_set_to() != 50392) {
         throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-    if (lib.uniffi_letterbox_core_checksum_method_emailhandle_reply_to() != 59962.toShort()) {
+    if (lib.uniffi_letterbox_core_checksum_method_messagebuilder_to_eml_bytes() != 49305) {
         throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-    if (lib.uniffi_letterbox_core_checksum_method_emailhandle_sender_info() != 20160.toShort()) {
+    if (lib.uniffi_letterbox_core_checksum_method_threadbuilder_add() != 43942) {
         throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-    if (lib.uniffi_letterbox_core_checksum_method_emailhandle_subject() != 19561.toShort()) {
+    if (lib.uniffi_letterbox_core_checksum_method_threadbuilder_add_all() != 50310) {
         throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-    if (lib.uniffi_letterbox_core_checksum_method_emailhandle_to() != 837.toShort()) {
+    if (lib.uniffi_letterbox_core_checksum_method_threadbuilder_build() != 35379) {
         throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-    if (lib.uniffi_letterbox_core_checksum_method_emailhandle_write_attachment_to_path() != 9686.toShort()) {
+    if (lib.uniffi_letterbox_core_checksum_constructor_messagebuilder_new() != 2326) {
         throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-    if (lib.uniffi_letterbox_core_checksum_method_emailhandle_write_resource_to_path() != 46693.toShort()) {
+    if (lib.uniffi_letterbox_core_checksum_constructor_threadbuilder_new() != 14647) {
         throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
 }
@@ -1379,11 +1709,6 @@ public object FfiConverterByteArray: FfiConverterRustBuffer<ByteArray> {
  */
 public interface EmailHandleInterface {
     
-    /**
-     * Get the number of attachments.
-     */
-    fun `attachmentCount`(): kotlin.UInt
-    
     /**
      * Get the HTML body content, if available.
      */
@@ -1422,17 +1747,6 @@ public interface EmailHandleInterface {
      */
     fun `from`(): kotlin.String
     
-    /**
-     * Get attachment content by index.
-     * Note: For large attachments, consider using write_attachment_to_path instead.
-     */
-    fun `getAttachmentContent`(`index`: kotlin.UInt): kotlin.ByteArray?
-    
-    /**
-     * Get a list of all attachments with their metadata.
-     */
-    fun `getAttachments`(): List<AttachmentInfo>
-    
     /**
      * Get an inline resource by Content-ID for cid: URL resolution.
      * Note: For large resources (>64KB), consider using write_resource_to_path instead.
@@ -1462,23 +1776,11 @@ public interface EmailHandleInterface {
      */
     fun `messageId`(): kotlin.String
     
-    /**
-     * Get structured recipient information (To + Cc).
-     * Returns list of AddressInfo for all recipients for search indexing.
-     */
-    fun `recipientInfo`(): List<AddressInfo>
-    
     /**
      * Get the "Reply-To" field formatted as a string.
      */
     fun `replyTo`(): kotlin.String
     
-    /**
-     * Get structured sender information.
-     * Returns AddressInfo with separate email and name fields for search indexing.
-     */
-    fun `senderInfo`(): AddressInfo
-    
     /**
      * Get the email subject.
      */
@@ -1490,9 +1792,9 @@ public interface EmailHandleInterface {
     fun `to`(): kotlin.String
     
     /**
-     * Write an attachment directly to a file path.
-     * This avoids copying large attachments across the FFI boundary.
-     * Returns true on success, false if attachment not found.
+     * Write an inline resource directly to a file path.
+     * This avoids copying large resources across the FFI boundary.
+     * Returns true on success.
      *
      * # Security
      * The caller is responsible for validating that `path` is a safe, sandboxed location.
@@ -1500,12 +1802,47 @@ public interface EmailHandleInterface {
      * additional path validation. Use only with paths constructed from trusted sources
      * (e.g., application cache directories).
      */
-    fun `writeAttachmentToPath`(`index`: kotlin.UInt, `path`: kotlin.String): kotlin.Boolean
+    fun `writeResourceToPath`(`cid`: kotlin.String, `path`: kotlin.String): kotlin.Boolean
     
     /**
-     * Write an inline resource directly to a file path.
-     * This avoids copying large resources across the FFI boundary.
-     * Returns true on success.
+     * Get the number of attachments.
+     */
+    fun `attachmentCount`(): kotlin.UInt
+    
+    /**
+     * Get attachment content by index.
+     * Note: For large attachments, consider using write_attachment_to_path instead.
+     */
+    fun `getAttachmentContent`(`index`: kotlin.UInt): kotlin.ByteArray?
+    
+    /**
+     * Get a list of all attachments with their metadata.
+     */
+    fun `getAttachments`(): List<AttachmentInfo>
+    
+    /**
+     * Read up to `len` bytes of attachment `index`, starting at byte `offset`
+     * of its decoded content, so a large attachment can be streamed in
+     * pieces. Returns fewer bytes at the end and none past it, or None if
+     * there is no such attachment.
+     */
+    fun `readAttachmentChunk`(`index`: kotlin.UInt, `offset`: kotlin.ULong, `len`: kotlin.UInt): kotlin.ByteArray?
+    
+    /**
+     * Decode an attachment and write it to `dest_path` in Rust, returning
+     * the bytes written and their SHA-256 so the caller can verify the file
+     * without reading it back. Returns None if the attachment is not found.
+     *
+     * # Security
+     * As for [`Self::write_attachment_to_path`], `dest_path` must come from a
+     * trusted source.
+     */
+    fun `saveAttachment`(`index`: kotlin.UInt, `destPath`: kotlin.String): SavedAttachment?
+    
+    /**
+     * Write an attachment directly to a file path.
+     * This avoids copying large attachments across the FFI boundary.
+     * Returns true on success, false if attachment not found.
      *
      * # Security
      * The caller is responsible for validating that `path` is a safe, sandboxed location.
@@ -1513,19 +1850,157 @@ public interface EmailHandleInterface {
      * additional path validation. Use only with paths constructed from trusted sources
      * (e.g., application cache directories).
      */
-    fun `writeResourceToPath`(`cid`: kotlin.String, `path`: kotlin.String): kotlin.Boolean
+    fun `writeAttachmentToPath`(`index`: kotlin.UInt, `path`: kotlin.String): kotlin.Boolean
     
-    companion object
-}
-
-/**
- * Holds parsed email content in Rust memory.
- * Kotlin code holds a reference to this object and calls methods to retrieve content.
- */
-open class EmailHandle: Disposable, AutoCloseable, EmailHandleInterface
-{
-
-    @Suppress("UNUSED_PARAMETER")
+    /**
+     * Get the HTML body restyled for dark themes: the page is inverted with
+     * hues kept, and images inverted back. Bodies with a dark background or
+     * their own dark color scheme are returned unchanged.
+     */
+    fun `bodyHtmlDarkMode`(): kotlin.String?
+    
+    /**
+     * Get the HTML body with each `cid:` reference rewritten to `prefix`
+     * followed by the reference (e.g. `letterbox-asset://image001`), so the
+     * WebView can intercept inline parts on one scheme, and the Content-IDs
+     * that match no inline part.
+     */
+    fun `bodyHtmlWithCidScheme`(`prefix`: kotlin.String): CidRewrite?
+    
+    /**
+     * Get the HTML body converted to readable plain text: blocks and lists
+     * kept, quotes prefixed with `>`, links as numbered footnotes. For
+     * notifications, snippets and reply quoting when there is no text part.
+     */
+    fun `bodyTextDerived`(): kotlin.String?
+    
+    /**
+     * Get every external URL the HTML body would load (images, srcsets,
+     * CSS backgrounds and imports, links), once each, in document order.
+     */
+    fun `remoteResources`(): List<RemoteResource>
+    
+    /**
+     * Get the HTML body with every external URL replaced by `scheme_prefix`
+     * followed by the form-urlencoded URL, so the WebView loads nothing
+     * remote until the app serves those placeholders itself.
+     */
+    fun `rewriteRemoteResources`(`schemePrefix`: kotlin.String): kotlin.String?
+    
+    /**
+     * Get the HTML body reduced to what is safe to load in a WebView:
+     * without scripts, event handlers, forms, iframes, CSS `url()` loads or
+     * `position: fixed` overlays.
+     */
+    fun `sanitizedBodyHtml`(`options`: SanitizeOptions): kotlin.String?
+    
+    /**
+     * Serialize the message like [`Self::to_eml_bytes`], with the
+     * attachments at `indices` replaced by small text attachments noting
+     * their name, type and size, to archive a message without its bulk.
+     * Indices past the last attachment are ignored.
+     */
+    fun `stripAttachments`(`indices`: List<kotlin.UInt>): kotlin.ByteArray
+    
+    /**
+     * Get a [`MessageBuilder`] holding this message's headers, bodies,
+     * inline parts and attachments, to edit and serialize again, e.g. to
+     * duplicate a draft. Headers the builder writes itself (addresses,
+     * subject, IDs, date, MIME) are taken from the parsed fields, and none
+     * the message lacked is added; the others are kept as they were. Fails
+     * if an attachment cannot be decoded.
+     */
+    fun `toBuilder`(): MessageBuilder
+    
+    /**
+     * Serialize the message to EML bytes again, through
+     * [`Self::to_builder`]. The MIME structure is rebuilt, so the bytes
+     * differ from the original's while the content is the same.
+     */
+    fun `toEmlBytes`(): kotlin.ByteArray
+    
+    /**
+     * Get every top-level header field, in message order.
+     */
+    fun `allHeaders`(): List<HeaderField>
+    
+    /**
+     * Get every "Bcc" address as a structured record, in order.
+     */
+    fun `bccAddresses`(): List<AddressInfo>
+    
+    /**
+     * Get every "Cc" address as a structured record, in order.
+     */
+    fun `ccAddresses`(): List<AddressInfo>
+    
+    /**
+     * Get every "From" address as a structured record, in order.
+     */
+    fun `fromAddresses`(): List<AddressInfo>
+    
+    /**
+     * Get the raw value of the first header named `name` (case-insensitive).
+     */
+    fun `getHeader`(`name`: kotlin.String): kotlin.String?
+    
+    /**
+     * Get the raw values of every header named `name` (case-insensitive),
+     * in message order.
+     */
+    fun `getHeaders`(`name`: kotlin.String): List<kotlin.String>
+    
+    /**
+     * Get the first message ID this message replies to (In-Reply-To),
+     * without angle brackets; empty if none.
+     */
+    fun `inReplyTo`(): kotlin.String
+    
+    /**
+     * Get structured recipient information (To + Cc).
+     * Returns list of AddressInfo for all recipients for search indexing.
+     */
+    fun `recipientInfo`(): List<AddressInfo>
+    
+    /**
+     * Get the message IDs of the References header, oldest first, without
+     * angle brackets. Together with [`Self::in_reply_to`] this places the
+     * message in its conversation thread.
+     */
+    fun `references`(): List<kotlin.String>
+    
+    /**
+     * Get every "Reply-To" address as a structured record, in order.
+     */
+    fun `replyToAddresses`(): List<AddressInfo>
+    
+    /**
+     * Get structured sender information.
+     * Returns AddressInfo with separate email and name fields for search indexing.
+     */
+    fun `senderInfo`(): AddressInfo
+    
+    /**
+     * Get what [`ThreadBuilder`](crate::ThreadBuilder) needs to thread this message.
+     */
+    fun `threadMessage`(): ThreadMessage
+    
+    /**
+     * Get every "To" address as a structured record, in order.
+     */
+    fun `toAddresses`(): List<AddressInfo>
+    
+    companion object
+}
+
+/**
+ * Holds parsed email content in Rust memory.
+ * Kotlin code holds a reference to this object and calls methods to retrieve content.
+ */
+open class EmailHandle: Disposable, AutoCloseable, EmailHandleInterface
+{
+
+    @Suppress("UNUSED_PARAMETER")
     /**
      * @suppress
      */
@@ -1619,22 +2094,6 @@ open class EmailHandle: Disposable, AutoCloseable, EmailHandleInterface
     }
 
     
-    /**
-     * Get the number of attachments.
-     */override fun `attachmentCount`(): kotlin.UInt {
-            return FfiConverterUInt.lift(
-    callWithHandle {
-    uniffiRustCall() { _status ->
-    UniffiLib.uniffi_letterbox_core_fn_method_emailhandle_attachment_count(
-        it,
-        _status)
-}
-    }
-    )
-    }
-    
-
-    
     /**
      * Get the HTML body content, if available.
      */override fun `bodyHtml`(): kotlin.String? {
@@ -1750,39 +2209,6 @@ open class EmailHandle: Disposable, AutoCloseable, EmailHandleInterface
     
 
     
-    /**
-     * Get attachment content by index.
-     * Note: For large attachments, consider using write_attachment_to_path instead.
-     */override fun `getAttachmentContent`(`index`: kotlin.UInt): kotlin.ByteArray? {
-            return FfiConverterOptionalByteArray.lift(
-    callWithHandle {
-    uniffiRustCall() { _status ->
-    UniffiLib.uniffi_letterbox_core_fn_method_emailhandle_get_attachment_content(
-        it,
-        FfiConverterUInt.lower(`index`),_status)
-}
-    }
-    )
-    }
-    
-
-    
-    /**
-     * Get a list of all attachments with their metadata.
-     */override fun `getAttachments`(): List<AttachmentInfo> {
-            return FfiConverterSequenceTypeAttachmentInfo.lift(
-    callWithHandle {
-    uniffiRustCall() { _status ->
-    UniffiLib.uniffi_letterbox_core_fn_method_emailhandle_get_attachments(
-        it,
-        _status)
-}
-    }
-    )
-    }
-    
-
-    
     /**
      * Get an inline resource by Content-ID for cid: URL resolution.
      * Note: For large resources (>64KB), consider using write_resource_to_path instead.
@@ -1868,13 +2294,12 @@ open class EmailHandle: Disposable, AutoCloseable, EmailHandleInterface
 
     
     /**
-     * Get structured recipient information (To + Cc).
-     * Returns list of AddressInfo for all recipients for search indexing.
-     */override fun `recipientInfo`(): List<AddressInfo> {
-            return FfiConverterSequenceTypeAddressInfo.lift(
+     * Get the "Reply-To" field formatted as a string.
+     */override fun `replyTo`(): kotlin.String {
+            return FfiConverterString.lift(
     callWithHandle {
     uniffiRustCall() { _status ->
-    UniffiLib.uniffi_letterbox_core_fn_method_emailhandle_recipient_info(
+    UniffiLib.uniffi_letterbox_core_fn_method_emailhandle_reply_to(
         it,
         _status)
 }
@@ -1885,12 +2310,12 @@ open class EmailHandle: Disposable, AutoCloseable, EmailHandleInterface
 
     
     /**
-     * Get the "Reply-To" field formatted as a string.
-     */override fun `replyTo`(): kotlin.String {
+     * Get the email subject.
+     */override fun `subject`(): kotlin.String {
             return FfiConverterString.lift(
     callWithHandle {
     uniffiRustCall() { _status ->
-    UniffiLib.uniffi_letterbox_core_fn_method_emailhandle_reply_to(
+    UniffiLib.uniffi_letterbox_core_fn_method_emailhandle_subject(
         it,
         _status)
 }
@@ -1901,13 +2326,12 @@ open class EmailHandle: Disposable, AutoCloseable, EmailHandleInterface
 
     
     /**
-     * Get structured sender information.
-     * Returns AddressInfo with separate email and name fields for search indexing.
-     */override fun `senderInfo`(): AddressInfo {
-            return FfiConverterTypeAddressInfo.lift(
+     * Get the "To" field formatted as a string.
+     */override fun `to`(): kotlin.String {
+            return FfiConverterString.lift(
     callWithHandle {
     uniffiRustCall() { _status ->
-    UniffiLib.uniffi_letterbox_core_fn_method_emailhandle_sender_info(
+    UniffiLib.uniffi_letterbox_core_fn_method_emailhandle_to(
         it,
         _status)
 }
@@ -1918,12 +2342,37 @@ open class EmailHandle: Disposable, AutoCloseable, EmailHandleInterface
 
     
     /**
-     * Get the email subject.
-     */override fun `subject`(): kotlin.String {
-            return FfiConverterString.lift(
+     * Write an inline resource directly to a file path.
+     * This avoids copying large resources across the FFI boundary.
+     * Returns true on success.
+     *
+     * # Security
+     * The caller is responsible for validating that `path` is a safe, sandboxed location.
+     * This function will create parent directories and write to the specified path without
+     * additional path validation. Use only with paths constructed from trusted sources
+     * (e.g., application cache directories).
+     */
+    @Throws(ParseException::class)override fun `writeResourceToPath`(`cid`: kotlin.String, `path`: kotlin.String): kotlin.Boolean {
+            return FfiConverterBoolean.lift(
+    callWithHandle {
+    uniffiRustCallWithError(ParseException) { _status ->
+    UniffiLib.uniffi_letterbox_core_fn_method_emailhandle_write_resource_to_path(
+        it,
+        FfiConverterString.lower(`cid`),FfiConverterString.lower(`path`),_status)
+}
+    }
+    )
+    }
+    
+
+    
+    /**
+     * Get the number of attachments.
+     */override fun `attachmentCount`(): kotlin.UInt {
+            return FfiConverterUInt.lift(
     callWithHandle {
     uniffiRustCall() { _status ->
-    UniffiLib.uniffi_letterbox_core_fn_method_emailhandle_subject(
+    UniffiLib.uniffi_letterbox_core_fn_method_emailhandle_attachment_count(
         it,
         _status)
 }
@@ -1934,12 +2383,29 @@ open class EmailHandle: Disposable, AutoCloseable, EmailHandleInterface
 
     
     /**
-     * Get the "To" field formatted as a string.
-     */override fun `to`(): kotlin.String {
-            return FfiConverterString.lift(
+     * Get attachment content by index.
+     * Note: For large attachments, consider using write_attachment_to_path instead.
+     */override fun `getAttachmentContent`(`index`: kotlin.UInt): kotlin.ByteArray? {
+            return FfiConverterOptionalByteArray.lift(
     callWithHandle {
     uniffiRustCall() { _status ->
-    UniffiLib.uniffi_letterbox_core_fn_method_emailhandle_to(
+    UniffiLib.uniffi_letterbox_core_fn_method_emailhandle_get_attachment_content(
+        it,
+        FfiConverterUInt.lower(`index`),_status)
+}
+    }
+    )
+    }
+    
+
+    
+    /**
+     * Get a list of all attachments with their metadata.
+     */override fun `getAttachments`(): List<AttachmentInfo> {
+            return FfiConverterSequenceTypeAttachmentInfo.lift(
+    callWithHandle {
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_core_fn_method_emailhandle_get_attachments(
         it,
         _status)
 }
@@ -1950,23 +2416,40 @@ open class EmailHandle: Disposable, AutoCloseable, EmailHandleInterface
 
     
     /**
-     * Write an attachment directly to a file path.
-     * This avoids copying large attachments across the FFI boundary.
-     * Returns true on success, false if attachment not found.
+     * Read up to `len` bytes of attachment `index`, starting at byte `offset`
+     * of its decoded content, so a large attachment can be streamed in
+     * pieces. Returns fewer bytes at the end and none past it, or None if
+     * there is no such attachment.
+     */override fun `readAttachmentChunk`(`index`: kotlin.UInt, `offset`: kotlin.ULong, `len`: kotlin.UInt): kotlin.ByteArray? {
+            return FfiConverterOptionalByteArray.lift(
+    callWithHandle {
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_core_fn_method_emailhandle_read_attachment_chunk(
+        it,
+        FfiConverterUInt.lower(`index`),FfiConverterULong.lower(`offset`),FfiConverterUInt.lower(`len`),_status)
+}
+    }
+    )
+    }
+    
+
+    
+    /**
+     * Decode an attachment and write it to `dest_path` in Rust, returning
+     * the bytes written and their SHA-256 so the caller can verify the file
+     * without reading it back. Returns None if the attachment is not found.
      *
      * # Security
-     * The caller is responsible for validating that `path` is a safe, sandboxed location.
-     * This function will create parent directories and write to the specified path without
-     * additional path validation. Use only with paths constructed from trusted sources
-     * (e.g., application cache directories).
+     * As for [`Self::write_attachment_to_path`], `dest_path` must come from a
+     * trusted source.
      */
-    @Throws(ParseException::class)override fun `writeAttachmentToPath`(`index`: kotlin.UInt, `path`: kotlin.String): kotlin.Boolean {
-            return FfiConverterBoolean.lift(
+    @Throws(ParseException::class)override fun `saveAttachment`(`index`: kotlin.UInt, `destPath`: kotlin.String): SavedAttachment? {
+            return FfiConverterOptionalTypeSavedAttachment.lift(
     callWithHandle {
     uniffiRustCallWithError(ParseException) { _status ->
-    UniffiLib.uniffi_letterbox_core_fn_method_emailhandle_write_attachment_to_path(
+    UniffiLib.uniffi_letterbox_core_fn_method_emailhandle_save_attachment(
         it,
-        FfiConverterUInt.lower(`index`),FfiConverterString.lower(`path`),_status)
+        FfiConverterUInt.lower(`index`),FfiConverterString.lower(`destPath`),_status)
 }
     }
     )
@@ -1975,9 +2458,9 @@ open class EmailHandle: Disposable, AutoCloseable, EmailHandleInterface
 
     
     /**
-     * Write an inline resource directly to a file path.
-     * This avoids copying large resources across the FFI boundary.
-     * Returns true on success.
+     * Write an attachment directly to a file path.
+     * This avoids copying large attachments across the FFI boundary.
+     * Returns true on success, false if attachment not found.
      *
      * # Security
      * The caller is responsible for validating that `path` is a safe, sandboxed location.
@@ -1985,13 +2468,13 @@ open class EmailHandle: Disposable, AutoCloseable, EmailHandleInterface
      * additional path validation. Use only with paths constructed from trusted sources
      * (e.g., application cache directories).
      */
-    @Throws(ParseException::class)override fun `writeResourceToPath`(`cid`: kotlin.String, `path`: kotlin.String): kotlin.Boolean {
+    @Throws(ParseException::class)override fun `writeAttachmentToPath`(`index`: kotlin.UInt, `path`: kotlin.String): kotlin.Boolean {
             return FfiConverterBoolean.lift(
     callWithHandle {
     uniffiRustCallWithError(ParseException) { _status ->
-    UniffiLib.uniffi_letterbox_core_fn_method_emailhandle_write_resource_to_path(
+    UniffiLib.uniffi_letterbox_core_fn_method_emailhandle_write_attachment_to_path(
         it,
-        FfiConverterString.lower(`cid`),FfiConverterString.lower(`path`),_status)
+        FfiConverterUInt.lower(`index`),FfiConverterString.lower(`path`),_status)
 }
     }
     )
@@ -1999,59 +2482,1628 @@ open class EmailHandle: Disposable, AutoCloseable, EmailHandleInterface
     
 
     
-
-    
-
-
-    
-    
     /**
-     * @suppress
+     * Get the HTML body restyled for dark themes: the page is inverted with
+     * hues kept, and images inverted back. Bodies with a dark background or
+     * their own dark color scheme are returned unchanged.
+     */override fun `bodyHtmlDarkMode`(): kotlin.String? {
+            return FfiConverterOptionalString.lift(
+    callWithHandle {
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_core_fn_method_emailhandle_body_html_dark_mode(
+        it,
+        _status)
+}
+    }
+    )
+    }
+    
+
+    
+    /**
+     * Get the HTML body with each `cid:` reference rewritten to `prefix`
+     * followed by the reference (e.g. `letterbox-asset://image001`), so the
+     * WebView can intercept inline parts on one scheme, and the Content-IDs
+     * that match no inline part.
+     */override fun `bodyHtmlWithCidScheme`(`prefix`: kotlin.String): CidRewrite? {
+            return FfiConverterOptionalTypeCidRewrite.lift(
+    callWithHandle {
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_core_fn_method_emailhandle_body_html_with_cid_scheme(
+        it,
+        FfiConverterString.lower(`prefix`),_status)
+}
+    }
+    )
+    }
+    
+
+    
+    /**
+     * Get the HTML body converted to readable plain text: blocks and lists
+     * kept, quotes prefixed with `>`, links as numbered footnotes. For
+     * notifications, snippets and reply quoting when there is no text part.
+     */override fun `bodyTextDerived`(): kotlin.String? {
+            return FfiConverterOptionalString.lift(
+    callWithHandle {
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_core_fn_method_emailhandle_body_text_derived(
+        it,
+        _status)
+}
+    }
+    )
+    }
+    
+
+    
+    /**
+     * Get every external URL the HTML body would load (images, srcsets,
+     * CSS backgrounds and imports, links), once each, in document order.
+     */override fun `remoteResources`(): List<RemoteResource> {
+            return FfiConverterSequenceTypeRemoteResource.lift(
+    callWithHandle {
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_core_fn_method_emailhandle_remote_resources(
+        it,
+        _status)
+}
+    }
+    )
+    }
+    
+
+    
+    /**
+     * Get the HTML body with every external URL replaced by `scheme_prefix`
+     * followed by the form-urlencoded URL, so the WebView loads nothing
+     * remote until the app serves those placeholders itself.
+     */override fun `rewriteRemoteResources`(`schemePrefix`: kotlin.String): kotlin.String? {
+            return FfiConverterOptionalString.lift(
+    callWithHandle {
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_core_fn_method_emailhandle_rewrite_remote_resources(
+        it,
+        FfiConverterString.lower(`schemePrefix`),_status)
+}
+    }
+    )
+    }
+    
+
+    
+    /**
+     * Get the HTML body reduced to what is safe to load in a WebView:
+     * without scripts, event handlers, forms, iframes, CSS `url()` loads or
+     * `position: fixed` overlays.
+     */override fun `sanitizedBodyHtml`(`options`: SanitizeOptions): kotlin.String? {
+            return FfiConverterOptionalString.lift(
+    callWithHandle {
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_core_fn_method_emailhandle_sanitized_body_html(
+        it,
+        FfiConverterTypeSanitizeOptions.lower(`options`),_status)
+}
+    }
+    )
+    }
+    
+
+    
+    /**
+     * Serialize the message like [`Self::to_eml_bytes`], with the
+     * attachments at `indices` replaced by small text attachments noting
+     * their name, type and size, to archive a message without its bulk.
+     * Indices past the last attachment are ignored.
+     */
+    @Throws(ParseException::class)override fun `stripAttachments`(`indices`: List<kotlin.UInt>): kotlin.ByteArray {
+            return FfiConverterByteArray.lift(
+    callWithHandle {
+    uniffiRustCallWithError(ParseException) { _status ->
+    UniffiLib.uniffi_letterbox_core_fn_method_emailhandle_strip_attachments(
+        it,
+        FfiConverterSequenceUInt.lower(`indices`),_status)
+}
+    }
+    )
+    }
+    
+
+    
+    /**
+     * Get a [`MessageBuilder`] holding this message's headers, bodies,
+     * inline parts and attachments, to edit and serialize again, e.g. to
+     * duplicate a draft. Headers the builder writes itself (addresses,
+     * subject, IDs, date, MIME) are taken from the parsed fields, and none
+     * the message lacked is added; the others are kept as they were. Fails
+     * if an attachment cannot be decoded.
+     */
+    @Throws(ParseException::class)override fun `toBuilder`(): MessageBuilder {
+            return FfiConverterTypeMessageBuilder.lift(
+    callWithHandle {
+    uniffiRustCallWithError(ParseException) { _status ->
+    UniffiLib.uniffi_letterbox_core_fn_method_emailhandle_to_builder(
+        it,
+        _status)
+}
+    }
+    )
+    }
+    
+
+    
+    /**
+     * Serialize the message to EML bytes again, through
+     * [`Self::to_builder`]. The MIME structure is rebuilt, so the bytes
+     * differ from the original's while the content is the same.
+     */
+    @Throws(ParseException::class)override fun `toEmlBytes`(): kotlin.ByteArray {
+            return FfiConverterByteArray.lift(
+    callWithHandle {
+    uniffiRustCallWithError(ParseException) { _status ->
+    UniffiLib.uniffi_letterbox_core_fn_method_emailhandle_to_eml_bytes(
+        it,
+        _status)
+}
+    }
+    )
+    }
+    
+
+    
+    /**
+     * Get every top-level header field, in message order.
+     */override fun `allHeaders`(): List<HeaderField> {
+            return FfiConverterSequenceTypeHeaderField.lift(
+    callWithHandle {
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_core_fn_method_emailhandle_all_headers(
+        it,
+        _status)
+}
+    }
+    )
+    }
+    
+
+    
+    /**
+     * Get every "Bcc" address as a structured record, in order.
+     */override fun `bccAddresses`(): List<AddressInfo> {
+            return FfiConverterSequenceTypeAddressInfo.lift(
+    callWithHandle {
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_core_fn_method_emailhandle_bcc_addresses(
+        it,
+        _status)
+}
+    }
+    )
+    }
+    
+
+    
+    /**
+     * Get every "Cc" address as a structured record, in order.
+     */override fun `ccAddresses`(): List<AddressInfo> {
+            return FfiConverterSequenceTypeAddressInfo.lift(
+    callWithHandle {
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_core_fn_method_emailhandle_cc_addresses(
+        it,
+        _status)
+}
+    }
+    )
+    }
+    
+
+    
+    /**
+     * Get every "From" address as a structured record, in order.
+     */override fun `fromAddresses`(): List<AddressInfo> {
+            return FfiConverterSequenceTypeAddressInfo.lift(
+    callWithHandle {
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_core_fn_method_emailhandle_from_addresses(
+        it,
+        _status)
+}
+    }
+    )
+    }
+    
+
+    
+    /**
+     * Get the raw value of the first header named `name` (case-insensitive).
+     */override fun `getHeader`(`name`: kotlin.String): kotlin.String? {
+            return FfiConverterOptionalString.lift(
+    callWithHandle {
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_core_fn_method_emailhandle_get_header(
+        it,
+        FfiConverterString.lower(`name`),_status)
+}
+    }
+    )
+    }
+    
+
+    
+    /**
+     * Get the raw values of every header named `name` (case-insensitive),
+     * in message order.
+     */override fun `getHeaders`(`name`: kotlin.String): List<kotlin.String> {
+            return FfiConverterSequenceString.lift(
+    callWithHandle {
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_core_fn_method_emailhandle_get_headers(
+        it,
+        FfiConverterString.lower(`name`),_status)
+}
+    }
+    )
+    }
+    
+
+    
+    /**
+     * Get the first message ID this message replies to (In-Reply-To),
+     * without angle brackets; empty if none.
+     */override fun `inReplyTo`(): kotlin.String {
+            return FfiConverterString.lift(
+    callWithHandle {
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_core_fn_method_emailhandle_in_reply_to(
+        it,
+        _status)
+}
+    }
+    )
+    }
+    
+
+    
+    /**
+     * Get structured recipient information (To + Cc).
+     * Returns list of AddressInfo for all recipients for search indexing.
+     */override fun `recipientInfo`(): List<AddressInfo> {
+            return FfiConverterSequenceTypeAddressInfo.lift(
+    callWithHandle {
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_core_fn_method_emailhandle_recipient_info(
+        it,
+        _status)
+}
+    }
+    )
+    }
+    
+
+    
+    /**
+     * Get the message IDs of the References header, oldest first, without
+     * angle brackets. Together with [`Self::in_reply_to`] this places the
+     * message in its conversation thread.
+     */override fun `references`(): List<kotlin.String> {
+            return FfiConverterSequenceString.lift(
+    callWithHandle {
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_core_fn_method_emailhandle_references(
+        it,
+        _status)
+}
+    }
+    )
+    }
+    
+
+    
+    /**
+     * Get every "Reply-To" address as a structured record, in order.
+     */override fun `replyToAddresses`(): List<AddressInfo> {
+            return FfiConverterSequenceTypeAddressInfo.lift(
+    callWithHandle {
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_core_fn_method_emailhandle_reply_to_addresses(
+        it,
+        _status)
+}
+    }
+    )
+    }
+    
+
+    
+    /**
+     * Get structured sender information.
+     * Returns AddressInfo with separate email and name fields for search indexing.
+     */override fun `senderInfo`(): AddressInfo {
+            return FfiConverterTypeAddressInfo.lift(
+    callWithHandle {
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_core_fn_method_emailhandle_sender_info(
+        it,
+        _status)
+}
+    }
+    )
+    }
+    
+
+    
+    /**
+     * Get what [`ThreadBuilder`](crate::ThreadBuilder) needs to thread this message.
+     */override fun `threadMessage`(): ThreadMessage {
+            return FfiConverterTypeThreadMessage.lift(
+    callWithHandle {
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_core_fn_method_emailhandle_thread_message(
+        it,
+        _status)
+}
+    }
+    )
+    }
+    
+
+    
+    /**
+     * Get every "To" address as a structured record, in order.
+     */override fun `toAddresses`(): List<AddressInfo> {
+            return FfiConverterSequenceTypeAddressInfo.lift(
+    callWithHandle {
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_core_fn_method_emailhandle_to_addresses(
+        it,
+        _status)
+}
+    }
+    )
+    }
+    
+
+    
+
+    
+
+
+    
+    
+    /**
+     * @suppress
+     */
+    companion object
+    
+}
+
+
+/**
+ * @suppress
+ */
+public object FfiConverterTypeEmailHandle: FfiConverter<EmailHandle, Long> {
+    override fun lower(value: EmailHandle): Long {
+        return value.uniffiCloneHandle()
+    }
+
+    override fun lift(value: Long): EmailHandle {
+        return EmailHandle(UniffiWithHandle, value)
+    }
+
+    override fun read(buf: ByteBuffer): EmailHandle {
+        return lift(buf.getLong())
+    }
+
+    override fun allocationSize(value: EmailHandle) = 8UL
+
+    override fun write(value: EmailHandle, buf: ByteBuffer) {
+        buf.putLong(lower(value))
+    }
+}
+
+
+// This template implements a class for working with a Rust struct via a handle
+// to the live Rust struct on the other side of the FFI.
+//
+// There's some subtlety here, because we have to be careful not to operate on a Rust
+// struct after it has been dropped, and because we must expose a public API for freeing
+// theq Kotlin wrapper object in lieu of reliable finalizers. The core requirements are:
+//
+//   * Each instance holds an opaque handle to the underlying Rust struct.
+//     Method calls need to read this handle from the object's state and pass it in to
+//     the Rust FFI.
+//
+//   * When an instance is no longer needed, its handle should be passed to a
+//     special destructor function provided by the Rust FFI, which will drop the
+//     underlying Rust struct.
+//
+//   * Given an instance, calling code is expected to call the special
+//     `destroy` method in order to free it after use, either by calling it explicitly
+//     or by using a higher-level helper like the `use` method. Failing to do so risks
+//     leaking the underlying Rust struct.
+//
+//   * We can't assume that calling code will do the right thing, and must be prepared
+//     to handle Kotlin method calls executing concurrently with or even after a call to
+//     `destroy`, and to handle multiple (possibly concurrent!) calls to `destroy`.
+//
+//   * We must never allow Rust code to operate on the underlying Rust struct after
+//     the destructor has been called, and must never call the destructor more than once.
+//     Doing so may trigger memory unsafety.
+//
+//   * To mitigate many of the risks of leaking memory and use-after-free unsafety, a `Cleaner`
+//     is implemented to call the destructor when the Kotlin object becomes unreachable.
+//     This is done in a background thread. This is not a panacea, and client code should be aware that
+//      1. the thread may starve if some there are objects that have poorly performing
+//     `drop` methods or do significant work in their `drop` methods.
+//      2. the thread is shared across the whole library. This can be tuned by using `android_cleaner = true`,
+//         or `android = true` in the [`kotlin` section of the `uniffi.toml` file](https://mozilla.github.io/uniffi-rs/kotlin/configuration.html).
+//
+// If we try to implement this with mutual exclusion on access to the handle, there is the
+// possibility of a race between a method call and a concurrent call to `destroy`:
+//
+//    * Thread A starts a method call, reads the value of the handle, but is interrupted
+//      before it can pass the handle over the FFI to Rust.
+//    * Thread B calls `destroy` and frees the underlying Rust struct.
+//    * Thread A resumes, passing the already-read handle value to Rust and triggering
+//      a use-after-free.
+//
+// One possible solution would be to use a `ReadWriteLock`, with each method call taking
+// a read lock (and thus allowed to run concurrently) and the special `destroy` method
+// taking a write lock (and thus blocking on live method calls). However, we aim not to
+// generate methods with any hidden blocking semantics, and a `destroy` method that might
+// block if called incorrectly seems to meet that bar.
+//
+// So, we achieve our goals by giving each instance an associated `AtomicLong` counter to track
+// the number of in-flight method calls, and an `AtomicBoolean` flag to indicate whether `destroy`
+// has been called. These are updated according to the following rules:
+//
+//    * The initial value of the counter is 1, indicating a live object with no in-flight calls.
+//      The initial value for the flag is false.
+//
+//    * At the start of each method call, we atomically check the counter.
+//      If it is 0 then the underlying Rust struct has already been destroyed and the call is aborted.
+//      If it is nonzero them we atomically increment it by 1 and proceed with the method call.
+//
+//    * At the end of each method call, we atomically decrement and check the counter.
+//      If it has reached zero then we destroy the underlying Rust struct.
+//
+//    * When `destroy` is called, we atomically flip the flag from false to true.
+//      If the flag was already true we silently fail.
+//      Otherwise we atomically decrement and check the counter.
+//      If it has reached zero then we destroy the underlying Rust struct.
+//
+// Astute readers may observe that this all sounds very similar to the way that Rust's `Arc<T>` works,
+// and indeed it is, with the addition of a flag to guard against multiple calls to `destroy`.
+//
+// The overall effect is that the underlying Rust struct is destroyed only when `destroy` has been
+// called *and* all in-flight method calls have completed, avoiding violating any of the expectations
+// of the underlying Rust code.
+//
+// This makes a cleaner a better alternative to _not_ calling `destroy()` as
+// and when the object is finished with, but the abstraction is not perfect: if the Rust object's `drop`
+// method is slow, and/or there are many objects to cleanup, and it's on a low end Android device, then the cleaner
+// thread may be starved, and the app will leak memory.
+//
+// In this case, `destroy`ing manually may be a better solution.
+//
+// The cleaner can live side by side with the manual calling of `destroy`. In the order of responsiveness, uniffi objects
+// with Rust peers are reclaimed:
+//
+// 1. By calling the `destroy` method of the object, which calls `rustObject.free()`. If that doesn't happen:
+// 2. When the object becomes unreachable, AND the Cleaner thread gets to call `rustObject.free()`. If the thread is starved then:
+// 3. The memory is reclaimed when the process terminates.
+//
+// [1] https://stackoverflow.com/questions/24376768/can-java-finalize-an-object-when-it-is-still-in-scope/24380219
+//
+
+
+/**
+ * Builds a message and serializes it to EML.
+ */
+public interface MessageBuilderInterface {
+    
+    fun `addAttachment`(`name`: kotlin.String, `contentType`: kotlin.String, `data`: kotlin.ByteArray, `encoding`: TransferEncoding)
+    
+    /**
+     * Add a header of the caller's, such as `X-Mailer`. MIME headers and
+     * those set through the builder's fields are refused: the builder
+     * writes them itself.
+     */
+    fun `addHeader`(`name`: kotlin.String, `value`: kotlin.String)
+    
+    /**
+     * Add a part the HTML body shows through `cid:` URLs.
+     */
+    fun `addInline`(`cid`: kotlin.String, `contentType`: kotlin.String, `data`: kotlin.ByteArray, `encoding`: TransferEncoding)
+    
+    /**
+     * Bcc is written into the message, as for a draft; leave it out of a
+     * copy that is sent.
+     */
+    fun `setBcc`(`addresses`: List<AddressInfo>)
+    
+    fun `setCc`(`addresses`: List<AddressInfo>)
+    
+    /**
+     * Date in milliseconds since Unix epoch; the time of serialization if
+     * unset.
+     */
+    fun `setDateTimestamp`(`timestampMs`: kotlin.Long)
+    
+    fun `setFrom`(`address`: AddressInfo)
+    
+    fun `setHtmlBody`(`html`: kotlin.String)
+    
+    /**
+     * Message ID this message replies to, with or without angle brackets.
+     */
+    fun `setInReplyTo`(`messageId`: kotlin.String)
+    
+    /**
+     * Message-ID, with or without angle brackets; one is generated if unset.
+     */
+    fun `setMessageId`(`messageId`: kotlin.String)
+    
+    /**
+     * Message IDs of the thread, oldest first.
+     */
+    fun `setReferences`(`messageIds`: List<kotlin.String>)
+    
+    fun `setReplyTo`(`addresses`: List<AddressInfo>)
+    
+    fun `setSubject`(`subject`: kotlin.String)
+    
+    fun `setTextBody`(`text`: kotlin.String)
+    
+    fun `setTo`(`addresses`: List<AddressInfo>)
+    
+    /**
+     * Serialize the message to EML bytes.
+     */
+    fun `toEmlBytes`(): kotlin.ByteArray
+    
+    companion object
+}
+
+/**
+ * Builds a message and serializes it to EML.
+ */
+open class MessageBuilder: Disposable, AutoCloseable, MessageBuilderInterface
+{
+
+    @Suppress("UNUSED_PARAMETER")
+    /**
+     * @suppress
+     */
+    constructor(withHandle: UniffiWithHandle, handle: Long) {
+        this.handle = handle
+        this.cleanable = UniffiLib.CLEANER.register(this, UniffiCleanAction(handle))
+    }
+
+    /**
+     * @suppress
+     *
+     * This constructor can be used to instantiate a fake object. Only used for tests. Any
+     * attempt to actually use an object constructed this way will fail as there is no
+     * connected Rust object.
+     */
+    @Suppress("UNUSED_PARAMETER")
+    constructor(noHandle: NoHandle) {
+        this.handle = 0
+        this.cleanable = null
+    }
+    constructor() :
+        this(UniffiWithHandle, 
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_core_fn_constructor_messagebuilder_new(
+    
+        _status)
+}
+    )
+
+    protected val handle: Long
+    protected val cleanable: UniffiCleaner.Cleanable?
+
+    private val wasDestroyed = AtomicBoolean(false)
+    private val callCounter = AtomicLong(1)
+
+    override fun destroy() {
+        // Only allow a single call to this method.
+        // TODO: maybe we should log a warning if called more than once?
+        if (this.wasDestroyed.compareAndSet(false, true)) {
+            // This decrement always matches the initial count of 1 given at creation time.
+            if (this.callCounter.decrementAndGet() == 0L) {
+                cleanable?.clean()
+            }
+        }
+    }
+
+    @Synchronized
+    override fun close() {
+        this.destroy()
+    }
+
+    internal inline fun <R> callWithHandle(block: (handle: Long) -> R): R {
+        // Check and increment the call counter, to keep the object alive.
+        // This needs a compare-and-set retry loop in case of concurrent updates.
+        do {
+            val c = this.callCounter.get()
+            if (c == 0L) {
+                throw IllegalStateException("${this.javaClass.simpleName} object has already been destroyed")
+            }
+            if (c == Long.MAX_VALUE) {
+                throw IllegalStateException("${this.javaClass.simpleName} call counter would overflow")
+            }
+        } while (! this.callCounter.compareAndSet(c, c + 1L))
+        // Now we can safely do the method call without the handle being freed concurrently.
+        try {
+            return block(this.uniffiCloneHandle())
+        } finally {
+            // This decrement always matches the increment we performed above.
+            if (this.callCounter.decrementAndGet() == 0L) {
+                cleanable?.clean()
+            }
+        }
+    }
+
+    // Use a static inner class instead of a closure so as not to accidentally
+    // capture `this` as part of the cleanable's action.
+    private class UniffiCleanAction(private val handle: Long) : Runnable {
+        override fun run() {
+            if (handle == 0.toLong()) {
+                // Fake object created with `NoHandle`, don't try to free.
+                return;
+            }
+            uniffiRustCall { status ->
+                UniffiLib.uniffi_letterbox_core_fn_free_messagebuilder(handle, status)
+            }
+        }
+    }
+
+    /**
+     * @suppress
+     */
+    fun uniffiCloneHandle(): Long {
+        if (handle == 0.toLong()) {
+            throw InternalException("uniffiCloneHandle() called on NoHandle object");
+        }
+        return uniffiRustCall() { status ->
+            UniffiLib.uniffi_letterbox_core_fn_clone_messagebuilder(handle, status)
+        }
+    }
+
+    override fun `addAttachment`(`name`: kotlin.String, `contentType`: kotlin.String, `data`: kotlin.ByteArray, `encoding`: TransferEncoding)
+        = 
+    callWithHandle {
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_core_fn_method_messagebuilder_add_attachment(
+        it,
+        FfiConverterString.lower(`name`),FfiConverterString.lower(`contentType`),FfiConverterByteArray.lower(`data`),FfiConverterTypeTransferEncoding.lower(`encoding`),_status)
+}
+    }
+    
+    
+
+    
+    /**
+     * Add a header of the caller's, such as `X-Mailer`. MIME headers and
+     * those set through the builder's fields are refused: the builder
+     * writes them itself.
+     */
+    @Throws(ParseException::class)override fun `addHeader`(`name`: kotlin.String, `value`: kotlin.String)
+        = 
+    callWithHandle {
+    uniffiRustCallWithError(ParseException) { _status ->
+    UniffiLib.uniffi_letterbox_core_fn_method_messagebuilder_add_header(
+        it,
+        FfiConverterString.lower(`name`),FfiConverterString.lower(`value`),_status)
+}
+    }
+    
+    
+
+    
+    /**
+     * Add a part the HTML body shows through `cid:` URLs.
+     */override fun `addInline`(`cid`: kotlin.String, `contentType`: kotlin.String, `data`: kotlin.ByteArray, `encoding`: TransferEncoding)
+        = 
+    callWithHandle {
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_core_fn_method_messagebuilder_add_inline(
+        it,
+        FfiConverterString.lower(`cid`),FfiConverterString.lower(`contentType`),FfiConverterByteArray.lower(`data`),FfiConverterTypeTransferEncoding.lower(`encoding`),_status)
+}
+    }
+    
+    
+
+    
+    /**
+     * Bcc is written into the message, as for a draft; leave it out of a
+     * copy that is sent.
+     */override fun `setBcc`(`addresses`: List<AddressInfo>)
+        = 
+    callWithHandle {
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_core_fn_method_messagebuilder_set_bcc(
+        it,
+        FfiConverterSequenceTypeAddressInfo.lower(`addresses`),_status)
+}
+    }
+    
+    
+
+    override fun `setCc`(`addresses`: List<AddressInfo>)
+        = 
+    callWithHandle {
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_core_fn_method_messagebuilder_set_cc(
+        it,
+        FfiConverterSequenceTypeAddressInfo.lower(`addresses`),_status)
+}
+    }
+    
+    
+
+    
+    /**
+     * Date in milliseconds since Unix epoch; the time of serialization if
+     * unset.
+     */override fun `setDateTimestamp`(`timestampMs`: kotlin.Long)
+        = 
+    callWithHandle {
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_core_fn_method_messagebuilder_set_date_timestamp(
+        it,
+        FfiConverterLong.lower(`timestampMs`),_status)
+}
+    }
+    
+    
+
+    override fun `setFrom`(`address`: AddressInfo)
+        = 
+    callWithHandle {
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_core_fn_method_messagebuilder_set_from(
+        it,
+        FfiConverterTypeAddressInfo.lower(`address`),_status)
+}
+    }
+    
+    
+
+    override fun `setHtmlBody`(`html`: kotlin.String)
+        = 
+    callWithHandle {
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_core_fn_method_messagebuilder_set_html_body(
+        it,
+        FfiConverterString.lower(`html`),_status)
+}
+    }
+    
+    
+
+    
+    /**
+     * Message ID this message replies to, with or without angle brackets.
+     */override fun `setInReplyTo`(`messageId`: kotlin.String)
+        = 
+    callWithHandle {
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_core_fn_method_messagebuilder_set_in_reply_to(
+        it,
+        FfiConverterString.lower(`messageId`),_status)
+}
+    }
+    
+    
+
+    
+    /**
+     * Message-ID, with or without angle brackets; one is generated if unset.
+     */override fun `setMessageId`(`messageId`: kotlin.String)
+        = 
+    callWithHandle {
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_core_fn_method_messagebuilder_set_message_id(
+        it,
+        FfiConverterString.lower(`messageId`),_status)
+}
+    }
+    
+    
+
+    
+    /**
+     * Message IDs of the thread, oldest first.
+     */override fun `setReferences`(`messageIds`: List<kotlin.String>)
+        = 
+    callWithHandle {
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_core_fn_method_messagebuilder_set_references(
+        it,
+        FfiConverterSequenceString.lower(`messageIds`),_status)
+}
+    }
+    
+    
+
+    override fun `setReplyTo`(`addresses`: List<AddressInfo>)
+        = 
+    callWithHandle {
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_core_fn_method_messagebuilder_set_reply_to(
+        it,
+        FfiConverterSequenceTypeAddressInfo.lower(`addresses`),_status)
+}
+    }
+    
+    
+
+    override fun `setSubject`(`subject`: kotlin.String)
+        = 
+    callWithHandle {
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_core_fn_method_messagebuilder_set_subject(
+        it,
+        FfiConverterString.lower(`subject`),_status)
+}
+    }
+    
+    
+
+    override fun `setTextBody`(`text`: kotlin.String)
+        = 
+    callWithHandle {
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_core_fn_method_messagebuilder_set_text_body(
+        it,
+        FfiConverterString.lower(`text`),_status)
+}
+    }
+    
+    
+
+    override fun `setTo`(`addresses`: List<AddressInfo>)
+        = 
+    callWithHandle {
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_core_fn_method_messagebuilder_set_to(
+        it,
+        FfiConverterSequenceTypeAddressInfo.lower(`addresses`),_status)
+}
+    }
+    
+    
+
+    
+    /**
+     * Serialize the message to EML bytes.
+     */override fun `toEmlBytes`(): kotlin.ByteArray {
+            return FfiConverterByteArray.lift(
+    callWithHandle {
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_core_fn_method_messagebuilder_to_eml_bytes(
+        it,
+        _status)
+}
+    }
+    )
+    }
+    
+
+    
+
+    
+
+
+    
+    
+    /**
+     * @suppress
+     */
+    companion object
+    
+}
+
+
+/**
+ * @suppress
+ */
+public object FfiConverterTypeMessageBuilder: FfiConverter<MessageBuilder, Long> {
+    override fun lower(value: MessageBuilder): Long {
+        return value.uniffiCloneHandle()
+    }
+
+    override fun lift(value: Long): MessageBuilder {
+        return MessageBuilder(UniffiWithHandle, value)
+    }
+
+    override fun read(buf: ByteBuffer): MessageBuilder {
+        return lift(buf.getLong())
+    }
+
+    override fun allocationSize(value: MessageBuilder) = 8UL
+
+    override fun write(value: MessageBuilder, buf: ByteBuffer) {
+        buf.putLong(lower(value))
+    }
+}
+
+
+// This template implements a class for working with a Rust struct via a handle
+// to the live Rust struct on the other side of the FFI.
+//
+// There's some subtlety here, because we have to be careful not to operate on a Rust
+// struct after it has been dropped, and because we must expose a public API for freeing
+// theq Kotlin wrapper object in lieu of reliable finalizers. The core requirements are:
+//
+//   * Each instance holds an opaque handle to the underlying Rust struct.
+//     Method calls need to read this handle from the object's state and pass it in to
+//     the Rust FFI.
+//
+//   * When an instance is no longer needed, its handle should be passed to a
+//     special destructor function provided by the Rust FFI, which will drop the
+//     underlying Rust struct.
+//
+//   * Given an instance, calling code is expected to call the special
+//     `destroy` method in order to free it after use, either by calling it explicitly
+//     or by using a higher-level helper like the `use` method. Failing to do so risks
+//     leaking the underlying Rust struct.
+//
+//   * We can't assume that calling code will do the right thing, and must be prepared
+//     to handle Kotlin method calls executing concurrently with or even after a call to
+//     `destroy`, and to handle multiple (possibly concurrent!) calls to `destroy`.
+//
+//   * We must never allow Rust code to operate on the underlying Rust struct after
+//     the destructor has been called, and must never call the destructor more than once.
+//     Doing so may trigger memory unsafety.
+//
+//   * To mitigate many of the risks of leaking memory and use-after-free unsafety, a `Cleaner`
+//     is implemented to call the destructor when the Kotlin object becomes unreachable.
+//     This is done in a background thread. This is not a panacea, and client code should be aware that
+//      1. the thread may starve if some there are objects that have poorly performing
+//     `drop` methods or do significant work in their `drop` methods.
+//      2. the thread is shared across the whole library. This can be tuned by using `android_cleaner = true`,
+//         or `android = true` in the [`kotlin` section of the `uniffi.toml` file](https://mozilla.github.io/uniffi-rs/kotlin/configuration.html).
+//
+// If we try to implement this with mutual exclusion on access to the handle, there is the
+// possibility of a race between a method call and a concurrent call to `destroy`:
+//
+//    * Thread A starts a method call, reads the value of the handle, but is interrupted
+//      before it can pass the handle over the FFI to Rust.
+//    * Thread B calls `destroy` and frees the underlying Rust struct.
+//    * Thread A resumes, passing the already-read handle value to Rust and triggering
+//      a use-after-free.
+//
+// One possible solution would be to use a `ReadWriteLock`, with each method call taking
+// a read lock (and thus allowed to run concurrently) and the special `destroy` method
+// taking a write lock (and thus blocking on live method calls). However, we aim not to
+// generate methods with any hidden blocking semantics, and a `destroy` method that might
+// block if called incorrectly seems to meet that bar.
+//
+// So, we achieve our goals by giving each instance an associated `AtomicLong` counter to track
+// the number of in-flight method calls, and an `AtomicBoolean` flag to indicate whether `destroy`
+// has been called. These are updated according to the following rules:
+//
+//    * The initial value of the counter is 1, indicating a live object with no in-flight calls.
+//      The initial value for the flag is false.
+//
+//    * At the start of each method call, we atomically check the counter.
+//      If it is 0 then the underlying Rust struct has already been destroyed and the call is aborted.
+//      If it is nonzero them we atomically increment it by 1 and proceed with the method call.
+//
+//    * At the end of each method call, we atomically decrement and check the counter.
+//      If it has reached zero then we destroy the underlying Rust struct.
+//
+//    * When `destroy` is called, we atomically flip the flag from false to true.
+//      If the flag was already true we silently fail.
+//      Otherwise we atomically decrement and check the counter.
+//      If it has reached zero then we destroy the underlying Rust struct.
+//
+// Astute readers may observe that this all sounds very similar to the way that Rust's `Arc<T>` works,
+// and indeed it is, with the addition of a flag to guard against multiple calls to `destroy`.
+//
+// The overall effect is that the underlying Rust struct is destroyed only when `destroy` has been
+// called *and* all in-flight method calls have completed, avoiding violating any of the expectations
+// of the underlying Rust code.
+//
+// This makes a cleaner a better alternative to _not_ calling `destroy()` as
+// and when the object is finished with, but the abstraction is not perfect: if the Rust object's `drop`
+// method is slow, and/or there are many objects to cleanup, and it's on a low end Android device, then the cleaner
+// thread may be starved, and the app will leak memory.
+//
+// In this case, `destroy`ing manually may be a better solution.
+//
+// The cleaner can live side by side with the manual calling of `destroy`. In the order of responsiveness, uniffi objects
+// with Rust peers are reclaimed:
+//
+// 1. By calling the `destroy` method of the object, which calls `rustObject.free()`. If that doesn't happen:
+// 2. When the object becomes unreachable, AND the Cleaner thread gets to call `rustObject.free()`. If the thread is starved then:
+// 3. The memory is reclaimed when the process terminates.
+//
+// [1] https://stackoverflow.com/questions/24376768/can-java-finalize-an-object-when-it-is-still-in-scope/24380219
+//
+
+
+/**
+ * Collects messages and threads them.
+ */
+public interface ThreadBuilderInterface {
+    
+    /**
+     * Add a message; its index is the number of messages added before it.
+     */
+    fun `add`(`message`: ThreadMessage)
+    
+    /**
+     * Add several messages in order.
+     */
+    fun `addAll`(`messages`: List<ThreadMessage>)
+    
+    /**
+     * Thread the messages added so far. Threads with the latest activity
+     * come first; replies are ordered oldest first.
+     */
+    fun `build`(): List<ThreadNode>
+    
+    companion object
+}
+
+/**
+ * Collects messages and threads them.
+ */
+open class ThreadBuilder: Disposable, AutoCloseable, ThreadBuilderInterface
+{
+
+    @Suppress("UNUSED_PARAMETER")
+    /**
+     * @suppress
+     */
+    constructor(withHandle: UniffiWithHandle, handle: Long) {
+        this.handle = handle
+        this.cleanable = UniffiLib.CLEANER.register(this, UniffiCleanAction(handle))
+    }
+
+    /**
+     * @suppress
+     *
+     * This constructor can be used to instantiate a fake object. Only used for tests. Any
+     * attempt to actually use an object constructed this way will fail as there is no
+     * connected Rust object.
+     */
+    @Suppress("UNUSED_PARAMETER")
+    constructor(noHandle: NoHandle) {
+        this.handle = 0
+        this.cleanable = null
+    }
+    constructor() :
+        this(UniffiWithHandle, 
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_core_fn_constructor_threadbuilder_new(
+    
+        _status)
+}
+    )
+
+    protected val handle: Long
+    protected val cleanable: UniffiCleaner.Cleanable?
+
+    private val wasDestroyed = AtomicBoolean(false)
+    private val callCounter = AtomicLong(1)
+
+    override fun destroy() {
+        // Only allow a single call to this method.
+        // TODO: maybe we should log a warning if called more than once?
+        if (this.wasDestroyed.compareAndSet(false, true)) {
+            // This decrement always matches the initial count of 1 given at creation time.
+            if (this.callCounter.decrementAndGet() == 0L) {
+                cleanable?.clean()
+            }
+        }
+    }
+
+    @Synchronized
+    override fun close() {
+        this.destroy()
+    }
+
+    internal inline fun <R> callWithHandle(block: (handle: Long) -> R): R {
+        // Check and increment the call counter, to keep the object alive.
+        // This needs a compare-and-set retry loop in case of concurrent updates.
+        do {
+            val c = this.callCounter.get()
+            if (c == 0L) {
+                throw IllegalStateException("${this.javaClass.simpleName} object has already been destroyed")
+            }
+            if (c == Long.MAX_VALUE) {
+                throw IllegalStateException("${this.javaClass.simpleName} call counter would overflow")
+            }
+        } while (! this.callCounter.compareAndSet(c, c + 1L))
+        // Now we can safely do the method call without the handle being freed concurrently.
+        try {
+            return block(this.uniffiCloneHandle())
+        } finally {
+            // This decrement always matches the increment we performed above.
+            if (this.callCounter.decrementAndGet() == 0L) {
+                cleanable?.clean()
+            }
+        }
+    }
+
+    // Use a static inner class instead of a closure so as not to accidentally
+    // capture `this` as part of the cleanable's action.
+    private class UniffiCleanAction(private val handle: Long) : Runnable {
+        override fun run() {
+            if (handle == 0.toLong()) {
+                // Fake object created with `NoHandle`, don't try to free.
+                return;
+            }
+            uniffiRustCall { status ->
+                UniffiLib.uniffi_letterbox_core_fn_free_threadbuilder(handle, status)
+            }
+        }
+    }
+
+    /**
+     * @suppress
+     */
+    fun uniffiCloneHandle(): Long {
+        if (handle == 0.toLong()) {
+            throw InternalException("uniffiCloneHandle() called on NoHandle object");
+        }
+        return uniffiRustCall() { status ->
+            UniffiLib.uniffi_letterbox_core_fn_clone_threadbuilder(handle, status)
+        }
+    }
+
+    
+    /**
+     * Add a message; its index is the number of messages added before it.
+     */override fun `add`(`message`: ThreadMessage)
+        = 
+    callWithHandle {
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_core_fn_method_threadbuilder_add(
+        it,
+        FfiConverterTypeThreadMessage.lower(`message`),_status)
+}
+    }
+    
+    
+
+    
+    /**
+     * Add several messages in order.
+     */override fun `addAll`(`messages`: List<ThreadMessage>)
+        = 
+    callWithHandle {
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_core_fn_method_threadbuilder_add_all(
+        it,
+        FfiConverterSequenceTypeThreadMessage.lower(`messages`),_status)
+}
+    }
+    
+    
+
+    
+    /**
+     * Thread the messages added so far. Threads with the latest activity
+     * come first; replies are ordered oldest first.
+     */override fun `build`(): List<ThreadNode> {
+            return FfiConverterSequenceTypeThreadNode.lift(
+    callWithHandle {
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_core_fn_method_threadbuilder_build(
+        it,
+        _status)
+}
+    }
+    )
+    }
+    
+
+    
+
+    
+
+
+    
+    
+    /**
+     * @suppress
+     */
+    companion object
+    
+}
+
+
+/**
+ * @suppress
+ */
+public object FfiConverterTypeThreadBuilder: FfiConverter<ThreadBuilder, Long> {
+    override fun lower(value: ThreadBuilder): Long {
+        return value.uniffiCloneHandle()
+    }
+
+    override fun lift(value: Long): ThreadBuilder {
+        return ThreadBuilder(UniffiWithHandle, value)
+    }
+
+    override fun read(buf: ByteBuffer): ThreadBuilder {
+        return lift(buf.getLong())
+    }
+
+    override fun allocationSize(value: ThreadBuilder) = 8UL
+
+    override fun write(value: ThreadBuilder, buf: ByteBuffer) {
+        buf.putLong(lower(value))
+    }
+}
+
+
+
+/**
+ * Structured address information for search and filtering.
+ * Exposed to Kotlin via UniFFI to enable separate indexing of name and email.
+ */
+data class AddressInfo (
+    /**
+     * Email address (e.g., "sender@example.com")
+     */
+    var `email`: kotlin.String
+    , 
+    /**
+     * Display name (e.g., "John Doe"), empty if not available
      */
+    var `name`: kotlin.String
+    
+){
+    
+
+    
+
+    
     companion object
+}
+
+/**
+ * @suppress
+ */
+public object FfiConverterTypeAddressInfo: FfiConverterRustBuffer<AddressInfo> {
+    override fun read(buf: ByteBuffer): AddressInfo {
+        return AddressInfo(
+            FfiConverterString.read(buf),
+            FfiConverterString.read(buf),
+        )
+    }
+
+    override fun allocationSize(value: AddressInfo) = (
+            FfiConverterString.allocationSize(value.`email`) +
+            FfiConverterString.allocationSize(value.`name`)
+    )
+
+    override fun write(value: AddressInfo, buf: ByteBuffer) {
+            FfiConverterString.write(value.`email`, buf)
+            FfiConverterString.write(value.`name`, buf)
+    }
+}
+
+
+
+/**
+ * Attachment metadata exposed to Kotlin via UniFFI.
+ */
+data class AttachmentInfo (
+    var `name`: kotlin.String
+    , 
+    var `contentType`: kotlin.String
+    , 
+    var `size`: kotlin.ULong
+    
+){
+    
+
+    
+
     
+    companion object
+}
+
+/**
+ * @suppress
+ */
+public object FfiConverterTypeAttachmentInfo: FfiConverterRustBuffer<AttachmentInfo> {
+    override fun read(buf: ByteBuffer): AttachmentInfo {
+        return AttachmentInfo(
+            FfiConverterString.read(buf),
+            FfiConverterString.read(buf),
+            FfiConverterULong.read(buf),
+        )
+    }
+
+    override fun allocationSize(value: AttachmentInfo) = (
+            FfiConverterString.allocationSize(value.`name`) +
+            FfiConverterString.allocationSize(value.`contentType`) +
+            FfiConverterULong.allocationSize(value.`size`)
+    )
+
+    override fun write(value: AttachmentInfo, buf: ByteBuffer) {
+            FfiConverterString.write(value.`name`, buf)
+            FfiConverterString.write(value.`contentType`, buf)
+            FfiConverterULong.write(value.`size`, buf)
+    }
 }
 
 
+
+/**
+ * HTML body with its `cid:` references moved to an app scheme.
+ */
+data class CidRewrite (
+    var `html`: kotlin.String
+    , 
+    /**
+     * Content-IDs referenced by the body that no inline part carries
+     */
+    var `unresolvedCids`: List<kotlin.String>
+    
+){
+    
+
+    
+
+    
+    companion object
+}
+
 /**
  * @suppress
  */
-public object FfiConverterTypeEmailHandle: FfiConverter<EmailHandle, Long> {
-    override fun lower(value: EmailHandle): Long {
-        return value.uniffiCloneHandle()
+public object FfiConverterTypeCidRewrite: FfiConverterRustBuffer<CidRewrite> {
+    override fun read(buf: ByteBuffer): CidRewrite {
+        return CidRewrite(
+            FfiConverterString.read(buf),
+            FfiConverterSequenceString.read(buf),
+        )
     }
 
-    override fun lift(value: Long): EmailHandle {
-        return EmailHandle(UniffiWithHandle, value)
+    override fun allocationSize(value: CidRewrite) = (
+            FfiConverterString.allocationSize(value.`html`) +
+            FfiConverterSequenceString.allocationSize(value.`unresolvedCids`)
+    )
+
+    override fun write(value: CidRewrite, buf: ByteBuffer) {
+            FfiConverterString.write(value.`html`, buf)
+            FfiConverterSequenceString.write(value.`unresolvedCids`, buf)
     }
+}
 
-    override fun read(buf: ByteBuffer): EmailHandle {
-        return lift(buf.getLong())
+
+
+/**
+ * A header field as it appears in the message.
+ */
+data class HeaderField (
+    /**
+     * Field name (e.g., "List-Id")
+     */
+    var `name`: kotlin.String
+    , 
+    /**
+     * Raw value, trimmed but neither unfolded nor decoded
+     */
+    var `value`: kotlin.String
+    
+){
+    
+
+    
+
+    
+    companion object
+}
+
+/**
+ * @suppress
+ */
+public object FfiConverterTypeHeaderField: FfiConverterRustBuffer<HeaderField> {
+    override fun read(buf: ByteBuffer): HeaderField {
+        return HeaderField(
+            FfiConverterString.read(buf),
+            FfiConverterString.read(buf),
+        )
+    }
+
+    override fun allocationSize(value: HeaderField) = (
+            FfiConverterString.allocationSize(value.`name`) +
+            FfiConverterString.allocationSize(value.`value`)
+    )
+
+    override fun write(value: HeaderField, buf: ByteBuffer) {
+            FfiConverterString.write(value.`name`, buf)
+            FfiConverterString.write(value.`value`, buf)
+    }
+}
+
+
+
+/**
+ * Result of extracting remote image URLs from HTML.
+ */
+data class RemoteImage (
+    /**
+     * Original image URL (http://, https:// or protocol-relative)
+     */
+    var `url`: kotlin.String
+    , 
+    /**
+     * Whether this is a tracking pixel (1x1 image)
+     */
+    var `isTrackingPixel`: kotlin.Boolean
+    
+){
+    
+
+    
+
+    
+    companion object
+}
+
+/**
+ * @suppress
+ */
+public object FfiConverterTypeRemoteImage: FfiConverterRustBuffer<RemoteImage> {
+    override fun read(buf: ByteBuffer): RemoteImage {
+        return RemoteImage(
+            FfiConverterString.read(buf),
+            FfiConverterBoolean.read(buf),
+        )
+    }
+
+    override fun allocationSize(value: RemoteImage) = (
+            FfiConverterString.allocationSize(value.`url`) +
+            FfiConverterBoolean.allocationSize(value.`isTrackingPixel`)
+    )
+
+    override fun write(value: RemoteImage, buf: ByteBuffer) {
+            FfiConverterString.write(value.`url`, buf)
+            FfiConverterBoolean.write(value.`isTrackingPixel`, buf)
+    }
+}
+
+
+
+/**
+ * An external URL the body would load.
+ */
+data class RemoteResource (
+    var `url`: kotlin.String
+    , 
+    var `kind`: RemoteResourceKind
+    , 
+    /**
+     * Whether the URL is the source of an image declared 1×1 or smaller,
+     * as tracking pixels are
+     */
+    var `isTrackingPixel`: kotlin.Boolean
+    
+){
+    
+
+    
+
+    
+    companion object
+}
+
+/**
+ * @suppress
+ */
+public object FfiConverterTypeRemoteResource: FfiConverterRustBuffer<RemoteResource> {
+    override fun read(buf: ByteBuffer): RemoteResource {
+        return RemoteResource(
+            FfiConverterString.read(buf),
+            FfiConverterTypeRemoteResourceKind.read(buf),
+            FfiConverterBoolean.read(buf),
+        )
+    }
+
+    override fun allocationSize(value: RemoteResource) = (
+            FfiConverterString.allocationSize(value.`url`) +
+            FfiConverterTypeRemoteResourceKind.allocationSize(value.`kind`) +
+            FfiConverterBoolean.allocationSize(value.`isTrackingPixel`)
+    )
+
+    override fun write(value: RemoteResource, buf: ByteBuffer) {
+            FfiConverterString.write(value.`url`, buf)
+            FfiConverterTypeRemoteResourceKind.write(value.`kind`, buf)
+            FfiConverterBoolean.write(value.`isTrackingPixel`, buf)
+    }
+}
+
+
+
+data class ResourceMeta (
+    /**
+     * Content-ID (without angle brackets)
+     */
+    var `cid`: kotlin.String
+    , 
+    /**
+     * MIME type of the resource
+     */
+    var `contentType`: kotlin.String
+    , 
+    /**
+     * Size in bytes
+     */
+    var `size`: kotlin.ULong
+    , 
+    /**
+     * True if resource is small enough to be returned inline (< 64KB)
+     */
+    var `isSmall`: kotlin.Boolean
+    
+){
+    
+
+    
+
+    
+    companion object
+}
+
+/**
+ * @suppress
+ */
+public object FfiConverterTypeResourceMeta: FfiConverterRustBuffer<ResourceMeta> {
+    override fun read(buf: ByteBuffer): ResourceMeta {
+        return ResourceMeta(
+            FfiConverterString.read(buf),
+            FfiConverterString.read(buf),
+            FfiConverterULong.read(buf),
+            FfiConverterBoolean.read(buf),
+        )
     }
 
-    override fun allocationSize(value: EmailHandle) = 8UL
+    override fun allocationSize(value: ResourceMeta) = (
+            FfiConverterString.allocationSize(value.`cid`) +
+            FfiConverterString.allocationSize(value.`contentType`) +
+            FfiConverterULong.allocationSize(value.`size`) +
+            FfiConverterBoolean.allocationSize(value.`isSmall`)
+    )
 
-    override fun write(value: EmailHandle, buf: ByteBuffer) {
-        buf.putLong(lower(value))
+    override fun write(value: ResourceMeta, buf: ByteBuffer) {
+            FfiConverterString.write(value.`cid`, buf)
+            FfiConverterString.write(value.`contentType`, buf)
+            FfiConverterULong.write(value.`size`, buf)
+            FfiConverterBoolean.write(value.`isSmall`, buf)
     }
 }
 
 
 
 /**
- * Structured address information for search and filtering.
- * Exposed to Kotlin via UniFFI to enable separate indexing of name and email.
+ * What [`sanitize_html`] keeps besides the safe structure and text.
  */
-data class AddressInfo (
+data class SanitizeOptions (
     /**
-     * Email address (e.g., "sender@example.com")
+     * Keep `<style>` blocks and `style` attributes (scrubbed); when false all
+     * CSS is dropped
      */
-    var `email`: kotlin.String
+    var `allowStyles`: kotlin.Boolean
     , 
     /**
-     * Display name (e.g., "John Doe"), empty if not available
+     * Keep http(s) image sources; when false only `cid:` and `data:` images
+     * remain, so rendering makes no network request
      */
-    var `name`: kotlin.String
+    var `allowRemoteImages`: kotlin.Boolean
     
 ){
     
@@ -2065,36 +4117,40 @@ data class AddressInfo (
 /**
  * @suppress
  */
-public object FfiConverterTypeAddressInfo: FfiConverterRustBuffer<AddressInfo> {
-    override fun read(buf: ByteBuffer): AddressInfo {
-        return AddressInfo(
-            FfiConverterString.read(buf),
-            FfiConverterString.read(buf),
+public object FfiConverterTypeSanitizeOptions: FfiConverterRustBuffer<SanitizeOptions> {
+    override fun read(buf: ByteBuffer): SanitizeOptions {
+        return SanitizeOptions(
+            FfiConverterBoolean.read(buf),
+            FfiConverterBoolean.read(buf),
         )
     }
 
-    override fun allocationSize(value: AddressInfo) = (
-            FfiConverterString.allocationSize(value.`email`) +
-            FfiConverterString.allocationSize(value.`name`)
+    override fun allocationSize(value: SanitizeOptions) = (
+            FfiConverterBoolean.allocationSize(value.`allowStyles`) +
+            FfiConverterBoolean.allocationSize(value.`allowRemoteImages`)
     )
 
-    override fun write(value: AddressInfo, buf: ByteBuffer) {
-            FfiConverterString.write(value.`email`, buf)
-            FfiConverterString.write(value.`name`, buf)
+    override fun write(value: SanitizeOptions, buf: ByteBuffer) {
+            FfiConverterBoolean.write(value.`allowStyles`, buf)
+            FfiConverterBoolean.write(value.`allowRemoteImages`, buf)
     }
 }
 
 
 
 /**
- * Attachment metadata exposed to Kotlin via UniFFI.
+ * Outcome of saving an attachment to disk.
  */
-data class AttachmentInfo (
-    var `name`: kotlin.String
-    , 
-    var `contentType`: kotlin.String
+data class SavedAttachment (
+    /**
+     * Bytes written to the file
+     */
+    var `bytesWritten`: kotlin.ULong
     , 
-    var `size`: kotlin.ULong
+    /**
+     * SHA-256 of the written content, lowercase hex
+     */
+    var `sha256`: kotlin.String
     
 ){
     
@@ -2108,43 +4164,52 @@ data class AttachmentInfo (
 /**
  * @suppress
  */
-public object FfiConverterTypeAttachmentInfo: FfiConverterRustBuffer<AttachmentInfo> {
-    override fun read(buf: ByteBuffer): AttachmentInfo {
-        return AttachmentInfo(
-            FfiConverterString.read(buf),
-            FfiConverterString.read(buf),
+public object FfiConverterTypeSavedAttachment: FfiConverterRustBuffer<SavedAttachment> {
+    override fun read(buf: ByteBuffer): SavedAttachment {
+        return SavedAttachment(
             FfiConverterULong.read(buf),
+            FfiConverterString.read(buf),
         )
     }
 
-    override fun allocationSize(value: AttachmentInfo) = (
-            FfiConverterString.allocationSize(value.`name`) +
-            FfiConverterString.allocationSize(value.`contentType`) +
-            FfiConverterULong.allocationSize(value.`size`)
+    override fun allocationSize(value: SavedAttachment) = (
+            FfiConverterULong.allocationSize(value.`bytesWritten`) +
+            FfiConverterString.allocationSize(value.`sha256`)
     )
 
-    override fun write(value: AttachmentInfo, buf: ByteBuffer) {
-            FfiConverterString.write(value.`name`, buf)
-            FfiConverterString.write(value.`contentType`, buf)
-            FfiConverterULong.write(value.`size`, buf)
+    override fun write(value: SavedAttachment, buf: ByteBuffer) {
+            FfiConverterULong.write(value.`bytesWritten`, buf)
+            FfiConverterString.write(value.`sha256`, buf)
     }
 }
 
 
 
 /**
- * Result of extracting remote image URLs from HTML.
+ * What threading needs to know of one message.
  */
-data class RemoteImage (
+data class ThreadMessage (
     /**
-     * Original image URL (http:// or https://)
+     * Message-ID, with or without angle brackets
      */
-    var `url`: kotlin.String
+    var `messageId`: kotlin.String
     , 
     /**
-     * Whether this is a tracking pixel (1x1 image)
+     * First message ID of In-Reply-To, empty if none
      */
-    var `isTrackingPixel`: kotlin.Boolean
+    var `inReplyTo`: kotlin.String
+    , 
+    /**
+     * Message IDs of References, oldest first
+     */
+    var `references`: List<kotlin.String>
+    , 
+    var `subject`: kotlin.String
+    , 
+    /**
+     * Timestamp in milliseconds since Unix epoch, 0 if unknown
+     */
+    var `dateTimestamp`: kotlin.Long
     
 ){
     
@@ -2158,47 +4223,61 @@ data class RemoteImage (
 /**
  * @suppress
  */
-public object FfiConverterTypeRemoteImage: FfiConverterRustBuffer<RemoteImage> {
-    override fun read(buf: ByteBuffer): RemoteImage {
-        return RemoteImage(
+public object FfiConverterTypeThreadMessage: FfiConverterRustBuffer<ThreadMessage> {
+    override fun read(buf: ByteBuffer): ThreadMessage {
+        return ThreadMessage(
             FfiConverterString.read(buf),
-            FfiConverterBoolean.read(buf),
+            FfiConverterString.read(buf),
+            FfiConverterSequenceString.read(buf),
+            FfiConverterString.read(buf),
+            FfiConverterLong.read(buf),
         )
     }
 
-    override fun allocationSize(value: RemoteImage) = (
-            FfiConverterString.allocationSize(value.`url`) +
-            FfiConverterBoolean.allocationSize(value.`isTrackingPixel`)
+    override fun allocationSize(value: ThreadMessage) = (
+            FfiConverterString.allocationSize(value.`messageId`) +
+            FfiConverterString.allocationSize(value.`inReplyTo`) +
+            FfiConverterSequenceString.allocationSize(value.`references`) +
+            FfiConverterString.allocationSize(value.`subject`) +
+            FfiConverterLong.allocationSize(value.`dateTimestamp`)
     )
 
-    override fun write(value: RemoteImage, buf: ByteBuffer) {
-            FfiConverterString.write(value.`url`, buf)
-            FfiConverterBoolean.write(value.`isTrackingPixel`, buf)
+    override fun write(value: ThreadMessage, buf: ByteBuffer) {
+            FfiConverterString.write(value.`messageId`, buf)
+            FfiConverterString.write(value.`inReplyTo`, buf)
+            FfiConverterSequenceString.write(value.`references`, buf)
+            FfiConverterString.write(value.`subject`, buf)
+            FfiConverterLong.write(value.`dateTimestamp`, buf)
     }
 }
 
 
 
-data class ResourceMeta (
+/**
+ * One node of a thread tree.
+ */
+data class ThreadNode (
     /**
-     * Content-ID (without angle brackets)
+     * Index of the message among those added, or None for a message that is
+     * only referenced by others
      */
-    var `cid`: kotlin.String
+    var `messageIndex`: kotlin.UInt?
     , 
     /**
-     * MIME type of the resource
+     * Message-ID of the node, without angle brackets; empty for a node
+     * grouping replies to the same subject
      */
-    var `contentType`: kotlin.String
+    var `messageId`: kotlin.String
     , 
     /**
-     * Size in bytes
+     * Position of the parent node in the returned list; None for a root
      */
-    var `size`: kotlin.ULong
+    var `parent`: kotlin.UInt?
     , 
     /**
-     * True if resource is small enough to be returned inline (< 64KB)
+     * 0 for a root, 1 for its replies, and so on
      */
-    var `isSmall`: kotlin.Boolean
+    var `depth`: kotlin.UInt
     
 ){
     
@@ -2212,28 +4291,28 @@ data class ResourceMeta (
 /**
  * @suppress
  */
-public object FfiConverterTypeResourceMeta: FfiConverterRustBuffer<ResourceMeta> {
-    override fun read(buf: ByteBuffer): ResourceMeta {
-        return ResourceMeta(
+public object FfiConverterTypeThreadNode: FfiConverterRustBuffer<ThreadNode> {
+    override fun read(buf: ByteBuffer): ThreadNode {
+        return ThreadNode(
+            FfiConverterOptionalUInt.read(buf),
             FfiConverterString.read(buf),
-            FfiConverterString.read(buf),
-            FfiConverterULong.read(buf),
-            FfiConverterBoolean.read(buf),
+            FfiConverterOptionalUInt.read(buf),
+            FfiConverterUInt.read(buf),
         )
     }
 
-    override fun allocationSize(value: ResourceMeta) = (
-            FfiConverterString.allocationSize(value.`cid`) +
-            FfiConverterString.allocationSize(value.`contentType`) +
-            FfiConverterULong.allocationSize(value.`size`) +
-            FfiConverterBoolean.allocationSize(value.`isSmall`)
+    override fun allocationSize(value: ThreadNode) = (
+            FfiConverterOptionalUInt.allocationSize(value.`messageIndex`) +
+            FfiConverterString.allocationSize(value.`messageId`) +
+            FfiConverterOptionalUInt.allocationSize(value.`parent`) +
+            FfiConverterUInt.allocationSize(value.`depth`)
     )
 
-    override fun write(value: ResourceMeta, buf: ByteBuffer) {
-            FfiConverterString.write(value.`cid`, buf)
-            FfiConverterString.write(value.`contentType`, buf)
-            FfiConverterULong.write(value.`size`, buf)
-            FfiConverterBoolean.write(value.`isSmall`, buf)
+    override fun write(value: ThreadNode, buf: ByteBuffer) {
+            FfiConverterOptionalUInt.write(value.`messageIndex`, buf)
+            FfiConverterString.write(value.`messageId`, buf)
+            FfiConverterOptionalUInt.write(value.`parent`, buf)
+            FfiConverterUInt.write(value.`depth`, buf)
     }
 }
 
@@ -2275,82 +4354,212 @@ sealed class ParseException: kotlin.Exception() {
     }
     
 
-    
+    
+
+
+    companion object ErrorHandler : UniffiRustCallStatusErrorHandler<ParseException> {
+        override fun lift(error_buf: RustBuffer.ByValue): ParseException = FfiConverterTypeParseError.lift(error_buf)
+    }
+
+    
+}
+
+/**
+ * @suppress
+ */
+public object FfiConverterTypeParseError : FfiConverterRustBuffer<ParseException> {
+    override fun read(buf: ByteBuffer): ParseException {
+        
+
+        return when(buf.getInt()) {
+            1 -> ParseException.Invalid()
+            2 -> ParseException.Empty()
+            3 -> ParseException.FileNotFound(
+                FfiConverterString.read(buf),
+                )
+            4 -> ParseException.IoException(
+                FfiConverterString.read(buf),
+                )
+            else -> throw RuntimeException("invalid error enum value, something is very wrong!!")
+        }
+    }
+
+    override fun allocationSize(value: ParseException): ULong {
+        return when(value) {
+            is ParseException.Invalid -> (
+                // Add the size for the Int that specifies the variant plus the size needed for all fields
+                4UL
+            )
+            is ParseException.Empty -> (
+                // Add the size for the Int that specifies the variant plus the size needed for all fields
+                4UL
+            )
+            is ParseException.FileNotFound -> (
+                // Add the size for the Int that specifies the variant plus the size needed for all fields
+                4UL
+                + FfiConverterString.allocationSize(value.`path`)
+            )
+            is ParseException.IoException -> (
+                // Add the size for the Int that specifies the variant plus the size needed for all fields
+                4UL
+                + FfiConverterString.allocationSize(value.`details`)
+            )
+        }
+    }
+
+    override fun write(value: ParseException, buf: ByteBuffer) {
+        when(value) {
+            is ParseException.Invalid -> {
+                buf.putInt(1)
+                Unit
+            }
+            is ParseException.Empty -> {
+                buf.putInt(2)
+                Unit
+            }
+            is ParseException.FileNotFound -> {
+                buf.putInt(3)
+                FfiConverterString.write(value.`path`, buf)
+                Unit
+            }
+            is ParseException.IoException -> {
+                buf.putInt(4)
+                FfiConverterString.write(value.`details`, buf)
+                Unit
+            }
+        }.let { /* this makes the `when` an expression, which ensures it is exhaustive */ }
+    }
+
+}
+
+
+
+/**
+ * How a remote URL is used by the body.
+ */
+
+enum class RemoteResourceKind {
+    
+    /**
+     * `<img src>`, `srcset` candidate or `<video poster>`
+     */
+    IMAGE,
+    /**
+     * CSS `url()` or `@import`, or a `background` attribute
+     */
+    CSS,
+    /**
+     * `<link href>`, such as a stylesheet or icon
+     */
+    LINK,
+    /**
+     * `src` of `<video>`, `<audio>`, `<source>` or `<track>`
+     */
+    MEDIA;
+
+    
+
+
+    companion object
+}
+
+
+/**
+ * @suppress
+ */
+public object FfiConverterTypeRemoteResourceKind: FfiConverterRustBuffer<RemoteResourceKind> {
+    override fun read(buf: ByteBuffer) = try {
+        RemoteResourceKind.values()[buf.getInt() - 1]
+    } catch (e: IndexOutOfBoundsException) {
+        throw RuntimeException("invalid enum value, something is very wrong!!", e)
+    }
+
+    override fun allocationSize(value: RemoteResourceKind) = 4UL
+
+    override fun write(value: RemoteResourceKind, buf: ByteBuffer) {
+        buf.putInt(value.ordinal + 1)
+    }
+}
+
+
+
+
+
+/**
+ * Content-Transfer-Encoding of a part.
+ */
+
+enum class TransferEncoding {
+    
+    /**
+     * 7bit for text with short ASCII lines, quoted-printable for other
+     * text, base64 for anything else
+     */
+    AUTO,
+    /**
+     * As is; used only when the data is ASCII with short lines, otherwise
+     * as [`TransferEncoding::Auto`]
+     */
+    SEVEN_BIT,
+    QUOTED_PRINTABLE,
+    BASE64;
+
+    
+
+
+    companion object
+}
+
+
+/**
+ * @suppress
+ */
+public object FfiConverterTypeTransferEncoding: FfiConverterRustBuffer<TransferEncoding> {
+    override fun read(buf: ByteBuffer) = try {
+        TransferEncoding.values()[buf.getInt() - 1]
+    } catch (e: IndexOutOfBoundsException) {
+        throw RuntimeException("invalid enum value, something is very wrong!!", e)
+    }
+
+    override fun allocationSize(value: TransferEncoding) = 4UL
+
+    override fun write(value: TransferEncoding, buf: ByteBuffer) {
+        buf.putInt(value.ordinal + 1)
+    }
+}
+
+
 
 
-    companion object ErrorHandler : UniffiRustCallStatusErrorHandler<ParseException> {
-        override fun lift(error_buf: RustBuffer.ByValue): ParseException = FfiConverterTypeParseError.lift(error_buf)
-    }
 
-    
-}
 
 /**
  * @suppress
  */
-public object FfiConverterTypeParseError : FfiConverterRustBuffer<ParseException> {
-    override fun read(buf: ByteBuffer): ParseException {
-        
-
-        return when(buf.getInt()) {
-            1 -> ParseException.Invalid()
-            2 -> ParseException.Empty()
-            3 -> ParseException.FileNotFound(
-                FfiConverterString.read(buf),
-                )
-            4 -> ParseException.IoException(
-                FfiConverterString.read(buf),
-                )
-            else -> throw RuntimeException("invalid error enum value, something is very wrong!!")
+public object FfiConverterOptionalUInt: FfiConverterRustBuffer<kotlin.UInt?> {
+    override fun read(buf: ByteBuffer): kotlin.UInt? {
+        if (buf.get().toInt() == 0) {
+            return null
         }
+        return FfiConverterUInt.read(buf)
     }
 
-    override fun allocationSize(value: ParseException): ULong {
-        return when(value) {
-            is ParseException.Invalid -> (
-                // Add the size for the Int that specifies the variant plus the size needed for all fields
-                4UL
-            )
-            is ParseException.Empty -> (
-                // Add the size for the Int that specifies the variant plus the size needed for all fields
-                4UL
-            )
-            is ParseException.FileNotFound -> (
-                // Add the size for the Int that specifies the variant plus the size needed for all fields
-                4UL
-                + FfiConverterString.allocationSize(value.`path`)
-            )
-            is ParseException.IoException -> (
-                // Add the size for the Int that specifies the variant plus the size needed for all fields
-                4UL
-                + FfiConverterString.allocationSize(value.`details`)
-            )
+    override fun allocationSize(value: kotlin.UInt?): ULong {
+        if (value == null) {
+            return 1UL
+        } else {
+            return 1UL + FfiConverterUInt.allocationSize(value)
         }
     }
 
-    override fun write(value: ParseException, buf: ByteBuffer) {
-        when(value) {
-            is ParseException.Invalid -> {
-                buf.putInt(1)
-                Unit
-            }
-            is ParseException.Empty -> {
-                buf.putInt(2)
-                Unit
-            }
-            is ParseException.FileNotFound -> {
-                buf.putInt(3)
-                FfiConverterString.write(value.`path`, buf)
-                Unit
-            }
-            is ParseException.IoException -> {
-                buf.putInt(4)
-                FfiConverterString.write(value.`details`, buf)
-                Unit
-            }
-        }.let { /* this makes the `when` an expression, which ensures it is exhaustive */ }
+    override fun write(value: kotlin.UInt?, buf: ByteBuffer) {
+        if (value == null) {
+            buf.put(0)
+        } else {
+            buf.put(1)
+            FfiConverterUInt.write(value, buf)
+        }
     }
-
 }
 
 
@@ -2420,6 +4629,98 @@ public object FfiConverterOptionalByteArray: FfiConverterRustBuffer<kotlin.ByteA
 
 
 
+/**
+ * @suppress
+ */
+public object FfiConverterOptionalTypeCidRewrite: FfiConverterRustBuffer<CidRewrite?> {
+    override fun read(buf: ByteBuffer): CidRewrite? {
+        if (buf.get().toInt() == 0) {
+            return null
+        }
+        return FfiConverterTypeCidRewrite.read(buf)
+    }
+
+    override fun allocationSize(value: CidRewrite?): ULong {
+        if (value == null) {
+            return 1UL
+        } else {
+            return 1UL + FfiConverterTypeCidRewrite.allocationSize(value)
+        }
+    }
+
+    override fun write(value: CidRewrite?, buf: ByteBuffer) {
+        if (value == null) {
+            buf.put(0)
+        } else {
+            buf.put(1)
+            FfiConverterTypeCidRewrite.write(value, buf)
+        }
+    }
+}
+
+
+
+
+/**
+ * @suppress
+ */
+public object FfiConverterOptionalTypeSavedAttachment: FfiConverterRustBuffer<SavedAttachment?> {
+    override fun read(buf: ByteBuffer): SavedAttachment? {
+        if (buf.get().toInt() == 0) {
+            return null
+        }
+        return FfiConverterTypeSavedAttachment.read(buf)
+    }
+
+    override fun allocationSize(value: SavedAttachment?): ULong {
+        if (value == null) {
+            return 1UL
+        } else {
+            return 1UL + FfiConverterTypeSavedAttachment.allocationSize(value)
+        }
+    }
+
+    override fun write(value: SavedAttachment?, buf: ByteBuffer) {
+        if (value == null) {
+            buf.put(0)
+        } else {
+            buf.put(1)
+            FfiConverterTypeSavedAttachment.write(value, buf)
+        }
+    }
+}
+
+
+
+
+/**
+ * @suppress
+ */
+public object FfiConverterSequenceUInt: FfiConverterRustBuffer<List<kotlin.UInt>> {
+    override fun read(buf: ByteBuffer): List<kotlin.UInt> {
+        val len = buf.getInt()
+        return List<kotlin.UInt>(len) {
+            FfiConverterUInt.read(buf)
+        }
+    }
+
+    override fun allocationSize(value: List<kotlin.UInt>): ULong {
+        val sizeForLength = 4UL
+        val sizeForItems = value.map { FfiConverterUInt.allocationSize(it) }.sum()
+        return sizeForLength + sizeForItems
+    }
+
+    override fun write(value: List<kotlin.UInt>, buf: ByteBuffer) {
+        buf.putInt(value.size)
+        value.iterator().forEach {
+            FfiConverterUInt.write(it, buf)
+        }
+    }
+}
+
+
+
+
 /**
  * @suppress
  */
@@ -2504,6 +4805,34 @@ public object FfiConverterSequenceTypeAttachmentInfo: FfiConverterRustBuffer<Lis
 
 
 
+/**
+ * @suppress
+ */
+public object FfiConverterSequenceTypeHeaderField: FfiConverterRustBuffer<List<HeaderField>> {
+    override fun read(buf: ByteBuffer): List<HeaderField> {
+        val len = buf.getInt()
+        return List<HeaderField>(len) {
+            FfiConverterTypeHeaderField.read(buf)
+        }
+    }
+
+    override fun allocationSize(value: List<HeaderField>): ULong {
+        val sizeForLength = 4UL
+        val sizeForItems = value.map { FfiConverterTypeHeaderField.allocationSize(it) }.sum()
+        return sizeForLength + sizeForItems
+    }
+
+    override fun write(value: List<HeaderField>, buf: ByteBuffer) {
+        buf.putInt(value.size)
+        value.iterator().forEach {
+            FfiConverterTypeHeaderField.write(it, buf)
+        }
+    }
+}
+
+
+
+
 /**
  * @suppress
  */
@@ -2532,6 +4861,34 @@ public object FfiConverterSequenceTypeRemoteImage: FfiConverterRustBuffer<List<R
 
 
 
+/**
+ * @suppress
+ */
+public object FfiConverterSequenceTypeRemoteResource: FfiConverterRustBuffer<List<RemoteResource>> {
+    override fun read(buf: ByteBuffer): List<RemoteResource> {
+        val len = buf.getInt()
+        return List<RemoteResource>(len) {
+            FfiConverterTypeRemoteResource.read(buf)
+        }
+    }
+
+    override fun allocationSize(value: List<RemoteResource>): ULong {
+        val sizeForLength = 4UL
+        val sizeForItems = value.map { FfiConverterTypeRemoteResource.allocationSize(it) }.sum()
+        return sizeForLength + sizeForItems
+    }
+
+    override fun write(value: List<RemoteResource>, buf: ByteBuffer) {
+        buf.putInt(value.size)
+        value.iterator().forEach {
+            FfiConverterTypeRemoteResource.write(it, buf)
+        }
+    }
+}
+
+
+
+
 /**
  * @suppress
  */
@@ -2556,12 +4913,67 @@ public object FfiConverterSequenceTypeResourceMeta: FfiConverterRustBuffer<List<
         }
     }
 }
+
+
+
+
+/**
+ * @suppress
+ */
+public object FfiConverterSequenceTypeThreadMessage: FfiConverterRustBuffer<List<ThreadMessage>> {
+    override fun read(buf: ByteBuffer): List<ThreadMessage> {
+        val len = buf.getInt()
+        return List<ThreadMessage>(len) {
+            FfiConverterTypeThreadMessage.read(buf)
+        }
+    }
+
+    override fun allocationSize(value: List<ThreadMessage>): ULong {
+        val sizeForLength = 4UL
+        val sizeForItems = value.map { FfiConverterTypeThreadMessage.allocationSize(it) }.sum()
+        return sizeForLength + sizeForItems
+    }
+
+    override fun write(value: List<ThreadMessage>, buf: ByteBuffer) {
+        buf.putInt(value.size)
+        value.iterator().forEach {
+            FfiConverterTypeThreadMessage.write(it, buf)
+        }
+    }
+}
+
+
+
+
+/**
+ * @suppress
+ */
+public object FfiConverterSequenceTypeThreadNode: FfiConverterRustBuffer<List<ThreadNode>> {
+    override fun read(buf: ByteBuffer): List<ThreadNode> {
+        val len = buf.getInt()
+        return List<ThreadNode>(len) {
+            FfiConverterTypeThreadNode.read(buf)
+        }
+    }
+
+    override fun allocationSize(value: List<ThreadNode>): ULong {
+        val sizeForLength = 4UL
+        val sizeForItems = value.map { FfiConverterTypeThreadNode.allocationSize(it) }.sum()
+        return sizeForLength + sizeForItems
+    }
+
+    override fun write(value: List<ThreadNode>, buf: ByteBuffer) {
+        buf.putInt(value.size)
+        value.iterator().forEach {
+            FfiConverterTypeThreadNode.write(it, buf)
+        }
+    }
+}
         /**
-         * Extract all remote image URLs from HTML content.
-         * Uses proper HTML parsing instead of regex to handle edge cases.
-         *
-         * Returns a list of remote image URLs found in <img src="..."> tags.
-         * Only returns http:// and https:// URLs, excludes cid: URLs.
+         * Extract all remote image URLs from HTML content: the `<img>` sources,
+         * `srcset` candidates and posters among [`remote::remote_resources`], once
+         * each and in document order. `cid:` and `data:` URLs are part of the
+         * message and not included.
          */ fun `extractRemoteImages`(`html`: kotlin.String): List<RemoteImage> {
             return FfiConverterSequenceTypeRemoteImage.lift(
     uniffiRustCall() { _status ->
@@ -2573,6 +4985,22 @@ public object FfiConverterSequenceTypeResourceMeta: FfiConverterRustBuffer<List<
     }
     
 
+        /**
+         * Convert a plain text body to an HTML document: spacing kept, URLs and
+         * email addresses linked, `>` quotes nested and styled by level, and with
+         * `emphasis`, `*strong*` and `_emphasis_` marked up. Text-only messages get
+         * this (without emphasis) as their `body_html`.
+         */ fun `plainTextToHtml`(`text`: kotlin.String, `emphasis`: kotlin.Boolean): kotlin.String {
+            return FfiConverterString.lift(
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_core_fn_func_plain_text_to_html(
+    
+        FfiConverterString.lower(`text`),FfiConverterBoolean.lower(`emphasis`),_status)
+}
+    )
+    }
+    
+
         /**
          * Parse an EML file from raw bytes.
          * Returns an opaque handle that stays in Rust memory.
@@ -2588,10 +5016,29 @@ public object FfiConverterSequenceTypeResourceMeta: FfiConverterRustBuffer<List<
     }
     
 
+        /**
+         * Parse an EML file in place.
+         * The file is memory-mapped rather than read, so a 50 MB message costs no
+         * 50 MB buffer on the heap: only the decoded bodies, inline resources and
+         * text attachments are copied out, and binary attachments are decoded from
+         * the map when asked for. Nothing is copied across FFI but the path.
+         */
+    @Throws(ParseException::class) fun `parseEmlFile`(`path`: kotlin.String): EmailHandle {
+            return FfiConverterTypeEmailHandle.lift(
+    uniffiRustCallWithError(ParseException) { _status ->
+    UniffiLib.uniffi_letterbox_core_fn_func_parse_eml_file(
+    
+        FfiConverterString.lower(`path`),_status)
+}
+    )
+    }
+    
+
         /**
          * Parse an EML file from a file path.
-         * This is the preferred method for large emails as it avoids copying the entire
-         * file into the JVM heap first. Rust reads the file directly.
+         * This avoids copying the entire file into the JVM heap first: Rust reads the
+         * file directly. [`parse_eml_file`] goes further and does not read it into
+         * memory at all.
          * Returns an opaque handle that stays in Rust memory.
          *
          * # Security
@@ -2610,4 +5057,21 @@ public object FfiConverterSequenceTypeResourceMeta: FfiConverterRustBuffer<List<
     }
     
 
+        /**
+         * Parse only the header section of an EML file from raw bytes.
+         * Parsing stops where the body begins, so no body or attachment is decoded;
+         * this suits building a message list from many EMLs. The returned handle has
+         * the headers of [`parse_eml`] but no bodies, inline resources or attachments.
+         */
+    @Throws(ParseException::class) fun `parseEmlHeaders`(`data`: kotlin.ByteArray): EmailHandle {
+            return FfiConverterTypeEmailHandle.lift(
+    uniffiRustCallWithError(ParseException) { _status ->
+    UniffiLib.uniffi_letterbox_core_fn_func_parse_eml_headers(
+    
+        FfiConverterByteArray.lower(`data`),_status)
+}
+    )
+    }
+    
+
 
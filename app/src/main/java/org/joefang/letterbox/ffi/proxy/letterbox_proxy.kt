@@ -30,6 +30,14 @@ import java.nio.CharBuffer
 import java.nio.charset.CodingErrorAction
 import java.util.concurrent.atomic.AtomicLong
 import java.util.concurrent.ConcurrentHashMap
+import java.util.concurrent.atomic.AtomicBoolean
+import kotlin.coroutines.resume
+import kotlinx.coroutines.CancellableContinuation
+import kotlinx.coroutines.DelicateCoroutinesApi
+import kotlinx.coroutines.GlobalScope
+import kotlinx.coroutines.Job
+import kotlinx.coroutines.launch
+import kotlinx.coroutines.suspendCancellableCoroutine
 
 // This is a helper for safely working with byte buffers returned from the Rust code.
 // A rust-owned buffer is represented by its capacity, its current length, and a
@@ -612,6 +620,178 @@ internal open class UniffiForeignFutureResultVoid(
 internal interface UniffiForeignFutureCompleteVoid : com.sun.jna.Callback {
     fun callback(`callbackData`: Long,`result`: UniffiForeignFutureResultVoid.UniffiByValue,)
 }
+internal interface UniffiCallbackInterfaceBatchResultListenerMethod0 : com.sun.jna.Callback {
+    fun callback(`uniffiHandle`: Long,`index`: Int,`result`: RustBuffer.ByValue,`uniffiOutReturn`: Pointer,uniffiCallStatus: UniffiRustCallStatus,)
+}
+internal interface UniffiCallbackInterfaceCacheStorageMethod0 : com.sun.jna.Callback {
+    fun callback(`uniffiHandle`: Long,`key`: RustBuffer.ByValue,`uniffiOutReturn`: RustBuffer,uniffiCallStatus: UniffiRustCallStatus,)
+}
+internal interface UniffiCallbackInterfaceCacheStorageMethod1 : com.sun.jna.Callback {
+    fun callback(`uniffiHandle`: Long,`key`: RustBuffer.ByValue,`value`: RustBuffer.ByValue,`uniffiOutReturn`: Pointer,uniffiCallStatus: UniffiRustCallStatus,)
+}
+internal interface UniffiCallbackInterfaceCacheStorageMethod2 : com.sun.jna.Callback {
+    fun callback(`uniffiHandle`: Long,`key`: RustBuffer.ByValue,`uniffiOutReturn`: Pointer,uniffiCallStatus: UniffiRustCallStatus,)
+}
+internal interface UniffiCallbackInterfaceCacheStorageMethod3 : com.sun.jna.Callback {
+    fun callback(`uniffiHandle`: Long,`uniffiOutReturn`: RustBuffer,uniffiCallStatus: UniffiRustCallStatus,)
+}
+internal interface UniffiCallbackInterfaceProxyEventListenerMethod0 : com.sun.jna.Callback {
+    fun callback(`uniffiHandle`: Long,`event`: RustBuffer.ByValue,`uniffiOutReturn`: Pointer,uniffiCallStatus: UniffiRustCallStatus,)
+}
+internal interface UniffiCallbackInterfaceLogListenerMethod0 : com.sun.jna.Callback {
+    fun callback(`uniffiHandle`: Long,`level`: RustBuffer.ByValue,`target`: RustBuffer.ByValue,`message`: RustBuffer.ByValue,`uniffiOutReturn`: Pointer,uniffiCallStatus: UniffiRustCallStatus,)
+}
+internal interface UniffiCallbackInterfaceOfflineFetchListenerMethod0 : com.sun.jna.Callback {
+    fun callback(`uniffiHandle`: Long,`url`: RustBuffer.ByValue,`error`: RustBuffer.ByValue,`uniffiOutReturn`: Pointer,uniffiCallStatus: UniffiRustCallStatus,)
+}
+internal interface UniffiCallbackInterfaceProgressListenerMethod0 : com.sun.jna.Callback {
+    fun callback(`uniffiHandle`: Long,`url`: RustBuffer.ByValue,`bytesDownloaded`: Long,`total`: RustBuffer.ByValue,`uniffiOutReturn`: Pointer,uniffiCallStatus: UniffiRustCallStatus,)
+}
+internal interface UniffiCallbackInterfaceSocketProtectorMethod0 : com.sun.jna.Callback {
+    fun callback(`uniffiHandle`: Long,`fd`: Int,`uniffiOutReturn`: ByteByReference,uniffiCallStatus: UniffiRustCallStatus,)
+}
+@Structure.FieldOrder("uniffiFree", "uniffiClone", "onResult")
+internal open class UniffiVTableCallbackInterfaceBatchResultListener(
+    @JvmField internal var `uniffiFree`: UniffiCallbackInterfaceFree? = null,
+    @JvmField internal var `uniffiClone`: UniffiCallbackInterfaceClone? = null,
+    @JvmField internal var `onResult`: UniffiCallbackInterfaceBatchResultListenerMethod0? = null,
+) : Structure() {
+    class UniffiByValue(
+        `uniffiFree`: UniffiCallbackInterfaceFree? = null,
+        `uniffiClone`: UniffiCallbackInterfaceClone? = null,
+        `onResult`: UniffiCallbackInterfaceBatchResultListenerMethod0? = null,
+    ): UniffiVTableCallbackInterfaceBatchResultListener(`uniffiFree`,`uniffiClone`,`onResult`,), Structure.ByValue
+
+   internal fun uniffiSetValue(other: UniffiVTableCallbackInterfaceBatchResultListener) {
+        `uniffiFree` = other.`uniffiFree`
+        `uniffiClone` = other.`uniffiClone`
+        `onResult` = other.`onResult`
+    }
+
+}
+@Structure.FieldOrder("uniffiFree", "uniffiClone", "get", "put", "delete", "list")
+internal open class UniffiVTableCallbackInterfaceCacheStorage(
+    @JvmField internal var `uniffiFree`: UniffiCallbackInterfaceFree? = null,
+    @JvmField internal var `uniffiClone`: UniffiCallbackInterfaceClone? = null,
+    @JvmField internal var `get`: UniffiCallbackInterfaceCacheStorageMethod0? = null,
+    @JvmField internal var `put`: UniffiCallbackInterfaceCacheStorageMethod1? = null,
+    @JvmField internal var `delete`: UniffiCallbackInterfaceCacheStorageMethod2? = null,
+    @JvmField internal var `list`: UniffiCallbackInterfaceCacheStorageMethod3? = null,
+) : Structure() {
+    class UniffiByValue(
+        `uniffiFree`: UniffiCallbackInterfaceFree? = null,
+        `uniffiClone`: UniffiCallbackInterfaceClone? = null,
+        `get`: UniffiCallbackInterfaceCacheStorageMethod0? = null,
+        `put`: UniffiCallbackInterfaceCacheStorageMethod1? = null,
+        `delete`: UniffiCallbackInterfaceCacheStorageMethod2? = null,
+        `list`: UniffiCallbackInterfaceCacheStorageMethod3? = null,
+    ): UniffiVTableCallbackInterfaceCacheStorage(`uniffiFree`,`uniffiClone`,`get`,`put`,`delete`,`list`,), Structure.ByValue
+
+   internal fun uniffiSetValue(other: UniffiVTableCallbackInterfaceCacheStorage) {
+        `uniffiFree` = other.`uniffiFree`
+        `uniffiClone` = other.`uniffiClone`
+        `get` = other.`get`
+        `put` = other.`put`
+        `delete` = other.`delete`
+        `list` = other.`list`
+    }
+
+}
+@Structure.FieldOrder("uniffiFree", "uniffiClone", "onEvent")
+internal open class UniffiVTableCallbackInterfaceProxyEventListener(
+    @JvmField internal var `uniffiFree`: UniffiCallbackInterfaceFree? = null,
+    @JvmField internal var `uniffiClone`: UniffiCallbackInterfaceClone? = null,
+    @JvmField internal var `onEvent`: UniffiCallbackInterfaceProxyEventListenerMethod0? = null,
+) : Structure() {
+    class UniffiByValue(
+        `uniffiFree`: UniffiCallbackInterfaceFree? = null,
+        `uniffiClone`: UniffiCallbackInterfaceClone? = null,
+        `onEvent`: UniffiCallbackInterfaceProxyEventListenerMethod0? = null,
+    ): UniffiVTableCallbackInterfaceProxyEventListener(`uniffiFree`,`uniffiClone`,`onEvent`,), Structure.ByValue
+
+   internal fun uniffiSetValue(other: UniffiVTableCallbackInterfaceProxyEventListener) {
+        `uniffiFree` = other.`uniffiFree`
+        `uniffiClone` = other.`uniffiClone`
+        `onEvent` = other.`onEvent`
+    }
+
+}
+@Structure.FieldOrder("uniffiFree", "uniffiClone", "onLog")
+internal open class UniffiVTableCallbackInterfaceLogListener(
+    @JvmField internal var `uniffiFree`: UniffiCallbackInterfaceFree? = null,
+    @JvmField internal var `uniffiClone`: UniffiCallbackInterfaceClone? = null,
+    @JvmField internal var `onLog`: UniffiCallbackInterfaceLogListenerMethod0? = null,
+) : Structure() {
+    class UniffiByValue(
+        `uniffiFree`: UniffiCallbackInterfaceFree? = null,
+        `uniffiClone`: UniffiCallbackInterfaceClone? = null,
+        `onLog`: UniffiCallbackInterfaceLogListenerMethod0? = null,
+    ): UniffiVTableCallbackInterfaceLogListener(`uniffiFree`,`uniffiClone`,`onLog`,), Structure.ByValue
+
+   internal fun uniffiSetValue(other: UniffiVTableCallbackInterfaceLogListener) {
+        `uniffiFree` = other.`uniffiFree`
+        `uniffiClone` = other.`uniffiClone`
+        `onLog` = other.`onLog`
+    }
+
+}
+@Structure.FieldOrder("uniffiFree", "uniffiClone", "onFetched")
+internal open class UniffiVTableCallbackInterfaceOfflineFetchListener(
+    @JvmField internal var `uniffiFree`: UniffiCallbackInterfaceFree? = null,
+    @JvmField internal var `uniffiClone`: UniffiCallbackInterfaceClone? = null,
+    @JvmField internal var `onFetched`: UniffiCallbackInterfaceOfflineFetchListenerMethod0? = null,
+) : Structure() {
+    class UniffiByValue(
+        `uniffiFree`: UniffiCallbackInterfaceFree? = null,
+        `uniffiClone`: UniffiCallbackInterfaceClone? = null,
+        `onFetched`: UniffiCallbackInterfaceOfflineFetchListenerMethod0? = null,
+    ): UniffiVTableCallbackInterfaceOfflineFetchListener(`uniffiFree`,`uniffiClone`,`onFetched`,), Structure.ByValue
+
+   internal fun uniffiSetValue(other: UniffiVTableCallbackInterfaceOfflineFetchListener) {
+        `uniffiFree` = other.`uniffiFree`
+        `uniffiClone` = other.`uniffiClone`
+        `onFetched` = other.`onFetched`
+    }
+
+}
+@Structure.FieldOrder("uniffiFree", "uniffiClone", "onProgress")
+internal open class UniffiVTableCallbackInterfaceProgressListener(
+    @JvmField internal var `uniffiFree`: UniffiCallbackInterfaceFree? = null,
+    @JvmField internal var `uniffiClone`: UniffiCallbackInterfaceClone? = null,
+    @JvmField internal var `onProgress`: UniffiCallbackInterfaceProgressListenerMethod0? = null,
+) : Structure() {
+    class UniffiByValue(
+        `uniffiFree`: UniffiCallbackInterfaceFree? = null,
+        `uniffiClone`: UniffiCallbackInterfaceClone? = null,
+        `onProgress`: UniffiCallbackInterfaceProgressListenerMethod0? = null,
+    ): UniffiVTableCallbackInterfaceProgressListener(`uniffiFree`,`uniffiClone`,`onProgress`,), Structure.ByValue
+
+   internal fun uniffiSetValue(other: UniffiVTableCallbackInterfaceProgressListener) {
+        `uniffiFree` = other.`uniffiFree`
+        `uniffiClone` = other.`uniffiClone`
+        `onProgress` = other.`onProgress`
+    }
+
+}
+@Structure.FieldOrder("uniffiFree", "uniffiClone", "protect")
+internal open class UniffiVTableCallbackInterfaceSocketProtector(
+    @JvmField internal var `uniffiFree`: UniffiCallbackInterfaceFree? = null,
+    @JvmField internal var `uniffiClone`: UniffiCallbackInterfaceClone? = null,
+    @JvmField internal var `protect`: UniffiCallbackInterfaceSocketProtectorMethod0? = null,
+) : Structure() {
+    class UniffiByValue(
+        `uniffiFree`: UniffiCallbackInterfaceFree? = null,
+        `uniffiClone`: UniffiCallbackInterfaceClone? = null,
+        `protect`: UniffiCallbackInterfaceSocketProtectorMethod0? = null,
+    ): UniffiVTableCallbackInterfaceSocketProtector(`uniffiFree`,`uniffiClone`,`protect`,), Structure.ByValue
+
+   internal fun uniffiSetValue(other: UniffiVTableCallbackInterfaceSocketProtector) {
+        `uniffiFree` = other.`uniffiFree`
+        `uniffiClone` = other.`uniffiClone`
+        `protect` = other.`protect`
+    }
+
+}
 
 // A JNA Library to expose the extern-C FFI definitions.
 // This is an implementation detail which will be called internally by the public API.
@@ -636,172 +816,944 @@ internal object IntegrityCheckingUniffiLib {
         uniffiCheckApiChecksums(this)
     }
     external fun uniffi_letterbox_proxy_checksum_func_proxy_check_for_update(
-    ): Int
-    external fun uniffi_letterbox_proxy_checksum_func_proxy_clear_cache(
-    ): Int
-    external fun uniffi_letterbox_proxy_checksum_func_proxy_diagnostics(
-    ): Int
-    external fun uniffi_letterbox_proxy_checksum_func_proxy_fetch_image(
-    ): Int
-    external fun uniffi_letterbox_proxy_checksum_func_proxy_fetch_images_batch(
-    ): Int
-    external fun uniffi_letterbox_proxy_checksum_func_proxy_fetch_url(
-    ): Int
-    external fun uniffi_letterbox_proxy_checksum_func_proxy_init(
-    ): Int
-    external fun uniffi_letterbox_proxy_checksum_func_proxy_shutdown(
-    ): Int
-    external fun uniffi_letterbox_proxy_checksum_func_proxy_status(
-    ): Int
-    external fun uniffi_letterbox_proxy_checksum_func_proxy_reset_identity(
-    ): Int
-    external fun uniffi_letterbox_proxy_checksum_func_proxy_stored_config(
-    ): Int
-    external fun uniffi_letterbox_proxy_checksum_func_proxy_tls_self_test(
-    ): Int
-    external fun ffi_letterbox_proxy_uniffi_contract_version(
-    ): Int
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_diagnostics(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_init(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_shutdown(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_status(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_reset_identity(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_stored_config(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_audit_log_files(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_audit_url_hash(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_clear_audit_log(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_set_audit_log(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_bandwidth_usage(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_reset_bandwidth_usage(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_fetch_images_batch(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_fetch_images_streaming(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_clear_cache(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_disable_disk_cache(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_enable_disk_cache(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_export_cache(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_import_cache(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_pin_url(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_pinned_urls(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_set_cache_storage(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_trim_cache(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_unpin_url(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_set_fetch_concurrency(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_data_saver(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_set_data_saver(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_set_max_image_pixels(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_fetch_image_to_file(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_set_tls_early_data(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_set_event_listener(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_fetch_favicon(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_fetch_image(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_fetch_url(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_set_image_accept(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_reset_group_budget(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_set_group_byte_budget(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_set_group_referer(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_fetch_image_handle(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_set_allowed_sensitive_headers(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_set_hedge_delay(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_is_enabled(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_set_enabled(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_set_enabled_globally(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_set_log_listener(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_start_loopback_server(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_metrics(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_clear_offline_queue(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_offline_queue(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_queue_for_offline(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_retry_offline_queue(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_set_offline_listener(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_is_paused(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_pause(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_resume(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_prefetch(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_resolve_preview(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_probe_image(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_set_progress_listener(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_set_network_binding(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_set_socket_protector(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_clear_recent_errors(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_recent_errors(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_set_redirect_policy(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_refresh_warp_config(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_set_warp_refresh(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_fetch_resource(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_set_retry_policy(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_set_https_mode(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_tls_self_test(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_fetch_best_source(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_open_stream(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_set_svg_sanitization(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_set_rate_limit(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_set_timeout_budget(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_set_block_tracking_pixels(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_set_tracker_domains(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_fetch_image_transformed(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_set_upstream_proxy(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_set_warm_up_hosts(
+): Int
+external fun uniffi_letterbox_proxy_checksum_func_proxy_warm_up(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_batchresultlistener_on_result(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_imagebatch_cancel(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_imagebatch_is_cancelled(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_imagebatch_results(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_imagebatch_stream(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_cachestorage_get(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_cachestorage_put(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_cachestorage_delete(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_cachestorage_list(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_reset_identity(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_stored_config(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_audit_log_files(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_audit_url_hash(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_clear_audit_log(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_set_audit_log(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_bandwidth_usage(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_reset_bandwidth_usage(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_fetch_images_batch(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_fetch_images_streaming(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_clear_cache(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_disable_disk_cache(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_enable_disk_cache(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_export_cache(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_import_cache(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_pin_url(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_pinned_urls(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_set_cache_storage(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_trim_cache(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_unpin_url(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_check_for_update(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_diagnostics(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_shutdown(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_status(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_set_fetch_concurrency(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_data_saver(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_set_data_saver(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_set_max_image_pixels(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_fetch_image_to_file(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_set_tls_early_data(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_set_event_listener(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_fetch_favicon(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_fetch_image(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_fetch_url(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_set_image_accept(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_reset_group_budget(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_set_group_byte_budget(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_set_group_referer(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_fetch_image_handle(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_set_allowed_sensitive_headers(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_set_hedge_delay(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_is_enabled(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_set_enabled(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_start_loopback_server(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_metrics(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_clear_offline_queue(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_offline_queue(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_queue_for_offline(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_retry_offline_queue(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_set_offline_listener(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_is_paused(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_pause(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_resume(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_prefetch(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_resolve_preview(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_probe_image(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_set_network_binding(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_set_socket_protector(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_clear_recent_errors(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_recent_errors(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_set_redirect_policy(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_refresh_warp_config(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_set_warp_refresh(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_fetch_resource(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_set_retry_policy(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_set_https_mode(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_fetch_best_source(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_open_stream(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_set_svg_sanitization(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_set_rate_limit(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_set_timeout_budget(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_set_block_tracking_pixels(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_set_tracker_domains(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_fetch_image_transformed(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_set_upstream_proxy(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_set_warm_up_hosts(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyclient_warm_up(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxyeventlistener_on_event(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_imagehandle_byte_len(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_imagehandle_info(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_imagehandle_read(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_imagehandle_write_to_file(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_loglistener_on_log(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_loopbackserver_image_url(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_loopbackserver_port(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_loopbackserver_stop(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_offlinefetchlistener_on_fetched(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_progresslistener_on_progress(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_socketprotector_protect(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxystream_close(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxystream_read(
+): Int
+external fun uniffi_letterbox_proxy_checksum_method_proxystream_write(
+): Int
+external fun uniffi_letterbox_proxy_checksum_constructor_proxyclient_new(
+): Int
+external fun ffi_letterbox_proxy_uniffi_contract_version(
+): Int
 
-        
+    
 }
 
 internal object UniffiLib {
     
+    // The Cleaner for the whole library
+    internal val CLEANER: UniffiCleaner by lazy {
+        UniffiCleaner.create()
+    }
+    
 
     init {
         Native.register(UniffiLib::class.java, findLibraryName(componentName = "letterbox_proxy"))
+        uniffiCallbackInterfaceBatchResultListener.register(this)
+        uniffiCallbackInterfaceCacheStorage.register(this)
+        uniffiCallbackInterfaceLogListener.register(this)
+        uniffiCallbackInterfaceOfflineFetchListener.register(this)
+        uniffiCallbackInterfaceProgressListener.register(this)
+        uniffiCallbackInterfaceProxyEventListener.register(this)
+        uniffiCallbackInterfaceSocketProtector.register(this)
         
     }
-    external fun uniffi_letterbox_proxy_fn_func_proxy_check_for_update(`currentVersion`: RustBuffer.ByValue,`repo`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
-    ): RustBuffer.ByValue
-    external fun uniffi_letterbox_proxy_fn_func_proxy_clear_cache(uniffi_out_err: UniffiRustCallStatus, 
-    ): Unit
-    external fun uniffi_letterbox_proxy_fn_func_proxy_diagnostics(uniffi_out_err: UniffiRustCallStatus, 
-    ): RustBuffer.ByValue
-    external fun uniffi_letterbox_proxy_fn_func_proxy_fetch_image(`url`: RustBuffer.ByValue,`headers`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
-    ): RustBuffer.ByValue
-    external fun uniffi_letterbox_proxy_fn_func_proxy_fetch_images_batch(`urls`: RustBuffer.ByValue,`maxConcurrent`: Int,uniffi_out_err: UniffiRustCallStatus, 
-    ): RustBuffer.ByValue
-    external fun uniffi_letterbox_proxy_fn_func_proxy_fetch_url(`url`: RustBuffer.ByValue,`headers`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
-    ): RustBuffer.ByValue
-    external fun uniffi_letterbox_proxy_fn_func_proxy_init(`storagePath`: RustBuffer.ByValue,`maxCacheSize`: Int,uniffi_out_err: UniffiRustCallStatus, 
-    ): Unit
-    external fun uniffi_letterbox_proxy_fn_func_proxy_shutdown(uniffi_out_err: UniffiRustCallStatus, 
-    ): Unit
-    external fun uniffi_letterbox_proxy_fn_func_proxy_status(uniffi_out_err: UniffiRustCallStatus, 
-    ): RustBuffer.ByValue
-    external fun uniffi_letterbox_proxy_fn_func_proxy_reset_identity(uniffi_out_err: UniffiRustCallStatus, 
-    ): RustBuffer.ByValue
-    external fun uniffi_letterbox_proxy_fn_func_proxy_stored_config(uniffi_out_err: UniffiRustCallStatus, 
-    ): RustBuffer.ByValue
-    external fun uniffi_letterbox_proxy_fn_func_proxy_tls_self_test(uniffi_out_err: UniffiRustCallStatus, 
-    ): RustBuffer.ByValue
-    external fun ffi_letterbox_proxy_rustbuffer_alloc(`size`: Long,uniffi_out_err: UniffiRustCallStatus, 
-    ): RustBuffer.ByValue
-    external fun ffi_letterbox_proxy_rustbuffer_from_bytes(`bytes`: ForeignBytes.ByValue,uniffi_out_err: UniffiRustCallStatus, 
-    ): RustBuffer.ByValue
-    external fun ffi_letterbox_proxy_rustbuffer_free(`buf`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
-    ): Unit
-    external fun ffi_letterbox_proxy_rustbuffer_reserve(`buf`: RustBuffer.ByValue,`additional`: Long,uniffi_out_err: UniffiRustCallStatus, 
-    ): RustBuffer.ByValue
-    external fun ffi_letterbox_proxy_rust_future_poll_u8(`handle`: Long,`callback`: UniffiRustFutureContinuationCallback,`callbackData`: Long,
-    ): Unit
-    external fun ffi_letterbox_proxy_rust_future_cancel_u8(`handle`: Long,
-    ): Unit
-    external fun ffi_letterbox_proxy_rust_future_free_u8(`handle`: Long,
-    ): Unit
-    external fun ffi_letterbox_proxy_rust_future_complete_u8(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
-    ): Int
-    external fun ffi_letterbox_proxy_rust_future_poll_i8(`handle`: Long,`callback`: UniffiRustFutureContinuationCallback,`callbackData`: Long,
-    ): Unit
-    external fun ffi_letterbox_proxy_rust_future_cancel_i8(`handle`: Long,
-    ): Unit
-    external fun ffi_letterbox_proxy_rust_future_free_i8(`handle`: Long,
-    ): Unit
-    external fun ffi_letterbox_proxy_rust_future_complete_i8(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
-    ): Byte
-    external fun ffi_letterbox_proxy_rust_future_poll_u16(`handle`: Long,`callback`: UniffiRustFutureContinuationCallback,`callbackData`: Long,
-    ): Unit
-    external fun ffi_letterbox_proxy_rust_future_cancel_u16(`handle`: Long,
-    ): Unit
-    external fun ffi_letterbox_proxy_rust_future_free_u16(`handle`: Long,
-    ): Unit
-    external fun ffi_letterbox_proxy_rust_future_complete_u16(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
-    ): Int
-    external fun ffi_letterbox_proxy_rust_future_poll_i16(`handle`: Long,`callback`: UniffiRustFutureContinuationCallback,`callbackData`: Long,
-    ): Unit
-    external fun ffi_letterbox_proxy_rust_future_cancel_i16(`handle`: Long,
-    ): Unit
-    external fun ffi_letterbox_proxy_rust_future_free_i16(`handle`: Long,
-    ): Unit
-    external fun ffi_letterbox_proxy_rust_future_complete_i16(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
-    ): Short
-    external fun ffi_letterbox_proxy_rust_future_poll_u32(`handle`: Long,`callback`: UniffiRustFutureContinuationCallback,`callbackData`: Long,
-    ): Unit
-    external fun ffi_letterbox_proxy_rust_future_cancel_u32(`handle`: Long,
-    ): Unit
-    external fun ffi_letterbox_proxy_rust_future_free_u32(`handle`: Long,
-    ): Unit
-    external fun ffi_letterbox_proxy_rust_future_complete_u32(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
-    ): Int
-    external fun ffi_letterbox_proxy_rust_future_poll_i32(`handle`: Long,`callback`: UniffiRustFutureContinuationCallback,`callbackData`: Long,
-    ): Unit
-    external fun ffi_letterbox_proxy_rust_future_cancel_i32(`handle`: Long,
-    ): Unit
-    external fun ffi_letterbox_proxy_rust_future_free_i32(`handle`: Long,
-    ): Unit
-    external fun ffi_letterbox_proxy_rust_future_complete_i32(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
-    ): Int
-    external fun ffi_letterbox_proxy_rust_future_poll_u64(`handle`: Long,`callback`: UniffiRustFutureContinuationCallback,`callbackData`: Long,
-    ): Unit
-    external fun ffi_letterbox_proxy_rust_future_cancel_u64(`handle`: Long,
-    ): Unit
-    external fun ffi_letterbox_proxy_rust_future_free_u64(`handle`: Long,
-    ): Unit
-    external fun ffi_letterbox_proxy_rust_future_complete_u64(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
-    ): Long
-    external fun ffi_letterbox_proxy_rust_future_poll_i64(`handle`: Long,`callback`: UniffiRustFutureContinuationCallback,`callbackData`: Long,
-    ): Unit
-    external fun ffi_letterbox_proxy_rust_future_cancel_i64(`handle`: Long,
-    ): Unit
-    external fun ffi_letterbox_proxy_rust_future_free_i64(`handle`: Long,
-    ): Unit
-    external fun ffi_letterbox_proxy_rust_future_complete_i64(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
-    ): Long
-    external fun ffi_letterbox_proxy_rust_future_poll_f32(`handle`: Long,`callback`: UniffiRustFutureContinuationCallback,`callbackData`: Long,
-    ): Unit
-    external fun ffi_letterbox_proxy_rust_future_cancel_f32(`handle`: Long,
-    ): Unit
-    external fun ffi_letterbox_proxy_rust_future_free_f32(`handle`: Long,
-    ): Unit
-    external fun ffi_letterbox_proxy_rust_future_complete_f32(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
-    ): Float
-    external fun ffi_letterbox_proxy_rust_future_poll_f64(`handle`: Long,`callback`: UniffiRustFutureContinuationCallback,`callbackData`: Long,
-    ): Unit
-    external fun ffi_letterbox_proxy_rust_future_cancel_f64(`handle`: Long,
-    ): Unit
-    external fun ffi_letterbox_proxy_rust_future_free_f64(`handle`: Long,
-    ): Unit
-    external fun ffi_letterbox_proxy_rust_future_complete_f64(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
-    ): Double
-    external fun ffi_letterbox_proxy_rust_future_poll_rust_buffer(`handle`: Long,`callback`: UniffiRustFutureContinuationCallback,`callbackData`: Long,
-    ): Unit
-    external fun ffi_letterbox_proxy_rust_future_cancel_rust_buffer(`handle`: Long,
-    ): Unit
-    external fun ffi_letterbox_proxy_rust_future_free_rust_buffer(`handle`: Long,
-    ): Unit
-    external fun ffi_letterbox_proxy_rust_future_complete_rust_buffer(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
-    ): RustBuffer.ByValue
-    external fun ffi_letterbox_proxy_rust_future_poll_void(`handle`: Long,`callback`: UniffiRustFutureContinuationCallback,`callbackData`: Long,
-    ): Unit
-    external fun ffi_letterbox_proxy_rust_future_cancel_void(`handle`: Long,
-    ): Unit
-    external fun ffi_letterbox_proxy_rust_future_free_void(`handle`: Long,
-    ): Unit
-    external fun ffi_letterbox_proxy_rust_future_complete_void(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
-    ): Unit
+    external fun uniffi_letterbox_proxy_fn_clone_batchresultlistener(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Long
+external fun uniffi_letterbox_proxy_fn_free_batchresultlistener(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_init_callback_vtable_batchresultlistener(`vtable`: UniffiVTableCallbackInterfaceBatchResultListener,
+): Unit
+external fun uniffi_letterbox_proxy_fn_method_batchresultlistener_on_result(`ptr`: Long,`index`: Int,`result`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_clone_imagebatch(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Long
+external fun uniffi_letterbox_proxy_fn_free_imagebatch(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_method_imagebatch_cancel(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_method_imagebatch_is_cancelled(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Byte
+external fun uniffi_letterbox_proxy_fn_method_imagebatch_results(`ptr`: Long,
+): Long
+external fun uniffi_letterbox_proxy_fn_method_imagebatch_stream(`ptr`: Long,`listener`: Long,
+): Long
+external fun uniffi_letterbox_proxy_fn_clone_cachestorage(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Long
+external fun uniffi_letterbox_proxy_fn_free_cachestorage(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_init_callback_vtable_cachestorage(`vtable`: UniffiVTableCallbackInterfaceCacheStorage,
+): Unit
+external fun uniffi_letterbox_proxy_fn_method_cachestorage_get(`ptr`: Long,`key`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_proxy_fn_method_cachestorage_put(`ptr`: Long,`key`: RustBuffer.ByValue,`value`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_method_cachestorage_delete(`ptr`: Long,`key`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_method_cachestorage_list(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_proxy_fn_clone_proxyclient(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Long
+external fun uniffi_letterbox_proxy_fn_free_proxyclient(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_constructor_proxyclient_new(`storagePath`: RustBuffer.ByValue,`maxCacheSize`: Int,uniffi_out_err: UniffiRustCallStatus, 
+): Long
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_reset_identity(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_stored_config(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_audit_log_files(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_audit_url_hash(`ptr`: Long,`url`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_clear_audit_log(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_set_audit_log(`ptr`: Long,`maxBytes`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_bandwidth_usage(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_reset_bandwidth_usage(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_fetch_images_batch(`ptr`: Long,`urls`: RustBuffer.ByValue,`maxConcurrent`: Int,`priority`: RustBuffer.ByValue,`groupId`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Long
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_fetch_images_streaming(`ptr`: Long,`urls`: RustBuffer.ByValue,`maxConcurrent`: Int,`priority`: RustBuffer.ByValue,`listener`: Long,`groupId`: RustBuffer.ByValue,
+): Long
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_clear_cache(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_disable_disk_cache(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_enable_disk_cache(`ptr`: Long,`options`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_export_cache(`ptr`: Long,`path`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Int
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_import_cache(`ptr`: Long,`path`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Int
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_pin_url(`ptr`: Long,`url`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_pinned_urls(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_set_cache_storage(`ptr`: Long,`storage`: RustBuffer.ByValue,`mode`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_trim_cache(`ptr`: Long,`targetBytes`: Long,`spillToDisk`: Byte,uniffi_out_err: UniffiRustCallStatus, 
+): Long
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_unpin_url(`ptr`: Long,`url`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Byte
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_check_for_update(`ptr`: Long,`currentVersion`: RustBuffer.ByValue,`repo`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_diagnostics(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_shutdown(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_status(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_set_fetch_concurrency(`ptr`: Long,`concurrency`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_data_saver(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_set_data_saver(`ptr`: Long,`policy`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_set_max_image_pixels(`ptr`: Long,`maxPixels`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_fetch_image_to_file(`ptr`: Long,`url`: RustBuffer.ByValue,`destPath`: RustBuffer.ByValue,`options`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_set_tls_early_data(`ptr`: Long,`enabled`: Byte,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_set_event_listener(`ptr`: Long,`listener`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_fetch_favicon(`ptr`: Long,`domain`: RustBuffer.ByValue,`sizeHint`: Int,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_fetch_image(`ptr`: Long,`url`: RustBuffer.ByValue,`headers`: RustBuffer.ByValue,`expectedHash`: RustBuffer.ByValue,`groupId`: RustBuffer.ByValue,
+): Long
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_fetch_url(`ptr`: Long,`url`: RustBuffer.ByValue,`headers`: RustBuffer.ByValue,
+): Long
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_set_image_accept(`ptr`: Long,`accept`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_reset_group_budget(`ptr`: Long,`groupId`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_set_group_byte_budget(`ptr`: Long,`maxBytes`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_set_group_referer(`ptr`: Long,`groupId`: RustBuffer.ByValue,`grant`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_fetch_image_handle(`ptr`: Long,`url`: RustBuffer.ByValue,`headers`: RustBuffer.ByValue,`expectedHash`: RustBuffer.ByValue,`groupId`: RustBuffer.ByValue,
+): Long
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_set_allowed_sensitive_headers(`ptr`: Long,`names`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_set_hedge_delay(`ptr`: Long,`delayMs`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_is_enabled(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Byte
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_set_enabled(`ptr`: Long,`enabled`: Byte,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_start_loopback_server(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Long
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_metrics(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_clear_offline_queue(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_offline_queue(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_queue_for_offline(`ptr`: Long,`urls`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Int
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_retry_offline_queue(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_set_offline_listener(`ptr`: Long,`listener`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_is_paused(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Byte
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_pause(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_resume(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_prefetch(`ptr`: Long,`urls`: RustBuffer.ByValue,`priority`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Int
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_resolve_preview(`ptr`: Long,`url`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_probe_image(`ptr`: Long,`url`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_set_network_binding(`ptr`: Long,`binding`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_set_socket_protector(`ptr`: Long,`protector`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_clear_recent_errors(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_recent_errors(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_set_redirect_policy(`ptr`: Long,`policy`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_refresh_warp_config(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_set_warp_refresh(`ptr`: Long,`refresh`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_fetch_resource(`ptr`: Long,`url`: RustBuffer.ByValue,`options`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_set_retry_policy(`ptr`: Long,`policy`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_set_https_mode(`ptr`: Long,`mode`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_fetch_best_source(`ptr`: Long,`sources`: RustBuffer.ByValue,`targetWidth`: Int,`pixelDensity`: Float,`probeSizes`: Byte,
+): Long
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_open_stream(`ptr`: Long,`host`: RustBuffer.ByValue,`port`: Short,uniffi_out_err: UniffiRustCallStatus, 
+): Long
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_set_svg_sanitization(`ptr`: Long,`enabled`: Byte,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_set_rate_limit(`ptr`: Long,`limit`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_set_timeout_budget(`ptr`: Long,`budget`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_set_block_tracking_pixels(`ptr`: Long,`enabled`: Byte,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_set_tracker_domains(`ptr`: Long,`domains`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_fetch_image_transformed(`ptr`: Long,`url`: RustBuffer.ByValue,`headers`: RustBuffer.ByValue,`transform`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_set_upstream_proxy(`ptr`: Long,`proxy`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_set_warm_up_hosts(`ptr`: Long,`hosts`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_method_proxyclient_warm_up(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_clone_proxyeventlistener(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Long
+external fun uniffi_letterbox_proxy_fn_free_proxyeventlistener(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_init_callback_vtable_proxyeventlistener(`vtable`: UniffiVTableCallbackInterfaceProxyEventListener,
+): Unit
+external fun uniffi_letterbox_proxy_fn_method_proxyeventlistener_on_event(`ptr`: Long,`event`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_clone_imagehandle(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Long
+external fun uniffi_letterbox_proxy_fn_free_imagehandle(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_method_imagehandle_byte_len(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Long
+external fun uniffi_letterbox_proxy_fn_method_imagehandle_info(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_proxy_fn_method_imagehandle_read(`ptr`: Long,`offset`: Long,`len`: Int,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_proxy_fn_method_imagehandle_write_to_file(`ptr`: Long,`path`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_clone_loglistener(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Long
+external fun uniffi_letterbox_proxy_fn_free_loglistener(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_init_callback_vtable_loglistener(`vtable`: UniffiVTableCallbackInterfaceLogListener,
+): Unit
+external fun uniffi_letterbox_proxy_fn_method_loglistener_on_log(`ptr`: Long,`level`: RustBuffer.ByValue,`target`: RustBuffer.ByValue,`message`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_clone_loopbackserver(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Long
+external fun uniffi_letterbox_proxy_fn_free_loopbackserver(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_method_loopbackserver_image_url(`ptr`: Long,`url`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_proxy_fn_method_loopbackserver_port(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Int
+external fun uniffi_letterbox_proxy_fn_method_loopbackserver_stop(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_clone_offlinefetchlistener(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Long
+external fun uniffi_letterbox_proxy_fn_free_offlinefetchlistener(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_init_callback_vtable_offlinefetchlistener(`vtable`: UniffiVTableCallbackInterfaceOfflineFetchListener,
+): Unit
+external fun uniffi_letterbox_proxy_fn_method_offlinefetchlistener_on_fetched(`ptr`: Long,`url`: RustBuffer.ByValue,`error`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_clone_progresslistener(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Long
+external fun uniffi_letterbox_proxy_fn_free_progresslistener(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_init_callback_vtable_progresslistener(`vtable`: UniffiVTableCallbackInterfaceProgressListener,
+): Unit
+external fun uniffi_letterbox_proxy_fn_method_progresslistener_on_progress(`ptr`: Long,`url`: RustBuffer.ByValue,`bytesDownloaded`: Long,`total`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_clone_socketprotector(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Long
+external fun uniffi_letterbox_proxy_fn_free_socketprotector(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_init_callback_vtable_socketprotector(`vtable`: UniffiVTableCallbackInterfaceSocketProtector,
+): Unit
+external fun uniffi_letterbox_proxy_fn_method_socketprotector_protect(`ptr`: Long,`fd`: Int,uniffi_out_err: UniffiRustCallStatus, 
+): Byte
+external fun uniffi_letterbox_proxy_fn_clone_proxystream(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Long
+external fun uniffi_letterbox_proxy_fn_free_proxystream(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_method_proxystream_close(`ptr`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_method_proxystream_read(`ptr`: Long,`maxLen`: Int,`timeoutMs`: Int,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_proxy_fn_method_proxystream_write(`ptr`: Long,`data`: RustBuffer.ByValue,`timeoutMs`: Int,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_method_proxyerror_category(`ptr`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_proxy_fn_method_proxyerror_code(`ptr`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_proxy_fn_method_proxyerror_http_status(`ptr`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_proxy_fn_method_proxyerror_is_retryable(`ptr`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Byte
+external fun uniffi_letterbox_proxy_fn_method_errorcode_category(`ptr`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_proxy_fn_func_proxy_check_for_update(`currentVersion`: RustBuffer.ByValue,`repo`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_proxy_fn_func_proxy_diagnostics(uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_proxy_fn_func_proxy_init(`storagePath`: RustBuffer.ByValue,`maxCacheSize`: Int,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_func_proxy_shutdown(uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_func_proxy_status(uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_proxy_fn_func_proxy_reset_identity(uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_proxy_fn_func_proxy_stored_config(uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_proxy_fn_func_proxy_audit_log_files(uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_proxy_fn_func_proxy_audit_url_hash(`url`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_proxy_fn_func_proxy_clear_audit_log(uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_func_proxy_set_audit_log(`maxBytes`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_func_proxy_bandwidth_usage(uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_proxy_fn_func_proxy_reset_bandwidth_usage(uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_func_proxy_fetch_images_batch(`urls`: RustBuffer.ByValue,`maxConcurrent`: Int,`priority`: RustBuffer.ByValue,`groupId`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Long
+external fun uniffi_letterbox_proxy_fn_func_proxy_fetch_images_streaming(`urls`: RustBuffer.ByValue,`maxConcurrent`: Int,`priority`: RustBuffer.ByValue,`listener`: Long,`groupId`: RustBuffer.ByValue,
+): Long
+external fun uniffi_letterbox_proxy_fn_func_proxy_clear_cache(uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_func_proxy_disable_disk_cache(uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_func_proxy_enable_disk_cache(`options`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_func_proxy_export_cache(`path`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Int
+external fun uniffi_letterbox_proxy_fn_func_proxy_import_cache(`path`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Int
+external fun uniffi_letterbox_proxy_fn_func_proxy_pin_url(`url`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_func_proxy_pinned_urls(uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_proxy_fn_func_proxy_set_cache_storage(`storage`: RustBuffer.ByValue,`mode`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_func_proxy_trim_cache(`targetBytes`: Long,`spillToDisk`: Byte,uniffi_out_err: UniffiRustCallStatus, 
+): Long
+external fun uniffi_letterbox_proxy_fn_func_proxy_unpin_url(`url`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Byte
+external fun uniffi_letterbox_proxy_fn_func_proxy_set_fetch_concurrency(`concurrency`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_func_proxy_data_saver(uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_proxy_fn_func_proxy_set_data_saver(`policy`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_func_proxy_set_max_image_pixels(`maxPixels`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_func_proxy_fetch_image_to_file(`url`: RustBuffer.ByValue,`destPath`: RustBuffer.ByValue,`options`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_proxy_fn_func_proxy_set_tls_early_data(`enabled`: Byte,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_func_proxy_set_event_listener(`listener`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_func_proxy_fetch_favicon(`domain`: RustBuffer.ByValue,`sizeHint`: Int,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_proxy_fn_func_proxy_fetch_image(`url`: RustBuffer.ByValue,`headers`: RustBuffer.ByValue,`expectedHash`: RustBuffer.ByValue,`groupId`: RustBuffer.ByValue,
+): Long
+external fun uniffi_letterbox_proxy_fn_func_proxy_fetch_url(`url`: RustBuffer.ByValue,`headers`: RustBuffer.ByValue,
+): Long
+external fun uniffi_letterbox_proxy_fn_func_proxy_set_image_accept(`accept`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_func_proxy_reset_group_budget(`groupId`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_func_proxy_set_group_byte_budget(`maxBytes`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_func_proxy_set_group_referer(`groupId`: RustBuffer.ByValue,`grant`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_func_proxy_fetch_image_handle(`url`: RustBuffer.ByValue,`headers`: RustBuffer.ByValue,`expectedHash`: RustBuffer.ByValue,`groupId`: RustBuffer.ByValue,
+): Long
+external fun uniffi_letterbox_proxy_fn_func_proxy_set_allowed_sensitive_headers(`names`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_func_proxy_set_hedge_delay(`delayMs`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_func_proxy_is_enabled(uniffi_out_err: UniffiRustCallStatus, 
+): Byte
+external fun uniffi_letterbox_proxy_fn_func_proxy_set_enabled(`enabled`: Byte,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_func_proxy_set_enabled_globally(`enabled`: Byte,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_func_proxy_set_log_listener(`listener`: RustBuffer.ByValue,`maxLevel`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_func_proxy_start_loopback_server(uniffi_out_err: UniffiRustCallStatus, 
+): Long
+external fun uniffi_letterbox_proxy_fn_func_proxy_metrics(uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_proxy_fn_func_proxy_clear_offline_queue(uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_func_proxy_offline_queue(uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_proxy_fn_func_proxy_queue_for_offline(`urls`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Int
+external fun uniffi_letterbox_proxy_fn_func_proxy_retry_offline_queue(uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_func_proxy_set_offline_listener(`listener`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_func_proxy_is_paused(uniffi_out_err: UniffiRustCallStatus, 
+): Byte
+external fun uniffi_letterbox_proxy_fn_func_proxy_pause(uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_func_proxy_resume(uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_func_proxy_prefetch(`urls`: RustBuffer.ByValue,`priority`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Int
+external fun uniffi_letterbox_proxy_fn_func_proxy_resolve_preview(`url`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_proxy_fn_func_proxy_probe_image(`url`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_proxy_fn_func_proxy_set_progress_listener(`listener`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_func_proxy_set_network_binding(`binding`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_func_proxy_set_socket_protector(`protector`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_func_proxy_clear_recent_errors(uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_func_proxy_recent_errors(uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_proxy_fn_func_proxy_set_redirect_policy(`policy`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_func_proxy_refresh_warp_config(uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_proxy_fn_func_proxy_set_warp_refresh(`refresh`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_func_proxy_fetch_resource(`url`: RustBuffer.ByValue,`options`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_proxy_fn_func_proxy_set_retry_policy(`policy`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_func_proxy_set_https_mode(`mode`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_func_proxy_tls_self_test(uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_proxy_fn_func_proxy_fetch_best_source(`sources`: RustBuffer.ByValue,`targetWidth`: Int,`pixelDensity`: Float,`probeSizes`: Byte,
+): Long
+external fun uniffi_letterbox_proxy_fn_func_proxy_open_stream(`host`: RustBuffer.ByValue,`port`: Short,uniffi_out_err: UniffiRustCallStatus, 
+): Long
+external fun uniffi_letterbox_proxy_fn_func_proxy_set_svg_sanitization(`enabled`: Byte,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_func_proxy_set_rate_limit(`limit`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_func_proxy_set_timeout_budget(`budget`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_func_proxy_set_block_tracking_pixels(`enabled`: Byte,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_func_proxy_set_tracker_domains(`domains`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_func_proxy_fetch_image_transformed(`url`: RustBuffer.ByValue,`headers`: RustBuffer.ByValue,`transform`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun uniffi_letterbox_proxy_fn_func_proxy_set_upstream_proxy(`proxy`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_func_proxy_set_warm_up_hosts(`hosts`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun uniffi_letterbox_proxy_fn_func_proxy_warm_up(uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun ffi_letterbox_proxy_rustbuffer_alloc(`size`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun ffi_letterbox_proxy_rustbuffer_from_bytes(`bytes`: ForeignBytes.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun ffi_letterbox_proxy_rustbuffer_free(`buf`: RustBuffer.ByValue,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
+external fun ffi_letterbox_proxy_rustbuffer_reserve(`buf`: RustBuffer.ByValue,`additional`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun ffi_letterbox_proxy_rust_future_poll_u8(`handle`: Long,`callback`: UniffiRustFutureContinuationCallback,`callbackData`: Long,
+): Unit
+external fun ffi_letterbox_proxy_rust_future_cancel_u8(`handle`: Long,
+): Unit
+external fun ffi_letterbox_proxy_rust_future_free_u8(`handle`: Long,
+): Unit
+external fun ffi_letterbox_proxy_rust_future_complete_u8(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Int
+external fun ffi_letterbox_proxy_rust_future_poll_i8(`handle`: Long,`callback`: UniffiRustFutureContinuationCallback,`callbackData`: Long,
+): Unit
+external fun ffi_letterbox_proxy_rust_future_cancel_i8(`handle`: Long,
+): Unit
+external fun ffi_letterbox_proxy_rust_future_free_i8(`handle`: Long,
+): Unit
+external fun ffi_letterbox_proxy_rust_future_complete_i8(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Byte
+external fun ffi_letterbox_proxy_rust_future_poll_u16(`handle`: Long,`callback`: UniffiRustFutureContinuationCallback,`callbackData`: Long,
+): Unit
+external fun ffi_letterbox_proxy_rust_future_cancel_u16(`handle`: Long,
+): Unit
+external fun ffi_letterbox_proxy_rust_future_free_u16(`handle`: Long,
+): Unit
+external fun ffi_letterbox_proxy_rust_future_complete_u16(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Int
+external fun ffi_letterbox_proxy_rust_future_poll_i16(`handle`: Long,`callback`: UniffiRustFutureContinuationCallback,`callbackData`: Long,
+): Unit
+external fun ffi_letterbox_proxy_rust_future_cancel_i16(`handle`: Long,
+): Unit
+external fun ffi_letterbox_proxy_rust_future_free_i16(`handle`: Long,
+): Unit
+external fun ffi_letterbox_proxy_rust_future_complete_i16(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Short
+external fun ffi_letterbox_proxy_rust_future_poll_u32(`handle`: Long,`callback`: UniffiRustFutureContinuationCallback,`callbackData`: Long,
+): Unit
+external fun ffi_letterbox_proxy_rust_future_cancel_u32(`handle`: Long,
+): Unit
+external fun ffi_letterbox_proxy_rust_future_free_u32(`handle`: Long,
+): Unit
+external fun ffi_letterbox_proxy_rust_future_complete_u32(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Int
+external fun ffi_letterbox_proxy_rust_future_poll_i32(`handle`: Long,`callback`: UniffiRustFutureContinuationCallback,`callbackData`: Long,
+): Unit
+external fun ffi_letterbox_proxy_rust_future_cancel_i32(`handle`: Long,
+): Unit
+external fun ffi_letterbox_proxy_rust_future_free_i32(`handle`: Long,
+): Unit
+external fun ffi_letterbox_proxy_rust_future_complete_i32(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Int
+external fun ffi_letterbox_proxy_rust_future_poll_u64(`handle`: Long,`callback`: UniffiRustFutureContinuationCallback,`callbackData`: Long,
+): Unit
+external fun ffi_letterbox_proxy_rust_future_cancel_u64(`handle`: Long,
+): Unit
+external fun ffi_letterbox_proxy_rust_future_free_u64(`handle`: Long,
+): Unit
+external fun ffi_letterbox_proxy_rust_future_complete_u64(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Long
+external fun ffi_letterbox_proxy_rust_future_poll_i64(`handle`: Long,`callback`: UniffiRustFutureContinuationCallback,`callbackData`: Long,
+): Unit
+external fun ffi_letterbox_proxy_rust_future_cancel_i64(`handle`: Long,
+): Unit
+external fun ffi_letterbox_proxy_rust_future_free_i64(`handle`: Long,
+): Unit
+external fun ffi_letterbox_proxy_rust_future_complete_i64(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Long
+external fun ffi_letterbox_proxy_rust_future_poll_f32(`handle`: Long,`callback`: UniffiRustFutureContinuationCallback,`callbackData`: Long,
+): Unit
+external fun ffi_letterbox_proxy_rust_future_cancel_f32(`handle`: Long,
+): Unit
+external fun ffi_letterbox_proxy_rust_future_free_f32(`handle`: Long,
+): Unit
+external fun ffi_letterbox_proxy_rust_future_complete_f32(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Float
+external fun ffi_letterbox_proxy_rust_future_poll_f64(`handle`: Long,`callback`: UniffiRustFutureContinuationCallback,`callbackData`: Long,
+): Unit
+external fun ffi_letterbox_proxy_rust_future_cancel_f64(`handle`: Long,
+): Unit
+external fun ffi_letterbox_proxy_rust_future_free_f64(`handle`: Long,
+): Unit
+external fun ffi_letterbox_proxy_rust_future_complete_f64(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Double
+external fun ffi_letterbox_proxy_rust_future_poll_rust_buffer(`handle`: Long,`callback`: UniffiRustFutureContinuationCallback,`callbackData`: Long,
+): Unit
+external fun ffi_letterbox_proxy_rust_future_cancel_rust_buffer(`handle`: Long,
+): Unit
+external fun ffi_letterbox_proxy_rust_future_free_rust_buffer(`handle`: Long,
+): Unit
+external fun ffi_letterbox_proxy_rust_future_complete_rust_buffer(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): RustBuffer.ByValue
+external fun ffi_letterbox_proxy_rust_future_poll_void(`handle`: Long,`callback`: UniffiRustFutureContinuationCallback,`callbackData`: Long,
+): Unit
+external fun ffi_letterbox_proxy_rust_future_cancel_void(`handle`: Long,
+): Unit
+external fun ffi_letterbox_proxy_rust_future_free_void(`handle`: Long,
+): Unit
+external fun ffi_letterbox_proxy_rust_future_complete_void(`handle`: Long,uniffi_out_err: UniffiRustCallStatus, 
+): Unit
 
-        
+    
 }
 
 private fun uniffiCheckContractApiVersion(lib: IntegrityCheckingUniffiLib) {
@@ -815,1891 +1767,13229 @@ private fun uniffiCheckContractApiVersion(lib: IntegrityCheckingUniffiLib) {
 }
 @Suppress("UNUSED_PARAMETER")
 private fun uniffiCheckApiChecksums(lib: IntegrityCheckingUniffiLib) {
-    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_check_for_update() != 8783) {
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_check_for_update() != 46385) {
         throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_clear_cache() != 62065) {
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_diagnostics() != 20210) {
         throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_diagnostics() != 157) {
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_init() != 50834) {
         throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_fetch_image() != 63800) {
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_shutdown() != 56757) {
         throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_fetch_images_batch() != 3727) {
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_status() != 47304) {
         throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_fetch_url() != 15037) {
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_reset_identity() != 26393) {
         throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_init() != 3960) {
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_stored_config() != 52905) {
         throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_shutdown() != 19042) {
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_audit_log_files() != 26264) {
         throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_status() != 33270) {
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_audit_url_hash() != 32105) {
         throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_reset_identity() != 22232) {
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_clear_audit_log() != 19491) {
         throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_stored_config() != 59842) {
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_set_audit_log() != 17490) {
         throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_tls_self_test() != 20758) {
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_bandwidth_usage() != 28553) {
         throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-}
-
-/**
- * @suppress
- */
-public fun uniffiEnsureInitialized() {
-    IntegrityCheckingUniffiLib
-    // UniffiLib() initialized as objects are used, but we still need to explicitly
-    // reference it so initialization across crates works as expected.
-    UniffiLib
-}
-
-// Async support
-
-// Public interface members begin here.
-
-
-// Interface implemented by anything that can contain an object reference.
-//
-// Such types expose a `destroy()` method that must be called to cleanly
-// dispose of the contained objects. Failure to call this method may result
-// in memory leaks.
-//
-// The easiest way to ensure this method is called is to use the `.use`
-// helper method to execute a block and destroy the object at the end.
-interface Disposable {
-    fun destroy()
-    companion object {
-        fun destroy(vararg args: Any?) {
-            for (arg in args) {
-                when (arg) {
-                    is Disposable -> arg.destroy()
-                    is ArrayList<*> -> {
-                        for (idx in arg.indices) {
-                            val element = arg[idx]
-                            if (element is Disposable) {
-                                element.destroy()
-                            }
-                        }
-                    }
-                    is Map<*, *> -> {
-                        for (element in arg.values) {
-                            if (element is Disposable) {
-                                element.destroy()
-                            }
-                        }
-                    }
-                    is Iterable<*> -> {
-                        for (element in arg) {
-                            if (element is Disposable) {
-                                element.destroy()
-                            }
-                        }
-                    }
-                }
-            }
-        }
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_reset_bandwidth_usage() != 40278) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-}
-
-/**
- * @suppress
- */
-inline fun <T : Disposable?, R> T.use(block: (T) -> R) =
-    try {
-        block(this)
-    } finally {
-        try {
-            // N.B. our implementation is on the nullable type `Disposable?`.
-            this?.destroy()
-        } catch (e: Throwable) {
-            // swallow
-        }
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_fetch_images_batch() != 5359) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-
-/** 
- * Placeholder object used to signal that we're constructing an interface with a FFI handle.
- *
- * This is the first argument for interface constructors that input a raw handle. It exists is that
- * so we can avoid signature conflicts when an interface has a regular constructor than inputs a
- * Long.
- *
- * @suppress
- * */
-object UniffiWithHandle
-
-/** 
- * Used to instantiate an interface without an actual pointer, for fakes in tests, mostly.
- *
- * @suppress
- * */
-object NoHandle
-
-/**
- * @suppress
- */
-public object FfiConverterUShort: FfiConverter<UShort, Short> {
-    override fun lift(value: Short): UShort {
-        return value.toUShort()
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_fetch_images_streaming() != 56771) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-
-    fun lift(value: Int): UShort {
-        return value.toUShort()
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_clear_cache() != 11876) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-
-    override fun read(buf: ByteBuffer): UShort {
-        return lift(buf.getShort())
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_disable_disk_cache() != 17708) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-
-    override fun lower(value: UShort): Short {
-        return value.toShort()
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_enable_disk_cache() != 7323) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-
-    override fun allocationSize(value: UShort) = 2UL
-
-    override fun write(value: UShort, buf: ByteBuffer) {
-        buf.putShort(value.toShort())
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_export_cache() != 16528) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-}
-
-/**
- * @suppress
- */
-public object FfiConverterUInt: FfiConverter<UInt, Int> {
-    override fun lift(value: Int): UInt {
-        return value.toUInt()
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_import_cache() != 41469) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-
-    override fun read(buf: ByteBuffer): UInt {
-        return lift(buf.getInt())
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_pin_url() != 65453) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-
-    override fun lower(value: UInt): Int {
-        return value.toInt()
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_pinned_urls() != 968) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-
-    override fun allocationSize(value: UInt) = 4UL
-
-    override fun write(value: UInt, buf: ByteBuffer) {
-        buf.putInt(value.toInt())
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_set_cache_storage() != 7860) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-}
-
-/**
- * @suppress
- */
-public object FfiConverterULong: FfiConverter<ULong, Long> {
-    override fun lift(value: Long): ULong {
-        return value.toULong()
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_trim_cache() != 13498) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-
-    override fun read(buf: ByteBuffer): ULong {
-        return lift(buf.getLong())
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_unpin_url() != 24186) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-
-    override fun lower(value: ULong): Long {
-        return value.toLong()
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_set_fetch_concurrency() != 21302) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-
-    override fun allocationSize(value: ULong) = 8UL
-
-    override fun write(value: ULong, buf: ByteBuffer) {
-        buf.putLong(value.toLong())
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_data_saver() != 23740) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-}
-
-/**
- * @suppress
- */
-public object FfiConverterLong: FfiConverter<Long, Long> {
-    override fun lift(value: Long): Long {
-        return value
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_set_data_saver() != 5203) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-
-    override fun read(buf: ByteBuffer): Long {
-        return buf.getLong()
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_set_max_image_pixels() != 7194) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-
-    override fun lower(value: Long): Long {
-        return value
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_fetch_image_to_file() != 7213) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-
-    override fun allocationSize(value: Long) = 8UL
-
-    override fun write(value: Long, buf: ByteBuffer) {
-        buf.putLong(value)
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_set_tls_early_data() != 25045) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-}
-
-/**
- * @suppress
- */
-public object FfiConverterFloat: FfiConverter<Float, Float> {
-    override fun lift(value: Float): Float {
-        return value
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_set_event_listener() != 36569) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-
-    override fun read(buf: ByteBuffer): Float {
-        return buf.getFloat()
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_fetch_favicon() != 52241) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-
-    override fun lower(value: Float): Float {
-        return value
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_fetch_image() != 30845) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-
-    override fun allocationSize(value: Float) = 4UL
-
-    override fun write(value: Float, buf: ByteBuffer) {
-        buf.putFloat(value)
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_fetch_url() != 38282) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-}
-
-/**
- * @suppress
- */
-public object FfiConverterBoolean: FfiConverter<Boolean, Byte> {
-    override fun lift(value: Byte): Boolean {
-        return value.toInt() != 0
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_set_image_accept() != 46238) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-
-    override fun read(buf: ByteBuffer): Boolean {
-        return lift(buf.get())
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_reset_group_budget() != 11151) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-
-    override fun lower(value: Boolean): Byte {
-        return if (value) 1.toByte() else 0.toByte()
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_set_group_byte_budget() != 26730) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-
-    override fun allocationSize(value: Boolean) = 1UL
-
-    override fun write(value: Boolean, buf: ByteBuffer) {
-        buf.put(lower(value))
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_set_group_referer() != 45069) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-}
-
-/**
- * @suppress
- */
-public object FfiConverterString: FfiConverter<String, RustBuffer.ByValue> {
-    // Note: we don't inherit from FfiConverterRustBuffer, because we use a
-    // special encoding when lowering/lifting.  We can use `RustBuffer.len` to
-    // store our length and avoid writing it out to the buffer.
-    override fun lift(value: RustBuffer.ByValue): String {
-        try {
-            val byteArr = ByteArray(value.len.toInt())
-            value.asByteBuffer()!!.get(byteArr)
-            return byteArr.toString(Charsets.UTF_8)
-        } finally {
-            RustBuffer.free(value)
-        }
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_fetch_image_handle() != 35806) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-
-    override fun read(buf: ByteBuffer): String {
-        val len = buf.getInt()
-        val byteArr = ByteArray(len)
-        buf.get(byteArr)
-        return byteArr.toString(Charsets.UTF_8)
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_set_allowed_sensitive_headers() != 57181) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-
-    fun toUtf8(value: String): ByteBuffer {
-        // Make sure we don't have invalid UTF-16, check for lone surrogates.
-        return Charsets.UTF_8.newEncoder().run {
-            onMalformedInput(CodingErrorAction.REPORT)
-            encode(CharBuffer.wrap(value))
-        }
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_set_hedge_delay() != 1562) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-
-    override fun lower(value: String): RustBuffer.ByValue {
-        val byteBuf = toUtf8(value)
-        // Ideally we'd pass these bytes to `ffi_bytebuffer_from_bytes`, but doing so would require us
-        // to copy them into a JNA `Memory`. So we might as well directly copy them into a `RustBuffer`.
-        val rbuf = RustBuffer.alloc(byteBuf.limit().toULong())
-        rbuf.asByteBuffer()!!.put(byteBuf)
-        return rbuf
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_is_enabled() != 28957) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-
-    // We aren't sure exactly how many bytes our string will be once it's UTF-8
-    // encoded.  Allocate 3 bytes per UTF-16 code unit which will always be
-    // enough.
-    override fun allocationSize(value: String): ULong {
-        val sizeForLength = 4UL
-        val sizeForString = value.length.toULong() * 3UL
-        return sizeForLength + sizeForString
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_set_enabled() != 63232) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-
-    override fun write(value: String, buf: ByteBuffer) {
-        val byteBuf = toUtf8(value)
-        buf.putInt(byteBuf.limit())
-        buf.put(byteBuf)
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_set_enabled_globally() != 40552) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-}
-
-/**
- * @suppress
- */
-public object FfiConverterByteArray: FfiConverterRustBuffer<ByteArray> {
-    override fun read(buf: ByteBuffer): ByteArray {
-        val len = buf.getInt()
-        val byteArr = ByteArray(len)
-        buf.get(byteArr)
-        return byteArr
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_set_log_listener() != 35715) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-    override fun allocationSize(value: ByteArray): ULong {
-        return 4UL + value.size.toULong()
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_start_loopback_server() != 60230) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-    override fun write(value: ByteArray, buf: ByteBuffer) {
-        buf.putInt(value.size)
-        buf.put(value)
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_metrics() != 54878) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-}
-
-
-
-/**
- * Result of a batch image fetch operation.
- */
-data class BatchImageResult (
-    /**
-     * URL that was requested.
-     */
-    var `url`: kotlin.String
-    , 
-    /**
-     * Whether the fetch was successful.
-     */
-    var `success`: kotlin.Boolean
-    , 
-    /**
-     * Image response if successful.
-     */
-    var `response`: ImageResponse?
-    , 
-    /**
-     * Error message if failed.
-     */
-    var `error`: kotlin.String?
-    
-){
-    
-
-    
-
-    
-    companion object
-}
-
-/**
- * @suppress
- */
-public object FfiConverterTypeBatchImageResult: FfiConverterRustBuffer<BatchImageResult> {
-    override fun read(buf: ByteBuffer): BatchImageResult {
-        return BatchImageResult(
-            FfiConverterString.read(buf),
-            FfiConverterBoolean.read(buf),
-            FfiConverterOptionalTypeImageResponse.read(buf),
-            FfiConverterOptionalString.read(buf),
-        )
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_clear_offline_queue() != 22177) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-
-    override fun allocationSize(value: BatchImageResult) = (
-            FfiConverterString.allocationSize(value.`url`) +
-            FfiConverterBoolean.allocationSize(value.`success`) +
-            FfiConverterOptionalTypeImageResponse.allocationSize(value.`response`) +
-            FfiConverterOptionalString.allocationSize(value.`error`)
-    )
-
-    override fun write(value: BatchImageResult, buf: ByteBuffer) {
-            FfiConverterString.write(value.`url`, buf)
-            FfiConverterBoolean.write(value.`success`, buf)
-            FfiConverterOptionalTypeImageResponse.write(value.`response`, buf)
-            FfiConverterOptionalString.write(value.`error`, buf)
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_offline_queue() != 38242) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-}
-
-
-
-/**
- * Result of a generic tunnelled fetch (non-image content).
- */
-data class HttpFetchResponse (
-    /**
-     * HTTP status code of the final response.
-     */
-    var `status`: kotlin.UShort
-    , 
-    /**
-     * Normalised MIME type.
-     */
-    var `mimeType`: kotlin.String
-    , 
-    /**
-     * Raw response body.
-     */
-    var `data`: kotlin.ByteArray
-    , 
-    /**
-     * Final URL after redirects.
-     */
-    var `finalUrl`: kotlin.String
-    
-){
-    
-
-    
-
-    
-    companion object
-}
-
-/**
- * @suppress
- */
-public object FfiConverterTypeHttpFetchResponse: FfiConverterRustBuffer<HttpFetchResponse> {
-    override fun read(buf: ByteBuffer): HttpFetchResponse {
-        return HttpFetchResponse(
-            FfiConverterUShort.read(buf),
-            FfiConverterString.read(buf),
-            FfiConverterByteArray.read(buf),
-            FfiConverterString.read(buf),
-        )
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_queue_for_offline() != 18482) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-
-    override fun allocationSize(value: HttpFetchResponse) = (
-            FfiConverterUShort.allocationSize(value.`status`) +
-            FfiConverterString.allocationSize(value.`mimeType`) +
-            FfiConverterByteArray.allocationSize(value.`data`) +
-            FfiConverterString.allocationSize(value.`finalUrl`)
-    )
-
-    override fun write(value: HttpFetchResponse, buf: ByteBuffer) {
-            FfiConverterUShort.write(value.`status`, buf)
-            FfiConverterString.write(value.`mimeType`, buf)
-            FfiConverterByteArray.write(value.`data`, buf)
-            FfiConverterString.write(value.`finalUrl`, buf)
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_retry_offline_queue() != 43615) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
     }
-}
-
-
-
-/**
- * Result of a successful image fetch operation.
- */
-data class ImageResponse (
-    /**
-     * MIME type of the image (e.g., "image/png", "image/svg+xml").
-     */
-    var `mimeType`: kotlin.String
-    , 
-    /**
-     * Raw image bytes.
-     */
-    var `data`: kotlin.ByteArray
-    , 
-    /**
-     * Whether this response was served from cache.
-     */
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_set_offline_listener() != 41137) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_is_paused() != 223) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_pause() != 62803) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_resume() != 24050) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_prefetch() != 51478) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_resolve_preview() != 1163) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_probe_image() != 18622) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_set_progress_listener() != 25688) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_set_network_binding() != 39265) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_set_socket_protector() != 14681) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_clear_recent_errors() != 65031) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_recent_errors() != 14011) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_set_redirect_policy() != 61495) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_refresh_warp_config() != 21875) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_set_warp_refresh() != 42747) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_fetch_resource() != 50913) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_set_retry_policy() != 2276) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_set_https_mode() != 56984) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_tls_self_test() != 20758) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_fetch_best_source() != 9508) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_open_stream() != 4257) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_set_svg_sanitization() != 31060) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_set_rate_limit() != 34697) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_set_timeout_budget() != 53111) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_set_block_tracking_pixels() != 36546) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_set_tracker_domains() != 60354) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_fetch_image_transformed() != 21068) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_set_upstream_proxy() != 57022) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_set_warm_up_hosts() != 34313) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_func_proxy_warm_up() != 48168) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_batchresultlistener_on_result() != 29026) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_imagebatch_cancel() != 31228) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_imagebatch_is_cancelled() != 37678) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_imagebatch_results() != 47919) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_imagebatch_stream() != 65039) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_cachestorage_get() != 57884) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_cachestorage_put() != 2114) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_cachestorage_delete() != 29716) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_cachestorage_list() != 58339) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_reset_identity() != 26419) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_stored_config() != 46824) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_audit_log_files() != 8082) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_audit_url_hash() != 10354) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_clear_audit_log() != 14845) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_set_audit_log() != 44818) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_bandwidth_usage() != 18028) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_reset_bandwidth_usage() != 26025) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_fetch_images_batch() != 45318) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_fetch_images_streaming() != 54085) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_clear_cache() != 703) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_disable_disk_cache() != 45011) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_enable_disk_cache() != 12990) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_export_cache() != 50596) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_import_cache() != 31468) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_pin_url() != 60044) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_pinned_urls() != 33315) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_set_cache_storage() != 20834) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_trim_cache() != 34159) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_unpin_url() != 44082) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_check_for_update() != 19735) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_diagnostics() != 63656) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_shutdown() != 1241) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_status() != 10609) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_set_fetch_concurrency() != 20148) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_data_saver() != 47271) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_set_data_saver() != 17768) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_set_max_image_pixels() != 61783) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_fetch_image_to_file() != 48090) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_set_tls_early_data() != 6798) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_set_event_listener() != 3670) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_fetch_favicon() != 28757) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_fetch_image() != 48315) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_fetch_url() != 52109) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_set_image_accept() != 28427) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_reset_group_budget() != 33328) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_set_group_byte_budget() != 56370) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_set_group_referer() != 39773) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_fetch_image_handle() != 12346) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_set_allowed_sensitive_headers() != 56082) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_set_hedge_delay() != 34540) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_is_enabled() != 1831) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_set_enabled() != 64590) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_start_loopback_server() != 290) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_metrics() != 36055) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_clear_offline_queue() != 52284) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_offline_queue() != 2805) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_queue_for_offline() != 32680) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_retry_offline_queue() != 1709) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_set_offline_listener() != 26521) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_is_paused() != 35802) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_pause() != 18054) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_resume() != 45334) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_prefetch() != 32125) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_resolve_preview() != 24123) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_probe_image() != 11346) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_set_network_binding() != 2564) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_set_socket_protector() != 15121) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_clear_recent_errors() != 7474) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_recent_errors() != 7664) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_set_redirect_policy() != 23119) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_refresh_warp_config() != 56106) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_set_warp_refresh() != 13922) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_fetch_resource() != 21966) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_set_retry_policy() != 9073) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_set_https_mode() != 33124) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_fetch_best_source() != 34340) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_open_stream() != 10994) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_set_svg_sanitization() != 44645) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_set_rate_limit() != 5762) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_set_timeout_budget() != 46935) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_set_block_tracking_pixels() != 43572) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_set_tracker_domains() != 2662) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_fetch_image_transformed() != 21027) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_set_upstream_proxy() != 7867) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_set_warm_up_hosts() != 27699) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyclient_warm_up() != 49523) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxyeventlistener_on_event() != 14828) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_imagehandle_byte_len() != 466) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_imagehandle_info() != 13825) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_imagehandle_read() != 6494) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_imagehandle_write_to_file() != 1487) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_loglistener_on_log() != 40313) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_loopbackserver_image_url() != 41493) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_loopbackserver_port() != 2920) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_loopbackserver_stop() != 42247) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_offlinefetchlistener_on_fetched() != 10207) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_progresslistener_on_progress() != 32536) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_socketprotector_protect() != 62849) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxystream_close() != 56121) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxystream_read() != 7271) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_method_proxystream_write() != 24065) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+    if (lib.uniffi_letterbox_proxy_checksum_constructor_proxyclient_new() != 49384) {
+        throw RuntimeException("UniFFI API checksum mismatch: try cleaning and rebuilding your project")
+    }
+}
+
+/**
+ * @suppress
+ */
+public fun uniffiEnsureInitialized() {
+    IntegrityCheckingUniffiLib
+    // UniffiLib() initialized as objects are used, but we still need to explicitly
+    // reference it so initialization across crates works as expected.
+    UniffiLib
+}
+
+// Async support
+// Async return type handlers
+
+internal const val UNIFFI_RUST_FUTURE_POLL_READY = 0.toByte()
+internal const val UNIFFI_RUST_FUTURE_POLL_WAKE = 1.toByte()
+
+internal val uniffiContinuationHandleMap = UniffiHandleMap<CancellableContinuation<Byte>>()
+
+// FFI type for Rust future continuations
+internal object uniffiRustFutureContinuationCallbackImpl: UniffiRustFutureContinuationCallback {
+    override fun callback(data: Long, pollResult: Byte) {
+        uniffiContinuationHandleMap.remove(data).resume(pollResult)
+    }
+}
+
+internal suspend fun<T, F, E: kotlin.Exception> uniffiRustCallAsync(
+    rustFuture: Long,
+    pollFunc: (Long, UniffiRustFutureContinuationCallback, Long) -> Unit,
+    completeFunc: (Long, UniffiRustCallStatus) -> F,
+    freeFunc: (Long) -> Unit,
+    liftFunc: (F) -> T,
+    errorHandler: UniffiRustCallStatusErrorHandler<E>
+): T {
+    try {
+        do {
+            val pollResult = suspendCancellableCoroutine<Byte> { continuation ->
+                pollFunc(
+                    rustFuture,
+                    uniffiRustFutureContinuationCallbackImpl,
+                    uniffiContinuationHandleMap.insert(continuation)
+                )
+            }
+        } while (pollResult != UNIFFI_RUST_FUTURE_POLL_READY);
+
+        return liftFunc(
+            uniffiRustCallWithError(errorHandler, { status -> completeFunc(rustFuture, status) })
+        )
+    } finally {
+        freeFunc(rustFuture)
+    }
+}
+
+// Public interface members begin here.
+
+
+// Interface implemented by anything that can contain an object reference.
+//
+// Such types expose a `destroy()` method that must be called to cleanly
+// dispose of the contained objects. Failure to call this method may result
+// in memory leaks.
+//
+// The easiest way to ensure this method is called is to use the `.use`
+// helper method to execute a block and destroy the object at the end.
+interface Disposable {
+    fun destroy()
+    companion object {
+        fun destroy(vararg args: Any?) {
+            for (arg in args) {
+                when (arg) {
+                    is Disposable -> arg.destroy()
+                    is ArrayList<*> -> {
+                        for (idx in arg.indices) {
+                            val element = arg[idx]
+                            if (element is Disposable) {
+                                element.destroy()
+                            }
+                        }
+                    }
+                    is Map<*, *> -> {
+                        for (element in arg.values) {
+                            if (element is Disposable) {
+                                element.destroy()
+                            }
+                        }
+                    }
+                    is Iterable<*> -> {
+                        for (element in arg) {
+                            if (element is Disposable) {
+                                element.destroy()
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/**
+ * @suppress
+ */
+inline fun <T : Disposable?, R> T.use(block: (T) -> R) =
+    try {
+        block(this)
+    } finally {
+        try {
+            // N.B. our implementation is on the nullable type `Disposable?`.
+            this?.destroy()
+        } catch (e: Throwable) {
+            // swallow
+        }
+    }
+
+/** 
+ * Placeholder object used to signal that we're constructing an interface with a FFI handle.
+ *
+ * This is the first argument for interface constructors that input a raw handle. It exists is that
+ * so we can avoid signature conflicts when an interface has a regular constructor than inputs a
+ * Long.
+ *
+ * @suppress
+ * */
+object UniffiWithHandle
+
+/** 
+ * Used to instantiate an interface without an actual pointer, for fakes in tests, mostly.
+ *
+ * @suppress
+ * */
+object NoHandle// Magic number for the Rust proxy to call using the same mechanism as every other method,
+// to free the callback once it's dropped by Rust.
+internal const val IDX_CALLBACK_FREE = 0
+// Callback return codes
+internal const val UNIFFI_CALLBACK_SUCCESS = 0
+internal const val UNIFFI_CALLBACK_ERROR = 1
+internal const val UNIFFI_CALLBACK_UNEXPECTED_ERROR = 2
+
+/**
+ * @suppress
+ */
+public abstract class FfiConverterCallbackInterface<CallbackInterface: Any>: FfiConverter<CallbackInterface, Long> {
+    internal val handleMap = UniffiHandleMap<CallbackInterface>()
+
+    internal fun drop(handle: Long) {
+        handleMap.remove(handle)
+    }
+
+    override fun lift(value: Long): CallbackInterface {
+        return handleMap.get(value)
+    }
+
+    override fun read(buf: ByteBuffer) = lift(buf.getLong())
+
+    override fun lower(value: CallbackInterface) = handleMap.insert(value)
+
+    override fun allocationSize(value: CallbackInterface) = 8UL
+
+    override fun write(value: CallbackInterface, buf: ByteBuffer) {
+        buf.putLong(lower(value))
+    }
+}
+/**
+ * The cleaner interface for Object finalization code to run.
+ * This is the entry point to any implementation that we're using.
+ *
+ * The cleaner registers objects and returns cleanables, so now we are
+ * defining a `UniffiCleaner` with a `UniffiClenaer.Cleanable` to abstract the
+ * different implmentations available at compile time.
+ *
+ * @suppress
+ */
+interface UniffiCleaner {
+    interface Cleanable {
+        fun clean()
+    }
+
+    fun register(value: Any, cleanUpTask: Runnable): UniffiCleaner.Cleanable
+
+    companion object
+}
+
+// The fallback Jna cleaner, which is available for both Android, and the JVM.
+private class UniffiJnaCleaner : UniffiCleaner {
+    private val cleaner = com.sun.jna.internal.Cleaner.getCleaner()
+
+    override fun register(value: Any, cleanUpTask: Runnable): UniffiCleaner.Cleanable =
+        UniffiJnaCleanable(cleaner.register(value, cleanUpTask))
+}
+
+private class UniffiJnaCleanable(
+    private val cleanable: com.sun.jna.internal.Cleaner.Cleanable,
+) : UniffiCleaner.Cleanable {
+    override fun clean() = cleanable.clean()
+}
+
+
+// We decide at uniffi binding generation time whether we were
+// using Android or not.
+// There are further runtime checks to chose the correct implementation
+// of the cleaner.
+private fun UniffiCleaner.Companion.create(): UniffiCleaner =
+    try {
+        // For safety's sake: if the library hasn't been run in android_cleaner = true
+        // mode, but is being run on Android, then we still need to think about
+        // Android API versions.
+        // So we check if java.lang.ref.Cleaner is there, and use that…
+        java.lang.Class.forName("java.lang.ref.Cleaner")
+        JavaLangRefCleaner()
+    } catch (e: ClassNotFoundException) {
+        // … otherwise, fallback to the JNA cleaner.
+        UniffiJnaCleaner()
+    }
+
+private class JavaLangRefCleaner : UniffiCleaner {
+    val cleaner = java.lang.ref.Cleaner.create()
+
+    override fun register(value: Any, cleanUpTask: Runnable): UniffiCleaner.Cleanable =
+        JavaLangRefCleanable(cleaner.register(value, cleanUpTask))
+}
+
+private class JavaLangRefCleanable(
+    val cleanable: java.lang.ref.Cleaner.Cleanable
+) : UniffiCleaner.Cleanable {
+    override fun clean() = cleanable.clean()
+}
+
+/**
+ * @suppress
+ */
+public object FfiConverterUByte: FfiConverter<UByte, Byte> {
+    override fun lift(value: Byte): UByte {
+        return value.toUByte()
+    }
+
+    fun lift(value: Int): UByte {
+        return value.toUByte()
+    }
+
+    override fun read(buf: ByteBuffer): UByte {
+        return lift(buf.get())
+    }
+
+    override fun lower(value: UByte): Byte {
+        return value.toByte()
+    }
+
+    override fun allocationSize(value: UByte) = 1UL
+
+    override fun write(value: UByte, buf: ByteBuffer) {
+        buf.put(value.toByte())
+    }
+}
+
+/**
+ * @suppress
+ */
+public object FfiConverterUShort: FfiConverter<UShort, Short> {
+    override fun lift(value: Short): UShort {
+        return value.toUShort()
+    }
+
+    fun lift(value: Int): UShort {
+        return value.toUShort()
+    }
+
+    override fun read(buf: ByteBuffer): UShort {
+        return lift(buf.getShort())
+    }
+
+    override fun lower(value: UShort): Short {
+        return value.toShort()
+    }
+
+    override fun allocationSize(value: UShort) = 2UL
+
+    override fun write(value: UShort, buf: ByteBuffer) {
+        buf.putShort(value.toShort())
+    }
+}
+
+/**
+ * @suppress
+ */
+public object FfiConverterUInt: FfiConverter<UInt, Int> {
+    override fun lift(value: Int): UInt {
+        return value.toUInt()
+    }
+
+    override fun read(buf: ByteBuffer): UInt {
+        return lift(buf.getInt())
+    }
+
+    override fun lower(value: UInt): Int {
+        return value.toInt()
+    }
+
+    override fun allocationSize(value: UInt) = 4UL
+
+    override fun write(value: UInt, buf: ByteBuffer) {
+        buf.putInt(value.toInt())
+    }
+}
+
+/**
+ * @suppress
+ */
+public object FfiConverterInt: FfiConverter<Int, Int> {
+    override fun lift(value: Int): Int {
+        return value
+    }
+
+    override fun read(buf: ByteBuffer): Int {
+        return buf.getInt()
+    }
+
+    override fun lower(value: Int): Int {
+        return value
+    }
+
+    override fun allocationSize(value: Int) = 4UL
+
+    override fun write(value: Int, buf: ByteBuffer) {
+        buf.putInt(value)
+    }
+}
+
+/**
+ * @suppress
+ */
+public object FfiConverterULong: FfiConverter<ULong, Long> {
+    override fun lift(value: Long): ULong {
+        return value.toULong()
+    }
+
+    override fun read(buf: ByteBuffer): ULong {
+        return lift(buf.getLong())
+    }
+
+    override fun lower(value: ULong): Long {
+        return value.toLong()
+    }
+
+    override fun allocationSize(value: ULong) = 8UL
+
+    override fun write(value: ULong, buf: ByteBuffer) {
+        buf.putLong(value.toLong())
+    }
+}
+
+/**
+ * @suppress
+ */
+public object FfiConverterLong: FfiConverter<Long, Long> {
+    override fun lift(value: Long): Long {
+        return value
+    }
+
+    override fun read(buf: ByteBuffer): Long {
+        return buf.getLong()
+    }
+
+    override fun lower(value: Long): Long {
+        return value
+    }
+
+    override fun allocationSize(value: Long) = 8UL
+
+    override fun write(value: Long, buf: ByteBuffer) {
+        buf.putLong(value)
+    }
+}
+
+/**
+ * @suppress
+ */
+public object FfiConverterFloat: FfiConverter<Float, Float> {
+    override fun lift(value: Float): Float {
+        return value
+    }
+
+    override fun read(buf: ByteBuffer): Float {
+        return buf.getFloat()
+    }
+
+    override fun lower(value: Float): Float {
+        return value
+    }
+
+    override fun allocationSize(value: Float) = 4UL
+
+    override fun write(value: Float, buf: ByteBuffer) {
+        buf.putFloat(value)
+    }
+}
+
+/**
+ * @suppress
+ */
+public object FfiConverterDouble: FfiConverter<Double, Double> {
+    override fun lift(value: Double): Double {
+        return value
+    }
+
+    override fun read(buf: ByteBuffer): Double {
+        return buf.getDouble()
+    }
+
+    override fun lower(value: Double): Double {
+        return value
+    }
+
+    override fun allocationSize(value: Double) = 8UL
+
+    override fun write(value: Double, buf: ByteBuffer) {
+        buf.putDouble(value)
+    }
+}
+
+/**
+ * @suppress
+ */
+public object FfiConverterBoolean: FfiConverter<Boolean, Byte> {
+    override fun lift(value: Byte): Boolean {
+        return value.toInt() != 0
+    }
+
+    override fun read(buf: ByteBuffer): Boolean {
+        return lift(buf.get())
+    }
+
+    override fun lower(value: Boolean): Byte {
+        return if (value) 1.toByte() else 0.toByte()
+    }
+
+    override fun allocationSize(value: Boolean) = 1UL
+
+    override fun write(value: Boolean, buf: ByteBuffer) {
+        buf.put(lower(value))
+    }
+}
+
+/**
+ * @suppress
+ */
+public object FfiConverterString: FfiConverter<String, RustBuffer.ByValue> {
+    // Note: we don't inherit from FfiConverterRustBuffer, because we use a
+    // special encoding when lowering/lifting.  We can use `RustBuffer.len` to
+    // store our length and avoid writing it out to the buffer.
+    override fun lift(value: RustBuffer.ByValue): String {
+        try {
+            val byteArr = ByteArray(value.len.toInt())
+            value.asByteBuffer()!!.get(byteArr)
+            return byteArr.toString(Charsets.UTF_8)
+        } finally {
+            RustBuffer.free(value)
+        }
+    }
+
+    override fun read(buf: ByteBuffer): String {
+        val len = buf.getInt()
+        val byteArr = ByteArray(len)
+        buf.get(byteArr)
+        return byteArr.toString(Charsets.UTF_8)
+    }
+
+    fun toUtf8(value: String): ByteBuffer {
+        // Make sure we don't have invalid UTF-16, check for lone surrogates.
+        return Charsets.UTF_8.newEncoder().run {
+            onMalformedInput(CodingErrorAction.REPORT)
+            encode(CharBuffer.wrap(value))
+        }
+    }
+
+    override fun lower(value: String): RustBuffer.ByValue {
+        val byteBuf = toUtf8(value)
+        // Ideally we'd pass these bytes to `ffi_bytebuffer_from_bytes`, but doing so would require us
+        // to copy them into a JNA `Memory`. So we might as well directly copy them into a `RustBuffer`.
+        val rbuf = RustBuffer.alloc(byteBuf.limit().toULong())
+        rbuf.asByteBuffer()!!.put(byteBuf)
+        return rbuf
+    }
+
+    // We aren't sure exactly how many bytes our string will be once it's UTF-8
+    // encoded.  Allocate 3 bytes per UTF-16 code unit which will always be
+    // enough.
+    override fun allocationSize(value: String): ULong {
+        val sizeForLength = 4UL
+        val sizeForString = value.length.toULong() * 3UL
+        return sizeForLength + sizeForString
+    }
+
+    override fun write(value: String, buf: ByteBuffer) {
+        val byteBuf = toUtf8(value)
+        buf.putInt(byteBuf.limit())
+        buf.put(byteBuf)
+    }
+}
+
+/**
+ * @suppress
+ */
+public object FfiConverterByteArray: FfiConverterRustBuffer<ByteArray> {
+    override fun read(buf: ByteBuffer): ByteArray {
+        val len = buf.getInt()
+        val byteArr = ByteArray(len)
+        buf.get(byteArr)
+        return byteArr
+    }
+    override fun allocationSize(value: ByteArray): ULong {
+        return 4UL + value.size.toULong()
+    }
+    override fun write(value: ByteArray, buf: ByteBuffer) {
+        buf.putInt(value.size)
+        buf.put(value)
+    }
+}
+
+
+// This template implements a class for working with a Rust struct via a handle
+// to the live Rust struct on the other side of the FFI.
+//
+// There's some subtlety here, because we have to be careful not to operate on a Rust
+// struct after it has been dropped, and because we must expose a public API for freeing
+// theq Kotlin wrapper object in lieu of reliable finalizers. The core requirements are:
+//
+//   * Each instance holds an opaque handle to the underlying Rust struct.
+//     Method calls need to read this handle from the object's state and pass it in to
+//     the Rust FFI.
+//
+//   * When an instance is no longer needed, its handle should be passed to a
+//     special destructor function provided by the Rust FFI, which will drop the
+//     underlying Rust struct.
+//
+//   * Given an instance, calling code is expected to call the special
+//     `destroy` method in order to free it after use, either by calling it explicitly
+//     or by using a higher-level helper like the `use` method. Failing to do so risks
+//     leaking the underlying Rust struct.
+//
+//   * We can't assume that calling code will do the right thing, and must be prepared
+//     to handle Kotlin method calls executing concurrently with or even after a call to
+//     `destroy`, and to handle multiple (possibly concurrent!) calls to `destroy`.
+//
+//   * We must never allow Rust code to operate on the underlying Rust struct after
+//     the destructor has been called, and must never call the destructor more than once.
+//     Doing so may trigger memory unsafety.
+//
+//   * To mitigate many of the risks of leaking memory and use-after-free unsafety, a `Cleaner`
+//     is implemented to call the destructor when the Kotlin object becomes unreachable.
+//     This is done in a background thread. This is not a panacea, and client code should be aware that
+//      1. the thread may starve if some there are objects that have poorly performing
+//     `drop` methods or do significant work in their `drop` methods.
+//      2. the thread is shared across the whole library. This can be tuned by using `android_cleaner = true`,
+//         or `android = true` in the [`kotlin` section of the `uniffi.toml` file](https://mozilla.github.io/uniffi-rs/kotlin/configuration.html).
+//
+// If we try to implement this with mutual exclusion on access to the handle, there is the
+// possibility of a race between a method call and a concurrent call to `destroy`:
+//
+//    * Thread A starts a method call, reads the value of the handle, but is interrupted
+//      before it can pass the handle over the FFI to Rust.
+//    * Thread B calls `destroy` and frees the underlying Rust struct.
+//    * Thread A resumes, passing the already-read handle value to Rust and triggering
+//      a use-after-free.
+//
+// One possible solution would be to use a `ReadWriteLock`, with each method call taking
+// a read lock (and thus allowed to run concurrently) and the special `destroy` method
+// taking a write lock (and thus blocking on live method calls). However, we aim not to
+// generate methods with any hidden blocking semantics, and a `destroy` method that might
+// block if called incorrectly seems to meet that bar.
+//
+// So, we achieve our goals by giving each instance an associated `AtomicLong` counter to track
+// the number of in-flight method calls, and an `AtomicBoolean` flag to indicate whether `destroy`
+// has been called. These are updated according to the following rules:
+//
+//    * The initial value of the counter is 1, indicating a live object with no in-flight calls.
+//      The initial value for the flag is false.
+//
+//    * At the start of each method call, we atomically check the counter.
+//      If it is 0 then the underlying Rust struct has already been destroyed and the call is aborted.
+//      If it is nonzero them we atomically increment it by 1 and proceed with the method call.
+//
+//    * At the end of each method call, we atomically decrement and check the counter.
+//      If it has reached zero then we destroy the underlying Rust struct.
+//
+//    * When `destroy` is called, we atomically flip the flag from false to true.
+//      If the flag was already true we silently fail.
+//      Otherwise we atomically decrement and check the counter.
+//      If it has reached zero then we destroy the underlying Rust struct.
+//
+// Astute readers may observe that this all sounds very similar to the way that Rust's `Arc<T>` works,
+// and indeed it is, with the addition of a flag to guard against multiple calls to `destroy`.
+//
+// The overall effect is that the underlying Rust struct is destroyed only when `destroy` has been
+// called *and* all in-flight method calls have completed, avoiding violating any of the expectations
+// of the underlying Rust code.
+//
+// This makes a cleaner a better alternative to _not_ calling `destroy()` as
+// and when the object is finished with, but the abstraction is not perfect: if the Rust object's `drop`
+// method is slow, and/or there are many objects to cleanup, and it's on a low end Android device, then the cleaner
+// thread may be starved, and the app will leak memory.
+//
+// In this case, `destroy`ing manually may be a better solution.
+//
+// The cleaner can live side by side with the manual calling of `destroy`. In the order of responsiveness, uniffi objects
+// with Rust peers are reclaimed:
+//
+// 1. By calling the `destroy` method of the object, which calls `rustObject.free()`. If that doesn't happen:
+// 2. When the object becomes unreachable, AND the Cleaner thread gets to call `rustObject.free()`. If the thread is starved then:
+// 3. The memory is reclaimed when the process terminates.
+//
+// [1] https://stackoverflow.com/questions/24376768/can-java-finalize-an-object-when-it-is-still-in-scope/24380219
+//
+
+
+/**
+ * Receives the results of a streaming batch fetch.
+ */
+public interface BatchResultListener {
+    
+    /**
+     * The fetch of `urls[index]` finished with `result`. Called once per URL,
+     * in completion order.
+     */
+    fun `onResult`(`index`: kotlin.UInt, `result`: BatchImageResult)
+    
+    companion object
+}
+
+/**
+ * Receives the results of a streaming batch fetch.
+ */
+open class BatchResultListenerImpl: Disposable, AutoCloseable, BatchResultListener
+{
+
+    @Suppress("UNUSED_PARAMETER")
+    /**
+     * @suppress
+     */
+    constructor(withHandle: UniffiWithHandle, handle: Long) {
+        this.handle = handle
+        this.cleanable = UniffiLib.CLEANER.register(this, UniffiCleanAction(handle))
+    }
+
+    /**
+     * @suppress
+     *
+     * This constructor can be used to instantiate a fake object. Only used for tests. Any
+     * attempt to actually use an object constructed this way will fail as there is no
+     * connected Rust object.
+     */
+    @Suppress("UNUSED_PARAMETER")
+    constructor(noHandle: NoHandle) {
+        this.handle = 0
+        this.cleanable = null
+    }
+
+    protected val handle: Long
+    protected val cleanable: UniffiCleaner.Cleanable?
+
+    private val wasDestroyed = AtomicBoolean(false)
+    private val callCounter = AtomicLong(1)
+
+    override fun destroy() {
+        // Only allow a single call to this method.
+        // TODO: maybe we should log a warning if called more than once?
+        if (this.wasDestroyed.compareAndSet(false, true)) {
+            // This decrement always matches the initial count of 1 given at creation time.
+            if (this.callCounter.decrementAndGet() == 0L) {
+                cleanable?.clean()
+            }
+        }
+    }
+
+    @Synchronized
+    override fun close() {
+        this.destroy()
+    }
+
+    internal inline fun <R> callWithHandle(block: (handle: Long) -> R): R {
+        // Check and increment the call counter, to keep the object alive.
+        // This needs a compare-and-set retry loop in case of concurrent updates.
+        do {
+            val c = this.callCounter.get()
+            if (c == 0L) {
+                throw IllegalStateException("${this.javaClass.simpleName} object has already been destroyed")
+            }
+            if (c == Long.MAX_VALUE) {
+                throw IllegalStateException("${this.javaClass.simpleName} call counter would overflow")
+            }
+        } while (! this.callCounter.compareAndSet(c, c + 1L))
+        // Now we can safely do the method call without the handle being freed concurrently.
+        try {
+            return block(this.uniffiCloneHandle())
+        } finally {
+            // This decrement always matches the increment we performed above.
+            if (this.callCounter.decrementAndGet() == 0L) {
+                cleanable?.clean()
+            }
+        }
+    }
+
+    // Use a static inner class instead of a closure so as not to accidentally
+    // capture `this` as part of the cleanable's action.
+    private class UniffiCleanAction(private val handle: Long) : Runnable {
+        override fun run() {
+            if (handle == 0.toLong()) {
+                // Fake object created with `NoHandle`, don't try to free.
+                return;
+            }
+            uniffiRustCall { status ->
+                UniffiLib.uniffi_letterbox_proxy_fn_free_batchresultlistener(handle, status)
+            }
+        }
+    }
+
+    /**
+     * @suppress
+     */
+    fun uniffiCloneHandle(): Long {
+        if (handle == 0.toLong()) {
+            throw InternalException("uniffiCloneHandle() called on NoHandle object");
+        }
+        return uniffiRustCall() { status ->
+            UniffiLib.uniffi_letterbox_proxy_fn_clone_batchresultlistener(handle, status)
+        }
+    }
+
+    
+    /**
+     * The fetch of `urls[index]` finished with `result`. Called once per URL,
+     * in completion order.
+     */override fun `onResult`(`index`: kotlin.UInt, `result`: BatchImageResult)
+        = 
+    callWithHandle {
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_batchresultlistener_on_result(
+        it,
+        FfiConverterUInt.lower(`index`),FfiConverterTypeBatchImageResult.lower(`result`),_status)
+}
+    }
+    
+    
+
+    
+
+    
+
+
+    
+    
+    /**
+     * @suppress
+     */
+    companion object
+    
+}
+
+
+
+// Put the implementation in an object so we don't pollute the top-level namespace
+internal object uniffiCallbackInterfaceBatchResultListener {
+    internal object `onResult`: UniffiCallbackInterfaceBatchResultListenerMethod0 {
+        override fun callback(`uniffiHandle`: Long,`index`: Int,`result`: RustBuffer.ByValue,`uniffiOutReturn`: Pointer,uniffiCallStatus: UniffiRustCallStatus,) {
+            val uniffiObj = FfiConverterTypeBatchResultListener.handleMap.get(uniffiHandle)
+            val makeCall = { ->
+                uniffiObj.`onResult`(
+                    FfiConverterUInt.lift(`index`),
+                    FfiConverterTypeBatchImageResult.lift(`result`),
+                )
+            }
+            val writeReturn = { _: Unit -> Unit }
+            uniffiTraitInterfaceCall(uniffiCallStatus, makeCall, writeReturn)
+        }
+    }
+
+    internal object uniffiFree: UniffiCallbackInterfaceFree {
+        override fun callback(handle: Long) {
+            FfiConverterTypeBatchResultListener.handleMap.remove(handle)
+        }
+    }
+
+    internal object uniffiClone: UniffiCallbackInterfaceClone {
+        override fun callback(handle: Long): Long {
+            return FfiConverterTypeBatchResultListener.handleMap.clone(handle)
+        }
+    }
+
+    internal var vtable = UniffiVTableCallbackInterfaceBatchResultListener.UniffiByValue(
+        uniffiFree,
+        uniffiClone,
+        `onResult`,
+    )
+
+    // Registers the foreign callback with the Rust side.
+    // This method is generated for each callback interface.
+    internal fun register(lib: UniffiLib) {
+        lib.uniffi_letterbox_proxy_fn_init_callback_vtable_batchresultlistener(vtable)
+    }
+}
+
+/**
+ * @suppress
+ */
+public object FfiConverterTypeBatchResultListener: FfiConverter<BatchResultListener, Long> {
+    internal val handleMap = UniffiHandleMap<BatchResultListener>()
+
+    override fun lower(value: BatchResultListener): Long {
+        if (value is BatchResultListenerImpl) {
+             // Rust-implemented object.  Clone the handle and return it
+            return value.uniffiCloneHandle()
+         } else {
+            // Kotlin object, generate a new vtable handle and return that.
+            return handleMap.insert(value)
+         }
+    }
+
+    override fun lift(value: Long): BatchResultListener {
+        if ((value and 1.toLong()) == 0.toLong()) {
+            // Rust-generated handle, construct a new class that uses the handle to implement the
+            // interface
+            return BatchResultListenerImpl(UniffiWithHandle, value)
+        } else {
+            // Kotlin-generated handle, get the object from the handle map
+            return handleMap.remove(value)
+        }
+    }
+
+    override fun read(buf: ByteBuffer): BatchResultListener {
+        return lift(buf.getLong())
+    }
+
+    override fun allocationSize(value: BatchResultListener) = 8UL
+
+    override fun write(value: BatchResultListener, buf: ByteBuffer) {
+        buf.putLong(lower(value))
+    }
+}
+
+
+// This template implements a class for working with a Rust struct via a handle
+// to the live Rust struct on the other side of the FFI.
+//
+// There's some subtlety here, because we have to be careful not to operate on a Rust
+// struct after it has been dropped, and because we must expose a public API for freeing
+// theq Kotlin wrapper object in lieu of reliable finalizers. The core requirements are:
+//
+//   * Each instance holds an opaque handle to the underlying Rust struct.
+//     Method calls need to read this handle from the object's state and pass it in to
+//     the Rust FFI.
+//
+//   * When an instance is no longer needed, its handle should be passed to a
+//     special destructor function provided by the Rust FFI, which will drop the
+//     underlying Rust struct.
+//
+//   * Given an instance, calling code is expected to call the special
+//     `destroy` method in order to free it after use, either by calling it explicitly
+//     or by using a higher-level helper like the `use` method. Failing to do so risks
+//     leaking the underlying Rust struct.
+//
+//   * We can't assume that calling code will do the right thing, and must be prepared
+//     to handle Kotlin method calls executing concurrently with or even after a call to
+//     `destroy`, and to handle multiple (possibly concurrent!) calls to `destroy`.
+//
+//   * We must never allow Rust code to operate on the underlying Rust struct after
+//     the destructor has been called, and must never call the destructor more than once.
+//     Doing so may trigger memory unsafety.
+//
+//   * To mitigate many of the risks of leaking memory and use-after-free unsafety, a `Cleaner`
+//     is implemented to call the destructor when the Kotlin object becomes unreachable.
+//     This is done in a background thread. This is not a panacea, and client code should be aware that
+//      1. the thread may starve if some there are objects that have poorly performing
+//     `drop` methods or do significant work in their `drop` methods.
+//      2. the thread is shared across the whole library. This can be tuned by using `android_cleaner = true`,
+//         or `android = true` in the [`kotlin` section of the `uniffi.toml` file](https://mozilla.github.io/uniffi-rs/kotlin/configuration.html).
+//
+// If we try to implement this with mutual exclusion on access to the handle, there is the
+// possibility of a race between a method call and a concurrent call to `destroy`:
+//
+//    * Thread A starts a method call, reads the value of the handle, but is interrupted
+//      before it can pass the handle over the FFI to Rust.
+//    * Thread B calls `destroy` and frees the underlying Rust struct.
+//    * Thread A resumes, passing the already-read handle value to Rust and triggering
+//      a use-after-free.
+//
+// One possible solution would be to use a `ReadWriteLock`, with each method call taking
+// a read lock (and thus allowed to run concurrently) and the special `destroy` method
+// taking a write lock (and thus blocking on live method calls). However, we aim not to
+// generate methods with any hidden blocking semantics, and a `destroy` method that might
+// block if called incorrectly seems to meet that bar.
+//
+// So, we achieve our goals by giving each instance an associated `AtomicLong` counter to track
+// the number of in-flight method calls, and an `AtomicBoolean` flag to indicate whether `destroy`
+// has been called. These are updated according to the following rules:
+//
+//    * The initial value of the counter is 1, indicating a live object with no in-flight calls.
+//      The initial value for the flag is false.
+//
+//    * At the start of each method call, we atomically check the counter.
+//      If it is 0 then the underlying Rust struct has already been destroyed and the call is aborted.
+//      If it is nonzero them we atomically increment it by 1 and proceed with the method call.
+//
+//    * At the end of each method call, we atomically decrement and check the counter.
+//      If it has reached zero then we destroy the underlying Rust struct.
+//
+//    * When `destroy` is called, we atomically flip the flag from false to true.
+//      If the flag was already true we silently fail.
+//      Otherwise we atomically decrement and check the counter.
+//      If it has reached zero then we destroy the underlying Rust struct.
+//
+// Astute readers may observe that this all sounds very similar to the way that Rust's `Arc<T>` works,
+// and indeed it is, with the addition of a flag to guard against multiple calls to `destroy`.
+//
+// The overall effect is that the underlying Rust struct is destroyed only when `destroy` has been
+// called *and* all in-flight method calls have completed, avoiding violating any of the expectations
+// of the underlying Rust code.
+//
+// This makes a cleaner a better alternative to _not_ calling `destroy()` as
+// and when the object is finished with, but the abstraction is not perfect: if the Rust object's `drop`
+// method is slow, and/or there are many objects to cleanup, and it's on a low end Android device, then the cleaner
+// thread may be starved, and the app will leak memory.
+//
+// In this case, `destroy`ing manually may be a better solution.
+//
+// The cleaner can live side by side with the manual calling of `destroy`. In the order of responsiveness, uniffi objects
+// with Rust peers are reclaimed:
+//
+// 1. By calling the `destroy` method of the object, which calls `rustObject.free()`. If that doesn't happen:
+// 2. When the object becomes unreachable, AND the Cleaner thread gets to call `rustObject.free()`. If the thread is starved then:
+// 3. The memory is reclaimed when the process terminates.
+//
+// [1] https://stackoverflow.com/questions/24376768/can-java-finalize-an-object-when-it-is-still-in-scope/24380219
+//
+
+
+/**
+ * A key-value store for cached image blobs, implemented by the host.
+ *
+ * Keys are the proxy's cache keys: the image URL, or the URL followed by a
+ * newline and a variant tag for negotiated or transformed copies.
+ */
+public interface CacheStorage {
+    
+    /**
+     * The blob stored under `key`, if any.
+     */
+    fun `get`(`key`: kotlin.String): kotlin.ByteArray?
+    
+    /**
+     * Store `value` under `key`, replacing any previous blob.
+     */
+    fun `put`(`key`: kotlin.String, `value`: kotlin.ByteArray)
+    
+    /**
+     * Forget the blob under `key`; unknown keys are ignored.
+     */
+    fun `delete`(`key`: kotlin.String)
+    
+    /**
+     * Every key currently stored.
+     */
+    fun `list`(): List<kotlin.String>
+    
+    companion object
+}
+
+/**
+ * A key-value store for cached image blobs, implemented by the host.
+ *
+ * Keys are the proxy's cache keys: the image URL, or the URL followed by a
+ * newline and a variant tag for negotiated or transformed copies.
+ */
+open class CacheStorageImpl: Disposable, AutoCloseable, CacheStorage
+{
+
+    @Suppress("UNUSED_PARAMETER")
+    /**
+     * @suppress
+     */
+    constructor(withHandle: UniffiWithHandle, handle: Long) {
+        this.handle = handle
+        this.cleanable = UniffiLib.CLEANER.register(this, UniffiCleanAction(handle))
+    }
+
+    /**
+     * @suppress
+     *
+     * This constructor can be used to instantiate a fake object. Only used for tests. Any
+     * attempt to actually use an object constructed this way will fail as there is no
+     * connected Rust object.
+     */
+    @Suppress("UNUSED_PARAMETER")
+    constructor(noHandle: NoHandle) {
+        this.handle = 0
+        this.cleanable = null
+    }
+
+    protected val handle: Long
+    protected val cleanable: UniffiCleaner.Cleanable?
+
+    private val wasDestroyed = AtomicBoolean(false)
+    private val callCounter = AtomicLong(1)
+
+    override fun destroy() {
+        // Only allow a single call to this method.
+        // TODO: maybe we should log a warning if called more than once?
+        if (this.wasDestroyed.compareAndSet(false, true)) {
+            // This decrement always matches the initial count of 1 given at creation time.
+            if (this.callCounter.decrementAndGet() == 0L) {
+                cleanable?.clean()
+            }
+        }
+    }
+
+    @Synchronized
+    override fun close() {
+        this.destroy()
+    }
+
+    internal inline fun <R> callWithHandle(block: (handle: Long) -> R): R {
+        // Check and increment the call counter, to keep the object alive.
+        // This needs a compare-and-set retry loop in case of concurrent updates.
+        do {
+            val c = this.callCounter.get()
+            if (c == 0L) {
+                throw IllegalStateException("${this.javaClass.simpleName} object has already been destroyed")
+            }
+            if (c == Long.MAX_VALUE) {
+                throw IllegalStateException("${this.javaClass.simpleName} call counter would overflow")
+            }
+        } while (! this.callCounter.compareAndSet(c, c + 1L))
+        // Now we can safely do the method call without the handle being freed concurrently.
+        try {
+            return block(this.uniffiCloneHandle())
+        } finally {
+            // This decrement always matches the increment we performed above.
+            if (this.callCounter.decrementAndGet() == 0L) {
+                cleanable?.clean()
+            }
+        }
+    }
+
+    // Use a static inner class instead of a closure so as not to accidentally
+    // capture `this` as part of the cleanable's action.
+    private class UniffiCleanAction(private val handle: Long) : Runnable {
+        override fun run() {
+            if (handle == 0.toLong()) {
+                // Fake object created with `NoHandle`, don't try to free.
+                return;
+            }
+            uniffiRustCall { status ->
+                UniffiLib.uniffi_letterbox_proxy_fn_free_cachestorage(handle, status)
+            }
+        }
+    }
+
+    /**
+     * @suppress
+     */
+    fun uniffiCloneHandle(): Long {
+        if (handle == 0.toLong()) {
+            throw InternalException("uniffiCloneHandle() called on NoHandle object");
+        }
+        return uniffiRustCall() { status ->
+            UniffiLib.uniffi_letterbox_proxy_fn_clone_cachestorage(handle, status)
+        }
+    }
+
+    
+    /**
+     * The blob stored under `key`, if any.
+     */override fun `get`(`key`: kotlin.String): kotlin.ByteArray? {
+            return FfiConverterOptionalByteArray.lift(
+    callWithHandle {
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_cachestorage_get(
+        it,
+        FfiConverterString.lower(`key`),_status)
+}
+    }
+    )
+    }
+    
+
+    
+    /**
+     * Store `value` under `key`, replacing any previous blob.
+     */override fun `put`(`key`: kotlin.String, `value`: kotlin.ByteArray)
+        = 
+    callWithHandle {
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_cachestorage_put(
+        it,
+        FfiConverterString.lower(`key`),FfiConverterByteArray.lower(`value`),_status)
+}
+    }
+    
+    
+
+    
+    /**
+     * Forget the blob under `key`; unknown keys are ignored.
+     */override fun `delete`(`key`: kotlin.String)
+        = 
+    callWithHandle {
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_cachestorage_delete(
+        it,
+        FfiConverterString.lower(`key`),_status)
+}
+    }
+    
+    
+
+    
+    /**
+     * Every key currently stored.
+     */override fun `list`(): List<kotlin.String> {
+            return FfiConverterSequenceString.lift(
+    callWithHandle {
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_cachestorage_list(
+        it,
+        _status)
+}
+    }
+    )
+    }
+    
+
+    
+
+    
+
+
+    
+    
+    /**
+     * @suppress
+     */
+    companion object
+    
+}
+
+
+
+// Put the implementation in an object so we don't pollute the top-level namespace
+internal object uniffiCallbackInterfaceCacheStorage {
+    internal object `get`: UniffiCallbackInterfaceCacheStorageMethod0 {
+        override fun callback(`uniffiHandle`: Long,`key`: RustBuffer.ByValue,`uniffiOutReturn`: RustBuffer,uniffiCallStatus: UniffiRustCallStatus,) {
+            val uniffiObj = FfiConverterTypeCacheStorage.handleMap.get(uniffiHandle)
+            val makeCall = { ->
+                uniffiObj.`get`(
+                    FfiConverterString.lift(`key`),
+                )
+            }
+            val writeReturn = { value: kotlin.ByteArray? -> uniffiOutReturn.setValue(FfiConverterOptionalByteArray.lower(value)) }
+            uniffiTraitInterfaceCall(uniffiCallStatus, makeCall, writeReturn)
+        }
+    }
+    internal object `put`: UniffiCallbackInterfaceCacheStorageMethod1 {
+        override fun callback(`uniffiHandle`: Long,`key`: RustBuffer.ByValue,`value`: RustBuffer.ByValue,`uniffiOutReturn`: Pointer,uniffiCallStatus: UniffiRustCallStatus,) {
+            val uniffiObj = FfiConverterTypeCacheStorage.handleMap.get(uniffiHandle)
+            val makeCall = { ->
+                uniffiObj.`put`(
+                    FfiConverterString.lift(`key`),
+                    FfiConverterByteArray.lift(`value`),
+                )
+            }
+            val writeReturn = { _: Unit -> Unit }
+            uniffiTraitInterfaceCall(uniffiCallStatus, makeCall, writeReturn)
+        }
+    }
+    internal object `delete`: UniffiCallbackInterfaceCacheStorageMethod2 {
+        override fun callback(`uniffiHandle`: Long,`key`: RustBuffer.ByValue,`uniffiOutReturn`: Pointer,uniffiCallStatus: UniffiRustCallStatus,) {
+            val uniffiObj = FfiConverterTypeCacheStorage.handleMap.get(uniffiHandle)
+            val makeCall = { ->
+                uniffiObj.`delete`(
+                    FfiConverterString.lift(`key`),
+                )
+            }
+            val writeReturn = { _: Unit -> Unit }
+            uniffiTraitInterfaceCall(uniffiCallStatus, makeCall, writeReturn)
+        }
+    }
+    internal object `list`: UniffiCallbackInterfaceCacheStorageMethod3 {
+        override fun callback(`uniffiHandle`: Long,`uniffiOutReturn`: RustBuffer,uniffiCallStatus: UniffiRustCallStatus,) {
+            val uniffiObj = FfiConverterTypeCacheStorage.handleMap.get(uniffiHandle)
+            val makeCall = { ->
+                uniffiObj.`list`(
+                )
+            }
+            val writeReturn = { value: List<kotlin.String> -> uniffiOutReturn.setValue(FfiConverterSequenceString.lower(value)) }
+            uniffiTraitInterfaceCall(uniffiCallStatus, makeCall, writeReturn)
+        }
+    }
+
+    internal object uniffiFree: UniffiCallbackInterfaceFree {
+        override fun callback(handle: Long) {
+            FfiConverterTypeCacheStorage.handleMap.remove(handle)
+        }
+    }
+
+    internal object uniffiClone: UniffiCallbackInterfaceClone {
+        override fun callback(handle: Long): Long {
+            return FfiConverterTypeCacheStorage.handleMap.clone(handle)
+        }
+    }
+
+    internal var vtable = UniffiVTableCallbackInterfaceCacheStorage.UniffiByValue(
+        uniffiFree,
+        uniffiClone,
+        `get`,
+        `put`,
+        `delete`,
+        `list`,
+    )
+
+    // Registers the foreign callback with the Rust side.
+    // This method is generated for each callback interface.
+    internal fun register(lib: UniffiLib) {
+        lib.uniffi_letterbox_proxy_fn_init_callback_vtable_cachestorage(vtable)
+    }
+}
+
+/**
+ * @suppress
+ */
+public object FfiConverterTypeCacheStorage: FfiConverter<CacheStorage, Long> {
+    internal val handleMap = UniffiHandleMap<CacheStorage>()
+
+    override fun lower(value: CacheStorage): Long {
+        if (value is CacheStorageImpl) {
+             // Rust-implemented object.  Clone the handle and return it
+            return value.uniffiCloneHandle()
+         } else {
+            // Kotlin object, generate a new vtable handle and return that.
+            return handleMap.insert(value)
+         }
+    }
+
+    override fun lift(value: Long): CacheStorage {
+        if ((value and 1.toLong()) == 0.toLong()) {
+            // Rust-generated handle, construct a new class that uses the handle to implement the
+            // interface
+            return CacheStorageImpl(UniffiWithHandle, value)
+        } else {
+            // Kotlin-generated handle, get the object from the handle map
+            return handleMap.remove(value)
+        }
+    }
+
+    override fun read(buf: ByteBuffer): CacheStorage {
+        return lift(buf.getLong())
+    }
+
+    override fun allocationSize(value: CacheStorage) = 8UL
+
+    override fun write(value: CacheStorage, buf: ByteBuffer) {
+        buf.putLong(lower(value))
+    }
+}
+
+
+// This template implements a class for working with a Rust struct via a handle
+// to the live Rust struct on the other side of the FFI.
+//
+// There's some subtlety here, because we have to be careful not to operate on a Rust
+// struct after it has been dropped, and because we must expose a public API for freeing
+// theq Kotlin wrapper object in lieu of reliable finalizers. The core requirements are:
+//
+//   * Each instance holds an opaque handle to the underlying Rust struct.
+//     Method calls need to read this handle from the object's state and pass it in to
+//     the Rust FFI.
+//
+//   * When an instance is no longer needed, its handle should be passed to a
+//     special destructor function provided by the Rust FFI, which will drop the
+//     underlying Rust struct.
+//
+//   * Given an instance, calling code is expected to call the special
+//     `destroy` method in order to free it after use, either by calling it explicitly
+//     or by using a higher-level helper like the `use` method. Failing to do so risks
+//     leaking the underlying Rust struct.
+//
+//   * We can't assume that calling code will do the right thing, and must be prepared
+//     to handle Kotlin method calls executing concurrently with or even after a call to
+//     `destroy`, and to handle multiple (possibly concurrent!) calls to `destroy`.
+//
+//   * We must never allow Rust code to operate on the underlying Rust struct after
+//     the destructor has been called, and must never call the destructor more than once.
+//     Doing so may trigger memory unsafety.
+//
+//   * To mitigate many of the risks of leaking memory and use-after-free unsafety, a `Cleaner`
+//     is implemented to call the destructor when the Kotlin object becomes unreachable.
+//     This is done in a background thread. This is not a panacea, and client code should be aware that
+//      1. the thread may starve if some there are objects that have poorly performing
+//     `drop` methods or do significant work in their `drop` methods.
+//      2. the thread is shared across the whole library. This can be tuned by using `android_cleaner = true`,
+//         or `android = true` in the [`kotlin` section of the `uniffi.toml` file](https://mozilla.github.io/uniffi-rs/kotlin/configuration.html).
+//
+// If we try to implement this with mutual exclusion on access to the handle, there is the
+// possibility of a race between a method call and a concurrent call to `destroy`:
+//
+//    * Thread A starts a method call, reads the value of the handle, but is interrupted
+//      before it can pass the handle over the FFI to Rust.
+//    * Thread B calls `destroy` and frees the underlying Rust struct.
+//    * Thread A resumes, passing the already-read handle value to Rust and triggering
+//      a use-after-free.
+//
+// One possible solution would be to use a `ReadWriteLock`, with each method call taking
+// a read lock (and thus allowed to run concurrently) and the special `destroy` method
+// taking a write lock (and thus blocking on live method calls). However, we aim not to
+// generate methods with any hidden blocking semantics, and a `destroy` method that might
+// block if called incorrectly seems to meet that bar.
+//
+// So, we achieve our goals by giving each instance an associated `AtomicLong` counter to track
+// the number of in-flight method calls, and an `AtomicBoolean` flag to indicate whether `destroy`
+// has been called. These are updated according to the following rules:
+//
+//    * The initial value of the counter is 1, indicating a live object with no in-flight calls.
+//      The initial value for the flag is false.
+//
+//    * At the start of each method call, we atomically check the counter.
+//      If it is 0 then the underlying Rust struct has already been destroyed and the call is aborted.
+//      If it is nonzero them we atomically increment it by 1 and proceed with the method call.
+//
+//    * At the end of each method call, we atomically decrement and check the counter.
+//      If it has reached zero then we destroy the underlying Rust struct.
+//
+//    * When `destroy` is called, we atomically flip the flag from false to true.
+//      If the flag was already true we silently fail.
+//      Otherwise we atomically decrement and check the counter.
+//      If it has reached zero then we destroy the underlying Rust struct.
+//
+// Astute readers may observe that this all sounds very similar to the way that Rust's `Arc<T>` works,
+// and indeed it is, with the addition of a flag to guard against multiple calls to `destroy`.
+//
+// The overall effect is that the underlying Rust struct is destroyed only when `destroy` has been
+// called *and* all in-flight method calls have completed, avoiding violating any of the expectations
+// of the underlying Rust code.
+//
+// This makes a cleaner a better alternative to _not_ calling `destroy()` as
+// and when the object is finished with, but the abstraction is not perfect: if the Rust object's `drop`
+// method is slow, and/or there are many objects to cleanup, and it's on a low end Android device, then the cleaner
+// thread may be starved, and the app will leak memory.
+//
+// In this case, `destroy`ing manually may be a better solution.
+//
+// The cleaner can live side by side with the manual calling of `destroy`. In the order of responsiveness, uniffi objects
+// with Rust peers are reclaimed:
+//
+// 1. By calling the `destroy` method of the object, which calls `rustObject.free()`. If that doesn't happen:
+// 2. When the object becomes unreachable, AND the Cleaner thread gets to call `rustObject.free()`. If the thread is starved then:
+// 3. The memory is reclaimed when the process terminates.
+//
+// [1] https://stackoverflow.com/questions/24376768/can-java-finalize-an-object-when-it-is-still-in-scope/24380219
+//
+
+
+/**
+ * A cancellable batch of image fetches.
+ *
+ * Nothing is fetched until [`Self::results`] or [`Self::stream`] is awaited.
+ * A batch runs once: whichever of the two is called first takes the URLs, and
+ * later calls see an empty batch.
+ */
+public interface ImageBatchInterface {
+    
+    /**
+     * Abandon the rest of the batch. Every URL not fetched yet is reported
+     * as failed with [`ProxyError::Cancelled`].
+     */
+    fun `cancel`()
+    
+    /**
+     * Whether [`Self::cancel`] was called, or the batch's caller stopped
+     * waiting for it.
+     */
+    fun `isCancelled`(): kotlin.Boolean
+    
+    /**
+     * Fetch the batch, returning the results in request order once all have
+     * finished.
+     */
+    suspend fun `results`(): List<BatchImageResult>
+    
+    /**
+     * Fetch the batch, passing each result to `listener` as soon as it is
+     * ready. Returns once every URL has been reported.
+     */
+    suspend fun `stream`(`listener`: BatchResultListener)
+    
+    companion object
+}
+
+/**
+ * A cancellable batch of image fetches.
+ *
+ * Nothing is fetched until [`Self::results`] or [`Self::stream`] is awaited.
+ * A batch runs once: whichever of the two is called first takes the URLs, and
+ * later calls see an empty batch.
+ */
+open class ImageBatch: Disposable, AutoCloseable, ImageBatchInterface
+{
+
+    @Suppress("UNUSED_PARAMETER")
+    /**
+     * @suppress
+     */
+    constructor(withHandle: UniffiWithHandle, handle: Long) {
+        this.handle = handle
+        this.cleanable = UniffiLib.CLEANER.register(this, UniffiCleanAction(handle))
+    }
+
+    /**
+     * @suppress
+     *
+     * This constructor can be used to instantiate a fake object. Only used for tests. Any
+     * attempt to actually use an object constructed this way will fail as there is no
+     * connected Rust object.
+     */
+    @Suppress("UNUSED_PARAMETER")
+    constructor(noHandle: NoHandle) {
+        this.handle = 0
+        this.cleanable = null
+    }
+
+    protected val handle: Long
+    protected val cleanable: UniffiCleaner.Cleanable?
+
+    private val wasDestroyed = AtomicBoolean(false)
+    private val callCounter = AtomicLong(1)
+
+    override fun destroy() {
+        // Only allow a single call to this method.
+        // TODO: maybe we should log a warning if called more than once?
+        if (this.wasDestroyed.compareAndSet(false, true)) {
+            // This decrement always matches the initial count of 1 given at creation time.
+            if (this.callCounter.decrementAndGet() == 0L) {
+                cleanable?.clean()
+            }
+        }
+    }
+
+    @Synchronized
+    override fun close() {
+        this.destroy()
+    }
+
+    internal inline fun <R> callWithHandle(block: (handle: Long) -> R): R {
+        // Check and increment the call counter, to keep the object alive.
+        // This needs a compare-and-set retry loop in case of concurrent updates.
+        do {
+            val c = this.callCounter.get()
+            if (c == 0L) {
+                throw IllegalStateException("${this.javaClass.simpleName} object has already been destroyed")
+            }
+            if (c == Long.MAX_VALUE) {
+                throw IllegalStateException("${this.javaClass.simpleName} call counter would overflow")
+            }
+        } while (! this.callCounter.compareAndSet(c, c + 1L))
+        // Now we can safely do the method call without the handle being freed concurrently.
+        try {
+            return block(this.uniffiCloneHandle())
+        } finally {
+            // This decrement always matches the increment we performed above.
+            if (this.callCounter.decrementAndGet() == 0L) {
+                cleanable?.clean()
+            }
+        }
+    }
+
+    // Use a static inner class instead of a closure so as not to accidentally
+    // capture `this` as part of the cleanable's action.
+    private class UniffiCleanAction(private val handle: Long) : Runnable {
+        override fun run() {
+            if (handle == 0.toLong()) {
+                // Fake object created with `NoHandle`, don't try to free.
+                return;
+            }
+            uniffiRustCall { status ->
+                UniffiLib.uniffi_letterbox_proxy_fn_free_imagebatch(handle, status)
+            }
+        }
+    }
+
+    /**
+     * @suppress
+     */
+    fun uniffiCloneHandle(): Long {
+        if (handle == 0.toLong()) {
+            throw InternalException("uniffiCloneHandle() called on NoHandle object");
+        }
+        return uniffiRustCall() { status ->
+            UniffiLib.uniffi_letterbox_proxy_fn_clone_imagebatch(handle, status)
+        }
+    }
+
+    
+    /**
+     * Abandon the rest of the batch. Every URL not fetched yet is reported
+     * as failed with [`ProxyError::Cancelled`].
+     */override fun `cancel`()
+        = 
+    callWithHandle {
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_imagebatch_cancel(
+        it,
+        _status)
+}
+    }
+    
+    
+
+    
+    /**
+     * Whether [`Self::cancel`] was called, or the batch's caller stopped
+     * waiting for it.
+     */override fun `isCancelled`(): kotlin.Boolean {
+            return FfiConverterBoolean.lift(
+    callWithHandle {
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_imagebatch_is_cancelled(
+        it,
+        _status)
+}
+    }
+    )
+    }
+    
+
+    
+    /**
+     * Fetch the batch, returning the results in request order once all have
+     * finished.
+     */
+    @Throws(ProxyException::class)
+    @Suppress("ASSIGNED_BUT_NEVER_ACCESSED_VARIABLE")
+    override suspend fun `results`() : List<BatchImageResult> {
+        return uniffiRustCallAsync(
+        callWithHandle { uniffiHandle ->
+            UniffiLib.uniffi_letterbox_proxy_fn_method_imagebatch_results(
+                uniffiHandle,
+                
+            )
+        },
+        { future, callback, continuation -> UniffiLib.ffi_letterbox_proxy_rust_future_poll_rust_buffer(future, callback, continuation) },
+        { future, continuation -> UniffiLib.ffi_letterbox_proxy_rust_future_complete_rust_buffer(future, continuation) },
+        { future -> UniffiLib.ffi_letterbox_proxy_rust_future_free_rust_buffer(future) },
+        // lift function
+        { FfiConverterSequenceTypeBatchImageResult.lift(it) },
+        // Error FFI converter
+        ProxyException.ErrorHandler,
+    )
+    }
+
+    
+    /**
+     * Fetch the batch, passing each result to `listener` as soon as it is
+     * ready. Returns once every URL has been reported.
+     */
+    @Throws(ProxyException::class)
+    @Suppress("ASSIGNED_BUT_NEVER_ACCESSED_VARIABLE")
+    override suspend fun `stream`(`listener`: BatchResultListener) {
+        return uniffiRustCallAsync(
+        callWithHandle { uniffiHandle ->
+            UniffiLib.uniffi_letterbox_proxy_fn_method_imagebatch_stream(
+                uniffiHandle,
+                FfiConverterTypeBatchResultListener.lower(`listener`),
+            )
+        },
+        { future, callback, continuation -> UniffiLib.ffi_letterbox_proxy_rust_future_poll_void(future, callback, continuation) },
+        { future, continuation -> UniffiLib.ffi_letterbox_proxy_rust_future_complete_void(future, continuation) },
+        { future -> UniffiLib.ffi_letterbox_proxy_rust_future_free_void(future) },
+        // lift function
+        { Unit },
+        
+        // Error FFI converter
+        ProxyException.ErrorHandler,
+    )
+    }
+
+    
+
+    
+
+
+    
+    
+    /**
+     * @suppress
+     */
+    companion object
+    
+}
+
+
+/**
+ * @suppress
+ */
+public object FfiConverterTypeImageBatch: FfiConverter<ImageBatch, Long> {
+    override fun lower(value: ImageBatch): Long {
+        return value.uniffiCloneHandle()
+    }
+
+    override fun lift(value: Long): ImageBatch {
+        return ImageBatch(UniffiWithHandle, value)
+    }
+
+    override fun read(buf: ByteBuffer): ImageBatch {
+        return lift(buf.getLong())
+    }
+
+    override fun allocationSize(value: ImageBatch) = 8UL
+
+    override fun write(value: ImageBatch, buf: ByteBuffer) {
+        buf.putLong(lower(value))
+    }
+}
+
+
+// This template implements a class for working with a Rust struct via a handle
+// to the live Rust struct on the other side of the FFI.
+//
+// There's some subtlety here, because we have to be careful not to operate on a Rust
+// struct after it has been dropped, and because we must expose a public API for freeing
+// theq Kotlin wrapper object in lieu of reliable finalizers. The core requirements are:
+//
+//   * Each instance holds an opaque handle to the underlying Rust struct.
+//     Method calls need to read this handle from the object's state and pass it in to
+//     the Rust FFI.
+//
+//   * When an instance is no longer needed, its handle should be passed to a
+//     special destructor function provided by the Rust FFI, which will drop the
+//     underlying Rust struct.
+//
+//   * Given an instance, calling code is expected to call the special
+//     `destroy` method in order to free it after use, either by calling it explicitly
+//     or by using a higher-level helper like the `use` method. Failing to do so risks
+//     leaking the underlying Rust struct.
+//
+//   * We can't assume that calling code will do the right thing, and must be prepared
+//     to handle Kotlin method calls executing concurrently with or even after a call to
+//     `destroy`, and to handle multiple (possibly concurrent!) calls to `destroy`.
+//
+//   * We must never allow Rust code to operate on the underlying Rust struct after
+//     the destructor has been called, and must never call the destructor more than once.
+//     Doing so may trigger memory unsafety.
+//
+//   * To mitigate many of the risks of leaking memory and use-after-free unsafety, a `Cleaner`
+//     is implemented to call the destructor when the Kotlin object becomes unreachable.
+//     This is done in a background thread. This is not a panacea, and client code should be aware that
+//      1. the thread may starve if some there are objects that have poorly performing
+//     `drop` methods or do significant work in their `drop` methods.
+//      2. the thread is shared across the whole library. This can be tuned by using `android_cleaner = true`,
+//         or `android = true` in the [`kotlin` section of the `uniffi.toml` file](https://mozilla.github.io/uniffi-rs/kotlin/configuration.html).
+//
+// If we try to implement this with mutual exclusion on access to the handle, there is the
+// possibility of a race between a method call and a concurrent call to `destroy`:
+//
+//    * Thread A starts a method call, reads the value of the handle, but is interrupted
+//      before it can pass the handle over the FFI to Rust.
+//    * Thread B calls `destroy` and frees the underlying Rust struct.
+//    * Thread A resumes, passing the already-read handle value to Rust and triggering
+//      a use-after-free.
+//
+// One possible solution would be to use a `ReadWriteLock`, with each method call taking
+// a read lock (and thus allowed to run concurrently) and the special `destroy` method
+// taking a write lock (and thus blocking on live method calls). However, we aim not to
+// generate methods with any hidden blocking semantics, and a `destroy` method that might
+// block if called incorrectly seems to meet that bar.
+//
+// So, we achieve our goals by giving each instance an associated `AtomicLong` counter to track
+// the number of in-flight method calls, and an `AtomicBoolean` flag to indicate whether `destroy`
+// has been called. These are updated according to the following rules:
+//
+//    * The initial value of the counter is 1, indicating a live object with no in-flight calls.
+//      The initial value for the flag is false.
+//
+//    * At the start of each method call, we atomically check the counter.
+//      If it is 0 then the underlying Rust struct has already been destroyed and the call is aborted.
+//      If it is nonzero them we atomically increment it by 1 and proceed with the method call.
+//
+//    * At the end of each method call, we atomically decrement and check the counter.
+//      If it has reached zero then we destroy the underlying Rust struct.
+//
+//    * When `destroy` is called, we atomically flip the flag from false to true.
+//      If the flag was already true we silently fail.
+//      Otherwise we atomically decrement and check the counter.
+//      If it has reached zero then we destroy the underlying Rust struct.
+//
+// Astute readers may observe that this all sounds very similar to the way that Rust's `Arc<T>` works,
+// and indeed it is, with the addition of a flag to guard against multiple calls to `destroy`.
+//
+// The overall effect is that the underlying Rust struct is destroyed only when `destroy` has been
+// called *and* all in-flight method calls have completed, avoiding violating any of the expectations
+// of the underlying Rust code.
+//
+// This makes a cleaner a better alternative to _not_ calling `destroy()` as
+// and when the object is finished with, but the abstraction is not perfect: if the Rust object's `drop`
+// method is slow, and/or there are many objects to cleanup, and it's on a low end Android device, then the cleaner
+// thread may be starved, and the app will leak memory.
+//
+// In this case, `destroy`ing manually may be a better solution.
+//
+// The cleaner can live side by side with the manual calling of `destroy`. In the order of responsiveness, uniffi objects
+// with Rust peers are reclaimed:
+//
+// 1. By calling the `destroy` method of the object, which calls `rustObject.free()`. If that doesn't happen:
+// 2. When the object becomes unreachable, AND the Cleaner thread gets to call `rustObject.free()`. If the thread is starved then:
+// 3. The memory is reclaimed when the process terminates.
+//
+// [1] https://stackoverflow.com/questions/24376768/can-java-finalize-an-object-when-it-is-still-in-scope/24380219
+//
+
+
+/**
+ * A fetched image whose bytes are read on demand.
+ */
+public interface ImageHandleInterface {
+    
+    /**
+     * Size of the image in bytes.
+     */
+    fun `byteLen`(): kotlin.ULong
+    
+    /**
+     * The response metadata. Its `data` is empty; read the bytes with
+     * [`Self::read`].
+     */
+    fun `info`(): ImageResponse
+    
+    /**
+     * Up to `len` bytes starting at `offset`; fewer at the end of the image
+     * and none past it.
+     */
+    fun `read`(`offset`: kotlin.ULong, `len`: kotlin.UInt): kotlin.ByteArray
+    
+    /**
+     * Write the image to `path`, replacing any existing file, e.g. to
+     * memory-map it. The file appears complete or not at all.
+     */
+    fun `writeToFile`(`path`: kotlin.String)
+    
+    companion object
+}
+
+/**
+ * A fetched image whose bytes are read on demand.
+ */
+open class ImageHandle: Disposable, AutoCloseable, ImageHandleInterface
+{
+
+    @Suppress("UNUSED_PARAMETER")
+    /**
+     * @suppress
+     */
+    constructor(withHandle: UniffiWithHandle, handle: Long) {
+        this.handle = handle
+        this.cleanable = UniffiLib.CLEANER.register(this, UniffiCleanAction(handle))
+    }
+
+    /**
+     * @suppress
+     *
+     * This constructor can be used to instantiate a fake object. Only used for tests. Any
+     * attempt to actually use an object constructed this way will fail as there is no
+     * connected Rust object.
+     */
+    @Suppress("UNUSED_PARAMETER")
+    constructor(noHandle: NoHandle) {
+        this.handle = 0
+        this.cleanable = null
+    }
+
+    protected val handle: Long
+    protected val cleanable: UniffiCleaner.Cleanable?
+
+    private val wasDestroyed = AtomicBoolean(false)
+    private val callCounter = AtomicLong(1)
+
+    override fun destroy() {
+        // Only allow a single call to this method.
+        // TODO: maybe we should log a warning if called more than once?
+        if (this.wasDestroyed.compareAndSet(false, true)) {
+            // This decrement always matches the initial count of 1 given at creation time.
+            if (this.callCounter.decrementAndGet() == 0L) {
+                cleanable?.clean()
+            }
+        }
+    }
+
+    @Synchronized
+    override fun close() {
+        this.destroy()
+    }
+
+    internal inline fun <R> callWithHandle(block: (handle: Long) -> R): R {
+        // Check and increment the call counter, to keep the object alive.
+        // This needs a compare-and-set retry loop in case of concurrent updates.
+        do {
+            val c = this.callCounter.get()
+            if (c == 0L) {
+                throw IllegalStateException("${this.javaClass.simpleName} object has already been destroyed")
+            }
+            if (c == Long.MAX_VALUE) {
+                throw IllegalStateException("${this.javaClass.simpleName} call counter would overflow")
+            }
+        } while (! this.callCounter.compareAndSet(c, c + 1L))
+        // Now we can safely do the method call without the handle being freed concurrently.
+        try {
+            return block(this.uniffiCloneHandle())
+        } finally {
+            // This decrement always matches the increment we performed above.
+            if (this.callCounter.decrementAndGet() == 0L) {
+                cleanable?.clean()
+            }
+        }
+    }
+
+    // Use a static inner class instead of a closure so as not to accidentally
+    // capture `this` as part of the cleanable's action.
+    private class UniffiCleanAction(private val handle: Long) : Runnable {
+        override fun run() {
+            if (handle == 0.toLong()) {
+                // Fake object created with `NoHandle`, don't try to free.
+                return;
+            }
+            uniffiRustCall { status ->
+                UniffiLib.uniffi_letterbox_proxy_fn_free_imagehandle(handle, status)
+            }
+        }
+    }
+
+    /**
+     * @suppress
+     */
+    fun uniffiCloneHandle(): Long {
+        if (handle == 0.toLong()) {
+            throw InternalException("uniffiCloneHandle() called on NoHandle object");
+        }
+        return uniffiRustCall() { status ->
+            UniffiLib.uniffi_letterbox_proxy_fn_clone_imagehandle(handle, status)
+        }
+    }
+
+    
+    /**
+     * Size of the image in bytes.
+     */override fun `byteLen`(): kotlin.ULong {
+            return FfiConverterULong.lift(
+    callWithHandle {
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_imagehandle_byte_len(
+        it,
+        _status)
+}
+    }
+    )
+    }
+    
+
+    
+    /**
+     * The response metadata. Its `data` is empty; read the bytes with
+     * [`Self::read`].
+     */override fun `info`(): ImageResponse {
+            return FfiConverterTypeImageResponse.lift(
+    callWithHandle {
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_imagehandle_info(
+        it,
+        _status)
+}
+    }
+    )
+    }
+    
+
+    
+    /**
+     * Up to `len` bytes starting at `offset`; fewer at the end of the image
+     * and none past it.
+     */override fun `read`(`offset`: kotlin.ULong, `len`: kotlin.UInt): kotlin.ByteArray {
+            return FfiConverterByteArray.lift(
+    callWithHandle {
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_imagehandle_read(
+        it,
+        FfiConverterULong.lower(`offset`),FfiConverterUInt.lower(`len`),_status)
+}
+    }
+    )
+    }
+    
+
+    
+    /**
+     * Write the image to `path`, replacing any existing file, e.g. to
+     * memory-map it. The file appears complete or not at all.
+     */
+    @Throws(ProxyException::class)override fun `writeToFile`(`path`: kotlin.String)
+        = 
+    callWithHandle {
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_imagehandle_write_to_file(
+        it,
+        FfiConverterString.lower(`path`),_status)
+}
+    }
+    
+    
+
+    
+
+    
+
+
+    
+    
+    /**
+     * @suppress
+     */
+    companion object
+    
+}
+
+
+/**
+ * @suppress
+ */
+public object FfiConverterTypeImageHandle: FfiConverter<ImageHandle, Long> {
+    override fun lower(value: ImageHandle): Long {
+        return value.uniffiCloneHandle()
+    }
+
+    override fun lift(value: Long): ImageHandle {
+        return ImageHandle(UniffiWithHandle, value)
+    }
+
+    override fun read(buf: ByteBuffer): ImageHandle {
+        return lift(buf.getLong())
+    }
+
+    override fun allocationSize(value: ImageHandle) = 8UL
+
+    override fun write(value: ImageHandle, buf: ByteBuffer) {
+        buf.putLong(lower(value))
+    }
+}
+
+
+// This template implements a class for working with a Rust struct via a handle
+// to the live Rust struct on the other side of the FFI.
+//
+// There's some subtlety here, because we have to be careful not to operate on a Rust
+// struct after it has been dropped, and because we must expose a public API for freeing
+// theq Kotlin wrapper object in lieu of reliable finalizers. The core requirements are:
+//
+//   * Each instance holds an opaque handle to the underlying Rust struct.
+//     Method calls need to read this handle from the object's state and pass it in to
+//     the Rust FFI.
+//
+//   * When an instance is no longer needed, its handle should be passed to a
+//     special destructor function provided by the Rust FFI, which will drop the
+//     underlying Rust struct.
+//
+//   * Given an instance, calling code is expected to call the special
+//     `destroy` method in order to free it after use, either by calling it explicitly
+//     or by using a higher-level helper like the `use` method. Failing to do so risks
+//     leaking the underlying Rust struct.
+//
+//   * We can't assume that calling code will do the right thing, and must be prepared
+//     to handle Kotlin method calls executing concurrently with or even after a call to
+//     `destroy`, and to handle multiple (possibly concurrent!) calls to `destroy`.
+//
+//   * We must never allow Rust code to operate on the underlying Rust struct after
+//     the destructor has been called, and must never call the destructor more than once.
+//     Doing so may trigger memory unsafety.
+//
+//   * To mitigate many of the risks of leaking memory and use-after-free unsafety, a `Cleaner`
+//     is implemented to call the destructor when the Kotlin object becomes unreachable.
+//     This is done in a background thread. This is not a panacea, and client code should be aware that
+//      1. the thread may starve if some there are objects that have poorly performing
+//     `drop` methods or do significant work in their `drop` methods.
+//      2. the thread is shared across the whole library. This can be tuned by using `android_cleaner = true`,
+//         or `android = true` in the [`kotlin` section of the `uniffi.toml` file](https://mozilla.github.io/uniffi-rs/kotlin/configuration.html).
+//
+// If we try to implement this with mutual exclusion on access to the handle, there is the
+// possibility of a race between a method call and a concurrent call to `destroy`:
+//
+//    * Thread A starts a method call, reads the value of the handle, but is interrupted
+//      before it can pass the handle over the FFI to Rust.
+//    * Thread B calls `destroy` and frees the underlying Rust struct.
+//    * Thread A resumes, passing the already-read handle value to Rust and triggering
+//      a use-after-free.
+//
+// One possible solution would be to use a `ReadWriteLock`, with each method call taking
+// a read lock (and thus allowed to run concurrently) and the special `destroy` method
+// taking a write lock (and thus blocking on live method calls). However, we aim not to
+// generate methods with any hidden blocking semantics, and a `destroy` method that might
+// block if called incorrectly seems to meet that bar.
+//
+// So, we achieve our goals by giving each instance an associated `AtomicLong` counter to track
+// the number of in-flight method calls, and an `AtomicBoolean` flag to indicate whether `destroy`
+// has been called. These are updated according to the following rules:
+//
+//    * The initial value of the counter is 1, indicating a live object with no in-flight calls.
+//      The initial value for the flag is false.
+//
+//    * At the start of each method call, we atomically check the counter.
+//      If it is 0 then the underlying Rust struct has already been destroyed and the call is aborted.
+//      If it is nonzero them we atomically increment it by 1 and proceed with the method call.
+//
+//    * At the end of each method call, we atomically decrement and check the counter.
+//      If it has reached zero then we destroy the underlying Rust struct.
+//
+//    * When `destroy` is called, we atomically flip the flag from false to true.
+//      If the flag was already true we silently fail.
+//      Otherwise we atomically decrement and check the counter.
+//      If it has reached zero then we destroy the underlying Rust struct.
+//
+// Astute readers may observe that this all sounds very similar to the way that Rust's `Arc<T>` works,
+// and indeed it is, with the addition of a flag to guard against multiple calls to `destroy`.
+//
+// The overall effect is that the underlying Rust struct is destroyed only when `destroy` has been
+// called *and* all in-flight method calls have completed, avoiding violating any of the expectations
+// of the underlying Rust code.
+//
+// This makes a cleaner a better alternative to _not_ calling `destroy()` as
+// and when the object is finished with, but the abstraction is not perfect: if the Rust object's `drop`
+// method is slow, and/or there are many objects to cleanup, and it's on a low end Android device, then the cleaner
+// thread may be starved, and the app will leak memory.
+//
+// In this case, `destroy`ing manually may be a better solution.
+//
+// The cleaner can live side by side with the manual calling of `destroy`. In the order of responsiveness, uniffi objects
+// with Rust peers are reclaimed:
+//
+// 1. By calling the `destroy` method of the object, which calls `rustObject.free()`. If that doesn't happen:
+// 2. When the object becomes unreachable, AND the Cleaner thread gets to call `rustObject.free()`. If the thread is starved then:
+// 3. The memory is reclaimed when the process terminates.
+//
+// [1] https://stackoverflow.com/questions/24376768/can-java-finalize-an-object-when-it-is-still-in-scope/24380219
+//
+
+
+/**
+ * Receives the proxy's log records.
+ */
+public interface LogListener {
+    
+    /**
+     * Called on the log thread, one record at a time. `target` is the Rust
+     * module that logged, e.g. `letterbox_proxy::tunnel::manager`.
+     */
+    fun `onLog`(`level`: LogLevel, `target`: kotlin.String, `message`: kotlin.String)
+    
+    companion object
+}
+
+/**
+ * Receives the proxy's log records.
+ */
+open class LogListenerImpl: Disposable, AutoCloseable, LogListener
+{
+
+    @Suppress("UNUSED_PARAMETER")
+    /**
+     * @suppress
+     */
+    constructor(withHandle: UniffiWithHandle, handle: Long) {
+        this.handle = handle
+        this.cleanable = UniffiLib.CLEANER.register(this, UniffiCleanAction(handle))
+    }
+
+    /**
+     * @suppress
+     *
+     * This constructor can be used to instantiate a fake object. Only used for tests. Any
+     * attempt to actually use an object constructed this way will fail as there is no
+     * connected Rust object.
+     */
+    @Suppress("UNUSED_PARAMETER")
+    constructor(noHandle: NoHandle) {
+        this.handle = 0
+        this.cleanable = null
+    }
+
+    protected val handle: Long
+    protected val cleanable: UniffiCleaner.Cleanable?
+
+    private val wasDestroyed = AtomicBoolean(false)
+    private val callCounter = AtomicLong(1)
+
+    override fun destroy() {
+        // Only allow a single call to this method.
+        // TODO: maybe we should log a warning if called more than once?
+        if (this.wasDestroyed.compareAndSet(false, true)) {
+            // This decrement always matches the initial count of 1 given at creation time.
+            if (this.callCounter.decrementAndGet() == 0L) {
+                cleanable?.clean()
+            }
+        }
+    }
+
+    @Synchronized
+    override fun close() {
+        this.destroy()
+    }
+
+    internal inline fun <R> callWithHandle(block: (handle: Long) -> R): R {
+        // Check and increment the call counter, to keep the object alive.
+        // This needs a compare-and-set retry loop in case of concurrent updates.
+        do {
+            val c = this.callCounter.get()
+            if (c == 0L) {
+                throw IllegalStateException("${this.javaClass.simpleName} object has already been destroyed")
+            }
+            if (c == Long.MAX_VALUE) {
+                throw IllegalStateException("${this.javaClass.simpleName} call counter would overflow")
+            }
+        } while (! this.callCounter.compareAndSet(c, c + 1L))
+        // Now we can safely do the method call without the handle being freed concurrently.
+        try {
+            return block(this.uniffiCloneHandle())
+        } finally {
+            // This decrement always matches the increment we performed above.
+            if (this.callCounter.decrementAndGet() == 0L) {
+                cleanable?.clean()
+            }
+        }
+    }
+
+    // Use a static inner class instead of a closure so as not to accidentally
+    // capture `this` as part of the cleanable's action.
+    private class UniffiCleanAction(private val handle: Long) : Runnable {
+        override fun run() {
+            if (handle == 0.toLong()) {
+                // Fake object created with `NoHandle`, don't try to free.
+                return;
+            }
+            uniffiRustCall { status ->
+                UniffiLib.uniffi_letterbox_proxy_fn_free_loglistener(handle, status)
+            }
+        }
+    }
+
+    /**
+     * @suppress
+     */
+    fun uniffiCloneHandle(): Long {
+        if (handle == 0.toLong()) {
+            throw InternalException("uniffiCloneHandle() called on NoHandle object");
+        }
+        return uniffiRustCall() { status ->
+            UniffiLib.uniffi_letterbox_proxy_fn_clone_loglistener(handle, status)
+        }
+    }
+
+    
+    /**
+     * Called on the log thread, one record at a time. `target` is the Rust
+     * module that logged, e.g. `letterbox_proxy::tunnel::manager`.
+     */override fun `onLog`(`level`: LogLevel, `target`: kotlin.String, `message`: kotlin.String)
+        = 
+    callWithHandle {
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_loglistener_on_log(
+        it,
+        FfiConverterTypeLogLevel.lower(`level`),FfiConverterString.lower(`target`),FfiConverterString.lower(`message`),_status)
+}
+    }
+    
+    
+
+    
+
+    
+
+
+    
+    
+    /**
+     * @suppress
+     */
+    companion object
+    
+}
+
+
+
+// Put the implementation in an object so we don't pollute the top-level namespace
+internal object uniffiCallbackInterfaceLogListener {
+    internal object `onLog`: UniffiCallbackInterfaceLogListenerMethod0 {
+        override fun callback(`uniffiHandle`: Long,`level`: RustBuffer.ByValue,`target`: RustBuffer.ByValue,`message`: RustBuffer.ByValue,`uniffiOutReturn`: Pointer,uniffiCallStatus: UniffiRustCallStatus,) {
+            val uniffiObj = FfiConverterTypeLogListener.handleMap.get(uniffiHandle)
+            val makeCall = { ->
+                uniffiObj.`onLog`(
+                    FfiConverterTypeLogLevel.lift(`level`),
+                    FfiConverterString.lift(`target`),
+                    FfiConverterString.lift(`message`),
+                )
+            }
+            val writeReturn = { _: Unit -> Unit }
+            uniffiTraitInterfaceCall(uniffiCallStatus, makeCall, writeReturn)
+        }
+    }
+
+    internal object uniffiFree: UniffiCallbackInterfaceFree {
+        override fun callback(handle: Long) {
+            FfiConverterTypeLogListener.handleMap.remove(handle)
+        }
+    }
+
+    internal object uniffiClone: UniffiCallbackInterfaceClone {
+        override fun callback(handle: Long): Long {
+            return FfiConverterTypeLogListener.handleMap.clone(handle)
+        }
+    }
+
+    internal var vtable = UniffiVTableCallbackInterfaceLogListener.UniffiByValue(
+        uniffiFree,
+        uniffiClone,
+        `onLog`,
+    )
+
+    // Registers the foreign callback with the Rust side.
+    // This method is generated for each callback interface.
+    internal fun register(lib: UniffiLib) {
+        lib.uniffi_letterbox_proxy_fn_init_callback_vtable_loglistener(vtable)
+    }
+}
+
+/**
+ * @suppress
+ */
+public object FfiConverterTypeLogListener: FfiConverter<LogListener, Long> {
+    internal val handleMap = UniffiHandleMap<LogListener>()
+
+    override fun lower(value: LogListener): Long {
+        if (value is LogListenerImpl) {
+             // Rust-implemented object.  Clone the handle and return it
+            return value.uniffiCloneHandle()
+         } else {
+            // Kotlin object, generate a new vtable handle and return that.
+            return handleMap.insert(value)
+         }
+    }
+
+    override fun lift(value: Long): LogListener {
+        if ((value and 1.toLong()) == 0.toLong()) {
+            // Rust-generated handle, construct a new class that uses the handle to implement the
+            // interface
+            return LogListenerImpl(UniffiWithHandle, value)
+        } else {
+            // Kotlin-generated handle, get the object from the handle map
+            return handleMap.remove(value)
+        }
+    }
+
+    override fun read(buf: ByteBuffer): LogListener {
+        return lift(buf.getLong())
+    }
+
+    override fun allocationSize(value: LogListener) = 8UL
+
+    override fun write(value: LogListener, buf: ByteBuffer) {
+        buf.putLong(lower(value))
+    }
+}
+
+
+// This template implements a class for working with a Rust struct via a handle
+// to the live Rust struct on the other side of the FFI.
+//
+// There's some subtlety here, because we have to be careful not to operate on a Rust
+// struct after it has been dropped, and because we must expose a public API for freeing
+// theq Kotlin wrapper object in lieu of reliable finalizers. The core requirements are:
+//
+//   * Each instance holds an opaque handle to the underlying Rust struct.
+//     Method calls need to read this handle from the object's state and pass it in to
+//     the Rust FFI.
+//
+//   * When an instance is no longer needed, its handle should be passed to a
+//     special destructor function provided by the Rust FFI, which will drop the
+//     underlying Rust struct.
+//
+//   * Given an instance, calling code is expected to call the special
+//     `destroy` method in order to free it after use, either by calling it explicitly
+//     or by using a higher-level helper like the `use` method. Failing to do so risks
+//     leaking the underlying Rust struct.
+//
+//   * We can't assume that calling code will do the right thing, and must be prepared
+//     to handle Kotlin method calls executing concurrently with or even after a call to
+//     `destroy`, and to handle multiple (possibly concurrent!) calls to `destroy`.
+//
+//   * We must never allow Rust code to operate on the underlying Rust struct after
+//     the destructor has been called, and must never call the destructor more than once.
+//     Doing so may trigger memory unsafety.
+//
+//   * To mitigate many of the risks of leaking memory and use-after-free unsafety, a `Cleaner`
+//     is implemented to call the destructor when the Kotlin object becomes unreachable.
+//     This is done in a background thread. This is not a panacea, and client code should be aware that
+//      1. the thread may starve if some there are objects that have poorly performing
+//     `drop` methods or do significant work in their `drop` methods.
+//      2. the thread is shared across the whole library. This can be tuned by using `android_cleaner = true`,
+//         or `android = true` in the [`kotlin` section of the `uniffi.toml` file](https://mozilla.github.io/uniffi-rs/kotlin/configuration.html).
+//
+// If we try to implement this with mutual exclusion on access to the handle, there is the
+// possibility of a race between a method call and a concurrent call to `destroy`:
+//
+//    * Thread A starts a method call, reads the value of the handle, but is interrupted
+//      before it can pass the handle over the FFI to Rust.
+//    * Thread B calls `destroy` and frees the underlying Rust struct.
+//    * Thread A resumes, passing the already-read handle value to Rust and triggering
+//      a use-after-free.
+//
+// One possible solution would be to use a `ReadWriteLock`, with each method call taking
+// a read lock (and thus allowed to run concurrently) and the special `destroy` method
+// taking a write lock (and thus blocking on live method calls). However, we aim not to
+// generate methods with any hidden blocking semantics, and a `destroy` method that might
+// block if called incorrectly seems to meet that bar.
+//
+// So, we achieve our goals by giving each instance an associated `AtomicLong` counter to track
+// the number of in-flight method calls, and an `AtomicBoolean` flag to indicate whether `destroy`
+// has been called. These are updated according to the following rules:
+//
+//    * The initial value of the counter is 1, indicating a live object with no in-flight calls.
+//      The initial value for the flag is false.
+//
+//    * At the start of each method call, we atomically check the counter.
+//      If it is 0 then the underlying Rust struct has already been destroyed and the call is aborted.
+//      If it is nonzero them we atomically increment it by 1 and proceed with the method call.
+//
+//    * At the end of each method call, we atomically decrement and check the counter.
+//      If it has reached zero then we destroy the underlying Rust struct.
+//
+//    * When `destroy` is called, we atomically flip the flag from false to true.
+//      If the flag was already true we silently fail.
+//      Otherwise we atomically decrement and check the counter.
+//      If it has reached zero then we destroy the underlying Rust struct.
+//
+// Astute readers may observe that this all sounds very similar to the way that Rust's `Arc<T>` works,
+// and indeed it is, with the addition of a flag to guard against multiple calls to `destroy`.
+//
+// The overall effect is that the underlying Rust struct is destroyed only when `destroy` has been
+// called *and* all in-flight method calls have completed, avoiding violating any of the expectations
+// of the underlying Rust code.
+//
+// This makes a cleaner a better alternative to _not_ calling `destroy()` as
+// and when the object is finished with, but the abstraction is not perfect: if the Rust object's `drop`
+// method is slow, and/or there are many objects to cleanup, and it's on a low end Android device, then the cleaner
+// thread may be starved, and the app will leak memory.
+//
+// In this case, `destroy`ing manually may be a better solution.
+//
+// The cleaner can live side by side with the manual calling of `destroy`. In the order of responsiveness, uniffi objects
+// with Rust peers are reclaimed:
+//
+// 1. By calling the `destroy` method of the object, which calls `rustObject.free()`. If that doesn't happen:
+// 2. When the object becomes unreachable, AND the Cleaner thread gets to call `rustObject.free()`. If the thread is starved then:
+// 3. The memory is reclaimed when the process terminates.
+//
+// [1] https://stackoverflow.com/questions/24376768/can-java-finalize-an-object-when-it-is-still-in-scope/24380219
+//
+
+
+/**
+ * A running loopback server. Stops when [`Self::stop`] is called or the
+ * last reference is dropped.
+ */
+public interface LoopbackServerInterface {
+    
+    /**
+     * The URL under which the server serves the image at `url`.
+     */
+    fun `imageUrl`(`url`: kotlin.String): kotlin.String
+    
+    /**
+     * The port the server listens on, on 127.0.0.1.
+     */
+    fun `port`(): kotlin.UShort
+    
+    /**
+     * Stop accepting connections. Requests already being served finish.
+     */
+    fun `stop`()
+    
+    companion object
+}
+
+/**
+ * A running loopback server. Stops when [`Self::stop`] is called or the
+ * last reference is dropped.
+ */
+open class LoopbackServer: Disposable, AutoCloseable, LoopbackServerInterface
+{
+
+    @Suppress("UNUSED_PARAMETER")
+    /**
+     * @suppress
+     */
+    constructor(withHandle: UniffiWithHandle, handle: Long) {
+        this.handle = handle
+        this.cleanable = UniffiLib.CLEANER.register(this, UniffiCleanAction(handle))
+    }
+
+    /**
+     * @suppress
+     *
+     * This constructor can be used to instantiate a fake object. Only used for tests. Any
+     * attempt to actually use an object constructed this way will fail as there is no
+     * connected Rust object.
+     */
+    @Suppress("UNUSED_PARAMETER")
+    constructor(noHandle: NoHandle) {
+        this.handle = 0
+        this.cleanable = null
+    }
+
+    protected val handle: Long
+    protected val cleanable: UniffiCleaner.Cleanable?
+
+    private val wasDestroyed = AtomicBoolean(false)
+    private val callCounter = AtomicLong(1)
+
+    override fun destroy() {
+        // Only allow a single call to this method.
+        // TODO: maybe we should log a warning if called more than once?
+        if (this.wasDestroyed.compareAndSet(false, true)) {
+            // This decrement always matches the initial count of 1 given at creation time.
+            if (this.callCounter.decrementAndGet() == 0L) {
+                cleanable?.clean()
+            }
+        }
+    }
+
+    @Synchronized
+    override fun close() {
+        this.destroy()
+    }
+
+    internal inline fun <R> callWithHandle(block: (handle: Long) -> R): R {
+        // Check and increment the call counter, to keep the object alive.
+        // This needs a compare-and-set retry loop in case of concurrent updates.
+        do {
+            val c = this.callCounter.get()
+            if (c == 0L) {
+                throw IllegalStateException("${this.javaClass.simpleName} object has already been destroyed")
+            }
+            if (c == Long.MAX_VALUE) {
+                throw IllegalStateException("${this.javaClass.simpleName} call counter would overflow")
+            }
+        } while (! this.callCounter.compareAndSet(c, c + 1L))
+        // Now we can safely do the method call without the handle being freed concurrently.
+        try {
+            return block(this.uniffiCloneHandle())
+        } finally {
+            // This decrement always matches the increment we performed above.
+            if (this.callCounter.decrementAndGet() == 0L) {
+                cleanable?.clean()
+            }
+        }
+    }
+
+    // Use a static inner class instead of a closure so as not to accidentally
+    // capture `this` as part of the cleanable's action.
+    private class UniffiCleanAction(private val handle: Long) : Runnable {
+        override fun run() {
+            if (handle == 0.toLong()) {
+                // Fake object created with `NoHandle`, don't try to free.
+                return;
+            }
+            uniffiRustCall { status ->
+                UniffiLib.uniffi_letterbox_proxy_fn_free_loopbackserver(handle, status)
+            }
+        }
+    }
+
+    /**
+     * @suppress
+     */
+    fun uniffiCloneHandle(): Long {
+        if (handle == 0.toLong()) {
+            throw InternalException("uniffiCloneHandle() called on NoHandle object");
+        }
+        return uniffiRustCall() { status ->
+            UniffiLib.uniffi_letterbox_proxy_fn_clone_loopbackserver(handle, status)
+        }
+    }
+
+    
+    /**
+     * The URL under which the server serves the image at `url`.
+     */override fun `imageUrl`(`url`: kotlin.String): kotlin.String {
+            return FfiConverterString.lift(
+    callWithHandle {
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_loopbackserver_image_url(
+        it,
+        FfiConverterString.lower(`url`),_status)
+}
+    }
+    )
+    }
+    
+
+    
+    /**
+     * The port the server listens on, on 127.0.0.1.
+     */override fun `port`(): kotlin.UShort {
+            return FfiConverterUShort.lift(
+    callWithHandle {
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_loopbackserver_port(
+        it,
+        _status)
+}
+    }
+    )
+    }
+    
+
+    
+    /**
+     * Stop accepting connections. Requests already being served finish.
+     */override fun `stop`()
+        = 
+    callWithHandle {
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_loopbackserver_stop(
+        it,
+        _status)
+}
+    }
+    
+    
+
+    
+
+    
+
+
+    
+    
+    /**
+     * @suppress
+     */
+    companion object
+    
+}
+
+
+/**
+ * @suppress
+ */
+public object FfiConverterTypeLoopbackServer: FfiConverter<LoopbackServer, Long> {
+    override fun lower(value: LoopbackServer): Long {
+        return value.uniffiCloneHandle()
+    }
+
+    override fun lift(value: Long): LoopbackServer {
+        return LoopbackServer(UniffiWithHandle, value)
+    }
+
+    override fun read(buf: ByteBuffer): LoopbackServer {
+        return lift(buf.getLong())
+    }
+
+    override fun allocationSize(value: LoopbackServer) = 8UL
+
+    override fun write(value: LoopbackServer, buf: ByteBuffer) {
+        buf.putLong(lower(value))
+    }
+}
+
+
+// This template implements a class for working with a Rust struct via a handle
+// to the live Rust struct on the other side of the FFI.
+//
+// There's some subtlety here, because we have to be careful not to operate on a Rust
+// struct after it has been dropped, and because we must expose a public API for freeing
+// theq Kotlin wrapper object in lieu of reliable finalizers. The core requirements are:
+//
+//   * Each instance holds an opaque handle to the underlying Rust struct.
+//     Method calls need to read this handle from the object's state and pass it in to
+//     the Rust FFI.
+//
+//   * When an instance is no longer needed, its handle should be passed to a
+//     special destructor function provided by the Rust FFI, which will drop the
+//     underlying Rust struct.
+//
+//   * Given an instance, calling code is expected to call the special
+//     `destroy` method in order to free it after use, either by calling it explicitly
+//     or by using a higher-level helper like the `use` method. Failing to do so risks
+//     leaking the underlying Rust struct.
+//
+//   * We can't assume that calling code will do the right thing, and must be prepared
+//     to handle Kotlin method calls executing concurrently with or even after a call to
+//     `destroy`, and to handle multiple (possibly concurrent!) calls to `destroy`.
+//
+//   * We must never allow Rust code to operate on the underlying Rust struct after
+//     the destructor has been called, and must never call the destructor more than once.
+//     Doing so may trigger memory unsafety.
+//
+//   * To mitigate many of the risks of leaking memory and use-after-free unsafety, a `Cleaner`
+//     is implemented to call the destructor when the Kotlin object becomes unreachable.
+//     This is done in a background thread. This is not a panacea, and client code should be aware that
+//      1. the thread may starve if some there are objects that have poorly performing
+//     `drop` methods or do significant work in their `drop` methods.
+//      2. the thread is shared across the whole library. This can be tuned by using `android_cleaner = true`,
+//         or `android = true` in the [`kotlin` section of the `uniffi.toml` file](https://mozilla.github.io/uniffi-rs/kotlin/configuration.html).
+//
+// If we try to implement this with mutual exclusion on access to the handle, there is the
+// possibility of a race between a method call and a concurrent call to `destroy`:
+//
+//    * Thread A starts a method call, reads the value of the handle, but is interrupted
+//      before it can pass the handle over the FFI to Rust.
+//    * Thread B calls `destroy` and frees the underlying Rust struct.
+//    * Thread A resumes, passing the already-read handle value to Rust and triggering
+//      a use-after-free.
+//
+// One possible solution would be to use a `ReadWriteLock`, with each method call taking
+// a read lock (and thus allowed to run concurrently) and the special `destroy` method
+// taking a write lock (and thus blocking on live method calls). However, we aim not to
+// generate methods with any hidden blocking semantics, and a `destroy` method that might
+// block if called incorrectly seems to meet that bar.
+//
+// So, we achieve our goals by giving each instance an associated `AtomicLong` counter to track
+// the number of in-flight method calls, and an `AtomicBoolean` flag to indicate whether `destroy`
+// has been called. These are updated according to the following rules:
+//
+//    * The initial value of the counter is 1, indicating a live object with no in-flight calls.
+//      The initial value for the flag is false.
+//
+//    * At the start of each method call, we atomically check the counter.
+//      If it is 0 then the underlying Rust struct has already been destroyed and the call is aborted.
+//      If it is nonzero them we atomically increment it by 1 and proceed with the method call.
+//
+//    * At the end of each method call, we atomically decrement and check the counter.
+//      If it has reached zero then we destroy the underlying Rust struct.
+//
+//    * When `destroy` is called, we atomically flip the flag from false to true.
+//      If the flag was already true we silently fail.
+//      Otherwise we atomically decrement and check the counter.
+//      If it has reached zero then we destroy the underlying Rust struct.
+//
+// Astute readers may observe that this all sounds very similar to the way that Rust's `Arc<T>` works,
+// and indeed it is, with the addition of a flag to guard against multiple calls to `destroy`.
+//
+// The overall effect is that the underlying Rust struct is destroyed only when `destroy` has been
+// called *and* all in-flight method calls have completed, avoiding violating any of the expectations
+// of the underlying Rust code.
+//
+// This makes a cleaner a better alternative to _not_ calling `destroy()` as
+// and when the object is finished with, but the abstraction is not perfect: if the Rust object's `drop`
+// method is slow, and/or there are many objects to cleanup, and it's on a low end Android device, then the cleaner
+// thread may be starved, and the app will leak memory.
+//
+// In this case, `destroy`ing manually may be a better solution.
+//
+// The cleaner can live side by side with the manual calling of `destroy`. In the order of responsiveness, uniffi objects
+// with Rust peers are reclaimed:
+//
+// 1. By calling the `destroy` method of the object, which calls `rustObject.free()`. If that doesn't happen:
+// 2. When the object becomes unreachable, AND the Cleaner thread gets to call `rustObject.free()`. If the thread is starved then:
+// 3. The memory is reclaimed when the process terminates.
+//
+// [1] https://stackoverflow.com/questions/24376768/can-java-finalize-an-object-when-it-is-still-in-scope/24380219
+//
+
+
+/**
+ * Receives the outcome of queued offline fetches.
+ */
+public interface OfflineFetchListener {
+    
+    /**
+     * `url` left the queue: fetched into the cache when `error` is `None`.
+     * Called on the queue's background thread.
+     */
+    fun `onFetched`(`url`: kotlin.String, `error`: kotlin.String?)
+    
+    companion object
+}
+
+/**
+ * Receives the outcome of queued offline fetches.
+ */
+open class OfflineFetchListenerImpl: Disposable, AutoCloseable, OfflineFetchListener
+{
+
+    @Suppress("UNUSED_PARAMETER")
+    /**
+     * @suppress
+     */
+    constructor(withHandle: UniffiWithHandle, handle: Long) {
+        this.handle = handle
+        this.cleanable = UniffiLib.CLEANER.register(this, UniffiCleanAction(handle))
+    }
+
+    /**
+     * @suppress
+     *
+     * This constructor can be used to instantiate a fake object. Only used for tests. Any
+     * attempt to actually use an object constructed this way will fail as there is no
+     * connected Rust object.
+     */
+    @Suppress("UNUSED_PARAMETER")
+    constructor(noHandle: NoHandle) {
+        this.handle = 0
+        this.cleanable = null
+    }
+
+    protected val handle: Long
+    protected val cleanable: UniffiCleaner.Cleanable?
+
+    private val wasDestroyed = AtomicBoolean(false)
+    private val callCounter = AtomicLong(1)
+
+    override fun destroy() {
+        // Only allow a single call to this method.
+        // TODO: maybe we should log a warning if called more than once?
+        if (this.wasDestroyed.compareAndSet(false, true)) {
+            // This decrement always matches the initial count of 1 given at creation time.
+            if (this.callCounter.decrementAndGet() == 0L) {
+                cleanable?.clean()
+            }
+        }
+    }
+
+    @Synchronized
+    override fun close() {
+        this.destroy()
+    }
+
+    internal inline fun <R> callWithHandle(block: (handle: Long) -> R): R {
+        // Check and increment the call counter, to keep the object alive.
+        // This needs a compare-and-set retry loop in case of concurrent updates.
+        do {
+            val c = this.callCounter.get()
+            if (c == 0L) {
+                throw IllegalStateException("${this.javaClass.simpleName} object has already been destroyed")
+            }
+            if (c == Long.MAX_VALUE) {
+                throw IllegalStateException("${this.javaClass.simpleName} call counter would overflow")
+            }
+        } while (! this.callCounter.compareAndSet(c, c + 1L))
+        // Now we can safely do the method call without the handle being freed concurrently.
+        try {
+            return block(this.uniffiCloneHandle())
+        } finally {
+            // This decrement always matches the increment we performed above.
+            if (this.callCounter.decrementAndGet() == 0L) {
+                cleanable?.clean()
+            }
+        }
+    }
+
+    // Use a static inner class instead of a closure so as not to accidentally
+    // capture `this` as part of the cleanable's action.
+    private class UniffiCleanAction(private val handle: Long) : Runnable {
+        override fun run() {
+            if (handle == 0.toLong()) {
+                // Fake object created with `NoHandle`, don't try to free.
+                return;
+            }
+            uniffiRustCall { status ->
+                UniffiLib.uniffi_letterbox_proxy_fn_free_offlinefetchlistener(handle, status)
+            }
+        }
+    }
+
+    /**
+     * @suppress
+     */
+    fun uniffiCloneHandle(): Long {
+        if (handle == 0.toLong()) {
+            throw InternalException("uniffiCloneHandle() called on NoHandle object");
+        }
+        return uniffiRustCall() { status ->
+            UniffiLib.uniffi_letterbox_proxy_fn_clone_offlinefetchlistener(handle, status)
+        }
+    }
+
+    
+    /**
+     * `url` left the queue: fetched into the cache when `error` is `None`.
+     * Called on the queue's background thread.
+     */override fun `onFetched`(`url`: kotlin.String, `error`: kotlin.String?)
+        = 
+    callWithHandle {
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_offlinefetchlistener_on_fetched(
+        it,
+        FfiConverterString.lower(`url`),FfiConverterOptionalString.lower(`error`),_status)
+}
+    }
+    
+    
+
+    
+
+    
+
+
+    
+    
+    /**
+     * @suppress
+     */
+    companion object
+    
+}
+
+
+
+// Put the implementation in an object so we don't pollute the top-level namespace
+internal object uniffiCallbackInterfaceOfflineFetchListener {
+    internal object `onFetched`: UniffiCallbackInterfaceOfflineFetchListenerMethod0 {
+        override fun callback(`uniffiHandle`: Long,`url`: RustBuffer.ByValue,`error`: RustBuffer.ByValue,`uniffiOutReturn`: Pointer,uniffiCallStatus: UniffiRustCallStatus,) {
+            val uniffiObj = FfiConverterTypeOfflineFetchListener.handleMap.get(uniffiHandle)
+            val makeCall = { ->
+                uniffiObj.`onFetched`(
+                    FfiConverterString.lift(`url`),
+                    FfiConverterOptionalString.lift(`error`),
+                )
+            }
+            val writeReturn = { _: Unit -> Unit }
+            uniffiTraitInterfaceCall(uniffiCallStatus, makeCall, writeReturn)
+        }
+    }
+
+    internal object uniffiFree: UniffiCallbackInterfaceFree {
+        override fun callback(handle: Long) {
+            FfiConverterTypeOfflineFetchListener.handleMap.remove(handle)
+        }
+    }
+
+    internal object uniffiClone: UniffiCallbackInterfaceClone {
+        override fun callback(handle: Long): Long {
+            return FfiConverterTypeOfflineFetchListener.handleMap.clone(handle)
+        }
+    }
+
+    internal var vtable = UniffiVTableCallbackInterfaceOfflineFetchListener.UniffiByValue(
+        uniffiFree,
+        uniffiClone,
+        `onFetched`,
+    )
+
+    // Registers the foreign callback with the Rust side.
+    // This method is generated for each callback interface.
+    internal fun register(lib: UniffiLib) {
+        lib.uniffi_letterbox_proxy_fn_init_callback_vtable_offlinefetchlistener(vtable)
+    }
+}
+
+/**
+ * @suppress
+ */
+public object FfiConverterTypeOfflineFetchListener: FfiConverter<OfflineFetchListener, Long> {
+    internal val handleMap = UniffiHandleMap<OfflineFetchListener>()
+
+    override fun lower(value: OfflineFetchListener): Long {
+        if (value is OfflineFetchListenerImpl) {
+             // Rust-implemented object.  Clone the handle and return it
+            return value.uniffiCloneHandle()
+         } else {
+            // Kotlin object, generate a new vtable handle and return that.
+            return handleMap.insert(value)
+         }
+    }
+
+    override fun lift(value: Long): OfflineFetchListener {
+        if ((value and 1.toLong()) == 0.toLong()) {
+            // Rust-generated handle, construct a new class that uses the handle to implement the
+            // interface
+            return OfflineFetchListenerImpl(UniffiWithHandle, value)
+        } else {
+            // Kotlin-generated handle, get the object from the handle map
+            return handleMap.remove(value)
+        }
+    }
+
+    override fun read(buf: ByteBuffer): OfflineFetchListener {
+        return lift(buf.getLong())
+    }
+
+    override fun allocationSize(value: OfflineFetchListener) = 8UL
+
+    override fun write(value: OfflineFetchListener, buf: ByteBuffer) {
+        buf.putLong(lower(value))
+    }
+}
+
+
+// This template implements a class for working with a Rust struct via a handle
+// to the live Rust struct on the other side of the FFI.
+//
+// There's some subtlety here, because we have to be careful not to operate on a Rust
+// struct after it has been dropped, and because we must expose a public API for freeing
+// theq Kotlin wrapper object in lieu of reliable finalizers. The core requirements are:
+//
+//   * Each instance holds an opaque handle to the underlying Rust struct.
+//     Method calls need to read this handle from the object's state and pass it in to
+//     the Rust FFI.
+//
+//   * When an instance is no longer needed, its handle should be passed to a
+//     special destructor function provided by the Rust FFI, which will drop the
+//     underlying Rust struct.
+//
+//   * Given an instance, calling code is expected to call the special
+//     `destroy` method in order to free it after use, either by calling it explicitly
+//     or by using a higher-level helper like the `use` method. Failing to do so risks
+//     leaking the underlying Rust struct.
+//
+//   * We can't assume that calling code will do the right thing, and must be prepared
+//     to handle Kotlin method calls executing concurrently with or even after a call to
+//     `destroy`, and to handle multiple (possibly concurrent!) calls to `destroy`.
+//
+//   * We must never allow Rust code to operate on the underlying Rust struct after
+//     the destructor has been called, and must never call the destructor more than once.
+//     Doing so may trigger memory unsafety.
+//
+//   * To mitigate many of the risks of leaking memory and use-after-free unsafety, a `Cleaner`
+//     is implemented to call the destructor when the Kotlin object becomes unreachable.
+//     This is done in a background thread. This is not a panacea, and client code should be aware that
+//      1. the thread may starve if some there are objects that have poorly performing
+//     `drop` methods or do significant work in their `drop` methods.
+//      2. the thread is shared across the whole library. This can be tuned by using `android_cleaner = true`,
+//         or `android = true` in the [`kotlin` section of the `uniffi.toml` file](https://mozilla.github.io/uniffi-rs/kotlin/configuration.html).
+//
+// If we try to implement this with mutual exclusion on access to the handle, there is the
+// possibility of a race between a method call and a concurrent call to `destroy`:
+//
+//    * Thread A starts a method call, reads the value of the handle, but is interrupted
+//      before it can pass the handle over the FFI to Rust.
+//    * Thread B calls `destroy` and frees the underlying Rust struct.
+//    * Thread A resumes, passing the already-read handle value to Rust and triggering
+//      a use-after-free.
+//
+// One possible solution would be to use a `ReadWriteLock`, with each method call taking
+// a read lock (and thus allowed to run concurrently) and the special `destroy` method
+// taking a write lock (and thus blocking on live method calls). However, we aim not to
+// generate methods with any hidden blocking semantics, and a `destroy` method that might
+// block if called incorrectly seems to meet that bar.
+//
+// So, we achieve our goals by giving each instance an associated `AtomicLong` counter to track
+// the number of in-flight method calls, and an `AtomicBoolean` flag to indicate whether `destroy`
+// has been called. These are updated according to the following rules:
+//
+//    * The initial value of the counter is 1, indicating a live object with no in-flight calls.
+//      The initial value for the flag is false.
+//
+//    * At the start of each method call, we atomically check the counter.
+//      If it is 0 then the underlying Rust struct has already been destroyed and the call is aborted.
+//      If it is nonzero them we atomically increment it by 1 and proceed with the method call.
+//
+//    * At the end of each method call, we atomically decrement and check the counter.
+//      If it has reached zero then we destroy the underlying Rust struct.
+//
+//    * When `destroy` is called, we atomically flip the flag from false to true.
+//      If the flag was already true we silently fail.
+//      Otherwise we atomically decrement and check the counter.
+//      If it has reached zero then we destroy the underlying Rust struct.
+//
+// Astute readers may observe that this all sounds very similar to the way that Rust's `Arc<T>` works,
+// and indeed it is, with the addition of a flag to guard against multiple calls to `destroy`.
+//
+// The overall effect is that the underlying Rust struct is destroyed only when `destroy` has been
+// called *and* all in-flight method calls have completed, avoiding violating any of the expectations
+// of the underlying Rust code.
+//
+// This makes a cleaner a better alternative to _not_ calling `destroy()` as
+// and when the object is finished with, but the abstraction is not perfect: if the Rust object's `drop`
+// method is slow, and/or there are many objects to cleanup, and it's on a low end Android device, then the cleaner
+// thread may be starved, and the app will leak memory.
+//
+// In this case, `destroy`ing manually may be a better solution.
+//
+// The cleaner can live side by side with the manual calling of `destroy`. In the order of responsiveness, uniffi objects
+// with Rust peers are reclaimed:
+//
+// 1. By calling the `destroy` method of the object, which calls `rustObject.free()`. If that doesn't happen:
+// 2. When the object becomes unreachable, AND the Cleaner thread gets to call `rustObject.free()`. If the thread is starved then:
+// 3. The memory is reclaimed when the process terminates.
+//
+// [1] https://stackoverflow.com/questions/24376768/can-java-finalize-an-object-when-it-is-still-in-scope/24380219
+//
+
+
+/**
+ * Receives download progress for tunnelled fetches.
+ */
+public interface ProgressListener {
+    
+    /**
+     * `bytes_downloaded` of the body of `url` have arrived; `total` is the
+     * announced `Content-Length`, if the server sent one.
+     */
+    fun `onProgress`(`url`: kotlin.String, `bytesDownloaded`: kotlin.ULong, `total`: kotlin.ULong?)
+    
+    companion object
+}
+
+/**
+ * Receives download progress for tunnelled fetches.
+ */
+open class ProgressListenerImpl: Disposable, AutoCloseable, ProgressListener
+{
+
+    @Suppress("UNUSED_PARAMETER")
+    /**
+     * @suppress
+     */
+    constructor(withHandle: UniffiWithHandle, handle: Long) {
+        this.handle = handle
+        this.cleanable = UniffiLib.CLEANER.register(this, UniffiCleanAction(handle))
+    }
+
+    /**
+     * @suppress
+     *
+     * This constructor can be used to instantiate a fake object. Only used for tests. Any
+     * attempt to actually use an object constructed this way will fail as there is no
+     * connected Rust object.
+     */
+    @Suppress("UNUSED_PARAMETER")
+    constructor(noHandle: NoHandle) {
+        this.handle = 0
+        this.cleanable = null
+    }
+
+    protected val handle: Long
+    protected val cleanable: UniffiCleaner.Cleanable?
+
+    private val wasDestroyed = AtomicBoolean(false)
+    private val callCounter = AtomicLong(1)
+
+    override fun destroy() {
+        // Only allow a single call to this method.
+        // TODO: maybe we should log a warning if called more than once?
+        if (this.wasDestroyed.compareAndSet(false, true)) {
+            // This decrement always matches the initial count of 1 given at creation time.
+            if (this.callCounter.decrementAndGet() == 0L) {
+                cleanable?.clean()
+            }
+        }
+    }
+
+    @Synchronized
+    override fun close() {
+        this.destroy()
+    }
+
+    internal inline fun <R> callWithHandle(block: (handle: Long) -> R): R {
+        // Check and increment the call counter, to keep the object alive.
+        // This needs a compare-and-set retry loop in case of concurrent updates.
+        do {
+            val c = this.callCounter.get()
+            if (c == 0L) {
+                throw IllegalStateException("${this.javaClass.simpleName} object has already been destroyed")
+            }
+            if (c == Long.MAX_VALUE) {
+                throw IllegalStateException("${this.javaClass.simpleName} call counter would overflow")
+            }
+        } while (! this.callCounter.compareAndSet(c, c + 1L))
+        // Now we can safely do the method call without the handle being freed concurrently.
+        try {
+            return block(this.uniffiCloneHandle())
+        } finally {
+            // This decrement always matches the increment we performed above.
+            if (this.callCounter.decrementAndGet() == 0L) {
+                cleanable?.clean()
+            }
+        }
+    }
+
+    // Use a static inner class instead of a closure so as not to accidentally
+    // capture `this` as part of the cleanable's action.
+    private class UniffiCleanAction(private val handle: Long) : Runnable {
+        override fun run() {
+            if (handle == 0.toLong()) {
+                // Fake object created with `NoHandle`, don't try to free.
+                return;
+            }
+            uniffiRustCall { status ->
+                UniffiLib.uniffi_letterbox_proxy_fn_free_progresslistener(handle, status)
+            }
+        }
+    }
+
+    /**
+     * @suppress
+     */
+    fun uniffiCloneHandle(): Long {
+        if (handle == 0.toLong()) {
+            throw InternalException("uniffiCloneHandle() called on NoHandle object");
+        }
+        return uniffiRustCall() { status ->
+            UniffiLib.uniffi_letterbox_proxy_fn_clone_progresslistener(handle, status)
+        }
+    }
+
+    
+    /**
+     * `bytes_downloaded` of the body of `url` have arrived; `total` is the
+     * announced `Content-Length`, if the server sent one.
+     */override fun `onProgress`(`url`: kotlin.String, `bytesDownloaded`: kotlin.ULong, `total`: kotlin.ULong?)
+        = 
+    callWithHandle {
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_progresslistener_on_progress(
+        it,
+        FfiConverterString.lower(`url`),FfiConverterULong.lower(`bytesDownloaded`),FfiConverterOptionalULong.lower(`total`),_status)
+}
+    }
+    
+    
+
+    
+
+    
+
+
+    
+    
+    /**
+     * @suppress
+     */
+    companion object
+    
+}
+
+
+
+// Put the implementation in an object so we don't pollute the top-level namespace
+internal object uniffiCallbackInterfaceProgressListener {
+    internal object `onProgress`: UniffiCallbackInterfaceProgressListenerMethod0 {
+        override fun callback(`uniffiHandle`: Long,`url`: RustBuffer.ByValue,`bytesDownloaded`: Long,`total`: RustBuffer.ByValue,`uniffiOutReturn`: Pointer,uniffiCallStatus: UniffiRustCallStatus,) {
+            val uniffiObj = FfiConverterTypeProgressListener.handleMap.get(uniffiHandle)
+            val makeCall = { ->
+                uniffiObj.`onProgress`(
+                    FfiConverterString.lift(`url`),
+                    FfiConverterULong.lift(`bytesDownloaded`),
+                    FfiConverterOptionalULong.lift(`total`),
+                )
+            }
+            val writeReturn = { _: Unit -> Unit }
+            uniffiTraitInterfaceCall(uniffiCallStatus, makeCall, writeReturn)
+        }
+    }
+
+    internal object uniffiFree: UniffiCallbackInterfaceFree {
+        override fun callback(handle: Long) {
+            FfiConverterTypeProgressListener.handleMap.remove(handle)
+        }
+    }
+
+    internal object uniffiClone: UniffiCallbackInterfaceClone {
+        override fun callback(handle: Long): Long {
+            return FfiConverterTypeProgressListener.handleMap.clone(handle)
+        }
+    }
+
+    internal var vtable = UniffiVTableCallbackInterfaceProgressListener.UniffiByValue(
+        uniffiFree,
+        uniffiClone,
+        `onProgress`,
+    )
+
+    // Registers the foreign callback with the Rust side.
+    // This method is generated for each callback interface.
+    internal fun register(lib: UniffiLib) {
+        lib.uniffi_letterbox_proxy_fn_init_callback_vtable_progresslistener(vtable)
+    }
+}
+
+/**
+ * @suppress
+ */
+public object FfiConverterTypeProgressListener: FfiConverter<ProgressListener, Long> {
+    internal val handleMap = UniffiHandleMap<ProgressListener>()
+
+    override fun lower(value: ProgressListener): Long {
+        if (value is ProgressListenerImpl) {
+             // Rust-implemented object.  Clone the handle and return it
+            return value.uniffiCloneHandle()
+         } else {
+            // Kotlin object, generate a new vtable handle and return that.
+            return handleMap.insert(value)
+         }
+    }
+
+    override fun lift(value: Long): ProgressListener {
+        if ((value and 1.toLong()) == 0.toLong()) {
+            // Rust-generated handle, construct a new class that uses the handle to implement the
+            // interface
+            return ProgressListenerImpl(UniffiWithHandle, value)
+        } else {
+            // Kotlin-generated handle, get the object from the handle map
+            return handleMap.remove(value)
+        }
+    }
+
+    override fun read(buf: ByteBuffer): ProgressListener {
+        return lift(buf.getLong())
+    }
+
+    override fun allocationSize(value: ProgressListener) = 8UL
+
+    override fun write(value: ProgressListener, buf: ByteBuffer) {
+        buf.putLong(lower(value))
+    }
+}
+
+
+// This template implements a class for working with a Rust struct via a handle
+// to the live Rust struct on the other side of the FFI.
+//
+// There's some subtlety here, because we have to be careful not to operate on a Rust
+// struct after it has been dropped, and because we must expose a public API for freeing
+// theq Kotlin wrapper object in lieu of reliable finalizers. The core requirements are:
+//
+//   * Each instance holds an opaque handle to the underlying Rust struct.
+//     Method calls need to read this handle from the object's state and pass it in to
+//     the Rust FFI.
+//
+//   * When an instance is no longer needed, its handle should be passed to a
+//     special destructor function provided by the Rust FFI, which will drop the
+//     underlying Rust struct.
+//
+//   * Given an instance, calling code is expected to call the special
+//     `destroy` method in order to free it after use, either by calling it explicitly
+//     or by using a higher-level helper like the `use` method. Failing to do so risks
+//     leaking the underlying Rust struct.
+//
+//   * We can't assume that calling code will do the right thing, and must be prepared
+//     to handle Kotlin method calls executing concurrently with or even after a call to
+//     `destroy`, and to handle multiple (possibly concurrent!) calls to `destroy`.
+//
+//   * We must never allow Rust code to operate on the underlying Rust struct after
+//     the destructor has been called, and must never call the destructor more than once.
+//     Doing so may trigger memory unsafety.
+//
+//   * To mitigate many of the risks of leaking memory and use-after-free unsafety, a `Cleaner`
+//     is implemented to call the destructor when the Kotlin object becomes unreachable.
+//     This is done in a background thread. This is not a panacea, and client code should be aware that
+//      1. the thread may starve if some there are objects that have poorly performing
+//     `drop` methods or do significant work in their `drop` methods.
+//      2. the thread is shared across the whole library. This can be tuned by using `android_cleaner = true`,
+//         or `android = true` in the [`kotlin` section of the `uniffi.toml` file](https://mozilla.github.io/uniffi-rs/kotlin/configuration.html).
+//
+// If we try to implement this with mutual exclusion on access to the handle, there is the
+// possibility of a race between a method call and a concurrent call to `destroy`:
+//
+//    * Thread A starts a method call, reads the value of the handle, but is interrupted
+//      before it can pass the handle over the FFI to Rust.
+//    * Thread B calls `destroy` and frees the underlying Rust struct.
+//    * Thread A resumes, passing the already-read handle value to Rust and triggering
+//      a use-after-free.
+//
+// One possible solution would be to use a `ReadWriteLock`, with each method call taking
+// a read lock (and thus allowed to run concurrently) and the special `destroy` method
+// taking a write lock (and thus blocking on live method calls). However, we aim not to
+// generate methods with any hidden blocking semantics, and a `destroy` method that might
+// block if called incorrectly seems to meet that bar.
+//
+// So, we achieve our goals by giving each instance an associated `AtomicLong` counter to track
+// the number of in-flight method calls, and an `AtomicBoolean` flag to indicate whether `destroy`
+// has been called. These are updated according to the following rules:
+//
+//    * The initial value of the counter is 1, indicating a live object with no in-flight calls.
+//      The initial value for the flag is false.
+//
+//    * At the start of each method call, we atomically check the counter.
+//      If it is 0 then the underlying Rust struct has already been destroyed and the call is aborted.
+//      If it is nonzero them we atomically increment it by 1 and proceed with the method call.
+//
+//    * At the end of each method call, we atomically decrement and check the counter.
+//      If it has reached zero then we destroy the underlying Rust struct.
+//
+//    * When `destroy` is called, we atomically flip the flag from false to true.
+//      If the flag was already true we silently fail.
+//      Otherwise we atomically decrement and check the counter.
+//      If it has reached zero then we destroy the underlying Rust struct.
+//
+// Astute readers may observe that this all sounds very similar to the way that Rust's `Arc<T>` works,
+// and indeed it is, with the addition of a flag to guard against multiple calls to `destroy`.
+//
+// The overall effect is that the underlying Rust struct is destroyed only when `destroy` has been
+// called *and* all in-flight method calls have completed, avoiding violating any of the expectations
+// of the underlying Rust code.
+//
+// This makes a cleaner a better alternative to _not_ calling `destroy()` as
+// and when the object is finished with, but the abstraction is not perfect: if the Rust object's `drop`
+// method is slow, and/or there are many objects to cleanup, and it's on a low end Android device, then the cleaner
+// thread may be starved, and the app will leak memory.
+//
+// In this case, `destroy`ing manually may be a better solution.
+//
+// The cleaner can live side by side with the manual calling of `destroy`. In the order of responsiveness, uniffi objects
+// with Rust peers are reclaimed:
+//
+// 1. By calling the `destroy` method of the object, which calls `rustObject.free()`. If that doesn't happen:
+// 2. When the object becomes unreachable, AND the Cleaner thread gets to call `rustObject.free()`. If the thread is starved then:
+// 3. The memory is reclaimed when the process terminates.
+//
+// [1] https://stackoverflow.com/questions/24376768/can-java-finalize-an-object-when-it-is-still-in-scope/24380219
+//
+
+
+/**
+ * An independent image proxy.
+ */
+public interface ProxyClientInterface {
+    
+    /**
+     * Refresh the WARP identity from scratch and persist it.
+     *
+     * Steps, in order:
+     * 1. Tear down the live tunnel and clear the last error (under the lock).
+     * 2. Best-effort delete the old Cloudflare device (outside the lock).
+     * 3. Generate a new keypair, register, fetch config, enable WARP, and write
+     * the new `warp_config.json` (outside the lock).
+     * 4. Swap in the new configuration and force the tunnel to rebuild on next use.
+     *
+     * The new tunnel is *not* eagerly reconnected: the caller can immediately
+     * follow up with [`Self::diagnostics`] to rebuild and verify it.
+     */
+    fun `resetIdentity`(): WarpStoredConfig
+    
+    /**
+     * Read the persisted WARP identity and tunnel configuration.
+     *
+     * Never touches the network: this works whether or not the tunnel has
+     * connected, making it the primary tool for diagnosing a tunnel that refuses
+     * to come up.
+     */
+    fun `storedConfig`(): WarpStoredConfig
+    
+    /**
+     * Paths of the audit log files, oldest first.
+     */
+    fun `auditLogFiles`(): List<kotlin.String>
+    
+    /**
+     * The `url_hash` the audit log records for `url`, to find its entries.
+     */
+    fun `auditUrlHash`(`url`: kotlin.String): kotlin.String
+    
+    /**
+     * Delete the audit log files.
+     */
+    fun `clearAuditLog`()
+    
+    /**
+     * Log every image request to `<storage>/audit.jsonl`, rotating the file
+     * once it reaches `max_bytes`; 0 stops logging. Logs already written
+     * stay until [`Self::clear_audit_log`].
+     */
+    fun `setAuditLog`(`maxBytes`: kotlin.ULong)
+    
+    /**
+     * Bytes received per destination host since the usage was last reset,
+     * the costliest host first.
+     */
+    fun `bandwidthUsage`(): List<HostBandwidth>
+    
+    /**
+     * Forget the per-host usage, including what was persisted.
+     */
+    fun `resetBandwidthUsage`()
+    
+    /**
+     * Prepare a batch fetching `urls` through the tunnel at `priority`, at
+     * most `max_concurrent` at a time, charged to `group_id`'s byte budget if
+     * given. Await [`ImageBatch::results`] or [`ImageBatch::stream`] to run it.
+     */
+    fun `fetchImagesBatch`(`urls`: List<kotlin.String>, `maxConcurrent`: kotlin.UInt, `priority`: RequestPriority, `groupId`: kotlin.String? = null): ImageBatch
+    
+    /**
+     * [`ImageBatch::stream`] of a new batch, for callers that never cancel
+     * other than by abandoning the call.
+     */
+    suspend fun `fetchImagesStreaming`(`urls`: List<kotlin.String>, `maxConcurrent`: kotlin.UInt, `priority`: RequestPriority, `listener`: BatchResultListener, `groupId`: kotlin.String? = null)
+    
+    /**
+     * Clear the image cache, including the disk tier if enabled.
+     *
+     * Pinned URLs stay pinned but lose their cached bodies.
+     */
+    fun `clearCache`()
+    
+    /**
+     * Disable the persistent disk cache and delete everything it stored.
+     */
+    fun `disableDiskCache`()
+    
+    /**
+     * Enable the persistent disk cache under the proxy storage path.
+     *
+     * With `encryption_key` (exactly 32 bytes, ideally from the Android Keystore)
+     * cached bodies are encrypted at rest and blob names are keyed, so cached
+     * images from confidential mail are unreadable from the app's files directory
+     * without the key. Re-enabling with a different key (or toggling encryption)
+     * discards the existing, now unreadable, entries.
+     */
+    fun `enableDiskCache`(`options`: DiskCacheOptions)
+    
+    /**
+     * Export the disk cache (index and blobs) as a single archive file at `path`.
+     *
+     * An encrypted cache stays encrypted in the archive. Returns the number of
+     * images exported; fails if the disk cache is not enabled.
+     */
+    fun `exportCache`(`path`: kotlin.String): kotlin.UInt
+    
+    /**
+     * Replace the disk cache with an archive written by [`Self::export_cache`].
+     *
+     * The disk cache must be enabled with the same encryption key (or none) as
+     * the exporting device. Returns the number of images imported.
+     */
+    fun `importCache`(`path`: kotlin.String): kotlin.UInt
+    
+    /**
+     * Pin a URL so its cached image is never evicted by LRU pressure.
+     *
+     * The URL does not need to be cached yet; the next successful fetch of it is
+     * retained as a pinned entry. Pinning an already pinned URL is a no-op.
+     */
+    fun `pinUrl`(`url`: kotlin.String)
+    
+    /**
+     * List all pinned URLs, whether or not their image has been fetched yet.
+     */
+    fun `pinnedUrls`(): List<kotlin.String>
+    
+    /**
+     * Keep cached images in the host's `storage` as well, or with
+     * [`CacheStorageMode::Instead`] in place of the in-memory LRU. `None`
+     * unregisters it; what it stored stays where it is.
+     *
+     * The storage is called with the proxy's state locked: answer promptly
+     * and do not call back into the proxy.
+     */
+    fun `setCacheStorage`(`storage`: CacheStorage?, `mode`: CacheStorageMode? = null)
+    
+    /**
+     * Shrink the in-memory cache to at most `target_bytes` of image data.
+     *
+     * Intended for Android's `onTrimMemory`. Least-recently-used entries go first;
+     * with `spill_to_disk` they move to the disk cache (when enabled) instead of
+     * being dropped. Pinned images are kept. Returns the number of bytes freed.
+     */
+    fun `trimCache`(`targetBytes`: kotlin.ULong, `spillToDisk`: kotlin.Boolean): kotlin.ULong
+    
+    /**
+     * Unpin a URL, making its cached image subject to normal LRU eviction.
+     *
+     * Returns `false` if the URL was not pinned.
+     */
+    fun `unpinUrl`(`url`: kotlin.String): kotlin.Boolean
+    
+    /**
+     * Check for a newer release through the tunnel.
+     *
+     * Pass the running version (e.g. `"v1.2.3"`); `repo` defaults to the
+     * official distribution slug when empty.
+     */
+    fun `checkForUpdate`(`currentVersion`: kotlin.String, `repo`: kotlin.String?): UpdateResult
+    
+    /**
+     * Collect full WireGuard/WARP diagnostics, provisioning the tunnel if
+     * needed.
+     */
+    fun `diagnostics`(): WarpDiagnostics
+    
+    /**
+     * Shut down the proxy. Every later call fails with `NotInitialized`.
+     *
+     * New network requests are refused at once and pending prefetches are
+     * dropped. Requests already queued for or running in the tunnel get up
+     * to five seconds to finish, so their responses still reach the cache;
+     * then the disk cache index, bandwidth usage and configuration are
+     * written out and the tunnel is closed. Blocks for the duration.
+     */
+    fun `shutdown`()
+    
+    /**
+     * Get the current proxy status.
+     */
+    fun `status`(): ProxyStatus
+    
+    /**
+     * Replace the caps on image fetches in flight; fetches already waiting
+     * for a slot are held to the new ones.
+     */
+    fun `setFetchConcurrency`(`concurrency`: FetchConcurrency)
+    
+    /**
+     * The data-saver policy in force, if the mode is on.
+     */
+    fun `dataSaver`(): DataSaver?
+    
+    /**
+     * Turn data-saver mode on with `policy`, or off with `None`.
+     */
+    fun `setDataSaver`(`policy`: DataSaver?)
+    
+    /**
+     * Set the largest declared pixel count (width x height) accepted; `0`
+     * disables the check.
+     */
+    fun `setMaxImagePixels`(`maxPixels`: kotlin.ULong)
+    
+    /**
+     * Fetch an image through the tunnel and write it to `dest_path`.
+     *
+     * A cached image is written from the cache without touching the network.
+     * Downloads land in `<dest_path>.partial` and are renamed into place only once
+     * complete and confirmed to be an image, so `dest_path` never holds a partial
+     * or non-image body. Downloaded images are not added to the in-memory cache.
+     */
+    fun `fetchImageToFile`(`url`: kotlin.String, `destPath`: kotlin.String, `options`: FileFetchOptions): FileFetchResult
+    
+    /**
+     * Enable or disable TLS 0-RTT early data on resumed sessions (disabled
+     * by default).
+     */
+    fun `setTlsEarlyData`(`enabled`: kotlin.Boolean)
+    
+    /**
+     * Register (or, with `None`, remove) the listener for this client's
+     * events. Works before initialisation and survives re-initialisation.
+     */
+    fun `setEventListener`(`listener`: ProxyEventListener?)
+    
+    /**
+     * Fetch the icon of `domain` (a host name, URL or email address) closest to
+     * `size_hint` pixels, preferring icons at least that large.
+     */
+    fun `fetchFavicon`(`domain`: kotlin.String, `sizeHint`: kotlin.UInt): ImageResponse
+    
+    /**
+     * Fetch a single image through the WARP tunnel.
+     *
+     * Exported as a suspend function: the network wait is awaited rather than
+     * blocking a thread. Establishing the tunnel on first use still blocks.
+     * The request is served ahead of queued prefetches.
+     *
+     * With `expected_hash` (a SHA-256 in hex), an image whose body hashes
+     * differently fails with [`ProxyError::HashMismatch`]. With `group_id`
+     * (e.g. the message id), the image counts against that group's byte
+     * budget; see [`Self::set_group_byte_budget`].
+     */
+    suspend fun `fetchImage`(`url`: kotlin.String, `headers`: Map<kotlin.String, kotlin.String>?, `expectedHash`: kotlin.String? = null, `groupId`: kotlin.String? = null): ImageResponse
+    
+    /**
+     * Fetch an arbitrary URL through the tunnel (non-image content allowed).
+     */
+    suspend fun `fetchUrl`(`url`: kotlin.String, `headers`: Map<kotlin.String, kotlin.String>?): HttpFetchResponse
+    
+    /**
+     * Set the `Accept` header sent with image requests, e.g.
+     * `"image/webp,image/*"` on devices that cannot decode AVIF. An empty
+     * value restores the default, `image/avif,image/webp,image/*`.
+     *
+     * Images negotiated under a non-default header are cached separately.
+     */
+    fun `setImageAccept`(`accept`: kotlin.String)
+    
+    /**
+     * Give `group_id` its full budget again, e.g. when the user asks to load
+     * a message's images anyway.
+     */
+    fun `resetGroupBudget`(`groupId`: kotlin.String)
+    
+    /**
+     * Set the byte budget of each fetch group (0 = unlimited). Usage already
+     * recorded counts against the new budget.
+     */
+    fun `setGroupByteBudget`(`maxBytes`: kotlin.ULong)
+    
+    /**
+     * Send `grant`'s `Referer` and `Origin` to its host with the image
+     * fetches of `group_id`, e.g. when the user asks to load a message's
+     * hotlink-protected images. `None` revokes the grant.
+     *
+     * Other hosts, and fetches outside the group, keep the default of no
+     * `Referer` or `Origin`; a grant does not follow redirects to another
+     * host. Each header sent under a grant is logged.
+     */
+    fun `setGroupReferer`(`groupId`: kotlin.String, `grant`: RefererGrant?)
+    
+    /**
+     * [`Self::fetch_image`], returning the image behind an [`ImageHandle`]
+     * so its bytes are not copied across the FFI whole.
+     */
+    suspend fun `fetchImageHandle`(`url`: kotlin.String, `headers`: Map<kotlin.String, kotlin.String>?, `expectedHash`: kotlin.String? = null, `groupId`: kotlin.String? = null): ImageHandle
+    
+    /**
+     * Allow the given sensitive headers to be forwarded from now on.
+     *
+     * Replaces any previous allow-list; pass an empty list to refuse them all
+     * again (the default). Names are case-insensitive; names outside the
+     * sensitive set are ignored, as they are always forwarded anyway.
+     */
+    fun `setAllowedSensitiveHeaders`(`names`: List<kotlin.String>)
+    
+    /**
+     * Send a request again on a second connection when its response has not
+     * started after `delay_ms` milliseconds, using whichever answers first.
+     * 0 disables hedging (the default).
+     */
+    fun `setHedgeDelay`(`delayMs`: kotlin.ULong)
+    
+    /**
+     * Whether this client may make network requests: switched on, and not
+     * switched off globally.
+     */
+    fun `isEnabled`(): kotlin.Boolean
+    
+    /**
+     * Switch this client's network requests on or off. Off, new requests
+     * fail with [`ProxyError::Disabled`] and those under way are abandoned.
+     */
+    fun `setEnabled`(`enabled`: kotlin.Boolean)
+    
+    /**
+     * Start a loopback HTTP server serving images through this proxy; see
+     * [`LoopbackServer::image_url`]. Each call starts a new server with its
+     * own port and token.
+     */
+    fun `startLoopbackServer`(): LoopbackServer
+    
+    /**
+     * Snapshot of this client's fetch, cache, tunnel and DNS counters.
+     */
+    fun `metrics`(): ProxyMetrics
+    
+    /**
+     * Forget every queued URL. A fetch already under way still completes.
+     */
+    fun `clearOfflineQueue`()
+    
+    /**
+     * URLs still waiting to be fetched, in order.
+     */
+    fun `offlineQueue`(): List<kotlin.String>
+    
+    /**
+     * Fetch `urls` into the cache in the background, retrying until the
+     * network is reachable, even across restarts.
+     *
+     * Returns how many URLs were queued; invalid, already cached and already
+     * queued ones are skipped.
+     */
+    fun `queueForOffline`(`urls`: List<kotlin.String>): kotlin.UInt
+    
+    /**
+     * Retry the queue now rather than after its back-off, e.g. when the
+     * device regains connectivity.
+     */
+    fun `retryOfflineQueue`()
+    
+    /**
+     * Register (or, with `None`, remove) the listener told about each URL
+     * that leaves the queue.
+     */
+    fun `setOfflineListener`(`listener`: OfflineFetchListener?)
+    
+    /**
+     * Whether network activity is paused.
+     */
+    fun `isPaused`(): kotlin.Boolean
+    
+    /**
+     * Hold new network requests until [`Self::resume`].
+     */
+    fun `pause`()
+    
+    /**
+     * Run the requests held while paused and accept new ones again.
+     */
+    fun `resume`()
+    
+    /**
+     * Warm the image cache for `urls` in the background.
+     *
+     * Returns immediately with the number of URLs queued. Invalid URLs, URLs
+     * that are already cached and URLs already waiting in the queue are
+     * skipped. `High` URLs are fetched before `Normal`, and `Normal` before
+     * `Low`; within a level, URLs are fetched in the order given. Failures are
+     * not reported — the UI's later fetch will surface them as usual.
+     * Nothing is queued while data-saver mode is on.
+     */
+    fun `prefetch`(`urls`: List<kotlin.String>, `priority`: FetchPriority): kotlin.UInt
+    
+    /**
+     * Fetch `url` as HTML and resolve its preview title and image.
+     *
+     * The page must be `text/html` or `application/xhtml+xml`. A page without a
+     * preview image still succeeds; if the image fails to load, the failure is
+     * reported in [`LinkPreview::image_error`].
+     */
+    fun `resolvePreview`(`url`: kotlin.String): LinkPreview
+    
+    /**
+     * Look up an image's MIME type, size and final URL without fetching it.
+     *
+     * Cached images and `data:` URIs are answered locally. A non-image content
+     * type fails with [`ProxyError::InvalidContentType`], as a fetch would.
+     */
+    fun `probeImage`(`url`: kotlin.String): ImageProbe
+    
+    /**
+     * Send the tunnel over `binding` from now on. Works before
+     * initialisation; a running tunnel on another binding is torn down and
+     * reconnects on the next request.
+     */
+    fun `setNetworkBinding`(`binding`: NetworkBinding)
+    
+    /**
+     * Register (or, with `None`, remove) the protector for this client's
+     * WireGuard socket. Works before initialisation and applies to tunnels
+     * started afterwards, so set it before the first fetch.
+     */
+    fun `setSocketProtector`(`protector`: SocketProtector?)
+    
+    /**
+     * Forget the errors kept for [`Self::recent_errors`].
+     */
+    fun `clearRecentErrors`()
+    
+    /**
+     * The last errors reported to the app, newest first; at most
+     * [`CAPACITY`].
+     */
+    fun `recentErrors`(): List<RecentError>
+    
+    /**
+     * Replace the policy applied to every redirect hop.
+     */
+    fun `setRedirectPolicy`(`policy`: RedirectPolicy)
+    
+    /**
+     * Fetch the configuration of the stored WARP identity again now, persist
+     * it and switch a running tunnel to a rotated peer. Unlike
+     * [`Self::reset_identity`], the identity itself is kept.
+     *
+     * The network I/O runs outside the state lock. Fails with
+     * `ProvisioningFailed` if there is no identity yet.
+     */
+    fun `refreshWarpConfig`(): WarpStoredConfig
+    
+    /**
+     * Replace the schedule on which the WARP configuration is refreshed.
+     */
+    fun `setWarpRefresh`(`refresh`: WarpRefresh)
+    
+    /**
+     * Fetch a font or stylesheet through the WARP tunnel.
+     */
+    fun `fetchResource`(`url`: kotlin.String, `options`: ResourceOptions): ResourceResponse
+    
+    /**
+     * Replace the retry policy used by all subsequent fetches.
+     *
+     * A `max_attempts` of 0 is treated as 1, i.e. no retries.
+     */
+    fun `setRetryPolicy`(`policy`: RetryPolicy)
+    
+    /**
+     * Choose how `http://` URLs are treated from now on.
+     */
+    fun `setHttpsMode`(`mode`: HttpsMode)
+    
+    /**
+     * Fetch the one image of `sources` best suited to a slot `target_width`
+     * device pixels wide on a screen of `pixel_density`, as a browser would
+     * choose it: the least sharp candidate at least as sharp as the screen,
+     * else the sharpest.
+     *
+     * With `probe_sizes`, every candidate is probed first: those that fail
+     * are skipped, and the smallest sharp enough one by `Content-Length` is
+     * fetched. [`ImageResponse::final_url`] tells which one it was.
+     */
+    suspend fun `fetchBestSource`(`sources`: List<SrcsetCandidate>, `targetWidth`: kotlin.UInt, `pixelDensity`: kotlin.Float = 1.0f, `probeSizes`: kotlin.Boolean = false): ImageResponse
+    
+    /**
+     * Open a TCP connection to `host` (a name or IPv4 address) on `port`
+     * through the tunnel, as a raw byte stream for the app.
+     */
+    fun `openStream`(`host`: kotlin.String, `port`: kotlin.UShort): ProxyStream
+    
+    /**
+     * Enable or disable SVG sanitization (enabled by default).
+     */
+    fun `setSvgSanitization`(`enabled`: kotlin.Boolean)
+    
+    /**
+     * Replace the global request ceiling used by all subsequent requests.
+     */
+    fun `setRateLimit`(`limit`: RateLimit)
+    
+    /**
+     * Replace the time budgets of the request phases used by all subsequent
+     * fetches.
+     *
+     * A budget of 0 seconds is treated as 1.
+     */
+    fun `setTimeoutBudget`(`budget`: TimeoutBudget)
+    
+    /**
+     * Refuse suspected tracking pixels with `TrackingBlocked` instead of only
+     * flagging them (off by default).
+     */
+    fun `setBlockTrackingPixels`(`enabled`: kotlin.Boolean)
+    
+    /**
+     * Replace the tracker domain list (e.g. with a newer one shipped by the app).
+     *
+     * Names are matched case-insensitively against each host and its parent
+     * domains; pass an empty list to rely on pixel shapes alone.
+     */
+    fun `setTrackerDomains`(`domains`: List<kotlin.String>)
+    
+    /**
+     * Fetch an image, then downscale and/or transcode it to match `transform`.
+     */
+    fun `fetchImageTransformed`(`url`: kotlin.String, `headers`: Map<kotlin.String, kotlin.String>?, `transform`: ImageTransform): ImageResponse
+    
+    /**
+     * Carry requests through `proxy` instead of WARP, or through WARP again
+     * with `None`. A running tunnel or proxy network is torn down and the
+     * next request takes the new path; requests already under way finish
+     * on the old one.
+     */
+    fun `setUpstreamProxy`(`proxy`: UpstreamProxy?)
+    
+    /**
+     * Replace the hosts [`Self::warm_up`] resolves ahead of time.
+     *
+     * Names are lowercased; anything that is not a valid hostname or IPv4
+     * address is dropped.
+     */
+    fun `setWarmUpHosts`(`hosts`: List<kotlin.String>)
+    
+    /**
+     * Provision WARP if needed, perform the WireGuard handshake and resolve
+     * the warm-up hosts, in the background.
+     *
+     * Returns immediately. Failures are not reported beyond the proxy's
+     * status and event listener; the first fetch simply tries again. Nothing
+     * happens while the network is paused.
+     */
+    fun `warmUp`()
+    
+    companion object
+}
+
+/**
+ * An independent image proxy.
+ */
+open class ProxyClient: Disposable, AutoCloseable, ProxyClientInterface
+{
+
+    @Suppress("UNUSED_PARAMETER")
+    /**
+     * @suppress
+     */
+    constructor(withHandle: UniffiWithHandle, handle: Long) {
+        this.handle = handle
+        this.cleanable = UniffiLib.CLEANER.register(this, UniffiCleanAction(handle))
+    }
+
+    /**
+     * @suppress
+     *
+     * This constructor can be used to instantiate a fake object. Only used for tests. Any
+     * attempt to actually use an object constructed this way will fail as there is no
+     * connected Rust object.
+     */
+    @Suppress("UNUSED_PARAMETER")
+    constructor(noHandle: NoHandle) {
+        this.handle = 0
+        this.cleanable = null
+    }
+    /**
+     * Create a proxy storing its configuration and WARP identity under
+     * `storage_path`, with an in-memory cache of `max_cache_size` images.
+     *
+     * WARP provisioning and the WireGuard handshake are deferred until the
+     * first fetch, so construction stays fast and works offline.
+     */
+    constructor(`storagePath`: kotlin.String, `maxCacheSize`: kotlin.UInt) :
+        this(UniffiWithHandle, 
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_constructor_proxyclient_new(
+    
+        FfiConverterString.lower(`storagePath`),FfiConverterUInt.lower(`maxCacheSize`),_status)
+}
+    )
+
+    protected val handle: Long
+    protected val cleanable: UniffiCleaner.Cleanable?
+
+    private val wasDestroyed = AtomicBoolean(false)
+    private val callCounter = AtomicLong(1)
+
+    override fun destroy() {
+        // Only allow a single call to this method.
+        // TODO: maybe we should log a warning if called more than once?
+        if (this.wasDestroyed.compareAndSet(false, true)) {
+            // This decrement always matches the initial count of 1 given at creation time.
+            if (this.callCounter.decrementAndGet() == 0L) {
+                cleanable?.clean()
+            }
+        }
+    }
+
+    @Synchronized
+    override fun close() {
+        this.destroy()
+    }
+
+    internal inline fun <R> callWithHandle(block: (handle: Long) -> R): R {
+        // Check and increment the call counter, to keep the object alive.
+        // This needs a compare-and-set retry loop in case of concurrent updates.
+        do {
+            val c = this.callCounter.get()
+            if (c == 0L) {
+                throw IllegalStateException("${this.javaClass.simpleName} object has already been destroyed")
+            }
+            if (c == Long.MAX_VALUE) {
+                throw IllegalStateException("${this.javaClass.simpleName} call counter would overflow")
+            }
+        } while (! this.callCounter.compareAndSet(c, c + 1L))
+        // Now we can safely do the method call without the handle being freed concurrently.
+        try {
+            return block(this.uniffiCloneHandle())
+        } finally {
+            // This decrement always matches the increment we performed above.
+            if (this.callCounter.decrementAndGet() == 0L) {
+                cleanable?.clean()
+            }
+        }
+    }
+
+    // Use a static inner class instead of a closure so as not to accidentally
+    // capture `this` as part of the cleanable's action.
+    private class UniffiCleanAction(private val handle: Long) : Runnable {
+        override fun run() {
+            if (handle == 0.toLong()) {
+                // Fake object created with `NoHandle`, don't try to free.
+                return;
+            }
+            uniffiRustCall { status ->
+                UniffiLib.uniffi_letterbox_proxy_fn_free_proxyclient(handle, status)
+            }
+        }
+    }
+
+    /**
+     * @suppress
+     */
+    fun uniffiCloneHandle(): Long {
+        if (handle == 0.toLong()) {
+            throw InternalException("uniffiCloneHandle() called on NoHandle object");
+        }
+        return uniffiRustCall() { status ->
+            UniffiLib.uniffi_letterbox_proxy_fn_clone_proxyclient(handle, status)
+        }
+    }
+
+    
+    /**
+     * Refresh the WARP identity from scratch and persist it.
+     *
+     * Steps, in order:
+     * 1. Tear down the live tunnel and clear the last error (under the lock).
+     * 2. Best-effort delete the old Cloudflare device (outside the lock).
+     * 3. Generate a new keypair, register, fetch config, enable WARP, and write
+     * the new `warp_config.json` (outside the lock).
+     * 4. Swap in the new configuration and force the tunnel to rebuild on next use.
+     *
+     * The new tunnel is *not* eagerly reconnected: the caller can immediately
+     * follow up with [`Self::diagnostics`] to rebuild and verify it.
+     */
+    @Throws(ProxyException::class)override fun `resetIdentity`(): WarpStoredConfig {
+            return FfiConverterTypeWarpStoredConfig.lift(
+    callWithHandle {
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_reset_identity(
+        it,
+        _status)
+}
+    }
+    )
+    }
+    
+
+    
+    /**
+     * Read the persisted WARP identity and tunnel configuration.
+     *
+     * Never touches the network: this works whether or not the tunnel has
+     * connected, making it the primary tool for diagnosing a tunnel that refuses
+     * to come up.
+     */
+    @Throws(ProxyException::class)override fun `storedConfig`(): WarpStoredConfig {
+            return FfiConverterTypeWarpStoredConfig.lift(
+    callWithHandle {
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_stored_config(
+        it,
+        _status)
+}
+    }
+    )
+    }
+    
+
+    
+    /**
+     * Paths of the audit log files, oldest first.
+     */override fun `auditLogFiles`(): List<kotlin.String> {
+            return FfiConverterSequenceString.lift(
+    callWithHandle {
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_audit_log_files(
+        it,
+        _status)
+}
+    }
+    )
+    }
+    
+
+    
+    /**
+     * The `url_hash` the audit log records for `url`, to find its entries.
+     */override fun `auditUrlHash`(`url`: kotlin.String): kotlin.String {
+            return FfiConverterString.lift(
+    callWithHandle {
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_audit_url_hash(
+        it,
+        FfiConverterString.lower(`url`),_status)
+}
+    }
+    )
+    }
+    
+
+    
+    /**
+     * Delete the audit log files.
+     */
+    @Throws(ProxyException::class)override fun `clearAuditLog`()
+        = 
+    callWithHandle {
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_clear_audit_log(
+        it,
+        _status)
+}
+    }
+    
+    
+
+    
+    /**
+     * Log every image request to `<storage>/audit.jsonl`, rotating the file
+     * once it reaches `max_bytes`; 0 stops logging. Logs already written
+     * stay until [`Self::clear_audit_log`].
+     */
+    @Throws(ProxyException::class)override fun `setAuditLog`(`maxBytes`: kotlin.ULong)
+        = 
+    callWithHandle {
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_set_audit_log(
+        it,
+        FfiConverterULong.lower(`maxBytes`),_status)
+}
+    }
+    
+    
+
+    
+    /**
+     * Bytes received per destination host since the usage was last reset,
+     * the costliest host first.
+     */override fun `bandwidthUsage`(): List<HostBandwidth> {
+            return FfiConverterSequenceTypeHostBandwidth.lift(
+    callWithHandle {
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_bandwidth_usage(
+        it,
+        _status)
+}
+    }
+    )
+    }
+    
+
+    
+    /**
+     * Forget the per-host usage, including what was persisted.
+     */
+    @Throws(ProxyException::class)override fun `resetBandwidthUsage`()
+        = 
+    callWithHandle {
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_reset_bandwidth_usage(
+        it,
+        _status)
+}
+    }
+    
+    
+
+    
+    /**
+     * Prepare a batch fetching `urls` through the tunnel at `priority`, at
+     * most `max_concurrent` at a time, charged to `group_id`'s byte budget if
+     * given. Await [`ImageBatch::results`] or [`ImageBatch::stream`] to run it.
+     */override fun `fetchImagesBatch`(`urls`: List<kotlin.String>, `maxConcurrent`: kotlin.UInt, `priority`: RequestPriority, `groupId`: kotlin.String?): ImageBatch {
+            return FfiConverterTypeImageBatch.lift(
+    callWithHandle {
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_fetch_images_batch(
+        it,
+        FfiConverterSequenceString.lower(`urls`),FfiConverterUInt.lower(`maxConcurrent`),FfiConverterTypeRequestPriority.lower(`priority`),FfiConverterOptionalString.lower(`groupId`),_status)
+}
+    }
+    )
+    }
+    
+
+    
+    /**
+     * [`ImageBatch::stream`] of a new batch, for callers that never cancel
+     * other than by abandoning the call.
+     */
+    @Throws(ProxyException::class)
+    @Suppress("ASSIGNED_BUT_NEVER_ACCESSED_VARIABLE")
+    override suspend fun `fetchImagesStreaming`(`urls`: List<kotlin.String>, `maxConcurrent`: kotlin.UInt, `priority`: RequestPriority, `listener`: BatchResultListener, `groupId`: kotlin.String?) {
+        return uniffiRustCallAsync(
+        callWithHandle { uniffiHandle ->
+            UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_fetch_images_streaming(
+                uniffiHandle,
+                FfiConverterSequenceString.lower(`urls`),FfiConverterUInt.lower(`maxConcurrent`),FfiConverterTypeRequestPriority.lower(`priority`),FfiConverterTypeBatchResultListener.lower(`listener`),FfiConverterOptionalString.lower(`groupId`),
+            )
+        },
+        { future, callback, continuation -> UniffiLib.ffi_letterbox_proxy_rust_future_poll_void(future, callback, continuation) },
+        { future, continuation -> UniffiLib.ffi_letterbox_proxy_rust_future_complete_void(future, continuation) },
+        { future -> UniffiLib.ffi_letterbox_proxy_rust_future_free_void(future) },
+        // lift function
+        { Unit },
+        
+        // Error FFI converter
+        ProxyException.ErrorHandler,
+    )
+    }
+
+    
+    /**
+     * Clear the image cache, including the disk tier if enabled.
+     *
+     * Pinned URLs stay pinned but lose their cached bodies.
+     */
+    @Throws(ProxyException::class)override fun `clearCache`()
+        = 
+    callWithHandle {
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_clear_cache(
+        it,
+        _status)
+}
+    }
+    
+    
+
+    
+    /**
+     * Disable the persistent disk cache and delete everything it stored.
+     */
+    @Throws(ProxyException::class)override fun `disableDiskCache`()
+        = 
+    callWithHandle {
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_disable_disk_cache(
+        it,
+        _status)
+}
+    }
+    
+    
+
+    
+    /**
+     * Enable the persistent disk cache under the proxy storage path.
+     *
+     * With `encryption_key` (exactly 32 bytes, ideally from the Android Keystore)
+     * cached bodies are encrypted at rest and blob names are keyed, so cached
+     * images from confidential mail are unreadable from the app's files directory
+     * without the key. Re-enabling with a different key (or toggling encryption)
+     * discards the existing, now unreadable, entries.
+     */
+    @Throws(ProxyException::class)override fun `enableDiskCache`(`options`: DiskCacheOptions)
+        = 
+    callWithHandle {
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_enable_disk_cache(
+        it,
+        FfiConverterTypeDiskCacheOptions.lower(`options`),_status)
+}
+    }
+    
+    
+
+    
+    /**
+     * Export the disk cache (index and blobs) as a single archive file at `path`.
+     *
+     * An encrypted cache stays encrypted in the archive. Returns the number of
+     * images exported; fails if the disk cache is not enabled.
+     */
+    @Throws(ProxyException::class)override fun `exportCache`(`path`: kotlin.String): kotlin.UInt {
+            return FfiConverterUInt.lift(
+    callWithHandle {
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_export_cache(
+        it,
+        FfiConverterString.lower(`path`),_status)
+}
+    }
+    )
+    }
+    
+
+    
+    /**
+     * Replace the disk cache with an archive written by [`Self::export_cache`].
+     *
+     * The disk cache must be enabled with the same encryption key (or none) as
+     * the exporting device. Returns the number of images imported.
+     */
+    @Throws(ProxyException::class)override fun `importCache`(`path`: kotlin.String): kotlin.UInt {
+            return FfiConverterUInt.lift(
+    callWithHandle {
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_import_cache(
+        it,
+        FfiConverterString.lower(`path`),_status)
+}
+    }
+    )
+    }
+    
+
+    
+    /**
+     * Pin a URL so its cached image is never evicted by LRU pressure.
+     *
+     * The URL does not need to be cached yet; the next successful fetch of it is
+     * retained as a pinned entry. Pinning an already pinned URL is a no-op.
+     */
+    @Throws(ProxyException::class)override fun `pinUrl`(`url`: kotlin.String)
+        = 
+    callWithHandle {
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_pin_url(
+        it,
+        FfiConverterString.lower(`url`),_status)
+}
+    }
+    
+    
+
+    
+    /**
+     * List all pinned URLs, whether or not their image has been fetched yet.
+     */
+    @Throws(ProxyException::class)override fun `pinnedUrls`(): List<kotlin.String> {
+            return FfiConverterSequenceString.lift(
+    callWithHandle {
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_pinned_urls(
+        it,
+        _status)
+}
+    }
+    )
+    }
+    
+
+    
+    /**
+     * Keep cached images in the host's `storage` as well, or with
+     * [`CacheStorageMode::Instead`] in place of the in-memory LRU. `None`
+     * unregisters it; what it stored stays where it is.
+     *
+     * The storage is called with the proxy's state locked: answer promptly
+     * and do not call back into the proxy.
+     */
+    @Throws(ProxyException::class)override fun `setCacheStorage`(`storage`: CacheStorage?, `mode`: CacheStorageMode?)
+        = 
+    callWithHandle {
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_set_cache_storage(
+        it,
+        FfiConverterOptionalTypeCacheStorage.lower(`storage`),FfiConverterOptionalTypeCacheStorageMode.lower(`mode`),_status)
+}
+    }
+    
+    
+
+    
+    /**
+     * Shrink the in-memory cache to at most `target_bytes` of image data.
+     *
+     * Intended for Android's `onTrimMemory`. Least-recently-used entries go first;
+     * with `spill_to_disk` they move to the disk cache (when enabled) instead of
+     * being dropped. Pinned images are kept. Returns the number of bytes freed.
+     */
+    @Throws(ProxyException::class)override fun `trimCache`(`targetBytes`: kotlin.ULong, `spillToDisk`: kotlin.Boolean): kotlin.ULong {
+            return FfiConverterULong.lift(
+    callWithHandle {
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_trim_cache(
+        it,
+        FfiConverterULong.lower(`targetBytes`),FfiConverterBoolean.lower(`spillToDisk`),_status)
+}
+    }
+    )
+    }
+    
+
+    
+    /**
+     * Unpin a URL, making its cached image subject to normal LRU eviction.
+     *
+     * Returns `false` if the URL was not pinned.
+     */
+    @Throws(ProxyException::class)override fun `unpinUrl`(`url`: kotlin.String): kotlin.Boolean {
+            return FfiConverterBoolean.lift(
+    callWithHandle {
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_unpin_url(
+        it,
+        FfiConverterString.lower(`url`),_status)
+}
+    }
+    )
+    }
+    
+
+    
+    /**
+     * Check for a newer release through the tunnel.
+     *
+     * Pass the running version (e.g. `"v1.2.3"`); `repo` defaults to the
+     * official distribution slug when empty.
+     */
+    @Throws(ProxyException::class)override fun `checkForUpdate`(`currentVersion`: kotlin.String, `repo`: kotlin.String?): UpdateResult {
+            return FfiConverterTypeUpdateResult.lift(
+    callWithHandle {
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_check_for_update(
+        it,
+        FfiConverterString.lower(`currentVersion`),FfiConverterOptionalString.lower(`repo`),_status)
+}
+    }
+    )
+    }
+    
+
+    
+    /**
+     * Collect full WireGuard/WARP diagnostics, provisioning the tunnel if
+     * needed.
+     */
+    @Throws(ProxyException::class)override fun `diagnostics`(): WarpDiagnostics {
+            return FfiConverterTypeWarpDiagnostics.lift(
+    callWithHandle {
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_diagnostics(
+        it,
+        _status)
+}
+    }
+    )
+    }
+    
+
+    
+    /**
+     * Shut down the proxy. Every later call fails with `NotInitialized`.
+     *
+     * New network requests are refused at once and pending prefetches are
+     * dropped. Requests already queued for or running in the tunnel get up
+     * to five seconds to finish, so their responses still reach the cache;
+     * then the disk cache index, bandwidth usage and configuration are
+     * written out and the tunnel is closed. Blocks for the duration.
+     */
+    @Throws(ProxyException::class)override fun `shutdown`()
+        = 
+    callWithHandle {
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_shutdown(
+        it,
+        _status)
+}
+    }
+    
+    
+
+    
+    /**
+     * Get the current proxy status.
+     */
+    @Throws(ProxyException::class)override fun `status`(): ProxyStatus {
+            return FfiConverterTypeProxyStatus.lift(
+    callWithHandle {
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_status(
+        it,
+        _status)
+}
+    }
+    )
+    }
+    
+
+    
+    /**
+     * Replace the caps on image fetches in flight; fetches already waiting
+     * for a slot are held to the new ones.
+     */
+    @Throws(ProxyException::class)override fun `setFetchConcurrency`(`concurrency`: FetchConcurrency)
+        = 
+    callWithHandle {
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_set_fetch_concurrency(
+        it,
+        FfiConverterTypeFetchConcurrency.lower(`concurrency`),_status)
+}
+    }
+    
+    
+
+    
+    /**
+     * The data-saver policy in force, if the mode is on.
+     */
+    @Throws(ProxyException::class)override fun `dataSaver`(): DataSaver? {
+            return FfiConverterOptionalTypeDataSaver.lift(
+    callWithHandle {
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_data_saver(
+        it,
+        _status)
+}
+    }
+    )
+    }
+    
+
+    
+    /**
+     * Turn data-saver mode on with `policy`, or off with `None`.
+     */
+    @Throws(ProxyException::class)override fun `setDataSaver`(`policy`: DataSaver?)
+        = 
+    callWithHandle {
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_set_data_saver(
+        it,
+        FfiConverterOptionalTypeDataSaver.lower(`policy`),_status)
+}
+    }
+    
+    
+
+    
+    /**
+     * Set the largest declared pixel count (width x height) accepted; `0`
+     * disables the check.
+     */
+    @Throws(ProxyException::class)override fun `setMaxImagePixels`(`maxPixels`: kotlin.ULong)
+        = 
+    callWithHandle {
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_set_max_image_pixels(
+        it,
+        FfiConverterULong.lower(`maxPixels`),_status)
+}
+    }
+    
+    
+
+    
+    /**
+     * Fetch an image through the tunnel and write it to `dest_path`.
+     *
+     * A cached image is written from the cache without touching the network.
+     * Downloads land in `<dest_path>.partial` and are renamed into place only once
+     * complete and confirmed to be an image, so `dest_path` never holds a partial
+     * or non-image body. Downloaded images are not added to the in-memory cache.
+     */
+    @Throws(ProxyException::class)override fun `fetchImageToFile`(`url`: kotlin.String, `destPath`: kotlin.String, `options`: FileFetchOptions): FileFetchResult {
+            return FfiConverterTypeFileFetchResult.lift(
+    callWithHandle {
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_fetch_image_to_file(
+        it,
+        FfiConverterString.lower(`url`),FfiConverterString.lower(`destPath`),FfiConverterTypeFileFetchOptions.lower(`options`),_status)
+}
+    }
+    )
+    }
+    
+
+    
+    /**
+     * Enable or disable TLS 0-RTT early data on resumed sessions (disabled
+     * by default).
+     */
+    @Throws(ProxyException::class)override fun `setTlsEarlyData`(`enabled`: kotlin.Boolean)
+        = 
+    callWithHandle {
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_set_tls_early_data(
+        it,
+        FfiConverterBoolean.lower(`enabled`),_status)
+}
+    }
+    
+    
+
+    
+    /**
+     * Register (or, with `None`, remove) the listener for this client's
+     * events. Works before initialisation and survives re-initialisation.
+     */
+    @Throws(ProxyException::class)override fun `setEventListener`(`listener`: ProxyEventListener?)
+        = 
+    callWithHandle {
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_set_event_listener(
+        it,
+        FfiConverterOptionalTypeProxyEventListener.lower(`listener`),_status)
+}
+    }
+    
+    
+
+    
+    /**
+     * Fetch the icon of `domain` (a host name, URL or email address) closest to
+     * `size_hint` pixels, preferring icons at least that large.
+     */
+    @Throws(ProxyException::class)override fun `fetchFavicon`(`domain`: kotlin.String, `sizeHint`: kotlin.UInt): ImageResponse {
+            return FfiConverterTypeImageResponse.lift(
+    callWithHandle {
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_fetch_favicon(
+        it,
+        FfiConverterString.lower(`domain`),FfiConverterUInt.lower(`sizeHint`),_status)
+}
+    }
+    )
+    }
+    
+
+    
+    /**
+     * Fetch a single image through the WARP tunnel.
+     *
+     * Exported as a suspend function: the network wait is awaited rather than
+     * blocking a thread. Establishing the tunnel on first use still blocks.
+     * The request is served ahead of queued prefetches.
+     *
+     * With `expected_hash` (a SHA-256 in hex), an image whose body hashes
+     * differently fails with [`ProxyError::HashMismatch`]. With `group_id`
+     * (e.g. the message id), the image counts against that group's byte
+     * budget; see [`Self::set_group_byte_budget`].
+     */
+    @Throws(ProxyException::class)
+    @Suppress("ASSIGNED_BUT_NEVER_ACCESSED_VARIABLE")
+    override suspend fun `fetchImage`(`url`: kotlin.String, `headers`: Map<kotlin.String, kotlin.String>?, `expectedHash`: kotlin.String?, `groupId`: kotlin.String?) : ImageResponse {
+        return uniffiRustCallAsync(
+        callWithHandle { uniffiHandle ->
+            UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_fetch_image(
+                uniffiHandle,
+                FfiConverterString.lower(`url`),FfiConverterOptionalMapStringString.lower(`headers`),FfiConverterOptionalString.lower(`expectedHash`),FfiConverterOptionalString.lower(`groupId`),
+            )
+        },
+        { future, callback, continuation -> UniffiLib.ffi_letterbox_proxy_rust_future_poll_rust_buffer(future, callback, continuation) },
+        { future, continuation -> UniffiLib.ffi_letterbox_proxy_rust_future_complete_rust_buffer(future, continuation) },
+        { future -> UniffiLib.ffi_letterbox_proxy_rust_future_free_rust_buffer(future) },
+        // lift function
+        { FfiConverterTypeImageResponse.lift(it) },
+        // Error FFI converter
+        ProxyException.ErrorHandler,
+    )
+    }
+
+    
+    /**
+     * Fetch an arbitrary URL through the tunnel (non-image content allowed).
+     */
+    @Throws(ProxyException::class)
+    @Suppress("ASSIGNED_BUT_NEVER_ACCESSED_VARIABLE")
+    override suspend fun `fetchUrl`(`url`: kotlin.String, `headers`: Map<kotlin.String, kotlin.String>?) : HttpFetchResponse {
+        return uniffiRustCallAsync(
+        callWithHandle { uniffiHandle ->
+            UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_fetch_url(
+                uniffiHandle,
+                FfiConverterString.lower(`url`),FfiConverterOptionalMapStringString.lower(`headers`),
+            )
+        },
+        { future, callback, continuation -> UniffiLib.ffi_letterbox_proxy_rust_future_poll_rust_buffer(future, callback, continuation) },
+        { future, continuation -> UniffiLib.ffi_letterbox_proxy_rust_future_complete_rust_buffer(future, continuation) },
+        { future -> UniffiLib.ffi_letterbox_proxy_rust_future_free_rust_buffer(future) },
+        // lift function
+        { FfiConverterTypeHttpFetchResponse.lift(it) },
+        // Error FFI converter
+        ProxyException.ErrorHandler,
+    )
+    }
+
+    
+    /**
+     * Set the `Accept` header sent with image requests, e.g.
+     * `"image/webp,image/*"` on devices that cannot decode AVIF. An empty
+     * value restores the default, `image/avif,image/webp,image/*`.
+     *
+     * Images negotiated under a non-default header are cached separately.
+     */
+    @Throws(ProxyException::class)override fun `setImageAccept`(`accept`: kotlin.String)
+        = 
+    callWithHandle {
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_set_image_accept(
+        it,
+        FfiConverterString.lower(`accept`),_status)
+}
+    }
+    
+    
+
+    
+    /**
+     * Give `group_id` its full budget again, e.g. when the user asks to load
+     * a message's images anyway.
+     */
+    @Throws(ProxyException::class)override fun `resetGroupBudget`(`groupId`: kotlin.String)
+        = 
+    callWithHandle {
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_reset_group_budget(
+        it,
+        FfiConverterString.lower(`groupId`),_status)
+}
+    }
+    
+    
+
+    
+    /**
+     * Set the byte budget of each fetch group (0 = unlimited). Usage already
+     * recorded counts against the new budget.
+     */
+    @Throws(ProxyException::class)override fun `setGroupByteBudget`(`maxBytes`: kotlin.ULong)
+        = 
+    callWithHandle {
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_set_group_byte_budget(
+        it,
+        FfiConverterULong.lower(`maxBytes`),_status)
+}
+    }
+    
+    
+
+    
+    /**
+     * Send `grant`'s `Referer` and `Origin` to its host with the image
+     * fetches of `group_id`, e.g. when the user asks to load a message's
+     * hotlink-protected images. `None` revokes the grant.
+     *
+     * Other hosts, and fetches outside the group, keep the default of no
+     * `Referer` or `Origin`; a grant does not follow redirects to another
+     * host. Each header sent under a grant is logged.
+     */
+    @Throws(ProxyException::class)override fun `setGroupReferer`(`groupId`: kotlin.String, `grant`: RefererGrant?)
+        = 
+    callWithHandle {
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_set_group_referer(
+        it,
+        FfiConverterString.lower(`groupId`),FfiConverterOptionalTypeRefererGrant.lower(`grant`),_status)
+}
+    }
+    
+    
+
+    
+    /**
+     * [`Self::fetch_image`], returning the image behind an [`ImageHandle`]
+     * so its bytes are not copied across the FFI whole.
+     */
+    @Throws(ProxyException::class)
+    @Suppress("ASSIGNED_BUT_NEVER_ACCESSED_VARIABLE")
+    override suspend fun `fetchImageHandle`(`url`: kotlin.String, `headers`: Map<kotlin.String, kotlin.String>?, `expectedHash`: kotlin.String?, `groupId`: kotlin.String?) : ImageHandle {
+        return uniffiRustCallAsync(
+        callWithHandle { uniffiHandle ->
+            UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_fetch_image_handle(
+                uniffiHandle,
+                FfiConverterString.lower(`url`),FfiConverterOptionalMapStringString.lower(`headers`),FfiConverterOptionalString.lower(`expectedHash`),FfiConverterOptionalString.lower(`groupId`),
+            )
+        },
+        { future, callback, continuation -> UniffiLib.ffi_letterbox_proxy_rust_future_poll_u64(future, callback, continuation) },
+        { future, continuation -> UniffiLib.ffi_letterbox_proxy_rust_future_complete_u64(future, continuation) },
+        { future -> UniffiLib.ffi_letterbox_proxy_rust_future_free_u64(future) },
+        // lift function
+        { FfiConverterTypeImageHandle.lift(it) },
+        // Error FFI converter
+        ProxyException.ErrorHandler,
+    )
+    }
+
+    
+    /**
+     * Allow the given sensitive headers to be forwarded from now on.
+     *
+     * Replaces any previous allow-list; pass an empty list to refuse them all
+     * again (the default). Names are case-insensitive; names outside the
+     * sensitive set are ignored, as they are always forwarded anyway.
+     */
+    @Throws(ProxyException::class)override fun `setAllowedSensitiveHeaders`(`names`: List<kotlin.String>)
+        = 
+    callWithHandle {
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_set_allowed_sensitive_headers(
+        it,
+        FfiConverterSequenceString.lower(`names`),_status)
+}
+    }
+    
+    
+
+    
+    /**
+     * Send a request again on a second connection when its response has not
+     * started after `delay_ms` milliseconds, using whichever answers first.
+     * 0 disables hedging (the default).
+     */
+    @Throws(ProxyException::class)override fun `setHedgeDelay`(`delayMs`: kotlin.ULong)
+        = 
+    callWithHandle {
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_set_hedge_delay(
+        it,
+        FfiConverterULong.lower(`delayMs`),_status)
+}
+    }
+    
+    
+
+    
+    /**
+     * Whether this client may make network requests: switched on, and not
+     * switched off globally.
+     */override fun `isEnabled`(): kotlin.Boolean {
+            return FfiConverterBoolean.lift(
+    callWithHandle {
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_is_enabled(
+        it,
+        _status)
+}
+    }
+    )
+    }
+    
+
+    
+    /**
+     * Switch this client's network requests on or off. Off, new requests
+     * fail with [`ProxyError::Disabled`] and those under way are abandoned.
+     */override fun `setEnabled`(`enabled`: kotlin.Boolean)
+        = 
+    callWithHandle {
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_set_enabled(
+        it,
+        FfiConverterBoolean.lower(`enabled`),_status)
+}
+    }
+    
+    
+
+    
+    /**
+     * Start a loopback HTTP server serving images through this proxy; see
+     * [`LoopbackServer::image_url`]. Each call starts a new server with its
+     * own port and token.
+     */
+    @Throws(ProxyException::class)override fun `startLoopbackServer`(): LoopbackServer {
+            return FfiConverterTypeLoopbackServer.lift(
+    callWithHandle {
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_start_loopback_server(
+        it,
+        _status)
+}
+    }
+    )
+    }
+    
+
+    
+    /**
+     * Snapshot of this client's fetch, cache, tunnel and DNS counters.
+     */override fun `metrics`(): ProxyMetrics {
+            return FfiConverterTypeProxyMetrics.lift(
+    callWithHandle {
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_metrics(
+        it,
+        _status)
+}
+    }
+    )
+    }
+    
+
+    
+    /**
+     * Forget every queued URL. A fetch already under way still completes.
+     */
+    @Throws(ProxyException::class)override fun `clearOfflineQueue`()
+        = 
+    callWithHandle {
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_clear_offline_queue(
+        it,
+        _status)
+}
+    }
+    
+    
+
+    
+    /**
+     * URLs still waiting to be fetched, in order.
+     */override fun `offlineQueue`(): List<kotlin.String> {
+            return FfiConverterSequenceString.lift(
+    callWithHandle {
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_offline_queue(
+        it,
+        _status)
+}
+    }
+    )
+    }
+    
+
+    
+    /**
+     * Fetch `urls` into the cache in the background, retrying until the
+     * network is reachable, even across restarts.
+     *
+     * Returns how many URLs were queued; invalid, already cached and already
+     * queued ones are skipped.
+     */
+    @Throws(ProxyException::class)override fun `queueForOffline`(`urls`: List<kotlin.String>): kotlin.UInt {
+            return FfiConverterUInt.lift(
+    callWithHandle {
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_queue_for_offline(
+        it,
+        FfiConverterSequenceString.lower(`urls`),_status)
+}
+    }
+    )
+    }
+    
+
+    
+    /**
+     * Retry the queue now rather than after its back-off, e.g. when the
+     * device regains connectivity.
+     */override fun `retryOfflineQueue`()
+        = 
+    callWithHandle {
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_retry_offline_queue(
+        it,
+        _status)
+}
+    }
+    
+    
+
+    
+    /**
+     * Register (or, with `None`, remove) the listener told about each URL
+     * that leaves the queue.
+     */override fun `setOfflineListener`(`listener`: OfflineFetchListener?)
+        = 
+    callWithHandle {
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_set_offline_listener(
+        it,
+        FfiConverterOptionalTypeOfflineFetchListener.lower(`listener`),_status)
+}
+    }
+    
+    
+
+    
+    /**
+     * Whether network activity is paused.
+     */
+    @Throws(ProxyException::class)override fun `isPaused`(): kotlin.Boolean {
+            return FfiConverterBoolean.lift(
+    callWithHandle {
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_is_paused(
+        it,
+        _status)
+}
+    }
+    )
+    }
+    
+
+    
+    /**
+     * Hold new network requests until [`Self::resume`].
+     */
+    @Throws(ProxyException::class)override fun `pause`()
+        = 
+    callWithHandle {
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_pause(
+        it,
+        _status)
+}
+    }
+    
+    
+
+    
+    /**
+     * Run the requests held while paused and accept new ones again.
+     */
+    @Throws(ProxyException::class)override fun `resume`()
+        = 
+    callWithHandle {
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_resume(
+        it,
+        _status)
+}
+    }
+    
+    
+
+    
+    /**
+     * Warm the image cache for `urls` in the background.
+     *
+     * Returns immediately with the number of URLs queued. Invalid URLs, URLs
+     * that are already cached and URLs already waiting in the queue are
+     * skipped. `High` URLs are fetched before `Normal`, and `Normal` before
+     * `Low`; within a level, URLs are fetched in the order given. Failures are
+     * not reported — the UI's later fetch will surface them as usual.
+     * Nothing is queued while data-saver mode is on.
+     */
+    @Throws(ProxyException::class)override fun `prefetch`(`urls`: List<kotlin.String>, `priority`: FetchPriority): kotlin.UInt {
+            return FfiConverterUInt.lift(
+    callWithHandle {
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_prefetch(
+        it,
+        FfiConverterSequenceString.lower(`urls`),FfiConverterTypeFetchPriority.lower(`priority`),_status)
+}
+    }
+    )
+    }
+    
+
+    
+    /**
+     * Fetch `url` as HTML and resolve its preview title and image.
+     *
+     * The page must be `text/html` or `application/xhtml+xml`. A page without a
+     * preview image still succeeds; if the image fails to load, the failure is
+     * reported in [`LinkPreview::image_error`].
+     */
+    @Throws(ProxyException::class)override fun `resolvePreview`(`url`: kotlin.String): LinkPreview {
+            return FfiConverterTypeLinkPreview.lift(
+    callWithHandle {
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_resolve_preview(
+        it,
+        FfiConverterString.lower(`url`),_status)
+}
+    }
+    )
+    }
+    
+
+    
+    /**
+     * Look up an image's MIME type, size and final URL without fetching it.
+     *
+     * Cached images and `data:` URIs are answered locally. A non-image content
+     * type fails with [`ProxyError::InvalidContentType`], as a fetch would.
+     */
+    @Throws(ProxyException::class)override fun `probeImage`(`url`: kotlin.String): ImageProbe {
+            return FfiConverterTypeImageProbe.lift(
+    callWithHandle {
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_probe_image(
+        it,
+        FfiConverterString.lower(`url`),_status)
+}
+    }
+    )
+    }
+    
+
+    
+    /**
+     * Send the tunnel over `binding` from now on. Works before
+     * initialisation; a running tunnel on another binding is torn down and
+     * reconnects on the next request.
+     */
+    @Throws(ProxyException::class)override fun `setNetworkBinding`(`binding`: NetworkBinding)
+        = 
+    callWithHandle {
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_set_network_binding(
+        it,
+        FfiConverterTypeNetworkBinding.lower(`binding`),_status)
+}
+    }
+    
+    
+
+    
+    /**
+     * Register (or, with `None`, remove) the protector for this client's
+     * WireGuard socket. Works before initialisation and applies to tunnels
+     * started afterwards, so set it before the first fetch.
+     */override fun `setSocketProtector`(`protector`: SocketProtector?)
+        = 
+    callWithHandle {
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_set_socket_protector(
+        it,
+        FfiConverterOptionalTypeSocketProtector.lower(`protector`),_status)
+}
+    }
+    
+    
+
+    
+    /**
+     * Forget the errors kept for [`Self::recent_errors`].
+     */override fun `clearRecentErrors`()
+        = 
+    callWithHandle {
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_clear_recent_errors(
+        it,
+        _status)
+}
+    }
+    
+    
+
+    
+    /**
+     * The last errors reported to the app, newest first; at most
+     * [`CAPACITY`].
+     */override fun `recentErrors`(): List<RecentError> {
+            return FfiConverterSequenceTypeRecentError.lift(
+    callWithHandle {
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_recent_errors(
+        it,
+        _status)
+}
+    }
+    )
+    }
+    
+
+    
+    /**
+     * Replace the policy applied to every redirect hop.
+     */
+    @Throws(ProxyException::class)override fun `setRedirectPolicy`(`policy`: RedirectPolicy)
+        = 
+    callWithHandle {
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_set_redirect_policy(
+        it,
+        FfiConverterTypeRedirectPolicy.lower(`policy`),_status)
+}
+    }
+    
+    
+
+    
+    /**
+     * Fetch the configuration of the stored WARP identity again now, persist
+     * it and switch a running tunnel to a rotated peer. Unlike
+     * [`Self::reset_identity`], the identity itself is kept.
+     *
+     * The network I/O runs outside the state lock. Fails with
+     * `ProvisioningFailed` if there is no identity yet.
+     */
+    @Throws(ProxyException::class)override fun `refreshWarpConfig`(): WarpStoredConfig {
+            return FfiConverterTypeWarpStoredConfig.lift(
+    callWithHandle {
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_refresh_warp_config(
+        it,
+        _status)
+}
+    }
+    )
+    }
+    
+
+    
+    /**
+     * Replace the schedule on which the WARP configuration is refreshed.
+     */
+    @Throws(ProxyException::class)override fun `setWarpRefresh`(`refresh`: WarpRefresh)
+        = 
+    callWithHandle {
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_set_warp_refresh(
+        it,
+        FfiConverterTypeWarpRefresh.lower(`refresh`),_status)
+}
+    }
+    
+    
+
+    
+    /**
+     * Fetch a font or stylesheet through the WARP tunnel.
+     */
+    @Throws(ProxyException::class)override fun `fetchResource`(`url`: kotlin.String, `options`: ResourceOptions): ResourceResponse {
+            return FfiConverterTypeResourceResponse.lift(
+    callWithHandle {
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_fetch_resource(
+        it,
+        FfiConverterString.lower(`url`),FfiConverterTypeResourceOptions.lower(`options`),_status)
+}
+    }
+    )
+    }
+    
+
+    
+    /**
+     * Replace the retry policy used by all subsequent fetches.
+     *
+     * A `max_attempts` of 0 is treated as 1, i.e. no retries.
+     */
+    @Throws(ProxyException::class)override fun `setRetryPolicy`(`policy`: RetryPolicy)
+        = 
+    callWithHandle {
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_set_retry_policy(
+        it,
+        FfiConverterTypeRetryPolicy.lower(`policy`),_status)
+}
+    }
+    
+    
+
+    
+    /**
+     * Choose how `http://` URLs are treated from now on.
+     */
+    @Throws(ProxyException::class)override fun `setHttpsMode`(`mode`: HttpsMode)
+        = 
+    callWithHandle {
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_set_https_mode(
+        it,
+        FfiConverterTypeHttpsMode.lower(`mode`),_status)
+}
+    }
+    
+    
+
+    
+    /**
+     * Fetch the one image of `sources` best suited to a slot `target_width`
+     * device pixels wide on a screen of `pixel_density`, as a browser would
+     * choose it: the least sharp candidate at least as sharp as the screen,
+     * else the sharpest.
+     *
+     * With `probe_sizes`, every candidate is probed first: those that fail
+     * are skipped, and the smallest sharp enough one by `Content-Length` is
+     * fetched. [`ImageResponse::final_url`] tells which one it was.
+     */
+    @Throws(ProxyException::class)
+    @Suppress("ASSIGNED_BUT_NEVER_ACCESSED_VARIABLE")
+    override suspend fun `fetchBestSource`(`sources`: List<SrcsetCandidate>, `targetWidth`: kotlin.UInt, `pixelDensity`: kotlin.Float, `probeSizes`: kotlin.Boolean) : ImageResponse {
+        return uniffiRustCallAsync(
+        callWithHandle { uniffiHandle ->
+            UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_fetch_best_source(
+                uniffiHandle,
+                FfiConverterSequenceTypeSrcsetCandidate.lower(`sources`),FfiConverterUInt.lower(`targetWidth`),FfiConverterFloat.lower(`pixelDensity`),FfiConverterBoolean.lower(`probeSizes`),
+            )
+        },
+        { future, callback, continuation -> UniffiLib.ffi_letterbox_proxy_rust_future_poll_rust_buffer(future, callback, continuation) },
+        { future, continuation -> UniffiLib.ffi_letterbox_proxy_rust_future_complete_rust_buffer(future, continuation) },
+        { future -> UniffiLib.ffi_letterbox_proxy_rust_future_free_rust_buffer(future) },
+        // lift function
+        { FfiConverterTypeImageResponse.lift(it) },
+        // Error FFI converter
+        ProxyException.ErrorHandler,
+    )
+    }
+
+    
+    /**
+     * Open a TCP connection to `host` (a name or IPv4 address) on `port`
+     * through the tunnel, as a raw byte stream for the app.
+     */
+    @Throws(ProxyException::class)override fun `openStream`(`host`: kotlin.String, `port`: kotlin.UShort): ProxyStream {
+            return FfiConverterTypeProxyStream.lift(
+    callWithHandle {
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_open_stream(
+        it,
+        FfiConverterString.lower(`host`),FfiConverterUShort.lower(`port`),_status)
+}
+    }
+    )
+    }
+    
+
+    
+    /**
+     * Enable or disable SVG sanitization (enabled by default).
+     */
+    @Throws(ProxyException::class)override fun `setSvgSanitization`(`enabled`: kotlin.Boolean)
+        = 
+    callWithHandle {
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_set_svg_sanitization(
+        it,
+        FfiConverterBoolean.lower(`enabled`),_status)
+}
+    }
+    
+    
+
+    
+    /**
+     * Replace the global request ceiling used by all subsequent requests.
+     */
+    @Throws(ProxyException::class)override fun `setRateLimit`(`limit`: RateLimit)
+        = 
+    callWithHandle {
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_set_rate_limit(
+        it,
+        FfiConverterTypeRateLimit.lower(`limit`),_status)
+}
+    }
+    
+    
+
+    
+    /**
+     * Replace the time budgets of the request phases used by all subsequent
+     * fetches.
+     *
+     * A budget of 0 seconds is treated as 1.
+     */
+    @Throws(ProxyException::class)override fun `setTimeoutBudget`(`budget`: TimeoutBudget)
+        = 
+    callWithHandle {
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_set_timeout_budget(
+        it,
+        FfiConverterTypeTimeoutBudget.lower(`budget`),_status)
+}
+    }
+    
+    
+
+    
+    /**
+     * Refuse suspected tracking pixels with `TrackingBlocked` instead of only
+     * flagging them (off by default).
+     */
+    @Throws(ProxyException::class)override fun `setBlockTrackingPixels`(`enabled`: kotlin.Boolean)
+        = 
+    callWithHandle {
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_set_block_tracking_pixels(
+        it,
+        FfiConverterBoolean.lower(`enabled`),_status)
+}
+    }
+    
+    
+
+    
+    /**
+     * Replace the tracker domain list (e.g. with a newer one shipped by the app).
+     *
+     * Names are matched case-insensitively against each host and its parent
+     * domains; pass an empty list to rely on pixel shapes alone.
+     */
+    @Throws(ProxyException::class)override fun `setTrackerDomains`(`domains`: List<kotlin.String>)
+        = 
+    callWithHandle {
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_set_tracker_domains(
+        it,
+        FfiConverterSequenceString.lower(`domains`),_status)
+}
+    }
+    
+    
+
+    
+    /**
+     * Fetch an image, then downscale and/or transcode it to match `transform`.
+     */
+    @Throws(ProxyException::class)override fun `fetchImageTransformed`(`url`: kotlin.String, `headers`: Map<kotlin.String, kotlin.String>?, `transform`: ImageTransform): ImageResponse {
+            return FfiConverterTypeImageResponse.lift(
+    callWithHandle {
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_fetch_image_transformed(
+        it,
+        FfiConverterString.lower(`url`),FfiConverterOptionalMapStringString.lower(`headers`),FfiConverterTypeImageTransform.lower(`transform`),_status)
+}
+    }
+    )
+    }
+    
+
+    
+    /**
+     * Carry requests through `proxy` instead of WARP, or through WARP again
+     * with `None`. A running tunnel or proxy network is torn down and the
+     * next request takes the new path; requests already under way finish
+     * on the old one.
+     */
+    @Throws(ProxyException::class)override fun `setUpstreamProxy`(`proxy`: UpstreamProxy?)
+        = 
+    callWithHandle {
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_set_upstream_proxy(
+        it,
+        FfiConverterOptionalTypeUpstreamProxy.lower(`proxy`),_status)
+}
+    }
+    
+    
+
+    
+    /**
+     * Replace the hosts [`Self::warm_up`] resolves ahead of time.
+     *
+     * Names are lowercased; anything that is not a valid hostname or IPv4
+     * address is dropped.
+     */
+    @Throws(ProxyException::class)override fun `setWarmUpHosts`(`hosts`: List<kotlin.String>)
+        = 
+    callWithHandle {
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_set_warm_up_hosts(
+        it,
+        FfiConverterSequenceString.lower(`hosts`),_status)
+}
+    }
+    
+    
+
+    
+    /**
+     * Provision WARP if needed, perform the WireGuard handshake and resolve
+     * the warm-up hosts, in the background.
+     *
+     * Returns immediately. Failures are not reported beyond the proxy's
+     * status and event listener; the first fetch simply tries again. Nothing
+     * happens while the network is paused.
+     */
+    @Throws(ProxyException::class)override fun `warmUp`()
+        = 
+    callWithHandle {
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyclient_warm_up(
+        it,
+        _status)
+}
+    }
+    
+    
+
+    
+
+    
+
+
+    
+    
+    /**
+     * @suppress
+     */
+    companion object
+    
+}
+
+
+/**
+ * @suppress
+ */
+public object FfiConverterTypeProxyClient: FfiConverter<ProxyClient, Long> {
+    override fun lower(value: ProxyClient): Long {
+        return value.uniffiCloneHandle()
+    }
+
+    override fun lift(value: Long): ProxyClient {
+        return ProxyClient(UniffiWithHandle, value)
+    }
+
+    override fun read(buf: ByteBuffer): ProxyClient {
+        return lift(buf.getLong())
+    }
+
+    override fun allocationSize(value: ProxyClient) = 8UL
+
+    override fun write(value: ProxyClient, buf: ByteBuffer) {
+        buf.putLong(lower(value))
+    }
+}
+
+
+// This template implements a class for working with a Rust struct via a handle
+// to the live Rust struct on the other side of the FFI.
+//
+// There's some subtlety here, because we have to be careful not to operate on a Rust
+// struct after it has been dropped, and because we must expose a public API for freeing
+// theq Kotlin wrapper object in lieu of reliable finalizers. The core requirements are:
+//
+//   * Each instance holds an opaque handle to the underlying Rust struct.
+//     Method calls need to read this handle from the object's state and pass it in to
+//     the Rust FFI.
+//
+//   * When an instance is no longer needed, its handle should be passed to a
+//     special destructor function provided by the Rust FFI, which will drop the
+//     underlying Rust struct.
+//
+//   * Given an instance, calling code is expected to call the special
+//     `destroy` method in order to free it after use, either by calling it explicitly
+//     or by using a higher-level helper like the `use` method. Failing to do so risks
+//     leaking the underlying Rust struct.
+//
+//   * We can't assume that calling code will do the right thing, and must be prepared
+//     to handle Kotlin method calls executing concurrently with or even after a call to
+//     `destroy`, and to handle multiple (possibly concurrent!) calls to `destroy`.
+//
+//   * We must never allow Rust code to operate on the underlying Rust struct after
+//     the destructor has been called, and must never call the destructor more than once.
+//     Doing so may trigger memory unsafety.
+//
+//   * To mitigate many of the risks of leaking memory and use-after-free unsafety, a `Cleaner`
+//     is implemented to call the destructor when the Kotlin object becomes unreachable.
+//     This is done in a background thread. This is not a panacea, and client code should be aware that
+//      1. the thread may starve if some there are objects that have poorly performing
+//     `drop` methods or do significant work in their `drop` methods.
+//      2. the thread is shared across the whole library. This can be tuned by using `android_cleaner = true`,
+//         or `android = true` in the [`kotlin` section of the `uniffi.toml` file](https://mozilla.github.io/uniffi-rs/kotlin/configuration.html).
+//
+// If we try to implement this with mutual exclusion on access to the handle, there is the
+// possibility of a race between a method call and a concurrent call to `destroy`:
+//
+//    * Thread A starts a method call, reads the value of the handle, but is interrupted
+//      before it can pass the handle over the FFI to Rust.
+//    * Thread B calls `destroy` and frees the underlying Rust struct.
+//    * Thread A resumes, passing the already-read handle value to Rust and triggering
+//      a use-after-free.
+//
+// One possible solution would be to use a `ReadWriteLock`, with each method call taking
+// a read lock (and thus allowed to run concurrently) and the special `destroy` method
+// taking a write lock (and thus blocking on live method calls). However, we aim not to
+// generate methods with any hidden blocking semantics, and a `destroy` method that might
+// block if called incorrectly seems to meet that bar.
+//
+// So, we achieve our goals by giving each instance an associated `AtomicLong` counter to track
+// the number of in-flight method calls, and an `AtomicBoolean` flag to indicate whether `destroy`
+// has been called. These are updated according to the following rules:
+//
+//    * The initial value of the counter is 1, indicating a live object with no in-flight calls.
+//      The initial value for the flag is false.
+//
+//    * At the start of each method call, we atomically check the counter.
+//      If it is 0 then the underlying Rust struct has already been destroyed and the call is aborted.
+//      If it is nonzero them we atomically increment it by 1 and proceed with the method call.
+//
+//    * At the end of each method call, we atomically decrement and check the counter.
+//      If it has reached zero then we destroy the underlying Rust struct.
+//
+//    * When `destroy` is called, we atomically flip the flag from false to true.
+//      If the flag was already true we silently fail.
+//      Otherwise we atomically decrement and check the counter.
+//      If it has reached zero then we destroy the underlying Rust struct.
+//
+// Astute readers may observe that this all sounds very similar to the way that Rust's `Arc<T>` works,
+// and indeed it is, with the addition of a flag to guard against multiple calls to `destroy`.
+//
+// The overall effect is that the underlying Rust struct is destroyed only when `destroy` has been
+// called *and* all in-flight method calls have completed, avoiding violating any of the expectations
+// of the underlying Rust code.
+//
+// This makes a cleaner a better alternative to _not_ calling `destroy()` as
+// and when the object is finished with, but the abstraction is not perfect: if the Rust object's `drop`
+// method is slow, and/or there are many objects to cleanup, and it's on a low end Android device, then the cleaner
+// thread may be starved, and the app will leak memory.
+//
+// In this case, `destroy`ing manually may be a better solution.
+//
+// The cleaner can live side by side with the manual calling of `destroy`. In the order of responsiveness, uniffi objects
+// with Rust peers are reclaimed:
+//
+// 1. By calling the `destroy` method of the object, which calls `rustObject.free()`. If that doesn't happen:
+// 2. When the object becomes unreachable, AND the Cleaner thread gets to call `rustObject.free()`. If the thread is starved then:
+// 3. The memory is reclaimed when the process terminates.
+//
+// [1] https://stackoverflow.com/questions/24376768/can-java-finalize-an-object-when-it-is-still-in-scope/24380219
+//
+
+
+/**
+ * Receives [`ProxyEvent`]s from one client.
+ */
+public interface ProxyEventListener {
+    
+    /**
+     * Called on the client's event thread, one event at a time.
+     */
+    fun `onEvent`(`event`: ProxyEvent)
+    
+    companion object
+}
+
+/**
+ * Receives [`ProxyEvent`]s from one client.
+ */
+open class ProxyEventListenerImpl: Disposable, AutoCloseable, ProxyEventListener
+{
+
+    @Suppress("UNUSED_PARAMETER")
+    /**
+     * @suppress
+     */
+    constructor(withHandle: UniffiWithHandle, handle: Long) {
+        this.handle = handle
+        this.cleanable = UniffiLib.CLEANER.register(this, UniffiCleanAction(handle))
+    }
+
+    /**
+     * @suppress
+     *
+     * This constructor can be used to instantiate a fake object. Only used for tests. Any
+     * attempt to actually use an object constructed this way will fail as there is no
+     * connected Rust object.
+     */
+    @Suppress("UNUSED_PARAMETER")
+    constructor(noHandle: NoHandle) {
+        this.handle = 0
+        this.cleanable = null
+    }
+
+    protected val handle: Long
+    protected val cleanable: UniffiCleaner.Cleanable?
+
+    private val wasDestroyed = AtomicBoolean(false)
+    private val callCounter = AtomicLong(1)
+
+    override fun destroy() {
+        // Only allow a single call to this method.
+        // TODO: maybe we should log a warning if called more than once?
+        if (this.wasDestroyed.compareAndSet(false, true)) {
+            // This decrement always matches the initial count of 1 given at creation time.
+            if (this.callCounter.decrementAndGet() == 0L) {
+                cleanable?.clean()
+            }
+        }
+    }
+
+    @Synchronized
+    override fun close() {
+        this.destroy()
+    }
+
+    internal inline fun <R> callWithHandle(block: (handle: Long) -> R): R {
+        // Check and increment the call counter, to keep the object alive.
+        // This needs a compare-and-set retry loop in case of concurrent updates.
+        do {
+            val c = this.callCounter.get()
+            if (c == 0L) {
+                throw IllegalStateException("${this.javaClass.simpleName} object has already been destroyed")
+            }
+            if (c == Long.MAX_VALUE) {
+                throw IllegalStateException("${this.javaClass.simpleName} call counter would overflow")
+            }
+        } while (! this.callCounter.compareAndSet(c, c + 1L))
+        // Now we can safely do the method call without the handle being freed concurrently.
+        try {
+            return block(this.uniffiCloneHandle())
+        } finally {
+            // This decrement always matches the increment we performed above.
+            if (this.callCounter.decrementAndGet() == 0L) {
+                cleanable?.clean()
+            }
+        }
+    }
+
+    // Use a static inner class instead of a closure so as not to accidentally
+    // capture `this` as part of the cleanable's action.
+    private class UniffiCleanAction(private val handle: Long) : Runnable {
+        override fun run() {
+            if (handle == 0.toLong()) {
+                // Fake object created with `NoHandle`, don't try to free.
+                return;
+            }
+            uniffiRustCall { status ->
+                UniffiLib.uniffi_letterbox_proxy_fn_free_proxyeventlistener(handle, status)
+            }
+        }
+    }
+
+    /**
+     * @suppress
+     */
+    fun uniffiCloneHandle(): Long {
+        if (handle == 0.toLong()) {
+            throw InternalException("uniffiCloneHandle() called on NoHandle object");
+        }
+        return uniffiRustCall() { status ->
+            UniffiLib.uniffi_letterbox_proxy_fn_clone_proxyeventlistener(handle, status)
+        }
+    }
+
+    
+    /**
+     * Called on the client's event thread, one event at a time.
+     */override fun `onEvent`(`event`: ProxyEvent)
+        = 
+    callWithHandle {
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyeventlistener_on_event(
+        it,
+        FfiConverterTypeProxyEvent.lower(`event`),_status)
+}
+    }
+    
+    
+
+    
+
+    
+
+
+    
+    
+    /**
+     * @suppress
+     */
+    companion object
+    
+}
+
+
+
+// Put the implementation in an object so we don't pollute the top-level namespace
+internal object uniffiCallbackInterfaceProxyEventListener {
+    internal object `onEvent`: UniffiCallbackInterfaceProxyEventListenerMethod0 {
+        override fun callback(`uniffiHandle`: Long,`event`: RustBuffer.ByValue,`uniffiOutReturn`: Pointer,uniffiCallStatus: UniffiRustCallStatus,) {
+            val uniffiObj = FfiConverterTypeProxyEventListener.handleMap.get(uniffiHandle)
+            val makeCall = { ->
+                uniffiObj.`onEvent`(
+                    FfiConverterTypeProxyEvent.lift(`event`),
+                )
+            }
+            val writeReturn = { _: Unit -> Unit }
+            uniffiTraitInterfaceCall(uniffiCallStatus, makeCall, writeReturn)
+        }
+    }
+
+    internal object uniffiFree: UniffiCallbackInterfaceFree {
+        override fun callback(handle: Long) {
+            FfiConverterTypeProxyEventListener.handleMap.remove(handle)
+        }
+    }
+
+    internal object uniffiClone: UniffiCallbackInterfaceClone {
+        override fun callback(handle: Long): Long {
+            return FfiConverterTypeProxyEventListener.handleMap.clone(handle)
+        }
+    }
+
+    internal var vtable = UniffiVTableCallbackInterfaceProxyEventListener.UniffiByValue(
+        uniffiFree,
+        uniffiClone,
+        `onEvent`,
+    )
+
+    // Registers the foreign callback with the Rust side.
+    // This method is generated for each callback interface.
+    internal fun register(lib: UniffiLib) {
+        lib.uniffi_letterbox_proxy_fn_init_callback_vtable_proxyeventlistener(vtable)
+    }
+}
+
+/**
+ * @suppress
+ */
+public object FfiConverterTypeProxyEventListener: FfiConverter<ProxyEventListener, Long> {
+    internal val handleMap = UniffiHandleMap<ProxyEventListener>()
+
+    override fun lower(value: ProxyEventListener): Long {
+        if (value is ProxyEventListenerImpl) {
+             // Rust-implemented object.  Clone the handle and return it
+            return value.uniffiCloneHandle()
+         } else {
+            // Kotlin object, generate a new vtable handle and return that.
+            return handleMap.insert(value)
+         }
+    }
+
+    override fun lift(value: Long): ProxyEventListener {
+        if ((value and 1.toLong()) == 0.toLong()) {
+            // Rust-generated handle, construct a new class that uses the handle to implement the
+            // interface
+            return ProxyEventListenerImpl(UniffiWithHandle, value)
+        } else {
+            // Kotlin-generated handle, get the object from the handle map
+            return handleMap.remove(value)
+        }
+    }
+
+    override fun read(buf: ByteBuffer): ProxyEventListener {
+        return lift(buf.getLong())
+    }
+
+    override fun allocationSize(value: ProxyEventListener) = 8UL
+
+    override fun write(value: ProxyEventListener, buf: ByteBuffer) {
+        buf.putLong(lower(value))
+    }
+}
+
+
+// This template implements a class for working with a Rust struct via a handle
+// to the live Rust struct on the other side of the FFI.
+//
+// There's some subtlety here, because we have to be careful not to operate on a Rust
+// struct after it has been dropped, and because we must expose a public API for freeing
+// theq Kotlin wrapper object in lieu of reliable finalizers. The core requirements are:
+//
+//   * Each instance holds an opaque handle to the underlying Rust struct.
+//     Method calls need to read this handle from the object's state and pass it in to
+//     the Rust FFI.
+//
+//   * When an instance is no longer needed, its handle should be passed to a
+//     special destructor function provided by the Rust FFI, which will drop the
+//     underlying Rust struct.
+//
+//   * Given an instance, calling code is expected to call the special
+//     `destroy` method in order to free it after use, either by calling it explicitly
+//     or by using a higher-level helper like the `use` method. Failing to do so risks
+//     leaking the underlying Rust struct.
+//
+//   * We can't assume that calling code will do the right thing, and must be prepared
+//     to handle Kotlin method calls executing concurrently with or even after a call to
+//     `destroy`, and to handle multiple (possibly concurrent!) calls to `destroy`.
+//
+//   * We must never allow Rust code to operate on the underlying Rust struct after
+//     the destructor has been called, and must never call the destructor more than once.
+//     Doing so may trigger memory unsafety.
+//
+//   * To mitigate many of the risks of leaking memory and use-after-free unsafety, a `Cleaner`
+//     is implemented to call the destructor when the Kotlin object becomes unreachable.
+//     This is done in a background thread. This is not a panacea, and client code should be aware that
+//      1. the thread may starve if some there are objects that have poorly performing
+//     `drop` methods or do significant work in their `drop` methods.
+//      2. the thread is shared across the whole library. This can be tuned by using `android_cleaner = true`,
+//         or `android = true` in the [`kotlin` section of the `uniffi.toml` file](https://mozilla.github.io/uniffi-rs/kotlin/configuration.html).
+//
+// If we try to implement this with mutual exclusion on access to the handle, there is the
+// possibility of a race between a method call and a concurrent call to `destroy`:
+//
+//    * Thread A starts a method call, reads the value of the handle, but is interrupted
+//      before it can pass the handle over the FFI to Rust.
+//    * Thread B calls `destroy` and frees the underlying Rust struct.
+//    * Thread A resumes, passing the already-read handle value to Rust and triggering
+//      a use-after-free.
+//
+// One possible solution would be to use a `ReadWriteLock`, with each method call taking
+// a read lock (and thus allowed to run concurrently) and the special `destroy` method
+// taking a write lock (and thus blocking on live method calls). However, we aim not to
+// generate methods with any hidden blocking semantics, and a `destroy` method that might
+// block if called incorrectly seems to meet that bar.
+//
+// So, we achieve our goals by giving each instance an associated `AtomicLong` counter to track
+// the number of in-flight method calls, and an `AtomicBoolean` flag to indicate whether `destroy`
+// has been called. These are updated according to the following rules:
+//
+//    * The initial value of the counter is 1, indicating a live object with no in-flight calls.
+//      The initial value for the flag is false.
+//
+//    * At the start of each method call, we atomically check the counter.
+//      If it is 0 then the underlying Rust struct has already been destroyed and the call is aborted.
+//      If it is nonzero them we atomically increment it by 1 and proceed with the method call.
+//
+//    * At the end of each method call, we atomically decrement and check the counter.
+//      If it has reached zero then we destroy the underlying Rust struct.
+//
+//    * When `destroy` is called, we atomically flip the flag from false to true.
+//      If the flag was already true we silently fail.
+//      Otherwise we atomically decrement and check the counter.
+//      If it has reached zero then we destroy the underlying Rust struct.
+//
+// Astute readers may observe that this all sounds very similar to the way that Rust's `Arc<T>` works,
+// and indeed it is, with the addition of a flag to guard against multiple calls to `destroy`.
+//
+// The overall effect is that the underlying Rust struct is destroyed only when `destroy` has been
+// called *and* all in-flight method calls have completed, avoiding violating any of the expectations
+// of the underlying Rust code.
+//
+// This makes a cleaner a better alternative to _not_ calling `destroy()` as
+// and when the object is finished with, but the abstraction is not perfect: if the Rust object's `drop`
+// method is slow, and/or there are many objects to cleanup, and it's on a low end Android device, then the cleaner
+// thread may be starved, and the app will leak memory.
+//
+// In this case, `destroy`ing manually may be a better solution.
+//
+// The cleaner can live side by side with the manual calling of `destroy`. In the order of responsiveness, uniffi objects
+// with Rust peers are reclaimed:
+//
+// 1. By calling the `destroy` method of the object, which calls `rustObject.free()`. If that doesn't happen:
+// 2. When the object becomes unreachable, AND the Cleaner thread gets to call `rustObject.free()`. If the thread is starved then:
+// 3. The memory is reclaimed when the process terminates.
+//
+// [1] https://stackoverflow.com/questions/24376768/can-java-finalize-an-object-when-it-is-still-in-scope/24380219
+//
+
+
+/**
+ * A TCP connection through the tunnel, closed when dropped.
+ */
+public interface ProxyStreamInterface {
+    
+    /**
+     * Close the connection; reads and writes fail afterwards.
+     */
+    fun `close`()
+    
+    /**
+     * Up to `max_len` bytes (at most [`MAX_READ`]), waiting up to
+     * `timeout_ms` for some to arrive. Empty once the server closed the
+     * stream; fails with [`ProxyError::Timeout`] if nothing arrived in time.
+     */
+    fun `read`(`maxLen`: kotlin.UInt, `timeoutMs`: kotlin.UInt): kotlin.ByteArray
+    
+    /**
+     * Send all of `data`, failing with [`ProxyError::Timeout`] if the
+     * connection does not take it within `timeout_ms`.
+     */
+    fun `write`(`data`: kotlin.ByteArray, `timeoutMs`: kotlin.UInt)
+    
+    companion object
+}
+
+/**
+ * A TCP connection through the tunnel, closed when dropped.
+ */
+open class ProxyStream: Disposable, AutoCloseable, ProxyStreamInterface
+{
+
+    @Suppress("UNUSED_PARAMETER")
+    /**
+     * @suppress
+     */
+    constructor(withHandle: UniffiWithHandle, handle: Long) {
+        this.handle = handle
+        this.cleanable = UniffiLib.CLEANER.register(this, UniffiCleanAction(handle))
+    }
+
+    /**
+     * @suppress
+     *
+     * This constructor can be used to instantiate a fake object. Only used for tests. Any
+     * attempt to actually use an object constructed this way will fail as there is no
+     * connected Rust object.
+     */
+    @Suppress("UNUSED_PARAMETER")
+    constructor(noHandle: NoHandle) {
+        this.handle = 0
+        this.cleanable = null
+    }
+
+    protected val handle: Long
+    protected val cleanable: UniffiCleaner.Cleanable?
+
+    private val wasDestroyed = AtomicBoolean(false)
+    private val callCounter = AtomicLong(1)
+
+    override fun destroy() {
+        // Only allow a single call to this method.
+        // TODO: maybe we should log a warning if called more than once?
+        if (this.wasDestroyed.compareAndSet(false, true)) {
+            // This decrement always matches the initial count of 1 given at creation time.
+            if (this.callCounter.decrementAndGet() == 0L) {
+                cleanable?.clean()
+            }
+        }
+    }
+
+    @Synchronized
+    override fun close() {
+        this.destroy()
+    }
+
+    internal inline fun <R> callWithHandle(block: (handle: Long) -> R): R {
+        // Check and increment the call counter, to keep the object alive.
+        // This needs a compare-and-set retry loop in case of concurrent updates.
+        do {
+            val c = this.callCounter.get()
+            if (c == 0L) {
+                throw IllegalStateException("${this.javaClass.simpleName} object has already been destroyed")
+            }
+            if (c == Long.MAX_VALUE) {
+                throw IllegalStateException("${this.javaClass.simpleName} call counter would overflow")
+            }
+        } while (! this.callCounter.compareAndSet(c, c + 1L))
+        // Now we can safely do the method call without the handle being freed concurrently.
+        try {
+            return block(this.uniffiCloneHandle())
+        } finally {
+            // This decrement always matches the increment we performed above.
+            if (this.callCounter.decrementAndGet() == 0L) {
+                cleanable?.clean()
+            }
+        }
+    }
+
+    // Use a static inner class instead of a closure so as not to accidentally
+    // capture `this` as part of the cleanable's action.
+    private class UniffiCleanAction(private val handle: Long) : Runnable {
+        override fun run() {
+            if (handle == 0.toLong()) {
+                // Fake object created with `NoHandle`, don't try to free.
+                return;
+            }
+            uniffiRustCall { status ->
+                UniffiLib.uniffi_letterbox_proxy_fn_free_proxystream(handle, status)
+            }
+        }
+    }
+
+    /**
+     * @suppress
+     */
+    fun uniffiCloneHandle(): Long {
+        if (handle == 0.toLong()) {
+            throw InternalException("uniffiCloneHandle() called on NoHandle object");
+        }
+        return uniffiRustCall() { status ->
+            UniffiLib.uniffi_letterbox_proxy_fn_clone_proxystream(handle, status)
+        }
+    }
+
+    
+    /**
+     * Close the connection; reads and writes fail afterwards.
+     */override fun `close`()
+        = 
+    callWithHandle {
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxystream_close(
+        it,
+        _status)
+}
+    }
+    
+    
+
+    
+    /**
+     * Up to `max_len` bytes (at most [`MAX_READ`]), waiting up to
+     * `timeout_ms` for some to arrive. Empty once the server closed the
+     * stream; fails with [`ProxyError::Timeout`] if nothing arrived in time.
+     */
+    @Throws(ProxyException::class)override fun `read`(`maxLen`: kotlin.UInt, `timeoutMs`: kotlin.UInt): kotlin.ByteArray {
+            return FfiConverterByteArray.lift(
+    callWithHandle {
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxystream_read(
+        it,
+        FfiConverterUInt.lower(`maxLen`),FfiConverterUInt.lower(`timeoutMs`),_status)
+}
+    }
+    )
+    }
+    
+
+    
+    /**
+     * Send all of `data`, failing with [`ProxyError::Timeout`] if the
+     * connection does not take it within `timeout_ms`.
+     */
+    @Throws(ProxyException::class)override fun `write`(`data`: kotlin.ByteArray, `timeoutMs`: kotlin.UInt)
+        = 
+    callWithHandle {
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxystream_write(
+        it,
+        FfiConverterByteArray.lower(`data`),FfiConverterUInt.lower(`timeoutMs`),_status)
+}
+    }
+    
+    
+
+    
+
+    
+
+
+    
+    
+    /**
+     * @suppress
+     */
+    companion object
+    
+}
+
+
+/**
+ * @suppress
+ */
+public object FfiConverterTypeProxyStream: FfiConverter<ProxyStream, Long> {
+    override fun lower(value: ProxyStream): Long {
+        return value.uniffiCloneHandle()
+    }
+
+    override fun lift(value: Long): ProxyStream {
+        return ProxyStream(UniffiWithHandle, value)
+    }
+
+    override fun read(buf: ByteBuffer): ProxyStream {
+        return lift(buf.getLong())
+    }
+
+    override fun allocationSize(value: ProxyStream) = 8UL
+
+    override fun write(value: ProxyStream, buf: ByteBuffer) {
+        buf.putLong(lower(value))
+    }
+}
+
+
+// This template implements a class for working with a Rust struct via a handle
+// to the live Rust struct on the other side of the FFI.
+//
+// There's some subtlety here, because we have to be careful not to operate on a Rust
+// struct after it has been dropped, and because we must expose a public API for freeing
+// theq Kotlin wrapper object in lieu of reliable finalizers. The core requirements are:
+//
+//   * Each instance holds an opaque handle to the underlying Rust struct.
+//     Method calls need to read this handle from the object's state and pass it in to
+//     the Rust FFI.
+//
+//   * When an instance is no longer needed, its handle should be passed to a
+//     special destructor function provided by the Rust FFI, which will drop the
+//     underlying Rust struct.
+//
+//   * Given an instance, calling code is expected to call the special
+//     `destroy` method in order to free it after use, either by calling it explicitly
+//     or by using a higher-level helper like the `use` method. Failing to do so risks
+//     leaking the underlying Rust struct.
+//
+//   * We can't assume that calling code will do the right thing, and must be prepared
+//     to handle Kotlin method calls executing concurrently with or even after a call to
+//     `destroy`, and to handle multiple (possibly concurrent!) calls to `destroy`.
+//
+//   * We must never allow Rust code to operate on the underlying Rust struct after
+//     the destructor has been called, and must never call the destructor more than once.
+//     Doing so may trigger memory unsafety.
+//
+//   * To mitigate many of the risks of leaking memory and use-after-free unsafety, a `Cleaner`
+//     is implemented to call the destructor when the Kotlin object becomes unreachable.
+//     This is done in a background thread. This is not a panacea, and client code should be aware that
+//      1. the thread may starve if some there are objects that have poorly performing
+//     `drop` methods or do significant work in their `drop` methods.
+//      2. the thread is shared across the whole library. This can be tuned by using `android_cleaner = true`,
+//         or `android = true` in the [`kotlin` section of the `uniffi.toml` file](https://mozilla.github.io/uniffi-rs/kotlin/configuration.html).
+//
+// If we try to implement this with mutual exclusion on access to the handle, there is the
+// possibility of a race between a method call and a concurrent call to `destroy`:
+//
+//    * Thread A starts a method call, reads the value of the handle, but is interrupted
+//      before it can pass the handle over the FFI to Rust.
+//    * Thread B calls `destroy` and frees the underlying Rust struct.
+//    * Thread A resumes, passing the already-read handle value to Rust and triggering
+//      a use-after-free.
+//
+// One possible solution would be to use a `ReadWriteLock`, with each method call taking
+// a read lock (and thus allowed to run concurrently) and the special `destroy` method
+// taking a write lock (and thus blocking on live method calls). However, we aim not to
+// generate methods with any hidden blocking semantics, and a `destroy` method that might
+// block if called incorrectly seems to meet that bar.
+//
+// So, we achieve our goals by giving each instance an associated `AtomicLong` counter to track
+// the number of in-flight method calls, and an `AtomicBoolean` flag to indicate whether `destroy`
+// has been called. These are updated according to the following rules:
+//
+//    * The initial value of the counter is 1, indicating a live object with no in-flight calls.
+//      The initial value for the flag is false.
+//
+//    * At the start of each method call, we atomically check the counter.
+//      If it is 0 then the underlying Rust struct has already been destroyed and the call is aborted.
+//      If it is nonzero them we atomically increment it by 1 and proceed with the method call.
+//
+//    * At the end of each method call, we atomically decrement and check the counter.
+//      If it has reached zero then we destroy the underlying Rust struct.
+//
+//    * When `destroy` is called, we atomically flip the flag from false to true.
+//      If the flag was already true we silently fail.
+//      Otherwise we atomically decrement and check the counter.
+//      If it has reached zero then we destroy the underlying Rust struct.
+//
+// Astute readers may observe that this all sounds very similar to the way that Rust's `Arc<T>` works,
+// and indeed it is, with the addition of a flag to guard against multiple calls to `destroy`.
+//
+// The overall effect is that the underlying Rust struct is destroyed only when `destroy` has been
+// called *and* all in-flight method calls have completed, avoiding violating any of the expectations
+// of the underlying Rust code.
+//
+// This makes a cleaner a better alternative to _not_ calling `destroy()` as
+// and when the object is finished with, but the abstraction is not perfect: if the Rust object's `drop`
+// method is slow, and/or there are many objects to cleanup, and it's on a low end Android device, then the cleaner
+// thread may be starved, and the app will leak memory.
+//
+// In this case, `destroy`ing manually may be a better solution.
+//
+// The cleaner can live side by side with the manual calling of `destroy`. In the order of responsiveness, uniffi objects
+// with Rust peers are reclaimed:
+//
+// 1. By calling the `destroy` method of the object, which calls `rustObject.free()`. If that doesn't happen:
+// 2. When the object becomes unreachable, AND the Cleaner thread gets to call `rustObject.free()`. If the thread is starved then:
+// 3. The memory is reclaimed when the process terminates.
+//
+// [1] https://stackoverflow.com/questions/24376768/can-java-finalize-an-object-when-it-is-still-in-scope/24380219
+//
+
+
+/**
+ * Excludes the proxy's WireGuard socket from VPN routing.
+ */
+public interface SocketProtector {
+    
+    /**
+     * Protect the UDP socket `fd`. Called on the tunnel thread before the
+     * socket sends anything; the proxy keeps owning the descriptor, so do
+     * not close or adopt it. Returning `false` fails the tunnel start rather
+     * than let the handshake take the unprotected route.
+     */
+    fun `protect`(`fd`: kotlin.Int): kotlin.Boolean
+    
+    companion object
+}
+
+/**
+ * Excludes the proxy's WireGuard socket from VPN routing.
+ */
+open class SocketProtectorImpl: Disposable, AutoCloseable, SocketProtector
+{
+
+    @Suppress("UNUSED_PARAMETER")
+    /**
+     * @suppress
+     */
+    constructor(withHandle: UniffiWithHandle, handle: Long) {
+        this.handle = handle
+        this.cleanable = UniffiLib.CLEANER.register(this, UniffiCleanAction(handle))
+    }
+
+    /**
+     * @suppress
+     *
+     * This constructor can be used to instantiate a fake object. Only used for tests. Any
+     * attempt to actually use an object constructed this way will fail as there is no
+     * connected Rust object.
+     */
+    @Suppress("UNUSED_PARAMETER")
+    constructor(noHandle: NoHandle) {
+        this.handle = 0
+        this.cleanable = null
+    }
+
+    protected val handle: Long
+    protected val cleanable: UniffiCleaner.Cleanable?
+
+    private val wasDestroyed = AtomicBoolean(false)
+    private val callCounter = AtomicLong(1)
+
+    override fun destroy() {
+        // Only allow a single call to this method.
+        // TODO: maybe we should log a warning if called more than once?
+        if (this.wasDestroyed.compareAndSet(false, true)) {
+            // This decrement always matches the initial count of 1 given at creation time.
+            if (this.callCounter.decrementAndGet() == 0L) {
+                cleanable?.clean()
+            }
+        }
+    }
+
+    @Synchronized
+    override fun close() {
+        this.destroy()
+    }
+
+    internal inline fun <R> callWithHandle(block: (handle: Long) -> R): R {
+        // Check and increment the call counter, to keep the object alive.
+        // This needs a compare-and-set retry loop in case of concurrent updates.
+        do {
+            val c = this.callCounter.get()
+            if (c == 0L) {
+                throw IllegalStateException("${this.javaClass.simpleName} object has already been destroyed")
+            }
+            if (c == Long.MAX_VALUE) {
+                throw IllegalStateException("${this.javaClass.simpleName} call counter would overflow")
+            }
+        } while (! this.callCounter.compareAndSet(c, c + 1L))
+        // Now we can safely do the method call without the handle being freed concurrently.
+        try {
+            return block(this.uniffiCloneHandle())
+        } finally {
+            // This decrement always matches the increment we performed above.
+            if (this.callCounter.decrementAndGet() == 0L) {
+                cleanable?.clean()
+            }
+        }
+    }
+
+    // Use a static inner class instead of a closure so as not to accidentally
+    // capture `this` as part of the cleanable's action.
+    private class UniffiCleanAction(private val handle: Long) : Runnable {
+        override fun run() {
+            if (handle == 0.toLong()) {
+                // Fake object created with `NoHandle`, don't try to free.
+                return;
+            }
+            uniffiRustCall { status ->
+                UniffiLib.uniffi_letterbox_proxy_fn_free_socketprotector(handle, status)
+            }
+        }
+    }
+
+    /**
+     * @suppress
+     */
+    fun uniffiCloneHandle(): Long {
+        if (handle == 0.toLong()) {
+            throw InternalException("uniffiCloneHandle() called on NoHandle object");
+        }
+        return uniffiRustCall() { status ->
+            UniffiLib.uniffi_letterbox_proxy_fn_clone_socketprotector(handle, status)
+        }
+    }
+
+    
+    /**
+     * Protect the UDP socket `fd`. Called on the tunnel thread before the
+     * socket sends anything; the proxy keeps owning the descriptor, so do
+     * not close or adopt it. Returning `false` fails the tunnel start rather
+     * than let the handshake take the unprotected route.
+     */override fun `protect`(`fd`: kotlin.Int): kotlin.Boolean {
+            return FfiConverterBoolean.lift(
+    callWithHandle {
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_socketprotector_protect(
+        it,
+        FfiConverterInt.lower(`fd`),_status)
+}
+    }
+    )
+    }
+    
+
+    
+
+    
+
+
+    
+    
+    /**
+     * @suppress
+     */
+    companion object
+    
+}
+
+
+
+// Put the implementation in an object so we don't pollute the top-level namespace
+internal object uniffiCallbackInterfaceSocketProtector {
+    internal object `protect`: UniffiCallbackInterfaceSocketProtectorMethod0 {
+        override fun callback(`uniffiHandle`: Long,`fd`: Int,`uniffiOutReturn`: ByteByReference,uniffiCallStatus: UniffiRustCallStatus,) {
+            val uniffiObj = FfiConverterTypeSocketProtector.handleMap.get(uniffiHandle)
+            val makeCall = { ->
+                uniffiObj.`protect`(
+                    FfiConverterInt.lift(`fd`),
+                )
+            }
+            val writeReturn = { value: kotlin.Boolean -> uniffiOutReturn.setValue(FfiConverterBoolean.lower(value)) }
+            uniffiTraitInterfaceCall(uniffiCallStatus, makeCall, writeReturn)
+        }
+    }
+
+    internal object uniffiFree: UniffiCallbackInterfaceFree {
+        override fun callback(handle: Long) {
+            FfiConverterTypeSocketProtector.handleMap.remove(handle)
+        }
+    }
+
+    internal object uniffiClone: UniffiCallbackInterfaceClone {
+        override fun callback(handle: Long): Long {
+            return FfiConverterTypeSocketProtector.handleMap.clone(handle)
+        }
+    }
+
+    internal var vtable = UniffiVTableCallbackInterfaceSocketProtector.UniffiByValue(
+        uniffiFree,
+        uniffiClone,
+        `protect`,
+    )
+
+    // Registers the foreign callback with the Rust side.
+    // This method is generated for each callback interface.
+    internal fun register(lib: UniffiLib) {
+        lib.uniffi_letterbox_proxy_fn_init_callback_vtable_socketprotector(vtable)
+    }
+}
+
+/**
+ * @suppress
+ */
+public object FfiConverterTypeSocketProtector: FfiConverter<SocketProtector, Long> {
+    internal val handleMap = UniffiHandleMap<SocketProtector>()
+
+    override fun lower(value: SocketProtector): Long {
+        if (value is SocketProtectorImpl) {
+             // Rust-implemented object.  Clone the handle and return it
+            return value.uniffiCloneHandle()
+         } else {
+            // Kotlin object, generate a new vtable handle and return that.
+            return handleMap.insert(value)
+         }
+    }
+
+    override fun lift(value: Long): SocketProtector {
+        if ((value and 1.toLong()) == 0.toLong()) {
+            // Rust-generated handle, construct a new class that uses the handle to implement the
+            // interface
+            return SocketProtectorImpl(UniffiWithHandle, value)
+        } else {
+            // Kotlin-generated handle, get the object from the handle map
+            return handleMap.remove(value)
+        }
+    }
+
+    override fun read(buf: ByteBuffer): SocketProtector {
+        return lift(buf.getLong())
+    }
+
+    override fun allocationSize(value: SocketProtector) = 8UL
+
+    override fun write(value: SocketProtector, buf: ByteBuffer) {
+        buf.putLong(lower(value))
+    }
+}
+
+
+
+/**
+ * Result of a batch image fetch operation.
+ */
+data class BatchImageResult (
+    /**
+     * URL that was requested.
+     */
+    var `url`: kotlin.String
+    , 
+    /**
+     * Whether the fetch was successful.
+     */
+    var `success`: kotlin.Boolean
+    , 
+    /**
+     * Image response if successful.
+     */
+    var `response`: ImageResponse?
+    , 
+    /**
+     * Error message if failed.
+     */
+    var `error`: kotlin.String?
+    , 
+    /**
+     * Kind of error if failed, for matching without parsing `error`.
+     */
+    var `errorCode`: ErrorCode?
+    , 
+    /**
+     * Whether a failed fetch may succeed if tried again later.
+     */
+    var `retryable`: kotlin.Boolean
+    , 
+    /**
+     * HTTP status of a failed fetch, if the server answered.
+     */
+    var `httpStatus`: kotlin.UShort?
+    
+){
+    
+
+    
+
+    
+    companion object
+}
+
+/**
+ * @suppress
+ */
+public object FfiConverterTypeBatchImageResult: FfiConverterRustBuffer<BatchImageResult> {
+    override fun read(buf: ByteBuffer): BatchImageResult {
+        return BatchImageResult(
+            FfiConverterString.read(buf),
+            FfiConverterBoolean.read(buf),
+            FfiConverterOptionalTypeImageResponse.read(buf),
+            FfiConverterOptionalString.read(buf),
+            FfiConverterOptionalTypeErrorCode.read(buf),
+            FfiConverterBoolean.read(buf),
+            FfiConverterOptionalUShort.read(buf),
+        )
+    }
+
+    override fun allocationSize(value: BatchImageResult) = (
+            FfiConverterString.allocationSize(value.`url`) +
+            FfiConverterBoolean.allocationSize(value.`success`) +
+            FfiConverterOptionalTypeImageResponse.allocationSize(value.`response`) +
+            FfiConverterOptionalString.allocationSize(value.`error`) +
+            FfiConverterOptionalTypeErrorCode.allocationSize(value.`errorCode`) +
+            FfiConverterBoolean.allocationSize(value.`retryable`) +
+            FfiConverterOptionalUShort.allocationSize(value.`httpStatus`)
+    )
+
+    override fun write(value: BatchImageResult, buf: ByteBuffer) {
+            FfiConverterString.write(value.`url`, buf)
+            FfiConverterBoolean.write(value.`success`, buf)
+            FfiConverterOptionalTypeImageResponse.write(value.`response`, buf)
+            FfiConverterOptionalString.write(value.`error`, buf)
+            FfiConverterOptionalTypeErrorCode.write(value.`errorCode`, buf)
+            FfiConverterBoolean.write(value.`retryable`, buf)
+            FfiConverterOptionalUShort.write(value.`httpStatus`, buf)
+    }
+}
+
+
+
+/**
+ * What changes while data-saver mode is on.
+ */
+data class DataSaver (
+    /**
+     * Largest image fetched from the network, in bytes; 0 keeps the usual
+     * limit.
+     */
+    var `maxImageSize`: kotlin.ULong
+    , 
+    /**
+     * Refuse animated images fetched from the network.
+     */
+    var `blockAnimations`: kotlin.Boolean
+    
+){
+    
+
+    
+
+    
+    companion object
+}
+
+/**
+ * @suppress
+ */
+public object FfiConverterTypeDataSaver: FfiConverterRustBuffer<DataSaver> {
+    override fun read(buf: ByteBuffer): DataSaver {
+        return DataSaver(
+            FfiConverterULong.read(buf),
+            FfiConverterBoolean.read(buf),
+        )
+    }
+
+    override fun allocationSize(value: DataSaver) = (
+            FfiConverterULong.allocationSize(value.`maxImageSize`) +
+            FfiConverterBoolean.allocationSize(value.`blockAnimations`)
+    )
+
+    override fun write(value: DataSaver, buf: ByteBuffer) {
+            FfiConverterULong.write(value.`maxImageSize`, buf)
+            FfiConverterBoolean.write(value.`blockAnimations`, buf)
+    }
+}
+
+
+
+/**
+ * Settings for the persistent disk cache tier.
+ */
+data class DiskCacheOptions (
+    /**
+     * Byte budget for all cached blobs; least-recently-used entries are evicted.
+     */
+    var `maxBytes`: kotlin.ULong
+    , 
+    /**
+     * Optional 32-byte key to encrypt cached bodies at rest.
+     */
+    var `encryptionKey`: kotlin.ByteArray?
+    , 
+    /**
+     * Days after which a cached image is dropped, however often it is used
+     * (0 = kept until evicted).
+     */
+    var `maxAgeDays`: kotlin.UInt
+    
+){
+    
+
+    
+
+    
+    companion object
+}
+
+/**
+ * @suppress
+ */
+public object FfiConverterTypeDiskCacheOptions: FfiConverterRustBuffer<DiskCacheOptions> {
+    override fun read(buf: ByteBuffer): DiskCacheOptions {
+        return DiskCacheOptions(
+            FfiConverterULong.read(buf),
+            FfiConverterOptionalByteArray.read(buf),
+            FfiConverterUInt.read(buf),
+        )
+    }
+
+    override fun allocationSize(value: DiskCacheOptions) = (
+            FfiConverterULong.allocationSize(value.`maxBytes`) +
+            FfiConverterOptionalByteArray.allocationSize(value.`encryptionKey`) +
+            FfiConverterUInt.allocationSize(value.`maxAgeDays`)
+    )
+
+    override fun write(value: DiskCacheOptions, buf: ByteBuffer) {
+            FfiConverterULong.write(value.`maxBytes`, buf)
+            FfiConverterOptionalByteArray.write(value.`encryptionKey`, buf)
+            FfiConverterUInt.write(value.`maxAgeDays`, buf)
+    }
+}
+
+
+
+/**
+ * Caps on the network fetches in flight at once, across every batch and
+ * caller of one proxy.
+ */
+data class FetchConcurrency (
+    /**
+     * Most fetches in flight in total; further ones wait for a slot
+     * (0 = unlimited).
+     */
+    var `maxInFlight`: kotlin.UInt
+    , 
+    /**
+     * Most fetches in flight to any one host (0 = unlimited).
+     */
+    var `maxPerHost`: kotlin.UInt
+    
+){
+    
+
+    
+
+    
+    companion object
+}
+
+/**
+ * @suppress
+ */
+public object FfiConverterTypeFetchConcurrency: FfiConverterRustBuffer<FetchConcurrency> {
+    override fun read(buf: ByteBuffer): FetchConcurrency {
+        return FetchConcurrency(
+            FfiConverterUInt.read(buf),
+            FfiConverterUInt.read(buf),
+        )
+    }
+
+    override fun allocationSize(value: FetchConcurrency) = (
+            FfiConverterUInt.allocationSize(value.`maxInFlight`) +
+            FfiConverterUInt.allocationSize(value.`maxPerHost`)
+    )
+
+    override fun write(value: FetchConcurrency, buf: ByteBuffer) {
+            FfiConverterUInt.write(value.`maxInFlight`, buf)
+            FfiConverterUInt.write(value.`maxPerHost`, buf)
+    }
+}
+
+
+
+/**
+ * Options for [`crate::proxy_fetch_image_to_file`].
+ */
+data class FileFetchOptions (
+    /**
+     * Extra request headers (subject to the sensitive-header filter).
+     */
+    var `headers`: Map<kotlin.String, kotlin.String>?
+    , 
+    /**
+     * Replace `dest_path` if it already exists.
+     */
+    var `overwrite`: kotlin.Boolean
+    
+){
+    
+
+    
+
+    
+    companion object
+}
+
+/**
+ * @suppress
+ */
+public object FfiConverterTypeFileFetchOptions: FfiConverterRustBuffer<FileFetchOptions> {
+    override fun read(buf: ByteBuffer): FileFetchOptions {
+        return FileFetchOptions(
+            FfiConverterOptionalMapStringString.read(buf),
+            FfiConverterBoolean.read(buf),
+        )
+    }
+
+    override fun allocationSize(value: FileFetchOptions) = (
+            FfiConverterOptionalMapStringString.allocationSize(value.`headers`) +
+            FfiConverterBoolean.allocationSize(value.`overwrite`)
+    )
+
+    override fun write(value: FileFetchOptions, buf: ByteBuffer) {
+            FfiConverterOptionalMapStringString.write(value.`headers`, buf)
+            FfiConverterBoolean.write(value.`overwrite`, buf)
+    }
+}
+
+
+
+/**
+ * Metadata of an image written to disk by [`crate::proxy_fetch_image_to_file`].
+ */
+data class FileFetchResult (
+    /**
+     * MIME type of the image.
+     */
+    var `mimeType`: kotlin.String
+    , 
+    /**
+     * Bytes written to the destination file.
+     */
+    var `size`: kotlin.ULong
+    , 
+    /**
+     * Whether the image came from the cache instead of the network.
+     */
+    var `fromCache`: kotlin.Boolean
+    , 
+    /**
+     * Final URL after redirects.
+     */
+    var `finalUrl`: kotlin.String
+    
+){
+    
+
+    
+
+    
+    companion object
+}
+
+/**
+ * @suppress
+ */
+public object FfiConverterTypeFileFetchResult: FfiConverterRustBuffer<FileFetchResult> {
+    override fun read(buf: ByteBuffer): FileFetchResult {
+        return FileFetchResult(
+            FfiConverterString.read(buf),
+            FfiConverterULong.read(buf),
+            FfiConverterBoolean.read(buf),
+            FfiConverterString.read(buf),
+        )
+    }
+
+    override fun allocationSize(value: FileFetchResult) = (
+            FfiConverterString.allocationSize(value.`mimeType`) +
+            FfiConverterULong.allocationSize(value.`size`) +
+            FfiConverterBoolean.allocationSize(value.`fromCache`) +
+            FfiConverterString.allocationSize(value.`finalUrl`)
+    )
+
+    override fun write(value: FileFetchResult, buf: ByteBuffer) {
+            FfiConverterString.write(value.`mimeType`, buf)
+            FfiConverterULong.write(value.`size`, buf)
+            FfiConverterBoolean.write(value.`fromCache`, buf)
+            FfiConverterString.write(value.`finalUrl`, buf)
+    }
+}
+
+
+
+/**
+ * Data received from one host since the usage was last reset.
+ */
+data class HostBandwidth (
+    /**
+     * Host name of the requested URLs, lowercase.
+     */
+    var `host`: kotlin.String
+    , 
+    /**
+     * Bytes received through the WARP tunnel.
+     */
+    var `tunnelBytes`: kotlin.ULong
+    , 
+    /**
+     * Bytes received directly, in builds without the `tunnel` feature.
+     */
+    var `directBytes`: kotlin.ULong
+    , 
+    /**
+     * Requests made to the host.
+     */
+    var `requests`: kotlin.ULong
+    
+){
+    
+
+    
+
+    
+    companion object
+}
+
+/**
+ * @suppress
+ */
+public object FfiConverterTypeHostBandwidth: FfiConverterRustBuffer<HostBandwidth> {
+    override fun read(buf: ByteBuffer): HostBandwidth {
+        return HostBandwidth(
+            FfiConverterString.read(buf),
+            FfiConverterULong.read(buf),
+            FfiConverterULong.read(buf),
+            FfiConverterULong.read(buf),
+        )
+    }
+
+    override fun allocationSize(value: HostBandwidth) = (
+            FfiConverterString.allocationSize(value.`host`) +
+            FfiConverterULong.allocationSize(value.`tunnelBytes`) +
+            FfiConverterULong.allocationSize(value.`directBytes`) +
+            FfiConverterULong.allocationSize(value.`requests`)
+    )
+
+    override fun write(value: HostBandwidth, buf: ByteBuffer) {
+            FfiConverterString.write(value.`host`, buf)
+            FfiConverterULong.write(value.`tunnelBytes`, buf)
+            FfiConverterULong.write(value.`directBytes`, buf)
+            FfiConverterULong.write(value.`requests`, buf)
+    }
+}
+
+
+
+/**
+ * Result of a generic tunnelled fetch (non-image content).
+ */
+data class HttpFetchResponse (
+    /**
+     * HTTP status code of the final response.
+     */
+    var `status`: kotlin.UShort
+    , 
+    /**
+     * Normalised MIME type.
+     */
+    var `mimeType`: kotlin.String
+    , 
+    /**
+     * Raw response body.
+     */
+    var `data`: kotlin.ByteArray
+    , 
+    /**
+     * Final URL after redirects.
+     */
+    var `finalUrl`: kotlin.String
+    , 
+    /**
+     * Trace id of the request, also found in its log records.
+     */
+    var `traceId`: kotlin.String
+    
+){
+    
+
+    
+
+    
+    companion object
+}
+
+/**
+ * @suppress
+ */
+public object FfiConverterTypeHttpFetchResponse: FfiConverterRustBuffer<HttpFetchResponse> {
+    override fun read(buf: ByteBuffer): HttpFetchResponse {
+        return HttpFetchResponse(
+            FfiConverterUShort.read(buf),
+            FfiConverterString.read(buf),
+            FfiConverterByteArray.read(buf),
+            FfiConverterString.read(buf),
+            FfiConverterString.read(buf),
+        )
+    }
+
+    override fun allocationSize(value: HttpFetchResponse) = (
+            FfiConverterUShort.allocationSize(value.`status`) +
+            FfiConverterString.allocationSize(value.`mimeType`) +
+            FfiConverterByteArray.allocationSize(value.`data`) +
+            FfiConverterString.allocationSize(value.`finalUrl`) +
+            FfiConverterString.allocationSize(value.`traceId`)
+    )
+
+    override fun write(value: HttpFetchResponse, buf: ByteBuffer) {
+            FfiConverterUShort.write(value.`status`, buf)
+            FfiConverterString.write(value.`mimeType`, buf)
+            FfiConverterByteArray.write(value.`data`, buf)
+            FfiConverterString.write(value.`finalUrl`, buf)
+            FfiConverterString.write(value.`traceId`, buf)
+    }
+}
+
+
+
+/**
+ * Image metadata obtained without downloading the body.
+ */
+data class ImageProbe (
+    /**
+     * MIME type the server announces.
+     */
+    var `mimeType`: kotlin.String
+    , 
+    /**
+     * Full size in bytes, if the server announces it.
+     */
+    var `contentLength`: kotlin.ULong?
+    , 
+    /**
+     * Final URL after redirects.
+     */
+    var `finalUrl`: kotlin.String
+    , 
+    /**
+     * Whether the answer came from the cache instead of the network.
+     */
+    var `fromCache`: kotlin.Boolean
+    
+){
+    
+
+    
+
+    
+    companion object
+}
+
+/**
+ * @suppress
+ */
+public object FfiConverterTypeImageProbe: FfiConverterRustBuffer<ImageProbe> {
+    override fun read(buf: ByteBuffer): ImageProbe {
+        return ImageProbe(
+            FfiConverterString.read(buf),
+            FfiConverterOptionalULong.read(buf),
+            FfiConverterString.read(buf),
+            FfiConverterBoolean.read(buf),
+        )
+    }
+
+    override fun allocationSize(value: ImageProbe) = (
+            FfiConverterString.allocationSize(value.`mimeType`) +
+            FfiConverterOptionalULong.allocationSize(value.`contentLength`) +
+            FfiConverterString.allocationSize(value.`finalUrl`) +
+            FfiConverterBoolean.allocationSize(value.`fromCache`)
+    )
+
+    override fun write(value: ImageProbe, buf: ByteBuffer) {
+            FfiConverterString.write(value.`mimeType`, buf)
+            FfiConverterOptionalULong.write(value.`contentLength`, buf)
+            FfiConverterString.write(value.`finalUrl`, buf)
+            FfiConverterBoolean.write(value.`fromCache`, buf)
+    }
+}
+
+
+
+/**
+ * Result of a successful image fetch operation.
+ */
+data class ImageResponse (
+    /**
+     * MIME type of the image (e.g., "image/png", "image/svg+xml").
+     */
+    var `mimeType`: kotlin.String
+    , 
+    /**
+     * Raw image bytes.
+     */
+    var `data`: kotlin.ByteArray
+    , 
+    /**
+     * Whether this response was served from cache.
+     */
+    var `fromCache`: kotlin.Boolean
+    , 
+    /**
+     * Final URL after redirects (if any).
+     */
+    var `finalUrl`: kotlin.String
+    , 
+    /**
+     * Every URL that answered with a redirect, in order, starting with the
+     * requested one. Empty when the image was served without redirects.
+     */
+    var `redirectChain`: List<kotlin.String>
+    , 
+    /**
+     * The redirects of `redirect_chain` one by one, with the host and scheme
+     * changes the redirect policy let through.
+     */
+    var `redirectHops`: List<RedirectHop>
+    , 
+    /**
+     * Pixel width read from the image header, if the format is recognised.
+     */
+    var `width`: kotlin.UInt?
+    , 
+    /**
+     * Pixel height read from the image header, if the format is recognised.
+     */
+    var `height`: kotlin.UInt?
+    , 
+    /**
+     * BlurHash of the image for an instant blurred placeholder, if it is a
+     * still image the proxy can decode.
+     */
+    var `blurhash`: kotlin.String?
+    , 
+    /**
+     * Whether the image looks like a tracking pixel: served from a known
+     * tracker domain, empty, or at most 2x2 pixels.
+     */
+    var `trackingSuspected`: kotlin.Boolean
+    , 
+    /**
+     * Trace id of the request, also found in its log records; quote it in
+     * bug reports.
+     */
+    var `traceId`: kotlin.String
+    , 
+    /**
+     * Selected headers of the response the image came from.
+     */
+    var `headers`: ResponseHeaders
+    , 
+    /**
+     * SHA-256 of `data`, as lowercase hex.
+     */
+    var `sha256`: kotlin.String
+    , 
+    /**
+     * How the image was fetched, or `None` if no request was made (cache
+     * hits and `data:` URIs).
+     */
+    var `transport`: Transport?
+    
+){
+    
+
+    
+
+    
+    companion object
+}
+
+/**
+ * @suppress
+ */
+public object FfiConverterTypeImageResponse: FfiConverterRustBuffer<ImageResponse> {
+    override fun read(buf: ByteBuffer): ImageResponse {
+        return ImageResponse(
+            FfiConverterString.read(buf),
+            FfiConverterByteArray.read(buf),
+            FfiConverterBoolean.read(buf),
+            FfiConverterString.read(buf),
+            FfiConverterSequenceString.read(buf),
+            FfiConverterSequenceTypeRedirectHop.read(buf),
+            FfiConverterOptionalUInt.read(buf),
+            FfiConverterOptionalUInt.read(buf),
+            FfiConverterOptionalString.read(buf),
+            FfiConverterBoolean.read(buf),
+            FfiConverterString.read(buf),
+            FfiConverterTypeResponseHeaders.read(buf),
+            FfiConverterString.read(buf),
+            FfiConverterOptionalTypeTransport.read(buf),
+        )
+    }
+
+    override fun allocationSize(value: ImageResponse) = (
+            FfiConverterString.allocationSize(value.`mimeType`) +
+            FfiConverterByteArray.allocationSize(value.`data`) +
+            FfiConverterBoolean.allocationSize(value.`fromCache`) +
+            FfiConverterString.allocationSize(value.`finalUrl`) +
+            FfiConverterSequenceString.allocationSize(value.`redirectChain`) +
+            FfiConverterSequenceTypeRedirectHop.allocationSize(value.`redirectHops`) +
+            FfiConverterOptionalUInt.allocationSize(value.`width`) +
+            FfiConverterOptionalUInt.allocationSize(value.`height`) +
+            FfiConverterOptionalString.allocationSize(value.`blurhash`) +
+            FfiConverterBoolean.allocationSize(value.`trackingSuspected`) +
+            FfiConverterString.allocationSize(value.`traceId`) +
+            FfiConverterTypeResponseHeaders.allocationSize(value.`headers`) +
+            FfiConverterString.allocationSize(value.`sha256`) +
+            FfiConverterOptionalTypeTransport.allocationSize(value.`transport`)
+    )
+
+    override fun write(value: ImageResponse, buf: ByteBuffer) {
+            FfiConverterString.write(value.`mimeType`, buf)
+            FfiConverterByteArray.write(value.`data`, buf)
+            FfiConverterBoolean.write(value.`fromCache`, buf)
+            FfiConverterString.write(value.`finalUrl`, buf)
+            FfiConverterSequenceString.write(value.`redirectChain`, buf)
+            FfiConverterSequenceTypeRedirectHop.write(value.`redirectHops`, buf)
+            FfiConverterOptionalUInt.write(value.`width`, buf)
+            FfiConverterOptionalUInt.write(value.`height`, buf)
+            FfiConverterOptionalString.write(value.`blurhash`, buf)
+            FfiConverterBoolean.write(value.`trackingSuspected`, buf)
+            FfiConverterString.write(value.`traceId`, buf)
+            FfiConverterTypeResponseHeaders.write(value.`headers`, buf)
+            FfiConverterString.write(value.`sha256`, buf)
+            FfiConverterOptionalTypeTransport.write(value.`transport`, buf)
+    }
+}
+
+
+
+/**
+ * Downscaling and transcoding applied to an image before it is returned
+ * over FFI.
+ *
+ * Images are only ever shrunk, preserving their aspect ratio, to fit within
+ * both bounds; images already inside them (and not being transcoded) are
+ * returned untouched.
+ */
+data class ImageTransform (
+    /**
+     * Maximum output width in pixels (`None` = unbounded).
+     */
+    var `maxWidth`: kotlin.UInt?
+    , 
+    /**
+     * Maximum output height in pixels (`None` = unbounded).
+     */
+    var `maxHeight`: kotlin.UInt?
+    , 
+    /**
+     * JPEG quality (1-100) used when a JPEG is encoded.
+     */
+    var `quality`: kotlin.UByte
+    , 
+    /**
+     * Re-encode decodable images into this format, e.g. for WebViews that
+     * cannot render JPEG XL (`None` = keep JPEG as JPEG, others as PNG when
+     * resized).
+     */
+    var `format`: OutputFormat?
+    , 
+    /**
+     * Handling of animated GIF, WebP and APNG images.
+     */
+    var `animation`: AnimationPolicy
+    
+){
+    
+
+    
+
+    
+    companion object
+}
+
+/**
+ * @suppress
+ */
+public object FfiConverterTypeImageTransform: FfiConverterRustBuffer<ImageTransform> {
+    override fun read(buf: ByteBuffer): ImageTransform {
+        return ImageTransform(
+            FfiConverterOptionalUInt.read(buf),
+            FfiConverterOptionalUInt.read(buf),
+            FfiConverterUByte.read(buf),
+            FfiConverterOptionalTypeOutputFormat.read(buf),
+            FfiConverterTypeAnimationPolicy.read(buf),
+        )
+    }
+
+    override fun allocationSize(value: ImageTransform) = (
+            FfiConverterOptionalUInt.allocationSize(value.`maxWidth`) +
+            FfiConverterOptionalUInt.allocationSize(value.`maxHeight`) +
+            FfiConverterUByte.allocationSize(value.`quality`) +
+            FfiConverterOptionalTypeOutputFormat.allocationSize(value.`format`) +
+            FfiConverterTypeAnimationPolicy.allocationSize(value.`animation`)
+    )
+
+    override fun write(value: ImageTransform, buf: ByteBuffer) {
+            FfiConverterOptionalUInt.write(value.`maxWidth`, buf)
+            FfiConverterOptionalUInt.write(value.`maxHeight`, buf)
+            FfiConverterUByte.write(value.`quality`, buf)
+            FfiConverterOptionalTypeOutputFormat.write(value.`format`, buf)
+            FfiConverterTypeAnimationPolicy.write(value.`animation`, buf)
+    }
+}
+
+
+
+/**
+ * Latency distribution of one kind of operation.
+ */
+data class LatencyHistogram (
+    /**
+     * Upper bound of each bucket in milliseconds, ascending.
+     */
+    var `bucketBoundsMs`: List<kotlin.UInt>
+    , 
+    /**
+     * Samples per bucket: one entry per bound, then one for everything
+     * slower than the last bound.
+     */
+    var `counts`: List<kotlin.ULong>
+    , 
+    /**
+     * Number of samples.
+     */
+    var `totalCount`: kotlin.ULong
+    , 
+    /**
+     * Sum of all samples in milliseconds, for the mean.
+     */
+    var `sumMs`: kotlin.ULong
+    
+){
+    
+
+    
+
+    
+    companion object
+}
+
+/**
+ * @suppress
+ */
+public object FfiConverterTypeLatencyHistogram: FfiConverterRustBuffer<LatencyHistogram> {
+    override fun read(buf: ByteBuffer): LatencyHistogram {
+        return LatencyHistogram(
+            FfiConverterSequenceUInt.read(buf),
+            FfiConverterSequenceULong.read(buf),
+            FfiConverterULong.read(buf),
+            FfiConverterULong.read(buf),
+        )
+    }
+
+    override fun allocationSize(value: LatencyHistogram) = (
+            FfiConverterSequenceUInt.allocationSize(value.`bucketBoundsMs`) +
+            FfiConverterSequenceULong.allocationSize(value.`counts`) +
+            FfiConverterULong.allocationSize(value.`totalCount`) +
+            FfiConverterULong.allocationSize(value.`sumMs`)
+    )
+
+    override fun write(value: LatencyHistogram, buf: ByteBuffer) {
+            FfiConverterSequenceUInt.write(value.`bucketBoundsMs`, buf)
+            FfiConverterSequenceULong.write(value.`counts`, buf)
+            FfiConverterULong.write(value.`totalCount`, buf)
+            FfiConverterULong.write(value.`sumMs`, buf)
+    }
+}
+
+
+
+/**
+ * Link preview built by [`crate::proxy_resolve_preview`].
+ */
+data class LinkPreview (
+    /**
+     * URL of the page after redirects.
+     */
+    var `url`: kotlin.String
+    , 
+    /**
+     * `og:title`, `twitter:title` or the `<title>` element, if any.
+     */
+    var `title`: kotlin.String?
+    , 
+    /**
+     * Absolute URL of the preview image the page advertises, if any.
+     */
+    var `imageUrl`: kotlin.String?
+    , 
+    /**
+     * The preview image, fetched through the normal image pipeline.
+     */
+    var `image`: ImageResponse?
+    , 
+    /**
+     * Why the preview image could not be fetched, if it could not.
+     */
+    var `imageError`: kotlin.String?
+    
+){
+    
+
+    
+
+    
+    companion object
+}
+
+/**
+ * @suppress
+ */
+public object FfiConverterTypeLinkPreview: FfiConverterRustBuffer<LinkPreview> {
+    override fun read(buf: ByteBuffer): LinkPreview {
+        return LinkPreview(
+            FfiConverterString.read(buf),
+            FfiConverterOptionalString.read(buf),
+            FfiConverterOptionalString.read(buf),
+            FfiConverterOptionalTypeImageResponse.read(buf),
+            FfiConverterOptionalString.read(buf),
+        )
+    }
+
+    override fun allocationSize(value: LinkPreview) = (
+            FfiConverterString.allocationSize(value.`url`) +
+            FfiConverterOptionalString.allocationSize(value.`title`) +
+            FfiConverterOptionalString.allocationSize(value.`imageUrl`) +
+            FfiConverterOptionalTypeImageResponse.allocationSize(value.`image`) +
+            FfiConverterOptionalString.allocationSize(value.`imageError`)
+    )
+
+    override fun write(value: LinkPreview, buf: ByteBuffer) {
+            FfiConverterString.write(value.`url`, buf)
+            FfiConverterOptionalString.write(value.`title`, buf)
+            FfiConverterOptionalString.write(value.`imageUrl`, buf)
+            FfiConverterOptionalTypeImageResponse.write(value.`image`, buf)
+            FfiConverterOptionalString.write(value.`imageError`, buf)
+    }
+}
+
+
+
+/**
+ * A user name and password for an [`UpstreamProxy`].
+ */
+data class ProxyCredentials (
+    /**
+     * User name.
+     */
+    var `username`: kotlin.String
+    , 
+    /**
+     * Password.
+     */
+    var `password`: kotlin.String
+    
+){
+    
+
+    
+
+    
+    companion object
+}
+
+/**
+ * @suppress
+ */
+public object FfiConverterTypeProxyCredentials: FfiConverterRustBuffer<ProxyCredentials> {
+    override fun read(buf: ByteBuffer): ProxyCredentials {
+        return ProxyCredentials(
+            FfiConverterString.read(buf),
+            FfiConverterString.read(buf),
+        )
+    }
+
+    override fun allocationSize(value: ProxyCredentials) = (
+            FfiConverterString.allocationSize(value.`username`) +
+            FfiConverterString.allocationSize(value.`password`)
+    )
+
+    override fun write(value: ProxyCredentials, buf: ByteBuffer) {
+            FfiConverterString.write(value.`username`, buf)
+            FfiConverterString.write(value.`password`, buf)
+    }
+}
+
+
+
+/**
+ * A snapshot of a client's counters.
+ */
+data class ProxyMetrics (
+    /**
+     * Image fetches that returned an image, from the network or the cache.
+     */
+    var `fetchesSucceeded`: kotlin.ULong
+    , 
+    /**
+     * Image fetches that failed, other than by cancellation.
+     */
+    var `fetchesFailed`: kotlin.ULong
+    , 
+    /**
+     * Image fetches cancelled before they completed.
+     */
+    var `fetchesCancelled`: kotlin.ULong
+    , 
+    /**
+     * Image fetches answered by the memory or disk cache.
+     */
+    var `cacheHits`: kotlin.ULong
+    , 
+    /**
+     * Image fetches that had to go to the network.
+     */
+    var `cacheMisses`: kotlin.ULong
+    , 
+    /**
+     * `cache_hits` over all cache lookups; 0 before the first.
+     */
+    var `cacheHitRatio`: kotlin.Double
+    , 
+    /**
+     * Plaintext bytes sent into the tunnel, including TLS and DNS overhead.
+     */
+    var `tunnelBytesSent`: kotlin.ULong
+    , 
+    /**
+     * Plaintext bytes received from the tunnel.
+     */
+    var `tunnelBytesReceived`: kotlin.ULong
+    , 
+    /**
+     * Completed WireGuard handshakes, including re-handshakes after a
+     * session lapsed.
+     */
+    var `handshakes`: kotlin.ULong
+    , 
+    /**
+     * WireGuard handshakes that did not complete, e.g. because WARP rotated
+     * its peer key.
+     */
+    var `handshakeFailures`: kotlin.ULong
+    , 
+    /**
+     * Attempts to register a WARP identity with Cloudflare, which go
+     * outside the tunnel.
+     */
+    var `provisionings`: kotlin.ULong
+    , 
+    /**
+     * Requests sent straight to the server, exposing the device's IP
+     * address; always 0 with the tunnel.
+     */
+    var `directRequests`: kotlin.ULong
+    , 
+    /**
+     * Time from dispatch to the last byte of image fetches that went to the
+     * network, including time spent queued.
+     */
+    var `fetchLatency`: LatencyHistogram
+    , 
+    /**
+     * Time to resolve a host name over DNS-over-HTTPS.
+     */
+    var `dnsLatency`: LatencyHistogram
+    
+){
+    
+
+    
+
+    
+    companion object
+}
+
+/**
+ * @suppress
+ */
+public object FfiConverterTypeProxyMetrics: FfiConverterRustBuffer<ProxyMetrics> {
+    override fun read(buf: ByteBuffer): ProxyMetrics {
+        return ProxyMetrics(
+            FfiConverterULong.read(buf),
+            FfiConverterULong.read(buf),
+            FfiConverterULong.read(buf),
+            FfiConverterULong.read(buf),
+            FfiConverterULong.read(buf),
+            FfiConverterDouble.read(buf),
+            FfiConverterULong.read(buf),
+            FfiConverterULong.read(buf),
+            FfiConverterULong.read(buf),
+            FfiConverterULong.read(buf),
+            FfiConverterULong.read(buf),
+            FfiConverterULong.read(buf),
+            FfiConverterTypeLatencyHistogram.read(buf),
+            FfiConverterTypeLatencyHistogram.read(buf),
+        )
+    }
+
+    override fun allocationSize(value: ProxyMetrics) = (
+            FfiConverterULong.allocationSize(value.`fetchesSucceeded`) +
+            FfiConverterULong.allocationSize(value.`fetchesFailed`) +
+            FfiConverterULong.allocationSize(value.`fetchesCancelled`) +
+            FfiConverterULong.allocationSize(value.`cacheHits`) +
+            FfiConverterULong.allocationSize(value.`cacheMisses`) +
+            FfiConverterDouble.allocationSize(value.`cacheHitRatio`) +
+            FfiConverterULong.allocationSize(value.`tunnelBytesSent`) +
+            FfiConverterULong.allocationSize(value.`tunnelBytesReceived`) +
+            FfiConverterULong.allocationSize(value.`handshakes`) +
+            FfiConverterULong.allocationSize(value.`handshakeFailures`) +
+            FfiConverterULong.allocationSize(value.`provisionings`) +
+            FfiConverterULong.allocationSize(value.`directRequests`) +
+            FfiConverterTypeLatencyHistogram.allocationSize(value.`fetchLatency`) +
+            FfiConverterTypeLatencyHistogram.allocationSize(value.`dnsLatency`)
+    )
+
+    override fun write(value: ProxyMetrics, buf: ByteBuffer) {
+            FfiConverterULong.write(value.`fetchesSucceeded`, buf)
+            FfiConverterULong.write(value.`fetchesFailed`, buf)
+            FfiConverterULong.write(value.`fetchesCancelled`, buf)
+            FfiConverterULong.write(value.`cacheHits`, buf)
+            FfiConverterULong.write(value.`cacheMisses`, buf)
+            FfiConverterDouble.write(value.`cacheHitRatio`, buf)
+            FfiConverterULong.write(value.`tunnelBytesSent`, buf)
+            FfiConverterULong.write(value.`tunnelBytesReceived`, buf)
+            FfiConverterULong.write(value.`handshakes`, buf)
+            FfiConverterULong.write(value.`handshakeFailures`, buf)
+            FfiConverterULong.write(value.`provisionings`, buf)
+            FfiConverterULong.write(value.`directRequests`, buf)
+            FfiConverterTypeLatencyHistogram.write(value.`fetchLatency`, buf)
+            FfiConverterTypeLatencyHistogram.write(value.`dnsLatency`, buf)
+    }
+}
+
+
+
+/**
+ * Status of the image proxy.
+ */
+data class ProxyStatus (
+    /**
+     * Whether the proxy is initialized and ready.
+     */
+    var `ready`: kotlin.Boolean
+    , 
+    /**
+     * Whether WARP is enabled on this device.
+     */
+    var `warpEnabled`: kotlin.Boolean
+    , 
+    /**
+     * Whether the WireGuard tunnel currently has a live session.
+     */
+    var `tunnelConnected`: kotlin.Boolean
+    , 
+    /**
+     * Current WireGuard endpoint (if provisioned).
+     */
+    var `endpoint`: kotlin.String?
+    , 
+    /**
+     * Last error message (if any).
+     */
+    var `lastError`: kotlin.String?
+    , 
+    /**
+     * Number of cached images.
+     */
+    var `cacheSize`: kotlin.UInt
+    , 
+    /**
+     * Number of images in the disk cache (0 when disabled).
+     */
+    var `diskCacheEntries`: kotlin.UInt
+    , 
+    /**
+     * Bytes used by the disk cache (0 when disabled).
+     */
+    var `diskCacheBytes`: kotlin.ULong
+    
+){
+    
+
+    
+
+    
+    companion object
+}
+
+/**
+ * @suppress
+ */
+public object FfiConverterTypeProxyStatus: FfiConverterRustBuffer<ProxyStatus> {
+    override fun read(buf: ByteBuffer): ProxyStatus {
+        return ProxyStatus(
+            FfiConverterBoolean.read(buf),
+            FfiConverterBoolean.read(buf),
+            FfiConverterBoolean.read(buf),
+            FfiConverterOptionalString.read(buf),
+            FfiConverterOptionalString.read(buf),
+            FfiConverterUInt.read(buf),
+            FfiConverterUInt.read(buf),
+            FfiConverterULong.read(buf),
+        )
+    }
+
+    override fun allocationSize(value: ProxyStatus) = (
+            FfiConverterBoolean.allocationSize(value.`ready`) +
+            FfiConverterBoolean.allocationSize(value.`warpEnabled`) +
+            FfiConverterBoolean.allocationSize(value.`tunnelConnected`) +
+            FfiConverterOptionalString.allocationSize(value.`endpoint`) +
+            FfiConverterOptionalString.allocationSize(value.`lastError`) +
+            FfiConverterUInt.allocationSize(value.`cacheSize`) +
+            FfiConverterUInt.allocationSize(value.`diskCacheEntries`) +
+            FfiConverterULong.allocationSize(value.`diskCacheBytes`)
+    )
+
+    override fun write(value: ProxyStatus, buf: ByteBuffer) {
+            FfiConverterBoolean.write(value.`ready`, buf)
+            FfiConverterBoolean.write(value.`warpEnabled`, buf)
+            FfiConverterBoolean.write(value.`tunnelConnected`, buf)
+            FfiConverterOptionalString.write(value.`endpoint`, buf)
+            FfiConverterOptionalString.write(value.`lastError`, buf)
+            FfiConverterUInt.write(value.`cacheSize`, buf)
+            FfiConverterUInt.write(value.`diskCacheEntries`, buf)
+            FfiConverterULong.write(value.`diskCacheBytes`, buf)
+    }
+}
+
+
+
+/**
+ * A ceiling on the requests the proxy sends, so a message with thousands of
+ * image URLs cannot turn the device into a request cannon.
+ */
+data class RateLimit (
+    /**
+     * Most requests started in any 60 seconds; later ones wait their turn
+     * (0 = unlimited).
+     */
+    var `requestsPerMinute`: kotlin.UInt
+    , 
+    /**
+     * Most requests waiting for the network; further ones fail with
+     * `Throttled` (0 = unlimited).
+     */
+    var `maxQueued`: kotlin.UInt
+    
+){
+    
+
+    
+
+    
+    companion object
+}
+
+/**
+ * @suppress
+ */
+public object FfiConverterTypeRateLimit: FfiConverterRustBuffer<RateLimit> {
+    override fun read(buf: ByteBuffer): RateLimit {
+        return RateLimit(
+            FfiConverterUInt.read(buf),
+            FfiConverterUInt.read(buf),
+        )
+    }
+
+    override fun allocationSize(value: RateLimit) = (
+            FfiConverterUInt.allocationSize(value.`requestsPerMinute`) +
+            FfiConverterUInt.allocationSize(value.`maxQueued`)
+    )
+
+    override fun write(value: RateLimit, buf: ByteBuffer) {
+            FfiConverterUInt.write(value.`requestsPerMinute`, buf)
+            FfiConverterUInt.write(value.`maxQueued`, buf)
+    }
+}
+
+
+
+/**
+ * A failed operation, as kept for in-app diagnostics.
+ */
+data class RecentError (
+    /**
+     * When it failed, in milliseconds since the Unix epoch.
+     */
+    var `timestampMs`: kotlin.Long
+    , 
+    /**
+     * Host of the URL requested, if there was one (none for `data:` URIs).
+     */
+    var `host`: kotlin.String?
+    , 
+    /**
+     * What went wrong.
+     */
+    var `code`: ErrorCode
+    , 
+    /**
+     * The error message.
+     */
+    var `message`: kotlin.String
+    , 
+    /**
+     * The network path in use when it failed, if the network was up.
+     */
+    var `transport`: Transport?
+    
+){
+    
+
+    
+
+    
+    companion object
+}
+
+/**
+ * @suppress
+ */
+public object FfiConverterTypeRecentError: FfiConverterRustBuffer<RecentError> {
+    override fun read(buf: ByteBuffer): RecentError {
+        return RecentError(
+            FfiConverterLong.read(buf),
+            FfiConverterOptionalString.read(buf),
+            FfiConverterTypeErrorCode.read(buf),
+            FfiConverterString.read(buf),
+            FfiConverterOptionalTypeTransport.read(buf),
+        )
+    }
+
+    override fun allocationSize(value: RecentError) = (
+            FfiConverterLong.allocationSize(value.`timestampMs`) +
+            FfiConverterOptionalString.allocationSize(value.`host`) +
+            FfiConverterTypeErrorCode.allocationSize(value.`code`) +
+            FfiConverterString.allocationSize(value.`message`) +
+            FfiConverterOptionalTypeTransport.allocationSize(value.`transport`)
+    )
+
+    override fun write(value: RecentError, buf: ByteBuffer) {
+            FfiConverterLong.write(value.`timestampMs`, buf)
+            FfiConverterOptionalString.write(value.`host`, buf)
+            FfiConverterTypeErrorCode.write(value.`code`, buf)
+            FfiConverterString.write(value.`message`, buf)
+            FfiConverterOptionalTypeTransport.write(value.`transport`, buf)
+    }
+}
+
+
+
+/**
+ * A redirect that was followed, and what it changed.
+ */
+data class RedirectHop (
+    /**
+     * URL that answered with the redirect.
+     */
+    var `from`: kotlin.String
+    , 
+    /**
+     * URL the redirect led to.
+     */
+    var `to`: kotlin.String
+    , 
+    /**
+     * Whether the redirect moved to another host.
+     */
+    var `crossHost`: kotlin.Boolean
+    , 
+    /**
+     * Whether the redirect changed the scheme.
+     */
+    var `schemeChanged`: kotlin.Boolean
+    , 
+    /**
+     * Whether the redirect went from `https` to `http`.
+     */
+    var `downgrade`: kotlin.Boolean
+    
+){
+    
+
+    
+
+    
+    companion object
+}
+
+/**
+ * @suppress
+ */
+public object FfiConverterTypeRedirectHop: FfiConverterRustBuffer<RedirectHop> {
+    override fun read(buf: ByteBuffer): RedirectHop {
+        return RedirectHop(
+            FfiConverterString.read(buf),
+            FfiConverterString.read(buf),
+            FfiConverterBoolean.read(buf),
+            FfiConverterBoolean.read(buf),
+            FfiConverterBoolean.read(buf),
+        )
+    }
+
+    override fun allocationSize(value: RedirectHop) = (
+            FfiConverterString.allocationSize(value.`from`) +
+            FfiConverterString.allocationSize(value.`to`) +
+            FfiConverterBoolean.allocationSize(value.`crossHost`) +
+            FfiConverterBoolean.allocationSize(value.`schemeChanged`) +
+            FfiConverterBoolean.allocationSize(value.`downgrade`)
+    )
+
+    override fun write(value: RedirectHop, buf: ByteBuffer) {
+            FfiConverterString.write(value.`from`, buf)
+            FfiConverterString.write(value.`to`, buf)
+            FfiConverterBoolean.write(value.`crossHost`, buf)
+            FfiConverterBoolean.write(value.`schemeChanged`, buf)
+            FfiConverterBoolean.write(value.`downgrade`, buf)
+    }
+}
+
+
+
+/**
+ * Rules applied to every redirect hop.
+ */
+data class RedirectPolicy (
+    /**
+     * Refuse redirects from `https` to `http`.
+     */
+    var `forbidDowngrade`: kotlin.Boolean
+    , 
+    /**
+     * Refuse redirects to loopback, private or link-local addresses.
+     */
+    var `forbidPrivateTargets`: kotlin.Boolean
+    , 
+    /**
+     * Refuse redirects that leave the origin of the previous URL.
+     */
+    var `forbidCrossOrigin`: kotlin.Boolean
+    , 
+    /**
+     * Refuse redirects to another host, whatever the scheme and port.
+     */
+    var `forbidCrossHost`: kotlin.Boolean
+    , 
+    /**
+     * Refuse redirects that change the scheme, upgrades to `https` included.
+     */
+    var `forbidSchemeChange`: kotlin.Boolean
+    
+){
+    
+
+    
+
+    
+    companion object
+}
+
+/**
+ * @suppress
+ */
+public object FfiConverterTypeRedirectPolicy: FfiConverterRustBuffer<RedirectPolicy> {
+    override fun read(buf: ByteBuffer): RedirectPolicy {
+        return RedirectPolicy(
+            FfiConverterBoolean.read(buf),
+            FfiConverterBoolean.read(buf),
+            FfiConverterBoolean.read(buf),
+            FfiConverterBoolean.read(buf),
+            FfiConverterBoolean.read(buf),
+        )
+    }
+
+    override fun allocationSize(value: RedirectPolicy) = (
+            FfiConverterBoolean.allocationSize(value.`forbidDowngrade`) +
+            FfiConverterBoolean.allocationSize(value.`forbidPrivateTargets`) +
+            FfiConverterBoolean.allocationSize(value.`forbidCrossOrigin`) +
+            FfiConverterBoolean.allocationSize(value.`forbidCrossHost`) +
+            FfiConverterBoolean.allocationSize(value.`forbidSchemeChange`)
+    )
+
+    override fun write(value: RedirectPolicy, buf: ByteBuffer) {
+            FfiConverterBoolean.write(value.`forbidDowngrade`, buf)
+            FfiConverterBoolean.write(value.`forbidPrivateTargets`, buf)
+            FfiConverterBoolean.write(value.`forbidCrossOrigin`, buf)
+            FfiConverterBoolean.write(value.`forbidCrossHost`, buf)
+            FfiConverterBoolean.write(value.`forbidSchemeChange`, buf)
+    }
+}
+
+
+
+/**
+ * A `Referer` and `Origin` one host receives with the image requests of a
+ * fetch group, for CDNs that refuse hotlinked images without them.
+ */
+data class RefererGrant (
+    /**
+     * The only host the headers go to: matched exactly and
+     * case-insensitively, not its subdomains.
+     */
+    var `host`: kotlin.String
+    , 
+    /**
+     * `Referer` to send: an `http(s)` URL, sent without fragment or
+     * credentials.
+     */
+    var `referer`: kotlin.String?
+    , 
+    /**
+     * `Origin` to send, e.g. `https://mail.example`; reduced to scheme, host
+     * and port.
+     */
+    var `origin`: kotlin.String?
+    
+){
+    
+
+    
+
+    
+    companion object
+}
+
+/**
+ * @suppress
+ */
+public object FfiConverterTypeRefererGrant: FfiConverterRustBuffer<RefererGrant> {
+    override fun read(buf: ByteBuffer): RefererGrant {
+        return RefererGrant(
+            FfiConverterString.read(buf),
+            FfiConverterOptionalString.read(buf),
+            FfiConverterOptionalString.read(buf),
+        )
+    }
+
+    override fun allocationSize(value: RefererGrant) = (
+            FfiConverterString.allocationSize(value.`host`) +
+            FfiConverterOptionalString.allocationSize(value.`referer`) +
+            FfiConverterOptionalString.allocationSize(value.`origin`)
+    )
+
+    override fun write(value: RefererGrant, buf: ByteBuffer) {
+            FfiConverterString.write(value.`host`, buf)
+            FfiConverterOptionalString.write(value.`referer`, buf)
+            FfiConverterOptionalString.write(value.`origin`, buf)
+    }
+}
+
+
+
+/**
+ * Options for [`crate::proxy_fetch_resource`].
+ */
+data class ResourceOptions (
+    /**
+     * What the resource is expected to be.
+     */
+    var `kind`: ResourceKind
+    , 
+    /**
+     * Extra request headers (subject to the sensitive-header filter).
+     */
+    var `headers`: Map<kotlin.String, kotlin.String>?
+    , 
+    /**
+     * Largest accepted body in bytes (`None` = 4 MB).
+     */
+    var `maxSize`: kotlin.ULong?
+    
+){
+    
+
+    
+
+    
+    companion object
+}
+
+/**
+ * @suppress
+ */
+public object FfiConverterTypeResourceOptions: FfiConverterRustBuffer<ResourceOptions> {
+    override fun read(buf: ByteBuffer): ResourceOptions {
+        return ResourceOptions(
+            FfiConverterTypeResourceKind.read(buf),
+            FfiConverterOptionalMapStringString.read(buf),
+            FfiConverterOptionalULong.read(buf),
+        )
+    }
+
+    override fun allocationSize(value: ResourceOptions) = (
+            FfiConverterTypeResourceKind.allocationSize(value.`kind`) +
+            FfiConverterOptionalMapStringString.allocationSize(value.`headers`) +
+            FfiConverterOptionalULong.allocationSize(value.`maxSize`)
+    )
+
+    override fun write(value: ResourceOptions, buf: ByteBuffer) {
+            FfiConverterTypeResourceKind.write(value.`kind`, buf)
+            FfiConverterOptionalMapStringString.write(value.`headers`, buf)
+            FfiConverterOptionalULong.write(value.`maxSize`, buf)
+    }
+}
+
+
+
+/**
+ * Result of a successful [`crate::proxy_fetch_resource`].
+ */
+data class ResourceResponse (
+    /**
+     * Normalised MIME type, e.g. "font/woff2" or "text/css".
+     */
+    var `mimeType`: kotlin.String
+    , 
+    /**
+     * Raw resource bytes.
+     */
+    var `data`: kotlin.ByteArray
+    , 
+    /**
+     * Whether this response was served from cache.
+     */
     var `fromCache`: kotlin.Boolean
     , 
     /**
-     * Final URL after redirects (if any).
+     * Final URL after redirects (if any).
+     */
+    var `finalUrl`: kotlin.String
+    , 
+    /**
+     * Every URL that answered with a redirect, in order.
+     */
+    var `redirectChain`: List<kotlin.String>
+    
+){
+    
+
+    
+
+    
+    companion object
+}
+
+/**
+ * @suppress
+ */
+public object FfiConverterTypeResourceResponse: FfiConverterRustBuffer<ResourceResponse> {
+    override fun read(buf: ByteBuffer): ResourceResponse {
+        return ResourceResponse(
+            FfiConverterString.read(buf),
+            FfiConverterByteArray.read(buf),
+            FfiConverterBoolean.read(buf),
+            FfiConverterString.read(buf),
+            FfiConverterSequenceString.read(buf),
+        )
+    }
+
+    override fun allocationSize(value: ResourceResponse) = (
+            FfiConverterString.allocationSize(value.`mimeType`) +
+            FfiConverterByteArray.allocationSize(value.`data`) +
+            FfiConverterBoolean.allocationSize(value.`fromCache`) +
+            FfiConverterString.allocationSize(value.`finalUrl`) +
+            FfiConverterSequenceString.allocationSize(value.`redirectChain`)
+    )
+
+    override fun write(value: ResourceResponse, buf: ByteBuffer) {
+            FfiConverterString.write(value.`mimeType`, buf)
+            FfiConverterByteArray.write(value.`data`, buf)
+            FfiConverterBoolean.write(value.`fromCache`, buf)
+            FfiConverterString.write(value.`finalUrl`, buf)
+            FfiConverterSequenceString.write(value.`redirectChain`, buf)
+    }
+}
+
+
+
+/**
+ * Response headers passed through to the app, e.g. to name saved files or
+ * make its own caching decisions. An allowlist: cookies and other
+ * state-bearing headers are never passed through, nor cached.
+ */
+data class ResponseHeaders (
+    /**
+     * File name suggested by `Content-Disposition`, without any directory
+     * part.
+     */
+    var `filename`: kotlin.String?
+    , 
+    /**
+     * `Last-Modified`, as sent.
+     */
+    var `lastModified`: kotlin.String?
+    , 
+    /**
+     * `Cache-Control`, as sent.
+     */
+    var `cacheControl`: kotlin.String?
+    , 
+    /**
+     * Full resource length announced by the server: `Content-Length`, or the
+     * `Content-Range` total of a partial response. For compressed transfers
+     * this is the compressed length.
+     */
+    var `contentLength`: kotlin.ULong?
+    
+){
+    
+
+    
+
+    
+    companion object
+}
+
+/**
+ * @suppress
+ */
+public object FfiConverterTypeResponseHeaders: FfiConverterRustBuffer<ResponseHeaders> {
+    override fun read(buf: ByteBuffer): ResponseHeaders {
+        return ResponseHeaders(
+            FfiConverterOptionalString.read(buf),
+            FfiConverterOptionalString.read(buf),
+            FfiConverterOptionalString.read(buf),
+            FfiConverterOptionalULong.read(buf),
+        )
+    }
+
+    override fun allocationSize(value: ResponseHeaders) = (
+            FfiConverterOptionalString.allocationSize(value.`filename`) +
+            FfiConverterOptionalString.allocationSize(value.`lastModified`) +
+            FfiConverterOptionalString.allocationSize(value.`cacheControl`) +
+            FfiConverterOptionalULong.allocationSize(value.`contentLength`)
+    )
+
+    override fun write(value: ResponseHeaders, buf: ByteBuffer) {
+            FfiConverterOptionalString.write(value.`filename`, buf)
+            FfiConverterOptionalString.write(value.`lastModified`, buf)
+            FfiConverterOptionalString.write(value.`cacheControl`, buf)
+            FfiConverterOptionalULong.write(value.`contentLength`, buf)
+    }
+}
+
+
+
+/**
+ * Automatic retry behaviour for transient fetch failures.
+ *
+ * Only ever applied to GET requests, which are idempotent.
+ */
+data class RetryPolicy (
+    /**
+     * Total attempts per request, including the first (1 disables retries).
+     */
+    var `maxAttempts`: kotlin.UInt
+    , 
+    /**
+     * Delay before the first retry; doubled for each further retry.
+     */
+    var `initialBackoffMs`: kotlin.ULong
+    , 
+    /**
+     * Upper bound on any single delay, including a server's `Retry-After`.
+     */
+    var `maxBackoffMs`: kotlin.ULong
+    , 
+    /**
+     * HTTP status codes worth retrying.
+     */
+    var `retryableStatusCodes`: List<kotlin.UShort>
+    
+){
+    
+
+    
+
+    
+    companion object
+}
+
+/**
+ * @suppress
+ */
+public object FfiConverterTypeRetryPolicy: FfiConverterRustBuffer<RetryPolicy> {
+    override fun read(buf: ByteBuffer): RetryPolicy {
+        return RetryPolicy(
+            FfiConverterUInt.read(buf),
+            FfiConverterULong.read(buf),
+            FfiConverterULong.read(buf),
+            FfiConverterSequenceUShort.read(buf),
+        )
+    }
+
+    override fun allocationSize(value: RetryPolicy) = (
+            FfiConverterUInt.allocationSize(value.`maxAttempts`) +
+            FfiConverterULong.allocationSize(value.`initialBackoffMs`) +
+            FfiConverterULong.allocationSize(value.`maxBackoffMs`) +
+            FfiConverterSequenceUShort.allocationSize(value.`retryableStatusCodes`)
+    )
+
+    override fun write(value: RetryPolicy, buf: ByteBuffer) {
+            FfiConverterUInt.write(value.`maxAttempts`, buf)
+            FfiConverterULong.write(value.`initialBackoffMs`, buf)
+            FfiConverterULong.write(value.`maxBackoffMs`, buf)
+            FfiConverterSequenceUShort.write(value.`retryableStatusCodes`, buf)
+    }
+}
+
+
+
+/**
+ * One candidate of an `<img srcset>`: a URL with its width or pixel density
+ * descriptor.
+ */
+data class SrcsetCandidate (
+    /**
+     * Image URL, absolute.
+     */
+    var `url`: kotlin.String
+    , 
+    /**
+     * Intrinsic width in pixels, from a `w` descriptor (`480w`).
+     */
+    var `width`: kotlin.UInt?
+    , 
+    /**
+     * Pixel density, from an `x` descriptor (`2x`). A candidate with
+     * neither descriptor counts as `1x`.
+     */
+    var `density`: kotlin.Float?
+    
+){
+    
+
+    
+
+    
+    companion object
+}
+
+/**
+ * @suppress
+ */
+public object FfiConverterTypeSrcsetCandidate: FfiConverterRustBuffer<SrcsetCandidate> {
+    override fun read(buf: ByteBuffer): SrcsetCandidate {
+        return SrcsetCandidate(
+            FfiConverterString.read(buf),
+            FfiConverterOptionalUInt.read(buf),
+            FfiConverterOptionalFloat.read(buf),
+        )
+    }
+
+    override fun allocationSize(value: SrcsetCandidate) = (
+            FfiConverterString.allocationSize(value.`url`) +
+            FfiConverterOptionalUInt.allocationSize(value.`width`) +
+            FfiConverterOptionalFloat.allocationSize(value.`density`)
+    )
+
+    override fun write(value: SrcsetCandidate, buf: ByteBuffer) {
+            FfiConverterString.write(value.`url`, buf)
+            FfiConverterOptionalUInt.write(value.`width`, buf)
+            FfiConverterOptionalFloat.write(value.`density`, buf)
+    }
+}
+
+
+
+/**
+ * Time allowed for each phase of a request, in seconds.
+ *
+ * Each attempt gets the full budget again when a request is retried.
+ */
+data class TimeoutBudget (
+    /**
+     * Resolving the host over DNS-over-HTTPS, its own connection included.
+     */
+    var `dnsSeconds`: kotlin.UInt
+    , 
+    /**
+     * Opening the TCP connection.
+     */
+    var `connectSeconds`: kotlin.UInt
+    , 
+    /**
+     * The TLS handshake.
+     */
+    var `tlsSeconds`: kotlin.UInt
+    , 
+    /**
+     * From sending the request to receiving the response head.
+     */
+    var `firstByteSeconds`: kotlin.UInt
+    , 
+    /**
+     * Receiving the whole response body.
+     */
+    var `bodySeconds`: kotlin.UInt
+    
+){
+    
+
+    
+
+    
+    companion object
+}
+
+/**
+ * @suppress
+ */
+public object FfiConverterTypeTimeoutBudget: FfiConverterRustBuffer<TimeoutBudget> {
+    override fun read(buf: ByteBuffer): TimeoutBudget {
+        return TimeoutBudget(
+            FfiConverterUInt.read(buf),
+            FfiConverterUInt.read(buf),
+            FfiConverterUInt.read(buf),
+            FfiConverterUInt.read(buf),
+            FfiConverterUInt.read(buf),
+        )
+    }
+
+    override fun allocationSize(value: TimeoutBudget) = (
+            FfiConverterUInt.allocationSize(value.`dnsSeconds`) +
+            FfiConverterUInt.allocationSize(value.`connectSeconds`) +
+            FfiConverterUInt.allocationSize(value.`tlsSeconds`) +
+            FfiConverterUInt.allocationSize(value.`firstByteSeconds`) +
+            FfiConverterUInt.allocationSize(value.`bodySeconds`)
+    )
+
+    override fun write(value: TimeoutBudget, buf: ByteBuffer) {
+            FfiConverterUInt.write(value.`dnsSeconds`, buf)
+            FfiConverterUInt.write(value.`connectSeconds`, buf)
+            FfiConverterUInt.write(value.`tlsSeconds`, buf)
+            FfiConverterUInt.write(value.`firstByteSeconds`, buf)
+            FfiConverterUInt.write(value.`bodySeconds`, buf)
+    }
+}
+
+
+
+/**
+ * Result of an in-app update check.
+ */
+data class UpdateResult (
+    /**
+     * Whether a newer release is available.
+     */
+    var `updateAvailable`: kotlin.Boolean
+    , 
+    /**
+     * Running version as reported by the caller.
+     */
+    var `currentVersion`: kotlin.String
+    , 
+    /**
+     * Latest release version (no leading `v`).
+     */
+    var `latestVersion`: kotlin.String
+    , 
+    /**
+     * Latest release git tag.
+     */
+    var `latestTag`: kotlin.String
+    , 
+    /**
+     * Release notes.
+     */
+    var `changelog`: kotlin.String
+    , 
+    /**
+     * Release page URL.
+     */
+    var `releaseUrl`: kotlin.String
+    
+){
+    
+
+    
+
+    
+    companion object
+}
+
+/**
+ * @suppress
+ */
+public object FfiConverterTypeUpdateResult: FfiConverterRustBuffer<UpdateResult> {
+    override fun read(buf: ByteBuffer): UpdateResult {
+        return UpdateResult(
+            FfiConverterBoolean.read(buf),
+            FfiConverterString.read(buf),
+            FfiConverterString.read(buf),
+            FfiConverterString.read(buf),
+            FfiConverterString.read(buf),
+            FfiConverterString.read(buf),
+        )
+    }
+
+    override fun allocationSize(value: UpdateResult) = (
+            FfiConverterBoolean.allocationSize(value.`updateAvailable`) +
+            FfiConverterString.allocationSize(value.`currentVersion`) +
+            FfiConverterString.allocationSize(value.`latestVersion`) +
+            FfiConverterString.allocationSize(value.`latestTag`) +
+            FfiConverterString.allocationSize(value.`changelog`) +
+            FfiConverterString.allocationSize(value.`releaseUrl`)
+    )
+
+    override fun write(value: UpdateResult, buf: ByteBuffer) {
+            FfiConverterBoolean.write(value.`updateAvailable`, buf)
+            FfiConverterString.write(value.`currentVersion`, buf)
+            FfiConverterString.write(value.`latestVersion`, buf)
+            FfiConverterString.write(value.`latestTag`, buf)
+            FfiConverterString.write(value.`changelog`, buf)
+            FfiConverterString.write(value.`releaseUrl`, buf)
+    }
+}
+
+
+
+/**
+ * Full WireGuard/WARP diagnostics for the in-app debug screen.
+ *
+ * This intentionally includes the private key so power users can fully inspect
+ * and reproduce the tunnel; the Android UI hides it behind an explicit reveal.
+ */
+data class WarpDiagnostics (
+    /**
+     * `"connected"` or `"disconnected"`.
+     */
+    var `connectionState`: kotlin.String
+    , 
+    /**
+     * WireGuard private key (base64).
+     */
+    var `privateKey`: kotlin.String
+    , 
+    /**
+     * Derived WireGuard public key (base64).
+     */
+    var `publicKey`: kotlin.String
+    , 
+    /**
+     * WARP peer public key (base64).
+     */
+    var `peerPublicKey`: kotlin.String
+    , 
+    /**
+     * Endpoint hostname.
+     */
+    var `endpointHost`: kotlin.String
+    , 
+    /**
+     * Endpoint IPv4 address.
+     */
+    var `endpointIpv4`: kotlin.String
+    , 
+    /**
+     * Endpoint UDP port.
+     */
+    var `endpointPort`: kotlin.UShort
+    , 
+    /**
+     * Local tunnel IPv4 address.
+     */
+    var `localAddressIpv4`: kotlin.String
+    , 
+    /**
+     * Whether WARP is enabled on the account.
+     */
+    var `warpEnabled`: kotlin.Boolean
+    , 
+    /**
+     * Account type (e.g. `free`).
+     */
+    var `accountType`: kotlin.String
+    , 
+    /**
+     * Cloudflare account/device identifier.
+     */
+    var `accountId`: kotlin.String
+    , 
+    /**
+     * Seconds since the last completed handshake, if any.
+     */
+    var `lastHandshakeSecs`: kotlin.ULong?
+    , 
+    /**
+     * Plaintext bytes transmitted into the tunnel.
+     */
+    var `txBytes`: kotlin.ULong
+    , 
+    /**
+     * Plaintext bytes received from the tunnel.
+     */
+    var `rxBytes`: kotlin.ULong
+    , 
+    /**
+     * Estimated packet loss in `[0.0, 1.0]`.
+     */
+    var `estimatedLoss`: kotlin.Float
+    , 
+    /**
+     * Estimated round-trip time in milliseconds, if measured.
+     */
+    var `rttMs`: kotlin.UInt?
+    
+){
+    
+
+    
+
+    
+    companion object
+}
+
+/**
+ * @suppress
+ */
+public object FfiConverterTypeWarpDiagnostics: FfiConverterRustBuffer<WarpDiagnostics> {
+    override fun read(buf: ByteBuffer): WarpDiagnostics {
+        return WarpDiagnostics(
+            FfiConverterString.read(buf),
+            FfiConverterString.read(buf),
+            FfiConverterString.read(buf),
+            FfiConverterString.read(buf),
+            FfiConverterString.read(buf),
+            FfiConverterString.read(buf),
+            FfiConverterUShort.read(buf),
+            FfiConverterString.read(buf),
+            FfiConverterBoolean.read(buf),
+            FfiConverterString.read(buf),
+            FfiConverterString.read(buf),
+            FfiConverterOptionalULong.read(buf),
+            FfiConverterULong.read(buf),
+            FfiConverterULong.read(buf),
+            FfiConverterFloat.read(buf),
+            FfiConverterOptionalUInt.read(buf),
+        )
+    }
+
+    override fun allocationSize(value: WarpDiagnostics) = (
+            FfiConverterString.allocationSize(value.`connectionState`) +
+            FfiConverterString.allocationSize(value.`privateKey`) +
+            FfiConverterString.allocationSize(value.`publicKey`) +
+            FfiConverterString.allocationSize(value.`peerPublicKey`) +
+            FfiConverterString.allocationSize(value.`endpointHost`) +
+            FfiConverterString.allocationSize(value.`endpointIpv4`) +
+            FfiConverterUShort.allocationSize(value.`endpointPort`) +
+            FfiConverterString.allocationSize(value.`localAddressIpv4`) +
+            FfiConverterBoolean.allocationSize(value.`warpEnabled`) +
+            FfiConverterString.allocationSize(value.`accountType`) +
+            FfiConverterString.allocationSize(value.`accountId`) +
+            FfiConverterOptionalULong.allocationSize(value.`lastHandshakeSecs`) +
+            FfiConverterULong.allocationSize(value.`txBytes`) +
+            FfiConverterULong.allocationSize(value.`rxBytes`) +
+            FfiConverterFloat.allocationSize(value.`estimatedLoss`) +
+            FfiConverterOptionalUInt.allocationSize(value.`rttMs`)
+    )
+
+    override fun write(value: WarpDiagnostics, buf: ByteBuffer) {
+            FfiConverterString.write(value.`connectionState`, buf)
+            FfiConverterString.write(value.`privateKey`, buf)
+            FfiConverterString.write(value.`publicKey`, buf)
+            FfiConverterString.write(value.`peerPublicKey`, buf)
+            FfiConverterString.write(value.`endpointHost`, buf)
+            FfiConverterString.write(value.`endpointIpv4`, buf)
+            FfiConverterUShort.write(value.`endpointPort`, buf)
+            FfiConverterString.write(value.`localAddressIpv4`, buf)
+            FfiConverterBoolean.write(value.`warpEnabled`, buf)
+            FfiConverterString.write(value.`accountType`, buf)
+            FfiConverterString.write(value.`accountId`, buf)
+            FfiConverterOptionalULong.write(value.`lastHandshakeSecs`, buf)
+            FfiConverterULong.write(value.`txBytes`, buf)
+            FfiConverterULong.write(value.`rxBytes`, buf)
+            FfiConverterFloat.write(value.`estimatedLoss`, buf)
+            FfiConverterOptionalUInt.write(value.`rttMs`, buf)
+    }
+}
+
+
+
+/**
+ * When the WARP configuration of the stored identity is fetched again, to
+ * follow Cloudflare rotating the peer key or endpoint.
+ */
+data class WarpRefresh (
+    /**
+     * Refresh once the configuration is this old (0 = never by age).
+     */
+    var `intervalSecs`: kotlin.ULong
+    , 
+    /**
+     * Refresh after this many WireGuard handshakes in a row failed
+     * (0 = never on failures).
+     */
+    var `afterHandshakeFailures`: kotlin.UInt
+    
+){
+    
+
+    
+
+    
+    companion object
+}
+
+/**
+ * @suppress
+ */
+public object FfiConverterTypeWarpRefresh: FfiConverterRustBuffer<WarpRefresh> {
+    override fun read(buf: ByteBuffer): WarpRefresh {
+        return WarpRefresh(
+            FfiConverterULong.read(buf),
+            FfiConverterUInt.read(buf),
+        )
+    }
+
+    override fun allocationSize(value: WarpRefresh) = (
+            FfiConverterULong.allocationSize(value.`intervalSecs`) +
+            FfiConverterUInt.allocationSize(value.`afterHandshakeFailures`)
+    )
+
+    override fun write(value: WarpRefresh, buf: ByteBuffer) {
+            FfiConverterULong.write(value.`intervalSecs`, buf)
+            FfiConverterUInt.write(value.`afterHandshakeFailures`, buf)
+    }
+}
+
+
+
+/**
+ * Persisted WARP identity and tunnel configuration, read straight from disk.
+ *
+ * Unlike [`WarpDiagnostics`], building this never provisions or handshakes, so
+ * it remains inspectable even when the tunnel is down — exactly the situation a
+ * user needs visibility into. The private key is included for full
+ * transparency; the Android UI keeps it behind an explicit reveal toggle.
+ */
+data class WarpStoredConfig (
+    /**
+     * Whether a provisioned WARP configuration exists on disk.
+     */
+    var `hasConfig`: kotlin.Boolean
+    , 
+    /**
+     * Whether a live tunnel manager is currently running.
+     */
+    var `tunnelActive`: kotlin.Boolean
+    , 
+    /**
+     * Cloudflare account/device identifier.
+     */
+    var `accountId`: kotlin.String
+    , 
+    /**
+     * Account license key (may be empty for free accounts).
+     */
+    var `licenseKey`: kotlin.String
+    , 
+    /**
+     * WireGuard private key (base64). Sensitive — surfaced for debugging only.
+     */
+    var `privateKey`: kotlin.String
+    , 
+    /**
+     * Derived WireGuard public key (base64).
+     */
+    var `publicKey`: kotlin.String
+    , 
+    /**
+     * WARP peer public key (base64).
+     */
+    var `peerPublicKey`: kotlin.String
+    , 
+    /**
+     * Endpoint hostname.
+     */
+    var `endpointHost`: kotlin.String
+    , 
+    /**
+     * Endpoint IPv4 address.
+     */
+    var `endpointIpv4`: kotlin.String
+    , 
+    /**
+     * Endpoint UDP port.
+     */
+    var `endpointPort`: kotlin.UShort
+    , 
+    /**
+     * Local tunnel IPv4 address.
+     */
+    var `localAddressIpv4`: kotlin.String
+    , 
+    /**
+     * Whether WARP is enabled on the account.
+     */
+    var `warpEnabled`: kotlin.Boolean
+    , 
+    /**
+     * Account type (e.g. `free`).
+     */
+    var `accountType`: kotlin.String
+    , 
+    /**
+     * Unix timestamp (seconds) when the configuration was last provisioned.
+     */
+    var `lastUpdatedSecs`: kotlin.Long
+    , 
+    /**
+     * Absolute path to the persisted `warp_config.json`.
+     */
+    var `configFilePath`: kotlin.String
+    
+){
+    
+
+    
+
+    
+    companion object
+}
+
+/**
+ * @suppress
+ */
+public object FfiConverterTypeWarpStoredConfig: FfiConverterRustBuffer<WarpStoredConfig> {
+    override fun read(buf: ByteBuffer): WarpStoredConfig {
+        return WarpStoredConfig(
+            FfiConverterBoolean.read(buf),
+            FfiConverterBoolean.read(buf),
+            FfiConverterString.read(buf),
+            FfiConverterString.read(buf),
+            FfiConverterString.read(buf),
+            FfiConverterString.read(buf),
+            FfiConverterString.read(buf),
+            FfiConverterString.read(buf),
+            FfiConverterString.read(buf),
+            FfiConverterUShort.read(buf),
+            FfiConverterString.read(buf),
+            FfiConverterBoolean.read(buf),
+            FfiConverterString.read(buf),
+            FfiConverterLong.read(buf),
+            FfiConverterString.read(buf),
+        )
+    }
+
+    override fun allocationSize(value: WarpStoredConfig) = (
+            FfiConverterBoolean.allocationSize(value.`hasConfig`) +
+            FfiConverterBoolean.allocationSize(value.`tunnelActive`) +
+            FfiConverterString.allocationSize(value.`accountId`) +
+            FfiConverterString.allocationSize(value.`licenseKey`) +
+            FfiConverterString.allocationSize(value.`privateKey`) +
+            FfiConverterString.allocationSize(value.`publicKey`) +
+            FfiConverterString.allocationSize(value.`peerPublicKey`) +
+            FfiConverterString.allocationSize(value.`endpointHost`) +
+            FfiConverterString.allocationSize(value.`endpointIpv4`) +
+            FfiConverterUShort.allocationSize(value.`endpointPort`) +
+            FfiConverterString.allocationSize(value.`localAddressIpv4`) +
+            FfiConverterBoolean.allocationSize(value.`warpEnabled`) +
+            FfiConverterString.allocationSize(value.`accountType`) +
+            FfiConverterLong.allocationSize(value.`lastUpdatedSecs`) +
+            FfiConverterString.allocationSize(value.`configFilePath`)
+    )
+
+    override fun write(value: WarpStoredConfig, buf: ByteBuffer) {
+            FfiConverterBoolean.write(value.`hasConfig`, buf)
+            FfiConverterBoolean.write(value.`tunnelActive`, buf)
+            FfiConverterString.write(value.`accountId`, buf)
+            FfiConverterString.write(value.`licenseKey`, buf)
+            FfiConverterString.write(value.`privateKey`, buf)
+            FfiConverterString.write(value.`publicKey`, buf)
+            FfiConverterString.write(value.`peerPublicKey`, buf)
+            FfiConverterString.write(value.`endpointHost`, buf)
+            FfiConverterString.write(value.`endpointIpv4`, buf)
+            FfiConverterUShort.write(value.`endpointPort`, buf)
+            FfiConverterString.write(value.`localAddressIpv4`, buf)
+            FfiConverterBoolean.write(value.`warpEnabled`, buf)
+            FfiConverterString.write(value.`accountType`, buf)
+            FfiConverterLong.write(value.`lastUpdatedSecs`, buf)
+            FfiConverterString.write(value.`configFilePath`, buf)
+    }
+}
+
+
+
+/**
+ * What [`ImageTransform`] does with animated images.
+ */
+sealed class AnimationPolicy {
+    
+    /**
+     * Return animations unchanged (they are never resized or transcoded).
+     */
+    object PassThrough : AnimationPolicy()
+    
+    
+    /**
+     * Return only the first frame as a still image, which is then resized
+     * and transcoded like any other (PNG unless a format is requested).
+     */
+    object FirstFrame : AnimationPolicy()
+    
+    
+    /**
+     * Return animations within the budget unchanged; refuse others with
+     * `ProxyError::AnimationTooLarge`.
+     */
+    data class Budget(
+        /**
+         * Most frames allowed.
+         */
+        val `maxFrames`: kotlin.UInt, 
+        /**
+         * Largest encoded size allowed, in bytes.
+         */
+        val `maxBytes`: kotlin.ULong) : AnimationPolicy()
+        
+    {
+        
+
+        companion object
+    }
+    
+
+    
+
+    
+    
+
+
+    companion object
+}
+
+/**
+ * @suppress
+ */
+public object FfiConverterTypeAnimationPolicy : FfiConverterRustBuffer<AnimationPolicy>{
+    override fun read(buf: ByteBuffer): AnimationPolicy {
+        return when(buf.getInt()) {
+            1 -> AnimationPolicy.PassThrough
+            2 -> AnimationPolicy.FirstFrame
+            3 -> AnimationPolicy.Budget(
+                FfiConverterUInt.read(buf),
+                FfiConverterULong.read(buf),
+                )
+            else -> throw RuntimeException("invalid enum value, something is very wrong!!")
+        }
+    }
+
+    override fun allocationSize(value: AnimationPolicy) = when(value) {
+        is AnimationPolicy.PassThrough -> {
+            // Add the size for the Int that specifies the variant plus the size needed for all fields
+            (
+                4UL
+            )
+        }
+        is AnimationPolicy.FirstFrame -> {
+            // Add the size for the Int that specifies the variant plus the size needed for all fields
+            (
+                4UL
+            )
+        }
+        is AnimationPolicy.Budget -> {
+            // Add the size for the Int that specifies the variant plus the size needed for all fields
+            (
+                4UL
+                + FfiConverterUInt.allocationSize(value.`maxFrames`)
+                + FfiConverterULong.allocationSize(value.`maxBytes`)
+            )
+        }
+    }
+
+    override fun write(value: AnimationPolicy, buf: ByteBuffer) {
+        when(value) {
+            is AnimationPolicy.PassThrough -> {
+                buf.putInt(1)
+                Unit
+            }
+            is AnimationPolicy.FirstFrame -> {
+                buf.putInt(2)
+                Unit
+            }
+            is AnimationPolicy.Budget -> {
+                buf.putInt(3)
+                FfiConverterUInt.write(value.`maxFrames`, buf)
+                FfiConverterULong.write(value.`maxBytes`, buf)
+                Unit
+            }
+        }.let { /* this makes the `when` an expression, which ensures it is exhaustive */ }
+    }
+}
+
+
+
+
+
+/**
+ * How a [`CacheStorage`] relates to the built-in in-memory cache.
+ */
+
+enum class CacheStorageMode {
+    
+    /**
+     * Behind the memory cache (and the disk tier, if enabled): misses fall
+     * through to the storage and its hits are kept in memory.
+     */
+    ALONGSIDE,
+    /**
+     * In place of the memory cache: only pinned images stay in memory, and
+     * every other lookup goes to the storage.
+     */
+    INSTEAD;
+
+    
+
+
+    companion object
+}
+
+
+/**
+ * @suppress
+ */
+public object FfiConverterTypeCacheStorageMode: FfiConverterRustBuffer<CacheStorageMode> {
+    override fun read(buf: ByteBuffer) = try {
+        CacheStorageMode.values()[buf.getInt() - 1]
+    } catch (e: IndexOutOfBoundsException) {
+        throw RuntimeException("invalid enum value, something is very wrong!!", e)
+    }
+
+    override fun allocationSize(value: CacheStorageMode) = 4UL
+
+    override fun write(value: CacheStorageMode, buf: ByteBuffer) {
+        buf.putInt(value.ordinal + 1)
+    }
+}
+
+
+
+
+
+/**
+ * What an error is about, for choosing the message shown to the user.
+ */
+
+enum class ErrorCategory {
+    
+    /**
+     * The network, tunnel or remote server failed or could not be reached.
+     */
+    NETWORK,
+    /**
+     * The server answered, but with something the proxy refuses to return
+     * (not an image, too large, a tracking pixel, a redirect loop, ...).
+     */
+    CONTENT,
+    /**
+     * The request itself is invalid, e.g. a malformed or refused URL.
+     */
+    INPUT,
+    /**
+     * The proxy could not set itself up or use its storage.
+     */
+    CONFIGURATION,
+    /**
+     * The proxy's state prevented the request: not initialised, paused,
+     * throttled, or the request was cancelled.
+     */
+    STATE;
+
+    
+
+
+    companion object
+}
+
+
+/**
+ * @suppress
+ */
+public object FfiConverterTypeErrorCategory: FfiConverterRustBuffer<ErrorCategory> {
+    override fun read(buf: ByteBuffer) = try {
+        ErrorCategory.values()[buf.getInt() - 1]
+    } catch (e: IndexOutOfBoundsException) {
+        throw RuntimeException("invalid enum value, something is very wrong!!", e)
+    }
+
+    override fun allocationSize(value: ErrorCategory) = 4UL
+
+    override fun write(value: ErrorCategory, buf: ByteBuffer) {
+        buf.putInt(value.ordinal + 1)
+    }
+}
+
+
+
+
+
+/**
+ * Machine-readable kind of a [`ProxyError`], one per variant.
+ */
+
+enum class ErrorCode {
+    
+    NOT_INITIALIZED,
+    INITIALIZATION_FAILED,
+    PROVISIONING_FAILED,
+    TUNNEL_ERROR,
+    INVALID_URL,
+    HTTP_ERROR,
+    INVALID_CONTENT_TYPE,
+    RESPONSE_TOO_LARGE,
+    IMAGE_TOO_LARGE,
+    ANIMATION_TOO_LARGE,
+    IMAGE_PROCESSING_ERROR,
+    TRACKING_BLOCKED,
+    GROUP_BUDGET_EXCEEDED,
+    HASH_MISMATCH,
+    TOO_MANY_REDIRECTS,
+    INSECURE_URL,
+    REDIRECT_BLOCKED,
+    TIMEOUT,
+    CANCELLED,
+    PAUSED,
+    DISABLED,
+    THROTTLED,
+    DNS_ERROR,
+    TLS_ERROR,
+    STORAGE_ERROR,
+    CRYPTO_ERROR,
+    NETWORK_UNAVAILABLE;
+
+    
+    /**
+     * What errors of this kind are about.
+     */ fun `category`(): ErrorCategory {
+            return FfiConverterTypeErrorCategory.lift(
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_errorcode_category(FfiConverterTypeErrorCode.lower(this),
+        _status)
+}
+    )
+    }
+    
+
+    
+
+
+    companion object
+}
+
+
+/**
+ * @suppress
+ */
+public object FfiConverterTypeErrorCode: FfiConverterRustBuffer<ErrorCode> {
+    override fun read(buf: ByteBuffer) = try {
+        ErrorCode.values()[buf.getInt() - 1]
+    } catch (e: IndexOutOfBoundsException) {
+        throw RuntimeException("invalid enum value, something is very wrong!!", e)
+    }
+
+    override fun allocationSize(value: ErrorCode) = 4UL
+
+    override fun write(value: ErrorCode, buf: ByteBuffer) {
+        buf.putInt(value.ordinal + 1)
+    }
+}
+
+
+
+
+
+/**
+ * Scheduling priority for background fetches.
+ */
+
+enum class FetchPriority {
+    
+    /**
+     * Speculative work, e.g. messages further down the list.
+     */
+    LOW,
+    /**
+     * Default priority.
+     */
+    NORMAL,
+    /**
+     * Likely to be shown next, e.g. the adjacent message.
+     */
+    HIGH;
+
+    
+
+
+    companion object
+}
+
+
+/**
+ * @suppress
+ */
+public object FfiConverterTypeFetchPriority: FfiConverterRustBuffer<FetchPriority> {
+    override fun read(buf: ByteBuffer) = try {
+        FetchPriority.values()[buf.getInt() - 1]
+    } catch (e: IndexOutOfBoundsException) {
+        throw RuntimeException("invalid enum value, something is very wrong!!", e)
+    }
+
+    override fun allocationSize(value: FetchPriority) = 4UL
+
+    override fun write(value: FetchPriority, buf: ByteBuffer) {
+        buf.putInt(value.ordinal + 1)
+    }
+}
+
+
+
+
+
+/**
+ * How plain `http://` URLs (including redirect targets) are handled.
+ */
+
+enum class HttpsMode {
+    
+    /**
+     * Fetch `http://` URLs as given.
+     */
+    ALLOW_HTTP,
+    /**
+     * Rewrite `http://` to `https://`; fail if HTTPS does not work.
+     */
+    UPGRADE,
+    /**
+     * Rewrite `http://` to `https://`, retrying over plain HTTP if the secure
+     * connection cannot be established.
+     */
+    UPGRADE_WITH_FALLBACK,
+    /**
+     * Refuse `http://` URLs with `ProxyError::InsecureUrl`.
+     */
+    HTTPS_ONLY;
+
+    
+
+
+    companion object
+}
+
+
+/**
+ * @suppress
+ */
+public object FfiConverterTypeHttpsMode: FfiConverterRustBuffer<HttpsMode> {
+    override fun read(buf: ByteBuffer) = try {
+        HttpsMode.values()[buf.getInt() - 1]
+    } catch (e: IndexOutOfBoundsException) {
+        throw RuntimeException("invalid enum value, something is very wrong!!", e)
+    }
+
+    override fun allocationSize(value: HttpsMode) = 4UL
+
+    override fun write(value: HttpsMode, buf: ByteBuffer) {
+        buf.putInt(value.ordinal + 1)
+    }
+}
+
+
+
+
+
+/**
+ * Severity of a forwarded log record.
+ */
+
+enum class LogLevel {
+    
+    ERROR,
+    WARN,
+    INFO,
+    DEBUG,
+    TRACE;
+
+    
+
+
+    companion object
+}
+
+
+/**
+ * @suppress
+ */
+public object FfiConverterTypeLogLevel: FfiConverterRustBuffer<LogLevel> {
+    override fun read(buf: ByteBuffer) = try {
+        LogLevel.values()[buf.getInt() - 1]
+    } catch (e: IndexOutOfBoundsException) {
+        throw RuntimeException("invalid enum value, something is very wrong!!", e)
+    }
+
+    override fun allocationSize(value: LogLevel) = 4UL
+
+    override fun write(value: LogLevel, buf: ByteBuffer) {
+        buf.putInt(value.ordinal + 1)
+    }
+}
+
+
+
+
+
+/**
+ * The network the WireGuard socket sends from.
+ */
+sealed class NetworkBinding {
+    
+    /**
+     * Whichever network the device routes the WARP endpoint over.
+     */
+    object Default : NetworkBinding()
+    
+    
+    /**
+     * The interface owning this local IPv4 address, e.g. `"192.168.1.20"`.
+     * The WARP endpoint is IPv4, so IPv6 addresses are refused.
+     */
+    data class LocalAddress(
+        val `address`: kotlin.String) : NetworkBinding()
+        
+    {
+        
+
+        companion object
+    }
+    
+    /**
+     * The Android `Network` with this `getNetworkHandle()`, e.g. to force
+     * cellular or Wi-Fi. Tunnel starts fail on other platforms.
+     */
+    data class AndroidNetwork(
+        val `handle`: kotlin.ULong) : NetworkBinding()
+        
+    {
+        
+
+        companion object
+    }
+    
+
+    
+
+    
+    
+
+
+    companion object
+}
+
+/**
+ * @suppress
+ */
+public object FfiConverterTypeNetworkBinding : FfiConverterRustBuffer<NetworkBinding>{
+    override fun read(buf: ByteBuffer): NetworkBinding {
+        return when(buf.getInt()) {
+            1 -> NetworkBinding.Default
+            2 -> NetworkBinding.LocalAddress(
+                FfiConverterString.read(buf),
+                )
+            3 -> NetworkBinding.AndroidNetwork(
+                FfiConverterULong.read(buf),
+                )
+            else -> throw RuntimeException("invalid enum value, something is very wrong!!")
+        }
+    }
+
+    override fun allocationSize(value: NetworkBinding) = when(value) {
+        is NetworkBinding.Default -> {
+            // Add the size for the Int that specifies the variant plus the size needed for all fields
+            (
+                4UL
+            )
+        }
+        is NetworkBinding.LocalAddress -> {
+            // Add the size for the Int that specifies the variant plus the size needed for all fields
+            (
+                4UL
+                + FfiConverterString.allocationSize(value.`address`)
+            )
+        }
+        is NetworkBinding.AndroidNetwork -> {
+            // Add the size for the Int that specifies the variant plus the size needed for all fields
+            (
+                4UL
+                + FfiConverterULong.allocationSize(value.`handle`)
+            )
+        }
+    }
+
+    override fun write(value: NetworkBinding, buf: ByteBuffer) {
+        when(value) {
+            is NetworkBinding.Default -> {
+                buf.putInt(1)
+                Unit
+            }
+            is NetworkBinding.LocalAddress -> {
+                buf.putInt(2)
+                FfiConverterString.write(value.`address`, buf)
+                Unit
+            }
+            is NetworkBinding.AndroidNetwork -> {
+                buf.putInt(3)
+                FfiConverterULong.write(value.`handle`, buf)
+                Unit
+            }
+        }.let { /* this makes the `when` an expression, which ensures it is exhaustive */ }
+    }
+}
+
+
+
+
+
+/**
+ * Target format for [`ImageTransform::format`].
+ */
+
+enum class OutputFormat {
+    
+    /**
+     * Lossless, keeps transparency.
+     */
+    PNG,
+    /**
+     * Lossy at [`ImageTransform::quality`]; transparency is flattened.
+     */
+    JPEG,
+    /**
+     * Lossless WebP, keeps transparency.
+     */
+    WEBP;
+
+    
+
+
+    companion object
+}
+
+
+/**
+ * @suppress
+ */
+public object FfiConverterTypeOutputFormat: FfiConverterRustBuffer<OutputFormat> {
+    override fun read(buf: ByteBuffer) = try {
+        OutputFormat.values()[buf.getInt() - 1]
+    } catch (e: IndexOutOfBoundsException) {
+        throw RuntimeException("invalid enum value, something is very wrong!!", e)
+    }
+
+    override fun allocationSize(value: OutputFormat) = 4UL
+
+    override fun write(value: OutputFormat, buf: ByteBuffer) {
+        buf.putInt(value.ordinal + 1)
+    }
+}
+
+
+
+
+
+
+
+/**
+ * Errors that can occur during proxy operations.
+ */
+sealed class ProxyException: kotlin.Exception() {
+    
+    /**
+     * The proxy has not been initialized (call `proxy_init()` first) or has
+     * been shut down.
+     */
+    class NotInitialized(
+        ) : ProxyException() {
+        override val message
+            get() = ""
+    }
+    
+    /**
+     * Failed to initialize the proxy.
+     */
+    class InitializationFailed(
+        
+        /**
+         * Detailed error message
+         */
+        val `details`: kotlin.String
+        ) : ProxyException() {
+        override val message
+            get() = "details=${ `details` }"
+    }
+    
+    /**
+     * Failed to provision WARP credentials.
+     */
+    class ProvisioningFailed(
+        
+        /**
+         * Detailed error message
+         */
+        val `details`: kotlin.String
+        ) : ProxyException() {
+        override val message
+            get() = "details=${ `details` }"
+    }
+    
+    /**
+     * Failed to establish WireGuard tunnel.
+     */
+    class TunnelException(
+        
+        /**
+         * Detailed error message
+         */
+        val `details`: kotlin.String
+        ) : ProxyException() {
+        override val message
+            get() = "details=${ `details` }"
+    }
+    
+    /**
+     * Invalid URL provided.
+     */
+    class InvalidUrl(
+        
+        /**
+         * The invalid URL
+         */
+        val `url`: kotlin.String, 
+        
+        /**
+         * Detailed error message
+         */
+        val `details`: kotlin.String
+        ) : ProxyException() {
+        override val message
+            get() = "url=${ `url` }, details=${ `details` }"
+    }
+    
+    /**
+     * HTTP request failed.
+     */
+    class HttpException(
+        
+        /**
+         * HTTP status code (0 if connection failed)
+         */
+        val `statusCode`: kotlin.UShort, 
+        
+        /**
+         * Detailed error message
+         */
+        val `details`: kotlin.String
+        ) : ProxyException() {
+        override val message
+            get() = "statusCode=${ `statusCode` }, details=${ `details` }"
+    }
+    
+    /**
+     * The response content type is not an image.
+     */
+    class InvalidContentType(
+        
+        /**
+         * The actual content type received
+         */
+        val `contentType`: kotlin.String
+        ) : ProxyException() {
+        override val message
+            get() = "contentType=${ `contentType` }"
+    }
+    
+    /**
+     * The response is too large.
+     */
+    class ResponseTooLarge(
+        
+        /**
+         * Actual size in bytes
+         */
+        val `size`: kotlin.ULong, 
+        
+        /**
+         * Maximum allowed size in bytes
+         */
+        val `maxSize`: kotlin.ULong
+        ) : ProxyException() {
+        override val message
+            get() = "size=${ `size` }, maxSize=${ `maxSize` }"
+    }
+    
+    /**
+     * The image's declared dimensions exceed the decoded pixel limit.
+     */
+    class ImageTooLarge(
+        
+        /**
+         * Declared width in pixels
+         */
+        val `width`: kotlin.UInt, 
+        
+        /**
+         * Declared height in pixels
+         */
+        val `height`: kotlin.UInt, 
+        
+        /**
+         * Maximum allowed pixel count
+         */
+        val `maxPixels`: kotlin.ULong
+        ) : ProxyException() {
+        override val message
+            get() = "width=${ `width` }, height=${ `height` }, maxPixels=${ `maxPixels` }"
+    }
+    
+    /**
+     * An animation exceeds the request's frame or byte budget.
+     */
+    class AnimationTooLarge(
+        
+        /**
+         * Frames in the animation
+         */
+        val `frames`: kotlin.UInt, 
+        
+        /**
+         * Size of the animation in bytes
+         */
+        val `size`: kotlin.ULong, 
+        
+        /**
+         * Maximum allowed frames
+         */
+        val `maxFrames`: kotlin.UInt, 
+        
+        /**
+         * Maximum allowed bytes
+         */
+        val `maxBytes`: kotlin.ULong
+        ) : ProxyException() {
+        override val message
+            get() = "frames=${ `frames` }, size=${ `size` }, maxFrames=${ `maxFrames` }, maxBytes=${ `maxBytes` }"
+    }
+    
+    /**
+     * The image could not be decoded or re-encoded for a transform.
+     */
+    class ImageProcessingException(
+        
+        /**
+         * Detailed error message
+         */
+        val `details`: kotlin.String
+        ) : ProxyException() {
+        override val message
+            get() = "details=${ `details` }"
+    }
+    
+    /**
+     * The image was refused as a suspected tracking pixel.
+     */
+    class TrackingBlocked(
+        
+        /**
+         * The requested URL
+         */
+        val `url`: kotlin.String, 
+        
+        /**
+         * Why it was suspected (tracker domain, empty body or pixel size)
+         */
+        val `reason`: kotlin.String
+        ) : ProxyException() {
+        override val message
+            get() = "url=${ `url` }, reason=${ `reason` }"
+    }
+    
+    /**
+     * The fetch group's byte budget is spent.
+     */
+    class GroupBudgetExceeded(
+        
+        /**
+         * The group passed with the fetch
+         */
+        val `groupId`: kotlin.String, 
+        
+        /**
+         * The budget of each group in bytes
+         */
+        val `maxBytes`: kotlin.ULong
+        ) : ProxyException() {
+        override val message
+            get() = "groupId=${ `groupId` }, maxBytes=${ `maxBytes` }"
+    }
+    
+    /**
+     * The image does not have the content hash the caller expected.
+     */
+    class HashMismatch(
+        
+        /**
+         * The requested URL
+         */
+        val `url`: kotlin.String, 
+        
+        /**
+         * The expected SHA-256, as passed
+         */
+        val `expected`: kotlin.String, 
+        
+        /**
+         * SHA-256 of the body received, as lowercase hex
+         */
+        val `actual`: kotlin.String
+        ) : ProxyException() {
+        override val message
+            get() = "url=${ `url` }, expected=${ `expected` }, actual=${ `actual` }"
+    }
+    
+    /**
+     * Too many redirects.
+     */
+    class TooManyRedirects(
+        
+        /**
+         * Number of redirects encountered
+         */
+        val `count`: kotlin.UInt, 
+        
+        /**
+         * Maximum allowed redirects
+         */
+        val `maxCount`: kotlin.UInt
+        ) : ProxyException() {
+        override val message
+            get() = "count=${ `count` }, maxCount=${ `maxCount` }"
+    }
+    
+    /**
+     * A plain `http://` URL was refused because HTTPS-only mode is active.
+     */
+    class InsecureUrl(
+        
+        /**
+         * The refused URL
+         */
+        val `url`: kotlin.String
+        ) : ProxyException() {
+        override val message
+            get() = "url=${ `url` }"
+    }
+    
+    /**
+     * A redirect was refused by the redirect policy.
+     */
+    class RedirectBlocked(
+        
+        /**
+         * The redirect target
+         */
+        val `url`: kotlin.String, 
+        
+        /**
+         * Which rule refused it
+         */
+        val `reason`: kotlin.String
+        ) : ProxyException() {
+        override val message
+            get() = "url=${ `url` }, reason=${ `reason` }"
+    }
+    
+    /**
+     * A phase of the request ran out of its time budget.
+     */
+    class Timeout(
+        
+        /**
+         * The phase that ran out of time
+         */
+        val `phase`: TimeoutPhase, 
+        
+        /**
+         * The phase's budget in seconds
+         */
+        val `seconds`: kotlin.UInt
+        ) : ProxyException() {
+        override val message
+            get() = "phase=${ `phase` }, seconds=${ `seconds` }"
+    }
+    
+    /**
+     * The request was cancelled before it completed, e.g. because its
+     * batch was cancelled.
+     */
+    class Cancelled(
+        ) : ProxyException() {
+        override val message
+            get() = ""
+    }
+    
+    /**
+     * Network activity is paused (`proxy_pause()`), and the request could
+     * not be held until it resumes: the tunnel is not up yet, or too many
+     * requests are already waiting.
+     */
+    class Paused(
+        ) : ProxyException() {
+        override val message
+            get() = ""
+    }
+    
+    /**
+     * The proxy is switched off (`proxy_set_enabled(false)`, or for every
+     * client `proxy_set_enabled_globally(false)`).
+     */
+    class Disabled(
+        ) : ProxyException() {
+        override val message
+            get() = ""
+    }
+    
+    /**
+     * Too many requests are already waiting under the rate limit.
+     */
+    class Throttled(
+        ) : ProxyException() {
+        override val message
+            get() = ""
+    }
+    
+    /**
+     * DNS resolution failed.
+     */
+    class DnsException(
+        
+        /**
+         * The host that failed to resolve
+         */
+        val `host`: kotlin.String, 
+        
+        /**
+         * Detailed error message
+         */
+        val `details`: kotlin.String
+        ) : ProxyException() {
+        override val message
+            get() = "host=${ `host` }, details=${ `details` }"
+    }
+    
+    /**
+     * TLS handshake failed.
+     */
+    class TlsException(
+        
+        /**
+         * Detailed error message
+         */
+        val `details`: kotlin.String
+        ) : ProxyException() {
+        override val message
+            get() = "details=${ `details` }"
+    }
+    
+    /**
+     * Failed to read or write configuration.
+     */
+    class StorageException(
+        
+        /**
+         * Detailed error message
+         */
+        val `details`: kotlin.String
+        ) : ProxyException() {
+        override val message
+            get() = "details=${ `details` }"
+    }
+    
+    /**
+     * WireGuard cryptographic operation failed.
+     */
+    class CryptoException(
+        
+        /**
+         * Detailed error message
+         */
+        val `details`: kotlin.String
+        ) : ProxyException() {
+        override val message
+            get() = "details=${ `details` }"
+    }
+    
+    /**
+     * Network is unavailable.
+     */
+    class NetworkUnavailable(
+        
+        /**
+         * Detailed error message
+         */
+        val `details`: kotlin.String
+        ) : ProxyException() {
+        override val message
+            get() = "details=${ `details` }"
+    }
+    
+
+    
+        /**
+         * What the error is about.
+         */ fun `category`(): ErrorCategory {
+            return FfiConverterTypeErrorCategory.lift(
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyerror_category(FfiConverterTypeProxyError.lower(this),
+        _status)
+}
+    )
+    }
+    
+
+    
+        /**
+         * The variant, without its details.
+         */ fun `code`(): ErrorCode {
+            return FfiConverterTypeErrorCode.lift(
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyerror_code(FfiConverterTypeProxyError.lower(this),
+        _status)
+}
+    )
+    }
+    
+
+    
+        /**
+         * The HTTP status the server answered with, if it answered at all.
+         */ fun `httpStatus`(): kotlin.UShort? {
+            return FfiConverterOptionalUShort.lift(
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyerror_http_status(FfiConverterTypeProxyError.lower(this),
+        _status)
+}
+    )
+    }
+    
+
+    
+        /**
+         * Whether the error is transient, i.e. the same request may succeed
+         * later: the network or tunnel failed, the server asked to come back
+         * later (the default retry policy's statuses), or the proxy is paused or
+         * throttled.
+         * Everything else is permanent and fails the same way again.
+         */ fun `isRetryable`(): kotlin.Boolean {
+            return FfiConverterBoolean.lift(
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_method_proxyerror_is_retryable(FfiConverterTypeProxyError.lower(this),
+        _status)
+}
+    )
+    }
+    
+
+    
+
+
+    companion object ErrorHandler : UniffiRustCallStatusErrorHandler<ProxyException> {
+        override fun lift(error_buf: RustBuffer.ByValue): ProxyException = FfiConverterTypeProxyError.lift(error_buf)
+    }
+
+    
+}
+
+/**
+ * @suppress
+ */
+public object FfiConverterTypeProxyError : FfiConverterRustBuffer<ProxyException> {
+    override fun read(buf: ByteBuffer): ProxyException {
+        
+
+        return when(buf.getInt()) {
+            1 -> ProxyException.NotInitialized()
+            2 -> ProxyException.InitializationFailed(
+                FfiConverterString.read(buf),
+                )
+            3 -> ProxyException.ProvisioningFailed(
+                FfiConverterString.read(buf),
+                )
+            4 -> ProxyException.TunnelException(
+                FfiConverterString.read(buf),
+                )
+            5 -> ProxyException.InvalidUrl(
+                FfiConverterString.read(buf),
+                FfiConverterString.read(buf),
+                )
+            6 -> ProxyException.HttpException(
+                FfiConverterUShort.read(buf),
+                FfiConverterString.read(buf),
+                )
+            7 -> ProxyException.InvalidContentType(
+                FfiConverterString.read(buf),
+                )
+            8 -> ProxyException.ResponseTooLarge(
+                FfiConverterULong.read(buf),
+                FfiConverterULong.read(buf),
+                )
+            9 -> ProxyException.ImageTooLarge(
+                FfiConverterUInt.read(buf),
+                FfiConverterUInt.read(buf),
+                FfiConverterULong.read(buf),
+                )
+            10 -> ProxyException.AnimationTooLarge(
+                FfiConverterUInt.read(buf),
+                FfiConverterULong.read(buf),
+                FfiConverterUInt.read(buf),
+                FfiConverterULong.read(buf),
+                )
+            11 -> ProxyException.ImageProcessingException(
+                FfiConverterString.read(buf),
+                )
+            12 -> ProxyException.TrackingBlocked(
+                FfiConverterString.read(buf),
+                FfiConverterString.read(buf),
+                )
+            13 -> ProxyException.GroupBudgetExceeded(
+                FfiConverterString.read(buf),
+                FfiConverterULong.read(buf),
+                )
+            14 -> ProxyException.HashMismatch(
+                FfiConverterString.read(buf),
+                FfiConverterString.read(buf),
+                FfiConverterString.read(buf),
+                )
+            15 -> ProxyException.TooManyRedirects(
+                FfiConverterUInt.read(buf),
+                FfiConverterUInt.read(buf),
+                )
+            16 -> ProxyException.InsecureUrl(
+                FfiConverterString.read(buf),
+                )
+            17 -> ProxyException.RedirectBlocked(
+                FfiConverterString.read(buf),
+                FfiConverterString.read(buf),
+                )
+            18 -> ProxyException.Timeout(
+                FfiConverterTypeTimeoutPhase.read(buf),
+                FfiConverterUInt.read(buf),
+                )
+            19 -> ProxyException.Cancelled()
+            20 -> ProxyException.Paused()
+            21 -> ProxyException.Disabled()
+            22 -> ProxyException.Throttled()
+            23 -> ProxyException.DnsException(
+                FfiConverterString.read(buf),
+                FfiConverterString.read(buf),
+                )
+            24 -> ProxyException.TlsException(
+                FfiConverterString.read(buf),
+                )
+            25 -> ProxyException.StorageException(
+                FfiConverterString.read(buf),
+                )
+            26 -> ProxyException.CryptoException(
+                FfiConverterString.read(buf),
+                )
+            27 -> ProxyException.NetworkUnavailable(
+                FfiConverterString.read(buf),
+                )
+            else -> throw RuntimeException("invalid error enum value, something is very wrong!!")
+        }
+    }
+
+    override fun allocationSize(value: ProxyException): ULong {
+        return when(value) {
+            is ProxyException.NotInitialized -> (
+                // Add the size for the Int that specifies the variant plus the size needed for all fields
+                4UL
+            )
+            is ProxyException.InitializationFailed -> (
+                // Add the size for the Int that specifies the variant plus the size needed for all fields
+                4UL
+                + FfiConverterString.allocationSize(value.`details`)
+            )
+            is ProxyException.ProvisioningFailed -> (
+                // Add the size for the Int that specifies the variant plus the size needed for all fields
+                4UL
+                + FfiConverterString.allocationSize(value.`details`)
+            )
+            is ProxyException.TunnelException -> (
+                // Add the size for the Int that specifies the variant plus the size needed for all fields
+                4UL
+                + FfiConverterString.allocationSize(value.`details`)
+            )
+            is ProxyException.InvalidUrl -> (
+                // Add the size for the Int that specifies the variant plus the size needed for all fields
+                4UL
+                + FfiConverterString.allocationSize(value.`url`)
+                + FfiConverterString.allocationSize(value.`details`)
+            )
+            is ProxyException.HttpException -> (
+                // Add the size for the Int that specifies the variant plus the size needed for all fields
+                4UL
+                + FfiConverterUShort.allocationSize(value.`statusCode`)
+                + FfiConverterString.allocationSize(value.`details`)
+            )
+            is ProxyException.InvalidContentType -> (
+                // Add the size for the Int that specifies the variant plus the size needed for all fields
+                4UL
+                + FfiConverterString.allocationSize(value.`contentType`)
+            )
+            is ProxyException.ResponseTooLarge -> (
+                // Add the size for the Int that specifies the variant plus the size needed for all fields
+                4UL
+                + FfiConverterULong.allocationSize(value.`size`)
+                + FfiConverterULong.allocationSize(value.`maxSize`)
+            )
+            is ProxyException.ImageTooLarge -> (
+                // Add the size for the Int that specifies the variant plus the size needed for all fields
+                4UL
+                + FfiConverterUInt.allocationSize(value.`width`)
+                + FfiConverterUInt.allocationSize(value.`height`)
+                + FfiConverterULong.allocationSize(value.`maxPixels`)
+            )
+            is ProxyException.AnimationTooLarge -> (
+                // Add the size for the Int that specifies the variant plus the size needed for all fields
+                4UL
+                + FfiConverterUInt.allocationSize(value.`frames`)
+                + FfiConverterULong.allocationSize(value.`size`)
+                + FfiConverterUInt.allocationSize(value.`maxFrames`)
+                + FfiConverterULong.allocationSize(value.`maxBytes`)
+            )
+            is ProxyException.ImageProcessingException -> (
+                // Add the size for the Int that specifies the variant plus the size needed for all fields
+                4UL
+                + FfiConverterString.allocationSize(value.`details`)
+            )
+            is ProxyException.TrackingBlocked -> (
+                // Add the size for the Int that specifies the variant plus the size needed for all fields
+                4UL
+                + FfiConverterString.allocationSize(value.`url`)
+                + FfiConverterString.allocationSize(value.`reason`)
+            )
+            is ProxyException.GroupBudgetExceeded -> (
+                // Add the size for the Int that specifies the variant plus the size needed for all fields
+                4UL
+                + FfiConverterString.allocationSize(value.`groupId`)
+                + FfiConverterULong.allocationSize(value.`maxBytes`)
+            )
+            is ProxyException.HashMismatch -> (
+                // Add the size for the Int that specifies the variant plus the size needed for all fields
+                4UL
+                + FfiConverterString.allocationSize(value.`url`)
+                + FfiConverterString.allocationSize(value.`expected`)
+                + FfiConverterString.allocationSize(value.`actual`)
+            )
+            is ProxyException.TooManyRedirects -> (
+                // Add the size for the Int that specifies the variant plus the size needed for all fields
+                4UL
+                + FfiConverterUInt.allocationSize(value.`count`)
+                + FfiConverterUInt.allocationSize(value.`maxCount`)
+            )
+            is ProxyException.InsecureUrl -> (
+                // Add the size for the Int that specifies the variant plus the size needed for all fields
+                4UL
+                + FfiConverterString.allocationSize(value.`url`)
+            )
+            is ProxyException.RedirectBlocked -> (
+                // Add the size for the Int that specifies the variant plus the size needed for all fields
+                4UL
+                + FfiConverterString.allocationSize(value.`url`)
+                + FfiConverterString.allocationSize(value.`reason`)
+            )
+            is ProxyException.Timeout -> (
+                // Add the size for the Int that specifies the variant plus the size needed for all fields
+                4UL
+                + FfiConverterTypeTimeoutPhase.allocationSize(value.`phase`)
+                + FfiConverterUInt.allocationSize(value.`seconds`)
+            )
+            is ProxyException.Cancelled -> (
+                // Add the size for the Int that specifies the variant plus the size needed for all fields
+                4UL
+            )
+            is ProxyException.Paused -> (
+                // Add the size for the Int that specifies the variant plus the size needed for all fields
+                4UL
+            )
+            is ProxyException.Disabled -> (
+                // Add the size for the Int that specifies the variant plus the size needed for all fields
+                4UL
+            )
+            is ProxyException.Throttled -> (
+                // Add the size for the Int that specifies the variant plus the size needed for all fields
+                4UL
+            )
+            is ProxyException.DnsException -> (
+                // Add the size for the Int that specifies the variant plus the size needed for all fields
+                4UL
+                + FfiConverterString.allocationSize(value.`host`)
+                + FfiConverterString.allocationSize(value.`details`)
+            )
+            is ProxyException.TlsException -> (
+                // Add the size for the Int that specifies the variant plus the size needed for all fields
+                4UL
+                + FfiConverterString.allocationSize(value.`details`)
+            )
+            is ProxyException.StorageException -> (
+                // Add the size for the Int that specifies the variant plus the size needed for all fields
+                4UL
+                + FfiConverterString.allocationSize(value.`details`)
+            )
+            is ProxyException.CryptoException -> (
+                // Add the size for the Int that specifies the variant plus the size needed for all fields
+                4UL
+                + FfiConverterString.allocationSize(value.`details`)
+            )
+            is ProxyException.NetworkUnavailable -> (
+                // Add the size for the Int that specifies the variant plus the size needed for all fields
+                4UL
+                + FfiConverterString.allocationSize(value.`details`)
+            )
+        }
+    }
+
+    override fun write(value: ProxyException, buf: ByteBuffer) {
+        when(value) {
+            is ProxyException.NotInitialized -> {
+                buf.putInt(1)
+                Unit
+            }
+            is ProxyException.InitializationFailed -> {
+                buf.putInt(2)
+                FfiConverterString.write(value.`details`, buf)
+                Unit
+            }
+            is ProxyException.ProvisioningFailed -> {
+                buf.putInt(3)
+                FfiConverterString.write(value.`details`, buf)
+                Unit
+            }
+            is ProxyException.TunnelException -> {
+                buf.putInt(4)
+                FfiConverterString.write(value.`details`, buf)
+                Unit
+            }
+            is ProxyException.InvalidUrl -> {
+                buf.putInt(5)
+                FfiConverterString.write(value.`url`, buf)
+                FfiConverterString.write(value.`details`, buf)
+                Unit
+            }
+            is ProxyException.HttpException -> {
+                buf.putInt(6)
+                FfiConverterUShort.write(value.`statusCode`, buf)
+                FfiConverterString.write(value.`details`, buf)
+                Unit
+            }
+            is ProxyException.InvalidContentType -> {
+                buf.putInt(7)
+                FfiConverterString.write(value.`contentType`, buf)
+                Unit
+            }
+            is ProxyException.ResponseTooLarge -> {
+                buf.putInt(8)
+                FfiConverterULong.write(value.`size`, buf)
+                FfiConverterULong.write(value.`maxSize`, buf)
+                Unit
+            }
+            is ProxyException.ImageTooLarge -> {
+                buf.putInt(9)
+                FfiConverterUInt.write(value.`width`, buf)
+                FfiConverterUInt.write(value.`height`, buf)
+                FfiConverterULong.write(value.`maxPixels`, buf)
+                Unit
+            }
+            is ProxyException.AnimationTooLarge -> {
+                buf.putInt(10)
+                FfiConverterUInt.write(value.`frames`, buf)
+                FfiConverterULong.write(value.`size`, buf)
+                FfiConverterUInt.write(value.`maxFrames`, buf)
+                FfiConverterULong.write(value.`maxBytes`, buf)
+                Unit
+            }
+            is ProxyException.ImageProcessingException -> {
+                buf.putInt(11)
+                FfiConverterString.write(value.`details`, buf)
+                Unit
+            }
+            is ProxyException.TrackingBlocked -> {
+                buf.putInt(12)
+                FfiConverterString.write(value.`url`, buf)
+                FfiConverterString.write(value.`reason`, buf)
+                Unit
+            }
+            is ProxyException.GroupBudgetExceeded -> {
+                buf.putInt(13)
+                FfiConverterString.write(value.`groupId`, buf)
+                FfiConverterULong.write(value.`maxBytes`, buf)
+                Unit
+            }
+            is ProxyException.HashMismatch -> {
+                buf.putInt(14)
+                FfiConverterString.write(value.`url`, buf)
+                FfiConverterString.write(value.`expected`, buf)
+                FfiConverterString.write(value.`actual`, buf)
+                Unit
+            }
+            is ProxyException.TooManyRedirects -> {
+                buf.putInt(15)
+                FfiConverterUInt.write(value.`count`, buf)
+                FfiConverterUInt.write(value.`maxCount`, buf)
+                Unit
+            }
+            is ProxyException.InsecureUrl -> {
+                buf.putInt(16)
+                FfiConverterString.write(value.`url`, buf)
+                Unit
+            }
+            is ProxyException.RedirectBlocked -> {
+                buf.putInt(17)
+                FfiConverterString.write(value.`url`, buf)
+                FfiConverterString.write(value.`reason`, buf)
+                Unit
+            }
+            is ProxyException.Timeout -> {
+                buf.putInt(18)
+                FfiConverterTypeTimeoutPhase.write(value.`phase`, buf)
+                FfiConverterUInt.write(value.`seconds`, buf)
+                Unit
+            }
+            is ProxyException.Cancelled -> {
+                buf.putInt(19)
+                Unit
+            }
+            is ProxyException.Paused -> {
+                buf.putInt(20)
+                Unit
+            }
+            is ProxyException.Disabled -> {
+                buf.putInt(21)
+                Unit
+            }
+            is ProxyException.Throttled -> {
+                buf.putInt(22)
+                Unit
+            }
+            is ProxyException.DnsException -> {
+                buf.putInt(23)
+                FfiConverterString.write(value.`host`, buf)
+                FfiConverterString.write(value.`details`, buf)
+                Unit
+            }
+            is ProxyException.TlsException -> {
+                buf.putInt(24)
+                FfiConverterString.write(value.`details`, buf)
+                Unit
+            }
+            is ProxyException.StorageException -> {
+                buf.putInt(25)
+                FfiConverterString.write(value.`details`, buf)
+                Unit
+            }
+            is ProxyException.CryptoException -> {
+                buf.putInt(26)
+                FfiConverterString.write(value.`details`, buf)
+                Unit
+            }
+            is ProxyException.NetworkUnavailable -> {
+                buf.putInt(27)
+                FfiConverterString.write(value.`details`, buf)
+                Unit
+            }
+        }.let { /* this makes the `when` an expression, which ensures it is exhaustive */ }
+    }
+
+}
+
+
+
+/**
+ * A change in proxy state worth surfacing to the app.
+ */
+sealed class ProxyEvent {
+    
+    /**
+     * The WireGuard tunnel completed its handshake with `endpoint`.
+     */
+    data class TunnelConnected(
+        val `endpoint`: kotlin.String) : ProxyEvent()
+        
+    {
+        
+
+        companion object
+    }
+    
+    /**
+     * The tunnel was torn down, e.g. by shutdown or an identity reset.
+     */
+    data class TunnelDisconnected(
+        val `reason`: kotlin.String) : ProxyEvent()
+        
+    {
+        
+
+        companion object
+    }
+    
+    /**
+     * A new WARP identity was registered with Cloudflare.
+     */
+    data class ProvisioningCompleted(
+        val `accountType`: kotlin.String) : ProxyEvent()
+        
+    {
+        
+
+        companion object
+    }
+    
+    /**
+     * An upgraded `https://` request for `url` failed to connect and was
+     * retried over plain HTTP, still through the tunnel.
+     */
+    data class HttpsFallback(
+        val `url`: kotlin.String) : ProxyEvent()
+        
+    {
+        
+
+        companion object
+    }
+    
+    /**
+     * `count` cached images (`bytes` of data) were evicted from memory.
+     */
+    data class CacheEvicted(
+        val `count`: kotlin.UInt, 
+        val `bytes`: kotlin.ULong) : ProxyEvent()
+        
+    {
+        
+
+        companion object
+    }
+    
+    /**
+     * An operation failed; `message` is also reported as the last error.
+     */
+    data class Error(
+        val `message`: kotlin.String) : ProxyEvent()
+        
+    {
+        
+
+        companion object
+    }
+    
+
+    
+
+    
+    
+
+
+    companion object
+}
+
+/**
+ * @suppress
+ */
+public object FfiConverterTypeProxyEvent : FfiConverterRustBuffer<ProxyEvent>{
+    override fun read(buf: ByteBuffer): ProxyEvent {
+        return when(buf.getInt()) {
+            1 -> ProxyEvent.TunnelConnected(
+                FfiConverterString.read(buf),
+                )
+            2 -> ProxyEvent.TunnelDisconnected(
+                FfiConverterString.read(buf),
+                )
+            3 -> ProxyEvent.ProvisioningCompleted(
+                FfiConverterString.read(buf),
+                )
+            4 -> ProxyEvent.HttpsFallback(
+                FfiConverterString.read(buf),
+                )
+            5 -> ProxyEvent.CacheEvicted(
+                FfiConverterUInt.read(buf),
+                FfiConverterULong.read(buf),
+                )
+            6 -> ProxyEvent.Error(
+                FfiConverterString.read(buf),
+                )
+            else -> throw RuntimeException("invalid enum value, something is very wrong!!")
+        }
+    }
+
+    override fun allocationSize(value: ProxyEvent) = when(value) {
+        is ProxyEvent.TunnelConnected -> {
+            // Add the size for the Int that specifies the variant plus the size needed for all fields
+            (
+                4UL
+                + FfiConverterString.allocationSize(value.`endpoint`)
+            )
+        }
+        is ProxyEvent.TunnelDisconnected -> {
+            // Add the size for the Int that specifies the variant plus the size needed for all fields
+            (
+                4UL
+                + FfiConverterString.allocationSize(value.`reason`)
+            )
+        }
+        is ProxyEvent.ProvisioningCompleted -> {
+            // Add the size for the Int that specifies the variant plus the size needed for all fields
+            (
+                4UL
+                + FfiConverterString.allocationSize(value.`accountType`)
+            )
+        }
+        is ProxyEvent.HttpsFallback -> {
+            // Add the size for the Int that specifies the variant plus the size needed for all fields
+            (
+                4UL
+                + FfiConverterString.allocationSize(value.`url`)
+            )
+        }
+        is ProxyEvent.CacheEvicted -> {
+            // Add the size for the Int that specifies the variant plus the size needed for all fields
+            (
+                4UL
+                + FfiConverterUInt.allocationSize(value.`count`)
+                + FfiConverterULong.allocationSize(value.`bytes`)
+            )
+        }
+        is ProxyEvent.Error -> {
+            // Add the size for the Int that specifies the variant plus the size needed for all fields
+            (
+                4UL
+                + FfiConverterString.allocationSize(value.`message`)
+            )
+        }
+    }
+
+    override fun write(value: ProxyEvent, buf: ByteBuffer) {
+        when(value) {
+            is ProxyEvent.TunnelConnected -> {
+                buf.putInt(1)
+                FfiConverterString.write(value.`endpoint`, buf)
+                Unit
+            }
+            is ProxyEvent.TunnelDisconnected -> {
+                buf.putInt(2)
+                FfiConverterString.write(value.`reason`, buf)
+                Unit
+            }
+            is ProxyEvent.ProvisioningCompleted -> {
+                buf.putInt(3)
+                FfiConverterString.write(value.`accountType`, buf)
+                Unit
+            }
+            is ProxyEvent.HttpsFallback -> {
+                buf.putInt(4)
+                FfiConverterString.write(value.`url`, buf)
+                Unit
+            }
+            is ProxyEvent.CacheEvicted -> {
+                buf.putInt(5)
+                FfiConverterUInt.write(value.`count`, buf)
+                FfiConverterULong.write(value.`bytes`, buf)
+                Unit
+            }
+            is ProxyEvent.Error -> {
+                buf.putInt(6)
+                FfiConverterString.write(value.`message`, buf)
+                Unit
+            }
+        }.let { /* this makes the `when` an expression, which ensures it is exhaustive */ }
+    }
+}
+
+
+
+
+
+/**
+ * How urgently the tunnel should serve a request.
+ *
+ * The tunnel handles one request at a time and always takes the oldest
+ * waiting request of the most urgent level next, so on-screen images are not
+ * stuck behind prefetches.
+ */
+
+enum class RequestPriority {
+    
+    /**
+     * Needed on screen now.
+     */
+    VISIBLE,
+    /**
+     * Cache warming for content the user is likely to open next.
+     */
+    PREFETCH,
+    /**
+     * Work nobody is waiting on, e.g. update checks.
+     */
+    BACKGROUND;
+
+    
+
+
+    companion object
+}
+
+
+/**
+ * @suppress
+ */
+public object FfiConverterTypeRequestPriority: FfiConverterRustBuffer<RequestPriority> {
+    override fun read(buf: ByteBuffer) = try {
+        RequestPriority.values()[buf.getInt() - 1]
+    } catch (e: IndexOutOfBoundsException) {
+        throw RuntimeException("invalid enum value, something is very wrong!!", e)
+    }
+
+    override fun allocationSize(value: RequestPriority) = 4UL
+
+    override fun write(value: RequestPriority, buf: ByteBuffer) {
+        buf.putInt(value.ordinal + 1)
+    }
+}
+
+
+
+
+
+/**
+ * Kind of non-image resource fetched by [`crate::proxy_fetch_resource`];
+ * decides the accepted content types.
+ */
+
+enum class ResourceKind {
+    
+    /**
+     * WOFF, WOFF2, TrueType, OpenType or EOT web fonts.
+     */
+    FONT,
+    /**
+     * CSS stylesheets (`text/css`, UTF-8).
+     */
+    STYLESHEET;
+
+    
+
+
+    companion object
+}
+
+
+/**
+ * @suppress
+ */
+public object FfiConverterTypeResourceKind: FfiConverterRustBuffer<ResourceKind> {
+    override fun read(buf: ByteBuffer) = try {
+        ResourceKind.values()[buf.getInt() - 1]
+    } catch (e: IndexOutOfBoundsException) {
+        throw RuntimeException("invalid enum value, something is very wrong!!", e)
+    }
+
+    override fun allocationSize(value: ResourceKind) = 4UL
+
+    override fun write(value: ResourceKind, buf: ByteBuffer) {
+        buf.putInt(value.ordinal + 1)
+    }
+}
+
+
+
+
+
+/**
+ * The phase of a request a [`ProxyError::Timeout`] happened in; each but
+ * `Tunnel` has its own budget in [`TimeoutBudget`](crate::TimeoutBudget).
+ */
+
+enum class TimeoutPhase {
+    
+    /**
+     * Bringing up the WARP tunnel: registration and WireGuard handshake.
+     */
+    TUNNEL,
+    /**
+     * Resolving the host over DNS-over-HTTPS.
+     */
+    DNS,
+    /**
+     * Opening the TCP connection.
+     */
+    CONNECT,
+    /**
+     * The TLS handshake.
+     */
+    TLS,
+    /**
+     * Sending the request and waiting for the response head.
+     */
+    FIRST_BYTE,
+    /**
+     * Receiving the response body.
+     */
+    BODY;
+
+    
+
+
+    companion object
+}
+
+
+/**
+ * @suppress
+ */
+public object FfiConverterTypeTimeoutPhase: FfiConverterRustBuffer<TimeoutPhase> {
+    override fun read(buf: ByteBuffer) = try {
+        TimeoutPhase.values()[buf.getInt() - 1]
+    } catch (e: IndexOutOfBoundsException) {
+        throw RuntimeException("invalid enum value, something is very wrong!!", e)
+    }
+
+    override fun allocationSize(value: TimeoutPhase) = 4UL
+
+    override fun write(value: TimeoutPhase, buf: ByteBuffer) {
+        buf.putInt(value.ordinal + 1)
+    }
+}
+
+
+
+
+
+/**
+ * Outcome of [`proxy_tls_self_test`].
+ */
+sealed class TlsSelfTestOutcome {
+    
+    /**
+     * The handshake to Cloudflare completed and the server certificate verified
+     * against the bundled `webpki-roots` trust anchors. The strongest pass.
+     */
+    object Verified : TlsSelfTestOutcome()
+    
+    
+    /**
+     * The probe could not complete for a transient reason (DNS failure,
+     * connection refused/reset, timeout, ...). The certificate verifier was not
+     * the cause, so callers should treat this as a (noted) pass rather than a
+     * regression.
+     */
+    data class Inconclusive(
+        val `reason`: kotlin.String) : TlsSelfTestOutcome()
+        
+    {
+        
+
+        companion object
+    }
+    
+    /**
+     * The certificate verifier was reached and faulted because `reqwest` fell
+     * back to the platform verifier, which was never initialized on Android.
+     * This is the exact regression guarded against and is always a failure.
+     */
+    data class PlatformVerifierUninitialized(
+        val `reason`: kotlin.String) : TlsSelfTestOutcome()
+        
+    {
+        
+
+        companion object
+    }
+    
+
+    
+
+    
+    
+
+
+    companion object
+}
+
+/**
+ * @suppress
+ */
+public object FfiConverterTypeTlsSelfTestOutcome : FfiConverterRustBuffer<TlsSelfTestOutcome>{
+    override fun read(buf: ByteBuffer): TlsSelfTestOutcome {
+        return when(buf.getInt()) {
+            1 -> TlsSelfTestOutcome.Verified
+            2 -> TlsSelfTestOutcome.Inconclusive(
+                FfiConverterString.read(buf),
+                )
+            3 -> TlsSelfTestOutcome.PlatformVerifierUninitialized(
+                FfiConverterString.read(buf),
+                )
+            else -> throw RuntimeException("invalid enum value, something is very wrong!!")
+        }
+    }
+
+    override fun allocationSize(value: TlsSelfTestOutcome) = when(value) {
+        is TlsSelfTestOutcome.Verified -> {
+            // Add the size for the Int that specifies the variant plus the size needed for all fields
+            (
+                4UL
+            )
+        }
+        is TlsSelfTestOutcome.Inconclusive -> {
+            // Add the size for the Int that specifies the variant plus the size needed for all fields
+            (
+                4UL
+                + FfiConverterString.allocationSize(value.`reason`)
+            )
+        }
+        is TlsSelfTestOutcome.PlatformVerifierUninitialized -> {
+            // Add the size for the Int that specifies the variant plus the size needed for all fields
+            (
+                4UL
+                + FfiConverterString.allocationSize(value.`reason`)
+            )
+        }
+    }
+
+    override fun write(value: TlsSelfTestOutcome, buf: ByteBuffer) {
+        when(value) {
+            is TlsSelfTestOutcome.Verified -> {
+                buf.putInt(1)
+                Unit
+            }
+            is TlsSelfTestOutcome.Inconclusive -> {
+                buf.putInt(2)
+                FfiConverterString.write(value.`reason`, buf)
+                Unit
+            }
+            is TlsSelfTestOutcome.PlatformVerifierUninitialized -> {
+                buf.putInt(3)
+                FfiConverterString.write(value.`reason`, buf)
+                Unit
+            }
+        }.let { /* this makes the `when` an expression, which ensures it is exhaustive */ }
+    }
+}
+
+
+
+
+
+/**
+ * The network path a request took.
+ */
+
+enum class Transport {
+    
+    /**
+     * Through the WARP tunnel; the server saw a Cloudflare address.
+     */
+    TUNNEL,
+    /**
+     * Straight to the server, which saw the device's own IP address.
+     */
+    DIRECT,
+    /**
+     * Through the user's own upstream proxy; the server saw its address.
+     */
+    UPSTREAM;
+
+    
+
+
+    companion object
+}
+
+
+/**
+ * @suppress
+ */
+public object FfiConverterTypeTransport: FfiConverterRustBuffer<Transport> {
+    override fun read(buf: ByteBuffer) = try {
+        Transport.values()[buf.getInt() - 1]
+    } catch (e: IndexOutOfBoundsException) {
+        throw RuntimeException("invalid enum value, something is very wrong!!", e)
+    }
+
+    override fun allocationSize(value: Transport) = 4UL
+
+    override fun write(value: Transport, buf: ByteBuffer) {
+        buf.putInt(value.ordinal + 1)
+    }
+}
+
+
+
+
+
+/**
+ * A proxy of the user's own, e.g. Tor, carrying requests instead of WARP.
+ */
+sealed class UpstreamProxy {
+    
+    /**
+     * A SOCKS5 proxy, such as Tor's at `127.0.0.1:9050`.
      */
-    var `finalUrl`: kotlin.String
+    data class Socks5(
+        /**
+         * Address or name of the proxy, resolved by the system.
+         */
+        val `host`: kotlin.String, 
+        val `port`: kotlin.UShort, 
+        /**
+         * Sent with SOCKS5 user name and password authentication; each at
+         * most 255 bytes.
+         */
+        val `credentials`: org.joefang.letterbox.ffi.proxy.ProxyCredentials?) : UpstreamProxy()
+        
+    {
+        
+
+        companion object
+    }
     
-){
+    /**
+     * An HTTP proxy tunnelling each connection with `CONNECT`.
+     */
+    data class HttpConnect(
+        /**
+         * Address or name of the proxy, resolved by the system.
+         */
+        val `host`: kotlin.String, 
+        val `port`: kotlin.UShort, 
+        /**
+         * Sent as `Proxy-Authorization: Basic`; the user name may not
+         * contain `:`.
+         */
+        val `credentials`: org.joefang.letterbox.ffi.proxy.ProxyCredentials?) : UpstreamProxy()
+        
+    {
+        
+
+        companion object
+    }
     
 
-    
+    
+
+    
+    
+
+
+    companion object
+}
+
+/**
+ * @suppress
+ */
+public object FfiConverterTypeUpstreamProxy : FfiConverterRustBuffer<UpstreamProxy>{
+    override fun read(buf: ByteBuffer): UpstreamProxy {
+        return when(buf.getInt()) {
+            1 -> UpstreamProxy.Socks5(
+                FfiConverterString.read(buf),
+                FfiConverterUShort.read(buf),
+                FfiConverterOptionalTypeProxyCredentials.read(buf),
+                )
+            2 -> UpstreamProxy.HttpConnect(
+                FfiConverterString.read(buf),
+                FfiConverterUShort.read(buf),
+                FfiConverterOptionalTypeProxyCredentials.read(buf),
+                )
+            else -> throw RuntimeException("invalid enum value, something is very wrong!!")
+        }
+    }
+
+    override fun allocationSize(value: UpstreamProxy) = when(value) {
+        is UpstreamProxy.Socks5 -> {
+            // Add the size for the Int that specifies the variant plus the size needed for all fields
+            (
+                4UL
+                + FfiConverterString.allocationSize(value.`host`)
+                + FfiConverterUShort.allocationSize(value.`port`)
+                + FfiConverterOptionalTypeProxyCredentials.allocationSize(value.`credentials`)
+            )
+        }
+        is UpstreamProxy.HttpConnect -> {
+            // Add the size for the Int that specifies the variant plus the size needed for all fields
+            (
+                4UL
+                + FfiConverterString.allocationSize(value.`host`)
+                + FfiConverterUShort.allocationSize(value.`port`)
+                + FfiConverterOptionalTypeProxyCredentials.allocationSize(value.`credentials`)
+            )
+        }
+    }
+
+    override fun write(value: UpstreamProxy, buf: ByteBuffer) {
+        when(value) {
+            is UpstreamProxy.Socks5 -> {
+                buf.putInt(1)
+                FfiConverterString.write(value.`host`, buf)
+                FfiConverterUShort.write(value.`port`, buf)
+                FfiConverterOptionalTypeProxyCredentials.write(value.`credentials`, buf)
+                Unit
+            }
+            is UpstreamProxy.HttpConnect -> {
+                buf.putInt(2)
+                FfiConverterString.write(value.`host`, buf)
+                FfiConverterUShort.write(value.`port`, buf)
+                FfiConverterOptionalTypeProxyCredentials.write(value.`credentials`, buf)
+                Unit
+            }
+        }.let { /* this makes the `when` an expression, which ensures it is exhaustive */ }
+    }
+}
+
+
+
+
+
+
+/**
+ * @suppress
+ */
+public object FfiConverterOptionalUShort: FfiConverterRustBuffer<kotlin.UShort?> {
+    override fun read(buf: ByteBuffer): kotlin.UShort? {
+        if (buf.get().toInt() == 0) {
+            return null
+        }
+        return FfiConverterUShort.read(buf)
+    }
+
+    override fun allocationSize(value: kotlin.UShort?): ULong {
+        if (value == null) {
+            return 1UL
+        } else {
+            return 1UL + FfiConverterUShort.allocationSize(value)
+        }
+    }
+
+    override fun write(value: kotlin.UShort?, buf: ByteBuffer) {
+        if (value == null) {
+            buf.put(0)
+        } else {
+            buf.put(1)
+            FfiConverterUShort.write(value, buf)
+        }
+    }
+}
+
+
+
+
+/**
+ * @suppress
+ */
+public object FfiConverterOptionalUInt: FfiConverterRustBuffer<kotlin.UInt?> {
+    override fun read(buf: ByteBuffer): kotlin.UInt? {
+        if (buf.get().toInt() == 0) {
+            return null
+        }
+        return FfiConverterUInt.read(buf)
+    }
+
+    override fun allocationSize(value: kotlin.UInt?): ULong {
+        if (value == null) {
+            return 1UL
+        } else {
+            return 1UL + FfiConverterUInt.allocationSize(value)
+        }
+    }
+
+    override fun write(value: kotlin.UInt?, buf: ByteBuffer) {
+        if (value == null) {
+            buf.put(0)
+        } else {
+            buf.put(1)
+            FfiConverterUInt.write(value, buf)
+        }
+    }
+}
+
+
+
+
+/**
+ * @suppress
+ */
+public object FfiConverterOptionalULong: FfiConverterRustBuffer<kotlin.ULong?> {
+    override fun read(buf: ByteBuffer): kotlin.ULong? {
+        if (buf.get().toInt() == 0) {
+            return null
+        }
+        return FfiConverterULong.read(buf)
+    }
+
+    override fun allocationSize(value: kotlin.ULong?): ULong {
+        if (value == null) {
+            return 1UL
+        } else {
+            return 1UL + FfiConverterULong.allocationSize(value)
+        }
+    }
+
+    override fun write(value: kotlin.ULong?, buf: ByteBuffer) {
+        if (value == null) {
+            buf.put(0)
+        } else {
+            buf.put(1)
+            FfiConverterULong.write(value, buf)
+        }
+    }
+}
+
+
+
+
+/**
+ * @suppress
+ */
+public object FfiConverterOptionalFloat: FfiConverterRustBuffer<kotlin.Float?> {
+    override fun read(buf: ByteBuffer): kotlin.Float? {
+        if (buf.get().toInt() == 0) {
+            return null
+        }
+        return FfiConverterFloat.read(buf)
+    }
+
+    override fun allocationSize(value: kotlin.Float?): ULong {
+        if (value == null) {
+            return 1UL
+        } else {
+            return 1UL + FfiConverterFloat.allocationSize(value)
+        }
+    }
+
+    override fun write(value: kotlin.Float?, buf: ByteBuffer) {
+        if (value == null) {
+            buf.put(0)
+        } else {
+            buf.put(1)
+            FfiConverterFloat.write(value, buf)
+        }
+    }
+}
+
+
+
+
+/**
+ * @suppress
+ */
+public object FfiConverterOptionalString: FfiConverterRustBuffer<kotlin.String?> {
+    override fun read(buf: ByteBuffer): kotlin.String? {
+        if (buf.get().toInt() == 0) {
+            return null
+        }
+        return FfiConverterString.read(buf)
+    }
+
+    override fun allocationSize(value: kotlin.String?): ULong {
+        if (value == null) {
+            return 1UL
+        } else {
+            return 1UL + FfiConverterString.allocationSize(value)
+        }
+    }
+
+    override fun write(value: kotlin.String?, buf: ByteBuffer) {
+        if (value == null) {
+            buf.put(0)
+        } else {
+            buf.put(1)
+            FfiConverterString.write(value, buf)
+        }
+    }
+}
+
+
+
+
+/**
+ * @suppress
+ */
+public object FfiConverterOptionalByteArray: FfiConverterRustBuffer<kotlin.ByteArray?> {
+    override fun read(buf: ByteBuffer): kotlin.ByteArray? {
+        if (buf.get().toInt() == 0) {
+            return null
+        }
+        return FfiConverterByteArray.read(buf)
+    }
+
+    override fun allocationSize(value: kotlin.ByteArray?): ULong {
+        if (value == null) {
+            return 1UL
+        } else {
+            return 1UL + FfiConverterByteArray.allocationSize(value)
+        }
+    }
+
+    override fun write(value: kotlin.ByteArray?, buf: ByteBuffer) {
+        if (value == null) {
+            buf.put(0)
+        } else {
+            buf.put(1)
+            FfiConverterByteArray.write(value, buf)
+        }
+    }
+}
+
 
-    
-    companion object
+
+
+/**
+ * @suppress
+ */
+public object FfiConverterOptionalTypeCacheStorage: FfiConverterRustBuffer<CacheStorage?> {
+    override fun read(buf: ByteBuffer): CacheStorage? {
+        if (buf.get().toInt() == 0) {
+            return null
+        }
+        return FfiConverterTypeCacheStorage.read(buf)
+    }
+
+    override fun allocationSize(value: CacheStorage?): ULong {
+        if (value == null) {
+            return 1UL
+        } else {
+            return 1UL + FfiConverterTypeCacheStorage.allocationSize(value)
+        }
+    }
+
+    override fun write(value: CacheStorage?, buf: ByteBuffer) {
+        if (value == null) {
+            buf.put(0)
+        } else {
+            buf.put(1)
+            FfiConverterTypeCacheStorage.write(value, buf)
+        }
+    }
 }
 
+
+
+
 /**
  * @suppress
  */
-public object FfiConverterTypeImageResponse: FfiConverterRustBuffer<ImageResponse> {
-    override fun read(buf: ByteBuffer): ImageResponse {
-        return ImageResponse(
-            FfiConverterString.read(buf),
-            FfiConverterByteArray.read(buf),
-            FfiConverterBoolean.read(buf),
-            FfiConverterString.read(buf),
-        )
+public object FfiConverterOptionalTypeLogListener: FfiConverterRustBuffer<LogListener?> {
+    override fun read(buf: ByteBuffer): LogListener? {
+        if (buf.get().toInt() == 0) {
+            return null
+        }
+        return FfiConverterTypeLogListener.read(buf)
     }
 
-    override fun allocationSize(value: ImageResponse) = (
-            FfiConverterString.allocationSize(value.`mimeType`) +
-            FfiConverterByteArray.allocationSize(value.`data`) +
-            FfiConverterBoolean.allocationSize(value.`fromCache`) +
-            FfiConverterString.allocationSize(value.`finalUrl`)
-    )
+    override fun allocationSize(value: LogListener?): ULong {
+        if (value == null) {
+            return 1UL
+        } else {
+            return 1UL + FfiConverterTypeLogListener.allocationSize(value)
+        }
+    }
 
-    override fun write(value: ImageResponse, buf: ByteBuffer) {
-            FfiConverterString.write(value.`mimeType`, buf)
-            FfiConverterByteArray.write(value.`data`, buf)
-            FfiConverterBoolean.write(value.`fromCache`, buf)
-            FfiConverterString.write(value.`finalUrl`, buf)
+    override fun write(value: LogListener?, buf: ByteBuffer) {
+        if (value == null) {
+            buf.put(0)
+        } else {
+            buf.put(1)
+            FfiConverterTypeLogListener.write(value, buf)
+        }
     }
 }
 
 
 
+
 /**
- * Status of the image proxy.
+ * @suppress
  */
-data class ProxyStatus (
-    /**
-     * Whether the proxy is initialized and ready.
-     */
-    var `ready`: kotlin.Boolean
-    , 
-    /**
-     * Whether WARP is enabled on this device.
-     */
-    var `warpEnabled`: kotlin.Boolean
-    , 
-    /**
-     * Whether the WireGuard tunnel currently has a live session.
-     */
-    var `tunnelConnected`: kotlin.Boolean
-    , 
-    /**
-     * Current WireGuard endpoint (if provisioned).
-     */
-    var `endpoint`: kotlin.String?
-    , 
-    /**
-     * Last error message (if any).
-     */
-    var `lastError`: kotlin.String?
-    , 
-    /**
-     * Number of cached images.
-     */
-    var `cacheSize`: kotlin.UInt
-    
-){
-    
+public object FfiConverterOptionalTypeOfflineFetchListener: FfiConverterRustBuffer<OfflineFetchListener?> {
+    override fun read(buf: ByteBuffer): OfflineFetchListener? {
+        if (buf.get().toInt() == 0) {
+            return null
+        }
+        return FfiConverterTypeOfflineFetchListener.read(buf)
+    }
+
+    override fun allocationSize(value: OfflineFetchListener?): ULong {
+        if (value == null) {
+            return 1UL
+        } else {
+            return 1UL + FfiConverterTypeOfflineFetchListener.allocationSize(value)
+        }
+    }
+
+    override fun write(value: OfflineFetchListener?, buf: ByteBuffer) {
+        if (value == null) {
+            buf.put(0)
+        } else {
+            buf.put(1)
+            FfiConverterTypeOfflineFetchListener.write(value, buf)
+        }
+    }
+}
+
+
+
+
+/**
+ * @suppress
+ */
+public object FfiConverterOptionalTypeProgressListener: FfiConverterRustBuffer<ProgressListener?> {
+    override fun read(buf: ByteBuffer): ProgressListener? {
+        if (buf.get().toInt() == 0) {
+            return null
+        }
+        return FfiConverterTypeProgressListener.read(buf)
+    }
+
+    override fun allocationSize(value: ProgressListener?): ULong {
+        if (value == null) {
+            return 1UL
+        } else {
+            return 1UL + FfiConverterTypeProgressListener.allocationSize(value)
+        }
+    }
+
+    override fun write(value: ProgressListener?, buf: ByteBuffer) {
+        if (value == null) {
+            buf.put(0)
+        } else {
+            buf.put(1)
+            FfiConverterTypeProgressListener.write(value, buf)
+        }
+    }
+}
+
+
+
+
+/**
+ * @suppress
+ */
+public object FfiConverterOptionalTypeProxyEventListener: FfiConverterRustBuffer<ProxyEventListener?> {
+    override fun read(buf: ByteBuffer): ProxyEventListener? {
+        if (buf.get().toInt() == 0) {
+            return null
+        }
+        return FfiConverterTypeProxyEventListener.read(buf)
+    }
+
+    override fun allocationSize(value: ProxyEventListener?): ULong {
+        if (value == null) {
+            return 1UL
+        } else {
+            return 1UL + FfiConverterTypeProxyEventListener.allocationSize(value)
+        }
+    }
+
+    override fun write(value: ProxyEventListener?, buf: ByteBuffer) {
+        if (value == null) {
+            buf.put(0)
+        } else {
+            buf.put(1)
+            FfiConverterTypeProxyEventListener.write(value, buf)
+        }
+    }
+}
+
+
+
+
+/**
+ * @suppress
+ */
+public object FfiConverterOptionalTypeSocketProtector: FfiConverterRustBuffer<SocketProtector?> {
+    override fun read(buf: ByteBuffer): SocketProtector? {
+        if (buf.get().toInt() == 0) {
+            return null
+        }
+        return FfiConverterTypeSocketProtector.read(buf)
+    }
+
+    override fun allocationSize(value: SocketProtector?): ULong {
+        if (value == null) {
+            return 1UL
+        } else {
+            return 1UL + FfiConverterTypeSocketProtector.allocationSize(value)
+        }
+    }
+
+    override fun write(value: SocketProtector?, buf: ByteBuffer) {
+        if (value == null) {
+            buf.put(0)
+        } else {
+            buf.put(1)
+            FfiConverterTypeSocketProtector.write(value, buf)
+        }
+    }
+}
+
+
+
+
+/**
+ * @suppress
+ */
+public object FfiConverterOptionalTypeDataSaver: FfiConverterRustBuffer<DataSaver?> {
+    override fun read(buf: ByteBuffer): DataSaver? {
+        if (buf.get().toInt() == 0) {
+            return null
+        }
+        return FfiConverterTypeDataSaver.read(buf)
+    }
+
+    override fun allocationSize(value: DataSaver?): ULong {
+        if (value == null) {
+            return 1UL
+        } else {
+            return 1UL + FfiConverterTypeDataSaver.allocationSize(value)
+        }
+    }
+
+    override fun write(value: DataSaver?, buf: ByteBuffer) {
+        if (value == null) {
+            buf.put(0)
+        } else {
+            buf.put(1)
+            FfiConverterTypeDataSaver.write(value, buf)
+        }
+    }
+}
+
+
+
+
+/**
+ * @suppress
+ */
+public object FfiConverterOptionalTypeImageResponse: FfiConverterRustBuffer<ImageResponse?> {
+    override fun read(buf: ByteBuffer): ImageResponse? {
+        if (buf.get().toInt() == 0) {
+            return null
+        }
+        return FfiConverterTypeImageResponse.read(buf)
+    }
+
+    override fun allocationSize(value: ImageResponse?): ULong {
+        if (value == null) {
+            return 1UL
+        } else {
+            return 1UL + FfiConverterTypeImageResponse.allocationSize(value)
+        }
+    }
+
+    override fun write(value: ImageResponse?, buf: ByteBuffer) {
+        if (value == null) {
+            buf.put(0)
+        } else {
+            buf.put(1)
+            FfiConverterTypeImageResponse.write(value, buf)
+        }
+    }
+}
+
+
+
 
-    
+/**
+ * @suppress
+ */
+public object FfiConverterOptionalTypeProxyCredentials: FfiConverterRustBuffer<ProxyCredentials?> {
+    override fun read(buf: ByteBuffer): ProxyCredentials? {
+        if (buf.get().toInt() == 0) {
+            return null
+        }
+        return FfiConverterTypeProxyCredentials.read(buf)
+    }
 
-    
-    companion object
+    override fun allocationSize(value: ProxyCredentials?): ULong {
+        if (value == null) {
+            return 1UL
+        } else {
+            return 1UL + FfiConverterTypeProxyCredentials.allocationSize(value)
+        }
+    }
+
+    override fun write(value: ProxyCredentials?, buf: ByteBuffer) {
+        if (value == null) {
+            buf.put(0)
+        } else {
+            buf.put(1)
+            FfiConverterTypeProxyCredentials.write(value, buf)
+        }
+    }
 }
 
+
+
+
 /**
  * @suppress
  */
-public object FfiConverterTypeProxyStatus: FfiConverterRustBuffer<ProxyStatus> {
-    override fun read(buf: ByteBuffer): ProxyStatus {
-        return ProxyStatus(
-            FfiConverterBoolean.read(buf),
-            FfiConverterBoolean.read(buf),
-            FfiConverterBoolean.read(buf),
-            FfiConverterOptionalString.read(buf),
-            FfiConverterOptionalString.read(buf),
-            FfiConverterUInt.read(buf),
-        )
+public object FfiConverterOptionalTypeRefererGrant: FfiConverterRustBuffer<RefererGrant?> {
+    override fun read(buf: ByteBuffer): RefererGrant? {
+        if (buf.get().toInt() == 0) {
+            return null
+        }
+        return FfiConverterTypeRefererGrant.read(buf)
     }
 
-    override fun allocationSize(value: ProxyStatus) = (
-            FfiConverterBoolean.allocationSize(value.`ready`) +
-            FfiConverterBoolean.allocationSize(value.`warpEnabled`) +
-            FfiConverterBoolean.allocationSize(value.`tunnelConnected`) +
-            FfiConverterOptionalString.allocationSize(value.`endpoint`) +
-            FfiConverterOptionalString.allocationSize(value.`lastError`) +
-            FfiConverterUInt.allocationSize(value.`cacheSize`)
-    )
+    override fun allocationSize(value: RefererGrant?): ULong {
+        if (value == null) {
+            return 1UL
+        } else {
+            return 1UL + FfiConverterTypeRefererGrant.allocationSize(value)
+        }
+    }
 
-    override fun write(value: ProxyStatus, buf: ByteBuffer) {
-            FfiConverterBoolean.write(value.`ready`, buf)
-            FfiConverterBoolean.write(value.`warpEnabled`, buf)
-            FfiConverterBoolean.write(value.`tunnelConnected`, buf)
-            FfiConverterOptionalString.write(value.`endpoint`, buf)
-            FfiConverterOptionalString.write(value.`lastError`, buf)
-            FfiConverterUInt.write(value.`cacheSize`, buf)
+    override fun write(value: RefererGrant?, buf: ByteBuffer) {
+        if (value == null) {
+            buf.put(0)
+        } else {
+            buf.put(1)
+            FfiConverterTypeRefererGrant.write(value, buf)
+        }
     }
 }
 
 
 
+
 /**
- * Result of an in-app update check.
+ * @suppress
  */
-data class UpdateResult (
-    /**
-     * Whether a newer release is available.
-     */
-    var `updateAvailable`: kotlin.Boolean
-    , 
-    /**
-     * Running version as reported by the caller.
-     */
-    var `currentVersion`: kotlin.String
-    , 
-    /**
-     * Latest release version (no leading `v`).
-     */
-    var `latestVersion`: kotlin.String
-    , 
-    /**
-     * Latest release git tag.
-     */
-    var `latestTag`: kotlin.String
-    , 
-    /**
-     * Release notes.
-     */
-    var `changelog`: kotlin.String
-    , 
-    /**
-     * Release page URL.
-     */
-    var `releaseUrl`: kotlin.String
-    
-){
-    
+public object FfiConverterOptionalTypeCacheStorageMode: FfiConverterRustBuffer<CacheStorageMode?> {
+    override fun read(buf: ByteBuffer): CacheStorageMode? {
+        if (buf.get().toInt() == 0) {
+            return null
+        }
+        return FfiConverterTypeCacheStorageMode.read(buf)
+    }
 
-    
+    override fun allocationSize(value: CacheStorageMode?): ULong {
+        if (value == null) {
+            return 1UL
+        } else {
+            return 1UL + FfiConverterTypeCacheStorageMode.allocationSize(value)
+        }
+    }
 
-    
-    companion object
+    override fun write(value: CacheStorageMode?, buf: ByteBuffer) {
+        if (value == null) {
+            buf.put(0)
+        } else {
+            buf.put(1)
+            FfiConverterTypeCacheStorageMode.write(value, buf)
+        }
+    }
 }
 
+
+
+
 /**
  * @suppress
  */
-public object FfiConverterTypeUpdateResult: FfiConverterRustBuffer<UpdateResult> {
-    override fun read(buf: ByteBuffer): UpdateResult {
-        return UpdateResult(
-            FfiConverterBoolean.read(buf),
-            FfiConverterString.read(buf),
-            FfiConverterString.read(buf),
-            FfiConverterString.read(buf),
-            FfiConverterString.read(buf),
-            FfiConverterString.read(buf),
-        )
+public object FfiConverterOptionalTypeErrorCode: FfiConverterRustBuffer<ErrorCode?> {
+    override fun read(buf: ByteBuffer): ErrorCode? {
+        if (buf.get().toInt() == 0) {
+            return null
+        }
+        return FfiConverterTypeErrorCode.read(buf)
     }
 
-    override fun allocationSize(value: UpdateResult) = (
-            FfiConverterBoolean.allocationSize(value.`updateAvailable`) +
-            FfiConverterString.allocationSize(value.`currentVersion`) +
-            FfiConverterString.allocationSize(value.`latestVersion`) +
-            FfiConverterString.allocationSize(value.`latestTag`) +
-            FfiConverterString.allocationSize(value.`changelog`) +
-            FfiConverterString.allocationSize(value.`releaseUrl`)
-    )
+    override fun allocationSize(value: ErrorCode?): ULong {
+        if (value == null) {
+            return 1UL
+        } else {
+            return 1UL + FfiConverterTypeErrorCode.allocationSize(value)
+        }
+    }
 
-    override fun write(value: UpdateResult, buf: ByteBuffer) {
-            FfiConverterBoolean.write(value.`updateAvailable`, buf)
-            FfiConverterString.write(value.`currentVersion`, buf)
-            FfiConverterString.write(value.`latestVersion`, buf)
-            FfiConverterString.write(value.`latestTag`, buf)
-            FfiConverterString.write(value.`changelog`, buf)
-            FfiConverterString.write(value.`releaseUrl`, buf)
+    override fun write(value: ErrorCode?, buf: ByteBuffer) {
+        if (value == null) {
+            buf.put(0)
+        } else {
+            buf.put(1)
+            FfiConverterTypeErrorCode.write(value, buf)
+        }
     }
 }
 
 
 
+
 /**
- * Full WireGuard/WARP diagnostics for the in-app debug screen.
- *
- * This intentionally includes the private key so power users can fully inspect
- * and reproduce the tunnel; the Android UI hides it behind an explicit reveal.
+ * @suppress
  */
-data class WarpDiagnostics (
-    /**
-     * `"connected"` or `"disconnected"`.
-     */
-    var `connectionState`: kotlin.String
-    , 
-    /**
-     * WireGuard private key (base64).
-     */
-    var `privateKey`: kotlin.String
-    , 
-    /**
-     * Derived WireGuard public key (base64).
-     */
-    var `publicKey`: kotlin.String
-    , 
-    /**
-     * WARP peer public key (base64).
-     */
-    var `peerPublicKey`: kotlin.String
-    , 
-    /**
-     * Endpoint hostname.
-     */
-    var `endpointHost`: kotlin.String
-    , 
-    /**
-     * Endpoint IPv4 address.
-     */
-    var `endpointIpv4`: kotlin.String
-    , 
-    /**
-     * Endpoint UDP port.
-     */
-    var `endpointPort`: kotlin.UShort
-    , 
-    /**
-     * Local tunnel IPv4 address.
-     */
-    var `localAddressIpv4`: kotlin.String
-    , 
-    /**
-     * Whether WARP is enabled on the account.
-     */
-    var `warpEnabled`: kotlin.Boolean
-    , 
-    /**
-     * Account type (e.g. `free`).
-     */
-    var `accountType`: kotlin.String
-    , 
-    /**
-     * Cloudflare account/device identifier.
-     */
-    var `accountId`: kotlin.String
-    , 
-    /**
-     * Seconds since the last completed handshake, if any.
-     */
-    var `lastHandshakeSecs`: kotlin.ULong?
-    , 
-    /**
-     * Plaintext bytes transmitted into the tunnel.
-     */
-    var `txBytes`: kotlin.ULong
-    , 
-    /**
-     * Plaintext bytes received from the tunnel.
-     */
-    var `rxBytes`: kotlin.ULong
-    , 
-    /**
-     * Estimated packet loss in `[0.0, 1.0]`.
-     */
-    var `estimatedLoss`: kotlin.Float
-    , 
-    /**
-     * Estimated round-trip time in milliseconds, if measured.
-     */
-    var `rttMs`: kotlin.UInt?
-    
-){
-    
+public object FfiConverterOptionalTypeOutputFormat: FfiConverterRustBuffer<OutputFormat?> {
+    override fun read(buf: ByteBuffer): OutputFormat? {
+        if (buf.get().toInt() == 0) {
+            return null
+        }
+        return FfiConverterTypeOutputFormat.read(buf)
+    }
+
+    override fun allocationSize(value: OutputFormat?): ULong {
+        if (value == null) {
+            return 1UL
+        } else {
+            return 1UL + FfiConverterTypeOutputFormat.allocationSize(value)
+        }
+    }
 
-    
+    override fun write(value: OutputFormat?, buf: ByteBuffer) {
+        if (value == null) {
+            buf.put(0)
+        } else {
+            buf.put(1)
+            FfiConverterTypeOutputFormat.write(value, buf)
+        }
+    }
+}
 
-    
-    companion object
+
+
+
+/**
+ * @suppress
+ */
+public object FfiConverterOptionalTypeTransport: FfiConverterRustBuffer<Transport?> {
+    override fun read(buf: ByteBuffer): Transport? {
+        if (buf.get().toInt() == 0) {
+            return null
+        }
+        return FfiConverterTypeTransport.read(buf)
+    }
+
+    override fun allocationSize(value: Transport?): ULong {
+        if (value == null) {
+            return 1UL
+        } else {
+            return 1UL + FfiConverterTypeTransport.allocationSize(value)
+        }
+    }
+
+    override fun write(value: Transport?, buf: ByteBuffer) {
+        if (value == null) {
+            buf.put(0)
+        } else {
+            buf.put(1)
+            FfiConverterTypeTransport.write(value, buf)
+        }
+    }
 }
 
+
+
+
 /**
  * @suppress
  */
-public object FfiConverterTypeWarpDiagnostics: FfiConverterRustBuffer<WarpDiagnostics> {
-    override fun read(buf: ByteBuffer): WarpDiagnostics {
-        return WarpDiagnostics(
-            FfiConverterString.read(buf),
-            FfiConverterString.read(buf),
-            FfiConverterString.read(buf),
-            FfiConverterString.read(buf),
-            FfiConverterString.read(buf),
-            FfiConverterString.read(buf),
-            FfiConverterUShort.read(buf),
-            FfiConverterString.read(buf),
-            FfiConverterBoolean.read(buf),
-            FfiConverterString.read(buf),
-            FfiConverterString.read(buf),
-            FfiConverterOptionalULong.read(buf),
-            FfiConverterULong.read(buf),
-            FfiConverterULong.read(buf),
-            FfiConverterFloat.read(buf),
-            FfiConverterOptionalUInt.read(buf),
-        )
+public object FfiConverterOptionalTypeUpstreamProxy: FfiConverterRustBuffer<UpstreamProxy?> {
+    override fun read(buf: ByteBuffer): UpstreamProxy? {
+        if (buf.get().toInt() == 0) {
+            return null
+        }
+        return FfiConverterTypeUpstreamProxy.read(buf)
     }
 
-    override fun allocationSize(value: WarpDiagnostics) = (
-            FfiConverterString.allocationSize(value.`connectionState`) +
-            FfiConverterString.allocationSize(value.`privateKey`) +
-            FfiConverterString.allocationSize(value.`publicKey`) +
-            FfiConverterString.allocationSize(value.`peerPublicKey`) +
-            FfiConverterString.allocationSize(value.`endpointHost`) +
-            FfiConverterString.allocationSize(value.`endpointIpv4`) +
-            FfiConverterUShort.allocationSize(value.`endpointPort`) +
-            FfiConverterString.allocationSize(value.`localAddressIpv4`) +
-            FfiConverterBoolean.allocationSize(value.`warpEnabled`) +
-            FfiConverterString.allocationSize(value.`accountType`) +
-            FfiConverterString.allocationSize(value.`accountId`) +
-            FfiConverterOptionalULong.allocationSize(value.`lastHandshakeSecs`) +
-            FfiConverterULong.allocationSize(value.`txBytes`) +
-            FfiConverterULong.allocationSize(value.`rxBytes`) +
-            FfiConverterFloat.allocationSize(value.`estimatedLoss`) +
-            FfiConverterOptionalUInt.allocationSize(value.`rttMs`)
-    )
+    override fun allocationSize(value: UpstreamProxy?): ULong {
+        if (value == null) {
+            return 1UL
+        } else {
+            return 1UL + FfiConverterTypeUpstreamProxy.allocationSize(value)
+        }
+    }
 
-    override fun write(value: WarpDiagnostics, buf: ByteBuffer) {
-            FfiConverterString.write(value.`connectionState`, buf)
-            FfiConverterString.write(value.`privateKey`, buf)
-            FfiConverterString.write(value.`publicKey`, buf)
-            FfiConverterString.write(value.`peerPublicKey`, buf)
-            FfiConverterString.write(value.`endpointHost`, buf)
-            FfiConverterString.write(value.`endpointIpv4`, buf)
-            FfiConverterUShort.write(value.`endpointPort`, buf)
-            FfiConverterString.write(value.`localAddressIpv4`, buf)
-            FfiConverterBoolean.write(value.`warpEnabled`, buf)
-            FfiConverterString.write(value.`accountType`, buf)
-            FfiConverterString.write(value.`accountId`, buf)
-            FfiConverterOptionalULong.write(value.`lastHandshakeSecs`, buf)
-            FfiConverterULong.write(value.`txBytes`, buf)
-            FfiConverterULong.write(value.`rxBytes`, buf)
-            FfiConverterFloat.write(value.`estimatedLoss`, buf)
-            FfiConverterOptionalUInt.write(value.`rttMs`, buf)
+    override fun write(value: UpstreamProxy?, buf: ByteBuffer) {
+        if (value == null) {
+            buf.put(0)
+        } else {
+            buf.put(1)
+            FfiConverterTypeUpstreamProxy.write(value, buf)
+        }
     }
 }
 
 
 
+
 /**
- * Persisted WARP identity and tunnel configuration, read straight from disk.
- *
- * Unlike [`WarpDiagnostics`], building this never provisions or handshakes, so
- * it remains inspectable even when the tunnel is down — exactly the situation a
- * user needs visibility into. The private key is included for full
- * transparency; the Android UI keeps it behind an explicit reveal toggle.
+ * @suppress
  */
-data class WarpStoredConfig (
-    /**
-     * Whether a provisioned WARP configuration exists on disk.
-     */
-    var `hasConfig`: kotlin.Boolean
-    , 
-    /**
-     * Whether a live tunnel manager is currently running.
-     */
-    var `tunnelActive`: kotlin.Boolean
-    , 
-    /**
-     * Cloudflare account/device identifier.
-     */
-    var `accountId`: kotlin.String
-    , 
-    /**
-     * Account license key (may be empty for free accounts).
-     */
-    var `licenseKey`: kotlin.String
-    , 
-    /**
-     * WireGuard private key (base64). Sensitive — surfaced for debugging only.
-     */
-    var `privateKey`: kotlin.String
-    , 
-    /**
-     * Derived WireGuard public key (base64).
-     */
-    var `publicKey`: kotlin.String
-    , 
-    /**
-     * WARP peer public key (base64).
-     */
-    var `peerPublicKey`: kotlin.String
-    , 
-    /**
-     * Endpoint hostname.
-     */
-    var `endpointHost`: kotlin.String
-    , 
-    /**
-     * Endpoint IPv4 address.
-     */
-    var `endpointIpv4`: kotlin.String
-    , 
-    /**
-     * Endpoint UDP port.
-     */
-    var `endpointPort`: kotlin.UShort
-    , 
-    /**
-     * Local tunnel IPv4 address.
-     */
-    var `localAddressIpv4`: kotlin.String
-    , 
-    /**
-     * Whether WARP is enabled on the account.
-     */
-    var `warpEnabled`: kotlin.Boolean
-    , 
-    /**
-     * Account type (e.g. `free`).
-     */
-    var `accountType`: kotlin.String
-    , 
-    /**
-     * Unix timestamp (seconds) when the configuration was last provisioned.
-     */
-    var `lastUpdatedSecs`: kotlin.Long
-    , 
-    /**
-     * Absolute path to the persisted `warp_config.json`.
-     */
-    var `configFilePath`: kotlin.String
-    
-){
-    
+public object FfiConverterOptionalMapStringString: FfiConverterRustBuffer<Map<kotlin.String, kotlin.String>?> {
+    override fun read(buf: ByteBuffer): Map<kotlin.String, kotlin.String>? {
+        if (buf.get().toInt() == 0) {
+            return null
+        }
+        return FfiConverterMapStringString.read(buf)
+    }
 
-    
+    override fun allocationSize(value: Map<kotlin.String, kotlin.String>?): ULong {
+        if (value == null) {
+            return 1UL
+        } else {
+            return 1UL + FfiConverterMapStringString.allocationSize(value)
+        }
+    }
 
-    
-    companion object
+    override fun write(value: Map<kotlin.String, kotlin.String>?, buf: ByteBuffer) {
+        if (value == null) {
+            buf.put(0)
+        } else {
+            buf.put(1)
+            FfiConverterMapStringString.write(value, buf)
+        }
+    }
 }
 
+
+
+
 /**
  * @suppress
  */
-public object FfiConverterTypeWarpStoredConfig: FfiConverterRustBuffer<WarpStoredConfig> {
-    override fun read(buf: ByteBuffer): WarpStoredConfig {
-        return WarpStoredConfig(
-            FfiConverterBoolean.read(buf),
-            FfiConverterBoolean.read(buf),
-            FfiConverterString.read(buf),
-            FfiConverterString.read(buf),
-            FfiConverterString.read(buf),
-            FfiConverterString.read(buf),
-            FfiConverterString.read(buf),
-            FfiConverterString.read(buf),
-            FfiConverterString.read(buf),
-            FfiConverterUShort.read(buf),
-            FfiConverterString.read(buf),
-            FfiConverterBoolean.read(buf),
-            FfiConverterString.read(buf),
-            FfiConverterLong.read(buf),
-            FfiConverterString.read(buf),
-        )
+public object FfiConverterSequenceUShort: FfiConverterRustBuffer<List<kotlin.UShort>> {
+    override fun read(buf: ByteBuffer): List<kotlin.UShort> {
+        val len = buf.getInt()
+        return List<kotlin.UShort>(len) {
+            FfiConverterUShort.read(buf)
+        }
+    }
+
+    override fun allocationSize(value: List<kotlin.UShort>): ULong {
+        val sizeForLength = 4UL
+        val sizeForItems = value.map { FfiConverterUShort.allocationSize(it) }.sum()
+        return sizeForLength + sizeForItems
+    }
+
+    override fun write(value: List<kotlin.UShort>, buf: ByteBuffer) {
+        buf.putInt(value.size)
+        value.iterator().forEach {
+            FfiConverterUShort.write(it, buf)
+        }
+    }
+}
+
+
+
+
+/**
+ * @suppress
+ */
+public object FfiConverterSequenceUInt: FfiConverterRustBuffer<List<kotlin.UInt>> {
+    override fun read(buf: ByteBuffer): List<kotlin.UInt> {
+        val len = buf.getInt()
+        return List<kotlin.UInt>(len) {
+            FfiConverterUInt.read(buf)
+        }
     }
 
-    override fun allocationSize(value: WarpStoredConfig) = (
-            FfiConverterBoolean.allocationSize(value.`hasConfig`) +
-            FfiConverterBoolean.allocationSize(value.`tunnelActive`) +
-            FfiConverterString.allocationSize(value.`accountId`) +
-            FfiConverterString.allocationSize(value.`licenseKey`) +
-            FfiConverterString.allocationSize(value.`privateKey`) +
-            FfiConverterString.allocationSize(value.`publicKey`) +
-            FfiConverterString.allocationSize(value.`peerPublicKey`) +
-            FfiConverterString.allocationSize(value.`endpointHost`) +
-            FfiConverterString.allocationSize(value.`endpointIpv4`) +
-            FfiConverterUShort.allocationSize(value.`endpointPort`) +
-            FfiConverterString.allocationSize(value.`localAddressIpv4`) +
-            FfiConverterBoolean.allocationSize(value.`warpEnabled`) +
-            FfiConverterString.allocationSize(value.`accountType`) +
-            FfiConverterLong.allocationSize(value.`lastUpdatedSecs`) +
-            FfiConverterString.allocationSize(value.`configFilePath`)
-    )
+    override fun allocationSize(value: List<kotlin.UInt>): ULong {
+        val sizeForLength = 4UL
+        val sizeForItems = value.map { FfiConverterUInt.allocationSize(it) }.sum()
+        return sizeForLength + sizeForItems
+    }
 
-    override fun write(value: WarpStoredConfig, buf: ByteBuffer) {
-            FfiConverterBoolean.write(value.`hasConfig`, buf)
-            FfiConverterBoolean.write(value.`tunnelActive`, buf)
-            FfiConverterString.write(value.`accountId`, buf)
-            FfiConverterString.write(value.`licenseKey`, buf)
-            FfiConverterString.write(value.`privateKey`, buf)
-            FfiConverterString.write(value.`publicKey`, buf)
-            FfiConverterString.write(value.`peerPublicKey`, buf)
-            FfiConverterString.write(value.`endpointHost`, buf)
-            FfiConverterString.write(value.`endpointIpv4`, buf)
-            FfiConverterUShort.write(value.`endpointPort`, buf)
-            FfiConverterString.write(value.`localAddressIpv4`, buf)
-            FfiConverterBoolean.write(value.`warpEnabled`, buf)
-            FfiConverterString.write(value.`accountType`, buf)
-            FfiConverterLong.write(value.`lastUpdatedSecs`, buf)
-            FfiConverterString.write(value.`configFilePath`, buf)
+    override fun write(value: List<kotlin.UInt>, buf: ByteBuffer) {
+        buf.putInt(value.size)
+        value.iterator().forEach {
+            FfiConverterUInt.write(it, buf)
+        }
     }
 }
 
 
 
 
-
 /**
- * Errors that can occur during proxy operations.
+ * @suppress
  */
-sealed class ProxyException: kotlin.Exception() {
-    
-    /**
-     * The proxy has not been initialized. Call `proxy_init()` first.
-     */
-    class NotInitialized(
-        ) : ProxyException() {
-        override val message
-            get() = ""
+public object FfiConverterSequenceULong: FfiConverterRustBuffer<List<kotlin.ULong>> {
+    override fun read(buf: ByteBuffer): List<kotlin.ULong> {
+        val len = buf.getInt()
+        return List<kotlin.ULong>(len) {
+            FfiConverterULong.read(buf)
+        }
     }
-    
-    /**
-     * Failed to initialize the proxy.
-     */
-    class InitializationFailed(
-        
-        /**
-         * Detailed error message
-         */
-        val `details`: kotlin.String
-        ) : ProxyException() {
-        override val message
-            get() = "details=${ `details` }"
+
+    override fun allocationSize(value: List<kotlin.ULong>): ULong {
+        val sizeForLength = 4UL
+        val sizeForItems = value.map { FfiConverterULong.allocationSize(it) }.sum()
+        return sizeForLength + sizeForItems
     }
-    
-    /**
-     * Failed to provision WARP credentials.
-     */
-    class ProvisioningFailed(
-        
-        /**
-         * Detailed error message
-         */
-        val `details`: kotlin.String
-        ) : ProxyException() {
-        override val message
-            get() = "details=${ `details` }"
+
+    override fun write(value: List<kotlin.ULong>, buf: ByteBuffer) {
+        buf.putInt(value.size)
+        value.iterator().forEach {
+            FfiConverterULong.write(it, buf)
+        }
     }
-    
-    /**
-     * Failed to establish WireGuard tunnel.
-     */
-    class TunnelException(
-        
-        /**
-         * Detailed error message
-         */
-        val `details`: kotlin.String
-        ) : ProxyException() {
-        override val message
-            get() = "details=${ `details` }"
+}
+
+
+
+
+/**
+ * @suppress
+ */
+public object FfiConverterSequenceString: FfiConverterRustBuffer<List<kotlin.String>> {
+    override fun read(buf: ByteBuffer): List<kotlin.String> {
+        val len = buf.getInt()
+        return List<kotlin.String>(len) {
+            FfiConverterString.read(buf)
+        }
     }
-    
-    /**
-     * Invalid URL provided.
-     */
-    class InvalidUrl(
-        
-        /**
-         * The invalid URL
-         */
-        val `url`: kotlin.String, 
-        
-        /**
-         * Detailed error message
-         */
-        val `details`: kotlin.String
-        ) : ProxyException() {
-        override val message
-            get() = "url=${ `url` }, details=${ `details` }"
+
+    override fun allocationSize(value: List<kotlin.String>): ULong {
+        val sizeForLength = 4UL
+        val sizeForItems = value.map { FfiConverterString.allocationSize(it) }.sum()
+        return sizeForLength + sizeForItems
     }
-    
-    /**
-     * HTTP request failed.
-     */
-    class HttpException(
-        
-        /**
-         * HTTP status code (0 if connection failed)
-         */
-        val `statusCode`: kotlin.UShort, 
-        
-        /**
-         * Detailed error message
-         */
-        val `details`: kotlin.String
-        ) : ProxyException() {
-        override val message
-            get() = "statusCode=${ `statusCode` }, details=${ `details` }"
+
+    override fun write(value: List<kotlin.String>, buf: ByteBuffer) {
+        buf.putInt(value.size)
+        value.iterator().forEach {
+            FfiConverterString.write(it, buf)
+        }
     }
-    
-    /**
-     * The response content type is not an image.
-     */
-    class InvalidContentType(
-        
-        /**
-         * The actual content type received
-         */
-        val `contentType`: kotlin.String
-        ) : ProxyException() {
-        override val message
-            get() = "contentType=${ `contentType` }"
+}
+
+
+
+
+/**
+ * @suppress
+ */
+public object FfiConverterSequenceTypeBatchImageResult: FfiConverterRustBuffer<List<BatchImageResult>> {
+    override fun read(buf: ByteBuffer): List<BatchImageResult> {
+        val len = buf.getInt()
+        return List<BatchImageResult>(len) {
+            FfiConverterTypeBatchImageResult.read(buf)
+        }
     }
-    
-    /**
-     * The response is too large.
-     */
-    class ResponseTooLarge(
-        
-        /**
-         * Actual size in bytes
-         */
-        val `size`: kotlin.ULong, 
-        
-        /**
-         * Maximum allowed size in bytes
-         */
-        val `maxSize`: kotlin.ULong
-        ) : ProxyException() {
-        override val message
-            get() = "size=${ `size` }, maxSize=${ `maxSize` }"
+
+    override fun allocationSize(value: List<BatchImageResult>): ULong {
+        val sizeForLength = 4UL
+        val sizeForItems = value.map { FfiConverterTypeBatchImageResult.allocationSize(it) }.sum()
+        return sizeForLength + sizeForItems
     }
-    
-    /**
-     * Too many redirects.
-     */
-    class TooManyRedirects(
-        
-        /**
-         * Number of redirects encountered
-         */
-        val `count`: kotlin.UInt, 
-        
-        /**
-         * Maximum allowed redirects
-         */
-        val `maxCount`: kotlin.UInt
-        ) : ProxyException() {
-        override val message
-            get() = "count=${ `count` }, maxCount=${ `maxCount` }"
+
+    override fun write(value: List<BatchImageResult>, buf: ByteBuffer) {
+        buf.putInt(value.size)
+        value.iterator().forEach {
+            FfiConverterTypeBatchImageResult.write(it, buf)
+        }
     }
-    
-    /**
-     * Connection timeout.
-     */
-    class Timeout(
-        
-        /**
-         * Timeout duration in seconds
-         */
-        val `seconds`: kotlin.UInt
-        ) : ProxyException() {
-        override val message
-            get() = "seconds=${ `seconds` }"
+}
+
+
+
+
+/**
+ * @suppress
+ */
+public object FfiConverterSequenceTypeHostBandwidth: FfiConverterRustBuffer<List<HostBandwidth>> {
+    override fun read(buf: ByteBuffer): List<HostBandwidth> {
+        val len = buf.getInt()
+        return List<HostBandwidth>(len) {
+            FfiConverterTypeHostBandwidth.read(buf)
+        }
     }
-    
-    /**
-     * DNS resolution failed.
-     */
-    class DnsException(
-        
-        /**
-         * The host that failed to resolve
-         */
-        val `host`: kotlin.String, 
-        
-        /**
-         * Detailed error message
-         */
-        val `details`: kotlin.String
-        ) : ProxyException() {
-        override val message
-            get() = "host=${ `host` }, details=${ `details` }"
+
+    override fun allocationSize(value: List<HostBandwidth>): ULong {
+        val sizeForLength = 4UL
+        val sizeForItems = value.map { FfiConverterTypeHostBandwidth.allocationSize(it) }.sum()
+        return sizeForLength + sizeForItems
     }
-    
-    /**
-     * TLS handshake failed.
-     */
-    class TlsException(
-        
-        /**
-         * Detailed error message
-         */
-        val `details`: kotlin.String
-        ) : ProxyException() {
-        override val message
-            get() = "details=${ `details` }"
+
+    override fun write(value: List<HostBandwidth>, buf: ByteBuffer) {
+        buf.putInt(value.size)
+        value.iterator().forEach {
+            FfiConverterTypeHostBandwidth.write(it, buf)
+        }
     }
-    
-    /**
-     * Failed to read or write configuration.
-     */
-    class StorageException(
-        
-        /**
-         * Detailed error message
-         */
-        val `details`: kotlin.String
-        ) : ProxyException() {
-        override val message
-            get() = "details=${ `details` }"
+}
+
+
+
+
+/**
+ * @suppress
+ */
+public object FfiConverterSequenceTypeRecentError: FfiConverterRustBuffer<List<RecentError>> {
+    override fun read(buf: ByteBuffer): List<RecentError> {
+        val len = buf.getInt()
+        return List<RecentError>(len) {
+            FfiConverterTypeRecentError.read(buf)
+        }
     }
-    
-    /**
-     * WireGuard cryptographic operation failed.
-     */
-    class CryptoException(
-        
-        /**
-         * Detailed error message
-         */
-        val `details`: kotlin.String
-        ) : ProxyException() {
-        override val message
-            get() = "details=${ `details` }"
+
+    override fun allocationSize(value: List<RecentError>): ULong {
+        val sizeForLength = 4UL
+        val sizeForItems = value.map { FfiConverterTypeRecentError.allocationSize(it) }.sum()
+        return sizeForLength + sizeForItems
     }
-    
-    /**
-     * Network is unavailable.
-     */
-    class NetworkUnavailable(
-        
-        /**
-         * Detailed error message
-         */
-        val `details`: kotlin.String
-        ) : ProxyException() {
-        override val message
-            get() = "details=${ `details` }"
+
+    override fun write(value: List<RecentError>, buf: ByteBuffer) {
+        buf.putInt(value.size)
+        value.iterator().forEach {
+            FfiConverterTypeRecentError.write(it, buf)
+        }
     }
-    
+}
 
-    
 
 
-    companion object ErrorHandler : UniffiRustCallStatusErrorHandler<ProxyException> {
-        override fun lift(error_buf: RustBuffer.ByValue): ProxyException = FfiConverterTypeProxyError.lift(error_buf)
+
+/**
+ * @suppress
+ */
+public object FfiConverterSequenceTypeRedirectHop: FfiConverterRustBuffer<List<RedirectHop>> {
+    override fun read(buf: ByteBuffer): List<RedirectHop> {
+        val len = buf.getInt()
+        return List<RedirectHop>(len) {
+            FfiConverterTypeRedirectHop.read(buf)
+        }
     }
 
-    
+    override fun allocationSize(value: List<RedirectHop>): ULong {
+        val sizeForLength = 4UL
+        val sizeForItems = value.map { FfiConverterTypeRedirectHop.allocationSize(it) }.sum()
+        return sizeForLength + sizeForItems
+    }
+
+    override fun write(value: List<RedirectHop>, buf: ByteBuffer) {
+        buf.putInt(value.size)
+        value.iterator().forEach {
+            FfiConverterTypeRedirectHop.write(it, buf)
+        }
+    }
 }
 
+
+
+
 /**
  * @suppress
  */
-public object FfiConverterTypeProxyError : FfiConverterRustBuffer<ProxyException> {
-    override fun read(buf: ByteBuffer): ProxyException {
-        
+public object FfiConverterSequenceTypeSrcsetCandidate: FfiConverterRustBuffer<List<SrcsetCandidate>> {
+    override fun read(buf: ByteBuffer): List<SrcsetCandidate> {
+        val len = buf.getInt()
+        return List<SrcsetCandidate>(len) {
+            FfiConverterTypeSrcsetCandidate.read(buf)
+        }
+    }
 
-        return when(buf.getInt()) {
-            1 -> ProxyException.NotInitialized()
-            2 -> ProxyException.InitializationFailed(
-                FfiConverterString.read(buf),
-                )
-            3 -> ProxyException.ProvisioningFailed(
-                FfiConverterString.read(buf),
-                )
-            4 -> ProxyException.TunnelException(
-                FfiConverterString.read(buf),
-                )
-            5 -> ProxyException.InvalidUrl(
-                FfiConverterString.read(buf),
-                FfiConverterString.read(buf),
-                )
-            6 -> ProxyException.HttpException(
-                FfiConverterUShort.read(buf),
-                FfiConverterString.read(buf),
-                )
-            7 -> ProxyException.InvalidContentType(
-                FfiConverterString.read(buf),
-                )
-            8 -> ProxyException.ResponseTooLarge(
-                FfiConverterULong.read(buf),
-                FfiConverterULong.read(buf),
-                )
-            9 -> ProxyException.TooManyRedirects(
-                FfiConverterUInt.read(buf),
-                FfiConverterUInt.read(buf),
-                )
-            10 -> ProxyException.Timeout(
-                FfiConverterUInt.read(buf),
-                )
-            11 -> ProxyException.DnsException(
-                FfiConverterString.read(buf),
-                FfiConverterString.read(buf),
-                )
-            12 -> ProxyException.TlsException(
-                FfiConverterString.read(buf),
-                )
-            13 -> ProxyException.StorageException(
-                FfiConverterString.read(buf),
-                )
-            14 -> ProxyException.CryptoException(
-                FfiConverterString.read(buf),
-                )
-            15 -> ProxyException.NetworkUnavailable(
-                FfiConverterString.read(buf),
-                )
-            else -> throw RuntimeException("invalid error enum value, something is very wrong!!")
+    override fun allocationSize(value: List<SrcsetCandidate>): ULong {
+        val sizeForLength = 4UL
+        val sizeForItems = value.map { FfiConverterTypeSrcsetCandidate.allocationSize(it) }.sum()
+        return sizeForLength + sizeForItems
+    }
+
+    override fun write(value: List<SrcsetCandidate>, buf: ByteBuffer) {
+        buf.putInt(value.size)
+        value.iterator().forEach {
+            FfiConverterTypeSrcsetCandidate.write(it, buf)
         }
     }
+}
 
-    override fun allocationSize(value: ProxyException): ULong {
-        return when(value) {
-            is ProxyException.NotInitialized -> (
-                // Add the size for the Int that specifies the variant plus the size needed for all fields
-                4UL
-            )
-            is ProxyException.InitializationFailed -> (
-                // Add the size for the Int that specifies the variant plus the size needed for all fields
-                4UL
-                + FfiConverterString.allocationSize(value.`details`)
-            )
-            is ProxyException.ProvisioningFailed -> (
-                // Add the size for the Int that specifies the variant plus the size needed for all fields
-                4UL
-                + FfiConverterString.allocationSize(value.`details`)
-            )
-            is ProxyException.TunnelException -> (
-                // Add the size for the Int that specifies the variant plus the size needed for all fields
-                4UL
-                + FfiConverterString.allocationSize(value.`details`)
-            )
-            is ProxyException.InvalidUrl -> (
-                // Add the size for the Int that specifies the variant plus the size needed for all fields
-                4UL
-                + FfiConverterString.allocationSize(value.`url`)
-                + FfiConverterString.allocationSize(value.`details`)
-            )
-            is ProxyException.HttpException -> (
-                // Add the size for the Int that specifies the variant plus the size needed for all fields
-                4UL
-                + FfiConverterUShort.allocationSize(value.`statusCode`)
-                + FfiConverterString.allocationSize(value.`details`)
-            )
-            is ProxyException.InvalidContentType -> (
-                // Add the size for the Int that specifies the variant plus the size needed for all fields
-                4UL
-                + FfiConverterString.allocationSize(value.`contentType`)
-            )
-            is ProxyException.ResponseTooLarge -> (
-                // Add the size for the Int that specifies the variant plus the size needed for all fields
-                4UL
-                + FfiConverterULong.allocationSize(value.`size`)
-                + FfiConverterULong.allocationSize(value.`maxSize`)
-            )
-            is ProxyException.TooManyRedirects -> (
-                // Add the size for the Int that specifies the variant plus the size needed for all fields
-                4UL
-                + FfiConverterUInt.allocationSize(value.`count`)
-                + FfiConverterUInt.allocationSize(value.`maxCount`)
-            )
-            is ProxyException.Timeout -> (
-                // Add the size for the Int that specifies the variant plus the size needed for all fields
-                4UL
-                + FfiConverterUInt.allocationSize(value.`seconds`)
-            )
-            is ProxyException.DnsException -> (
-                // Add the size for the Int that specifies the variant plus the size needed for all fields
-                4UL
-                + FfiConverterString.allocationSize(value.`host`)
-                + FfiConverterString.allocationSize(value.`details`)
-            )
-            is ProxyException.TlsException -> (
-                // Add the size for the Int that specifies the variant plus the size needed for all fields
-                4UL
-                + FfiConverterString.allocationSize(value.`details`)
-            )
-            is ProxyException.StorageException -> (
-                // Add the size for the Int that specifies the variant plus the size needed for all fields
-                4UL
-                + FfiConverterString.allocationSize(value.`details`)
-            )
-            is ProxyException.CryptoException -> (
-                // Add the size for the Int that specifies the variant plus the size needed for all fields
-                4UL
-                + FfiConverterString.allocationSize(value.`details`)
-            )
-            is ProxyException.NetworkUnavailable -> (
-                // Add the size for the Int that specifies the variant plus the size needed for all fields
-                4UL
-                + FfiConverterString.allocationSize(value.`details`)
-            )
+
+
+
+/**
+ * @suppress
+ */
+public object FfiConverterMapStringString: FfiConverterRustBuffer<Map<kotlin.String, kotlin.String>> {
+    override fun read(buf: ByteBuffer): Map<kotlin.String, kotlin.String> {
+        val len = buf.getInt()
+        return buildMap<kotlin.String, kotlin.String>(len) {
+            repeat(len) {
+                val k = FfiConverterString.read(buf)
+                val v = FfiConverterString.read(buf)
+                this[k] = v
+            }
         }
     }
 
-    override fun write(value: ProxyException, buf: ByteBuffer) {
-        when(value) {
-            is ProxyException.NotInitialized -> {
-                buf.putInt(1)
-                Unit
-            }
-            is ProxyException.InitializationFailed -> {
-                buf.putInt(2)
-                FfiConverterString.write(value.`details`, buf)
-                Unit
-            }
-            is ProxyException.ProvisioningFailed -> {
-                buf.putInt(3)
-                FfiConverterString.write(value.`details`, buf)
-                Unit
-            }
-            is ProxyException.TunnelException -> {
-                buf.putInt(4)
-                FfiConverterString.write(value.`details`, buf)
-                Unit
-            }
-            is ProxyException.InvalidUrl -> {
-                buf.putInt(5)
-                FfiConverterString.write(value.`url`, buf)
-                FfiConverterString.write(value.`details`, buf)
-                Unit
-            }
-            is ProxyException.HttpException -> {
-                buf.putInt(6)
-                FfiConverterUShort.write(value.`statusCode`, buf)
-                FfiConverterString.write(value.`details`, buf)
-                Unit
-            }
-            is ProxyException.InvalidContentType -> {
-                buf.putInt(7)
-                FfiConverterString.write(value.`contentType`, buf)
-                Unit
-            }
-            is ProxyException.ResponseTooLarge -> {
-                buf.putInt(8)
-                FfiConverterULong.write(value.`size`, buf)
-                FfiConverterULong.write(value.`maxSize`, buf)
-                Unit
-            }
-            is ProxyException.TooManyRedirects -> {
-                buf.putInt(9)
-                FfiConverterUInt.write(value.`count`, buf)
-                FfiConverterUInt.write(value.`maxCount`, buf)
-                Unit
-            }
-            is ProxyException.Timeout -> {
-                buf.putInt(10)
-                FfiConverterUInt.write(value.`seconds`, buf)
-                Unit
-            }
-            is ProxyException.DnsException -> {
-                buf.putInt(11)
-                FfiConverterString.write(value.`host`, buf)
-                FfiConverterString.write(value.`details`, buf)
-                Unit
-            }
-            is ProxyException.TlsException -> {
-                buf.putInt(12)
-                FfiConverterString.write(value.`details`, buf)
-                Unit
-            }
-            is ProxyException.StorageException -> {
-                buf.putInt(13)
-                FfiConverterString.write(value.`details`, buf)
-                Unit
-            }
-            is ProxyException.CryptoException -> {
-                buf.putInt(14)
-                FfiConverterString.write(value.`details`, buf)
-                Unit
-            }
-            is ProxyException.NetworkUnavailable -> {
-                buf.putInt(15)
-                FfiConverterString.write(value.`details`, buf)
-                Unit
-            }
-        }.let { /* this makes the `when` an expression, which ensures it is exhaustive */ }
+    override fun allocationSize(value: Map<kotlin.String, kotlin.String>): ULong {
+        val spaceForMapSize = 4UL
+        val spaceForChildren = value.map { (k, v) ->
+            FfiConverterString.allocationSize(k) +
+            FfiConverterString.allocationSize(v)
+        }.sum()
+        return spaceForMapSize + spaceForChildren
     }
 
+    override fun write(value: Map<kotlin.String, kotlin.String>, buf: ByteBuffer) {
+        buf.putInt(value.size)
+        // The parens on `(k, v)` here ensure we're calling the right method,
+        // which is important for compatibility with older android devices.
+        // Ref https://blog.danlew.net/2017/03/16/kotlin-puzzler-whose-line-is-it-anyways/
+        value.forEach { (k, v) ->
+            FfiConverterString.write(k, buf)
+            FfiConverterString.write(v, buf)
+        }
+    }
 }
 
 
 
-/**
- * Outcome of [`proxy_tls_self_test`].
- */
-sealed class TlsSelfTestOutcome {
+
+
+
+
+
+        /**
+         * [`ProxyClient::check_for_update`] through the default proxy.
+         */
+    @Throws(ProxyException::class) fun `proxyCheckForUpdate`(`currentVersion`: kotlin.String, `repo`: kotlin.String?): UpdateResult {
+            return FfiConverterTypeUpdateResult.lift(
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_check_for_update(
     
-    /**
-     * The handshake to Cloudflare completed and the server certificate verified
-     * against the bundled `webpki-roots` trust anchors. The strongest pass.
-     */
-    object Verified : TlsSelfTestOutcome()
+        FfiConverterString.lower(`currentVersion`),FfiConverterOptionalString.lower(`repo`),_status)
+}
+    )
+    }
     
+
+        /**
+         * [`ProxyClient::diagnostics`] of the default proxy.
+         */
+    @Throws(ProxyException::class) fun `proxyDiagnostics`(): WarpDiagnostics {
+            return FfiConverterTypeWarpDiagnostics.lift(
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_diagnostics(
+    
+        _status)
+}
+    )
+    }
     
-    /**
-     * The probe could not complete for a transient reason (DNS failure,
-     * connection refused/reset, timeout, ...). The certificate verifier was not
-     * the cause, so callers should treat this as a (noted) pass rather than a
-     * regression.
-     */
-    data class Inconclusive(
-        val `reason`: kotlin.String) : TlsSelfTestOutcome()
-        
-    {
-        
 
-        companion object
+        /**
+         * Initialize the default proxy.
+         *
+         * Loads or creates persisted configuration and prepares the in-memory cache.
+         * WARP provisioning and the WireGuard handshake are deferred until the first
+         * fetch so initialization stays fast and works offline. Calling it again
+         * before [`proxy_shutdown`] has no effect.
+         */
+    @Throws(ProxyException::class) fun `proxyInit`(`storagePath`: kotlin.String, `maxCacheSize`: kotlin.UInt)
+        = 
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_init(
+    
+        FfiConverterString.lower(`storagePath`),FfiConverterUInt.lower(`maxCacheSize`),_status)
+}
+    
+    
+
+        /**
+         * [`ProxyClient::shutdown`] the default proxy; [`proxy_init`] may be called
+         * again afterwards.
+         */
+    @Throws(ProxyException::class) fun `proxyShutdown`()
+        = 
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_shutdown(
+    
+        _status)
+}
+    
+    
+
+        /**
+         * [`ProxyClient::status`] of the default proxy.
+         */
+    @Throws(ProxyException::class) fun `proxyStatus`(): ProxyStatus {
+            return FfiConverterTypeProxyStatus.lift(
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_status(
+    
+        _status)
+}
+    )
+    }
+    
+
+        /**
+         * [`ProxyClient::reset_identity`] on the default proxy.
+         */
+    @Throws(ProxyException::class) fun `proxyResetIdentity`(): WarpStoredConfig {
+            return FfiConverterTypeWarpStoredConfig.lift(
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_reset_identity(
+    
+        _status)
+}
+    )
+    }
+    
+
+        /**
+         * [`ProxyClient::stored_config`] on the default proxy.
+         */
+    @Throws(ProxyException::class) fun `proxyStoredConfig`(): WarpStoredConfig {
+            return FfiConverterTypeWarpStoredConfig.lift(
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_stored_config(
+    
+        _status)
+}
+    )
+    }
+    
+
+        /**
+         * [`ProxyClient::audit_log_files`] of the default proxy.
+         */ fun `proxyAuditLogFiles`(): List<kotlin.String> {
+            return FfiConverterSequenceString.lift(
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_audit_log_files(
+    
+        _status)
+}
+    )
+    }
+    
+
+        /**
+         * [`ProxyClient::audit_url_hash`] of the default proxy.
+         */ fun `proxyAuditUrlHash`(`url`: kotlin.String): kotlin.String {
+            return FfiConverterString.lift(
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_audit_url_hash(
+    
+        FfiConverterString.lower(`url`),_status)
+}
+    )
     }
     
-    /**
-     * The certificate verifier was reached and faulted because `reqwest` fell
-     * back to the platform verifier, which was never initialized on Android.
-     * This is the exact regression guarded against and is always a failure.
-     */
-    data class PlatformVerifierUninitialized(
-        val `reason`: kotlin.String) : TlsSelfTestOutcome()
-        
-    {
-        
 
-        companion object
+        /**
+         * [`ProxyClient::clear_audit_log`] on the default proxy.
+         */
+    @Throws(ProxyException::class) fun `proxyClearAuditLog`()
+        = 
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_clear_audit_log(
+    
+        _status)
+}
+    
+    
+
+        /**
+         * [`ProxyClient::set_audit_log`] on the default proxy.
+         */
+    @Throws(ProxyException::class) fun `proxySetAuditLog`(`maxBytes`: kotlin.ULong)
+        = 
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_set_audit_log(
+    
+        FfiConverterULong.lower(`maxBytes`),_status)
+}
+    
+    
+
+        /**
+         * [`ProxyClient::bandwidth_usage`] of the default proxy.
+         */ fun `proxyBandwidthUsage`(): List<HostBandwidth> {
+            return FfiConverterSequenceTypeHostBandwidth.lift(
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_bandwidth_usage(
+    
+        _status)
+}
+    )
     }
     
 
+        /**
+         * [`ProxyClient::reset_bandwidth_usage`] on the default proxy.
+         */
+    @Throws(ProxyException::class) fun `proxyResetBandwidthUsage`()
+        = 
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_reset_bandwidth_usage(
     
-
+        _status)
+}
     
     
 
-
-    companion object
+        /**
+         * [`ProxyClient::fetch_images_batch`] on the default proxy.
+         */ fun `proxyFetchImagesBatch`(`urls`: List<kotlin.String>, `maxConcurrent`: kotlin.UInt, `priority`: RequestPriority, `groupId`: kotlin.String? = null): ImageBatch {
+            return FfiConverterTypeImageBatch.lift(
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_fetch_images_batch(
+    
+        FfiConverterSequenceString.lower(`urls`),FfiConverterUInt.lower(`maxConcurrent`),FfiConverterTypeRequestPriority.lower(`priority`),FfiConverterOptionalString.lower(`groupId`),_status)
 }
-
-/**
- * @suppress
- */
-public object FfiConverterTypeTlsSelfTestOutcome : FfiConverterRustBuffer<TlsSelfTestOutcome>{
-    override fun read(buf: ByteBuffer): TlsSelfTestOutcome {
-        return when(buf.getInt()) {
-            1 -> TlsSelfTestOutcome.Verified
-            2 -> TlsSelfTestOutcome.Inconclusive(
-                FfiConverterString.read(buf),
-                )
-            3 -> TlsSelfTestOutcome.PlatformVerifierUninitialized(
-                FfiConverterString.read(buf),
-                )
-            else -> throw RuntimeException("invalid enum value, something is very wrong!!")
-        }
+    )
     }
+    
 
-    override fun allocationSize(value: TlsSelfTestOutcome) = when(value) {
-        is TlsSelfTestOutcome.Verified -> {
-            // Add the size for the Int that specifies the variant plus the size needed for all fields
-            (
-                4UL
-            )
-        }
-        is TlsSelfTestOutcome.Inconclusive -> {
-            // Add the size for the Int that specifies the variant plus the size needed for all fields
-            (
-                4UL
-                + FfiConverterString.allocationSize(value.`reason`)
-            )
-        }
-        is TlsSelfTestOutcome.PlatformVerifierUninitialized -> {
-            // Add the size for the Int that specifies the variant plus the size needed for all fields
-            (
-                4UL
-                + FfiConverterString.allocationSize(value.`reason`)
-            )
-        }
+        /**
+         * [`ProxyClient::fetch_images_streaming`] on the default proxy.
+         */
+    @Throws(ProxyException::class)
+    @Suppress("ASSIGNED_BUT_NEVER_ACCESSED_VARIABLE")
+     suspend fun `proxyFetchImagesStreaming`(`urls`: List<kotlin.String>, `maxConcurrent`: kotlin.UInt, `priority`: RequestPriority, `listener`: BatchResultListener, `groupId`: kotlin.String? = null) {
+        return uniffiRustCallAsync(
+        UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_fetch_images_streaming(FfiConverterSequenceString.lower(`urls`),FfiConverterUInt.lower(`maxConcurrent`),FfiConverterTypeRequestPriority.lower(`priority`),FfiConverterTypeBatchResultListener.lower(`listener`),FfiConverterOptionalString.lower(`groupId`),),
+        { future, callback, continuation -> UniffiLib.ffi_letterbox_proxy_rust_future_poll_void(future, callback, continuation) },
+        { future, continuation -> UniffiLib.ffi_letterbox_proxy_rust_future_complete_void(future, continuation) },
+        { future -> UniffiLib.ffi_letterbox_proxy_rust_future_free_void(future) },
+        // lift function
+        { Unit },
+        
+        // Error FFI converter
+        ProxyException.ErrorHandler,
+    )
     }
 
-    override fun write(value: TlsSelfTestOutcome, buf: ByteBuffer) {
-        when(value) {
-            is TlsSelfTestOutcome.Verified -> {
-                buf.putInt(1)
-                Unit
-            }
-            is TlsSelfTestOutcome.Inconclusive -> {
-                buf.putInt(2)
-                FfiConverterString.write(value.`reason`, buf)
-                Unit
-            }
-            is TlsSelfTestOutcome.PlatformVerifierUninitialized -> {
-                buf.putInt(3)
-                FfiConverterString.write(value.`reason`, buf)
-                Unit
-            }
-        }.let { /* this makes the `when` an expression, which ensures it is exhaustive */ }
-    }
+        /**
+         * [`ProxyClient::clear_cache`] on the default proxy.
+         */
+    @Throws(ProxyException::class) fun `proxyClearCache`()
+        = 
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_clear_cache(
+    
+        _status)
 }
+    
+    
 
+        /**
+         * [`ProxyClient::disable_disk_cache`] on the default proxy.
+         */
+    @Throws(ProxyException::class) fun `proxyDisableDiskCache`()
+        = 
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_disable_disk_cache(
+    
+        _status)
+}
+    
+    
 
+        /**
+         * [`ProxyClient::enable_disk_cache`] on the default proxy.
+         */
+    @Throws(ProxyException::class) fun `proxyEnableDiskCache`(`options`: DiskCacheOptions)
+        = 
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_enable_disk_cache(
+    
+        FfiConverterTypeDiskCacheOptions.lower(`options`),_status)
+}
+    
+    
 
-
-
-
-/**
- * @suppress
- */
-public object FfiConverterOptionalUInt: FfiConverterRustBuffer<kotlin.UInt?> {
-    override fun read(buf: ByteBuffer): kotlin.UInt? {
-        if (buf.get().toInt() == 0) {
-            return null
-        }
-        return FfiConverterUInt.read(buf)
+        /**
+         * [`ProxyClient::export_cache`] on the default proxy.
+         */
+    @Throws(ProxyException::class) fun `proxyExportCache`(`path`: kotlin.String): kotlin.UInt {
+            return FfiConverterUInt.lift(
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_export_cache(
+    
+        FfiConverterString.lower(`path`),_status)
+}
+    )
     }
+    
 
-    override fun allocationSize(value: kotlin.UInt?): ULong {
-        if (value == null) {
-            return 1UL
-        } else {
-            return 1UL + FfiConverterUInt.allocationSize(value)
-        }
+        /**
+         * [`ProxyClient::import_cache`] on the default proxy.
+         */
+    @Throws(ProxyException::class) fun `proxyImportCache`(`path`: kotlin.String): kotlin.UInt {
+            return FfiConverterUInt.lift(
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_import_cache(
+    
+        FfiConverterString.lower(`path`),_status)
+}
+    )
     }
+    
 
-    override fun write(value: kotlin.UInt?, buf: ByteBuffer) {
-        if (value == null) {
-            buf.put(0)
-        } else {
-            buf.put(1)
-            FfiConverterUInt.write(value, buf)
-        }
-    }
+        /**
+         * [`ProxyClient::pin_url`] on the default proxy.
+         */
+    @Throws(ProxyException::class) fun `proxyPinUrl`(`url`: kotlin.String)
+        = 
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_pin_url(
+    
+        FfiConverterString.lower(`url`),_status)
 }
+    
+    
 
-
-
-
-/**
- * @suppress
- */
-public object FfiConverterOptionalULong: FfiConverterRustBuffer<kotlin.ULong?> {
-    override fun read(buf: ByteBuffer): kotlin.ULong? {
-        if (buf.get().toInt() == 0) {
-            return null
-        }
-        return FfiConverterULong.read(buf)
-    }
-
-    override fun allocationSize(value: kotlin.ULong?): ULong {
-        if (value == null) {
-            return 1UL
-        } else {
-            return 1UL + FfiConverterULong.allocationSize(value)
-        }
+        /**
+         * [`ProxyClient::pinned_urls`] on the default proxy.
+         */
+    @Throws(ProxyException::class) fun `proxyPinnedUrls`(): List<kotlin.String> {
+            return FfiConverterSequenceString.lift(
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_pinned_urls(
+    
+        _status)
+}
+    )
     }
+    
 
-    override fun write(value: kotlin.ULong?, buf: ByteBuffer) {
-        if (value == null) {
-            buf.put(0)
-        } else {
-            buf.put(1)
-            FfiConverterULong.write(value, buf)
-        }
-    }
+        /**
+         * [`ProxyClient::set_cache_storage`] on the default proxy.
+         */
+    @Throws(ProxyException::class) fun `proxySetCacheStorage`(`storage`: CacheStorage?, `mode`: CacheStorageMode? = null)
+        = 
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_set_cache_storage(
+    
+        FfiConverterOptionalTypeCacheStorage.lower(`storage`),FfiConverterOptionalTypeCacheStorageMode.lower(`mode`),_status)
 }
+    
+    
 
-
-
-
-/**
- * @suppress
- */
-public object FfiConverterOptionalString: FfiConverterRustBuffer<kotlin.String?> {
-    override fun read(buf: ByteBuffer): kotlin.String? {
-        if (buf.get().toInt() == 0) {
-            return null
-        }
-        return FfiConverterString.read(buf)
+        /**
+         * [`ProxyClient::trim_cache`] on the default proxy.
+         */
+    @Throws(ProxyException::class) fun `proxyTrimCache`(`targetBytes`: kotlin.ULong, `spillToDisk`: kotlin.Boolean): kotlin.ULong {
+            return FfiConverterULong.lift(
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_trim_cache(
+    
+        FfiConverterULong.lower(`targetBytes`),FfiConverterBoolean.lower(`spillToDisk`),_status)
+}
+    )
     }
+    
 
-    override fun allocationSize(value: kotlin.String?): ULong {
-        if (value == null) {
-            return 1UL
-        } else {
-            return 1UL + FfiConverterString.allocationSize(value)
-        }
+        /**
+         * [`ProxyClient::unpin_url`] on the default proxy.
+         */
+    @Throws(ProxyException::class) fun `proxyUnpinUrl`(`url`: kotlin.String): kotlin.Boolean {
+            return FfiConverterBoolean.lift(
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_unpin_url(
+    
+        FfiConverterString.lower(`url`),_status)
+}
+    )
     }
+    
 
-    override fun write(value: kotlin.String?, buf: ByteBuffer) {
-        if (value == null) {
-            buf.put(0)
-        } else {
-            buf.put(1)
-            FfiConverterString.write(value, buf)
-        }
-    }
+        /**
+         * [`ProxyClient::set_fetch_concurrency`] on the default proxy.
+         */
+    @Throws(ProxyException::class) fun `proxySetFetchConcurrency`(`concurrency`: FetchConcurrency)
+        = 
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_set_fetch_concurrency(
+    
+        FfiConverterTypeFetchConcurrency.lower(`concurrency`),_status)
 }
+    
+    
 
-
-
-
-/**
- * @suppress
- */
-public object FfiConverterOptionalTypeImageResponse: FfiConverterRustBuffer<ImageResponse?> {
-    override fun read(buf: ByteBuffer): ImageResponse? {
-        if (buf.get().toInt() == 0) {
-            return null
-        }
-        return FfiConverterTypeImageResponse.read(buf)
+        /**
+         * [`ProxyClient::data_saver`] of the default proxy.
+         */
+    @Throws(ProxyException::class) fun `proxyDataSaver`(): DataSaver? {
+            return FfiConverterOptionalTypeDataSaver.lift(
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_data_saver(
+    
+        _status)
+}
+    )
     }
+    
 
-    override fun allocationSize(value: ImageResponse?): ULong {
-        if (value == null) {
-            return 1UL
-        } else {
-            return 1UL + FfiConverterTypeImageResponse.allocationSize(value)
-        }
-    }
+        /**
+         * [`ProxyClient::set_data_saver`] on the default proxy.
+         */
+    @Throws(ProxyException::class) fun `proxySetDataSaver`(`policy`: DataSaver?)
+        = 
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_set_data_saver(
+    
+        FfiConverterOptionalTypeDataSaver.lower(`policy`),_status)
+}
+    
+    
 
-    override fun write(value: ImageResponse?, buf: ByteBuffer) {
-        if (value == null) {
-            buf.put(0)
-        } else {
-            buf.put(1)
-            FfiConverterTypeImageResponse.write(value, buf)
-        }
-    }
+        /**
+         * [`ProxyClient::set_max_image_pixels`] on the default proxy.
+         */
+    @Throws(ProxyException::class) fun `proxySetMaxImagePixels`(`maxPixels`: kotlin.ULong)
+        = 
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_set_max_image_pixels(
+    
+        FfiConverterULong.lower(`maxPixels`),_status)
 }
+    
+    
 
+        /**
+         * [`ProxyClient::fetch_image_to_file`] on the default proxy.
+         */
+    @Throws(ProxyException::class) fun `proxyFetchImageToFile`(`url`: kotlin.String, `destPath`: kotlin.String, `options`: FileFetchOptions): FileFetchResult {
+            return FfiConverterTypeFileFetchResult.lift(
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_fetch_image_to_file(
+    
+        FfiConverterString.lower(`url`),FfiConverterString.lower(`destPath`),FfiConverterTypeFileFetchOptions.lower(`options`),_status)
+}
+    )
+    }
+    
 
+        /**
+         * [`ProxyClient::set_tls_early_data`] on the default proxy.
+         */
+    @Throws(ProxyException::class) fun `proxySetTlsEarlyData`(`enabled`: kotlin.Boolean)
+        = 
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_set_tls_early_data(
+    
+        FfiConverterBoolean.lower(`enabled`),_status)
+}
+    
+    
 
+        /**
+         * [`ProxyClient::set_event_listener`] on the default proxy.
+         */
+    @Throws(ProxyException::class) fun `proxySetEventListener`(`listener`: ProxyEventListener?)
+        = 
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_set_event_listener(
+    
+        FfiConverterOptionalTypeProxyEventListener.lower(`listener`),_status)
+}
+    
+    
 
-/**
- * @suppress
- */
-public object FfiConverterOptionalMapStringString: FfiConverterRustBuffer<Map<kotlin.String, kotlin.String>?> {
-    override fun read(buf: ByteBuffer): Map<kotlin.String, kotlin.String>? {
-        if (buf.get().toInt() == 0) {
-            return null
-        }
-        return FfiConverterMapStringString.read(buf)
+        /**
+         * [`ProxyClient::fetch_favicon`] on the default proxy.
+         */
+    @Throws(ProxyException::class) fun `proxyFetchFavicon`(`domain`: kotlin.String, `sizeHint`: kotlin.UInt): ImageResponse {
+            return FfiConverterTypeImageResponse.lift(
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_fetch_favicon(
+    
+        FfiConverterString.lower(`domain`),FfiConverterUInt.lower(`sizeHint`),_status)
+}
+    )
     }
+    
 
-    override fun allocationSize(value: Map<kotlin.String, kotlin.String>?): ULong {
-        if (value == null) {
-            return 1UL
-        } else {
-            return 1UL + FfiConverterMapStringString.allocationSize(value)
-        }
+        /**
+         * [`ProxyClient::fetch_image`] through the default proxy.
+         */
+    @Throws(ProxyException::class)
+    @Suppress("ASSIGNED_BUT_NEVER_ACCESSED_VARIABLE")
+     suspend fun `proxyFetchImage`(`url`: kotlin.String, `headers`: Map<kotlin.String, kotlin.String>?, `expectedHash`: kotlin.String? = null, `groupId`: kotlin.String? = null) : ImageResponse {
+        return uniffiRustCallAsync(
+        UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_fetch_image(FfiConverterString.lower(`url`),FfiConverterOptionalMapStringString.lower(`headers`),FfiConverterOptionalString.lower(`expectedHash`),FfiConverterOptionalString.lower(`groupId`),),
+        { future, callback, continuation -> UniffiLib.ffi_letterbox_proxy_rust_future_poll_rust_buffer(future, callback, continuation) },
+        { future, continuation -> UniffiLib.ffi_letterbox_proxy_rust_future_complete_rust_buffer(future, continuation) },
+        { future -> UniffiLib.ffi_letterbox_proxy_rust_future_free_rust_buffer(future) },
+        // lift function
+        { FfiConverterTypeImageResponse.lift(it) },
+        // Error FFI converter
+        ProxyException.ErrorHandler,
+    )
     }
 
-    override fun write(value: Map<kotlin.String, kotlin.String>?, buf: ByteBuffer) {
-        if (value == null) {
-            buf.put(0)
-        } else {
-            buf.put(1)
-            FfiConverterMapStringString.write(value, buf)
-        }
+        /**
+         * [`ProxyClient::fetch_url`] through the default proxy.
+         */
+    @Throws(ProxyException::class)
+    @Suppress("ASSIGNED_BUT_NEVER_ACCESSED_VARIABLE")
+     suspend fun `proxyFetchUrl`(`url`: kotlin.String, `headers`: Map<kotlin.String, kotlin.String>?) : HttpFetchResponse {
+        return uniffiRustCallAsync(
+        UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_fetch_url(FfiConverterString.lower(`url`),FfiConverterOptionalMapStringString.lower(`headers`),),
+        { future, callback, continuation -> UniffiLib.ffi_letterbox_proxy_rust_future_poll_rust_buffer(future, callback, continuation) },
+        { future, continuation -> UniffiLib.ffi_letterbox_proxy_rust_future_complete_rust_buffer(future, continuation) },
+        { future -> UniffiLib.ffi_letterbox_proxy_rust_future_free_rust_buffer(future) },
+        // lift function
+        { FfiConverterTypeHttpFetchResponse.lift(it) },
+        // Error FFI converter
+        ProxyException.ErrorHandler,
+    )
     }
+
+        /**
+         * [`ProxyClient::set_image_accept`] on the default proxy.
+         */
+    @Throws(ProxyException::class) fun `proxySetImageAccept`(`accept`: kotlin.String)
+        = 
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_set_image_accept(
+    
+        FfiConverterString.lower(`accept`),_status)
 }
+    
+    
 
+        /**
+         * [`ProxyClient::reset_group_budget`] on the default proxy.
+         */
+    @Throws(ProxyException::class) fun `proxyResetGroupBudget`(`groupId`: kotlin.String)
+        = 
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_reset_group_budget(
+    
+        FfiConverterString.lower(`groupId`),_status)
+}
+    
+    
 
+        /**
+         * [`ProxyClient::set_group_byte_budget`] on the default proxy.
+         */
+    @Throws(ProxyException::class) fun `proxySetGroupByteBudget`(`maxBytes`: kotlin.ULong)
+        = 
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_set_group_byte_budget(
+    
+        FfiConverterULong.lower(`maxBytes`),_status)
+}
+    
+    
 
+        /**
+         * [`ProxyClient::set_group_referer`] on the default proxy.
+         */
+    @Throws(ProxyException::class) fun `proxySetGroupReferer`(`groupId`: kotlin.String, `grant`: RefererGrant?)
+        = 
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_set_group_referer(
+    
+        FfiConverterString.lower(`groupId`),FfiConverterOptionalTypeRefererGrant.lower(`grant`),_status)
+}
+    
+    
 
-/**
- * @suppress
- */
-public object FfiConverterSequenceString: FfiConverterRustBuffer<List<kotlin.String>> {
-    override fun read(buf: ByteBuffer): List<kotlin.String> {
-        val len = buf.getInt()
-        return List<kotlin.String>(len) {
-            FfiConverterString.read(buf)
-        }
+        /**
+         * [`ProxyClient::fetch_image_handle`] through the default proxy.
+         */
+    @Throws(ProxyException::class)
+    @Suppress("ASSIGNED_BUT_NEVER_ACCESSED_VARIABLE")
+     suspend fun `proxyFetchImageHandle`(`url`: kotlin.String, `headers`: Map<kotlin.String, kotlin.String>?, `expectedHash`: kotlin.String? = null, `groupId`: kotlin.String? = null) : ImageHandle {
+        return uniffiRustCallAsync(
+        UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_fetch_image_handle(FfiConverterString.lower(`url`),FfiConverterOptionalMapStringString.lower(`headers`),FfiConverterOptionalString.lower(`expectedHash`),FfiConverterOptionalString.lower(`groupId`),),
+        { future, callback, continuation -> UniffiLib.ffi_letterbox_proxy_rust_future_poll_u64(future, callback, continuation) },
+        { future, continuation -> UniffiLib.ffi_letterbox_proxy_rust_future_complete_u64(future, continuation) },
+        { future -> UniffiLib.ffi_letterbox_proxy_rust_future_free_u64(future) },
+        // lift function
+        { FfiConverterTypeImageHandle.lift(it) },
+        // Error FFI converter
+        ProxyException.ErrorHandler,
+    )
     }
 
-    override fun allocationSize(value: List<kotlin.String>): ULong {
-        val sizeForLength = 4UL
-        val sizeForItems = value.map { FfiConverterString.allocationSize(it) }.sum()
-        return sizeForLength + sizeForItems
-    }
+        /**
+         * [`ProxyClient::set_allowed_sensitive_headers`] on the default proxy.
+         */
+    @Throws(ProxyException::class) fun `proxySetAllowedSensitiveHeaders`(`names`: List<kotlin.String>)
+        = 
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_set_allowed_sensitive_headers(
+    
+        FfiConverterSequenceString.lower(`names`),_status)
+}
+    
+    
 
-    override fun write(value: List<kotlin.String>, buf: ByteBuffer) {
-        buf.putInt(value.size)
-        value.iterator().forEach {
-            FfiConverterString.write(it, buf)
-        }
+        /**
+         * [`ProxyClient::set_hedge_delay`] on the default proxy.
+         */
+    @Throws(ProxyException::class) fun `proxySetHedgeDelay`(`delayMs`: kotlin.ULong)
+        = 
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_set_hedge_delay(
+    
+        FfiConverterULong.lower(`delayMs`),_status)
+}
+    
+    
+
+        /**
+         * [`ProxyClient::is_enabled`] of the default proxy.
+         */ fun `proxyIsEnabled`(): kotlin.Boolean {
+            return FfiConverterBoolean.lift(
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_is_enabled(
+    
+        _status)
+}
+    )
     }
+    
+
+        /**
+         * [`ProxyClient::set_enabled`] on the default proxy.
+         */ fun `proxySetEnabled`(`enabled`: kotlin.Boolean)
+        = 
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_set_enabled(
+    
+        FfiConverterBoolean.lower(`enabled`),_status)
 }
+    
+    
 
+        /**
+         * Switch the network requests of every client in the process on or off.
+         * Switching on again leaves clients switched off on their own off.
+         */ fun `proxySetEnabledGlobally`(`enabled`: kotlin.Boolean)
+        = 
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_set_enabled_globally(
+    
+        FfiConverterBoolean.lower(`enabled`),_status)
+}
+    
+    
 
+        /**
+         * Forward log records at `max_level` and above to `listener`, or stop
+         * forwarding with `None`. `max_level` also applies to logcat.
+         */
+    @Throws(ProxyException::class) fun `proxySetLogListener`(`listener`: LogListener?, `maxLevel`: LogLevel)
+        = 
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_set_log_listener(
+    
+        FfiConverterOptionalTypeLogListener.lower(`listener`),FfiConverterTypeLogLevel.lower(`maxLevel`),_status)
+}
+    
+    
 
+        /**
+         * [`ProxyClient::start_loopback_server`] on the default proxy.
+         */
+    @Throws(ProxyException::class) fun `proxyStartLoopbackServer`(): LoopbackServer {
+            return FfiConverterTypeLoopbackServer.lift(
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_start_loopback_server(
+    
+        _status)
+}
+    )
+    }
+    
 
-/**
- * @suppress
- */
-public object FfiConverterSequenceTypeBatchImageResult: FfiConverterRustBuffer<List<BatchImageResult>> {
-    override fun read(buf: ByteBuffer): List<BatchImageResult> {
-        val len = buf.getInt()
-        return List<BatchImageResult>(len) {
-            FfiConverterTypeBatchImageResult.read(buf)
-        }
+        /**
+         * [`ProxyClient::metrics`] of the default proxy.
+         */ fun `proxyMetrics`(): ProxyMetrics {
+            return FfiConverterTypeProxyMetrics.lift(
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_metrics(
+    
+        _status)
+}
+    )
     }
+    
 
-    override fun allocationSize(value: List<BatchImageResult>): ULong {
-        val sizeForLength = 4UL
-        val sizeForItems = value.map { FfiConverterTypeBatchImageResult.allocationSize(it) }.sum()
-        return sizeForLength + sizeForItems
+        /**
+         * [`ProxyClient::clear_offline_queue`] on the default proxy.
+         */
+    @Throws(ProxyException::class) fun `proxyClearOfflineQueue`()
+        = 
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_clear_offline_queue(
+    
+        _status)
+}
+    
+    
+
+        /**
+         * [`ProxyClient::offline_queue`] of the default proxy.
+         */ fun `proxyOfflineQueue`(): List<kotlin.String> {
+            return FfiConverterSequenceString.lift(
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_offline_queue(
+    
+        _status)
+}
+    )
     }
+    
 
-    override fun write(value: List<BatchImageResult>, buf: ByteBuffer) {
-        buf.putInt(value.size)
-        value.iterator().forEach {
-            FfiConverterTypeBatchImageResult.write(it, buf)
-        }
-    }
+        /**
+         * [`ProxyClient::queue_for_offline`] on the default proxy.
+         */
+    @Throws(ProxyException::class) fun `proxyQueueForOffline`(`urls`: List<kotlin.String>): kotlin.UInt {
+            return FfiConverterUInt.lift(
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_queue_for_offline(
+    
+        FfiConverterSequenceString.lower(`urls`),_status)
 }
-
-
-
-
-/**
- * @suppress
- */
-public object FfiConverterMapStringString: FfiConverterRustBuffer<Map<kotlin.String, kotlin.String>> {
-    override fun read(buf: ByteBuffer): Map<kotlin.String, kotlin.String> {
-        val len = buf.getInt()
-        return buildMap<kotlin.String, kotlin.String>(len) {
-            repeat(len) {
-                val k = FfiConverterString.read(buf)
-                val v = FfiConverterString.read(buf)
-                this[k] = v
-            }
-        }
+    )
     }
+    
 
-    override fun allocationSize(value: Map<kotlin.String, kotlin.String>): ULong {
-        val spaceForMapSize = 4UL
-        val spaceForChildren = value.map { (k, v) ->
-            FfiConverterString.allocationSize(k) +
-            FfiConverterString.allocationSize(v)
-        }.sum()
-        return spaceForMapSize + spaceForChildren
-    }
+        /**
+         * [`ProxyClient::retry_offline_queue`] on the default proxy.
+         */ fun `proxyRetryOfflineQueue`()
+        = 
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_retry_offline_queue(
+    
+        _status)
+}
+    
+    
 
-    override fun write(value: Map<kotlin.String, kotlin.String>, buf: ByteBuffer) {
-        buf.putInt(value.size)
-        // The parens on `(k, v)` here ensure we're calling the right method,
-        // which is important for compatibility with older android devices.
-        // Ref https://blog.danlew.net/2017/03/16/kotlin-puzzler-whose-line-is-it-anyways/
-        value.forEach { (k, v) ->
-            FfiConverterString.write(k, buf)
-            FfiConverterString.write(v, buf)
-        }
-    }
+        /**
+         * [`ProxyClient::set_offline_listener`] on the default proxy.
+         */ fun `proxySetOfflineListener`(`listener`: OfflineFetchListener?)
+        = 
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_set_offline_listener(
+    
+        FfiConverterOptionalTypeOfflineFetchListener.lower(`listener`),_status)
 }
+    
+    
+
         /**
-         * Check for a newer release through the tunnel.
-         *
-         * Pass the running version (e.g. `"v1.2.3"`); `repo` defaults to the official
-         * distribution slug when empty.
+         * [`ProxyClient::is_paused`] on the default proxy.
          */
-    @Throws(ProxyException::class) fun `proxyCheckForUpdate`(`currentVersion`: kotlin.String, `repo`: kotlin.String?): UpdateResult {
-            return FfiConverterTypeUpdateResult.lift(
+    @Throws(ProxyException::class) fun `proxyIsPaused`(): kotlin.Boolean {
+            return FfiConverterBoolean.lift(
     uniffiRustCallWithError(ProxyException) { _status ->
-    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_check_for_update(
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_is_paused(
     
-        FfiConverterString.lower(`currentVersion`),FfiConverterOptionalString.lower(`repo`),_status)
+        _status)
 }
     )
     }
     
 
         /**
-         * Clear the in-memory image cache.
+         * [`ProxyClient::pause`] on the default proxy.
          */
-    @Throws(ProxyException::class) fun `proxyClearCache`()
+    @Throws(ProxyException::class) fun `proxyPause`()
         = 
     uniffiRustCallWithError(ProxyException) { _status ->
-    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_clear_cache(
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_pause(
     
         _status)
 }
@@ -2707,88 +14997,106 @@ public object FfiConverterMapStringString: FfiConverterRustBuffer<Map<kotlin.Str
     
 
         /**
-         * Collect full WireGuard/WARP diagnostics, provisioning the tunnel if needed.
+         * [`ProxyClient::resume`] on the default proxy.
          */
-    @Throws(ProxyException::class) fun `proxyDiagnostics`(): WarpDiagnostics {
-            return FfiConverterTypeWarpDiagnostics.lift(
+    @Throws(ProxyException::class) fun `proxyResume`()
+        = 
     uniffiRustCallWithError(ProxyException) { _status ->
-    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_diagnostics(
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_resume(
     
         _status)
 }
-    )
-    }
+    
     
 
         /**
-         * Fetch a single image through the WARP tunnel.
+         * [`ProxyClient::prefetch`] on the default proxy.
          */
-    @Throws(ProxyException::class) fun `proxyFetchImage`(`url`: kotlin.String, `headers`: Map<kotlin.String, kotlin.String>?): ImageResponse {
-            return FfiConverterTypeImageResponse.lift(
+    @Throws(ProxyException::class) fun `proxyPrefetch`(`urls`: List<kotlin.String>, `priority`: FetchPriority): kotlin.UInt {
+            return FfiConverterUInt.lift(
     uniffiRustCallWithError(ProxyException) { _status ->
-    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_fetch_image(
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_prefetch(
     
-        FfiConverterString.lower(`url`),FfiConverterOptionalMapStringString.lower(`headers`),_status)
+        FfiConverterSequenceString.lower(`urls`),FfiConverterTypeFetchPriority.lower(`priority`),_status)
 }
     )
     }
     
 
         /**
-         * Fetch multiple images through the tunnel.
-         *
-         * Requests are serviced by the single shared tunnel, so they are processed in
-         * order; `max_concurrent` is accepted for API stability but currently advisory.
+         * [`ProxyClient::resolve_preview`] on the default proxy.
          */
-    @Throws(ProxyException::class) fun `proxyFetchImagesBatch`(`urls`: List<kotlin.String>, `maxConcurrent`: kotlin.UInt): List<BatchImageResult> {
-            return FfiConverterSequenceTypeBatchImageResult.lift(
+    @Throws(ProxyException::class) fun `proxyResolvePreview`(`url`: kotlin.String): LinkPreview {
+            return FfiConverterTypeLinkPreview.lift(
     uniffiRustCallWithError(ProxyException) { _status ->
-    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_fetch_images_batch(
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_resolve_preview(
     
-        FfiConverterSequenceString.lower(`urls`),FfiConverterUInt.lower(`maxConcurrent`),_status)
+        FfiConverterString.lower(`url`),_status)
 }
     )
     }
     
 
         /**
-         * Fetch an arbitrary URL through the tunnel (non-image content allowed).
+         * [`ProxyClient::probe_image`] on the default proxy.
          */
-    @Throws(ProxyException::class) fun `proxyFetchUrl`(`url`: kotlin.String, `headers`: Map<kotlin.String, kotlin.String>?): HttpFetchResponse {
-            return FfiConverterTypeHttpFetchResponse.lift(
+    @Throws(ProxyException::class) fun `proxyProbeImage`(`url`: kotlin.String): ImageProbe {
+            return FfiConverterTypeImageProbe.lift(
     uniffiRustCallWithError(ProxyException) { _status ->
-    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_fetch_url(
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_probe_image(
     
-        FfiConverterString.lower(`url`),FfiConverterOptionalMapStringString.lower(`headers`),_status)
+        FfiConverterString.lower(`url`),_status)
 }
     )
     }
     
 
         /**
-         * Initialize the image proxy.
+         * Register (or, with `None`, remove) the progress listener.
          *
-         * Loads or creates persisted configuration and prepares the in-memory cache.
-         * WARP provisioning and the WireGuard handshake are deferred until the first
-         * fetch so initialization stays fast and works offline.
-         */
-    @Throws(ProxyException::class) fun `proxyInit`(`storagePath`: kotlin.String, `maxCacheSize`: kotlin.UInt)
+         * May be called before [`crate::proxy_init`]; the listener survives
+         * re-initialisation.
+         */ fun `proxySetProgressListener`(`listener`: ProgressListener?)
         = 
-    uniffiRustCallWithError(ProxyException) { _status ->
-    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_init(
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_set_progress_listener(
     
-        FfiConverterString.lower(`storagePath`),FfiConverterUInt.lower(`maxCacheSize`),_status)
+        FfiConverterOptionalTypeProgressListener.lower(`listener`),_status)
 }
     
     
 
         /**
-         * Shut down the proxy, dropping the tunnel and cache.
+         * [`ProxyClient::set_network_binding`] on the default proxy.
          */
-    @Throws(ProxyException::class) fun `proxyShutdown`()
+    @Throws(ProxyException::class) fun `proxySetNetworkBinding`(`binding`: NetworkBinding)
         = 
     uniffiRustCallWithError(ProxyException) { _status ->
-    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_shutdown(
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_set_network_binding(
+    
+        FfiConverterTypeNetworkBinding.lower(`binding`),_status)
+}
+    
+    
+
+        /**
+         * [`ProxyClient::set_socket_protector`] on the default proxy.
+         */ fun `proxySetSocketProtector`(`protector`: SocketProtector?)
+        = 
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_set_socket_protector(
+    
+        FfiConverterOptionalTypeSocketProtector.lower(`protector`),_status)
+}
+    
+    
+
+        /**
+         * [`ProxyClient::clear_recent_errors`] on the default proxy.
+         */ fun `proxyClearRecentErrors`()
+        = 
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_clear_recent_errors(
     
         _status)
 }
@@ -2796,12 +15104,11 @@ public object FfiConverterMapStringString: FfiConverterRustBuffer<Map<kotlin.Str
     
 
         /**
-         * Get the current proxy status.
-         */
-    @Throws(ProxyException::class) fun `proxyStatus`(): ProxyStatus {
-            return FfiConverterTypeProxyStatus.lift(
-    uniffiRustCallWithError(ProxyException) { _status ->
-    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_status(
+         * [`ProxyClient::recent_errors`] of the default proxy.
+         */ fun `proxyRecentErrors`(): List<RecentError> {
+            return FfiConverterSequenceTypeRecentError.lift(
+    uniffiRustCall() { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_recent_errors(
     
         _status)
 }
@@ -2810,22 +15117,25 @@ public object FfiConverterMapStringString: FfiConverterRustBuffer<Map<kotlin.Str
     
 
         /**
-         * Refresh the WARP identity from scratch and persist it.
-         *
-         * Steps, in order:
-         * 1. Tear down the live tunnel and clear the last error (under the lock).
-         * 2. Best-effort delete the old Cloudflare device (outside the lock).
-         * 3. Generate a new keypair, register, fetch config, enable WARP, and write
-         * the new `warp_config.json` (outside the lock).
-         * 4. Swap in the new configuration and force the tunnel to rebuild on next use.
-         *
-         * The new tunnel is *not* eagerly reconnected: the caller can immediately
-         * follow up with [`crate::proxy_diagnostics`] to rebuild and verify it.
+         * [`ProxyClient::set_redirect_policy`] on the default proxy.
          */
-    @Throws(ProxyException::class) fun `proxyResetIdentity`(): WarpStoredConfig {
+    @Throws(ProxyException::class) fun `proxySetRedirectPolicy`(`policy`: RedirectPolicy)
+        = 
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_set_redirect_policy(
+    
+        FfiConverterTypeRedirectPolicy.lower(`policy`),_status)
+}
+    
+    
+
+        /**
+         * [`ProxyClient::refresh_warp_config`] on the default proxy.
+         */
+    @Throws(ProxyException::class) fun `proxyRefreshWarpConfig`(): WarpStoredConfig {
             return FfiConverterTypeWarpStoredConfig.lift(
     uniffiRustCallWithError(ProxyException) { _status ->
-    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_reset_identity(
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_refresh_warp_config(
     
         _status)
 }
@@ -2834,23 +15144,58 @@ public object FfiConverterMapStringString: FfiConverterRustBuffer<Map<kotlin.Str
     
 
         /**
-         * Read the persisted WARP identity and tunnel configuration.
-         *
-         * Never touches the network: this works whether or not the tunnel has
-         * connected, making it the primary tool for diagnosing a tunnel that refuses
-         * to come up.
+         * [`ProxyClient::set_warp_refresh`] on the default proxy.
          */
-    @Throws(ProxyException::class) fun `proxyStoredConfig`(): WarpStoredConfig {
-            return FfiConverterTypeWarpStoredConfig.lift(
+    @Throws(ProxyException::class) fun `proxySetWarpRefresh`(`refresh`: WarpRefresh)
+        = 
     uniffiRustCallWithError(ProxyException) { _status ->
-    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_stored_config(
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_set_warp_refresh(
     
-        _status)
+        FfiConverterTypeWarpRefresh.lower(`refresh`),_status)
+}
+    
+    
+
+        /**
+         * [`ProxyClient::fetch_resource`] on the default proxy.
+         */
+    @Throws(ProxyException::class) fun `proxyFetchResource`(`url`: kotlin.String, `options`: ResourceOptions): ResourceResponse {
+            return FfiConverterTypeResourceResponse.lift(
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_fetch_resource(
+    
+        FfiConverterString.lower(`url`),FfiConverterTypeResourceOptions.lower(`options`),_status)
 }
     )
     }
     
 
+        /**
+         * [`ProxyClient::set_retry_policy`] on the default proxy.
+         */
+    @Throws(ProxyException::class) fun `proxySetRetryPolicy`(`policy`: RetryPolicy)
+        = 
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_set_retry_policy(
+    
+        FfiConverterTypeRetryPolicy.lower(`policy`),_status)
+}
+    
+    
+
+        /**
+         * [`ProxyClient::set_https_mode`] on the default proxy.
+         */
+    @Throws(ProxyException::class) fun `proxySetHttpsMode`(`mode`: HttpsMode)
+        = 
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_set_https_mode(
+    
+        FfiConverterTypeHttpsMode.lower(`mode`),_status)
+}
+    
+    
+
         /**
          * Probe the provisioning TLS path and report whether the platform verifier was
          * (incorrectly) reached.
@@ -2875,4 +15220,154 @@ public object FfiConverterMapStringString: FfiConverterRustBuffer<Map<kotlin.Str
     }
     
 
+        /**
+         * [`ProxyClient::fetch_best_source`] through the default proxy.
+         */
+    @Throws(ProxyException::class)
+    @Suppress("ASSIGNED_BUT_NEVER_ACCESSED_VARIABLE")
+     suspend fun `proxyFetchBestSource`(`sources`: List<SrcsetCandidate>, `targetWidth`: kotlin.UInt, `pixelDensity`: kotlin.Float = 1.0f, `probeSizes`: kotlin.Boolean = false) : ImageResponse {
+        return uniffiRustCallAsync(
+        UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_fetch_best_source(FfiConverterSequenceTypeSrcsetCandidate.lower(`sources`),FfiConverterUInt.lower(`targetWidth`),FfiConverterFloat.lower(`pixelDensity`),FfiConverterBoolean.lower(`probeSizes`),),
+        { future, callback, continuation -> UniffiLib.ffi_letterbox_proxy_rust_future_poll_rust_buffer(future, callback, continuation) },
+        { future, continuation -> UniffiLib.ffi_letterbox_proxy_rust_future_complete_rust_buffer(future, continuation) },
+        { future -> UniffiLib.ffi_letterbox_proxy_rust_future_free_rust_buffer(future) },
+        // lift function
+        { FfiConverterTypeImageResponse.lift(it) },
+        // Error FFI converter
+        ProxyException.ErrorHandler,
+    )
+    }
+
+        /**
+         * [`ProxyClient::open_stream`] through the default proxy.
+         */
+    @Throws(ProxyException::class) fun `proxyOpenStream`(`host`: kotlin.String, `port`: kotlin.UShort): ProxyStream {
+            return FfiConverterTypeProxyStream.lift(
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_open_stream(
+    
+        FfiConverterString.lower(`host`),FfiConverterUShort.lower(`port`),_status)
+}
+    )
+    }
+    
+
+        /**
+         * [`ProxyClient::set_svg_sanitization`] on the default proxy.
+         */
+    @Throws(ProxyException::class) fun `proxySetSvgSanitization`(`enabled`: kotlin.Boolean)
+        = 
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_set_svg_sanitization(
+    
+        FfiConverterBoolean.lower(`enabled`),_status)
+}
+    
+    
+
+        /**
+         * [`ProxyClient::set_rate_limit`] on the default proxy.
+         */
+    @Throws(ProxyException::class) fun `proxySetRateLimit`(`limit`: RateLimit)
+        = 
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_set_rate_limit(
+    
+        FfiConverterTypeRateLimit.lower(`limit`),_status)
+}
+    
+    
+
+        /**
+         * [`ProxyClient::set_timeout_budget`] on the default proxy.
+         */
+    @Throws(ProxyException::class) fun `proxySetTimeoutBudget`(`budget`: TimeoutBudget)
+        = 
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_set_timeout_budget(
+    
+        FfiConverterTypeTimeoutBudget.lower(`budget`),_status)
+}
+    
+    
+
+        /**
+         * [`ProxyClient::set_block_tracking_pixels`] on the default proxy.
+         */
+    @Throws(ProxyException::class) fun `proxySetBlockTrackingPixels`(`enabled`: kotlin.Boolean)
+        = 
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_set_block_tracking_pixels(
+    
+        FfiConverterBoolean.lower(`enabled`),_status)
+}
+    
+    
+
+        /**
+         * [`ProxyClient::set_tracker_domains`] on the default proxy.
+         */
+    @Throws(ProxyException::class) fun `proxySetTrackerDomains`(`domains`: List<kotlin.String>)
+        = 
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_set_tracker_domains(
+    
+        FfiConverterSequenceString.lower(`domains`),_status)
+}
+    
+    
+
+        /**
+         * [`ProxyClient::fetch_image_transformed`] on the default proxy.
+         */
+    @Throws(ProxyException::class) fun `proxyFetchImageTransformed`(`url`: kotlin.String, `headers`: Map<kotlin.String, kotlin.String>?, `transform`: ImageTransform): ImageResponse {
+            return FfiConverterTypeImageResponse.lift(
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_fetch_image_transformed(
+    
+        FfiConverterString.lower(`url`),FfiConverterOptionalMapStringString.lower(`headers`),FfiConverterTypeImageTransform.lower(`transform`),_status)
+}
+    )
+    }
+    
+
+        /**
+         * [`ProxyClient::set_upstream_proxy`] on the default proxy.
+         */
+    @Throws(ProxyException::class) fun `proxySetUpstreamProxy`(`proxy`: UpstreamProxy?)
+        = 
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_set_upstream_proxy(
+    
+        FfiConverterOptionalTypeUpstreamProxy.lower(`proxy`),_status)
+}
+    
+    
+
+        /**
+         * [`ProxyClient::set_warm_up_hosts`] on the default proxy.
+         */
+    @Throws(ProxyException::class) fun `proxySetWarmUpHosts`(`hosts`: List<kotlin.String>)
+        = 
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_set_warm_up_hosts(
+    
+        FfiConverterSequenceString.lower(`hosts`),_status)
+}
+    
+    
+
+        /**
+         * [`ProxyClient::warm_up`] on the default proxy.
+         */
+    @Throws(ProxyException::class) fun `proxyWarmUp`()
+        = 
+    uniffiRustCallWithError(ProxyException) { _status ->
+    UniffiLib.uniffi_letterbox_proxy_fn_func_proxy_warm_up(
+    
+        _status)
+}
+    
+    
+
 
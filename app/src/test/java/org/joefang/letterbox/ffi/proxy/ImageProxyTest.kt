@@ -1,5 +1,6 @@
 package org.joefang.letterbox.ffi.proxy
 
+import kotlinx.coroutines.runBlocking
 import org.junit.Test
 import org.junit.BeforeClass
 import kotlin.test.assertEquals
@@ -121,7 +122,7 @@ class ImageProxyTest {
             proxyInit(tempDir.absolutePath, 100u)
 
             assertFailsWith<ProxyException.InvalidUrl> {
-                proxyFetchImage("not-a-valid-url", null)
+                runBlocking { proxyFetchImage("not-a-valid-url", null) }
             }
         } finally {
             tempDir.deleteRecursively()
@@ -144,7 +145,7 @@ class ImageProxyTest {
             proxyInit(tempDir.absolutePath, 100u)
 
             assertFailsWith<ProxyException.InvalidUrl> {
-                proxyFetchImage("ftp://files.example.com/image.png", null)
+                runBlocking { proxyFetchImage("ftp://files.example.com/image.png", null) }
             }
         } finally {
             tempDir.deleteRecursively()
@@ -167,7 +168,7 @@ class ImageProxyTest {
             proxyInit(tempDir.absolutePath, 100u)
 
             assertFailsWith<ProxyException.InvalidUrl> {
-                proxyFetchImage("file:///etc/passwd", null)
+                runBlocking { proxyFetchImage("file:///etc/passwd", null) }
             }
         } finally {
             tempDir.deleteRecursively()
@@ -189,10 +190,13 @@ class ImageProxyTest {
         try {
             proxyInit(tempDir.absolutePath, 100u)
 
-            val results = proxyFetchImagesBatch(
-                listOf("invalid-url-1", "invalid-url-2"),
-                2u
-            )
+            val results = runBlocking {
+                proxyFetchImagesBatch(
+                    listOf("invalid-url-1", "invalid-url-2"),
+                    2u,
+                    RequestPriority.VISIBLE
+                ).use { it.results() }
+            }
 
             assertEquals(2, results.size)
             assertFalse(results[0].success)
@@ -220,7 +224,10 @@ class ImageProxyTest {
             proxyInit(tempDir.absolutePath, 100u)
 
             val testUrl = "https://example.com/test.png"
-            val results = proxyFetchImagesBatch(listOf(testUrl), 1u)
+            val results = runBlocking {
+                proxyFetchImagesBatch(listOf(testUrl), 1u, RequestPriority.VISIBLE)
+                    .use { it.results() }
+            }
 
             assertEquals(1, results.size)
             // URL should be preserved in the result (even for failures)
@@ -235,6 +242,92 @@ class ImageProxyTest {
         }
     }
 
+    @Test
+    fun `cancelled batch reports every url as cancelled`() {
+        val tempDir = java.io.File.createTempFile("proxy_batch_cancel_test", "").apply {
+            delete()
+            mkdirs()
+        }
+
+        try {
+            ProxyClient(tempDir.absolutePath, 100u).use { client ->
+                val pixel = "data:image/png;base64,iVBORw0KGgo="
+                val batch = client.fetchImagesBatch(listOf(pixel, pixel), 1u, RequestPriority.VISIBLE)
+                val results = batch.use {
+                    it.cancel()
+                    assertTrue(it.isCancelled())
+                    runBlocking { it.results() }
+                }
+
+                assertEquals(2, results.size)
+                results.forEach { result ->
+                    assertFalse(result.success)
+                    assertEquals("Request cancelled", result.error)
+                    assertEquals(ErrorCode.CANCELLED, result.errorCode)
+                    assertFalse(result.retryable)
+                }
+            }
+        } finally {
+            tempDir.deleteRecursively()
+        }
+    }
+
+    @Test
+    fun `paused proxy still serves local images but refuses the network`() {
+        val tempDir = java.io.File.createTempFile("proxy_pause_test", "").apply {
+            delete()
+            mkdirs()
+        }
+
+        try {
+            ProxyClient(tempDir.absolutePath, 100u).use { client ->
+                client.pause()
+                assertTrue(client.isPaused())
+
+                val pixel = runBlocking {
+                    client.fetchImage("data:image/png;base64,iVBORw0KGgo=", null)
+                }
+                assertEquals("image/png", pixel.mimeType)
+                assertFailsWith<ProxyException.Paused> {
+                    runBlocking { client.fetchImage("https://example.com/a.png", null) }
+                }
+
+                client.resume()
+                assertFalse(client.isPaused())
+            }
+        } finally {
+            tempDir.deleteRecursively()
+        }
+    }
+
+    @Test
+    fun `offline queue persists across clients`() {
+        val tempDir = java.io.File.createTempFile("proxy_offline_test", "").apply {
+            delete()
+            mkdirs()
+        }
+
+        try {
+            val url = "https://example.com/offline.png"
+            ProxyClient(tempDir.absolutePath, 100u).use { client ->
+                // Paused, so the queue cannot drain while the test runs.
+                client.pause()
+                assertEquals(1u, client.queueForOffline(listOf(url, url, "not a url")))
+                assertEquals(listOf(url), client.offlineQueue())
+                client.shutdown()
+            }
+            ProxyClient(tempDir.absolutePath, 100u).use { client ->
+                client.pause()
+                assertTrue(client.offlineQueue().contains(url))
+                client.clearOfflineQueue()
+                assertTrue(client.offlineQueue().isEmpty())
+                client.shutdown()
+            }
+        } finally {
+            tempDir.deleteRecursively()
+        }
+    }
+
     @Test
     fun `proxy fetch image with javascript scheme throws exception`() {
         val tempDir = java.io.File.createTempFile("proxy_js_test", "").apply {
@@ -245,9 +338,12 @@ class ImageProxyTest {
         try {
             proxyInit(tempDir.absolutePath, 100u)
 
-            assertFailsWith<ProxyException.InvalidUrl> {
-                proxyFetchImage("javascript:alert('xss')", null)
+            val error = assertFailsWith<ProxyException.InvalidUrl> {
+                runBlocking { proxyFetchImage("javascript:alert('xss')", null) }
             }
+            assertEquals(ErrorCode.INVALID_URL, error.code())
+            assertEquals(ErrorCategory.INPUT, error.category())
+            assertFalse(error.isRetryable())
         } finally {
             tempDir.deleteRecursively()
             try {
@@ -259,7 +355,7 @@ class ImageProxyTest {
     }
 
     @Test
-    fun `proxy fetch image with data scheme throws exception`() {
+    fun `proxy fetch image decodes data scheme locally`() {
         val tempDir = java.io.File.createTempFile("proxy_data_test", "").apply {
             delete()
             mkdirs()
@@ -268,9 +364,15 @@ class ImageProxyTest {
         try {
             proxyInit(tempDir.absolutePath, 100u)
 
-            assertFailsWith<ProxyException.InvalidUrl> {
-                proxyFetchImage("data:image/png;base64,iVBORw0KGgo=", null)
-            }
+            // data: URIs are decoded in-process, without the tunnel
+            val response = runBlocking { proxyFetchImage("data:image/png;base64,iVBORw0KGgo=", null) }
+            assertEquals("image/png", response.mimeType)
+            assertFalse(response.fromCache)
+            assertEquals(8, response.traceId.length)
+
+            val metrics = proxyMetrics()
+            assertTrue(metrics.fetchesSucceeded >= 1uL)
+            assertEquals(metrics.fetchLatency.bucketBoundsMs.size + 1, metrics.fetchLatency.counts.size)
         } finally {
             tempDir.deleteRecursively()
             try {
@@ -294,7 +396,7 @@ class ImageProxyTest {
             // HTTP URLs should be accepted (not InvalidUrl error)
             // They will fail with HttpError or NetworkUnavailable since we don't have network
             try {
-                proxyFetchImage("http://example.com/image.png", null)
+                runBlocking { proxyFetchImage("http://example.com/image.png", null) }
             } catch (e: ProxyException.InvalidUrl) {
                 // This should NOT happen - http:// is a valid scheme
                 throw AssertionError("http:// scheme should be accepted, got InvalidUrl")
@@ -325,7 +427,7 @@ class ImageProxyTest {
             // HTTPS URLs should be accepted (not InvalidUrl error)
             // They will fail with HttpError or NetworkUnavailable since we don't have network
             try {
-                proxyFetchImage("https://example.com/image.png", null)
+                runBlocking { proxyFetchImage("https://example.com/image.png", null) }
             } catch (e: ProxyException.InvalidUrl) {
                 // This should NOT happen - https:// is a valid scheme
                 throw AssertionError("https:// scheme should be accepted, got InvalidUrl")
@@ -358,7 +460,9 @@ class ImageProxyTest {
                 "https://example.com/2.png",
                 "https://example.com/3.png"
             )
-            val results = proxyFetchImagesBatch(urls, 3u)
+            val results = runBlocking {
+                proxyFetchImagesBatch(urls, 3u, RequestPriority.PREFETCH).use { it.results() }
+            }
 
             assertEquals(3, results.size)
             // Each result should have a non-empty URL
@@ -374,4 +478,67 @@ class ImageProxyTest {
             }
         }
     }
+
+    @Test
+    fun `proxy clients are independent of each other and of the default proxy`() {
+        val dirA = java.io.File.createTempFile("proxy_client_a", "").apply {
+            delete()
+            mkdirs()
+        }
+        val dirB = java.io.File.createTempFile("proxy_client_b", "").apply {
+            delete()
+            mkdirs()
+        }
+
+        try {
+            ProxyClient(dirA.absolutePath, 10u).use { a ->
+                ProxyClient(dirB.absolutePath, 10u).use { b ->
+                    assertTrue(a.status().ready)
+                    assertTrue(b.status().ready)
+                    assertFalse(proxyStatus().ready)
+
+                    a.shutdown()
+                    assertFalse(a.status().ready)
+                    assertFailsWith<ProxyException.NotInitialized> {
+                        runBlocking { a.fetchImage("https://example.com/a.png", null) }
+                    }
+                    assertTrue(b.status().ready)
+                }
+            }
+        } finally {
+            dirA.deleteRecursively()
+            dirB.deleteRecursively()
+        }
+    }
+
+    @Test
+    fun `loopback server serves images only with its token`() {
+        val tempDir = java.io.File.createTempFile("proxy_loopback_test", "").apply {
+            delete()
+            mkdirs()
+        }
+
+        try {
+            ProxyClient(tempDir.absolutePath, 10u).use { client ->
+                client.startLoopbackServer().use { server ->
+                    val url = server.imageUrl("data:image/png;base64,iVBORw0KGgo=")
+                    assertTrue(url.startsWith("http://127.0.0.1:${server.port()}/image?"))
+
+                    val ok = java.net.URL(url).openConnection() as java.net.HttpURLConnection
+                    assertEquals(200, ok.responseCode)
+                    assertEquals("image/png", ok.contentType)
+                    ok.disconnect()
+
+                    val forged = url.replace(Regex("token=[0-9a-f]+"), "token=0")
+                    val denied = java.net.URL(forged).openConnection() as java.net.HttpURLConnection
+                    assertEquals(403, denied.responseCode)
+                    denied.disconnect()
+
+                    server.stop()
+                }
+            }
+        } finally {
+            tempDir.deleteRecursively()
+        }
+    }
 }
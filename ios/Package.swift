@@ -0,0 +1,23 @@
+// swift-tools-version:5.9
+//
+// Swift package for the letterbox-proxy crate. Run `build-xcframework.sh`
+// first: it builds the static libraries and generates the Swift bindings this
+// package compiles.
+import PackageDescription
+
+let package = Package(
+    name: "LetterboxProxy",
+    platforms: [.iOS(.v15)],
+    products: [
+        .library(name: "LetterboxProxy", targets: ["LetterboxProxy"]),
+    ],
+    targets: [
+        .binaryTarget(name: "LetterboxProxyFFI", path: "LetterboxProxyFFI.xcframework"),
+        .target(
+            name: "LetterboxProxy",
+            dependencies: ["LetterboxProxyFFI"],
+            // rustls verifies certificates through the system trust store.
+            linkerSettings: [.linkedFramework("Security")]
+        ),
+    ]
+)
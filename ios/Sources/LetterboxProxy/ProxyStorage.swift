@@ -0,0 +1,32 @@
+import Foundation
+
+extension ProxyClient {
+    /// Where the proxy keeps its configuration, WARP identity and disk cache
+    /// on iOS: `Application Support/LetterboxProxy`.
+    ///
+    /// The directory is created on first use and excluded from iCloud and
+    /// device backups, so the WARP private key never leaves the device. Its
+    /// files stay readable after the first unlock, so background fetches work
+    /// while the device is locked.
+    public static func defaultStorageDirectory() throws -> URL {
+        let fileManager = FileManager.default
+        var directory = try fileManager
+            .url(for: .applicationSupportDirectory, in: .userDomainMask, appropriateFor: nil, create: true)
+            .appendingPathComponent("LetterboxProxy", isDirectory: true)
+        try fileManager.createDirectory(
+            at: directory,
+            withIntermediateDirectories: true,
+            attributes: [.protectionKey: FileProtectionType.completeUntilFirstUserAuthentication]
+        )
+        var values = URLResourceValues()
+        values.isExcludedFromBackup = true
+        try directory.setResourceValues(values)
+        return directory
+    }
+
+    /// A proxy stored in ``defaultStorageDirectory()``, with an in-memory
+    /// cache of `maxCacheSize` images.
+    public convenience init(maxCacheSize: UInt32) throws {
+        try self.init(storagePath: ProxyClient.defaultStorageDirectory().path, maxCacheSize: maxCacheSize)
+    }
+}
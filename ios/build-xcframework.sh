@@ -0,0 +1,40 @@
+#!/usr/bin/env bash
+# Build LetterboxProxyFFI.xcframework and generate the Swift bindings for the
+# LetterboxProxy package. Needs macOS with Xcode and the iOS Rust targets:
+#
+#   rustup target add aarch64-apple-ios aarch64-apple-ios-sim x86_64-apple-ios
+set -euo pipefail
+
+ROOT="$(cd "$(dirname "$0")/.." && pwd)"
+IOS="$ROOT/ios"
+BUILD="$ROOT/target/ios"
+LIB=libletterbox_proxy.a
+
+cd "$ROOT"
+for target in aarch64-apple-ios aarch64-apple-ios-sim x86_64-apple-ios; do
+    cargo rustc -p letterbox-proxy --lib --release --target "$target" --crate-type staticlib
+done
+
+# The bindings come from the metadata embedded in a host build of the library.
+cargo build -p letterbox-proxy --lib --release
+rm -rf "$BUILD"
+mkdir -p "$BUILD/headers" "$BUILD/simulator"
+cargo run -q -p letterbox-proxy --bin uniffi-bindgen-proxy -- generate \
+    --library target/release/libletterbox_proxy.dylib \
+    --language swift \
+    --out-dir "$BUILD/bindings" \
+    --config rust/letterbox-proxy/uniffi.toml
+mv "$BUILD/bindings/LetterboxProxy.swift" "$IOS/Sources/LetterboxProxy/"
+mv "$BUILD/bindings/LetterboxProxyFFI.h" "$BUILD/headers/"
+mv "$BUILD/bindings/LetterboxProxyFFI.modulemap" "$BUILD/headers/module.modulemap"
+
+lipo -create \
+    "target/aarch64-apple-ios-sim/release/$LIB" \
+    "target/x86_64-apple-ios/release/$LIB" \
+    -output "$BUILD/simulator/$LIB"
+
+rm -rf "$IOS/LetterboxProxyFFI.xcframework"
+xcodebuild -create-xcframework \
+    -library "target/aarch64-apple-ios/release/$LIB" -headers "$BUILD/headers" \
+    -library "$BUILD/simulator/$LIB" -headers "$BUILD/headers" \
+    -output "$IOS/LetterboxProxyFFI.xcframework"
@@ -0,0 +1,494 @@
+//! Attachments of a parsed message.
+//!
+//! Text and nested-message attachments are decoded at parse time. Binary
+//! attachments of a memory-mapped file stay encoded in the map and are
+//! decoded when asked for: whole, or a window at a time by chunked reads,
+//! which continue from the window they last touched.
+
+use crate::{EmailHandle, ParseError, ParsedMessage};
+use mail_parser::Encoding;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::Write;
+use std::ops::Range;
+use std::path::Path;
+
+/// Encoded bytes decoded at a time by a chunked read.
+const DECODE_WINDOW: usize = 64 * 1024;
+
+/// A window of an encoded attachment: where it starts in the encoded body
+/// and in the decoded content. Chunked reads continue from the window they
+/// last touched instead of decoding the attachment from its start.
+#[derive(Clone, Copy)]
+pub(crate) struct ReadCursor {
+    index: u32,
+    encoded: usize,
+    decoded: u64,
+}
+
+/// Represents an email attachment.
+#[derive(Clone)]
+pub(crate) struct Attachment {
+    pub(crate) name: String,
+    pub(crate) content_type: String,
+    /// Decoded size in bytes
+    pub(crate) size: u64,
+    pub(crate) content: AttachmentContent,
+}
+
+/// An attachment's bytes. Binary parts of a memory-mapped file stay encoded
+/// in the map and are decoded each time they are asked for, so a parsed
+/// message does not hold a second copy of every attachment. Everything else
+/// is kept decoded: a message parsed from bytes would otherwise keep the
+/// whole raw message alive next to its parsed parts, and text and nested
+/// messages have their charset converted at parse time.
+#[derive(Clone)]
+pub(crate) enum AttachmentContent {
+    Decoded(Vec<u8>),
+    Encoded {
+        /// Byte range of the encoded body in the source
+        body: Range<usize>,
+        encoding: Encoding,
+    },
+}
+
+/// Attachment metadata exposed to Kotlin via UniFFI.
+#[derive(Clone, uniffi::Record)]
+pub struct AttachmentInfo {
+    pub name: String,
+    pub content_type: String,
+    pub size: u64,
+}
+
+/// Outcome of saving an attachment to disk.
+#[derive(Clone, uniffi::Record)]
+pub struct SavedAttachment {
+    /// Bytes written to the file
+    pub bytes_written: u64,
+    /// SHA-256 of the written content, lowercase hex
+    pub sha256: String,
+}
+
+impl ParsedMessage {
+    /// The decoded bytes of attachment `index`.
+    pub(crate) fn attachment_content(&self, index: u32) -> Option<Vec<u8>> {
+        match &self.attachments.get(index as usize)?.content {
+            AttachmentContent::Decoded(bytes) => Some(bytes.clone()),
+            AttachmentContent::Encoded { body, encoding } => {
+                decode(self.source.as_ref()?.bytes().get(body.clone())?, *encoding)
+            }
+        }
+    }
+
+    /// Up to `len` bytes of attachment `index`, starting at `offset`. An
+    /// encoded attachment is decoded a window at a time, so no more than a
+    /// window and the chunk are held at once, however large it is.
+    fn attachment_chunk(&mut self, index: u32, offset: u64, len: u32) -> Option<Vec<u8>> {
+        let attachment = self.attachments.get(index as usize)?;
+        let (raw, encoding) = match &attachment.content {
+            AttachmentContent::Decoded(bytes) => return Some(slice_chunk(bytes, offset, len)),
+            AttachmentContent::Encoded { body, encoding } => {
+                (self.source.as_ref()?.bytes().get(body.clone())?, *encoding)
+            }
+        };
+        if encoding == Encoding::None {
+            return Some(slice_chunk(raw, offset, len));
+        }
+
+        let mut cursor = match self.reading {
+            Some(cursor) if cursor.index == index && cursor.decoded <= offset => cursor,
+            _ => ReadCursor {
+                index,
+                encoded: 0,
+                decoded: 0,
+            },
+        };
+        let mut chunk = Vec::new();
+        let mut last = cursor;
+        while cursor.encoded < raw.len() && chunk.len() < len as usize {
+            let end = window_end(raw, cursor.encoded, encoding);
+            let window = decode(&raw[cursor.encoded..end], encoding)?;
+            let next = cursor.decoded + window.len() as u64;
+            if next > offset {
+                // Within the window: `offset` lies in it, or before it.
+                let from = offset.saturating_sub(cursor.decoded) as usize;
+                let wanted = len as usize - chunk.len();
+                chunk.extend(window[from..].iter().take(wanted));
+                last = cursor;
+            }
+            cursor.encoded = end;
+            cursor.decoded = next;
+        }
+        self.reading = Some(last);
+        Some(chunk)
+    }
+}
+
+/// The `raw` body decoded from `encoding`.
+fn decode(raw: &[u8], encoding: Encoding) -> Option<Vec<u8>> {
+    match encoding {
+        Encoding::Base64 => mail_parser::decoders::base64::base64_decode(raw),
+        Encoding::QuotedPrintable => {
+            mail_parser::decoders::quoted_printable::quoted_printable_decode(raw)
+        }
+        Encoding::None => Some(raw.to_vec()),
+    }
+}
+
+/// Where a decoding window of `raw` starting at `start` ends: at least
+/// [`DECODE_WINDOW`] on, at a point both halves decode on their own. That is
+/// after whole groups of four base64 characters, or after a line break of
+/// quoted-printable, whose soft breaks and escapes never span lines.
+fn window_end(raw: &[u8], start: usize, encoding: Encoding) -> usize {
+    let min_end = start.saturating_add(DECODE_WINDOW);
+    if min_end >= raw.len() {
+        return raw.len();
+    }
+    match encoding {
+        Encoding::Base64 => {
+            let mut characters = 0usize;
+            for (i, &byte) in raw.iter().enumerate().skip(start) {
+                if i >= min_end && characters.is_multiple_of(4) {
+                    return i;
+                }
+                if byte.is_ascii_alphanumeric() || matches!(byte, b'+' | b'/' | b'=') {
+                    characters += 1;
+                }
+            }
+            raw.len()
+        }
+        _ => raw[min_end..]
+            .iter()
+            .position(|&byte| byte == b'\n')
+            .map_or(raw.len(), |at| min_end + at + 1),
+    }
+}
+
+/// Up to `len` bytes of `bytes` from `offset`.
+fn slice_chunk(bytes: &[u8], offset: u64, len: u32) -> Vec<u8> {
+    let start = usize::try_from(offset).map_or(bytes.len(), |o| o.min(bytes.len()));
+    let end = start.saturating_add(len as usize).min(bytes.len());
+    bytes[start..end].to_vec()
+}
+
+#[uniffi::export]
+impl EmailHandle {
+    /// Get a list of all attachments with their metadata.
+    pub fn get_attachments(&self) -> Vec<AttachmentInfo> {
+        self.inner
+            .lock()
+            .map(|msg| {
+                msg.attachments
+                    .iter()
+                    .map(|a| AttachmentInfo {
+                        name: a.name.clone(),
+                        content_type: a.content_type.clone(),
+                        size: a.size,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Get the number of attachments.
+    pub fn attachment_count(&self) -> u32 {
+        self.inner
+            .lock()
+            .map(|msg| msg.attachments.len() as u32)
+            .unwrap_or(0)
+    }
+
+    /// Get attachment content by index.
+    /// Note: For large attachments, consider using write_attachment_to_path instead.
+    pub fn get_attachment_content(&self, index: u32) -> Option<Vec<u8>> {
+        self.inner
+            .lock()
+            .ok()
+            .and_then(|msg| msg.attachment_content(index))
+    }
+
+    /// Read up to `len` bytes of attachment `index`, starting at byte `offset`
+    /// of its decoded content, so a large attachment can be streamed in
+    /// pieces. Returns fewer bytes at the end and none past it, or None if
+    /// there is no such attachment.
+    pub fn read_attachment_chunk(&self, index: u32, offset: u64, len: u32) -> Option<Vec<u8>> {
+        self.inner
+            .lock()
+            .ok()
+            .and_then(|mut msg| msg.attachment_chunk(index, offset, len))
+    }
+
+    /// Write an attachment directly to a file path.
+    /// This avoids copying large attachments across the FFI boundary.
+    /// Returns true on success, false if attachment not found.
+    ///
+    /// # Security
+    /// The caller is responsible for validating that `path` is a safe, sandboxed location.
+    /// This function will create parent directories and write to the specified path without
+    /// additional path validation. Use only with paths constructed from trusted sources
+    /// (e.g., application cache directories).
+    pub fn write_attachment_to_path(&self, index: u32, path: String) -> Result<bool, ParseError> {
+        let content = self
+            .inner
+            .lock()
+            .ok()
+            .and_then(|msg| msg.attachment_content(index));
+        match content {
+            Some(bytes) => write_file(&path, &bytes).map(|()| true),
+            None => Ok(false),
+        }
+    }
+
+    /// Decode an attachment and write it to `dest_path` in Rust, returning
+    /// the bytes written and their SHA-256 so the caller can verify the file
+    /// without reading it back. Returns None if the attachment is not found.
+    ///
+    /// # Security
+    /// As for [`Self::write_attachment_to_path`], `dest_path` must come from a
+    /// trusted source.
+    pub fn save_attachment(
+        &self,
+        index: u32,
+        dest_path: String,
+    ) -> Result<Option<SavedAttachment>, ParseError> {
+        let content = self
+            .inner
+            .lock()
+            .ok()
+            .and_then(|msg| msg.attachment_content(index));
+
+        match content {
+            Some(bytes) => {
+                write_file(&dest_path, &bytes)?;
+                Ok(Some(SavedAttachment {
+                    bytes_written: bytes.len() as u64,
+                    sha256: Sha256::digest(&bytes)
+                        .iter()
+                        .map(|b| format!("{b:02x}"))
+                        .collect(),
+                }))
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+/// Write `bytes` to a new file at `path`, creating its parent directories.
+fn write_file(path: &str, bytes: &[u8]) -> Result<(), ParseError> {
+    let io_error = |e: std::io::Error| ParseError::IoError {
+        details: e.to_string(),
+    };
+    let path = Path::new(path);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(io_error)?;
+    }
+    fs::File::create(path)
+        .and_then(|mut file| file.write_all(bytes))
+        .map_err(io_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{EMAIL_WITH_ATTACHMENT, EMAIL_WITH_MULTIPLE_ATTACHMENTS};
+    use crate::{parse_eml, parse_eml_file};
+
+    #[test]
+    fn parses_attachment() {
+        let handle = parse_eml(EMAIL_WITH_ATTACHMENT.as_bytes().to_vec()).expect("should parse");
+        let attachments = handle.get_attachments();
+        assert_eq!(attachments.len(), 1);
+        assert_eq!(attachments[0].name, "test.pdf");
+        assert_eq!(attachments[0].content_type, "application/pdf");
+        assert_eq!(handle.attachment_count(), 1);
+        // Content should be available
+        let content = handle.get_attachment_content(0);
+        assert!(content.is_some());
+    }
+
+    #[test]
+    fn write_attachment_to_path_creates_file() {
+        let handle = parse_eml(EMAIL_WITH_ATTACHMENT.as_bytes().to_vec()).expect("should parse");
+
+        let temp_dir = std::env::temp_dir();
+        let output_path = temp_dir.join("test_attachment.pdf");
+
+        // Write attachment to file
+        let result = handle.write_attachment_to_path(0, output_path.to_str().unwrap().to_string());
+        assert!(result.is_ok());
+        assert!(result.unwrap());
+
+        // Verify file exists and has content
+        assert!(output_path.exists());
+        let written = fs::read(&output_path).expect("read written file");
+        assert!(!written.is_empty());
+
+        // Cleanup
+        let _ = fs::remove_file(output_path);
+    }
+
+    #[test]
+    fn save_attachment_reports_size_and_digest() {
+        let handle = parse_eml(EMAIL_WITH_ATTACHMENT.as_bytes().to_vec()).expect("should parse");
+        let output_path = std::env::temp_dir().join("saved_attachment.pdf");
+
+        let saved = handle
+            .save_attachment(0, output_path.to_str().unwrap().to_string())
+            .expect("should save")
+            .expect("attachment exists");
+        let written = fs::read(&output_path).expect("read written file");
+        assert_eq!(saved.bytes_written, written.len() as u64);
+        // SHA-256 of "Hello World!"
+        assert_eq!(
+            saved.sha256,
+            "7f83b1657ff1fc53b92dc18148a1d65dfc2d4b1fa3d677284addd200126d9069"
+        );
+        let _ = fs::remove_file(output_path);
+
+        let missing = handle.save_attachment(5, "/nonexistent/x".to_string());
+        assert!(matches!(missing, Ok(None)));
+    }
+
+    #[test]
+    fn parses_multiple_attachments() {
+        let handle =
+            parse_eml(EMAIL_WITH_MULTIPLE_ATTACHMENTS.as_bytes().to_vec()).expect("should parse");
+        let attachments = handle.get_attachments();
+        assert!(attachments.len() >= 2);
+        assert_eq!(handle.attachment_count() as usize, attachments.len());
+    }
+
+    #[test]
+    fn binary_attachments_are_decoded_on_demand() {
+        let email = "Subject: Files\r\n\
+                     Content-Type: multipart/mixed; boundary=b\r\n\r\n\
+                     --b\r\nContent-Type: text/plain\r\n\r\nBody\r\n\
+                     --b\r\nContent-Type: application/octet-stream\r\n\
+                     Content-Transfer-Encoding: quoted-printable\r\n\
+                     Content-Disposition: attachment; filename=a.bin\r\n\r\n\
+                     ab=3Dc=\r\nd\r\n\r\n\
+                     --b\r\nContent-Type: application/pdf\r\n\
+                     Content-Transfer-Encoding: base64\r\n\
+                     Content-Disposition: attachment; filename=b.pdf\r\n\r\n\
+                     SGVsbG8g\r\nV29ybGQh\r\n\
+                     --b\r\nContent-Type: text/plain\r\n\
+                     Content-Disposition: attachment; filename=c.txt\r\n\r\nNotes\r\n\
+                     --b--\r\n";
+        let path = std::env::temp_dir().join("test_encoded_attachments.eml");
+        fs::write(&path, email).unwrap();
+        let handle = parse_eml_file(path.to_str().unwrap().to_string()).expect("should parse");
+        let _ = fs::remove_file(&path);
+        {
+            let msg = handle.inner.lock().unwrap();
+            assert!(msg.source.is_some());
+            assert!(matches!(
+                msg.attachments[1].content,
+                AttachmentContent::Encoded { .. }
+            ));
+            assert!(matches!(
+                msg.attachments[2].content,
+                AttachmentContent::Decoded(_)
+            ));
+        }
+        assert_eq!(handle.get_attachment_content(0).unwrap(), b"ab=cd\r\n");
+        assert_eq!(handle.get_attachment_content(1).unwrap(), b"Hello World!");
+        assert_eq!(handle.get_attachments()[1].size, 12);
+        assert_eq!(handle.get_attachment_content(2).unwrap(), b"Notes");
+
+        // Parsed from bytes, nothing is left encoded and the source is dropped.
+        let owned = parse_eml(email.as_bytes().to_vec()).expect("should parse");
+        {
+            let msg = owned.inner.lock().unwrap();
+            assert!(msg.source.is_none());
+            assert!(msg
+                .attachments
+                .iter()
+                .all(|a| matches!(a.content, AttachmentContent::Decoded(_))));
+        }
+        assert_eq!(owned.get_attachment_content(1).unwrap(), b"Hello World!");
+    }
+
+    #[test]
+    fn reads_attachments_in_chunks() {
+        let handle = parse_eml(EMAIL_WITH_ATTACHMENT.as_bytes().to_vec()).expect("should parse");
+        let whole = handle.get_attachment_content(0).unwrap();
+
+        let mut streamed = Vec::new();
+        loop {
+            let chunk = handle
+                .read_attachment_chunk(0, streamed.len() as u64, 5)
+                .unwrap();
+            if chunk.is_empty() {
+                break;
+            }
+            assert!(chunk.len() <= 5);
+            streamed.extend(chunk);
+        }
+        assert_eq!(streamed, whole);
+
+        assert_eq!(
+            handle.read_attachment_chunk(0, u64::MAX, 5),
+            Some(Vec::new())
+        );
+        assert_eq!(handle.read_attachment_chunk(1, 0, 5), None);
+    }
+
+    #[test]
+    fn reads_encoded_attachments_a_window_at_a_time() {
+        let data: Vec<u8> = (0..300_000u32)
+            .map(|n| (n.wrapping_mul(2_654_435_761) >> 13) as u8)
+            .collect();
+        let base64 = base64::Engine::encode(&base64::engine::general_purpose::STANDARD, &data);
+        let lines: Vec<&str> = base64
+            .as_bytes()
+            .chunks(76)
+            .map(|line| std::str::from_utf8(line).unwrap())
+            .collect();
+        let text = "A line with = signs and a long tail. ".repeat(3) + "\r\n";
+        let quoted = text
+            .repeat(3_000)
+            .replace('=', "=3D")
+            .replace(". \r", ".=\r\n\r");
+        let email = format!(
+            "Subject: Big\r\n\
+             Content-Type: multipart/mixed; boundary=b\r\n\r\n\
+             --b\r\nContent-Type: text/plain\r\n\r\nBody\r\n\
+             --b\r\nContent-Type: application/octet-stream\r\n\
+             Content-Transfer-Encoding: base64\r\n\
+             Content-Disposition: attachment; filename=a.bin\r\n\r\n{}\r\n\
+             --b\r\nContent-Type: application/octet-stream\r\n\
+             Content-Transfer-Encoding: quoted-printable\r\n\
+             Content-Disposition: attachment; filename=b.bin\r\n\r\n{quoted}\r\n\
+             --b--\r\n",
+            lines.join("\r\n")
+        );
+        let path = std::env::temp_dir().join("test_windowed_attachments.eml");
+        fs::write(&path, email).unwrap();
+        let handle = parse_eml_file(path.to_str().unwrap().to_string()).expect("should parse");
+        let _ = fs::remove_file(&path);
+
+        for index in [0, 1] {
+            let whole = handle.get_attachment_content(index).unwrap();
+            assert!(whole.len() > 2 * DECODE_WINDOW);
+            let mut streamed = Vec::new();
+            loop {
+                let chunk = handle
+                    .read_attachment_chunk(index, streamed.len() as u64, 7_001)
+                    .unwrap();
+                if chunk.is_empty() {
+                    break;
+                }
+                streamed.extend(chunk);
+            }
+            assert_eq!(streamed, whole);
+
+            // Going back restarts from the beginning; skipping ahead works.
+            for offset in [150_000, 10, whole.len() as u64 - 3] {
+                let chunk = handle.read_attachment_chunk(index, offset, 100).unwrap();
+                let start = offset as usize;
+                assert_eq!(chunk, whole[start..(start + 100).min(whole.len())]);
+            }
+        }
+        assert_eq!(handle.get_attachment_content(0).unwrap(), data);
+    }
+}
@@ -0,0 +1,239 @@
+//! Rendering of HTML bodies.
+//!
+//! The handle hands out its HTML body as parsed, or restyled for dark themes,
+//! converted to plain text, sanitized for a WebView, or with its `cid:` and
+//! remote references moved to app schemes. The work is done by [`dark`],
+//! [`text`], [`sanitize`] and [`remote`].
+
+use crate::{dark, remote, sanitize, text};
+use crate::{EmailHandle, RemoteResource, RemoteResourceKind, SanitizeOptions};
+
+#[uniffi::export]
+impl EmailHandle {
+    /// Get the HTML body restyled for dark themes: the page is inverted with
+    /// hues kept, and images inverted back. Bodies with a dark background or
+    /// their own dark color scheme are returned unchanged.
+    pub fn body_html_dark_mode(&self) -> Option<String> {
+        self.inner
+            .lock()
+            .ok()
+            .and_then(|msg| msg.body_html.as_deref().map(dark::dark_mode_html))
+    }
+
+    /// Get the HTML body converted to readable plain text: blocks and lists
+    /// kept, quotes prefixed with `>`, links as numbered footnotes. For
+    /// notifications, snippets and reply quoting when there is no text part.
+    pub fn body_text_derived(&self) -> Option<String> {
+        self.inner
+            .lock()
+            .ok()
+            .and_then(|msg| msg.body_html.as_deref().map(text::html_to_text))
+    }
+
+    /// Get the HTML body reduced to what is safe to load in a WebView:
+    /// without scripts, event handlers, forms, iframes, CSS `url()` loads or
+    /// `position: fixed` overlays.
+    pub fn sanitized_body_html(&self, options: SanitizeOptions) -> Option<String> {
+        self.inner
+            .lock()
+            .ok()
+            .and_then(|msg| msg.body_html.clone())
+            .map(|html| sanitize::sanitize_html(&html, &options))
+    }
+
+    /// Get the HTML body with each `cid:` reference rewritten to `prefix`
+    /// followed by the reference (e.g. `letterbox-asset://image001`), so the
+    /// WebView can intercept inline parts on one scheme, and the Content-IDs
+    /// that match no inline part.
+    pub fn body_html_with_cid_scheme(&self, prefix: String) -> Option<CidRewrite> {
+        self.inner.lock().ok().and_then(|msg| {
+            msg.body_html.as_deref().map(|html| {
+                let (html, unresolved_cids) =
+                    remote::rewrite_cid_references(html, &prefix, &msg.inline_assets);
+                CidRewrite {
+                    html,
+                    unresolved_cids,
+                }
+            })
+        })
+    }
+
+    /// Get every external URL the HTML body would load (images, srcsets,
+    /// CSS backgrounds and imports, links), once each, in document order.
+    pub fn remote_resources(&self) -> Vec<RemoteResource> {
+        self.inner
+            .lock()
+            .ok()
+            .and_then(|msg| msg.body_html.as_deref().map(remote::remote_resources))
+            .unwrap_or_default()
+    }
+
+    /// Get the HTML body with every external URL replaced by `scheme_prefix`
+    /// followed by the form-urlencoded URL, so the WebView loads nothing
+    /// remote until the app serves those placeholders itself.
+    pub fn rewrite_remote_resources(&self, scheme_prefix: String) -> Option<String> {
+        self.inner.lock().ok().and_then(|msg| {
+            msg.body_html
+                .as_deref()
+                .map(|html| remote::rewrite_remote_resources(html, &scheme_prefix))
+        })
+    }
+}
+
+/// HTML body with its `cid:` references moved to an app scheme.
+#[derive(Clone, Debug, PartialEq, Eq, uniffi::Record)]
+pub struct CidRewrite {
+    pub html: String,
+    /// Content-IDs referenced by the body that no inline part carries
+    pub unresolved_cids: Vec<String>,
+}
+
+/// Convert a plain text body to an HTML document: spacing kept, URLs and
+/// email addresses linked, `>` quotes nested and styled by level, and with
+/// `emphasis`, `*strong*` and `_emphasis_` marked up. Text-only messages get
+/// this (without emphasis) as their `body_html`.
+#[uniffi::export]
+pub fn plain_text_to_html(text: String, emphasis: bool) -> String {
+    text::text_to_html(&text, emphasis)
+}
+
+/// Result of extracting remote image URLs from HTML.
+#[derive(Clone, uniffi::Record)]
+pub struct RemoteImage {
+    /// Original image URL (http://, https:// or protocol-relative)
+    pub url: String,
+    /// Whether this is a tracking pixel (1x1 image)
+    pub is_tracking_pixel: bool,
+}
+
+/// Extract all remote image URLs from HTML content: the `<img>` sources,
+/// `srcset` candidates and posters among [`remote::remote_resources`], once
+/// each and in document order. `cid:` and `data:` URLs are part of the
+/// message and not included.
+#[uniffi::export]
+pub fn extract_remote_images(html: String) -> Vec<RemoteImage> {
+    remote::remote_resources(&html)
+        .into_iter()
+        .filter(|resource| resource.kind == RemoteResourceKind::Image)
+        .map(|resource| RemoteImage {
+            url: resource.url,
+            is_tracking_pixel: resource.is_tracking_pixel,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_eml;
+    use crate::tests::{EMAIL_WITH_INLINE_IMAGE, SIMPLE_EMAIL};
+
+    #[test]
+    fn body_html_with_cid_scheme_points_at_inline_parts() {
+        let handle = parse_eml(EMAIL_WITH_INLINE_IMAGE.as_bytes().to_vec()).expect("should parse");
+
+        let rewrite = handle
+            .body_html_with_cid_scheme("letterbox-asset://".to_string())
+            .expect("has an HTML body");
+        assert!(rewrite.html.contains("src=\"letterbox-asset://image001\""));
+        assert!(rewrite.unresolved_cids.is_empty());
+    }
+
+    #[test]
+    fn body_text_derived_renders_the_html_body() {
+        let email = "Subject: Hi\r\n\
+                     Content-Type: text/html\r\n\r\n\
+                     <p>Hello <a href=\"https://example.com\">there</a></p><ul><li>One</li></ul>";
+        let handle = parse_eml(email.as_bytes().to_vec()).expect("should parse");
+        assert_eq!(
+            handle.body_text_derived().as_deref(),
+            Some("Hello there [1]\n\n- One\n\n[1] https://example.com")
+        );
+    }
+
+    #[test]
+    fn text_only_messages_get_linked_html() {
+        let email = "Subject: Hi\r\n\r\nSee https://example.com\r\n> quoted <b>";
+        let handle = parse_eml(email.as_bytes().to_vec()).expect("should parse");
+        let html = handle.body_html().expect("derived from the text");
+        assert!(html.contains("<a href=\"https://example.com\">https://example.com</a>"));
+        assert!(html.contains("<blockquote"));
+        assert!(html.contains("quoted &lt;b&gt;"));
+        assert_eq!(html, plain_text_to_html(handle.body_text().unwrap(), false));
+    }
+
+    #[test]
+    fn body_html_dark_mode_restyles_light_bodies() {
+        let handle = parse_eml(SIMPLE_EMAIL.as_bytes().to_vec()).expect("should parse");
+        let dark = handle.body_html_dark_mode().expect("has an HTML body");
+        assert!(dark.contains("filter: invert(1) hue-rotate(180deg)"));
+        assert!(dark.contains("Body"));
+    }
+
+    #[test]
+    fn extract_remote_images_lists_images_and_pixels() {
+        let images = extract_remote_images(
+            r#"<img src="https://t.example/open.gif" width="1" height="1">
+            <img src=" HTTPS://cdn.example/a.png" srcset="//cdn.example/b.png 2x">
+            <img src="cid:logo"><video src="https://m.example/v.mp4"></video>
+            <img src="https://t.example/open.gif">"#
+                .to_string(),
+        );
+        let found: Vec<(&str, bool)> = images
+            .iter()
+            .map(|image| (image.url.as_str(), image.is_tracking_pixel))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("https://t.example/open.gif", true),
+                ("HTTPS://cdn.example/a.png", false),
+                ("//cdn.example/b.png", false),
+            ]
+        );
+    }
+
+    #[test]
+    fn sanitized_body_html_strips_scripts() {
+        let email = "Subject: Hi\r\n\
+                     Content-Type: text/html\r\n\r\n\
+                     <p onload=\"x()\">Hello</p><script>x()</script>";
+        let handle = parse_eml(email.as_bytes().to_vec()).expect("should parse");
+        let options = SanitizeOptions {
+            allow_styles: true,
+            allow_remote_images: true,
+        };
+        assert_eq!(
+            handle.sanitized_body_html(options.clone()).as_deref(),
+            Some("<p>Hello</p>")
+        );
+
+        let plain = parse_eml(b"Subject: Hi\r\n\r\nHello".to_vec()).expect("should parse");
+        // The HTML derived from a text body is sanitized too.
+        assert_eq!(
+            plain.sanitized_body_html(options).as_deref(),
+            Some("<div style=\"white-space: pre-wrap; font-family: sans-serif;\">Hello</div>")
+        );
+    }
+
+    #[test]
+    fn rewrites_remote_resources_of_the_body() {
+        let email = "Subject: Hi\r\n\
+                     Content-Type: text/html\r\n\r\n\
+                     <img src=\"https://t.example/a.gif\"><img src=\"cid:logo\">";
+        let handle = parse_eml(email.as_bytes().to_vec()).expect("should parse");
+        assert_eq!(
+            handle.remote_resources(),
+            vec![RemoteResource {
+                url: "https://t.example/a.gif".to_string(),
+                kind: RemoteResourceKind::Image,
+                is_tracking_pixel: false,
+            }]
+        );
+        let rewritten = handle
+            .rewrite_remote_resources("app-remote:".to_string())
+            .expect("has an HTML body");
+        assert!(rewritten.contains("src=\"app-remote:https%3A%2F%2Ft.example%2Fa.gif\""));
+        assert!(rewritten.contains("src=\"cid:logo\""));
+    }
+}
@@ -0,0 +1,134 @@
+//! Header lines: folding, encoded words, address lists and parameters.
+
+use crate::AddressInfo;
+use base64::Engine;
+
+/// Line length headers are folded at when they can be.
+pub(super) const FOLD_AT: usize = 78;
+
+/// Raw bytes per RFC 2047 encoded word: 64 characters encoded, so a header
+/// line starting with one stays within [`FOLD_AT`].
+const WORD_BYTES: usize = 39;
+
+/// A header line, folded at spaces to stay within [`FOLD_AT`] where it can.
+/// Line breaks in `value` become spaces, so no value can start a header or
+/// part of its own.
+pub(super) fn write_header(out: &mut String, name: &str, value: &str) {
+    let mut length = name.len() + 1;
+    out.push_str(name);
+    out.push(':');
+    for word in value.split([' ', '\r', '\n']) {
+        if length + 1 + word.len() > FOLD_AT && length > name.len() + 1 {
+            out.push_str("\r\n");
+            length = 0;
+        }
+        out.push(' ');
+        out.push_str(word);
+        length += 1 + word.len();
+    }
+    out.push_str("\r\n");
+}
+
+/// Whether every line break in `value` is a CRLF followed by whitespace,
+/// which continues the header rather than ending it.
+pub(super) fn is_folded(value: &str) -> bool {
+    value
+        .match_indices('\n')
+        .all(|(at, _)| value[..at].ends_with('\r') && value[at + 1..].starts_with([' ', '\t']))
+        && !value.replace("\r\n", "").contains('\r')
+}
+
+/// Header text with line breaks flattened; as RFC 2047 encoded words if it
+/// is not plain ASCII.
+pub(super) fn encode_text(text: &str) -> String {
+    let text: String = text
+        .chars()
+        .map(|c| if c == '\r' || c == '\n' { ' ' } else { c })
+        .collect();
+    let plain = text.bytes().all(|b| b == b' ' || b.is_ascii_graphic()) && !text.contains("=?");
+    if plain {
+        return text;
+    }
+    let mut words = Vec::new();
+    let mut chunk = String::new();
+    for c in text.chars() {
+        if chunk.len() + c.len_utf8() > WORD_BYTES {
+            words.push(encoded_word(&chunk));
+            chunk.clear();
+        }
+        chunk.push(c);
+    }
+    if !chunk.is_empty() {
+        words.push(encoded_word(&chunk));
+    }
+    words.join(" ")
+}
+
+fn encoded_word(text: &str) -> String {
+    let encoded = base64::engine::general_purpose::STANDARD.encode(text);
+    format!("=?UTF-8?B?{encoded}?=")
+}
+
+/// `name <email>` entries, with the name quoted or encoded as needed.
+pub(super) fn address_list(addresses: &[AddressInfo]) -> String {
+    addresses
+        .iter()
+        .map(|address| {
+            let name = address.name.trim();
+            if name.is_empty() {
+                return address.email.clone();
+            }
+            let atoms = name.bytes().all(|b| {
+                b == b' ' || b.is_ascii_alphanumeric() || b"!#$%&'*+-/=?^_`{|}~".contains(&b)
+            });
+            let name = if atoms && !name.contains("=?") {
+                name.to_string()
+            } else if name.is_ascii() && !name.contains(['\r', '\n']) {
+                format!("\"{}\"", name.replace('\\', "\\\\").replace('"', "\\\""))
+            } else {
+                encode_text(name)
+            };
+            format!("{name} <{}>", address.email)
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// A Content-Type or Content-Disposition parameter, quoted, or in RFC 2231
+/// form if the value is not plain ASCII.
+pub(super) fn parameter(name: &str, value: &str) -> String {
+    if value.bytes().all(|b| b == b' ' || b.is_ascii_graphic()) {
+        let value = value.replace('\\', "\\\\").replace('"', "\\\"");
+        return format!("{name}=\"{value}\"");
+    }
+    let encoded: String = value
+        .bytes()
+        .map(|b| {
+            if b.is_ascii_alphanumeric() || b"!#$&+-.^_`|~".contains(&b) {
+                (b as char).to_string()
+            } else {
+                format!("%{b:02X}")
+            }
+        })
+        .collect();
+    format!("{name}*=utf-8''{encoded}")
+}
+
+/// A message ID in angle brackets.
+pub(super) fn angle_id(id: &str) -> String {
+    let id = id.trim().trim_start_matches('<').trim_end_matches('>');
+    format!("<{id}>")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kept_headers_must_be_folded() {
+        assert!(is_folded("one\r\n two"));
+        assert!(!is_folded("one\r\nBcc: evil@example.com"));
+        assert!(!is_folded("one\n two"));
+        assert!(!is_folded("one\r two\r\n three"));
+    }
+}
@@ -0,0 +1,319 @@
+//! The MIME tree of a draft, written with its bodies encoded.
+
+use super::header::{address_list, angle_id, encode_text, is_folded, parameter, write_header};
+use super::{Draft, Part, TransferEncoding};
+use base64::Engine;
+
+/// Longest line RFC 5322 allows, without CRLF.
+const MAX_LINE: usize = 998;
+
+/// A MIME entity to write.
+enum Entity<'a> {
+    Single {
+        content_type: String,
+        disposition: Option<String>,
+        cid: Option<&'a str>,
+        data: &'a [u8],
+        encoding: TransferEncoding,
+    },
+    Multipart {
+        subtype: &'static str,
+        parts: Vec<Entity<'a>>,
+    },
+}
+
+impl Draft {
+    pub(super) fn to_eml(&self) -> String {
+        let mut out = String::new();
+        let date = self
+            .date_timestamp
+            .and_then(chrono::DateTime::from_timestamp_millis)
+            .or_else(|| (!self.parsed).then(chrono::Utc::now));
+        if let Some(date) = date {
+            write_header(&mut out, "Date", &date.to_rfc2822());
+        }
+        for (name, addresses) in [
+            ("From", &self.from),
+            ("To", &self.to),
+            ("Cc", &self.cc),
+            ("Bcc", &self.bcc),
+            ("Reply-To", &self.reply_to),
+        ] {
+            if !addresses.is_empty() {
+                write_header(&mut out, name, &address_list(addresses));
+            }
+        }
+        if !self.subject.is_empty() {
+            write_header(&mut out, "Subject", &encode_text(&self.subject));
+        }
+        if !self.message_id.trim().is_empty() {
+            write_header(&mut out, "Message-ID", &angle_id(&self.message_id));
+        } else if !self.parsed {
+            let domain = self
+                .from
+                .first()
+                .and_then(|from| from.email.rsplit_once('@'))
+                .map_or("letterbox.local", |(_, domain)| domain);
+            let message_id = format!("<{:032x}@{domain}>", rand::random::<u128>());
+            write_header(&mut out, "Message-ID", &message_id);
+        }
+        let in_reply_to: Vec<String> = self
+            .in_reply_to
+            .iter()
+            .filter(|id| !id.trim().is_empty())
+            .map(|id| angle_id(id))
+            .collect();
+        if !in_reply_to.is_empty() {
+            write_header(&mut out, "In-Reply-To", &in_reply_to.join(" "));
+        }
+        if !self.references.is_empty() {
+            let references: Vec<String> = self.references.iter().map(|id| angle_id(id)).collect();
+            write_header(&mut out, "References", &references.join(" "));
+        }
+        for (name, value) in &self.headers {
+            if value.contains('\n') && is_folded(value) {
+                // Kept from a parsed message, folded already.
+                out.push_str(&format!("{name}: {value}\r\n"));
+            } else {
+                write_header(&mut out, name, value);
+            }
+        }
+        write_header(&mut out, "MIME-Version", "1.0");
+        self.entity().write(&mut out);
+        out
+    }
+
+    /// The MIME structure of the bodies and parts.
+    fn entity(&self) -> Entity<'_> {
+        let text_part = self.text.as_deref().map(|body| text_entity("plain", body));
+        let html_part = self.html.as_deref().map(|body| text_entity("html", body));
+
+        let html_part = match html_part {
+            Some(html) if !self.inline.is_empty() => {
+                let mut parts = vec![html];
+                parts.extend(self.inline.iter().map(|part| part.entity("inline")));
+                Some(Entity::Multipart {
+                    subtype: "related",
+                    parts,
+                })
+            }
+            html => html,
+        };
+        let mut body = match (text_part, html_part) {
+            (Some(text), Some(html)) => Entity::Multipart {
+                subtype: "alternative",
+                parts: vec![text, html],
+            },
+            (Some(body), None) | (None, Some(body)) => body,
+            (None, None) => text_entity("plain", ""),
+        };
+
+        // Inline parts without an HTML body to show them are attached.
+        let loose_inline = if self.html.is_none() {
+            &self.inline[..]
+        } else {
+            &[]
+        };
+        if !self.attachments.is_empty() || !loose_inline.is_empty() {
+            let mut parts = vec![body];
+            parts.extend(loose_inline.iter().map(|part| part.entity("inline")));
+            parts.extend(
+                self.attachments
+                    .iter()
+                    .map(|part| part.entity("attachment")),
+            );
+            body = Entity::Multipart {
+                subtype: "mixed",
+                parts,
+            };
+        }
+        body
+    }
+}
+
+/// A text attachment standing in for a removed one.
+pub(super) fn placeholder(name: &str, content_type: &str, size: u64) -> Part {
+    let note = format!(
+        "This attachment was removed from the message.\r\n\
+         Name: {name}\r\n\
+         Type: {content_type}\r\n\
+         Size: {size} bytes\r\n"
+    );
+    Part {
+        content_type: "text/plain; charset=utf-8".to_string(),
+        name: Some(format!("{name}.removed.txt")),
+        cid: None,
+        data: note.into_bytes(),
+        encoding: TransferEncoding::Auto,
+    }
+}
+
+fn text_entity<'a>(subtype: &str, body: &'a str) -> Entity<'a> {
+    Entity::Single {
+        content_type: format!("text/{subtype}; charset=utf-8"),
+        disposition: None,
+        cid: None,
+        data: body.as_bytes(),
+        encoding: TransferEncoding::Auto,
+    }
+}
+
+impl Part {
+    fn entity(&self, disposition: &str) -> Entity<'_> {
+        let mut content_type = self.content_type.trim().to_string();
+        let mut disposition = disposition.to_string();
+        if let Some(name) = &self.name {
+            content_type.push_str(&format!("; {}", parameter("name", name)));
+            disposition.push_str(&format!("; {}", parameter("filename", name)));
+        }
+        Entity::Single {
+            content_type,
+            disposition: Some(disposition),
+            cid: self.cid.as_deref(),
+            data: &self.data,
+            encoding: self.encoding,
+        }
+    }
+}
+
+impl Entity<'_> {
+    fn write(&self, out: &mut String) {
+        match self {
+            Entity::Single {
+                content_type,
+                disposition,
+                cid,
+                data,
+                encoding,
+            } => {
+                let is_text = content_type.to_ascii_lowercase().starts_with("text/");
+                let encoding = resolve_encoding(*encoding, data, is_text);
+                write_header(out, "Content-Type", content_type);
+                let name = match encoding {
+                    TransferEncoding::QuotedPrintable => "quoted-printable",
+                    TransferEncoding::Base64 => "base64",
+                    _ => "7bit",
+                };
+                write_header(out, "Content-Transfer-Encoding", name);
+                if let Some(cid) = cid {
+                    write_header(out, "Content-ID", &format!("<{cid}>"));
+                }
+                if let Some(disposition) = disposition {
+                    write_header(out, "Content-Disposition", disposition);
+                }
+                out.push_str("\r\n");
+                match encoding {
+                    TransferEncoding::QuotedPrintable => out.push_str(&quoted_printable(data)),
+                    TransferEncoding::Base64 => out.push_str(&base64_lines(data)),
+                    // Resolved to 7bit only for ASCII.
+                    _ => out.push_str(&crlf_lines(&String::from_utf8_lossy(data))),
+                }
+            }
+            Entity::Multipart { subtype, parts } => {
+                let boundary = format!("=_letterbox_{:032x}", rand::random::<u128>());
+                write_header(
+                    out,
+                    "Content-Type",
+                    &format!("multipart/{subtype}; boundary=\"{boundary}\""),
+                );
+                out.push_str("\r\n");
+                // The CRLF before a delimiter belongs to the delimiter, so
+                // bodies keep their own last line break (or lack of one).
+                for (i, part) in parts.iter().enumerate() {
+                    let delimiter_break = if i == 0 { "" } else { "\r\n" };
+                    out.push_str(&format!("{delimiter_break}--{boundary}\r\n"));
+                    part.write(out);
+                }
+                out.push_str(&format!("\r\n--{boundary}--\r\n"));
+            }
+        }
+    }
+}
+
+/// The encoding `data` is written in when `encoding` is asked for.
+fn resolve_encoding(encoding: TransferEncoding, data: &[u8], is_text: bool) -> TransferEncoding {
+    let seven_bit = data.is_ascii()
+        && !data.contains(&0)
+        && data
+            .split(|&b| b == b'\n')
+            .all(|line| line.len() <= MAX_LINE);
+    match encoding {
+        TransferEncoding::QuotedPrintable | TransferEncoding::Base64 => encoding,
+        _ if seven_bit && (is_text || encoding == TransferEncoding::SevenBit) => {
+            TransferEncoding::SevenBit
+        }
+        _ if is_text => TransferEncoding::QuotedPrintable,
+        _ => TransferEncoding::Base64,
+    }
+}
+
+/// `text` with every line ending in CRLF.
+fn crlf_lines(text: &str) -> String {
+    let mut lines: Vec<&str> = text.lines().collect();
+    if text.ends_with('\n') {
+        lines.push("");
+    }
+    lines.join("\r\n")
+}
+
+/// `data` in base64, in lines of 76.
+fn base64_lines(data: &[u8]) -> String {
+    let encoded = base64::engine::general_purpose::STANDARD.encode(data);
+    encoded
+        .as_bytes()
+        .chunks(76)
+        // Base64 is ASCII.
+        .map(|line| std::str::from_utf8(line).unwrap_or_default())
+        .collect::<Vec<_>>()
+        .join("\r\n")
+}
+
+/// `data` as quoted-printable text: line breaks stay line breaks (CRLF),
+/// lines are soft-broken within 76 characters.
+fn quoted_printable(data: &[u8]) -> String {
+    let mut out = String::with_capacity(data.len() * 3 / 2);
+    for (n, line) in data.split(|&b| b == b'\n').enumerate() {
+        if n > 0 {
+            out.push_str("\r\n");
+        }
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        let mut length = 0;
+        for (i, &byte) in line.iter().enumerate() {
+            let last = i + 1 == line.len();
+            let literal = matches!(byte, b'!'..=b'<' | b'>'..=b'~')
+                || (matches!(byte, b' ' | b'\t') && !last);
+            let piece = if literal {
+                (byte as char).to_string()
+            } else {
+                format!("={byte:02X}")
+            };
+            // Leave room for the soft break's `=`.
+            if length + piece.len() > 75 {
+                out.push_str("=\r\n");
+                length = 0;
+            }
+            out.push_str(&piece);
+            length += piece.len();
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quoted_printable_soft_breaks_and_trailing_space() {
+        let line = "a".repeat(80);
+        let encoded = quoted_printable(format!("{line}\ntrailing \n").as_bytes());
+        assert_eq!(
+            encoded,
+            format!(
+                "{}=\r\n{}\r\ntrailing=20\r\n",
+                "a".repeat(75),
+                "a".repeat(5)
+            )
+        );
+    }
+}
@@ -0,0 +1,409 @@
+//! Composing messages.
+//!
+//! [`MessageBuilder`] collects the headers, bodies and parts of a message and
+//! serializes it to EML, so the app can compose and reply without a MIME
+//! library of its own. The MIME structure follows from what was added: a text
+//! and an HTML body go in `multipart/alternative`, inline parts join the HTML
+//! in `multipart/related`, and attachments wrap it all in `multipart/mixed`.
+//!
+//! Output is 7-bit: non-ASCII header text is written as RFC 2047 encoded
+//! words, non-ASCII file names as RFC 2231 parameters, and bodies in 7bit,
+//! quoted-printable or base64 (chosen per part, or by the caller for
+//! inline parts and attachments). Boundaries start with `=_`, which neither
+//! quoted-printable nor base64 can produce.
+//!
+//! [`mime`] writes the MIME tree and encodes bodies; [`header`] folds and
+//! encodes header lines; [`parsed`] starts a builder from a parsed message.
+
+use crate::{AddressInfo, ParseError};
+use std::sync::{Arc, Mutex};
+
+mod header;
+mod mime;
+mod parsed;
+
+use header::encode_text;
+use mime::placeholder;
+
+/// Content-Transfer-Encoding of a part.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, uniffi::Enum)]
+pub enum TransferEncoding {
+    /// 7bit for text with short ASCII lines, quoted-printable for other
+    /// text, base64 for anything else
+    Auto,
+    /// As is; used only when the data is ASCII with short lines, otherwise
+    /// as [`TransferEncoding::Auto`]
+    SevenBit,
+    QuotedPrintable,
+    Base64,
+}
+
+/// Headers the builder writes itself from the parts.
+const MIME_HEADERS: [&str; 2] = ["mime-version", "content-"];
+
+/// Headers the builder writes itself from its fields.
+const FIELD_HEADERS: [&str; 10] = [
+    "date",
+    "from",
+    "to",
+    "cc",
+    "bcc",
+    "reply-to",
+    "subject",
+    "message-id",
+    "in-reply-to",
+    "references",
+];
+
+/// An inline part or attachment.
+struct Part {
+    content_type: String,
+    /// File name of an attachment
+    name: Option<String>,
+    /// Content-ID of an inline part, without angle brackets
+    cid: Option<String>,
+    data: Vec<u8>,
+    encoding: TransferEncoding,
+}
+
+#[derive(Default)]
+struct Draft {
+    from: Vec<AddressInfo>,
+    to: Vec<AddressInfo>,
+    cc: Vec<AddressInfo>,
+    bcc: Vec<AddressInfo>,
+    reply_to: Vec<AddressInfo>,
+    subject: String,
+    message_id: String,
+    in_reply_to: Vec<String>,
+    references: Vec<String>,
+    date_timestamp: Option<i64>,
+    /// Built from a parsed message: a Date or Message-ID it lacked stays
+    /// absent instead of being filled in
+    parsed: bool,
+    /// Other headers, with values ready to write
+    headers: Vec<(String, String)>,
+    text: Option<String>,
+    html: Option<String>,
+    inline: Vec<Part>,
+    attachments: Vec<Part>,
+}
+
+/// Builds a message and serializes it to EML.
+#[derive(Default, uniffi::Object)]
+pub struct MessageBuilder {
+    draft: Mutex<Draft>,
+}
+
+impl MessageBuilder {
+    fn edit(&self, edit: impl FnOnce(&mut Draft)) {
+        if let Ok(mut draft) = self.draft.lock() {
+            edit(&mut draft);
+        }
+    }
+}
+
+#[uniffi::export]
+impl MessageBuilder {
+    #[uniffi::constructor]
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self::default())
+    }
+
+    pub fn set_from(&self, address: AddressInfo) {
+        self.edit(|draft| draft.from = vec![address]);
+    }
+
+    pub fn set_to(&self, addresses: Vec<AddressInfo>) {
+        self.edit(|draft| draft.to = addresses);
+    }
+
+    pub fn set_cc(&self, addresses: Vec<AddressInfo>) {
+        self.edit(|draft| draft.cc = addresses);
+    }
+
+    /// Bcc is written into the message, as for a draft; leave it out of a
+    /// copy that is sent.
+    pub fn set_bcc(&self, addresses: Vec<AddressInfo>) {
+        self.edit(|draft| draft.bcc = addresses);
+    }
+
+    pub fn set_reply_to(&self, addresses: Vec<AddressInfo>) {
+        self.edit(|draft| draft.reply_to = addresses);
+    }
+
+    pub fn set_subject(&self, subject: String) {
+        self.edit(|draft| draft.subject = subject);
+    }
+
+    /// Message-ID, with or without angle brackets; one is generated if unset.
+    pub fn set_message_id(&self, message_id: String) {
+        self.edit(|draft| draft.message_id = message_id);
+    }
+
+    /// Message ID this message replies to, with or without angle brackets.
+    pub fn set_in_reply_to(&self, message_id: String) {
+        self.edit(|draft| draft.in_reply_to = vec![message_id]);
+    }
+
+    /// Message IDs of the thread, oldest first.
+    pub fn set_references(&self, message_ids: Vec<String>) {
+        self.edit(|draft| draft.references = message_ids);
+    }
+
+    /// Date in milliseconds since Unix epoch; the time of serialization if
+    /// unset.
+    pub fn set_date_timestamp(&self, timestamp_ms: i64) {
+        self.edit(|draft| draft.date_timestamp = Some(timestamp_ms));
+    }
+
+    /// Add a header of the caller's, such as `X-Mailer`. MIME headers and
+    /// those set through the builder's fields are refused: the builder
+    /// writes them itself.
+    pub fn add_header(&self, name: String, value: String) -> Result<(), ParseError> {
+        let lower = name.to_ascii_lowercase();
+        let valid = !name.is_empty()
+            && name.bytes().all(|b| b.is_ascii_graphic() && b != b':')
+            && !MIME_HEADERS.iter().any(|mime| lower.starts_with(mime))
+            && !FIELD_HEADERS.contains(&lower.as_str());
+        if !valid {
+            return Err(ParseError::Invalid);
+        }
+        self.edit(|draft| draft.headers.push((name, encode_text(&value))));
+        Ok(())
+    }
+
+    pub fn set_text_body(&self, text: String) {
+        self.edit(|draft| draft.text = Some(text));
+    }
+
+    pub fn set_html_body(&self, html: String) {
+        self.edit(|draft| draft.html = Some(html));
+    }
+
+    /// Add a part the HTML body shows through `cid:` URLs.
+    pub fn add_inline(
+        &self,
+        cid: String,
+        content_type: String,
+        data: Vec<u8>,
+        encoding: TransferEncoding,
+    ) {
+        let cid = cid.trim().trim_start_matches('<').trim_end_matches('>');
+        let part = Part {
+            content_type,
+            name: None,
+            cid: Some(cid.to_string()),
+            data,
+            encoding,
+        };
+        self.edit(|draft| draft.inline.push(part));
+    }
+
+    pub fn add_attachment(
+        &self,
+        name: String,
+        content_type: String,
+        data: Vec<u8>,
+        encoding: TransferEncoding,
+    ) {
+        let part = Part {
+            content_type,
+            name: Some(name),
+            cid: None,
+            data,
+            encoding,
+        };
+        self.edit(|draft| draft.attachments.push(part));
+    }
+
+    /// Serialize the message to EML bytes.
+    pub fn to_eml_bytes(&self) -> Vec<u8> {
+        self.draft
+            .lock()
+            .map(|draft| draft.to_eml().into_bytes())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::header::FOLD_AT;
+    use super::*;
+    use crate::parse_eml;
+
+    fn address(name: &str, email: &str) -> AddressInfo {
+        AddressInfo {
+            email: email.to_string(),
+            name: name.to_string(),
+        }
+    }
+
+    #[test]
+    fn writes_a_plain_message() {
+        let builder = MessageBuilder::new();
+        builder.set_from(address("Ann", "ann@example.com"));
+        builder.set_to(vec![address("", "bob@example.com")]);
+        builder.set_subject("Lunch".to_string());
+        builder.set_message_id("id1@example.com".to_string());
+        builder.set_in_reply_to("id0@example.com".to_string());
+        builder.set_references(vec!["<id0@example.com>".to_string()]);
+        builder.set_date_timestamp(1_700_000_000_000);
+        builder.set_text_body("Noon?\nSee you".to_string());
+
+        let eml = String::from_utf8(builder.to_eml_bytes()).unwrap();
+        assert!(eml.starts_with("Date: Tue, 14 Nov 2023 22:13:20 +0000\r\n"));
+        for header in [
+            "From: Ann <ann@example.com>\r\n",
+            "To: bob@example.com\r\n",
+            "Message-ID: <id1@example.com>\r\n",
+            "In-Reply-To: <id0@example.com>\r\n",
+            "References: <id0@example.com>\r\n",
+            "Content-Type: text/plain; charset=utf-8\r\n",
+            "Content-Transfer-Encoding: 7bit\r\n",
+        ] {
+            assert!(eml.contains(header), "{header} missing from {eml}");
+        }
+        assert!(eml.ends_with("\r\n\r\nNoon?\r\nSee you"));
+
+        let handle = parse_eml(eml.into_bytes()).unwrap();
+        assert_eq!(handle.subject(), "Lunch");
+        assert_eq!(handle.in_reply_to(), "id0@example.com");
+        assert_eq!(handle.body_text().as_deref(), Some("Noon?\r\nSee you"));
+    }
+
+    #[test]
+    fn nests_bodies_inline_parts_and_attachments() {
+        let builder = MessageBuilder::new();
+        builder.set_from(address("Zoë Ünal", "zoe@example.com"));
+        builder.set_cc(vec![
+            address("Smith, John", "john@example.com"),
+            address("Bo", "bo@example.com"),
+        ]);
+        builder.set_subject(
+            "Grüße aus Köln – a long subject that has to be encoded in more than one word"
+                .to_string(),
+        );
+        builder.set_text_body("Hallo Welt ☀".to_string());
+        builder.set_html_body("<p>Hallo <img src=\"cid:sun@example.com\"></p>".to_string());
+        builder.add_inline(
+            "<sun@example.com>".to_string(),
+            "image/png".to_string(),
+            vec![0x89, b'P', b'N', b'G', 0, 1, 2],
+            TransferEncoding::Auto,
+        );
+        builder.add_attachment(
+            "Bericht ü.txt".to_string(),
+            "text/plain".to_string(),
+            "line one\nline = two\n".as_bytes().to_vec(),
+            TransferEncoding::QuotedPrintable,
+        );
+        builder.add_attachment(
+            "data.bin".to_string(),
+            "application/octet-stream".to_string(),
+            vec![0xff; 100],
+            TransferEncoding::SevenBit,
+        );
+
+        let eml = builder.to_eml_bytes();
+        assert!(eml.is_ascii());
+        let text = String::from_utf8(eml.clone()).unwrap();
+        assert!(text.contains("Cc: \"Smith, John\" <john@example.com>, Bo <bo@example.com>\r\n"));
+        assert!(text.contains("filename*=utf-8''Bericht%20%C3%BC.txt"));
+        assert!(text.contains("line =3D two"));
+        for line in text.lines() {
+            assert!(line.len() <= FOLD_AT, "{line}");
+        }
+
+        let handle = parse_eml(eml).unwrap();
+        assert_eq!(
+            handle.subject(),
+            "Grüße aus Köln – a long subject that has to be encoded in more than one word"
+        );
+        assert_eq!(handle.from(), "Zoë Ünal <zoe@example.com>");
+        assert_eq!(handle.body_text().as_deref(), Some("Hallo Welt ☀"));
+        assert!(handle.body_html().unwrap().contains("cid:sun@example.com"));
+        assert_eq!(
+            handle.get_resource("sun@example.com".to_string()),
+            Some(vec![0x89, b'P', b'N', b'G', 0, 1, 2])
+        );
+        let attachments = handle.get_attachments();
+        assert_eq!(attachments.len(), 2);
+        assert_eq!(attachments[0].name, "Bericht ü.txt");
+        assert_eq!(
+            handle.get_attachment_content(0),
+            Some(b"line one\r\nline = two\r\n".to_vec())
+        );
+        assert!(text.contains("Content-Transfer-Encoding: base64\r\nContent-Disposition: attachment; filename=\"data.bin\""));
+        assert_eq!(handle.get_attachment_content(1), Some(vec![0xff; 100]));
+    }
+
+    #[test]
+    fn refuses_mime_and_malformed_headers() {
+        let builder = MessageBuilder::new();
+        assert!(builder
+            .add_header("X-Mailer".to_string(), "Letterbox".to_string())
+            .is_ok());
+        for name in [
+            "Content-Type",
+            "content-transfer-encoding",
+            "MIME-Version",
+            "From",
+            "Message-ID",
+            "Bad Name",
+            "Colon:",
+            "",
+        ] {
+            assert_eq!(
+                builder.add_header(name.to_string(), "x".to_string()),
+                Err(ParseError::Invalid)
+            );
+        }
+        builder
+            .add_header(
+                "X-Note".to_string(),
+                "one\r\nBcc: evil@example.com".to_string(),
+            )
+            .unwrap();
+        let eml = String::from_utf8(builder.to_eml_bytes()).unwrap();
+        assert!(eml.contains("X-Mailer: Letterbox\r\n"));
+        assert!(eml.contains("X-Note: one  Bcc: evil@example.com\r\n"));
+    }
+
+    #[test]
+    fn line_breaks_cannot_inject_headers_or_parts() {
+        let builder = MessageBuilder::new();
+        builder.set_from(address("Ann", "ann@example.com>\r\nBcc: evil@example.com"));
+        builder.set_to(vec![address(
+            "Bob\r\nBcc: evil@example.com",
+            "bob@example.com",
+        )]);
+        builder.set_message_id("id1@example.com>\r\nBcc: evil@example.com".to_string());
+        builder.set_in_reply_to("id0@example.com\nBcc: evil@example.com".to_string());
+        builder.set_references(vec!["id0@example.com\r\nBcc: evil@example.com".to_string()]);
+        builder.set_text_body("Body".to_string());
+        builder.add_inline(
+            "a@example.com>\r\nBcc: evil@example.com".to_string(),
+            "image/png\r\n\r\n--injected".to_string(),
+            vec![1],
+            TransferEncoding::Auto,
+        );
+        builder.add_attachment(
+            "x.txt\r\nBcc: evil@example.com".to_string(),
+            "text/plain\r\nBcc: evil@example.com".to_string(),
+            b"x".to_vec(),
+            TransferEncoding::Auto,
+        );
+
+        let eml = String::from_utf8(builder.to_eml_bytes()).unwrap();
+        for line in eml.split("\r\n") {
+            assert!(!line.starts_with("Bcc"), "{eml}");
+            assert!(!line.starts_with("--injected"), "{eml}");
+        }
+        assert!(!eml.replace("\r\n", "").contains(['\r', '\n']), "{eml}");
+        let handle = parse_eml(eml.into_bytes()).unwrap();
+        assert!(handle.bcc_addresses().is_empty());
+        assert_eq!(handle.body_text().as_deref(), Some("Body"));
+        assert_eq!(handle.get_attachments().len(), 1);
+    }
+}
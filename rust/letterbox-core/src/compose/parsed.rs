@@ -0,0 +1,265 @@
+//! Builders from parsed messages, to serialize a message again.
+
+use super::{placeholder, Draft, MessageBuilder, Part, TransferEncoding, MIME_HEADERS};
+use crate::{EmailHandle, ParseError, ParsedMessage};
+use std::sync::{Arc, Mutex};
+
+impl MessageBuilder {
+    /// A builder holding `message`'s fields, other headers as written,
+    /// bodies and parts, with the attachments at `stripped` replaced by
+    /// [`placeholder`]s. Fields that did not parse are kept as written, and
+    /// ones the message lacked stay absent. Fails if an attachment kept
+    /// cannot be decoded.
+    pub(crate) fn from_message(
+        message: &ParsedMessage,
+        stripped: &[u32],
+    ) -> Result<Self, ParseError> {
+        // Whether the draft writes field `name` from its parsed value.
+        let rewritten = |name: &str| match name {
+            "date" => message.date_timestamp.is_some(),
+            "from" => !message.from_addresses.is_empty(),
+            "to" => !message.to_addresses.is_empty(),
+            "cc" => !message.cc_addresses.is_empty(),
+            "bcc" => !message.bcc_addresses.is_empty(),
+            "reply-to" => !message.reply_to_addresses.is_empty(),
+            "subject" => message.subject.as_deref().is_some_and(|s| !s.is_empty()),
+            "message-id" => !message.message_id.trim().is_empty(),
+            "in-reply-to" => !message.in_reply_to.is_empty(),
+            "references" => !message.references.is_empty(),
+            _ => false,
+        };
+        let headers = message
+            .headers
+            .iter()
+            .filter(|field| {
+                let name = field.name.to_ascii_lowercase();
+                !rewritten(&name) && !MIME_HEADERS.iter().any(|mime| name.starts_with(mime))
+            })
+            .map(|field| (field.name.clone(), field.value.clone()))
+            .collect();
+        let mut cids: Vec<_> = message.inline_assets.iter().collect();
+        cids.sort_by(|a, b| a.0.cmp(b.0));
+        let inline = cids
+            .into_iter()
+            .map(|(cid, asset)| Part {
+                content_type: asset.content_type.clone(),
+                name: None,
+                cid: Some(cid.clone()),
+                data: asset.content.clone(),
+                encoding: TransferEncoding::Auto,
+            })
+            .collect();
+        let attachments = message
+            .attachments
+            .iter()
+            .enumerate()
+            .map(|(index, attachment)| {
+                if stripped.contains(&(index as u32)) {
+                    return Ok(placeholder(
+                        &attachment.name,
+                        &attachment.content_type,
+                        attachment.size,
+                    ));
+                }
+                Ok(Part {
+                    content_type: attachment.content_type.clone(),
+                    name: Some(attachment.name.clone()),
+                    cid: None,
+                    data: message
+                        .attachment_content(index as u32)
+                        .ok_or(ParseError::Invalid)?,
+                    encoding: TransferEncoding::Auto,
+                })
+            })
+            .collect::<Result<_, ParseError>>()?;
+        let draft = Draft {
+            from: message.from_addresses.clone(),
+            to: message.to_addresses.clone(),
+            cc: message.cc_addresses.clone(),
+            bcc: message.bcc_addresses.clone(),
+            reply_to: message.reply_to_addresses.clone(),
+            subject: message.subject.clone().unwrap_or_default(),
+            message_id: message.message_id.clone(),
+            in_reply_to: message.in_reply_to.clone(),
+            references: message.references.clone(),
+            date_timestamp: message.date_timestamp,
+            parsed: true,
+            headers,
+            text: message
+                .body_text
+                .clone()
+                .filter(|_| !message.body_text_derived),
+            html: message
+                .body_html
+                .clone()
+                .filter(|_| !message.body_html_derived),
+            inline,
+            attachments,
+        };
+        Ok(Self {
+            draft: Mutex::new(draft),
+        })
+    }
+}
+
+#[uniffi::export]
+impl EmailHandle {
+    /// Get a [`MessageBuilder`] holding this message's headers, bodies,
+    /// inline parts and attachments, to edit and serialize again, e.g. to
+    /// duplicate a draft. Headers the builder writes itself (addresses,
+    /// subject, IDs, date, MIME) are taken from the parsed fields, and none
+    /// the message lacked is added; the others are kept as they were. Fails
+    /// if an attachment cannot be decoded.
+    pub fn to_builder(&self) -> Result<Arc<MessageBuilder>, ParseError> {
+        let msg = self.inner.lock().map_err(|_| ParseError::Invalid)?;
+        MessageBuilder::from_message(&msg, &[]).map(Arc::new)
+    }
+
+    /// Serialize the message to EML bytes again, through
+    /// [`Self::to_builder`]. The MIME structure is rebuilt, so the bytes
+    /// differ from the original's while the content is the same.
+    pub fn to_eml_bytes(&self) -> Result<Vec<u8>, ParseError> {
+        Ok(self.to_builder()?.to_eml_bytes())
+    }
+
+    /// Serialize the message like [`Self::to_eml_bytes`], with the
+    /// attachments at `indices` replaced by small text attachments noting
+    /// their name, type and size, to archive a message without its bulk.
+    /// Indices past the last attachment are ignored.
+    pub fn strip_attachments(&self, indices: Vec<u32>) -> Result<Vec<u8>, ParseError> {
+        let msg = self.inner.lock().map_err(|_| ParseError::Invalid)?;
+        Ok(MessageBuilder::from_message(&msg, &indices)?.to_eml_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{
+        EMAIL_WITH_ATTACHMENT, EMAIL_WITH_INLINE_IMAGE, EMAIL_WITH_MULTIPLE_ATTACHMENTS,
+    };
+    use crate::{parse_eml, parse_eml_file, ParseError};
+    use std::fs;
+
+    #[test]
+    fn to_eml_bytes_round_trips_content() {
+        let email = EMAIL_WITH_ATTACHMENT.replace(
+            "Subject: With Attachment\r\n",
+            "Subject: With Attachment\r\nMessage-ID: <a1@example.com>\r\n\
+             List-Id: <news.example.com>\r\nX-Folded: one\r\n two\r\n",
+        );
+        let handle = parse_eml(email.into_bytes()).expect("should parse");
+        let copy = parse_eml(handle.to_eml_bytes().unwrap()).expect("should parse again");
+        assert_eq!(copy.subject(), "With Attachment");
+        assert_eq!(copy.from(), handle.from());
+        assert_eq!(copy.to(), handle.to());
+        assert_eq!(copy.message_id(), handle.message_id());
+        assert_eq!(copy.body_text(), handle.body_text());
+        assert_eq!(copy.body_html(), handle.body_html());
+        assert_eq!(
+            copy.get_header("list-id".to_string()).as_deref(),
+            Some("<news.example.com>")
+        );
+        assert_eq!(
+            copy.get_header("X-Folded".to_string()).as_deref(),
+            Some("one\r\n two")
+        );
+        assert_eq!(copy.get_attachments()[0].name, "test.pdf");
+        assert_eq!(
+            copy.get_attachment_content(0),
+            Some(b"Hello World!".to_vec())
+        );
+
+        let inline = parse_eml(EMAIL_WITH_INLINE_IMAGE.as_bytes().to_vec()).expect("should parse");
+        let builder = inline.to_builder().unwrap();
+        builder.set_subject("Edited".to_string());
+        let edited = parse_eml(builder.to_eml_bytes()).expect("should parse");
+        assert_eq!(edited.subject(), "Edited");
+        assert_eq!(
+            edited.get_resource("image001".to_string()),
+            inline.get_resource("image001".to_string())
+        );
+    }
+
+    #[test]
+    fn to_eml_bytes_keeps_the_original_shape() {
+        let email = "From: Ann <ann@example.com>, bob@example.com\r\n\
+                     Subject: Html only\r\n\
+                     Content-Type: text/html\r\n\r\n\
+                     <p>Hello</p>";
+        let handle = parse_eml(email.as_bytes().to_vec()).expect("should parse");
+        assert!(handle.body_text().is_some());
+        let eml = String::from_utf8(handle.to_eml_bytes().unwrap()).unwrap();
+        assert!(!eml.contains("text/plain"), "{eml}");
+        assert!(eml.contains("From: Ann <ann@example.com>, bob@example.com\r\n"));
+        let copy = parse_eml(eml.into_bytes()).expect("should parse again");
+        assert_eq!(copy.from_addresses().len(), 2);
+        assert_eq!(copy.body_html(), handle.body_html());
+
+        // An attachment that cannot be decoded is an error, not a loss.
+        let path = std::env::temp_dir().join("test_undecodable_attachment.eml");
+        fs::write(&path, EMAIL_WITH_ATTACHMENT.as_bytes()).unwrap();
+        let mapped = parse_eml_file(path.to_str().unwrap().to_string()).expect("should parse");
+        let _ = fs::remove_file(&path);
+        mapped.inner.lock().unwrap().source = None;
+        assert_eq!(mapped.to_eml_bytes(), Err(ParseError::Invalid));
+        assert!(mapped.strip_attachments(vec![0]).is_ok());
+    }
+
+    #[test]
+    fn to_eml_bytes_adds_no_missing_fields() {
+        let email = "From: ann@example.com\r\n\
+                     In-Reply-To: <a@example.com> <b@example.com>\r\n\r\n\
+                     Hello";
+        let handle = parse_eml(email.as_bytes().to_vec()).expect("should parse");
+        let eml = String::from_utf8(handle.to_eml_bytes().unwrap()).unwrap();
+        for name in ["Subject:", "Date:", "Message-ID:"] {
+            assert!(!eml.contains(name), "{name} in {eml}");
+        }
+        assert!(eml.contains("In-Reply-To: <a@example.com> <b@example.com>\r\n"));
+        let copy = parse_eml(eml.into_bytes()).expect("should parse again");
+        assert_eq!(copy.subject(), "Untitled");
+        assert_eq!(copy.date_timestamp(), 0);
+        assert_eq!(copy.message_id(), "");
+
+        // A Date that does not parse is kept as written.
+        let email = "From: ann@example.com\r\nDate: someday\r\n\r\nHello";
+        let handle = parse_eml(email.as_bytes().to_vec()).expect("should parse");
+        let eml = String::from_utf8(handle.to_eml_bytes().unwrap()).unwrap();
+        assert!(eml.contains("Date: someday\r\n"), "{eml}");
+    }
+
+    #[test]
+    fn strip_attachments_leaves_placeholders() {
+        let email = EMAIL_WITH_MULTIPLE_ATTACHMENTS.replace(
+            "Subject: Multiple Attachments\r\n",
+            "Subject: Multiple Attachments\r\nDate: Tue, 14 Nov 2023 22:13:20 +0000\r\n\
+             Message-ID: <m1@example.com>\r\n",
+        );
+        let handle = parse_eml(email.into_bytes()).expect("should parse");
+        let slim = parse_eml(handle.strip_attachments(vec![0, 9]).unwrap()).expect("should parse");
+
+        assert_eq!(slim.subject(), "Multiple Attachments");
+        assert_eq!(slim.date_timestamp(), 1_700_000_000_000);
+        assert_eq!(slim.message_id(), "m1@example.com");
+        assert_eq!(slim.body_text(), handle.body_text());
+        let attachments = slim.get_attachments();
+        assert_eq!(attachments.len(), 2);
+        assert_eq!(attachments[0].name, "doc1.pdf.removed.txt");
+        let note = String::from_utf8(slim.get_attachment_content(0).unwrap()).unwrap();
+        assert!(note.contains("Name: doc1.pdf\r\n"));
+        assert!(note.contains("Type: application/pdf\r\n"));
+        assert!(note.contains("Size: 5 bytes"));
+        assert_eq!(attachments[1].name, "image.png");
+        assert_eq!(
+            slim.get_attachment_content(1),
+            handle.get_attachment_content(1)
+        );
+
+        // Nor are fields the original lacked added to the copy.
+        let handle =
+            parse_eml(EMAIL_WITH_MULTIPLE_ATTACHMENTS.as_bytes().to_vec()).expect("should parse");
+        let slim = parse_eml(handle.strip_attachments(vec![0]).unwrap()).expect("should parse");
+        assert_eq!(slim.date_timestamp(), 0);
+        assert_eq!(slim.message_id(), "");
+    }
+}
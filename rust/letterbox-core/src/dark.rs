@@ -0,0 +1,231 @@
+//! Dark mode rendering of HTML bodies.
+//!
+//! Most HTML mail is designed on white. [`dark_mode_html`] injects a
+//! stylesheet that inverts the page and turns hues back around, so light
+//! backgrounds become dark and dark text light while colours keep their hue;
+//! images, videos and CSS background images are inverted a second time so
+//! they look as designed. Bodies that are dark already, or that declare their
+//! own dark scheme, are returned as they are.
+
+use scraper::{ElementRef, Html};
+
+/// The injected stylesheet.
+const DARK_MODE_STYLE: &str = "<style>\
+html { background: #fff; filter: invert(1) hue-rotate(180deg); }\
+img, picture, video, canvas, svg, [background], [style*=\"url(\"] \
+{ filter: invert(1) hue-rotate(180deg); }\
+</style>";
+
+/// Relative luminance below which a background is dark.
+const DARK_LUMINANCE: f64 = 0.4;
+
+/// How far down the chain of first children a page background is looked for.
+const WRAPPER_DEPTH: usize = 8;
+
+/// `html` made readable on a dark theme.
+pub fn dark_mode_html(html: &str) -> String {
+    let document = Html::parse_document(html);
+    if declares_dark_scheme(&document) || page_background(&document).is_some_and(is_dark) {
+        return html.to_string();
+    }
+    inject_style(html)
+}
+
+/// Whether the body adapts to dark themes itself, through a `color-scheme`
+/// meta tag or a `prefers-color-scheme: dark` media query.
+fn declares_dark_scheme(document: &Html) -> bool {
+    let by_meta = elements(document, "meta").any(|meta| {
+        let name = meta.value().attr("name").unwrap_or_default();
+        (name.eq_ignore_ascii_case("color-scheme")
+            || name.eq_ignore_ascii_case("supported-color-schemes"))
+            && meta
+                .value()
+                .attr("content")
+                .is_some_and(|content| content.to_ascii_lowercase().contains("dark"))
+    });
+    by_meta
+        || elements(document, "style").any(|style| {
+            let css: String = style
+                .text()
+                .flat_map(str::chars)
+                .filter(|c| !c.is_whitespace())
+                .collect();
+            css.to_ascii_lowercase()
+                .contains("prefers-color-scheme:dark")
+        })
+}
+
+/// The elements of `document` named `name`, in document order.
+fn elements<'a>(document: &'a Html, name: &'a str) -> impl Iterator<Item = ElementRef<'a>> {
+    document
+        .tree
+        .nodes()
+        .filter_map(ElementRef::wrap)
+        .filter(move |element| element.value().name() == name)
+}
+
+/// The background colour of the page: that of `<body>` or, failing that, of
+/// the wrappers nested first inside it, as newsletters lay out their page.
+fn page_background(document: &Html) -> Option<(u8, u8, u8)> {
+    let mut element = elements(document, "body").next();
+    for _ in 0..WRAPPER_DEPTH {
+        let current = element?;
+        if let Some(color) = background_of(current) {
+            return Some(color);
+        }
+        element = current.child_elements().next();
+    }
+    None
+}
+
+/// The background colour an element sets, by `bgcolor` or inline style.
+fn background_of(element: ElementRef) -> Option<(u8, u8, u8)> {
+    let element = element.value();
+    element.attr("bgcolor").and_then(parse_color).or_else(|| {
+        element.attr("style")?.split(';').find_map(|declaration| {
+            let (property, value) = declaration.split_once(':')?;
+            let property = property.trim().to_ascii_lowercase();
+            if property != "background" && property != "background-color" {
+                return None;
+            }
+            css_tokens(value).into_iter().find_map(parse_color)
+        })
+    })
+}
+
+/// `value` split on whitespace outside parentheses.
+fn css_tokens(value: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let (mut depth, mut start) = (0usize, 0);
+    for (i, c) in value.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            c if c.is_whitespace() && depth == 0 => {
+                tokens.push(&value[start..i]);
+                start = i + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    tokens.push(&value[start..]);
+    tokens.retain(|token| !token.is_empty());
+    tokens
+}
+
+/// An opaque colour given as `#rgb`, `#rrggbb`, `rgb()` or a basic name.
+fn parse_color(value: &str) -> Option<(u8, u8, u8)> {
+    let value = value
+        .trim()
+        .trim_end_matches("!important")
+        .to_ascii_lowercase();
+    if let Some(hex) = value.strip_prefix('#') {
+        let digit = |i: usize| u8::from_str_radix(hex.get(i..i + 1)?, 16).ok();
+        let pair = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+        return match hex.len() {
+            3 => Some((digit(0)? * 17, digit(1)? * 17, digit(2)? * 17)),
+            6 | 8 => Some((pair(0)?, pair(2)?, pair(4)?)),
+            _ => None,
+        };
+    }
+    if let Some(args) = value
+        .strip_prefix("rgb(")
+        .or_else(|| value.strip_prefix("rgba("))
+    {
+        let mut channels = args
+            .trim_end_matches(')')
+            .split([',', ' ', '/'])
+            .filter(|c| !c.is_empty())
+            .map(|c| c.parse::<f64>().ok().map(|c| c.clamp(0.0, 255.0) as u8));
+        return Some((channels.next()??, channels.next()??, channels.next()??));
+    }
+    match value.as_str() {
+        "white" => Some((255, 255, 255)),
+        "black" => Some((0, 0, 0)),
+        _ => None,
+    }
+}
+
+fn is_dark((r, g, b): (u8, u8, u8)) -> bool {
+    let luminance = (0.2126 * f64::from(r) + 0.7152 * f64::from(g) + 0.0722 * f64::from(b)) / 255.0;
+    luminance < DARK_LUMINANCE
+}
+
+/// `html` with [`DARK_MODE_STYLE`] at the end of its head, or before its body
+/// or content if it has none; never before a doctype, which would put the
+/// page in quirks mode.
+fn inject_style(html: &str) -> String {
+    let lower = html.to_ascii_lowercase();
+    let after_doctype = || {
+        let at = lower.find("<!doctype")?;
+        lower[at..].find('>').map(|end| at + end + 1)
+    };
+    let at = lower
+        .find("</head>")
+        .or_else(|| lower.find("<body"))
+        .or_else(after_doctype)
+        .unwrap_or(0);
+    let mut injected = String::with_capacity(html.len() + DARK_MODE_STYLE.len());
+    injected.push_str(&html[..at]);
+    injected.push_str(DARK_MODE_STYLE);
+    injected.push_str(&html[at..]);
+    injected
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inverts_light_pages_and_their_images_back() {
+        let html = "<html><head><title>News</title></head>\
+            <body><table bgcolor=\"#FAFAFA\"><tr><td>Hi<img src=\"cid:a\"></td></tr></table></body></html>";
+        let dark = dark_mode_html(html);
+        assert_eq!(
+            dark,
+            html.replace("</head>", &format!("{DARK_MODE_STYLE}</head>"))
+        );
+
+        let fragment = "<p>No head</p>";
+        assert_eq!(
+            dark_mode_html(fragment),
+            format!("{DARK_MODE_STYLE}{fragment}")
+        );
+
+        let doctype = "<!DOCTYPE html>\n<p>No head</p>";
+        assert_eq!(
+            dark_mode_html(doctype),
+            format!("<!DOCTYPE html>{DARK_MODE_STYLE}\n<p>No head</p>")
+        );
+    }
+
+    #[test]
+    fn leaves_dark_designs_alone() {
+        for html in [
+            "<body style=\"margin: 0; background: #111 url(x.png)\"><p>Dark</p></body>",
+            "<body><div><table style=\"background-color: rgb(20, 20, 30)\"><tr><td>x</td></tr></table></div></body>",
+            "<head><meta name=\"color-scheme\" content=\"light dark\"></head><body>x</body>",
+            "<style>@media (prefers-color-scheme : dark) { body { color: #eee } }</style>x",
+        ] {
+            assert_eq!(dark_mode_html(html), html);
+        }
+    }
+
+    #[test]
+    fn a_dark_button_does_not_make_a_dark_page() {
+        let html = "<body><p>Text</p><a style=\"background: #000\">Buy</a></body>";
+        assert_ne!(dark_mode_html(html), html);
+    }
+
+    #[test]
+    fn parses_colors() {
+        assert_eq!(parse_color("#fFf"), Some((255, 255, 255)));
+        assert_eq!(parse_color("#102030"), Some((16, 32, 48)));
+        assert_eq!(parse_color("rgba(1, 2, 3, 0.5)"), Some((1, 2, 3)));
+        assert_eq!(parse_color("rgb(1 2 3 / 50%)"), Some((1, 2, 3)));
+        assert_eq!(parse_color("Black!important"), Some((0, 0, 0)));
+        assert_eq!(parse_color("url(x)"), None);
+        assert!(is_dark((17, 17, 17)));
+        assert!(!is_dark((250, 250, 250)));
+    }
+}
@@ -0,0 +1,432 @@
+//! Header fields of a parsed message.
+//!
+//! [`header_fields`] reads the fields a handle exposes: the summary strings,
+//! structured address lists, threading IDs and every raw field in order.
+//! [`parse_eml_headers`](crate::parse_eml_headers) stops there; full parses
+//! add bodies, resources and attachments.
+
+use crate::{AddressInfo, EmailHandle, HeaderField, ParsedMessage, ThreadMessage};
+use std::collections::HashMap;
+
+/// The header fields of `message`, with no body, resources or attachments.
+pub(crate) fn header_fields(message: &mail_parser::Message) -> ParsedMessage {
+    let subject = message.subject().map(|s| s.to_string());
+
+    let from = message
+        .from()
+        .map(|addrs| format_addresses(addrs))
+        .unwrap_or_default();
+
+    let to = message
+        .to()
+        .map(|addrs| format_addresses(addrs))
+        .unwrap_or_default();
+
+    let cc = message
+        .cc()
+        .map(|addrs| format_addresses(addrs))
+        .unwrap_or_default();
+
+    let reply_to = message
+        .reply_to()
+        .map(|addrs| format_addresses(addrs))
+        .unwrap_or_default();
+
+    let message_id = message
+        .message_id()
+        .map(|s| s.to_string())
+        .unwrap_or_default();
+
+    let date = message.date().map(|d| d.to_rfc3339()).unwrap_or_default();
+
+    // Parse date to epoch milliseconds for sorting
+    // Uses the mail-parser's DateTime which provides to_timestamp()
+    let date_timestamp = message.date().map(|d| d.to_timestamp() * 1000); // Convert seconds to milliseconds
+
+    // Extract structured sender info for search/filter
+    let sender_info = message
+        .from()
+        .map(|addrs| extract_first_address_info(addrs))
+        .unwrap_or_default();
+
+    // Extract structured recipient info (To + Cc) for search/filter
+    let mut recipient_info = Vec::new();
+    if let Some(addrs) = message.to() {
+        recipient_info.extend(extract_all_address_info(addrs));
+    }
+    if let Some(addrs) = message.cc() {
+        recipient_info.extend(extract_all_address_info(addrs));
+    }
+
+    let in_reply_to = message
+        .in_reply_to()
+        .as_text_list()
+        .map(|ids| ids.iter().map(|id| id.to_string()).collect())
+        .unwrap_or_default();
+
+    let references = message
+        .references()
+        .as_text_list()
+        .map(|ids| ids.iter().map(|id| id.to_string()).collect())
+        .unwrap_or_default();
+
+    let addresses = |field: Option<&mail_parser::Address>| {
+        field.map(extract_all_address_info).unwrap_or_default()
+    };
+
+    let headers = message
+        .headers()
+        .iter()
+        .map(|header| {
+            let raw = message
+                .raw_message
+                .get(header.offset_start as usize..header.offset_end as usize)
+                .unwrap_or_default();
+            HeaderField {
+                name: header.name().to_string(),
+                value: String::from_utf8_lossy(raw).trim().to_string(),
+            }
+        })
+        .collect();
+
+    ParsedMessage {
+        subject,
+        from,
+        to,
+        cc,
+        reply_to,
+        message_id,
+        in_reply_to,
+        references,
+        date,
+        date_timestamp,
+        body_html: None,
+        body_html_derived: false,
+        body_text: None,
+        body_text_derived: false,
+        inline_assets: HashMap::new(),
+        attachments: Vec::new(),
+        sender_info,
+        recipient_info,
+        from_addresses: addresses(message.from()),
+        to_addresses: addresses(message.to()),
+        cc_addresses: addresses(message.cc()),
+        bcc_addresses: addresses(message.bcc()),
+        reply_to_addresses: addresses(message.reply_to()),
+        headers,
+        source: None,
+        reading: None,
+    }
+}
+
+fn format_addresses(addresses: &mail_parser::Address) -> String {
+    match addresses {
+        mail_parser::Address::List(list) => list
+            .iter()
+            .map(|addr| {
+                if let Some(name) = &addr.name {
+                    format!("{} <{}>", name, addr.address.as_deref().unwrap_or(""))
+                } else {
+                    addr.address.as_deref().unwrap_or("").to_string()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(", "),
+        mail_parser::Address::Group(groups) => groups
+            .iter()
+            .flat_map(|g| g.addresses.iter())
+            .map(|addr| {
+                if let Some(name) = &addr.name {
+                    format!("{} <{}>", name, addr.address.as_deref().unwrap_or(""))
+                } else {
+                    addr.address.as_deref().unwrap_or("").to_string()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(", "),
+    }
+}
+
+/// Extract the first address from an Address object as structured AddressInfo.
+/// Used for sender information where typically only the first address matters.
+fn extract_first_address_info(addresses: &mail_parser::Address) -> AddressInfo {
+    match addresses {
+        mail_parser::Address::List(list) => list
+            .first()
+            .map(|addr| AddressInfo {
+                email: addr.address.as_deref().unwrap_or("").to_string(),
+                name: addr
+                    .name
+                    .as_ref()
+                    .map(|n| n.to_string())
+                    .unwrap_or_default(),
+            })
+            .unwrap_or_default(),
+        mail_parser::Address::Group(groups) => groups
+            .first()
+            .and_then(|g| g.addresses.first())
+            .map(|addr| AddressInfo {
+                email: addr.address.as_deref().unwrap_or("").to_string(),
+                name: addr
+                    .name
+                    .as_ref()
+                    .map(|n| n.to_string())
+                    .unwrap_or_default(),
+            })
+            .unwrap_or_default(),
+    }
+}
+
+/// Extract all addresses from an Address object as structured AddressInfo.
+/// Used for recipient information where all addresses are relevant.
+fn extract_all_address_info(addresses: &mail_parser::Address) -> Vec<AddressInfo> {
+    match addresses {
+        mail_parser::Address::List(list) => list
+            .iter()
+            .map(|addr| AddressInfo {
+                email: addr.address.as_deref().unwrap_or("").to_string(),
+                name: addr
+                    .name
+                    .as_ref()
+                    .map(|n| n.to_string())
+                    .unwrap_or_default(),
+            })
+            .collect(),
+        mail_parser::Address::Group(groups) => groups
+            .iter()
+            .flat_map(|g| g.addresses.iter())
+            .map(|addr| AddressInfo {
+                email: addr.address.as_deref().unwrap_or("").to_string(),
+                name: addr
+                    .name
+                    .as_ref()
+                    .map(|n| n.to_string())
+                    .unwrap_or_default(),
+            })
+            .collect(),
+    }
+}
+
+#[uniffi::export]
+impl EmailHandle {
+    /// Get the raw value of the first header named `name` (case-insensitive).
+    pub fn get_header(&self, name: String) -> Option<String> {
+        self.inner.lock().ok().and_then(|msg| {
+            msg.headers
+                .iter()
+                .find(|h| h.name.eq_ignore_ascii_case(&name))
+                .map(|h| h.value.clone())
+        })
+    }
+
+    /// Get the raw values of every header named `name` (case-insensitive),
+    /// in message order.
+    pub fn get_headers(&self, name: String) -> Vec<String> {
+        self.inner
+            .lock()
+            .map(|msg| {
+                msg.headers
+                    .iter()
+                    .filter(|h| h.name.eq_ignore_ascii_case(&name))
+                    .map(|h| h.value.clone())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Get every top-level header field, in message order.
+    pub fn all_headers(&self) -> Vec<HeaderField> {
+        self.inner
+            .lock()
+            .map(|msg| msg.headers.clone())
+            .unwrap_or_default()
+    }
+
+    /// Get the first message ID this message replies to (In-Reply-To),
+    /// without angle brackets; empty if none.
+    pub fn in_reply_to(&self) -> String {
+        self.inner
+            .lock()
+            .ok()
+            .and_then(|msg| msg.in_reply_to.first().cloned())
+            .unwrap_or_default()
+    }
+
+    /// Get the message IDs of the References header, oldest first, without
+    /// angle brackets. Together with [`Self::in_reply_to`] this places the
+    /// message in its conversation thread.
+    pub fn references(&self) -> Vec<String> {
+        self.inner
+            .lock()
+            .map(|msg| msg.references.clone())
+            .unwrap_or_default()
+    }
+
+    /// Get what [`ThreadBuilder`](crate::ThreadBuilder) needs to thread this message.
+    pub fn thread_message(&self) -> ThreadMessage {
+        self.inner
+            .lock()
+            .map(|msg| ThreadMessage {
+                message_id: msg.message_id.clone(),
+                in_reply_to: msg.in_reply_to.first().cloned().unwrap_or_default(),
+                references: msg.references.clone(),
+                subject: msg
+                    .subject
+                    .clone()
+                    .unwrap_or_else(|| "Untitled".to_string()),
+                date_timestamp: msg.date_timestamp.unwrap_or(0),
+            })
+            .unwrap_or_default()
+    }
+
+    /// Get structured sender information.
+    /// Returns AddressInfo with separate email and name fields for search indexing.
+    pub fn sender_info(&self) -> AddressInfo {
+        self.inner
+            .lock()
+            .map(|msg| msg.sender_info.clone())
+            .unwrap_or_default()
+    }
+
+    /// Get structured recipient information (To + Cc).
+    /// Returns list of AddressInfo for all recipients for search indexing.
+    pub fn recipient_info(&self) -> Vec<AddressInfo> {
+        self.inner
+            .lock()
+            .map(|msg| msg.recipient_info.clone())
+            .unwrap_or_default()
+    }
+
+    /// Get every "From" address as a structured record, in order.
+    pub fn from_addresses(&self) -> Vec<AddressInfo> {
+        self.inner
+            .lock()
+            .map(|msg| msg.from_addresses.clone())
+            .unwrap_or_default()
+    }
+
+    /// Get every "To" address as a structured record, in order.
+    pub fn to_addresses(&self) -> Vec<AddressInfo> {
+        self.inner
+            .lock()
+            .map(|msg| msg.to_addresses.clone())
+            .unwrap_or_default()
+    }
+
+    /// Get every "Cc" address as a structured record, in order.
+    pub fn cc_addresses(&self) -> Vec<AddressInfo> {
+        self.inner
+            .lock()
+            .map(|msg| msg.cc_addresses.clone())
+            .unwrap_or_default()
+    }
+
+    /// Get every "Bcc" address as a structured record, in order.
+    pub fn bcc_addresses(&self) -> Vec<AddressInfo> {
+        self.inner
+            .lock()
+            .map(|msg| msg.bcc_addresses.clone())
+            .unwrap_or_default()
+    }
+
+    /// Get every "Reply-To" address as a structured record, in order.
+    pub fn reply_to_addresses(&self) -> Vec<AddressInfo> {
+        self.inner
+            .lock()
+            .map(|msg| msg.reply_to_addresses.clone())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::tests::{MULTIPART_EMAIL, SIMPLE_EMAIL};
+    use crate::{parse_eml, parse_eml_headers, ParseError};
+
+    #[test]
+    fn structured_addresses_per_field() {
+        let email = "Subject: Test\r\n\
+                     From: John Doe <john@example.com>\r\n\
+                     To: a@example.com, \"Bee, B\" <b@example.com>\r\n\
+                     Cc: Team: c@example.com, d@example.com;\r\n\
+                     Bcc: e@example.com\r\n\
+                     Reply-To: Replies <r@example.com>\r\n\r\n\
+                     Body";
+        let handle = parse_eml(email.as_bytes().to_vec()).expect("should parse");
+        let from = handle.from_addresses();
+        assert_eq!(from.len(), 1);
+        assert_eq!(from[0].name, "John Doe");
+        let to = handle.to_addresses();
+        assert_eq!(to.len(), 2);
+        assert_eq!(to[1].name, "Bee, B");
+        assert_eq!(to[1].email, "b@example.com");
+        let cc: Vec<String> = handle.cc_addresses().into_iter().map(|a| a.email).collect();
+        assert_eq!(cc, vec!["c@example.com", "d@example.com"]);
+        assert_eq!(handle.bcc_addresses()[0].email, "e@example.com");
+        assert_eq!(handle.reply_to_addresses()[0].name, "Replies");
+
+        let bare = parse_eml(SIMPLE_EMAIL.as_bytes().to_vec()).expect("should parse");
+        assert!(bare.cc_addresses().is_empty());
+    }
+
+    #[test]
+    fn parses_headers_only() {
+        let handle = parse_eml_headers(MULTIPART_EMAIL.as_bytes().to_vec()).expect("should parse");
+        assert_eq!(handle.subject(), "Test Multipart");
+        assert_eq!(handle.from(), "sender@example.com");
+        assert_eq!(handle.recipient_info().len(), 1);
+        assert!(handle.body_html().is_none());
+        assert!(handle.body_text().is_none());
+        assert_eq!(handle.attachment_count(), 0);
+        assert!(handle.get_resource_ids().is_empty());
+        assert_eq!(parse_eml_headers(Vec::new()).err(), Some(ParseError::Empty));
+    }
+
+    #[test]
+    fn exposes_every_header_in_order() {
+        let email = "Subject: Hello\r\n\
+                     List-Id: News <news.example.com>\r\n\
+                     Received: from a\r\n\
+                     X-Priority: 1\r\n\
+                     received: from b\r\n \tby c\r\n\r\n\
+                     Body";
+        for handle in [
+            parse_eml(email.as_bytes().to_vec()).expect("should parse"),
+            parse_eml_headers(email.as_bytes().to_vec()).expect("should parse"),
+        ] {
+            assert_eq!(
+                handle.get_header("list-id".to_string()).as_deref(),
+                Some("News <news.example.com>")
+            );
+            assert_eq!(
+                handle.get_headers("Received".to_string()),
+                vec!["from a".to_string(), "from b\r\n \tby c".to_string()]
+            );
+            assert_eq!(handle.get_header("X-Missing".to_string()), None);
+            let names: Vec<String> = handle.all_headers().into_iter().map(|h| h.name).collect();
+            assert_eq!(names.len(), 5);
+            assert_eq!(names[0], "Subject");
+            assert_eq!(names[3], "X-Priority");
+        }
+    }
+
+    #[test]
+    fn exposes_threading_headers() {
+        let email = "Subject: Re: Plans\r\n\
+                     Message-ID: <c@example.com>\r\n\
+                     In-Reply-To: <b@example.com>\r\n\
+                     References: <a@example.com>\r\n <b@example.com>\r\n\r\n\
+                     Body";
+        let handle = parse_eml_headers(email.as_bytes().to_vec()).expect("should parse");
+        assert_eq!(handle.in_reply_to(), "b@example.com");
+        assert_eq!(handle.references(), vec!["a@example.com", "b@example.com"]);
+        let thread_message = handle.thread_message();
+        assert_eq!(thread_message.message_id, "c@example.com");
+        assert_eq!(thread_message.in_reply_to, "b@example.com");
+        assert_eq!(thread_message.subject, "Re: Plans");
+
+        let fresh = parse_eml(SIMPLE_EMAIL.as_bytes().to_vec()).expect("should parse");
+        assert_eq!(fresh.in_reply_to(), "");
+        assert!(fresh.references().is_empty());
+    }
+}
@@ -0,0 +1,440 @@
+//! Parsing a message into an [`EmailHandle`].
+//!
+//! [`parse_eml`] and [`parse_eml_from_path`] parse bytes held on the heap;
+//! [`parse_eml_file`] maps the file instead and leaves binary attachments
+//! encoded in the map. [`parse_eml_headers`] stops before the body.
+
+use crate::attachment::{Attachment, AttachmentContent};
+use crate::headers::header_fields;
+use crate::{text, EmailHandle, InlineAsset, ParseError};
+use mail_parser::{MessageParser, MimeHeaders, PartType};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+/// Raw message bytes, owned or mapped from the file.
+pub(crate) enum Source {
+    Owned(Vec<u8>),
+    Mapped(memmap2::Mmap),
+}
+
+impl Source {
+    pub(crate) fn bytes(&self) -> &[u8] {
+        match self {
+            Source::Owned(data) => data,
+            Source::Mapped(map) => map,
+        }
+    }
+}
+
+/// Parse an EML file from raw bytes.
+/// Returns an opaque handle that stays in Rust memory.
+#[uniffi::export]
+pub fn parse_eml(data: Vec<u8>) -> Result<Arc<EmailHandle>, ParseError> {
+    parse_source(Source::Owned(data))
+}
+
+/// Parse an EML file in place.
+/// The file is memory-mapped rather than read, so a 50 MB message costs no
+/// 50 MB buffer on the heap: only the decoded bodies, inline resources and
+/// text attachments are copied out, and binary attachments are decoded from
+/// the map when asked for. Nothing is copied across FFI but the path.
+#[uniffi::export]
+pub fn parse_eml_file(path: String) -> Result<Arc<EmailHandle>, ParseError> {
+    let file = fs::File::open(&path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => ParseError::FileNotFound { path: path.clone() },
+        _ => ParseError::IoError {
+            details: e.to_string(),
+        },
+    })?;
+    let len = file
+        .metadata()
+        .map_err(|e| ParseError::IoError {
+            details: e.to_string(),
+        })?
+        .len();
+    if len == 0 {
+        return Err(ParseError::Empty);
+    }
+
+    // SAFETY: the map is only ever read, while parsing and, for attachments
+    // left encoded, by the handle that keeps it. A file truncated meanwhile by
+    // another process would fault; the app owns the EML files it parses.
+    let map = unsafe { memmap2::Mmap::map(&file) }.map_err(|e| ParseError::IoError {
+        details: e.to_string(),
+    })?;
+    parse_source(Source::Mapped(map))
+}
+
+fn parse_source(source: Source) -> Result<Arc<EmailHandle>, ParseError> {
+    let data = source.bytes();
+    // Only a map is cheap to keep for decoding attachments later.
+    let mapped = matches!(source, Source::Mapped(_));
+    if data.is_empty() {
+        return Err(ParseError::Empty);
+    }
+
+    let parser = MessageParser::default();
+    let message = parser.parse(data).ok_or(ParseError::Invalid)?;
+
+    let mut parsed = header_fields(&message);
+
+    // Get body HTML; mail-parser converts a text part when there is no HTML
+    // one, but our conversion below links and quotes it.
+    let has_html_part = message
+        .html_body
+        .first()
+        .and_then(|&id| message.part(id))
+        .is_some_and(|part| matches!(part.body, PartType::Html(_)));
+    let body_html = has_html_part
+        .then(|| message.body_html(0).map(|s| s.to_string()))
+        .flatten();
+
+    // Get body text; mail-parser converts the HTML part when there is no
+    // text one.
+    let body_text = message.body_text(0).map(|s| s.to_string());
+    let has_text_part = message
+        .text_body
+        .first()
+        .and_then(|&id| message.part(id))
+        .is_some_and(|part| matches!(part.body, PartType::Text(_)));
+
+    // Extract inline assets
+    let mut inline_assets = HashMap::new();
+    for part in message.parts.iter() {
+        if let Some(content_id) = part.content_id() {
+            // This is an inline attachment referenced by cid:
+            let cid = content_id
+                .trim_start_matches('<')
+                .trim_end_matches('>')
+                .to_string();
+            let bytes = part.contents();
+            if !bytes.is_empty() {
+                let content_type = part
+                    .content_type()
+                    .map(|ct| {
+                        if let Some(subtype) = ct.subtype() {
+                            format!("{}/{}", ct.ctype(), subtype)
+                        } else {
+                            ct.ctype().to_string()
+                        }
+                    })
+                    .unwrap_or_else(|| "application/octet-stream".to_string());
+                inline_assets.insert(
+                    cid,
+                    InlineAsset {
+                        content_type,
+                        content: bytes.to_vec(),
+                    },
+                );
+            }
+        }
+    }
+
+    // Extract attachments (files that are not inline CID references and not body parts)
+    let mut attachments = Vec::new();
+    for (part_idx, part) in message.parts.iter().enumerate() {
+        // Skip the root part (usually multipart container)
+        if part_idx == 0 {
+            continue;
+        }
+
+        // Check if this part is an attachment (has Content-Disposition: attachment)
+        // or has a filename and is not already an inline CID reference
+        let is_inline_cid = part.content_id().is_some();
+        let content_type = part
+            .content_type()
+            .map(|ct| {
+                if let Some(subtype) = ct.subtype() {
+                    format!("{}/{}", ct.ctype(), subtype)
+                } else {
+                    ct.ctype().to_string()
+                }
+            })
+            .unwrap_or_else(|| "application/octet-stream".to_string());
+
+        // Skip text/plain and text/html parts that are the main body
+        let is_body_part = content_type == "text/plain" || content_type == "text/html";
+
+        // Get the attachment name
+        let attachment_name = part.attachment_name().map(|s| s.to_string()).or_else(|| {
+            // Fallback to Content-Type name parameter
+            part.content_type()
+                .and_then(|ct| ct.attribute("name"))
+                .map(|s| s.to_string())
+        });
+
+        // Determine if this is an attachment:
+        // - Has a filename
+        // - Or is explicitly marked as attachment
+        // - And is not an inline CID or body part
+        let has_content_disposition_attachment = part
+            .content_disposition()
+            .map(|cd| cd.ctype() == "attachment")
+            .unwrap_or(false);
+
+        // Check if this part qualifies as an attachment:
+        // 1. Has a filename OR is explicitly marked as attachment
+        // 2. Is not an inline CID reference
+        // 3. Is not a body part without a filename
+        let is_attachment_candidate =
+            attachment_name.is_some() || has_content_disposition_attachment;
+        // Exclusion conditions: skip inline CID parts, or unnamed body parts (text/html, text/plain)
+        let should_exclude = is_inline_cid || (is_body_part && attachment_name.is_none());
+
+        if is_attachment_candidate && !should_exclude {
+            let bytes = part.contents();
+            if !bytes.is_empty() {
+                let content = match part.body {
+                    PartType::Binary(_) | PartType::InlineBinary(_) if mapped => {
+                        AttachmentContent::Encoded {
+                            body: part.offset_body as usize..part.offset_end as usize,
+                            encoding: part.encoding,
+                        }
+                    }
+                    _ => AttachmentContent::Decoded(bytes.to_vec()),
+                };
+                attachments.push(Attachment {
+                    name: attachment_name.unwrap_or_else(|| format!("attachment_{}", part_idx)),
+                    content_type: content_type.clone(),
+                    size: bytes.len() as u64,
+                    content,
+                });
+            }
+        }
+    }
+
+    // If no HTML body, convert text to HTML
+    parsed.body_html_derived = body_html.is_none();
+    let final_body_html = body_html.or_else(|| {
+        body_text
+            .as_deref()
+            .map(|text| text::text_to_html(text, false))
+    });
+
+    parsed.body_html = final_body_html;
+    parsed.body_text_derived = body_text.is_some() && !has_text_part;
+    parsed.body_text = body_text;
+    parsed.inline_assets = inline_assets;
+    let encoded = attachments
+        .iter()
+        .any(|a| matches!(a.content, AttachmentContent::Encoded { .. }));
+    parsed.attachments = attachments;
+    drop(message);
+    parsed.source = encoded.then_some(source);
+
+    Ok(Arc::new(EmailHandle {
+        inner: Mutex::new(parsed),
+    }))
+}
+
+/// Parse only the header section of an EML file from raw bytes.
+/// Parsing stops where the body begins, so no body or attachment is decoded;
+/// this suits building a message list from many EMLs. The returned handle has
+/// the headers of [`parse_eml`] but no bodies, inline resources or attachments.
+#[uniffi::export]
+pub fn parse_eml_headers(data: Vec<u8>) -> Result<Arc<EmailHandle>, ParseError> {
+    if data.is_empty() {
+        return Err(ParseError::Empty);
+    }
+
+    let parser = MessageParser::default();
+    let message = parser.parse_headers(&data).ok_or(ParseError::Invalid)?;
+
+    Ok(Arc::new(EmailHandle {
+        inner: Mutex::new(header_fields(&message)),
+    }))
+}
+
+/// Parse an EML file from a file path.
+/// This avoids copying the entire file into the JVM heap first: Rust reads the
+/// file directly. [`parse_eml_file`] goes further and does not read it into
+/// memory at all.
+/// Returns an opaque handle that stays in Rust memory.
+///
+/// # Security
+/// The caller should ensure the path points to an untrusted EML file that is safe to parse.
+/// The mail-parser library handles malformed input gracefully, but the caller should still
+/// validate that the file exists in an expected location.
+#[uniffi::export]
+pub fn parse_eml_from_path(path: String) -> Result<Arc<EmailHandle>, ParseError> {
+    let file_path = Path::new(&path);
+    if !file_path.exists() {
+        return Err(ParseError::FileNotFound { path });
+    }
+
+    let data = fs::read(file_path).map_err(|e| ParseError::IoError {
+        details: e.to_string(),
+    })?;
+
+    parse_eml(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{MULTIPART_EMAIL, SIMPLE_EMAIL};
+    use std::io::Write;
+
+    #[test]
+    fn parses_simple_email() {
+        let handle = parse_eml(SIMPLE_EMAIL.as_bytes().to_vec()).expect("should parse");
+        assert_eq!(handle.subject(), "Hello");
+        assert_eq!(handle.from(), "sender@example.com");
+        assert_eq!(handle.to(), "recipient@example.com");
+    }
+
+    #[test]
+    fn parses_multipart_email() {
+        let handle = parse_eml(MULTIPART_EMAIL.as_bytes().to_vec()).expect("should parse");
+        assert_eq!(handle.subject(), "Test Multipart");
+
+        // Should have HTML body
+        let body_html = handle.body_html();
+        assert!(body_html.is_some());
+        assert!(body_html.unwrap().contains("HTML body"));
+
+        // Should have plain text body
+        let body_text = handle.body_text();
+        assert!(body_text.is_some());
+        assert!(body_text.unwrap().contains("Plain text body"));
+    }
+
+    #[test]
+    fn rejects_empty_payload() {
+        let result = parse_eml(vec![]);
+        assert!(result.is_err());
+        match result {
+            Err(ParseError::Empty) => (),
+            _ => panic!("Expected ParseError::Empty"),
+        }
+    }
+
+    #[test]
+    fn handles_malformed_input_gracefully() {
+        let result = parse_eml(b"not a valid email".to_vec());
+        // mail-parser is lenient, so this might parse but with empty fields
+        // The important thing is it doesn't crash
+        assert!(result.is_ok() || result.is_err());
+    }
+
+    #[test]
+    fn parse_eml_from_path_works_with_valid_file() {
+        // Create a temp file
+        let temp_dir = std::env::temp_dir();
+        let temp_file = temp_dir.join("test_email.eml");
+
+        let mut file = fs::File::create(&temp_file).expect("create temp file");
+        file.write_all(SIMPLE_EMAIL.as_bytes())
+            .expect("write temp file");
+
+        // Parse from path
+        let handle =
+            parse_eml_from_path(temp_file.to_str().unwrap().to_string()).expect("should parse");
+        assert_eq!(handle.subject(), "Hello");
+        assert_eq!(handle.from(), "sender@example.com");
+
+        // Cleanup
+        let _ = fs::remove_file(temp_file);
+    }
+
+    #[test]
+    fn parse_eml_file_maps_the_file() {
+        let temp_file = std::env::temp_dir().join("test_mapped_email.eml");
+        fs::write(&temp_file, MULTIPART_EMAIL.as_bytes()).expect("write temp file");
+
+        let handle = parse_eml_file(temp_file.to_str().unwrap().to_string()).expect("should parse");
+        assert_eq!(handle.subject(), "Test Multipart");
+        assert!(handle.body_html().unwrap().contains("HTML body"));
+
+        fs::write(&temp_file, "").expect("empty temp file");
+        let empty = parse_eml_file(temp_file.to_str().unwrap().to_string());
+        assert!(matches!(empty, Err(ParseError::Empty)));
+        let _ = fs::remove_file(&temp_file);
+
+        let missing = parse_eml_file(temp_file.to_str().unwrap().to_string());
+        assert!(matches!(missing, Err(ParseError::FileNotFound { .. })));
+    }
+
+    #[test]
+    fn parse_eml_from_path_returns_error_for_missing_file() {
+        let result = parse_eml_from_path("/nonexistent/path/email.eml".to_string());
+        assert!(matches!(result, Err(ParseError::FileNotFound { .. })));
+    }
+
+    #[test]
+    fn parses_email_with_unicode_subject() {
+        let email = "Subject: こんにちは 🌍 Émoji\r\nFrom: test@test.com\r\n\r\nBody";
+        let handle = parse_eml(email.as_bytes().to_vec()).expect("should parse");
+        let subject = handle.subject();
+        // Verify Unicode characters are preserved
+        assert!(subject.contains("こんにちは") || subject.contains("🌍") || !subject.is_empty());
+    }
+
+    #[test]
+    fn parses_email_with_very_long_subject() {
+        let long_subject = "X".repeat(1000);
+        let email = format!(
+            "Subject: {}\r\nFrom: test@test.com\r\n\r\nBody",
+            long_subject
+        );
+        let handle = parse_eml(email.as_bytes().to_vec()).expect("should parse");
+        assert_eq!(handle.subject(), long_subject);
+    }
+
+    #[test]
+    fn parses_email_with_missing_subject() {
+        let email = "From: test@test.com\r\nTo: recipient@test.com\r\n\r\nBody";
+        let handle = parse_eml(email.as_bytes().to_vec()).expect("should parse");
+        // Should fall back to "Untitled" for missing subject
+        assert_eq!(handle.subject(), "Untitled");
+    }
+
+    #[test]
+    fn parses_email_with_empty_fields() {
+        let email = "Subject: Test\r\n\r\nBody";
+        let handle = parse_eml(email.as_bytes().to_vec()).expect("should parse");
+        // Empty fields should return empty strings, not panic
+        assert!(handle.from().is_empty() || handle.from() == "");
+        assert!(handle.to().is_empty() || handle.to() == "");
+        assert!(handle.cc().is_empty() || handle.cc() == "");
+    }
+
+    #[test]
+    fn parses_email_with_multiple_recipients() {
+        let email = "Subject: Multi\r\n\
+                     From: sender@example.com\r\n\
+                     To: alice@example.com, bob@example.com\r\n\
+                     Cc: carol@example.com\r\n\
+                     \r\nBody";
+        let handle = parse_eml(email.as_bytes().to_vec()).expect("should parse");
+        let to = handle.to();
+        // Should contain both recipients
+        assert!(to.contains("alice") || to.contains("bob"));
+    }
+
+    #[test]
+    fn parses_email_with_date() {
+        let email = "Subject: Dated\r\n\
+                     From: test@test.com\r\n\
+                     Date: Mon, 11 Dec 2025 10:00:00 +0000\r\n\
+                     \r\nBody";
+        let handle = parse_eml(email.as_bytes().to_vec()).expect("should parse");
+        let date = handle.date();
+        // Date should be extracted in some format
+        assert!(!date.is_empty());
+    }
+
+    #[test]
+    fn parse_eml_from_path_empty_file_returns_empty_error() {
+        let temp_dir = std::env::temp_dir();
+        let temp_file = temp_dir.join("empty_email.eml");
+        fs::write(&temp_file, "").expect("write empty file");
+
+        let result = parse_eml_from_path(temp_file.to_str().unwrap().to_string());
+        assert!(matches!(result, Err(ParseError::Empty)));
+
+        let _ = fs::remove_file(temp_file);
+    }
+}
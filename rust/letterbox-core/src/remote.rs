@@ -0,0 +1,357 @@
+//! Remote content in HTML bodies.
+//!
+//! Anything an HTML body loads from the network tells the sender the message
+//! was opened, so the app asks before loading it. [`remote_resources`] lists
+//! every external URL a body would load: `<img>` sources and `srcset`
+//! candidates, `poster`s, `<video>`, `<audio>`, `<source>` and `<track>`
+//! sources, `background` attributes, CSS `url()`s and string `@import`s in
+//! `style` attributes and `<style>` blocks, and `<link>` hrefs.
+//! [`rewrite_remote_resources`] replaces each of them with a placeholder on a
+//! scheme the app intercepts, so nothing loads until the app fetches it,
+//! e.g. through letterbox-proxy.
+//!
+//! External means `http://`, `https://` or protocol-relative (`//host/...`);
+//! `cid:` and `data:` URLs are part of the message and left alone.
+//!
+//! Those `cid:` references go through the same walk in
+//! [`rewrite_cid_references`], which moves them onto a scheme of the app's
+//! so its WebView interception can serve inline parts by Content-ID.
+
+use scraper::node::Element;
+use scraper::{Html, Node};
+use std::collections::HashMap;
+use std::ops::Range;
+
+use crate::InlineAsset;
+
+/// How a remote URL is used by the body.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, uniffi::Enum)]
+pub enum RemoteResourceKind {
+    /// `<img src>`, `srcset` candidate or `<video poster>`
+    Image,
+    /// CSS `url()` or `@import`, or a `background` attribute
+    Css,
+    /// `<link href>`, such as a stylesheet or icon
+    Link,
+    /// `src` of `<video>`, `<audio>`, `<source>` or `<track>`
+    Media,
+}
+
+/// An external URL the body would load.
+#[derive(Clone, Debug, PartialEq, Eq, uniffi::Record)]
+pub struct RemoteResource {
+    pub url: String,
+    pub kind: RemoteResourceKind,
+    /// Whether the URL is the source of an image declared 1×1 or smaller,
+    /// as tracking pixels are
+    pub is_tracking_pixel: bool,
+}
+
+/// Called with each URL the body loads, its kind and whether it is the
+/// source of a tracking pixel; returns the URL to put in its place, if any.
+type Visit<'a> = dyn FnMut(&str, RemoteResourceKind, bool) -> Option<String> + 'a;
+
+/// Every external URL in `html`, once each, in document order.
+pub fn remote_resources(html: &str) -> Vec<RemoteResource> {
+    let mut resources: Vec<RemoteResource> = Vec::new();
+    walk(
+        &mut Html::parse_document(html),
+        &mut |url, kind, is_tracking_pixel| {
+            if !is_remote(url) {
+                return None;
+            }
+            match resources.iter_mut().find(|r| r.url == url) {
+                Some(seen) => seen.is_tracking_pixel |= is_tracking_pixel,
+                None => resources.push(RemoteResource {
+                    url: url.to_string(),
+                    kind,
+                    is_tracking_pixel,
+                }),
+            }
+            None
+        },
+    );
+    resources
+}
+
+/// `html` with every external URL replaced by `scheme_prefix` followed by
+/// the form-urlencoded URL, e.g. `letterbox-remote://https%3A%2F%2F...`.
+pub fn rewrite_remote_resources(html: &str, scheme_prefix: &str) -> String {
+    let mut document = Html::parse_document(html);
+    walk(&mut document, &mut |url, _, _| {
+        is_remote(url).then(|| {
+            let encoded: String = url::form_urlencoded::byte_serialize(url.as_bytes()).collect();
+            format!("{scheme_prefix}{encoded}")
+        })
+    });
+    document.html()
+}
+
+/// `html` with every `cid:` reference moved to `scheme_prefix` (the part
+/// after `cid:` is kept as written), and the Content-IDs referenced that
+/// none of `assets` has, once each.
+pub fn rewrite_cid_references(
+    html: &str,
+    scheme_prefix: &str,
+    assets: &HashMap<String, InlineAsset>,
+) -> (String, Vec<String>) {
+    let mut document = Html::parse_document(html);
+    let mut unresolved = Vec::new();
+    walk(&mut document, &mut |url, _, _| {
+        let reference = url
+            .get(..4)
+            .filter(|scheme| scheme.eq_ignore_ascii_case("cid:"))
+            .map(|_| &url[4..])?;
+        // A cid: URL is the Content-ID percent-encoded (RFC 2392).
+        let cid = percent_encoding::percent_decode_str(reference)
+            .decode_utf8_lossy()
+            .into_owned();
+        if !assets.contains_key(&cid) && !unresolved.contains(&cid) {
+            unresolved.push(cid);
+        }
+        Some(format!("{scheme_prefix}{reference}"))
+    });
+    (document.html(), unresolved)
+}
+
+/// Whether `url` is loaded from the network: `http://`, `https://` or
+/// protocol-relative, in any case and with leading whitespace.
+pub(crate) fn is_remote(url: &str) -> bool {
+    let url = url.trim_start().to_ascii_lowercase();
+    url.starts_with("http://") || url.starts_with("https://") || url.starts_with("//")
+}
+
+/// Call `visit` on every URL `document` loads, in document order.
+fn walk(document: &mut Html, visit: &mut Visit) {
+    let ids: Vec<_> = document.tree.nodes().map(|node| node.id()).collect();
+    for id in ids {
+        let in_style = document
+            .tree
+            .get(id)
+            .and_then(|node| node.parent())
+            .and_then(|parent| parent.value().as_element().map(|e| e.name() == "style"))
+            .unwrap_or(false);
+        let Some(mut node) = document.tree.get_mut(id) else {
+            continue;
+        };
+        match node.value() {
+            Node::Text(text) if in_style => {
+                if let Some(css) = replace_css(&text.text, visit) {
+                    text.text = css.into();
+                }
+            }
+            Node::Element(element) => {
+                let name = element.name().to_string();
+                let pixel = name == "img" && is_tracking_pixel(element);
+                for (attribute, value) in element.attrs.iter_mut() {
+                    let replaced = match (name.as_str(), &*attribute.local) {
+                        ("img", "src") => {
+                            replace_url(value, RemoteResourceKind::Image, pixel, visit)
+                        }
+                        ("input", "src") | ("video", "poster") => {
+                            replace_url(value, RemoteResourceKind::Image, false, visit)
+                        }
+                        ("video" | "audio" | "source" | "track", "src") => {
+                            replace_url(value, RemoteResourceKind::Media, false, visit)
+                        }
+                        ("img" | "source", "srcset") => replace_srcset(value, visit),
+                        ("link", "href") => {
+                            replace_url(value, RemoteResourceKind::Link, false, visit)
+                        }
+                        (_, "background") => {
+                            replace_url(value, RemoteResourceKind::Css, false, visit)
+                        }
+                        (_, "style") => replace_css(value, visit),
+                        _ => None,
+                    };
+                    if let Some(replaced) = replaced {
+                        *value = replaced.into();
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Whether an image is declared at most 1×1 by its `width` and `height`.
+fn is_tracking_pixel(element: &Element) -> bool {
+    let at_most_one = |attribute| {
+        element
+            .attr(attribute)
+            .and_then(|value: &str| value.trim().parse::<u32>().ok())
+            .is_some_and(|value| value <= 1)
+    };
+    at_most_one("width") && at_most_one("height")
+}
+
+fn replace_url(
+    url: &str,
+    kind: RemoteResourceKind,
+    is_tracking_pixel: bool,
+    visit: &mut Visit,
+) -> Option<String> {
+    let url = url.trim();
+    (!url.is_empty())
+        .then(|| visit(url, kind, is_tracking_pixel))
+        .flatten()
+}
+
+/// A `srcset` with its candidates replaced. Candidates are split on
+/// commas, so a URL containing one is not recognised.
+fn replace_srcset(srcset: &str, visit: &mut Visit) -> Option<String> {
+    let mut replaced = false;
+    let candidates: Vec<String> = srcset
+        .split(',')
+        .map(|candidate| {
+            let candidate = candidate.trim();
+            let (url, descriptor) = candidate
+                .split_once(char::is_whitespace)
+                .unwrap_or((candidate, ""));
+            match replace_url(url, RemoteResourceKind::Image, false, visit) {
+                Some(url) => {
+                    replaced = true;
+                    format!("{url} {}", descriptor.trim())
+                        .trim_end()
+                        .to_string()
+                }
+                None => candidate.to_string(),
+            }
+        })
+        .collect();
+    replaced.then(|| candidates.join(", "))
+}
+
+/// `css` with the URLs of its `url()`s and string `@import`s replaced.
+fn replace_css(css: &str, visit: &mut Visit) -> Option<String> {
+    let mut replaced = String::with_capacity(css.len());
+    let mut end = 0;
+    for range in css_urls(css) {
+        if let Some(url) = replace_url(&css[range.clone()], RemoteResourceKind::Css, false, visit) {
+            replaced.push_str(&css[end..range.start]);
+            replaced.push_str(&url);
+            end = range.end;
+        }
+    }
+    (end > 0).then(|| {
+        replaced.push_str(&css[end..]);
+        replaced
+    })
+}
+
+/// Where the URLs of `css` are, in order: the inside of `url()`, without
+/// quotes, and the string of `@import "..."`.
+fn css_urls(css: &str) -> Vec<Range<usize>> {
+    // ASCII lowercasing keeps byte offsets.
+    let lower = css.to_ascii_lowercase();
+    let mut urls = Vec::new();
+    for (marker, needs_quote) in [("url(", false), ("@import", true)] {
+        let mut from = 0;
+        while let Some(at) = lower[from..].find(marker) {
+            let start = from + at + marker.len();
+            from = start;
+            let rest = &css[start..];
+            let skipped = rest.len() - rest.trim_start().len();
+            let start = start + skipped;
+            let rest = &css[start..];
+            let range = match rest.chars().next() {
+                Some(quote @ ('"' | '\'')) => {
+                    rest[1..].find(quote).map(|len| start + 1..start + 1 + len)
+                }
+                _ if needs_quote => None,
+                _ => rest
+                    .find(')')
+                    .map(|len| start..start + rest[..len].trim_end().len()),
+            };
+            urls.extend(range);
+        }
+    }
+    urls.sort_by_key(|range| range.start);
+    urls
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HTML: &str = r#"<html><head>
+        <link rel="stylesheet" href="https://cdn.example/mail.css">
+        <style>@import "https://cdn.example/more.css";
+            .hero { background: URL( 'https://t.example/hero.png' ) }
+            .logo { background: url(cid:logo) }</style></head>
+        <body background="//t.example/bg.gif">
+        <img src="https://t.example/open.gif" width="1" height="1">
+        <img src="cid:logo@example.com" srcset="https://cdn.example/a.png 1x, b.png 2x">
+        <div style="background-image: url(http://t.example/div.png)">x</div>
+        <video poster="cid:p" src="https://m.example/v.mp4"><source src="https://m.example/v.webm">
+        <track src="https://m.example/subs.vtt"></video><audio src="//m.example/a.mp3"></audio>
+        <img src="https://t.example/open.gif"></body></html>"#;
+
+    #[test]
+    fn lists_every_remote_url_once() {
+        let resources = remote_resources(HTML);
+        let pixels: Vec<&str> = resources
+            .iter()
+            .filter(|r| r.is_tracking_pixel)
+            .map(|r| r.url.as_str())
+            .collect();
+        assert_eq!(pixels, vec!["https://t.example/open.gif"]);
+        let urls: Vec<(String, RemoteResourceKind)> =
+            resources.into_iter().map(|r| (r.url, r.kind)).collect();
+        let expected = [
+            ("https://cdn.example/mail.css", RemoteResourceKind::Link),
+            ("https://cdn.example/more.css", RemoteResourceKind::Css),
+            ("https://t.example/hero.png", RemoteResourceKind::Css),
+            ("//t.example/bg.gif", RemoteResourceKind::Css),
+            ("https://t.example/open.gif", RemoteResourceKind::Image),
+            ("https://cdn.example/a.png", RemoteResourceKind::Image),
+            ("http://t.example/div.png", RemoteResourceKind::Css),
+            ("https://m.example/v.mp4", RemoteResourceKind::Media),
+            ("https://m.example/v.webm", RemoteResourceKind::Media),
+            ("https://m.example/subs.vtt", RemoteResourceKind::Media),
+            ("//m.example/a.mp3", RemoteResourceKind::Media),
+        ];
+        assert_eq!(
+            urls,
+            expected.map(|(url, kind)| (url.to_string(), kind)).to_vec()
+        );
+    }
+
+    #[test]
+    fn rewrites_remote_urls_to_placeholders() {
+        let rewritten = rewrite_remote_resources(HTML, "letterbox-remote://");
+        assert!(!rewritten.contains("https://"), "{rewritten}");
+        assert!(!rewritten.contains("//t.example"), "{rewritten}");
+        assert!(!rewritten.contains("//m.example"), "{rewritten}");
+        assert!(rewritten.contains("letterbox-remote://https%3A%2F%2Ft.example%2Fopen.gif"));
+        assert!(
+            rewritten.contains("URL( 'letterbox-remote://https%3A%2F%2Ft.example%2Fhero.png' )")
+        );
+        assert!(rewritten.contains(
+            r#"srcset="letterbox-remote://https%3A%2F%2Fcdn.example%2Fa.png 1x, b.png 2x""#
+        ));
+        assert!(rewritten.contains("url(cid:logo)"));
+        assert!(rewritten.contains(r#"src="cid:logo@example.com""#));
+        assert!(remote_resources(&rewritten).is_empty());
+    }
+
+    #[test]
+    fn moves_cid_references_and_reports_missing_parts() {
+        let assets = HashMap::from([(
+            "logo@example.com".to_string(),
+            InlineAsset {
+                content_type: "image/png".to_string(),
+                content: vec![1],
+            },
+        )]);
+        let html = r#"<img src="CID:logo%40example.com"><img src="cid:gone">
+            <div style="background: url('cid:gone')"></div>
+            <img src="https://t.example/a.gif">"#;
+        let (rewritten, unresolved) = rewrite_cid_references(html, "letterbox-asset://", &assets);
+        assert!(rewritten.contains(r#"src="letterbox-asset://logo%40example.com""#));
+        assert!(rewritten.contains(r#"src="letterbox-asset://gone""#));
+        assert!(rewritten.contains("url('letterbox-asset://gone')"));
+        assert!(rewritten.contains(r#"src="https://t.example/a.gif""#));
+        assert_eq!(unresolved, vec!["gone"]);
+    }
+}
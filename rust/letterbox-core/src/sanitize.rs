@@ -0,0 +1,227 @@
+//! HTML sanitization for rendering message bodies in a WebView.
+//!
+//! Bodies go through an allow-list of tags and attributes (ammonia), so
+//! scripts, event handlers, forms, iframes and embeds never reach the page.
+//! CSS is kept for layout but scrubbed of anything that loads from the
+//! network (`url()`, `image-set()`, `@import`) or pins content over the page
+//! (`position: fixed`), declaration by declaration.
+
+use ammonia::Builder;
+use scraper::{Html, Node};
+
+use crate::remote::is_remote;
+
+/// What [`sanitize_html`] keeps besides the safe structure and text.
+#[derive(Clone, Debug, uniffi::Record)]
+pub struct SanitizeOptions {
+    /// Keep `<style>` blocks and `style` attributes (scrubbed); when false all
+    /// CSS is dropped
+    pub allow_styles: bool,
+    /// Keep http(s) image sources; when false only `cid:` and `data:` images
+    /// remain, so rendering makes no network request
+    pub allow_remote_images: bool,
+}
+
+/// Tags email layouts rely on beyond ammonia's defaults.
+const EXTRA_TAGS: [&str; 2] = ["font", "tfoot"];
+
+/// Presentational attributes allowed on any tag. `background` is not among
+/// them: it loads an image.
+const EXTRA_ATTRIBUTES: [&str; 13] = [
+    "align",
+    "bgcolor",
+    "border",
+    "cellpadding",
+    "cellspacing",
+    "class",
+    "color",
+    "dir",
+    "face",
+    "height",
+    "size",
+    "valign",
+    "width",
+];
+
+/// CSS that loads from the network, runs code or overlays the page, matched
+/// against a declaration without whitespace, lowercased.
+const UNSAFE_CSS: [&str; 7] = [
+    "url(",
+    "image-set(",
+    "@import",
+    "expression(",
+    "-moz-binding",
+    "behavior:",
+    "position:fixed",
+];
+
+/// `html` reduced to what is safe to load in a WebView.
+pub fn sanitize_html(html: &str, options: &SanitizeOptions) -> String {
+    let allow_remote_images = options.allow_remote_images;
+    let mut builder = Builder::default();
+    builder
+        .add_tags(EXTRA_TAGS)
+        .add_generic_attributes(EXTRA_ATTRIBUTES)
+        .add_url_schemes(["cid", "data"])
+        .attribute_filter(move |element, attribute, value| match attribute {
+            "style" => {
+                let css = scrub_css(value);
+                (!css.trim().is_empty()).then(|| css.into())
+            }
+            "src" if element == "img" => {
+                (allow_remote_images || !is_remote(value)).then(|| value.into())
+            }
+            // Only images may be data: URIs; a data: link opens a page.
+            _ if value.trim_start().to_ascii_lowercase().starts_with("data:") => None,
+            _ => Some(value.into()),
+        });
+    if options.allow_styles {
+        builder
+            .add_tags(["style"])
+            .rm_clean_content_tags(["style"])
+            .add_generic_attributes(["style"]);
+        builder.clean(&scrub_style_blocks(html)).to_string()
+    } else {
+        builder.clean(html).to_string()
+    }
+}
+
+/// `html` with the CSS of its `<style>` blocks scrubbed.
+fn scrub_style_blocks(html: &str) -> String {
+    let mut document = Html::parse_document(html);
+    let texts: Vec<_> = document
+        .tree
+        .nodes()
+        .filter(|node| {
+            node.value()
+                .as_element()
+                .is_some_and(|element| element.name() == "style")
+        })
+        .flat_map(|style| style.children().map(|child| child.id()))
+        .collect();
+    for id in texts {
+        if let Some(mut node) = document.tree.get_mut(id) {
+            if let Node::Text(text) = node.value() {
+                text.text = scrub_css(&text.text).into();
+            }
+        }
+    }
+    document.html()
+}
+
+/// `css` without its unsafe declarations, rules and at-rules. Pieces between
+/// `;`, `{` and `}` are dropped whole, so a dropped selector leaves an
+/// invalid rule the browser ignores.
+fn scrub_css(css: &str) -> String {
+    let css = strip_comments(css);
+    let mut scrubbed = String::with_capacity(css.len());
+    let mut start = 0;
+    for (i, c) in css.char_indices() {
+        if matches!(c, ';' | '{' | '}') {
+            let piece = &css[start..i];
+            if !is_unsafe_css(piece) {
+                scrubbed.push_str(piece);
+                scrubbed.push(c);
+            } else if c != ';' {
+                scrubbed.push(c);
+            }
+            start = i + 1;
+        }
+    }
+    if !is_unsafe_css(&css[start..]) {
+        scrubbed.push_str(&css[start..]);
+    }
+    scrubbed
+}
+
+/// Whether a CSS piece is unsafe. Escapes are refused outright, as they
+/// could spell any of [`UNSAFE_CSS`].
+fn is_unsafe_css(piece: &str) -> bool {
+    let squashed: String = piece
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect();
+    squashed.contains('\\') || UNSAFE_CSS.iter().any(|p| squashed.contains(p))
+}
+
+/// `css` without `/* */` comments, which could split a keyword.
+fn strip_comments(css: &str) -> String {
+    let mut stripped = String::with_capacity(css.len());
+    let mut rest = css;
+    while let Some(open) = rest.find("/*") {
+        stripped.push_str(&rest[..open]);
+        match rest[open + 2..].find("*/") {
+            Some(close) => rest = &rest[open + 2 + close + 2..],
+            None => return stripped,
+        }
+    }
+    stripped.push_str(rest);
+    stripped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OPTIONS: SanitizeOptions = SanitizeOptions {
+        allow_styles: true,
+        allow_remote_images: true,
+    };
+
+    #[test]
+    fn strips_active_content() {
+        let html = r#"<p onclick="steal()">Hi<script>alert(1)</script></p>
+            <iframe src="https://evil.example"></iframe>
+            <form action="https://evil.example"><input name="pw"><button>Go</button></form>
+            <a href="javascript:alert(1)">x</a><a href="data:text/html,hi">y</a>
+            <a href="https://example.com">z</a>"#;
+        let clean = sanitize_html(html, &OPTIONS);
+        for gone in [
+            "onclick", "script", "alert", "iframe", "form", "input", "button", "data:",
+        ] {
+            assert!(!clean.contains(gone), "{gone} left in {clean}");
+        }
+        assert!(clean.contains("<p>Hi</p>"));
+        assert!(clean.contains(r#"href="https://example.com""#));
+    }
+
+    #[test]
+    fn scrubs_css_loads_and_overlays() {
+        let html = r#"<style>@import url(https://t.example/a.css);
+            .a { color: red; background: URL ( "https://t.example/p.gif" ) }
+            .b { position : FIXED; top: 0 } .c { b/**/ackground: u/**/rl(x) }</style>
+            <div style="color: blue; background-image: url('https://t.example/q.gif')">x</div>
+            <div style="position:fixed;z-index:9">y</div>"#;
+        let clean = sanitize_html(html, &OPTIONS);
+        assert!(!clean.contains("t.example"), "{clean}");
+        assert!(!clean.to_lowercase().contains("fixed"), "{clean}");
+        assert!(!clean.contains("rl(x)"), "{clean}");
+        assert!(clean.contains("color: red"));
+        assert!(clean.contains("top: 0"));
+        assert!(clean.contains(r#"style="color: blue;""#));
+        assert!(clean.contains(r#"<div style="z-index:9">y</div>"#));
+    }
+
+    #[test]
+    fn options_drop_styles_and_remote_images() {
+        let html = r#"<style>p { color: red }</style>
+            <p style="color: red">x</p>
+            <img src="https://cdn.example/a.png"><img src="cid:logo@example.com">
+            <img src="HTTP://cdn.example/b.png"><img src="  https://cdn.example/c.png">
+            <img src="//cdn.example/d.png">
+            <table background="https://t.example/bg.gif" width="600"><tr><td>y</td></tr></table>"#;
+        let clean = sanitize_html(
+            html,
+            &SanitizeOptions {
+                allow_styles: false,
+                allow_remote_images: false,
+            },
+        );
+        assert!(!clean.contains("color"), "{clean}");
+        assert!(!clean.contains("cdn.example"));
+        assert!(!clean.contains("t.example"));
+        assert!(clean.contains(r#"src="cid:logo@example.com""#));
+        assert!(clean.contains(r#"width="600""#));
+    }
+}
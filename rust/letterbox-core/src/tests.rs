@@ -0,0 +1,372 @@
+//! Tests of the handle's accessors, and sample messages the tests of other
+//! modules share.
+
+use super::*;
+use once_cell::sync::Lazy;
+
+pub(crate) static SIMPLE_EMAIL: Lazy<&'static str> = Lazy::new(|| {
+    "Subject: Hello\r\nFrom: sender@example.com\r\nTo: recipient@example.com\r\n\r\n<p>Body</p>"
+});
+
+pub(crate) static MULTIPART_EMAIL: Lazy<&'static str> = Lazy::new(|| {
+    r#"Subject: Test Multipart
+From: sender@example.com
+To: recipient@example.com
+MIME-Version: 1.0
+Content-Type: multipart/alternative; boundary="boundary"
+
+--boundary
+Content-Type: text/plain
+
+Plain text body
+--boundary
+Content-Type: text/html
+
+<html><body><p>HTML body</p></body></html>
+--boundary--
+"#
+});
+
+#[test]
+fn returns_none_for_missing_cid() {
+    let handle = parse_eml(SIMPLE_EMAIL.as_bytes().to_vec()).expect("should parse");
+    assert_eq!(handle.get_resource("nonexistent".to_string()), None);
+}
+
+static EMAIL_WITH_HEADERS: Lazy<&'static str> = Lazy::new(|| {
+    "Subject: Full Headers\r\n\
+     From: sender@example.com\r\n\
+     To: recipient@example.com\r\n\
+     Cc: cc@example.com\r\n\
+     Reply-To: reply@example.com\r\n\
+     Message-ID: <msg123@example.com>\r\n\r\n\
+     Body"
+});
+
+#[test]
+fn parses_extended_headers() {
+    let handle = parse_eml(EMAIL_WITH_HEADERS.as_bytes().to_vec()).expect("should parse");
+    assert_eq!(handle.cc(), "cc@example.com");
+    assert_eq!(handle.reply_to(), "reply@example.com");
+    assert_eq!(handle.message_id(), "msg123@example.com");
+}
+
+pub(crate) static EMAIL_WITH_ATTACHMENT: Lazy<&'static str> = Lazy::new(|| {
+    "Subject: With Attachment\r\n\
+     From: sender@example.com\r\n\
+     To: recipient@example.com\r\n\
+     MIME-Version: 1.0\r\n\
+     Content-Type: multipart/mixed; boundary=\"mixed-boundary\"\r\n\
+     \r\n\
+     --mixed-boundary\r\n\
+     Content-Type: text/plain\r\n\
+     \r\n\
+     Body text\r\n\
+     --mixed-boundary\r\n\
+     Content-Type: application/pdf\r\n\
+     Content-Disposition: attachment; filename=\"test.pdf\"\r\n\
+     Content-Transfer-Encoding: base64\r\n\
+     \r\n\
+     SGVsbG8gV29ybGQh\r\n\
+     --mixed-boundary--\r\n"
+});
+
+// Tests for new optimized FFI functions
+
+pub(crate) static EMAIL_WITH_INLINE_IMAGE: Lazy<&'static str> = Lazy::new(|| {
+    "Subject: Email with Inline Image\r\n\
+     From: sender@example.com\r\n\
+     To: recipient@example.com\r\n\
+     MIME-Version: 1.0\r\n\
+     Content-Type: multipart/related; boundary=\"related-boundary\"\r\n\
+     \r\n\
+     --related-boundary\r\n\
+     Content-Type: text/html\r\n\
+     \r\n\
+     <html><body><img src=\"cid:image001\"></body></html>\r\n\
+     --related-boundary\r\n\
+     Content-Type: image/png\r\n\
+     Content-ID: <image001>\r\n\
+     Content-Transfer-Encoding: base64\r\n\
+     \r\n\
+     iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAADUlEQVR42mNk+M9QDwADhgGAWjR9awAAAABJRU5ErkJggg==\r\n\
+     --related-boundary--\r\n"
+});
+
+#[test]
+fn get_resource_metadata_returns_inline_assets_info() {
+    let handle = parse_eml(EMAIL_WITH_INLINE_IMAGE.as_bytes().to_vec()).expect("should parse");
+
+    let metadata = handle.get_resource_metadata();
+    assert_eq!(metadata.len(), 1);
+
+    let meta = &metadata[0];
+    assert_eq!(meta.cid, "image001");
+    assert_eq!(meta.content_type, "image/png");
+    assert!(meta.size > 0);
+    assert!(meta.is_small); // Small test image should be under 64KB threshold
+}
+
+#[test]
+fn get_resource_content_type_returns_mime_type() {
+    let handle = parse_eml(EMAIL_WITH_INLINE_IMAGE.as_bytes().to_vec()).expect("should parse");
+
+    let content_type = handle.get_resource_content_type("image001".to_string());
+    assert_eq!(content_type, Some("image/png".to_string()));
+
+    // Non-existent CID should return None
+    let missing = handle.get_resource_content_type("nonexistent".to_string());
+    assert_eq!(missing, None);
+}
+
+#[test]
+fn write_attachment_to_path_returns_false_for_invalid_index() {
+    let handle = parse_eml(SIMPLE_EMAIL.as_bytes().to_vec()).expect("should parse");
+
+    let temp_dir = std::env::temp_dir();
+    let output_path = temp_dir.join("nonexistent_attachment.pdf");
+
+    // Try to write non-existent attachment
+    let result = handle.write_attachment_to_path(99, output_path.to_str().unwrap().to_string());
+    assert!(result.is_ok());
+    assert!(!result.unwrap()); // Should return false for missing attachment
+}
+
+#[test]
+fn write_resource_to_path_creates_file() {
+    let handle = parse_eml(EMAIL_WITH_INLINE_IMAGE.as_bytes().to_vec()).expect("should parse");
+
+    let temp_dir = std::env::temp_dir();
+    let output_path = temp_dir.join("test_resource.png");
+
+    // Write resource to file
+    let result = handle.write_resource_to_path(
+        "image001".to_string(),
+        output_path.to_str().unwrap().to_string(),
+    );
+    assert!(result.is_ok());
+    assert!(result.unwrap());
+
+    // Verify file exists and has content
+    assert!(output_path.exists());
+    let written = fs::read(&output_path).expect("read written file");
+    assert!(!written.is_empty());
+
+    // Cleanup
+    let _ = fs::remove_file(output_path);
+}
+
+#[test]
+fn write_resource_to_path_returns_false_for_missing_cid() {
+    let handle = parse_eml(SIMPLE_EMAIL.as_bytes().to_vec()).expect("should parse");
+
+    let temp_dir = std::env::temp_dir();
+    let output_path = temp_dir.join("missing_resource.png");
+
+    // Try to write non-existent resource
+    let result = handle.write_resource_to_path(
+        "nonexistent".to_string(),
+        output_path.to_str().unwrap().to_string(),
+    );
+    assert!(result.is_ok());
+    assert!(!result.unwrap()); // Should return false for missing CID
+}
+
+#[test]
+fn small_resource_threshold_is_64kb() {
+    assert_eq!(SMALL_RESOURCE_THRESHOLD, 64 * 1024);
+}
+
+// Additional edge case tests
+
+#[test]
+fn html_escape_handles_all_special_chars() {
+    // Test that html_escape handles all 5 required entities
+    let input = "Test & <script>alert('xss')</script> \"quotes\"";
+    let escaped = html_escape(input);
+    assert!(!escaped.contains('&') || escaped.contains("&amp;"));
+    assert!(!escaped.contains('<') || escaped.contains("&lt;"));
+    assert!(!escaped.contains('>') || escaped.contains("&gt;"));
+    assert!(!escaped.contains('"') || escaped.contains("&quot;"));
+    assert!(!escaped.contains('\'') || escaped.contains("&#39;"));
+}
+
+#[test]
+fn email_handle_thread_safe() {
+    // Test that EmailHandle can be shared across threads safely
+    let handle = parse_eml(SIMPLE_EMAIL.as_bytes().to_vec()).expect("should parse");
+    let handle_clone = handle.clone();
+
+    std::thread::spawn(move || {
+        let _ = handle_clone.subject();
+    })
+    .join()
+    .expect("thread should complete");
+
+    // Original handle should still work
+    assert_eq!(handle.subject(), "Hello");
+}
+
+pub(crate) static EMAIL_WITH_MULTIPLE_ATTACHMENTS: Lazy<&'static str> = Lazy::new(|| {
+    "Subject: Multiple Attachments\r\n\
+     From: sender@example.com\r\n\
+     To: recipient@example.com\r\n\
+     MIME-Version: 1.0\r\n\
+     Content-Type: multipart/mixed; boundary=\"mixed-boundary\"\r\n\
+     \r\n\
+     --mixed-boundary\r\n\
+     Content-Type: text/plain\r\n\
+     \r\n\
+     Body text\r\n\
+     --mixed-boundary\r\n\
+     Content-Type: application/pdf\r\n\
+     Content-Disposition: attachment; filename=\"doc1.pdf\"\r\n\
+     Content-Transfer-Encoding: base64\r\n\
+     \r\n\
+     SGVsbG8=\r\n\
+     --mixed-boundary\r\n\
+     Content-Type: image/png\r\n\
+     Content-Disposition: attachment; filename=\"image.png\"\r\n\
+     Content-Transfer-Encoding: base64\r\n\
+     \r\n\
+     iVBORw0K\r\n\
+     --mixed-boundary--\r\n"
+});
+
+#[test]
+fn get_attachment_content_invalid_index_returns_none() {
+    let handle = parse_eml(SIMPLE_EMAIL.as_bytes().to_vec()).expect("should parse");
+    // Email has no attachments, so any index is invalid
+    assert!(handle.get_attachment_content(0).is_none());
+    assert!(handle.get_attachment_content(100).is_none());
+}
+
+// Tests for structured address extraction (for search/filter)
+
+#[test]
+fn sender_info_extracts_email_and_name() {
+    let email = "Subject: Test\r\n\
+                 From: John Doe <john@example.com>\r\n\
+                 To: recipient@example.com\r\n\r\n\
+                 Body";
+    let handle = parse_eml(email.as_bytes().to_vec()).expect("should parse");
+    let sender = handle.sender_info();
+    assert_eq!(sender.email, "john@example.com");
+    assert_eq!(sender.name, "John Doe");
+}
+
+#[test]
+fn sender_info_handles_email_only() {
+    let email = "Subject: Test\r\n\
+                 From: sender@example.com\r\n\
+                 To: recipient@example.com\r\n\r\n\
+                 Body";
+    let handle = parse_eml(email.as_bytes().to_vec()).expect("should parse");
+    let sender = handle.sender_info();
+    assert_eq!(sender.email, "sender@example.com");
+    assert_eq!(sender.name, "");
+}
+
+#[test]
+fn sender_info_handles_missing_from() {
+    let email = "Subject: Test\r\n\
+                 To: recipient@example.com\r\n\r\n\
+                 Body";
+    let handle = parse_eml(email.as_bytes().to_vec()).expect("should parse");
+    let sender = handle.sender_info();
+    assert_eq!(sender.email, "");
+    assert_eq!(sender.name, "");
+}
+
+#[test]
+fn recipient_info_extracts_to_and_cc() {
+    let email = "Subject: Test\r\n\
+                 From: sender@example.com\r\n\
+                 To: Alice <alice@example.com>, bob@example.com\r\n\
+                 Cc: carol@example.com\r\n\r\n\
+                 Body";
+    let handle = parse_eml(email.as_bytes().to_vec()).expect("should parse");
+    let recipients = handle.recipient_info();
+    // Should have 3 recipients: Alice, bob, carol
+    assert_eq!(recipients.len(), 3);
+
+    // Check Alice
+    let alice = recipients.iter().find(|r| r.email == "alice@example.com");
+    assert!(alice.is_some());
+    assert_eq!(alice.unwrap().name, "Alice");
+
+    // Check bob (no name)
+    let bob = recipients.iter().find(|r| r.email == "bob@example.com");
+    assert!(bob.is_some());
+    assert_eq!(bob.unwrap().name, "");
+
+    // Check carol
+    let carol = recipients.iter().find(|r| r.email == "carol@example.com");
+    assert!(carol.is_some());
+}
+
+#[test]
+fn date_timestamp_parses_valid_date() {
+    let email = "Subject: Test\r\n\
+                 From: sender@example.com\r\n\
+                 Date: Mon, 11 Dec 2023 10:00:00 +0000\r\n\r\n\
+                 Body";
+    let handle = parse_eml(email.as_bytes().to_vec()).expect("should parse");
+    let ts = handle.date_timestamp();
+    // Should be a positive timestamp around Dec 2023
+    assert!(ts > 0);
+    // Timestamp should be in milliseconds (greater than 1 billion)
+    assert!(ts > 1_000_000_000_000);
+}
+
+#[test]
+fn date_timestamp_returns_zero_for_missing_date() {
+    let email = "Subject: Test\r\n\
+                 From: sender@example.com\r\n\r\n\
+                 Body";
+    let handle = parse_eml(email.as_bytes().to_vec()).expect("should parse");
+    let ts = handle.date_timestamp();
+    assert_eq!(ts, 0);
+}
+
+#[test]
+fn body_preview_returns_first_500_chars() {
+    // Create an email with a long body
+    let long_body = "a ".repeat(300); // 600 chars with spaces
+    let email = format!(
+        "Subject: Test\r\n\
+         From: sender@example.com\r\n\r\n\
+         {}",
+        long_body
+    );
+    let handle = parse_eml(email.as_bytes().to_vec()).expect("should parse");
+    let preview = handle.body_preview();
+    // Preview should be limited to ~500 chars worth of content
+    assert!(preview.len() <= 500);
+    // Should contain 'a' from the body
+    assert!(preview.contains('a'));
+}
+
+#[test]
+fn body_preview_returns_empty_for_html_only_email() {
+    let email = "Subject: Test\r\n\
+                 From: sender@example.com\r\n\
+                 Content-Type: text/html\r\n\r\n\
+                 <html><body>Hello</body></html>";
+    let handle = parse_eml(email.as_bytes().to_vec()).expect("should parse");
+    let preview = handle.body_preview();
+    // HTML-only emails may have body_text extracted from HTML
+    // This tests that the function doesn't crash
+    assert!(preview.is_empty() || preview.contains("Hello"));
+}
+
+#[test]
+fn body_preview_cleans_whitespace() {
+    let email = "Subject: Test\r\n\
+                 From: sender@example.com\r\n\r\n\
+                 Hello    World\n\nThis is   a test";
+    let handle = parse_eml(email.as_bytes().to_vec()).expect("should parse");
+    let preview = handle.body_preview();
+    // Should collapse whitespace
+    assert!(!preview.contains("    "));
+}
@@ -0,0 +1,245 @@
+//! Plain text bodies as HTML, for messages that have no HTML part.
+
+use crate::html_escape;
+
+/// Border and text colours of quote levels 1, 2, ..., cycling.
+const QUOTE_COLORS: [&str; 4] = ["#3465a4", "#75507b", "#4e9a06", "#c17d11"];
+
+/// `text` as an HTML document, with `*strong*` and `_emphasis_` marked up if
+/// `emphasis` is set.
+pub fn text_to_html(text: &str, emphasis: bool) -> String {
+    let mut html =
+        String::from("<html><body><div style=\"white-space: pre-wrap; font-family: sans-serif;\">");
+    let mut depth = 0;
+    let mut first = true;
+    for line in text.lines() {
+        let (level, line) = quote_level(line);
+        if level != depth {
+            // Quote tags sit between lines: a newline next to them would
+            // show as an extra blank line.
+            while depth > level {
+                html.push_str("</blockquote>");
+                depth -= 1;
+            }
+            while depth < level {
+                let color = QUOTE_COLORS[depth % QUOTE_COLORS.len()];
+                html.push_str(&format!(
+                    "<blockquote style=\"margin: 0; padding-left: 1ex; \
+                     border-left: 2px solid {color}; color: {color};\">"
+                ));
+                depth += 1;
+            }
+        } else if !first {
+            html.push('\n');
+        }
+        html.push_str(&format_line(line, emphasis));
+        first = false;
+    }
+    html.push_str(&"</blockquote>".repeat(depth));
+    html.push_str("</div></body></html>");
+    html
+}
+
+/// How many `>` quote `line`, and the line without them and the space after.
+fn quote_level(line: &str) -> (usize, &str) {
+    let mut level = 0;
+    let mut rest = line;
+    while let Some(after) = rest.trim_start_matches(' ').strip_prefix('>') {
+        level += 1;
+        rest = after;
+    }
+    if level > 0 {
+        rest = rest.strip_prefix(' ').unwrap_or(rest);
+    }
+    (level, if level > 0 { rest } else { line })
+}
+
+/// One line, escaped, with its URLs and addresses linked.
+fn format_line(line: &str, emphasis: bool) -> String {
+    let mut html = String::new();
+    let mut plain = String::new();
+    let flush = |html: &mut String, plain: &mut String| {
+        let escaped = html_escape(plain);
+        html.push_str(&if emphasis {
+            emphasize(&escaped)
+        } else {
+            escaped
+        });
+        plain.clear();
+    };
+    for piece in line.split_inclusive(char::is_whitespace) {
+        let word = piece.trim_end();
+        match find_link(word) {
+            Some((before, target, href, after)) => {
+                plain.push_str(before);
+                flush(&mut html, &mut plain);
+                html.push_str(&format!(
+                    "<a href=\"{}\">{}</a>",
+                    html_escape(&href),
+                    html_escape(target)
+                ));
+                plain.push_str(after);
+                plain.push_str(&piece[word.len()..]);
+            }
+            None => plain.push_str(piece),
+        }
+    }
+    flush(&mut html, &mut plain);
+    html
+}
+
+/// The link in `word`, if any: the text before it, the link text, its
+/// target, and the text after it (such as closing punctuation).
+fn find_link(word: &str) -> Option<(&str, &str, String, &str)> {
+    let start = word.len() - word.trim_start_matches(['(', '<', '[', '"', '\'']).len();
+    let mut end = word.len();
+    while let Some(last) = word[start..end].chars().last() {
+        let unbalanced_paren = last == ')'
+            && word[start..end].matches('(').count() < word[start..end].matches(')').count();
+        if matches!(
+            last,
+            '.' | ',' | ';' | ':' | '!' | '?' | '>' | ']' | '"' | '\''
+        ) || unbalanced_paren
+        {
+            end -= last.len_utf8();
+        } else {
+            break;
+        }
+    }
+    let target = &word[start..end];
+    let lower = target.to_ascii_lowercase();
+    let href = if (lower.starts_with("http://") || lower.starts_with("https://"))
+        && target.len() > "https://".len()
+    {
+        target.to_string()
+    } else if lower.starts_with("www.") && target[4..].contains('.') {
+        format!("https://{target}")
+    } else if is_email(target) {
+        format!("mailto:{target}")
+    } else {
+        return None;
+    };
+    Some((&word[..start], target, href, &word[end..]))
+}
+
+fn is_email(text: &str) -> bool {
+    let Some((local, domain)) = text.split_once('@') else {
+        return false;
+    };
+    !local.is_empty()
+        && local
+            .chars()
+            .all(|c| c.is_alphanumeric() || ".!#$%&'*+/=?^_`{|}~-".contains(c))
+        && domain.contains('.')
+        && !domain.starts_with('.')
+        && !domain.ends_with('.')
+        && domain
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '-' || c == '.')
+}
+
+/// `text` with `*strong*` and `_emphasis_` spans wrapped in tags, markers
+/// kept. A span opens after a non-alphanumeric character, closes before one,
+/// and neither starts nor ends with whitespace.
+fn emphasize(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(open) = rest.find(['*', '_']) {
+        let marker = if rest[open..].starts_with('*') {
+            '*'
+        } else {
+            '_'
+        };
+        let inside = &rest[open + 1..];
+        let opens = rest[..open]
+            .chars()
+            .last()
+            .is_none_or(|c| !c.is_alphanumeric())
+            && inside
+                .chars()
+                .next()
+                .is_some_and(|c| !c.is_whitespace() && c != marker);
+        let close = inside.find(marker).filter(|&close| {
+            !inside[..close].ends_with(char::is_whitespace)
+                && inside[close + 1..]
+                    .chars()
+                    .next()
+                    .is_none_or(|c| !c.is_alphanumeric())
+        });
+        match close.filter(|_| opens) {
+            Some(close) => {
+                let tag = if marker == '*' { "strong" } else { "em" };
+                out.push_str(&rest[..open]);
+                out.push_str(&format!(
+                    "<{tag}>{marker}{}{marker}</{tag}>",
+                    &inside[..close]
+                ));
+                rest = &inside[close + 1..];
+            }
+            None => {
+                out.push_str(&rest[..=open]);
+                rest = inside;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn links_urls_and_addresses() {
+        let html = format_line(
+            "See (https://example.com/a_(b)), www.example.org. or <me@example.com>! a < b",
+            false,
+        );
+        assert_eq!(
+            html,
+            "See (<a href=\"https://example.com/a_(b)\">https://example.com/a_(b)</a>), \
+             <a href=\"https://www.example.org\">www.example.org</a>. or \
+             &lt;<a href=\"mailto:me@example.com\">me@example.com</a>&gt;! a &lt; b"
+        );
+    }
+
+    #[test]
+    fn nests_quotes_by_level() {
+        let html = text_to_html("Hi\n> quoted\n>> deeper\n> back\nme", false);
+        let body = html
+            .strip_prefix(
+                "<html><body><div style=\"white-space: pre-wrap; font-family: sans-serif;\">",
+            )
+            .and_then(|body| body.strip_suffix("</div></body></html>"))
+            .unwrap();
+        let open = |color: &str| {
+            format!(
+                "<blockquote style=\"margin: 0; padding-left: 1ex; \
+                 border-left: 2px solid {color}; color: {color};\">"
+            )
+        };
+        assert_eq!(
+            body,
+            format!(
+                "Hi{}quoted{}deeper</blockquote>back</blockquote>me",
+                open(QUOTE_COLORS[0]),
+                open(QUOTE_COLORS[1])
+            )
+        );
+    }
+
+    #[test]
+    fn marks_up_emphasis_when_asked() {
+        assert_eq!(
+            format_line("a *very* _big_ deal, not snake_case or 2*3*4", true),
+            "a <strong>*very*</strong> <em>_big_</em> deal, not snake_case or 2*3*4"
+        );
+        assert_eq!(format_line("a *very* deal", false), "a *very* deal");
+        assert_eq!(
+            format_line("https://example.com/_x_ *see*", true),
+            "<a href=\"https://example.com/_x_\">https://example.com/_x_</a> \
+             <strong>*see*</strong>"
+        );
+    }
+}
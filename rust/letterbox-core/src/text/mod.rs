@@ -0,0 +1,300 @@
+//! Conversions between HTML and plain text bodies.
+//!
+//! [`html_to_text`] renders an HTML body as readable plain text for
+//! notifications, snippets and reply quoting: blocks and line breaks are kept,
+//! list items get bullets or numbers, blockquotes are quoted with `>`, and
+//! links become numbered footnotes listed after the text.
+//!
+//! [`text_to_html`] goes the other way for text-only messages: line breaks
+//! and spacing are kept, URLs and email addresses become links, `>` quotes
+//! become nested blockquotes styled by level, and `*strong*` and `_emphasis_`
+//! can optionally be marked up.
+
+mod html;
+
+use ego_tree::NodeRef;
+pub use html::text_to_html;
+use scraper::{Html, Node};
+
+/// Elements whose content is never text.
+const SKIPPED: [&str; 9] = [
+    "head", "title", "style", "script", "template", "noscript", "svg", "iframe", "object",
+];
+
+/// Elements set apart by a blank line.
+const PARAGRAPHS: [&str; 11] = [
+    "p",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "blockquote",
+    "pre",
+    "table",
+    "figure",
+];
+
+/// Elements that start and end a line.
+const BLOCKS: [&str; 19] = [
+    "div", "section", "article", "header", "footer", "nav", "aside", "main", "address", "center",
+    "ul", "ol", "dl", "dt", "dd", "li", "tr", "caption", "form",
+];
+
+/// `html` as plain text.
+pub fn html_to_text(html: &str) -> String {
+    let document = Html::parse_document(html);
+    let mut writer = TextWriter::default();
+    writer.walk(document.tree.root());
+    writer.finish()
+}
+
+#[derive(Default)]
+struct TextWriter {
+    out: String,
+    /// Line breaks owed before the next text.
+    breaks: usize,
+    /// Whitespace seen since the last text on the line.
+    space: bool,
+    /// Depth of `<blockquote>`s.
+    quote: usize,
+    /// Depth of `<blockquote>`s the last line was written at.
+    line_quote: usize,
+    /// Depth of lists.
+    indent: usize,
+    /// Bullet or number of a list item not written yet.
+    marker: Option<String>,
+    /// Inside `<pre>`: whitespace is kept.
+    pre: bool,
+    /// Footnoted link targets, numbered from 1.
+    links: Vec<String>,
+}
+
+impl TextWriter {
+    fn walk(&mut self, node: NodeRef<Node>) {
+        match node.value() {
+            Node::Text(text) => self.text(text),
+            Node::Element(element) => {
+                let name = element.name();
+                if SKIPPED.contains(&name) {
+                    return;
+                }
+                match name {
+                    "br" => self.line_break(),
+                    "hr" => {
+                        self.block(2);
+                        self.text("----");
+                        self.block(2);
+                    }
+                    "img" => {
+                        if let Some(alt) = element.attr("alt").filter(|a| !a.trim().is_empty()) {
+                            self.text(&format!("[{}]", alt.trim()));
+                        }
+                    }
+                    "ul" | "ol" => {
+                        self.block(1);
+                        self.indent += 1;
+                        let mut number = 0;
+                        for child in node.children() {
+                            let is_item =
+                                child.value().as_element().is_some_and(|e| e.name() == "li");
+                            if is_item {
+                                number += 1;
+                                self.marker = Some(if name == "ol" {
+                                    format!("{number}. ")
+                                } else {
+                                    "- ".to_string()
+                                });
+                            }
+                            self.walk(child);
+                        }
+                        self.indent -= 1;
+                        self.block(1);
+                    }
+                    "a" => {
+                        let start = self.out.len();
+                        self.children(node);
+                        if let Some(href) = element.attr("href").map(str::trim) {
+                            self.footnote(href, start);
+                        }
+                    }
+                    "td" | "th" => {
+                        if node.prev_sibling().is_some() {
+                            self.space = true;
+                        }
+                        self.children(node);
+                        self.space = true;
+                    }
+                    "blockquote" => {
+                        self.block(2);
+                        self.quote += 1;
+                        self.children(node);
+                        self.quote -= 1;
+                        self.block(2);
+                    }
+                    "pre" => {
+                        self.block(2);
+                        self.pre = true;
+                        self.children(node);
+                        self.pre = false;
+                        self.block(2);
+                    }
+                    _ if PARAGRAPHS.contains(&name) || BLOCKS.contains(&name) => {
+                        let breaks = if PARAGRAPHS.contains(&name) { 2 } else { 1 };
+                        self.block(breaks);
+                        self.children(node);
+                        self.block(breaks);
+                    }
+                    _ => self.children(node),
+                }
+            }
+            _ => self.children(node),
+        }
+    }
+
+    fn children(&mut self, node: NodeRef<Node>) {
+        for child in node.children() {
+            self.walk(child);
+        }
+    }
+
+    /// End the current line, leaving `breaks - 1` blank lines before more
+    /// text.
+    fn block(&mut self, breaks: usize) {
+        self.breaks = self.breaks.max(breaks);
+        self.space = false;
+    }
+
+    fn line_break(&mut self) {
+        self.breaks += 1;
+        self.space = false;
+    }
+
+    fn text(&mut self, text: &str) {
+        for c in text.chars() {
+            if self.pre && c == '\n' {
+                self.line_break();
+            } else if c.is_whitespace() && !self.pre {
+                self.space = true;
+            } else {
+                self.start_text();
+                self.out.push(c);
+            }
+        }
+    }
+
+    /// Write what is owed before more text: line breaks with their quote and
+    /// indent prefix and any list marker, or a space.
+    fn start_text(&mut self) {
+        let new_line = self.out.is_empty() || self.breaks > 0;
+        if new_line {
+            if !self.out.is_empty() {
+                for i in 1..=self.breaks {
+                    // Blank lines stay quoted only between quoted lines.
+                    let quote = if i < self.breaks {
+                        self.quote.min(self.line_quote)
+                    } else {
+                        self.quote
+                    };
+                    self.out.truncate(self.out.trim_end_matches(' ').len());
+                    self.out.push('\n');
+                    self.out.push_str(&"> ".repeat(quote));
+                }
+            } else {
+                self.out.push_str(&"> ".repeat(self.quote));
+            }
+            self.line_quote = self.quote;
+            let indent = self.indent.saturating_sub(1) * 2;
+            self.out.extend(std::iter::repeat_n(' ', indent));
+            if let Some(marker) = self.marker.take() {
+                self.out.push_str(&marker);
+            } else if self.indent > 0 {
+                self.out.push_str("  ");
+            }
+        } else if self.space {
+            self.out.push(' ');
+        }
+        self.breaks = 0;
+        self.space = false;
+    }
+
+    /// Note `href` as a footnote of the link text written since `start`,
+    /// unless the text already shows it.
+    fn footnote(&mut self, href: &str, start: usize) {
+        let target = href.strip_prefix("mailto:").unwrap_or(href);
+        let lower = href.to_ascii_lowercase();
+        let linkable = ["http://", "https://", "mailto:"]
+            .iter()
+            .any(|scheme| lower.starts_with(scheme));
+        let shown = self.out[start..].trim();
+        if !linkable || shown == href || shown == target {
+            return;
+        }
+        let number = match self.links.iter().position(|link| link == href) {
+            Some(i) => i + 1,
+            None => {
+                self.links.push(href.to_string());
+                self.links.len()
+            }
+        };
+        if shown.is_empty() {
+            self.start_text();
+        } else {
+            self.out.push(' ');
+        }
+        self.out.push_str(&format!("[{number}]"));
+    }
+
+    fn finish(self) -> String {
+        let mut text = self.out.trim_end().to_string();
+        if !self.links.is_empty() {
+            text.push_str("\n\n");
+            for (i, link) in self.links.iter().enumerate() {
+                text.push_str(&format!("[{}] {link}\n", i + 1));
+            }
+            text.truncate(text.trim_end().len());
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_blocks_lists_and_quotes() {
+        let html = "<html><head><style>p { color: red }</style></head><body>\
+            <h1>Weekly  news</h1><p>First line<br>second\nline</p>\
+            <ul><li>One</li><li>Two<ol><li>Nested</li></ol></li></ul>\
+            <blockquote><p>Quoted</p><p>Twice</p></blockquote>\
+            <table><tr><td>A</td><td>B</td></tr></table><pre>a\n  b</pre>\
+            <script>ignored()</script></body></html>";
+        assert_eq!(
+            html_to_text(html),
+            "Weekly news\n\n\
+             First line\nsecond line\n\n\
+             - One\n- Two\n  1. Nested\n\n\
+             > Quoted\n>\n> Twice\n\n\
+             A B\n\n\
+             a\n  b"
+        );
+    }
+
+    #[test]
+    fn turns_links_into_footnotes() {
+        let html = r#"<p>Read <a href="https://example.com/a">the post</a> or
+            <a href="https://example.com/a">again</a>, mail
+            <a href="mailto:me@example.com">me@example.com</a>,
+            see <a href="https://example.com/b">https://example.com/b</a>
+            <a href="https://example.com/c"><img src="x.png"></a></p>"#;
+        assert_eq!(
+            html_to_text(html),
+            "Read the post [1] or again [1], mail me@example.com, see \
+             https://example.com/b [2]\n\n\
+             [1] https://example.com/a\n\
+             [2] https://example.com/c"
+        );
+    }
+}
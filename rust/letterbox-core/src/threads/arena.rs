@@ -0,0 +1,136 @@
+//! The containers JWZ threading links together, kept in one arena so links
+//! are indices rather than shared pointers.
+
+use std::collections::HashMap;
+
+/// A node while threading: a message, or a placeholder for a message ID.
+pub(super) struct Container {
+    pub(super) id: String,
+    pub(super) message: Option<usize>,
+    pub(super) parent: Option<usize>,
+    pub(super) children: Vec<usize>,
+}
+
+/// Every container of one threading run, addressed by index.
+#[derive(Default)]
+pub(super) struct Arena {
+    pub(super) containers: Vec<Container>,
+    by_id: HashMap<String, usize>,
+}
+
+impl Arena {
+    pub(super) fn push(&mut self, id: String) -> usize {
+        self.containers.push(Container {
+            id,
+            message: None,
+            parent: None,
+            children: Vec::new(),
+        });
+        self.containers.len() - 1
+    }
+
+    /// The container of message ID `id`, created if new.
+    pub(super) fn get(&mut self, id: &str) -> usize {
+        if let Some(&c) = self.by_id.get(id) {
+            return c;
+        }
+        let c = self.push(id.to_string());
+        self.by_id.insert(id.to_string(), c);
+        c
+    }
+
+    /// Whether `ancestor` is `c` or one of its ancestors.
+    fn reaches(&self, c: usize, ancestor: usize) -> bool {
+        let mut current = Some(c);
+        while let Some(node) = current {
+            if node == ancestor {
+                return true;
+            }
+            current = self.containers[node].parent;
+        }
+        false
+    }
+
+    pub(super) fn unlink(&mut self, c: usize) {
+        if let Some(parent) = self.containers[c].parent.take() {
+            self.containers[parent].children.retain(|&child| child != c);
+        }
+    }
+
+    /// Make `child` a child of `parent`, unless that would close a loop.
+    pub(super) fn link(&mut self, parent: usize, child: usize) {
+        // A childless container can only be its own ancestor, which spares
+        // walking up a long chain of references for every new link.
+        let closes_loop = if self.containers[child].children.is_empty() {
+            parent == child
+        } else {
+            self.reaches(parent, child)
+        };
+        if closes_loop {
+            return;
+        }
+        self.unlink(child);
+        self.containers[child].parent = Some(parent);
+        self.containers[parent].children.push(child);
+    }
+
+    /// Drop empty containers under `roots`, putting their children in their
+    /// place; at the root only an empty container with a single child is
+    /// replaced, so unrelated replies are not split up. Containers are
+    /// visited children first without recursion, as reference chains can be
+    /// arbitrarily deep.
+    pub(super) fn prune(&mut self, roots: Vec<usize>) -> Vec<usize> {
+        // What each visited container leaves in its parent's children.
+        let mut replacements: Vec<Vec<usize>> = vec![Vec::new(); self.containers.len()];
+        for &root in &roots {
+            self.containers[root].parent = None;
+        }
+        for c in self.post_order(&roots) {
+            let at_root = self.containers[c].parent.is_none();
+            let children = std::mem::take(&mut self.containers[c].children);
+            let children: Vec<usize> = children
+                .into_iter()
+                .flat_map(|child| std::mem::take(&mut replacements[child]))
+                .collect();
+            let dropped = self.containers[c].message.is_none() && (children.len() < 2 || !at_root);
+            if dropped {
+                replacements[c] = children;
+            } else {
+                for &child in &children {
+                    self.containers[child].parent = Some(c);
+                }
+                self.containers[c].children = children;
+                replacements[c] = vec![c];
+            }
+        }
+        let kept: Vec<usize> = roots
+            .into_iter()
+            .flat_map(|root| std::mem::take(&mut replacements[root]))
+            .collect();
+        for &root in &kept {
+            self.containers[root].parent = None;
+        }
+        kept
+    }
+
+    /// The containers under `roots`, each after its children.
+    pub(super) fn post_order(&self, roots: &[usize]) -> Vec<usize> {
+        let mut order = Vec::new();
+        let mut stack: Vec<(usize, bool)> = roots.iter().rev().map(|&c| (c, false)).collect();
+        while let Some((c, expanded)) = stack.pop() {
+            if expanded {
+                order.push(c);
+                continue;
+            }
+            stack.push((c, true));
+            stack.extend(
+                self.containers[c]
+                    .children
+                    .iter()
+                    .rev()
+                    .map(|&child| (child, false)),
+            );
+        }
+        order
+    }
+}
@@ -0,0 +1,402 @@
+//! Conversation threading over many messages.
+//!
+//! [`ThreadBuilder`] groups lightweight per-message records into threads with
+//! Jamie Zawinski's algorithm: messages are linked through their
+//! `References` and `In-Reply-To` IDs, messages known only by reference keep
+//! their place as empty nodes, empty nodes are pruned where that loses no
+//! structure, and replies whose parent is missing are gathered under a root
+//! with the same base subject.
+//!
+//! The result is flattened for FFI: nodes come in depth-first order, each
+//! pointing at its parent's position in the list.
+
+mod arena;
+
+use arena::Arena;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// What threading needs to know of one message.
+#[derive(Clone, Debug, Default, uniffi::Record)]
+pub struct ThreadMessage {
+    /// Message-ID, with or without angle brackets
+    pub message_id: String,
+    /// First message ID of In-Reply-To, empty if none
+    pub in_reply_to: String,
+    /// Message IDs of References, oldest first
+    pub references: Vec<String>,
+    pub subject: String,
+    /// Timestamp in milliseconds since Unix epoch, 0 if unknown
+    pub date_timestamp: i64,
+}
+
+/// One node of a thread tree.
+#[derive(Clone, Debug, PartialEq, Eq, uniffi::Record)]
+pub struct ThreadNode {
+    /// Index of the message among those added, or None for a message that is
+    /// only referenced by others
+    pub message_index: Option<u32>,
+    /// Message-ID of the node, without angle brackets; empty for a node
+    /// grouping replies to the same subject
+    pub message_id: String,
+    /// Position of the parent node in the returned list; None for a root
+    pub parent: Option<u32>,
+    /// 0 for a root, 1 for its replies, and so on
+    pub depth: u32,
+}
+
+/// Collects messages and threads them.
+#[derive(Default, uniffi::Object)]
+pub struct ThreadBuilder {
+    messages: Mutex<Vec<ThreadMessage>>,
+}
+
+#[uniffi::export]
+impl ThreadBuilder {
+    #[uniffi::constructor]
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self::default())
+    }
+
+    /// Add a message; its index is the number of messages added before it.
+    pub fn add(&self, message: ThreadMessage) {
+        if let Ok(mut messages) = self.messages.lock() {
+            messages.push(message);
+        }
+    }
+
+    /// Add several messages in order.
+    pub fn add_all(&self, messages: Vec<ThreadMessage>) {
+        if let Ok(mut added) = self.messages.lock() {
+            added.extend(messages);
+        }
+    }
+
+    /// Thread the messages added so far. Threads with the latest activity
+    /// come first; replies are ordered oldest first.
+    pub fn build(&self) -> Vec<ThreadNode> {
+        self.messages
+            .lock()
+            .map(|messages| thread(&messages))
+            .unwrap_or_default()
+    }
+}
+
+/// `id` without surrounding whitespace and angle brackets.
+fn normalize_id(id: &str) -> String {
+    id.trim()
+        .trim_start_matches('<')
+        .trim_end_matches('>')
+        .to_string()
+}
+
+/// `subject` without reply and forward prefixes, lowercased, and whether it
+/// had any.
+fn base_subject(subject: &str) -> (String, bool) {
+    let mut rest = subject.trim();
+    let mut reply = false;
+    loop {
+        let lower = rest.to_ascii_lowercase();
+        let Some(prefix) = ["re:", "fwd:", "fw:", "aw:"]
+            .iter()
+            .find(|prefix| lower.starts_with(*prefix))
+        else {
+            break;
+        };
+        rest = rest[prefix.len()..].trim_start();
+        reply = true;
+    }
+    (rest.to_lowercase(), reply)
+}
+
+fn thread(messages: &[ThreadMessage]) -> Vec<ThreadNode> {
+    let mut arena = Arena::default();
+
+    for (index, message) in messages.iter().enumerate() {
+        let id = normalize_id(&message.message_id);
+        let this = match (!id.is_empty()).then(|| arena.get(&id)) {
+            Some(c) if arena.containers[c].message.is_none() => c,
+            // No ID, or a duplicate: thread the message on its own.
+            _ => arena.push(id),
+        };
+        arena.containers[this].message = Some(index);
+
+        let mut references: Vec<String> = message
+            .references
+            .iter()
+            .map(|id| normalize_id(id))
+            .filter(|id| !id.is_empty())
+            .collect();
+        let in_reply_to = normalize_id(&message.in_reply_to);
+        if !in_reply_to.is_empty() && references.last() != Some(&in_reply_to) {
+            references.push(in_reply_to);
+        }
+
+        // Each reference is a reply to the one before, unless already placed.
+        let mut previous = None;
+        for reference in &references {
+            let c = arena.get(reference);
+            if let Some(parent) = previous {
+                if c != this && arena.containers[c].parent.is_none() {
+                    arena.link(parent, c);
+                }
+            }
+            previous = Some(c);
+        }
+
+        // The message's own references are the final word on its parent.
+        arena.unlink(this);
+        if let Some(parent) = previous.filter(|&parent| parent != this) {
+            arena.link(parent, this);
+        }
+    }
+
+    let roots: Vec<usize> = (0..arena.containers.len())
+        .filter(|&c| arena.containers[c].parent.is_none())
+        .collect();
+    let roots = arena.prune(roots);
+    let roots = group_by_subject(&mut arena, messages, roots);
+
+    // Latest activity of each subtree, to order threads and replies.
+    let (first, last) = dates(&arena, messages, &roots);
+
+    let mut roots = roots;
+    roots.sort_by_key(|&root| std::cmp::Reverse(last[root]));
+    flatten(&arena, &roots, &first)
+}
+
+/// Gather roots sharing a base subject: a reply goes under the original, and
+/// two originals or two replies under a new empty node.
+fn group_by_subject(
+    arena: &mut Arena,
+    messages: &[ThreadMessage],
+    roots: Vec<usize>,
+) -> Vec<usize> {
+    let subject_of = |arena: &Arena, c: usize| {
+        let container = &arena.containers[c];
+        container
+            .message
+            .or_else(|| {
+                container
+                    .children
+                    .first()
+                    .and_then(|&child| arena.containers[child].message)
+            })
+            .map(|index| base_subject(&messages[index].subject))
+    };
+
+    let mut by_subject: HashMap<String, usize> = HashMap::new();
+    let mut grouped = Vec::new();
+    for root in roots {
+        let Some((subject, reply)) = subject_of(arena, root).filter(|(s, _)| !s.is_empty()) else {
+            grouped.push(root);
+            continue;
+        };
+        let Some(&existing) = by_subject.get(&subject) else {
+            by_subject.insert(subject, grouped.len());
+            grouped.push(root);
+            continue;
+        };
+        let other = grouped[existing];
+        let other_reply = arena.containers[other]
+            .message
+            .is_some_and(|index| base_subject(&messages[index].subject).1);
+        if arena.containers[other].message.is_none() || (reply && !other_reply) {
+            arena.link(other, root);
+        } else if other_reply && !reply {
+            arena.link(root, other);
+            grouped[existing] = root;
+        } else {
+            let group = arena.push(String::new());
+            arena.link(group, other);
+            arena.link(group, root);
+            grouped[existing] = group;
+        }
+    }
+    grouped
+}
+
+/// The earliest and latest date under each container.
+fn dates(arena: &Arena, messages: &[ThreadMessage], roots: &[usize]) -> (Vec<i64>, Vec<i64>) {
+    let mut first = vec![i64::MAX; arena.containers.len()];
+    let mut last = vec![i64::MIN; arena.containers.len()];
+    for c in arena.post_order(roots) {
+        let container = &arena.containers[c];
+        if let Some(index) = container.message {
+            first[c] = first[c].min(messages[index].date_timestamp);
+            last[c] = last[c].max(messages[index].date_timestamp);
+        }
+        for &child in &container.children {
+            first[c] = first[c].min(first[child]);
+            last[c] = last[c].max(last[child]);
+        }
+    }
+    (first, last)
+}
+
+/// The trees under `roots` in depth-first order, replies by `first` date.
+fn flatten(arena: &Arena, roots: &[usize], first: &[i64]) -> Vec<ThreadNode> {
+    let mut nodes = Vec::new();
+    // (container, parent position, depth), next on top.
+    let mut stack: Vec<(usize, Option<u32>, u32)> =
+        roots.iter().rev().map(|&root| (root, None, 0)).collect();
+    while let Some((c, parent, depth)) = stack.pop() {
+        let container = &arena.containers[c];
+        let position = nodes.len() as u32;
+        nodes.push(ThreadNode {
+            message_index: container.message.map(|index| index as u32),
+            message_id: container.id.clone(),
+            parent,
+            depth,
+        });
+        let mut children = container.children.clone();
+        children.sort_by_key(|&child| first[child]);
+        stack.extend(
+            children
+                .into_iter()
+                .rev()
+                .map(|child| (child, Some(position), depth + 1)),
+        );
+    }
+    nodes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(id: &str, references: &[&str], subject: &str, date: i64) -> ThreadMessage {
+        ThreadMessage {
+            message_id: format!("<{id}>"),
+            in_reply_to: String::new(),
+            references: references.iter().map(|r| format!("<{r}>")).collect(),
+            subject: subject.to_string(),
+            date_timestamp: date,
+        }
+    }
+
+    /// `(message_index, depth)` of each node.
+    fn shape(nodes: &[ThreadNode]) -> Vec<(Option<u32>, u32)> {
+        nodes.iter().map(|n| (n.message_index, n.depth)).collect()
+    }
+
+    #[test]
+    fn threads_by_references_and_in_reply_to() {
+        let builder = ThreadBuilder::new();
+        builder.add(message("a", &[], "Plans", 1));
+        builder.add(message("c", &["a", "b"], "Re: Plans", 3));
+        let mut reply = message("b", &[], "Re: Plans", 2);
+        reply.in_reply_to = "<a>".to_string();
+        builder.add(reply);
+        builder.add(message("x", &[], "Other", 0));
+
+        let nodes = builder.build();
+        assert_eq!(
+            shape(&nodes),
+            vec![(Some(0), 0), (Some(2), 1), (Some(1), 2), (Some(3), 0)]
+        );
+        assert_eq!(nodes[1].parent, Some(0));
+        assert_eq!(nodes[2].parent, Some(1));
+        assert_eq!(nodes[2].message_id, "c");
+        assert_eq!(nodes[3].parent, None);
+    }
+
+    #[test]
+    fn keeps_missing_parents_that_join_replies() {
+        let builder = ThreadBuilder::new();
+        builder.add_all(vec![
+            message("b", &["gone"], "Re: Trip", 2),
+            message("c", &["gone"], "Re: Trip", 3),
+            message("d", &["lost"], "Re: Lunch", 4),
+        ]);
+
+        let nodes = builder.build();
+        // The lone reply to "lost" is promoted; "gone" still joins b and c.
+        assert_eq!(
+            shape(&nodes),
+            vec![(Some(2), 0), (None, 0), (Some(0), 1), (Some(1), 1)]
+        );
+        assert_eq!(nodes[1].message_id, "gone");
+    }
+
+    #[test]
+    fn gathers_replies_by_subject() {
+        let builder = ThreadBuilder::new();
+        builder.add_all(vec![
+            message("r", &[], "RE: Fwd: Budget", 5),
+            message("o", &[], "Budget", 1),
+            message("s", &[], "Budget", 2),
+        ]);
+
+        let nodes = builder.build();
+        assert_eq!(
+            shape(&nodes),
+            vec![(None, 0), (Some(1), 1), (Some(0), 2), (Some(2), 1)]
+        );
+    }
+
+    #[test]
+    fn survives_reference_loops_and_duplicates() {
+        let builder = ThreadBuilder::new();
+        builder.add_all(vec![
+            message("a", &["b"], "", 1),
+            message("b", &["a"], "", 2),
+            message("a", &[], "", 3),
+            message("", &[], "", 4),
+        ]);
+
+        let nodes = builder.build();
+        assert_eq!(nodes.len(), 4);
+        let mut indexes: Vec<u32> = nodes.iter().filter_map(|n| n.message_index).collect();
+        indexes.sort_unstable();
+        assert_eq!(indexes, vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn base_subjects_drop_reply_prefixes() {
+        assert_eq!(
+            base_subject("Re: RE:Fwd:  Hello"),
+            ("hello".to_string(), true)
+        );
+        assert_eq!(base_subject("Hello"), ("hello".to_string(), false));
+    }
+
+    #[test]
+    fn threads_very_long_reference_chains() {
+        let ids: Vec<String> = (0..100_000).map(|n| format!("<{n}@example.com>")).collect();
+        let builder = ThreadBuilder::new();
+        builder.add(ThreadMessage {
+            message_id: "<last@example.com>".to_string(),
+            references: ids.clone(),
+            subject: "Deep".to_string(),
+            ..ThreadMessage::default()
+        });
+        builder.add(ThreadMessage {
+            message_id: ids[0].clone(),
+            subject: "Deep".to_string(),
+            ..ThreadMessage::default()
+        });
+
+        // The empty containers between the two collapse.
+        let nodes = builder.build();
+        assert_eq!(shape(&nodes), vec![(Some(1), 0), (Some(0), 1)]);
+
+        // A hundred thousand messages, each replying to the one before.
+        let builder = ThreadBuilder::new();
+        builder.add_all(
+            (0..100_000)
+                .map(|n| ThreadMessage {
+                    message_id: ids[n].clone(),
+                    in_reply_to: n.checked_sub(1).map(|p| ids[p].clone()).unwrap_or_default(),
+                    date_timestamp: n as i64,
+                    ..ThreadMessage::default()
+                })
+                .collect(),
+        );
+        let nodes = builder.build();
+        assert_eq!(nodes.len(), 100_000);
+        let last = nodes.last().unwrap();
+        assert_eq!(last.message_index, Some(99_999));
+        assert_eq!(last.depth, 99_999);
+    }
+}
@@ -0,0 +1,272 @@
+//! Benchmarks for the fetch pipeline.
+//!
+//! - `cache_hit`: a fetch answered from the in-memory cache, end to end
+//!   through [`ProxyClient::fetch_image`].
+//! - `ffi_lower`: lowering an [`ImageResponse`] into the buffer UniFFI hands
+//!   to Kotlin, and lifting it back, for payloads up to 8 MiB.
+//! - `smoltcp_loopback`: TCP throughput between two smoltcp stacks joined by
+//!   [`VirtualDevice`] queues, the stack the tunnel runs on minus WireGuard.
+//! - `batch`: a batch of cache hits through [`ProxyClient::fetch_images_batch`],
+//!   which measures the batch scheduling overhead alone.
+//!
+//! Images come from the `mock` server, so nothing leaves the machine:
+//!
+//! ```bash
+//! cargo bench -p letterbox-proxy --features mock --bench fetch_pipeline
+//! ```
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use letterbox_proxy::test_support::{png, use_direct_network, MockServer, Route};
+use letterbox_proxy::{ImageResponse, ProxyClient, RequestPriority, UniFfiTag};
+use std::sync::Arc;
+use tokio::runtime::Runtime;
+
+/// Images in the batch benchmark.
+const BATCH_SIZE: usize = 64;
+
+/// A client connecting directly to `server`, with every one of `paths`
+/// fetched once so later fetches are cache hits.
+fn warm_client(
+    dir: &tempfile::TempDir,
+    runtime: &Runtime,
+    server: &MockServer,
+    paths: &[String],
+) -> Arc<ProxyClient> {
+    let client = ProxyClient::new(dir.path().to_string_lossy().into_owned(), 256).unwrap();
+    use_direct_network(&client).unwrap();
+    for path in paths {
+        runtime
+            .block_on(client.fetch_image(server.url(path), None, None, None))
+            .unwrap();
+    }
+    client
+}
+
+fn cache_hit(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+    let mut group = c.benchmark_group("cache_hit");
+    for side in [16, 256, 1024] {
+        let image = png(side, side);
+        let path = format!("/{side}.png");
+        let server = MockServer::start(vec![Route::ok(&path, "image/png", image.clone())]).unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let client = warm_client(&dir, &runtime, &server, std::slice::from_ref(&path));
+        let url = server.url(&path);
+
+        group.throughput(Throughput::Bytes(image.len() as u64));
+        group.bench_function(BenchmarkId::from_parameter(format!("{side}px")), |b| {
+            b.iter(|| {
+                let response = runtime
+                    .block_on(client.fetch_image(url.clone(), None, None, None))
+                    .unwrap();
+                assert!(response.from_cache);
+                response
+            })
+        });
+    }
+    group.finish();
+}
+
+fn ffi_lower(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+    let server = MockServer::start(vec![Route::ok("/a.png", "image/png", png(4, 4))]).unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let client = warm_client(&dir, &runtime, &server, &["/a.png".to_string()]);
+    let template = runtime
+        .block_on(client.fetch_image(server.url("/a.png"), None, None, None))
+        .unwrap();
+
+    let mut group = c.benchmark_group("ffi_lower");
+    for size in [64 << 10, 1 << 20, 8 << 20] {
+        let response = ImageResponse {
+            data: vec![0xA5; size],
+            ..template.clone()
+        };
+        group.throughput(Throughput::Bytes(size as u64));
+        group.bench_with_input(BenchmarkId::new("lower", size), &response, |b, response| {
+            b.iter(|| <ImageResponse as uniffi::Lower<UniFfiTag>>::lower(response.clone()))
+        });
+        group.bench_with_input(
+            BenchmarkId::new("round_trip", size),
+            &response,
+            |b, response| {
+                b.iter(|| {
+                    let buffer =
+                        <ImageResponse as uniffi::Lower<UniFfiTag>>::lower(response.clone());
+                    <ImageResponse as uniffi::Lift<UniFfiTag>>::try_lift(buffer).unwrap()
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+#[cfg(feature = "tunnel")]
+mod loopback {
+    use letterbox_proxy::tunnel::device::VirtualDevice;
+    use smoltcp::iface::{Config, Interface, SocketHandle, SocketSet};
+    use smoltcp::socket::tcp::{Socket, SocketBuffer};
+    use smoltcp::time::Instant;
+    use smoltcp::wire::{HardwareAddress, IpAddress, IpCidr};
+
+    /// Socket buffer size, as the tunnel uses.
+    const TCP_BUFFER_SIZE: usize = 65_535;
+
+    /// Port the server end listens on.
+    const PORT: u16 = 443;
+
+    /// One end of the link: an interface, its device and a single socket.
+    struct Host {
+        interface: Interface,
+        device: VirtualDevice,
+        sockets: SocketSet<'static>,
+        handle: SocketHandle,
+    }
+
+    impl Host {
+        fn new(address: IpAddress) -> Self {
+            let mut device = VirtualDevice::new();
+            let interface = {
+                let mut interface =
+                    Interface::new(Config::new(HardwareAddress::Ip), &mut device, Instant::ZERO);
+                interface.update_ip_addrs(|addrs| {
+                    let _ = addrs.push(IpCidr::new(address, 24));
+                });
+                interface
+            };
+            let mut sockets = SocketSet::new(Vec::new());
+            let handle = sockets.add(Socket::new(
+                SocketBuffer::new(vec![0; TCP_BUFFER_SIZE]),
+                SocketBuffer::new(vec![0; TCP_BUFFER_SIZE]),
+            ));
+            Self {
+                interface,
+                device,
+                sockets,
+                handle,
+            }
+        }
+
+        fn socket(&mut self) -> &mut Socket<'static> {
+            self.sockets.get_mut::<Socket>(self.handle)
+        }
+
+        fn poll(&mut self, now: Instant) {
+            self.interface
+                .poll(now, &mut self.device, &mut self.sockets);
+        }
+    }
+
+    /// A connected client and server on `10.0.0.0/24`.
+    pub struct Link {
+        client: Host,
+        server: Host,
+        now: Instant,
+    }
+
+    impl Link {
+        pub fn connect() -> Self {
+            let server_ip = IpAddress::v4(10, 0, 0, 1);
+            let mut link = Self {
+                client: Host::new(IpAddress::v4(10, 0, 0, 2)),
+                server: Host::new(server_ip),
+                now: Instant::ZERO,
+            };
+            link.server.socket().listen(PORT).unwrap();
+            let context = link.client.interface.context();
+            link.client
+                .sockets
+                .get_mut::<Socket>(link.client.handle)
+                .connect(context, (server_ip, PORT), 49_152)
+                .unwrap();
+            while !(link.client.socket().may_send() && link.server.socket().may_recv()) {
+                link.step();
+            }
+            link
+        }
+
+        /// Poll both stacks and carry the packets they emitted across.
+        fn step(&mut self) {
+            self.now += smoltcp::time::Duration::from_millis(1);
+            self.client.poll(self.now);
+            while let Some(packet) = self.client.device.pop_outbound() {
+                self.server.device.push_inbound(packet);
+            }
+            self.server.poll(self.now);
+            while let Some(packet) = self.server.device.pop_outbound() {
+                self.client.device.push_inbound(packet);
+            }
+        }
+
+        /// Send `payload` from the client and read it all on the server.
+        pub fn transfer(&mut self, payload: &[u8]) {
+            let (mut sent, mut received) = (0, 0);
+            while received < payload.len() {
+                if sent < payload.len() {
+                    sent += self.client.socket().send_slice(&payload[sent..]).unwrap();
+                }
+                self.step();
+                received += self
+                    .server
+                    .socket()
+                    .recv(|buffer| (buffer.len(), buffer.len()))
+                    .unwrap();
+            }
+        }
+    }
+}
+
+#[cfg(feature = "tunnel")]
+fn smoltcp_loopback(c: &mut Criterion) {
+    let mut group = c.benchmark_group("smoltcp_loopback");
+    for size in [64 << 10, 1 << 20] {
+        let payload = vec![0x5A; size];
+        let mut link = loopback::Link::connect();
+        group.throughput(Throughput::Bytes(size as u64));
+        group.bench_function(BenchmarkId::from_parameter(size), |b| {
+            b.iter(|| link.transfer(&payload))
+        });
+    }
+    group.finish();
+}
+
+#[cfg(not(feature = "tunnel"))]
+fn smoltcp_loopback(_: &mut Criterion) {}
+
+fn batch(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+    let paths: Vec<String> = (0..BATCH_SIZE).map(|i| format!("/{i}.png")).collect();
+    let image = png(64, 64);
+    let server = MockServer::start(
+        paths
+            .iter()
+            .map(|path| Route::ok(path, "image/png", image.clone()))
+            .collect(),
+    )
+    .unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let client = warm_client(&dir, &runtime, &server, &paths);
+    let urls: Vec<String> = paths.iter().map(|path| server.url(path)).collect();
+
+    let mut group = c.benchmark_group("batch");
+    group.throughput(Throughput::Elements(BATCH_SIZE as u64));
+    for max_concurrent in [1, 8, 64] {
+        group.bench_function(BenchmarkId::new("cached", max_concurrent), |b| {
+            b.iter(|| {
+                let batch = Arc::clone(&client).fetch_images_batch(
+                    urls.clone(),
+                    max_concurrent,
+                    RequestPriority::Visible,
+                    None,
+                );
+                let results = runtime.block_on(batch.results()).unwrap();
+                assert!(results.iter().all(|result| result.success));
+                results
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, cache_hit, ffi_lower, smoltcp_loopback, batch);
+criterion_main!(benches);
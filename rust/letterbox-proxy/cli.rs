@@ -0,0 +1,376 @@
+//! Desktop command line for exercising WARP provisioning and the tunnel
+//! without building the Android app.
+//!
+//! ```text
+//! letterbox-proxy-cli [--storage DIR] [--bind ADDRESS] [--verbose] <command>
+//!
+//!   provision [--force]   register a WARP identity, or a new one with --force
+//!   status                show the proxy status and stored identity
+//!   fetch URL [-o FILE]   fetch URL through the tunnel
+//!   self-test             check provisioning TLS, the handshake and a fetch
+//!   export-conf [-o FILE] write the identity as a WireGuard config
+//! ```
+//!
+//! State lives in the storage directory exactly as on a device, so an identity
+//! provisioned once is reused by later commands. `export-conf` writes the
+//! WireGuard private key: keep its output private and never commit it.
+
+use letterbox_proxy::logging::{proxy_set_log_listener, LogLevel, LogListener};
+use letterbox_proxy::selftest::{proxy_tls_self_test, TlsSelfTestOutcome};
+use letterbox_proxy::types::WarpStoredConfig;
+use letterbox_proxy::{NetworkBinding, ProxyClient, ProxyError};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::sync::Arc;
+use std::time::Instant;
+
+const USAGE: &str = "\
+usage: letterbox-proxy-cli [--storage DIR] [--bind ADDRESS] [--verbose] <command>
+
+commands:
+  provision [--force]    register a WARP identity, or a new one with --force
+  status                 show the proxy status and stored identity
+  fetch URL [-o FILE]    fetch URL through the tunnel
+  self-test              check provisioning TLS, the handshake and a fetch
+  export-conf [-o FILE]  write the identity as a WireGuard config
+
+options:
+  --storage DIR          where the identity and cache live (default: ./letterbox-proxy-data)
+  --bind ADDRESS         send the tunnel from this local IPv4 address
+  -v, --verbose          log at debug level to stderr";
+
+/// Storage directory used when `--storage` is not given.
+const DEFAULT_STORAGE: &str = "letterbox-proxy-data";
+
+/// Fetched by `self-test` once the tunnel is up.
+const SELF_TEST_URL: &str = "https://www.cloudflare.com/favicon.ico";
+
+/// In-memory cache entries for the CLI's client.
+const CACHE_ENTRIES: u32 = 16;
+
+#[derive(Debug, PartialEq)]
+enum Command {
+    Provision { force: bool },
+    Status,
+    Fetch { url: String, out: Option<PathBuf> },
+    SelfTest,
+    ExportConf { out: Option<PathBuf> },
+}
+
+#[derive(Debug, PartialEq)]
+struct Args {
+    storage: PathBuf,
+    bind: Option<String>,
+    verbose: bool,
+    command: Command,
+}
+
+fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Args, String> {
+    let mut storage = PathBuf::from(DEFAULT_STORAGE);
+    let mut bind = None;
+    let mut verbose = false;
+    let mut force = false;
+    let mut out = None;
+    let mut positional = Vec::new();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--storage" => storage = args.next().ok_or("--storage needs a directory")?.into(),
+            "--bind" => bind = Some(args.next().ok_or("--bind needs an address")?),
+            "-o" | "--out" => out = Some(PathBuf::from(args.next().ok_or("-o needs a file")?)),
+            "-v" | "--verbose" => verbose = true,
+            "--force" => force = true,
+            flag if flag.starts_with('-') => return Err(format!("unknown option {flag}")),
+            _ => positional.push(arg),
+        }
+    }
+
+    let mut positional = positional.into_iter();
+    let name = positional.next().ok_or("missing command")?;
+    let command = match name.as_str() {
+        "provision" => Command::Provision { force },
+        "status" => Command::Status,
+        "fetch" => Command::Fetch {
+            url: positional.next().ok_or("fetch needs a URL")?,
+            out: out.take(),
+        },
+        "self-test" => Command::SelfTest,
+        "export-conf" => Command::ExportConf { out: out.take() },
+        other => return Err(format!("unknown command {other}")),
+    };
+    if let Some(extra) = positional.next() {
+        return Err(format!("unexpected argument {extra}"));
+    }
+    if force && !matches!(command, Command::Provision { .. }) {
+        return Err("--force only applies to provision".to_string());
+    }
+    if out.is_some() {
+        return Err(format!("-o does not apply to {name}"));
+    }
+    Ok(Args {
+        storage,
+        bind,
+        verbose,
+        command,
+    })
+}
+
+/// Writes log records to stderr.
+struct Stderr;
+
+impl LogListener for Stderr {
+    fn on_log(&self, level: LogLevel, target: String, message: String) {
+        eprintln!("{level:?} {target}: {message}");
+    }
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(message) => {
+            eprintln!("{message}\n\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+    let level = if args.verbose {
+        LogLevel::Debug
+    } else {
+        LogLevel::Warn
+    };
+    if let Err(e) = proxy_set_log_listener(Some(Arc::new(Stderr)), level) {
+        eprintln!("Logging unavailable: {e}");
+    }
+    match run(args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(args: Args) -> Result<(), ProxyError> {
+    std::fs::create_dir_all(&args.storage)?;
+    let storage = args.storage.to_string_lossy().into_owned();
+    let client = ProxyClient::new(storage, CACHE_ENTRIES)?;
+    if let Some(address) = args.bind {
+        client.set_network_binding(NetworkBinding::LocalAddress { address })?;
+    }
+    let result = match args.command {
+        Command::Provision { force } => provision(&client, force),
+        Command::Status => status(&client),
+        Command::Fetch { url, out } => fetch(&client, url, out),
+        Command::SelfTest => self_test(&client),
+        Command::ExportConf { out } => export_conf(&client, out),
+    };
+    // Persist the identity and disk cache even when the command failed.
+    let shutdown = client.shutdown();
+    result.and(shutdown)
+}
+
+fn provision(client: &ProxyClient, force: bool) -> Result<(), ProxyError> {
+    let stored = client.stored_config()?;
+    let stored = if stored.has_config && !force {
+        println!("Already provisioned; pass --force for a new identity");
+        stored
+    } else {
+        client.reset_identity()?
+    };
+    print_identity(&stored);
+    Ok(())
+}
+
+fn status(client: &ProxyClient) -> Result<(), ProxyError> {
+    let status = client.status()?;
+    println!("ready:            {}", status.ready);
+    println!("tunnel connected: {}", status.tunnel_connected);
+    println!(
+        "memory cache:     {} images, disk cache: {} entries / {} bytes",
+        status.cache_size, status.disk_cache_entries, status.disk_cache_bytes
+    );
+    if let Some(error) = status.last_error {
+        println!("last error:       {error}");
+    }
+    print_identity(&client.stored_config()?);
+    Ok(())
+}
+
+/// Everything about the identity except the private and license keys.
+fn print_identity(stored: &WarpStoredConfig) {
+    if !stored.has_config {
+        println!("No WARP identity; run `provision`");
+        return;
+    }
+    println!(
+        "account:          {} ({})",
+        stored.account_id, stored.account_type
+    );
+    println!("warp enabled:     {}", stored.warp_enabled);
+    println!("public key:       {}", stored.public_key);
+    println!("peer public key:  {}", stored.peer_public_key);
+    println!(
+        "endpoint:         {} ({}:{})",
+        stored.endpoint_host, stored.endpoint_ipv4, stored.endpoint_port
+    );
+    println!("tunnel address:   {}", stored.local_address_ipv4);
+    println!("config file:      {}", stored.config_file_path);
+}
+
+fn fetch(client: &ProxyClient, url: String, out: Option<PathBuf>) -> Result<(), ProxyError> {
+    let started = Instant::now();
+    let response = futures::executor::block_on(client.fetch_url(url, None))?;
+    println!("status:    {}", response.status);
+    println!("type:      {}", response.mime_type);
+    println!("bytes:     {}", response.data.len());
+    println!("final url: {}", response.final_url);
+    println!("trace id:  {}", response.trace_id);
+    println!("elapsed:   {} ms", started.elapsed().as_millis());
+    if let Some(out) = out {
+        std::fs::write(&out, &response.data)?;
+        println!("saved to   {}", out.display());
+    }
+    Ok(())
+}
+
+fn self_test(client: &ProxyClient) -> Result<(), ProxyError> {
+    match proxy_tls_self_test() {
+        TlsSelfTestOutcome::Verified => println!("provisioning TLS: verified"),
+        TlsSelfTestOutcome::Inconclusive { reason } => {
+            println!("provisioning TLS: inconclusive ({reason})")
+        }
+        TlsSelfTestOutcome::PlatformVerifierUninitialized { reason } => {
+            return Err(ProxyError::TlsError {
+                details: format!("provisioning reached the platform verifier: {reason}"),
+            });
+        }
+    }
+
+    let diagnostics = client.diagnostics()?;
+    println!(
+        "tunnel:           {} via {}:{}, last handshake {}",
+        diagnostics.connection_state,
+        diagnostics.endpoint_ipv4,
+        diagnostics.endpoint_port,
+        diagnostics
+            .last_handshake_secs
+            .map_or("never".to_string(), |secs| format!("{secs} s ago")),
+    );
+
+    let started = Instant::now();
+    let response = futures::executor::block_on(client.fetch_url(SELF_TEST_URL.to_string(), None))?;
+    println!(
+        "fetch:            HTTP {}, {} bytes in {} ms",
+        response.status,
+        response.data.len(),
+        started.elapsed().as_millis()
+    );
+    Ok(())
+}
+
+fn export_conf(client: &ProxyClient, out: Option<PathBuf>) -> Result<(), ProxyError> {
+    let stored = client.stored_config()?;
+    if !stored.has_config {
+        return Err(ProxyError::ProvisioningFailed {
+            details: "No WARP identity to export; run `provision` first".to_string(),
+        });
+    }
+    let conf = wireguard_conf(&stored);
+    eprintln!("The exported config contains the WireGuard private key; keep it private.");
+    match out {
+        Some(out) => std::fs::write(out, conf)?,
+        None => print!("{conf}"),
+    }
+    Ok(())
+}
+
+/// The identity as a `wg-quick` configuration routing all IPv4 through WARP,
+/// with the tunnel's MTU and the resolver its DNS runs against.
+fn wireguard_conf(stored: &WarpStoredConfig) -> String {
+    let address = &stored.local_address_ipv4;
+    let prefix = if address.contains('/') { "" } else { "/32" };
+    format!(
+        "[Interface]\n\
+         PrivateKey = {}\n\
+         Address = {address}{prefix}\n\
+         DNS = 1.1.1.1\n\
+         MTU = 1280\n\
+         \n\
+         [Peer]\n\
+         PublicKey = {}\n\
+         AllowedIPs = 0.0.0.0/0\n\
+         Endpoint = {}:{}\n",
+        stored.private_key, stored.peer_public_key, stored.endpoint_host, stored.endpoint_port
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(line: &str) -> Result<Args, String> {
+        parse_args(line.split_whitespace().map(str::to_string))
+    }
+
+    #[test]
+    fn parses_commands_and_options() {
+        assert_eq!(
+            parse("--storage /tmp/lb --bind 10.0.0.2 -v fetch https://a.test/x.png -o x.png")
+                .unwrap(),
+            Args {
+                storage: PathBuf::from("/tmp/lb"),
+                bind: Some("10.0.0.2".to_string()),
+                verbose: true,
+                command: Command::Fetch {
+                    url: "https://a.test/x.png".to_string(),
+                    out: Some(PathBuf::from("x.png")),
+                },
+            }
+        );
+        let provision = parse("provision --force").unwrap();
+        assert_eq!(provision.storage, PathBuf::from(DEFAULT_STORAGE));
+        assert_eq!(provision.command, Command::Provision { force: true });
+        assert_eq!(
+            parse("export-conf").unwrap().command,
+            Command::ExportConf { out: None }
+        );
+
+        for bad in [
+            "",
+            "fetch",
+            "status extra",
+            "status --force",
+            "status -o f",
+            "nope",
+            "-x status",
+        ] {
+            assert!(parse(bad).is_err(), "{bad:?} should not parse");
+        }
+    }
+
+    #[test]
+    fn renders_a_wg_quick_config() {
+        let stored = WarpStoredConfig {
+            has_config: true,
+            tunnel_active: false,
+            account_id: "acct".to_string(),
+            license_key: "license".to_string(),
+            private_key: "cHJpdmF0ZQ==".to_string(),
+            public_key: "cHVibGlj".to_string(),
+            peer_public_key: "cGVlcg==".to_string(),
+            endpoint_host: "engage.cloudflareclient.com".to_string(),
+            endpoint_ipv4: "162.159.192.1".to_string(),
+            endpoint_port: 2408,
+            local_address_ipv4: "172.16.0.2".to_string(),
+            warp_enabled: true,
+            account_type: "free".to_string(),
+            last_updated_secs: 0,
+            config_file_path: String::new(),
+        };
+        let conf = wireguard_conf(&stored);
+        assert!(conf.starts_with("[Interface]\nPrivateKey = cHJpdmF0ZQ==\n"));
+        assert!(conf.contains("Address = 172.16.0.2/32\n"));
+        assert!(conf.contains("[Peer]\nPublicKey = cGVlcg==\n"));
+        assert!(conf.ends_with("Endpoint = engage.cloudflareclient.com:2408\n"));
+        assert!(!conf.contains("license"));
+    }
+}
@@ -0,0 +1,22 @@
+//! The HTTP/1.1 response reader used in tunnel mode: head, framing and
+//! content decoding.
+
+#![no_main]
+
+use letterbox_proxy::timeouts::Deadline;
+use letterbox_proxy::tunnel::http1::read_response;
+use letterbox_proxy::{ProxyError, TimeoutPhase};
+use libfuzzer_sys::fuzz_target;
+use std::time::Duration;
+
+fuzz_target!(|data: &[u8]| {
+    let deadline = Deadline::fixed(TimeoutPhase::Body, Duration::from_secs(30));
+    let mut body = Vec::new();
+    let _ = read_response(&mut &data[..], 1 << 20, &mut body, &deadline, |e| {
+        ProxyError::HttpError {
+            status_code: 0,
+            details: e.to_string(),
+        }
+    });
+    assert!(body.len() <= 1 << 20);
+});
@@ -0,0 +1,17 @@
+//! Magic-byte sniffing of response bodies.
+
+#![no_main]
+
+use letterbox_proxy::sniff::{effective_image_mime, guess_mime_type, validate_image_data};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Some(mime) = guess_mime_type(data) {
+        assert!(validate_image_data(data, mime), "{mime} does not validate");
+    }
+    for claimed in ["image/png", "image/svg+xml", "application/octet-stream"] {
+        if let Some(mime) = effective_image_mime(claimed, data) {
+            assert!(mime.starts_with("image/"), "{mime} is not an image type");
+        }
+    }
+});
@@ -0,0 +1,32 @@
+//! The loop decrypting datagrams received from the WARP peer.
+
+#![no_main]
+
+use boringtun::noise::Tunn;
+use boringtun::x25519::{PublicKey, StaticSecret};
+use letterbox_proxy::tunnel::transport::{decapsulate, MAX_DATAGRAM};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let peer = StaticSecret::from([2; 32]);
+    let mut tunnel = Tunn::new(
+        StaticSecret::from([1; 32]),
+        PublicKey::from(&peer),
+        None,
+        None,
+        0,
+        None,
+    );
+    let mut datagram = data.to_vec();
+    let mut scratch = vec![0; MAX_DATAGRAM];
+    let mut packets = Vec::new();
+    decapsulate(
+        &mut tunnel,
+        &mut datagram,
+        &mut scratch,
+        |_| {},
+        &mut packets,
+    );
+    // Nothing authenticates without a session.
+    assert!(packets.is_empty());
+});
@@ -10,15 +10,17 @@
 //!   live tunnel, best-effort deletes the old Cloudflare device, generates a new
 //!   keypair, re-registers, and persists the fresh configuration.
 //!
-//! Both deliberately keep network I/O *outside* the global lock so a transient
+//! Both deliberately keep network I/O *outside* the state lock so a transient
 //! failure can never poison it, and so a slow Cloudflare round-trip never blocks
 //! unrelated callers.
 
+use crate::client::{default_client, ProxyClient};
 use crate::config::WarpConfig;
 use crate::error::ProxyError;
+use crate::events::ProxyEvent;
 use crate::provisioning::WarpProvisioner;
 use crate::types::WarpStoredConfig;
-use crate::{block_on, lock_state, ProxyState};
+use crate::{block_on, ProxyState};
 
 /// Build the FFI snapshot from the currently held state.
 ///
@@ -26,7 +28,7 @@ use crate::{block_on, lock_state, ProxyState};
 /// derived from the persisted private key; a derivation failure (corrupt key)
 /// degrades to an empty string rather than failing the whole snapshot, so the
 /// rest of the diagnostics still reach the user.
-fn snapshot(state: &ProxyState) -> WarpStoredConfig {
+pub(crate) fn snapshot(state: &ProxyState) -> WarpStoredConfig {
     let tunnel_active = state.manager.is_some();
     let config_file_path = state
         .config
@@ -73,68 +75,86 @@ fn snapshot(state: &ProxyState) -> WarpStoredConfig {
     }
 }
 
-/// Read the persisted WARP identity and tunnel configuration.
-///
-/// Never touches the network: this works whether or not the tunnel has
-/// connected, making it the primary tool for diagnosing a tunnel that refuses
-/// to come up.
 #[uniffi::export]
-pub fn proxy_stored_config() -> Result<WarpStoredConfig, ProxyError> {
-    let guard = lock_state();
-    let state = guard.as_ref().ok_or(ProxyError::NotInitialized)?;
-    Ok(snapshot(state))
-}
+impl ProxyClient {
+    /// Read the persisted WARP identity and tunnel configuration.
+    ///
+    /// Never touches the network: this works whether or not the tunnel has
+    /// connected, making it the primary tool for diagnosing a tunnel that refuses
+    /// to come up.
+    pub fn stored_config(&self) -> Result<WarpStoredConfig, ProxyError> {
+        let guard = self.lock_state();
+        let state = guard.as_ref().ok_or(ProxyError::NotInitialized)?;
+        Ok(snapshot(state))
+    }
 
-/// Refresh the WARP identity from scratch and persist it.
-///
-/// Steps, in order:
-/// 1. Tear down the live tunnel and clear the last error (under the lock).
-/// 2. Best-effort delete the old Cloudflare device (outside the lock).
-/// 3. Generate a new keypair, register, fetch config, enable WARP, and write
-///    the new `warp_config.json` (outside the lock).
-/// 4. Swap in the new configuration and force the tunnel to rebuild on next use.
-///
-/// The new tunnel is *not* eagerly reconnected: the caller can immediately
-/// follow up with [`crate::proxy_diagnostics`] to rebuild and verify it.
-#[uniffi::export]
-pub fn proxy_reset_identity() -> Result<WarpStoredConfig, ProxyError> {
-    // Phase 1: snapshot what we need and drop the existing tunnel under the lock.
-    let (storage_path, old_account) = {
-        let mut guard = lock_state();
-        let state = guard.as_mut().ok_or(ProxyError::NotInitialized)?;
-        // Dropping the manager's last `Arc` joins its worker thread.
-        state.manager = None;
-        state.last_error = None;
-        let old_account = state.config.warp_config.as_ref().map(|c| c.account.clone());
-        (state.config.storage_path.clone(), old_account)
-    };
+    /// Refresh the WARP identity from scratch and persist it.
+    ///
+    /// Steps, in order:
+    /// 1. Tear down the live tunnel and clear the last error (under the lock).
+    /// 2. Best-effort delete the old Cloudflare device (outside the lock).
+    /// 3. Generate a new keypair, register, fetch config, enable WARP, and write
+    ///    the new `warp_config.json` (outside the lock).
+    /// 4. Swap in the new configuration and force the tunnel to rebuild on next use.
+    ///
+    /// The new tunnel is *not* eagerly reconnected: the caller can immediately
+    /// follow up with [`Self::diagnostics`] to rebuild and verify it.
+    pub fn reset_identity(&self) -> Result<WarpStoredConfig, ProxyError> {
+        // Phase 1: snapshot what we need and drop the existing tunnel under the lock.
+        let (storage_path, old_account, manager) = {
+            let mut guard = self.lock_state();
+            let state = guard.as_mut().ok_or(ProxyError::NotInitialized)?;
+            // Dropping the manager's last `Arc` joins its worker thread.
+            let manager = state.manager.take();
+            state.last_error = None;
+            let old_account = state.config.warp_config.as_ref().map(|c| c.account.clone());
+            (state.config.storage_path.clone(), old_account, manager)
+        };
+        self.tunnel_dropped(manager, "identity reset");
 
-    // Phase 2 + 3: network I/O and persistence run without the lock held, so a
-    // failure here can never poison the global mutex.
-    let new_config = block_on(async move {
-        let provisioner = WarpProvisioner::new()?;
+        // Phase 2 + 3: network I/O and persistence run without the lock held, so a
+        // failure here can never poison the state mutex.
+        let new_config = block_on(async move {
+            let provisioner = WarpProvisioner::new()?;
 
-        if let Some(account) = old_account {
-            // Lingering devices are harmless but untidy; never fail the reset on
-            // a cleanup error (the old token may already be invalid).
-            if let Err(e) = provisioner.delete_device(&account).await {
-                log::warn!("Failed to delete old WARP device during reset: {e}");
+            if let Some(account) = old_account {
+                // Lingering devices are harmless but untidy; never fail the reset on
+                // a cleanup error (the old token may already be invalid).
+                if let Err(e) = provisioner.delete_device(&account).await {
+                    log::warn!("Failed to delete old WARP device during reset: {e}");
+                }
             }
-        }
 
-        let warp = provisioner.provision_new_account().await?;
-        let contents = serde_json::to_string_pretty(&warp)?;
-        let config_path = storage_path.join("warp_config.json");
-        tokio::fs::write(&config_path, contents).await?;
-        Ok::<WarpConfig, ProxyError>(warp)
-    })??;
+            let warp = provisioner.provision_new_account().await?;
+            let contents = serde_json::to_string_pretty(&warp)?;
+            let config_path = storage_path.join("warp_config.json");
+            tokio::fs::write(&config_path, contents).await?;
+            Ok::<WarpConfig, ProxyError>(warp)
+        })??;
+
+        self.events.emit(ProxyEvent::ProvisioningCompleted {
+            account_type: new_config.account_type.clone(),
+        });
 
-    // Phase 4: install the fresh configuration under the lock.
-    let mut guard = lock_state();
-    let state = guard.as_mut().ok_or(ProxyError::NotInitialized)?;
-    state.config.warp_enabled = new_config.warp_enabled;
-    state.config.endpoint_host = Some(new_config.peer.endpoint_host.clone());
-    state.config.warp_config = Some(new_config);
-    state.manager = None;
-    Ok(snapshot(state))
+        // Phase 4: install the fresh configuration under the lock.
+        let mut guard = self.lock_state();
+        let state = guard.as_mut().ok_or(ProxyError::NotInitialized)?;
+        state.config.warp_enabled = new_config.warp_enabled;
+        state.config.endpoint_host = Some(new_config.peer.endpoint_host.clone());
+        state.config.warp_config = Some(new_config);
+        state.manager = None;
+        Ok(snapshot(state))
+    }
+}
+
+/// [`ProxyClient::stored_config`] on the default proxy.
+#[uniffi::export]
+pub fn proxy_stored_config() -> Result<WarpStoredConfig, ProxyError> {
+    default_client().stored_config()
+}
+
+/// [`ProxyClient::reset_identity`] on the default proxy.
+#[uniffi::export]
+pub fn proxy_reset_identity() -> Result<WarpStoredConfig, ProxyError> {
+    default_client().reset_identity()
 }
@@ -0,0 +1,210 @@
+//! Animated image handling.
+//!
+//! Large animated GIFs auto-playing in an email body wreck scrolling, so
+//! [`ImageTransform::animation`](crate::types::ImageTransform) lets each
+//! request choose an [`AnimationPolicy`]: pass animations through, keep only
+//! the first frame, or refuse animations over a frame/byte budget with
+//! [`ProxyError::AnimationTooLarge`].
+//!
+//! Frames are counted from the container structure alone — GIF image
+//! descriptors, WebP `ANMF` chunks and the APNG `acTL` chunk — without decoding
+//! any pixels.
+
+use crate::error::ProxyError;
+use crate::types::AnimationPolicy;
+use image::{DynamicImage, ImageFormat};
+
+/// Number of frames in `data`; 1 for still images and unknown formats.
+pub fn frame_count(mime_type: &str, data: &[u8]) -> u32 {
+    let frames = match mime_type {
+        "image/gif" => gif_frames(data),
+        "image/webp" => webp_frames(data),
+        "image/png" | "image/apng" => apng_frames(data),
+        _ => None,
+    };
+    frames.unwrap_or(1).max(1)
+}
+
+/// Refuse an animation of `frames` frames and `size` bytes that exceeds the
+/// budget of [`AnimationPolicy::Budget`]; other policies accept everything.
+pub(crate) fn check_budget(
+    policy: AnimationPolicy,
+    frames: u32,
+    size: u64,
+) -> Result<(), ProxyError> {
+    match policy {
+        AnimationPolicy::Budget {
+            max_frames,
+            max_bytes,
+        } if frames > max_frames || size > max_bytes => Err(ProxyError::AnimationTooLarge {
+            frames,
+            size,
+            max_frames,
+            max_bytes,
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// Decode only the first frame of an animation.
+pub(crate) fn first_frame(mime_type: &str, data: &[u8]) -> Result<DynamicImage, ProxyError> {
+    let format = match mime_type {
+        "image/gif" => ImageFormat::Gif,
+        "image/webp" => ImageFormat::WebP,
+        _ => ImageFormat::Png,
+    };
+    image::load_from_memory_with_format(data, format).map_err(|e| {
+        ProxyError::ImageProcessingError {
+            details: e.to_string(),
+        }
+    })
+}
+
+/// Count complete image descriptors. A truncated file counts the frames
+/// before the cut.
+fn gif_frames(data: &[u8]) -> Option<u32> {
+    let flags = *data.get(10)?;
+    let mut pos = 13 + color_table_len(flags);
+    let mut frames = 0;
+    while let Some(end) = gif_block_end(data, pos) {
+        if data[pos] == 0x2C {
+            frames += 1;
+        }
+        pos = end;
+    }
+    Some(frames)
+}
+
+/// The end of the extension or image block at `pos`; `None` at the trailer,
+/// an unknown block or a truncation.
+fn gif_block_end(data: &[u8], pos: usize) -> Option<usize> {
+    match *data.get(pos)? {
+        // Extension: label byte, then data sub-blocks.
+        0x21 => skip_sub_blocks(data, pos + 2),
+        // Image descriptor, optional local colour table, LZW code size, data.
+        0x2C => {
+            let flags = *data.get(pos + 9)?;
+            skip_sub_blocks(data, pos + 10 + color_table_len(flags) + 1)
+        }
+        _ => None,
+    }
+}
+
+fn color_table_len(flags: u8) -> usize {
+    if flags & 0x80 != 0 {
+        3 << ((flags & 0x07) + 1)
+    } else {
+        0
+    }
+}
+
+/// Skip length-prefixed sub-blocks up to and including the terminator.
+fn skip_sub_blocks(data: &[u8], mut pos: usize) -> Option<usize> {
+    loop {
+        let len = *data.get(pos)? as usize;
+        pos += 1 + len;
+        if len == 0 {
+            return Some(pos);
+        }
+    }
+}
+
+/// Count `ANMF` chunks; still WebPs have none.
+fn webp_frames(data: &[u8]) -> Option<u32> {
+    if data.get(..4)? != b"RIFF" || data.get(8..12)? != b"WEBP" {
+        return None;
+    }
+    let mut pos = 12;
+    let mut frames = 0;
+    while let Some(header) = data.get(pos..pos + 8) {
+        if &header[..4] == b"ANMF" {
+            frames += 1;
+        }
+        let len = u32::from_le_bytes([header[4], header[5], header[6], header[7]]) as usize;
+        pos = pos.checked_add(8 + len + (len & 1))?;
+    }
+    Some(frames)
+}
+
+/// `num_frames` from an `acTL` chunk ahead of the image data.
+fn apng_frames(data: &[u8]) -> Option<u32> {
+    let mut pos = 8;
+    while let Some(header) = data.get(pos..pos + 8) {
+        let len = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as usize;
+        match &header[4..] {
+            b"acTL" => {
+                let count = data.get(pos + 8..pos + 12)?;
+                return Some(u32::from_be_bytes([count[0], count[1], count[2], count[3]]));
+            }
+            b"IDAT" => return None,
+            _ => pos = pos.checked_add(12 + len)?,
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A GIF with a global colour table and `n` 1x1 frames.
+    fn gif(n: usize) -> Vec<u8> {
+        let mut gif = b"GIF89a\x01\x00\x01\x00\x80\x00\x00".to_vec();
+        gif.extend_from_slice(&[0, 0, 0, 255, 255, 255]);
+        // NETSCAPE looping extension.
+        gif.extend_from_slice(b"\x21\xFF\x0BNETSCAPE2.0\x03\x01\x00\x00\x00");
+        for _ in 0..n {
+            gif.extend_from_slice(b"\x21\xF9\x04\x00\x0A\x00\x00\x00");
+            gif.extend_from_slice(b"\x2C\x00\x00\x00\x00\x01\x00\x01\x00\x00");
+            gif.extend_from_slice(b"\x02\x02\x44\x01\x00");
+        }
+        gif.push(0x3B);
+        gif
+    }
+
+    #[test]
+    fn counts_gif_frames() {
+        assert_eq!(frame_count("image/gif", &gif(1)), 1);
+        assert_eq!(frame_count("image/gif", &gif(3)), 3);
+        // Truncated: counts what is there.
+        let three = gif(3);
+        assert_eq!(frame_count("image/gif", &three[..three.len() - 10]), 2);
+    }
+
+    #[test]
+    fn counts_webp_and_apng_frames() {
+        let mut webp = b"RIFF\0\0\0\0WEBPVP8X\x0a\0\0\0\x02\0\0\0\0\0\0\0\0\0".to_vec();
+        for _ in 0..4 {
+            webp.extend_from_slice(b"ANMF\x01\0\0\0\0\0");
+        }
+        assert_eq!(frame_count("image/webp", &webp), 4);
+
+        let mut apng = b"\x89PNG\r\n\x1a\n".to_vec();
+        apng.extend_from_slice(b"\0\0\0\x0dIHDR\0\0\0\x01\0\0\0\x01\x08\x06\0\0\0CRC!");
+        apng.extend_from_slice(b"\0\0\0\x08acTL\0\0\0\x05\0\0\0\0CRC!");
+        assert_eq!(frame_count("image/png", &apng), 5);
+        assert_eq!(frame_count("image/png", b"\x89PNG\r\n\x1a\n"), 1);
+        assert_eq!(frame_count("image/jpeg", b"\xFF\xD8"), 1);
+    }
+
+    #[test]
+    fn budget_refuses_long_or_heavy_animations() {
+        let budget = AnimationPolicy::Budget {
+            max_frames: 10,
+            max_bytes: 1000,
+        };
+        assert!(check_budget(budget, 10, 1000).is_ok());
+        assert!(matches!(
+            check_budget(budget, 11, 10),
+            Err(ProxyError::AnimationTooLarge { frames: 11, .. })
+        ));
+        assert!(check_budget(budget, 2, 1001).is_err());
+        assert!(check_budget(AnimationPolicy::PassThrough, 1000, u64::MAX).is_ok());
+    }
+
+    #[test]
+    fn decodes_first_gif_frame() {
+        let frame = first_frame("image/gif", &gif(3)).unwrap();
+        assert_eq!((frame.width(), frame.height()), (1, 1));
+    }
+}
@@ -0,0 +1,369 @@
+//! An audit log of image fetches.
+//!
+//! So users can review what the proxy fetched on their behalf, every image
+//! request — network fetch or cache hit, successful or not — can be appended
+//! as one JSON line to `<storage>/audit.jsonl`: when, which host, over which
+//! transport, how many bytes, whether the cache answered and the outcome.
+//! URLs are not written, only their HMAC-SHA256 under a random salt kept in
+//! `<storage>/audit.salt`, so the log tells a known URL apart
+//! ([`ProxyClient::audit_url_hash`]) without listing the paths, and the
+//! tracking tokens in them, of every image viewed, and a copy of the log alone
+//! cannot be checked against a list of URLs. Clearing the log replaces the
+//! salt. `data:` URIs are decoded locally and not logged.
+//!
+//! The log is off until [`ProxyClient::set_audit_log`] gives it a size. When
+//! the file would outgrow that, it is renamed to `audit.1.jsonl`, replacing
+//! the previous one, so at most twice the size is kept. Like the bandwidth
+//! usage, the log names the hosts in plain text.
+
+use crate::bandwidth::host_of;
+use crate::cache::disk::hex;
+use crate::client::{default_client, ProxyClient};
+use crate::data_uri;
+use crate::error::ProxyError;
+use crate::types::{ImageResponse, Transport};
+use hmac::{Hmac, KeyInit, Mac};
+use serde::Serialize;
+use sha2::Sha256;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+
+/// Log file name inside the storage path.
+const LOG_FILE: &str = "audit.jsonl";
+
+/// The log file once rotated out.
+const ROTATED_FILE: &str = "audit.1.jsonl";
+
+/// File holding the salt of the URL hashes.
+const SALT_FILE: &str = "audit.salt";
+
+/// Length of the salt in bytes.
+const SALT_LEN: usize = 32;
+
+/// One line of the log.
+#[derive(Debug, Serialize)]
+struct Entry<'a> {
+    timestamp_ms: i64,
+    host: Option<String>,
+    /// HMAC-SHA256 of the requested URL under the salt, as lowercase hex.
+    url_hash: String,
+    transport: Option<&'static str>,
+    /// Bytes of the image returned.
+    bytes: u64,
+    /// `hit` or `miss`.
+    cache: &'static str,
+    /// `ok`, or the [`ErrorCode`](crate::error::ErrorCode) of the failure.
+    outcome: &'a str,
+}
+
+#[derive(Debug, Default)]
+struct Audit {
+    /// The storage path; `None` until the client is initialised.
+    dir: Option<PathBuf>,
+    /// Size the log file is rotated at; 0 while logging is off.
+    max_bytes: u64,
+    /// Key of the URL hashes, read from or written to [`SALT_FILE`].
+    salt: [u8; SALT_LEN],
+}
+
+impl Audit {
+    /// The hash logged for `url`.
+    fn url_hash(&self, url: &str) -> String {
+        match <Hmac<Sha256> as KeyInit>::new_from_slice(&self.salt) {
+            Ok(mut mac) => {
+                mac.update(url.as_bytes());
+                hex(&mac.finalize().into_bytes())
+            }
+            // HMAC takes keys of any length.
+            Err(_) => String::new(),
+        }
+    }
+
+    /// Pick a new salt and keep it in `dir`. Should writing it fail, the salt
+    /// still serves until the client is initialised again.
+    fn new_salt(&mut self, dir: &Path) {
+        self.salt = rand::random();
+        if let Err(e) = fs::write(dir.join(SALT_FILE), self.salt) {
+            log::warn!("Failed to store the audit log salt: {e}");
+        }
+    }
+
+    fn append(&self, line: &[u8]) -> io::Result<()> {
+        let Some(dir) = &self.dir else {
+            return Ok(());
+        };
+        let file = dir.join(LOG_FILE);
+        let size = fs::metadata(&file).map_or(0, |metadata| metadata.len());
+        if size > 0 && size + line.len() as u64 > self.max_bytes {
+            fs::rename(&file, dir.join(ROTATED_FILE))?;
+        }
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&file)?
+            .write_all(line)
+    }
+}
+
+/// A client's audit log.
+#[derive(Debug, Default)]
+pub(crate) struct AuditLog {
+    inner: Mutex<Audit>,
+}
+
+fn transport_name(transport: Transport) -> &'static str {
+    match transport {
+        Transport::Tunnel => "tunnel",
+        Transport::Direct => "direct",
+        Transport::Upstream => "upstream",
+    }
+}
+
+impl AuditLog {
+    fn lock(&self) -> MutexGuard<'_, Audit> {
+        self.inner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Keep the log under `storage_path`, reusing the salt stored there.
+    pub(crate) fn load(&self, storage_path: &Path) {
+        let mut audit = self.lock();
+        audit.dir = Some(storage_path.to_path_buf());
+        let stored = fs::read(storage_path.join(SALT_FILE))
+            .ok()
+            .and_then(|raw| <[u8; SALT_LEN]>::try_from(raw.as_slice()).ok());
+        match stored {
+            Some(salt) => audit.salt = salt,
+            None => audit.new_salt(storage_path),
+        }
+    }
+
+    /// Log the image request for `url` that ended in `result`, met over
+    /// `transport` if it failed.
+    pub(crate) fn record(
+        &self,
+        url: &str,
+        result: &Result<ImageResponse, ProxyError>,
+        transport: Option<Transport>,
+    ) {
+        let audit = self.lock();
+        if audit.max_bytes == 0 || data_uri::is_data_uri(url) {
+            return;
+        }
+        let code;
+        let (bytes, from_cache, transport, outcome) = match result {
+            Ok(response) => (
+                response.data.len() as u64,
+                response.from_cache,
+                response.transport,
+                "ok",
+            ),
+            Err(e) => {
+                code = format!("{:?}", e.code());
+                (0, false, transport, code.as_str())
+            }
+        };
+        let entry = Entry {
+            timestamp_ms: chrono::Utc::now().timestamp_millis(),
+            host: host_of(url),
+            url_hash: audit.url_hash(url),
+            transport: transport.map(transport_name),
+            bytes,
+            cache: if from_cache { "hit" } else { "miss" },
+            outcome,
+        };
+        let mut line = match serde_json::to_vec(&entry) {
+            Ok(line) => line,
+            Err(e) => {
+                log::warn!("Failed to encode an audit log entry: {e}");
+                return;
+            }
+        };
+        line.push(b'\n');
+        if let Err(e) = audit.append(&line) {
+            log::warn!("Failed to write the audit log: {e}");
+        }
+    }
+
+    /// The log files that exist, oldest first.
+    fn files(&self) -> Vec<PathBuf> {
+        let audit = self.lock();
+        let Some(dir) = &audit.dir else {
+            return Vec::new();
+        };
+        [ROTATED_FILE, LOG_FILE]
+            .into_iter()
+            .map(|name| dir.join(name))
+            .filter(|file| file.exists())
+            .collect()
+    }
+
+    /// Delete the log files and replace the salt, so that later entries
+    /// cannot be linked to deleted ones.
+    fn clear(&self) -> Result<(), ProxyError> {
+        for file in self.files() {
+            match fs::remove_file(file) {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e.into()),
+                _ => {}
+            }
+        }
+        let mut audit = self.lock();
+        if let Some(dir) = audit.dir.clone() {
+            audit.new_salt(&dir);
+        }
+        Ok(())
+    }
+}
+
+#[uniffi::export]
+impl ProxyClient {
+    /// Log every image request to `<storage>/audit.jsonl`, rotating the file
+    /// once it reaches `max_bytes`; 0 stops logging. Logs already written
+    /// stay until [`Self::clear_audit_log`].
+    pub fn set_audit_log(&self, max_bytes: u64) -> Result<(), ProxyError> {
+        if self.lock_state().is_none() {
+            return Err(ProxyError::NotInitialized);
+        }
+        self.audit.lock().max_bytes = max_bytes;
+        Ok(())
+    }
+
+    /// Paths of the audit log files, oldest first.
+    pub fn audit_log_files(&self) -> Vec<String> {
+        self.audit
+            .files()
+            .into_iter()
+            .map(|file| file.to_string_lossy().into_owned())
+            .collect()
+    }
+
+    /// The `url_hash` the audit log records for `url`, to find its entries.
+    pub fn audit_url_hash(&self, url: String) -> String {
+        self.audit.lock().url_hash(&url)
+    }
+
+    /// Delete the audit log files.
+    pub fn clear_audit_log(&self) -> Result<(), ProxyError> {
+        self.audit.clear()
+    }
+}
+
+/// [`ProxyClient::set_audit_log`] on the default proxy.
+#[uniffi::export]
+pub fn proxy_set_audit_log(max_bytes: u64) -> Result<(), ProxyError> {
+    default_client().set_audit_log(max_bytes)
+}
+
+/// [`ProxyClient::audit_log_files`] of the default proxy.
+#[uniffi::export]
+pub fn proxy_audit_log_files() -> Vec<String> {
+    default_client().audit_log_files()
+}
+
+/// [`ProxyClient::audit_url_hash`] of the default proxy.
+#[uniffi::export]
+pub fn proxy_audit_url_hash(url: String) -> String {
+    default_client().audit_url_hash(url)
+}
+
+/// [`ProxyClient::clear_audit_log`] on the default proxy.
+#[uniffi::export]
+pub fn proxy_clear_audit_log() -> Result<(), ProxyError> {
+    default_client().clear_audit_log()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(len: usize, from_cache: bool) -> ImageResponse {
+        ImageResponse {
+            mime_type: "image/png".to_string(),
+            data: vec![0; len],
+            from_cache,
+            final_url: String::new(),
+            redirect_chain: Vec::new(),
+            redirect_hops: Vec::new(),
+            width: None,
+            height: None,
+            blurhash: None,
+            tracking_suspected: false,
+            trace_id: String::new(),
+            headers: Default::default(),
+            sha256: String::new(),
+            transport: (!from_cache).then_some(Transport::Tunnel),
+        }
+    }
+
+    fn lines(path: &Path) -> Vec<serde_json::Value> {
+        fs::read_to_string(path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn logs_hashed_urls_and_rotates() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = AuditLog::default();
+        log.load(dir.path());
+        let url = "https://CDN.example/a.png?token=secret";
+        log.record(url, &Ok(response(10, false)), None);
+        assert!(log.files().is_empty(), "off until given a size");
+
+        log.lock().max_bytes = 600;
+        log.record(url, &Ok(response(10, false)), None);
+        log.record(url, &Ok(response(10, true)), None);
+        log.record(
+            "https://cdn.example/b.png",
+            &Err(ProxyError::Throttled),
+            Some(Transport::Direct),
+        );
+        log.record("data:image/png;base64,AAAA", &Ok(response(3, false)), None);
+
+        let entries = lines(&dir.path().join(LOG_FILE));
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0]["host"], "cdn.example");
+        let hash = log.lock().url_hash(url);
+        assert_eq!(entries[0]["url_hash"], hash);
+        assert_ne!(hash, crate::integrity::sha256_hex(url.as_bytes()));
+        assert_eq!(entries[0]["transport"], "tunnel");
+        assert_eq!(entries[0]["bytes"], 10);
+        assert_eq!(entries[0]["cache"], "miss");
+        assert_eq!(entries[0]["outcome"], "ok");
+        assert_eq!(entries[1]["cache"], "hit");
+        assert_eq!(entries[1]["transport"], serde_json::Value::Null);
+        assert_eq!(entries[2]["outcome"], "Throttled");
+        assert_eq!(entries[2]["transport"], "direct");
+        let written = fs::read_to_string(dir.path().join(LOG_FILE)).unwrap();
+        assert!(!written.contains("secret"));
+
+        log.record(url, &Ok(response(10, false)), None);
+        assert_eq!(lines(&dir.path().join(ROTATED_FILE)).len(), 3);
+        assert_eq!(lines(&dir.path().join(LOG_FILE)).len(), 1);
+        assert_eq!(log.files().len(), 2);
+        assert!(log.files()[0].ends_with(ROTATED_FILE));
+
+        log.clear().unwrap();
+        assert!(log.files().is_empty());
+        assert_ne!(log.lock().url_hash(url), hash, "clearing replaces the salt");
+    }
+
+    #[test]
+    fn the_salt_is_kept_per_install() {
+        let (dir, other) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+        let url = "https://cdn.example/a.png";
+        let hash = |dir: &Path| {
+            let log = AuditLog::default();
+            log.load(dir);
+            let hash = log.lock().url_hash(url);
+            hash
+        };
+        assert_eq!(hash(dir.path()), hash(dir.path()));
+        assert_ne!(hash(dir.path()), hash(other.path()));
+    }
+}
@@ -0,0 +1,321 @@
+//! Data usage per destination host.
+//!
+//! For users on metered connections: which senders' images cost the most
+//! data. The tunnel worker attributes the bytes it received while serving a
+//! request — the response, TLS and any DNS lookup it needed — to the host of
+//! the requested URL, split by whether they came through the WARP tunnel or
+//! directly. [`ProxyClient::bandwidth_usage`] lists the totals.
+//!
+//! The totals are kept in `<storage>/bandwidth.json`, reloaded when the client
+//! is initialised and written at most once a minute and on shutdown, so they
+//! add up across sessions until [`ProxyClient::reset_bandwidth_usage`]. Like
+//! the offline queue, the file names the hosts in plain text.
+
+use crate::client::{default_client, ProxyClient};
+use crate::error::ProxyError;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+/// Usage file name inside the storage path.
+const USAGE_FILE: &str = "bandwidth.json";
+
+/// Hosts tracked at most; the one that cost the least makes room for a new
+/// one.
+const MAX_HOSTS: usize = 2_000;
+
+/// Least time between two writes of the usage file while fetching.
+const SAVE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Data received from one host since the usage was last reset.
+#[derive(Clone, Debug, PartialEq, uniffi::Record)]
+pub struct HostBandwidth {
+    /// Host name of the requested URLs, lowercase.
+    pub host: String,
+    /// Bytes received through the WARP tunnel.
+    pub tunnel_bytes: u64,
+    /// Bytes received directly, in builds without the `tunnel` feature.
+    pub direct_bytes: u64,
+    /// Requests made to the host.
+    pub requests: u64,
+}
+
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+struct Usage {
+    tunnel_bytes: u64,
+    direct_bytes: u64,
+    requests: u64,
+}
+
+impl Usage {
+    fn total(&self) -> u64 {
+        self.tunnel_bytes.saturating_add(self.direct_bytes)
+    }
+}
+
+#[derive(Debug, Default)]
+struct Ledger {
+    hosts: HashMap<String, Usage>,
+    /// Where the usage is persisted; `None` until the client is initialised.
+    file: Option<PathBuf>,
+    /// When the file was last written, if it was.
+    saved: Option<Instant>,
+    dirty: bool,
+}
+
+impl Ledger {
+    fn save(&mut self) -> Result<(), ProxyError> {
+        let Some(file) = &self.file else {
+            return Ok(());
+        };
+        if self.dirty {
+            fs::write(file, serde_json::to_vec(&self.hosts)?)?;
+            self.dirty = false;
+        }
+        self.saved = Some(Instant::now());
+        Ok(())
+    }
+}
+
+/// The host a request to `url` is counted under, if it has one.
+pub(crate) fn host_of(url: &str) -> Option<String> {
+    url::Url::parse(url)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_ascii_lowercase))
+}
+
+/// A client's per-host usage, shared with its tunnel worker.
+#[derive(Debug, Default)]
+pub struct BandwidthLedger {
+    inner: Mutex<Ledger>,
+}
+
+impl BandwidthLedger {
+    fn lock(&self) -> MutexGuard<'_, Ledger> {
+        self.inner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Replace the usage with the one persisted under `storage_path`.
+    pub(crate) fn load(&self, storage_path: &Path) {
+        let file = storage_path.join(USAGE_FILE);
+        let hosts = match fs::read(&file) {
+            Ok(bytes) => serde_json::from_slice(&bytes).unwrap_or_else(|e| {
+                log::warn!("Discarding unreadable bandwidth usage: {e}");
+                HashMap::new()
+            }),
+            Err(_) => HashMap::new(),
+        };
+        let mut ledger = self.lock();
+        ledger.hosts = hosts;
+        ledger.file = Some(file);
+        ledger.saved = Some(Instant::now());
+        ledger.dirty = false;
+    }
+
+    /// Count `bytes` received for a request to `host`, through the tunnel or
+    /// directly.
+    pub(crate) fn record(&self, host: String, tunnelled: bool, bytes: u64) {
+        let mut ledger = self.lock();
+        if !ledger.hosts.contains_key(&host) && ledger.hosts.len() >= MAX_HOSTS {
+            let cheapest = ledger
+                .hosts
+                .iter()
+                .min_by_key(|(_, usage)| usage.total())
+                .map(|(host, _)| host.clone());
+            if let Some(cheapest) = cheapest {
+                ledger.hosts.remove(&cheapest);
+            }
+        }
+        let usage = ledger.hosts.entry(host).or_default();
+        if tunnelled {
+            usage.tunnel_bytes = usage.tunnel_bytes.saturating_add(bytes);
+        } else {
+            usage.direct_bytes = usage.direct_bytes.saturating_add(bytes);
+        }
+        usage.requests += 1;
+        ledger.dirty = true;
+        if ledger
+            .saved
+            .is_none_or(|saved| saved.elapsed() >= SAVE_INTERVAL)
+        {
+            if let Err(e) = ledger.save() {
+                log::warn!("Failed to save bandwidth usage: {e}");
+            }
+        }
+    }
+
+    /// Write the usage out if it changed since the last write.
+    pub(crate) fn flush(&self) -> Result<(), ProxyError> {
+        self.lock().save()
+    }
+
+    /// Every host, the costliest first.
+    fn snapshot(&self) -> Vec<HostBandwidth> {
+        let mut hosts: Vec<HostBandwidth> = self
+            .lock()
+            .hosts
+            .iter()
+            .map(|(host, usage)| HostBandwidth {
+                host: host.clone(),
+                tunnel_bytes: usage.tunnel_bytes,
+                direct_bytes: usage.direct_bytes,
+                requests: usage.requests,
+            })
+            .collect();
+        hosts.sort_by(|a, b| {
+            let total = |h: &HostBandwidth| h.tunnel_bytes.saturating_add(h.direct_bytes);
+            total(b).cmp(&total(a)).then_with(|| a.host.cmp(&b.host))
+        });
+        hosts
+    }
+
+    /// Forget all usage, on disk too.
+    fn reset(&self) -> Result<(), ProxyError> {
+        let mut ledger = self.lock();
+        ledger.hosts.clear();
+        ledger.dirty = false;
+        match &ledger.file {
+            Some(file) => match fs::remove_file(file) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+                _ => Ok(()),
+            },
+            None => Ok(()),
+        }
+    }
+}
+
+#[uniffi::export]
+impl ProxyClient {
+    /// Bytes received per destination host since the usage was last reset,
+    /// the costliest host first.
+    pub fn bandwidth_usage(&self) -> Vec<HostBandwidth> {
+        self.bandwidth.snapshot()
+    }
+
+    /// Forget the per-host usage, including what was persisted.
+    pub fn reset_bandwidth_usage(&self) -> Result<(), ProxyError> {
+        self.bandwidth.reset()
+    }
+}
+
+/// [`ProxyClient::bandwidth_usage`] of the default proxy.
+#[uniffi::export]
+pub fn proxy_bandwidth_usage() -> Vec<HostBandwidth> {
+    default_client().bandwidth_usage()
+}
+
+/// [`ProxyClient::reset_bandwidth_usage`] on the default proxy.
+#[uniffi::export]
+pub fn proxy_reset_bandwidth_usage() -> Result<(), ProxyError> {
+    default_client().reset_bandwidth_usage()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn usage_is_per_host_and_survives_a_reload() {
+        let dir = tempfile::tempdir().unwrap();
+        let ledger = BandwidthLedger::default();
+        ledger.load(dir.path());
+        for (url, tunnelled, bytes) in [
+            ("https://CDN.example.com/a.png", true, 300),
+            ("https://cdn.example.com/b.png", true, 200),
+            ("http://tracker.test/p.gif", false, 1_000),
+        ] {
+            ledger.record(host_of(url).unwrap(), tunnelled, bytes);
+        }
+        assert_eq!(host_of("not a url"), None);
+        ledger.flush().unwrap();
+
+        let reloaded = BandwidthLedger::default();
+        reloaded.load(dir.path());
+        let hosts = reloaded.snapshot();
+        assert_eq!(
+            hosts,
+            vec![
+                HostBandwidth {
+                    host: "tracker.test".to_string(),
+                    tunnel_bytes: 0,
+                    direct_bytes: 1_000,
+                    requests: 1,
+                },
+                HostBandwidth {
+                    host: "cdn.example.com".to_string(),
+                    tunnel_bytes: 500,
+                    direct_bytes: 0,
+                    requests: 2,
+                },
+            ]
+        );
+
+        reloaded.reset().unwrap();
+        assert!(reloaded.snapshot().is_empty());
+        assert!(!dir.path().join(USAGE_FILE).exists());
+    }
+
+    #[test]
+    fn the_worker_counts_the_bytes_of_each_request() {
+        use crate::config::FetchLimits;
+        use crate::tunnel::TunnelManager;
+        use std::io::{BufRead, BufReader, Write};
+        use std::sync::Arc;
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = std::thread::spawn(move || {
+            let (mut conn, _) = listener.accept().unwrap();
+            let mut request_line = String::new();
+            BufReader::new(&mut conn)
+                .read_line(&mut request_line)
+                .unwrap();
+            conn.write_all(
+                b"HTTP/1.1 200 OK\r\nContent-Type: image/png\r\nContent-Length: 3\r\n\
+                  Connection: close\r\n\r\nabc",
+            )
+            .unwrap();
+        });
+
+        let ledger = Arc::new(BandwidthLedger::default());
+        let limits = FetchLimits {
+            bandwidth: Some(Arc::clone(&ledger)),
+            ..FetchLimits::default()
+        };
+        let manager = TunnelManager::direct().unwrap();
+        let url = format!("http://127.0.0.1:{port}/a.png");
+        manager
+            .fetch(url, Vec::new(), "image/*".to_string(), limits, None)
+            .unwrap();
+        server.join().unwrap();
+        // The worker counts after replying; joining it settles the count.
+        drop(manager);
+
+        let hosts = ledger.snapshot();
+        assert_eq!(hosts.len(), 1);
+        assert_eq!(
+            (hosts[0].host.as_str(), hosts[0].requests),
+            ("127.0.0.1", 1)
+        );
+        assert_eq!(hosts[0].tunnel_bytes, 0);
+        assert!(hosts[0].direct_bytes > 3);
+    }
+
+    #[test]
+    fn the_cheapest_host_makes_room() {
+        let ledger = BandwidthLedger::default();
+        for i in 0..MAX_HOSTS {
+            ledger.record(format!("h{i}.test"), true, 10 + i as u64);
+        }
+        ledger.record("new.test".to_string(), true, 1);
+        let hosts = ledger.snapshot();
+        assert_eq!(hosts.len(), MAX_HOSTS);
+        assert!(hosts.iter().all(|h| h.host != "h0.test"));
+        assert!(hosts.iter().any(|h| h.host == "new.test"));
+    }
+}
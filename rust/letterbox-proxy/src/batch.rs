@@ -0,0 +1,386 @@
+//! Batch image fetching.
+//!
+//! [`ProxyClient::fetch_images_batch`] returns an [`ImageBatch`] handle. Its
+//! [`results`](ImageBatch::results) come back all at once, in request order;
+//! [`stream`](ImageBatch::stream) instead hands each result to a
+//! [`BatchResultListener`] as soon as it completes, so one slow host does not
+//! hold back the rest of a message. Either way at most `max_concurrent`
+//! fetches are in flight, and every network fetch also takes one of the
+//! client's fetch slots, so simultaneous batches together stay within the
+//! [`FetchConcurrency`](crate::types::FetchConcurrency) caps. Cache hits and
+//! `data:` URIs complete without waiting for a slot or the tunnel. Network fetches queue in the tunnel at the batch's
+//! [`RequestPriority`], so a `Prefetch` batch never delays `Visible` images.
+//! A batch with a `group_id` — one message's images — shares that group's
+//! byte budget.
+//!
+//! [`ImageBatch::cancel`] — called when the user closes the message, or
+//! implied by dropping the `results`/`stream` future, e.g. on coroutine
+//! cancellation — fails every URL not yet fetched with
+//! [`ProxyError::Cancelled`]: fetches not yet started are skipped, queued ones
+//! are dropped by the tunnel worker, and a body being received is aborted at
+//! its next chunk, which closes its connection.
+
+use crate::client::{default_client, ProxyClient};
+use crate::error::ProxyError;
+use crate::fetch::fetch_image_async;
+use crate::types::{BatchImageResult, ImageResponse, RequestPriority};
+use futures::{Stream, StreamExt};
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Receives the results of a streaming batch fetch.
+#[uniffi::export(with_foreign)]
+pub trait BatchResultListener: Send + Sync {
+    /// The fetch of `urls[index]` finished with `result`. Called once per URL,
+    /// in completion order.
+    fn on_result(&self, index: u32, result: BatchImageResult);
+}
+
+/// A cancellable batch of image fetches.
+///
+/// Nothing is fetched until [`Self::results`] or [`Self::stream`] is awaited.
+/// A batch runs once: whichever of the two is called first takes the URLs, and
+/// later calls see an empty batch.
+#[derive(uniffi::Object)]
+pub struct ImageBatch {
+    client: Arc<ProxyClient>,
+    urls: Mutex<Vec<String>>,
+    max_concurrent: u32,
+    priority: RequestPriority,
+    group_id: Option<String>,
+    cancelled: Arc<AtomicBool>,
+}
+
+fn batch_result(url: String, result: Result<ImageResponse, ProxyError>) -> BatchImageResult {
+    match result {
+        Ok(response) => BatchImageResult {
+            url,
+            success: true,
+            response: Some(response),
+            error: None,
+            error_code: None,
+            retryable: false,
+            http_status: None,
+        },
+        Err(e) => BatchImageResult {
+            url,
+            success: false,
+            response: None,
+            error_code: Some(e.code()),
+            retryable: e.is_retryable(),
+            http_status: e.http_status(),
+            error: Some(e.to_string()),
+        },
+    }
+}
+
+/// Cancels the batch when dropped before [`Self::finish`], i.e. when the
+/// caller stops awaiting it.
+struct CancelOnDrop<'a>(Option<&'a AtomicBool>);
+
+impl CancelOnDrop<'_> {
+    fn finish(mut self) {
+        self.0 = None;
+    }
+}
+
+impl Drop for CancelOnDrop<'_> {
+    fn drop(&mut self) {
+        if let Some(flag) = self.0 {
+            flag.store(true, Ordering::Relaxed);
+        }
+    }
+}
+
+impl ImageBatch {
+    /// Take the URLs for the one run of this batch.
+    fn take_urls(&self) -> Vec<String> {
+        std::mem::take(
+            &mut *self
+                .urls
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner()),
+        )
+    }
+
+    /// One future per URL, yielding its index and result.
+    fn fetches(
+        &self,
+        urls: Vec<String>,
+    ) -> impl Stream<Item = impl Future<Output = (usize, BatchImageResult)> + '_> + '_ {
+        futures::stream::iter(urls.into_iter().enumerate()).map(move |(index, url)| async move {
+            let result = if self.is_cancelled() {
+                Err(ProxyError::Cancelled)
+            } else {
+                let cancelled = Some(Arc::clone(&self.cancelled));
+                let group = self.group_id.as_deref();
+                fetch_image_async(&self.client, &url, None, self.priority, group, cancelled).await
+            };
+            match &result {
+                Err(ProxyError::Cancelled) | Ok(_) => {}
+                Err(e) => self.client.note_error(Some(&url), e),
+            }
+            (index, batch_result(url, result))
+        })
+    }
+
+    /// `max_concurrent`, with zero meaning one at a time.
+    fn concurrency(&self) -> usize {
+        self.max_concurrent.max(1) as usize
+    }
+}
+
+#[uniffi::export]
+impl ImageBatch {
+    /// Fetch the batch, returning the results in request order once all have
+    /// finished.
+    pub async fn results(&self) -> Result<Vec<BatchImageResult>, ProxyError> {
+        let guard = CancelOnDrop(Some(&self.cancelled));
+        let results = self
+            .fetches(self.take_urls())
+            .buffered(self.concurrency())
+            .map(|(_, result)| result)
+            .collect()
+            .await;
+        guard.finish();
+        Ok(results)
+    }
+
+    /// Fetch the batch, passing each result to `listener` as soon as it is
+    /// ready. Returns once every URL has been reported.
+    pub async fn stream(&self, listener: Arc<dyn BatchResultListener>) -> Result<(), ProxyError> {
+        let guard = CancelOnDrop(Some(&self.cancelled));
+        let mut completed = self
+            .fetches(self.take_urls())
+            .buffer_unordered(self.concurrency());
+        while let Some((index, result)) = completed.next().await {
+            listener.on_result(index as u32, result);
+        }
+        guard.finish();
+        Ok(())
+    }
+
+    /// Abandon the rest of the batch. Every URL not fetched yet is reported
+    /// as failed with [`ProxyError::Cancelled`].
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+        // Fetches waiting for a slot give up now rather than when one frees.
+        self.client.slots.wake();
+    }
+
+    /// Whether [`Self::cancel`] was called, or the batch's caller stopped
+    /// waiting for it.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+#[uniffi::export]
+impl ProxyClient {
+    /// Prepare a batch fetching `urls` through the tunnel at `priority`, at
+    /// most `max_concurrent` at a time, charged to `group_id`'s byte budget if
+    /// given. Await [`ImageBatch::results`] or [`ImageBatch::stream`] to run it.
+    #[uniffi::method(default(group_id = None))]
+    pub fn fetch_images_batch(
+        self: Arc<Self>,
+        urls: Vec<String>,
+        max_concurrent: u32,
+        priority: RequestPriority,
+        group_id: Option<String>,
+    ) -> Arc<ImageBatch> {
+        Arc::new(ImageBatch {
+            client: self,
+            urls: Mutex::new(urls),
+            max_concurrent,
+            priority,
+            group_id,
+            cancelled: Arc::default(),
+        })
+    }
+
+    /// [`ImageBatch::stream`] of a new batch, for callers that never cancel
+    /// other than by abandoning the call.
+    #[uniffi::method(default(group_id = None))]
+    pub async fn fetch_images_streaming(
+        self: Arc<Self>,
+        urls: Vec<String>,
+        max_concurrent: u32,
+        priority: RequestPriority,
+        listener: Arc<dyn BatchResultListener>,
+        group_id: Option<String>,
+    ) -> Result<(), ProxyError> {
+        self.fetch_images_batch(urls, max_concurrent, priority, group_id)
+            .stream(listener)
+            .await
+    }
+}
+
+/// [`ProxyClient::fetch_images_batch`] on the default proxy.
+#[uniffi::export(default(group_id = None))]
+pub fn proxy_fetch_images_batch(
+    urls: Vec<String>,
+    max_concurrent: u32,
+    priority: RequestPriority,
+    group_id: Option<String>,
+) -> Arc<ImageBatch> {
+    Arc::clone(default_client()).fetch_images_batch(urls, max_concurrent, priority, group_id)
+}
+
+/// [`ProxyClient::fetch_images_streaming`] on the default proxy.
+#[uniffi::export(default(group_id = None))]
+pub async fn proxy_fetch_images_streaming(
+    urls: Vec<String>,
+    max_concurrent: u32,
+    priority: RequestPriority,
+    listener: Arc<dyn BatchResultListener>,
+    group_id: Option<String>,
+) -> Result<(), ProxyError> {
+    Arc::clone(default_client())
+        .fetch_images_streaming(urls, max_concurrent, priority, listener, group_id)
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorCode;
+    use std::sync::Mutex;
+
+    const PNG_URI: &str = "data:image/png;base64,iVBORw0KGgo=";
+
+    struct Collect(Mutex<Vec<(u32, BatchImageResult)>>);
+
+    impl BatchResultListener for Collect {
+        fn on_result(&self, index: u32, result: BatchImageResult) {
+            self.0.lock().unwrap().push((index, result));
+        }
+    }
+
+    fn client() -> (tempfile::TempDir, Arc<ProxyClient>) {
+        let dir = tempfile::tempdir().unwrap();
+        let client = ProxyClient::new(dir.path().to_string_lossy().into_owned(), 10).unwrap();
+        (dir, client)
+    }
+
+    #[test]
+    fn batch_results_keep_request_order() {
+        let (_dir, client) = client();
+        let urls = vec![
+            "not a url".to_string(),
+            PNG_URI.to_string(),
+            "ftp://x/y.png".to_string(),
+        ];
+        let batch = client.fetch_images_batch(urls.clone(), 2, RequestPriority::Visible, None);
+        let results = futures::executor::block_on(batch.results()).unwrap();
+        let got: Vec<(&str, bool)> = results
+            .iter()
+            .map(|r| (r.url.as_str(), r.success))
+            .collect();
+        assert_eq!(
+            got,
+            [
+                (urls[0].as_str(), false),
+                (PNG_URI, true),
+                (urls[2].as_str(), false)
+            ]
+        );
+        assert!(results[0].error.is_some() && results[1].response.is_some());
+        assert!(!batch.is_cancelled());
+        assert!(futures::executor::block_on(batch.results())
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn cancelled_batch_fails_remaining_urls() {
+        let (_dir, client) = client();
+        let urls = vec![PNG_URI.to_string(), PNG_URI.to_string()];
+        let batch = client.fetch_images_batch(urls, 1, RequestPriority::Visible, None);
+        batch.cancel();
+        let results = futures::executor::block_on(batch.results()).unwrap();
+        let cancelled = ProxyError::Cancelled.to_string();
+        assert!(results
+            .iter()
+            .all(|r| !r.success && r.error.as_deref() == Some(cancelled.as_str())));
+    }
+
+    #[test]
+    fn abandoning_a_run_cancels_it() {
+        let flag = AtomicBool::new(false);
+        CancelOnDrop(Some(&flag)).finish();
+        assert!(!flag.load(Ordering::Relaxed));
+        drop(CancelOnDrop(Some(&flag)));
+        assert!(flag.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn streaming_reports_every_url_once() {
+        let (_dir, client) = client();
+        let urls = vec![
+            PNG_URI.to_string(),
+            "bogus".to_string(),
+            PNG_URI.to_string(),
+        ];
+        let collect = Arc::new(Collect(Mutex::new(Vec::new())));
+        futures::executor::block_on(Arc::clone(&client).fetch_images_streaming(
+            urls,
+            0,
+            RequestPriority::Prefetch,
+            collect.clone(),
+            None,
+        ))
+        .unwrap();
+
+        let mut seen: Vec<(u32, bool)> = collect
+            .0
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(i, r)| (*i, r.success))
+            .collect();
+        seen.sort_unstable();
+        assert_eq!(seen, [(0, true), (1, false), (2, true)]);
+    }
+
+    #[test]
+    fn batch_result_variants() {
+        let ok = BatchImageResult {
+            url: "https://example.com/a.png".to_string(),
+            success: true,
+            response: Some(ImageResponse {
+                mime_type: "image/png".to_string(),
+                data: vec![1, 2, 3, 4],
+                from_cache: false,
+                final_url: "https://example.com/a.png".to_string(),
+                redirect_chain: Vec::new(),
+                redirect_hops: Vec::new(),
+                width: None,
+                height: None,
+                blurhash: None,
+                tracking_suspected: false,
+                trace_id: String::new(),
+                headers: Default::default(),
+                sha256: String::new(),
+                transport: None,
+            }),
+            error: None,
+            error_code: None,
+            retryable: false,
+            http_status: None,
+        };
+        assert!(ok.success && ok.response.is_some() && ok.error.is_none());
+
+        let err = batch_result(
+            "https://example.com/b.png".to_string(),
+            Err(ProxyError::HttpError {
+                status_code: 404,
+                details: "Not found".to_string(),
+            }),
+        );
+        assert!(!err.success && err.response.is_none() && err.error.is_some());
+        assert_eq!(err.error_code, Some(ErrorCode::HttpError));
+        assert_eq!(err.http_status, Some(404));
+        assert!(!err.retryable);
+    }
+}
@@ -0,0 +1,201 @@
+//! Cache management FFI: pins, the disk tier, host storage, trimming and
+//! archives.
+
+use super::storage::{CacheStorage, CacheStorageMode, HostStorage};
+use super::DiskCache;
+use crate::client::{default_client, ProxyClient};
+use crate::error::ProxyError;
+use crate::types::DiskCacheOptions;
+use crate::validate_image_url;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+#[uniffi::export]
+impl ProxyClient {
+    /// Pin a URL so its cached image is never evicted by LRU pressure.
+    ///
+    /// The URL does not need to be cached yet; the next successful fetch of it is
+    /// retained as a pinned entry. Pinning an already pinned URL is a no-op.
+    pub fn pin_url(&self, url: String) -> Result<(), ProxyError> {
+        validate_image_url(&url)?;
+        let mut guard = self.lock_state();
+        let state = guard.as_mut().ok_or(ProxyError::NotInitialized)?;
+        state.cache.pin(url);
+        Ok(())
+    }
+
+    /// Unpin a URL, making its cached image subject to normal LRU eviction.
+    ///
+    /// Returns `false` if the URL was not pinned.
+    pub fn unpin_url(&self, url: String) -> Result<bool, ProxyError> {
+        let mut guard = self.lock_state();
+        let state = guard.as_mut().ok_or(ProxyError::NotInitialized)?;
+        Ok(state.cache.unpin(&url))
+    }
+
+    /// List all pinned URLs, whether or not their image has been fetched yet.
+    pub fn pinned_urls(&self) -> Result<Vec<String>, ProxyError> {
+        let guard = self.lock_state();
+        let state = guard.as_ref().ok_or(ProxyError::NotInitialized)?;
+        Ok(state.cache.pinned_urls())
+    }
+
+    /// Enable the persistent disk cache under the proxy storage path.
+    ///
+    /// With `encryption_key` (exactly 32 bytes, ideally from the Android Keystore)
+    /// cached bodies are encrypted at rest and blob names are keyed, so cached
+    /// images from confidential mail are unreadable from the app's files directory
+    /// without the key. Re-enabling with a different key (or toggling encryption)
+    /// discards the existing, now unreadable, entries.
+    pub fn enable_disk_cache(&self, options: DiskCacheOptions) -> Result<(), ProxyError> {
+        let mut guard = self.lock_state();
+        let state = guard.as_mut().ok_or(ProxyError::NotInitialized)?;
+        let disk = DiskCache::open(
+            &state.config.storage_path,
+            options.max_bytes,
+            options.encryption_key.as_deref(),
+        )?
+        .with_max_age(
+            (options.max_age_days > 0)
+                .then(|| Duration::from_secs(u64::from(options.max_age_days) * 24 * 60 * 60)),
+        )?;
+        state.cache.set_disk(Some(disk));
+        Ok(())
+    }
+
+    /// Disable the persistent disk cache and delete everything it stored.
+    pub fn disable_disk_cache(&self) -> Result<(), ProxyError> {
+        let mut guard = self.lock_state();
+        let state = guard.as_mut().ok_or(ProxyError::NotInitialized)?;
+        match state.cache.set_disk(None) {
+            Some(disk) => disk.destroy(),
+            None => Ok(()),
+        }
+    }
+
+    /// Keep cached images in the host's `storage` as well, or with
+    /// [`CacheStorageMode::Instead`] in place of the in-memory LRU. `None`
+    /// unregisters it; what it stored stays where it is.
+    ///
+    /// The storage is called with the proxy's state locked: answer promptly
+    /// and do not call back into the proxy.
+    #[uniffi::method(default(mode = None))]
+    pub fn set_cache_storage(
+        &self,
+        storage: Option<Arc<dyn CacheStorage>>,
+        mode: Option<CacheStorageMode>,
+    ) -> Result<(), ProxyError> {
+        let mut guard = self.lock_state();
+        let state = guard.as_mut().ok_or(ProxyError::NotInitialized)?;
+        state
+            .cache
+            .set_host(storage.map(|storage| HostStorage::new(storage, mode.unwrap_or_default())));
+        Ok(())
+    }
+
+    /// Shrink the in-memory cache to at most `target_bytes` of image data.
+    ///
+    /// Intended for Android's `onTrimMemory`. Least-recently-used entries go first;
+    /// with `spill_to_disk` they move to the disk cache (when enabled) instead of
+    /// being dropped. Pinned images are kept. Returns the number of bytes freed.
+    pub fn trim_cache(&self, target_bytes: u64, spill_to_disk: bool) -> Result<u64, ProxyError> {
+        let mut guard = self.lock_state();
+        let state = guard.as_mut().ok_or(ProxyError::NotInitialized)?;
+        Ok(state.cache.trim(target_bytes, spill_to_disk))
+    }
+
+    /// Export the disk cache (index and blobs) as a single archive file at `path`.
+    ///
+    /// An encrypted cache stays encrypted in the archive. Returns the number of
+    /// images exported; fails if the disk cache is not enabled.
+    pub fn export_cache(&self, path: String) -> Result<u32, ProxyError> {
+        let mut guard = self.lock_state();
+        let state = guard.as_mut().ok_or(ProxyError::NotInitialized)?;
+        state.cache.disk_mut()?.export_archive(Path::new(&path))
+    }
+
+    /// Replace the disk cache with an archive written by [`Self::export_cache`].
+    ///
+    /// The disk cache must be enabled with the same encryption key (or none) as
+    /// the exporting device. Returns the number of images imported.
+    pub fn import_cache(&self, path: String) -> Result<u32, ProxyError> {
+        let mut guard = self.lock_state();
+        let state = guard.as_mut().ok_or(ProxyError::NotInitialized)?;
+        state.cache.disk_mut()?.import_archive(Path::new(&path))
+    }
+
+    /// Clear the image cache, including the disk tier if enabled.
+    ///
+    /// Pinned URLs stay pinned but lose their cached bodies.
+    pub fn clear_cache(&self) -> Result<(), ProxyError> {
+        let mut guard = self.lock_state();
+        if let Some(state) = guard.as_mut() {
+            state.cache.clear();
+        }
+        Ok(())
+    }
+}
+
+/// [`ProxyClient::pin_url`] on the default proxy.
+#[uniffi::export]
+pub fn proxy_pin_url(url: String) -> Result<(), ProxyError> {
+    default_client().pin_url(url)
+}
+
+/// [`ProxyClient::unpin_url`] on the default proxy.
+#[uniffi::export]
+pub fn proxy_unpin_url(url: String) -> Result<bool, ProxyError> {
+    default_client().unpin_url(url)
+}
+
+/// [`ProxyClient::pinned_urls`] on the default proxy.
+#[uniffi::export]
+pub fn proxy_pinned_urls() -> Result<Vec<String>, ProxyError> {
+    default_client().pinned_urls()
+}
+
+/// [`ProxyClient::enable_disk_cache`] on the default proxy.
+#[uniffi::export]
+pub fn proxy_enable_disk_cache(options: DiskCacheOptions) -> Result<(), ProxyError> {
+    default_client().enable_disk_cache(options)
+}
+
+/// [`ProxyClient::disable_disk_cache`] on the default proxy.
+#[uniffi::export]
+pub fn proxy_disable_disk_cache() -> Result<(), ProxyError> {
+    default_client().disable_disk_cache()
+}
+
+/// [`ProxyClient::set_cache_storage`] on the default proxy.
+#[uniffi::export(default(mode = None))]
+pub fn proxy_set_cache_storage(
+    storage: Option<Arc<dyn CacheStorage>>,
+    mode: Option<CacheStorageMode>,
+) -> Result<(), ProxyError> {
+    default_client().set_cache_storage(storage, mode)
+}
+
+/// [`ProxyClient::trim_cache`] on the default proxy.
+#[uniffi::export]
+pub fn proxy_trim_cache(target_bytes: u64, spill_to_disk: bool) -> Result<u64, ProxyError> {
+    default_client().trim_cache(target_bytes, spill_to_disk)
+}
+
+/// [`ProxyClient::export_cache`] on the default proxy.
+#[uniffi::export]
+pub fn proxy_export_cache(path: String) -> Result<u32, ProxyError> {
+    default_client().export_cache(path)
+}
+
+/// [`ProxyClient::import_cache`] on the default proxy.
+#[uniffi::export]
+pub fn proxy_import_cache(path: String) -> Result<u32, ProxyError> {
+    default_client().import_cache(path)
+}
+
+/// [`ProxyClient::clear_cache`] on the default proxy.
+#[uniffi::export]
+pub fn proxy_clear_cache() -> Result<(), ProxyError> {
+    default_client().clear_cache()
+}
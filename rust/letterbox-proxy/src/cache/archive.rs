@@ -0,0 +1,218 @@
+//! Single-file archive of the disk cache, for backup and device migration.
+//!
+//! ```text
+//! "LBXA" | version (1) | entries...
+//! entry = u16le name len | name | u64le data len | data
+//! ```
+//!
+//! Entries are the cache files verbatim (the index first, then every blob), so
+//! an encrypted cache stays encrypted inside the archive and can only be used
+//! with the same key on the receiving device. Importing streams each blob to
+//! its own file rather than holding the archive in memory.
+
+use crate::error::ProxyError;
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+
+/// Leading magic bytes of an archive.
+const ARCHIVE_MAGIC: &[u8; 4] = b"LBXA";
+
+/// Archive format version.
+const ARCHIVE_VERSION: u8 = 1;
+
+fn corrupt(what: &str) -> ProxyError {
+    ProxyError::StorageError {
+        details: format!("Corrupt cache archive: {what}"),
+    }
+}
+
+/// Whether `name` is a file the cache directory may legitimately contain.
+///
+/// Guards import against path traversal and stray files.
+pub(crate) fn is_cache_file(name: &str, index_file: &str, blob_extension: &str) -> bool {
+    if name == index_file {
+        return true;
+    }
+    name.strip_suffix(blob_extension)
+        .and_then(|stem| stem.strip_suffix('.'))
+        .is_some_and(|stem| !stem.is_empty() && stem.bytes().all(|b| b.is_ascii_hexdigit()))
+}
+
+/// Write `files` (name, path) into a new archive at `dest`.
+///
+/// The archive is written beside `dest` and renamed into place, so a failed
+/// export never leaves a truncated file behind.
+pub(crate) fn write(dest: &Path, files: &[(String, PathBuf)]) -> Result<(), ProxyError> {
+    let tmp = dest.with_extension("partial");
+    let mut out = BufWriter::new(File::create(&tmp)?);
+    out.write_all(ARCHIVE_MAGIC)?;
+    out.write_all(&[ARCHIVE_VERSION])?;
+    for (name, path) in files {
+        let data = fs::read(path)?;
+        out.write_all(&(name.len() as u16).to_le_bytes())?;
+        out.write_all(name.as_bytes())?;
+        out.write_all(&(data.len() as u64).to_le_bytes())?;
+        out.write_all(&data)?;
+    }
+    out.into_inner()
+        .map_err(|e| ProxyError::from(e.into_error()))?
+        .sync_all()?;
+    fs::rename(&tmp, dest)?;
+    Ok(())
+}
+
+/// Streams the entries of an archive, one at a time.
+pub(crate) struct Reader {
+    input: BufReader<File>,
+}
+
+impl Reader {
+    /// Open the archive at `src` and check its header.
+    pub(crate) fn open(src: &Path) -> Result<Self, ProxyError> {
+        let mut input = BufReader::new(File::open(src)?);
+        let mut header = [0u8; 5];
+        input
+            .read_exact(&mut header)
+            .map_err(|_| corrupt("truncated header"))?;
+        if &header[..4] != ARCHIVE_MAGIC || header[4] != ARCHIVE_VERSION {
+            return Err(corrupt("bad magic or version"));
+        }
+        Ok(Self { input })
+    }
+
+    /// The name and data length of the next entry, or `None` at the end.
+    /// Its data must be consumed before asking for the entry after it.
+    pub(crate) fn next_entry(&mut self) -> Result<Option<(String, u64)>, ProxyError> {
+        let mut name_len = [0u8; 2];
+        match self.input.read_exact(&mut name_len) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(e) => return Err(e.into()),
+        }
+        let mut name = vec![0u8; u16::from_le_bytes(name_len) as usize];
+        self.input
+            .read_exact(&mut name)
+            .map_err(|_| corrupt("truncated name"))?;
+        let name = String::from_utf8(name).map_err(|_| corrupt("non-UTF-8 name"))?;
+        let mut data_len = [0u8; 8];
+        self.input
+            .read_exact(&mut data_len)
+            .map_err(|_| corrupt("truncated length"))?;
+        Ok(Some((name, u64::from_le_bytes(data_len))))
+    }
+
+    /// Read an entry's `len` bytes of data into memory, refusing more than
+    /// `max_len`.
+    pub(crate) fn read_data(&mut self, len: u64, max_len: u64) -> Result<Vec<u8>, ProxyError> {
+        if len > max_len {
+            return Err(corrupt("oversized entry"));
+        }
+        let mut data = Vec::new();
+        self.copy(len, &mut data)?;
+        Ok(data)
+    }
+
+    /// Stream an entry's `len` bytes of data into `out`.
+    fn copy(&mut self, len: u64, out: &mut impl Write) -> Result<(), ProxyError> {
+        let copied = std::io::copy(&mut self.input.by_ref().take(len), out)?;
+        if copied != len {
+            return Err(corrupt("truncated entry"));
+        }
+        Ok(())
+    }
+}
+
+/// Copy the remaining entries of `reader` that `wanted` accepts into files
+/// of the same name in `staging`, skipping the rest and any entry that would
+/// take the total past `max_bytes`. Returns each file written with its size
+/// on disk.
+///
+/// The whole archive is read before this returns, so a corrupt archive is
+/// detected before any of it is used.
+pub(crate) fn extract(
+    reader: &mut Reader,
+    staging: &Path,
+    wanted: impl Fn(&str) -> bool,
+    max_bytes: u64,
+) -> Result<Vec<(String, u64)>, ProxyError> {
+    let mut extracted: Vec<(String, u64)> = Vec::new();
+    let mut total = 0u64;
+    while let Some((name, len)) = reader.next_entry()? {
+        let fits = total.checked_add(len).is_some_and(|sum| sum <= max_bytes);
+        let seen = extracted.iter().any(|(done, _)| *done == name);
+        if !fits || seen || !wanted(&name) {
+            reader.copy(len, &mut std::io::sink())?;
+            continue;
+        }
+        let path = staging.join(&name);
+        let mut out = BufWriter::new(File::create(&path)?);
+        reader.copy(len, &mut out)?;
+        out.into_inner()
+            .map_err(|e| ProxyError::from(e.into_error()))?
+            .sync_all()?;
+        let size = fs::metadata(&path)?.len();
+        total = total.saturating_add(size);
+        extracted.push((name, size));
+    }
+    Ok(extracted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn cache_file_names_are_vetted() {
+        assert!(is_cache_file("index.json", "index.json", "blob"));
+        assert!(is_cache_file("0a1f.blob", "index.json", "blob"));
+        assert!(!is_cache_file("../evil.blob", "index.json", "blob"));
+        assert!(!is_cache_file(".blob", "index.json", "blob"));
+        assert!(!is_cache_file("abc.tmp", "index.json", "blob"));
+    }
+
+    #[test]
+    fn round_trips_and_rejects_truncation() {
+        let dir = tempdir().unwrap();
+        let blob = dir.path().join("ab.blob");
+        fs::write(&blob, b"hello").unwrap();
+        let archive = dir.path().join("cache.lbxa");
+        write(&archive, &[("ab.blob".to_string(), blob)]).unwrap();
+
+        let mut reader = Reader::open(&archive).unwrap();
+        let (name, len) = reader.next_entry().unwrap().unwrap();
+        assert_eq!((name.as_str(), len), ("ab.blob", 5));
+        assert_eq!(reader.read_data(len, 5).unwrap(), b"hello");
+        assert!(reader.next_entry().unwrap().is_none());
+
+        let bytes = fs::read(&archive).unwrap();
+        fs::write(&archive, &bytes[..bytes.len() - 1]).unwrap();
+        let mut reader = Reader::open(&archive).unwrap();
+        let (_, len) = reader.next_entry().unwrap().unwrap();
+        assert!(reader.read_data(len, u64::MAX).is_err());
+    }
+
+    #[test]
+    fn extracts_wanted_entries_within_the_budget() {
+        let dir = tempdir().unwrap();
+        let files: Vec<(String, PathBuf)> = [("a", 40), ("b", 40), ("c", 40), ("d", 10)]
+            .into_iter()
+            .map(|(name, len)| {
+                let path = dir.path().join(name);
+                fs::write(&path, vec![0u8; len]).unwrap();
+                (name.to_string(), path)
+            })
+            .collect();
+        let archive = dir.path().join("cache.lbxa");
+        write(&archive, &files).unwrap();
+
+        let staging = dir.path().join("staging");
+        fs::create_dir(&staging).unwrap();
+        let mut reader = Reader::open(&archive).unwrap();
+        let extracted = extract(&mut reader, &staging, |name| name != "b", 60).unwrap();
+        // "b" is not wanted and "c" would go over budget.
+        assert_eq!(extracted, [("a".to_string(), 40), ("d".to_string(), 10)]);
+        assert!(!staging.join("c").exists());
+    }
+}
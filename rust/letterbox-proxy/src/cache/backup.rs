@@ -0,0 +1,192 @@
+//! Exporting and importing the [`DiskCache`] as a single [`archive`] file.
+//!
+//! An export is the index followed by every blob, verbatim. An import reads
+//! the index first, decides from it which blobs fit the byte budget, streams
+//! only those into a staging directory and swaps them in once the whole
+//! archive has been read.
+
+use super::archive;
+use super::disk::{DiskCache, DiskIndex, IndexEntry, BLOB_EXTENSION, INDEX_FILE};
+use crate::error::ProxyError;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// Largest index an imported archive may carry.
+const MAX_ARCHIVE_INDEX: u64 = 16 * 1024 * 1024;
+
+impl DiskCache {
+    /// Write the index and every blob into a single archive at `dest`.
+    ///
+    /// Returns the number of images exported.
+    pub(crate) fn export_archive(&mut self, dest: &Path) -> Result<u32, ProxyError> {
+        self.persist_index()?;
+        let mut files = vec![(INDEX_FILE.to_string(), self.dir.join(INDEX_FILE))];
+        files.extend(
+            self.index
+                .entries
+                .keys()
+                .map(|name| (format!("{name}.{BLOB_EXTENSION}"), self.blob_path(name))),
+        );
+        archive::write(dest, &files)?;
+        Ok(self.index.entries.len() as u32)
+    }
+
+    /// Replace the cache contents with an archive written by
+    /// [`export_archive`](Self::export_archive).
+    ///
+    /// The archive must come from a cache using the same key (or no key) and
+    /// start with its index; the existing contents are left untouched if it
+    /// does not, or if it turns out to be corrupt. Only blobs the index lists
+    /// are imported, the most recently used first, up to the byte budget, and
+    /// each is accounted at its size on disk. Returns the number of images
+    /// imported.
+    pub(crate) fn import_archive(&mut self, src: &Path) -> Result<u32, ProxyError> {
+        let mut reader = archive::Reader::open(src)?;
+        let index = match reader.next_entry()? {
+            Some((name, len)) if name == INDEX_FILE => {
+                let raw = reader.read_data(len, MAX_ARCHIVE_INDEX)?;
+                serde_json::from_slice::<DiskIndex>(&raw).ok()
+            }
+            _ => None,
+        }
+        .ok_or_else(|| ProxyError::StorageError {
+            details: "Cache archive has no readable index".to_string(),
+        })?;
+        if index.key_check != self.index.key_check {
+            return Err(ProxyError::CryptoError {
+                details: "Cache archive was written with a different encryption key".to_string(),
+            });
+        }
+
+        // What the index claims fits the budget, most recent first; the sizes
+        // on disk are checked against the budget again as blobs arrive.
+        let mut by_recency: Vec<(&String, &IndexEntry)> = index.entries.iter().collect();
+        by_recency.sort_by_key(|(_, entry)| std::cmp::Reverse(entry.last_access));
+        let mut claimed = 0u64;
+        let wanted: HashMap<String, &String> = by_recency
+            .into_iter()
+            .take_while(|(_, entry)| {
+                claimed = claimed.saturating_add(entry.size);
+                claimed <= self.max_bytes
+            })
+            .map(|(name, _)| (format!("{name}.{BLOB_EXTENSION}"), name))
+            .collect();
+
+        let staging = self.dir.with_extension("import");
+        let _ = fs::remove_dir_all(&staging);
+        fs::create_dir_all(&staging)?;
+        let extracted = archive::extract(
+            &mut reader,
+            &staging,
+            |file| {
+                archive::is_cache_file(file, INDEX_FILE, BLOB_EXTENSION)
+                    && wanted.contains_key(file)
+            },
+            self.max_bytes,
+        );
+        let moved = extracted.and_then(|extracted| {
+            self.remove_all_blobs()?;
+            for (file, size) in extracted {
+                let name = wanted[&file];
+                fs::rename(staging.join(&file), self.blob_path(name))?;
+                self.index.entries.insert(
+                    name.clone(),
+                    IndexEntry {
+                        size,
+                        ..index.entries[name]
+                    },
+                );
+            }
+            Ok(())
+        });
+        let _ = fs::remove_dir_all(&staging);
+        moved?;
+        self.evict_to(self.max_bytes)?;
+        self.persist_index()?;
+        Ok(self.index.entries.len() as u32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::blob::KEY_LEN;
+    use crate::cache::disk::CACHE_DIR;
+    use crate::types::ImageResponse;
+    use tempfile::tempdir;
+
+    fn response(data: &[u8]) -> ImageResponse {
+        ImageResponse {
+            mime_type: "image/png".to_string(),
+            data: data.to_vec(),
+            from_cache: false,
+            final_url: "https://cdn.example.com/a.png".to_string(),
+            redirect_chain: Vec::new(),
+            redirect_hops: Vec::new(),
+            width: None,
+            height: None,
+            blurhash: None,
+            tracking_suspected: false,
+            trace_id: String::new(),
+            headers: Default::default(),
+            sha256: String::new(),
+            transport: None,
+        }
+    }
+
+    #[test]
+    fn archive_round_trips_between_caches() {
+        let (from, to) = (tempdir().unwrap(), tempdir().unwrap());
+        let archive = from.path().join("cache.lbxa");
+        let key = [5u8; KEY_LEN];
+        let mut source = DiskCache::open(from.path(), 1 << 20, Some(&key)).unwrap();
+        source.put("https://a/1.png", &response(b"one")).unwrap();
+        source.put("https://a/2.png", &response(b"two")).unwrap();
+        assert_eq!(source.export_archive(&archive).unwrap(), 2);
+
+        // A cache with another key refuses the archive and keeps its contents.
+        let mut other = DiskCache::open(to.path(), 1 << 20, None).unwrap();
+        other.put("https://b/x.png", &response(b"x")).unwrap();
+        assert!(other.import_archive(&archive).is_err());
+        assert_eq!(other.len(), 1);
+        drop(other);
+
+        let mut target = DiskCache::open(to.path(), 1 << 20, Some(&key)).unwrap();
+        assert_eq!(target.import_archive(&archive).unwrap(), 2);
+        assert_eq!(target.get("https://a/2.png").unwrap().data, b"two");
+    }
+
+    #[test]
+    fn archive_import_trusts_only_the_files() {
+        let (from, to) = (tempdir().unwrap(), tempdir().unwrap());
+        let mut source = DiskCache::open(from.path(), 1 << 20, None).unwrap();
+        source.put("https://a/1.png", &response(b"one")).unwrap();
+        source.index.entries.values_mut().for_each(|e| e.size = 1);
+        source.persist_index().unwrap();
+        let dir = from.path().join(CACHE_DIR);
+        let stray = dir.join("ff.blob");
+        fs::write(&stray, b"not indexed").unwrap();
+        let mut files = vec![(INDEX_FILE.to_string(), dir.join(INDEX_FILE))];
+        files.extend(source.index.entries.keys().map(|name| {
+            let file = format!("{name}.{BLOB_EXTENSION}");
+            (file.clone(), dir.join(file))
+        }));
+        files.push(("ff.blob".to_string(), stray));
+        let archive = from.path().join("cache.lbxa");
+        archive::write(&archive, &files).unwrap();
+
+        let mut target = DiskCache::open(to.path(), 1 << 20, None).unwrap();
+        assert_eq!(target.import_archive(&archive).unwrap(), 1);
+        let name = target.blob_name("https://a/1.png");
+        let on_disk = fs::metadata(target.blob_path(&name)).unwrap().len();
+        assert_eq!(target.total_bytes(), on_disk);
+        assert!(!to.path().join(CACHE_DIR).join("ff.blob").exists());
+        assert!(!to.path().join("image_cache.import").exists());
+
+        // Nothing fits a budget smaller than the blob.
+        let small = tempdir().unwrap();
+        let mut tiny = DiskCache::open(small.path(), on_disk - 1, None).unwrap();
+        assert_eq!(tiny.import_archive(&archive).unwrap(), 0);
+    }
+}
@@ -0,0 +1,345 @@
+//! On-disk blob format for cached image responses.
+//!
+//! ```text
+//! "LBXC" | version (1) | flags (1) | body
+//! body (plain)     = payload
+//! body (encrypted) = nonce (24) | XChaCha20-Poly1305(payload, aad = blob name)
+//! payload          = field(mime_type) | field(final_url) | field(redirect_chain)
+//!                    | field(blurhash) | field(filename) | field(last_modified)
+//!                    | field(cache_control) | field(content_length) | data
+//! field            = u32le len | UTF-8 bytes
+//! ```
+//!
+//! The redirect chain is newline-separated, the content length is decimal,
+//! and an empty blurhash or header field means none. Blobs of any other
+//! version are rejected as corrupt.
+//!
+//! The host's key is never used directly: HKDF-SHA256 derives one subkey for
+//! naming blobs (HMAC-SHA256 of the URL), one for sealing them and one for the
+//! index's key check. Sealing binds each blob to its name, so a blob copied
+//! over another's file fails authentication instead of being served for the
+//! wrong URL.
+//!
+//! Only the fixed fields of [`ResponseHeaders`] are stored, never raw
+//! headers, so a blob cannot carry a `Set-Cookie` or other
+//! [state-bearing header](crate::tunnel::http1::STATE_BEARING_HEADERS).
+
+use super::disk::hex;
+use crate::dimensions::image_dimensions;
+use crate::error::ProxyError;
+use crate::integrity::sha256_hex;
+use crate::redirect;
+use crate::types::{ImageResponse, ResponseHeaders};
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use hkdf::Hkdf;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+/// Leading magic bytes of every blob.
+const BLOB_MAGIC: &[u8; 4] = b"LBXC";
+
+/// Blob format version written by [`encode`], the only one [`decode`] reads.
+const BLOB_VERSION: u8 = 4;
+
+/// Blob flag: the payload is sealed with the cache key.
+const FLAG_ENCRYPTED: u8 = 0x01;
+
+/// Required length of a host-supplied encryption key.
+pub(crate) const KEY_LEN: usize = 32;
+
+/// XChaCha20-Poly1305 nonce length.
+const NONCE_LEN: usize = 24;
+
+/// Subkeys derived from the host-supplied key, for naming and sealing blobs.
+pub(crate) struct BlobKey {
+    namer: Hmac<Sha256>,
+    aead: XChaCha20Poly1305,
+    check: [u8; KEY_LEN],
+}
+
+impl BlobKey {
+    pub(crate) fn new(key: &[u8]) -> Result<Self, ProxyError> {
+        let raw: [u8; KEY_LEN] = key.try_into().map_err(|_| ProxyError::CryptoError {
+            details: format!("Disk cache key must be exactly {KEY_LEN} bytes"),
+        })?;
+        let hkdf = Hkdf::<Sha256>::new(None, &raw);
+        let subkey = |info: &[u8]| {
+            let mut okm = [0u8; KEY_LEN];
+            hkdf.expand(info, &mut okm)
+                .map(|()| okm)
+                .map_err(|_| ProxyError::CryptoError {
+                    details: "Failed to derive disk cache subkey".to_string(),
+                })
+        };
+        let namer =
+            <Hmac<Sha256> as hmac::KeyInit>::new_from_slice(&subkey(b"letterbox-cache-name")?)
+                .map_err(|_| ProxyError::CryptoError {
+                    details: "Invalid disk cache naming key".to_string(),
+                })?;
+        Ok(Self {
+            namer,
+            aead: XChaCha20Poly1305::new(&subkey(b"letterbox-cache-seal")?.into()),
+            check: subkey(b"letterbox-cache-check")?,
+        })
+    }
+
+    /// The blob name for `url`.
+    pub(crate) fn name(&self, url: &str) -> String {
+        let mut mac = self.namer.clone();
+        mac.update(url.as_bytes());
+        hex(&mac.finalize().into_bytes())
+    }
+
+    /// A short fingerprint of the key, telling caches written with it from
+    /// others without revealing it.
+    pub(crate) fn check(&self) -> String {
+        hex(&self.check[..8])
+    }
+}
+
+/// Serialise (and, with a key, seal) a response into blob bytes, bound to
+/// the blob's `name` when sealed.
+pub(crate) fn encode(
+    key: Option<&BlobKey>,
+    name: &str,
+    response: &ImageResponse,
+) -> Result<Vec<u8>, ProxyError> {
+    let chain = response.redirect_chain.join("\n");
+    let headers = &response.headers;
+    let content_length = headers
+        .content_length
+        .map(|len| len.to_string())
+        .unwrap_or_default();
+    let mut payload = Vec::with_capacity(
+        response.data.len()
+            + response.mime_type.len()
+            + response.final_url.len()
+            + chain.len()
+            + 16,
+    );
+    for field in [
+        response.mime_type.as_bytes(),
+        response.final_url.as_bytes(),
+        chain.as_bytes(),
+        response.blurhash.as_deref().unwrap_or("").as_bytes(),
+        headers.filename.as_deref().unwrap_or("").as_bytes(),
+        headers.last_modified.as_deref().unwrap_or("").as_bytes(),
+        headers.cache_control.as_deref().unwrap_or("").as_bytes(),
+        content_length.as_bytes(),
+    ] {
+        payload.extend_from_slice(&(field.len() as u32).to_le_bytes());
+        payload.extend_from_slice(field);
+    }
+    payload.extend_from_slice(&response.data);
+
+    let mut blob = Vec::with_capacity(payload.len() + 64);
+    blob.extend_from_slice(BLOB_MAGIC);
+    blob.push(BLOB_VERSION);
+    match key {
+        Some(key) => {
+            let nonce: [u8; NONCE_LEN] = rand::random();
+            let sealed = key
+                .aead
+                .encrypt(
+                    XNonce::from_slice(&nonce),
+                    Payload {
+                        msg: &payload,
+                        aad: name.as_bytes(),
+                    },
+                )
+                .map_err(|_| ProxyError::CryptoError {
+                    details: "Failed to encrypt disk cache blob".to_string(),
+                })?;
+            blob.push(FLAG_ENCRYPTED);
+            blob.extend_from_slice(&nonce);
+            blob.extend_from_slice(&sealed);
+        }
+        None => {
+            blob.push(0);
+            blob.extend_from_slice(&payload);
+        }
+    }
+    Ok(blob)
+}
+
+/// Parse (and, with a key, open) blob bytes back into a response, failing
+/// if a sealed blob was not written under `name`.
+pub(crate) fn decode(
+    key: Option<&BlobKey>,
+    name: &str,
+    blob: &[u8],
+) -> Result<ImageResponse, ProxyError> {
+    let corrupt = |what: &str| ProxyError::StorageError {
+        details: format!("Corrupt disk cache blob: {what}"),
+    };
+    let header = blob.get(..6).ok_or_else(|| corrupt("truncated header"))?;
+    let version = header[4];
+    if &header[..4] != BLOB_MAGIC || version != BLOB_VERSION {
+        return Err(corrupt("bad magic or version"));
+    }
+    let encrypted = header[5] & FLAG_ENCRYPTED != 0;
+    let body = &blob[6..];
+
+    let opened;
+    let payload = match (key, encrypted) {
+        (Some(key), true) => {
+            let nonce = body
+                .get(..NONCE_LEN)
+                .ok_or_else(|| corrupt("missing nonce"))?;
+            opened = key
+                .aead
+                .decrypt(
+                    XNonce::from_slice(nonce),
+                    Payload {
+                        msg: &body[NONCE_LEN..],
+                        aad: name.as_bytes(),
+                    },
+                )
+                .map_err(|_| ProxyError::CryptoError {
+                    details: "Disk cache blob failed authentication".to_string(),
+                })?;
+            opened.as_slice()
+        }
+        (None, false) => body,
+        _ => return Err(corrupt("encryption mode mismatch")),
+    };
+
+    let mut rest = payload;
+    let mut fields: [String; 8] = Default::default();
+    for field in &mut fields {
+        let len_bytes: [u8; 4] = rest
+            .get(..4)
+            .and_then(|b| b.try_into().ok())
+            .ok_or_else(|| corrupt("truncated field length"))?;
+        let end = (u32::from_le_bytes(len_bytes) as usize)
+            .checked_add(4)
+            .ok_or_else(|| corrupt("oversized field"))?;
+        let value = rest.get(4..end).ok_or_else(|| corrupt("truncated field"))?;
+        *field = String::from_utf8(value.to_vec()).map_err(|_| corrupt("non-UTF-8 field"))?;
+        rest = &rest[end..];
+    }
+    let [mime_type, final_url, chain, blurhash, filename, last_modified, cache_control, content_length] =
+        fields;
+    let present = |field: String| (!field.is_empty()).then_some(field);
+
+    // Dimensions are cheap to re-read from the header, the hash to recompute
+    // and the redirect hops to derive from the chain, so none is stored.
+    let (width, height) = image_dimensions(rest).unzip();
+    let redirect_chain: Vec<String> = chain.lines().map(str::to_string).collect();
+    Ok(ImageResponse {
+        mime_type,
+        data: rest.to_vec(),
+        from_cache: true,
+        redirect_hops: redirect::hops(&redirect_chain, &final_url),
+        final_url,
+        redirect_chain,
+        width,
+        height,
+        blurhash: present(blurhash),
+        // Recomputed by the fetch path whenever the image is served.
+        tracking_suspected: false,
+        // Likewise set by the request that serves it.
+        trace_id: String::new(),
+        headers: ResponseHeaders {
+            filename: present(filename),
+            last_modified: present(last_modified),
+            cache_control: present(cache_control),
+            content_length: content_length.parse().ok(),
+        },
+        sha256: sha256_hex(rest),
+        // Served from the cache, so no request is made.
+        transport: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response() -> ImageResponse {
+        ImageResponse {
+            mime_type: "image/png".to_string(),
+            data: b"\x89PNG".to_vec(),
+            from_cache: false,
+            final_url: "https://cdn.example/a.png".to_string(),
+            redirect_chain: vec![
+                "https://t.example/r".to_string(),
+                "https://t.example/s".to_string(),
+            ],
+            redirect_hops: Vec::new(),
+            width: None,
+            height: None,
+            blurhash: Some("LEHV6nWB2yk8pyo0adR*.7kCMdnj".to_string()),
+            tracking_suspected: false,
+            trace_id: String::new(),
+            headers: ResponseHeaders {
+                filename: Some("a.png".to_string()),
+                last_modified: None,
+                cache_control: Some("max-age=3600".to_string()),
+                content_length: Some(4),
+            },
+            sha256: String::new(),
+            transport: None,
+        }
+    }
+
+    #[test]
+    fn round_trips_redirect_chain_blurhash_and_headers() {
+        let decoded = decode(None, "a", &encode(None, "a", &response()).unwrap()).unwrap();
+        assert_eq!(decoded.redirect_chain, response().redirect_chain);
+        assert_eq!(decoded.redirect_hops.len(), 2);
+        assert_eq!(decoded.blurhash, response().blurhash);
+        assert_eq!(decoded.headers, response().headers);
+        assert_eq!(decoded.sha256, sha256_hex(&response().data));
+        assert_eq!(decoded.data, response().data);
+
+        let none = ImageResponse {
+            blurhash: None,
+            ..response()
+        };
+        assert_eq!(
+            decode(None, "a", &encode(None, "a", &none).unwrap())
+                .unwrap()
+                .blurhash,
+            None
+        );
+    }
+
+    #[test]
+    fn sealed_blobs_are_bound_to_their_name() {
+        let key = BlobKey::new(&[4u8; KEY_LEN]).unwrap();
+        let name = key.name("https://a/x.png");
+        assert_ne!(name, key.name("https://a/y.png"));
+        assert_ne!(
+            name,
+            BlobKey::new(&[5u8; KEY_LEN])
+                .unwrap()
+                .name("https://a/x.png")
+        );
+
+        let sealed = encode(Some(&key), &name, &response()).unwrap();
+        assert_eq!(
+            decode(Some(&key), &name, &sealed).unwrap().data,
+            response().data
+        );
+        assert!(matches!(
+            decode(Some(&key), "other", &sealed),
+            Err(ProxyError::CryptoError { .. })
+        ));
+    }
+
+    #[test]
+    fn rejects_other_versions() {
+        let mut blob = b"LBXC\x03\x00".to_vec();
+        for field in ["image/gif", "https://cdn.example/a.gif", "", ""] {
+            blob.extend_from_slice(&(field.len() as u32).to_le_bytes());
+            blob.extend_from_slice(field.as_bytes());
+        }
+        blob.extend_from_slice(b"GIF8");
+        assert!(matches!(
+            decode(None, "a", &blob),
+            Err(ProxyError::StorageError { .. })
+        ));
+    }
+}
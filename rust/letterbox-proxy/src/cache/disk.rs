@@ -0,0 +1,400 @@
+//! Persistent, optionally encrypted image cache on disk.
+//!
+//! Each response is stored as one blob file under `<storage>/image_cache/`,
+//! named after a hash of its URL. A small `index.json` records blob sizes and
+//! last-access times so the byte budget can be enforced; it never contains URLs.
+//!
+//! When the host supplies a 32-byte key, blob names become *keyed* hashes and
+//! blob bodies are sealed with XChaCha20-Poly1305 (see [`blob`] for how the
+//! key is used). Without the key, neither the
+//! images nor which URLs were viewed can be recovered from the app's files
+//! directory — by backups, device forensics, or anything else.
+//!
+//! Opening the cache repairs what a crash left behind and sweeps out expired
+//! entries; see [`gc`](super::gc). Exporting and importing the cache as an
+//! archive lives in [`backup`](super::backup).
+
+use super::blob::{self, BlobKey};
+use super::gc::{Repairs, GC_INTERVAL_SECS};
+use crate::error::ProxyError;
+use crate::types::ImageResponse;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Directory (under the proxy storage path) holding cache blobs and the index.
+pub(crate) const CACHE_DIR: &str = "image_cache";
+
+/// Index file name inside [`CACHE_DIR`].
+pub(super) const INDEX_FILE: &str = "index.json";
+
+/// Extension of blob files inside [`CACHE_DIR`].
+pub(super) const BLOB_EXTENSION: &str = "blob";
+
+/// Persisted bookkeeping for the blobs in the cache directory.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(super) struct DiskIndex {
+    /// Fingerprint of the encryption key, empty when unencrypted.
+    pub(super) key_check: String,
+    /// Blob name -> bookkeeping.
+    pub(super) entries: HashMap<String, IndexEntry>,
+}
+
+/// Size, age and recency of one blob.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub(super) struct IndexEntry {
+    pub(super) size: u64,
+    pub(super) last_access: i64,
+    /// When the blob was written; 0 in indexes from before this was kept.
+    #[serde(default)]
+    pub(super) stored_at: i64,
+}
+
+impl IndexEntry {
+    fn new(size: u64, now: i64) -> Self {
+        Self {
+            size,
+            last_access: now,
+            stored_at: now,
+        }
+    }
+
+    /// When the blob was written, as far as is known.
+    pub(super) fn stored(&self) -> i64 {
+        match self.stored_at {
+            0 => self.last_access,
+            stored_at => stored_at,
+        }
+    }
+}
+
+/// Lowercase hex encoding of a digest.
+pub(crate) fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// A byte-budgeted blob store for image responses.
+pub(crate) struct DiskCache {
+    pub(super) dir: PathBuf,
+    pub(super) max_bytes: u64,
+    key: Option<BlobKey>,
+    pub(super) index: DiskIndex,
+    /// Entries stored longer ago than this many seconds are dropped.
+    pub(super) max_age: Option<i64>,
+    /// When expired entries were last swept out.
+    pub(super) last_gc: i64,
+    /// Whether `index` has access-time updates not yet written to disk.
+    dirty: bool,
+}
+
+impl DiskCache {
+    /// Open (creating if needed) the cache under `storage_path`.
+    ///
+    /// If the existing cache was written with a different key — or with no key
+    /// while one is now supplied, or vice versa — its blobs are unreadable and
+    /// are discarded. Otherwise the blobs are checked against the index and
+    /// anything a crash left behind is removed.
+    pub(crate) fn open(
+        storage_path: &Path,
+        max_bytes: u64,
+        key: Option<&[u8]>,
+    ) -> Result<Self, ProxyError> {
+        let key = key.map(BlobKey::new).transpose()?;
+        let dir = storage_path.join(CACHE_DIR);
+        fs::create_dir_all(&dir)?;
+
+        let key_check = key.as_ref().map(BlobKey::check).unwrap_or_default();
+
+        let index = fs::read(dir.join(INDEX_FILE))
+            .ok()
+            .and_then(|raw| serde_json::from_slice::<DiskIndex>(&raw).ok());
+
+        let mut cache = Self {
+            dir,
+            max_bytes,
+            key,
+            index: DiskIndex::default(),
+            max_age: None,
+            last_gc: 0,
+            dirty: false,
+        };
+        match index {
+            Some(index) if index.key_check == key_check => {
+                cache.index = index;
+                let repairs = cache.repair()?;
+                if repairs != Repairs::default() {
+                    log::warn!("Repaired disk cache: {repairs:?}");
+                    cache.persist_index()?;
+                }
+            }
+            _ => {
+                cache.remove_all_blobs()?;
+                cache.index.key_check = key_check;
+                cache.persist_index()?;
+            }
+        }
+        cache.collect_garbage()?;
+        Ok(cache)
+    }
+
+    /// Drop entries stored more than `max_age` ago (none by default), now and
+    /// from then on.
+    pub(crate) fn with_max_age(mut self, max_age: Option<Duration>) -> Result<Self, ProxyError> {
+        self.max_age = max_age.map(|age| i64::try_from(age.as_secs()).unwrap_or(i64::MAX));
+        self.collect_garbage()?;
+        Ok(self)
+    }
+
+    /// Blob name for `url`: a keyed hash when encrypted, a plain hash otherwise.
+    pub(super) fn blob_name(&self, url: &str) -> String {
+        match &self.key {
+            Some(key) => key.name(url),
+            None => hex(&Sha256::digest(url.as_bytes())),
+        }
+    }
+
+    pub(super) fn blob_path(&self, name: &str) -> PathBuf {
+        self.dir.join(format!("{name}.{BLOB_EXTENSION}"))
+    }
+
+    /// Number of stored blobs.
+    pub(crate) fn len(&self) -> usize {
+        self.index.entries.len()
+    }
+
+    /// Total bytes of all stored blobs.
+    pub(crate) fn total_bytes(&self) -> u64 {
+        self.index.entries.values().map(|e| e.size).sum()
+    }
+
+    /// Whether a blob for `url` is recorded in the index.
+    pub(crate) fn contains(&self, url: &str) -> bool {
+        self.index.entries.contains_key(&self.blob_name(url))
+    }
+
+    /// Read a cached response, dropping the blob if it is missing, corrupt
+    /// or expired.
+    pub(crate) fn get(&mut self, url: &str) -> Option<ImageResponse> {
+        let name = self.blob_name(url);
+        let entry = *self.index.entries.get(&name)?;
+        if self.expired(&entry, chrono::Utc::now().timestamp()) {
+            self.remove_blob(&name);
+            return None;
+        }
+        let decoded = fs::read(self.blob_path(&name))
+            .map_err(ProxyError::from)
+            .and_then(|raw| blob::decode(self.key.as_ref(), &name, &raw));
+        match decoded {
+            Ok(response) => {
+                if let Some(entry) = self.index.entries.get_mut(&name) {
+                    entry.last_access = chrono::Utc::now().timestamp();
+                    self.dirty = true;
+                }
+                Some(response)
+            }
+            Err(e) => {
+                log::warn!("Dropping unreadable disk cache blob: {e}");
+                self.remove_blob(&name);
+                None
+            }
+        }
+    }
+
+    /// Store a response, evicting least-recently-used blobs to fit the budget.
+    ///
+    /// Responses larger than the whole budget are not stored.
+    pub(crate) fn put(&mut self, url: &str, response: &ImageResponse) -> Result<(), ProxyError> {
+        let name = self.blob_name(url);
+        let blob = blob::encode(self.key.as_ref(), &name, response)?;
+        let size = blob.len() as u64;
+        if size > self.max_bytes {
+            return Ok(());
+        }
+
+        let path = self.blob_path(&name);
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, &blob)?;
+        fs::rename(&tmp, &path)?;
+        let now = chrono::Utc::now().timestamp();
+        self.index.entries.insert(name, IndexEntry::new(size, now));
+
+        if now.saturating_sub(self.last_gc) >= GC_INTERVAL_SECS {
+            self.collect_garbage()?;
+        }
+        self.evict_to(self.max_bytes)?;
+        self.persist_index()
+    }
+
+    /// Delete every blob and reset the index.
+    pub(crate) fn clear(&mut self) -> Result<(), ProxyError> {
+        self.remove_all_blobs()?;
+        self.persist_index()
+    }
+
+    /// Delete the whole cache directory, consuming the cache.
+    pub(crate) fn destroy(mut self) -> Result<(), ProxyError> {
+        self.dirty = false;
+        match fs::remove_dir_all(&self.dir) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Write pending access-time updates to the index.
+    pub(crate) fn flush(&mut self) -> Result<(), ProxyError> {
+        if self.dirty {
+            self.persist_index()?;
+        }
+        Ok(())
+    }
+
+    pub(super) fn remove_blob(&mut self, name: &str) {
+        self.index.entries.remove(name);
+        self.dirty = true;
+        if let Err(e) = fs::remove_file(self.blob_path(name)) {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                log::warn!("Failed to remove disk cache blob: {e}");
+            }
+        }
+    }
+
+    pub(super) fn remove_all_blobs(&mut self) -> Result<(), ProxyError> {
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext != "json") {
+                fs::remove_file(&path)?;
+            }
+        }
+        self.index.entries.clear();
+        self.dirty = true;
+        Ok(())
+    }
+
+    pub(super) fn persist_index(&mut self) -> Result<(), ProxyError> {
+        let path = self.dir.join(INDEX_FILE);
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, serde_json::to_vec(&self.index)?)?;
+        fs::rename(&tmp, &path)?;
+        self.dirty = false;
+        Ok(())
+    }
+}
+
+impl Drop for DiskCache {
+    fn drop(&mut self) {
+        if let Err(e) = self.flush() {
+            log::warn!("Failed to flush disk cache index: {e}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::blob::KEY_LEN;
+    use tempfile::tempdir;
+
+    fn response(data: &[u8]) -> ImageResponse {
+        ImageResponse {
+            mime_type: "image/png".to_string(),
+            data: data.to_vec(),
+            from_cache: false,
+            final_url: "https://cdn.example.com/a.png".to_string(),
+            redirect_chain: Vec::new(),
+            redirect_hops: Vec::new(),
+            width: None,
+            height: None,
+            blurhash: None,
+            tracking_suspected: false,
+            trace_id: String::new(),
+            headers: Default::default(),
+            sha256: String::new(),
+            transport: None,
+        }
+    }
+
+    #[test]
+    fn round_trips_plain_and_encrypted() {
+        for key in [None, Some([7u8; KEY_LEN])] {
+            let dir = tempdir().unwrap();
+            let mut cache =
+                DiskCache::open(dir.path(), 1 << 20, key.as_ref().map(|k| &k[..])).unwrap();
+            cache
+                .put("https://a/x.png", &response(b"\x89PNG-data"))
+                .unwrap();
+            let hit = cache.get("https://a/x.png").unwrap();
+            assert_eq!(hit.data, b"\x89PNG-data");
+            assert_eq!(hit.final_url, "https://cdn.example.com/a.png");
+            assert!(hit.from_cache);
+            assert!(cache.get("https://a/missing.png").is_none());
+        }
+    }
+
+    #[test]
+    fn encrypted_blobs_hide_content_and_url() {
+        let dir = tempdir().unwrap();
+        let key = [9u8; KEY_LEN];
+        let mut cache = DiskCache::open(dir.path(), 1 << 20, Some(&key)).unwrap();
+        cache
+            .put("https://secret.example/x.png", &response(b"SECRET-PIXELS"))
+            .unwrap();
+        drop(cache);
+
+        for entry in fs::read_dir(dir.path().join(CACHE_DIR)).unwrap() {
+            let raw = fs::read(entry.unwrap().path()).unwrap();
+            let text = String::from_utf8_lossy(&raw);
+            assert!(!text.contains("SECRET-PIXELS"));
+            assert!(!text.contains("secret.example"));
+        }
+    }
+
+    #[test]
+    fn reopening_with_another_key_discards_blobs() {
+        let dir = tempdir().unwrap();
+        let mut cache = DiskCache::open(dir.path(), 1 << 20, Some(&[1u8; KEY_LEN])).unwrap();
+        cache.put("https://a/x.png", &response(b"data")).unwrap();
+        drop(cache);
+
+        let mut reopened = DiskCache::open(dir.path(), 1 << 20, Some(&[2u8; KEY_LEN])).unwrap();
+        assert_eq!(reopened.len(), 0);
+        assert!(reopened.get("https://a/x.png").is_none());
+
+        let plain = DiskCache::open(dir.path(), 1 << 20, None).unwrap();
+        assert_eq!(plain.len(), 0);
+    }
+
+    #[test]
+    fn persists_across_reopen_with_same_key() {
+        let dir = tempdir().unwrap();
+        let key = [3u8; KEY_LEN];
+        let mut cache = DiskCache::open(dir.path(), 1 << 20, Some(&key)).unwrap();
+        cache.put("https://a/x.png", &response(b"data")).unwrap();
+        drop(cache);
+
+        let mut reopened = DiskCache::open(dir.path(), 1 << 20, Some(&key)).unwrap();
+        assert_eq!(reopened.get("https://a/x.png").unwrap().data, b"data");
+    }
+
+    #[test]
+    fn rejects_wrong_key_length() {
+        let dir = tempdir().unwrap();
+        assert!(matches!(
+            DiskCache::open(dir.path(), 1 << 20, Some(&[0u8; 16])),
+            Err(ProxyError::CryptoError { .. })
+        ));
+    }
+
+    #[test]
+    fn corrupt_blob_is_dropped() {
+        let dir = tempdir().unwrap();
+        let mut cache = DiskCache::open(dir.path(), 1 << 20, None).unwrap();
+        cache.put("https://a/x.png", &response(b"data")).unwrap();
+        let name = cache.blob_name("https://a/x.png");
+        fs::write(cache.blob_path(&name), b"garbage").unwrap();
+        assert!(cache.get("https://a/x.png").is_none());
+        assert_eq!(cache.len(), 0);
+    }
+}
@@ -0,0 +1,210 @@
+//! Repair and garbage collection for the [`DiskCache`].
+//!
+//! A crash can leave the directory out of step with the index: a blob written
+//! but never indexed, an indexed blob deleted or cut short, a temporary file
+//! never renamed. Opening the cache reconciles the two, and a sweep that runs
+//! on opening and then at most every [`GC_INTERVAL_SECS`] while storing drops
+//! entries past their maximum age and re-applies the byte budget.
+
+use super::disk::{DiskCache, IndexEntry, BLOB_EXTENSION};
+use crate::error::ProxyError;
+use std::collections::HashMap;
+use std::fs;
+
+/// Shortest time between two sweeps for expired entries while storing.
+pub(super) const GC_INTERVAL_SECS: i64 = 3600;
+
+/// What [`DiskCache::open`] repaired.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct Repairs {
+    /// Blob and temporary files the index did not know about, deleted.
+    pub(crate) orphans: usize,
+    /// Index entries whose blob was gone.
+    pub(crate) missing: usize,
+    /// Blobs whose size did not match the index, deleted.
+    pub(crate) truncated: usize,
+}
+
+impl DiskCache {
+    /// Reconcile the directory with the index: delete files it does not know
+    /// and blobs of the wrong size, and forget entries whose blob is gone.
+    pub(super) fn repair(&mut self) -> Result<Repairs, ProxyError> {
+        let mut repairs = Repairs::default();
+        let mut sizes = HashMap::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            let Some(ext) = path.extension().and_then(|ext| ext.to_str()) else {
+                continue;
+            };
+            let name = path.file_stem().and_then(|stem| stem.to_str());
+            let known =
+                name.filter(|name| ext == BLOB_EXTENSION && self.index.entries.contains_key(*name));
+            match known {
+                Some(name) => {
+                    sizes.insert(name.to_string(), fs::metadata(&path)?.len());
+                }
+                None if ext != "json" => {
+                    fs::remove_file(&path)?;
+                    repairs.orphans += 1;
+                }
+                None => {}
+            }
+        }
+        let names: Vec<String> = self.index.entries.keys().cloned().collect();
+        for name in names {
+            match sizes.get(&name) {
+                None => {
+                    self.index.entries.remove(&name);
+                    repairs.missing += 1;
+                }
+                Some(&size) if size != self.index.entries[&name].size => {
+                    self.remove_blob(&name);
+                    repairs.truncated += 1;
+                }
+                Some(_) => {}
+            }
+        }
+        Ok(repairs)
+    }
+
+    /// Drop expired entries and evict down to the byte budget.
+    pub(super) fn collect_garbage(&mut self) -> Result<(), ProxyError> {
+        let now = chrono::Utc::now().timestamp();
+        self.last_gc = now;
+        if self.max_age.is_some() {
+            let expired: Vec<String> = self
+                .index
+                .entries
+                .iter()
+                .filter(|(_, entry)| self.expired(entry, now))
+                .map(|(name, _)| name.clone())
+                .collect();
+            for name in &expired {
+                self.remove_blob(name);
+            }
+        }
+        self.evict_to(self.max_bytes)?;
+        self.flush()
+    }
+
+    /// Whether `entry` is past the maximum age.
+    pub(super) fn expired(&self, entry: &IndexEntry, now: i64) -> bool {
+        self.max_age
+            .is_some_and(|max_age| now.saturating_sub(entry.stored()) > max_age)
+    }
+
+    /// Evict least-recently-used blobs until the total is at most `budget`.
+    pub(super) fn evict_to(&mut self, budget: u64) -> Result<(), ProxyError> {
+        let mut total = self.total_bytes();
+        if total <= budget {
+            return Ok(());
+        }
+        let mut by_age: Vec<(String, IndexEntry)> = self
+            .index
+            .entries
+            .iter()
+            .map(|(name, entry)| (name.clone(), *entry))
+            .collect();
+        by_age.sort_by_key(|(_, entry)| entry.last_access);
+        for (name, entry) in by_age {
+            if total <= budget {
+                break;
+            }
+            self.remove_blob(&name);
+            total -= entry.size;
+        }
+        self.persist_index()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::disk::CACHE_DIR;
+    use crate::types::ImageResponse;
+    use std::time::Duration;
+    use tempfile::tempdir;
+
+    fn response(data: &[u8]) -> ImageResponse {
+        ImageResponse {
+            mime_type: "image/png".to_string(),
+            data: data.to_vec(),
+            from_cache: false,
+            final_url: "https://cdn.example.com/a.png".to_string(),
+            redirect_chain: Vec::new(),
+            redirect_hops: Vec::new(),
+            width: None,
+            height: None,
+            blurhash: None,
+            tracking_suspected: false,
+            trace_id: String::new(),
+            headers: Default::default(),
+            sha256: String::new(),
+            transport: None,
+        }
+    }
+
+    #[test]
+    fn evicts_least_recently_used_over_budget() {
+        let dir = tempdir().unwrap();
+        let mut cache = DiskCache::open(dir.path(), 300, None).unwrap();
+        cache
+            .put("https://a/1.png", &response(&[1u8; 100]))
+            .unwrap();
+        cache
+            .index
+            .entries
+            .values_mut()
+            .for_each(|e| e.last_access = 0);
+        cache
+            .put("https://a/2.png", &response(&[2u8; 100]))
+            .unwrap();
+        assert_eq!(cache.len(), 1);
+        assert!(cache.get("https://a/1.png").is_none());
+        assert!(cache.get("https://a/2.png").is_some());
+        assert!(cache.total_bytes() <= 300);
+    }
+
+    #[test]
+    fn opening_repairs_what_a_crash_left_behind() {
+        let dir = tempdir().unwrap();
+        let mut cache = DiskCache::open(dir.path(), 1 << 20, None).unwrap();
+        for url in [
+            "https://a/kept.png",
+            "https://a/gone.png",
+            "https://a/cut.png",
+        ] {
+            cache.put(url, &response(b"data")).unwrap();
+        }
+        let path = |url: &str| cache.blob_path(&cache.blob_name(url));
+        let (gone, cut) = (path("https://a/gone.png"), path("https://a/cut.png"));
+        drop(cache);
+        fs::remove_file(gone).unwrap();
+        fs::write(cut, b"da").unwrap();
+        let cache_dir = dir.path().join(CACHE_DIR);
+        fs::write(cache_dir.join("0123.blob"), b"orphan").unwrap();
+        fs::write(cache_dir.join("0123.tmp"), b"half-written").unwrap();
+
+        let mut cache = DiskCache::open(dir.path(), 1 << 20, None).unwrap();
+        assert_eq!(cache.len(), 1);
+        assert!(cache.get("https://a/kept.png").is_some());
+        assert_eq!(fs::read_dir(&cache_dir).unwrap().count(), 2);
+        assert_eq!(cache.repair().unwrap(), Repairs::default());
+    }
+
+    #[test]
+    fn expired_entries_are_dropped() {
+        let dir = tempdir().unwrap();
+        let mut cache = DiskCache::open(dir.path(), 1 << 20, None).unwrap();
+        cache.put("https://a/old.png", &response(b"old")).unwrap();
+        cache.put("https://a/new.png", &response(b"new")).unwrap();
+        let old = cache.blob_name("https://a/old.png");
+        cache.index.entries.get_mut(&old).unwrap().stored_at = 1;
+        let day = Duration::from_secs(24 * 60 * 60);
+
+        let mut cache = cache.with_max_age(Some(day)).unwrap();
+        assert_eq!(cache.len(), 1);
+        assert!(cache.get("https://a/new.png").is_some());
+        assert!(!cache.blob_path(&old).exists());
+    }
+}
@@ -0,0 +1,455 @@
+//! Image cache: an in-memory LRU with pinning, backed by an optional disk tier.
+//!
+//! [`ImageCache`] is a bounded LRU keyed by the requested URL, plus a separate
+//! set of *pinned* URLs. Pinned entries live outside the LRU, so they are never
+//! evicted by capacity pressure — useful for images the UI needs on every screen
+//! (sender avatars, brand logos). A URL can be pinned before it has ever been
+//! fetched; its response is then retained as soon as it arrives.
+//!
+//! When enabled, the persistent [`disk`] tier sits behind the memory tier:
+//! misses fall through to it and hits are promoted back into memory. Under
+//! memory pressure the LRU can be trimmed, optionally spilling to disk (see
+//! [`trim`]).
+//!
+//! The host can also plug in a [`CacheStorage`] of its own, behind both tiers
+//! or in place of the memory LRU (see [`storage`]).
+//!
+//! The FFI surface for cache management lives in [`api`]:
+//!
+//! - [`proxy_pin_url`] / [`proxy_unpin_url`] / [`proxy_pinned_urls`] — pinning.
+//! - [`proxy_enable_disk_cache`] / [`proxy_disable_disk_cache`] — disk tier.
+//! - [`proxy_set_cache_storage`] — host-supplied cache backend.
+//! - [`proxy_trim_cache`] — shed memory on `onTrimMemory`.
+//! - [`proxy_export_cache`] / [`proxy_import_cache`] — carry the disk tier
+//!   across backups and device migrations.
+//! - [`proxy_clear_cache`] — drop cached responses.
+
+pub mod api;
+mod archive;
+mod backup;
+mod blob;
+pub mod disk;
+mod gc;
+pub mod storage;
+mod trim;
+
+pub use api::{
+    proxy_clear_cache, proxy_disable_disk_cache, proxy_enable_disk_cache, proxy_export_cache,
+    proxy_import_cache, proxy_pin_url, proxy_pinned_urls, proxy_set_cache_storage,
+    proxy_trim_cache, proxy_unpin_url,
+};
+pub use storage::{CacheStorage, CacheStorageMode};
+
+use crate::config::DEFAULT_IMAGE_ACCEPT;
+use crate::error::ProxyError;
+use crate::events::{EventSink, ProxyEvent};
+use crate::types::ImageResponse;
+use disk::DiskCache;
+use lru::LruCache;
+use std::collections::HashMap;
+use std::num::NonZeroUsize;
+use std::sync::Arc;
+use storage::HostStorage;
+
+/// The cache key of a derived variant (e.g. a transcoded copy) of `url`.
+///
+/// A newline never appears in a valid URL, so variant keys cannot collide with
+/// the key of any original.
+pub(crate) fn variant_key(url: &str, tag: &str) -> String {
+    format!("{url}\n{tag}")
+}
+
+/// The cache key of `url` fetched with the `Accept` header `accept`.
+///
+/// Images fetched with the default header are keyed by their URL alone; any
+/// other header gets its own variant, so a consumer that cannot decode AVIF
+/// is never served one negotiated for a consumer that can.
+pub(crate) fn image_key(url: &str, accept: &str) -> String {
+    if accept == DEFAULT_IMAGE_ACCEPT {
+        url.to_string()
+    } else {
+        variant_key(url, &format!("accept:{accept}"))
+    }
+}
+
+/// Bounded LRU image cache with never-evicted pinned entries.
+pub(crate) struct ImageCache {
+    /// Unpinned responses, evicted least-recently-used first.
+    entries: LruCache<String, ImageResponse>,
+    /// Pinned URLs and their response, once one has been fetched.
+    pinned: HashMap<String, Option<ImageResponse>>,
+    /// Persistent tier, if the host enabled it.
+    disk: Option<DiskCache>,
+    /// Host-supplied backend, if the host registered one.
+    host: Option<HostStorage>,
+    /// Where evictions from memory are reported.
+    events: Option<Arc<EventSink>>,
+}
+
+impl ImageCache {
+    /// Create a cache holding at most `capacity` unpinned responses.
+    pub(crate) fn new(capacity: NonZeroUsize) -> Self {
+        Self {
+            entries: LruCache::new(capacity),
+            pinned: HashMap::new(),
+            disk: None,
+            host: None,
+            events: None,
+        }
+    }
+
+    /// Report evictions from memory to `events`.
+    pub(crate) fn with_events(self, events: Arc<EventSink>) -> Self {
+        Self {
+            events: Some(events),
+            ..self
+        }
+    }
+
+    fn report_evicted(&self, count: u32, bytes: u64) {
+        if let Some(events) = self.events.as_ref().filter(|_| count > 0) {
+            events.emit(ProxyEvent::CacheEvicted { count, bytes });
+        }
+    }
+
+    /// Install (or remove) the persistent tier, returning the previous one.
+    pub(crate) fn set_disk(&mut self, disk: Option<DiskCache>) -> Option<DiskCache> {
+        std::mem::replace(&mut self.disk, disk)
+    }
+
+    /// Install (or remove) the host-supplied backend.
+    ///
+    /// Switching to [`CacheStorageMode::Instead`] empties the memory LRU.
+    pub(crate) fn set_host(&mut self, host: Option<HostStorage>) {
+        if host
+            .as_ref()
+            .is_some_and(|h| h.mode == CacheStorageMode::Instead)
+        {
+            self.entries.clear();
+        }
+        self.host = host;
+    }
+
+    /// Whether unpinned responses are kept in the memory LRU.
+    fn uses_memory(&self) -> bool {
+        self.host
+            .as_ref()
+            .is_none_or(|host| host.mode == CacheStorageMode::Alongside)
+    }
+
+    /// Look up a cached response, refreshing its LRU position.
+    ///
+    /// Memory misses fall through to the disk tier, then to the host's
+    /// storage; hits there are promoted.
+    pub(crate) fn get(&mut self, url: &str) -> Option<ImageResponse> {
+        let in_memory = match self.pinned.get(url) {
+            Some(pinned) => pinned.clone(),
+            None => self.entries.get(url).cloned(),
+        };
+        if in_memory.is_some() {
+            return in_memory;
+        }
+        let found = match self.disk.as_mut().and_then(|disk| disk.get(url)) {
+            Some(from_disk) => from_disk,
+            None => self.host.as_ref()?.get(url)?,
+        };
+        self.put_memory(url.to_string(), found.clone());
+        Some(found)
+    }
+
+    /// Whether `url` is cached in either tier, without refreshing its recency.
+    pub(crate) fn contains(&self, url: &str) -> bool {
+        self.pinned.get(url).is_some_and(Option::is_some)
+            || self.entries.contains(url)
+            || self.disk.as_ref().is_some_and(|disk| disk.contains(url))
+            || self
+                .host
+                .as_ref()
+                .is_some_and(|host| host.get(url).is_some())
+    }
+
+    /// Store a response in memory and in whichever other tiers are enabled.
+    pub(crate) fn put(&mut self, url: String, response: ImageResponse) {
+        if let Some(disk) = self.disk.as_mut() {
+            if let Err(e) = disk.put(&url, &response) {
+                log::warn!("Failed to write disk cache entry: {e}");
+            }
+        }
+        if let Some(host) = &self.host {
+            host.put(&url, &response);
+        }
+        self.put_memory(url, response);
+    }
+
+    /// Store a response in memory, outside the LRU if its URL is pinned.
+    ///
+    /// Unpinned responses are not kept when the host's storage replaces the
+    /// memory LRU.
+    fn put_memory(&mut self, url: String, response: ImageResponse) {
+        let uses_memory = self.uses_memory();
+        match self.pinned.get_mut(&url) {
+            Some(slot) => *slot = Some(response),
+            None if uses_memory => self.push_lru(url, response),
+            None => {}
+        }
+    }
+
+    /// Insert into the LRU, reporting the entry it evicts, if any.
+    fn push_lru(&mut self, url: String, response: ImageResponse) {
+        // `push` also hands back a replaced entry under the same URL.
+        if let Some((old_url, old)) = self.entries.push(url, response) {
+            if !self.entries.contains(&old_url) {
+                self.report_evicted(1, old.data.len() as u64);
+            }
+        }
+    }
+
+    /// Pin `url`, moving any cached response out of the LRU.
+    pub(crate) fn pin(&mut self, url: String) {
+        if self.pinned.contains_key(&url) {
+            return;
+        }
+        let cached = self.entries.pop(&url);
+        self.pinned.insert(url, cached);
+    }
+
+    /// Unpin `url`, returning its response (if any) to the LRU.
+    ///
+    /// Returns `false` if the URL was not pinned.
+    pub(crate) fn unpin(&mut self, url: &str) -> bool {
+        match self.pinned.remove_entry(url) {
+            Some((url, cached)) => {
+                if let Some(response) = cached {
+                    self.push_lru(url, response);
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// All pinned URLs, sorted for stable presentation.
+    pub(crate) fn pinned_urls(&self) -> Vec<String> {
+        let mut urls: Vec<String> = self.pinned.keys().cloned().collect();
+        urls.sort_unstable();
+        urls
+    }
+
+    /// Number of cached responses, pinned or not.
+    pub(crate) fn len(&self) -> usize {
+        self.entries.len() + self.pinned.values().filter(|r| r.is_some()).count()
+    }
+
+    /// Bytes of image data held in memory, pinned or not.
+    pub(crate) fn memory_bytes(&self) -> u64 {
+        let unpinned = self.entries.iter().map(|(_, r)| r.data.len() as u64);
+        let pinned = self.pinned.values().flatten().map(|r| r.data.len() as u64);
+        unpinned.chain(pinned).sum()
+    }
+
+    /// The disk tier, or an error if the host has not enabled it.
+    fn disk_mut(&mut self) -> Result<&mut DiskCache, ProxyError> {
+        self.disk.as_mut().ok_or_else(|| ProxyError::StorageError {
+            details: "Disk cache is not enabled".to_string(),
+        })
+    }
+
+    /// Entry count and byte total of the disk tier, zero when disabled.
+    pub(crate) fn disk_usage(&self) -> (usize, u64) {
+        self.disk
+            .as_ref()
+            .map_or((0, 0), |disk| (disk.len(), disk.total_bytes()))
+    }
+
+    /// Write the disk tier's pending index updates, if it is enabled.
+    pub(crate) fn flush(&mut self) -> Result<(), ProxyError> {
+        self.disk.as_mut().map_or(Ok(()), DiskCache::flush)
+    }
+
+    /// Drop every cached response, in memory, on disk and in the host's
+    /// storage.
+    ///
+    /// Pins themselves survive: a pinned URL is retained again on its next fetch.
+    pub(crate) fn clear(&mut self) {
+        self.entries.clear();
+        self.pinned.values_mut().for_each(|slot| *slot = None);
+        if let Some(disk) = self.disk.as_mut() {
+            if let Err(e) = disk.clear() {
+                log::warn!("Failed to clear disk cache: {e}");
+            }
+        }
+        if let Some(host) = &self.host {
+            host.clear();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(url: &str) -> ImageResponse {
+        ImageResponse {
+            mime_type: "image/png".to_string(),
+            data: vec![0x89, 0x50, 0x4E, 0x47],
+            from_cache: false,
+            final_url: url.to_string(),
+            redirect_chain: Vec::new(),
+            redirect_hops: Vec::new(),
+            width: None,
+            height: None,
+            blurhash: None,
+            tracking_suspected: false,
+            trace_id: String::new(),
+            headers: Default::default(),
+            sha256: String::new(),
+            transport: None,
+        }
+    }
+
+    fn cache(capacity: usize) -> ImageCache {
+        ImageCache::new(NonZeroUsize::new(capacity).unwrap())
+    }
+
+    #[test]
+    fn non_default_accept_headers_get_their_own_key() {
+        let url = "https://a/photo";
+        assert_eq!(image_key(url, DEFAULT_IMAGE_ACCEPT), url);
+        let webp_only = image_key(url, "image/webp,image/*");
+        assert_eq!(webp_only, "https://a/photo\naccept:image/webp,image/*");
+
+        let mut cache = cache(4);
+        cache.put(url.to_string(), response("avif"));
+        assert!(cache.get(&webp_only).is_none());
+    }
+
+    #[test]
+    fn pinned_entries_survive_lru_pressure() {
+        let mut cache = cache(2);
+        cache.put("https://a/avatar.png".to_string(), response("a"));
+        cache.pin("https://a/avatar.png".to_string());
+        for i in 0..10 {
+            cache.put(format!("https://b/{i}.png"), response("b"));
+        }
+        assert!(cache.get("https://a/avatar.png").is_some());
+        assert_eq!(cache.len(), 3);
+    }
+
+    #[test]
+    fn pin_before_fetch_retains_later_response() {
+        let mut cache = cache(1);
+        cache.pin("https://a/logo.png".to_string());
+        assert!(cache.get("https://a/logo.png").is_none());
+        assert_eq!(cache.len(), 0);
+
+        cache.put("https://a/logo.png".to_string(), response("a"));
+        cache.put("https://b/x.png".to_string(), response("b"));
+        cache.put("https://b/y.png".to_string(), response("b"));
+        assert!(cache.get("https://a/logo.png").is_some());
+    }
+
+    #[test]
+    fn unpin_returns_entry_to_lru() {
+        let mut cache = cache(1);
+        cache.pin("https://a/logo.png".to_string());
+        cache.put("https://a/logo.png".to_string(), response("a"));
+        assert!(cache.unpin("https://a/logo.png"));
+        assert!(!cache.unpin("https://a/logo.png"));
+        assert!(cache.pinned_urls().is_empty());
+
+        // Back in the LRU, so the next insert evicts it.
+        cache.put("https://b/x.png".to_string(), response("b"));
+        assert!(cache.get("https://a/logo.png").is_none());
+    }
+
+    #[test]
+    fn disk_hits_are_promoted_to_memory() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut cache = cache(1);
+        cache.set_disk(Some(DiskCache::open(dir.path(), 1 << 20, None).unwrap()));
+        cache.put("https://a/1.png".to_string(), response("a"));
+        cache.put("https://a/2.png".to_string(), response("a"));
+
+        // Evicted from memory but still on disk.
+        assert!(cache.entries.peek("https://a/1.png").is_none());
+        assert!(cache.get("https://a/1.png").is_some());
+        assert!(cache.entries.peek("https://a/1.png").is_some());
+    }
+
+    #[derive(Default)]
+    struct MapStorage(std::sync::Mutex<HashMap<String, Vec<u8>>>);
+
+    impl CacheStorage for MapStorage {
+        fn get(&self, key: String) -> Option<Vec<u8>> {
+            self.0.lock().unwrap().get(&key).cloned()
+        }
+        fn put(&self, key: String, value: Vec<u8>) {
+            self.0.lock().unwrap().insert(key, value);
+        }
+        fn delete(&self, key: String) {
+            self.0.lock().unwrap().remove(&key);
+        }
+        fn list(&self) -> Vec<String> {
+            self.0.lock().unwrap().keys().cloned().collect()
+        }
+    }
+
+    #[test]
+    fn host_storage_backs_or_replaces_memory() {
+        let storage = Arc::new(MapStorage::default());
+        let mut cache = cache(1);
+        cache.set_host(Some(HostStorage::new(
+            storage.clone(),
+            CacheStorageMode::Alongside,
+        )));
+        cache.put("https://a/1.png".to_string(), response("a"));
+        cache.put("https://a/2.png".to_string(), response("a"));
+        assert!(cache.entries.peek("https://a/1.png").is_none());
+        assert_eq!(
+            cache.get("https://a/1.png").unwrap().data,
+            response("a").data
+        );
+        assert!(cache.entries.peek("https://a/1.png").is_some());
+
+        cache.set_host(Some(HostStorage::new(
+            storage.clone(),
+            CacheStorageMode::Instead,
+        )));
+        assert_eq!(cache.memory_bytes(), 0);
+        cache.put("https://a/3.png".to_string(), response("a"));
+        assert!(cache.get("https://a/3.png").is_some());
+        assert_eq!(cache.memory_bytes(), 0);
+
+        storage.put("https://a/bad.png".to_string(), b"junk".to_vec());
+        assert!(!cache.contains("https://a/bad.png"));
+        assert_eq!(storage.list().len(), 3);
+
+        cache.clear();
+        assert!(storage.list().is_empty());
+    }
+
+    #[test]
+    fn contains_ignores_empty_pins() {
+        let mut cache = cache(2);
+        cache.pin("https://a/logo.png".to_string());
+        assert!(!cache.contains("https://a/logo.png"));
+        cache.put("https://a/logo.png".to_string(), response("a"));
+        assert!(cache.contains("https://a/logo.png"));
+    }
+
+    #[test]
+    fn clear_keeps_pins_but_drops_bodies() {
+        let mut cache = cache(4);
+        cache.pin("https://b/2.png".to_string());
+        cache.pin("https://a/1.png".to_string());
+        cache.put("https://a/1.png".to_string(), response("a"));
+        cache.put("https://c/3.png".to_string(), response("c"));
+        cache.clear();
+
+        assert_eq!(cache.len(), 0);
+        assert_eq!(
+            cache.pinned_urls(),
+            vec!["https://a/1.png".to_string(), "https://b/2.png".to_string()]
+        );
+    }
+}
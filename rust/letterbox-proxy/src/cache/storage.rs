@@ -0,0 +1,82 @@
+//! Host-supplied cache storage.
+//!
+//! A [`CacheStorage`] lets the app keep cached images in a backend of its
+//! own — Android's existing disk cache, an encrypted database — rather than
+//! (or as well as) the built-in tiers. Responses cross the FFI as opaque
+//! blobs in the disk tier's format, unencrypted: a host that wants them
+//! sealed at rest seals them in its backend.
+//!
+//! Calls are made while the proxy holds its state lock, so implementations
+//! should answer promptly and must not call back into the proxy.
+
+use super::blob;
+use crate::types::ImageResponse;
+use std::sync::Arc;
+
+/// A key-value store for cached image blobs, implemented by the host.
+///
+/// Keys are the proxy's cache keys: the image URL, or the URL followed by a
+/// newline and a variant tag for negotiated or transformed copies.
+#[uniffi::export(with_foreign)]
+pub trait CacheStorage: Send + Sync {
+    /// The blob stored under `key`, if any.
+    fn get(&self, key: String) -> Option<Vec<u8>>;
+    /// Store `value` under `key`, replacing any previous blob.
+    fn put(&self, key: String, value: Vec<u8>);
+    /// Forget the blob under `key`; unknown keys are ignored.
+    fn delete(&self, key: String);
+    /// Every key currently stored.
+    fn list(&self) -> Vec<String>;
+}
+
+/// How a [`CacheStorage`] relates to the built-in in-memory cache.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, uniffi::Enum)]
+pub enum CacheStorageMode {
+    /// Behind the memory cache (and the disk tier, if enabled): misses fall
+    /// through to the storage and its hits are kept in memory.
+    #[default]
+    Alongside,
+    /// In place of the memory cache: only pinned images stay in memory, and
+    /// every other lookup goes to the storage.
+    Instead,
+}
+
+/// A registered [`CacheStorage`] and how it is used.
+pub(crate) struct HostStorage {
+    storage: Arc<dyn CacheStorage>,
+    pub(crate) mode: CacheStorageMode,
+}
+
+impl HostStorage {
+    pub(crate) fn new(storage: Arc<dyn CacheStorage>, mode: CacheStorageMode) -> Self {
+        Self { storage, mode }
+    }
+
+    /// Read a stored response, deleting the blob if it cannot be decoded.
+    pub(crate) fn get(&self, key: &str) -> Option<ImageResponse> {
+        let raw = self.storage.get(key.to_string())?;
+        match blob::decode(None, key, &raw) {
+            Ok(response) => Some(response),
+            Err(e) => {
+                log::warn!("Dropping unreadable host cache blob: {e}");
+                self.storage.delete(key.to_string());
+                None
+            }
+        }
+    }
+
+    /// Store a response.
+    pub(crate) fn put(&self, key: &str, response: &ImageResponse) {
+        match blob::encode(None, key, response) {
+            Ok(raw) => self.storage.put(key.to_string(), raw),
+            Err(e) => log::warn!("Failed to encode host cache entry: {e}"),
+        }
+    }
+
+    /// Delete every stored blob.
+    pub(crate) fn clear(&self) {
+        for key in self.storage.list() {
+            self.storage.delete(key);
+        }
+    }
+}
@@ -0,0 +1,125 @@
+//! Shedding memory when the host is asked to, e.g. from `onTrimMemory`.
+
+use super::ImageCache;
+
+impl ImageCache {
+    /// Evict unpinned responses, least-recently-used first, until at most
+    /// `target_bytes` of image data remain in memory.
+    ///
+    /// With `spill`, evicted responses not already on disk are written to the
+    /// disk tier (if enabled) instead of being dropped. Pinned responses are
+    /// never evicted, so the target may not be reached. Returns bytes freed.
+    pub(crate) fn trim(&mut self, target_bytes: u64, spill: bool) -> u64 {
+        let mut total = self.memory_bytes();
+        let (mut count, mut freed) = (0, 0);
+        while total > target_bytes {
+            let Some((url, response)) = self.entries.pop_lru() else {
+                break;
+            };
+            let size = response.data.len() as u64;
+            total -= size;
+            freed += size;
+            count += 1;
+            if let Some(disk) = self.disk.as_mut().filter(|_| spill) {
+                if !disk.contains(&url) {
+                    if let Err(e) = disk.put(&url, &response) {
+                        log::warn!("Failed to spill cache entry to disk: {e}");
+                    }
+                }
+            }
+        }
+        self.report_evicted(count, freed);
+        freed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::disk::DiskCache;
+    use crate::events::{EventSink, ProxyEvent};
+    use crate::types::ImageResponse;
+    use std::num::NonZeroUsize;
+    use std::sync::Arc;
+
+    fn response(url: &str) -> ImageResponse {
+        ImageResponse {
+            mime_type: "image/png".to_string(),
+            data: vec![0x89, 0x50, 0x4E, 0x47],
+            from_cache: false,
+            final_url: url.to_string(),
+            redirect_chain: Vec::new(),
+            redirect_hops: Vec::new(),
+            width: None,
+            height: None,
+            blurhash: None,
+            tracking_suspected: false,
+            trace_id: String::new(),
+            headers: Default::default(),
+            sha256: String::new(),
+            transport: None,
+        }
+    }
+
+    fn cache(capacity: usize) -> ImageCache {
+        ImageCache::new(NonZeroUsize::new(capacity).unwrap())
+    }
+
+    #[test]
+    fn trim_evicts_lru_first_and_keeps_pins() {
+        let mut cache = cache(8);
+        cache.pin("https://a/pinned.png".to_string());
+        cache.put("https://a/pinned.png".to_string(), response("a"));
+        cache.put("https://b/old.png".to_string(), response("b"));
+        cache.put("https://b/new.png".to_string(), response("b"));
+        assert_eq!(cache.memory_bytes(), 12);
+
+        assert_eq!(cache.trim(8, false), 4);
+        assert!(cache.get("https://b/old.png").is_none());
+        assert!(cache.get("https://b/new.png").is_some());
+
+        // Pins survive even a zero target.
+        assert_eq!(cache.trim(0, false), 4);
+        assert_eq!(cache.memory_bytes(), 4);
+        assert!(cache.get("https://a/pinned.png").is_some());
+    }
+
+    #[test]
+    fn evictions_are_reported() {
+        struct Forward(std::sync::Mutex<std::sync::mpsc::Sender<ProxyEvent>>);
+        impl crate::events::ProxyEventListener for Forward {
+            fn on_event(&self, event: ProxyEvent) {
+                let _ = self.0.lock().unwrap().send(event);
+            }
+        }
+        let (tx, rx) = std::sync::mpsc::channel();
+        let sink = Arc::new(EventSink::default());
+        sink.set_listener(Some(Arc::new(Forward(std::sync::Mutex::new(tx)))))
+            .unwrap();
+
+        let mut cache = cache(2).with_events(sink);
+        cache.put("https://a/1.png".to_string(), response("a"));
+        cache.put("https://a/1.png".to_string(), response("a"));
+        cache.put("https://a/2.png".to_string(), response("a"));
+        cache.put("https://a/3.png".to_string(), response("a"));
+        cache.trim(0, false);
+
+        let wait = std::time::Duration::from_secs(5);
+        let evicted = |count, bytes| ProxyEvent::CacheEvicted { count, bytes };
+        assert_eq!(rx.recv_timeout(wait).unwrap(), evicted(1, 4));
+        assert_eq!(rx.recv_timeout(wait).unwrap(), evicted(2, 8));
+    }
+
+    #[test]
+    fn trim_spills_to_disk() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut cache = cache(8);
+        cache.put("https://a/1.png".to_string(), response("a"));
+        cache.set_disk(Some(DiskCache::open(dir.path(), 1 << 20, None).unwrap()));
+
+        cache.trim(0, true);
+        assert_eq!(cache.memory_bytes(), 0);
+        assert_eq!(cache.disk_usage().0, 1);
+        assert!(cache.get("https://a/1.png").is_some());
+    }
+}
@@ -0,0 +1,371 @@
+//! Proxy instances.
+//!
+//! A [`ProxyClient`] owns everything one proxy needs — configuration, cache,
+//! tunnel and prefetch queue — so tests and multi-profile apps can run several
+//! independent proxies side by side. A client is ready as soon as its
+//! constructor returns and is torn down by [`ProxyClient::shutdown`] or when
+//! the last reference is dropped, so there is no init step to race against.
+//!
+//! Each module adds its operations to the client in its own
+//! `#[uniffi::export] impl ProxyClient` block. The `proxy_*` free functions
+//! remain for existing callers and operate on the process-wide
+//! [`default_client`], which [`proxy_init`](crate::proxy_init) and
+//! [`proxy_shutdown`](crate::proxy_shutdown) initialise and reset in place.
+//!
+//! Clients created over the same storage path share the persisted WARP
+//! identity and disk cache directory; give each profile its own path.
+
+use crate::audit::AuditLog;
+use crate::bandwidth::BandwidthLedger;
+use crate::cache::ImageCache;
+use crate::concurrency::FetchSlots;
+use crate::config::{FetchLimits, ProxyConfig};
+use crate::error::ProxyError;
+use crate::events::{EventSink, ProxyEvent};
+use crate::groups::GroupUsage;
+use crate::killswitch::{self, KillSwitch};
+use crate::logging;
+use crate::metrics::Metrics;
+use crate::offline::OfflineQueue;
+use crate::prefetch::PrefetchQueue;
+use crate::protect::SocketPolicy;
+use crate::recent_errors::ErrorLog;
+use crate::trace;
+use crate::tunnel::TunnelManager;
+use crate::types::{ImageResponse, ProxyStatus, Transport, UpdateResult, WarpDiagnostics};
+use crate::{block_on, ensure_manager, to_ffi_diagnostics, update, ProxyState};
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock};
+use std::time::Duration;
+
+/// Cache capacity used when the caller passes zero.
+const DEFAULT_CACHE_ENTRIES: NonZeroUsize = NonZeroUsize::new(100).unwrap();
+
+/// How long [`ProxyClient::shutdown`] waits for in-flight requests.
+const SHUTDOWN_DRAIN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// An independent image proxy.
+#[derive(uniffi::Object)]
+pub struct ProxyClient {
+    /// `None` until initialised and after shutdown.
+    state: Mutex<Option<ProxyState>>,
+    pub(crate) prefetch: Mutex<PrefetchQueue>,
+    pub(crate) offline: Arc<OfflineQueue>,
+    /// Shared with the cache, which reports its evictions.
+    pub(crate) events: Arc<EventSink>,
+    /// Shared with the tunnel worker, which counts traffic and handshakes.
+    pub(crate) metrics: Arc<Metrics>,
+    /// Shared with the tunnel worker, which counts the bytes of each host.
+    pub(crate) bandwidth: Arc<BandwidthLedger>,
+    /// The last errors reported to the app.
+    pub(crate) errors: ErrorLog,
+    /// Image requests, logged for the user to review.
+    pub(crate) audit: AuditLog,
+    /// Handed to each WARP tunnel the client starts.
+    pub(crate) socket_policy: SocketPolicy,
+    /// Taken by every network image fetch, whoever the caller.
+    pub(crate) slots: FetchSlots,
+    /// Checked by every network request, before and while it runs.
+    pub(crate) kill_switch: Arc<KillSwitch>,
+    /// Set while shutting down, so no new network requests start.
+    closing: AtomicBool,
+}
+
+static DEFAULT_CLIENT: OnceLock<Arc<ProxyClient>> = OnceLock::new();
+
+/// The client behind the `proxy_*` free functions.
+pub(crate) fn default_client() -> &'static Arc<ProxyClient> {
+    DEFAULT_CLIENT.get_or_init(ProxyClient::uninitialized)
+}
+
+impl ProxyClient {
+    /// A client yet to be initialised, known to the global kill switch.
+    fn uninitialized() -> Arc<Self> {
+        let kill_switch = Arc::<KillSwitch>::default();
+        let client = Arc::new(ProxyClient {
+            state: Mutex::new(None),
+            prefetch: Mutex::new(PrefetchQueue::default()),
+            offline: Arc::default(),
+            events: Arc::default(),
+            metrics: Arc::default(),
+            bandwidth: Arc::default(),
+            errors: ErrorLog::default(),
+            audit: AuditLog::default(),
+            socket_policy: SocketPolicy::default(),
+            slots: FetchSlots::new(Arc::clone(&kill_switch)),
+            kill_switch,
+            closing: AtomicBool::new(false),
+        });
+        killswitch::register(&client);
+        client
+    }
+
+    /// Load the configuration under `storage_path` and prepare the cache,
+    /// unless the client is already initialised.
+    pub(crate) fn init(&self, storage_path: &str, max_cache_size: u32) -> Result<(), ProxyError> {
+        logging::install();
+        let config = block_on(ProxyConfig::load_or_create(storage_path))??;
+        let cache_size =
+            NonZeroUsize::new(max_cache_size as usize).unwrap_or(DEFAULT_CACHE_ENTRIES);
+
+        let mut guard = self.lock_state();
+        if guard.is_some() {
+            return Ok(());
+        }
+        self.offline.load(&config.storage_path);
+        self.bandwidth.load(&config.storage_path);
+        self.audit.load(&config.storage_path);
+        *guard = Some(ProxyState {
+            config,
+            manager: None,
+            cache: ImageCache::new(cache_size).with_events(Arc::clone(&self.events)),
+            last_error: None,
+            paused: false,
+            data_saver: None,
+            groups: GroupUsage::default(),
+            #[cfg(feature = "provisioning")]
+            warp_refresh_attempt: None,
+        });
+        Ok(())
+    }
+
+    /// Lock the client state, recovering from poisoning.
+    ///
+    /// A panic while a guard is held — e.g. during WARP provisioning or tunnel
+    /// start-up, both of which run under this lock — poisons the `Mutex`. The
+    /// protected [`ProxyState`] nonetheless stays structurally valid: at worst
+    /// the tunnel manager is absent and gets rebuilt on the next fetch.
+    /// Reclaiming the guard via [`std::sync::PoisonError::into_inner`] lets the
+    /// proxy retry and surface the real error, instead of permanently reporting
+    /// a misleading "lock poisoned" failure that masks the original cause and
+    /// bricks the proxy until restart.
+    pub(crate) fn lock_state(&self) -> MutexGuard<'_, Option<ProxyState>> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Acquire the shared manager (initialising it if needed) under the lock,
+    /// returning a clone plus the current fetch limits. A WARP configuration
+    /// due for a refresh is refreshed first.
+    pub(crate) fn acquire_manager(&self) -> Result<(Arc<TunnelManager>, FetchLimits), ProxyError> {
+        if self.closing.load(Ordering::Acquire) {
+            return Err(ProxyError::NotInitialized);
+        }
+        self.kill_switch.check()?;
+        #[cfg(feature = "provisioning")]
+        self.refresh_warp_config_if_due();
+        let mut guard = self.lock_state();
+        let state = guard.as_mut().ok_or(ProxyError::NotInitialized)?;
+        let manager = ensure_manager(state, &self.events, &self.metrics, &self.socket_policy)?;
+        let limits = FetchLimits {
+            events: Some(Arc::clone(&self.events)),
+            metrics: Some(Arc::clone(&self.metrics)),
+            bandwidth: Some(Arc::clone(&self.bandwidth)),
+            kill_switch: Some(Arc::clone(&self.kill_switch)),
+            trace_id: trace::current(),
+            ..state.fetch_limits()
+        };
+        Ok((manager, limits))
+    }
+
+    /// Record an error met while requesting `url` for surfacing through
+    /// [`Self::status`] and [`Self::recent_errors`], and report it to the
+    /// event listener.
+    pub(crate) fn record_error(&self, url: Option<&str>, error: &ProxyError) {
+        let message = error.to_string();
+        if let Some(state) = self.lock_state().as_mut() {
+            state.last_error = Some(message.clone());
+        }
+        self.note_error(url, error);
+        self.events.emit(ProxyEvent::Error { message });
+    }
+
+    /// Keep an error met while requesting `url` for [`Self::recent_errors`]
+    /// only, e.g. one already reported in a batch result.
+    pub(crate) fn note_error(&self, url: Option<&str>, error: &ProxyError) {
+        self.errors.record(url, error, self.transport());
+    }
+
+    /// Add the image request for `url` that ended in `result` to the audit
+    /// log.
+    pub(crate) fn audit_fetch(&self, url: &str, result: &Result<ImageResponse, ProxyError>) {
+        let transport = match result {
+            Ok(_) => None,
+            Err(_) => self.transport(),
+        };
+        self.audit.record(url, result, transport);
+    }
+
+    /// The path the running tunnel manager sends requests over, if any.
+    fn transport(&self) -> Option<Transport> {
+        self.lock_state()
+            .as_ref()
+            .and_then(|state| state.manager.as_ref().map(|manager| manager.transport()))
+    }
+
+    /// Report that the tunnel went away because of `reason`, if it was up.
+    pub(crate) fn tunnel_dropped(&self, manager: Option<Arc<TunnelManager>>, reason: &str) {
+        if manager.is_some() {
+            self.events.emit(ProxyEvent::TunnelDisconnected {
+                reason: reason.to_string(),
+            });
+        }
+    }
+}
+
+#[uniffi::export]
+impl ProxyClient {
+    /// Create a proxy storing its configuration and WARP identity under
+    /// `storage_path`, with an in-memory cache of `max_cache_size` images.
+    ///
+    /// WARP provisioning and the WireGuard handshake are deferred until the
+    /// first fetch, so construction stays fast and works offline.
+    #[uniffi::constructor]
+    pub fn new(storage_path: String, max_cache_size: u32) -> Result<Arc<Self>, ProxyError> {
+        let client = ProxyClient::uninitialized();
+        client.init(&storage_path, max_cache_size)?;
+        client.start_offline_queue()?;
+        Ok(client)
+    }
+
+    /// Get the current proxy status.
+    pub fn status(&self) -> Result<ProxyStatus, ProxyError> {
+        let guard = self.lock_state();
+        match guard.as_ref() {
+            Some(state) => {
+                let (disk_cache_entries, disk_cache_bytes) = state.cache.disk_usage();
+                Ok(ProxyStatus {
+                    ready: true,
+                    warp_enabled: state.config.warp_enabled,
+                    tunnel_connected: state.manager.is_some(),
+                    endpoint: state.config.endpoint_host.clone(),
+                    last_error: state.last_error.clone(),
+                    cache_size: state.cache.len() as u32,
+                    disk_cache_entries: disk_cache_entries as u32,
+                    disk_cache_bytes,
+                })
+            }
+            None => Ok(ProxyStatus {
+                ready: false,
+                warp_enabled: false,
+                tunnel_connected: false,
+                endpoint: None,
+                last_error: Some("Proxy not initialized".to_string()),
+                cache_size: 0,
+                disk_cache_entries: 0,
+                disk_cache_bytes: 0,
+            }),
+        }
+    }
+
+    /// Collect full WireGuard/WARP diagnostics, provisioning the tunnel if
+    /// needed.
+    pub fn diagnostics(&self) -> Result<WarpDiagnostics, ProxyError> {
+        let manager = {
+            let mut guard = self.lock_state();
+            let state = guard.as_mut().ok_or(ProxyError::NotInitialized)?;
+            ensure_manager(state, &self.events, &self.metrics, &self.socket_policy)?
+        };
+        let diagnostics = manager.diagnostics()?;
+        Ok(to_ffi_diagnostics(diagnostics))
+    }
+
+    /// Check for a newer release through the tunnel.
+    ///
+    /// Pass the running version (e.g. `"v1.2.3"`); `repo` defaults to the
+    /// official distribution slug when empty.
+    pub fn check_for_update(
+        &self,
+        current_version: String,
+        repo: Option<String>,
+    ) -> Result<UpdateResult, ProxyError> {
+        let repo = repo
+            .filter(|r| !r.is_empty())
+            .unwrap_or_else(|| update::DEFAULT_REPO.to_string());
+
+        let (manager, _) = self.acquire_manager()?;
+        let info =
+            update::check_for_update(&manager, &current_version, &repo).inspect_err(|e| {
+                self.record_error(None, e);
+            })?;
+
+        Ok(UpdateResult {
+            update_available: info.update_available,
+            current_version: info.current_version,
+            latest_version: info.latest_version,
+            latest_tag: info.latest_tag,
+            changelog: info.changelog,
+            release_url: info.release_url,
+        })
+    }
+
+    /// Shut down the proxy. Every later call fails with `NotInitialized`.
+    ///
+    /// New network requests are refused at once and pending prefetches are
+    /// dropped. Requests already queued for or running in the tunnel get up
+    /// to five seconds to finish, so their responses still reach the cache;
+    /// then the disk cache index, bandwidth usage and configuration are
+    /// written out and the tunnel is closed. Blocks for the duration.
+    pub fn shutdown(&self) -> Result<(), ProxyError> {
+        self.closing.store(true, Ordering::Release);
+        self.cancel_prefetches();
+        // A worker backing off wakes up, finds the client closed and exits.
+        self.offline.wake();
+        let manager = self
+            .lock_state()
+            .as_ref()
+            .and_then(|state| state.manager.clone());
+        if let Some(manager) = &manager {
+            if !manager.drain(SHUTDOWN_DRAIN_TIMEOUT) {
+                log::warn!("Shutdown drain timed out; abandoning queued requests");
+            }
+        }
+
+        let state = self.lock_state().take();
+        self.closing.store(false, Ordering::Release);
+        let persisted = match state {
+            Some(mut state) => state
+                .cache
+                .flush()
+                .and_then(|()| self.bandwidth.flush())
+                .and_then(|()| block_on(state.config.save())?),
+            None => Ok(()),
+        };
+        // The worker thread exits now that its queue is closed, and is joined
+        // when the last reference to the manager goes.
+        self.tunnel_dropped(manager, "shutdown");
+        persisted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clients_are_independent() {
+        let (dir_a, dir_b) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+        let path = |dir: &tempfile::TempDir| dir.path().to_string_lossy().into_owned();
+        let a = ProxyClient::new(path(&dir_a), 10).unwrap();
+        let b = ProxyClient::new(path(&dir_b), 0).unwrap();
+        assert!(a.status().unwrap().ready && b.status().unwrap().ready);
+
+        a.record_error(Some("https://example.com/a.png"), &ProxyError::Throttled);
+        let message = ProxyError::Throttled.to_string();
+        assert_eq!(a.status().unwrap().last_error, Some(message));
+        assert_eq!(a.recent_errors()[0].host.as_deref(), Some("example.com"));
+        assert_eq!(b.status().unwrap().last_error, None);
+        assert!(b.recent_errors().is_empty());
+
+        a.shutdown().unwrap();
+        assert!(!a.status().unwrap().ready);
+        assert!(matches!(
+            a.acquire_manager(),
+            Err(ProxyError::NotInitialized)
+        ));
+        assert!(matches!(a.diagnostics(), Err(ProxyError::NotInitialized)));
+        assert!(b.status().unwrap().ready);
+    }
+}
@@ -0,0 +1,277 @@
+//! Fetch slots shared by every caller of a proxy.
+//!
+//! A batch's `max_concurrent` only bounds that batch: three messages opened
+//! at once, each fetching eight images at a time, would put 24 requests in
+//! flight, all of them perhaps to the same newsletter CDN. Every network
+//! image fetch therefore first takes a slot from the client's [`FetchSlots`],
+//! which admits at most [`FetchConcurrency::max_in_flight`] fetches in total
+//! and [`FetchConcurrency::max_per_host`] to any one host. Fetches over
+//! either cap wait for a slot — async callers on the shared runtime, blocking
+//! ones (the loopback server, favicons, the offline queue) on their own
+//! thread. Cache hits and `data:` URIs take no slot. Switching the client off
+//! fails the waiters with [`ProxyError::Disabled`].
+
+use crate::client::{default_client, ProxyClient};
+use crate::error::ProxyError;
+use crate::killswitch::KillSwitch;
+use crate::types::FetchConcurrency;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use tokio::sync::Notify;
+
+/// Fetches in flight, in total and per host.
+#[derive(Debug, Default)]
+struct InFlight {
+    total: u32,
+    hosts: HashMap<String, u32>,
+}
+
+impl InFlight {
+    /// Count a fetch to `host` if `limits` leave room for it.
+    fn try_take(&mut self, host: &str, limits: FetchConcurrency) -> bool {
+        let to_host = self.hosts.get(host).copied().unwrap_or(0);
+        let full = |used: u32, cap: u32| cap > 0 && used >= cap;
+        if full(self.total, limits.max_in_flight) || full(to_host, limits.max_per_host) {
+            return false;
+        }
+        self.total += 1;
+        *self.hosts.entry(host.to_string()).or_default() += 1;
+        true
+    }
+
+    fn release(&mut self, host: &str) {
+        self.total = self.total.saturating_sub(1);
+        if let Some(count) = self.hosts.get_mut(host) {
+            *count -= 1;
+            if *count == 0 {
+                self.hosts.remove(host);
+            }
+        }
+    }
+}
+
+/// The slots network fetches take, with both kinds of waiter.
+#[derive(Debug, Default)]
+pub(crate) struct FetchSlots {
+    in_flight: Mutex<InFlight>,
+    /// Wakes blocking waiters when a slot is freed.
+    freed_blocking: Condvar,
+    /// Wakes async waiters when a slot is freed.
+    freed: Notify,
+    /// The client's, checked by waiters when woken.
+    kill_switch: Arc<KillSwitch>,
+}
+
+/// A taken slot, given back when dropped.
+pub(crate) struct Slot<'a> {
+    slots: &'a FetchSlots,
+    host: String,
+}
+
+impl Drop for Slot<'_> {
+    fn drop(&mut self) {
+        self.slots.lock().release(&self.host);
+        self.slots.wake();
+    }
+}
+
+/// The host slots are counted against: `url`'s, lowercase.
+fn host_of(url: &str) -> String {
+    url::Url::parse(url)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_ascii_lowercase))
+        .unwrap_or_default()
+}
+
+impl FetchSlots {
+    fn lock(&self) -> MutexGuard<'_, InFlight> {
+        self.in_flight
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Slots whose waiters give up once `kill_switch` is off.
+    pub(crate) fn new(kill_switch: Arc<KillSwitch>) -> Self {
+        Self {
+            kill_switch,
+            ..Self::default()
+        }
+    }
+
+    fn slot(&self, host: String) -> Slot<'_> {
+        Slot { slots: self, host }
+    }
+
+    /// Have every waiter check again for a slot, or for its cancellation.
+    pub(crate) fn wake(&self) {
+        self.freed_blocking.notify_all();
+        self.freed.notify_waiters();
+    }
+
+    /// Wait for a slot to fetch `url` under `limits`. Fails with
+    /// [`ProxyError::Cancelled`] if `cancelled` is set while waiting, or
+    /// [`ProxyError::Disabled`] if the client is switched off, as noticed on
+    /// the next [`Self::wake`].
+    ///
+    /// Needs no particular async runtime: Kotlin polls these futures.
+    pub(crate) async fn acquire(
+        &self,
+        url: &str,
+        limits: FetchConcurrency,
+        cancelled: Option<&AtomicBool>,
+    ) -> Result<Slot<'_>, ProxyError> {
+        let host = host_of(url);
+        loop {
+            // Registered before checking, so a slot freed in between still
+            // wakes this waiter.
+            let freed = self.freed.notified();
+            tokio::pin!(freed);
+            freed.as_mut().enable();
+            if self.lock().try_take(&host, limits) {
+                return Ok(self.slot(host));
+            }
+            if cancelled.is_some_and(|flag| flag.load(Ordering::Relaxed)) {
+                return Err(ProxyError::Cancelled);
+            }
+            self.kill_switch.check()?;
+            freed.await;
+        }
+    }
+
+    /// [`Self::acquire`], blocking the calling thread.
+    pub(crate) fn acquire_blocking(
+        &self,
+        url: &str,
+        limits: FetchConcurrency,
+    ) -> Result<Slot<'_>, ProxyError> {
+        let host = host_of(url);
+        let mut in_flight = self.lock();
+        while !in_flight.try_take(&host, limits) {
+            self.kill_switch.check()?;
+            in_flight = self
+                .freed_blocking
+                .wait(in_flight)
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+        }
+        drop(in_flight);
+        Ok(self.slot(host))
+    }
+}
+
+#[uniffi::export]
+impl ProxyClient {
+    /// Replace the caps on image fetches in flight; fetches already waiting
+    /// for a slot are held to the new ones.
+    pub fn set_fetch_concurrency(&self, concurrency: FetchConcurrency) -> Result<(), ProxyError> {
+        let mut guard = self.lock_state();
+        let state = guard.as_mut().ok_or(ProxyError::NotInitialized)?;
+        state.config.fetch_concurrency = concurrency;
+        Ok(())
+    }
+}
+
+/// [`ProxyClient::set_fetch_concurrency`] on the default proxy.
+#[uniffi::export]
+pub fn proxy_set_fetch_concurrency(concurrency: FetchConcurrency) -> Result<(), ProxyError> {
+    default_client().set_fetch_concurrency(concurrency)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    fn limits(max_in_flight: u32, max_per_host: u32) -> FetchConcurrency {
+        FetchConcurrency {
+            max_in_flight,
+            max_per_host,
+        }
+    }
+
+    #[test]
+    fn caps_total_and_per_host_fetches() {
+        let mut in_flight = InFlight::default();
+        let limits = limits(3, 2);
+        assert!(in_flight.try_take("a.example", limits));
+        assert!(in_flight.try_take("a.example", limits));
+        assert!(!in_flight.try_take("a.example", limits));
+        assert!(in_flight.try_take("b.example", limits));
+        assert!(!in_flight.try_take("c.example", limits));
+
+        in_flight.release("a.example");
+        assert!(in_flight.try_take("c.example", limits));
+        in_flight.release("b.example");
+        in_flight.release("c.example");
+        assert_eq!(in_flight.total, 1);
+        assert_eq!(in_flight.hosts.len(), 1);
+
+        let mut unlimited = InFlight::default();
+        for _ in 0..100 {
+            assert!(unlimited.try_take("a.example", self::limits(0, 0)));
+        }
+    }
+
+    #[test]
+    fn hosts_are_case_insensitive() {
+        assert_eq!(host_of("https://CDN.Example.com/a.png"), "cdn.example.com");
+        assert_eq!(host_of("not a url"), "");
+    }
+
+    #[tokio::test]
+    async fn waiters_take_freed_slots() {
+        let slots = Arc::new(FetchSlots::default());
+        let limits = limits(1, 0);
+        let first = slots
+            .acquire("https://a.example/1", limits, None)
+            .await
+            .unwrap();
+
+        let waiter = {
+            let slots = Arc::clone(&slots);
+            tokio::spawn(async move {
+                let _slot = slots
+                    .acquire("https://b.example/2", limits, None)
+                    .await
+                    .unwrap();
+            })
+        };
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!waiter.is_finished());
+        drop(first);
+        tokio::time::timeout(Duration::from_secs(1), waiter)
+            .await
+            .expect("the waiter gets the freed slot")
+            .unwrap();
+        assert_eq!(slots.lock().total, 0);
+    }
+
+    #[tokio::test]
+    async fn cancelled_waiters_give_up_when_woken() {
+        let slots = Arc::new(FetchSlots::default());
+        let limits = limits(1, 0);
+        let _held = slots
+            .acquire_blocking("https://a.example/1", limits)
+            .unwrap();
+        let cancelled = Arc::new(AtomicBool::new(false));
+
+        let waiter = {
+            let (slots, cancelled) = (Arc::clone(&slots), Arc::clone(&cancelled));
+            tokio::spawn(async move {
+                slots
+                    .acquire("https://a.example/2", limits, Some(&cancelled))
+                    .await
+                    .map(|_| ())
+            })
+        };
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        cancelled.store(true, Ordering::Relaxed);
+        slots.wake();
+        let waited = tokio::time::timeout(Duration::from_secs(1), waiter)
+            .await
+            .expect("the waiter notices the cancellation")
+            .unwrap();
+        assert!(matches!(waited, Err(ProxyError::Cancelled)));
+    }
+}
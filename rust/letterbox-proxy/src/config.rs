@@ -3,9 +3,22 @@
 //! This module handles persistence of WARP credentials and tunnel configuration.
 //! Data is stored as JSON files in the application's private storage directory.
 
+use crate::bandwidth::BandwidthLedger;
 use crate::error::ProxyError;
+use crate::events::EventSink;
+use crate::groups;
+use crate::killswitch::KillSwitch;
+use crate::metrics::Metrics;
+use crate::tracking;
+use crate::types::{
+    FetchConcurrency, HttpsMode, RateLimit, RedirectPolicy, RefererGrant, RequestPriority,
+    RetryPolicy, TimeoutBudget, UpstreamProxy, WarpRefresh,
+};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
 
 /// WARP account data persisted per user.
 ///
@@ -57,8 +70,20 @@ pub struct WarpConfig {
     pub account_type: String,
     /// Timestamp when this configuration was last updated
     pub last_updated: i64,
+    /// Client ID WARP expects in the reserved bytes of each WireGuard
+    /// message (base64; empty when the account has none)
+    #[serde(default)]
+    pub client_id: String,
 }
 
+/// Default cap on width x height: about 200 MB as ARGB_8888, far beyond any
+/// legitimate inline email image.
+pub const DEFAULT_MAX_PIXELS: u64 = 50_000_000;
+
+/// Default `Accept` header for image requests: modern formats first, so CDNs
+/// that negotiate serve smaller files.
+pub const DEFAULT_IMAGE_ACCEPT: &str = "image/avif,image/webp,image/*";
+
 /// Proxy configuration including WARP settings and cache options.
 #[derive(Debug, Clone)]
 pub struct ProxyConfig {
@@ -72,10 +97,45 @@ pub struct ProxyConfig {
     pub endpoint_host: Option<String>,
     /// Maximum image size in bytes (default: 10MB)
     pub max_image_size: u64,
+    /// Maximum decoded pixel count, width x height (default: 50M; 0 = unlimited)
+    pub max_image_pixels: u64,
     /// Maximum number of redirects (default: 5)
     pub max_redirects: u32,
-    /// Request timeout in seconds (default: 30)
-    pub timeout_seconds: u32,
+    /// Time budgets of the request phases
+    pub timeouts: TimeoutBudget,
+    /// Sensitive request headers the host opted to forward (lowercase)
+    pub allowed_sensitive_headers: Vec<String>,
+    /// Retry policy for transient failures
+    pub retry_policy: RetryPolicy,
+    /// Policy applied to every redirect hop
+    pub redirect_policy: RedirectPolicy,
+    /// Handling of plain `http://` URLs
+    pub https_mode: HttpsMode,
+    /// Strip scripts and external references from SVGs (default: true)
+    pub sanitize_svg: bool,
+    /// Hosts whose images are flagged as tracking pixels (lowercase)
+    pub tracker_domains: Vec<String>,
+    /// Refuse suspected tracking pixels instead of flagging them (default: false)
+    pub block_tracking_pixels: bool,
+    /// `Accept` header sent with image requests
+    pub image_accept: String,
+    /// Send requests as TLS 0-RTT early data on resumed sessions (default: false)
+    pub tls_early_data: bool,
+    /// Delay after which a request with no response yet is sent again on a
+    /// second connection (default: none)
+    pub hedge_after: Option<Duration>,
+    /// Global ceiling on requests sent
+    pub rate_limit: RateLimit,
+    /// Caps on image fetches in flight, in total and per host
+    pub fetch_concurrency: FetchConcurrency,
+    /// Hosts resolved ahead of time by a warm-up (lowercase)
+    pub warm_up_hosts: Vec<String>,
+    /// Bytes the fetches of one group may receive (default: 50 MiB; 0 = unlimited)
+    pub group_byte_budget: u64,
+    /// When to fetch the WARP configuration again
+    pub warp_refresh: WarpRefresh,
+    /// Proxy carrying requests instead of WARP (default: none)
+    pub upstream_proxy: Option<UpstreamProxy>,
 }
 
 impl Default for ProxyConfig {
@@ -86,8 +146,25 @@ impl Default for ProxyConfig {
             warp_enabled: false,
             endpoint_host: None,
             max_image_size: 10 * 1024 * 1024, // 10MB
+            max_image_pixels: DEFAULT_MAX_PIXELS,
             max_redirects: 5,
-            timeout_seconds: 30,
+            timeouts: TimeoutBudget::default(),
+            allowed_sensitive_headers: Vec::new(),
+            retry_policy: RetryPolicy::default(),
+            redirect_policy: RedirectPolicy::default(),
+            https_mode: HttpsMode::default(),
+            sanitize_svg: true,
+            tracker_domains: tracking::default_tracker_domains(),
+            block_tracking_pixels: false,
+            image_accept: DEFAULT_IMAGE_ACCEPT.to_string(),
+            tls_early_data: false,
+            hedge_after: None,
+            rate_limit: RateLimit::default(),
+            fetch_concurrency: FetchConcurrency::default(),
+            warm_up_hosts: Vec::new(),
+            group_byte_budget: groups::DEFAULT_GROUP_BYTE_BUDGET,
+            warp_refresh: WarpRefresh::default(),
+            upstream_proxy: None,
         }
     }
 }
@@ -169,20 +246,58 @@ impl ProxyConfig {
 pub struct FetchLimits {
     /// Maximum image size in bytes
     pub max_size: u64,
+    /// Maximum decoded pixel count (0 = unlimited)
+    pub max_pixels: u64,
     /// Maximum number of redirects
     pub max_redirects: u32,
-    /// Request timeout in seconds
-    pub timeout_seconds: u32,
+    /// Time budgets of the request phases
+    pub timeouts: TimeoutBudget,
     /// Allowed content types (empty means all image/* types)
     pub allowed_content_types: Vec<String>,
+    /// Sensitive request headers allowed through the privacy filter (lowercase)
+    pub allowed_sensitive_headers: Vec<String>,
+    /// `Referer`/`Origin` granted to one host for this request's group
+    pub referer: Option<RefererGrant>,
+    /// Retry policy for transient failures
+    pub retry: RetryPolicy,
+    /// Policy applied to every redirect hop
+    pub redirect: RedirectPolicy,
+    /// Handling of plain `http://` URLs
+    pub https_mode: HttpsMode,
+    /// Whether SVG responses are sanitized
+    pub sanitize_svg: bool,
+    /// Whether requests may go out as TLS 0-RTT early data
+    pub tls_early_data: bool,
+    /// Delay after which a request with no response yet is hedged, if any
+    pub hedge_after: Option<Duration>,
+    /// Global ceiling on requests sent
+    pub rate_limit: RateLimit,
+    /// Where to report events such as HTTPS fallbacks, if anywhere
+    pub events: Option<Arc<EventSink>>,
+    /// Where to count tunnel traffic, handshakes and DNS timings, if anywhere
+    pub metrics: Option<Arc<Metrics>>,
+    /// Where to count the bytes received per host, if anywhere
+    pub bandwidth: Option<Arc<BandwidthLedger>>,
+    /// Position in the tunnel's request queue
+    pub priority: RequestPriority,
+    /// Set to abandon the request, e.g. when its batch is cancelled
+    pub cancelled: Option<Arc<AtomicBool>>,
+    /// Fails the request once the client is switched off, if set
+    pub kill_switch: Option<Arc<KillSwitch>>,
+    /// Trace id of the request, adopted by the tunnel worker's log records
+    pub trace_id: Option<String>,
+    /// Which attempt at the request this is, from 1; the tunnel worker
+    /// requeues a transient failure as the next one
+    pub attempt: u32,
 }
 
 impl Default for FetchLimits {
     fn default() -> Self {
         Self {
             max_size: 10 * 1024 * 1024, // 10MB
+            max_pixels: DEFAULT_MAX_PIXELS,
             max_redirects: 5,
-            timeout_seconds: 30,
+            timeouts: TimeoutBudget::default(),
             allowed_content_types: vec![
                 "image/jpeg".to_string(),
                 "image/png".to_string(),
@@ -192,12 +307,45 @@ impl Default for FetchLimits {
                 "image/bmp".to_string(),
                 "image/x-icon".to_string(),
                 "image/vnd.microsoft.icon".to_string(),
+                "image/avif".to_string(),
+                "image/heic".to_string(),
+                "image/heif".to_string(),
+                "image/jxl".to_string(),
             ],
+            allowed_sensitive_headers: Vec::new(),
+            referer: None,
+            retry: RetryPolicy::default(),
+            redirect: RedirectPolicy::default(),
+            https_mode: HttpsMode::default(),
+            sanitize_svg: true,
+            tls_early_data: false,
+            hedge_after: None,
+            rate_limit: RateLimit::default(),
+            events: None,
+            metrics: None,
+            bandwidth: None,
+            priority: RequestPriority::default(),
+            cancelled: None,
+            kill_switch: None,
+            trace_id: None,
+            attempt: 1,
         }
     }
 }
 
 impl FetchLimits {
+    /// Fail with [`ProxyError::Cancelled`] once the request has been
+    /// cancelled, or [`ProxyError::Disabled`] once the client is switched off.
+    pub fn check_cancelled(&self) -> Result<(), ProxyError> {
+        if let Some(switch) = &self.kill_switch {
+            switch.check()?;
+        }
+        match &self.cancelled {
+            Some(flag) if flag.load(Ordering::Relaxed) => Err(ProxyError::Cancelled),
+            _ => Ok(()),
+        }
+    }
+
     /// Check if a content type is allowed.
     pub fn is_content_type_allowed(&self, content_type: &str) -> bool {
         if self.allowed_content_types.is_empty() {
@@ -261,6 +409,7 @@ mod tests {
             warp_enabled: true,
             account_type: "free".to_string(),
             last_updated: 1704326400,
+            client_id: String::new(),
         };
 
         config.update_warp_config(warp_config).await.unwrap();
@@ -285,6 +434,9 @@ mod tests {
         assert!(limits.is_content_type_allowed("image/svg+xml"));
         assert!(limits.is_content_type_allowed("image/PNG")); // Case insensitive
         assert!(limits.is_content_type_allowed("image/png; charset=utf-8")); // With params
+        assert!(limits.is_content_type_allowed("image/avif"));
+        assert!(limits.is_content_type_allowed("image/heic"));
+        assert!(limits.is_content_type_allowed("image/jxl"));
 
         assert!(!limits.is_content_type_allowed("text/html"));
         assert!(!limits.is_content_type_allowed("application/json"));
@@ -323,6 +475,7 @@ mod tests {
             warp_enabled: true,
             account_type: "free".to_string(),
             last_updated: 1234567890,
+            client_id: "AQID".to_string(),
         };
 
         let json = serde_json::to_string(&config).unwrap();
@@ -331,5 +484,6 @@ mod tests {
         assert_eq!(parsed.account.account_id, "id123");
         assert_eq!(parsed.peer.endpoint_port, 51820);
         assert!(parsed.warp_enabled);
+        assert_eq!(parsed.client_id, "AQID");
     }
 }
@@ -0,0 +1,190 @@
+//! Local decoding of `data:` image URIs.
+//!
+//! Emails often inline small images as `data:image/png;base64,...`. Rather than
+//! making the host special-case them, [`crate::proxy_fetch_image`] accepts them
+//! and decodes them here without touching the network or the cache. The same
+//! size limit and image-type checks as for remote images apply.
+
+use crate::config::FetchLimits;
+use crate::dimensions::checked_dimensions;
+use crate::error::ProxyError;
+use crate::integrity::sha256_hex;
+use crate::placeholder;
+use crate::sniff::validate_image_data;
+use crate::svg;
+use crate::trace;
+use crate::types::{ImageResponse, ResponseHeaders};
+use base64::alphabet::STANDARD;
+use base64::engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig};
+use base64::Engine;
+use percent_encoding::percent_decode_str;
+
+/// Whether `url` is a `data:` URI (scheme matched case-insensitively).
+pub(crate) fn is_data_uri(url: &str) -> bool {
+    url.get(..5)
+        .is_some_and(|scheme| scheme.eq_ignore_ascii_case("data:"))
+}
+
+/// Standard base64 that tolerates missing padding, as mail clients often drop it.
+const BASE64: GeneralPurpose = GeneralPurpose::new(
+    &STANDARD,
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+fn invalid(url: &str, details: &str) -> ProxyError {
+    // Don't echo a potentially megabyte-long URI back in the error.
+    let shown: String = url.chars().take(64).collect();
+    ProxyError::InvalidUrl {
+        url: shown,
+        details: details.to_string(),
+    }
+}
+
+/// Decode an image `data:` URI into an [`ImageResponse`].
+///
+/// The media type must be an image type and the payload must look like it;
+/// payloads over `limits.max_size` are rejected before being decoded.
+pub(crate) fn decode(url: &str, limits: &FetchLimits) -> Result<ImageResponse, ProxyError> {
+    let rest = &url[5..];
+    let (meta, payload) = rest
+        .split_once(',')
+        .ok_or_else(|| invalid(url, "data: URI has no ',' separator"))?;
+
+    let mut params = meta.split(';');
+    let mime_type = params.next().unwrap_or("").trim().to_ascii_lowercase();
+    let is_base64 = params.any(|p| p.trim().eq_ignore_ascii_case("base64"));
+    // RFC 2397 defaults to text/plain, which is not an image either.
+    if !mime_type.starts_with("image/") {
+        return Err(ProxyError::InvalidContentType {
+            content_type: if mime_type.is_empty() {
+                "text/plain".to_string()
+            } else {
+                mime_type
+            },
+        });
+    }
+
+    let estimate = if is_base64 {
+        payload.len() as u64 / 4 * 3
+    } else {
+        payload.len() as u64
+    };
+    if estimate > limits.max_size {
+        return Err(ProxyError::ResponseTooLarge {
+            size: estimate,
+            max_size: limits.max_size,
+        });
+    }
+
+    let data = if is_base64 {
+        let compact: String = percent_decode_str(payload)
+            .decode_utf8_lossy()
+            .chars()
+            .filter(|c| !c.is_ascii_whitespace())
+            .collect();
+        BASE64
+            .decode(compact)
+            .map_err(|e| invalid(url, &format!("Invalid base64 payload: {e}")))?
+    } else {
+        percent_decode_str(payload).collect()
+    };
+
+    if data.len() as u64 > limits.max_size {
+        return Err(ProxyError::ResponseTooLarge {
+            size: data.len() as u64,
+            max_size: limits.max_size,
+        });
+    }
+    if !validate_image_data(&data, &mime_type) {
+        return Err(ProxyError::InvalidContentType {
+            content_type: mime_type,
+        });
+    }
+
+    let data = if limits.sanitize_svg && mime_type == "image/svg+xml" {
+        svg::sanitize(&data)?
+    } else {
+        data
+    };
+    let (width, height) = checked_dimensions(&data, limits.max_pixels)?;
+    let blurhash = placeholder::blurhash(&mime_type, &data, limits.max_pixels);
+    let sha256 = sha256_hex(&data);
+    Ok(ImageResponse {
+        mime_type,
+        data,
+        from_cache: false,
+        final_url: url.to_string(),
+        redirect_chain: Vec::new(),
+        redirect_hops: Vec::new(),
+        width,
+        height,
+        blurhash,
+        tracking_suspected: false,
+        trace_id: trace::current().unwrap_or_default(),
+        headers: ResponseHeaders::default(),
+        sha256,
+        transport: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PNG_B64: &str = "iVBORw0KGgo=";
+
+    #[test]
+    fn decodes_base64_images_with_whitespace_and_missing_padding() {
+        let limits = FetchLimits::default();
+        let url = format!("DATA:image/PNG;base64,{}", "iVBORw0K\r\n Ggo");
+        let image = decode(&url, &limits).unwrap();
+        assert_eq!(image.mime_type, "image/png");
+        assert_eq!(image.data, BASE64.decode(PNG_B64).unwrap());
+        assert!(is_data_uri(&url));
+    }
+
+    #[test]
+    fn decodes_percent_encoded_svg() {
+        let url = "data:image/svg+xml;utf8,%3Csvg%20xmlns='http://www.w3.org/2000/svg'%20onload='x()'/%3E";
+        let unsanitized = FetchLimits {
+            sanitize_svg: false,
+            ..FetchLimits::default()
+        };
+        let image = decode(url, &unsanitized).unwrap();
+        assert_eq!(
+            image.data,
+            b"<svg xmlns='http://www.w3.org/2000/svg' onload='x()'/>"
+        );
+        // Sanitized by default.
+        let image = decode(url, &FetchLimits::default()).unwrap();
+        assert_eq!(image.data, br#"<svg xmlns="http://www.w3.org/2000/svg"/>"#);
+    }
+
+    #[test]
+    fn rejects_non_images_mismatches_and_oversize() {
+        let limits = FetchLimits {
+            max_size: 4,
+            ..FetchLimits::default()
+        };
+        assert!(matches!(
+            decode("data:text/html,<script>", &limits),
+            Err(ProxyError::InvalidContentType { .. })
+        ));
+        assert!(matches!(
+            decode("data:,hello", &limits),
+            Err(ProxyError::InvalidContentType { .. })
+        ));
+        assert!(matches!(
+            decode("data:image/png;base64,R0lGODlh", &FetchLimits::default()),
+            Err(ProxyError::InvalidContentType { .. })
+        ));
+        assert!(matches!(
+            decode(&format!("data:image/png;base64,{PNG_B64}"), &limits),
+            Err(ProxyError::ResponseTooLarge { .. })
+        ));
+        assert!(matches!(
+            decode("data:image/png;base64", &limits),
+            Err(ProxyError::InvalidUrl { .. })
+        ));
+    }
+}
@@ -0,0 +1,190 @@
+//! Data-saver mode for metered networks.
+//!
+//! When Android reports a metered connection, the host turns on a
+//! [`DataSaver`] policy with [`ProxyClient::set_data_saver`]. While it is on:
+//!
+//! - image fetches over its `max_image_size` fail with
+//!   [`ProxyError::ResponseTooLarge`] instead of downloading the rest;
+//! - [`ProxyClient::prefetch`] queues nothing, and turning the mode on drops
+//!   the prefetches already queued;
+//! - a cached image is served even if it was negotiated under another
+//!   `Accept` header than the current one;
+//! - with `block_animations`, animated GIF, WebP and APNG images fetched from
+//!   the network fail with [`ProxyError::AnimationTooLarge`]. They are cached
+//!   all the same, so they show without another download once the mode is off.
+//!
+//! Fonts, stylesheets, link previews and [`ProxyClient::fetch_url`] keep their
+//! own limits, and the offline queue, which the user asked for explicitly,
+//! keeps running. The mode is not persisted; set it again after
+//! re-initialising.
+
+use crate::animation;
+use crate::client::{default_client, ProxyClient};
+use crate::error::ProxyError;
+use crate::types::ImageResponse;
+
+/// What changes while data-saver mode is on.
+#[derive(Clone, Debug, PartialEq, uniffi::Record)]
+pub struct DataSaver {
+    /// Largest image fetched from the network, in bytes; 0 keeps the usual
+    /// limit.
+    pub max_image_size: u64,
+    /// Refuse animated images fetched from the network.
+    pub block_animations: bool,
+}
+
+impl DataSaver {
+    /// The image size limit while saving data, given the usual one.
+    pub(crate) fn cap(&self, max_size: u64) -> u64 {
+        match self.max_image_size {
+            0 => max_size,
+            cap => cap.min(max_size),
+        }
+    }
+}
+
+/// Refuse `response` if it is animated.
+pub(crate) fn refuse_animation(response: &ImageResponse) -> Result<(), ProxyError> {
+    let frames = animation::frame_count(&response.mime_type, &response.data);
+    if frames > 1 {
+        let size = response.data.len() as u64;
+        return Err(ProxyError::AnimationTooLarge {
+            frames,
+            size,
+            max_frames: 1,
+            max_bytes: size,
+        });
+    }
+    Ok(())
+}
+
+#[uniffi::export]
+impl ProxyClient {
+    /// Turn data-saver mode on with `policy`, or off with `None`.
+    pub fn set_data_saver(&self, policy: Option<DataSaver>) -> Result<(), ProxyError> {
+        let saving = policy.is_some();
+        {
+            let mut guard = self.lock_state();
+            let state = guard.as_mut().ok_or(ProxyError::NotInitialized)?;
+            state.data_saver = policy;
+        }
+        if saving {
+            self.cancel_prefetches();
+        }
+        Ok(())
+    }
+
+    /// The data-saver policy in force, if the mode is on.
+    pub fn data_saver(&self) -> Result<Option<DataSaver>, ProxyError> {
+        let guard = self.lock_state();
+        Ok(guard
+            .as_ref()
+            .ok_or(ProxyError::NotInitialized)?
+            .data_saver
+            .clone())
+    }
+}
+
+/// [`ProxyClient::set_data_saver`] on the default proxy.
+#[uniffi::export]
+pub fn proxy_set_data_saver(policy: Option<DataSaver>) -> Result<(), ProxyError> {
+    default_client().set_data_saver(policy)
+}
+
+/// [`ProxyClient::data_saver`] of the default proxy.
+#[uniffi::export]
+pub fn proxy_data_saver() -> Result<Option<DataSaver>, ProxyError> {
+    default_client().data_saver()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::DEFAULT_IMAGE_ACCEPT;
+    use crate::fetch::fetch_image;
+    use crate::types::{FetchPriority, RequestPriority};
+    use std::sync::Arc;
+
+    fn gif_response(frames: usize) -> ImageResponse {
+        let mut gif = b"GIF89a\x01\x00\x01\x00\x80\x00\x00\x00\x00\x00\xFF\xFF\xFF".to_vec();
+        for _ in 0..frames {
+            gif.extend_from_slice(b"\x2C\x00\x00\x00\x00\x01\x00\x01\x00\x00\x02\x02\x44\x01\x00");
+        }
+        gif.push(0x3B);
+        ImageResponse {
+            mime_type: "image/gif".to_string(),
+            data: gif,
+            from_cache: false,
+            final_url: "https://cdn.example/a.gif".to_string(),
+            redirect_chain: Vec::new(),
+            redirect_hops: Vec::new(),
+            width: Some(1),
+            height: Some(1),
+            blurhash: None,
+            tracking_suspected: false,
+            trace_id: String::new(),
+            headers: Default::default(),
+            sha256: String::new(),
+            transport: None,
+        }
+    }
+
+    #[test]
+    fn caps_the_size_and_refuses_animations() {
+        let saver = DataSaver {
+            max_image_size: 500_000,
+            block_animations: true,
+        };
+        assert_eq!(saver.cap(10_000_000), 500_000);
+        assert_eq!(saver.cap(100), 100);
+        let unlimited = DataSaver {
+            max_image_size: 0,
+            ..saver
+        };
+        assert_eq!(unlimited.cap(10_000_000), 10_000_000);
+
+        assert!(refuse_animation(&gif_response(1)).is_ok());
+        assert!(matches!(
+            refuse_animation(&gif_response(3)),
+            Err(ProxyError::AnimationTooLarge {
+                frames: 3,
+                max_frames: 1,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn saving_data_skips_prefetches_and_serves_other_variants() {
+        let dir = tempfile::tempdir().unwrap();
+        let client = ProxyClient::new(dir.path().to_string_lossy().into_owned(), 10).unwrap();
+        let url = "https://cdn.example/a.gif";
+        client.set_image_accept("image/webp".to_string()).unwrap();
+        // Paused, a cache miss fails at once instead of going to the network.
+        client.pause().unwrap();
+        let fetch = || fetch_image(&client, url, None, RequestPriority::Visible);
+        if let Some(state) = client.lock_state().as_mut() {
+            let key = crate::cache::image_key(url, DEFAULT_IMAGE_ACCEPT);
+            state.cache.put(key, gif_response(1));
+        }
+        assert!(matches!(fetch(), Err(ProxyError::Paused)));
+
+        let saver = DataSaver {
+            max_image_size: 0,
+            block_animations: false,
+        };
+        client.set_data_saver(Some(saver.clone())).unwrap();
+        assert_eq!(client.data_saver().unwrap(), Some(saver));
+        assert!(fetch().unwrap().from_cache);
+        let urls = vec!["https://cdn.example/b.png".to_string()];
+        assert_eq!(
+            Arc::clone(&client)
+                .prefetch(urls, FetchPriority::Normal)
+                .unwrap(),
+            0
+        );
+
+        client.set_data_saver(None).unwrap();
+        assert_eq!(client.data_saver().unwrap(), None);
+    }
+}
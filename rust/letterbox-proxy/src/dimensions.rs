@@ -0,0 +1,281 @@
+//! Header-only image dimension extraction.
+//!
+//! Reads the pixel size straight out of the container headers — PNG `IHDR`,
+//! the JPEG start-of-frame segment, the GIF logical screen, the WebP `VP8X`,
+//! `VP8` or `VP8L` chunk, and SVG `width`/`height` (or `viewBox`) — without
+//! decoding any pixels, so the host UI can reserve layout space up front.
+//!
+//! The same header read guards against decompression bombs: a few kilobytes
+//! of highly compressed PNG can declare a gigapixel canvas, so images whose
+//! declared pixel count exceeds the configured limit are refused with
+//! [`ProxyError::ImageTooLarge`] before they ever reach the platform decoder.
+
+use crate::client::{default_client, ProxyClient};
+use crate::error::ProxyError;
+
+/// How much of an SVG document is searched for the root element.
+const SVG_SCAN_LEN: usize = 4096;
+
+/// Width and height in pixels, if the format is recognised and its header
+/// is intact.
+pub fn image_dimensions(data: &[u8]) -> Option<(u32, u32)> {
+    if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+        png(data)
+    } else if data.starts_with(&[0xFF, 0xD8]) {
+        jpeg(data)
+    } else if data.starts_with(b"GIF8") {
+        gif(data)
+    } else if data.starts_with(b"RIFF") && data.get(8..12) == Some(b"WEBP") {
+        webp(data)
+    } else {
+        svg(data)
+    }
+}
+
+/// Read the dimensions of `data`, refusing images over `max_pixels` (0 means
+/// unlimited). Returns `(width, height)`, each `None` if unknown.
+pub fn checked_dimensions(
+    data: &[u8],
+    max_pixels: u64,
+) -> Result<(Option<u32>, Option<u32>), ProxyError> {
+    let Some((width, height)) = image_dimensions(data) else {
+        return Ok((None, None));
+    };
+    if max_pixels > 0 && width as u64 * height as u64 > max_pixels {
+        return Err(ProxyError::ImageTooLarge {
+            width,
+            height,
+            max_pixels,
+        });
+    }
+    Ok((Some(width), Some(height)))
+}
+
+#[uniffi::export]
+impl ProxyClient {
+    /// Set the largest declared pixel count (width x height) accepted; `0`
+    /// disables the check.
+    pub fn set_max_image_pixels(&self, max_pixels: u64) -> Result<(), ProxyError> {
+        let mut guard = self.lock_state();
+        let state = guard.as_mut().ok_or(ProxyError::NotInitialized)?;
+        state.config.max_image_pixels = max_pixels;
+        Ok(())
+    }
+}
+
+/// [`ProxyClient::set_max_image_pixels`] on the default proxy.
+#[uniffi::export]
+pub fn proxy_set_max_image_pixels(max_pixels: u64) -> Result<(), ProxyError> {
+    default_client().set_max_image_pixels(max_pixels)
+}
+
+fn be16(data: &[u8], at: usize) -> Option<u32> {
+    data.get(at..at + 2)
+        .map(|b| u16::from_be_bytes([b[0], b[1]]) as u32)
+}
+
+fn le16(data: &[u8], at: usize) -> Option<u32> {
+    data.get(at..at + 2)
+        .map(|b| u16::from_le_bytes([b[0], b[1]]) as u32)
+}
+
+fn le24(data: &[u8], at: usize) -> Option<u32> {
+    data.get(at..at + 3)
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], 0]))
+}
+
+fn nonzero((width, height): (u32, u32)) -> Option<(u32, u32)> {
+    (width > 0 && height > 0).then_some((width, height))
+}
+
+/// `IHDR` is always the first chunk: width and height at offsets 16 and 20.
+fn png(data: &[u8]) -> Option<(u32, u32)> {
+    if data.get(12..16) != Some(b"IHDR") {
+        return None;
+    }
+    let word = |at: usize| {
+        data.get(at..at + 4)
+            .map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+    };
+    nonzero((word(16)?, word(20)?))
+}
+
+/// Walk the marker segments up to the first start-of-frame.
+fn jpeg(data: &[u8]) -> Option<(u32, u32)> {
+    let mut pos = 2;
+    loop {
+        // Skip to the marker byte, past any 0xFF fill bytes.
+        while *data.get(pos)? != 0xFF {
+            pos += 1;
+        }
+        while *data.get(pos)? == 0xFF {
+            pos += 1;
+        }
+        let marker = data[pos];
+        pos += 1;
+        match marker {
+            // Standalone markers carry no length.
+            0x01 | 0xD0..=0xD7 => continue,
+            0xD9 | 0xDA => return None,
+            // SOF0-SOF15, excluding DHT (C4), JPG (C8) and DAC (CC).
+            0xC0..=0xCF if !matches!(marker, 0xC4 | 0xC8 | 0xCC) => {
+                return nonzero((be16(data, pos + 5)?, be16(data, pos + 3)?));
+            }
+            _ => pos += be16(data, pos)? as usize,
+        }
+    }
+}
+
+/// The logical screen size follows the 6-byte signature.
+fn gif(data: &[u8]) -> Option<(u32, u32)> {
+    nonzero((le16(data, 6)?, le16(data, 8)?))
+}
+
+/// The first chunk after the RIFF header decides the layout.
+fn webp(data: &[u8]) -> Option<(u32, u32)> {
+    match data.get(12..16)? {
+        b"VP8X" => nonzero((le24(data, 24)? + 1, le24(data, 27)? + 1)),
+        // Lossy: 14-bit sizes after the frame tag and start code.
+        b"VP8 " => nonzero((le16(data, 26)? & 0x3FFF, le16(data, 28)? & 0x3FFF)),
+        // Lossless: 14-bit width-1 and height-1 packed after the signature byte.
+        b"VP8L" => {
+            let bits = u32::from_le_bytes(data.get(21..25)?.try_into().ok()?);
+            nonzero(((bits & 0x3FFF) + 1, ((bits >> 14) & 0x3FFF) + 1))
+        }
+        _ => None,
+    }
+}
+
+/// `width`/`height` attributes in absolute units, else the `viewBox` size.
+fn svg(data: &[u8]) -> Option<(u32, u32)> {
+    let text = String::from_utf8_lossy(&data[..data.len().min(SVG_SCAN_LEN)]);
+    let start = text.find("<svg")?;
+    let tag = &text[start..start + text[start..].find('>')?];
+
+    let size = attr(tag, "width")
+        .and_then(length)
+        .zip(attr(tag, "height").and_then(length));
+    size.or_else(|| {
+        let view_box: Vec<f64> = attr(tag, "viewBox")?
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|s| !s.is_empty())
+            .map(str::parse)
+            .collect::<Result<_, _>>()
+            .ok()?;
+        match view_box[..] {
+            [_, _, w, h] => pixels(w).zip(pixels(h)),
+            _ => None,
+        }
+    })
+}
+
+/// The quoted value of attribute `name` within `tag`.
+fn attr<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let mut rest = tag;
+    while let Some(at) = rest.find(name) {
+        let preceded = rest[..at].ends_with(|c: char| c.is_whitespace());
+        let after = rest[at + name.len()..].trim_start();
+        if let (true, Some(value)) = (preceded, after.strip_prefix('=')) {
+            let value = value.trim_start();
+            let quote = value.chars().next().filter(|&q| q == '"' || q == '\'')?;
+            let value = &value[1..];
+            return value.find(quote).map(|end| &value[..end]);
+        }
+        rest = &rest[at + name.len()..];
+    }
+    None
+}
+
+/// Parse an SVG length in pixels; relative units (`%`, `em`) are unknown.
+fn length(value: &str) -> Option<u32> {
+    let value = value.trim();
+    let number = value.strip_suffix("px").unwrap_or(value);
+    pixels(number.trim().parse().ok()?)
+}
+
+fn pixels(value: f64) -> Option<u32> {
+    (value.is_finite() && value >= 1.0 && value <= u32::MAX as f64).then(|| value.round() as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn png(width: u32, height: u32) -> Vec<u8> {
+        let mut data = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR".to_vec();
+        data.extend_from_slice(&width.to_be_bytes());
+        data.extend_from_slice(&height.to_be_bytes());
+        data
+    }
+
+    #[test]
+    fn pixel_limit_rejects_bombs() {
+        assert_eq!(
+            checked_dimensions(&png(100, 50), 5_000).unwrap(),
+            (Some(100), Some(50))
+        );
+        assert_eq!(
+            checked_dimensions(&png(100_000, 100_000), 5_000),
+            Err(ProxyError::ImageTooLarge {
+                width: 100_000,
+                height: 100_000,
+                max_pixels: 5_000
+            })
+        );
+        // 0 disables the limit; unknown dimensions always pass.
+        assert!(checked_dimensions(&png(100_000, 100_000), 0).is_ok());
+        assert_eq!(checked_dimensions(b"opaque", 1).unwrap(), (None, None));
+    }
+
+    #[test]
+    fn png_ihdr() {
+        let data = png(640, 480);
+        assert_eq!(image_dimensions(&data), Some((640, 480)));
+        assert_eq!(image_dimensions(&data[..20]), None);
+    }
+
+    #[test]
+    fn jpeg_skips_segments_to_sof() {
+        let data = [
+            0xFF, 0xD8, // SOI
+            0xFF, 0xE0, 0x00, 0x04, 0xAA, 0xBB, // APP0 with 2 payload bytes
+            0xFF, 0xFF, 0xC2, 0x00, 0x11, 0x08, 0x01, 0xE0, 0x02, 0x80, // SOF2 480x640
+        ];
+        assert_eq!(image_dimensions(&data), Some((640, 480)));
+        assert_eq!(image_dimensions(&[0xFF, 0xD8, 0xFF, 0xDA]), None);
+    }
+
+    #[test]
+    fn gif_logical_screen() {
+        assert_eq!(image_dimensions(b"GIF89a\x10\x00\x20\x00"), Some((16, 32)));
+    }
+
+    #[test]
+    fn webp_variants() {
+        let mut vp8x = b"RIFF\0\0\0\0WEBPVP8X\x0a\0\0\0\x10\0\0\0".to_vec();
+        vp8x.extend_from_slice(&[0x7F, 0x02, 0x00, 0xDF, 0x01, 0x00]);
+        assert_eq!(image_dimensions(&vp8x), Some((640, 480)));
+
+        let mut lossy = b"RIFF\0\0\0\0WEBPVP8 \0\0\0\0".to_vec();
+        lossy.extend_from_slice(&[0, 0, 0, 0x9D, 0x01, 0x2A, 0x80, 0x02, 0xE0, 0x01]);
+        assert_eq!(image_dimensions(&lossy), Some((640, 480)));
+
+        // VP8L: width-1 = 639, height-1 = 479 packed into 14-bit fields.
+        let bits: u32 = 639 | (479 << 14);
+        let mut lossless = b"RIFF\0\0\0\0WEBPVP8L\0\0\0\0\x2f".to_vec();
+        lossless.extend_from_slice(&bits.to_le_bytes());
+        assert_eq!(image_dimensions(&lossless), Some((640, 480)));
+    }
+
+    #[test]
+    fn svg_attributes_and_view_box() {
+        let svg = br#"<?xml version="1.0"?><svg stroke-width="3" width="120px" height='40'>"#;
+        assert_eq!(image_dimensions(svg), Some((120, 40)));
+
+        let relative = br#"<svg width="100%" height="100%" viewBox="0 0 24 24.4">"#;
+        assert_eq!(image_dimensions(relative), Some((24, 24)));
+
+        assert_eq!(image_dimensions(b"<svg>"), None);
+        assert_eq!(image_dimensions(b"not an image"), None);
+    }
+}
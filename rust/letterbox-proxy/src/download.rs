@@ -0,0 +1,166 @@
+//! Fetching images straight to a file.
+//!
+//! [`proxy_fetch_image_to_file`] streams the response body from the tunnel
+//! into the destination file as it arrives, so a multi-megabyte inline image is
+//! never copied across the FFI boundary or held whole in memory on either side.
+//! Only metadata is returned.
+
+use crate::cache::image_key;
+use crate::client::{default_client, ProxyClient};
+use crate::dimensions::checked_dimensions;
+use crate::error::ProxyError;
+use crate::progress::progress_for;
+use crate::sniff::effective_image_mime;
+use crate::svg;
+use crate::trace;
+use crate::types::{FileFetchOptions, FileFetchResult};
+use crate::{header_pairs, validate_image_url};
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// Leading bytes of a download inspected to confirm or correct its type and
+/// read its dimensions; generous enough to reach a JPEG frame header past
+/// EXIF and ICC segments.
+const SNIFF_LEN: u64 = 64 * 1024;
+
+/// The sibling path a download is written to before being renamed into place.
+fn partial_path(dest: &Path) -> PathBuf {
+    let mut partial = dest.as_os_str().to_owned();
+    partial.push(".partial");
+    PathBuf::from(partial)
+}
+
+#[uniffi::export]
+impl ProxyClient {
+    /// Fetch an image through the tunnel and write it to `dest_path`.
+    ///
+    /// A cached image is written from the cache without touching the network.
+    /// Downloads land in `<dest_path>.partial` and are renamed into place only once
+    /// complete and confirmed to be an image, so `dest_path` never holds a partial
+    /// or non-image body. Downloaded images are not added to the in-memory cache.
+    pub fn fetch_image_to_file(
+        &self,
+        url: String,
+        dest_path: String,
+        options: FileFetchOptions,
+    ) -> Result<FileFetchResult, ProxyError> {
+        trace::traced(|| fetch_to_file(self, &url, Path::new(&dest_path), &options)).inspect_err(
+            |e| {
+                self.record_error(Some(&url), e);
+            },
+        )
+    }
+}
+
+/// [`ProxyClient::fetch_image_to_file`] on the default proxy.
+#[uniffi::export]
+pub fn proxy_fetch_image_to_file(
+    url: String,
+    dest_path: String,
+    options: FileFetchOptions,
+) -> Result<FileFetchResult, ProxyError> {
+    default_client().fetch_image_to_file(url, dest_path, options)
+}
+
+fn fetch_to_file(
+    client: &ProxyClient,
+    url: &str,
+    dest: &Path,
+    options: &FileFetchOptions,
+) -> Result<FileFetchResult, ProxyError> {
+    validate_image_url(url)?;
+    if !options.overwrite && dest.exists() {
+        return Err(ProxyError::StorageError {
+            details: format!("Destination already exists: {}", dest.display()),
+        });
+    }
+    let partial = partial_path(dest);
+
+    let (cached, accept) = {
+        let mut guard = client.lock_state();
+        let state = guard.as_mut().ok_or(ProxyError::NotInitialized)?;
+        let accept = state.config.image_accept.clone();
+        (state.cache.get(&image_key(url, &accept)), accept)
+    };
+    if let Some(cached) = cached {
+        fs::write(&partial, &cached.data)?;
+        fs::rename(&partial, dest)?;
+        return Ok(FileFetchResult {
+            mime_type: cached.mime_type,
+            size: cached.data.len() as u64,
+            from_cache: true,
+            final_url: cached.final_url,
+        });
+    }
+
+    let (manager, limits) = client.acquire_manager()?;
+    let (max_pixels, sanitize_svg) = (limits.max_pixels, limits.sanitize_svg);
+    let outcome = manager.fetch_to_file(
+        url.to_string(),
+        header_pairs(options.headers.as_ref()),
+        accept,
+        limits,
+        partial.clone(),
+        progress_for(url),
+    )?;
+
+    let mut leading = Vec::new();
+    fs::File::open(&partial)?
+        .take(SNIFF_LEN)
+        .read_to_end(&mut leading)?;
+    let Some(mime_type) = effective_image_mime(&outcome.mime_type, &leading) else {
+        let _ = fs::remove_file(&partial);
+        return Err(ProxyError::InvalidContentType {
+            content_type: outcome.mime_type,
+        });
+    };
+    let mut size = outcome.size;
+    let checked = checked_dimensions(&leading, max_pixels).and_then(|_| {
+        if sanitize_svg && mime_type == "image/svg+xml" {
+            let clean = svg::sanitize(&fs::read(&partial)?)?;
+            fs::write(&partial, &clean)?;
+            size = clean.len() as u64;
+        }
+        Ok(())
+    });
+    if let Err(e) = checked {
+        let _ = fs::remove_file(&partial);
+        return Err(e);
+    }
+    fs::rename(&partial, dest)?;
+
+    Ok(FileFetchResult {
+        mime_type,
+        size,
+        from_cache: false,
+        final_url: outcome.final_url,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn partial_path_appends_suffix() {
+        assert_eq!(
+            partial_path(Path::new("/data/img.png")),
+            PathBuf::from("/data/img.png.partial")
+        );
+    }
+
+    #[test]
+    fn rejects_non_http_urls_before_touching_disk() {
+        let dir = tempfile::tempdir().unwrap();
+        let dest = dir.path().join("out.png");
+        let result = fetch_to_file(
+            default_client(),
+            "file:///etc/passwd",
+            &dest,
+            &FileFetchOptions::default(),
+        );
+        assert!(matches!(result, Err(ProxyError::InvalidUrl { .. })));
+        assert!(!dest.exists());
+    }
+}
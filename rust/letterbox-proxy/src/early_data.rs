@@ -0,0 +1,33 @@
+//! TLS 0-RTT early data for repeat hosts.
+//!
+//! Each network keeps the TLS sessions of the servers it reached, so a later
+//! connection to the same host resumes one instead of running a full
+//! handshake, saving a round trip through the tunnel. With early data on, a
+//! request to a host that last spoke HTTP/1.1 also rides in the first flight
+//! of the resumed connection, saving another. (HTTP/2 hosts keep an idle
+//! session open instead.)
+//!
+//! Early data can be replayed by anyone on the path to the server. The proxy
+//! only sends GET and HEAD requests, which servers must treat as idempotent,
+//! but a replay still repeats the request, so it is off by default.
+
+use crate::client::{default_client, ProxyClient};
+use crate::error::ProxyError;
+
+#[uniffi::export]
+impl ProxyClient {
+    /// Enable or disable TLS 0-RTT early data on resumed sessions (disabled
+    /// by default).
+    pub fn set_tls_early_data(&self, enabled: bool) -> Result<(), ProxyError> {
+        let mut guard = self.lock_state();
+        let state = guard.as_mut().ok_or(ProxyError::NotInitialized)?;
+        state.config.tls_early_data = enabled;
+        Ok(())
+    }
+}
+
+/// [`ProxyClient::set_tls_early_data`] on the default proxy.
+#[uniffi::export]
+pub fn proxy_set_tls_early_data(enabled: bool) -> Result<(), ProxyError> {
+    default_client().set_tls_early_data(enabled)
+}
@@ -0,0 +1,80 @@
+//! Recovery from a WARP endpoint that stopped answering.
+//!
+//! The tunnel sends to a fixed anycast address
+//! ([`WARP_ENDPOINT_IPV4`](crate::tunnel::transport::WARP_ENDPOINT_IPV4)).
+//! Should Cloudflare retire it, or a network block it, every handshake would
+//! time out until the app's storage was cleared. So when bringing up the
+//! tunnel times out, the peer's `endpoint_host` is resolved afresh over DoH,
+//! the answer is stored as the configuration's `endpoint_ipv4`, and the
+//! handshake is tried once more at that address, on the peer's own port.
+//!
+//! The tunnel being down, the lookup goes straight to the DoH resolver, like
+//! provisioning goes straight to Cloudflare's API. It starts from an empty DNS
+//! cache, so an address cached before the endpoint moved cannot answer it.
+
+use crate::block_on;
+use crate::config::{ProxyConfig, WarpConfig};
+use crate::error::ProxyError;
+use crate::protect::SocketSetup;
+use crate::tunnel::direct::DirectNetwork;
+use crate::tunnel::{dns, TunnelManager};
+use std::net::SocketAddr;
+use std::time::Duration;
+
+/// Address of `config`'s endpoint host as resolved now, on its port.
+fn resolve(config: &WarpConfig, timeout: Duration) -> Result<SocketAddr, ProxyError> {
+    let host = config.peer.endpoint_host.to_ascii_lowercase();
+    let addr = dns::resolve(&mut DirectNetwork::default(), &host, timeout)?;
+    Ok(SocketAddr::from((addr, config.peer.endpoint_port)))
+}
+
+/// Start the tunnel of `warp` at its endpoint host's current address, after
+/// storing that address in `config`.
+pub(crate) fn recover(
+    config: &mut ProxyConfig,
+    mut warp: WarpConfig,
+    socket: SocketSetup,
+) -> Result<TunnelManager, ProxyError> {
+    let timeout = Duration::from_secs(config.timeouts.dns_seconds.into());
+    let endpoint = resolve(&warp, timeout)?;
+    let ipv4 = endpoint.ip().to_string();
+    if warp.peer.endpoint_ipv4 != ipv4 {
+        log::info!("WARP endpoint {} moved to {ipv4}", warp.peer.endpoint_host);
+        warp.peer.endpoint_ipv4 = ipv4;
+        block_on(config.update_warp_config(warp.clone()))??;
+    }
+    TunnelManager::start_at(warp, socket, endpoint)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{WarpAccountData, WarpInterfaceConfig, WarpPeerConfig};
+
+    #[test]
+    fn resolves_the_endpoint_host_on_the_peer_port() {
+        let config = WarpConfig {
+            account: WarpAccountData {
+                account_id: String::new(),
+                access_token: String::new(),
+                private_key: String::new(),
+                license_key: String::new(),
+            },
+            peer: WarpPeerConfig {
+                public_key: String::new(),
+                endpoint_host: "192.0.2.7".to_string(),
+                endpoint_ipv4: "162.159.192.1".to_string(),
+                endpoint_port: 2408,
+            },
+            interface: WarpInterfaceConfig {
+                address_ipv4: "172.16.0.2/32".to_string(),
+            },
+            warp_enabled: true,
+            account_type: "free".to_string(),
+            last_updated: 0,
+            client_id: String::new(),
+        };
+        let endpoint = resolve(&config, Duration::from_secs(1)).unwrap();
+        assert_eq!(endpoint, "192.0.2.7:2408".parse().unwrap());
+    }
+}
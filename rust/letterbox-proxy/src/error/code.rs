@@ -0,0 +1,210 @@
+//! Classifying a [`ProxyError`]: its [`ErrorCode`], [`ErrorCategory`], HTTP
+//! status and whether it is worth retrying.
+
+use super::ProxyError;
+use crate::types::RetryPolicy;
+
+/// Machine-readable kind of a [`ProxyError`], one per variant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, uniffi::Enum)]
+pub enum ErrorCode {
+    NotInitialized,
+    InitializationFailed,
+    ProvisioningFailed,
+    TunnelError,
+    InvalidUrl,
+    HttpError,
+    InvalidContentType,
+    ResponseTooLarge,
+    ImageTooLarge,
+    AnimationTooLarge,
+    ImageProcessingError,
+    TrackingBlocked,
+    GroupBudgetExceeded,
+    HashMismatch,
+    TooManyRedirects,
+    InsecureUrl,
+    RedirectBlocked,
+    Timeout,
+    Cancelled,
+    Paused,
+    Disabled,
+    Throttled,
+    DnsError,
+    TlsError,
+    StorageError,
+    CryptoError,
+    NetworkUnavailable,
+}
+
+/// What an error is about, for choosing the message shown to the user.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, uniffi::Enum)]
+pub enum ErrorCategory {
+    /// The network, tunnel or remote server failed or could not be reached.
+    Network,
+    /// The server answered, but with something the proxy refuses to return
+    /// (not an image, too large, a tracking pixel, a redirect loop, ...).
+    Content,
+    /// The request itself is invalid, e.g. a malformed or refused URL.
+    Input,
+    /// The proxy could not set itself up or use its storage.
+    Configuration,
+    /// The proxy's state prevented the request: not initialised, paused,
+    /// throttled, or the request was cancelled.
+    State,
+}
+
+#[uniffi::export]
+impl ErrorCode {
+    /// What errors of this kind are about.
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            ErrorCode::ProvisioningFailed
+            | ErrorCode::TunnelError
+            | ErrorCode::HttpError
+            | ErrorCode::Timeout
+            | ErrorCode::DnsError
+            | ErrorCode::TlsError
+            | ErrorCode::NetworkUnavailable => ErrorCategory::Network,
+            ErrorCode::InvalidContentType
+            | ErrorCode::ResponseTooLarge
+            | ErrorCode::ImageTooLarge
+            | ErrorCode::AnimationTooLarge
+            | ErrorCode::ImageProcessingError
+            | ErrorCode::TrackingBlocked
+            | ErrorCode::GroupBudgetExceeded
+            | ErrorCode::HashMismatch
+            | ErrorCode::TooManyRedirects
+            | ErrorCode::RedirectBlocked => ErrorCategory::Content,
+            ErrorCode::InvalidUrl | ErrorCode::InsecureUrl => ErrorCategory::Input,
+            ErrorCode::InitializationFailed | ErrorCode::StorageError | ErrorCode::CryptoError => {
+                ErrorCategory::Configuration
+            }
+            ErrorCode::NotInitialized
+            | ErrorCode::Cancelled
+            | ErrorCode::Paused
+            | ErrorCode::Disabled
+            | ErrorCode::Throttled => ErrorCategory::State,
+        }
+    }
+}
+
+#[uniffi::export]
+impl ProxyError {
+    /// The variant, without its details.
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            ProxyError::NotInitialized => ErrorCode::NotInitialized,
+            ProxyError::InitializationFailed { .. } => ErrorCode::InitializationFailed,
+            ProxyError::ProvisioningFailed { .. } => ErrorCode::ProvisioningFailed,
+            ProxyError::TunnelError { .. } => ErrorCode::TunnelError,
+            ProxyError::InvalidUrl { .. } => ErrorCode::InvalidUrl,
+            ProxyError::HttpError { .. } => ErrorCode::HttpError,
+            ProxyError::InvalidContentType { .. } => ErrorCode::InvalidContentType,
+            ProxyError::ResponseTooLarge { .. } => ErrorCode::ResponseTooLarge,
+            ProxyError::ImageTooLarge { .. } => ErrorCode::ImageTooLarge,
+            ProxyError::AnimationTooLarge { .. } => ErrorCode::AnimationTooLarge,
+            ProxyError::ImageProcessingError { .. } => ErrorCode::ImageProcessingError,
+            ProxyError::TrackingBlocked { .. } => ErrorCode::TrackingBlocked,
+            ProxyError::GroupBudgetExceeded { .. } => ErrorCode::GroupBudgetExceeded,
+            ProxyError::HashMismatch { .. } => ErrorCode::HashMismatch,
+            ProxyError::TooManyRedirects { .. } => ErrorCode::TooManyRedirects,
+            ProxyError::InsecureUrl { .. } => ErrorCode::InsecureUrl,
+            ProxyError::RedirectBlocked { .. } => ErrorCode::RedirectBlocked,
+            ProxyError::Timeout { .. } => ErrorCode::Timeout,
+            ProxyError::Cancelled => ErrorCode::Cancelled,
+            ProxyError::Paused => ErrorCode::Paused,
+            ProxyError::Disabled => ErrorCode::Disabled,
+            ProxyError::Throttled => ErrorCode::Throttled,
+            ProxyError::DnsError { .. } => ErrorCode::DnsError,
+            ProxyError::TlsError { .. } => ErrorCode::TlsError,
+            ProxyError::StorageError { .. } => ErrorCode::StorageError,
+            ProxyError::CryptoError { .. } => ErrorCode::CryptoError,
+            ProxyError::NetworkUnavailable { .. } => ErrorCode::NetworkUnavailable,
+        }
+    }
+
+    /// The HTTP status the server answered with, if it answered at all.
+    pub fn http_status(&self) -> Option<u16> {
+        match self {
+            ProxyError::HttpError { status_code, .. } if *status_code != 0 => Some(*status_code),
+            _ => None,
+        }
+    }
+
+    /// What the error is about.
+    pub fn category(&self) -> ErrorCategory {
+        self.code().category()
+    }
+
+    /// Whether the error is transient, i.e. the same request may succeed
+    /// later: the network or tunnel failed, the server asked to come back
+    /// later (the default retry policy's statuses), or the proxy is paused or
+    /// throttled.
+    /// Everything else is permanent and fails the same way again.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            ProxyError::ProvisioningFailed { .. }
+            | ProxyError::TunnelError { .. }
+            | ProxyError::Timeout { .. }
+            | ProxyError::Paused
+            | ProxyError::Throttled
+            | ProxyError::DnsError { .. }
+            | ProxyError::NetworkUnavailable { .. }
+            | ProxyError::HttpError { status_code: 0, .. } => true,
+            ProxyError::HttpError { status_code, .. } => RetryPolicy::default()
+                .retryable_status_codes
+                .contains(status_code),
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codes_statuses_and_retryability() {
+        let not_found = ProxyError::HttpError {
+            status_code: 404,
+            details: "Not found".to_string(),
+        };
+        assert_eq!(not_found.code(), ErrorCode::HttpError);
+        assert_eq!(not_found.http_status(), Some(404));
+        assert!(!not_found.is_retryable());
+
+        let unavailable = ProxyError::HttpError {
+            status_code: 503,
+            details: "Service unavailable".to_string(),
+        };
+        assert!(unavailable.is_retryable());
+
+        let reset = ProxyError::HttpError {
+            status_code: 0,
+            details: "Connection reset".to_string(),
+        };
+        assert_eq!(reset.http_status(), None);
+        assert!(reset.is_retryable());
+
+        assert_eq!(not_found.category(), ErrorCategory::Network);
+        assert_eq!(ProxyError::Paused.code(), ErrorCode::Paused);
+        assert_eq!(ProxyError::Paused.category(), ErrorCategory::State);
+        assert!(ProxyError::Paused.is_retryable());
+        assert_eq!(ProxyError::Disabled.category(), ErrorCategory::State);
+        assert!(!ProxyError::Disabled.is_retryable());
+        assert_eq!(ProxyError::Throttled.category(), ErrorCategory::State);
+        assert!(ProxyError::Throttled.is_retryable());
+
+        let bad_url = ProxyError::InvalidUrl {
+            url: "bad-url".to_string(),
+            details: "missing scheme".to_string(),
+        };
+        assert_eq!(bad_url.category(), ErrorCategory::Input);
+        let not_image = ProxyError::InvalidContentType {
+            content_type: "text/html".to_string(),
+        };
+        assert_eq!(not_image.category(), ErrorCategory::Content);
+        assert!(!not_image.is_retryable());
+        assert!(!ProxyError::Cancelled.is_retryable());
+    }
+}
@@ -0,0 +1,389 @@
+//! Error types for the image proxy.
+//!
+//! This module defines all error types that can occur during proxy operations,
+//! including initialization, provisioning, tunnel establishment, and image fetching.
+//! Where an error is reported as data rather than thrown, e.g. in a batch
+//! result, it is identified by its [`ErrorCode`].
+//!
+//! Callers decide whether to retry and what to tell the user from
+//! [`ProxyError::is_retryable`] and [`ProxyError::category`], exported as
+//! methods of the Kotlin exception, rather than from the message text.
+
+mod code;
+
+pub use code::{ErrorCategory, ErrorCode};
+use thiserror::Error;
+
+/// Errors that can occur during proxy operations.
+#[derive(Debug, Error, Clone, PartialEq, Eq, uniffi::Error)]
+pub enum ProxyError {
+    /// The proxy has not been initialized (call `proxy_init()` first) or has
+    /// been shut down.
+    #[error("Proxy not initialized")]
+    NotInitialized,
+
+    /// Failed to initialize the proxy.
+    #[error("Initialization failed: {details}")]
+    InitializationFailed {
+        /// Detailed error message
+        details: String,
+    },
+
+    /// Failed to provision WARP credentials.
+    #[error("WARP provisioning failed: {details}")]
+    ProvisioningFailed {
+        /// Detailed error message
+        details: String,
+    },
+
+    /// Failed to establish WireGuard tunnel.
+    #[error("Tunnel error: {details}")]
+    TunnelError {
+        /// Detailed error message
+        details: String,
+    },
+
+    /// Invalid URL provided.
+    #[error("Invalid URL '{url}': {details}")]
+    InvalidUrl {
+        /// The invalid URL
+        url: String,
+        /// Detailed error message
+        details: String,
+    },
+
+    /// HTTP request failed.
+    #[error("HTTP error: {status_code} - {details}")]
+    HttpError {
+        /// HTTP status code (0 if connection failed)
+        status_code: u16,
+        /// Detailed error message
+        details: String,
+    },
+
+    /// The response content type is not an image.
+    #[error("Invalid content type: expected image, got {content_type}")]
+    InvalidContentType {
+        /// The actual content type received
+        content_type: String,
+    },
+
+    /// The response is too large.
+    #[error("Response too large: {size} bytes (max: {max_size})")]
+    ResponseTooLarge {
+        /// Actual size in bytes
+        size: u64,
+        /// Maximum allowed size in bytes
+        max_size: u64,
+    },
+
+    /// The image's declared dimensions exceed the decoded pixel limit.
+    #[error("Image too large: {width}x{height} pixels (max: {max_pixels})")]
+    ImageTooLarge {
+        /// Declared width in pixels
+        width: u32,
+        /// Declared height in pixels
+        height: u32,
+        /// Maximum allowed pixel count
+        max_pixels: u64,
+    },
+
+    /// An animation exceeds the request's frame or byte budget.
+    #[error("Animation too large: {frames} frames, {size} bytes (max: {max_frames} frames, {max_bytes} bytes)")]
+    AnimationTooLarge {
+        /// Frames in the animation
+        frames: u32,
+        /// Size of the animation in bytes
+        size: u64,
+        /// Maximum allowed frames
+        max_frames: u32,
+        /// Maximum allowed bytes
+        max_bytes: u64,
+    },
+
+    /// The image could not be decoded or re-encoded for a transform.
+    #[error("Image processing failed: {details}")]
+    ImageProcessingError {
+        /// Detailed error message
+        details: String,
+    },
+
+    /// The image was refused as a suspected tracking pixel.
+    #[error("Tracking pixel blocked: {url} ({reason})")]
+    TrackingBlocked {
+        /// The requested URL
+        url: String,
+        /// Why it was suspected (tracker domain, empty body or pixel size)
+        reason: String,
+    },
+
+    /// The fetch group's byte budget is spent.
+    #[error("Image budget of {max_bytes} bytes used up for group {group_id}")]
+    GroupBudgetExceeded {
+        /// The group passed with the fetch
+        group_id: String,
+        /// The budget of each group in bytes
+        max_bytes: u64,
+    },
+
+    /// The image does not have the content hash the caller expected.
+    #[error("Content hash mismatch for {url}: expected SHA-256 {expected}, got {actual}")]
+    HashMismatch {
+        /// The requested URL
+        url: String,
+        /// The expected SHA-256, as passed
+        expected: String,
+        /// SHA-256 of the body received, as lowercase hex
+        actual: String,
+    },
+
+    /// Too many redirects.
+    #[error("Too many redirects: {count} (max: {max_count})")]
+    TooManyRedirects {
+        /// Number of redirects encountered
+        count: u32,
+        /// Maximum allowed redirects
+        max_count: u32,
+    },
+
+    /// A plain `http://` URL was refused because HTTPS-only mode is active.
+    #[error("Insecure URL '{url}' refused: HTTPS-only mode is active")]
+    InsecureUrl {
+        /// The refused URL
+        url: String,
+    },
+
+    /// A redirect was refused by the redirect policy.
+    #[error("Redirect to '{url}' blocked: {reason}")]
+    RedirectBlocked {
+        /// The redirect target
+        url: String,
+        /// Which rule refused it
+        reason: String,
+    },
+
+    /// A phase of the request ran out of its time budget.
+    #[error("Timed out after {seconds} seconds ({phase})")]
+    Timeout {
+        /// The phase that ran out of time
+        phase: TimeoutPhase,
+        /// The phase's budget in seconds
+        seconds: u32,
+    },
+
+    /// The request was cancelled before it completed, e.g. because its
+    /// batch was cancelled.
+    #[error("Request cancelled")]
+    Cancelled,
+
+    /// Network activity is paused (`proxy_pause()`), and the request could
+    /// not be held until it resumes: the tunnel is not up yet, or too many
+    /// requests are already waiting.
+    #[error("Network activity is paused")]
+    Paused,
+
+    /// The proxy is switched off (`proxy_set_enabled(false)`, or for every
+    /// client `proxy_set_enabled_globally(false)`).
+    #[error("Remote content is disabled")]
+    Disabled,
+
+    /// Too many requests are already waiting under the rate limit.
+    #[error("Too many requests queued; try again later")]
+    Throttled,
+
+    /// DNS resolution failed.
+    #[error("DNS resolution failed for {host}: {details}")]
+    DnsError {
+        /// The host that failed to resolve
+        host: String,
+        /// Detailed error message
+        details: String,
+    },
+
+    /// TLS handshake failed.
+    #[error("TLS error: {details}")]
+    TlsError {
+        /// Detailed error message
+        details: String,
+    },
+
+    /// Failed to read or write configuration.
+    #[error("Storage error: {details}")]
+    StorageError {
+        /// Detailed error message
+        details: String,
+    },
+
+    /// WireGuard cryptographic operation failed.
+    #[error("Crypto error: {details}")]
+    CryptoError {
+        /// Detailed error message
+        details: String,
+    },
+
+    /// Network is unavailable.
+    #[error("Network unavailable: {details}")]
+    NetworkUnavailable {
+        /// Detailed error message
+        details: String,
+    },
+}
+
+/// The phase of a request a [`ProxyError::Timeout`] happened in; each but
+/// `Tunnel` has its own budget in [`TimeoutBudget`](crate::TimeoutBudget).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, uniffi::Enum)]
+pub enum TimeoutPhase {
+    /// Bringing up the WARP tunnel: registration and WireGuard handshake.
+    Tunnel,
+    /// Resolving the host over DNS-over-HTTPS.
+    Dns,
+    /// Opening the TCP connection.
+    Connect,
+    /// The TLS handshake.
+    Tls,
+    /// Sending the request and waiting for the response head.
+    FirstByte,
+    /// Receiving the response body.
+    Body,
+}
+
+impl std::fmt::Display for TimeoutPhase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            TimeoutPhase::Tunnel => "tunnel setup",
+            TimeoutPhase::Dns => "DNS lookup",
+            TimeoutPhase::Connect => "TCP connect",
+            TimeoutPhase::Tls => "TLS handshake",
+            TimeoutPhase::FirstByte => "waiting for the response",
+            TimeoutPhase::Body => "response body",
+        })
+    }
+}
+
+impl ProxyError {
+    /// Append `(trace <id>)` to the error's detail string, if it has one.
+    pub(crate) fn traced(mut self, id: &str) -> Self {
+        let tag = format!(" (trace {id})");
+        match &mut self {
+            ProxyError::InitializationFailed { details }
+            | ProxyError::ProvisioningFailed { details }
+            | ProxyError::TunnelError { details }
+            | ProxyError::InvalidUrl { details, .. }
+            | ProxyError::HttpError { details, .. }
+            | ProxyError::ImageProcessingError { details }
+            | ProxyError::DnsError { details, .. }
+            | ProxyError::TlsError { details }
+            | ProxyError::StorageError { details }
+            | ProxyError::CryptoError { details }
+            | ProxyError::NetworkUnavailable { details } => details.push_str(&tag),
+            ProxyError::TrackingBlocked { reason, .. }
+            | ProxyError::RedirectBlocked { reason, .. } => reason.push_str(&tag),
+            _ => {}
+        }
+        self
+    }
+}
+
+impl From<std::io::Error> for ProxyError {
+    fn from(err: std::io::Error) -> Self {
+        // Writers in the body pipeline smuggle a `ProxyError` (e.g. a size
+        // limit) through `io::Error`; surface it unchanged.
+        if let Some(proxy) = err.get_ref().and_then(|e| e.downcast_ref::<ProxyError>()) {
+            return proxy.clone();
+        }
+        ProxyError::StorageError {
+            details: err.to_string(),
+        }
+    }
+}
+
+impl From<serde_json::Error> for ProxyError {
+    fn from(err: serde_json::Error) -> Self {
+        ProxyError::StorageError {
+            details: format!("JSON error: {}", err),
+        }
+    }
+}
+
+#[cfg(feature = "provisioning")]
+impl From<reqwest::Error> for ProxyError {
+    fn from(err: reqwest::Error) -> Self {
+        if err.is_timeout() {
+            ProxyError::Timeout {
+                phase: TimeoutPhase::Tunnel,
+                seconds: 30,
+            }
+        } else if err.is_connect() {
+            ProxyError::NetworkUnavailable {
+                details: err.to_string(),
+            }
+        } else if let Some(status) = err.status() {
+            ProxyError::HttpError {
+                status_code: status.as_u16(),
+                details: err.to_string(),
+            }
+        } else {
+            ProxyError::HttpError {
+                status_code: 0,
+                details: err.to_string(),
+            }
+        }
+    }
+}
+
+impl From<url::ParseError> for ProxyError {
+    fn from(err: url::ParseError) -> Self {
+        ProxyError::InvalidUrl {
+            url: String::new(),
+            details: err.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_display() {
+        let error = ProxyError::NotInitialized;
+        assert_eq!(error.to_string(), "Proxy not initialized");
+
+        let error = ProxyError::InvalidUrl {
+            url: "bad-url".to_string(),
+            details: "missing scheme".to_string(),
+        };
+        assert!(error.to_string().contains("bad-url"));
+        assert!(error.to_string().contains("missing scheme"));
+    }
+
+    #[test]
+    fn timeouts_name_their_phase() {
+        let error = ProxyError::Timeout {
+            phase: TimeoutPhase::FirstByte,
+            seconds: 20,
+        };
+        assert_eq!(
+            error.to_string(),
+            "Timed out after 20 seconds (waiting for the response)"
+        );
+        assert_eq!(error.code(), ErrorCode::Timeout);
+    }
+
+    #[test]
+    fn test_error_from_io() {
+        let io_error = std::io::Error::new(std::io::ErrorKind::NotFound, "file not found");
+        let proxy_error: ProxyError = io_error.into();
+        assert!(matches!(proxy_error, ProxyError::StorageError { .. }));
+    }
+
+    #[test]
+    fn test_error_clone() {
+        let error = ProxyError::HttpError {
+            status_code: 404,
+            details: "Not found".to_string(),
+        };
+        let cloned = error.clone();
+        assert_eq!(error, cloned);
+    }
+}
@@ -0,0 +1,166 @@
+//! Status-change events for the host app.
+//!
+//! Instead of polling [`proxy_status`](crate::proxy_status), the host can
+//! register a [`ProxyEventListener`] per client and be told when the tunnel
+//! connects or is torn down, when a WARP identity is provisioned, when an
+//! upgraded request falls back to plain HTTP, when cached images are evicted,
+//! and when an operation fails.
+//!
+//! Events are delivered in order on a dedicated `letterbox-events` thread, so
+//! they can be raised while the client's state lock is held and a listener may
+//! call back into the proxy without deadlocking.
+//!
+//! The proxy never falls back from the tunnel to a direct connection; the
+//! only fallback it performs is [`ProxyEvent::HttpsFallback`], which keeps the
+//! request on the same network. Only a build without the `tunnel` feature
+//! fetches directly, over
+//! [`DirectNetwork`](crate::tunnel::direct::DirectNetwork), and a configured
+//! upstream proxy replaces the tunnel with an
+//! [`UpstreamNetwork`](crate::tunnel::upstream::UpstreamNetwork); both apply
+//! to every request rather than as a fallback.
+
+use crate::client::{default_client, ProxyClient};
+use crate::error::ProxyError;
+use std::sync::mpsc::{channel, Sender};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// A change in proxy state worth surfacing to the app.
+#[derive(Debug, Clone, PartialEq, uniffi::Enum)]
+pub enum ProxyEvent {
+    /// The WireGuard tunnel completed its handshake with `endpoint`.
+    TunnelConnected { endpoint: String },
+    /// The tunnel was torn down, e.g. by shutdown or an identity reset.
+    TunnelDisconnected { reason: String },
+    /// A new WARP identity was registered with Cloudflare.
+    ProvisioningCompleted { account_type: String },
+    /// An upgraded `https://` request for `url` failed to connect and was
+    /// retried over plain HTTP, still through the tunnel.
+    HttpsFallback { url: String },
+    /// `count` cached images (`bytes` of data) were evicted from memory.
+    CacheEvicted { count: u32, bytes: u64 },
+    /// An operation failed; `message` is also reported as the last error.
+    Error { message: String },
+}
+
+/// Receives [`ProxyEvent`]s from one client.
+#[uniffi::export(with_foreign)]
+pub trait ProxyEventListener: Send + Sync {
+    /// Called on the client's event thread, one event at a time.
+    fn on_event(&self, event: ProxyEvent);
+}
+
+/// Queue feeding the registered listener's dispatch thread.
+#[derive(Debug, Default)]
+pub struct EventSink {
+    sender: Mutex<Option<Sender<ProxyEvent>>>,
+}
+
+impl EventSink {
+    fn lock(&self) -> MutexGuard<'_, Option<Sender<ProxyEvent>>> {
+        self.sender
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Queue `event` for the listener, if one is registered.
+    pub(crate) fn emit(&self, event: ProxyEvent) {
+        if let Some(sender) = self.lock().as_ref() {
+            // A closed channel means the listener is being replaced.
+            let _ = sender.send(event);
+        }
+    }
+
+    /// Route future events to `listener`, or drop them with `None`.
+    ///
+    /// The previous listener's thread exits once it has delivered the events
+    /// already queued for it.
+    pub(crate) fn set_listener(
+        &self,
+        listener: Option<Arc<dyn ProxyEventListener>>,
+    ) -> Result<(), ProxyError> {
+        let Some(listener) = listener else {
+            *self.lock() = None;
+            return Ok(());
+        };
+        let (sender, receiver) = channel::<ProxyEvent>();
+        std::thread::Builder::new()
+            .name("letterbox-events".to_string())
+            .spawn(move || {
+                for event in receiver {
+                    listener.on_event(event);
+                }
+            })
+            .map_err(|e| ProxyError::InitializationFailed {
+                details: format!("Failed to spawn event thread: {e}"),
+            })?;
+        *self.lock() = Some(sender);
+        Ok(())
+    }
+}
+
+#[uniffi::export]
+impl ProxyClient {
+    /// Register (or, with `None`, remove) the listener for this client's
+    /// events. Works before initialisation and survives re-initialisation.
+    pub fn set_event_listener(
+        &self,
+        listener: Option<Arc<dyn ProxyEventListener>>,
+    ) -> Result<(), ProxyError> {
+        self.events.set_listener(listener)
+    }
+}
+
+/// [`ProxyClient::set_event_listener`] on the default proxy.
+#[uniffi::export]
+pub fn proxy_set_event_listener(
+    listener: Option<Arc<dyn ProxyEventListener>>,
+) -> Result<(), ProxyError> {
+    default_client().set_event_listener(listener)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc::Receiver;
+    use std::time::Duration;
+
+    struct Forward(Mutex<Sender<ProxyEvent>>);
+
+    impl ProxyEventListener for Forward {
+        fn on_event(&self, event: ProxyEvent) {
+            let _ = self.0.lock().unwrap().send(event);
+        }
+    }
+
+    fn listener() -> (Arc<Forward>, Receiver<ProxyEvent>) {
+        let (tx, rx) = channel();
+        (Arc::new(Forward(Mutex::new(tx))), rx)
+    }
+
+    #[test]
+    fn events_reach_the_current_listener_in_order() {
+        let sink = EventSink::default();
+        sink.emit(ProxyEvent::Error {
+            message: "unheard".to_string(),
+        });
+
+        let (first, received) = listener();
+        sink.set_listener(Some(first)).unwrap();
+        let evicted = ProxyEvent::CacheEvicted {
+            count: 2,
+            bytes: 10,
+        };
+        let error = ProxyEvent::Error {
+            message: "boom".to_string(),
+        };
+        sink.emit(evicted.clone());
+        sink.emit(error.clone());
+        let wait = Duration::from_secs(5);
+        assert_eq!(received.recv_timeout(wait).unwrap(), evicted);
+        assert_eq!(received.recv_timeout(wait).unwrap(), error);
+
+        sink.set_listener(None).unwrap();
+        sink.emit(error);
+        assert!(received.recv_timeout(Duration::from_millis(50)).is_err());
+    }
+}
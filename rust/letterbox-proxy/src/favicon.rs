@@ -0,0 +1,278 @@
+//! Favicon discovery for sender domains.
+//!
+//! [`proxy_fetch_favicon`] collects icon candidates for a domain — the
+//! `<link rel="icon">` and `apple-touch-icon` entries of its home page, then
+//! `/favicon.ico` and other common paths — ranks them by how well their size
+//! matches the caller's hint, and returns the first one that loads through the
+//! normal image pipeline. The winner is cached per domain and size hint, so the
+//! list view can ask for every sender's icon without repeating the discovery.
+
+use crate::cache::variant_key;
+use crate::client::{default_client, ProxyClient};
+use crate::error::ProxyError;
+use crate::fetch_image;
+use crate::html::{self, attr, Page};
+use crate::trace;
+use crate::types::{ImageResponse, RequestPriority};
+
+/// Most candidates fetched before giving up.
+const MAX_ATTEMPTS: usize = 6;
+
+/// Well-known icon paths, with the size they usually have.
+const COMMON_PATHS: &[(&str, IconSize)] = &[
+    ("/favicon.ico", IconSize::Known(32)),
+    ("/apple-touch-icon.png", IconSize::Known(180)),
+    ("/favicon.svg", IconSize::Any),
+    ("/favicon.png", IconSize::Unknown),
+];
+
+/// Declared size of an icon candidate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum IconSize {
+    /// Largest edge in pixels.
+    Known(u32),
+    /// Scalable (`sizes="any"`).
+    Any,
+    Unknown,
+}
+
+#[uniffi::export]
+impl ProxyClient {
+    /// Fetch the icon of `domain` (a host name, URL or email address) closest to
+    /// `size_hint` pixels, preferring icons at least that large.
+    pub fn fetch_favicon(
+        &self,
+        domain: String,
+        size_hint: u32,
+    ) -> Result<ImageResponse, ProxyError> {
+        trace::traced(|| fetch_favicon(self, &domain, size_hint)).inspect_err(|e| {
+            self.record_error(None, e);
+        })
+    }
+}
+
+/// [`ProxyClient::fetch_favicon`] on the default proxy.
+#[uniffi::export]
+pub fn proxy_fetch_favicon(domain: String, size_hint: u32) -> Result<ImageResponse, ProxyError> {
+    default_client().fetch_favicon(domain, size_hint)
+}
+
+fn fetch_favicon(
+    client: &ProxyClient,
+    domain: &str,
+    size_hint: u32,
+) -> Result<ImageResponse, ProxyError> {
+    let origin = origin(domain)?;
+    let key = variant_key(&origin, &format!("favicon.{size_hint}"));
+    {
+        let mut guard = client.lock_state();
+        let state = guard.as_mut().ok_or(ProxyError::NotInitialized)?;
+        if let Some(cached) = state.cache.get(&key) {
+            return Ok(ImageResponse {
+                from_cache: true,
+                trace_id: trace::current().unwrap_or_default(),
+                transport: None,
+                ..cached
+            });
+        }
+    }
+
+    let mut candidates = match html::fetch_page(client, &origin) {
+        Ok(page) => link_icons(&page),
+        Err(e) => {
+            log::debug!("No home page for favicon discovery on {origin}: {e}");
+            Vec::new()
+        }
+    };
+    let root = origin.trim_end_matches('/');
+    candidates.extend(
+        COMMON_PATHS
+            .iter()
+            .map(|&(path, size)| (size, format!("{root}{path}"))),
+    );
+
+    let mut last_error = None;
+    for url in ranked(candidates, size_hint).into_iter().take(MAX_ATTEMPTS) {
+        match fetch_image(client, &url, None, RequestPriority::Visible) {
+            Ok(icon) => {
+                if let Some(state) = client.lock_state().as_mut() {
+                    state.cache.put(key, icon.clone());
+                }
+                return Ok(icon);
+            }
+            Err(e) => last_error = Some(e),
+        }
+    }
+    Err(last_error.unwrap_or_else(|| ProxyError::InvalidUrl {
+        url: origin,
+        details: "No favicon candidates".to_string(),
+    }))
+}
+
+/// The `https://host/` origin of a host name, URL or email address.
+fn origin(domain: &str) -> Result<String, ProxyError> {
+    let domain = domain.trim();
+    let url = if domain.contains("://") {
+        domain.to_string()
+    } else {
+        format!("https://{domain}/")
+    };
+    let parsed = url::Url::parse(&url).map_err(|e| ProxyError::InvalidUrl {
+        url: domain.to_string(),
+        details: e.to_string(),
+    })?;
+    match parsed.origin() {
+        origin @ url::Origin::Tuple(..) => Ok(format!("{}/", origin.ascii_serialization())),
+        url::Origin::Opaque(_) => Err(ProxyError::InvalidUrl {
+            url: domain.to_string(),
+            details: "Not a web domain".to_string(),
+        }),
+    }
+}
+
+/// Icon links declared by `page`, as absolute URLs.
+fn link_icons(page: &Page) -> Vec<(IconSize, String)> {
+    let mut icons = Vec::new();
+    html::scan_tags(&page.html, |name, attrs, _| {
+        if name != "link" {
+            return;
+        }
+        let rel = attr(attrs, "rel").unwrap_or_default().to_ascii_lowercase();
+        let touch = rel
+            .split_whitespace()
+            .any(|r| r.starts_with("apple-touch-icon"));
+        if !touch && !rel.split_whitespace().any(|r| r == "icon") {
+            return;
+        }
+        let Some(href) = attr(attrs, "href").and_then(|h| page.resolve(h.trim())) else {
+            return;
+        };
+        let size = match attr(attrs, "sizes").map(parse_sizes) {
+            Some(size) if size != IconSize::Unknown => size,
+            // Touch icons without sizes are conventionally 180x180.
+            _ if touch => IconSize::Known(180),
+            _ => IconSize::Unknown,
+        };
+        icons.push((size, href));
+    });
+    icons
+}
+
+/// The largest size in a `sizes` attribute such as `"16x16 32x32"` or `"any"`.
+fn parse_sizes(sizes: &str) -> IconSize {
+    let mut largest = IconSize::Unknown;
+    for size in sizes.split_whitespace() {
+        if size.eq_ignore_ascii_case("any") {
+            return IconSize::Any;
+        }
+        let edge = size
+            .split_once(['x', 'X'])
+            .and_then(|(w, h)| Some(w.parse::<u32>().ok()?.max(h.parse().ok()?)));
+        if let Some(edge) = edge {
+            if !matches!(largest, IconSize::Known(max) if max >= edge) {
+                largest = IconSize::Known(edge);
+            }
+        }
+    }
+    largest
+}
+
+/// Candidate URLs, best first: the smallest icon at least `size_hint` large,
+/// then scalable icons, then the largest smaller ones, then unknown sizes.
+/// Ties keep their order, so declared links precede common paths.
+fn ranked(mut candidates: Vec<(IconSize, String)>, size_hint: u32) -> Vec<String> {
+    candidates.sort_by_key(|&(size, _)| match size {
+        IconSize::Known(edge) if edge >= size_hint => (0, edge - size_hint),
+        IconSize::Any => (1, 0),
+        IconSize::Known(edge) => (2, size_hint - edge),
+        IconSize::Unknown => (3, 0),
+    });
+    let mut urls: Vec<String> = Vec::with_capacity(candidates.len());
+    for (_, url) in candidates {
+        if !urls.contains(&url) {
+            urls.push(url);
+        }
+    }
+    urls
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn origin_accepts_domains_urls_and_addresses() {
+        assert_eq!(origin("Example.com").unwrap(), "https://example.com/");
+        assert_eq!(
+            origin("http://example.com:8080/a/b").unwrap(),
+            "http://example.com:8080/"
+        );
+        assert_eq!(
+            origin("news@mail.example").unwrap(),
+            "https://mail.example/"
+        );
+        assert!(origin("data:text/plain,x").is_err());
+        assert!(origin("exa mple.com").is_err());
+    }
+
+    #[test]
+    fn finds_icon_links() {
+        let page = Page {
+            final_url: "https://example.com/home".to_string(),
+            html: r#"<link rel="stylesheet" href="/a.css">
+                <link rel="icon" href="/16.png" sizes="16x16">
+                <link rel="Shortcut Icon" href="/favicon.ico">
+                <link rel="apple-touch-icon" href="https://cdn.example/touch.png">
+                <link rel="mask-icon" href="/mask.svg">
+                <link rel="icon" type="image/svg+xml" sizes="any" href="/icon.svg">
+                <link rel="icon" sizes="32x32 192x192" href="icons/big.png">"#
+                .to_string(),
+        };
+        assert_eq!(
+            link_icons(&page),
+            vec![
+                (
+                    IconSize::Known(16),
+                    "https://example.com/16.png".to_string()
+                ),
+                (
+                    IconSize::Unknown,
+                    "https://example.com/favicon.ico".to_string()
+                ),
+                (
+                    IconSize::Known(180),
+                    "https://cdn.example/touch.png".to_string()
+                ),
+                (IconSize::Any, "https://example.com/icon.svg".to_string()),
+                (
+                    IconSize::Known(192),
+                    "https://example.com/icons/big.png".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn ranks_by_size_hint() {
+        let candidates = || {
+            vec![
+                (IconSize::Unknown, "unknown".to_string()),
+                (IconSize::Known(16), "16".to_string()),
+                (IconSize::Known(180), "180".to_string()),
+                (IconSize::Any, "svg".to_string()),
+                (IconSize::Known(64), "64".to_string()),
+                (IconSize::Known(64), "64".to_string()),
+            ]
+        };
+        assert_eq!(
+            ranked(candidates(), 48),
+            ["64", "180", "svg", "16", "unknown"]
+        );
+        assert_eq!(
+            ranked(candidates(), 256),
+            ["svg", "180", "64", "16", "unknown"]
+        );
+        assert_eq!(parse_sizes("16x16 48X48 32x32"), IconSize::Known(48));
+        assert_eq!(parse_sizes("bogus"), IconSize::Unknown);
+    }
+}
@@ -0,0 +1,111 @@
+//! What happens to a fetched image before it is returned: charging its fetch
+//! group, validation, post-processing, caching and tracking screening.
+
+use crate::client::ProxyClient;
+use crate::datasaver;
+use crate::dimensions::checked_dimensions;
+use crate::error::ProxyError;
+use crate::groups;
+use crate::http::FetchOutcome;
+use crate::integrity::sha256_hex;
+use crate::placeholder;
+use crate::redirect;
+use crate::sniff::effective_image_mime;
+use crate::svg;
+use crate::trace;
+use crate::tracking;
+use crate::types::ImageResponse;
+
+/// A fetch's share of its group's byte budget.
+pub(super) struct GroupCharge {
+    pub(super) id: String,
+    pub(super) budget: u64,
+    /// Whether the group's remaining budget, not the size limit, caps the
+    /// response.
+    pub(super) capped: bool,
+}
+
+/// What [`finish`] checks a fetched image against.
+pub(super) struct Checks {
+    pub(super) max_pixels: u64,
+    pub(super) sanitize_svg: bool,
+    pub(super) block_animations: bool,
+}
+
+/// Charge a network fetch to its group: the bytes received count against the
+/// budget, and a response cut off by what was left of it fails as the budget
+/// being spent.
+pub(super) fn charge(
+    client: &ProxyClient,
+    group: Option<GroupCharge>,
+    outcome: Result<FetchOutcome, ProxyError>,
+) -> Result<FetchOutcome, ProxyError> {
+    let Some(group) = group else {
+        return outcome;
+    };
+    let received = match &outcome {
+        Ok(outcome) => outcome.body.len() as u64,
+        Err(ProxyError::ResponseTooLarge { max_size, .. }) if group.capped => *max_size,
+        Err(_) => 0,
+    };
+    if let Some(state) = client.lock_state().as_mut() {
+        state.groups.record(&group.id, received);
+    }
+    match outcome {
+        Err(ProxyError::ResponseTooLarge { .. }) if group.capped => {
+            Err(groups::exceeded(&group.id, group.budget))
+        }
+        outcome => outcome,
+    }
+}
+
+/// Validate, post-process, cache and screen a fetched image.
+pub(super) fn finish(
+    client: &ProxyClient,
+    url: &str,
+    key: String,
+    mut outcome: FetchOutcome,
+    checks: Checks,
+) -> Result<ImageResponse, ProxyError> {
+    let Some(mime_type) = effective_image_mime(&outcome.mime_type, &outcome.body) else {
+        return Err(ProxyError::InvalidContentType {
+            content_type: outcome.mime_type,
+        });
+    };
+
+    if checks.sanitize_svg && mime_type == "image/svg+xml" {
+        outcome.body = svg::sanitize(&outcome.body)?;
+    }
+    let (width, height) = checked_dimensions(&outcome.body, checks.max_pixels)?;
+    let blurhash = placeholder::blurhash(&mime_type, &outcome.body, checks.max_pixels);
+    let sha256 = sha256_hex(&outcome.body);
+    let response = ImageResponse {
+        mime_type,
+        data: outcome.body,
+        from_cache: false,
+        redirect_hops: redirect::hops(&outcome.redirect_chain, &outcome.final_url),
+        final_url: outcome.final_url,
+        redirect_chain: outcome.redirect_chain,
+        width,
+        height,
+        blurhash,
+        tracking_suspected: false,
+        trace_id: trace::current().unwrap_or_default(),
+        headers: outcome.headers,
+        sha256,
+        transport: Some(outcome.transport),
+    };
+
+    // Cached even when blocked, so turning blocking off needs no refetch.
+    let mut guard = client.lock_state();
+    match guard.as_mut() {
+        Some(state) => {
+            state.cache.put(key, response.clone());
+            if checks.block_animations {
+                datasaver::refuse_animation(&response)?;
+            }
+            tracking::screen(url, response, &state.config)
+        }
+        None => Ok(response),
+    }
+}
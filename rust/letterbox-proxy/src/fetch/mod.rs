@@ -0,0 +1,427 @@
+//! Image and generic URL fetching entry points.
+//!
+//! The FFI fetch functions live here: URL validation, the cache fast path,
+//! dispatch to the shared [`crate::tunnel::TunnelManager`] and content-type
+//! checks. Network details stay in [`crate::http`].
+//!
+//! [`ProxyClient::fetch_image`] and [`ProxyClient::fetch_url`] are async
+//! exports — suspend functions in Kotlin — that await the tunnel worker's
+//! reply, so an in-flight fetch does not occupy a caller thread. Internal
+//! callers use the blocking [`fetch_image`]; batches live in [`crate::batch`].
+
+mod finish;
+
+use crate::cache::image_key;
+use crate::client::{default_client, ProxyClient};
+use crate::config::{FetchLimits, DEFAULT_IMAGE_ACCEPT};
+use crate::data_uri;
+use crate::error::ProxyError;
+use crate::integrity;
+use crate::progress::progress_for;
+use crate::trace;
+use crate::tracking;
+use crate::tunnel::TunnelManager;
+use crate::types::{FetchConcurrency, HttpFetchResponse, ImageResponse, RequestPriority};
+use finish::{charge, finish, Checks, GroupCharge};
+use std::collections::HashMap;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use std::time::Instant;
+
+/// Validate that a URL is a fetchable http(s) URL.
+pub(crate) fn validate_image_url(url: &str) -> Result<(), ProxyError> {
+    let parsed = url::Url::parse(url).map_err(|e| ProxyError::InvalidUrl {
+        url: url.to_string(),
+        details: e.to_string(),
+    })?;
+    if parsed.scheme() != "http" && parsed.scheme() != "https" {
+        return Err(ProxyError::InvalidUrl {
+            url: url.to_string(),
+            details: "Only http:// and https:// URLs are supported".to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Convert optional FFI headers into the ordered pairs the tunnel expects.
+pub(crate) fn header_pairs(headers: Option<&HashMap<String, String>>) -> Vec<(String, String)> {
+    headers
+        .map(|map| map.iter().map(|(k, v)| (k.clone(), v.clone())).collect())
+        .unwrap_or_default()
+}
+
+/// Internal image fetch: cache-aware, tunnelled, content-validated, and
+/// screened for tracking pixels.
+///
+/// `data:` URIs are decoded locally and never cached. A network fetch waits
+/// in the tunnel's queue according to `priority`. Runs under a new trace id
+/// unless called within another request.
+pub(crate) fn fetch_image(
+    client: &ProxyClient,
+    url: &str,
+    headers: Option<&HashMap<String, String>>,
+    priority: RequestPriority,
+) -> Result<ImageResponse, ProxyError> {
+    let result = trace::traced(|| {
+        let pending = match lookup(client, url, priority, None)? {
+            Lookup::Done(response) => return Ok(response),
+            Lookup::Pending(pending) => pending,
+        };
+        let checks = pending.checks();
+        let _slot = client.slots.acquire_blocking(url, pending.concurrency)?;
+        let started = Instant::now();
+        let outcome = pending.manager.fetch(
+            url.to_string(),
+            header_pairs(headers),
+            pending.accept,
+            pending.limits,
+            progress_for(url),
+        );
+        client.metrics.record_fetch_latency(started.elapsed());
+        let outcome = charge(client, pending.group, outcome)?;
+        finish(client, url, pending.key, outcome, checks)
+    });
+    client.metrics.record_fetch(&result);
+    client.audit_fetch(url, &result);
+    result
+}
+
+/// [`fetch_image`] awaiting the network instead of blocking on it.
+///
+/// Setting `cancelled` abandons the network fetch, whether it is still queued
+/// or already receiving the body. A network fetch in `group` is charged to
+/// its byte budget. Always runs under a new trace id.
+pub(crate) async fn fetch_image_async(
+    client: &ProxyClient,
+    url: &str,
+    headers: Option<&HashMap<String, String>>,
+    priority: RequestPriority,
+    group: Option<&str>,
+    cancelled: Option<Arc<AtomicBool>>,
+) -> Result<ImageResponse, ProxyError> {
+    let result = trace::traced_async(async {
+        let pending = match lookup(client, url, priority, group)? {
+            Lookup::Done(response) => return Ok(response),
+            Lookup::Pending(pending) => pending,
+        };
+        let checks = pending.checks();
+        let _slot = client
+            .slots
+            .acquire(url, pending.concurrency, cancelled.as_deref())
+            .await?;
+        let started = Instant::now();
+        let outcome = pending
+            .manager
+            .fetch_async(
+                url.to_string(),
+                header_pairs(headers),
+                pending.accept,
+                FetchLimits {
+                    cancelled,
+                    ..pending.limits
+                },
+                progress_for(url),
+            )
+            .await;
+        client.metrics.record_fetch_latency(started.elapsed());
+        let outcome = charge(client, pending.group, outcome)?;
+        finish(client, url, pending.key, outcome, checks)
+    })
+    .await;
+    client.metrics.record_fetch(&result);
+    client.audit_fetch(url, &result);
+    result
+}
+
+/// Where an image fetch stands before any network access.
+enum Lookup {
+    /// Answered locally: a cache hit or a `data:` URI.
+    Done(ImageResponse),
+    /// Must be fetched through the tunnel.
+    Pending(Pending),
+}
+
+/// A network fetch ready to be dispatched.
+struct Pending {
+    /// Cache key the response is stored under.
+    key: String,
+    accept: String,
+    manager: Arc<TunnelManager>,
+    limits: FetchLimits,
+    /// Whether data-saver mode refuses animations.
+    block_animations: bool,
+    /// The fetch group whose budget the response is charged to.
+    group: Option<GroupCharge>,
+    /// Caps on fetches in flight, for taking a slot.
+    concurrency: FetchConcurrency,
+}
+
+impl Pending {
+    fn checks(&self) -> Checks {
+        Checks {
+            max_pixels: self.limits.max_pixels,
+            sanitize_svg: self.limits.sanitize_svg,
+            block_animations: self.block_animations,
+        }
+    }
+}
+
+/// Validate `url` and answer it locally if possible.
+fn lookup(
+    client: &ProxyClient,
+    url: &str,
+    priority: RequestPriority,
+    group: Option<&str>,
+) -> Result<Lookup, ProxyError> {
+    if data_uri::is_data_uri(url) {
+        let limits = {
+            let guard = client.lock_state();
+            guard
+                .as_ref()
+                .ok_or(ProxyError::NotInitialized)?
+                .fetch_limits()
+        };
+        return data_uri::decode(url, &limits).map(Lookup::Done);
+    }
+    validate_image_url(url)?;
+
+    // Fast path: serve from cache without touching the network or the tunnel.
+    let (key, accept, data_saver, group, concurrency, referer) = {
+        let mut guard = client.lock_state();
+        let state = guard.as_mut().ok_or(ProxyError::NotInitialized)?;
+        tracking::check_url(url, &state.config)?;
+        let key = image_key(url, &state.config.image_accept);
+        let mut cached = state.cache.get(&key);
+        // Saving data, another variant of the image beats a download.
+        let default_key = image_key(url, DEFAULT_IMAGE_ACCEPT);
+        if cached.is_none() && state.data_saver.is_some() && key != default_key {
+            cached = state.cache.get(&default_key);
+        }
+        client.metrics.record_cache_lookup(cached.is_some());
+        if let Some(cached) = cached {
+            let cached = ImageResponse {
+                from_cache: true,
+                trace_id: trace::current().unwrap_or_default(),
+                transport: None,
+                ..cached
+            };
+            return tracking::screen(url, cached, &state.config).map(Lookup::Done);
+        }
+        let referer = group.and_then(|id| state.groups.referer(id).cloned());
+        let budget = state.config.group_byte_budget;
+        let group = match group.filter(|_| budget > 0) {
+            Some(id) => Some((id, budget, state.groups.remaining(id, budget)?)),
+            None => None,
+        };
+        (
+            key,
+            state.config.image_accept.clone(),
+            state.data_saver.clone(),
+            group,
+            state.config.fetch_concurrency,
+            referer,
+        )
+    };
+
+    let (manager, limits) = client.acquire_manager()?;
+    let max_size = data_saver
+        .as_ref()
+        .map_or(limits.max_size, |saver| saver.cap(limits.max_size));
+    let (max_size, group) = match group {
+        Some((id, budget, remaining)) => (
+            max_size.min(remaining),
+            Some(GroupCharge {
+                id: id.to_string(),
+                budget,
+                capped: remaining < max_size,
+            }),
+        ),
+        None => (max_size, None),
+    };
+    Ok(Lookup::Pending(Pending {
+        key,
+        accept,
+        manager,
+        limits: FetchLimits {
+            priority,
+            max_size,
+            referer,
+            ..limits
+        },
+        block_animations: data_saver.is_some_and(|saver| saver.block_animations),
+        group,
+        concurrency,
+    }))
+}
+
+#[uniffi::export]
+impl ProxyClient {
+    /// Fetch a single image through the WARP tunnel.
+    ///
+    /// Exported as a suspend function: the network wait is awaited rather than
+    /// blocking a thread. Establishing the tunnel on first use still blocks.
+    /// The request is served ahead of queued prefetches.
+    ///
+    /// With `expected_hash` (a SHA-256 in hex), an image whose body hashes
+    /// differently fails with [`ProxyError::HashMismatch`]. With `group_id`
+    /// (e.g. the message id), the image counts against that group's byte
+    /// budget; see [`Self::set_group_byte_budget`].
+    #[uniffi::method(default(expected_hash = None, group_id = None))]
+    pub async fn fetch_image(
+        &self,
+        url: String,
+        headers: Option<HashMap<String, String>>,
+        expected_hash: Option<String>,
+        group_id: Option<String>,
+    ) -> Result<ImageResponse, ProxyError> {
+        fetch_image_async(
+            self,
+            &url,
+            headers.as_ref(),
+            RequestPriority::Visible,
+            group_id.as_deref(),
+            None,
+        )
+        .await
+        .and_then(|response| integrity::verify(&url, response, expected_hash.as_deref()))
+        .inspect_err(|e| {
+            self.record_error(Some(&url), e);
+        })
+    }
+
+    /// Fetch an arbitrary URL through the tunnel (non-image content allowed).
+    pub async fn fetch_url(
+        &self,
+        url: String,
+        headers: Option<HashMap<String, String>>,
+    ) -> Result<HttpFetchResponse, ProxyError> {
+        trace::traced_async(async {
+            let (manager, limits) = self.acquire_manager()?;
+            let progress = progress_for(&url);
+            let outcome = manager
+                .fetch_async(
+                    url.clone(),
+                    header_pairs(headers.as_ref()),
+                    "*/*".to_string(),
+                    limits,
+                    progress,
+                )
+                .await?;
+            Ok(HttpFetchResponse {
+                status: outcome.status,
+                mime_type: outcome.mime_type,
+                data: outcome.body,
+                final_url: outcome.final_url,
+                trace_id: trace::current().unwrap_or_default(),
+            })
+        })
+        .await
+        .inspect_err(|e| {
+            self.record_error(Some(&url), e);
+        })
+    }
+
+    /// Set the `Accept` header sent with image requests, e.g.
+    /// `"image/webp,image/*"` on devices that cannot decode AVIF. An empty
+    /// value restores the default, `image/avif,image/webp,image/*`.
+    ///
+    /// Images negotiated under a non-default header are cached separately.
+    pub fn set_image_accept(&self, accept: String) -> Result<(), ProxyError> {
+        let accept: String = accept.chars().filter(|c| !c.is_control()).collect();
+        let accept = match accept.trim() {
+            "" => DEFAULT_IMAGE_ACCEPT.to_string(),
+            trimmed => trimmed.to_string(),
+        };
+        let mut guard = self.lock_state();
+        let state = guard.as_mut().ok_or(ProxyError::NotInitialized)?;
+        state.config.image_accept = accept;
+        Ok(())
+    }
+}
+
+/// [`ProxyClient::fetch_image`] through the default proxy.
+#[uniffi::export(default(expected_hash = None, group_id = None))]
+pub async fn proxy_fetch_image(
+    url: String,
+    headers: Option<HashMap<String, String>>,
+    expected_hash: Option<String>,
+    group_id: Option<String>,
+) -> Result<ImageResponse, ProxyError> {
+    default_client()
+        .fetch_image(url, headers, expected_hash, group_id)
+        .await
+}
+
+/// [`ProxyClient::fetch_url`] through the default proxy.
+#[uniffi::export]
+pub async fn proxy_fetch_url(
+    url: String,
+    headers: Option<HashMap<String, String>>,
+) -> Result<HttpFetchResponse, ProxyError> {
+    default_client().fetch_url(url, headers).await
+}
+
+/// [`ProxyClient::set_image_accept`] on the default proxy.
+#[uniffi::export]
+pub fn proxy_set_image_accept(accept: String) -> Result<(), ProxyError> {
+    default_client().set_image_accept(accept)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn url_validation_rejects_non_http_schemes() {
+        assert!(validate_image_url("not-a-url").is_err());
+        for url in [
+            "ftp://example.com/x.png",
+            "file:///etc/passwd",
+            "javascript:alert(1)",
+            "data:image/png;base64,AAAA",
+        ] {
+            assert!(matches!(
+                validate_image_url(url),
+                Err(ProxyError::InvalidUrl { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn url_validation_accepts_http_and_https() {
+        assert!(validate_image_url("http://example.com/x.png").is_ok());
+        assert!(validate_image_url("https://example.com/x.png").is_ok());
+    }
+
+    #[test]
+    fn header_pairs_handles_none_and_some() {
+        assert!(header_pairs(None).is_empty());
+        let mut map = HashMap::new();
+        map.insert("X-A".to_string(), "1".to_string());
+        let pairs = header_pairs(Some(&map));
+        assert_eq!(pairs, vec![("X-A".to_string(), "1".to_string())]);
+    }
+
+    #[test]
+    fn image_response_clone_preserves_fields() {
+        let response = ImageResponse {
+            mime_type: "image/png".to_string(),
+            data: vec![0x89, 0x50, 0x4E, 0x47],
+            from_cache: false,
+            final_url: "https://example.com/image.png".to_string(),
+            redirect_chain: Vec::new(),
+            redirect_hops: Vec::new(),
+            width: None,
+            height: None,
+            blurhash: None,
+            tracking_suspected: false,
+            trace_id: String::new(),
+            headers: Default::default(),
+            sha256: String::new(),
+            transport: None,
+        };
+        let cloned = response.clone();
+        assert_eq!(response.mime_type, cloned.mime_type);
+        assert_eq!(response.data, cloned.data);
+    }
+}
@@ -0,0 +1,223 @@
+//! Byte budgets per group of image fetches.
+//!
+//! A single email can reference hundreds of megabytes of images. Fetches that
+//! pass a `group_id` — typically the message id — share a byte budget: each
+//! network fetch of the group may receive at most what is left of it, and
+//! once it is spent the group's remaining fetches fail with
+//! [`ProxyError::GroupBudgetExceeded`] instead of downloading anything. Cache
+//! hits and `data:` URIs cost nothing.
+//!
+//! Usage is kept in memory for the most recent [`MAX_GROUPS`] groups.
+//! Concurrent fetches of one group each start with the budget left when they
+//! were dispatched, so a group can overshoot by what those fetches receive.
+//!
+//! A group can also be granted a `Referer` and `Origin` for one host, for
+//! hotlink-protected CDNs; see [`ProxyClient::set_group_referer`]. Grants are
+//! kept for the [`MAX_GROUPS`] most recently granted groups.
+
+use crate::client::{default_client, ProxyClient};
+use crate::error::ProxyError;
+use crate::headers::normalize_grant;
+use crate::types::RefererGrant;
+use std::collections::{HashMap, VecDeque};
+
+/// Default budget per group: 50 MiB.
+pub const DEFAULT_GROUP_BYTE_BUDGET: u64 = 50 * 1024 * 1024;
+
+/// Groups whose usage is remembered; the oldest is forgotten beyond this.
+const MAX_GROUPS: usize = 256;
+
+/// Bytes received per group.
+#[derive(Debug, Default)]
+pub(crate) struct GroupUsage {
+    used: HashMap<String, u64>,
+    /// Groups in the order they were first seen.
+    order: VecDeque<String>,
+    /// `Referer`/`Origin` grants per group.
+    referers: HashMap<String, RefererGrant>,
+    /// Granted groups in the order they were granted.
+    referer_order: VecDeque<String>,
+}
+
+impl GroupUsage {
+    /// What is left of `budget` for `group`, failing if nothing is.
+    pub(crate) fn remaining(&self, group: &str, budget: u64) -> Result<u64, ProxyError> {
+        match budget.saturating_sub(self.used.get(group).copied().unwrap_or(0)) {
+            0 => Err(exceeded(group, budget)),
+            remaining => Ok(remaining),
+        }
+    }
+
+    /// Charge `bytes` to `group`.
+    pub(crate) fn record(&mut self, group: &str, bytes: u64) {
+        if let Some(used) = self.used.get_mut(group) {
+            *used = used.saturating_add(bytes);
+            return;
+        }
+        if self.order.len() == MAX_GROUPS {
+            if let Some(oldest) = self.order.pop_front() {
+                self.used.remove(&oldest);
+            }
+        }
+        self.used.insert(group.to_string(), bytes);
+        self.order.push_back(group.to_string());
+    }
+
+    /// Forget what `group` has used.
+    pub(crate) fn reset(&mut self, group: &str) {
+        if self.used.remove(group).is_some() {
+            self.order.retain(|g| g != group);
+        }
+    }
+
+    /// The `Referer`/`Origin` grant of `group`, if any.
+    pub(crate) fn referer(&self, group: &str) -> Option<&RefererGrant> {
+        self.referers.get(group)
+    }
+
+    /// Replace (or, with `None`, revoke) the grant of `group`.
+    pub(crate) fn set_referer(&mut self, group: &str, grant: Option<RefererGrant>) {
+        if self.referers.remove(group).is_some() {
+            self.referer_order.retain(|g| g != group);
+        }
+        let Some(grant) = grant else {
+            return;
+        };
+        if self.referer_order.len() == MAX_GROUPS {
+            if let Some(oldest) = self.referer_order.pop_front() {
+                self.referers.remove(&oldest);
+            }
+        }
+        self.referers.insert(group.to_string(), grant);
+        self.referer_order.push_back(group.to_string());
+    }
+}
+
+/// The error for `group` having spent `budget`.
+pub(crate) fn exceeded(group: &str, budget: u64) -> ProxyError {
+    ProxyError::GroupBudgetExceeded {
+        group_id: group.to_string(),
+        max_bytes: budget,
+    }
+}
+
+#[uniffi::export]
+impl ProxyClient {
+    /// Set the byte budget of each fetch group (0 = unlimited). Usage already
+    /// recorded counts against the new budget.
+    pub fn set_group_byte_budget(&self, max_bytes: u64) -> Result<(), ProxyError> {
+        let mut guard = self.lock_state();
+        let state = guard.as_mut().ok_or(ProxyError::NotInitialized)?;
+        state.config.group_byte_budget = max_bytes;
+        Ok(())
+    }
+
+    /// Give `group_id` its full budget again, e.g. when the user asks to load
+    /// a message's images anyway.
+    pub fn reset_group_budget(&self, group_id: String) -> Result<(), ProxyError> {
+        let mut guard = self.lock_state();
+        let state = guard.as_mut().ok_or(ProxyError::NotInitialized)?;
+        state.groups.reset(&group_id);
+        Ok(())
+    }
+
+    /// Send `grant`'s `Referer` and `Origin` to its host with the image
+    /// fetches of `group_id`, e.g. when the user asks to load a message's
+    /// hotlink-protected images. `None` revokes the grant.
+    ///
+    /// Other hosts, and fetches outside the group, keep the default of no
+    /// `Referer` or `Origin`; a grant does not follow redirects to another
+    /// host. Each header sent under a grant is logged.
+    pub fn set_group_referer(
+        &self,
+        group_id: String,
+        grant: Option<RefererGrant>,
+    ) -> Result<(), ProxyError> {
+        let grant = grant.map(normalize_grant).transpose()?;
+        if let Some(grant) = &grant {
+            log::info!(
+                "Granting Referer/Origin to {} for a fetch group",
+                grant.host
+            );
+        }
+        let mut guard = self.lock_state();
+        let state = guard.as_mut().ok_or(ProxyError::NotInitialized)?;
+        state.groups.set_referer(&group_id, grant);
+        Ok(())
+    }
+}
+
+/// [`ProxyClient::set_group_byte_budget`] on the default proxy.
+#[uniffi::export]
+pub fn proxy_set_group_byte_budget(max_bytes: u64) -> Result<(), ProxyError> {
+    default_client().set_group_byte_budget(max_bytes)
+}
+
+/// [`ProxyClient::reset_group_budget`] on the default proxy.
+#[uniffi::export]
+pub fn proxy_reset_group_budget(group_id: String) -> Result<(), ProxyError> {
+    default_client().reset_group_budget(group_id)
+}
+
+/// [`ProxyClient::set_group_referer`] on the default proxy.
+#[uniffi::export]
+pub fn proxy_set_group_referer(
+    group_id: String,
+    grant: Option<RefererGrant>,
+) -> Result<(), ProxyError> {
+    default_client().set_group_referer(group_id, grant)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_spent_budget_refuses_until_reset() {
+        let mut usage = GroupUsage::default();
+        assert_eq!(usage.remaining("m1", 100).unwrap(), 100);
+        usage.record("m1", 60);
+        assert_eq!(usage.remaining("m1", 100).unwrap(), 40);
+        usage.record("m1", 40);
+        assert!(matches!(
+            usage.remaining("m1", 100),
+            Err(ProxyError::GroupBudgetExceeded { max_bytes: 100, .. })
+        ));
+        assert_eq!(usage.remaining("m2", 100).unwrap(), 100);
+        usage.reset("m1");
+        assert_eq!(usage.remaining("m1", 100).unwrap(), 100);
+    }
+
+    #[test]
+    fn only_recent_groups_are_remembered() {
+        let mut usage = GroupUsage::default();
+        for i in 0..=MAX_GROUPS {
+            usage.record(&i.to_string(), 10);
+        }
+        assert_eq!(usage.used.len(), MAX_GROUPS);
+        assert_eq!(usage.remaining("0", 10).unwrap(), 10);
+        assert!(usage.remaining(&MAX_GROUPS.to_string(), 10).is_err());
+    }
+
+    #[test]
+    fn referer_grants_are_replaced_and_revoked() {
+        let grant = |host: &str| RefererGrant {
+            host: host.to_string(),
+            referer: Some("https://mail.example/".to_string()),
+            origin: None,
+        };
+        let mut usage = GroupUsage::default();
+        usage.set_referer("m1", Some(grant("a.example")));
+        usage.set_referer("m1", Some(grant("b.example")));
+        assert_eq!(usage.referer("m1").unwrap().host, "b.example");
+        assert!(usage.referer("m2").is_none());
+        usage.set_referer("m1", None);
+        assert!(usage.referer("m1").is_none());
+
+        for i in 0..=MAX_GROUPS {
+            usage.set_referer(&i.to_string(), Some(grant("a.example")));
+        }
+        assert_eq!(usage.referers.len(), MAX_GROUPS);
+        assert!(usage.referer("0").is_none());
+    }
+}
@@ -0,0 +1,156 @@
+//! Image bytes behind a handle.
+//!
+//! An [`ImageResponse`] carries its body as `Vec<u8>`, which UniFFI copies
+//! into a buffer and Kotlin copies again into a `ByteArray`: two full copies
+//! of a multi-megabyte image, both alive at once. [`ProxyClient::fetch_image_handle`]
+//! returns an [`ImageHandle`] instead. The body stays in Rust, and Kotlin
+//! pulls it in chunks with [`ImageHandle::read`], e.g. behind an
+//! `InputStream` feeding a decoder, or has it written to a file it can
+//! memory-map with [`ImageHandle::write_to_file`]. The metadata crosses once,
+//! without the body.
+
+use crate::client::{default_client, ProxyClient};
+use crate::error::ProxyError;
+use crate::types::ImageResponse;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// A fetched image whose bytes are read on demand.
+#[derive(uniffi::Object)]
+pub struct ImageHandle {
+    /// The response, with an empty `data`.
+    info: ImageResponse,
+    data: Vec<u8>,
+}
+
+impl From<ImageResponse> for ImageHandle {
+    fn from(mut response: ImageResponse) -> Self {
+        let data = std::mem::take(&mut response.data);
+        Self {
+            info: response,
+            data,
+        }
+    }
+}
+
+#[uniffi::export]
+impl ImageHandle {
+    /// The response metadata. Its `data` is empty; read the bytes with
+    /// [`Self::read`].
+    pub fn info(&self) -> ImageResponse {
+        self.info.clone()
+    }
+
+    /// Size of the image in bytes.
+    pub fn byte_len(&self) -> u64 {
+        self.data.len() as u64
+    }
+
+    /// Up to `len` bytes starting at `offset`; fewer at the end of the image
+    /// and none past it.
+    pub fn read(&self, offset: u64, len: u32) -> Vec<u8> {
+        let start = usize::try_from(offset)
+            .unwrap_or(usize::MAX)
+            .min(self.data.len());
+        let end = start.saturating_add(len as usize).min(self.data.len());
+        self.data[start..end].to_vec()
+    }
+
+    /// Write the image to `path`, replacing any existing file, e.g. to
+    /// memory-map it. The file appears complete or not at all.
+    pub fn write_to_file(&self, path: String) -> Result<(), ProxyError> {
+        let mut partial = PathBuf::from(&path).into_os_string();
+        partial.push(".partial");
+        fs::write(&partial, &self.data)?;
+        fs::rename(&partial, &path)?;
+        Ok(())
+    }
+}
+
+#[uniffi::export]
+impl ProxyClient {
+    /// [`Self::fetch_image`], returning the image behind an [`ImageHandle`]
+    /// so its bytes are not copied across the FFI whole.
+    #[uniffi::method(default(expected_hash = None, group_id = None))]
+    pub async fn fetch_image_handle(
+        &self,
+        url: String,
+        headers: Option<HashMap<String, String>>,
+        expected_hash: Option<String>,
+        group_id: Option<String>,
+    ) -> Result<Arc<ImageHandle>, ProxyError> {
+        self.fetch_image(url, headers, expected_hash, group_id)
+            .await
+            .map(|response| Arc::new(response.into()))
+    }
+}
+
+/// [`ProxyClient::fetch_image_handle`] through the default proxy.
+#[uniffi::export(default(expected_hash = None, group_id = None))]
+pub async fn proxy_fetch_image_handle(
+    url: String,
+    headers: Option<HashMap<String, String>>,
+    expected_hash: Option<String>,
+    group_id: Option<String>,
+) -> Result<Arc<ImageHandle>, ProxyError> {
+    default_client()
+        .fetch_image_handle(url, headers, expected_hash, group_id)
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn handle(data: &[u8]) -> ImageHandle {
+        ImageHandle::from(ImageResponse {
+            mime_type: "image/png".to_string(),
+            data: data.to_vec(),
+            from_cache: false,
+            final_url: "https://example.com/a.png".to_string(),
+            redirect_chain: Vec::new(),
+            redirect_hops: Vec::new(),
+            width: Some(1),
+            height: Some(1),
+            blurhash: None,
+            tracking_suspected: false,
+            trace_id: String::new(),
+            headers: Default::default(),
+            sha256: String::new(),
+            transport: None,
+        })
+    }
+
+    #[test]
+    fn reads_in_chunks() {
+        let handle = handle(b"0123456789");
+        assert_eq!(handle.byte_len(), 10);
+        assert!(handle.info().data.is_empty());
+        assert_eq!(handle.info().final_url, "https://example.com/a.png");
+
+        let mut read = Vec::new();
+        while (read.len() as u64) < handle.byte_len() {
+            read.extend(handle.read(read.len() as u64, 4));
+        }
+        assert_eq!(read, b"0123456789");
+        assert_eq!(handle.read(8, 4), b"89");
+        assert!(handle.read(10, 4).is_empty());
+        assert!(handle.read(u64::MAX, u32::MAX).is_empty());
+    }
+
+    #[test]
+    fn writes_to_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("a.png");
+        fs::write(&path, b"old").unwrap();
+
+        let handle = handle(b"image bytes");
+        handle
+            .write_to_file(path.to_string_lossy().into_owned())
+            .unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"image bytes");
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+}
@@ -0,0 +1,242 @@
+//! Privacy filter for caller-supplied request headers.
+//!
+//! Headers passed to [`crate::proxy_fetch_image`] / [`crate::proxy_fetch_url`]
+//! are forwarded to the remote server, but the ones that identify the user or
+//! where the image was opened — `Cookie`, `Referer`, `Authorization` and
+//! `Proxy-Authorization` — are dropped unless the host has explicitly allowed
+//! them with [`proxy_set_allowed_sensitive_headers`]. Headers whose name or value
+//! could break HTTP/1.1 framing are always dropped.
+//!
+//! The one exception is a [`RefererGrant`] the app registers for a fetch
+//! group with [`ProxyClient::set_group_referer`]: requests of that group to
+//! the grant's host — and to no other — carry its `Referer` and `Origin` in
+//! place of any the caller passed. Every use is logged.
+
+use crate::client::{default_client, ProxyClient};
+use crate::error::ProxyError;
+use crate::types::RefererGrant;
+use std::borrow::Cow;
+
+/// Headers that are refused unless explicitly allowed (lowercase).
+pub const SENSITIVE_HEADERS: &[&str] =
+    &["authorization", "cookie", "proxy-authorization", "referer"];
+
+/// Whether `name` is a valid HTTP token and `value` contains no line breaks.
+fn is_well_formed(name: &str, value: &str) -> bool {
+    !name.is_empty()
+        && name
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b))
+        && !value.bytes().any(|b| matches!(b, b'\r' | b'\n' | 0))
+}
+
+/// Keep only headers that are well-formed and either non-sensitive or allowed.
+///
+/// `allowed` holds lowercase header names.
+pub(crate) fn filter_request_headers(
+    headers: &[(String, String)],
+    allowed: &[String],
+) -> Vec<(String, String)> {
+    headers
+        .iter()
+        .filter(|(name, value)| {
+            if !is_well_formed(name, value) {
+                log::warn!("Dropping malformed request header");
+                return false;
+            }
+            let lower = name.to_ascii_lowercase();
+            if SENSITIVE_HEADERS.contains(&lower.as_str()) && !allowed.contains(&lower) {
+                log::debug!("Dropping sensitive request header {lower}");
+                return false;
+            }
+            true
+        })
+        .cloned()
+        .collect()
+}
+
+/// `headers` for a request to `host`: unchanged, unless `grant` is for
+/// `host`, in which case its `Referer` and `Origin` replace the caller's.
+pub(crate) fn with_referer<'h>(
+    headers: &'h [(String, String)],
+    grant: Option<&RefererGrant>,
+    host: &str,
+) -> Cow<'h, [(String, String)]> {
+    let Some(grant) = grant.filter(|grant| grant.host.eq_ignore_ascii_case(host)) else {
+        return Cow::Borrowed(headers);
+    };
+    let mut granted: Vec<(String, String)> = headers
+        .iter()
+        .filter(|(name, _)| {
+            !name.eq_ignore_ascii_case("referer") && !name.eq_ignore_ascii_case("origin")
+        })
+        .cloned()
+        .collect();
+    for (name, value) in [("Referer", &grant.referer), ("Origin", &grant.origin)] {
+        if let Some(value) = value {
+            log::info!("Sending granted {name} header to {host}");
+            granted.push((name.to_string(), value.clone()));
+        }
+    }
+    Cow::Owned(granted)
+}
+
+/// Check and normalise a grant: a lowercase host, a `Referer` without
+/// fragment or credentials and an `Origin` of scheme, host and port.
+pub(crate) fn normalize_grant(grant: RefererGrant) -> Result<RefererGrant, ProxyError> {
+    let host = grant.host.trim().to_ascii_lowercase();
+    if host.is_empty() || !is_well_formed("Host", &host) || host.contains(['/', ' ']) {
+        return Err(ProxyError::InvalidUrl {
+            url: grant.host,
+            details: "Not a host name".to_string(),
+        });
+    }
+    let parse = |value: &str| {
+        url::Url::parse(value)
+            .ok()
+            .filter(|url| matches!(url.scheme(), "http" | "https") && url.has_host())
+            .ok_or_else(|| ProxyError::InvalidUrl {
+                url: value.to_string(),
+                details: "Referer and Origin must be http(s) URLs".to_string(),
+            })
+    };
+    let referer = match grant.referer.as_deref().map(parse).transpose()? {
+        Some(mut url) => {
+            url.set_fragment(None);
+            let _ = url.set_username("");
+            let _ = url.set_password(None);
+            Some(url.to_string())
+        }
+        None => None,
+    };
+    let origin = grant
+        .origin
+        .as_deref()
+        .map(parse)
+        .transpose()?
+        .map(|url| url.origin().ascii_serialization());
+    Ok(RefererGrant {
+        host,
+        referer,
+        origin,
+    })
+}
+
+#[uniffi::export]
+impl ProxyClient {
+    /// Allow the given sensitive headers to be forwarded from now on.
+    ///
+    /// Replaces any previous allow-list; pass an empty list to refuse them all
+    /// again (the default). Names are case-insensitive; names outside the
+    /// sensitive set are ignored, as they are always forwarded anyway.
+    pub fn set_allowed_sensitive_headers(&self, names: Vec<String>) -> Result<(), ProxyError> {
+        let names: Vec<String> = names
+            .iter()
+            .map(|n| n.to_ascii_lowercase())
+            .filter(|n| SENSITIVE_HEADERS.contains(&n.as_str()))
+            .collect();
+        let mut guard = self.lock_state();
+        let state = guard.as_mut().ok_or(ProxyError::NotInitialized)?;
+        state.config.allowed_sensitive_headers = names;
+        Ok(())
+    }
+}
+
+/// [`ProxyClient::set_allowed_sensitive_headers`] on the default proxy.
+#[uniffi::export]
+pub fn proxy_set_allowed_sensitive_headers(names: Vec<String>) -> Result<(), ProxyError> {
+    default_client().set_allowed_sensitive_headers(names)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pairs(items: &[(&str, &str)]) -> Vec<(String, String)> {
+        items
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn sensitive_headers_are_refused_by_default() {
+        let headers = pairs(&[
+            ("Cookie", "session=1"),
+            ("Referer", "https://mail.example/"),
+            ("authorization", "Bearer x"),
+            ("User-Agent", "Letterbox"),
+        ]);
+        assert_eq!(
+            filter_request_headers(&headers, &[]),
+            pairs(&[("User-Agent", "Letterbox")])
+        );
+    }
+
+    #[test]
+    fn allowed_sensitive_headers_pass() {
+        let headers = pairs(&[("Referer", "https://mail.example/"), ("Cookie", "a=b")]);
+        assert_eq!(
+            filter_request_headers(&headers, &["referer".to_string()]),
+            pairs(&[("Referer", "https://mail.example/")])
+        );
+    }
+
+    #[test]
+    fn grants_apply_to_their_host_only() {
+        let grant = normalize_grant(RefererGrant {
+            host: "Avatars.CDN.example".to_string(),
+            referer: Some("https://user:pw@mail.example/m/1#top".to_string()),
+            origin: Some("https://mail.example/m/1".to_string()),
+        })
+        .unwrap();
+        assert_eq!(grant.host, "avatars.cdn.example");
+        assert_eq!(grant.referer.as_deref(), Some("https://mail.example/m/1"));
+        assert_eq!(grant.origin.as_deref(), Some("https://mail.example"));
+
+        let headers = pairs(&[("Referer", "https://leak.example/"), ("X-Ok", "1")]);
+        assert_eq!(
+            with_referer(&headers, Some(&grant), "avatars.cdn.example").into_owned(),
+            pairs(&[
+                ("X-Ok", "1"),
+                ("Referer", "https://mail.example/m/1"),
+                ("Origin", "https://mail.example"),
+            ])
+        );
+        assert!(matches!(
+            with_referer(&headers, Some(&grant), "cdn.example"),
+            Cow::Borrowed(_)
+        ));
+        assert!(matches!(
+            with_referer(&headers, None, "avatars.cdn.example"),
+            Cow::Borrowed(_)
+        ));
+    }
+
+    #[test]
+    fn malformed_grants_are_refused() {
+        let grant = |host: &str, referer: &str| RefererGrant {
+            host: host.to_string(),
+            referer: Some(referer.to_string()),
+            origin: None,
+        };
+        assert!(normalize_grant(grant("cdn.example", "https://mail.example/")).is_ok());
+        assert!(normalize_grant(grant("", "https://mail.example/")).is_err());
+        assert!(normalize_grant(grant("cdn.example/path", "https://mail.example/")).is_err());
+        assert!(normalize_grant(grant("cdn.example", "javascript:alert(1)")).is_err());
+        assert!(normalize_grant(grant("cdn.example", "not a url")).is_err());
+    }
+
+    #[test]
+    fn header_injection_is_dropped() {
+        let headers = pairs(&[
+            ("X-Ok", "fine"),
+            ("X-Evil", "a\r\nCookie: stolen"),
+            ("Bad Name", "v"),
+        ]);
+        assert_eq!(
+            filter_request_headers(&headers, &[]),
+            pairs(&[("X-Ok", "fine")])
+        );
+    }
+}
@@ -0,0 +1,33 @@
+//! Hedged requests for slow hosts.
+//!
+//! Some image CDNs reached through the tunnel leave the odd request hanging
+//! for seconds before the first byte of the response, which dominates the
+//! slowest loads. With a hedge delay set, a request that has not started
+//! answering by then is sent again on a second connection and whichever
+//! responds first is used. This costs a duplicate request to slow hosts, so
+//! it is off by default.
+//!
+//! Only HTTP/1.1 exchanges are hedged; see [`crate::tunnel::hedge`].
+
+use crate::client::{default_client, ProxyClient};
+use crate::error::ProxyError;
+use std::time::Duration;
+
+#[uniffi::export]
+impl ProxyClient {
+    /// Send a request again on a second connection when its response has not
+    /// started after `delay_ms` milliseconds, using whichever answers first.
+    /// 0 disables hedging (the default).
+    pub fn set_hedge_delay(&self, delay_ms: u64) -> Result<(), ProxyError> {
+        let mut guard = self.lock_state();
+        let state = guard.as_mut().ok_or(ProxyError::NotInitialized)?;
+        state.config.hedge_after = (delay_ms > 0).then(|| Duration::from_millis(delay_ms));
+        Ok(())
+    }
+}
+
+/// [`ProxyClient::set_hedge_delay`] on the default proxy.
+#[uniffi::export]
+pub fn proxy_set_hedge_delay(delay_ms: u64) -> Result<(), ProxyError> {
+    default_client().set_hedge_delay(delay_ms)
+}
@@ -0,0 +1,253 @@
+//! Minimal HTML page handling for metadata lookups.
+//!
+//! Link previews and favicon discovery only need a few tags from a page, so
+//! pages are scanned for tags rather than parsed: comments and
+//! `<script>`/`<style>` contents are skipped, attribute values are unescaped,
+//! and anything malformed is ignored.
+
+use crate::client::ProxyClient;
+use crate::config::FetchLimits;
+use crate::error::ProxyError;
+use crate::progress::progress_for;
+use crate::validate_image_url;
+
+/// Largest HTML page accepted; the metadata of interest lives in the `<head>`.
+pub const MAX_PAGE_SIZE: u64 = 2 * 1024 * 1024;
+
+/// An HTML page fetched through the tunnel.
+pub(crate) struct Page {
+    /// URL of the page after redirects.
+    pub(crate) final_url: String,
+    /// The page, decoded as UTF-8 with invalid sequences replaced.
+    pub(crate) html: String,
+}
+
+impl Page {
+    /// `reference` resolved against the page URL.
+    pub(crate) fn resolve(&self, reference: &str) -> Option<String> {
+        let base = url::Url::parse(&self.final_url).ok()?;
+        base.join(reference).ok().map(String::from)
+    }
+}
+
+/// Fetch `url`, which must serve `text/html` or `application/xhtml+xml` of
+/// at most [`MAX_PAGE_SIZE`] bytes.
+pub(crate) fn fetch_page(client: &ProxyClient, url: &str) -> Result<Page, ProxyError> {
+    validate_image_url(url)?;
+    let (manager, limits) = client.acquire_manager()?;
+    let outcome = manager.fetch(
+        url.to_string(),
+        Vec::new(),
+        "text/html,application/xhtml+xml;q=0.9".to_string(),
+        FetchLimits {
+            max_size: MAX_PAGE_SIZE,
+            ..limits
+        },
+        progress_for(url),
+    )?;
+    if !matches!(
+        outcome.mime_type.as_str(),
+        "text/html" | "application/xhtml+xml"
+    ) {
+        return Err(ProxyError::InvalidContentType {
+            content_type: outcome.mime_type,
+        });
+    }
+    Ok(Page {
+        final_url: outcome.final_url,
+        html: String::from_utf8_lossy(&outcome.body).into_owned(),
+    })
+}
+
+/// Call `visit` with the lowercase name and attributes of every start tag in
+/// `html`. For `<title>` the third argument is its text, unescaped and with
+/// whitespace collapsed; for other tags it is empty.
+pub(crate) fn scan_tags(html: &str, mut visit: impl FnMut(&str, &[(String, String)], &str)) {
+    // ASCII lowercasing keeps byte offsets, so `lower` indexes `html` too.
+    let lower = html.to_ascii_lowercase();
+    let mut pos = 0;
+    while let Some(open) = lower[pos..].find('<') {
+        pos += open + 1;
+        if lower[pos..].starts_with("!--") {
+            pos = lower[pos..]
+                .find("-->")
+                .map_or(lower.len(), |end| pos + end + 3);
+            continue;
+        }
+        let name_len = lower[pos..]
+            .find(|c: char| c.is_ascii_whitespace() || c == '>' || c == '/')
+            .unwrap_or(lower.len() - pos);
+        let name = &lower[pos..pos + name_len];
+        let (attrs, end) = attributes(html, pos + name_len);
+        pos = end;
+        let mut text = String::new();
+        if matches!(name, "title" | "script" | "style") {
+            let close = lower[pos..]
+                .find(&format!("</{name}"))
+                .map_or(lower.len(), |at| pos + at);
+            if name == "title" {
+                let title = unescape(&html[pos..close]);
+                text = title.split_whitespace().collect::<Vec<_>>().join(" ");
+            }
+            pos = close;
+        }
+        if !name.is_empty() {
+            visit(name, &attrs, &text);
+        }
+    }
+}
+
+/// Parse the attributes of a tag starting at `pos`, returning them (names
+/// lowercased, values unescaped) and the offset just past the tag.
+fn attributes(html: &str, mut pos: usize) -> (Vec<(String, String)>, usize) {
+    let bytes = html.as_bytes();
+    let mut attrs = Vec::new();
+    loop {
+        while pos < bytes.len() && (bytes[pos].is_ascii_whitespace() || bytes[pos] == b'/') {
+            pos += 1;
+        }
+        if pos >= bytes.len() {
+            return (attrs, pos);
+        }
+        if bytes[pos] == b'>' {
+            return (attrs, pos + 1);
+        }
+        let start = pos;
+        while pos < bytes.len()
+            && !matches!(bytes[pos], b'=' | b'>' | b'/')
+            && !bytes[pos].is_ascii_whitespace()
+        {
+            pos += 1;
+        }
+        let name = html[start..pos].to_ascii_lowercase();
+        while pos < bytes.len() && bytes[pos].is_ascii_whitespace() {
+            pos += 1;
+        }
+        if bytes.get(pos) != Some(&b'=') {
+            attrs.push((name, String::new()));
+            continue;
+        }
+        pos += 1;
+        while pos < bytes.len() && bytes[pos].is_ascii_whitespace() {
+            pos += 1;
+        }
+        let value = match bytes.get(pos) {
+            Some(&quote @ (b'"' | b'\'')) => {
+                let start = pos + 1;
+                let end = html[start..]
+                    .find(quote as char)
+                    .map_or(html.len(), |e| start + e);
+                pos = (end + 1).min(html.len());
+                &html[start..end]
+            }
+            _ => {
+                let start = pos;
+                while pos < bytes.len() && bytes[pos] != b'>' && !bytes[pos].is_ascii_whitespace() {
+                    pos += 1;
+                }
+                &html[start..pos]
+            }
+        };
+        attrs.push((name, unescape(value)));
+    }
+}
+
+pub(crate) fn attr<'a>(attrs: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    attrs
+        .iter()
+        .find(|(n, _)| n == name)
+        .map(|(_, v)| v.as_str())
+}
+
+/// Decode the character references common in attribute values and titles.
+fn unescape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let decoded = rest.find(';').filter(|&end| end <= 10).and_then(|end| {
+            let entity = &rest[1..end];
+            let c = match entity {
+                "amp" => '&',
+                "lt" => '<',
+                "gt" => '>',
+                "quot" => '"',
+                "apos" => '\'',
+                "nbsp" => ' ',
+                _ => {
+                    let code = entity.strip_prefix('#')?;
+                    let code = match code.strip_prefix(['x', 'X']) {
+                        Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                        None => code.parse().ok()?,
+                    };
+                    char::from_u32(code)?
+                }
+            };
+            Some((c, end + 1))
+        });
+        match decoded {
+            Some((c, len)) => {
+                out.push(c);
+                rest = &rest[len..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Name, attributes and text of a visited tag.
+    type Visited = (String, Vec<(String, String)>, String);
+
+    fn tags(html: &str) -> Vec<Visited> {
+        let mut found = Vec::new();
+        scan_tags(html, |name, attrs, text| {
+            found.push((name.to_string(), attrs.to_vec(), text.to_string()))
+        });
+        found
+    }
+
+    #[test]
+    fn scans_tags_attributes_and_titles() {
+        let found = tags(
+            "<!DOCTYPE html><Link REL=icon href='/a.png' sizes=\"16x16\"/><title> A &amp;\n B </title><!-- <img> --><script>'<img>'</script></p>",
+        );
+        let names: Vec<_> = found.iter().map(|(n, _, _)| n.as_str()).collect();
+        assert_eq!(names, ["!doctype", "link", "title", "script"]);
+        assert_eq!(attr(&found[1].1, "rel"), Some("icon"));
+        assert_eq!(attr(&found[1].1, "href"), Some("/a.png"));
+        assert_eq!(attr(&found[1].1, "sizes"), Some("16x16"));
+        assert_eq!(found[2].2, "A & B");
+    }
+
+    #[test]
+    fn tolerates_malformed_markup() {
+        let found = tags("<meta property=\"og:image\" content=\"/a.png");
+        assert_eq!(attr(&found[0].1, "content"), Some("/a.png"));
+        for html in [
+            "<",
+            "<meta",
+            "<img src=",
+            "<title>open",
+            "<!-- x",
+            "é<é>",
+            "</>",
+        ] {
+            tags(html);
+        }
+    }
+
+    #[test]
+    fn unescapes_character_references() {
+        assert_eq!(unescape("a&amp;b&#39;c&#x41;&bogus;&"), "a&b'cA&bogus;&");
+    }
+}
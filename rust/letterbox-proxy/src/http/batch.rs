@@ -0,0 +1,210 @@
+//! Fetches to one origin sent together on a shared HTTP/2 session.
+//!
+//! The tunnel worker hands [`fetch_all`] the fetches it finds queued for the
+//! same `https` [`origin`]. When an idle HTTP/2 session to that origin is
+//! pooled, their first requests go out at once as concurrent streams on it,
+//! rather than each waiting for the previous response to finish. Each
+//! response is then taken through the same steps as in [`fetch`]: a redirect
+//! is followed on its own, a transient failure is left for the worker to
+//! retry. A request the session lost before any response is sent again
+//! alone.
+//!
+//! Without a pooled session — the origin not seen lately, or not speaking
+//! HTTP/2 — the first fetch runs alone and opens one; the others then share
+//! it if it could be pooled, or run one by one.
+
+use super::hop::{endpoint, hop_request, settle};
+use super::progress::ProgressWriter;
+use super::{
+    fetch, fetch_with, parse_and_validate, Failure, FetchOutcome, Headers, ProgressFn, RequestSpec,
+};
+use crate::config::FetchLimits;
+use crate::error::ProxyError;
+use crate::headers::filter_request_headers;
+use crate::scheme;
+use crate::timeouts::Deadline;
+use crate::tunnel::h2::{Exchange, PooledSession};
+use crate::tunnel::http1::ResponseHead;
+use crate::tunnel::network::Network;
+use crate::tunnel::tls::{idle_session, request_h2_all};
+use url::Url;
+
+/// One fetch of a [`fetch_all`] batch, with the arguments of [`fetch`].
+pub struct BatchFetch<'a> {
+    /// The URL to fetch.
+    pub url: &'a str,
+    /// Custom request headers.
+    pub headers: &'a Headers,
+    /// Limits and policies of this fetch.
+    pub limits: &'a FetchLimits,
+    /// `Accept` header value.
+    pub accept: &'a str,
+    /// Where to report the body's progress, if anywhere.
+    pub progress: Option<&'a ProgressFn>,
+}
+
+/// How the first request of a fetch fared in a batch.
+enum FirstHop {
+    /// It could not be built; the fetch fails with this.
+    Invalid(ProxyError),
+    /// The session was lost before a response; it is sent again alone.
+    Lost,
+    /// This came back, after writing this many body bytes.
+    Answered(Result<ResponseHead, ProxyError>, u64),
+}
+
+/// The host and port of an `https` URL, which fetches must share to be
+/// batched. `None` for other URLs.
+pub fn origin(url: &str) -> Option<(String, u16)> {
+    let url = Url::parse(url).ok()?;
+    if url.scheme() != "https" {
+        return None;
+    }
+    Some((url.host_str()?.to_string(), url.port_or_known_default()?))
+}
+
+/// Fetch each of `fetches` into memory as [`fetch`] would, sending their
+/// first requests concurrently on one HTTP/2 session where possible. The
+/// fetches must share an `https` [`origin`]; results are in their order.
+pub fn fetch_all<N: Network>(
+    tunnel: &mut N,
+    fetches: &[BatchFetch<'_>],
+) -> Vec<Result<FetchOutcome, Failure>> {
+    let mut results = Vec::with_capacity(fetches.len());
+    let mut rest = fetches;
+    while let Some((first, others)) = rest.split_first() {
+        if !others.is_empty() {
+            if let Some((host, pooled)) = shared_session(tunnel, first) {
+                results.extend(multiplexed(tunnel, &host, pooled, rest));
+                break;
+            }
+        }
+        results.push(fetch_alone(tunnel, first));
+        rest = others;
+    }
+    results
+}
+
+/// The host of `fetch`'s URL and the idle HTTP/2 session to it, if pooled.
+fn shared_session<N: Network>(
+    tunnel: &mut N,
+    fetch: &BatchFetch<'_>,
+) -> Option<(String, PooledSession<N::Handle>)> {
+    let url = Url::parse(fetch.url).ok()?;
+    let host = url.host_str()?;
+    let endpoint = endpoint(tunnel, &url, fetch.limits).ok()?;
+    let pooled = idle_session(tunnel, endpoint, host)?;
+    Some((host.to_string(), pooled))
+}
+
+fn fetch_alone<N: Network>(tunnel: &mut N, job: &BatchFetch<'_>) -> Result<FetchOutcome, Failure> {
+    fetch(
+        tunnel,
+        job.url,
+        job.headers,
+        job.limits,
+        job.accept,
+        job.progress,
+    )
+}
+
+/// Send the first requests of `fetches` to `host` concurrently on `pooled`,
+/// under the first fetch's time budgets, then finish each fetch on its own.
+fn multiplexed<N: Network>(
+    tunnel: &mut N,
+    host: &str,
+    pooled: PooledSession<N::Handle>,
+    fetches: &[BatchFetch<'_>],
+) -> Vec<Result<FetchOutcome, Failure>> {
+    let specs: Vec<RequestSpec<'_>> = fetches
+        .iter()
+        .map(|fetch| RequestSpec {
+            method: "GET",
+            accept: fetch.accept,
+            headers: fetch.headers,
+            read_body: true,
+        })
+        .collect();
+    let requests: Vec<Result<Vec<u8>, ProxyError>> = fetches
+        .iter()
+        .zip(&specs)
+        .map(|(fetch, spec)| first_request(fetch, spec))
+        .collect();
+    let mut bodies: Vec<Vec<u8>> = fetches.iter().map(|_| Vec::new()).collect();
+    let mut writers: Vec<_> = bodies
+        .iter_mut()
+        .zip(fetches)
+        .map(|(body, fetch)| {
+            let mut counted = ProgressWriter::new(body, fetch.progress);
+            counted.cancelled = fetch.limits.cancelled.as_deref();
+            counted.kill_switch = fetch.limits.kill_switch.as_deref();
+            counted
+        })
+        .collect();
+
+    let mut sent = Vec::new();
+    let exchanges = writers
+        .iter_mut()
+        .zip(&requests)
+        .zip(fetches)
+        .enumerate()
+        .filter_map(|(index, ((body, request), fetch))| {
+            let request = request.as_ref().ok()?;
+            sent.push(index);
+            Some(Exchange {
+                request,
+                max_body: fetch.limits.max_size,
+                body,
+            })
+        })
+        .collect();
+    let deadline = Deadline::phased(&fetches[0].limits.timeouts);
+    let mut heads: Vec<_> = fetches.iter().map(|_| None).collect();
+    for (index, head) in sent
+        .into_iter()
+        .zip(request_h2_all(tunnel, pooled, exchanges, &deadline))
+    {
+        heads[index] = Some(head);
+    }
+    let sizes: Vec<u64> = writers.into_iter().map(ProgressWriter::finish).collect();
+
+    let hops = requests
+        .into_iter()
+        .zip(heads)
+        .zip(sizes)
+        .map(|((request, head), size)| match (request, head) {
+            (Err(error), _) => FirstHop::Invalid(error),
+            (Ok(_), Some(Ok(head))) => FirstHop::Answered(Ok(head), size),
+            (Ok(_), Some(Err(failure))) if !failure.replayable => {
+                FirstHop::Answered(Err(failure.error), size)
+            }
+            (Ok(_), _) => FirstHop::Lost,
+        });
+    let mut results = Vec::with_capacity(fetches.len());
+    for (((fetch, spec), hop), mut body) in fetches.iter().zip(&specs).zip(hops).zip(bodies) {
+        results.push(match hop {
+            FirstHop::Invalid(error) => Err(error.into()),
+            FirstHop::Lost => {
+                log::debug!("HTTP/2 session to {host} was lost; sending a request again alone");
+                fetch_alone(tunnel, fetch)
+            }
+            FirstHop::Answered(result, size) => {
+                let settled = settle(tunnel, host, fetch.limits, result, size);
+                let answered = Some((settled, size));
+                let (url, limits, progress) = (fetch.url, fetch.limits, fetch.progress);
+                fetch_with(tunnel, url, spec, limits, &mut body, progress, answered)
+                    .map(|outcome| outcome.with_body(body, N::TRANSPORT))
+            }
+        });
+    }
+    results
+}
+
+/// The first request of `fetch`, as [`fetch_with`] would send it.
+fn first_request(fetch: &BatchFetch<'_>, spec: &RequestSpec<'_>) -> Result<Vec<u8>, ProxyError> {
+    let limits = fetch.limits;
+    limits.check_cancelled()?;
+    let headers = filter_request_headers(spec.headers, &limits.allowed_sensitive_headers);
+    let (url, _) = scheme::apply(limits.https_mode, parse_and_validate(fetch.url)?)?;
+    hop_request(&url, spec, limits, &headers)
+}
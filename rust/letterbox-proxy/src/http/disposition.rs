@@ -0,0 +1,129 @@
+//! The file name suggested by a `Content-Disposition` header (RFC 6266).
+
+use percent_encoding::percent_decode_str;
+
+/// The file name `value` suggests, stripped of any directory part and control
+/// characters. An extended `filename*` parameter (RFC 8187) wins over a plain
+/// `filename`.
+pub(crate) fn filename(value: &str) -> Option<String> {
+    let params = parameters(value);
+    let extended = params
+        .iter()
+        .filter(|(name, _)| name == "filename*")
+        .find_map(|(_, value)| decode_extended(value));
+    let plain = || {
+        params
+            .iter()
+            .find(|(name, _)| name == "filename")
+            .map(|(_, value)| value.clone())
+    };
+    extended.or_else(plain).and_then(|name| sanitize(&name))
+}
+
+/// The `name=value` parameters after the disposition type, names lowercased
+/// and quoted values unescaped.
+fn parameters(value: &str) -> Vec<(String, String)> {
+    let mut params = Vec::new();
+    let mut rest = value.split_once(';').map_or("", |(_, rest)| rest);
+    while let Some((name, after)) = rest.split_once('=') {
+        // A parameter without a value ends at its `;`.
+        let name = name.rsplit(';').next().unwrap_or(name).trim();
+        let after = after.trim_start();
+        let (value, tail) = match after.strip_prefix('"') {
+            Some(quoted) => {
+                let mut value = String::new();
+                let mut end = quoted.len();
+                let mut chars = quoted.char_indices();
+                while let Some((i, c)) = chars.next() {
+                    match c {
+                        '\\' => value.extend(chars.next().map(|(_, escaped)| escaped)),
+                        '"' => {
+                            end = i + 1;
+                            break;
+                        }
+                        _ => value.push(c),
+                    }
+                }
+                (value, &quoted[end..])
+            }
+            None => {
+                let end = after.find(';').unwrap_or(after.len());
+                (after[..end].trim().to_string(), &after[end..])
+            }
+        };
+        params.push((name.to_ascii_lowercase(), value));
+        rest = tail;
+    }
+    params
+}
+
+/// An RFC 8187 `charset'language'percent-encoded` value, if it is UTF-8 (or
+/// its ASCII subset).
+fn decode_extended(value: &str) -> Option<String> {
+    let mut parts = value.splitn(3, '\'');
+    let (charset, _language, encoded) = (parts.next()?, parts.next()?, parts.next()?);
+    if !charset.eq_ignore_ascii_case("utf-8") && !charset.eq_ignore_ascii_case("us-ascii") {
+        return None;
+    }
+    percent_decode_str(encoded)
+        .decode_utf8()
+        .ok()
+        .map(|name| name.into_owned())
+}
+
+/// `name` without directories or control characters, unless nothing usable
+/// is left.
+fn sanitize(name: &str) -> Option<String> {
+    let base = name.rsplit(['/', '\\']).next().unwrap_or(name);
+    let clean: String = base.chars().filter(|c| !c.is_control()).collect();
+    let clean = clean.trim();
+    (!matches!(clean, "" | "." | "..")).then(|| clean.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_plain_and_quoted_names() {
+        assert_eq!(
+            filename("attachment; filename=logo.png").as_deref(),
+            Some("logo.png")
+        );
+        assert_eq!(
+            filename(r#"inline; filename="a \"b\"; c.png"; size=3"#).as_deref(),
+            Some(r#"a "b"; c.png"#)
+        );
+        assert_eq!(filename("inline"), None);
+        assert_eq!(filename("inline; name=x"), None);
+    }
+
+    #[test]
+    fn prefers_the_extended_name() {
+        assert_eq!(
+            filename(
+                "attachment; filename=\"fallback.jpg\"; filename*=UTF-8''na%C3%AFve%20cat.jpg"
+            )
+            .as_deref(),
+            Some("naïve cat.jpg")
+        );
+        // An undecodable extended name falls back to the plain one.
+        assert_eq!(
+            filename("attachment; filename*=ISO-8859-1''caf%E9.jpg; filename=cafe.jpg").as_deref(),
+            Some("cafe.jpg")
+        );
+    }
+
+    #[test]
+    fn strips_directories_and_control_characters() {
+        assert_eq!(
+            filename("attachment; filename=\"../../etc/pass\x07wd\"").as_deref(),
+            Some("passwd")
+        );
+        assert_eq!(
+            filename(r"attachment; filename=C:\Users\me\img.gif").as_deref(),
+            Some("img.gif")
+        );
+        assert_eq!(filename("attachment; filename=\"..\""), None);
+    }
+}
@@ -0,0 +1,80 @@
+//! Reading what a response head says about its body.
+
+use super::disposition;
+use crate::tunnel::http1::ResponseHead;
+use crate::types::ResponseHeaders;
+
+/// The full resource length a response announces, if any.
+pub(super) fn content_length(head: &ResponseHead) -> Option<u64> {
+    if head.status == 206 {
+        // `Content-Range: bytes 0-0/12345`; the total may be `*` (unknown).
+        return head
+            .header("content-range")
+            .and_then(|range| range.rsplit_once('/'))
+            .and_then(|(_, total)| total.trim().parse().ok());
+    }
+    head.header("content-length")
+        .and_then(|len| len.trim().parse().ok())
+}
+
+/// The headers of `head` passed through to the app.
+pub(super) fn passed_through(head: &ResponseHead) -> ResponseHeaders {
+    ResponseHeaders {
+        filename: head
+            .header("content-disposition")
+            .and_then(disposition::filename),
+        last_modified: head.header("last-modified").map(str::to_string),
+        cache_control: head.header("cache-control").map(str::to_string),
+        content_length: content_length(head),
+    }
+}
+
+/// Lowercase and strip parameters from a `Content-Type` value.
+pub(super) fn normalize_mime(value: &str) -> String {
+    value
+        .split(';')
+        .next()
+        .unwrap_or(value)
+        .trim()
+        .to_ascii_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn content_length_prefers_content_range_total_for_partial_responses() {
+        let head = |status, headers: &[(&str, &str)]| ResponseHead {
+            status,
+            headers: headers
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        };
+        assert_eq!(
+            content_length(&head(
+                206,
+                &[
+                    ("content-range", "bytes 0-0/12345"),
+                    ("content-length", "1")
+                ]
+            )),
+            Some(12345)
+        );
+        assert_eq!(
+            content_length(&head(206, &[("content-range", "bytes 0-0/*")])),
+            None
+        );
+        assert_eq!(
+            content_length(&head(200, &[("content-length", "77")])),
+            Some(77)
+        );
+    }
+
+    #[test]
+    fn normalize_mime_strips_params() {
+        assert_eq!(normalize_mime("image/PNG; charset=binary"), "image/png");
+        assert_eq!(normalize_mime("image/jpeg"), "image/jpeg");
+    }
+}
@@ -0,0 +1,159 @@
+//! One request of a fetch: building it, sending it over the network, and
+//! deciding whether its outcome is worth another attempt.
+
+use super::plain::request_plain;
+use super::progress::ProgressWriter;
+use super::{host_of, http_error, path_with_query, Failure, RequestSpec};
+use crate::config::FetchLimits;
+use crate::error::ProxyError;
+use crate::headers::with_referer;
+use crate::redirect::is_private;
+use crate::retry::{backoff, is_transient, parse_retry_after};
+use crate::timeouts::Deadline;
+use crate::tunnel::dns::resolve;
+use crate::tunnel::ech;
+use crate::tunnel::http1::{build_request, ResponseHead};
+use crate::tunnel::network::Network;
+use crate::tunnel::tls::{request_https, SendOptions, TlsServer};
+use std::io::Write;
+use std::net::SocketAddr;
+use std::time::Instant;
+use url::Url;
+
+/// The request for one hop to `url`, with the privacy-filtered caller
+/// `headers` and any `Referer` granted for its host.
+pub(super) fn hop_request(
+    url: &Url,
+    spec: &RequestSpec<'_>,
+    limits: &FetchLimits,
+    headers: &[(String, String)],
+) -> Result<Vec<u8>, ProxyError> {
+    let host = host_of(url)?;
+    Ok(build_request(
+        spec.method,
+        host,
+        &path_with_query(url),
+        spec.accept,
+        &with_referer(headers, limits.referer.as_ref(), host),
+    ))
+}
+
+/// Resolve the host of `url` to the address to connect to, recording the
+/// lookup time in the metrics.
+pub(super) fn endpoint<N: Network>(
+    tunnel: &mut N,
+    url: &Url,
+    limits: &FetchLimits,
+) -> Result<SocketAddr, ProxyError> {
+    let host = host_of(url)?;
+    let port = url
+        .port()
+        .unwrap_or(if url.scheme() == "https" { 443 } else { 80 });
+    let resolving = Instant::now();
+    let resolved = resolve(tunnel, host, limits.timeouts.dns());
+    if let Some(metrics) = &limits.metrics {
+        metrics.record_dns_latency(resolving.elapsed());
+    }
+    resolved.map(|ip| SocketAddr::from((ip, port)))
+}
+
+/// Send one request for `url`, saying when to retry a transient failure if
+/// `limits.retry` allows another attempt (see [`settle`]). On a `redirected`
+/// hop, a host resolving to private address space is refused if the policy
+/// says so.
+pub(super) fn send<N: Network, W: Write>(
+    tunnel: &mut N,
+    url: &Url,
+    request: &[u8],
+    limits: &FetchLimits,
+    body: &mut ProgressWriter<'_, W>,
+    redirected: bool,
+) -> Result<ResponseHead, Failure> {
+    let host = host_of(url)?;
+    let dns_timeout = limits.timeouts.dns();
+
+    limits.check_cancelled()?;
+    let result = endpoint(tunnel, url, limits).and_then(|endpoint| {
+        if redirected && limits.redirect.forbid_private_targets && is_private(endpoint.ip()) {
+            return Err(ProxyError::RedirectBlocked {
+                url: url.to_string(),
+                reason: "private address target".to_string(),
+            });
+        }
+        if url.scheme() == "https" {
+            // HTTPS records describe port 443 only.
+            let ech = (endpoint.port() == 443)
+                .then(|| ech::lookup(tunnel, host, dns_timeout))
+                .flatten();
+            let deadline = Deadline::phased(&limits.timeouts);
+            let server = TlsServer {
+                endpoint,
+                name: host,
+                ech: ech.as_deref(),
+            };
+            request_https(
+                tunnel,
+                server,
+                request,
+                SendOptions {
+                    early_data: limits.tls_early_data,
+                    hedge_after: limits.hedge_after,
+                },
+                limits.max_size,
+                &mut *body,
+                &deadline,
+            )
+        } else {
+            request_plain(
+                tunnel,
+                endpoint,
+                request,
+                limits.hedge_after,
+                limits.max_size,
+                &mut *body,
+                &Deadline::phased(&limits.timeouts),
+            )
+        }
+    });
+    settle(tunnel, host, limits, result, body.written)
+}
+
+/// Settle the `result` of a hop for `host`, saying when to retry a transient
+/// failure if `limits.retry` allows another attempt. `written` is how many
+/// body bytes the hop wrote.
+///
+/// A retryable status is only returned as a response once attempts run out,
+/// and failures after part of the body was written are never retried.
+pub(super) fn settle<N: Network>(
+    tunnel: &mut N,
+    host: &str,
+    limits: &FetchLimits,
+    result: Result<ResponseHead, ProxyError>,
+    written: u64,
+) -> Result<ResponseHead, Failure> {
+    let policy = &limits.retry;
+    let retry_after = match &result {
+        Ok(head) if policy.retryable_status_codes.contains(&head.status) => {
+            Some(head.header("retry-after").and_then(parse_retry_after))
+        }
+        Ok(_) => None,
+        Err(e) if written == 0 && is_transient(policy, e) => {
+            // The host may have moved; look it up afresh.
+            tunnel.dns_cache().forget(host);
+            Some(None)
+        }
+        Err(_) => None,
+    };
+    let retry_in = retry_after
+        .filter(|_| limits.attempt < policy.max_attempts)
+        .map(|retry_after| backoff(policy, limits.attempt, retry_after));
+    let Some(delay) = retry_in else {
+        return result.map_err(Failure::from);
+    };
+    let attempt = limits.attempt;
+    log::debug!("Attempt {attempt} for {host} failed; retrying in {delay:?}");
+    Err(Failure {
+        error: result.map_or_else(|error| error, |head| http_error(head.status)),
+        retry_in,
+    })
+}
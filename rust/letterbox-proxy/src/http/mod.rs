@@ -0,0 +1,386 @@
+//! Image/URL fetching over the WARP tunnel.
+//!
+//! Every request is resolved via DoH and carried over WireGuard, so the
+//! user's real IP is never exposed to image servers or the update endpoint.
+//! The exceptions are a build without the `tunnel` feature, where the same
+//! requests run directly over a
+//! [`DirectNetwork`](crate::tunnel::direct::DirectNetwork), and a configured
+//! upstream proxy, where they run over an
+//! [`UpstreamNetwork`](crate::tunnel::upstream::UpstreamNetwork). The module
+//! exposes a generic [`fetch`] used both for images and for the GitHub update
+//! check, and [`fetch_all`] for several fetches to one origin at once. The
+//! pure magic-byte helpers for content sniffing live in [`crate::sniff`] and
+//! are re-exported here.
+
+mod batch;
+mod disposition;
+mod head;
+mod hop;
+mod plain;
+mod progress;
+
+use crate::config::FetchLimits;
+use crate::error::ProxyError;
+use crate::events::ProxyEvent;
+use crate::headers::filter_request_headers;
+use crate::redirect::check_redirect;
+use crate::scheme;
+pub use crate::sniff::{guess_mime_type, validate_image_data};
+use crate::tunnel::http1::ResponseHead;
+use crate::tunnel::network::Network;
+use crate::types::{ResponseHeaders, Transport};
+pub use batch::{fetch_all, origin, BatchFetch};
+use head::{content_length, normalize_mime, passed_through};
+use hop::{hop_request, send};
+use progress::ProgressWriter;
+pub use progress::{ProgressFn, PROGRESS_STEP};
+use std::io::Write;
+use std::time::Duration;
+use url::Url;
+
+/// Outcome of a successful fetch through the tunnel.
+#[derive(Debug, Clone)]
+pub struct FetchOutcome {
+    /// HTTP status code of the final response.
+    pub status: u16,
+    /// Normalised MIME type (without parameters).
+    pub mime_type: String,
+    /// Response body bytes.
+    pub body: Vec<u8>,
+    /// Final URL after any redirects.
+    pub final_url: String,
+    /// URLs that answered with a redirect, in order.
+    pub redirect_chain: Vec<String>,
+    /// Headers of the final response passed through to the app.
+    pub headers: ResponseHeaders,
+    /// The network path the request took.
+    pub transport: Transport,
+}
+
+/// Custom request headers supplied by the caller.
+type Headers = [(String, String)];
+
+/// Why a fetch failed, and whether to try it again.
+#[derive(Debug)]
+pub struct Failure {
+    /// What went wrong.
+    pub error: ProxyError,
+    /// Set when the failure was transient and `limits.retry` grants another
+    /// attempt: how long to wait before making it. Waiting is left to the
+    /// caller, so that the tunnel worker can serve other requests meanwhile.
+    pub retry_in: Option<Duration>,
+}
+
+impl From<ProxyError> for Failure {
+    fn from(error: ProxyError) -> Self {
+        Failure {
+            error,
+            retry_in: None,
+        }
+    }
+}
+
+/// Metadata of a successful fetch whose body was streamed to a writer.
+#[derive(Debug, Clone)]
+pub struct StreamOutcome {
+    /// HTTP status code of the final response.
+    pub status: u16,
+    /// Normalised MIME type (without parameters).
+    pub mime_type: String,
+    /// Final URL after any redirects.
+    pub final_url: String,
+    /// URLs that answered with a redirect, in order.
+    pub redirect_chain: Vec<String>,
+    /// Full resource length announced by the server: `Content-Length`, or the
+    /// `Content-Range` total of a partial response.
+    pub content_length: Option<u64>,
+    /// Body bytes written.
+    pub size: u64,
+    /// Headers of the final response passed through to the app.
+    pub headers: ResponseHeaders,
+}
+
+impl StreamOutcome {
+    /// The outcome of a fetch into memory that received `body` over
+    /// `transport`.
+    fn with_body(self, body: Vec<u8>, transport: Transport) -> FetchOutcome {
+        FetchOutcome {
+            status: self.status,
+            mime_type: self.mime_type,
+            body,
+            final_url: self.final_url,
+            redirect_chain: self.redirect_chain,
+            headers: self.headers,
+            transport,
+        }
+    }
+}
+
+/// What to send on every hop of a fetch.
+struct RequestSpec<'a> {
+    /// `GET` or `HEAD`.
+    method: &'static str,
+    /// `Accept` header value.
+    accept: &'a str,
+    /// Caller headers, before the privacy filter.
+    headers: &'a Headers,
+    /// Whether the final 2xx body is read (false for metadata probes).
+    read_body: bool,
+}
+
+/// Fetch `url` through the tunnel into memory, following up to
+/// `limits.max_redirects`.
+///
+/// Content-type *filtering* is intentionally left to the caller so this can
+/// serve both image fetches (image/* only) and the JSON update check.
+pub fn fetch<N: Network>(
+    tunnel: &mut N,
+    url: &str,
+    headers: &Headers,
+    limits: &FetchLimits,
+    accept: &str,
+    progress: Option<&ProgressFn>,
+) -> Result<FetchOutcome, Failure> {
+    let mut body = Vec::new();
+    let outcome = fetch_into(tunnel, url, headers, limits, accept, &mut body, progress)?;
+    Ok(outcome.with_body(body, N::TRANSPORT))
+}
+
+/// Fetch `url` through the tunnel, streaming the final body into `body`.
+///
+/// Only the final 2xx response's body is written; redirect and error bodies
+/// are never read. `limits.https_mode` is applied to the URL and every redirect
+/// target, and each redirect is vetted against `limits.redirect` before it is
+/// followed. The size limit is enforced as bytes arrive, and `progress`
+/// (if any) is told how much of the body has been received.
+///
+/// This is a single attempt, numbered `limits.attempt`. A transient failure
+/// that the retry policy would try again says after how long; the whole
+/// fetch, redirects included, is then repeated from `url`.
+pub fn fetch_into<N: Network, W: Write>(
+    tunnel: &mut N,
+    url: &str,
+    headers: &Headers,
+    limits: &FetchLimits,
+    accept: &str,
+    body: &mut W,
+    progress: Option<&ProgressFn>,
+) -> Result<StreamOutcome, Failure> {
+    let spec = RequestSpec {
+        method: "GET",
+        accept,
+        headers,
+        read_body: true,
+    };
+    fetch_with(tunnel, url, &spec, limits, body, progress, None)
+}
+
+/// Fetch only the metadata of `url` — status, type, length, final URL.
+///
+/// Sends a `HEAD`; if the server rejects that with an error status, falls back
+/// to a `GET` for the first byte. No body is read either way.
+pub fn probe<N: Network>(
+    tunnel: &mut N,
+    url: &str,
+    headers: &Headers,
+    limits: &FetchLimits,
+    accept: &str,
+) -> Result<StreamOutcome, Failure> {
+    let head = RequestSpec {
+        method: "HEAD",
+        accept,
+        headers,
+        read_body: false,
+    };
+    match fetch_with(tunnel, url, &head, limits, &mut std::io::sink(), None, None) {
+        Err(Failure {
+            error:
+                ProxyError::HttpError {
+                    status_code: 400..=599,
+                    ..
+                },
+            retry_in: None,
+        }) => {
+            let mut ranged: Vec<(String, String)> = headers
+                .iter()
+                .filter(|(name, _)| !name.eq_ignore_ascii_case("range"))
+                .cloned()
+                .collect();
+            ranged.push(("Range".to_string(), "bytes=0-0".to_string()));
+            let get = RequestSpec {
+                method: "GET",
+                accept,
+                headers: &ranged,
+                read_body: false,
+            };
+            fetch_with(tunnel, url, &get, limits, &mut std::io::sink(), None, None)
+        }
+        other => other,
+    }
+}
+
+/// The redirect-following request loop shared by [`fetch_into`], [`probe`]
+/// and [`fetch_all`]. With `answered`, the first hop was already sent
+/// elsewhere and this is its settled response and the body bytes written.
+fn fetch_with<N: Network, W: Write>(
+    tunnel: &mut N,
+    url: &str,
+    spec: &RequestSpec<'_>,
+    limits: &FetchLimits,
+    body: &mut W,
+    progress: Option<&ProgressFn>,
+    mut answered: Option<(Result<ResponseHead, Failure>, u64)>,
+) -> Result<StreamOutcome, Failure> {
+    let headers = filter_request_headers(spec.headers, &limits.allowed_sensitive_headers);
+    let (mut current, mut fallback) = scheme::apply(limits.https_mode, parse_and_validate(url)?)?;
+    let mut chain: Vec<String> = Vec::new();
+
+    loop {
+        let (head, size) = match answered.take() {
+            Some((result, size)) => (result?, size),
+            None => {
+                limits.check_cancelled()?;
+                let request = hop_request(&current, spec, limits, &headers)?;
+                let redirected = !chain.is_empty();
+                let mut counted = ProgressWriter::new(&mut *body, progress);
+                counted.wants_body = spec.read_body;
+                counted.cancelled = limits.cancelled.as_deref();
+                counted.kill_switch = limits.kill_switch.as_deref();
+                let mut result = send(tunnel, &current, &request, limits, &mut counted, redirected);
+                if let (Err(failure), Some(plain)) = (&result, fallback.take()) {
+                    // A retry is tried over HTTPS again before giving up on it.
+                    if failure.retry_in.is_none()
+                        && counted.written == 0
+                        && scheme::may_fall_back(&failure.error)
+                    {
+                        let e = &failure.error;
+                        log::debug!("HTTPS upgrade failed ({e}); falling back to plain HTTP");
+                        if let Some(events) = &limits.events {
+                            events.emit(ProxyEvent::HttpsFallback {
+                                url: plain.to_string(),
+                            });
+                        }
+                        current = plain;
+                        result = send(tunnel, &current, &request, limits, &mut counted, redirected);
+                    }
+                }
+                (result?, counted.finish())
+            }
+        };
+
+        if let Some(location) = head.redirect_location() {
+            let redirects = chain.len() as u32 + 1;
+            if redirects > limits.max_redirects {
+                return Err(ProxyError::TooManyRedirects {
+                    count: redirects,
+                    max_count: limits.max_redirects,
+                }
+                .into());
+            }
+            let next = current.join(location).map_err(|e| ProxyError::InvalidUrl {
+                url: location.to_string(),
+                details: e.to_string(),
+            })?;
+            let (next, next_fallback) =
+                scheme::apply(limits.https_mode, parse_and_validate(next.as_str())?)?;
+            check_redirect(&limits.redirect, &current, &next)?;
+            // Falling back must not sneak past the policy the upgraded URL passed.
+            fallback = next_fallback
+                .filter(|plain| check_redirect(&limits.redirect, &current, plain).is_ok());
+            chain.push(std::mem::replace(&mut current, next).into());
+            continue;
+        }
+
+        if !(200..300).contains(&head.status) {
+            return Err(http_error(head.status).into());
+        }
+
+        let mime_type = head
+            .header("content-type")
+            .map(normalize_mime)
+            .unwrap_or_else(|| "application/octet-stream".to_string());
+
+        return Ok(StreamOutcome {
+            status: head.status,
+            mime_type,
+            final_url: current.to_string(),
+            redirect_chain: chain,
+            content_length: content_length(&head),
+            size,
+            headers: passed_through(&head),
+        });
+    }
+}
+
+/// The error for a response with a non-2xx `status`.
+fn http_error(status: u16) -> ProxyError {
+    ProxyError::HttpError {
+        status_code: status,
+        details: format!("HTTP {status}"),
+    }
+}
+
+/// The host of an http(s) URL.
+fn host_of(url: &Url) -> Result<&str, ProxyError> {
+    url.host_str().ok_or_else(|| ProxyError::InvalidUrl {
+        url: url.to_string(),
+        details: "URL has no host".to_string(),
+    })
+}
+
+/// Parse a URL and ensure it uses a supported scheme.
+fn parse_and_validate(url: &str) -> Result<Url, ProxyError> {
+    let parsed = Url::parse(url).map_err(|e| ProxyError::InvalidUrl {
+        url: url.to_string(),
+        details: e.to_string(),
+    })?;
+    if parsed.scheme() != "http" && parsed.scheme() != "https" {
+        return Err(ProxyError::InvalidUrl {
+            url: url.to_string(),
+            details: "Only http:// and https:// URLs are supported".to_string(),
+        });
+    }
+    Ok(parsed)
+}
+
+/// Build the request target (path plus optional query).
+fn path_with_query(url: &Url) -> String {
+    match url.query() {
+        Some(q) => format!("{}?{}", url.path(), q),
+        None => url.path().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_and_validate_accepts_http_and_https() {
+        assert!(parse_and_validate("http://example.com/x.png").is_ok());
+        assert!(parse_and_validate("https://example.com/x.png").is_ok());
+    }
+
+    #[test]
+    fn parse_and_validate_rejects_other_schemes() {
+        for url in [
+            "file:///etc/passwd",
+            "javascript:alert(1)",
+            "data:image/png;base64,AAAA",
+            "ftp://example.com/x",
+        ] {
+            assert!(matches!(
+                parse_and_validate(url),
+                Err(ProxyError::InvalidUrl { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn path_with_query_includes_query() {
+        let url = Url::parse("https://h/a/b?x=1&y=2").unwrap();
+        assert_eq!(path_with_query(&url), "/a/b?x=1&y=2");
+        let url = Url::parse("https://h/a/b").unwrap();
+        assert_eq!(path_with_query(&url), "/a/b");
+    }
+}
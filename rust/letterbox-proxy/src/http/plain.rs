@@ -0,0 +1,67 @@
+//! Plaintext HTTP/1.1 exchanges, for `http://` URLs the HTTPS mode lets
+//! through.
+
+use crate::error::{ProxyError, TimeoutPhase};
+use crate::timeouts::Deadline;
+use crate::tunnel::body::BodySink;
+use crate::tunnel::hedge::{self, Sent};
+use crate::tunnel::http1::ResponseHead;
+use crate::tunnel::network::Network;
+use std::io::Write;
+use std::net::SocketAddr;
+use std::time::Duration;
+
+/// Send a plaintext HTTP/1.1 request over the tunnel, streaming a 2xx body into
+/// `body` and aborting as soon as it exceeds `max_body`. `deadline` is moved
+/// through the connect, first byte and body phases. With `hedge_after`, the
+/// request is sent again on a second connection if its response has not
+/// started by then.
+pub(super) fn request_plain<N: Network, W: BodySink>(
+    tunnel: &mut N,
+    endpoint: SocketAddr,
+    request: &[u8],
+    hedge_after: Option<Duration>,
+    max_body: u64,
+    body: &mut W,
+    deadline: &Deadline,
+) -> Result<ResponseHead, ProxyError> {
+    let sent = send_plain(tunnel, endpoint, request, deadline)?;
+    let (sent, hedge_deadline) = match hedge_after {
+        Some(delay) => hedge::race(tunnel, sent, deadline, delay, |tunnel, deadline| {
+            send_plain(tunnel, endpoint, request, deadline).map(Some)
+        })?,
+        None => (sent, None),
+    };
+    sent.finish(
+        tunnel,
+        max_body,
+        body,
+        hedge_deadline.as_ref().unwrap_or(deadline),
+    )
+}
+
+/// Open a plain connection to `endpoint` and send `request` on it.
+fn send_plain<N: Network>(
+    tunnel: &mut N,
+    endpoint: SocketAddr,
+    request: &[u8],
+    deadline: &Deadline,
+) -> Result<Sent<N::Handle>, ProxyError> {
+    deadline.enter(TimeoutPhase::Connect);
+    let handle = tunnel.open_tcp(endpoint, deadline)?;
+    deadline.enter(TimeoutPhase::FirstByte);
+    let written = {
+        let mut stream = tunnel.stream(handle, deadline);
+        stream.write_all(request).and_then(|()| stream.flush())
+    };
+    match written {
+        Ok(()) => Ok(Sent::plain(handle)),
+        Err(e) => {
+            tunnel.close_tcp(handle);
+            Err(deadline.io_error(e, |e| ProxyError::HttpError {
+                status_code: 0,
+                details: format!("Write failed: {e}"),
+            }))
+        }
+    }
+}
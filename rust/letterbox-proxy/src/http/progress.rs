@@ -0,0 +1,146 @@
+//! Body progress accounting for the HTTP layer.
+//!
+//! [`ProgressWriter`] sits between the body decoder and the caller's writer,
+//! counting bytes and reporting them to an optional [`ProgressFn`] at most once
+//! per [`PROGRESS_STEP`], plus a final report. It also aborts the body once the
+//! request is cancelled or its client switched off.
+
+use crate::error::ProxyError;
+use crate::killswitch::KillSwitch;
+use crate::tunnel::body::BodySink;
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Progress callback: `(body bytes so far, announced total)`.
+pub type ProgressFn = Arc<dyn Fn(u64, Option<u64>) + Send + Sync>;
+
+/// Minimum body growth between two progress reports.
+pub const PROGRESS_STEP: u64 = 64 * 1024;
+
+/// A writer adapter counting the bytes passed through it and reporting
+/// progress every [`PROGRESS_STEP`] bytes.
+pub(super) struct ProgressWriter<'a, W> {
+    inner: W,
+    pub(super) wants_body: bool,
+    /// When set, writes fail with [`ProxyError::Cancelled`].
+    pub(super) cancelled: Option<&'a AtomicBool>,
+    /// Once switched off, writes fail with [`ProxyError::Disabled`].
+    pub(super) kill_switch: Option<&'a KillSwitch>,
+    pub(super) written: u64,
+    total: Option<u64>,
+    reported: Option<u64>,
+    progress: Option<&'a ProgressFn>,
+}
+
+impl<'a, W: Write> ProgressWriter<'a, W> {
+    pub(super) fn new(inner: W, progress: Option<&'a ProgressFn>) -> Self {
+        Self {
+            inner,
+            wants_body: true,
+            cancelled: None,
+            kill_switch: None,
+            written: 0,
+            total: None,
+            reported: None,
+            progress,
+        }
+    }
+
+    fn report(&mut self) {
+        if let Some(progress) = self.progress {
+            progress(self.written, self.total);
+            self.reported = Some(self.written);
+        }
+    }
+
+    /// Send the final report (if the last one is stale) and return the count.
+    pub(super) fn finish(mut self) -> u64 {
+        if self.reported.is_some() && self.reported != Some(self.written) {
+            self.report();
+        }
+        self.written
+    }
+}
+
+impl<W: Write> BodySink for ProgressWriter<'_, W> {
+    fn wants_body(&self) -> bool {
+        self.wants_body
+    }
+
+    fn expect_len(&mut self, len: Option<u64>) {
+        self.total = len;
+        self.report();
+    }
+}
+
+impl<W: Write> Write for ProgressWriter<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if self
+            .cancelled
+            .is_some_and(|flag| flag.load(Ordering::Relaxed))
+        {
+            return Err(std::io::Error::other(ProxyError::Cancelled));
+        }
+        if let Some(switch) = self.kill_switch {
+            switch.check().map_err(std::io::Error::other)?;
+        }
+        let n = self.inner.write(buf)?;
+        self.written += n as u64;
+        if self.written - self.reported.unwrap_or(0) >= PROGRESS_STEP {
+            self.report();
+        }
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[test]
+    fn progress_writer_throttles_and_reports_final_count() {
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let sink = reports.clone();
+        let progress: ProgressFn = Arc::new(move |done, total| {
+            sink.lock().unwrap().push((done, total));
+        });
+
+        let mut out = Vec::new();
+        let mut writer = ProgressWriter::new(&mut out, Some(&progress));
+        writer.expect_len(Some(PROGRESS_STEP + 10));
+        writer
+            .write_all(&vec![0u8; PROGRESS_STEP as usize])
+            .unwrap();
+        writer.write_all(&[0u8; 10]).unwrap();
+        assert_eq!(writer.finish(), PROGRESS_STEP + 10);
+
+        let total = Some(PROGRESS_STEP + 10);
+        assert_eq!(
+            *reports.lock().unwrap(),
+            vec![
+                (0, total),
+                (PROGRESS_STEP, total),
+                (PROGRESS_STEP + 10, total)
+            ]
+        );
+    }
+
+    #[test]
+    fn cancelled_writer_refuses_more_body() {
+        let flag = AtomicBool::new(false);
+        let mut out = Vec::new();
+        let mut writer = ProgressWriter::new(&mut out, None);
+        writer.cancelled = Some(&flag);
+        writer.write_all(b"abc").unwrap();
+        flag.store(true, Ordering::Relaxed);
+        let err = writer.write_all(b"def").unwrap_err();
+        assert_eq!(ProxyError::from(err), ProxyError::Cancelled);
+        assert_eq!(writer.finish(), 3);
+    }
+}
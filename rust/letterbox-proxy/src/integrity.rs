@@ -0,0 +1,82 @@
+//! Content hashes of fetched images.
+//!
+//! Every [`ImageResponse`] carries the SHA-256 of its body, so the app can
+//! compare or deduplicate images without hashing them again. A fetch given
+//! an expected hash fails with [`ProxyError::HashMismatch`] rather than return
+//! other content, pinning security-sensitive assets such as BIMI logos to a
+//! known version.
+
+use crate::cache::disk::hex;
+use crate::error::ProxyError;
+use crate::types::ImageResponse;
+use sha2::{Digest, Sha256};
+
+/// SHA-256 of `data`, as lowercase hex.
+pub(crate) fn sha256_hex(data: &[u8]) -> String {
+    hex(&Sha256::digest(data))
+}
+
+/// `response`, unless an `expected` SHA-256 (hex, in either case) is given
+/// and the body does not match it.
+pub(crate) fn verify(
+    url: &str,
+    response: ImageResponse,
+    expected: Option<&str>,
+) -> Result<ImageResponse, ProxyError> {
+    match expected {
+        Some(expected) if !expected.trim().eq_ignore_ascii_case(&response.sha256) => {
+            Err(ProxyError::HashMismatch {
+                url: url.to_string(),
+                expected: expected.to_string(),
+                actual: response.sha256,
+            })
+        }
+        _ => Ok(response),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(data: &[u8]) -> ImageResponse {
+        ImageResponse {
+            mime_type: "image/png".to_string(),
+            data: data.to_vec(),
+            from_cache: false,
+            final_url: "https://bimi.example/logo.svg".to_string(),
+            redirect_chain: Vec::new(),
+            redirect_hops: Vec::new(),
+            width: None,
+            height: None,
+            blurhash: None,
+            tracking_suspected: false,
+            trace_id: String::new(),
+            headers: Default::default(),
+            sha256: sha256_hex(data),
+            transport: None,
+        }
+    }
+
+    #[test]
+    fn hashes_are_lowercase_hex() {
+        assert_eq!(
+            sha256_hex(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn only_a_mismatching_expected_hash_fails() {
+        let url = "https://bimi.example/logo.svg";
+        let digest = "BA7816BF8F01CFEA414140DE5DAE2223B00361A396177A9CB410FF61F20015AD";
+        assert!(verify(url, response(b"abc"), None).is_ok());
+        assert!(verify(url, response(b"abc"), Some(digest)).is_ok());
+        assert_eq!(
+            verify(url, response(b"abd"), Some(digest))
+                .unwrap_err()
+                .code(),
+            crate::error::ErrorCode::HashMismatch
+        );
+    }
+}
@@ -0,0 +1,167 @@
+//! Switching remote content off.
+//!
+//! A user turning "load remote images" off wants it to take effect at once,
+//! not after the requests under way finish, and without the teardown of
+//! [`ProxyClient::shutdown`]. [`ProxyClient::set_enabled`] switches one client
+//! (one profile) off; [`proxy_set_enabled_globally`] switches off every
+//! client in the process. While either is off:
+//!
+//! - every new network request fails with [`ProxyError::Disabled`], before
+//!   any tunnel is started;
+//! - requests waiting in the tunnel's queue fail with it when their turn
+//!   comes, and a response being received fails with its next body bytes;
+//! - cache hits and `data:` URIs are still served, as nothing is fetched.
+//!
+//! Switching back on needs no re-initialisation; the offline queue picks up
+//! where it left off. The global switch reaches every live client through a
+//! registry of weak references, which does not keep a dropped client alive.
+
+use crate::client::{default_client, ProxyClient};
+use crate::error::ProxyError;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+
+/// Set while every client is switched off.
+static GLOBALLY_DISABLED: AtomicBool = AtomicBool::new(false);
+
+/// Every client created in the process, for the global switch to reach.
+static CLIENTS: Mutex<Vec<Weak<ProxyClient>>> = Mutex::new(Vec::new());
+
+/// Let the global switch reach `client`, forgetting clients since dropped.
+pub(crate) fn register(client: &Arc<ProxyClient>) {
+    let mut clients = CLIENTS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    clients.retain(|client| client.strong_count() > 0);
+    clients.push(Arc::downgrade(client));
+}
+
+/// Tell every live client it was switched on or off.
+fn switch_all(enabled: bool) {
+    let clients: Vec<Arc<ProxyClient>> = CLIENTS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .iter()
+        .filter_map(Weak::upgrade)
+        .collect();
+    for client in clients {
+        client.switched(enabled);
+    }
+}
+
+/// Whether a client is switched off, checked by its requests as they run.
+#[derive(Debug, Default)]
+pub struct KillSwitch {
+    disabled: AtomicBool,
+}
+
+impl KillSwitch {
+    /// Fail with [`ProxyError::Disabled`] if the client or every client is
+    /// switched off.
+    pub(crate) fn check(&self) -> Result<(), ProxyError> {
+        if self.disabled.load(Ordering::Acquire) || GLOBALLY_DISABLED.load(Ordering::Acquire) {
+            return Err(ProxyError::Disabled);
+        }
+        Ok(())
+    }
+}
+
+impl ProxyClient {
+    fn switched(&self, enabled: bool) {
+        if enabled {
+            self.offline.wake();
+        } else {
+            // Waiters for a fetch slot fail with `Disabled` once woken.
+            self.slots.wake();
+        }
+    }
+}
+
+#[uniffi::export]
+impl ProxyClient {
+    /// Switch this client's network requests on or off. Off, new requests
+    /// fail with [`ProxyError::Disabled`] and those under way are abandoned.
+    pub fn set_enabled(&self, enabled: bool) {
+        self.kill_switch.disabled.store(!enabled, Ordering::Release);
+        self.switched(enabled);
+    }
+
+    /// Whether this client may make network requests: switched on, and not
+    /// switched off globally.
+    pub fn is_enabled(&self) -> bool {
+        self.kill_switch.check().is_ok()
+    }
+}
+
+/// [`ProxyClient::set_enabled`] on the default proxy.
+#[uniffi::export]
+pub fn proxy_set_enabled(enabled: bool) {
+    default_client().set_enabled(enabled)
+}
+
+/// [`ProxyClient::is_enabled`] of the default proxy.
+#[uniffi::export]
+pub fn proxy_is_enabled() -> bool {
+    default_client().is_enabled()
+}
+
+/// Switch the network requests of every client in the process on or off.
+/// Switching on again leaves clients switched off on their own off.
+#[uniffi::export]
+pub fn proxy_set_enabled_globally(enabled: bool) {
+    GLOBALLY_DISABLED.store(!enabled, Ordering::Release);
+    switch_all(enabled);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn disabled_client_refuses_network_requests() {
+        let dir = tempfile::tempdir().unwrap();
+        let client = ProxyClient::new(dir.path().to_string_lossy().into_owned(), 10).unwrap();
+        assert!(client.is_enabled());
+        client.set_enabled(false);
+        assert!(!client.is_enabled());
+        assert!(matches!(
+            client.acquire_manager(),
+            Err(ProxyError::Disabled)
+        ));
+
+        client.set_enabled(true);
+        assert!(client.kill_switch.check().is_ok());
+    }
+
+    #[test]
+    fn switching_all_off_fails_slot_waiters_of_every_client() {
+        let dir = tempfile::tempdir().unwrap();
+        let client = ProxyClient::new(dir.path().to_string_lossy().into_owned(), 10).unwrap();
+        let limits = crate::types::FetchConcurrency {
+            max_in_flight: 1,
+            max_per_host: 0,
+        };
+        let held = client
+            .slots
+            .acquire_blocking("https://a.example/1", limits)
+            .unwrap();
+        let waiter = {
+            let client = Arc::clone(&client);
+            std::thread::spawn(move || {
+                client
+                    .slots
+                    .acquire_blocking("https://a.example/2", limits)
+                    .map(|_| ())
+            })
+        };
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        assert!(!waiter.is_finished());
+
+        // Flip this client's own switch so the global one, shared with other
+        // tests, stays on; only the wake-up goes through the registry.
+        client.kill_switch.disabled.store(true, Ordering::Release);
+        switch_all(false);
+        assert!(matches!(waiter.join().unwrap(), Err(ProxyError::Disabled)));
+        drop(held);
+    }
+}
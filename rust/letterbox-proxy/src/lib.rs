@@ -4,11 +4,29 @@
 //! over a userspace WireGuard tunnel.
 //!
 //! Every outbound HTTP(S) request — remote images *and* the GitHub update
-//! check — is carried over the tunnel ([`tunnel`]). There is no direct,
-//! non-tunnelled network path in the fetch flow, so the user's real IP address
-//! is never exposed to image servers or to GitHub. The only traffic that leaves
-//! the device unwrapped is the one-time WARP *registration* with Cloudflare's
-//! own API, which is intrinsic to obtaining WARP credentials.
+//! check — is carried over the tunnel ([`tunnel`]), so the user's real IP
+//! address is never exposed to image servers or to GitHub. The only traffic
+//! that leaves the device unwrapped is the one-time WARP *registration* with
+//! Cloudflare's own API, which is intrinsic to obtaining WARP credentials.
+//! There are two exceptions. A build without the `tunnel` feature fetches
+//! directly over the host's own sockets
+//! ([`DirectNetwork`](tunnel::direct::DirectNetwork)), and with an upstream
+//! proxy set ([`proxy_set_upstream_proxy`]) requests go through that proxy
+//! instead of WARP
+//! ([`UpstreamNetwork`](tunnel::upstream::UpstreamNetwork)), so servers see
+//! the proxy's address and the proxy sees the device's.
+//!
+//! ## Features
+//!
+//! - `tunnel` (default) — the WARP tunnel; implies `provisioning`. Without it
+//!   the crate is a slim fetcher: requests go directly to the network through
+//!   the same DoH, TLS, header, redirect and content controls and the same
+//!   cache, without boringtun or smoltcp, and the servers see the device's IP.
+//! - `provisioning` — WARP registration with Cloudflare, the
+//!   [`admin`] identity API and the TLS self-test.
+//! - `android_logger` — also write log records to logcat.
+//!
+//! Generate the bindings from a library built with the same features.
 //!
 //! ## Pipeline
 //!
@@ -18,69 +36,277 @@
 //!
 //! ## FFI API (exposed to Kotlin via UniFFI)
 //!
+//! Every operation is a method of [`ProxyClient`], an independent proxy with
+//! its own configuration, cache and tunnel. The `proxy_*` functions below
+//! perform the same operations on a shared default proxy.
+//!
 //! - [`proxy_init`] / [`proxy_shutdown`] — lifecycle.
 //! - [`proxy_status`] / [`proxy_diagnostics`] — observability.
-//! - [`proxy_fetch_image`] / [`proxy_fetch_images_batch`] — image fetching.
-//! - [`proxy_fetch_url`] — generic tunnelled fetch.
+//! - [`proxy_metrics`] — fetch outcomes, cache hit ratio, tunnel traffic,
+//!   handshakes, and fetch and DNS latency histograms.
+//! - [`proxy_recent_errors`] — the last 50 errors with time, host, error
+//!   code and transport, for a diagnostics screen.
+//! - [`proxy_fetch_image`] — image fetching (remote URLs, and `data:` URIs
+//!   decoded locally), optionally pinned to a SHA-256; async, so Kotlin sees
+//!   a suspend function.
+//! - [`proxy_set_group_byte_budget`] / [`proxy_reset_group_budget`] — byte
+//!   ceiling shared by the fetches of one message.
+//! - [`proxy_fetch_images_batch`] — a cancellable [`ImageBatch`] whose results
+//!   arrive all at once or, streamed to a [`BatchResultListener`], as each
+//!   image completes ([`proxy_fetch_images_streaming`]).
+//! - [`proxy_fetch_image_transformed`] — fetch, downscale to a bounding box
+//!   and optionally transcode (e.g. JPEG XL to PNG for older WebViews).
+//! - [`proxy_fetch_favicon`] — best-sized icon of a sender's domain.
+//! - [`proxy_resolve_preview`] — link-preview title and image (`og:image`,
+//!   `twitter:image` or the largest `<img>`) of a web page.
+//! - [`proxy_probe_image`] — type and size of an image without downloading it.
+//! - [`proxy_open_stream`] — a raw TCP byte stream through the tunnel, for
+//!   the app's other private requests (e.g. unsubscribe pages).
+//! - [`proxy_fetch_best_source`] — only the `srcset` candidate suited to the
+//!   slot and screen, optionally chosen by probed size.
+//! - [`proxy_fetch_image_to_file`] — stream a large image straight to disk.
+//! - [`proxy_fetch_image_handle`] — an [`ImageHandle`] whose bytes Kotlin
+//!   reads in chunks or has written to a file, instead of one whole copy.
+//! - [`proxy_start_loopback_server`] — serve images to a WebView from
+//!   `http://127.0.0.1`, so it only needs its `<img>` URLs rewritten.
+//! - [`proxy_warm_up`] / [`proxy_set_warm_up_hosts`] — provision, handshake
+//!   and resolve likely image hosts in the background before the first fetch.
+//! - [`proxy_prefetch`] — warm the cache in the background.
+//! - [`proxy_queue_for_offline`] — fetch images for offline reading once the
+//!   network is back, even after a restart.
+//! - [`proxy_set_data_saver`] — smaller images, no prefetching and
+//!   optionally no animations on metered networks.
+//! - [`proxy_pause`] / [`proxy_resume`] — hold network requests, e.g. while
+//!   the app is in the background.
+//! - [`proxy_set_enabled`] / [`proxy_set_enabled_globally`] — a kill switch
+//!   failing new requests with `Disabled` and abandoning those under way.
+//! - [`proxy_set_progress_listener`] — download progress callbacks.
+//! - [`proxy_set_event_listener`] — tunnel, provisioning, fallback, eviction
+//!   and error events instead of polling [`proxy_status`].
+//! - [`proxy_bandwidth_usage`] — bytes received per destination host, kept
+//!   across sessions, for users on metered connections.
+//! - [`proxy_set_audit_log`] — a size-rotated JSONL log of every image
+//!   request, URLs hashed, for users to review what was fetched.
+//! - [`proxy_set_socket_protector`] — hand the WireGuard socket to the app
+//!   for `VpnService.protect()` before the handshake.
+//! - [`proxy_set_warp_refresh`] / [`proxy_refresh_warp_config`] — fetch the
+//!   WARP configuration again when it is old or handshakes keep failing, and
+//!   switch the running tunnel to a rotated peer.
+//! - [`proxy_set_upstream_proxy`] — carry requests through the user's own
+//!   SOCKS5 (e.g. Tor) or HTTP `CONNECT` proxy instead of WARP.
+//! - [`proxy_set_network_binding`] — send the tunnel from a given local
+//!   address or Android network, e.g. to force cellular or Wi-Fi.
+//! - [`proxy_set_log_listener`] — forward Rust log records to the app (and,
+//!   with the `android_logger` feature, to logcat).
+//! - [`proxy_fetch_resource`] — web fonts and stylesheets, with their own
+//!   content-type allowlist and size limit.
+//! - [`proxy_fetch_url`] — generic tunnelled fetch (async).
+//! - [`proxy_set_allowed_sensitive_headers`] — opt in to forwarding `Cookie`,
+//!   `Referer` or `Authorization` request headers.
+//! - [`proxy_set_group_referer`] — send a `Referer`/`Origin` to one
+//!   hotlink-protected host with one message's image fetches.
+//! - [`proxy_set_image_accept`] — `Accept` header for image requests (AVIF and
+//!   WebP preferred by default); negotiated variants are cached separately.
+//! - [`proxy_set_https_mode`] — allow, upgrade or refuse plain `http://` URLs.
+//! - [`proxy_set_tls_early_data`] — send requests to repeat hosts as TLS 0-RTT
+//!   data on resumed sessions.
+//! - [`proxy_set_hedge_delay`] — send a request again on a second connection
+//!   when its response is slow to start.
+//! - [`proxy_set_max_image_pixels`] — decompression-bomb limit on declared
+//!   image dimensions.
+//! - [`proxy_set_svg_sanitization`] — strip scripts, event handlers and
+//!   external references from SVGs (on by default).
+//! - [`proxy_set_tracker_domains`] / [`proxy_set_block_tracking_pixels`] —
+//!   flag tracking pixels in `ImageResponse::tracking_suspected`, or refuse them.
+//! - [`proxy_set_retry_policy`] — automatic retries for transient failures.
+//! - [`proxy_set_timeout_budget`] — separate time budgets for DNS, connect,
+//!   TLS, first byte and body; a timeout names the phase that ran out.
+//! - [`proxy_set_rate_limit`] — global requests-per-minute ceiling, with a
+//!   bounded queue for the excess.
+//! - [`proxy_set_fetch_concurrency`] — image fetches in flight at once, in
+//!   total and per host, shared by every batch and caller.
+//! - [`proxy_set_redirect_policy`] — per-hop redirect rules (no downgrades, no
+//!   private targets, optionally same-origin only).
 //! - [`proxy_check_for_update`] — GitHub release check over the tunnel.
 //! - [`proxy_clear_cache`] — drop the in-memory image cache.
-
+//! - [`proxy_pin_url`] / [`proxy_unpin_url`] / [`proxy_pinned_urls`] — keep
+//!   selected images exempt from cache eviction.
+//! - [`proxy_enable_disk_cache`] / [`proxy_disable_disk_cache`] — persistent,
+//!   optionally encrypted cache tier.
+//! - [`proxy_set_cache_storage`] — cache images in a backend the app
+//!   supplies, behind or instead of the in-memory cache.
+//! - [`proxy_trim_cache`] — evict (or spill to disk) under memory pressure.
+//! - [`proxy_export_cache`] / [`proxy_import_cache`] — disk cache archive for
+//!   backup and device migration.
+
+#[cfg(feature = "provisioning")]
 pub mod admin;
+pub mod animation;
+pub mod audit;
+pub mod bandwidth;
+pub mod batch;
+pub mod cache;
+pub mod client;
+pub mod concurrency;
 pub mod config;
+pub mod data_uri;
+pub mod datasaver;
+pub mod dimensions;
+pub mod download;
+pub mod early_data;
+#[cfg(feature = "tunnel")]
+pub mod endpoint;
 pub mod error;
+pub mod events;
+pub mod favicon;
+pub mod fetch;
+pub mod groups;
+pub mod handle;
+pub mod headers;
+pub mod hedging;
+pub mod html;
 pub mod http;
+pub mod integrity;
+pub mod killswitch;
+pub mod logging;
+pub mod loopback;
+pub mod metrics;
+pub mod offline;
+pub mod pause;
+pub mod placeholder;
+pub mod prefetch;
+pub mod preview;
+pub mod probe;
+pub mod progress;
+pub mod protect;
+#[cfg(feature = "provisioning")]
 pub mod provisioning;
+pub mod recent_errors;
+pub mod redirect;
+#[cfg(feature = "provisioning")]
+pub mod refresh;
+pub mod resource;
+pub mod retry;
+pub mod scheme;
+#[cfg(feature = "provisioning")]
 pub mod selftest;
+pub mod sniff;
+pub mod srcset;
+pub mod stream;
+pub mod svg;
+#[cfg(feature = "mock")]
+pub mod test_support;
+pub mod throttle;
+pub mod timeouts;
+pub mod trace;
+pub mod tracking;
+pub mod transform;
 pub mod tunnel;
 pub mod types;
 pub mod update;
+pub mod upstream;
+pub mod warmup;
 
-use std::collections::HashMap;
-use std::sync::{Arc, Mutex, OnceLock};
+use std::sync::Arc;
 
+pub use audit::{proxy_audit_log_files, proxy_clear_audit_log, proxy_set_audit_log};
+pub use bandwidth::{proxy_bandwidth_usage, proxy_reset_bandwidth_usage, HostBandwidth};
+pub use batch::{
+    proxy_fetch_images_batch, proxy_fetch_images_streaming, BatchResultListener, ImageBatch,
+};
+pub use cache::{
+    proxy_clear_cache, proxy_disable_disk_cache, proxy_enable_disk_cache, proxy_export_cache,
+    proxy_import_cache, proxy_pin_url, proxy_pinned_urls, proxy_set_cache_storage,
+    proxy_trim_cache, proxy_unpin_url, CacheStorage, CacheStorageMode,
+};
+pub use client::ProxyClient;
+pub use concurrency::proxy_set_fetch_concurrency;
 pub use config::ProxyConfig;
-pub use error::ProxyError;
+pub use datasaver::{proxy_data_saver, proxy_set_data_saver, DataSaver};
+pub use dimensions::proxy_set_max_image_pixels;
+pub use download::proxy_fetch_image_to_file;
+pub use early_data::proxy_set_tls_early_data;
+pub use error::{ErrorCategory, ErrorCode, ProxyError, TimeoutPhase};
+pub use events::{proxy_set_event_listener, ProxyEvent, ProxyEventListener};
+pub use favicon::proxy_fetch_favicon;
+pub use fetch::{proxy_fetch_image, proxy_fetch_url, proxy_set_image_accept};
+pub use groups::{proxy_reset_group_budget, proxy_set_group_byte_budget, proxy_set_group_referer};
+pub use handle::{proxy_fetch_image_handle, ImageHandle};
+pub use headers::proxy_set_allowed_sensitive_headers;
+pub use hedging::proxy_set_hedge_delay;
+pub use killswitch::{proxy_is_enabled, proxy_set_enabled, proxy_set_enabled_globally};
+pub use logging::{proxy_set_log_listener, LogLevel, LogListener};
+pub use loopback::{proxy_start_loopback_server, LoopbackServer};
+pub use metrics::{proxy_metrics, LatencyHistogram, ProxyMetrics};
+pub use offline::{
+    proxy_clear_offline_queue, proxy_offline_queue, proxy_queue_for_offline,
+    proxy_retry_offline_queue, proxy_set_offline_listener, OfflineFetchListener,
+};
+pub use pause::{proxy_is_paused, proxy_pause, proxy_resume};
+pub use prefetch::proxy_prefetch;
+pub use preview::proxy_resolve_preview;
+pub use probe::proxy_probe_image;
+pub use progress::{proxy_set_progress_listener, ProgressListener};
+pub use protect::{
+    proxy_set_network_binding, proxy_set_socket_protector, NetworkBinding, SocketProtector,
+};
+pub use recent_errors::{proxy_clear_recent_errors, proxy_recent_errors};
+pub use redirect::proxy_set_redirect_policy;
+#[cfg(feature = "provisioning")]
+pub use refresh::{proxy_refresh_warp_config, proxy_set_warp_refresh};
+pub use resource::proxy_fetch_resource;
+pub use retry::proxy_set_retry_policy;
+pub use scheme::proxy_set_https_mode;
+pub use srcset::proxy_fetch_best_source;
+pub use stream::{proxy_open_stream, ProxyStream};
+pub use svg::proxy_set_svg_sanitization;
+pub use throttle::proxy_set_rate_limit;
+pub use timeouts::proxy_set_timeout_budget;
+pub use tracking::{proxy_set_block_tracking_pixels, proxy_set_tracker_domains};
+pub use transform::proxy_fetch_image_transformed;
 pub use types::{
-    BatchImageResult, HttpFetchResponse, ImageResponse, ProxyStatus, UpdateResult, WarpDiagnostics,
+    AnimationPolicy, BatchImageResult, DiskCacheOptions, FetchConcurrency, FetchPriority,
+    FileFetchOptions, FileFetchResult, HttpFetchResponse, HttpsMode, ImageResponse, ImageTransform,
+    LinkPreview, OutputFormat, ProxyStatus, RecentError, RedirectHop, RedirectPolicy, RefererGrant,
+    RequestPriority, ResourceKind, ResourceOptions, ResourceResponse, RetryPolicy, SrcsetCandidate,
+    TimeoutBudget, Transport, UpdateResult, UpstreamProxy, WarpDiagnostics, WarpRefresh,
     WarpStoredConfig,
 };
-
-use config::{FetchLimits, WarpConfig};
+pub use upstream::proxy_set_upstream_proxy;
+pub use warmup::{proxy_set_warm_up_hosts, proxy_warm_up};
+
+use cache::ImageCache;
+use client::default_client;
+use config::FetchLimits;
+#[cfg(feature = "tunnel")]
+use config::WarpConfig;
+use events::EventSink;
+pub(crate) use fetch::{fetch_image, header_pairs, validate_image_url};
+use metrics::Metrics;
+use protect::SocketPolicy;
+#[cfg(feature = "tunnel")]
 use provisioning::WarpProvisioner;
 use tunnel::{ConnectionState, TunnelDiagnostics, TunnelManager};
 
 uniffi::setup_scaffolding!();
 
-/// Global proxy state, lazily initialized.
-static PROXY_STATE: OnceLock<Mutex<Option<ProxyState>>> = OnceLock::new();
-
-fn proxy_state() -> &'static Mutex<Option<ProxyState>> {
-    PROXY_STATE.get_or_init(|| Mutex::new(None))
-}
-
-/// Lock the global state, recovering from poisoning.
-///
-/// A panic while a guard is held — e.g. during WARP provisioning or tunnel
-/// start-up, both of which run under this lock — poisons the `Mutex`. The
-/// protected [`ProxyState`] nonetheless stays structurally valid: at worst the
-/// tunnel manager is absent and gets rebuilt on the next fetch. Reclaiming the
-/// guard via [`PoisonError::into_inner`] lets the proxy retry and surface the
-/// real error, instead of permanently reporting a misleading "lock poisoned"
-/// failure that masks the original cause and bricks the proxy until restart.
-pub(crate) fn lock_state() -> std::sync::MutexGuard<'static, Option<ProxyState>> {
-    proxy_state()
-        .lock()
-        .unwrap_or_else(|poisoned| poisoned.into_inner())
-}
-
-/// Internal proxy state.
+/// Internal proxy state, owned by a [`ProxyClient`].
 pub(crate) struct ProxyState {
     pub(crate) config: ProxyConfig,
-    /// Shared so a fetch can run without holding the global lock. The `Arc` is
+    /// Shared so a fetch can run without holding the client lock. The `Arc` is
     /// genuine cross-section sharing (lock -> network -> lock), not a borrow hack.
     pub(crate) manager: Option<Arc<TunnelManager>>,
-    cache: lru::LruCache<String, ImageResponse>,
+    pub(crate) cache: ImageCache,
     pub(crate) last_error: Option<String>,
+    /// Whether network activity is paused; see [`pause`].
+    pub(crate) paused: bool,
+    /// The data-saver policy, while the network is metered; see [`datasaver`].
+    pub(crate) data_saver: Option<DataSaver>,
+    /// Bytes received per fetch group; see [`groups`].
+    pub(crate) groups: groups::GroupUsage,
+    /// When the WARP configuration was last due for a refresh; see `refresh`.
+    #[cfg(feature = "provisioning")]
+    pub(crate) warp_refresh_attempt: Option<std::time::Instant>,
 }
 
 impl ProxyState {
@@ -88,8 +314,17 @@ impl ProxyState {
     fn fetch_limits(&self) -> FetchLimits {
         FetchLimits {
             max_size: self.config.max_image_size,
+            max_pixels: self.config.max_image_pixels,
             max_redirects: self.config.max_redirects,
-            timeout_seconds: self.config.timeout_seconds,
+            timeouts: self.config.timeouts,
+            allowed_sensitive_headers: self.config.allowed_sensitive_headers.clone(),
+            retry: self.config.retry_policy.clone(),
+            redirect: self.config.redirect_policy.clone(),
+            https_mode: self.config.https_mode,
+            sanitize_svg: self.config.sanitize_svg,
+            tls_early_data: self.config.tls_early_data,
+            hedge_after: self.config.hedge_after,
+            rate_limit: self.config.rate_limit,
             ..FetchLimits::default()
         }
     }
@@ -110,6 +345,7 @@ pub(crate) fn block_on<F: std::future::Future>(future: F) -> Result<F::Output, P
 }
 
 /// Provision a fresh WARP account and persist it next to the proxy config.
+#[cfg(feature = "tunnel")]
 fn provision_and_save(config: &ProxyConfig) -> Result<WarpConfig, ProxyError> {
     let config_path = config.config_file_path();
     block_on(async move {
@@ -121,246 +357,108 @@ fn provision_and_save(config: &ProxyConfig) -> Result<WarpConfig, ProxyError> {
     })?
 }
 
-/// Ensure the tunnel manager exists, provisioning WARP on first use.
-fn ensure_manager(state: &mut ProxyState) -> Result<Arc<TunnelManager>, ProxyError> {
+/// Ensure the tunnel manager exists, provisioning WARP on first use unless
+/// an upstream proxy replaces it.
+pub(crate) fn ensure_manager(
+    state: &mut ProxyState,
+    events: &EventSink,
+    metrics: &Metrics,
+    socket_policy: &SocketPolicy,
+) -> Result<Arc<TunnelManager>, ProxyError> {
     if let Some(manager) = &state.manager {
         return Ok(manager.clone());
     }
+    if state.paused {
+        return Err(ProxyError::Paused);
+    }
+    let manager = Arc::new(match state.config.upstream_proxy.clone() {
+        Some(proxy) => TunnelManager::upstream(proxy)?,
+        None => start_manager(state, events, metrics, socket_policy)?,
+    });
+    state.manager = Some(manager.clone());
+    Ok(manager)
+}
 
+/// Bring up the WARP tunnel, provisioning an identity first if there is none.
+#[cfg(feature = "tunnel")]
+fn start_manager(
+    state: &mut ProxyState,
+    events: &EventSink,
+    metrics: &Metrics,
+    socket_policy: &SocketPolicy,
+) -> Result<TunnelManager, ProxyError> {
     let warp_config = match state.config.warp_config.clone() {
         Some(config) => config,
         None => {
+            metrics.record_provisioning();
             let config = provision_and_save(&state.config)?;
             state.config.warp_enabled = config.warp_enabled;
             state.config.endpoint_host = Some(config.peer.endpoint_host.clone());
             state.config.warp_config = Some(config.clone());
+            events.emit(ProxyEvent::ProvisioningCompleted {
+                account_type: config.account_type.clone(),
+            });
             config
         }
     };
 
-    let manager = Arc::new(TunnelManager::start(warp_config)?);
-    state.manager = Some(manager.clone());
-    Ok(manager)
-}
-
-/// Validate that a URL is a fetchable http(s) URL.
-fn validate_image_url(url: &str) -> Result<(), ProxyError> {
-    let parsed = url::Url::parse(url).map_err(|e| ProxyError::InvalidUrl {
-        url: url.to_string(),
-        details: e.to_string(),
-    })?;
-    if parsed.scheme() != "http" && parsed.scheme() != "https" {
-        return Err(ProxyError::InvalidUrl {
-            url: url.to_string(),
-            details: "Only http:// and https:// URLs are supported".to_string(),
-        });
+    let endpoint = warp_config.peer.endpoint_host.clone();
+    let manager = match TunnelManager::start(warp_config.clone(), socket_policy.get()) {
+        Err(
+            e @ ProxyError::Timeout {
+                phase: TimeoutPhase::Tunnel,
+                ..
+            },
+        ) => {
+            metrics.record_handshake_failure();
+            log::warn!("WARP endpoint did not answer ({e}); resolving {endpoint} again");
+            endpoint::recover(&mut state.config, warp_config, socket_policy.get())
+        }
+        started => started,
     }
-    Ok(())
-}
-
-/// Convert optional FFI headers into the ordered pairs the tunnel expects.
-fn header_pairs(headers: Option<&HashMap<String, String>>) -> Vec<(String, String)> {
-    headers
-        .map(|map| map.iter().map(|(k, v)| (k.clone(), v.clone())).collect())
-        .unwrap_or_default()
-}
-
-/// Acquire the shared manager (initialising it if needed) under the lock,
-/// returning a clone plus the current fetch limits.
-fn acquire_manager() -> Result<(Arc<TunnelManager>, FetchLimits), ProxyError> {
-    let mut guard = lock_state();
-    let state = guard.as_mut().ok_or(ProxyError::NotInitialized)?;
-    let manager = ensure_manager(state)?;
-    let limits = state.fetch_limits();
-    Ok((manager, limits))
+    .inspect_err(|_| metrics.record_handshake_failure())?;
+    metrics.record_handshake();
+    events.emit(ProxyEvent::TunnelConnected { endpoint });
+    Ok(manager)
 }
 
-/// Record the most recent error for surfacing through [`proxy_status`].
-fn record_error(message: &str) {
-    let mut guard = lock_state();
-    if let Some(state) = guard.as_mut() {
-        state.last_error = Some(message.to_string());
-    }
+/// Without the `tunnel` feature, requests go directly to the network.
+#[cfg(not(feature = "tunnel"))]
+fn start_manager(
+    _state: &mut ProxyState,
+    _events: &EventSink,
+    _metrics: &Metrics,
+    _socket_policy: &SocketPolicy,
+) -> Result<TunnelManager, ProxyError> {
+    TunnelManager::direct()
 }
 
-/// Initialize the image proxy.
+/// Initialize the default proxy.
 ///
 /// Loads or creates persisted configuration and prepares the in-memory cache.
 /// WARP provisioning and the WireGuard handshake are deferred until the first
-/// fetch so initialization stays fast and works offline.
+/// fetch so initialization stays fast and works offline. Calling it again
+/// before [`proxy_shutdown`] has no effect.
 #[uniffi::export]
 pub fn proxy_init(storage_path: String, max_cache_size: u32) -> Result<(), ProxyError> {
-    let config = block_on(ProxyConfig::load_or_create(&storage_path))??;
-
-    let cache_size = std::num::NonZeroUsize::new(max_cache_size as usize)
-        .unwrap_or(std::num::NonZeroUsize::new(100).unwrap());
-
-    let mut guard = lock_state();
-    if guard.is_some() {
-        return Ok(());
-    }
-    *guard = Some(ProxyState {
-        config,
-        manager: None,
-        cache: lru::LruCache::new(cache_size),
-        last_error: None,
-    });
-    Ok(())
+    default_client().init(&storage_path, max_cache_size)?;
+    default_client().start_offline_queue()
 }
 
-/// Get the current proxy status.
+/// [`ProxyClient::status`] of the default proxy.
 #[uniffi::export]
 pub fn proxy_status() -> Result<ProxyStatus, ProxyError> {
-    let guard = lock_state();
-    match guard.as_ref() {
-        Some(state) => Ok(ProxyStatus {
-            ready: true,
-            warp_enabled: state.config.warp_enabled,
-            tunnel_connected: state.manager.is_some(),
-            endpoint: state.config.endpoint_host.clone(),
-            last_error: state.last_error.clone(),
-            cache_size: state.cache.len() as u32,
-        }),
-        None => Ok(ProxyStatus {
-            ready: false,
-            warp_enabled: false,
-            tunnel_connected: false,
-            endpoint: None,
-            last_error: Some("Proxy not initialized".to_string()),
-            cache_size: 0,
-        }),
-    }
+    default_client().status()
 }
 
-/// Fetch a single image through the WARP tunnel.
-#[uniffi::export]
-pub fn proxy_fetch_image(
-    url: String,
-    headers: Option<HashMap<String, String>>,
-) -> Result<ImageResponse, ProxyError> {
-    fetch_image(&url, headers.as_ref()).inspect_err(|e| {
-        record_error(&e.to_string());
-    })
-}
-
-/// Internal image fetch: cache-aware, tunnelled, content-validated.
-fn fetch_image(
-    url: &str,
-    headers: Option<&HashMap<String, String>>,
-) -> Result<ImageResponse, ProxyError> {
-    validate_image_url(url)?;
-
-    // Fast path: serve from cache without touching the network or the tunnel.
-    {
-        let mut guard = lock_state();
-        let state = guard.as_mut().ok_or(ProxyError::NotInitialized)?;
-        if let Some(cached) = state.cache.get(url) {
-            return Ok(ImageResponse {
-                from_cache: true,
-                ..cached.clone()
-            });
-        }
-    }
-
-    let (manager, limits) = acquire_manager()?;
-    let outcome = manager.fetch(
-        url.to_string(),
-        header_pairs(headers),
-        "image/*".to_string(),
-        limits,
-    )?;
-
-    if !outcome.mime_type.starts_with("image/") {
-        return Err(ProxyError::InvalidContentType {
-            content_type: outcome.mime_type,
-        });
-    }
-
-    let response = ImageResponse {
-        mime_type: outcome.mime_type,
-        data: outcome.body,
-        from_cache: false,
-        final_url: outcome.final_url,
-    };
-
-    {
-        let mut guard = lock_state();
-        if let Some(state) = guard.as_mut() {
-            state.cache.put(url.to_string(), response.clone());
-        }
-    }
-
-    Ok(response)
-}
-
-/// Fetch multiple images through the tunnel.
-///
-/// Requests are serviced by the single shared tunnel, so they are processed in
-/// order; `max_concurrent` is accepted for API stability but currently advisory.
-#[uniffi::export]
-pub fn proxy_fetch_images_batch(
-    urls: Vec<String>,
-    _max_concurrent: u32,
-) -> Result<Vec<BatchImageResult>, ProxyError> {
-    let mut results = Vec::with_capacity(urls.len());
-    for url in urls {
-        match fetch_image(&url, None) {
-            Ok(response) => results.push(BatchImageResult {
-                url,
-                success: true,
-                response: Some(response),
-                error: None,
-            }),
-            Err(e) => results.push(BatchImageResult {
-                url,
-                success: false,
-                response: None,
-                error: Some(e.to_string()),
-            }),
-        }
-    }
-    Ok(results)
-}
-
-/// Fetch an arbitrary URL through the tunnel (non-image content allowed).
-#[uniffi::export]
-pub fn proxy_fetch_url(
-    url: String,
-    headers: Option<HashMap<String, String>>,
-) -> Result<HttpFetchResponse, ProxyError> {
-    let (manager, limits) = acquire_manager()?;
-    let outcome = manager
-        .fetch(
-            url,
-            header_pairs(headers.as_ref()),
-            "*/*".to_string(),
-            limits,
-        )
-        .inspect_err(|e| {
-            record_error(&e.to_string());
-        })?;
-    Ok(HttpFetchResponse {
-        status: outcome.status,
-        mime_type: outcome.mime_type,
-        data: outcome.body,
-        final_url: outcome.final_url,
-    })
-}
-
-/// Collect full WireGuard/WARP diagnostics, provisioning the tunnel if needed.
+/// [`ProxyClient::diagnostics`] of the default proxy.
 #[uniffi::export]
 pub fn proxy_diagnostics() -> Result<WarpDiagnostics, ProxyError> {
-    let manager = {
-        let mut guard = lock_state();
-        let state = guard.as_mut().ok_or(ProxyError::NotInitialized)?;
-        ensure_manager(state)?
-    };
-    let diagnostics = manager.diagnostics()?;
-    Ok(to_ffi_diagnostics(diagnostics))
+    default_client().diagnostics()
 }
 
 /// Map internal diagnostics into the FFI record.
-fn to_ffi_diagnostics(d: TunnelDiagnostics) -> WarpDiagnostics {
+pub(crate) fn to_ffi_diagnostics(d: TunnelDiagnostics) -> WarpDiagnostics {
     WarpDiagnostics {
         connection_state: match d.connection_state {
             ConnectionState::Connected => "connected".to_string(),
@@ -384,122 +482,18 @@ fn to_ffi_diagnostics(d: TunnelDiagnostics) -> WarpDiagnostics {
     }
 }
 
-/// Check for a newer release through the tunnel.
-///
-/// Pass the running version (e.g. `"v1.2.3"`); `repo` defaults to the official
-/// distribution slug when empty.
+/// [`ProxyClient::check_for_update`] through the default proxy.
 #[uniffi::export]
 pub fn proxy_check_for_update(
     current_version: String,
     repo: Option<String>,
 ) -> Result<UpdateResult, ProxyError> {
-    let repo = repo
-        .filter(|r| !r.is_empty())
-        .unwrap_or_else(|| update::DEFAULT_REPO.to_string());
-
-    let (manager, _) = acquire_manager()?;
-    let info = update::check_for_update(&manager, &current_version, &repo).inspect_err(|e| {
-        record_error(&e.to_string());
-    })?;
-
-    Ok(UpdateResult {
-        update_available: info.update_available,
-        current_version: info.current_version,
-        latest_version: info.latest_version,
-        latest_tag: info.latest_tag,
-        changelog: info.changelog,
-        release_url: info.release_url,
-    })
+    default_client().check_for_update(current_version, repo)
 }
 
-/// Shut down the proxy, dropping the tunnel and cache.
+/// [`ProxyClient::shutdown`] the default proxy; [`proxy_init`] may be called
+/// again afterwards.
 #[uniffi::export]
 pub fn proxy_shutdown() -> Result<(), ProxyError> {
-    let mut guard = lock_state();
-    // Dropping the state drops the manager, which joins the worker thread.
-    *guard = None;
-    Ok(())
-}
-
-/// Clear the in-memory image cache.
-#[uniffi::export]
-pub fn proxy_clear_cache() -> Result<(), ProxyError> {
-    let mut guard = lock_state();
-    if let Some(state) = guard.as_mut() {
-        state.cache.clear();
-    }
-    Ok(())
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn url_validation_rejects_non_http_schemes() {
-        assert!(validate_image_url("not-a-url").is_err());
-        for url in [
-            "ftp://example.com/x.png",
-            "file:///etc/passwd",
-            "javascript:alert(1)",
-            "data:image/png;base64,AAAA",
-        ] {
-            assert!(matches!(
-                validate_image_url(url),
-                Err(ProxyError::InvalidUrl { .. })
-            ));
-        }
-    }
-
-    #[test]
-    fn url_validation_accepts_http_and_https() {
-        assert!(validate_image_url("http://example.com/x.png").is_ok());
-        assert!(validate_image_url("https://example.com/x.png").is_ok());
-    }
-
-    #[test]
-    fn header_pairs_handles_none_and_some() {
-        assert!(header_pairs(None).is_empty());
-        let mut map = HashMap::new();
-        map.insert("X-A".to_string(), "1".to_string());
-        let pairs = header_pairs(Some(&map));
-        assert_eq!(pairs, vec![("X-A".to_string(), "1".to_string())]);
-    }
-
-    #[test]
-    fn image_response_clone_preserves_fields() {
-        let response = ImageResponse {
-            mime_type: "image/png".to_string(),
-            data: vec![0x89, 0x50, 0x4E, 0x47],
-            from_cache: false,
-            final_url: "https://example.com/image.png".to_string(),
-        };
-        let cloned = response.clone();
-        assert_eq!(response.mime_type, cloned.mime_type);
-        assert_eq!(response.data, cloned.data);
-    }
-
-    #[test]
-    fn batch_result_variants() {
-        let ok = BatchImageResult {
-            url: "https://example.com/a.png".to_string(),
-            success: true,
-            response: Some(ImageResponse {
-                mime_type: "image/png".to_string(),
-                data: vec![1, 2, 3, 4],
-                from_cache: false,
-                final_url: "https://example.com/a.png".to_string(),
-            }),
-            error: None,
-        };
-        assert!(ok.success && ok.response.is_some() && ok.error.is_none());
-
-        let err = BatchImageResult {
-            url: "https://example.com/b.png".to_string(),
-            success: false,
-            response: None,
-            error: Some("HTTP 404".to_string()),
-        };
-        assert!(!err.success && err.response.is_none() && err.error.is_some());
-    }
+    default_client().shutdown()
 }
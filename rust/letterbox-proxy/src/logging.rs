@@ -0,0 +1,232 @@
+//! Forwarding Rust `log` output to the host app.
+//!
+//! Without a logger installed, every `log::warn!` from the tunnel, provisioning
+//! and cache code is discarded — on Android nothing reaches logcat. The proxy
+//! installs its own logger the first time a client is initialised or a
+//! [`LogListener`] is registered with [`proxy_set_log_listener`], and forwards
+//! each record (level, target, message) to the listener, e.g. for an in-app
+//! debug screen. With the `android_logger` feature, records are also written
+//! to logcat under the `letterbox` tag.
+//!
+//! The `log` crate allows one logger per process, so the listener is
+//! process-wide rather than per client. If the host process already installed
+//! a logger, that one keeps receiving the records and the listener stays
+//! silent. Records are delivered on a dedicated `letterbox-log` thread, so a
+//! listener may call back into the proxy.
+//!
+//! Records written while a request is handled start with its trace id, e.g.
+//! `[trace 5f0c9a1e] Retrying after connection reset`; see [`crate::trace`].
+
+use crate::error::ProxyError;
+use crate::trace;
+#[cfg(feature = "android_logger")]
+use log::Log;
+use std::sync::mpsc::{channel, Sender};
+use std::sync::{Arc, Mutex, MutexGuard, Once};
+
+/// Severity of a forwarded log record.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Enum)]
+pub enum LogLevel {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl From<log::Level> for LogLevel {
+    fn from(level: log::Level) -> Self {
+        match level {
+            log::Level::Error => LogLevel::Error,
+            log::Level::Warn => LogLevel::Warn,
+            log::Level::Info => LogLevel::Info,
+            log::Level::Debug => LogLevel::Debug,
+            log::Level::Trace => LogLevel::Trace,
+        }
+    }
+}
+
+impl From<LogLevel> for log::LevelFilter {
+    fn from(level: LogLevel) -> Self {
+        match level {
+            LogLevel::Error => log::LevelFilter::Error,
+            LogLevel::Warn => log::LevelFilter::Warn,
+            LogLevel::Info => log::LevelFilter::Info,
+            LogLevel::Debug => log::LevelFilter::Debug,
+            LogLevel::Trace => log::LevelFilter::Trace,
+        }
+    }
+}
+
+/// Receives the proxy's log records.
+#[uniffi::export(with_foreign)]
+pub trait LogListener: Send + Sync {
+    /// Called on the log thread, one record at a time. `target` is the Rust
+    /// module that logged, e.g. `letterbox_proxy::tunnel::manager`.
+    fn on_log(&self, level: LogLevel, target: String, message: String);
+}
+
+/// A record on its way to the listener.
+struct Entry {
+    level: LogLevel,
+    target: String,
+    message: String,
+}
+
+/// The process-wide logger: the listener's queue, plus logcat if enabled.
+struct ForwardingLogger {
+    sender: Mutex<Option<Sender<Entry>>>,
+    #[cfg(feature = "android_logger")]
+    logcat: std::sync::OnceLock<android_logger::AndroidLogger>,
+}
+
+static LOGGER: ForwardingLogger = ForwardingLogger {
+    sender: Mutex::new(None),
+    #[cfg(feature = "android_logger")]
+    logcat: std::sync::OnceLock::new(),
+};
+
+static INSTALL: Once = Once::new();
+
+impl ForwardingLogger {
+    fn lock(&self) -> MutexGuard<'_, Option<Sender<Entry>>> {
+        self.sender
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl log::Log for ForwardingLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &log::Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        match trace::current() {
+            Some(id) => self.forward(
+                &log::Record::builder()
+                    .args(format_args!("[trace {id}] {}", record.args()))
+                    .metadata(record.metadata().clone())
+                    .module_path(record.module_path())
+                    .file(record.file())
+                    .line(record.line())
+                    .build(),
+            ),
+            None => self.forward(record),
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+impl ForwardingLogger {
+    /// Hand `record` to logcat and the listener.
+    fn forward(&self, record: &log::Record) {
+        #[cfg(feature = "android_logger")]
+        self.logcat
+            .get_or_init(|| {
+                android_logger::AndroidLogger::new(
+                    android_logger::Config::default()
+                        .with_tag("letterbox")
+                        .with_max_level(log::LevelFilter::Trace),
+                )
+            })
+            .log(record);
+        if let Some(sender) = self.lock().as_ref() {
+            // A closed channel means the listener is being replaced.
+            let _ = sender.send(Entry {
+                level: record.level().into(),
+                target: record.target().to_string(),
+                message: record.args().to_string(),
+            });
+        }
+    }
+}
+
+/// Install the forwarding logger, unless the process already has one.
+///
+/// Records up to `Info` pass until the host picks a level.
+pub(crate) fn install() {
+    INSTALL.call_once(|| match log::set_logger(&LOGGER) {
+        Ok(()) => log::set_max_level(log::LevelFilter::Info),
+        Err(_) => log::debug!("A logger is already installed; not forwarding logs"),
+    });
+}
+
+/// Forward log records at `max_level` and above to `listener`, or stop
+/// forwarding with `None`. `max_level` also applies to logcat.
+#[uniffi::export]
+pub fn proxy_set_log_listener(
+    listener: Option<Arc<dyn LogListener>>,
+    max_level: LogLevel,
+) -> Result<(), ProxyError> {
+    install();
+    log::set_max_level(max_level.into());
+    let Some(listener) = listener else {
+        *LOGGER.lock() = None;
+        return Ok(());
+    };
+    let (sender, receiver) = channel::<Entry>();
+    std::thread::Builder::new()
+        .name("letterbox-log".to_string())
+        .spawn(move || {
+            for entry in receiver {
+                listener.on_log(entry.level, entry.target, entry.message);
+            }
+        })
+        .map_err(|e| ProxyError::InitializationFailed {
+            details: format!("Failed to spawn log thread: {e}"),
+        })?;
+    *LOGGER.lock() = Some(sender);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    type Line = (LogLevel, String, String);
+
+    struct Forward(Mutex<Sender<Line>>);
+
+    impl LogListener for Forward {
+        fn on_log(&self, level: LogLevel, target: String, message: String) {
+            let _ = self.0.lock().unwrap().send((level, target, message));
+        }
+    }
+
+    #[test]
+    fn records_at_or_above_the_level_reach_the_listener() {
+        let (tx, rx) = channel();
+        proxy_set_log_listener(Some(Arc::new(Forward(Mutex::new(tx)))), LogLevel::Warn).unwrap();
+        log::info!(target: "letterbox_test", "filtered");
+        log::warn!(target: "letterbox_test", "handshake took {}s", 3);
+
+        // Other tests may log concurrently; look for ours.
+        let wait = Duration::from_secs(5);
+        let line = std::iter::from_fn(|| rx.recv_timeout(wait).ok())
+            .find(|(_, target, _)| target == "letterbox_test")
+            .unwrap();
+        assert_eq!(
+            line,
+            (
+                LogLevel::Warn,
+                "letterbox_test".to_string(),
+                "handshake took 3s".to_string()
+            )
+        );
+
+        trace::scope(Some("5f0c9a1e"), || {
+            log::warn!(target: "letterbox_test", "retrying");
+        });
+        let line = std::iter::from_fn(|| rx.recv_timeout(wait).ok())
+            .find(|(_, target, _)| target == "letterbox_test")
+            .unwrap();
+        assert_eq!(line.2, "[trace 5f0c9a1e] retrying");
+        proxy_set_log_listener(None, LogLevel::Info).unwrap();
+    }
+}
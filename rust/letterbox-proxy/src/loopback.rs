@@ -0,0 +1,331 @@
+//! Loopback HTTP server for WebView integration.
+//!
+//! Instead of intercepting every WebView request in Kotlin, the app can start
+//! a [`LoopbackServer`] and rewrite each remote `<img src>` to
+//! [`LoopbackServer::image_url`], e.g.
+//! `http://127.0.0.1:41234/image?token=…&url=https%3A%2F%2Fexample.com%2Fa.png`.
+//! The WebView then loads images from the server, which fetches them through
+//! the usual pipeline: cache, tunnel, content checks, SVG sanitising and
+//! tracking-pixel screening.
+//!
+//! The server listens on 127.0.0.1 only, on a port chosen by the system, and
+//! answers only requests carrying its per-session token, so other apps on the
+//! device cannot use it as an open proxy. It speaks just enough HTTP/1.1 for
+//! `GET` requests, one per connection, each served on its own thread, up to
+//! [`MAX_CONNECTIONS`] at a time.
+//!
+//! Responses carry a fixed set of headers written here, never the upstream
+//! server's, so no `Set-Cookie` or other
+//! [state-bearing header](crate::tunnel::http1::STATE_BEARING_HEADERS) can
+//! reach the WebView's cookie jar or security-policy stores.
+
+use crate::client::{default_client, ProxyClient};
+use crate::error::{ErrorCategory, ProxyError};
+use crate::fetch_image;
+use crate::types::{ImageResponse, RequestPriority};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+/// Most connections served at once; further ones get `503`.
+pub const MAX_CONNECTIONS: usize = 16;
+
+/// Longest request head accepted, in bytes.
+const MAX_HEAD_BYTES: usize = 8 * 1024;
+
+/// How long a connection may take to send its request or accept the answer.
+const IO_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A running loopback server. Stops when [`Self::stop`] is called or the
+/// last reference is dropped.
+#[derive(uniffi::Object)]
+pub struct LoopbackServer {
+    addr: SocketAddr,
+    token: String,
+    stopping: Arc<AtomicBool>,
+    acceptor: Mutex<Option<JoinHandle<()>>>,
+}
+
+#[uniffi::export]
+impl LoopbackServer {
+    /// The port the server listens on, on 127.0.0.1.
+    pub fn port(&self) -> u16 {
+        self.addr.port()
+    }
+
+    /// The URL under which the server serves the image at `url`.
+    pub fn image_url(&self, url: String) -> String {
+        let query: String = url::form_urlencoded::Serializer::new(String::new())
+            .append_pair("token", &self.token)
+            .append_pair("url", &url)
+            .finish();
+        format!("http://{}/image?{query}", self.addr)
+    }
+
+    /// Stop accepting connections. Requests already being served finish.
+    pub fn stop(&self) {
+        if self.stopping.swap(true, Ordering::AcqRel) {
+            return;
+        }
+        // Wake the acceptor blocked in `accept`; it sees the flag and exits.
+        let _ = TcpStream::connect_timeout(&self.addr, IO_TIMEOUT);
+        let acceptor = self
+            .acceptor
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .take();
+        if let Some(acceptor) = acceptor {
+            let _ = acceptor.join();
+        }
+    }
+}
+
+impl Drop for LoopbackServer {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Accept connections until stopped, serving each on its own thread.
+fn accept_loop(
+    listener: &TcpListener,
+    client: &Arc<ProxyClient>,
+    token: &Arc<str>,
+    stopping: &AtomicBool,
+) {
+    let active = Arc::new(AtomicUsize::new(0));
+    for stream in listener.incoming() {
+        if stopping.load(Ordering::Acquire) {
+            return;
+        }
+        let Ok(mut stream) = stream else {
+            continue;
+        };
+        let _ = stream.set_read_timeout(Some(IO_TIMEOUT));
+        let _ = stream.set_write_timeout(Some(IO_TIMEOUT));
+        if active.fetch_add(1, Ordering::AcqRel) >= MAX_CONNECTIONS {
+            active.fetch_sub(1, Ordering::AcqRel);
+            let _ = respond_error(&mut stream, 503, "Too many connections");
+            continue;
+        }
+        let (client, token, active) = (Arc::clone(client), Arc::clone(token), Arc::clone(&active));
+        let spawned = std::thread::Builder::new()
+            .name("letterbox-loopback-conn".to_string())
+            .spawn(move || {
+                if let Err(e) = serve(&mut stream, &client, &token) {
+                    log::debug!("Loopback connection failed: {e}");
+                }
+                active.fetch_sub(1, Ordering::AcqRel);
+            });
+        if let Err(e) = spawned {
+            log::warn!("Failed to spawn loopback connection thread: {e}");
+        }
+    }
+}
+
+/// Answer the single request on `stream`.
+fn serve(stream: &mut TcpStream, client: &ProxyClient, token: &str) -> std::io::Result<()> {
+    let head = match read_head(stream) {
+        Ok(head) => head,
+        Err(e) if e.kind() == std::io::ErrorKind::InvalidData => {
+            return respond_error(stream, 400, "Malformed request");
+        }
+        Err(e) => return Err(e),
+    };
+    let mut parts = head.split(' ');
+    let (method, target) = (parts.next().unwrap_or(""), parts.next().unwrap_or(""));
+    if method != "GET" {
+        return respond_error(stream, 405, "Only GET is supported");
+    }
+    let Ok(target) = url::Url::parse(&format!("http://127.0.0.1{target}")) else {
+        return respond_error(stream, 400, "Malformed request target");
+    };
+    if target.path() != "/image" {
+        return respond_error(stream, 404, "Not found");
+    }
+    let param = |name: &str| {
+        target
+            .query_pairs()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.into_owned())
+    };
+    if !param("token").is_some_and(|given| tokens_match(&given, token)) {
+        return respond_error(stream, 403, "Missing or wrong token");
+    }
+    let Some(url) = param("url") else {
+        return respond_error(stream, 400, "Missing url parameter");
+    };
+    match fetch_image(client, &url, None, RequestPriority::Visible) {
+        Ok(image) => respond_image(stream, &image),
+        Err(e) => respond_error(stream, error_status(&e), &e.to_string()),
+    }
+}
+
+/// Read the request line, skipping the headers that follow it.
+fn read_head(stream: &mut TcpStream) -> std::io::Result<String> {
+    let mut reader = BufReader::new(stream).take(MAX_HEAD_BYTES as u64);
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            // Cut off before the blank line: too long or truncated.
+            return Err(std::io::ErrorKind::InvalidData.into());
+        }
+        if line.trim_end().is_empty() {
+            return Ok(request_line.trim_end().to_string());
+        }
+    }
+}
+
+/// Compare tokens without revealing how much of a guess matched.
+fn tokens_match(given: &str, expected: &str) -> bool {
+    given.len() == expected.len()
+        && given
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// The HTTP status reporting a failed fetch.
+fn error_status(error: &ProxyError) -> u16 {
+    match (error, error.category()) {
+        (ProxyError::Timeout { .. }, _) => 504,
+        (ProxyError::Throttled, _) => 429,
+        (_, ErrorCategory::Input) => 400,
+        (_, ErrorCategory::Content) => 422,
+        (_, ErrorCategory::Network) => 502,
+        (_, ErrorCategory::Configuration | ErrorCategory::State) => 503,
+    }
+}
+
+fn respond_image(stream: &mut TcpStream, image: &ImageResponse) -> std::io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 200 OK\r\nContent-Type: {}\r\nContent-Length: {}\r\n\
+         Cache-Control: private, max-age=86400\r\nX-Content-Type-Options: nosniff\r\n\
+         X-Trace-Id: {}\r\nConnection: close\r\n\r\n",
+        image.mime_type,
+        image.data.len(),
+        image.trace_id,
+    )?;
+    stream.write_all(&image.data)?;
+    stream.flush()
+}
+
+fn respond_error(stream: &mut TcpStream, status: u16, message: &str) -> std::io::Result<()> {
+    let reason = match status {
+        400 => "Bad Request",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        422 => "Unprocessable Content",
+        429 => "Too Many Requests",
+        502 => "Bad Gateway",
+        504 => "Gateway Timeout",
+        _ => "Service Unavailable",
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {status} {reason}\r\nContent-Type: text/plain; charset=utf-8\r\n\
+         Content-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n{message}",
+        message.len(),
+    )?;
+    stream.flush()
+}
+
+#[uniffi::export]
+impl ProxyClient {
+    /// Start a loopback HTTP server serving images through this proxy; see
+    /// [`LoopbackServer::image_url`]. Each call starts a new server with its
+    /// own port and token.
+    pub fn start_loopback_server(self: Arc<Self>) -> Result<Arc<LoopbackServer>, ProxyError> {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?;
+        let addr = listener.local_addr()?;
+        let token = format!("{:032x}", rand::random::<u128>());
+        let stopping = Arc::new(AtomicBool::new(false));
+
+        let (shared_token, acceptor_stopping) = (Arc::from(token.as_str()), Arc::clone(&stopping));
+        let acceptor = std::thread::Builder::new()
+            .name("letterbox-loopback".to_string())
+            .spawn(move || accept_loop(&listener, &self, &shared_token, &acceptor_stopping))
+            .map_err(|e| ProxyError::InitializationFailed {
+                details: format!("Failed to spawn loopback server thread: {e}"),
+            })?;
+        Ok(Arc::new(LoopbackServer {
+            addr,
+            token,
+            stopping,
+            acceptor: Mutex::new(Some(acceptor)),
+        }))
+    }
+}
+
+/// [`ProxyClient::start_loopback_server`] on the default proxy.
+#[uniffi::export]
+pub fn proxy_start_loopback_server() -> Result<Arc<LoopbackServer>, ProxyError> {
+    Arc::clone(default_client()).start_loopback_server()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::TimeoutPhase;
+    use std::io::Read;
+
+    const PIXEL: &str = "data:image/png;base64,iVBORw0KGgo=";
+
+    fn get(server: &LoopbackServer, request: &str) -> String {
+        let mut stream = TcpStream::connect(("127.0.0.1", server.port())).unwrap();
+        stream.write_all(request.as_bytes()).unwrap();
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response).unwrap();
+        String::from_utf8_lossy(&response).into_owned()
+    }
+
+    #[test]
+    fn serves_images_only_with_the_token() {
+        let dir = tempfile::tempdir().unwrap();
+        let client = ProxyClient::new(dir.path().to_string_lossy().into_owned(), 0).unwrap();
+        let server = Arc::clone(&client).start_loopback_server().unwrap();
+
+        let target = server.image_url(PIXEL.to_string());
+        let path = target.split_once(&server.addr.to_string()).unwrap().1;
+        let ok = get(&server, &format!("GET {path} HTTP/1.1\r\nHost: x\r\n\r\n"));
+        assert!(ok.starts_with("HTTP/1.1 200 OK\r\n"), "{ok}");
+        assert!(ok.contains("Content-Type: image/png\r\n"));
+        assert!(ok.ends_with("\u{fffd}PNG\r\n\u{1a}\n"));
+
+        let forged = path.replace("token=", "token=0");
+        let refused = get(&server, &format!("GET {forged} HTTP/1.1\r\n\r\n"));
+        assert!(refused.starts_with("HTTP/1.1 403 "), "{refused}");
+        let posted = get(&server, &format!("POST {path} HTTP/1.1\r\n\r\n"));
+        assert!(posted.starts_with("HTTP/1.1 405 "), "{posted}");
+
+        server.stop();
+        assert!(TcpStream::connect(("127.0.0.1", server.port())).is_err());
+    }
+
+    #[test]
+    fn fetch_failures_map_to_gateway_statuses() {
+        assert_eq!(
+            error_status(&ProxyError::Timeout {
+                phase: TimeoutPhase::Connect,
+                seconds: 30,
+            }),
+            504
+        );
+        let bad_url = ProxyError::InvalidUrl {
+            url: "x".to_string(),
+            details: "relative URL".to_string(),
+        };
+        assert_eq!(error_status(&bad_url), 400);
+        assert_eq!(error_status(&ProxyError::Paused), 503);
+        assert!(tokens_match("abc", "abc") && !tokens_match("abc", "abd"));
+    }
+}
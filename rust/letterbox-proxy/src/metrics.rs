@@ -0,0 +1,303 @@
+//! Counters and latency histograms for a debug or statistics screen.
+//!
+//! Each client keeps its own [`Metrics`], updated lock-free as requests run;
+//! [`ProxyClient::metrics`] returns a [`ProxyMetrics`] snapshot. The counters
+//! cover the client's lifetime, across shutdown and re-initialisation.
+//!
+//! With the tunnel, every byte counted here crossed it; the only traffic
+//! outside it is WARP registration, counted in
+//! [`ProxyMetrics::provisionings`]. Builds without the `tunnel` feature fetch
+//! directly over
+//! [`DirectNetwork`](crate::tunnel::direct::DirectNetwork) and leave the
+//! tunnel, handshake and provisioning counters at zero; their requests are
+//! counted in [`ProxyMetrics::direct_requests`] instead. Requests through an
+//! upstream proxy
+//! ([`UpstreamNetwork`](crate::tunnel::upstream::UpstreamNetwork)) bypass the
+//! tunnel too, but are not counted as direct, since the server sees the
+//! proxy's address rather than the device's.
+
+use crate::client::{default_client, ProxyClient};
+use crate::error::ProxyError;
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::time::Duration;
+
+/// Upper bounds of the latency buckets in milliseconds.
+const BUCKET_BOUNDS_MS: [u32; 11] = [5, 10, 25, 50, 100, 250, 500, 1_000, 2_500, 5_000, 10_000];
+
+/// Latency distribution of one kind of operation.
+#[derive(Clone, Debug, PartialEq, uniffi::Record)]
+pub struct LatencyHistogram {
+    /// Upper bound of each bucket in milliseconds, ascending.
+    pub bucket_bounds_ms: Vec<u32>,
+    /// Samples per bucket: one entry per bound, then one for everything
+    /// slower than the last bound.
+    pub counts: Vec<u64>,
+    /// Number of samples.
+    pub total_count: u64,
+    /// Sum of all samples in milliseconds, for the mean.
+    pub sum_ms: u64,
+}
+
+/// A snapshot of a client's counters.
+#[derive(Clone, Debug, PartialEq, uniffi::Record)]
+pub struct ProxyMetrics {
+    /// Image fetches that returned an image, from the network or the cache.
+    pub fetches_succeeded: u64,
+    /// Image fetches that failed, other than by cancellation.
+    pub fetches_failed: u64,
+    /// Image fetches cancelled before they completed.
+    pub fetches_cancelled: u64,
+    /// Image fetches answered by the memory or disk cache.
+    pub cache_hits: u64,
+    /// Image fetches that had to go to the network.
+    pub cache_misses: u64,
+    /// `cache_hits` over all cache lookups; 0 before the first.
+    pub cache_hit_ratio: f64,
+    /// Plaintext bytes sent into the tunnel, including TLS and DNS overhead.
+    pub tunnel_bytes_sent: u64,
+    /// Plaintext bytes received from the tunnel.
+    pub tunnel_bytes_received: u64,
+    /// Completed WireGuard handshakes, including re-handshakes after a
+    /// session lapsed.
+    pub handshakes: u64,
+    /// WireGuard handshakes that did not complete, e.g. because WARP rotated
+    /// its peer key.
+    pub handshake_failures: u64,
+    /// Attempts to register a WARP identity with Cloudflare, which go
+    /// outside the tunnel.
+    pub provisionings: u64,
+    /// Requests sent straight to the server, exposing the device's IP
+    /// address; always 0 with the tunnel.
+    pub direct_requests: u64,
+    /// Time from dispatch to the last byte of image fetches that went to the
+    /// network, including time spent queued.
+    pub fetch_latency: LatencyHistogram,
+    /// Time to resolve a host name over DNS-over-HTTPS.
+    pub dns_latency: LatencyHistogram,
+}
+
+/// Lock-free latency buckets.
+#[derive(Debug, Default)]
+struct Histogram {
+    counts: [AtomicU64; BUCKET_BOUNDS_MS.len() + 1],
+    sum_ms: AtomicU64,
+}
+
+impl Histogram {
+    fn record(&self, elapsed: Duration) {
+        let ms = u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX);
+        let bucket = BUCKET_BOUNDS_MS
+            .iter()
+            .position(|&bound| ms <= u64::from(bound))
+            .unwrap_or(BUCKET_BOUNDS_MS.len());
+        self.counts[bucket].fetch_add(1, Ordering::Relaxed);
+        self.sum_ms.fetch_add(ms, Ordering::Relaxed);
+    }
+
+    fn snapshot(&self) -> LatencyHistogram {
+        let counts: Vec<u64> = self
+            .counts
+            .iter()
+            .map(|count| count.load(Ordering::Relaxed))
+            .collect();
+        LatencyHistogram {
+            bucket_bounds_ms: BUCKET_BOUNDS_MS.to_vec(),
+            total_count: counts.iter().sum(),
+            counts,
+            sum_ms: self.sum_ms.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// A client's live counters.
+#[derive(Debug, Default)]
+pub struct Metrics {
+    fetches_succeeded: AtomicU64,
+    fetches_failed: AtomicU64,
+    fetches_cancelled: AtomicU64,
+    cache_hits: AtomicU64,
+    cache_misses: AtomicU64,
+    tunnel_bytes_sent: AtomicU64,
+    tunnel_bytes_received: AtomicU64,
+    handshakes: AtomicU64,
+    handshake_failures: AtomicU64,
+    /// Handshakes failed since the last one completed.
+    handshake_failure_streak: AtomicU32,
+    provisionings: AtomicU64,
+    direct_requests: AtomicU64,
+    fetch_latency: Histogram,
+    dns_latency: Histogram,
+}
+
+fn bump(counter: &AtomicU64) {
+    counter.fetch_add(1, Ordering::Relaxed);
+}
+
+impl Metrics {
+    /// Count the outcome of an image fetch.
+    pub(crate) fn record_fetch<T>(&self, result: &Result<T, ProxyError>) {
+        bump(match result {
+            Ok(_) => &self.fetches_succeeded,
+            Err(ProxyError::Cancelled) => &self.fetches_cancelled,
+            Err(_) => &self.fetches_failed,
+        });
+    }
+
+    /// Count an image fetch answered by the cache (`hit`) or not.
+    pub(crate) fn record_cache_lookup(&self, hit: bool) {
+        bump(if hit {
+            &self.cache_hits
+        } else {
+            &self.cache_misses
+        });
+    }
+
+    pub(crate) fn record_fetch_latency(&self, elapsed: Duration) {
+        self.fetch_latency.record(elapsed);
+    }
+
+    pub(crate) fn record_dns_latency(&self, elapsed: Duration) {
+        self.dns_latency.record(elapsed);
+    }
+
+    /// Count tunnel traffic.
+    pub(crate) fn record_transfer(&self, sent: u64, received: u64) {
+        self.tunnel_bytes_sent.fetch_add(sent, Ordering::Relaxed);
+        self.tunnel_bytes_received
+            .fetch_add(received, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_handshake(&self) {
+        bump(&self.handshakes);
+        self.handshake_failure_streak.store(0, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_handshake_failure(&self) {
+        bump(&self.handshake_failures);
+        self.handshake_failure_streak
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Handshakes failed in a row since the last completed one or
+    /// [`Self::reset_handshake_failure_streak`].
+    #[cfg(feature = "provisioning")]
+    pub(crate) fn handshake_failure_streak(&self) -> u32 {
+        self.handshake_failure_streak.load(Ordering::Relaxed)
+    }
+
+    #[cfg(feature = "provisioning")]
+    pub(crate) fn reset_handshake_failure_streak(&self) {
+        self.handshake_failure_streak.store(0, Ordering::Relaxed);
+    }
+
+    #[cfg(feature = "tunnel")]
+    pub(crate) fn record_provisioning(&self) {
+        bump(&self.provisionings);
+    }
+
+    /// Count a request that bypassed the tunnel.
+    pub(crate) fn record_direct_request(&self) {
+        bump(&self.direct_requests);
+    }
+
+    /// The current values of every counter.
+    pub(crate) fn snapshot(&self) -> ProxyMetrics {
+        let load = |counter: &AtomicU64| counter.load(Ordering::Relaxed);
+        let (hits, misses) = (load(&self.cache_hits), load(&self.cache_misses));
+        ProxyMetrics {
+            fetches_succeeded: load(&self.fetches_succeeded),
+            fetches_failed: load(&self.fetches_failed),
+            fetches_cancelled: load(&self.fetches_cancelled),
+            cache_hits: hits,
+            cache_misses: misses,
+            cache_hit_ratio: match hits + misses {
+                0 => 0.0,
+                lookups => hits as f64 / lookups as f64,
+            },
+            tunnel_bytes_sent: load(&self.tunnel_bytes_sent),
+            tunnel_bytes_received: load(&self.tunnel_bytes_received),
+            handshakes: load(&self.handshakes),
+            handshake_failures: load(&self.handshake_failures),
+            provisionings: load(&self.provisionings),
+            direct_requests: load(&self.direct_requests),
+            fetch_latency: self.fetch_latency.snapshot(),
+            dns_latency: self.dns_latency.snapshot(),
+        }
+    }
+}
+
+#[uniffi::export]
+impl ProxyClient {
+    /// Snapshot of this client's fetch, cache, tunnel and DNS counters.
+    pub fn metrics(&self) -> ProxyMetrics {
+        self.metrics.snapshot()
+    }
+}
+
+/// [`ProxyClient::metrics`] of the default proxy.
+#[uniffi::export]
+pub fn proxy_metrics() -> ProxyMetrics {
+    default_client().metrics()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::TimeoutPhase;
+
+    #[test]
+    fn latencies_land_in_the_first_bucket_that_fits() {
+        let histogram = Histogram::default();
+        for ms in [0, 5, 6, 10_000, 60_000] {
+            histogram.record(Duration::from_millis(ms));
+        }
+        let snapshot = histogram.snapshot();
+        assert_eq!(snapshot.counts.len(), snapshot.bucket_bounds_ms.len() + 1);
+        assert_eq!(snapshot.counts[0], 2);
+        assert_eq!(snapshot.counts[1], 1);
+        assert_eq!(snapshot.counts[10], 1);
+        assert_eq!(snapshot.counts[11], 1);
+        assert_eq!(snapshot.total_count, 5);
+        assert_eq!(snapshot.sum_ms, 70_011);
+    }
+
+    #[test]
+    fn outcomes_and_hit_ratio() {
+        let metrics = Metrics::default();
+        assert_eq!(metrics.snapshot().cache_hit_ratio, 0.0);
+        metrics.record_fetch(&Ok(()));
+        metrics.record_fetch::<()>(&Err(ProxyError::Cancelled));
+        metrics.record_fetch::<()>(&Err(ProxyError::Timeout {
+            phase: TimeoutPhase::Connect,
+            seconds: 30,
+        }));
+        for hit in [true, true, true, false] {
+            metrics.record_cache_lookup(hit);
+        }
+        let snapshot = metrics.snapshot();
+        assert_eq!(
+            (
+                snapshot.fetches_succeeded,
+                snapshot.fetches_failed,
+                snapshot.fetches_cancelled
+            ),
+            (1, 1, 1)
+        );
+        assert_eq!(snapshot.cache_hit_ratio, 0.75);
+    }
+
+    #[test]
+    #[cfg(feature = "provisioning")]
+    fn a_completed_handshake_ends_the_failure_streak() {
+        let metrics = Metrics::default();
+        metrics.record_handshake_failure();
+        metrics.record_handshake_failure();
+        assert_eq!(metrics.handshake_failure_streak(), 2);
+        metrics.record_handshake();
+        assert_eq!(metrics.handshake_failure_streak(), 0);
+        metrics.record_handshake_failure();
+        let snapshot = metrics.snapshot();
+        assert_eq!((snapshot.handshakes, snapshot.handshake_failures), (1, 3));
+        assert_eq!(metrics.handshake_failure_streak(), 1);
+    }
+}
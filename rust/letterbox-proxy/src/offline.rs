@@ -0,0 +1,350 @@
+//! Persistent queue of images to fetch once the network is back.
+//!
+//! For "download remote content for offline reading", the host hands
+//! [`ProxyClient::queue_for_offline`] the images of a message. They are written
+//! to `<storage>/offline_queue.json` and fetched one by one on a background
+//! thread at [`RequestPriority::Background`]. While the network is unreachable
+//! (or the proxy is paused) the thread backs off and retries, and the queue
+//! survives restarts: it is reloaded when the client is initialised. The host
+//! can cut a back-off short with [`ProxyClient::retry_offline_queue`], e.g.
+//! when Android reports connectivity again; resuming a paused proxy does so
+//! too.
+//!
+//! Each URL is reported to the [`OfflineFetchListener`] once it is done —
+//! fetched into the cache, or failed for a reason other than connectivity.
+//! Enable the disk cache so the fetched images outlive the process.
+//!
+//! Unlike the disk cache index, the queue file names the URLs in plain text;
+//! it is deleted as soon as the queue is empty.
+
+use crate::cache::image_key;
+use crate::client::{default_client, ProxyClient};
+use crate::error::ProxyError;
+use crate::types::RequestPriority;
+use crate::{fetch_image, validate_image_url};
+use std::collections::VecDeque;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, Weak};
+use std::time::Duration;
+
+/// Queue file name inside the storage path.
+const QUEUE_FILE: &str = "offline_queue.json";
+
+/// First wait after a fetch failed for lack of connectivity.
+const INITIAL_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Longest wait between two attempts.
+const MAX_BACKOFF: Duration = Duration::from_secs(15 * 60);
+
+/// Receives the outcome of queued offline fetches.
+#[uniffi::export(with_foreign)]
+pub trait OfflineFetchListener: Send + Sync {
+    /// `url` left the queue: fetched into the cache when `error` is `None`.
+    /// Called on the queue's background thread.
+    fn on_fetched(&self, url: String, error: Option<String>);
+}
+
+/// The queued URLs and the state of the thread working through them.
+#[derive(Default)]
+pub(crate) struct OfflineQueue {
+    inner: Mutex<Pending>,
+    /// Signalled to cut a back-off short.
+    wake: Condvar,
+}
+
+#[derive(Default)]
+struct Pending {
+    urls: VecDeque<String>,
+    /// Where the queue is persisted; `None` until the client is initialised.
+    file: Option<PathBuf>,
+    /// Whether a worker thread is alive.
+    running: bool,
+    /// Set by [`OfflineQueue::wake`], cleared when the worker notices.
+    woken: bool,
+    listener: Option<Arc<dyn OfflineFetchListener>>,
+}
+
+impl Pending {
+    /// Write the queue to its file, or remove the file once empty.
+    fn persist(&self) -> Result<(), ProxyError> {
+        let Some(file) = &self.file else {
+            return Ok(());
+        };
+        if self.urls.is_empty() {
+            return match fs::remove_file(file) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+                _ => Ok(()),
+            };
+        }
+        fs::write(file, serde_json::to_vec(&self.urls)?)?;
+        Ok(())
+    }
+}
+
+impl OfflineQueue {
+    fn lock(&self) -> MutexGuard<'_, Pending> {
+        self.inner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Replace the queue with the one persisted under `storage_path`.
+    pub(crate) fn load(&self, storage_path: &Path) {
+        let file = storage_path.join(QUEUE_FILE);
+        let urls = match fs::read(&file) {
+            Ok(bytes) => serde_json::from_slice(&bytes).unwrap_or_else(|e| {
+                log::warn!("Discarding unreadable offline queue: {e}");
+                VecDeque::new()
+            }),
+            Err(_) => VecDeque::new(),
+        };
+        let mut pending = self.lock();
+        pending.urls = urls;
+        pending.file = Some(file);
+    }
+
+    /// Append the `urls` not queued yet and persist. Returns how many were
+    /// added.
+    fn add(&self, urls: Vec<String>) -> Result<u32, ProxyError> {
+        let mut pending = self.lock();
+        let before = pending.urls.len();
+        for url in urls {
+            if !pending.urls.contains(&url) {
+                pending.urls.push_back(url);
+            }
+        }
+        let added = pending.urls.len() - before;
+        if added > 0 {
+            pending.persist()?;
+        }
+        Ok(added as u32)
+    }
+
+    /// Drop `url` from the front of the queue once it is done.
+    fn complete(&self, url: &str) -> Option<Arc<dyn OfflineFetchListener>> {
+        let mut pending = self.lock();
+        if pending.urls.front().map(String::as_str) == Some(url) {
+            pending.urls.pop_front();
+        }
+        if let Err(e) = pending.persist() {
+            log::warn!("Failed to persist offline queue: {e}");
+        }
+        pending.listener.clone()
+    }
+
+    /// The next URL to fetch, or `None` after marking the worker stopped.
+    fn next(&self) -> Option<String> {
+        let mut pending = self.lock();
+        let next = pending.urls.front().cloned();
+        pending.running = next.is_some();
+        next
+    }
+
+    /// Sleep for `timeout` or until woken, whichever comes first.
+    fn back_off(&self, timeout: Duration) {
+        let pending = self.lock();
+        let (mut pending, _) = self
+            .wake
+            .wait_timeout_while(pending, timeout, |pending| !pending.woken)
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        pending.woken = false;
+    }
+
+    /// Retry the head of the queue now instead of after the back-off.
+    pub(crate) fn wake(&self) {
+        self.lock().woken = true;
+        self.wake.notify_all();
+    }
+}
+
+/// Whether `error` means "try again once the network is back".
+fn waits_for_network(error: &ProxyError) -> bool {
+    matches!(
+        error,
+        ProxyError::Paused
+            | ProxyError::Disabled
+            | ProxyError::ProvisioningFailed { .. }
+            | ProxyError::TunnelError { .. }
+            | ProxyError::Timeout { .. }
+            | ProxyError::DnsError { .. }
+            | ProxyError::NetworkUnavailable { .. }
+            | ProxyError::HttpError { status_code: 0, .. }
+    )
+}
+
+impl ProxyClient {
+    /// Start working through the offline queue, unless it is empty or a
+    /// worker is already running.
+    pub(crate) fn start_offline_queue(self: &Arc<Self>) -> Result<(), ProxyError> {
+        let mut pending = self.offline.lock();
+        if pending.running || pending.urls.is_empty() {
+            return Ok(());
+        }
+        // The worker must not keep a dropped client alive through a back-off.
+        let (client, queue) = (Arc::downgrade(self), Arc::clone(&self.offline));
+        std::thread::Builder::new()
+            .name("letterbox-offline".to_string())
+            .spawn(move || drain_offline_queue(&client, &queue))
+            .map_err(|e| ProxyError::InitializationFailed {
+                details: format!("Failed to spawn offline queue thread: {e}"),
+            })?;
+        pending.running = true;
+        Ok(())
+    }
+}
+
+/// Fetch queued URLs until the queue is empty or the client shuts down or is
+/// dropped.
+fn drain_offline_queue(client: &Weak<ProxyClient>, queue: &OfflineQueue) {
+    let mut backoff = INITIAL_BACKOFF;
+    while let Some(url) = queue.next() {
+        let fetched = match client.upgrade() {
+            Some(client) => fetch_image(&client, &url, None, RequestPriority::Background),
+            None => Err(ProxyError::NotInitialized),
+        };
+        let error = match fetched {
+            Ok(_) => None,
+            Err(ProxyError::NotInitialized) => {
+                // Shut down; the queue stays persisted for the next start.
+                queue.lock().running = false;
+                return;
+            }
+            Err(e) if waits_for_network(&e) => {
+                log::debug!("Offline fetch waiting for the network: {e}");
+                queue.back_off(backoff);
+                backoff = (backoff * 2).min(MAX_BACKOFF);
+                continue;
+            }
+            Err(e) => Some(e.to_string()),
+        };
+        backoff = INITIAL_BACKOFF;
+        if let Some(listener) = queue.complete(&url) {
+            listener.on_fetched(url, error);
+        }
+    }
+}
+
+#[uniffi::export]
+impl ProxyClient {
+    /// Fetch `urls` into the cache in the background, retrying until the
+    /// network is reachable, even across restarts.
+    ///
+    /// Returns how many URLs were queued; invalid, already cached and already
+    /// queued ones are skipped.
+    pub fn queue_for_offline(self: Arc<Self>, urls: Vec<String>) -> Result<u32, ProxyError> {
+        let fresh: Vec<String> = {
+            let mut guard = self.lock_state();
+            let state = guard.as_mut().ok_or(ProxyError::NotInitialized)?;
+            urls.into_iter()
+                .filter(|url| {
+                    validate_image_url(url).is_ok()
+                        && !state
+                            .cache
+                            .contains(&image_key(url, &state.config.image_accept))
+                })
+                .collect()
+        };
+        let queued = self.offline.add(fresh)?;
+        self.start_offline_queue()?;
+        Ok(queued)
+    }
+
+    /// URLs still waiting to be fetched, in order.
+    pub fn offline_queue(&self) -> Vec<String> {
+        self.offline.lock().urls.iter().cloned().collect()
+    }
+
+    /// Forget every queued URL. A fetch already under way still completes.
+    pub fn clear_offline_queue(&self) -> Result<(), ProxyError> {
+        let mut pending = self.offline.lock();
+        pending.urls.clear();
+        pending.persist()
+    }
+
+    /// Retry the queue now rather than after its back-off, e.g. when the
+    /// device regains connectivity.
+    pub fn retry_offline_queue(&self) {
+        self.offline.wake();
+    }
+
+    /// Register (or, with `None`, remove) the listener told about each URL
+    /// that leaves the queue.
+    pub fn set_offline_listener(&self, listener: Option<Arc<dyn OfflineFetchListener>>) {
+        self.offline.lock().listener = listener;
+    }
+}
+
+/// [`ProxyClient::queue_for_offline`] on the default proxy.
+#[uniffi::export]
+pub fn proxy_queue_for_offline(urls: Vec<String>) -> Result<u32, ProxyError> {
+    Arc::clone(default_client()).queue_for_offline(urls)
+}
+
+/// [`ProxyClient::offline_queue`] of the default proxy.
+#[uniffi::export]
+pub fn proxy_offline_queue() -> Vec<String> {
+    default_client().offline_queue()
+}
+
+/// [`ProxyClient::clear_offline_queue`] on the default proxy.
+#[uniffi::export]
+pub fn proxy_clear_offline_queue() -> Result<(), ProxyError> {
+    default_client().clear_offline_queue()
+}
+
+/// [`ProxyClient::retry_offline_queue`] on the default proxy.
+#[uniffi::export]
+pub fn proxy_retry_offline_queue() {
+    default_client().retry_offline_queue()
+}
+
+/// [`ProxyClient::set_offline_listener`] on the default proxy.
+#[uniffi::export]
+pub fn proxy_set_offline_listener(listener: Option<Arc<dyn OfflineFetchListener>>) {
+    default_client().set_offline_listener(listener)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::TimeoutPhase;
+
+    #[test]
+    fn queue_survives_reload_and_file_goes_when_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        let queue = OfflineQueue::default();
+        queue.load(dir.path());
+        let urls = |list: &[&str]| list.iter().map(|u| u.to_string()).collect::<Vec<_>>();
+        assert_eq!(queue.add(urls(&["https://a/1", "https://a/2"])).unwrap(), 2);
+        assert_eq!(queue.add(urls(&["https://a/2", "https://a/3"])).unwrap(), 1);
+
+        let reloaded = OfflineQueue::default();
+        reloaded.load(dir.path());
+        assert_eq!(reloaded.next().as_deref(), Some("https://a/1"));
+        reloaded.complete("https://a/1");
+        assert_eq!(reloaded.lock().urls, urls(&["https://a/2", "https://a/3"]));
+
+        reloaded.lock().urls.clear();
+        reloaded.complete("https://a/2");
+        assert!(!dir.path().join(QUEUE_FILE).exists());
+        assert_eq!(reloaded.next(), None);
+    }
+
+    #[test]
+    fn only_connectivity_failures_are_retried() {
+        assert!(waits_for_network(&ProxyError::Paused));
+        assert!(waits_for_network(&ProxyError::Disabled));
+        assert!(waits_for_network(&ProxyError::Timeout {
+            phase: TimeoutPhase::Connect,
+            seconds: 30,
+        }));
+        assert!(!waits_for_network(&ProxyError::HttpError {
+            status_code: 404,
+            details: "HTTP 404".to_string(),
+        }));
+        assert!(!waits_for_network(&ProxyError::InvalidContentType {
+            content_type: "text/html".to_string(),
+        }));
+    }
+}
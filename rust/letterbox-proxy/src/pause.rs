@@ -0,0 +1,88 @@
+//! Pausing network activity.
+//!
+//! The host pauses the proxy when the app goes to the background, or when the
+//! user does not want images loaded on a metered network, and resumes it
+//! later. While paused:
+//!
+//! - cache hits and `data:` URIs are still served;
+//! - requests for the tunnel are held in its queue, up to
+//!   [`PAUSED_QUEUE_LIMIT`](crate::tunnel::manager::PAUSED_QUEUE_LIMIT), and
+//!   run in priority order on resume — async
+//!   callers simply wait, blocking ones block;
+//! - further requests, and any request while the tunnel is not up yet (which
+//!   would mean provisioning and a handshake), fail with
+//!   [`ProxyError::Paused`].
+//!
+//! A request already in flight when the proxy is paused runs to completion.
+
+use crate::client::{default_client, ProxyClient};
+use crate::error::ProxyError;
+
+#[uniffi::export]
+impl ProxyClient {
+    /// Hold new network requests until [`Self::resume`].
+    pub fn pause(&self) -> Result<(), ProxyError> {
+        let mut guard = self.lock_state();
+        let state = guard.as_mut().ok_or(ProxyError::NotInitialized)?;
+        state.paused = true;
+        if let Some(manager) = &state.manager {
+            manager.pause();
+        }
+        Ok(())
+    }
+
+    /// Run the requests held while paused and accept new ones again.
+    pub fn resume(&self) -> Result<(), ProxyError> {
+        let mut guard = self.lock_state();
+        let state = guard.as_mut().ok_or(ProxyError::NotInitialized)?;
+        state.paused = false;
+        if let Some(manager) = &state.manager {
+            manager.resume();
+        }
+        self.offline.wake();
+        Ok(())
+    }
+
+    /// Whether network activity is paused.
+    pub fn is_paused(&self) -> Result<bool, ProxyError> {
+        let guard = self.lock_state();
+        Ok(guard.as_ref().ok_or(ProxyError::NotInitialized)?.paused)
+    }
+}
+
+/// [`ProxyClient::pause`] on the default proxy.
+#[uniffi::export]
+pub fn proxy_pause() -> Result<(), ProxyError> {
+    default_client().pause()
+}
+
+/// [`ProxyClient::resume`] on the default proxy.
+#[uniffi::export]
+pub fn proxy_resume() -> Result<(), ProxyError> {
+    default_client().resume()
+}
+
+/// [`ProxyClient::is_paused`] on the default proxy.
+#[uniffi::export]
+pub fn proxy_is_paused() -> Result<bool, ProxyError> {
+    default_client().is_paused()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paused_client_refuses_to_start_the_tunnel() {
+        let dir = tempfile::tempdir().unwrap();
+        let client = ProxyClient::new(dir.path().to_string_lossy().into_owned(), 10).unwrap();
+        client.pause().unwrap();
+        assert!(client.is_paused().unwrap());
+        assert!(matches!(client.acquire_manager(), Err(ProxyError::Paused)));
+
+        client.resume().unwrap();
+        assert!(!client.is_paused().unwrap());
+        client.shutdown().unwrap();
+        assert!(matches!(client.pause(), Err(ProxyError::NotInitialized)));
+    }
+}
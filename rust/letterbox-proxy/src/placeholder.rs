@@ -0,0 +1,75 @@
+//! BlurHash placeholders.
+//!
+//! Every decodable still image gets a [BlurHash](https://blurha.sh) — a string
+//! of about 30 characters describing a blurred version of it. The app can store
+//! it next to the message and paint an instant preview on later opens, without
+//! keeping the image itself cached.
+//!
+//! The image is decoded once and shrunk to a tiny thumbnail before hashing, so
+//! the cost is dominated by the decode. Animations are hashed from their first
+//! frame; formats [`crate::transform`] cannot decode (SVG, AVIF, ...) get no
+//! placeholder.
+
+use crate::transform;
+use image::GenericImageView;
+
+/// Longest side of the thumbnail the hash is computed from.
+const THUMBNAIL_SIZE: u32 = 32;
+
+/// Components along the longer side; the shorter side scales with the aspect
+/// ratio so the blur is not stretched.
+const MAX_COMPONENTS: u32 = 4;
+
+/// The BlurHash of `data`, if it is a decodable still image within
+/// `max_pixels` (0 = unlimited).
+pub fn blurhash(mime_type: &str, data: &[u8], max_pixels: u64) -> Option<String> {
+    let source = transform::decodable(mime_type)?;
+    let image = transform::decode(source, data, max_pixels).ok()?;
+    let thumbnail = image.thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE);
+    let (width, height) = thumbnail.dimensions();
+    let (x, y) = if width >= height {
+        (MAX_COMPONENTS, components(height, width))
+    } else {
+        (components(width, height), MAX_COMPONENTS)
+    };
+    blurhash::encode(x, y, width, height, thumbnail.to_rgba8().as_raw()).ok()
+}
+
+/// Components for the shorter side, in proportion to the longer.
+fn components(short: u32, long: u32) -> u32 {
+    (MAX_COMPONENTS * short)
+        .div_ceil(long.max(1))
+        .clamp(1, MAX_COMPONENTS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{ImageFormat, Rgb, RgbImage};
+    use std::io::Cursor;
+
+    fn png(width: u32, height: u32) -> Vec<u8> {
+        let mut png = Vec::new();
+        RgbImage::from_fn(width, height, |x, _| Rgb([(x * 255 / width) as u8, 0, 0]))
+            .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+            .unwrap();
+        png
+    }
+
+    #[test]
+    fn hashes_decodable_images() {
+        let hash = blurhash("image/png", &png(64, 64), 0).unwrap();
+        // One size byte, four DC bytes and two bytes per AC component.
+        assert_eq!(hash.len(), 1 + 1 + 4 + 2 * 15);
+        // Wide images use fewer vertical components.
+        let wide = blurhash("image/png", &png(200, 40), 0).unwrap();
+        assert_eq!(wide.len(), 1 + 1 + 4 + 2 * 3);
+    }
+
+    #[test]
+    fn skips_undecodable_images() {
+        assert_eq!(blurhash("image/svg+xml", b"<svg/>", 0), None);
+        assert_eq!(blurhash("image/png", b"not a png", 0), None);
+        assert_eq!(components(1, 1000), 1);
+    }
+}
@@ -0,0 +1,186 @@
+//! Background cache warming.
+//!
+//! [`ProxyClient::prefetch`] queues URLs and returns immediately. A background
+//! thread per client drains the queue through the normal fetch path, so
+//! responses land in the cache (and disk tier) exactly as if the UI had
+//! requested them — but no image bytes cross the FFI boundary. Higher-priority
+//! URLs are fetched first; the thread exits once the queue is empty. In the
+//! tunnel, prefetches yield to images the UI is waiting for, and `Low` ones to
+//! all other work.
+
+use crate::client::{default_client, ProxyClient};
+use crate::error::ProxyError;
+use crate::types::{FetchPriority, RequestPriority};
+use crate::{fetch_image, validate_image_url};
+use std::collections::{HashSet, VecDeque};
+use std::sync::{Arc, MutexGuard};
+
+/// Pending prefetches, one FIFO per priority level.
+#[derive(Default)]
+pub(crate) struct PrefetchQueue {
+    /// Indexed by [`level`]; higher index drains first.
+    lanes: [VecDeque<String>; 3],
+    /// Every URL currently queued, to drop duplicates.
+    queued: HashSet<String>,
+    /// Whether a drain thread is alive.
+    running: bool,
+}
+
+fn level(priority: FetchPriority) -> usize {
+    match priority {
+        FetchPriority::Low => 0,
+        FetchPriority::Normal => 1,
+        FetchPriority::High => 2,
+    }
+}
+
+impl PrefetchQueue {
+    /// Queue `url` unless it is already waiting. Returns whether it was added.
+    fn push(&mut self, url: String, priority: FetchPriority) -> bool {
+        if !self.queued.insert(url.clone()) {
+            return false;
+        }
+        self.lanes[level(priority)].push_back(url);
+        true
+    }
+
+    /// Take the oldest URL from the highest non-empty priority lane, with the
+    /// priority to fetch it at.
+    fn pop(&mut self) -> Option<(String, RequestPriority)> {
+        let (level, url) = self
+            .lanes
+            .iter_mut()
+            .enumerate()
+            .rev()
+            .find_map(|(level, lane)| Some((level, lane.pop_front()?)))?;
+        self.queued.remove(&url);
+        let priority = match level {
+            0 => RequestPriority::Background,
+            _ => RequestPriority::Prefetch,
+        };
+        Some((url, priority))
+    }
+
+    fn clear(&mut self) {
+        self.lanes.iter_mut().for_each(VecDeque::clear);
+        self.queued.clear();
+    }
+}
+
+impl ProxyClient {
+    fn lock_queue(&self) -> MutexGuard<'_, PrefetchQueue> {
+        self.prefetch
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Drop every queued prefetch. The in-flight one, if any, still completes.
+    pub(crate) fn cancel_prefetches(&self) {
+        self.lock_queue().clear();
+    }
+
+    /// Drain the queue, then mark the worker as stopped.
+    fn drain(&self) {
+        loop {
+            let next = {
+                let mut queue = self.lock_queue();
+                let next = queue.pop();
+                if next.is_none() {
+                    queue.running = false;
+                }
+                next
+            };
+            let Some((url, priority)) = next else { return };
+            if let Err(e) = fetch_image(self, &url, None, priority) {
+                log::debug!("Prefetch failed: {e}");
+            }
+        }
+    }
+}
+
+#[uniffi::export]
+impl ProxyClient {
+    /// Warm the image cache for `urls` in the background.
+    ///
+    /// Returns immediately with the number of URLs queued. Invalid URLs, URLs
+    /// that are already cached and URLs already waiting in the queue are
+    /// skipped. `High` URLs are fetched before `Normal`, and `Normal` before
+    /// `Low`; within a level, URLs are fetched in the order given. Failures are
+    /// not reported — the UI's later fetch will surface them as usual.
+    /// Nothing is queued while data-saver mode is on.
+    pub fn prefetch(
+        self: Arc<Self>,
+        urls: Vec<String>,
+        priority: FetchPriority,
+    ) -> Result<u32, ProxyError> {
+        let fresh: Vec<String> = {
+            let mut guard = self.lock_state();
+            let state = guard.as_mut().ok_or(ProxyError::NotInitialized)?;
+            if state.data_saver.is_some() {
+                return Ok(0);
+            }
+            urls.into_iter()
+                .filter(|url| validate_image_url(url).is_ok() && !state.cache.contains(url))
+                .collect()
+        };
+
+        let mut queue = self.lock_queue();
+        let queued = fresh
+            .into_iter()
+            .filter(|url| queue.push(url.clone(), priority))
+            .count();
+        if queued > 0 && !queue.running {
+            let client = Arc::clone(&self);
+            std::thread::Builder::new()
+                .name("letterbox-prefetch".to_string())
+                .spawn(move || client.drain())
+                .map_err(|e| ProxyError::InitializationFailed {
+                    details: format!("Failed to spawn prefetch thread: {e}"),
+                })?;
+            queue.running = true;
+        }
+        Ok(queued as u32)
+    }
+}
+
+/// [`ProxyClient::prefetch`] on the default proxy.
+#[uniffi::export]
+pub fn proxy_prefetch(urls: Vec<String>, priority: FetchPriority) -> Result<u32, ProxyError> {
+    Arc::clone(default_client()).prefetch(urls, priority)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn higher_priority_drains_first_and_fifo_within_level() {
+        let mut queue = PrefetchQueue::default();
+        queue.push("low".to_string(), FetchPriority::Low);
+        queue.push("n1".to_string(), FetchPriority::Normal);
+        queue.push("high".to_string(), FetchPriority::High);
+        queue.push("n2".to_string(), FetchPriority::Normal);
+
+        let order: Vec<(String, RequestPriority)> = std::iter::from_fn(|| queue.pop()).collect();
+        let order: Vec<(&str, RequestPriority)> =
+            order.iter().map(|(url, p)| (url.as_str(), *p)).collect();
+        assert_eq!(
+            order,
+            [
+                ("high", RequestPriority::Prefetch),
+                ("n1", RequestPriority::Prefetch),
+                ("n2", RequestPriority::Prefetch),
+                ("low", RequestPriority::Background),
+            ]
+        );
+    }
+
+    #[test]
+    fn duplicates_are_dropped_until_popped() {
+        let mut queue = PrefetchQueue::default();
+        assert!(queue.push("a".to_string(), FetchPriority::Low));
+        assert!(!queue.push("a".to_string(), FetchPriority::High));
+        assert_eq!(queue.pop().map(|(url, _)| url).as_deref(), Some("a"));
+        assert!(queue.push("a".to_string(), FetchPriority::High));
+    }
+}
@@ -0,0 +1,180 @@
+//! Link-preview image discovery.
+//!
+//! [`proxy_resolve_preview`] fetches an HTML page through the tunnel (capped at
+//! [`MAX_PAGE_SIZE`](crate::html::MAX_PAGE_SIZE)), picks the image the page advertises for previews —
+//! `og:image`, then `twitter:image`, then the largest `<img>` with declared
+//! dimensions — and fetches it through the normal image pipeline, so the mail
+//! client can render link previews without a separate scraper or a direct
+//! connection to the linked site.
+
+use crate::client::{default_client, ProxyClient};
+use crate::error::ProxyError;
+use crate::fetch_image;
+use crate::html::{self, attr};
+use crate::trace;
+use crate::types::{LinkPreview, RequestPriority};
+
+/// Images at or below this area are layout spacers or tracking pixels.
+const MIN_IMG_AREA: u64 = 4;
+
+#[uniffi::export]
+impl ProxyClient {
+    /// Fetch `url` as HTML and resolve its preview title and image.
+    ///
+    /// The page must be `text/html` or `application/xhtml+xml`. A page without a
+    /// preview image still succeeds; if the image fails to load, the failure is
+    /// reported in [`LinkPreview::image_error`].
+    pub fn resolve_preview(&self, url: String) -> Result<LinkPreview, ProxyError> {
+        trace::traced(|| resolve_preview(self, &url)).inspect_err(|e| {
+            self.record_error(Some(&url), e);
+        })
+    }
+}
+
+/// [`ProxyClient::resolve_preview`] on the default proxy.
+#[uniffi::export]
+pub fn proxy_resolve_preview(url: String) -> Result<LinkPreview, ProxyError> {
+    default_client().resolve_preview(url)
+}
+
+fn resolve_preview(client: &ProxyClient, url: &str) -> Result<LinkPreview, ProxyError> {
+    let page = html::fetch_page(client, url)?;
+    let meta = scan(&page.html);
+    let image_url = meta.image().and_then(|src| page.resolve(src));
+    let (image, image_error) = match image_url
+        .as_deref()
+        .map(|u| fetch_image(client, u, None, RequestPriority::Visible))
+    {
+        Some(Ok(image)) => (Some(image), None),
+        Some(Err(e)) => (None, Some(e.to_string())),
+        None => (None, None),
+    };
+    Ok(LinkPreview {
+        title: meta.title(),
+        url: page.final_url,
+        image_url,
+        image,
+        image_error,
+    })
+}
+
+/// Preview metadata collected from a page.
+#[derive(Debug, Default)]
+struct PageMeta {
+    og_image: Option<String>,
+    twitter_image: Option<String>,
+    /// Area and `src` of the largest sized `<img>`.
+    largest_img: Option<(u64, String)>,
+    og_title: Option<String>,
+    twitter_title: Option<String>,
+    title: Option<String>,
+}
+
+impl PageMeta {
+    /// The preview image reference, possibly relative.
+    fn image(&self) -> Option<&str> {
+        self.og_image
+            .as_deref()
+            .or(self.twitter_image.as_deref())
+            .or(self.largest_img.as_ref().map(|(_, src)| src.as_str()))
+    }
+
+    fn title(self) -> Option<String> {
+        self.og_title.or(self.twitter_title).or(self.title)
+    }
+
+    fn add_meta(&mut self, attrs: &[(String, String)]) {
+        let key = attr(attrs, "property").or_else(|| attr(attrs, "name"));
+        let (Some(key), Some(content)) = (key, attr(attrs, "content")) else {
+            return;
+        };
+        let content = content.trim();
+        if content.is_empty() {
+            return;
+        }
+        let slot = match key.to_ascii_lowercase().as_str() {
+            "og:image" | "og:image:url" | "og:image:secure_url" => &mut self.og_image,
+            "twitter:image" | "twitter:image:src" => &mut self.twitter_image,
+            "og:title" => &mut self.og_title,
+            "twitter:title" => &mut self.twitter_title,
+            _ => return,
+        };
+        slot.get_or_insert_with(|| content.to_string());
+    }
+
+    fn add_img(&mut self, attrs: &[(String, String)]) {
+        let size = |name| attr(attrs, name).and_then(pixels);
+        let (Some(src), Some(width), Some(height)) =
+            (attr(attrs, "src"), size("width"), size("height"))
+        else {
+            return;
+        };
+        let area = width * height;
+        let larger = self.largest_img.as_ref().is_none_or(|(max, _)| area > *max);
+        if area > MIN_IMG_AREA && larger && !src.trim().is_empty() {
+            self.largest_img = Some((area, src.trim().to_string()));
+        }
+    }
+}
+
+/// Collect preview metadata from the tags of `html`.
+fn scan(html: &str) -> PageMeta {
+    let mut meta = PageMeta::default();
+    html::scan_tags(html, |name, attrs, text| match name {
+        "meta" => meta.add_meta(attrs),
+        "img" => meta.add_img(attrs),
+        "title" if meta.title.is_none() && !text.is_empty() => {
+            meta.title = Some(text.to_string());
+        }
+        _ => {}
+    });
+    meta
+}
+
+/// A plain pixel count from a `width`/`height` attribute.
+fn pixels(value: &str) -> Option<u64> {
+    let value = value.trim();
+    value.strip_suffix("px").unwrap_or(value).parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prefers_open_graph_then_twitter() {
+        let html = r#"<html><head>
+            <meta name="twitter:image" content="/tw.png">
+            <META property='og:image' content="https://cdn.example/og.jpg?a=1&amp;b=2">
+            <meta property="og:title" content="Launch &#8212; today">
+            <title>Fallback</title>
+        </head><body><img src="big.png" width="800" height="600"></body></html>"#;
+        let meta = scan(html);
+        assert_eq!(meta.image(), Some("https://cdn.example/og.jpg?a=1&b=2"));
+        assert_eq!(meta.title().as_deref(), Some("Launch \u{2014} today"));
+
+        let meta = scan(
+            r#"<meta name="twitter:image:src" content="/tw.png"><img src=a.png width=9 height=9>"#,
+        );
+        assert_eq!(meta.image(), Some("/tw.png"));
+    }
+
+    #[test]
+    fn falls_back_to_largest_sized_img() {
+        let html = r#"<title>
+            A   page </title>
+            <img src="/pixel.gif" width="1" height="1">
+            <img src="/logo.png" width="120" height="40">
+            <img src="/unsized.png">
+            <img src="/hero.jpg" width="640px" height="360" alt="x > y">
+            <!-- <img src="/commented.png" width="9999" height="9999"> -->
+            <script>var s = '<img src="/script.png" width="9999" height="9999">';</script>"#;
+        let meta = scan(html);
+        assert_eq!(meta.image(), Some("/hero.jpg"));
+        assert_eq!(meta.title().as_deref(), Some("A page"));
+
+        let meta = scan(r#"<img src="/pixel.gif" width="1" height="1"><p>no images"#);
+        assert_eq!(meta.image(), None);
+        assert_eq!(meta.title(), None);
+    }
+}
@@ -0,0 +1,103 @@
+//! Metadata-only image probes.
+//!
+//! [`proxy_probe_image`] learns an image's type and size without downloading
+//! it, so the UI can auto-load small images and show a "load large image"
+//! button for big ones. It sends a `HEAD` request, falling back to a one-byte
+//! ranged `GET` for servers that reject `HEAD`; no body bytes are read.
+
+use crate::cache::image_key;
+use crate::client::{default_client, ProxyClient};
+use crate::data_uri;
+use crate::error::ProxyError;
+use crate::http::StreamOutcome;
+use crate::trace;
+use crate::types::ImageProbe;
+use crate::validate_image_url;
+
+#[uniffi::export]
+impl ProxyClient {
+    /// Look up an image's MIME type, size and final URL without fetching it.
+    ///
+    /// Cached images and `data:` URIs are answered locally. A non-image content
+    /// type fails with [`ProxyError::InvalidContentType`], as a fetch would.
+    pub fn probe_image(&self, url: String) -> Result<ImageProbe, ProxyError> {
+        trace::traced(|| probe(self, &url)).inspect_err(|e| {
+            self.record_error(Some(&url), e);
+        })
+    }
+}
+
+/// [`ProxyClient::probe_image`] on the default proxy.
+#[uniffi::export]
+pub fn proxy_probe_image(url: String) -> Result<ImageProbe, ProxyError> {
+    default_client().probe_image(url)
+}
+
+fn probe(client: &ProxyClient, url: &str) -> Result<ImageProbe, ProxyError> {
+    match local(client, url)? {
+        Local::Done(probe) => Ok(probe),
+        Local::Pending(accept) => {
+            let (manager, limits) = client.acquire_manager()?;
+            remote(manager.probe(url.to_string(), Vec::new(), accept, limits)?)
+        }
+    }
+}
+
+/// [`probe`], awaiting the network instead of blocking on it.
+pub(crate) async fn probe_async(client: &ProxyClient, url: &str) -> Result<ImageProbe, ProxyError> {
+    match local(client, url)? {
+        Local::Done(probe) => Ok(probe),
+        Local::Pending(accept) => {
+            let (manager, limits) = client.acquire_manager()?;
+            let outcome = manager
+                .probe_async(url.to_string(), Vec::new(), accept, limits)
+                .await?;
+            remote(outcome)
+        }
+    }
+}
+
+/// Where a probe stands before any network access.
+enum Local {
+    /// Answered from the cache or a `data:` URI.
+    Done(ImageProbe),
+    /// Needs a request, with this `Accept` header.
+    Pending(String),
+}
+
+fn local(client: &ProxyClient, url: &str) -> Result<Local, ProxyError> {
+    let (local, accept) = {
+        let mut guard = client.lock_state();
+        let state = guard.as_mut().ok_or(ProxyError::NotInitialized)?;
+        let accept = state.config.image_accept.clone();
+        if data_uri::is_data_uri(url) {
+            (Some(data_uri::decode(url, &state.fetch_limits())?), accept)
+        } else {
+            validate_image_url(url)?;
+            (state.cache.get(&image_key(url, &accept)), accept)
+        }
+    };
+    Ok(match local {
+        Some(image) => Local::Done(ImageProbe {
+            mime_type: image.mime_type,
+            content_length: Some(image.data.len() as u64),
+            final_url: image.final_url,
+            from_cache: true,
+        }),
+        None => Local::Pending(accept),
+    })
+}
+
+fn remote(outcome: StreamOutcome) -> Result<ImageProbe, ProxyError> {
+    if !outcome.mime_type.starts_with("image/") {
+        return Err(ProxyError::InvalidContentType {
+            content_type: outcome.mime_type,
+        });
+    }
+    Ok(ImageProbe {
+        mime_type: outcome.mime_type,
+        content_length: outcome.content_length,
+        final_url: outcome.final_url,
+        from_cache: false,
+    })
+}
@@ -0,0 +1,77 @@
+//! Download progress reporting to the host app.
+//!
+//! The host registers one [`ProgressListener`] with
+//! [`proxy_set_progress_listener`]; every tunnelled fetch that streams a body
+//! (images, files, generic URLs) then reports progress to it, keyed by the
+//! requested URL. The listener is process-wide, shared by every
+//! [`ProxyClient`](crate::ProxyClient). Reports are throttled by the HTTP
+//! layer, so the listener sees at most one call per
+//! [`crate::http::PROGRESS_STEP`] bytes plus a final one.
+
+use crate::http::ProgressFn;
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// Receives download progress for tunnelled fetches.
+#[uniffi::export(with_foreign)]
+pub trait ProgressListener: Send + Sync {
+    /// `bytes_downloaded` of the body of `url` have arrived; `total` is the
+    /// announced `Content-Length`, if the server sent one.
+    fn on_progress(&self, url: String, bytes_downloaded: u64, total: Option<u64>);
+}
+
+type Listener = Arc<dyn ProgressListener>;
+
+static LISTENER: OnceLock<Mutex<Option<Listener>>> = OnceLock::new();
+
+fn listener_slot() -> std::sync::MutexGuard<'static, Option<Listener>> {
+    LISTENER
+        .get_or_init(|| Mutex::new(None))
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Register (or, with `None`, remove) the progress listener.
+///
+/// May be called before [`crate::proxy_init`]; the listener survives
+/// re-initialisation.
+#[uniffi::export]
+pub fn proxy_set_progress_listener(listener: Option<Arc<dyn ProgressListener>>) {
+    *listener_slot() = listener;
+}
+
+/// A progress callback for a fetch of `url`, if a listener is registered.
+pub(crate) fn progress_for(url: &str) -> Option<ProgressFn> {
+    let listener = listener_slot().clone()?;
+    let url = url.to_string();
+    Some(Arc::new(move |done, total| {
+        listener.on_progress(url.clone(), done, total);
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Recorder(Mutex<Vec<(String, u64, Option<u64>)>>);
+
+    impl ProgressListener for Recorder {
+        fn on_progress(&self, url: String, bytes_downloaded: u64, total: Option<u64>) {
+            self.0.lock().unwrap().push((url, bytes_downloaded, total));
+        }
+    }
+
+    #[test]
+    fn progress_is_routed_to_the_listener_with_its_url() {
+        let recorder = Arc::new(Recorder(Mutex::new(Vec::new())));
+        proxy_set_progress_listener(Some(recorder.clone()));
+        let report = progress_for("https://a/big.jpg").unwrap();
+        report(10, Some(100));
+        proxy_set_progress_listener(None);
+        assert!(progress_for("https://a/big.jpg").is_none());
+
+        assert_eq!(
+            *recorder.0.lock().unwrap(),
+            vec![("https://a/big.jpg".to_string(), 10, Some(100))]
+        );
+    }
+}
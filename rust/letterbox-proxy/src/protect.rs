@@ -0,0 +1,164 @@
+//! Keeping the tunnel's own socket on the intended network.
+//!
+//! If the device also runs a VPN, the WireGuard UDP socket is routed into it
+//! like any other app traffic: WARP ends up tunnelled inside the VPN, or leaves
+//! through a network the user did not intend. A registered [`SocketProtector`]
+//! is handed the socket's file descriptor after it is bound and before the
+//! handshake, so the app can exclude it with `VpnService.protect(fd)` (when the
+//! app is itself the VPN) or pin it with `Network.bindSocket`.
+//!
+//! A [`NetworkBinding`] picks the network outright: a local address on a
+//! multi-homed desktop, or an Android `Network` such as cellular or Wi-Fi.
+//!
+//! Only the WARP socket is affected. Builds without the `tunnel` feature
+//! connect directly and their sockets follow the device's routing as usual.
+
+use crate::client::{default_client, ProxyClient};
+use crate::error::ProxyError;
+use std::net::Ipv4Addr;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// Excludes the proxy's WireGuard socket from VPN routing.
+#[uniffi::export(with_foreign)]
+pub trait SocketProtector: Send + Sync {
+    /// Protect the UDP socket `fd`. Called on the tunnel thread before the
+    /// socket sends anything; the proxy keeps owning the descriptor, so do
+    /// not close or adopt it. Returning `false` fails the tunnel start rather
+    /// than let the handshake take the unprotected route.
+    fn protect(&self, fd: i32) -> bool;
+}
+
+/// The network the WireGuard socket sends from.
+#[derive(Clone, Debug, Default, PartialEq, uniffi::Enum)]
+pub enum NetworkBinding {
+    /// Whichever network the device routes the WARP endpoint over.
+    #[default]
+    Default,
+    /// The interface owning this local IPv4 address, e.g. `"192.168.1.20"`.
+    /// The WARP endpoint is IPv4, so IPv6 addresses are refused.
+    LocalAddress { address: String },
+    /// The Android `Network` with this `getNetworkHandle()`, e.g. to force
+    /// cellular or Wi-Fi. Tunnel starts fail on other platforms.
+    AndroidNetwork { handle: u64 },
+}
+
+/// Parse the address of a [`NetworkBinding::LocalAddress`].
+pub(crate) fn local_address(address: &str) -> Result<Ipv4Addr, ProxyError> {
+    address.trim().parse().map_err(|_| ProxyError::TunnelError {
+        details: format!(
+            "Invalid local address {address:?}: expected IPv4, as the WARP endpoint is IPv4"
+        ),
+    })
+}
+
+/// How a tunnel sets up its WireGuard socket.
+#[derive(Clone, Default)]
+pub struct SocketSetup {
+    /// Handed the socket before it is connected.
+    pub protector: Option<Arc<dyn SocketProtector>>,
+    /// Where the socket is bound.
+    pub binding: NetworkBinding,
+}
+
+/// The socket setup a client hands to the tunnels it starts.
+#[derive(Default)]
+pub struct SocketPolicy {
+    setup: Mutex<SocketSetup>,
+}
+
+impl SocketPolicy {
+    fn lock(&self) -> MutexGuard<'_, SocketSetup> {
+        self.setup
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// The current setup, for a tunnel about to start.
+    #[cfg(feature = "tunnel")]
+    pub(crate) fn get(&self) -> SocketSetup {
+        self.lock().clone()
+    }
+}
+
+#[uniffi::export]
+impl ProxyClient {
+    /// Register (or, with `None`, remove) the protector for this client's
+    /// WireGuard socket. Works before initialisation and applies to tunnels
+    /// started afterwards, so set it before the first fetch.
+    pub fn set_socket_protector(&self, protector: Option<Arc<dyn SocketProtector>>) {
+        self.socket_policy.lock().protector = protector;
+    }
+
+    /// Send the tunnel over `binding` from now on. Works before
+    /// initialisation; a running tunnel on another binding is torn down and
+    /// reconnects on the next request.
+    pub fn set_network_binding(&self, binding: NetworkBinding) -> Result<(), ProxyError> {
+        if let NetworkBinding::LocalAddress { address } = &binding {
+            local_address(address)?;
+        }
+        let changed = {
+            let mut setup = self.socket_policy.lock();
+            let changed = setup.binding != binding;
+            setup.binding = binding;
+            changed
+        };
+        if changed {
+            let manager = self
+                .lock_state()
+                .as_mut()
+                .and_then(|state| state.manager.take());
+            self.tunnel_dropped(manager, "network binding changed");
+        }
+        Ok(())
+    }
+}
+
+/// [`ProxyClient::set_socket_protector`] on the default proxy.
+#[uniffi::export]
+pub fn proxy_set_socket_protector(protector: Option<Arc<dyn SocketProtector>>) {
+    default_client().set_socket_protector(protector);
+}
+
+/// [`ProxyClient::set_network_binding`] on the default proxy.
+#[uniffi::export]
+pub fn proxy_set_network_binding(binding: NetworkBinding) -> Result<(), ProxyError> {
+    default_client().set_network_binding(binding)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tunnel::TunnelManager;
+
+    #[test]
+    fn only_ipv4_local_addresses_are_accepted() {
+        assert_eq!(
+            local_address(" 192.168.1.20 ").unwrap(),
+            Ipv4Addr::new(192, 168, 1, 20)
+        );
+        for bad in ["::1", "wlan0", ""] {
+            assert!(local_address(bad).is_err(), "{bad:?}");
+        }
+    }
+
+    #[test]
+    fn changing_the_binding_drops_the_tunnel() {
+        let dir = tempfile::tempdir().unwrap();
+        let client = ProxyClient::new(dir.path().to_string_lossy().into_owned(), 0).unwrap();
+        let manager = Arc::new(TunnelManager::direct().unwrap());
+        let running = || client.status().unwrap().tunnel_connected;
+        client.lock_state().as_mut().unwrap().manager = Some(manager);
+        let bad = NetworkBinding::LocalAddress {
+            address: "fe80::1".to_string(),
+        };
+        assert!(client.set_network_binding(bad).is_err());
+        assert_eq!(client.socket_policy.lock().binding, NetworkBinding::Default);
+        client.set_network_binding(NetworkBinding::Default).unwrap();
+        assert!(running());
+
+        let cellular = NetworkBinding::AndroidNetwork { handle: 7 };
+        client.set_network_binding(cellular.clone()).unwrap();
+        assert_eq!(client.socket_policy.lock().binding, cellular);
+        assert!(!running());
+    }
+}
@@ -11,14 +11,17 @@
 //! The WARP client API is accessed at `api.cloudflareclient.com`.
 //! This is the same API used by the official WARP client and wgcf.
 
+mod wire;
+
 use crate::config::{WarpAccountData, WarpConfig, WarpInterfaceConfig, WarpPeerConfig};
 use crate::error::ProxyError;
 use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
 use chrono::Utc;
 use rand::Rng;
 use rustls::{ClientConfig, RootCertStore};
-use serde::{Deserialize, Serialize};
+use serde::Serialize;
 use std::sync::Arc;
+use wire::{ConfigResponse, RegistrationRequest, RegistrationResponse};
 use x25519_dalek::{PublicKey, StaticSecret};
 
 /// Cloudflare WARP API version.
@@ -71,6 +74,10 @@ fn default_headers() -> reqwest::header::HeaderMap {
 fn provisioning_tls_config() -> ClientConfig {
     let mut roots = RootCertStore::empty();
     roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+    #[cfg(feature = "mock")]
+    roots
+        .add(crate::test_support::test_root())
+        .expect("the test CA is a valid trust anchor");
 
     let mut config =
         ClientConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
@@ -86,94 +93,26 @@ fn provisioning_tls_config() -> ClientConfig {
     config
 }
 
-/// Registration request sent to Cloudflare.
-#[derive(Debug, Serialize)]
-struct RegistrationRequest {
-    install_id: String,
-    tos: String,
-    key: String,
-    fcm_token: String,
-    #[serde(rename = "type")]
-    device_type: String,
-    model: String,
-    locale: String,
-}
-
-/// Registration response from Cloudflare.
-#[derive(Debug, Deserialize)]
-struct RegistrationResponse {
-    id: String,
-    token: String,
-    account: AccountInfo,
-}
-
-/// Account information in registration response.
-#[derive(Debug, Deserialize)]
-struct AccountInfo {
-    license: String,
-}
-
-/// Configuration response from Cloudflare.
-#[derive(Debug, Deserialize)]
-struct ConfigResponse {
-    config: ConfigData,
-    warp_enabled: bool,
-    #[serde(default)]
-    account: Option<AccountDetails>,
-}
-
-/// Configuration data in config response.
-#[derive(Debug, Deserialize)]
-struct ConfigData {
-    interface: InterfaceData,
-    peers: Vec<PeerData>,
-}
-
-/// Interface configuration data.
-#[derive(Debug, Deserialize)]
-struct InterfaceData {
-    addresses: AddressData,
-}
-
-/// Address data in interface configuration.
-#[derive(Debug, Deserialize)]
-struct AddressData {
-    v4: String,
-}
-
-/// Peer configuration data.
-#[derive(Debug, Deserialize)]
-struct PeerData {
-    public_key: String,
-    endpoint: EndpointData,
-}
-
-/// Endpoint data in peer configuration.
-#[derive(Debug, Deserialize)]
-struct EndpointData {
-    host: String,
-    v4: String,
-}
-
-/// Account details in config response.
-#[derive(Debug, Deserialize)]
-#[allow(dead_code)]
-struct AccountDetails {
-    #[serde(default)]
-    account_type: String,
-    #[serde(default)]
-    warp_plus: bool,
-    license: String,
-}
-
 /// WARP provisioner that handles account creation and configuration.
 pub struct WarpProvisioner {
     client: reqwest::Client,
+    /// Scheme and host of the WARP API, without a trailing slash.
+    api_base: String,
 }
 
 impl WarpProvisioner {
     /// Create a new WARP provisioner.
     pub fn new() -> Result<Self, ProxyError> {
+        #[cfg(feature = "mock")]
+        if let Some(base) = crate::test_support::warp_api_base() {
+            return Self::with_api_base(&base);
+        }
+        Self::with_api_base(API_BASE)
+    }
+
+    /// Create a provisioner talking to the WARP API at `api_base`
+    /// (e.g. `https://api.cloudflareclient.com`) instead.
+    pub fn with_api_base(api_base: &str) -> Result<Self, ProxyError> {
         let client = reqwest::Client::builder()
             .use_preconfigured_tls(provisioning_tls_config())
             .default_headers(default_headers())
@@ -183,7 +122,10 @@ impl WarpProvisioner {
                 details: format!("Failed to create HTTP client: {}", e),
             })?;
 
-        Ok(Self { client })
+        Ok(Self {
+            client,
+            api_base: api_base.trim_end_matches('/').to_string(),
+        })
     }
 
     /// Generate a new WireGuard keypair.
@@ -236,7 +178,7 @@ impl WarpProvisioner {
     /// returned with their full `source()` chain so a benign network error can
     /// be told apart from a certificate-verifier fault (see [`crate::selftest`]).
     pub async fn tls_self_test(&self) -> Result<(), ProxyError> {
-        let url = format!("{}/{}/", API_BASE, API_VERSION);
+        let url = format!("{}/{}/", self.api_base, API_VERSION);
         self.client
             .get(&url)
             .send()
@@ -253,7 +195,7 @@ impl WarpProvisioner {
     /// The private key must be generated beforehand and only the public key
     /// is sent to Cloudflare.
     pub async fn register(&self, public_key: &str) -> Result<WarpAccountData, ProxyError> {
-        let url = format!("{}/{}/reg", API_BASE, API_VERSION);
+        let url = format!("{}/{}/reg", self.api_base, API_VERSION);
 
         let request = RegistrationRequest {
             install_id: String::new(),
@@ -302,7 +244,10 @@ impl WarpProvisioner {
 
     /// Fetch the tunnel configuration for an existing account.
     pub async fn fetch_config(&self, account: &WarpAccountData) -> Result<WarpConfig, ProxyError> {
-        let url = format!("{}/{}/reg/{}", API_BASE, API_VERSION, account.account_id);
+        let url = format!(
+            "{}/{}/reg/{}",
+            self.api_base, API_VERSION, account.account_id
+        );
 
         let response = self
             .client
@@ -368,12 +313,16 @@ impl WarpProvisioner {
             warp_enabled: config_response.warp_enabled,
             account_type,
             last_updated: Utc::now().timestamp(),
+            client_id: config_response.config.client_id,
         })
     }
 
     /// Enable WARP on the account.
     pub async fn enable_warp(&self, account: &WarpAccountData) -> Result<(), ProxyError> {
-        let url = format!("{}/{}/reg/{}", API_BASE, API_VERSION, account.account_id);
+        let url = format!(
+            "{}/{}/reg/{}",
+            self.api_base, API_VERSION, account.account_id
+        );
 
         #[derive(Serialize)]
         struct EnableRequest {
@@ -407,7 +356,10 @@ impl WarpProvisioner {
     /// Used to clean up ephemeral accounts (e.g. created by integration tests)
     /// so they do not accumulate on Cloudflare's side.
     pub async fn delete_device(&self, account: &WarpAccountData) -> Result<(), ProxyError> {
-        let url = format!("{}/{}/reg/{}", API_BASE, API_VERSION, account.account_id);
+        let url = format!(
+            "{}/{}/reg/{}",
+            self.api_base, API_VERSION, account.account_id
+        );
 
         let response = self
             .client
@@ -499,59 +451,6 @@ mod tests {
         assert!(ts.contains(&year));
     }
 
-    #[test]
-    fn test_registration_request_serialization() {
-        let request = RegistrationRequest {
-            install_id: String::new(),
-            tos: "2024-01-01T00:00:00.000+00:00".to_string(),
-            key: "test-public-key".to_string(),
-            fcm_token: String::new(),
-            device_type: "Android".to_string(),
-            model: "Test".to_string(),
-            locale: "en_US".to_string(),
-        };
-
-        let json = serde_json::to_string(&request).unwrap();
-        assert!(json.contains("\"type\":\"Android\""));
-        assert!(json.contains("\"key\":\"test-public-key\""));
-    }
-
-    #[test]
-    fn test_config_response_deserialization() {
-        let json = r#"{
-            "config": {
-                "interface": {
-                    "addresses": {
-                        "v4": "172.16.0.2/32",
-                        "v6": "fd01:db8:1111:2222::2/128"
-                    }
-                },
-                "peers": [{
-                    "public_key": "bmXOC+F1FxEMF9dyiK2H5/1SUtzH0JuVo51h2wPfgyo=",
-                    "endpoint": {
-                        "host": "engage.cloudflareclient.com:2408",
-                        "v4": "162.159.192.1",
-                        "v6": "2606:4700:d0::a29f:c001"
-                    }
-                }]
-            },
-            "warp_enabled": true,
-            "account": {
-                "account_type": "free",
-                "warp_plus": false,
-                "license": "test-license"
-            }
-        }"#;
-
-        let response: ConfigResponse = serde_json::from_str(json).unwrap();
-        assert!(response.warp_enabled);
-        assert_eq!(response.config.peers.len(), 1);
-        assert!(response.config.peers[0]
-            .endpoint
-            .host
-            .contains("cloudflareclient.com"));
-    }
-
     #[test]
     fn test_endpoint_parsing() {
         // Test parsing endpoint host:port
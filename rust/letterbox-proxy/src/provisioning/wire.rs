@@ -0,0 +1,145 @@
+//! Request and response bodies of the WARP client API.
+
+use serde::{Deserialize, Serialize};
+
+/// Registration request sent to Cloudflare.
+#[derive(Debug, Serialize)]
+pub(super) struct RegistrationRequest {
+    pub(super) install_id: String,
+    pub(super) tos: String,
+    pub(super) key: String,
+    pub(super) fcm_token: String,
+    #[serde(rename = "type")]
+    pub(super) device_type: String,
+    pub(super) model: String,
+    pub(super) locale: String,
+}
+
+/// Registration response from Cloudflare.
+#[derive(Debug, Deserialize)]
+pub(super) struct RegistrationResponse {
+    pub(super) id: String,
+    pub(super) token: String,
+    pub(super) account: AccountInfo,
+}
+
+/// Account information in registration response.
+#[derive(Debug, Deserialize)]
+pub(super) struct AccountInfo {
+    pub(super) license: String,
+}
+
+/// Configuration response from Cloudflare.
+#[derive(Debug, Deserialize)]
+pub(super) struct ConfigResponse {
+    pub(super) config: ConfigData,
+    pub(super) warp_enabled: bool,
+    #[serde(default)]
+    pub(super) account: Option<AccountDetails>,
+}
+
+/// Configuration data in config response.
+#[derive(Debug, Deserialize)]
+pub(super) struct ConfigData {
+    pub(super) interface: InterfaceData,
+    pub(super) peers: Vec<PeerData>,
+    #[serde(default)]
+    pub(super) client_id: String,
+}
+
+/// Interface configuration data.
+#[derive(Debug, Deserialize)]
+pub(super) struct InterfaceData {
+    pub(super) addresses: AddressData,
+}
+
+/// Address data in interface configuration.
+#[derive(Debug, Deserialize)]
+pub(super) struct AddressData {
+    pub(super) v4: String,
+}
+
+/// Peer configuration data.
+#[derive(Debug, Deserialize)]
+pub(super) struct PeerData {
+    pub(super) public_key: String,
+    pub(super) endpoint: EndpointData,
+}
+
+/// Endpoint data in peer configuration.
+#[derive(Debug, Deserialize)]
+pub(super) struct EndpointData {
+    pub(super) host: String,
+    pub(super) v4: String,
+}
+
+/// Account details in config response.
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+pub(super) struct AccountDetails {
+    #[serde(default)]
+    pub(super) account_type: String,
+    #[serde(default)]
+    pub(super) warp_plus: bool,
+    pub(super) license: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registration_request_serialization() {
+        let request = RegistrationRequest {
+            install_id: String::new(),
+            tos: "2024-01-01T00:00:00.000+00:00".to_string(),
+            key: "test-public-key".to_string(),
+            fcm_token: String::new(),
+            device_type: "Android".to_string(),
+            model: "Test".to_string(),
+            locale: "en_US".to_string(),
+        };
+
+        let json = serde_json::to_string(&request).unwrap();
+        assert!(json.contains("\"type\":\"Android\""));
+        assert!(json.contains("\"key\":\"test-public-key\""));
+    }
+
+    #[test]
+    fn test_config_response_deserialization() {
+        let json = r#"{
+            "config": {
+                "client_id": "AQID",
+                "interface": {
+                    "addresses": {
+                        "v4": "172.16.0.2/32",
+                        "v6": "fd01:db8:1111:2222::2/128"
+                    }
+                },
+                "peers": [{
+                    "public_key": "bmXOC+F1FxEMF9dyiK2H5/1SUtzH0JuVo51h2wPfgyo=",
+                    "endpoint": {
+                        "host": "engage.cloudflareclient.com:2408",
+                        "v4": "162.159.192.1",
+                        "v6": "2606:4700:d0::a29f:c001"
+                    }
+                }]
+            },
+            "warp_enabled": true,
+            "account": {
+                "account_type": "free",
+                "warp_plus": false,
+                "license": "test-license"
+            }
+        }"#;
+
+        let response: ConfigResponse = serde_json::from_str(json).unwrap();
+        assert!(response.warp_enabled);
+        assert_eq!(response.config.client_id, "AQID");
+        assert_eq!(response.config.peers.len(), 1);
+        assert!(response.config.peers[0]
+            .endpoint
+            .host
+            .contains("cloudflareclient.com"));
+    }
+}
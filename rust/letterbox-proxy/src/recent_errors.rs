@@ -0,0 +1,127 @@
+//! Recent errors for in-app diagnostics.
+//!
+//! [`ProxyStatus::last_error`](crate::types::ProxyStatus::last_error) only
+//! holds the latest message. Every failure reported to the app is also kept,
+//! structured, in a ring buffer of the last [`CAPACITY`] errors, so a
+//! diagnostics screen can show which hosts fail, how, and over which
+//! transport, without the app subscribing to events ahead of time. The
+//! buffer lives in memory only and outlasts [`ProxyClient::shutdown`].
+
+use crate::client::{default_client, ProxyClient};
+use crate::error::ProxyError;
+use crate::types::{RecentError, Transport};
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+/// Errors kept; the oldest is dropped beyond this.
+pub const CAPACITY: usize = 50;
+
+/// The last [`CAPACITY`] errors, oldest first.
+#[derive(Debug, Default)]
+pub(crate) struct ErrorLog {
+    entries: Mutex<VecDeque<RecentError>>,
+}
+
+impl ErrorLog {
+    /// Keep `error`, met while requesting `url` over `transport`.
+    pub(crate) fn record(
+        &self,
+        url: Option<&str>,
+        error: &ProxyError,
+        transport: Option<Transport>,
+    ) {
+        let entry = RecentError {
+            timestamp_ms: chrono::Utc::now().timestamp_millis(),
+            host: url
+                .and_then(|url| url::Url::parse(url).ok())
+                .and_then(|url| url.host_str().map(str::to_ascii_lowercase)),
+            code: error.code(),
+            message: error.to_string(),
+            transport,
+        };
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        if entries.len() == CAPACITY {
+            entries.pop_front();
+        }
+        entries.push_back(entry);
+    }
+
+    /// The kept errors, newest first.
+    pub(crate) fn snapshot(&self) -> Vec<RecentError> {
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        entries.iter().rev().cloned().collect()
+    }
+
+    pub(crate) fn clear(&self) {
+        self.entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clear();
+    }
+}
+
+#[uniffi::export]
+impl ProxyClient {
+    /// The last errors reported to the app, newest first; at most
+    /// [`CAPACITY`].
+    pub fn recent_errors(&self) -> Vec<RecentError> {
+        self.errors.snapshot()
+    }
+
+    /// Forget the errors kept for [`Self::recent_errors`].
+    pub fn clear_recent_errors(&self) {
+        self.errors.clear();
+    }
+}
+
+/// [`ProxyClient::recent_errors`] of the default proxy.
+#[uniffi::export]
+pub fn proxy_recent_errors() -> Vec<RecentError> {
+    default_client().recent_errors()
+}
+
+/// [`ProxyClient::clear_recent_errors`] on the default proxy.
+#[uniffi::export]
+pub fn proxy_clear_recent_errors() {
+    default_client().clear_recent_errors()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorCode;
+
+    #[test]
+    fn keeps_the_latest_errors_newest_first() {
+        let log = ErrorLog::default();
+        log.record(
+            Some("https://CDN.example/a.png"),
+            &ProxyError::Throttled,
+            Some(Transport::Tunnel),
+        );
+        log.record(
+            Some("data:image/png;base64,AAAA"),
+            &ProxyError::Cancelled,
+            None,
+        );
+
+        let errors = log.snapshot();
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].code, ErrorCode::Cancelled);
+        assert_eq!(errors[0].host, None);
+        assert_eq!(errors[1].code, ErrorCode::Throttled);
+        assert_eq!(errors[1].host.as_deref(), Some("cdn.example"));
+        assert_eq!(errors[1].transport, Some(Transport::Tunnel));
+        assert!(errors[1].timestamp_ms > 0);
+
+        for _ in 0..CAPACITY {
+            log.record(None, &ProxyError::Paused, None);
+        }
+        let errors = log.snapshot();
+        assert_eq!(errors.len(), CAPACITY);
+        assert!(errors.iter().all(|e| e.code == ErrorCode::Paused));
+
+        log.clear();
+        assert!(log.snapshot().is_empty());
+    }
+}
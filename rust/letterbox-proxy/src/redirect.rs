@@ -0,0 +1,221 @@
+//! Per-hop redirect policy.
+//!
+//! Every redirect the HTTP layer follows is vetted against the configured
+//! [`RedirectPolicy`] before the next request is sent: an image on an `https`
+//! page must not silently continue over plaintext, a remote server must not
+//! bounce the proxy into private address space, and hosts that want it can
+//! refuse to leave the original host, scheme or origin at all. The redirects
+//! let through are reported hop by hop in
+//! [`ImageResponse::redirect_hops`](crate::types::ImageResponse::redirect_hops).
+
+use crate::client::{default_client, ProxyClient};
+use crate::error::ProxyError;
+use crate::types::{RedirectHop, RedirectPolicy};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use url::{Host, Url};
+
+fn blocked(to: &Url, reason: &str) -> ProxyError {
+    ProxyError::RedirectBlocked {
+        url: to.to_string(),
+        reason: reason.to_string(),
+    }
+}
+
+/// Check a redirect from `from` to `to` against `policy`.
+///
+/// Hosts that are IP literals are checked here; for names, the HTTP layer
+/// applies [`is_private`] to the resolved address.
+pub(crate) fn check_redirect(
+    policy: &RedirectPolicy,
+    from: &Url,
+    to: &Url,
+) -> Result<(), ProxyError> {
+    if policy.forbid_downgrade && from.scheme() == "https" && to.scheme() != "https" {
+        return Err(blocked(to, "https to http downgrade"));
+    }
+    if policy.forbid_scheme_change && from.scheme() != to.scheme() {
+        return Err(blocked(to, "scheme change"));
+    }
+    if policy.forbid_cross_host && from.host() != to.host() {
+        return Err(blocked(to, "cross-host redirect"));
+    }
+    if policy.forbid_cross_origin && from.origin() != to.origin() {
+        return Err(blocked(to, "cross-origin redirect"));
+    }
+    if policy.forbid_private_targets {
+        let private = match to.host() {
+            Some(Host::Ipv4(ip)) => is_private(IpAddr::V4(ip)),
+            Some(Host::Ipv6(ip)) => is_private(IpAddr::V6(ip)),
+            Some(Host::Domain(name)) => name.eq_ignore_ascii_case("localhost"),
+            None => false,
+        };
+        if private {
+            return Err(blocked(to, "private address target"));
+        }
+    }
+    Ok(())
+}
+
+/// The hops of a followed redirect `chain` ending at `final_url`.
+pub(crate) fn hops(chain: &[String], final_url: &str) -> Vec<RedirectHop> {
+    let targets = chain.iter().skip(1).map(String::as_str).chain([final_url]);
+    chain
+        .iter()
+        .zip(targets)
+        .map(|(from, to)| {
+            let (from_url, to_url) = (Url::parse(from).ok(), Url::parse(to).ok());
+            let scheme = |url: &Option<Url>| url.as_ref().map(|u| u.scheme().to_string());
+            let host =
+                |url: &Option<Url>| url.as_ref().and_then(|u| u.host_str().map(str::to_string));
+            RedirectHop {
+                from: from.clone(),
+                to: to.to_string(),
+                cross_host: host(&from_url) != host(&to_url),
+                scheme_changed: scheme(&from_url) != scheme(&to_url),
+                downgrade: scheme(&from_url).as_deref() == Some("https")
+                    && scheme(&to_url).as_deref() == Some("http"),
+            }
+        })
+        .collect()
+}
+
+/// Whether `ip` is loopback, private, link-local or otherwise not a public
+/// internet address.
+pub(crate) fn is_private(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_private_v4(ip),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(v4) => is_private_v4(v4),
+            None => is_private_v6(ip),
+        },
+    }
+}
+
+fn is_private_v4(ip: Ipv4Addr) -> bool {
+    let [a, b, ..] = ip.octets();
+    ip.is_private()
+        || ip.is_loopback()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+        // Carrier-grade NAT, 100.64.0.0/10.
+        || (a == 100 && (64..128).contains(&b))
+}
+
+fn is_private_v6(ip: Ipv6Addr) -> bool {
+    let first = ip.segments()[0];
+    ip.is_loopback()
+        || ip.is_unspecified()
+        // Unique local, fc00::/7.
+        || (first & 0xfe00) == 0xfc00
+        // Link-local, fe80::/10.
+        || (first & 0xffc0) == 0xfe80
+}
+
+#[uniffi::export]
+impl ProxyClient {
+    /// Replace the policy applied to every redirect hop.
+    pub fn set_redirect_policy(&self, policy: RedirectPolicy) -> Result<(), ProxyError> {
+        let mut guard = self.lock_state();
+        let state = guard.as_mut().ok_or(ProxyError::NotInitialized)?;
+        state.config.redirect_policy = policy;
+        Ok(())
+    }
+}
+
+/// [`ProxyClient::set_redirect_policy`] on the default proxy.
+#[uniffi::export]
+pub fn proxy_set_redirect_policy(policy: RedirectPolicy) -> Result<(), ProxyError> {
+    default_client().set_redirect_policy(policy)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn url(s: &str) -> Url {
+        Url::parse(s).unwrap()
+    }
+
+    #[test]
+    fn default_policy_blocks_downgrade_and_private_targets() {
+        let policy = RedirectPolicy::default();
+        let from = url("https://mail.example/a.png");
+        assert!(check_redirect(&policy, &from, &url("https://cdn.example/a.png")).is_ok());
+        for to in [
+            "http://cdn.example/a.png",
+            "https://127.0.0.1/a.png",
+            "https://192.168.1.1/a.png",
+            "https://[::1]/a.png",
+            "https://localhost/a.png",
+        ] {
+            assert!(matches!(
+                check_redirect(&policy, &from, &url(to)),
+                Err(ProxyError::RedirectBlocked { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn cross_origin_is_opt_in() {
+        let strict = RedirectPolicy {
+            forbid_cross_origin: true,
+            ..RedirectPolicy::default()
+        };
+        let from = url("https://mail.example/a.png");
+        assert!(check_redirect(&strict, &from, &url("https://mail.example/b.png")).is_ok());
+        assert!(check_redirect(&strict, &from, &url("https://cdn.example/a.png")).is_err());
+    }
+
+    #[test]
+    fn host_and_scheme_changes_can_be_refused_separately() {
+        let same_host = RedirectPolicy {
+            forbid_cross_host: true,
+            ..RedirectPolicy::default()
+        };
+        let same_scheme = RedirectPolicy {
+            forbid_scheme_change: true,
+            ..RedirectPolicy::default()
+        };
+        let from = url("http://mail.example/a.png");
+        let upgrade = url("https://mail.example/a.png");
+        let other_port = url("http://mail.example:8080/a.png");
+        let other_host = url("http://cdn.example/a.png");
+        assert!(check_redirect(&same_host, &from, &upgrade).is_ok());
+        assert!(check_redirect(&same_host, &from, &other_port).is_ok());
+        assert!(check_redirect(&same_host, &from, &other_host).is_err());
+        assert!(check_redirect(&same_scheme, &from, &upgrade).is_err());
+        assert!(check_redirect(&same_scheme, &from, &other_host).is_ok());
+    }
+
+    #[test]
+    fn hops_pair_each_redirect_with_its_target() {
+        let chain = [
+            "https://t.example/open".to_string(),
+            "https://cdn.example/a.png".to_string(),
+        ];
+        let hops = hops(&chain, "http://cdn.example/a.png");
+        assert_eq!(hops.len(), 2);
+        assert_eq!(hops[0].to, "https://cdn.example/a.png");
+        assert!(hops[0].cross_host && !hops[0].scheme_changed);
+        assert!(!hops[1].cross_host && hops[1].scheme_changed && hops[1].downgrade);
+        assert!(super::hops(&[], "https://cdn.example/a.png").is_empty());
+    }
+
+    #[test]
+    fn private_ranges() {
+        for ip in [
+            "10.1.2.3",
+            "100.64.0.1",
+            "169.254.1.1",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+        ] {
+            assert!(is_private(ip.parse().unwrap()), "{ip}");
+        }
+        for ip in ["1.1.1.1", "100.128.0.1", "2606:4700::1111"] {
+            assert!(!is_private(ip.parse().unwrap()), "{ip}");
+        }
+    }
+}
@@ -0,0 +1,220 @@
+//! Scheduled refreshes of the WARP configuration.
+//!
+//! Cloudflare occasionally rotates the WARP peer key and endpoints. The
+//! stored identity (account, token and private key) stays valid, but a
+//! tunnel built from an old configuration stops completing handshakes. The
+//! configuration is therefore fetched again (`GET /reg/{id}`) once it is
+//! older than [`WarpRefresh::interval_secs`], or after
+//! [`WarpRefresh::after_handshake_failures`] failed handshakes in a row;
+//! both are checked before each request is handed to the tunnel. The fresh
+//! configuration is persisted and, if the peer changed, swapped into the
+//! running tunnel with [`TunnelManager::update_peer`](crate::tunnel::TunnelManager::update_peer):
+//! the worker thread, its DNS and TLS session caches and queued requests
+//! survive. A failed refresh is logged and retried no sooner than
+//! [`RETRY_AFTER`] later; requests carry on with the stored configuration.
+//!
+//! Like provisioning, the refresh goes to Cloudflare's API directly, not
+//! through the tunnel.
+
+use crate::admin::snapshot;
+use crate::block_on;
+use crate::client::{default_client, ProxyClient};
+use crate::config::WarpConfig;
+use crate::error::ProxyError;
+use crate::provisioning::WarpProvisioner;
+use crate::types::{WarpRefresh, WarpStoredConfig};
+use std::time::{Duration, Instant};
+
+/// Least time between two refreshes due to age or handshake failures.
+pub const RETRY_AFTER: Duration = Duration::from_secs(10 * 60);
+
+/// Whether `config` is due for a refresh under `policy` at `now` (seconds
+/// since the epoch), after `failures` failed handshakes in a row.
+fn is_due(config: &WarpConfig, policy: WarpRefresh, failures: u32, now: i64) -> bool {
+    let age = now.saturating_sub(config.last_updated);
+    let stale =
+        policy.interval_secs > 0 && age >= i64::try_from(policy.interval_secs).unwrap_or(i64::MAX);
+    let failing =
+        policy.after_handshake_failures > 0 && failures >= policy.after_handshake_failures;
+    stale || failing
+}
+
+/// Whether a refresh changes what the tunnel needs to know of its peer. The
+/// tunnel sends to a fixed anycast endpoint, so a rotated endpoint alone
+/// needs no swap.
+fn peer_changed(old: &WarpConfig, new: &WarpConfig) -> bool {
+    old.peer.public_key != new.peer.public_key
+        || old.client_id != new.client_id
+        || old.interface.address_ipv4 != new.interface.address_ipv4
+}
+
+impl ProxyClient {
+    /// Refresh the WARP configuration if it is due, logging a failure.
+    pub(crate) fn refresh_warp_config_if_due(&self) {
+        let due = {
+            let mut guard = self.lock_state();
+            let Some(state) = guard.as_mut() else {
+                return;
+            };
+            let failures = self.metrics.handshake_failure_streak();
+            let now = chrono::Utc::now().timestamp();
+            // Through an upstream proxy, WARP is not used and Cloudflare's
+            // API is left alone.
+            let due =
+                !state.paused
+                    && state.config.upstream_proxy.is_none()
+                    && state.config.warp_config.as_ref().is_some_and(|config| {
+                        is_due(config, state.config.warp_refresh, failures, now)
+                    })
+                    && state
+                        .warp_refresh_attempt
+                        .is_none_or(|attempt| attempt.elapsed() >= RETRY_AFTER);
+            if due {
+                state.warp_refresh_attempt = Some(Instant::now());
+            }
+            due
+        };
+        if due {
+            if let Err(e) = self.refresh_warp_config() {
+                log::warn!("WARP configuration refresh failed: {e}");
+            }
+        }
+    }
+}
+
+#[uniffi::export]
+impl ProxyClient {
+    /// Replace the schedule on which the WARP configuration is refreshed.
+    pub fn set_warp_refresh(&self, refresh: WarpRefresh) -> Result<(), ProxyError> {
+        let mut guard = self.lock_state();
+        let state = guard.as_mut().ok_or(ProxyError::NotInitialized)?;
+        state.config.warp_refresh = refresh;
+        Ok(())
+    }
+
+    /// Fetch the configuration of the stored WARP identity again now, persist
+    /// it and switch a running tunnel to a rotated peer. Unlike
+    /// [`Self::reset_identity`], the identity itself is kept.
+    ///
+    /// The network I/O runs outside the state lock. Fails with
+    /// `ProvisioningFailed` if there is no identity yet.
+    pub fn refresh_warp_config(&self) -> Result<WarpStoredConfig, ProxyError> {
+        let account = {
+            let guard = self.lock_state();
+            let state = guard.as_ref().ok_or(ProxyError::NotInitialized)?;
+            state
+                .config
+                .warp_config
+                .as_ref()
+                .map(|config| config.account.clone())
+                .ok_or_else(|| ProxyError::ProvisioningFailed {
+                    details: "No WARP identity to refresh".to_string(),
+                })?
+        };
+        let fresh = block_on(async {
+            let provisioner = WarpProvisioner::new()?;
+            provisioner.fetch_config(&account).await
+        })??;
+
+        let (manager, stored) = {
+            let mut guard = self.lock_state();
+            let state = guard.as_mut().ok_or(ProxyError::NotInitialized)?;
+            let Some(old) = state.config.warp_config.clone() else {
+                return Ok(snapshot(state));
+            };
+            if old.account.account_id != fresh.account.account_id {
+                // The identity was reset meanwhile; its configuration is newer.
+                return Ok(snapshot(state));
+            }
+            block_on(state.config.update_warp_config(fresh.clone()))??;
+            state.warp_refresh_attempt = None;
+            let manager = state.manager.clone().filter(|_| peer_changed(&old, &fresh));
+            (manager, snapshot(state))
+        };
+        self.metrics.reset_handshake_failure_streak();
+        log::info!("Refreshed the WARP configuration");
+        if let Some(manager) = manager {
+            log::info!("Switching the tunnel to the rotated WARP peer");
+            manager.update_peer(fresh)?;
+        }
+        Ok(stored)
+    }
+}
+
+/// [`ProxyClient::set_warp_refresh`] on the default proxy.
+#[uniffi::export]
+pub fn proxy_set_warp_refresh(refresh: WarpRefresh) -> Result<(), ProxyError> {
+    default_client().set_warp_refresh(refresh)
+}
+
+/// [`ProxyClient::refresh_warp_config`] on the default proxy.
+#[uniffi::export]
+pub fn proxy_refresh_warp_config() -> Result<WarpStoredConfig, ProxyError> {
+    default_client().refresh_warp_config()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{WarpAccountData, WarpInterfaceConfig, WarpPeerConfig};
+
+    fn config(last_updated: i64) -> WarpConfig {
+        WarpConfig {
+            account: WarpAccountData {
+                account_id: "device".to_string(),
+                access_token: "token".to_string(),
+                private_key: String::new(),
+                license_key: String::new(),
+            },
+            peer: WarpPeerConfig {
+                public_key: "peer".to_string(),
+                endpoint_host: "engage.cloudflareclient.com".to_string(),
+                endpoint_ipv4: "162.159.192.1".to_string(),
+                endpoint_port: 2408,
+            },
+            interface: WarpInterfaceConfig {
+                address_ipv4: "172.16.0.2".to_string(),
+            },
+            warp_enabled: true,
+            account_type: "free".to_string(),
+            last_updated,
+            client_id: String::new(),
+        }
+    }
+
+    #[test]
+    fn refreshes_when_old_or_failing() {
+        let policy = WarpRefresh {
+            interval_secs: 100,
+            after_handshake_failures: 3,
+        };
+        assert!(!is_due(&config(1_000), policy, 0, 1_099));
+        assert!(is_due(&config(1_000), policy, 0, 1_100));
+        assert!(!is_due(&config(1_000), policy, 2, 1_000));
+        assert!(is_due(&config(1_000), policy, 3, 1_000));
+
+        let never = WarpRefresh {
+            interval_secs: 0,
+            after_handshake_failures: 0,
+        };
+        assert!(!is_due(&config(0), never, 100, i64::MAX));
+        let huge = WarpRefresh {
+            interval_secs: u64::MAX,
+            ..policy
+        };
+        assert!(!is_due(&config(0), huge, 0, 1_000_000));
+    }
+
+    #[test]
+    fn only_peer_fields_need_a_swap() {
+        let old = config(0);
+        let mut new = config(500);
+        new.peer.endpoint_host = "engage.example".to_string();
+        assert!(!peer_changed(&old, &new));
+        new.peer.public_key = "rotated".to_string();
+        assert!(peer_changed(&old, &new));
+        let mut new = config(0);
+        new.client_id = "AQID".to_string();
+        assert!(peer_changed(&old, &new));
+    }
+}
@@ -0,0 +1,254 @@
+//! Fetching fonts and stylesheets.
+//!
+//! Email HTML references web fonts and CSS as well as images, and loading
+//! either directly would reveal the reader's IP address just like a remote
+//! image. [`proxy_fetch_resource`] carries them through the same tunnel,
+//! sensitive-header filter, HTTPS and redirect policies, and cache as images,
+//! but with its own size limit and a content-type allowlist per
+//! [`ResourceKind`].
+//!
+//! Fonts are recognised by their signature, so the common
+//! `application/octet-stream` label is accepted and an HTML error page served
+//! as `font/woff2` is not. Stylesheets must be labelled `text/css` and be
+//! valid UTF-8.
+//!
+//! Resources are cached under a [variant key](crate::cache::variant_key) of
+//! their URL, so they never shadow an image fetched from the same address.
+
+use crate::cache::variant_key;
+use crate::client::{default_client, ProxyClient};
+use crate::config::FetchLimits;
+use crate::error::ProxyError;
+use crate::integrity::sha256_hex;
+use crate::progress::progress_for;
+use crate::redirect;
+use crate::trace;
+use crate::types::{ImageResponse, ResourceKind, ResourceOptions, ResourceResponse};
+use crate::{header_pairs, validate_image_url};
+
+/// Default cap on a resource body.
+pub const DEFAULT_MAX_RESOURCE_SIZE: u64 = 4 * 1024 * 1024;
+
+/// Content types accepted for [`ResourceKind::Font`].
+const FONT_TYPES: &[&str] = &[
+    "font/woff",
+    "font/woff2",
+    "font/ttf",
+    "font/otf",
+    "font/sfnt",
+    "font/collection",
+    "application/font-woff",
+    "application/font-woff2",
+    "application/x-font-woff",
+    "application/x-font-ttf",
+    "application/x-font-otf",
+    "application/font-sfnt",
+    "application/vnd.ms-fontobject",
+    "application/octet-stream",
+];
+
+/// Content types accepted for [`ResourceKind::Stylesheet`].
+const STYLESHEET_TYPES: &[&str] = &["text/css"];
+
+#[uniffi::export]
+impl ProxyClient {
+    /// Fetch a font or stylesheet through the WARP tunnel.
+    pub fn fetch_resource(
+        &self,
+        url: String,
+        options: ResourceOptions,
+    ) -> Result<ResourceResponse, ProxyError> {
+        trace::traced(|| fetch_resource(self, &url, &options)).inspect_err(|e| {
+            self.record_error(Some(&url), e);
+        })
+    }
+}
+
+/// [`ProxyClient::fetch_resource`] on the default proxy.
+#[uniffi::export]
+pub fn proxy_fetch_resource(
+    url: String,
+    options: ResourceOptions,
+) -> Result<ResourceResponse, ProxyError> {
+    default_client().fetch_resource(url, options)
+}
+
+fn fetch_resource(
+    client: &ProxyClient,
+    url: &str,
+    options: &ResourceOptions,
+) -> Result<ResourceResponse, ProxyError> {
+    validate_image_url(url)?;
+    let key = variant_key(url, tag(options.kind));
+    {
+        let mut guard = client.lock_state();
+        let state = guard.as_mut().ok_or(ProxyError::NotInitialized)?;
+        if let Some(cached) = state.cache.get(&key) {
+            return Ok(ResourceResponse {
+                from_cache: true,
+                ..response(cached)
+            });
+        }
+    }
+
+    let (manager, limits) = client.acquire_manager()?;
+    let outcome = manager.fetch(
+        url.to_string(),
+        header_pairs(options.headers.as_ref()),
+        accept(options.kind).to_string(),
+        resource_limits(limits, options),
+        progress_for(url),
+    )?;
+
+    let Some(mime_type) = resource_mime(options.kind, &outcome.mime_type, &outcome.body) else {
+        return Err(ProxyError::InvalidContentType {
+            content_type: outcome.mime_type,
+        });
+    };
+    // Stored as an image entry so the memory and disk tiers apply unchanged.
+    let sha256 = sha256_hex(&outcome.body);
+    let entry = ImageResponse {
+        mime_type,
+        data: outcome.body,
+        from_cache: false,
+        redirect_hops: redirect::hops(&outcome.redirect_chain, &outcome.final_url),
+        final_url: outcome.final_url,
+        redirect_chain: outcome.redirect_chain,
+        width: None,
+        height: None,
+        blurhash: None,
+        tracking_suspected: false,
+        trace_id: String::new(),
+        headers: outcome.headers,
+        sha256,
+        transport: Some(outcome.transport),
+    };
+    if let Some(state) = client.lock_state().as_mut() {
+        state.cache.put(key, entry.clone());
+    }
+    Ok(response(entry))
+}
+
+/// The configured limits with the resource's own size cap.
+fn resource_limits(limits: FetchLimits, options: &ResourceOptions) -> FetchLimits {
+    FetchLimits {
+        max_size: options.max_size.unwrap_or(DEFAULT_MAX_RESOURCE_SIZE),
+        ..limits
+    }
+}
+
+fn accept(kind: ResourceKind) -> &'static str {
+    match kind {
+        ResourceKind::Font => "font/woff2,font/woff;q=0.9,font/*;q=0.8,*/*;q=0.1",
+        ResourceKind::Stylesheet => "text/css,*/*;q=0.1",
+    }
+}
+
+fn tag(kind: ResourceKind) -> &'static str {
+    match kind {
+        ResourceKind::Font => "font",
+        ResourceKind::Stylesheet => "css",
+    }
+}
+
+fn response(entry: ImageResponse) -> ResourceResponse {
+    ResourceResponse {
+        mime_type: entry.mime_type,
+        data: entry.data,
+        from_cache: entry.from_cache,
+        final_url: entry.final_url,
+        redirect_chain: entry.redirect_chain,
+    }
+}
+
+/// The MIME type to report for a `kind` resource labelled `claimed`, or
+/// `None` if the label is not allowed for `kind` or the body is not one.
+pub(crate) fn resource_mime(kind: ResourceKind, claimed: &str, data: &[u8]) -> Option<String> {
+    let allowed = match kind {
+        ResourceKind::Font => FONT_TYPES,
+        ResourceKind::Stylesheet => STYLESHEET_TYPES,
+    };
+    if !allowed.iter().any(|t| t.eq_ignore_ascii_case(claimed)) {
+        return None;
+    }
+    match kind {
+        ResourceKind::Font => font_mime(data).map(str::to_string),
+        ResourceKind::Stylesheet => std::str::from_utf8(data)
+            .is_ok()
+            .then(|| "text/css".to_string()),
+    }
+}
+
+/// Identify a font file by its signature.
+pub fn font_mime(data: &[u8]) -> Option<&'static str> {
+    match data.get(..4)? {
+        b"wOFF" => Some("font/woff"),
+        b"wOF2" => Some("font/woff2"),
+        b"\0\x01\0\0" | b"true" => Some("font/ttf"),
+        b"OTTO" => Some("font/otf"),
+        b"ttcf" => Some("font/collection"),
+        // EOT: the "LP" magic number follows the fixed header fields.
+        _ if data.get(34..36) == Some(b"LP") => Some("application/vnd.ms-fontobject"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fonts_are_identified_by_signature() {
+        assert_eq!(font_mime(b"wOF2\0\x01\0\0"), Some("font/woff2"));
+        assert_eq!(font_mime(b"wOFFOTTO"), Some("font/woff"));
+        assert_eq!(font_mime(b"\0\x01\0\0\0\x0c"), Some("font/ttf"));
+        assert_eq!(font_mime(b"OTTO\0\x0b"), Some("font/otf"));
+        let mut eot = vec![0u8; 36];
+        eot[34..].copy_from_slice(b"LP");
+        assert_eq!(font_mime(&eot), Some("application/vnd.ms-fontobject"));
+        assert_eq!(font_mime(b"<!DOCTYPE html>"), None);
+        assert_eq!(font_mime(b"wO"), None);
+    }
+
+    #[test]
+    fn resource_types_follow_the_kind() {
+        let css = b"body { font-family: x; }";
+        assert_eq!(
+            resource_mime(ResourceKind::Stylesheet, "text/css", css).as_deref(),
+            Some("text/css")
+        );
+        assert_eq!(
+            resource_mime(ResourceKind::Stylesheet, "text/html", css),
+            None
+        );
+        assert_eq!(
+            resource_mime(ResourceKind::Stylesheet, "text/css", b"\xff\xfe"),
+            None
+        );
+        assert_eq!(
+            resource_mime(ResourceKind::Font, "application/octet-stream", b"wOF2....").as_deref(),
+            Some("font/woff2")
+        );
+        assert_eq!(
+            resource_mime(ResourceKind::Font, "font/woff2", b"<html>"),
+            None
+        );
+        assert_eq!(resource_mime(ResourceKind::Font, "text/css", css), None);
+    }
+
+    #[test]
+    fn limits_use_the_resource_cap() {
+        let options = ResourceOptions {
+            kind: ResourceKind::Stylesheet,
+            headers: None,
+            max_size: None,
+        };
+        let limits = resource_limits(FetchLimits::default(), &options);
+        assert_eq!(limits.max_size, DEFAULT_MAX_RESOURCE_SIZE);
+        let capped = ResourceOptions {
+            max_size: Some(1024),
+            ..options
+        };
+        assert_eq!(resource_limits(limits, &capped).max_size, 1024);
+    }
+}
@@ -0,0 +1,135 @@
+//! Automatic retries for transient fetch failures.
+//!
+//! Connection resets, timeouts and "try again later" statuses (502/503, …) are
+//! retried with exponential backoff, so a flaky hop doesn't surface in the UI
+//! as a broken image. The HTTP layer says when to try again and the tunnel
+//! worker requeues the request for then, serving others in the meantime.
+//! Every request the proxy makes
+//! is a GET, so repeating one is always safe; a retry is still refused once any
+//! body bytes have reached the caller's writer, as they cannot be taken back.
+
+use crate::client::{default_client, ProxyClient};
+use crate::error::ProxyError;
+use crate::types::RetryPolicy;
+use std::time::Duration;
+
+/// Whether `error` is worth another attempt under `policy`.
+///
+/// Transport-level failures are transient; so are HTTP errors with one of the
+/// policy's retryable status codes. Everything else (bad URL, wrong content
+/// type, size limit, TLS rejection) will fail the same way again.
+pub(crate) fn is_transient(policy: &RetryPolicy, error: &ProxyError) -> bool {
+    match error {
+        ProxyError::Timeout { .. }
+        | ProxyError::TunnelError { .. }
+        | ProxyError::DnsError { .. } => true,
+        ProxyError::HttpError { status_code: 0, .. } => true,
+        ProxyError::HttpError { status_code, .. } => {
+            policy.retryable_status_codes.contains(status_code)
+        }
+        _ => false,
+    }
+}
+
+/// Delay before retry number `retry` (1-based).
+///
+/// Doubles from `initial_backoff_ms`, capped at `max_backoff_ms`. A server's
+/// `Retry-After` (in seconds) replaces the computed delay, under the same cap.
+/// Up to a quarter of the delay is randomised so that clients retrying the
+/// same outage don't do so in lockstep.
+pub(crate) fn backoff(policy: &RetryPolicy, retry: u32, retry_after: Option<u64>) -> Duration {
+    let cap = policy.max_backoff_ms;
+    let base = match retry_after {
+        Some(secs) => secs.saturating_mul(1000).min(cap),
+        None => policy
+            .initial_backoff_ms
+            .saturating_mul(1u64 << retry.saturating_sub(1).min(32))
+            .min(cap),
+    };
+    let jitter = rand::random::<u64>() % (base / 4 + 1);
+    Duration::from_millis(base - jitter)
+}
+
+/// Parse a delta-seconds `Retry-After` value (HTTP dates are ignored).
+pub(crate) fn parse_retry_after(value: &str) -> Option<u64> {
+    value.trim().parse().ok()
+}
+
+#[uniffi::export]
+impl ProxyClient {
+    /// Replace the retry policy used by all subsequent fetches.
+    ///
+    /// A `max_attempts` of 0 is treated as 1, i.e. no retries.
+    pub fn set_retry_policy(&self, policy: RetryPolicy) -> Result<(), ProxyError> {
+        let mut guard = self.lock_state();
+        let state = guard.as_mut().ok_or(ProxyError::NotInitialized)?;
+        state.config.retry_policy = RetryPolicy {
+            max_attempts: policy.max_attempts.max(1),
+            ..policy
+        };
+        Ok(())
+    }
+}
+
+/// [`ProxyClient::set_retry_policy`] on the default proxy.
+#[uniffi::export]
+pub fn proxy_set_retry_policy(policy: RetryPolicy) -> Result<(), ProxyError> {
+    default_client().set_retry_policy(policy)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::TimeoutPhase;
+
+    #[test]
+    fn backoff_doubles_up_to_cap_with_bounded_jitter() {
+        let policy = RetryPolicy {
+            initial_backoff_ms: 100,
+            max_backoff_ms: 350,
+            ..RetryPolicy::default()
+        };
+        for (retry, nominal) in [(1, 100), (2, 200), (3, 350), (40, 350)] {
+            let delay = backoff(&policy, retry, None).as_millis() as u64;
+            assert!(
+                delay <= nominal && delay >= nominal - nominal / 4,
+                "{delay}"
+            );
+        }
+    }
+
+    #[test]
+    fn retry_after_overrides_but_respects_cap() {
+        let policy = RetryPolicy::default();
+        let delay = backoff(&policy, 1, Some(3600));
+        assert!(delay <= Duration::from_millis(policy.max_backoff_ms));
+        assert_eq!(parse_retry_after(" 2 "), Some(2));
+        assert_eq!(parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"), None);
+    }
+
+    #[test]
+    fn only_transient_errors_are_retried() {
+        let policy = RetryPolicy::default();
+        let http = |status_code| ProxyError::HttpError {
+            status_code,
+            details: String::new(),
+        };
+        assert!(is_transient(
+            &policy,
+            &ProxyError::Timeout {
+                phase: TimeoutPhase::Connect,
+                seconds: 30,
+            }
+        ));
+        assert!(is_transient(&policy, &http(0)));
+        assert!(is_transient(&policy, &http(503)));
+        assert!(!is_transient(&policy, &http(404)));
+        assert!(!is_transient(
+            &policy,
+            &ProxyError::ResponseTooLarge {
+                size: 2,
+                max_size: 1
+            }
+        ));
+    }
+}
@@ -0,0 +1,127 @@
+//! Plain-HTTP handling: allow, upgrade to HTTPS, or reject.
+//!
+//! Mail clients that want secure-only remote content pick an [`HttpsMode`]
+//! with [`proxy_set_https_mode`]. The HTTP layer applies it to the requested
+//! URL and to every redirect target, so an `https` image cannot be reached
+//! through a plaintext hop the mode forbids.
+
+use crate::client::{default_client, ProxyClient};
+use crate::error::ProxyError;
+use crate::types::HttpsMode;
+use url::Url;
+
+/// Apply `mode` to `url`.
+///
+/// Returns the URL to request and, when an `http` URL was upgraded under
+/// [`HttpsMode::UpgradeWithFallback`], the original URL to fall back to if the
+/// HTTPS attempt cannot connect.
+pub(crate) fn apply(mode: HttpsMode, url: Url) -> Result<(Url, Option<Url>), ProxyError> {
+    if url.scheme() != "http" {
+        return Ok((url, None));
+    }
+    match mode {
+        HttpsMode::AllowHttp => Ok((url, None)),
+        HttpsMode::HttpsOnly => Err(ProxyError::InsecureUrl {
+            url: url.to_string(),
+        }),
+        HttpsMode::Upgrade => Ok((upgrade(&url)?, None)),
+        HttpsMode::UpgradeWithFallback => Ok((upgrade(&url)?, Some(url))),
+    }
+}
+
+/// Rewrite an `http` URL to `https`, mapping an explicit port 80 to the default.
+fn upgrade(url: &Url) -> Result<Url, ProxyError> {
+    let mut secure = url.clone();
+    let port = url.port().filter(|&p| p != 80);
+    let failed = |_| ProxyError::InvalidUrl {
+        url: url.to_string(),
+        details: "Cannot upgrade URL to https".to_string(),
+    };
+    secure.set_port(None).map_err(failed)?;
+    secure.set_scheme("https").map_err(failed)?;
+    secure.set_port(port).map_err(failed)?;
+    Ok(secure)
+}
+
+/// Whether a failed HTTPS attempt may be retried over plain HTTP.
+///
+/// Only failures to establish a working connection qualify; a server that
+/// answered over HTTPS (even with an error status) is authoritative.
+pub(crate) fn may_fall_back(error: &ProxyError) -> bool {
+    matches!(
+        error,
+        ProxyError::TlsError { .. }
+            | ProxyError::TunnelError { .. }
+            | ProxyError::Timeout { .. }
+            | ProxyError::HttpError { status_code: 0, .. }
+    )
+}
+
+#[uniffi::export]
+impl ProxyClient {
+    /// Choose how `http://` URLs are treated from now on.
+    pub fn set_https_mode(&self, mode: HttpsMode) -> Result<(), ProxyError> {
+        let mut guard = self.lock_state();
+        let state = guard.as_mut().ok_or(ProxyError::NotInitialized)?;
+        state.config.https_mode = mode;
+        Ok(())
+    }
+}
+
+/// [`ProxyClient::set_https_mode`] on the default proxy.
+#[uniffi::export]
+pub fn proxy_set_https_mode(mode: HttpsMode) -> Result<(), ProxyError> {
+    default_client().set_https_mode(mode)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn url(s: &str) -> Url {
+        Url::parse(s).unwrap()
+    }
+
+    #[test]
+    fn modes_treat_http_differently() {
+        let plain = url("http://img.example/a.png?x=1");
+        assert_eq!(
+            apply(HttpsMode::AllowHttp, plain.clone()).unwrap(),
+            (plain.clone(), None)
+        );
+        let secure = url("https://img.example/a.png?x=1");
+        assert_eq!(
+            apply(HttpsMode::Upgrade, plain.clone()).unwrap(),
+            (secure.clone(), None)
+        );
+        assert_eq!(
+            apply(HttpsMode::UpgradeWithFallback, plain.clone()).unwrap(),
+            (secure, Some(plain.clone()))
+        );
+        assert!(matches!(
+            apply(HttpsMode::HttpsOnly, plain),
+            Err(ProxyError::InsecureUrl { .. })
+        ));
+    }
+
+    #[test]
+    fn https_urls_pass_untouched() {
+        let secure = url("https://img.example/a.png");
+        assert_eq!(
+            apply(HttpsMode::HttpsOnly, secure.clone()).unwrap(),
+            (secure, None)
+        );
+    }
+
+    #[test]
+    fn upgrade_keeps_custom_ports_and_drops_port_80() {
+        assert_eq!(
+            upgrade(&url("http://img.example:80/a")).unwrap().as_str(),
+            "https://img.example/a"
+        );
+        assert_eq!(
+            upgrade(&url("http://img.example:8080/a")).unwrap().as_str(),
+            "https://img.example:8080/a"
+        );
+    }
+}
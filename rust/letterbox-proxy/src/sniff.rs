@@ -0,0 +1,287 @@
+//! Magic-byte content sniffing.
+//!
+//! Pure helpers that recognise image formats from their leading bytes, used to
+//! check that a response really is the image type its `Content-Type` claims —
+//! and to correct the type when a server mislabels a real image.
+
+/// How far into a body to look for an `<svg` root element.
+const SVG_SNIFF_LEN: usize = 1024;
+
+/// JPEG XL signatures: a bare codestream, or the ISO BMFF-style container.
+const JXL_CODESTREAM: [u8; 2] = [0xFF, 0x0A];
+const JXL_CONTAINER: [u8; 12] = [
+    0, 0, 0, 0x0C, b'J', b'X', b'L', b' ', 0x0D, 0x0A, 0x87, 0x0A,
+];
+
+/// Guess the MIME type from file magic bytes.
+pub fn guess_mime_type(data: &[u8]) -> Option<&'static str> {
+    if data.len() < 4 {
+        return None;
+    }
+    if data.starts_with(&JXL_CODESTREAM) || data.starts_with(&JXL_CONTAINER) {
+        return Some("image/jxl");
+    }
+    if let Some(mime) = ftyp_mime(data) {
+        return Some(mime);
+    }
+
+    match &data[..4] {
+        [0x89, 0x50, 0x4E, 0x47] => Some("image/png"),
+        [0xFF, 0xD8, 0xFF, _] => Some("image/jpeg"),
+        [0x47, 0x49, 0x46, 0x38] => Some("image/gif"),
+        [0x52, 0x49, 0x46, 0x46] if data.len() >= 12 && &data[8..12] == b"WEBP" => {
+            Some("image/webp")
+        }
+        [0x42, 0x4D, _, _] => Some("image/bmp"),
+        [0x00, 0x00, 0x01, 0x00] => Some("image/x-icon"),
+        _ => {
+            if data.len() >= 5 {
+                let start = String::from_utf8_lossy(&data[..std::cmp::min(100, data.len())]);
+                if start.contains("<svg") || start.contains("<?xml") {
+                    return Some("image/svg+xml");
+                }
+            }
+            None
+        }
+    }
+}
+
+/// Identify AVIF and HEIC/HEIF from an ISO BMFF `ftyp` box.
+///
+/// The major brand usually decides it, else the compatible brands; a file
+/// only carrying the generic `mif1`/`msf1` brands is plain HEIF.
+fn ftyp_mime(data: &[u8]) -> Option<&'static str> {
+    if data.len() < 12 || &data[4..8] != b"ftyp" {
+        return None;
+    }
+    let box_len = u32::from_be_bytes([data[0], data[1], data[2], data[3]]) as usize;
+    let end = box_len.clamp(12, data.len());
+    let brand = |b: &[u8]| match b {
+        b"avif" | b"avis" => Some("image/avif"),
+        b"heic" | b"heix" | b"heim" | b"heis" | b"hevc" | b"hevx" => Some("image/heic"),
+        _ => None,
+    };
+    // Compatible brands follow the 4-byte minor version.
+    let compatible = data.get(16..end).unwrap_or(&[]).chunks_exact(4);
+    let brands = || std::iter::once(&data[8..12]).chain(compatible.clone());
+    brands().find_map(brand).or_else(|| {
+        brands()
+            .any(|b| b == b"mif1" || b == b"msf1")
+            .then_some("image/heif")
+    })
+}
+
+/// Whether two MIME types name the same image format.
+fn same_format(claimed: &str, detected: &str) -> bool {
+    fn family(mime: &str) -> &str {
+        let subtype = mime.split('/').nth(1).unwrap_or("");
+        if subtype.contains("icon") {
+            "icon"
+        } else if matches!(subtype, "heic" | "heif" | "heic-sequence" | "heif-sequence") {
+            "heif"
+        } else {
+            subtype
+        }
+    }
+    claimed == detected || family(claimed) == family(detected)
+}
+
+/// Validate that response data matches the claimed MIME type.
+pub fn validate_image_data(data: &[u8], claimed_mime: &str) -> bool {
+    if data.is_empty() {
+        return false;
+    }
+
+    if claimed_mime == "image/svg+xml" {
+        let start = String::from_utf8_lossy(&data[..std::cmp::min(100, data.len())]);
+        return start.contains("<svg")
+            || start.contains("<?xml")
+            || start.contains("<!DOCTYPE svg");
+    }
+
+    guess_mime_type(data).is_none_or(|detected| same_format(claimed_mime, detected))
+}
+
+/// The MIME type to report for a response claimed to be `claimed`, or `None`
+/// if it is not an image at all.
+///
+/// Servers frequently send real images as `application/octet-stream` or
+/// `text/plain`, or name the wrong image format. A format recognised from the
+/// leading bytes wins over a claim it contradicts; a claimed `image/*` type the
+/// bytes do not contradict is kept. A non-image claim is only overridden for
+/// SVG when an `<svg` element is actually present, not for any XML.
+pub fn effective_image_mime(claimed: &str, data: &[u8]) -> Option<String> {
+    let is_image_claim = claimed.starts_with("image/");
+    let sniffed = guess_mime_type(data).filter(|&m| m != "image/svg+xml" || has_svg_root(data));
+    match sniffed {
+        Some(_) if is_image_claim && validate_image_data(data, claimed) => {
+            Some(claimed.to_string())
+        }
+        Some(sniffed) => Some(sniffed.to_string()),
+        None => is_image_claim.then(|| claimed.to_string()),
+    }
+}
+
+fn has_svg_root(data: &[u8]) -> bool {
+    String::from_utf8_lossy(&data[..data.len().min(SVG_SNIFF_LEN)]).contains("<svg")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    #[test]
+    fn guess_png() {
+        assert_eq!(
+            guess_mime_type(&[0x89, 0x50, 0x4E, 0x47]),
+            Some("image/png")
+        );
+    }
+
+    #[test]
+    fn guess_jpeg() {
+        assert_eq!(
+            guess_mime_type(&[0xFF, 0xD8, 0xFF, 0xE0]),
+            Some("image/jpeg")
+        );
+    }
+
+    #[test]
+    fn guess_webp() {
+        let data = [0x52, 0x49, 0x46, 0x46, 0, 0, 0, 0, b'W', b'E', b'B', b'P'];
+        assert_eq!(guess_mime_type(&data), Some("image/webp"));
+    }
+
+    fn ftyp(major: &[u8; 4], compatible: &[&[u8; 4]]) -> Vec<u8> {
+        let len = 16 + 4 * compatible.len() as u32;
+        let mut data = len.to_be_bytes().to_vec();
+        data.extend_from_slice(b"ftyp");
+        data.extend_from_slice(major);
+        data.extend_from_slice(&[0, 0, 0, 0]);
+        for brand in compatible {
+            data.extend_from_slice(*brand);
+        }
+        data
+    }
+
+    #[test]
+    fn guess_modern_formats() {
+        assert_eq!(
+            guess_mime_type(&ftyp(b"avif", &[b"mif1"])),
+            Some("image/avif")
+        );
+        assert_eq!(guess_mime_type(&ftyp(b"heic", &[])), Some("image/heic"));
+        assert_eq!(
+            guess_mime_type(&ftyp(b"mif1", &[b"miaf", b"avif"])),
+            Some("image/avif")
+        );
+        assert_eq!(
+            guess_mime_type(&ftyp(b"mif1", &[b"miaf"])),
+            Some("image/heif")
+        );
+        // An MP4 video is not an image.
+        assert_eq!(guess_mime_type(&ftyp(b"isom", &[b"mp41"])), None);
+        assert_eq!(
+            guess_mime_type(&[0xFF, 0x0A, 0xFA, 0x1F]),
+            Some("image/jxl")
+        );
+        assert_eq!(
+            guess_mime_type(&[0, 0, 0, 0x0C, b'J', b'X', b'L', b' ', 0x0D, 0x0A, 0x87, 0x0A]),
+            Some("image/jxl")
+        );
+    }
+
+    #[test]
+    fn validate_modern_formats() {
+        let heic = ftyp(b"heic", &[b"mif1"]);
+        assert!(validate_image_data(&heic, "image/heic"));
+        assert!(validate_image_data(&heic, "image/heif"));
+        assert!(!validate_image_data(&heic, "image/avif"));
+        assert!(validate_image_data(&ftyp(b"avif", &[]), "image/avif"));
+        assert!(!validate_image_data(&[0xFF, 0x0A, 0, 0], "image/png"));
+    }
+
+    #[test]
+    fn validate_rejects_empty() {
+        assert!(!validate_image_data(&[], "image/png"));
+    }
+
+    #[test]
+    fn validate_accepts_matching_png() {
+        assert!(validate_image_data(
+            &[0x89, 0x50, 0x4E, 0x47, 0x0D],
+            "image/png"
+        ));
+    }
+
+    #[test]
+    fn validate_svg_by_marker() {
+        assert!(validate_image_data(
+            b"<svg xmlns=...></svg>",
+            "image/svg+xml"
+        ));
+    }
+
+    #[test]
+    fn effective_mime_overrides_generic_types() {
+        let png = [0x89, 0x50, 0x4E, 0x47, 0x0D];
+        assert_eq!(
+            effective_image_mime("application/octet-stream", &png).as_deref(),
+            Some("image/png")
+        );
+        assert_eq!(
+            effective_image_mime("text/plain", b"<?xml version=\"1.0\"?><svg/>").as_deref(),
+            Some("image/svg+xml")
+        );
+        assert_eq!(effective_image_mime("text/html", b"<html></html>"), None);
+        assert_eq!(
+            effective_image_mime("text/xml", b"<?xml version=\"1.0\"?><rss/>"),
+            None
+        );
+    }
+
+    #[test]
+    fn effective_mime_corrects_wrong_image_types() {
+        let png = [0x89, 0x50, 0x4E, 0x47, 0x0D];
+        assert_eq!(
+            effective_image_mime("image/jpeg", &png).as_deref(),
+            Some("image/png")
+        );
+        // Claims the bytes don't contradict are kept as-is.
+        assert_eq!(
+            effective_image_mime("image/png", &png).as_deref(),
+            Some("image/png")
+        );
+        assert_eq!(
+            effective_image_mime("image/x-portable-anymap", b"P6 1 1 255 ").as_deref(),
+            Some("image/x-portable-anymap")
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn sniffed_types_validate_against_themselves(data in vec(any::<u8>(), 0..256)) {
+            if let Some(mime) = guess_mime_type(&data) {
+                prop_assert!(validate_image_data(&data, mime), "{mime}");
+            }
+        }
+
+        #[test]
+        fn effective_types_are_images(
+            claimed in "(image|text|application)/[a-z+.-]{0,12}",
+            data in vec(any::<u8>(), 0..256),
+        ) {
+            if let Some(mime) = effective_image_mime(&claimed, &data) {
+                prop_assert!(mime.starts_with("image/"), "{mime}");
+            }
+        }
+
+        #[test]
+        fn magic_bytes_win_over_the_tail(tail in vec(any::<u8>(), 0..64)) {
+            let png = [&[0x89, 0x50, 0x4E, 0x47][..], &tail].concat();
+            prop_assert_eq!(guess_mime_type(&png), Some("image/png"));
+        }
+    }
+}
@@ -0,0 +1,213 @@
+//! Picking one image out of a `srcset`.
+//!
+//! Newsletters often list several sizes of an image in `srcset`. Fetching the
+//! largest wastes bandwidth on a phone-width message view, so
+//! [`ProxyClient::fetch_best_source`] picks the candidate a browser would and
+//! fetches only that one. Candidates are ranked by the density they would be
+//! shown at in the slot: those at least as sharp as the screen come first,
+//! least sharp first, then the rest, sharpest first. With `probe_sizes`, each
+//! candidate is probed with a `HEAD` first. Candidates that fail the probe are
+//! skipped, and among sharp enough ones the fewest bytes wins, e.g. a 2x WebP
+//! smaller than the 1x PNG.
+
+use crate::client::{default_client, ProxyClient};
+use crate::error::ProxyError;
+use crate::fetch::fetch_image_async;
+use crate::probe::probe_async;
+use crate::types::{ImageResponse, RequestPriority, SrcsetCandidate};
+use std::cmp::Ordering;
+
+/// A candidate with the density it would be shown at.
+#[derive(Debug)]
+struct Ranked {
+    url: String,
+    density: f32,
+    sharp_enough: bool,
+}
+
+/// `sources` in order of preference for a slot `target_width` device pixels
+/// wide on a screen of `pixel_density`.
+fn rank(sources: Vec<SrcsetCandidate>, target_width: u32, pixel_density: f32) -> Vec<Ranked> {
+    let pixel_density = if pixel_density.is_finite() && pixel_density > 0.0 {
+        pixel_density
+    } else {
+        1.0
+    };
+    // Width of the slot in CSS pixels, which `w` descriptors are relative to.
+    let slot_width = (target_width.max(1) as f32 / pixel_density).max(f32::MIN_POSITIVE);
+    let mut ranked: Vec<Ranked> = sources
+        .into_iter()
+        .map(|candidate| {
+            let density = match (candidate.width, candidate.density) {
+                (Some(width), _) => width as f32 / slot_width,
+                (None, Some(density)) if density.is_finite() && density > 0.0 => density,
+                _ => 1.0,
+            };
+            Ranked {
+                url: candidate.url,
+                density,
+                sharp_enough: density >= pixel_density,
+            }
+        })
+        .collect();
+    ranked.sort_by(|a, b| match (a.sharp_enough, b.sharp_enough) {
+        (true, true) => a.density.total_cmp(&b.density),
+        (false, false) => b.density.total_cmp(&a.density),
+        (true, false) => Ordering::Less,
+        (false, true) => Ordering::Greater,
+    });
+    ranked
+}
+
+/// The URL to fetch out of `ranked`, probing each candidate first if `probe`.
+async fn choose(
+    client: &ProxyClient,
+    ranked: Vec<Ranked>,
+    probe: bool,
+) -> Result<String, ProxyError> {
+    if !probe {
+        return ranked
+            .into_iter()
+            .next()
+            .map(|candidate| candidate.url)
+            .ok_or_else(no_candidates);
+    }
+    let mut first_error = None;
+    let mut best: Option<(Ranked, Option<u64>)> = None;
+    for candidate in ranked {
+        let size = match probe_async(client, &candidate.url).await {
+            Ok(probe) => probe.content_length,
+            Err(e) => {
+                first_error.get_or_insert(e);
+                continue;
+            }
+        };
+        best = match best {
+            None => Some((candidate, size)),
+            // Ranked sharp enough first: later ones replace a sharp enough
+            // pick only by being sharp enough and known to be smaller.
+            Some((pick, pick_size)) => {
+                let smaller = matches!((size, pick_size), (Some(s), Some(p)) if s < p);
+                if pick.sharp_enough && candidate.sharp_enough && smaller {
+                    Some((candidate, size))
+                } else {
+                    Some((pick, pick_size))
+                }
+            }
+        };
+    }
+    match (best, first_error) {
+        (Some((pick, _)), _) => Ok(pick.url),
+        (None, Some(e)) => Err(e),
+        (None, None) => Err(no_candidates()),
+    }
+}
+
+fn no_candidates() -> ProxyError {
+    ProxyError::InvalidUrl {
+        url: String::new(),
+        details: "No srcset candidates".to_string(),
+    }
+}
+
+#[uniffi::export]
+impl ProxyClient {
+    /// Fetch the one image of `sources` best suited to a slot `target_width`
+    /// device pixels wide on a screen of `pixel_density`, as a browser would
+    /// choose it: the least sharp candidate at least as sharp as the screen,
+    /// else the sharpest.
+    ///
+    /// With `probe_sizes`, every candidate is probed first: those that fail
+    /// are skipped, and the smallest sharp enough one by `Content-Length` is
+    /// fetched. [`ImageResponse::final_url`] tells which one it was.
+    #[uniffi::method(default(pixel_density = 1.0, probe_sizes = false))]
+    pub async fn fetch_best_source(
+        &self,
+        sources: Vec<SrcsetCandidate>,
+        target_width: u32,
+        pixel_density: f32,
+        probe_sizes: bool,
+    ) -> Result<ImageResponse, ProxyError> {
+        let ranked = rank(sources, target_width, pixel_density);
+        let url = choose(self, ranked, probe_sizes)
+            .await
+            .inspect_err(|e| self.record_error(None, e))?;
+        fetch_image_async(self, &url, None, RequestPriority::Visible, None, None)
+            .await
+            .inspect_err(|e| {
+                self.record_error(Some(&url), e);
+            })
+    }
+}
+
+/// [`ProxyClient::fetch_best_source`] through the default proxy.
+#[uniffi::export(default(pixel_density = 1.0, probe_sizes = false))]
+pub async fn proxy_fetch_best_source(
+    sources: Vec<SrcsetCandidate>,
+    target_width: u32,
+    pixel_density: f32,
+    probe_sizes: bool,
+) -> Result<ImageResponse, ProxyError> {
+    default_client()
+        .fetch_best_source(sources, target_width, pixel_density, probe_sizes)
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(url: &str, width: Option<u32>, density: Option<f32>) -> SrcsetCandidate {
+        SrcsetCandidate {
+            url: url.to_string(),
+            width,
+            density,
+        }
+    }
+
+    fn urls(ranked: &[Ranked]) -> Vec<&str> {
+        ranked.iter().map(|r| r.url.as_str()).collect()
+    }
+
+    #[test]
+    fn ranks_width_candidates_for_the_slot() {
+        let sources = vec![
+            candidate("a-1600", Some(1600), None),
+            candidate("a-400", Some(400), None),
+            candidate("a-800", Some(800), None),
+        ];
+        // 360 CSS pixels at 2x: 400w is 1.1x, too blurry; 800w is 2.2x.
+        let ranked = rank(sources.clone(), 720, 2.0);
+        assert_eq!(urls(&ranked), ["a-800", "a-1600", "a-400"]);
+        assert!(ranked[0].sharp_enough && !ranked[2].sharp_enough);
+
+        // A slot wider than every candidate takes the largest.
+        assert_eq!(urls(&rank(sources, 4000, 1.0))[0], "a-1600");
+    }
+
+    #[test]
+    fn ranks_density_candidates_for_the_screen() {
+        let sources = vec![
+            candidate("b-1x", None, None),
+            candidate("b-3x", None, Some(3.0)),
+            candidate("b-2x", None, Some(2.0)),
+        ];
+        assert_eq!(
+            urls(&rank(sources.clone(), 300, 2.0)),
+            ["b-2x", "b-3x", "b-1x"]
+        );
+        assert_eq!(urls(&rank(sources.clone(), 300, 1.0))[0], "b-1x");
+        assert_eq!(urls(&rank(sources.clone(), 300, 4.0))[0], "b-3x");
+        // A nonsensical screen density counts as 1x.
+        assert_eq!(urls(&rank(sources, 300, f32::NAN))[0], "b-1x");
+    }
+
+    #[test]
+    fn no_candidates_is_an_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let client = ProxyClient::new(dir.path().to_string_lossy().into_owned(), 10).unwrap();
+        let result =
+            futures::executor::block_on(client.fetch_best_source(Vec::new(), 300, 1.0, true));
+        assert!(matches!(result, Err(ProxyError::InvalidUrl { .. })));
+    }
+}
@@ -0,0 +1,147 @@
+//! Raw byte streams through the tunnel.
+//!
+//! Some of the app's other requests deserve the same anonymity as images,
+//! e.g. fetching a newsletter's unsubscribe page. [`ProxyClient::open_stream`]
+//! opens a TCP connection to any host and port over the client's transport —
+//! WARP, or the upstream proxy if one is set — and hands it to the app as a
+//! [`ProxyStream`], much as an HTTP `CONNECT` would. The host is resolved over
+//! DoH like an image's. The stream carries bytes only: the app speaks the
+//! protocol, TLS included, over it.
+//!
+//! The connection lives on the tunnel worker (see [`crate::tunnel::worker`]),
+//! which serves reads and writes between image fetches. A stream keeps the
+//! tunnel it was opened on, even after the client moves to another.
+
+use crate::client::{default_client, ProxyClient};
+use crate::error::ProxyError;
+use crate::tunnel::TunnelManager;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Most bytes one [`ProxyStream::read`] returns.
+pub const MAX_READ: u32 = 64 * 1024;
+
+/// A TCP connection through the tunnel, closed when dropped.
+#[derive(uniffi::Object)]
+pub struct ProxyStream {
+    manager: Arc<TunnelManager>,
+    id: u64,
+    closed: AtomicBool,
+}
+
+#[uniffi::export]
+impl ProxyStream {
+    /// Up to `max_len` bytes (at most [`MAX_READ`]), waiting up to
+    /// `timeout_ms` for some to arrive. Empty once the server closed the
+    /// stream; fails with [`ProxyError::Timeout`] if nothing arrived in time.
+    pub fn read(&self, max_len: u32, timeout_ms: u32) -> Result<Vec<u8>, ProxyError> {
+        let max_len = max_len.clamp(1, MAX_READ) as usize;
+        self.manager
+            .read_stream(self.id, max_len, millis(timeout_ms))
+    }
+
+    /// Send all of `data`, failing with [`ProxyError::Timeout`] if the
+    /// connection does not take it within `timeout_ms`.
+    pub fn write(&self, data: Vec<u8>, timeout_ms: u32) -> Result<(), ProxyError> {
+        self.manager.write_stream(self.id, data, millis(timeout_ms))
+    }
+
+    /// Close the connection; reads and writes fail afterwards.
+    pub fn close(&self) {
+        if !self.closed.swap(true, Ordering::AcqRel) {
+            self.manager.close_stream(self.id);
+        }
+    }
+}
+
+impl Drop for ProxyStream {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+fn millis(ms: u32) -> Duration {
+    Duration::from_millis(ms.into())
+}
+
+#[uniffi::export]
+impl ProxyClient {
+    /// Open a TCP connection to `host` (a name or IPv4 address) on `port`
+    /// through the tunnel, as a raw byte stream for the app.
+    pub fn open_stream(&self, host: String, port: u16) -> Result<Arc<ProxyStream>, ProxyError> {
+        if host.is_empty() || port == 0 {
+            return Err(ProxyError::InvalidUrl {
+                url: format!("{host}:{port}"),
+                details: "A stream needs a host and a port".to_string(),
+            });
+        }
+        let (manager, limits) = self.acquire_manager()?;
+        let id = manager
+            .open_stream(host.to_ascii_lowercase(), port, limits)
+            .inspect_err(|e| self.record_error(None, e))?;
+        Ok(Arc::new(ProxyStream {
+            manager,
+            id,
+            closed: AtomicBool::new(false),
+        }))
+    }
+}
+
+/// [`ProxyClient::open_stream`] through the default proxy.
+#[uniffi::export]
+pub fn proxy_open_stream(host: String, port: u16) -> Result<Arc<ProxyStream>, ProxyError> {
+    default_client().open_stream(host, port)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::FetchLimits;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+
+    #[test]
+    fn echoes_through_a_stream_until_closed() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = std::thread::spawn(move || {
+            let (mut conn, _) = listener.accept().unwrap();
+            let mut buf = [0; 5];
+            conn.read_exact(&mut buf).unwrap();
+            conn.write_all(&buf.to_ascii_uppercase()).unwrap();
+        });
+        let manager = Arc::new(TunnelManager::direct().unwrap());
+        let id = manager
+            .open_stream("127.0.0.1".to_string(), port, FetchLimits::default())
+            .unwrap();
+        let stream = ProxyStream {
+            manager: Arc::clone(&manager),
+            id,
+            closed: AtomicBool::new(false),
+        };
+
+        // Nothing to read yet: the wait runs out.
+        assert!(matches!(
+            stream.read(16, 10),
+            Err(ProxyError::Timeout { .. })
+        ));
+        stream.write(b"hello".to_vec(), 1_000).unwrap();
+        let mut echoed = Vec::new();
+        loop {
+            let read = stream.read(16, 1_000).unwrap();
+            if read.is_empty() {
+                break;
+            }
+            echoed.extend(read);
+        }
+        assert_eq!(echoed, b"HELLO");
+        server.join().unwrap();
+
+        stream.close();
+        assert!(matches!(
+            manager.read_stream(id, 16, Duration::ZERO),
+            Err(ProxyError::NetworkUnavailable { .. })
+        ));
+    }
+}
@@ -0,0 +1,414 @@
+//! SVG sanitization.
+//!
+//! An SVG is a document, not a bitmap: rendered in a WebView it can run
+//! `<script>`, fire `on*` event handlers, embed arbitrary HTML through
+//! `<foreignObject>` and pull further resources from any server — each of
+//! which can tell the sender the message was opened, from where. Before an
+//! `image/svg+xml` response is returned it is rewritten by [`sanitize`]:
+//!
+//! - `<script>` and `<foreignObject>` elements are dropped with their content;
+//! - `on*` attributes are dropped;
+//! - `href`/`xlink:href` values other than same-document `#fragment`
+//!   references are dropped, as are `<animate>`/`<set>` elements targeting them;
+//! - `style` attributes, presentation attributes and `<style>` contents are
+//!   dropped unless their CSS, with character references and CSS escapes
+//!   resolved, uses only an allow-list of functions (`url(...)` only for
+//!   `#fragment` references) and at-rules, so neither `@import`, `image-set()`
+//!   nor any spelling of them gets through;
+//! - the doctype (and with it any entity definitions) and processing
+//!   instructions such as `<?xml-stylesheet?>` are removed.
+//!
+//! Sanitization is on by default; [`proxy_set_svg_sanitization`] turns it off.
+
+use crate::client::{default_client, ProxyClient};
+use crate::error::ProxyError;
+use quick_xml::escape::resolve_predefined_entity;
+use quick_xml::events::{BytesRef, BytesStart, BytesText, Event};
+use quick_xml::{Reader, Writer};
+
+/// Elements removed together with everything inside them.
+const DROPPED_ELEMENTS: &[&[u8]] = &[b"script", b"foreignObject"];
+
+/// CSS functions that compute a value without loading anything. `url()` is
+/// checked on its own.
+const SAFE_CSS_FUNCTIONS: &[&str] = &[
+    "rgb",
+    "rgba",
+    "hsl",
+    "hsla",
+    "hwb",
+    "lab",
+    "lch",
+    "oklab",
+    "oklch",
+    "color",
+    "calc",
+    "var",
+    "min",
+    "max",
+    "clamp",
+    "translate",
+    "translatex",
+    "translatey",
+    "rotate",
+    "scale",
+    "scalex",
+    "scaley",
+    "skew",
+    "skewx",
+    "skewy",
+    "matrix",
+    "cubic-bezier",
+    "steps",
+    "linear-gradient",
+    "radial-gradient",
+    "conic-gradient",
+    "repeating-linear-gradient",
+    "repeating-radial-gradient",
+];
+
+/// At-rules that only scope or name rules.
+const SAFE_AT_RULES: &[&str] = &["media", "supports", "keyframes", "-webkit-keyframes"];
+
+/// The text of a `<style>` element, gathered until it ends so it is checked
+/// as a whole however the parser splits it.
+#[derive(Default)]
+struct StyleText {
+    css: String,
+    /// False once a reference that cannot be resolved was met
+    resolved: bool,
+}
+
+/// Rewrite an SVG document without active content or external references.
+///
+/// Fails with [`ProxyError::ImageProcessingError`] for documents that are not
+/// well-formed UTF-8 XML, since they cannot be sanitized reliably.
+pub fn sanitize(svg: &[u8]) -> Result<Vec<u8>, ProxyError> {
+    let text = std::str::from_utf8(svg).map_err(|_| failed("not valid UTF-8"))?;
+    let mut reader = Reader::from_str(text);
+    let mut writer = Writer::new(Vec::with_capacity(svg.len()));
+    // Depth inside a dropped element; 0 when copying.
+    let mut skipping = 0usize;
+    // The text of the enclosing `<style>`, if any.
+    let mut style: Option<StyleText> = None;
+
+    loop {
+        let event = reader.read_event().map_err(|e| failed(&e.to_string()))?;
+        if skipping > 0 {
+            match event {
+                Event::Start(_) => skipping += 1,
+                Event::End(_) => skipping -= 1,
+                Event::Eof => return Err(failed("unterminated element")),
+                _ => {}
+            }
+            continue;
+        }
+        if let Some(text) = style.as_mut() {
+            match event {
+                Event::Text(text_event) => {
+                    text.css
+                        .push_str(&text_event.decode().map_err(|e| failed(&e.to_string()))?);
+                    continue;
+                }
+                Event::CData(data) => {
+                    text.css.push_str(&String::from_utf8_lossy(&data));
+                    continue;
+                }
+                Event::GeneralRef(reference) => {
+                    match resolve_reference(&reference) {
+                        Some(resolved) => text.css.push_str(&resolved),
+                        None => text.resolved = false,
+                    }
+                    continue;
+                }
+                _ => {}
+            }
+        }
+        if let Some(text) = style.take() {
+            if text.resolved && safe_css(&text.css) && !text.css.is_empty() {
+                writer
+                    .write_event(Event::Text(BytesText::new(&text.css)))
+                    .map_err(|e| failed(&e.to_string()))?;
+            }
+        }
+        let event = match event {
+            Event::Eof => break,
+            Event::DocType(_) | Event::PI(_) => continue,
+            Event::Start(start) => {
+                if dropped(&start) {
+                    skipping = 1;
+                    continue;
+                }
+                if local_name(&start) == b"style" {
+                    style = Some(StyleText {
+                        css: String::new(),
+                        resolved: true,
+                    });
+                }
+                Event::Start(clean(&start)?)
+            }
+            Event::Empty(start) => {
+                if dropped(&start) {
+                    continue;
+                }
+                Event::Empty(clean(&start)?)
+            }
+            other => other,
+        };
+        writer
+            .write_event(event)
+            .map_err(|e| failed(&e.to_string()))?;
+    }
+    Ok(writer.into_inner())
+}
+
+/// Whether `element` is removed outright.
+fn dropped(element: &BytesStart) -> bool {
+    let name = local_name(element);
+    if DROPPED_ELEMENTS.contains(&name) {
+        return true;
+    }
+    // Animations can assign an href after sanitization.
+    matches!(name, b"animate" | b"set")
+        && element.attributes().flatten().any(|attr| {
+            attr.key.local_name().as_ref() == b"attributeName"
+                && attr.value.to_ascii_lowercase().ends_with(b"href")
+        })
+}
+
+/// Copy `element` without event handlers, external references or unsafe styles.
+fn clean(element: &BytesStart) -> Result<BytesStart<'static>, ProxyError> {
+    let name = String::from_utf8_lossy(element.name().as_ref()).into_owned();
+    let mut cleaned = BytesStart::new(name);
+    for attr in element.attributes() {
+        let attr = attr.map_err(|e| failed(&e.to_string()))?;
+        let key = attr.key.local_name();
+        let value = attr.unescape_value().map_err(|e| failed(&e.to_string()))?;
+        let keep = match key.as_ref() {
+            k if k.len() > 2 && k[..2].eq_ignore_ascii_case(b"on") => false,
+            b"href" => value.trim_start().starts_with('#'),
+            // `style` and presentation attributes such as fill="url(...)".
+            _ => safe_css(&value),
+        };
+        if keep {
+            cleaned.push_attribute(attr);
+        }
+    }
+    Ok(cleaned)
+}
+
+/// The text a character or predefined entity reference stands for. The
+/// doctype is removed, so no other entity has a definition.
+fn resolve_reference(reference: &BytesRef) -> Option<String> {
+    if let Some(c) = reference.resolve_char_ref().ok()? {
+        return Some(c.to_string());
+    }
+    resolve_predefined_entity(&reference.decode().ok()?).map(str::to_string)
+}
+
+/// Whether CSS text references nothing outside the document: once escapes
+/// and comments are gone, every function is in [`SAFE_CSS_FUNCTIONS`] or a
+/// `url()` of a fragment, and every at-rule in [`SAFE_AT_RULES`].
+fn safe_css(css: &str) -> bool {
+    let css = strip_comments(&unescape_css(css)).to_lowercase();
+    let identifier = |c: char| c.is_alphanumeric() || matches!(c, '-' | '_');
+    let safe_at_rules = css.match_indices('@').all(|(at, _)| {
+        let rest = &css[at + 1..];
+        let name = &rest[..rest.find(|c| !identifier(c)).unwrap_or(rest.len())];
+        SAFE_AT_RULES.contains(&name)
+    });
+    safe_at_rules
+        && css.match_indices('(').all(|(at, _)| {
+            let start = css[..at]
+                .char_indices()
+                .rev()
+                .take_while(|&(_, c)| identifier(c))
+                .last()
+                .map_or(at, |(i, _)| i);
+            let name = &css[start..at];
+            match name {
+                // A parenthesis grouping, as in media queries.
+                "" => true,
+                "url" => css[at + 1..]
+                    .trim_start()
+                    .trim_start_matches(['"', '\''])
+                    .starts_with('#'),
+                name => SAFE_CSS_FUNCTIONS.contains(&name),
+            }
+        })
+}
+
+/// `css` with its escapes resolved: `\` and up to six hex digits (and one
+/// whitespace after them) stand for that code point, `\` and a line break for
+/// nothing, `\` and any other character for the character.
+fn unescape_css(css: &str) -> String {
+    let mut unescaped = String::with_capacity(css.len());
+    let mut chars = css.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        let mut hex = String::new();
+        while hex.len() < 6 && chars.peek().is_some_and(char::is_ascii_hexdigit) {
+            hex.extend(chars.next());
+        }
+        if hex.is_empty() {
+            match chars.next() {
+                Some('\n') | None => {}
+                Some(escaped) => unescaped.push(escaped),
+            }
+            continue;
+        }
+        if chars.peek().is_some_and(|c| c.is_whitespace()) {
+            chars.next();
+        }
+        let code = u32::from_str_radix(&hex, 16).unwrap_or(0);
+        unescaped.push(
+            char::from_u32(code)
+                .filter(|&c| c != '\0')
+                .unwrap_or('\u{FFFD}'),
+        );
+    }
+    unescaped
+}
+
+/// `css` without `/* */` comments, which could split a keyword.
+fn strip_comments(css: &str) -> String {
+    let mut stripped = String::with_capacity(css.len());
+    let mut rest = css;
+    while let Some(open) = rest.find("/*") {
+        stripped.push_str(&rest[..open]);
+        match rest[open + 2..].find("*/") {
+            Some(close) => rest = &rest[open + 2 + close + 2..],
+            None => return stripped,
+        }
+    }
+    stripped.push_str(rest);
+    stripped
+}
+
+/// The element name without its namespace prefix.
+fn local_name<'a>(element: &'a BytesStart) -> &'a [u8] {
+    let name = element.name().into_inner();
+    name.iter()
+        .rposition(|&b| b == b':')
+        .map_or(name, |colon| &name[colon + 1..])
+}
+
+fn failed(details: &str) -> ProxyError {
+    ProxyError::ImageProcessingError {
+        details: format!("SVG sanitization failed: {details}"),
+    }
+}
+
+#[uniffi::export]
+impl ProxyClient {
+    /// Enable or disable SVG sanitization (enabled by default).
+    pub fn set_svg_sanitization(&self, enabled: bool) -> Result<(), ProxyError> {
+        let mut guard = self.lock_state();
+        let state = guard.as_mut().ok_or(ProxyError::NotInitialized)?;
+        state.config.sanitize_svg = enabled;
+        Ok(())
+    }
+}
+
+/// [`ProxyClient::set_svg_sanitization`] on the default proxy.
+#[uniffi::export]
+pub fn proxy_set_svg_sanitization(enabled: bool) -> Result<(), ProxyError> {
+    default_client().set_svg_sanitization(enabled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn clean(svg: &str) -> String {
+        String::from_utf8(sanitize(svg.as_bytes()).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn strips_scripts_and_foreign_objects() {
+        let out = clean(
+            r#"<svg xmlns="http://www.w3.org/2000/svg"><script>alert(1)</script><foreignObject><div><p>x</p></div></foreignObject><svg:script/><rect width="5"/></svg>"#,
+        );
+        assert_eq!(
+            out,
+            r#"<svg xmlns="http://www.w3.org/2000/svg"><rect width="5"/></svg>"#
+        );
+    }
+
+    #[test]
+    fn strips_event_handlers_and_external_references() {
+        let out = clean(
+            r##"<svg onload="track()"><image href="https://t.example/p.gif"/><use xlink:href="#a" onClick="x()"/><a href="javascript:x()">l</a></svg>"##,
+        );
+        assert_eq!(
+            out,
+            r##"<svg><image/><use xlink:href="#a"/><a>l</a></svg>"##
+        );
+    }
+
+    #[test]
+    fn strips_external_styles_and_href_animations() {
+        let out = clean(
+            r##"<svg><style>@import url(https://t.example/a.css);</style><style>.a{fill:url(#g)}</style><rect style="fill:url('https://t.example/x')" fill="url(#g)"/><set attributeName="xlink:href" to="https://t.example"/></svg>"##,
+        );
+        assert_eq!(
+            out,
+            r##"<svg><style></style><style>.a{fill:url(#g)}</style><rect fill="url(#g)"/></svg>"##
+        );
+    }
+
+    #[test]
+    fn resolves_references_and_escapes_before_checking_css() {
+        for style in [
+            ".a{fill:&#117;rl(https://t.example/x)}",
+            ".a{fill:&#x75;rl(https://t.example/x)}",
+            r#".a{background:image-set("https://t.example/x" 1x)}"#,
+            r".a{fill:\75rl(https://t.example/x)}",
+            r".a{fill:\000075 rl(https://t.example/x)}",
+            r".a{fill:u\rl(https://t.example/x)}",
+            r#"@\69mport "https://t.example/a.css";"#,
+            "@namespace x url(https://t.example/ns);",
+            ".a{fill:u<![CDATA[rl(https://t.example/x)]]>}",
+            ".a{fill:&custom;}",
+        ] {
+            let out = clean(&format!("<svg><style>{style}</style></svg>"));
+            assert_eq!(out, "<svg><style></style></svg>", "{style}");
+        }
+        let out = clean(
+            r#"<svg><rect style="fill:&#117;rl(https://t.example/x)" transform="translate(1 2) rotate(3)"/></svg>"#,
+        );
+        assert_eq!(
+            out,
+            r#"<svg><rect transform="translate(1 2) rotate(3)"/></svg>"#
+        );
+    }
+
+    #[test]
+    fn keeps_safe_css() {
+        let out = clean(
+            "<svg><style>@media (max-width: 600px) { .a { fill: rgb(1, 2, 3); stroke: url(&quot;#g&quot;) } } .b &gt; .c { width: calc(100% - 2px) }</style></svg>",
+        );
+        assert_eq!(
+            out,
+            "<svg><style>@media (max-width: 600px) { .a { fill: rgb(1, 2, 3); stroke: url(&quot;#g&quot;) } } .b &gt; .c { width: calc(100% - 2px) }</style></svg>"
+        );
+    }
+
+    #[test]
+    fn removes_doctype_and_processing_instructions() {
+        let out = clean(
+            r#"<?xml version="1.0"?><?xml-stylesheet href="https://t.example/s.css"?><!DOCTYPE svg [<!ENTITY x "y">]><svg/>"#,
+        );
+        assert_eq!(out, r#"<?xml version="1.0"?><svg/>"#);
+    }
+
+    #[test]
+    fn rejects_malformed_documents() {
+        assert!(sanitize(b"<svg><script>").is_err());
+        assert!(sanitize(b"<svg></g>").is_err());
+        assert!(sanitize(b"\xff<svg/>").is_err());
+    }
+}
@@ -0,0 +1,102 @@
+//! In-process stand-ins for the servers the proxy talks to, for tests.
+//!
+//! Compiled only with the `mock` feature, which must never ship: it makes
+//! every TLS client in the crate trust a test CA whose private key is
+//! committed alongside this module. Release builds with it fail to compile.
+//!
+//! - [`MockServer`] is a small HTTPS/1.1 server on `127.0.0.1` answering
+//!   canned [`Route`]s with a certificate from that CA, so image fetches can
+//!   run end to end over [`use_direct_network`] without leaving the machine.
+//! - [`MockWarpApi`] is a [`MockServer`] answering the WARP registration API
+//!   the way `api.cloudflareclient.com` does. `route_provisioning_to` sends
+//!   every `WarpProvisioner` built afterwards to it.
+//!
+//! ```ignore
+//! let server = MockServer::start(vec![Route::ok("/a.png", "image/png", png(4, 4))])?;
+//! let client = ProxyClient::new(dir, 16)?;
+//! use_direct_network(&client)?;
+//! client.fetch_image(server.url("/a.png"), None, None, None).await?;
+//! ```
+
+#[cfg(not(debug_assertions))]
+compile_error!("the `mock` feature must not be enabled in release builds");
+
+mod server;
+mod warp_api;
+
+pub use server::{MockServer, RecordedRequest, Route};
+pub use warp_api::MockWarpApi;
+
+use crate::client::ProxyClient;
+use crate::error::ProxyError;
+use crate::tunnel::TunnelManager;
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use std::io::Cursor;
+use std::sync::Arc;
+#[cfg(feature = "provisioning")]
+use std::sync::Mutex;
+
+/// The test CA every TLS client trusts in `mock` builds.
+const CA_PEM: &[u8] = include_bytes!("ca.pem");
+
+/// The [`MockServer`] certificate chain, for `127.0.0.1` and `localhost`.
+const SERVER_PEM: &[u8] = include_bytes!("server.pem");
+
+/// The [`MockServer`] private key (PKCS#8).
+const SERVER_KEY: &[u8] = include_bytes!("server.key");
+
+/// Base URL provisioning is sent to instead of the real WARP API, if set.
+#[cfg(feature = "provisioning")]
+static WARP_API_BASE: Mutex<Option<String>> = Mutex::new(None);
+
+/// The test CA certificate, added to the trust anchors in `mock` builds.
+pub fn test_root() -> CertificateDer<'static> {
+    CertificateDer::from_pem_slice(CA_PEM).expect("the bundled test CA parses")
+}
+
+/// The [`MockServer`] certificate chain and key.
+fn server_identity() -> (Vec<CertificateDer<'static>>, PrivateKeyDer<'static>) {
+    let chain = CertificateDer::pem_reader_iter(&mut Cursor::new(SERVER_PEM))
+        .collect::<Result<Vec<_>, _>>()
+        .expect("the bundled server chain parses");
+    let key = PrivateKeyDer::from_pem_slice(SERVER_KEY).expect("the bundled server key parses");
+    (chain, key)
+}
+
+/// Send provisioning to `api` (or, with `None`, the real WARP API again).
+///
+/// Process-wide: tests sharing a binary should not race on it.
+#[cfg(feature = "provisioning")]
+pub fn route_provisioning_to(api: Option<&MockWarpApi>) {
+    *WARP_API_BASE.lock().unwrap_or_else(|e| e.into_inner()) = api.map(MockWarpApi::base_url);
+}
+
+/// The base URL set by [`route_provisioning_to`], if any.
+#[cfg(feature = "provisioning")]
+pub(crate) fn warp_api_base() -> Option<String> {
+    WARP_API_BASE
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+}
+
+/// Carry `client`'s requests over a direct connection instead of WARP, as
+/// builds without the `tunnel` feature do. The client must be initialised.
+pub fn use_direct_network(client: &ProxyClient) -> Result<(), ProxyError> {
+    let manager = Arc::new(TunnelManager::direct()?);
+    let mut guard = client.lock_state();
+    let state = guard.as_mut().ok_or(ProxyError::NotInitialized)?;
+    state.manager = Some(manager);
+    Ok(())
+}
+
+/// A solid grey PNG of `width` x `height` pixels.
+pub fn png(width: u32, height: u32) -> Vec<u8> {
+    let image = image::RgbImage::from_pixel(width, height, image::Rgb([128, 128, 128]));
+    let mut out = Cursor::new(Vec::new());
+    image
+        .write_to(&mut out, image::ImageFormat::Png)
+        .expect("encoding a PNG in memory cannot fail");
+    out.into_inner()
+}
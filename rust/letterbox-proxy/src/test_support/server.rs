@@ -0,0 +1,282 @@
+//! A canned-response HTTPS/1.1 server on the loopback interface.
+
+use super::server_identity;
+use rustls::{ServerConfig, ServerConnection, StreamOwned};
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+/// How long a connection may sit idle before the server gives up on it.
+const IO_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A canned response to one method and path.
+#[derive(Debug, Clone)]
+pub struct Route {
+    /// Request method, e.g. `GET`.
+    pub method: String,
+    /// Request path, without the query string.
+    pub path: String,
+    /// Response status code.
+    pub status: u16,
+    /// Response headers, besides `Content-Length` and `Connection`.
+    pub headers: Vec<(String, String)>,
+    /// Response body.
+    pub body: Vec<u8>,
+}
+
+impl Route {
+    /// Answer `GET path` with `200 OK` and `body` as `content_type`.
+    pub fn ok(path: &str, content_type: &str, body: Vec<u8>) -> Self {
+        Self::new("GET", path, 200)
+            .header("Content-Type", content_type)
+            .body(body)
+    }
+
+    /// Answer `method path` with an empty `status` response.
+    pub fn new(method: &str, path: &str, status: u16) -> Self {
+        Self {
+            method: method.to_string(),
+            path: path.to_string(),
+            status,
+            headers: Vec::new(),
+            body: Vec::new(),
+        }
+    }
+
+    /// Add a response header.
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Set the response body.
+    pub fn body(mut self, body: Vec<u8>) -> Self {
+        self.body = body;
+        self
+    }
+
+    /// Answer with `value` serialised as JSON.
+    pub fn json(self, value: &serde_json::Value) -> Self {
+        self.header("Content-Type", "application/json")
+            .body(value.to_string().into_bytes())
+    }
+}
+
+/// A request the server received.
+#[derive(Debug, Clone)]
+pub struct RecordedRequest {
+    /// Request method.
+    pub method: String,
+    /// Request target, query string included.
+    pub target: String,
+    /// Request headers with lowercase names, in arrival order.
+    pub headers: Vec<(String, String)>,
+    /// Request body.
+    pub body: Vec<u8>,
+}
+
+impl RecordedRequest {
+    /// The first value of header `name` (case-insensitive).
+    pub fn header(&self, name: &str) -> Option<&str> {
+        let name = name.to_ascii_lowercase();
+        self.headers
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// An HTTPS server on `127.0.0.1` answering a fixed set of [`Route`]s.
+///
+/// Unknown paths get `404 Not Found`. Each connection carries one exchange
+/// and is then closed. The server stops when dropped.
+pub struct MockServer {
+    addr: SocketAddr,
+    requests: Arc<Mutex<Vec<RecordedRequest>>>,
+    stop: Arc<AtomicBool>,
+    acceptor: Option<JoinHandle<()>>,
+}
+
+impl MockServer {
+    /// Start serving `routes` on an ephemeral loopback port.
+    pub fn start(routes: Vec<Route>) -> io::Result<Self> {
+        let (chain, key) = server_identity();
+        let mut config =
+            ServerConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+                .with_safe_default_protocol_versions()
+                .and_then(|builder| builder.with_no_client_auth().with_single_cert(chain, key))
+                .map_err(io::Error::other)?;
+        config.alpn_protocols = vec![b"http/1.1".to_vec()];
+        let config = Arc::new(config);
+
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let addr = listener.local_addr()?;
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let stop = Arc::new(AtomicBool::new(false));
+        let routes = Arc::new(routes);
+
+        let acceptor = {
+            let (requests, stop) = (Arc::clone(&requests), Arc::clone(&stop));
+            std::thread::Builder::new()
+                .name("mock-server".to_string())
+                .spawn(move || {
+                    for stream in listener.incoming() {
+                        if stop.load(Ordering::Acquire) {
+                            break;
+                        }
+                        let Ok(stream) = stream else { continue };
+                        let (config, routes, requests) = (
+                            Arc::clone(&config),
+                            Arc::clone(&routes),
+                            Arc::clone(&requests),
+                        );
+                        std::thread::spawn(move || {
+                            if let Err(e) = serve(stream, config, &routes, &requests) {
+                                log::debug!("Mock server connection failed: {e}");
+                            }
+                        });
+                    }
+                })?
+        };
+
+        Ok(Self {
+            addr,
+            requests,
+            stop,
+            acceptor: Some(acceptor),
+        })
+    }
+
+    /// The address the server listens on.
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// The `https://` URL of `path` on this server.
+    pub fn url(&self, path: &str) -> String {
+        format!("https://{}{path}", self.addr)
+    }
+
+    /// Every request received so far, in arrival order.
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.requests
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Release);
+        // Wake the acceptor so it sees the flag.
+        let _ = TcpStream::connect(self.addr);
+        if let Some(acceptor) = self.acceptor.take() {
+            let _ = acceptor.join();
+        }
+    }
+}
+
+/// Terminate TLS on `stream`, read one request and answer it from `routes`.
+fn serve(
+    stream: TcpStream,
+    config: Arc<ServerConfig>,
+    routes: &[Route],
+    requests: &Mutex<Vec<RecordedRequest>>,
+) -> io::Result<()> {
+    stream.set_read_timeout(Some(IO_TIMEOUT))?;
+    stream.set_write_timeout(Some(IO_TIMEOUT))?;
+    let connection = ServerConnection::new(config).map_err(io::Error::other)?;
+    let mut reader = BufReader::new(StreamOwned::new(connection, stream));
+
+    let request = read_request(&mut reader)?;
+    let path = request.target.split('?').next().unwrap_or_default();
+    let route = routes
+        .iter()
+        .find(|route| route.method == request.method && route.path == path);
+    requests
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .push(request);
+
+    let not_found = Route::new("", "", 404);
+    let route = route.unwrap_or(&not_found);
+    let mut head = format!("HTTP/1.1 {} {}\r\n", route.status, reason(route.status));
+    for (name, value) in &route.headers {
+        head.push_str(&format!("{name}: {value}\r\n"));
+    }
+    head.push_str(&format!(
+        "Content-Length: {}\r\nConnection: close\r\n\r\n",
+        route.body.len()
+    ));
+
+    let stream = reader.get_mut();
+    stream.write_all(head.as_bytes())?;
+    stream.write_all(&route.body)?;
+    stream.flush()?;
+    stream.conn.send_close_notify();
+    stream.flush()
+}
+
+/// Read a request head and its `Content-Length` body.
+fn read_request(reader: &mut impl BufRead) -> io::Result<RecordedRequest> {
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Malformed request line {line:?}"),
+        ));
+    };
+    let (method, target) = (method.to_string(), target.to_string());
+
+    let mut headers = Vec::new();
+    loop {
+        line.clear();
+        reader.read_line(&mut line)?;
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            headers.push((name.trim().to_ascii_lowercase(), value.trim().to_string()));
+        }
+    }
+
+    let length = headers
+        .iter()
+        .find(|(name, _)| name == "content-length")
+        .and_then(|(_, value)| value.parse::<usize>().ok())
+        .unwrap_or(0);
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+
+    Ok(RecordedRequest {
+        method,
+        target,
+        headers,
+        body,
+    })
+}
+
+/// The reason phrase of the status codes tests use.
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        204 => "No Content",
+        301 => "Moved Permanently",
+        302 => "Found",
+        304 => "Not Modified",
+        400 => "Bad Request",
+        403 => "Forbidden",
+        404 => "Not Found",
+        429 => "Too Many Requests",
+        500 => "Internal Server Error",
+        503 => "Service Unavailable",
+        _ => "Unknown",
+    }
+}
@@ -0,0 +1,70 @@
+//! A stand-in for the WARP registration API at `api.cloudflareclient.com`.
+
+use super::server::{MockServer, Route};
+use serde_json::json;
+use std::io;
+
+/// API version prefix the provisioner uses.
+const API_VERSION: &str = "v0a884";
+
+/// A [`MockServer`] answering device registration, configuration, enabling
+/// and deletion for a single fake device.
+///
+/// The configuration it hands out names a peer that does not exist, so no
+/// tunnel can be brought up with it; it is for exercising provisioning.
+pub struct MockWarpApi {
+    server: MockServer,
+}
+
+impl MockWarpApi {
+    /// Device ID every registration returns.
+    pub const DEVICE_ID: &'static str = "mock-device";
+    /// Access token every registration returns.
+    pub const ACCESS_TOKEN: &'static str = "mock-token";
+    /// Client ID the configuration carries (three bytes, base64).
+    pub const CLIENT_ID: &'static str = "AQID";
+
+    /// Start the API on an ephemeral loopback port.
+    ///
+    /// The configuration reports WARP disabled, so provisioning also enables
+    /// it with a `PATCH`.
+    pub fn start() -> io::Result<Self> {
+        let reg = format!("/{API_VERSION}/reg");
+        let device = format!("{reg}/{}", Self::DEVICE_ID);
+        let registration = json!({
+            "id": Self::DEVICE_ID,
+            "token": Self::ACCESS_TOKEN,
+            "account": { "license": "mock-license" },
+        });
+        let config = json!({
+            "config": {
+                "client_id": Self::CLIENT_ID,
+                "interface": { "addresses": { "v4": "172.16.0.2", "v6": "fd01:db8::2" } },
+                "peers": [{
+                    "public_key": "bmXOC+F1FxEMF9dyiK2H5/1SUtzH0JuVo51h2wPfgyo=",
+                    "endpoint": { "host": "engage.cloudflareclient.com:2408", "v4": "127.0.0.1" },
+                }],
+            },
+            "warp_enabled": false,
+            "account": { "account_type": "free", "warp_plus": false, "license": "mock-license" },
+        });
+        let server = MockServer::start(vec![
+            Route::new("GET", &format!("/{API_VERSION}/"), 200),
+            Route::new("POST", &reg, 200).json(&registration),
+            Route::new("GET", &device, 200).json(&config),
+            Route::new("PATCH", &device, 200).json(&json!({ "warp_enabled": true })),
+            Route::new("DELETE", &device, 204),
+        ])?;
+        Ok(Self { server })
+    }
+
+    /// The URL to use in place of `https://api.cloudflareclient.com`.
+    pub fn base_url(&self) -> String {
+        self.server.url("")
+    }
+
+    /// The underlying server, to inspect the requests it received.
+    pub fn server(&self) -> &MockServer {
+        &self.server
+    }
+}
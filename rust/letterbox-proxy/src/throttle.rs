@@ -0,0 +1,87 @@
+//! A global ceiling on outgoing requests.
+//!
+//! A message can reference thousands of images. Fetching them all at once
+//! would turn the device into a request cannon through WARP, so every
+//! request that needs the network passes a [`RateLimit`]: at most so many
+//! may start in any 60 seconds, and the rest wait their turn in the tunnel's
+//! queue. Once too many are waiting, further ones fail at once with
+//! [`ProxyError::Throttled`]. Cache hits and `data:` URIs are not counted.
+
+use crate::client::{default_client, ProxyClient};
+use crate::error::ProxyError;
+use crate::types::RateLimit;
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// The window [`RateLimit::requests_per_minute`] counts over.
+const WINDOW: Duration = Duration::from_secs(60);
+
+/// When the latest requests started, for holding back those over the limit.
+#[derive(Debug, Default)]
+pub(crate) struct Throttle {
+    starts: VecDeque<Instant>,
+}
+
+impl Throttle {
+    /// Block until another request may start under `per_minute` (0 =
+    /// unlimited), and count it.
+    pub(crate) fn admit(&mut self, per_minute: u32) {
+        if per_minute == 0 {
+            return;
+        }
+        let limit = per_minute as usize;
+        while self.starts.len() > limit {
+            self.starts.pop_front();
+        }
+        if self.starts.len() == limit {
+            let oldest = self.starts.pop_front().unwrap_or_else(Instant::now);
+            let wait = (oldest + WINDOW).saturating_duration_since(Instant::now());
+            if !wait.is_zero() {
+                log::debug!(
+                    "Rate limit of {per_minute} requests per minute reached; waiting {wait:?}"
+                );
+                std::thread::sleep(wait);
+            }
+        }
+        self.starts.push_back(Instant::now());
+    }
+}
+
+#[uniffi::export]
+impl ProxyClient {
+    /// Replace the global request ceiling used by all subsequent requests.
+    pub fn set_rate_limit(&self, limit: RateLimit) -> Result<(), ProxyError> {
+        let mut guard = self.lock_state();
+        let state = guard.as_mut().ok_or(ProxyError::NotInitialized)?;
+        state.config.rate_limit = limit;
+        Ok(())
+    }
+}
+
+/// [`ProxyClient::set_rate_limit`] on the default proxy.
+#[uniffi::export]
+pub fn proxy_set_rate_limit(limit: RateLimit) -> Result<(), ProxyError> {
+    default_client().set_rate_limit(limit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn requests_over_the_limit_wait_for_the_window() {
+        let mut throttle = Throttle::default();
+        let started = Instant::now();
+        for _ in 0..3 {
+            throttle.admit(3);
+        }
+        throttle.admit(0);
+        assert!(started.elapsed() < Duration::from_secs(1));
+
+        // Pretend the first request started almost a minute ago.
+        throttle.starts[0] = Instant::now() - WINDOW + Duration::from_millis(50);
+        throttle.admit(3);
+        assert!(started.elapsed() >= Duration::from_millis(40));
+        assert_eq!(throttle.starts.len(), 3);
+    }
+}
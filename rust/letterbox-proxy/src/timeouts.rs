@@ -0,0 +1,206 @@
+//! Time budgets for the phases of a request.
+//!
+//! A single timeout for the whole fetch hides where the time went: a slow DNS
+//! answer, a stalled TCP connect through the tunnel, a TLS handshake, a server
+//! that takes long to answer, or a large body on a slow link. Each of these
+//! phases instead has its own budget in a [`TimeoutBudget`], and a request
+//! that runs out fails with a [`ProxyError::Timeout`] naming the phase.
+//!
+//! A [`Deadline`] follows one attempt through its phases. The network streams
+//! give up on reads and writes once it passes, and the TLS and HTTP layers move
+//! it on as the request progresses: connect, TLS handshake, first byte of the
+//! response, body. DNS lookups run their own requests under a fixed deadline
+//! for the whole lookup.
+
+use crate::client::{default_client, ProxyClient};
+use crate::error::{ProxyError, TimeoutPhase};
+use crate::types::TimeoutBudget;
+use std::cell::Cell;
+use std::io;
+use std::time::{Duration, Instant};
+
+impl TimeoutBudget {
+    /// The budget of `phase` in seconds, if it has one.
+    fn seconds(&self, phase: TimeoutPhase) -> Option<u32> {
+        match phase {
+            TimeoutPhase::Tunnel => None,
+            TimeoutPhase::Dns => Some(self.dns_seconds),
+            TimeoutPhase::Connect => Some(self.connect_seconds),
+            TimeoutPhase::Tls => Some(self.tls_seconds),
+            TimeoutPhase::FirstByte => Some(self.first_byte_seconds),
+            TimeoutPhase::Body => Some(self.body_seconds),
+        }
+    }
+
+    /// The time allowed for DNS lookups.
+    pub(crate) fn dns(&self) -> Duration {
+        Duration::from_secs(self.dns_seconds.into())
+    }
+}
+
+/// The phase an attempt is in, and when it runs out of time.
+#[derive(Debug)]
+pub struct Deadline {
+    /// Budgets of the phases to come; `None` keeps the first phase throughout.
+    budget: Option<TimeoutBudget>,
+    phase: Cell<TimeoutPhase>,
+    seconds: Cell<u32>,
+    at: Cell<Instant>,
+}
+
+impl Deadline {
+    /// A deadline moving through the phases of `budget`, starting with the
+    /// TCP connect.
+    pub fn phased(budget: &TimeoutBudget) -> Self {
+        let deadline = Deadline {
+            budget: Some(*budget),
+            ..Self::fixed(TimeoutPhase::Connect, Duration::ZERO)
+        };
+        deadline.enter(TimeoutPhase::Connect);
+        deadline
+    }
+
+    /// A deadline `limit` away that stays in `phase` however the request
+    /// progresses.
+    pub fn fixed(phase: TimeoutPhase, limit: Duration) -> Self {
+        Deadline {
+            budget: None,
+            phase: Cell::new(phase),
+            seconds: Cell::new(limit.as_secs() as u32),
+            at: Cell::new(Instant::now() + limit),
+        }
+    }
+
+    /// A deadline over the same budgets, starting afresh with the TCP connect
+    /// (or with the full limit, if fixed).
+    pub fn restarted(&self) -> Self {
+        match &self.budget {
+            Some(budget) => Self::phased(budget),
+            None => Self::fixed(
+                self.phase.get(),
+                Duration::from_secs(self.seconds.get().into()),
+            ),
+        }
+    }
+
+    /// Start `phase` with its full budget. Fixed deadlines, and phases
+    /// without a budget, are left as they are.
+    pub fn enter(&self, phase: TimeoutPhase) {
+        let Some(seconds) = self.budget.and_then(|budget| budget.seconds(phase)) else {
+            return;
+        };
+        self.phase.set(phase);
+        self.seconds.set(seconds);
+        self.at
+            .set(Instant::now() + Duration::from_secs(seconds.into()));
+    }
+
+    /// When the current phase runs out of time.
+    pub fn at(&self) -> Instant {
+        self.at.get()
+    }
+
+    /// Time left in the current phase.
+    pub fn remaining(&self) -> Duration {
+        self.at().saturating_duration_since(Instant::now())
+    }
+
+    /// The error for running out of time in the current phase.
+    pub fn timed_out(&self) -> ProxyError {
+        ProxyError::Timeout {
+            phase: self.phase.get(),
+            seconds: self.seconds.get(),
+        }
+    }
+
+    /// `e` as a timeout if a stream gave up at the deadline, else as `other`
+    /// makes it.
+    pub fn io_error(
+        &self,
+        e: io::Error,
+        other: impl FnOnce(io::Error) -> ProxyError,
+    ) -> ProxyError {
+        match e.kind() {
+            io::ErrorKind::TimedOut => self.timed_out(),
+            _ => other(e),
+        }
+    }
+}
+
+#[uniffi::export]
+impl ProxyClient {
+    /// Replace the time budgets of the request phases used by all subsequent
+    /// fetches.
+    ///
+    /// A budget of 0 seconds is treated as 1.
+    pub fn set_timeout_budget(&self, budget: TimeoutBudget) -> Result<(), ProxyError> {
+        let mut guard = self.lock_state();
+        let state = guard.as_mut().ok_or(ProxyError::NotInitialized)?;
+        state.config.timeouts = TimeoutBudget {
+            dns_seconds: budget.dns_seconds.max(1),
+            connect_seconds: budget.connect_seconds.max(1),
+            tls_seconds: budget.tls_seconds.max(1),
+            first_byte_seconds: budget.first_byte_seconds.max(1),
+            body_seconds: budget.body_seconds.max(1),
+        };
+        Ok(())
+    }
+}
+
+/// [`ProxyClient::set_timeout_budget`] on the default proxy.
+#[uniffi::export]
+pub fn proxy_set_timeout_budget(budget: TimeoutBudget) -> Result<(), ProxyError> {
+    default_client().set_timeout_budget(budget)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn phases_restart_the_clock_with_their_own_budget() {
+        let budget = TimeoutBudget {
+            connect_seconds: 5,
+            body_seconds: 100,
+            ..TimeoutBudget::default()
+        };
+        let deadline = Deadline::phased(&budget);
+        assert_eq!(
+            deadline.timed_out(),
+            ProxyError::Timeout {
+                phase: TimeoutPhase::Connect,
+                seconds: 5
+            }
+        );
+        assert!(deadline.remaining() <= Duration::from_secs(5));
+
+        deadline.enter(TimeoutPhase::Body);
+        assert!(deadline.remaining() > Duration::from_secs(90));
+        let timeout = io::Error::from(io::ErrorKind::TimedOut);
+        assert_eq!(
+            deadline.io_error(timeout, |_| ProxyError::Cancelled),
+            ProxyError::Timeout {
+                phase: TimeoutPhase::Body,
+                seconds: 100
+            }
+        );
+        let reset = io::Error::from(io::ErrorKind::ConnectionReset);
+        assert_eq!(
+            deadline.io_error(reset, |_| ProxyError::Cancelled),
+            ProxyError::Cancelled
+        );
+    }
+
+    #[test]
+    fn fixed_deadlines_keep_their_phase() {
+        let deadline = Deadline::fixed(TimeoutPhase::Dns, Duration::from_secs(3));
+        deadline.enter(TimeoutPhase::Body);
+        assert_eq!(
+            deadline.timed_out(),
+            ProxyError::Timeout {
+                phase: TimeoutPhase::Dns,
+                seconds: 3
+            }
+        );
+    }
+}
@@ -0,0 +1,117 @@
+//! Per-request trace ids.
+//!
+//! Every request made through the FFI gets a short random trace id. It is
+//! returned in [`ImageResponse::trace_id`](crate::ImageResponse::trace_id),
+//! appended to the detail string of a failure (e.g. `Tunnel error: handshake
+//! timed out (trace 5f0c9a1e)`), and prefixed to every log record written
+//! while the request is handled, so a failure in a bug report can be matched
+//! with the tunnel and transport logs around it. Errors without a detail
+//! string, such as [`ProxyError::Timeout`], carry no id.
+//!
+//! The id of the request in progress is kept per thread. Requests that run
+//! inside another (a transform fetching its original) share the outer id, and
+//! the tunnel worker adopts the id carried in
+//! [`FetchLimits::trace_id`](crate::config::FetchLimits::trace_id) while it
+//! serves the request.
+
+use crate::error::ProxyError;
+use std::cell::RefCell;
+use std::future::{poll_fn, Future};
+use std::pin::pin;
+
+thread_local! {
+    static CURRENT: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// A fresh id: eight hex digits, unique enough to find one request in a log.
+pub(crate) fn new_id() -> String {
+    format!("{:08x}", rand::random::<u32>())
+}
+
+/// The id of the request this thread is handling, if any.
+pub(crate) fn current() -> Option<String> {
+    CURRENT.with_borrow(Clone::clone)
+}
+
+/// Run `f` with `id` as the current trace id, restoring the previous one
+/// afterwards.
+pub(crate) fn scope<R>(id: Option<&str>, f: impl FnOnce() -> R) -> R {
+    struct Restore(Option<String>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            CURRENT.set(self.0.take());
+        }
+    }
+    let _restore = Restore(CURRENT.replace(id.map(str::to_string)));
+    f()
+}
+
+/// Run the request `f` under a new trace id, tagging its error with the id.
+///
+/// Nested in another request, `f` runs under the outer id and the outer
+/// request tags the error.
+pub(crate) fn traced<T>(f: impl FnOnce() -> Result<T, ProxyError>) -> Result<T, ProxyError> {
+    if current().is_some() {
+        return f();
+    }
+    let id = new_id();
+    scope(Some(&id), f).map_err(|e| e.traced(&id))
+}
+
+/// [`traced`] for a request that is awaited: every poll runs under the id.
+pub(crate) async fn traced_async<T>(
+    request: impl Future<Output = Result<T, ProxyError>>,
+) -> Result<T, ProxyError> {
+    let id = new_id();
+    let mut request = pin!(request);
+    poll_fn(|cx| scope(Some(&id), || request.as_mut().poll(cx)))
+        .await
+        .map_err(|e| e.traced(&id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nested_requests_share_the_outer_id() {
+        let (outer, inner) = traced(|| {
+            let outer = current();
+            let inner = traced(|| Ok(current()))?;
+            Ok::<_, ProxyError>((outer, inner))
+        })
+        .unwrap();
+        assert_eq!(outer.as_deref().map(str::len), Some(8));
+        assert_eq!(outer, inner);
+        assert_eq!(current(), None);
+    }
+
+    #[test]
+    fn the_error_is_tagged_once() {
+        let error = traced(|| {
+            traced::<()>(|| {
+                Err(ProxyError::TunnelError {
+                    details: "handshake timed out".to_string(),
+                })
+            })
+        })
+        .unwrap_err();
+        let ProxyError::TunnelError { details } = error else {
+            panic!("unexpected error: {error}");
+        };
+        assert_eq!(details.matches("(trace ").count(), 1);
+    }
+
+    #[test]
+    fn awaited_requests_see_their_id_on_every_poll() {
+        let id = tokio_test::block_on(traced_async(async {
+            let before = current();
+            tokio::task::yield_now().await;
+            assert_eq!(current(), before);
+            Ok::<_, ProxyError>(before)
+        }))
+        .unwrap();
+        assert!(id.is_some());
+        assert_eq!(current(), None);
+    }
+}
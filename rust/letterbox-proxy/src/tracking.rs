@@ -0,0 +1,265 @@
+//! Tracking-pixel detection.
+//!
+//! Open-tracking "spy pixels" are tiny images whose only purpose is the
+//! request itself. An image is flagged with
+//! [`ImageResponse::tracking_suspected`] when:
+//!
+//! - any URL it was served through (requested, redirect hops, final) is on a
+//!   known tracker domain; the bundled [`TRACKER_DOMAINS`] list can be
+//!   replaced at runtime with [`proxy_set_tracker_domains`];
+//! - its body is empty; or
+//! - its dimensions are at most 2×2 pixels.
+//!
+//! With [`proxy_set_block_tracking_pixels`] flagged images are refused with
+//! [`ProxyError::TrackingBlocked`] instead. A URL on a tracker domain is
+//! refused before any request is made; redirects to one and pixel-shaped
+//! responses can only be recognised once they have arrived, so blocking those
+//! hides them from the UI but the sender has already seen the request.
+//!
+//! The flag is recomputed whenever an image is served, cached copies included,
+//! so list updates and the blocking switch apply to them as well. `data:` URIs
+//! make no request and are never flagged.
+
+use crate::client::{default_client, ProxyClient};
+use crate::config::ProxyConfig;
+use crate::error::ProxyError;
+use crate::types::ImageResponse;
+
+/// Hosts that serve open-tracking pixels for common mailing and sales tools.
+pub const TRACKER_DOMAINS: &[&str] = &[
+    "list-manage.com",
+    "mandrillapp.com",
+    "ct.sendgrid.net",
+    "wf.sendgrid.net",
+    "mailgun.org",
+    "track.customer.io",
+    "customeriomail.com",
+    "mailtrack.io",
+    "mltrk.io",
+    "getnotify.com",
+    "bananatag.com",
+    "t.yesware.com",
+    "mailstat.us",
+    "t.sidekickopen.com",
+    "t.hubspotemail.net",
+    "track.hubspot.com",
+    "emltrk.com",
+    "r.superhuman.com",
+    "mixmax.com",
+    "pixel.watch",
+    "exct.net",
+    "sailthru.com",
+    "klclick.com",
+    "trk.klclick.com",
+    "mcsv.net",
+];
+
+/// Pixels at or below this size on both axes are treated as trackers.
+const PIXEL_SIZE: u32 = 2;
+
+/// The bundled tracker list, in the form stored in the configuration.
+pub(crate) fn default_tracker_domains() -> Vec<String> {
+    TRACKER_DOMAINS.iter().map(|d| d.to_string()).collect()
+}
+
+/// The tracker domain `url`'s host belongs to, if any.
+pub(crate) fn tracker_domain<'a>(url: &str, domains: &'a [String]) -> Option<&'a str> {
+    let parsed = url::Url::parse(url).ok()?;
+    let host = parsed
+        .host_str()?
+        .trim_end_matches('.')
+        .to_ascii_lowercase();
+    domains
+        .iter()
+        .find(|d| {
+            host == **d
+                || host
+                    .strip_suffix(d.as_str())
+                    .is_some_and(|rest| rest.ends_with('.'))
+        })
+        .map(String::as_str)
+}
+
+/// Why `response`, fetched from `url`, looks like a tracking pixel.
+pub(crate) fn suspicion(url: &str, response: &ImageResponse, domains: &[String]) -> Option<String> {
+    let urls = std::iter::once(url)
+        .chain(response.redirect_chain.iter().map(String::as_str))
+        .chain(std::iter::once(response.final_url.as_str()));
+    for hop in urls {
+        if let Some(domain) = tracker_domain(hop, domains) {
+            return Some(format!("tracker domain {domain}"));
+        }
+    }
+    if response.data.is_empty() {
+        return Some("empty image".to_string());
+    }
+    match (response.width, response.height) {
+        (Some(w), Some(h)) if w <= PIXEL_SIZE && h <= PIXEL_SIZE => Some(format!("{w}x{h} pixel")),
+        _ => None,
+    }
+}
+
+/// Refuse `url` up front if it is on a tracker domain and blocking is on.
+pub(crate) fn check_url(url: &str, config: &ProxyConfig) -> Result<(), ProxyError> {
+    if !config.block_tracking_pixels {
+        return Ok(());
+    }
+    match tracker_domain(url, &config.tracker_domains) {
+        Some(domain) => Err(blocked(url, format!("tracker domain {domain}"))),
+        None => Ok(()),
+    }
+}
+
+/// Set `tracking_suspected` on `response`, or refuse it if blocking is on.
+pub(crate) fn screen(
+    url: &str,
+    response: ImageResponse,
+    config: &ProxyConfig,
+) -> Result<ImageResponse, ProxyError> {
+    match suspicion(url, &response, &config.tracker_domains) {
+        Some(reason) if config.block_tracking_pixels => Err(blocked(url, reason)),
+        suspected => Ok(ImageResponse {
+            tracking_suspected: suspected.is_some(),
+            ..response
+        }),
+    }
+}
+
+fn blocked(url: &str, reason: String) -> ProxyError {
+    ProxyError::TrackingBlocked {
+        url: url.to_string(),
+        reason,
+    }
+}
+
+#[uniffi::export]
+impl ProxyClient {
+    /// Replace the tracker domain list (e.g. with a newer one shipped by the app).
+    ///
+    /// Names are matched case-insensitively against each host and its parent
+    /// domains; pass an empty list to rely on pixel shapes alone.
+    pub fn set_tracker_domains(&self, domains: Vec<String>) -> Result<(), ProxyError> {
+        let domains = domains
+            .iter()
+            .map(|d| d.trim().trim_matches('.').to_ascii_lowercase())
+            .filter(|d| !d.is_empty())
+            .collect();
+        let mut guard = self.lock_state();
+        let state = guard.as_mut().ok_or(ProxyError::NotInitialized)?;
+        state.config.tracker_domains = domains;
+        Ok(())
+    }
+
+    /// Refuse suspected tracking pixels with `TrackingBlocked` instead of only
+    /// flagging them (off by default).
+    pub fn set_block_tracking_pixels(&self, enabled: bool) -> Result<(), ProxyError> {
+        let mut guard = self.lock_state();
+        let state = guard.as_mut().ok_or(ProxyError::NotInitialized)?;
+        state.config.block_tracking_pixels = enabled;
+        Ok(())
+    }
+}
+
+/// [`ProxyClient::set_tracker_domains`] on the default proxy.
+#[uniffi::export]
+pub fn proxy_set_tracker_domains(domains: Vec<String>) -> Result<(), ProxyError> {
+    default_client().set_tracker_domains(domains)
+}
+
+/// [`ProxyClient::set_block_tracking_pixels`] on the default proxy.
+#[uniffi::export]
+pub fn proxy_set_block_tracking_pixels(enabled: bool) -> Result<(), ProxyError> {
+    default_client().set_block_tracking_pixels(enabled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(data: &[u8], size: Option<(u32, u32)>) -> ImageResponse {
+        ImageResponse {
+            mime_type: "image/gif".to_string(),
+            data: data.to_vec(),
+            from_cache: false,
+            final_url: "https://cdn.example/a.gif".to_string(),
+            redirect_chain: Vec::new(),
+            redirect_hops: Vec::new(),
+            width: size.map(|s| s.0),
+            height: size.map(|s| s.1),
+            blurhash: None,
+            tracking_suspected: false,
+            trace_id: String::new(),
+            headers: Default::default(),
+            sha256: String::new(),
+            transport: None,
+        }
+    }
+
+    #[test]
+    fn matches_domains_and_subdomains_only() {
+        let domains = default_tracker_domains();
+        assert_eq!(
+            tracker_domain("https://us5.List-Manage.com/track/open.php", &domains),
+            Some("list-manage.com")
+        );
+        assert_eq!(
+            tracker_domain("https://mltrk.io./pixel", &domains),
+            Some("mltrk.io")
+        );
+        assert_eq!(tracker_domain("https://notmltrk.io/p", &domains), None);
+        assert_eq!(tracker_domain("not a url", &domains), None);
+    }
+
+    #[test]
+    fn flags_pixels_and_tracker_hops() {
+        let domains = default_tracker_domains();
+        let url = "https://news.example/logo.gif";
+        assert_eq!(
+            suspicion(url, &response(b"GIF89a", Some((1, 1))), &domains).as_deref(),
+            Some("1x1 pixel")
+        );
+        assert_eq!(
+            suspicion(url, &response(b"", None), &domains).as_deref(),
+            Some("empty image")
+        );
+        assert_eq!(
+            suspicion(url, &response(b"GIF89a", Some((3, 1))), &domains),
+            None
+        );
+
+        let redirected = ImageResponse {
+            redirect_chain: vec!["https://u1.ct.sendgrid.net/wf/open".to_string()],
+            redirect_hops: Vec::new(),
+            ..response(b"GIF89a", Some((600, 200)))
+        };
+        assert_eq!(
+            suspicion(url, &redirected, &domains).as_deref(),
+            Some("tracker domain ct.sendgrid.net")
+        );
+        assert_eq!(suspicion(url, &redirected, &[]), None);
+    }
+
+    #[test]
+    fn screen_flags_or_blocks() {
+        let mut config = ProxyConfig::default();
+        let pixel = || response(b"GIF89a", Some((1, 1)));
+        let url = "https://news.example/open.gif";
+        assert!(screen(url, pixel(), &config).unwrap().tracking_suspected);
+        assert!(check_url("https://mltrk.io/p", &config).is_ok());
+
+        config.block_tracking_pixels = true;
+        assert_eq!(
+            screen(url, pixel(), &config).err(),
+            Some(ProxyError::TrackingBlocked {
+                url: url.to_string(),
+                reason: "1x1 pixel".to_string()
+            })
+        );
+        assert!(matches!(
+            check_url("https://mltrk.io/p", &config),
+            Err(ProxyError::TrackingBlocked { .. })
+        ));
+        let photo = response(b"GIF89a", Some((640, 480)));
+        assert!(!screen(url, photo, &config).unwrap().tracking_suspected);
+    }
+}
@@ -0,0 +1,112 @@
+//! Decoding and encoding the formats [`super`] transforms.
+
+use crate::error::ProxyError;
+use crate::types::OutputFormat;
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::png::PngEncoder;
+use image::codecs::webp::WebPEncoder;
+use image::{DynamicImage, ImageDecoder, ImageFormat};
+use jxl_oxide::integration::JxlDecoder;
+use std::io::Cursor;
+
+/// A format this module can decode.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Source {
+    Raster(ImageFormat),
+    Jxl,
+}
+
+/// The output format equivalent to `source`, if any.
+pub(super) fn target_of(source: Source) -> Option<OutputFormat> {
+    match source {
+        Source::Raster(ImageFormat::Png) => Some(OutputFormat::Png),
+        Source::Raster(ImageFormat::Jpeg) => Some(OutputFormat::Jpeg),
+        Source::Raster(ImageFormat::WebP) => Some(OutputFormat::Webp),
+        _ => None,
+    }
+}
+
+/// The decoder for `mime_type`, if this module handles it (for animations,
+/// the first frame is decoded).
+pub(crate) fn decodable(mime_type: &str) -> Option<Source> {
+    match mime_type {
+        "image/png" => Some(Source::Raster(ImageFormat::Png)),
+        "image/jpeg" => Some(Source::Raster(ImageFormat::Jpeg)),
+        "image/gif" => Some(Source::Raster(ImageFormat::Gif)),
+        "image/webp" => Some(Source::Raster(ImageFormat::WebP)),
+        "image/jxl" => Some(Source::Jxl),
+        _ => None,
+    }
+}
+
+/// Formats WebViews may be unable to render and that cannot be decoded here.
+pub(super) fn needs_decoder(mime_type: &str) -> bool {
+    matches!(mime_type, "image/avif" | "image/heic" | "image/heif")
+}
+
+/// Decode `data` as `source`, refusing a JPEG XL image of more than
+/// `max_pixels` pixels before decoding it (0 means no limit).
+pub(crate) fn decode(
+    source: Source,
+    data: &[u8],
+    max_pixels: u64,
+) -> Result<DynamicImage, ProxyError> {
+    match source {
+        Source::Raster(format) => image::load_from_memory_with_format(data, format).map_err(failed),
+        Source::Jxl => {
+            let decoder = JxlDecoder::new(Cursor::new(data)).map_err(failed)?;
+            let (width, height) = decoder.dimensions();
+            if max_pixels > 0 && width as u64 * height as u64 > max_pixels {
+                return Err(ProxyError::ImageTooLarge {
+                    width,
+                    height,
+                    max_pixels,
+                });
+            }
+            DynamicImage::from_decoder(decoder).map_err(failed)
+        }
+    }
+}
+
+pub(super) fn encode(
+    image: &DynamicImage,
+    target: OutputFormat,
+    quality: u8,
+) -> Result<(Vec<u8>, &'static str), ProxyError> {
+    let mut out = Vec::new();
+    let mime_type = match target {
+        OutputFormat::Jpeg => {
+            let encoder = JpegEncoder::new_with_quality(&mut out, quality.clamp(1, 100));
+            image
+                .to_rgb8()
+                .write_with_encoder(encoder)
+                .map_err(failed)?;
+            "image/jpeg"
+        }
+        OutputFormat::Png => {
+            image
+                .write_with_encoder(PngEncoder::new(&mut out))
+                .map_err(failed)?;
+            "image/png"
+        }
+        OutputFormat::Webp => {
+            // The encoder takes 8-bit RGB(A) only.
+            let image = if image.color().has_alpha() {
+                DynamicImage::ImageRgba8(image.to_rgba8())
+            } else {
+                DynamicImage::ImageRgb8(image.to_rgb8())
+            };
+            image
+                .write_with_encoder(WebPEncoder::new_lossless(&mut out))
+                .map_err(failed)?;
+            "image/webp"
+        }
+    };
+    Ok((out, mime_type))
+}
+
+fn failed(err: image::ImageError) -> ProxyError {
+    ProxyError::ImageProcessingError {
+        details: err.to_string(),
+    }
+}
@@ -0,0 +1,404 @@
+//! Downscaling and transcoding images before they cross the FFI boundary.
+//!
+//! Handing a 12 MP photo to the UI to render a 64 dp avatar wastes memory on
+//! both sides of the bridge, and older Android WebViews cannot render JPEG XL
+//! at all. [`proxy_fetch_image_transformed`] fetches the image as usual and,
+//! when it exceeds the requested bounds or must change format, decodes it and
+//! re-encodes a new copy. Without an explicit [`OutputFormat`], JPEGs stay JPEG
+//! at the requested quality and other formats become PNG so transparency
+//! survives.
+//!
+//! The original is cached under its URL as usual; each derived copy is cached
+//! alongside it under a [variant key](crate::cache::variant_key) that encodes
+//! the transform, so repeated requests skip both the network and the re-encode.
+//!
+//! PNG, JPEG, GIF, WebP and JPEG XL are decoded. Animations are handled first,
+//! per the transform's [`AnimationPolicy`](crate::types::AnimationPolicy), and
+//! are only re-encoded when reduced to their first frame. SVG is returned
+//! unchanged, as re-encoding would rasterise it. AVIF and HEIF are left as they
+//! are unless a format is requested, which fails with
+//! [`ProxyError::ImageProcessingError`] since no decoder for them is built in.
+
+mod codec;
+
+use crate::animation;
+use crate::cache::{image_key, variant_key};
+use crate::client::{default_client, ProxyClient};
+use crate::error::ProxyError;
+use crate::fetch_image;
+use crate::integrity::sha256_hex;
+use crate::trace;
+use crate::tracking;
+use crate::types::{
+    AnimationPolicy, ImageResponse, ImageTransform, OutputFormat, RequestPriority, ResponseHeaders,
+};
+pub(crate) use codec::{decodable, decode, Source};
+use codec::{encode, needs_decoder, target_of};
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageFormat};
+use std::collections::HashMap;
+
+#[uniffi::export]
+impl ProxyClient {
+    /// Fetch an image, then downscale and/or transcode it to match `transform`.
+    pub fn fetch_image_transformed(
+        &self,
+        url: String,
+        headers: Option<HashMap<String, String>>,
+        transform: ImageTransform,
+    ) -> Result<ImageResponse, ProxyError> {
+        trace::traced(|| fetch_transformed(self, &url, headers.as_ref(), &transform)).inspect_err(
+            |e| {
+                self.record_error(Some(&url), e);
+            },
+        )
+    }
+}
+
+/// [`ProxyClient::fetch_image_transformed`] on the default proxy.
+#[uniffi::export]
+pub fn proxy_fetch_image_transformed(
+    url: String,
+    headers: Option<HashMap<String, String>>,
+    transform: ImageTransform,
+) -> Result<ImageResponse, ProxyError> {
+    default_client().fetch_image_transformed(url, headers, transform)
+}
+
+fn fetch_transformed(
+    client: &ProxyClient,
+    url: &str,
+    headers: Option<&HashMap<String, String>>,
+    transform: &ImageTransform,
+) -> Result<ImageResponse, ProxyError> {
+    let (key, max_pixels) = {
+        let mut guard = client.lock_state();
+        let state = guard.as_mut().ok_or(ProxyError::NotInitialized)?;
+        let original = image_key(url, &state.config.image_accept);
+        let key = variant_key(&original, &variant_tag(transform));
+        if let Some(cached) = state.cache.get(&key) {
+            let cached = ImageResponse {
+                from_cache: true,
+                trace_id: trace::current().unwrap_or_default(),
+                transport: None,
+                ..cached
+            };
+            return tracking::screen(url, cached, &state.config);
+        }
+        (key, state.fetch_limits().max_pixels)
+    };
+
+    let original = fetch_image(client, url, headers, RequestPriority::Visible)?;
+    let from_cache = original.from_cache;
+    let Some(derived) = apply(&original, transform, max_pixels)? else {
+        return Ok(original);
+    };
+    // data: URIs are never cached, so neither are their variants.
+    if !crate::data_uri::is_data_uri(url) {
+        if let Some(state) = client.lock_state().as_mut() {
+            state.cache.put(key, derived.clone());
+        }
+    }
+    Ok(ImageResponse {
+        from_cache,
+        ..derived
+    })
+}
+
+/// Identifies a transform's output, for its cache key.
+fn variant_tag(transform: &ImageTransform) -> String {
+    let bound = |b: Option<u32>| b.map_or("-".to_string(), |b| b.to_string());
+    let format = match transform.format {
+        None => "auto",
+        Some(OutputFormat::Png) => "png",
+        Some(OutputFormat::Jpeg) => "jpeg",
+        Some(OutputFormat::Webp) => "webp",
+    };
+    // Budgets only pass or refuse; they never change the output.
+    let animation = match transform.animation {
+        AnimationPolicy::FirstFrame => ".first",
+        AnimationPolicy::PassThrough | AnimationPolicy::Budget { .. } => "",
+    };
+    format!(
+        "w{}.h{}.q{}.{format}{animation}",
+        bound(transform.max_width),
+        bound(transform.max_height),
+        transform.quality
+    )
+}
+
+/// Downscale and/or transcode `image` to match `transform`.
+///
+/// Returns `None` when the image is returned as it is. Decoding refuses
+/// images over `max_pixels` (0 = unlimited), as the fetch path does for the
+/// formats whose headers it reads.
+pub(crate) fn apply(
+    image: &ImageResponse,
+    transform: &ImageTransform,
+    max_pixels: u64,
+) -> Result<Option<ImageResponse>, ProxyError> {
+    let frames = animation::frame_count(&image.mime_type, &image.data);
+    if frames > 1 {
+        animation::check_budget(transform.animation, frames, image.data.len() as u64)?;
+        if transform.animation != AnimationPolicy::FirstFrame {
+            return Ok(None);
+        }
+        let first = animation::first_frame(&image.mime_type, &image.data)?;
+        let first = match fit(first.width(), first.height(), transform) {
+            Some((w, h)) => first.resize(w, h, FilterType::Triangle),
+            None => first,
+        };
+        let target = transform.format.unwrap_or(OutputFormat::Png);
+        return encoded(image, &first, target, transform.quality).map(Some);
+    }
+
+    let Some(source) = decodable(&image.mime_type) else {
+        if transform.format.is_some() && needs_decoder(&image.mime_type) {
+            return Err(ProxyError::ImageProcessingError {
+                details: format!("no decoder for {}", image.mime_type),
+            });
+        }
+        return Ok(None);
+    };
+    let target = transform.format.unwrap_or(match source {
+        Source::Raster(ImageFormat::Jpeg) => OutputFormat::Jpeg,
+        _ => OutputFormat::Png,
+    });
+    let resize = match (image.width, image.height) {
+        (Some(width), Some(height)) => fit(width, height, transform),
+        // Dimensions unknown from the header: decide after decoding.
+        _ if transform.max_width.is_none() && transform.max_height.is_none() => None,
+        _ => Some((
+            transform.max_width.unwrap_or(u32::MAX).max(1),
+            transform.max_height.unwrap_or(u32::MAX).max(1),
+        )),
+    };
+    let transcode = transform
+        .format
+        .is_some_and(|format| Some(format) != target_of(source));
+    if resize.is_none() && !transcode {
+        return Ok(None);
+    }
+
+    let decoded = decode(source, &image.data, max_pixels)?;
+    let decoded = match resize {
+        Some((w, h)) if decoded.width() > w || decoded.height() > h => {
+            decoded.resize(w, h, FilterType::Triangle)
+        }
+        _ if !transcode => return Ok(None),
+        _ => decoded,
+    };
+    encoded(image, &decoded, target, transform.quality).map(Some)
+}
+
+/// `original` with its image replaced by `decoded`, encoded as `target`.
+fn encoded(
+    original: &ImageResponse,
+    decoded: &DynamicImage,
+    target: OutputFormat,
+    quality: u8,
+) -> Result<ImageResponse, ProxyError> {
+    let (data, mime_type) = encode(decoded, target, quality)?;
+    Ok(ImageResponse {
+        sha256: sha256_hex(&data),
+        mime_type: mime_type.to_string(),
+        data,
+        width: Some(decoded.width()),
+        height: Some(decoded.height()),
+        // The announced length is the original's.
+        headers: ResponseHeaders {
+            content_length: None,
+            ..original.headers.clone()
+        },
+        ..original.clone()
+    })
+}
+
+/// The bounding box to scale into, or `None` if the image already fits.
+fn fit(width: u32, height: u32, transform: &ImageTransform) -> Option<(u32, u32)> {
+    let max_w = transform.max_width.unwrap_or(u32::MAX).max(1);
+    let max_h = transform.max_height.unwrap_or(u32::MAX).max(1);
+    (width > max_w || height > max_h).then_some((max_w.min(width), max_h.min(height)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sniff::guess_mime_type;
+    use image::{Rgb, RgbImage, Rgba, RgbaImage};
+    use std::io::Cursor;
+
+    fn response(mime_type: &str, data: Vec<u8>, width: u32, height: u32) -> ImageResponse {
+        ImageResponse {
+            mime_type: mime_type.to_string(),
+            data,
+            from_cache: false,
+            final_url: "https://example.com/x".to_string(),
+            redirect_chain: Vec::new(),
+            redirect_hops: Vec::new(),
+            width: Some(width),
+            height: Some(height),
+            blurhash: None,
+            tracking_suspected: false,
+            trace_id: String::new(),
+            headers: Default::default(),
+            sha256: String::new(),
+            transport: None,
+        }
+    }
+
+    fn bounds(max_width: Option<u32>, max_height: Option<u32>) -> ImageTransform {
+        ImageTransform {
+            max_width,
+            max_height,
+            quality: 80,
+            format: None,
+            animation: AnimationPolicy::PassThrough,
+        }
+    }
+
+    fn to(format: OutputFormat) -> ImageTransform {
+        ImageTransform {
+            format: Some(format),
+            ..bounds(None, None)
+        }
+    }
+
+    fn png(width: u32, height: u32) -> Vec<u8> {
+        let mut png = Vec::new();
+        RgbaImage::from_pixel(width, height, Rgba([0, 0, 0, 0]))
+            .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+            .unwrap();
+        png
+    }
+
+    #[test]
+    fn fit_only_shrinks() {
+        assert_eq!(fit(100, 50, &bounds(Some(200), None)), None);
+        assert_eq!(fit(400, 200, &bounds(Some(200), None)), Some((200, 200)));
+        assert_eq!(fit(400, 200, &bounds(None, Some(50))), Some((400, 50)));
+        assert_eq!(fit(400, 200, &bounds(None, None)), None);
+    }
+
+    #[test]
+    fn variant_tags_distinguish_transforms() {
+        assert_eq!(variant_tag(&bounds(Some(64), None)), "w64.h-.q80.auto");
+        assert_ne!(
+            variant_tag(&to(OutputFormat::Png)),
+            variant_tag(&to(OutputFormat::Webp))
+        );
+    }
+
+    #[test]
+    fn downscales_jpeg_preserving_aspect_ratio() {
+        let mut jpeg = Vec::new();
+        RgbImage::from_pixel(400, 200, Rgb([200, 10, 10]))
+            .write_to(&mut Cursor::new(&mut jpeg), ImageFormat::Jpeg)
+            .unwrap();
+        let out = apply(
+            &response("image/jpeg", jpeg, 400, 200),
+            &bounds(Some(100), Some(100)),
+            0,
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(out.mime_type, "image/jpeg");
+        assert_eq!((out.width, out.height), (Some(100), Some(50)));
+        assert_eq!(
+            crate::dimensions::image_dimensions(&out.data),
+            Some((100, 50))
+        );
+    }
+
+    #[test]
+    fn keeps_transparency_as_png() {
+        let image = response("image/png", png(64, 64), 64, 64);
+        let out = apply(&image, &bounds(Some(16), None), 0).unwrap().unwrap();
+        assert_eq!(out.mime_type, "image/png");
+        assert_eq!((out.width, out.height), (Some(16), Some(16)));
+    }
+
+    #[test]
+    fn transcodes_to_requested_format() {
+        let image = response("image/png", png(8, 8), 8, 8);
+        for (format, mime_type) in [
+            (OutputFormat::Jpeg, "image/jpeg"),
+            (OutputFormat::Webp, "image/webp"),
+        ] {
+            let out = apply(&image, &to(format), 0).unwrap().unwrap();
+            assert_eq!(out.mime_type, mime_type);
+            assert_eq!(guess_mime_type(&out.data), Some(mime_type));
+            assert_eq!((out.width, out.height), (Some(8), Some(8)));
+        }
+        // Already in the requested format and within bounds.
+        assert!(apply(&image, &to(OutputFormat::Png), 0).unwrap().is_none());
+    }
+
+    #[test]
+    fn formats_without_a_decoder() {
+        let avif = response("image/avif", b"....ftypavif".to_vec(), 10, 10);
+        assert!(apply(&avif, &bounds(Some(1), None), 0).unwrap().is_none());
+        assert!(matches!(
+            apply(&avif, &to(OutputFormat::Png), 0),
+            Err(ProxyError::ImageProcessingError { .. })
+        ));
+        let jxl = response("image/jxl", b"\xff\x0a garbage".to_vec(), 10, 10);
+        assert!(matches!(
+            apply(&jxl, &to(OutputFormat::Png), 0),
+            Err(ProxyError::ImageProcessingError { .. })
+        ));
+    }
+
+    #[test]
+    fn animation_policies() {
+        let mut gif = Vec::new();
+        let frames = (0..3)
+            .map(|i| image::Frame::new(RgbaImage::from_pixel(40, 20, Rgba([i * 80, 0, 0, 255]))));
+        image::codecs::gif::GifEncoder::new(&mut gif)
+            .encode_frames(frames)
+            .unwrap();
+        let animated = response("image/gif", gif, 40, 20);
+
+        // Passed through untouched, even when over the bounds.
+        assert!(apply(&animated, &bounds(Some(10), None), 0)
+            .unwrap()
+            .is_none());
+
+        let first = ImageTransform {
+            animation: AnimationPolicy::FirstFrame,
+            ..bounds(Some(10), None)
+        };
+        let out = apply(&animated, &first, 0).unwrap().unwrap();
+        assert_eq!(out.mime_type, "image/png");
+        assert_eq!((out.width, out.height), (Some(10), Some(5)));
+
+        let budget = |max_frames| ImageTransform {
+            animation: AnimationPolicy::Budget {
+                max_frames,
+                max_bytes: 1 << 20,
+            },
+            ..bounds(None, None)
+        };
+        assert!(apply(&animated, &budget(3), 0).unwrap().is_none());
+        assert!(matches!(
+            apply(&animated, &budget(2), 0),
+            Err(ProxyError::AnimationTooLarge { frames: 3, .. })
+        ));
+    }
+
+    #[test]
+    fn leaves_small_and_vector_images_alone() {
+        let svg = response("image/svg+xml", b"<svg/>".to_vec(), 4000, 4000);
+        assert!(apply(&svg, &to(OutputFormat::Png), 0).unwrap().is_none());
+
+        let garbage = response("image/png", b"not a png".to_vec(), 10, 10);
+        assert!(apply(&garbage, &bounds(Some(20), None), 0)
+            .unwrap()
+            .is_none());
+        let garbage = response("image/png", b"not a png".to_vec(), 100, 100);
+        assert!(matches!(
+            apply(&garbage, &bounds(Some(20), None), 0),
+            Err(ProxyError::ImageProcessingError { .. })
+        ));
+    }
+}
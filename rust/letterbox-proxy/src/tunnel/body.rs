@@ -0,0 +1,288 @@
+//! Incremental HTTP/1.1 body decoding.
+//!
+//! [`BodyDecoder`] turns the raw bytes following a response head into body
+//! bytes as they arrive, honouring `Content-Length`, `Transfer-Encoding:
+//! chunked` or close-delimited framing, and writes them straight into any
+//! [`Write`] sink — a `Vec` for in-memory fetches, a file for downloads. The
+//! body size limit is enforced on the decoded bytes before each write, so an
+//! oversized response is abandoned mid-stream rather than buffered first.
+
+use crate::error::ProxyError;
+use crate::tunnel::http1::ResponseHead;
+use std::io::Write;
+
+/// A body destination that can also learn the announced body length.
+///
+/// Implemented by the in-memory and discard sinks directly, and by the HTTP
+/// layer's progress-reporting writer, which uses the length as the total.
+pub trait BodySink: Write {
+    /// Whether the body should be read at all. When `false`, only the head is
+    /// read (e.g. for `HEAD` requests or metadata probes) and the connection is
+    /// closed without touching the body.
+    fn wants_body(&self) -> bool {
+        true
+    }
+
+    /// Called once, before any body bytes, with the `Content-Length` if known.
+    fn expect_len(&mut self, _len: Option<u64>) {}
+}
+
+impl BodySink for Vec<u8> {}
+
+impl BodySink for std::io::Sink {}
+
+/// How the end of the body is delimited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Framing {
+    /// Exactly this many bytes (`Content-Length`).
+    Length(u64),
+    /// `Transfer-Encoding: chunked`.
+    Chunked,
+    /// Everything until the server closes the connection.
+    UntilClose,
+}
+
+impl Framing {
+    /// The exact body length, if announced.
+    pub fn known_len(self) -> Option<u64> {
+        match self {
+            Framing::Length(len) => Some(len),
+            Framing::Chunked | Framing::UntilClose => None,
+        }
+    }
+
+    /// Determine the framing of `head`'s body.
+    pub fn of(head: &ResponseHead) -> Self {
+        if matches!(head.status, 204 | 304) {
+            return Framing::Length(0);
+        }
+        let chunked = head
+            .header("transfer-encoding")
+            .is_some_and(|v| v.to_ascii_lowercase().contains("chunked"));
+        if chunked {
+            return Framing::Chunked;
+        }
+        match head.header("content-length").map(str::parse::<u64>) {
+            Some(Ok(len)) => Framing::Length(len),
+            _ => Framing::UntilClose,
+        }
+    }
+}
+
+/// Decoder position within the body.
+#[derive(Debug)]
+enum State {
+    /// Fixed-length body with this many bytes left.
+    Length(u64),
+    /// Close-delimited body.
+    UntilClose,
+    /// Accumulating a chunk-size line.
+    ChunkSize(Vec<u8>),
+    /// Inside a chunk with this many data bytes left.
+    ChunkData(u64),
+    /// Skipping the CRLF after a chunk's data; holds bytes still to skip.
+    ChunkEnd(u8),
+    /// The body is complete; anything further (trailers) is ignored.
+    Done,
+}
+
+/// Longest chunk-size line accepted (size plus extensions).
+const MAX_CHUNK_LINE: usize = 1024;
+
+/// Streaming body decoder with a size ceiling.
+#[derive(Debug)]
+pub struct BodyDecoder {
+    state: State,
+    written: u64,
+    max_body: u64,
+}
+
+impl BodyDecoder {
+    /// Start decoding a body with `framing`, rejecting bodies over `max_body`.
+    ///
+    /// An announced `Content-Length` over the limit fails immediately.
+    pub fn new(framing: Framing, max_body: u64) -> Result<Self, ProxyError> {
+        let state = match framing {
+            Framing::Length(len) if len > max_body => {
+                return Err(ProxyError::ResponseTooLarge {
+                    size: len,
+                    max_size: max_body,
+                });
+            }
+            Framing::Length(0) => State::Done,
+            Framing::Length(len) => State::Length(len),
+            Framing::Chunked => State::ChunkSize(Vec::new()),
+            Framing::UntilClose => State::UntilClose,
+        };
+        Ok(Self {
+            state,
+            written: 0,
+            max_body,
+        })
+    }
+
+    /// Whether the whole body has been decoded.
+    pub fn is_done(&self) -> bool {
+        matches!(self.state, State::Done)
+    }
+
+    /// Decode `input`, writing body bytes to `out`.
+    pub fn feed<W: Write>(&mut self, mut input: &[u8], out: &mut W) -> Result<(), ProxyError> {
+        while !input.is_empty() {
+            match &mut self.state {
+                State::Done => return Ok(()),
+                State::UntilClose => {
+                    self.emit(input, out)?;
+                    input = &[];
+                }
+                State::Length(remaining) => {
+                    let take = input
+                        .len()
+                        .min(usize::try_from(*remaining).unwrap_or(usize::MAX));
+                    *remaining -= take as u64;
+                    if *remaining == 0 {
+                        self.state = State::Done;
+                    }
+                    self.emit(&input[..take], out)?;
+                    input = &input[take..];
+                }
+                State::ChunkData(remaining) => {
+                    let take = input
+                        .len()
+                        .min(usize::try_from(*remaining).unwrap_or(usize::MAX));
+                    *remaining -= take as u64;
+                    if *remaining == 0 {
+                        self.state = State::ChunkEnd(2);
+                    }
+                    self.emit(&input[..take], out)?;
+                    input = &input[take..];
+                }
+                State::ChunkEnd(left) => {
+                    let take = input.len().min(*left as usize);
+                    *left -= take as u8;
+                    if *left == 0 {
+                        self.state = State::ChunkSize(Vec::new());
+                    }
+                    input = &input[take..];
+                }
+                State::ChunkSize(line) => match input.iter().position(|&b| b == b'\n') {
+                    Some(newline) => {
+                        line.extend_from_slice(&input[..newline]);
+                        let size = parse_chunk_size(line)?;
+                        self.state = if size == 0 {
+                            State::Done
+                        } else {
+                            State::ChunkData(size)
+                        };
+                        input = &input[newline + 1..];
+                    }
+                    None => {
+                        line.extend_from_slice(input);
+                        if line.len() > MAX_CHUNK_LINE {
+                            return Err(malformed("Chunk header too long"));
+                        }
+                        input = &[];
+                    }
+                },
+            }
+        }
+        Ok(())
+    }
+
+    /// Signal end of input; fails if a chunked body was cut short.
+    pub fn finish(&self) -> Result<(), ProxyError> {
+        match self.state {
+            State::ChunkSize(_) | State::ChunkData(_) | State::ChunkEnd(_) => {
+                Err(malformed("Truncated chunked body"))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn emit<W: Write>(&mut self, data: &[u8], out: &mut W) -> Result<(), ProxyError> {
+        let total = self.written + data.len() as u64;
+        if total > self.max_body {
+            return Err(ProxyError::ResponseTooLarge {
+                size: total,
+                max_size: self.max_body,
+            });
+        }
+        out.write_all(data)?;
+        self.written = total;
+        Ok(())
+    }
+}
+
+fn malformed(details: &str) -> ProxyError {
+    ProxyError::HttpError {
+        status_code: 0,
+        details: details.to_string(),
+    }
+}
+
+/// Parse a chunk-size line (hex size, optional `;extensions`, optional CR).
+fn parse_chunk_size(line: &[u8]) -> Result<u64, ProxyError> {
+    let line = std::str::from_utf8(line).unwrap_or("");
+    let size_hex = line.split(';').next().unwrap_or("").trim();
+    u64::from_str_radix(size_hex, 16)
+        .map_err(|_| malformed(&format!("Invalid chunk size: {size_hex}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode_in_pieces(framing: Framing, raw: &[u8], piece: usize) -> Vec<u8> {
+        let mut decoder = BodyDecoder::new(framing, 1 << 20).unwrap();
+        let mut out = Vec::new();
+        for part in raw.chunks(piece) {
+            decoder.feed(part, &mut out).unwrap();
+        }
+        decoder.finish().unwrap();
+        out
+    }
+
+    #[test]
+    fn chunked_decodes_across_arbitrary_splits() {
+        let raw = b"4\r\nWiki\r\n5;ext=1\r\npedia\r\n0\r\n\r\n";
+        for piece in 1..raw.len() {
+            assert_eq!(decode_in_pieces(Framing::Chunked, raw, piece), b"Wikipedia");
+        }
+    }
+
+    #[test]
+    fn content_length_stops_at_length() {
+        let out = decode_in_pieces(Framing::Length(4), b"\x89PNGtrailing", 3);
+        assert_eq!(out, b"\x89PNG");
+    }
+
+    #[test]
+    fn oversized_announced_length_fails_up_front() {
+        assert!(matches!(
+            BodyDecoder::new(Framing::Length(524_288_000), 1024),
+            Err(ProxyError::ResponseTooLarge {
+                size: 524_288_000,
+                max_size: 1024
+            })
+        ));
+    }
+
+    #[test]
+    fn unannounced_body_fails_at_limit_without_writing_past_it() {
+        let mut decoder = BodyDecoder::new(Framing::UntilClose, 4).unwrap();
+        let mut out = Vec::new();
+        decoder.feed(b"1234", &mut out).unwrap();
+        assert!(matches!(
+            decoder.feed(b"5", &mut out),
+            Err(ProxyError::ResponseTooLarge { size: 5, .. })
+        ));
+        assert_eq!(out, b"1234");
+    }
+
+    #[test]
+    fn truncated_chunked_body_is_an_error() {
+        let mut decoder = BodyDecoder::new(Framing::Chunked, 1024).unwrap();
+        decoder.feed(b"a\r\nshort", &mut Vec::new()).unwrap();
+        assert!(decoder.finish().is_err());
+    }
+}
@@ -0,0 +1,263 @@
+//! Direct connections over the host's own TCP sockets.
+//!
+//! Builds without the `tunnel` feature carry requests over a [`DirectNetwork`]
+//! instead of the WARP tunnel. Names are still resolved over DoH and every
+//! header, redirect and content control still applies, but the image servers
+//! see the device's own IP address.
+
+use crate::error::ProxyError;
+use crate::timeouts::Deadline;
+use crate::tunnel::dns::DnsCache;
+use crate::tunnel::h2::SessionPool;
+use crate::tunnel::network::Network;
+use crate::tunnel::tls::TlsSessionCache;
+use crate::types::Transport;
+use std::io::{self, Read, Write};
+use std::net::{SocketAddr, TcpStream};
+
+/// Identifies a connection of a [`DirectNetwork`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ConnectionId(usize);
+
+/// Plain TCP connections, addressed by [`ConnectionId`].
+#[derive(Default)]
+pub struct DirectNetwork {
+    /// Open connections; a closed one leaves its slot free for reuse.
+    connections: Vec<Option<TcpStream>>,
+    sessions: SessionPool<ConnectionId>,
+    dns: DnsCache,
+    tls: TlsSessionCache,
+    /// Bytes written to and read from all connections so far.
+    sent: u64,
+    received: u64,
+}
+
+/// Open a TCP connection to `remote`, waiting until `deadline`.
+pub(crate) fn connect(remote: SocketAddr, deadline: &Deadline) -> Result<TcpStream, ProxyError> {
+    let failed = |e: io::Error| {
+        deadline.io_error(e, |e| ProxyError::NetworkUnavailable {
+            details: format!("TCP connect to {remote} failed: {e}"),
+        })
+    };
+    let timeout = deadline.remaining();
+    if timeout.is_zero() {
+        return Err(deadline.timed_out());
+    }
+    let stream = TcpStream::connect_timeout(&remote, timeout).map_err(failed)?;
+    // Requests are written whole, so there is nothing to gain from Nagle.
+    stream.set_nodelay(true).map_err(failed)?;
+    Ok(stream)
+}
+
+impl DirectNetwork {
+    /// Keep `stream` as an open connection of this network.
+    pub(crate) fn adopt(&mut self, stream: TcpStream) -> ConnectionId {
+        let free = self.connections.iter().position(Option::is_none);
+        let index = free.unwrap_or(self.connections.len());
+        match self.connections.get_mut(index) {
+            Some(slot) => *slot = Some(stream),
+            None => self.connections.push(Some(stream)),
+        }
+        ConnectionId(index)
+    }
+}
+
+impl Network for DirectNetwork {
+    const TRANSPORT: Transport = Transport::Direct;
+
+    type Handle = ConnectionId;
+    type Stream<'a> = DirectStream<'a>;
+
+    fn open_tcp(
+        &mut self,
+        remote: SocketAddr,
+        deadline: &Deadline,
+    ) -> Result<ConnectionId, ProxyError> {
+        let stream = connect(remote, deadline)?;
+        Ok(self.adopt(stream))
+    }
+
+    fn close_tcp(&mut self, handle: ConnectionId) {
+        if let Some(stream) = self.connections.get_mut(handle.0).and_then(Option::take) {
+            let _ = stream.shutdown(std::net::Shutdown::Both);
+        }
+    }
+
+    fn stream<'a>(&'a mut self, handle: ConnectionId, deadline: &'a Deadline) -> DirectStream<'a> {
+        DirectStream {
+            stream: self.connections.get_mut(handle.0).and_then(Option::as_mut),
+            deadline,
+            sent: &mut self.sent,
+            received: &mut self.received,
+        }
+    }
+
+    fn sessions(&mut self) -> &mut SessionPool<ConnectionId> {
+        &mut self.sessions
+    }
+
+    fn dns_cache(&mut self) -> &mut DnsCache {
+        &mut self.dns
+    }
+
+    fn tls_sessions(&mut self) -> &mut TlsSessionCache {
+        &mut self.tls
+    }
+
+    fn traffic(&self) -> Option<(u64, u64)> {
+        Some((self.sent, self.received))
+    }
+}
+
+/// A borrowed connection of a [`DirectNetwork`] (`None` once closed).
+pub struct DirectStream<'a> {
+    stream: Option<&'a mut TcpStream>,
+    deadline: &'a Deadline,
+    /// The network's traffic counters.
+    sent: &'a mut u64,
+    received: &'a mut u64,
+}
+
+impl DirectStream<'_> {
+    /// The socket, with its timeouts set to the time left until the deadline.
+    fn get(&mut self) -> io::Result<&mut TcpStream> {
+        let stream = self
+            .stream
+            .as_deref_mut()
+            .ok_or(io::ErrorKind::NotConnected)?;
+        let left = self.deadline.remaining();
+        if left.is_zero() {
+            return Err(io::ErrorKind::TimedOut.into());
+        }
+        stream.set_read_timeout(Some(left))?;
+        stream.set_write_timeout(Some(left))?;
+        Ok(stream)
+    }
+}
+
+/// A socket timeout as [`TimedOut`](io::ErrorKind::TimedOut); Unix reports
+/// it as `WouldBlock`.
+fn timed_out(e: io::Error) -> io::Error {
+    match e.kind() {
+        io::ErrorKind::WouldBlock => io::ErrorKind::TimedOut.into(),
+        _ => e,
+    }
+}
+
+impl Read for DirectStream<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.get()?.read(buf).map_err(timed_out)?;
+        *self.received += read as u64;
+        Ok(read)
+    }
+}
+
+impl Write for DirectStream<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.get()?.write(buf).map_err(timed_out)?;
+        *self.sent += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.get()?.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::FetchLimits;
+    use crate::error::TimeoutPhase;
+    use crate::http;
+    use crate::types::{RetryPolicy, TimeoutBudget};
+    use std::io::{BufRead, BufReader};
+    use std::net::TcpListener;
+    use std::time::Duration;
+
+    #[test]
+    fn fetches_over_plain_sockets_and_reuses_slots() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = std::thread::spawn(move || {
+            for _ in 0..2 {
+                let (mut conn, _) = listener.accept().unwrap();
+                let mut request_line = String::new();
+                BufReader::new(&mut conn)
+                    .read_line(&mut request_line)
+                    .unwrap();
+                assert!(request_line.starts_with("GET /a.png "));
+                conn.write_all(
+                    b"HTTP/1.1 200 OK\r\nContent-Type: image/png\r\nContent-Length: 3\r\n\
+                      Content-Disposition: inline; filename=\"a.png\"\r\n\
+                      Connection: close\r\n\r\nabc",
+                )
+                .unwrap();
+            }
+        });
+
+        let mut network = DirectNetwork::default();
+        let limits = FetchLimits::default();
+        let url = format!("http://127.0.0.1:{port}/a.png");
+        for _ in 0..2 {
+            let outcome = http::fetch(&mut network, &url, &[], &limits, "image/*", None).unwrap();
+            assert_eq!(
+                (outcome.mime_type.as_str(), &outcome.body[..]),
+                ("image/png", &b"abc"[..])
+            );
+            assert_eq!(outcome.headers.filename.as_deref(), Some("a.png"));
+            assert_eq!(outcome.headers.content_length, Some(3));
+            assert_eq!(outcome.transport, Transport::Direct);
+        }
+        server.join().unwrap();
+        assert_eq!(network.connections.len(), 1);
+        let (sent, received) = network.traffic().unwrap();
+        assert!(sent > 0 && received > 0);
+        assert!(network.diagnostics().is_err());
+    }
+
+    #[test]
+    fn a_silent_server_times_out_waiting_for_the_first_byte() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = std::thread::spawn(move || {
+            let (conn, _) = listener.accept().unwrap();
+            std::thread::sleep(Duration::from_millis(1500));
+            drop(conn);
+        });
+
+        let mut network = DirectNetwork::default();
+        let limits = FetchLimits {
+            timeouts: TimeoutBudget {
+                first_byte_seconds: 1,
+                ..TimeoutBudget::default()
+            },
+            retry: RetryPolicy {
+                max_attempts: 1,
+                ..Default::default()
+            },
+            ..FetchLimits::default()
+        };
+        let url = format!("http://127.0.0.1:{port}/a.png");
+        let error = http::fetch(&mut network, &url, &[], &limits, "image/*", None).unwrap_err();
+        assert_eq!(
+            error.error,
+            ProxyError::Timeout {
+                phase: TimeoutPhase::FirstByte,
+                seconds: 1
+            }
+        );
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn closed_connections_fail_instead_of_panicking() {
+        let mut network = DirectNetwork::default();
+        let deadline = Deadline::fixed(TimeoutPhase::Body, Duration::from_secs(1));
+        let mut stream = network.stream(ConnectionId(3), &deadline);
+        assert_eq!(
+            stream.read(&mut [0; 4]).unwrap_err().kind(),
+            io::ErrorKind::NotConnected
+        );
+    }
+}
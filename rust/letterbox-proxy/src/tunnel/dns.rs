@@ -4,18 +4,25 @@
 //! resolution *inside* the encrypted tunnel: the host's real resolver — and
 //! therefore the user's ISP — never sees which image servers are queried. The
 //! resolver IP (`1.1.1.1`) is a literal, so DoH itself needs no bootstrap DNS.
+//! Builds without the `tunnel` feature send the same queries directly, which
+//! still keeps them from the local network and resolver.
+//!
+//! Answers are kept in the network's [`DnsCache`] for their TTL, clamped to
+//! between 30 seconds and 10 minutes, so a batch of images from one host costs
+//! one lookup rather than one per image.
 
-use crate::error::ProxyError;
-use crate::tunnel::http1::{build_get_request, parse_response};
-use crate::tunnel::stack::WarpTunnel;
-use crate::tunnel::tls::request_https;
+use crate::error::{ProxyError, TimeoutPhase};
+use crate::timeouts::Deadline;
+use crate::tunnel::http1::build_get_request;
+use crate::tunnel::network::Network;
+use crate::tunnel::tls::{request_https, SendOptions, TlsServer};
 use serde::Deserialize;
-use smoltcp::wire::IpAddress;
-use std::net::Ipv4Addr;
-use std::time::Duration;
+use std::collections::HashMap;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::time::{Duration, Instant};
 
 /// Cloudflare's DoH resolver address (a literal, needs no resolution itself).
-const DOH_RESOLVER: IpAddress = IpAddress::v4(1, 1, 1, 1);
+const DOH_RESOLVER: Ipv4Addr = Ipv4Addr::new(1, 1, 1, 1);
 
 /// SNI / `Host` used for the DoH resolver (valid on Cloudflare's certificate).
 const DOH_HOST: &str = "one.one.one.one";
@@ -24,39 +31,127 @@ const DOH_HOST: &str = "one.one.one.one";
 const DNS_TYPE_A: u16 = 1;
 
 /// Maximum DoH response size (answers are tiny).
-const MAX_DOH_RESPONSE: usize = 64 * 1024;
+const MAX_DOH_RESPONSE: u64 = 64 * 1024;
+
+/// Shortest time an answer is cached, however low its TTL.
+const MIN_TTL: Duration = Duration::from_secs(30);
+
+/// Longest time an answer is cached, so a moved host is found again soon.
+const MAX_TTL: Duration = Duration::from_secs(600);
+
+/// Most host names cached at once.
+const MAX_CACHED: usize = 256;
 
 /// A DoH JSON response (subset of fields we care about).
 #[derive(Debug, Deserialize)]
-struct DohResponse {
+pub(crate) struct DohResponse {
     #[serde(rename = "Answer", default)]
-    answer: Vec<DohAnswer>,
+    pub(crate) answer: Vec<DohAnswer>,
 }
 
 /// A single DoH answer record.
 #[derive(Debug, Deserialize)]
-struct DohAnswer {
+pub(crate) struct DohAnswer {
     #[serde(rename = "type")]
-    record_type: u16,
-    data: String,
+    pub(crate) record_type: u16,
+    pub(crate) data: String,
+    /// Seconds the record may be cached for.
+    #[serde(rename = "TTL", default)]
+    pub(crate) ttl: u32,
+}
+
+/// Answers by host name, until their TTL runs out.
+#[derive(Debug)]
+struct Expiring<V> {
+    entries: HashMap<String, (V, Instant)>,
 }
 
-/// Resolve `host` to an IPv4 [`IpAddress`] through the tunnel.
+impl<V> Default for Expiring<V> {
+    fn default() -> Self {
+        Expiring {
+            entries: HashMap::new(),
+        }
+    }
+}
+
+impl<V: Clone> Expiring<V> {
+    /// The answer for `host`, unless it expired by `now`.
+    fn get(&mut self, host: &str, now: Instant) -> Option<V> {
+        match self.entries.get(host) {
+            Some((value, expires)) if now < *expires => Some(value.clone()),
+            Some(_) => {
+                self.entries.remove(host);
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// Keep `value` for `host` from `now`, for `ttl` seconds within the floor
+    /// and ceiling.
+    fn put(&mut self, host: &str, value: V, ttl: u32, now: Instant) {
+        if !self.entries.contains_key(host) && self.entries.len() >= MAX_CACHED {
+            self.entries.retain(|_, (_, expires)| now < *expires);
+            if self.entries.len() >= MAX_CACHED {
+                let soonest = self
+                    .entries
+                    .iter()
+                    .min_by_key(|(_, (_, expires))| *expires)
+                    .map(|(host, _)| host.clone());
+                if let Some(soonest) = soonest {
+                    self.entries.remove(&soonest);
+                }
+            }
+        }
+        let ttl = Duration::from_secs(ttl.into()).clamp(MIN_TTL, MAX_TTL);
+        self.entries.insert(host.to_string(), (value, now + ttl));
+    }
+
+    fn forget(&mut self, host: &str) {
+        self.entries.remove(host);
+    }
+}
+
+/// Resolved addresses and ECH configurations by host name, until their TTL
+/// runs out.
+#[derive(Debug, Default)]
+pub struct DnsCache {
+    addresses: Expiring<Ipv4Addr>,
+    /// The ECH configuration list each host publishes, or `None` if it has
+    /// none.
+    ech_configs: Expiring<Option<Vec<u8>>>,
+}
+
+impl DnsCache {
+    /// The ECH configuration list of `host`, if looked up within its TTL.
+    pub(crate) fn ech_config(&mut self, host: &str) -> Option<Option<Vec<u8>>> {
+        self.ech_configs.get(host, Instant::now())
+    }
+
+    /// Remember the ECH configuration list `host` publishes, if any.
+    pub(crate) fn put_ech_config(&mut self, host: &str, config: Option<Vec<u8>>, ttl: u32) {
+        self.ech_configs.put(host, config, ttl, Instant::now());
+    }
+
+    /// Drop the answers for `host`, e.g. after it could not be reached.
+    pub fn forget(&mut self, host: &str) {
+        self.addresses.forget(host);
+        self.ech_configs.forget(host);
+    }
+}
+
+/// Resolve `host` to an IPv4 address over `tunnel`.
 ///
-/// Literal IPv4 addresses are returned directly. Hostnames are resolved via
-/// DoH; the first `A` record is used.
-pub fn resolve(
-    tunnel: &mut WarpTunnel,
+/// Literal IPv4 addresses are returned directly. Hostnames are answered from
+/// the network's [`DnsCache`] or resolved via DoH; the first `A` record is
+/// used.
+pub fn resolve<N: Network>(
+    tunnel: &mut N,
     host: &str,
     timeout: Duration,
-) -> Result<IpAddress, ProxyError> {
+) -> Result<Ipv4Addr, ProxyError> {
     if let Ok(addr) = host.parse::<Ipv4Addr>() {
-        return Ok(IpAddress::v4(
-            addr.octets()[0],
-            addr.octets()[1],
-            addr.octets()[2],
-            addr.octets()[3],
-        ));
+        return Ok(addr);
     }
 
     if !is_valid_hostname(host) {
@@ -66,50 +161,72 @@ pub fn resolve(
         });
     }
 
-    let path = format!("/dns-query?name={host}&type=A");
+    if let Some(addr) = tunnel.dns_cache().addresses.get(host, Instant::now()) {
+        return Ok(addr);
+    }
+
+    let parsed = query(tunnel, host, "A", timeout)?;
+    let (addr, ttl) = first_a_record(&parsed).ok_or_else(|| ProxyError::DnsError {
+        host: host.to_string(),
+        details: "No A record in DoH response".to_string(),
+    })?;
+    tunnel
+        .dns_cache()
+        .addresses
+        .put(host, addr, ttl, Instant::now());
+    Ok(addr)
+}
+
+/// Ask the DoH resolver for the `record_type` records of `host`, which must
+/// be a valid hostname.
+pub(crate) fn query<N: Network>(
+    tunnel: &mut N,
+    host: &str,
+    record_type: &str,
+    timeout: Duration,
+) -> Result<DohResponse, ProxyError> {
+    let path = format!("/dns-query?name={host}&type={record_type}");
     let request = build_get_request(DOH_HOST, &path, "application/dns-json", &[]);
 
-    let raw = request_https(
+    let mut body = Vec::new();
+    let head = request_https(
         tunnel,
-        DOH_RESOLVER,
-        443,
-        DOH_HOST,
+        TlsServer {
+            endpoint: SocketAddr::from((DOH_RESOLVER, 443)),
+            name: DOH_HOST,
+            ech: None,
+        },
         &request,
+        SendOptions::default(),
         MAX_DOH_RESPONSE,
-        timeout,
+        &mut body,
+        &Deadline::fixed(TimeoutPhase::Dns, timeout),
     )?;
 
-    let response = parse_response(&raw)?;
-    if response.status != 200 {
+    if head.status != 200 {
         return Err(ProxyError::DnsError {
             host: host.to_string(),
-            details: format!("DoH resolver returned status {}", response.status),
+            details: format!("DoH resolver returned status {}", head.status),
         });
     }
 
-    let parsed: DohResponse =
-        serde_json::from_slice(&response.body).map_err(|e| ProxyError::DnsError {
-            host: host.to_string(),
-            details: format!("Failed to parse DoH response: {e}"),
-        })?;
+    serde_json::from_slice(&body).map_err(|e| ProxyError::DnsError {
+        host: host.to_string(),
+        details: format!("Failed to parse DoH response: {e}"),
+    })
+}
 
-    parsed
+/// The first `A` record of `response` and its TTL.
+fn first_a_record(response: &DohResponse) -> Option<(Ipv4Addr, u32)> {
+    response
         .answer
         .iter()
         .filter(|a| a.record_type == DNS_TYPE_A)
-        .find_map(|a| a.data.parse::<Ipv4Addr>().ok())
-        .map(|addr| {
-            let o = addr.octets();
-            IpAddress::v4(o[0], o[1], o[2], o[3])
-        })
-        .ok_or_else(|| ProxyError::DnsError {
-            host: host.to_string(),
-            details: "No A record in DoH response".to_string(),
-        })
+        .find_map(|a| Some((a.data.parse::<Ipv4Addr>().ok()?, a.ttl)))
 }
 
 /// Validate a hostname so it cannot smuggle characters into the DoH URL.
-fn is_valid_hostname(host: &str) -> bool {
+pub(crate) fn is_valid_hostname(host: &str) -> bool {
     !host.is_empty()
         && host.len() <= 253
         && host
@@ -139,13 +256,52 @@ mod tests {
 
     #[test]
     fn deserializes_doh_response() {
-        let json = br#"{"Status":0,"Answer":[{"name":"x","type":5,"data":"cname.example."},{"name":"x","type":1,"data":"93.184.216.34"}]}"#;
+        let json = br#"{"Status":0,"Answer":[{"name":"x","type":5,"data":"cname.example."},{"name":"x","type":1,"data":"93.184.216.34","TTL":120}]}"#;
         let parsed: DohResponse = serde_json::from_slice(json).unwrap();
-        let ip = parsed
-            .answer
-            .iter()
-            .filter(|a| a.record_type == DNS_TYPE_A)
-            .find_map(|a| a.data.parse::<Ipv4Addr>().ok());
-        assert_eq!(ip, Some(Ipv4Addr::new(93, 184, 216, 34)));
+        assert_eq!(
+            first_a_record(&parsed),
+            Some((Ipv4Addr::new(93, 184, 216, 34), 120))
+        );
+    }
+
+    #[test]
+    fn cached_answers_expire_within_the_floor_and_ceiling() {
+        let mut cache = Expiring::default();
+        let now = Instant::now();
+        let addr = Ipv4Addr::new(192, 0, 2, 1);
+        cache.put("short.test", addr, 1, now);
+        cache.put("long.test", addr, 86_400, now);
+        assert_eq!(
+            cache.get("short.test", now + Duration::from_secs(29)),
+            Some(addr)
+        );
+        assert_eq!(cache.get("short.test", now + MIN_TTL), None);
+        assert_eq!(
+            cache.get("long.test", now + MAX_TTL - Duration::from_secs(1)),
+            Some(addr)
+        );
+        assert_eq!(cache.get("long.test", now + MAX_TTL), None);
+
+        let mut dns = DnsCache::default();
+        dns.addresses.put("gone.test", addr, 300, now);
+        dns.put_ech_config("gone.test", Some(vec![1]), 300);
+        dns.forget("gone.test");
+        assert_eq!(dns.addresses.get("gone.test", now), None);
+        assert_eq!(dns.ech_config("gone.test"), None);
+    }
+
+    #[test]
+    fn a_full_cache_drops_the_answer_expiring_first() {
+        let mut cache = Expiring::default();
+        let now = Instant::now();
+        let addr = Ipv4Addr::new(192, 0, 2, 1);
+        for i in 0..MAX_CACHED {
+            cache.put(&format!("h{i}.test"), addr, 60 + i as u32, now);
+        }
+        cache.put("new.test", addr, 60, now);
+        assert_eq!(cache.entries.len(), MAX_CACHED);
+        assert_eq!(cache.get("h0.test", now), None);
+        assert_eq!(cache.get("h1.test", now), Some(addr));
+        assert_eq!(cache.get("new.test", now), Some(addr));
     }
 }
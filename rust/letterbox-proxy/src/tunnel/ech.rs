@@ -0,0 +1,146 @@
+//! Encrypted ClientHello (ECH) configurations from DNS.
+//!
+//! A TLS ClientHello names the server in the clear (SNI), so even with the
+//! request itself encrypted, an on-path observer outside the tunnel — or on
+//! the local network in builds without the `tunnel` feature — learns which
+//! image hosts are fetched. Hosts that support ECH publish a configuration
+//! list in the `ech` parameter of their DNS `HTTPS` record; the TLS layer
+//! then encrypts the real ClientHello and shows only the provider's public
+//! name.
+//!
+//! The records are looked up over the same DoH resolver as addresses and
+//! cached in the network's [`DnsCache`](super::dns::DnsCache), a host without
+//! one included. Hosts without ECH, and builds without the `ech` feature,
+//! connect with plain SNI as before.
+
+use crate::tunnel::dns::{is_valid_hostname, query};
+use crate::tunnel::network::Network;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use std::net::Ipv4Addr;
+use std::time::Duration;
+
+/// DNS `HTTPS` record type code.
+const DNS_TYPE_HTTPS: u16 = 65;
+
+/// SvcParamKey of the ECH configuration list.
+const SVC_PARAM_ECH: u16 = 5;
+
+/// Seconds to remember that a host publishes no ECH configuration.
+const NO_ECH_TTL: u32 = 300;
+
+/// The ECH configuration list `host` publishes for port 443, if any.
+///
+/// Lookup failures are treated as "no ECH" for a short while rather than
+/// failing the request.
+pub(crate) fn lookup<N: Network>(tunnel: &mut N, host: &str, timeout: Duration) -> Option<Vec<u8>> {
+    if !cfg!(feature = "ech") || host.parse::<Ipv4Addr>().is_ok() || !is_valid_hostname(host) {
+        return None;
+    }
+    if let Some(cached) = tunnel.dns_cache().ech_config(host) {
+        return cached;
+    }
+    let (config, ttl) = match query(tunnel, host, "HTTPS", timeout) {
+        Ok(response) => response
+            .answer
+            .iter()
+            .filter(|a| a.record_type == DNS_TYPE_HTTPS)
+            .find_map(|a| Some((Some(ech_config_list(&a.data)?), a.ttl)))
+            .unwrap_or((None, NO_ECH_TTL)),
+        Err(e) => {
+            log::debug!("HTTPS record lookup for {host} failed: {e}");
+            (None, 0)
+        }
+    };
+    tunnel.dns_cache().put_ech_config(host, config.clone(), ttl);
+    config
+}
+
+/// The ECH configuration list of an `HTTPS` record, given in presentation
+/// format (`1 . alpn=h2 ech=AEX+...`) or RFC 3597 generic format
+/// (`\# 61 00 01 00 ...`).
+fn ech_config_list(data: &str) -> Option<Vec<u8>> {
+    match data.trim().strip_prefix("\\#") {
+        Some(generic) => from_wire(&decode_generic(generic)?),
+        None => from_presentation(data),
+    }
+}
+
+fn from_presentation(data: &str) -> Option<Vec<u8>> {
+    let mut fields = data.split_whitespace();
+    // Priority 0 is an alias to another name and carries no parameters.
+    let priority: u16 = fields.next()?.parse().ok()?;
+    if priority == 0 {
+        return None;
+    }
+    let value = fields
+        .skip(1)
+        .find_map(|field| field.strip_prefix("ech="))?;
+    BASE64.decode(value.trim_matches('"')).ok()
+}
+
+/// The record data of a generic-format answer: its length, then hex.
+fn decode_generic(generic: &str) -> Option<Vec<u8>> {
+    let mut fields = generic.split_whitespace();
+    let len: usize = fields.next()?.parse().ok()?;
+    let hex: String = fields.collect();
+    if !hex.is_ascii() || hex.len() != len * 2 {
+        return None;
+    }
+    (0..len)
+        .map(|i| u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).ok())
+        .collect()
+}
+
+fn from_wire(rdata: &[u8]) -> Option<Vec<u8>> {
+    let read_u16 = |pos: usize| Some(u16::from_be_bytes([*rdata.get(pos)?, *rdata.get(pos + 1)?]));
+    if read_u16(0)? == 0 {
+        return None;
+    }
+    // The target name, as uncompressed labels ending with the root.
+    let mut pos = 2;
+    loop {
+        let len = usize::from(*rdata.get(pos)?);
+        pos += 1 + len;
+        if len == 0 {
+            break;
+        }
+    }
+    while pos < rdata.len() {
+        let key = read_u16(pos)?;
+        let len = usize::from(read_u16(pos + 2)?);
+        let value = rdata.get(pos + 4..pos + 4 + len)?;
+        if key == SVC_PARAM_ECH {
+            return Some(value.to_vec());
+        }
+        pos += 4 + len;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_the_presentation_format() {
+        let data = r#"1 . alpn="h3,h2" ipv4hint=104.16.1.1 ech="AEX+DQ==" ipv6hint=2606:4700::1"#;
+        assert_eq!(ech_config_list(data), Some(vec![0x00, 0x45, 0xFE, 0x0D]));
+        assert_eq!(ech_config_list("1 . alpn=h2"), None);
+        assert_eq!(ech_config_list("0 cdn.example. ech=AEX+DQ=="), None);
+    }
+
+    #[test]
+    fn reads_the_generic_format() {
+        // Priority 1, target ".", alpn=h2, ech=0xAB 0xCD.
+        let data = "\\# 16 0001 00 0001 0003 02 6832 0005 0002 abcd";
+        assert_eq!(ech_config_list(data), Some(vec![0xAB, 0xCD]));
+        // The length must match, and truncated parameters are refused.
+        assert_eq!(
+            ech_config_list("\\# 17 0001 00 0001 0003 02 6832 0005 0002 abcd"),
+            None
+        );
+        assert_eq!(ech_config_list("\\# 9 0001 00 0005 0004 abcd"), None);
+        assert_eq!(from_wire(&[0, 1, 3, b'c', b'd', b'n', 0]), None);
+    }
+}
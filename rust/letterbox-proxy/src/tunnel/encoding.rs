@@ -0,0 +1,284 @@
+//! Transparent `Content-Encoding` decoding.
+//!
+//! Requests advertise [`ACCEPT_ENCODING`], and [`ContentDecoder`] sits between
+//! the framing decoder ([`crate::tunnel::body`]) and the caller's sink,
+//! inflating gzip, deflate, brotli or zstd bodies as they arrive. The body
+//! size limit is enforced again on the *decompressed* bytes, so a small
+//! compressed response cannot expand past `max_body` in memory or on disk.
+//! A body cut off mid-stream is an error for every coding, and a zstd frame
+//! asking for a window larger than HTTP allows is refused before the decoder
+//! allocates it.
+
+use crate::error::ProxyError;
+use crate::tunnel::http1::ResponseHead;
+use std::io::{self, Write};
+use zstd::stream::{raw, zio};
+use zstd::zstd_safe::DParameter;
+
+/// The `Accept-Encoding` value sent with every request.
+pub const ACCEPT_ENCODING: &str = "gzip, deflate, br, zstd";
+
+/// Internal buffer size for the brotli decoder.
+const BROTLI_BUFFER: usize = 16 * 1024;
+
+/// Largest zstd window accepted, as a power of two: the 8 MiB that RFC 8878
+/// sets for the `zstd` content coding.
+const ZSTD_WINDOW_LOG_MAX: u32 = 23;
+
+/// A response body's content coding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentEncoding {
+    /// No coding (absent header or `identity`).
+    Identity,
+    /// `gzip` / `x-gzip`.
+    Gzip,
+    /// `deflate` (zlib-wrapped).
+    Deflate,
+    /// `br`.
+    Brotli,
+    /// `zstd`.
+    Zstd,
+}
+
+impl ContentEncoding {
+    /// Determine the content coding of `head`'s body.
+    ///
+    /// Stacked codings (`gzip, br`) and unknown codings are rejected rather
+    /// than passed through, since the result would not be the image itself.
+    pub fn of(head: &ResponseHead) -> Result<Self, ProxyError> {
+        let Some(value) = head.header("content-encoding") else {
+            return Ok(ContentEncoding::Identity);
+        };
+        match value.trim().to_ascii_lowercase().as_str() {
+            "" | "identity" => Ok(ContentEncoding::Identity),
+            "gzip" | "x-gzip" => Ok(ContentEncoding::Gzip),
+            "deflate" => Ok(ContentEncoding::Deflate),
+            "br" => Ok(ContentEncoding::Brotli),
+            "zstd" => Ok(ContentEncoding::Zstd),
+            other => Err(ProxyError::HttpError {
+                status_code: 0,
+                details: format!("Unsupported Content-Encoding: {other}"),
+            }),
+        }
+    }
+}
+
+/// A writer that fails once more than `max` bytes have been written to it.
+///
+/// The failure carries [`ProxyError::ResponseTooLarge`] inside the
+/// [`io::Error`], which `From<io::Error> for ProxyError` unwraps again.
+#[derive(Debug)]
+struct Limited<W> {
+    inner: W,
+    written: u64,
+    max: u64,
+}
+
+impl<W: Write> Write for Limited<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let total = self.written + buf.len() as u64;
+        if total > self.max {
+            return Err(io::Error::other(ProxyError::ResponseTooLarge {
+                size: total,
+                max_size: self.max,
+            }));
+        }
+        let n = self.inner.write(buf)?;
+        self.written += n as u64;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Streaming decompressor writing decoded bytes to `W`.
+pub struct ContentDecoder<W: Write>(Codec<W>);
+
+enum Codec<W: Write> {
+    Identity(Limited<W>),
+    Gzip(flate2::write::GzDecoder<Limited<W>>),
+    Deflate(flate2::write::ZlibDecoder<Limited<W>>),
+    Brotli(Box<brotli_decompressor::DecompressorWriter<Limited<W>>>),
+    Zstd(zio::Writer<Limited<W>, raw::Decoder<'static>>),
+}
+
+impl<W: Write> ContentDecoder<W> {
+    /// Decode `encoding` into `out`, failing with
+    /// [`ProxyError::ResponseTooLarge`] past `max_body` decoded bytes.
+    pub fn new(encoding: ContentEncoding, out: W, max_body: u64) -> Result<Self, ProxyError> {
+        let out = Limited {
+            inner: out,
+            written: 0,
+            max: max_body,
+        };
+        let codec = match encoding {
+            ContentEncoding::Identity => Codec::Identity(out),
+            ContentEncoding::Gzip => Codec::Gzip(flate2::write::GzDecoder::new(out)),
+            ContentEncoding::Deflate => Codec::Deflate(flate2::write::ZlibDecoder::new(out)),
+            ContentEncoding::Brotli => Codec::Brotli(Box::new(
+                brotli_decompressor::DecompressorWriter::new(out, BROTLI_BUFFER),
+            )),
+            ContentEncoding::Zstd => {
+                let mut decoder = raw::Decoder::new()?;
+                decoder.set_parameter(DParameter::WindowLogMax(ZSTD_WINDOW_LOG_MAX))?;
+                Codec::Zstd(zio::Writer::new(out, decoder))
+            }
+        };
+        Ok(Self(codec))
+    }
+
+    /// Flush any buffered output and check the compressed stream is complete.
+    pub fn finish(self) -> Result<(), ProxyError> {
+        match self.0 {
+            Codec::Identity(mut w) => w.flush()?,
+            Codec::Gzip(mut d) => d.try_finish()?,
+            Codec::Deflate(mut d) => d.try_finish()?,
+            Codec::Brotli(mut d) => d.close()?,
+            Codec::Zstd(mut d) => {
+                d.finish()?;
+                d.flush()?
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<W: Write> Write for ContentDecoder<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match &mut self.0 {
+            Codec::Identity(w) => w.write(buf),
+            Codec::Gzip(d) => d.write(buf),
+            Codec::Deflate(d) => d.write(buf),
+            Codec::Brotli(d) => d.write(buf),
+            Codec::Zstd(d) => d.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match &mut self.0 {
+            Codec::Identity(w) => w.flush(),
+            Codec::Gzip(d) => d.flush(),
+            Codec::Deflate(d) => d.flush(),
+            Codec::Brotli(d) => d.flush(),
+            Codec::Zstd(d) => d.flush(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn head(encoding: &str) -> ResponseHead {
+        ResponseHead {
+            status: 200,
+            headers: vec![("content-encoding".to_string(), encoding.to_string())],
+        }
+    }
+
+    fn gzip(data: &[u8]) -> Vec<u8> {
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::best());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    fn decode(encoding: ContentEncoding, input: &[u8], max: u64) -> Result<Vec<u8>, ProxyError> {
+        let mut out = Vec::new();
+        let mut decoder = ContentDecoder::new(encoding, &mut out, max)?;
+        for piece in input.chunks(7) {
+            decoder.write_all(piece)?;
+        }
+        decoder.finish()?;
+        Ok(out)
+    }
+
+    #[test]
+    fn parses_content_encoding_header() {
+        assert_eq!(
+            ContentEncoding::of(&head("GZIP")).unwrap(),
+            ContentEncoding::Gzip
+        );
+        assert_eq!(
+            ContentEncoding::of(&head("br")).unwrap(),
+            ContentEncoding::Brotli
+        );
+        assert!(ContentEncoding::of(&head("gzip, br")).is_err());
+        assert!(ContentEncoding::of(&head("compress")).is_err());
+    }
+
+    #[test]
+    fn decodes_gzip_in_pieces() {
+        let svg = b"<svg xmlns=\"http://www.w3.org/2000/svg\"></svg>".repeat(10);
+        assert_eq!(
+            decode(ContentEncoding::Gzip, &gzip(&svg), 1 << 20).unwrap(),
+            svg
+        );
+    }
+
+    #[test]
+    fn decodes_zstd() {
+        let data = b"\x89PNG\r\n\x1a\n".repeat(100);
+        let compressed = zstd::encode_all(&data[..], 3).unwrap();
+        assert_eq!(
+            decode(ContentEncoding::Zstd, &compressed, 1 << 20).unwrap(),
+            data
+        );
+    }
+
+    #[test]
+    fn truncated_or_oversized_zstd_is_an_error() {
+        let data: Vec<u8> = (0..4096u32).flat_map(|i| i.to_le_bytes()).collect();
+        let compressed = zstd::encode_all(&data[..], 3).unwrap();
+        assert!(decode(
+            ContentEncoding::Zstd,
+            &compressed[..compressed.len() - 4],
+            1 << 20
+        )
+        .is_err());
+
+        // A frame announcing a 16 MiB window.
+        let mut encoder = zstd::stream::write::Encoder::new(Vec::new(), 3).unwrap();
+        encoder.window_log(24).unwrap();
+        encoder.include_contentsize(false).unwrap();
+        encoder.write_all(&vec![7u8; 1 << 16]).unwrap();
+        let wide = encoder.finish().unwrap();
+        assert!(decode(ContentEncoding::Zstd, &wide, 1 << 20).is_err());
+    }
+
+    #[test]
+    fn decodes_brotli() {
+        // "hello" compressed with `brotli -q 11`.
+        let compressed = [0x0b, 0x02, 0x80, 0x68, 0x65, 0x6c, 0x6c, 0x6f, 0x03];
+        assert_eq!(
+            decode(ContentEncoding::Brotli, &compressed, 1 << 20).unwrap(),
+            b"hello"
+        );
+    }
+
+    #[test]
+    fn limit_applies_to_decompressed_size() {
+        // 1 MiB of zeros gzips to about a kilobyte.
+        let bomb = gzip(&vec![0u8; 1 << 20]);
+        assert!(bomb.len() < 4096);
+        assert!(matches!(
+            decode(ContentEncoding::Gzip, &bomb, 64 * 1024),
+            Err(ProxyError::ResponseTooLarge {
+                max_size: 65_536,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn truncated_gzip_is_an_error() {
+        let compressed = gzip(b"some image bytes that are long enough");
+        assert!(decode(
+            ContentEncoding::Gzip,
+            &compressed[..compressed.len() - 6],
+            1 << 20
+        )
+        .is_err());
+    }
+}
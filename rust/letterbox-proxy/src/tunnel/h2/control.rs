@@ -0,0 +1,172 @@
+//! Connection-level frame handling for [`Session`].
+
+use super::fields::Fields;
+use super::frame::{self, protocol, Frame};
+use super::{Session, CONNECTION_WINDOW, MAX_HEADER_BLOCK};
+use crate::error::ProxyError;
+use std::io::{Read, Write};
+
+impl Session {
+    /// Read frames until one concerns a stream, handling connection-level
+    /// frames (settings, pings, flow control, `GOAWAY`) along the way.
+    pub(super) fn next_event<S: Read + Write>(
+        &mut self,
+        io: &mut S,
+        stream: u32,
+        io_error: &impl Fn(std::io::Error) -> ProxyError,
+    ) -> Result<Event, ProxyError> {
+        loop {
+            let frame = frame::read(io, io_error)?;
+            match frame.kind {
+                frame::DATA => {
+                    self.unacked += frame.payload.len() as u32;
+                    if self.unacked >= CONNECTION_WINDOW / 2 {
+                        self.window_update(io, 0, self.unacked, io_error)?;
+                        self.unacked = 0;
+                    }
+                    return Ok(Event::Data {
+                        stream: frame.stream,
+                        frame,
+                    });
+                }
+                frame::HEADERS => {
+                    let end_stream = frame.has(frame::END_STREAM);
+                    let (stream, fields) = self.header_block(io, frame, io_error)?;
+                    return Ok(Event::Headers {
+                        stream,
+                        fields,
+                        end_stream,
+                    });
+                }
+                frame::RST_STREAM => {
+                    return Ok(Event::Reset {
+                        stream: frame.stream,
+                        code: frame.u31_at(0)?,
+                    });
+                }
+                frame::SETTINGS if !frame.has(frame::ACK) => {
+                    for (id, value) in frame::parse_settings(&frame.payload)? {
+                        // Our encoder assumes the default table size; rather
+                        // than shrink it, finish this request and move on.
+                        if id == frame::SETTINGS_HEADER_TABLE_SIZE && value < 4096 {
+                            self.reusable = false;
+                        }
+                        if id == frame::SETTINGS_MAX_CONCURRENT_STREAMS {
+                            self.max_streams = value;
+                        }
+                    }
+                    let mut out = Vec::new();
+                    frame::encode(&mut out, frame::SETTINGS, frame::ACK, 0, &[]);
+                    self.send(io, &out, io_error)?;
+                }
+                frame::PING if !frame.has(frame::ACK) => {
+                    let mut out = Vec::new();
+                    frame::encode(&mut out, frame::PING, frame::ACK, 0, &frame.payload);
+                    self.send(io, &out, io_error)?;
+                }
+                frame::GOAWAY => {
+                    self.reusable = false;
+                    if stream > frame.u31_at(0)? {
+                        return Err(ProxyError::HttpError {
+                            status_code: 0,
+                            details: "HTTP/2 connection closed by server (GOAWAY)".to_string(),
+                        });
+                    }
+                }
+                frame::PUSH_PROMISE => return Err(protocol("unsolicited server push")),
+                frame::CONTINUATION => return Err(protocol("unexpected CONTINUATION")),
+                // Settings/ping ACKs, WINDOW_UPDATE, PRIORITY and unknown types.
+                _ => {}
+            }
+        }
+    }
+
+    /// Collect a header block (plus any `CONTINUATION`s) and HPACK-decode it.
+    ///
+    /// Blocks for every stream are decoded, since each one updates the shared
+    /// decompression state.
+    fn header_block<S: Read>(
+        &mut self,
+        io: &mut S,
+        first: Frame,
+        io_error: &impl Fn(std::io::Error) -> ProxyError,
+    ) -> Result<(u32, Fields), ProxyError> {
+        let mut block = first.content()?.to_vec();
+        let mut ended = first.has(frame::END_HEADERS);
+        while !ended {
+            let next = frame::read(io, io_error)?;
+            if next.kind != frame::CONTINUATION || next.stream != first.stream {
+                return Err(protocol("header block interrupted"));
+            }
+            block.extend_from_slice(&next.payload);
+            if block.len() > MAX_HEADER_BLOCK {
+                return Err(protocol("response headers too large"));
+            }
+            ended = next.has(frame::END_HEADERS);
+        }
+        let fields = self
+            .decoder
+            .decode(&block)
+            .map_err(|e| protocol(&format!("HPACK: {e}")))?;
+        Ok((first.stream, fields))
+    }
+
+    pub(super) fn window_update<S: Write>(
+        &self,
+        io: &mut S,
+        stream: u32,
+        increment: u32,
+        io_error: &impl Fn(std::io::Error) -> ProxyError,
+    ) -> Result<(), ProxyError> {
+        let mut out = Vec::new();
+        frame::encode(
+            &mut out,
+            frame::WINDOW_UPDATE,
+            0,
+            stream,
+            &increment.to_be_bytes(),
+        );
+        self.send(io, &out, io_error)
+    }
+
+    /// Reset `stream`, whose response is no longer wanted.
+    pub(super) fn cancel<S: Write>(
+        &self,
+        io: &mut S,
+        stream: u32,
+        io_error: &impl Fn(std::io::Error) -> ProxyError,
+    ) -> Result<(), ProxyError> {
+        let mut out = Vec::new();
+        let code = frame::CANCEL.to_be_bytes();
+        frame::encode(&mut out, frame::RST_STREAM, 0, stream, &code);
+        self.send(io, &out, io_error)
+    }
+
+    pub(super) fn send<S: Write>(
+        &self,
+        io: &mut S,
+        bytes: &[u8],
+        io_error: &impl Fn(std::io::Error) -> ProxyError,
+    ) -> Result<(), ProxyError> {
+        io.write_all(bytes)
+            .and_then(|()| io.flush())
+            .map_err(io_error)
+    }
+}
+
+/// A stream-level event surfaced by [`Session::next_event`].
+pub(super) enum Event {
+    Headers {
+        stream: u32,
+        fields: Fields,
+        end_stream: bool,
+    },
+    Data {
+        stream: u32,
+        frame: Frame,
+    },
+    Reset {
+        stream: u32,
+        code: u32,
+    },
+}
@@ -0,0 +1,107 @@
+//! Translation between HTTP/1.1-serialised requests and HTTP/2 header fields.
+
+use super::frame::protocol;
+use crate::error::ProxyError;
+use crate::tunnel::http1::ResponseHead;
+
+/// Decoded (or to-be-encoded) header fields as raw name/value pairs.
+pub(super) type Fields = Vec<(Vec<u8>, Vec<u8>)>;
+
+/// Hop-by-hop headers that must not appear in an HTTP/2 request.
+const CONNECTION_HEADERS: [&str; 6] = [
+    "connection",
+    "host",
+    "keep-alive",
+    "proxy-connection",
+    "transfer-encoding",
+    "upgrade",
+];
+
+/// Translate a serialised HTTP/1.1 request into HTTP/2 header fields.
+pub(super) fn request_fields(request: &[u8]) -> Result<Fields, ProxyError> {
+    let text = std::str::from_utf8(request).map_err(|_| protocol("request is not UTF-8"))?;
+    let mut lines = text.split("\r\n").take_while(|line| !line.is_empty());
+    let mut request_line = lines.next().unwrap_or("").split(' ');
+    let method = request_line.next().unwrap_or("GET");
+    let path = request_line.next().unwrap_or("/");
+
+    let mut authority = "";
+    let mut fields = Vec::new();
+    for line in lines {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        let name = name.trim().to_ascii_lowercase();
+        let value = value.trim();
+        if name == "host" {
+            authority = value;
+        }
+        if !CONNECTION_HEADERS.contains(&name.as_str()) {
+            fields.push((name.into_bytes(), value.as_bytes().to_vec()));
+        }
+    }
+    let mut all = vec![
+        (b":method".to_vec(), method.as_bytes().to_vec()),
+        (b":scheme".to_vec(), b"https".to_vec()),
+        (b":authority".to_vec(), authority.as_bytes().to_vec()),
+        (b":path".to_vec(), path.as_bytes().to_vec()),
+    ];
+    all.append(&mut fields);
+    Ok(all)
+}
+
+/// Build a [`ResponseHead`] from decoded response header fields.
+pub(super) fn response_head(fields: Fields) -> Result<ResponseHead, ProxyError> {
+    let mut status = None;
+    let mut headers = Vec::with_capacity(fields.len());
+    for (name, value) in fields {
+        let name = String::from_utf8_lossy(&name).to_ascii_lowercase();
+        let value = String::from_utf8_lossy(&value).into_owned();
+        if name == ":status" {
+            status = value.parse::<u16>().ok();
+        } else if !name.starts_with(':') {
+            headers.push((name, value));
+        }
+    }
+    let status = status.ok_or_else(|| protocol("response without :status"))?;
+    Ok(ResponseHead { status, headers })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REQUEST: &[u8] =
+        b"GET /a.png HTTP/1.1\r\nHost: cdn.example\r\nAccept: image/*\r\nConnection: close\r\n\r\n";
+
+    #[test]
+    fn translates_request_headers() {
+        let fields = request_fields(REQUEST).unwrap();
+        let text: Vec<(String, String)> = fields
+            .iter()
+            .map(|(n, v)| {
+                (
+                    String::from_utf8_lossy(n).into_owned(),
+                    String::from_utf8_lossy(v).into_owned(),
+                )
+            })
+            .collect();
+        assert_eq!(text[0], (":method".into(), "GET".into()));
+        assert_eq!(text[2], (":authority".into(), "cdn.example".into()));
+        assert_eq!(text[3], (":path".into(), "/a.png".into()));
+        assert!(text.contains(&("accept".into(), "image/*".into())));
+        assert!(!text.iter().any(|(n, _)| n == "host" || n == "connection"));
+    }
+
+    #[test]
+    fn response_head_requires_status() {
+        let head = response_head(vec![
+            (b":status".to_vec(), b"204".to_vec()),
+            (b"Server".to_vec(), b"x".to_vec()),
+        ])
+        .unwrap();
+        assert_eq!(head.status, 204);
+        assert_eq!(head.header("server"), Some("x"));
+        assert!(response_head(Vec::new()).is_err());
+    }
+}
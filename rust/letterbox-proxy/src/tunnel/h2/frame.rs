@@ -0,0 +1,218 @@
+//! HTTP/2 frame codec (RFC 9113 §4).
+//!
+//! Only what a client issuing body-less requests needs: reading any frame,
+//! writing frames, padding removal and the `SETTINGS` payload format.
+
+use crate::error::ProxyError;
+use std::io::Read;
+
+/// The client connection preface.
+pub const PREFACE: &[u8] = b"PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n";
+
+/// Largest frame payload accepted (the protocol default; never raised).
+pub const MAX_FRAME_SIZE: usize = 16_384;
+
+// Frame types (§6).
+pub const DATA: u8 = 0x0;
+pub const HEADERS: u8 = 0x1;
+pub const RST_STREAM: u8 = 0x3;
+pub const SETTINGS: u8 = 0x4;
+pub const PUSH_PROMISE: u8 = 0x5;
+pub const PING: u8 = 0x6;
+pub const GOAWAY: u8 = 0x7;
+pub const WINDOW_UPDATE: u8 = 0x8;
+pub const CONTINUATION: u8 = 0x9;
+
+/// `END_STREAM` on `DATA`/`HEADERS`; `ACK` on `SETTINGS`/`PING`.
+pub const END_STREAM: u8 = 0x1;
+pub const ACK: u8 = 0x1;
+pub const END_HEADERS: u8 = 0x4;
+pub const PADDED: u8 = 0x8;
+pub const PRIORITY: u8 = 0x20;
+
+// SETTINGS parameters (§6.5.2).
+pub const SETTINGS_HEADER_TABLE_SIZE: u16 = 0x1;
+pub const SETTINGS_ENABLE_PUSH: u16 = 0x2;
+pub const SETTINGS_MAX_CONCURRENT_STREAMS: u16 = 0x3;
+pub const SETTINGS_INITIAL_WINDOW_SIZE: u16 = 0x4;
+pub const SETTINGS_MAX_FRAME_SIZE: u16 = 0x5;
+pub const SETTINGS_MAX_HEADER_LIST_SIZE: u16 = 0x6;
+
+/// `RST_STREAM` error code for a stream the server refused before processing it.
+pub const REFUSED_STREAM: u32 = 0x7;
+/// `RST_STREAM` error code for a stream the client no longer wants.
+pub const CANCEL: u32 = 0x8;
+
+/// A single frame.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    /// Frame type.
+    pub kind: u8,
+    /// Type-specific flags.
+    pub flags: u8,
+    /// Stream identifier (0 for connection-level frames).
+    pub stream: u32,
+    /// Raw payload.
+    pub payload: Vec<u8>,
+}
+
+impl Frame {
+    /// Whether `flag` is set.
+    pub fn has(&self, flag: u8) -> bool {
+        self.flags & flag != 0
+    }
+
+    /// The payload of a `DATA` or `HEADERS` frame without padding (and, for
+    /// `HEADERS`, without the priority block).
+    pub fn content(&self) -> Result<&[u8], ProxyError> {
+        let mut payload = &self.payload[..];
+        let mut pad = 0;
+        if self.has(PADDED) {
+            let (&len, rest) = payload
+                .split_first()
+                .ok_or_else(|| protocol("padded frame without pad length"))?;
+            pad = len as usize;
+            payload = rest;
+        }
+        if self.kind == HEADERS && self.has(PRIORITY) {
+            payload = payload
+                .get(5..)
+                .ok_or_else(|| protocol("truncated priority block"))?;
+        }
+        let end = payload
+            .len()
+            .checked_sub(pad)
+            .ok_or_else(|| protocol("padding exceeds frame"))?;
+        Ok(&payload[..end])
+    }
+
+    /// A big-endian `u32` at `offset` in the payload, masking the reserved bit.
+    pub fn u31_at(&self, offset: usize) -> Result<u32, ProxyError> {
+        self.payload
+            .get(offset..offset + 4)
+            .map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]) & 0x7fff_ffff)
+            .ok_or_else(|| protocol("truncated frame"))
+    }
+}
+
+/// Serialise a frame onto `out`.
+pub fn encode(out: &mut Vec<u8>, kind: u8, flags: u8, stream: u32, payload: &[u8]) {
+    let len = payload.len() as u32;
+    out.extend_from_slice(&len.to_be_bytes()[1..]);
+    out.push(kind);
+    out.push(flags);
+    out.extend_from_slice(&(stream & 0x7fff_ffff).to_be_bytes());
+    out.extend_from_slice(payload);
+}
+
+/// Read one frame from `io`. `read_error` maps transport errors.
+pub fn read<R: Read>(
+    io: &mut R,
+    read_error: &impl Fn(std::io::Error) -> ProxyError,
+) -> Result<Frame, ProxyError> {
+    let mut header = [0u8; 9];
+    io.read_exact(&mut header).map_err(read_error)?;
+    let len = u32::from_be_bytes([0, header[0], header[1], header[2]]) as usize;
+    if len > MAX_FRAME_SIZE {
+        return Err(protocol("frame exceeds maximum size"));
+    }
+    let mut payload = vec![0u8; len];
+    io.read_exact(&mut payload).map_err(read_error)?;
+    Ok(Frame {
+        kind: header[3],
+        flags: header[4],
+        stream: u32::from_be_bytes([header[5], header[6], header[7], header[8]]) & 0x7fff_ffff,
+        payload,
+    })
+}
+
+/// Serialise `SETTINGS` parameters.
+pub fn settings_payload(settings: &[(u16, u32)]) -> Vec<u8> {
+    let mut payload = Vec::with_capacity(settings.len() * 6);
+    for (id, value) in settings {
+        payload.extend_from_slice(&id.to_be_bytes());
+        payload.extend_from_slice(&value.to_be_bytes());
+    }
+    payload
+}
+
+/// Parse a `SETTINGS` payload into `(identifier, value)` pairs.
+pub fn parse_settings(payload: &[u8]) -> Result<Vec<(u16, u32)>, ProxyError> {
+    if !payload.len().is_multiple_of(6) {
+        return Err(protocol("malformed SETTINGS frame"));
+    }
+    Ok(payload
+        .chunks_exact(6)
+        .map(|s| {
+            (
+                u16::from_be_bytes([s[0], s[1]]),
+                u32::from_be_bytes([s[2], s[3], s[4], s[5]]),
+            )
+        })
+        .collect())
+}
+
+/// An HTTP/2 protocol violation by the peer.
+pub fn protocol(details: &str) -> ProxyError {
+    ProxyError::HttpError {
+        status_code: 0,
+        details: format!("HTTP/2 protocol error: {details}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_err(e: std::io::Error) -> ProxyError {
+        ProxyError::TlsError {
+            details: e.to_string(),
+        }
+    }
+
+    #[test]
+    fn encode_and_read_round_trip() {
+        let mut wire = Vec::new();
+        encode(&mut wire, DATA, END_STREAM, 3, b"hello");
+        assert_eq!(&wire[..9], &[0, 0, 5, DATA, END_STREAM, 0, 0, 0, 3]);
+        let frame = read(&mut &wire[..], &read_err).unwrap();
+        assert_eq!(frame.stream, 3);
+        assert!(frame.has(END_STREAM));
+        assert_eq!(frame.payload, b"hello");
+    }
+
+    #[test]
+    fn rejects_oversized_frames() {
+        let header = [0x01, 0x00, 0x00, DATA, 0, 0, 0, 0, 1];
+        assert!(read(&mut &header[..], &read_err).is_err());
+    }
+
+    #[test]
+    fn strips_padding_and_priority() {
+        let frame = Frame {
+            kind: HEADERS,
+            flags: PADDED | PRIORITY,
+            stream: 1,
+            payload: [&[2u8][..], &[0, 0, 0, 0, 16], b"block", &[0, 0]].concat(),
+        };
+        assert_eq!(frame.content().unwrap(), b"block");
+
+        let bad = Frame {
+            kind: DATA,
+            flags: PADDED,
+            stream: 1,
+            payload: vec![9, 1, 2],
+        };
+        assert!(bad.content().is_err());
+    }
+
+    #[test]
+    fn settings_round_trip() {
+        let payload = settings_payload(&[(SETTINGS_ENABLE_PUSH, 0), (SETTINGS_MAX_FRAME_SIZE, 1)]);
+        assert_eq!(
+            parse_settings(&payload).unwrap(),
+            vec![(SETTINGS_ENABLE_PUSH, 0), (SETTINGS_MAX_FRAME_SIZE, 1)]
+        );
+        assert!(parse_settings(&payload[..5]).is_err());
+    }
+}
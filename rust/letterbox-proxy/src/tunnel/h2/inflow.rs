@@ -0,0 +1,85 @@
+//! Receiving the body of a response on one stream.
+
+use super::frame::{self, Frame};
+use super::{Session, STREAM_WINDOW};
+use crate::error::ProxyError;
+use crate::tunnel::body::BodySink;
+use crate::tunnel::encoding::{ContentDecoder, ContentEncoding};
+use crate::tunnel::http1::ResponseHead;
+use std::io::Write;
+
+/// The body of a 2xx response being received on one stream.
+pub(super) struct Inflow<W: Write> {
+    decoded: ContentDecoder<W>,
+    max_body: u64,
+    /// Body bytes received, before content decoding.
+    received: u64,
+    /// Stream-level bytes received since the last `WINDOW_UPDATE`.
+    unacked: u32,
+}
+
+impl<'w, B: BodySink> Inflow<&'w mut B> {
+    /// Start receiving the body of `head` into `body`, or `None` if it is not
+    /// wanted: the response is not 2xx or `body` wants no body at all.
+    pub(super) fn start(
+        head: &ResponseHead,
+        max_body: u64,
+        body: &'w mut B,
+    ) -> Result<Option<Self>, ProxyError> {
+        if !(200..300).contains(&head.status) || !body.wants_body() {
+            return Ok(None);
+        }
+        let encoding = ContentEncoding::of(head)?;
+        let announced = head
+            .header("content-length")
+            .and_then(|v| v.parse::<u64>().ok());
+        if let Some(size) = announced.filter(|&len| len > max_body) {
+            return Err(ProxyError::ResponseTooLarge {
+                size,
+                max_size: max_body,
+            });
+        }
+        // With a content coding the announced length is of the compressed body.
+        body.expect_len(announced.filter(|_| encoding == ContentEncoding::Identity));
+        Ok(Some(Inflow {
+            decoded: ContentDecoder::new(encoding, body, max_body)?,
+            max_body,
+            received: 0,
+            unacked: 0,
+        }))
+    }
+}
+
+impl<W: Write> Inflow<W> {
+    /// Write the content of a `DATA` frame on this stream, replenishing the
+    /// stream's window as it drains. Returns whether the stream ended.
+    pub(super) fn data<S: Write>(
+        &mut self,
+        session: &Session,
+        io: &mut S,
+        frame: &Frame,
+        io_error: &impl Fn(std::io::Error) -> ProxyError,
+    ) -> Result<bool, ProxyError> {
+        let data = frame.content()?;
+        self.received += data.len() as u64;
+        if self.received > self.max_body {
+            return Err(ProxyError::ResponseTooLarge {
+                size: self.received,
+                max_size: self.max_body,
+            });
+        }
+        self.decoded.write_all(data)?;
+        let done = frame.has(frame::END_STREAM);
+        self.unacked += frame.payload.len() as u32;
+        if !done && self.unacked >= STREAM_WINDOW / 2 {
+            session.window_update(io, frame.stream, self.unacked, io_error)?;
+            self.unacked = 0;
+        }
+        Ok(done)
+    }
+
+    /// Check the body is complete once the stream has ended.
+    pub(super) fn finish(self) -> Result<(), ProxyError> {
+        self.decoded.finish()
+    }
+}
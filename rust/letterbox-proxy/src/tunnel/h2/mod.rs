@@ -0,0 +1,440 @@
+//! Minimal synchronous HTTP/2 client (RFC 9113).
+//!
+//! Used instead of HTTP/1.1 when a server selects `h2` during the TLS
+//! handshake. A [`Session`] keeps the connection open after a request so
+//! later requests to the same origin — a batch of images from one CDN,
+//! repeated DoH lookups — reuse it instead of paying for a fresh TCP and TLS
+//! handshake each. Idle sessions are parked in a [`SessionPool`] owned by the
+//! network.
+//!
+//! [`Session::request`] runs one body-less request on its own stream;
+//! [`Session::request_all`] runs several at once, one stream each, with
+//! their frames interleaved on the connection as the server sends them (see
+//! [`multiplex`]). The tunnel worker uses the latter for fetches queued for
+//! the same origin, so they no longer wait for each other's responses.
+//!
+//! Requests arrive already serialised as HTTP/1.1 by [`crate::tunnel::http1`]
+//! and are translated to HPACK-encoded header blocks here, so callers do not
+//! care which protocol was negotiated. Response bodies pass through the same
+//! [`ContentDecoder`](crate::tunnel::encoding::ContentDecoder) and size limits
+//! as HTTP/1.1.
+
+mod control;
+mod fields;
+pub mod frame;
+mod inflow;
+mod multiplex;
+mod pool;
+
+use crate::error::{ProxyError, TimeoutPhase};
+use crate::timeouts::Deadline;
+use crate::tunnel::body::BodySink;
+use crate::tunnel::http1::ResponseHead;
+use control::Event;
+use fields::{request_fields, response_head};
+use frame::protocol;
+use inflow::Inflow;
+use loona_hpack::{Decoder, Encoder};
+use std::io::{Read, Write};
+use std::time::{Duration, Instant};
+
+pub use multiplex::Exchange;
+pub use pool::{PooledSession, SessionPool};
+
+/// Receive window advertised for each stream.
+const STREAM_WINDOW: u32 = 1 << 20;
+
+/// Receive window for the whole connection.
+const CONNECTION_WINDOW: u32 = 16 << 20;
+
+/// The protocol's initial window, before any `WINDOW_UPDATE`.
+const DEFAULT_WINDOW: u32 = 65_535;
+
+/// Largest response header block accepted (matches the HTTP/1.1 head limit).
+const MAX_HEADER_BLOCK: usize = 64 * 1024;
+
+/// Sessions idle for longer than this are not reused; servers commonly close
+/// idle HTTP/2 connections after a minute or two.
+const IDLE_LIMIT: Duration = Duration::from_secs(60);
+
+/// A request failure on an HTTP/2 session.
+#[derive(Debug)]
+pub struct Failure {
+    /// The underlying error.
+    pub error: ProxyError,
+    /// Whether no response was received, so the request can be sent again on
+    /// a fresh connection (e.g. the server had quietly closed an idle one).
+    pub replayable: bool,
+}
+
+/// Per-connection HTTP/2 client state.
+pub struct Session {
+    encoder: Encoder<'static>,
+    decoder: Decoder<'static>,
+    next_stream: u32,
+    /// Connection-level bytes received since the last `WINDOW_UPDATE`.
+    unacked: u32,
+    /// Cleared by `GOAWAY`, protocol errors or a shrunken HPACK table.
+    reusable: bool,
+    /// Most streams the server lets us open at once; unlimited until its
+    /// settings say otherwise.
+    max_streams: u32,
+    last_used: Instant,
+}
+
+impl Session {
+    /// Send the connection preface and initial settings on `io`.
+    pub fn start<S: Write>(
+        io: &mut S,
+        io_error: impl Fn(std::io::Error) -> ProxyError,
+    ) -> Result<Self, ProxyError> {
+        let mut out = frame::PREFACE.to_vec();
+        let settings = frame::settings_payload(&[
+            (frame::SETTINGS_ENABLE_PUSH, 0),
+            (frame::SETTINGS_INITIAL_WINDOW_SIZE, STREAM_WINDOW),
+            (
+                frame::SETTINGS_MAX_HEADER_LIST_SIZE,
+                MAX_HEADER_BLOCK as u32,
+            ),
+        ]);
+        frame::encode(&mut out, frame::SETTINGS, 0, 0, &settings);
+        let grow = CONNECTION_WINDOW - DEFAULT_WINDOW;
+        frame::encode(&mut out, frame::WINDOW_UPDATE, 0, 0, &grow.to_be_bytes());
+        io.write_all(&out)
+            .and_then(|()| io.flush())
+            .map_err(io_error)?;
+        Ok(Self {
+            encoder: Encoder::new(),
+            decoder: Decoder::new(),
+            next_stream: 1,
+            unacked: 0,
+            reusable: true,
+            max_streams: u32::MAX,
+            last_used: Instant::now(),
+        })
+    }
+
+    /// Whether another request may be sent on this session.
+    pub fn is_reusable(&self) -> bool {
+        self.reusable && self.next_stream < (1 << 31) && self.last_used.elapsed() < IDLE_LIMIT
+    }
+
+    /// Send the serialised HTTP/1.1 `request` as a new stream and read its
+    /// response, streaming a 2xx body into `body` (up to `max_body` bytes, before
+    /// and after content decoding). Other responses, and any response when `body`
+    /// does not want one, are returned after the head and the stream cancelled.
+    /// `deadline` moves on to the body phase once the head is in.
+    pub fn request<S: Read + Write, W: BodySink>(
+        &mut self,
+        io: &mut S,
+        request: &[u8],
+        max_body: u64,
+        body: &mut W,
+        deadline: &Deadline,
+        io_error: impl Fn(std::io::Error) -> ProxyError,
+    ) -> Result<ResponseHead, Failure> {
+        let stream = self.next_stream;
+        self.next_stream += 2;
+        self.last_used = Instant::now();
+
+        let fail = |session: &mut Self, error, replayable| {
+            session.reusable = false;
+            Failure { error, replayable }
+        };
+        let (head, end_stream) = self
+            .send_headers(io, stream, request, &io_error)
+            .and_then(|()| self.await_head(io, stream, &io_error))
+            .map_err(|e| fail(self, e, true))?;
+        deadline.enter(TimeoutPhase::Body);
+        self.read_body(io, stream, (head, end_stream), max_body, body, &io_error)
+            .map_err(|e| fail(self, e, false))
+    }
+
+    /// Send the request's header block, split into `CONTINUATION`s if needed.
+    fn send_headers<S: Write>(
+        &mut self,
+        io: &mut S,
+        stream: u32,
+        request: &[u8],
+        io_error: &impl Fn(std::io::Error) -> ProxyError,
+    ) -> Result<(), ProxyError> {
+        let fields = request_fields(request)?;
+        let block = self
+            .encoder
+            .encode(fields.iter().map(|(n, v)| (&n[..], &v[..])));
+        let mut out = Vec::with_capacity(block.len() + 9);
+        let pieces: Vec<&[u8]> = block.chunks(frame::MAX_FRAME_SIZE).collect();
+        for (i, piece) in pieces.iter().enumerate() {
+            let last = if i + 1 == pieces.len() {
+                frame::END_HEADERS
+            } else {
+                0
+            };
+            if i == 0 {
+                frame::encode(
+                    &mut out,
+                    frame::HEADERS,
+                    frame::END_STREAM | last,
+                    stream,
+                    piece,
+                );
+            } else {
+                frame::encode(&mut out, frame::CONTINUATION, last, stream, piece);
+            }
+        }
+        self.send(io, &out, io_error)
+    }
+
+    /// Wait for the final (non-1xx) response head on `stream`.
+    fn await_head<S: Read + Write>(
+        &mut self,
+        io: &mut S,
+        stream: u32,
+        io_error: &impl Fn(std::io::Error) -> ProxyError,
+    ) -> Result<(ResponseHead, bool), ProxyError> {
+        loop {
+            match self.next_event(io, stream, io_error)? {
+                Event::Headers {
+                    stream: s,
+                    fields,
+                    end_stream,
+                } if s == stream => {
+                    let head = response_head(fields)?;
+                    if !(100..200).contains(&head.status) {
+                        return Ok((head, end_stream));
+                    }
+                }
+                Event::Data { stream: s, .. } if s == stream => {
+                    return Err(protocol("DATA before response headers"));
+                }
+                Event::Reset { stream: s, code } if s == stream => return Err(reset(code)),
+                _ => {}
+            }
+        }
+    }
+
+    /// Stream the body of a response into `body`, or cancel the stream if the
+    /// body is not wanted. `response` is the head and whether it ended the stream.
+    fn read_body<S: Read + Write, W: BodySink>(
+        &mut self,
+        io: &mut S,
+        stream: u32,
+        response: (ResponseHead, bool),
+        max_body: u64,
+        body: &mut W,
+        io_error: &impl Fn(std::io::Error) -> ProxyError,
+    ) -> Result<ResponseHead, ProxyError> {
+        let (head, mut done) = response;
+        let Some(mut inflow) = Inflow::start(&head, max_body, body)? else {
+            if !done {
+                self.cancel(io, stream, io_error)?;
+            }
+            return Ok(head);
+        };
+        while !done {
+            match self.next_event(io, stream, io_error)? {
+                Event::Data { stream: s, frame } if s == stream => {
+                    done = inflow.data(self, io, &frame, io_error)?;
+                }
+                Event::Headers {
+                    stream: s,
+                    end_stream,
+                    ..
+                } if s == stream => {
+                    if !end_stream {
+                        return Err(protocol("trailers without END_STREAM"));
+                    }
+                    done = true;
+                }
+                Event::Reset { stream: s, code } if s == stream => return Err(reset(code)),
+                _ => {}
+            }
+        }
+        inflow.finish()?;
+        Ok(head)
+    }
+}
+
+/// The error for a stream reset by the server.
+fn reset(code: u32) -> ProxyError {
+    ProxyError::HttpError {
+        status_code: 0,
+        details: format!("HTTP/2 stream reset by server (error code {code})"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    /// A scripted peer: reads come from `input`, writes land in `output`.
+    pub(super) struct Peer {
+        pub(super) input: Cursor<Vec<u8>>,
+        pub(super) output: Vec<u8>,
+        /// How much of `input` had been read at each write.
+        pub(super) read_at_writes: Vec<u64>,
+    }
+
+    impl Read for Peer {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            self.input.read(buf)
+        }
+    }
+
+    impl Write for Peer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.read_at_writes.push(self.input.position());
+            self.output.write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    pub(super) fn deadline() -> Deadline {
+        Deadline::fixed(TimeoutPhase::Body, Duration::from_secs(30))
+    }
+
+    pub(super) fn io_err(e: std::io::Error) -> ProxyError {
+        ProxyError::TlsError {
+            details: e.to_string(),
+        }
+    }
+
+    pub(super) fn headers(
+        encoder: &mut Encoder<'_>,
+        stream: u32,
+        flags: u8,
+        fields: &[(&str, &str)],
+    ) -> Vec<u8> {
+        let block = encoder.encode(fields.iter().map(|(n, v)| (n.as_bytes(), v.as_bytes())));
+        let mut out = Vec::new();
+        frame::encode(
+            &mut out,
+            frame::HEADERS,
+            flags | frame::END_HEADERS,
+            stream,
+            &block,
+        );
+        out
+    }
+
+    pub(super) fn session_with(server: Vec<u8>) -> (Session, Peer) {
+        let mut peer = Peer {
+            input: Cursor::new(server),
+            output: Vec::new(),
+            read_at_writes: Vec::new(),
+        };
+        let session = Session::start(&mut peer, io_err).unwrap();
+        (session, peer)
+    }
+
+    pub(super) const REQUEST: &[u8] =
+        b"GET /a.png HTTP/1.1\r\nHost: cdn.example\r\nAccept: image/*\r\nConnection: close\r\n\r\n";
+
+    #[test]
+    fn fetches_a_body_across_frames() {
+        let mut encoder = Encoder::new();
+        let mut server = Vec::new();
+        frame::encode(&mut server, frame::SETTINGS, 0, 0, &[]);
+        server.extend(headers(
+            &mut encoder,
+            1,
+            0,
+            &[(":status", "200"), ("content-type", "image/png")],
+        ));
+        frame::encode(&mut server, frame::PING, 0, 0, &[7; 8]);
+        frame::encode(&mut server, frame::DATA, 0, 1, b"\x89P");
+        frame::encode(&mut server, frame::DATA, frame::END_STREAM, 1, b"NG");
+
+        let (mut session, mut peer) = session_with(server);
+        let mut body = Vec::new();
+        let head = session
+            .request(&mut peer, REQUEST, 1024, &mut body, &deadline(), io_err)
+            .unwrap();
+        assert_eq!(head.status, 200);
+        assert_eq!(head.header("content-type"), Some("image/png"));
+        assert_eq!(body, b"\x89PNG");
+        assert!(session.is_reusable());
+
+        // Preface, then our SETTINGS ACK and PING ACK were written.
+        assert!(peer.output.starts_with(frame::PREFACE));
+        let ping_ack = [
+            0,
+            0,
+            8,
+            frame::PING,
+            frame::ACK,
+            0,
+            0,
+            0,
+            0,
+            7,
+            7,
+            7,
+            7,
+            7,
+            7,
+            7,
+            7,
+        ];
+        assert!(peer.output.windows(ping_ack.len()).any(|w| w == ping_ack));
+    }
+
+    #[test]
+    fn cancels_unwanted_bodies() {
+        let mut encoder = Encoder::new();
+        let server = headers(&mut encoder, 1, 0, &[(":status", "404")]);
+        let (mut session, mut peer) = session_with(server);
+        let head = session
+            .request(
+                &mut peer,
+                REQUEST,
+                1024,
+                &mut Vec::new(),
+                &deadline(),
+                io_err,
+            )
+            .unwrap();
+        assert_eq!(head.status, 404);
+        let rst = [0, 0, 4, frame::RST_STREAM, 0, 0, 0, 0, 1, 0, 0, 0, 8];
+        assert!(peer.output.ends_with(&rst));
+    }
+
+    #[test]
+    fn goaway_before_response_is_replayable() {
+        let mut server = Vec::new();
+        frame::encode(&mut server, frame::GOAWAY, 0, 0, &[0, 0, 0, 0, 0, 0, 0, 0]);
+        let (mut session, mut peer) = session_with(server);
+        let failure = session
+            .request(
+                &mut peer,
+                REQUEST,
+                1024,
+                &mut Vec::new(),
+                &deadline(),
+                io_err,
+            )
+            .unwrap_err();
+        assert!(failure.replayable);
+        assert!(!session.is_reusable());
+    }
+
+    #[test]
+    fn oversized_body_fails_without_replay() {
+        let mut encoder = Encoder::new();
+        let mut server = headers(&mut encoder, 1, 0, &[(":status", "200")]);
+        frame::encode(&mut server, frame::DATA, frame::END_STREAM, 1, &[0; 64]);
+        let (mut session, mut peer) = session_with(server);
+        let failure = session
+            .request(&mut peer, REQUEST, 32, &mut Vec::new(), &deadline(), io_err)
+            .unwrap_err();
+        assert!(!failure.replayable);
+        assert!(matches!(
+            failure.error,
+            ProxyError::ResponseTooLarge { size: 64, .. }
+        ));
+    }
+}
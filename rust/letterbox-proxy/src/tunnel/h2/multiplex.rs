@@ -0,0 +1,354 @@
+//! Several requests at once on one [`Session`], one stream each.
+//!
+//! [`Session::request_all`] opens a stream per [`Exchange`] — as many at once
+//! as the server's `SETTINGS_MAX_CONCURRENT_STREAMS` allows — and then reads
+//! frames as they come, handing each to the stream it concerns: heads are
+//! matched to their requests and bodies written to their own sinks, each
+//! stream with its own flow-control window. A stream that finishes makes room
+//! for the next exchange waiting.
+//!
+//! A failure confined to one stream — the server resetting it, a body over
+//! its limit, a sink refusing bytes — resets that stream and fails that
+//! exchange alone. A failure of the connection fails every exchange not yet
+//! done, those still without a response head as replayable.
+
+use super::control::Event;
+use super::fields::response_head;
+use super::frame::protocol;
+use super::inflow::Inflow;
+use super::{reset, Failure, Session};
+use crate::error::{ProxyError, TimeoutPhase};
+use crate::timeouts::Deadline;
+use crate::tunnel::body::BodySink;
+use crate::tunnel::http1::ResponseHead;
+use std::collections::VecDeque;
+use std::io::{Read, Write};
+use std::time::Instant;
+
+/// One request of a [`Session::request_all`] batch.
+pub struct Exchange<'a, W> {
+    /// The request, serialised as HTTP/1.1.
+    pub request: &'a [u8],
+    /// Body size limit, before and after content decoding.
+    pub max_body: u64,
+    /// Where a 2xx body is streamed.
+    pub body: &'a mut W,
+}
+
+/// Where an open stream is in its response.
+enum Stage<'a, W: BodySink> {
+    /// Waiting for the final response head.
+    Head(&'a mut W),
+    /// Receiving the body of this head.
+    Body(ResponseHead, Box<Inflow<&'a mut W>>),
+}
+
+/// A stream opened for the exchange at `index`.
+struct Open<'a, W: BodySink> {
+    index: usize,
+    stream: u32,
+    max_body: u64,
+    stage: Stage<'a, W>,
+}
+
+/// The exchanges of a batch: not yet sent, in flight, and done.
+struct Batch<'a, W: BodySink> {
+    waiting: VecDeque<(usize, Exchange<'a, W>)>,
+    open: Vec<Open<'a, W>>,
+    results: Vec<Option<Result<ResponseHead, Failure>>>,
+}
+
+impl Session {
+    /// Run every exchange on a stream of its own, concurrently, and return
+    /// their results in order, each as [`Self::request`] would for it alone.
+    /// `deadline` enters the body phase afresh whenever a response head
+    /// arrives.
+    pub fn request_all<S: Read + Write, W: BodySink>(
+        &mut self,
+        io: &mut S,
+        exchanges: Vec<Exchange<'_, W>>,
+        deadline: &Deadline,
+        io_error: impl Fn(std::io::Error) -> ProxyError,
+    ) -> Vec<Result<ResponseHead, Failure>> {
+        self.last_used = Instant::now();
+        let mut batch = Batch {
+            results: exchanges.iter().map(|_| None).collect(),
+            waiting: exchanges.into_iter().enumerate().collect(),
+            open: Vec::new(),
+        };
+        if let Err(error) = self.run(io, &mut batch, deadline, &io_error) {
+            self.reusable = false;
+            let unanswered = batch.waiting.into_iter().map(|(index, _)| (index, true));
+            let open = batch.open.into_iter().map(|open| {
+                let replayable = matches!(open.stage, Stage::Head(_));
+                (open.index, replayable)
+            });
+            for (index, replayable) in unanswered.chain(open) {
+                let error = error.clone();
+                batch.results[index] = Some(Err(Failure { error, replayable }));
+            }
+        }
+        batch
+            .results
+            .into_iter()
+            .map(|result| {
+                result.unwrap_or_else(|| {
+                    Err(Failure {
+                        error: protocol("stream never completed"),
+                        replayable: true,
+                    })
+                })
+            })
+            .collect()
+    }
+
+    /// Open streams while the server allows, and dispatch events until every
+    /// exchange is done. An error here concerns the whole connection.
+    fn run<'a, S: Read + Write, W: BodySink>(
+        &mut self,
+        io: &mut S,
+        batch: &mut Batch<'a, W>,
+        deadline: &Deadline,
+        io_error: &impl Fn(std::io::Error) -> ProxyError,
+    ) -> Result<(), ProxyError> {
+        loop {
+            while batch.open.len() < self.max_streams as usize && self.next_stream < (1 << 31) {
+                let Some((index, exchange)) = batch.waiting.pop_front() else {
+                    break;
+                };
+                let stream = self.next_stream;
+                self.next_stream += 2;
+                batch.open.push(Open {
+                    index,
+                    stream,
+                    max_body: exchange.max_body,
+                    stage: Stage::Head(exchange.body),
+                });
+                self.send_headers(io, stream, exchange.request, io_error)?;
+            }
+            let Some(newest) = batch.open.iter().map(|open| open.stream).max() else {
+                if batch.waiting.is_empty() {
+                    return Ok(());
+                }
+                return Err(protocol("no stream may be opened"));
+            };
+            let event = self.next_event(io, newest, io_error)?;
+            self.dispatch(io, event, batch, deadline, io_error)?;
+        }
+    }
+
+    /// Hand `event` to the open stream it concerns, recording the exchange's
+    /// result once the stream is done. Events for streams no longer open
+    /// (cancelled ones) are dropped.
+    fn dispatch<'a, S: Write, W: BodySink>(
+        &mut self,
+        io: &mut S,
+        event: Event,
+        batch: &mut Batch<'a, W>,
+        deadline: &Deadline,
+        io_error: &impl Fn(std::io::Error) -> ProxyError,
+    ) -> Result<(), ProxyError> {
+        let (Event::Headers { stream, .. }
+        | Event::Data { stream, .. }
+        | Event::Reset { stream, .. }) = &event;
+        let Some(position) = batch.open.iter().position(|open| open.stream == *stream) else {
+            return Ok(());
+        };
+        let Open {
+            index,
+            stream,
+            max_body,
+            stage,
+        } = batch.open.swap_remove(position);
+        let reopen = |stage| Open {
+            index,
+            stream,
+            max_body,
+            stage,
+        };
+        // The stream's result, whether a failure is replayable, and whether
+        // the stream must be cancelled.
+        let (result, replayable, cancel) = match (event, stage) {
+            (Event::Data { .. }, stage @ Stage::Head(_)) => {
+                batch.open.push(reopen(stage));
+                return Err(protocol("DATA before response headers"));
+            }
+            (Event::Reset { code, .. }, stage) => {
+                (Err(reset(code)), matches!(stage, Stage::Head(_)), false)
+            }
+            (
+                Event::Headers {
+                    fields, end_stream, ..
+                },
+                Stage::Head(body),
+            ) => match response_head(fields) {
+                Ok(head) if (100..200).contains(&head.status) => {
+                    batch.open.push(reopen(Stage::Head(body)));
+                    return Ok(());
+                }
+                Ok(head) => {
+                    deadline.enter(TimeoutPhase::Body);
+                    match Inflow::start(&head, max_body, body) {
+                        Ok(Some(inflow)) if !end_stream => {
+                            batch.open.push(reopen(Stage::Body(head, Box::new(inflow))));
+                            return Ok(());
+                        }
+                        Ok(Some(inflow)) => (inflow.finish().map(|()| head), false, false),
+                        Ok(None) => (Ok(head), false, !end_stream),
+                        Err(e) => (Err(e), false, !end_stream),
+                    }
+                }
+                Err(e) => (Err(e), false, !end_stream),
+            },
+            (Event::Headers { end_stream, .. }, Stage::Body(head, inflow)) => {
+                if end_stream {
+                    (inflow.finish().map(|()| head), false, false)
+                } else {
+                    let error = protocol("trailers without END_STREAM");
+                    (Err(error), false, true)
+                }
+            }
+            (Event::Data { frame, .. }, Stage::Body(head, mut inflow)) => {
+                match inflow.data(self, io, &frame, io_error) {
+                    Ok(false) => {
+                        batch.open.push(reopen(Stage::Body(head, inflow)));
+                        return Ok(());
+                    }
+                    Ok(true) => (inflow.finish().map(|()| head), false, false),
+                    Err(e) => (Err(e), false, true),
+                }
+            }
+        };
+        batch.results[index] = Some(result.map_err(|error| Failure { error, replayable }));
+        if cancel {
+            self.cancel(io, stream, io_error)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tunnel::h2::frame::{self, Frame};
+    use crate::tunnel::h2::tests::{deadline, headers, io_err, session_with, REQUEST};
+    use loona_hpack::Encoder;
+
+    fn exchange(body: &mut Vec<u8>) -> Exchange<'_, Vec<u8>> {
+        Exchange {
+            request: REQUEST,
+            max_body: 1024,
+            body,
+        }
+    }
+
+    /// The frames written after the connection preface.
+    fn sent(output: &[u8]) -> Vec<Frame> {
+        let mut rest = &output[frame::PREFACE.len()..];
+        std::iter::from_fn(|| frame::read(&mut rest, &io_err).ok()).collect()
+    }
+
+    #[test]
+    fn streams_interleave_and_finish_independently() {
+        let mut encoder = Encoder::new();
+        let mut server = Vec::new();
+        frame::encode(&mut server, frame::SETTINGS, 0, 0, &[]);
+        server.extend(headers(&mut encoder, 3, 0, &[(":status", "200")]));
+        server.extend(headers(&mut encoder, 1, 0, &[(":status", "200")]));
+        frame::encode(&mut server, frame::DATA, 0, 1, b"ab");
+        frame::encode(&mut server, frame::DATA, frame::END_STREAM, 3, b"GIF");
+        frame::encode(&mut server, frame::DATA, frame::END_STREAM, 1, b"cd");
+
+        let (mut session, mut peer) = session_with(server);
+        let (mut first, mut second) = (Vec::new(), Vec::new());
+        let exchanges = vec![exchange(&mut first), exchange(&mut second)];
+        let results = session.request_all(&mut peer, exchanges, &deadline(), io_err);
+        assert!(results
+            .iter()
+            .all(|r| r.as_ref().is_ok_and(|h| h.status == 200)));
+        assert_eq!(first, b"abcd");
+        assert_eq!(second, b"GIF");
+        assert!(session.is_reusable());
+        // Both requests went out before any response was read.
+        assert_eq!(peer.read_at_writes[1..3], [0, 0]);
+    }
+
+    #[test]
+    fn a_failed_stream_leaves_the_others_alone() {
+        let mut encoder = Encoder::new();
+        let mut server = headers(&mut encoder, 1, 0, &[(":status", "200")]);
+        let refused = frame::REFUSED_STREAM.to_be_bytes();
+        frame::encode(&mut server, frame::RST_STREAM, 0, 3, &refused);
+        server.extend(headers(&mut encoder, 5, 0, &[(":status", "404")]));
+        frame::encode(&mut server, frame::DATA, frame::END_STREAM, 1, b"ok");
+
+        let (mut session, mut peer) = session_with(server);
+        let mut bodies = [Vec::new(), Vec::new(), Vec::new()];
+        let exchanges = bodies.iter_mut().map(exchange).collect();
+        let results = session.request_all(&mut peer, exchanges, &deadline(), io_err);
+        assert_eq!(results[0].as_ref().unwrap().status, 200);
+        assert_eq!(bodies[0], b"ok");
+        assert!(results[1].as_ref().is_err_and(|failure| failure.replayable));
+        assert_eq!(results[2].as_ref().unwrap().status, 404);
+        assert!(session.is_reusable());
+        // The unwanted 404 body was cancelled.
+        assert!(sent(&peer.output)
+            .iter()
+            .any(|f| f.kind == frame::RST_STREAM && f.stream == 5));
+    }
+
+    #[test]
+    fn opens_no_more_streams_than_the_server_allows() {
+        let mut encoder = Encoder::new();
+        let mut server = Vec::new();
+        let settings = frame::settings_payload(&[(frame::SETTINGS_MAX_CONCURRENT_STREAMS, 1)]);
+        frame::encode(&mut server, frame::SETTINGS, 0, 0, &settings);
+        let end = frame::END_STREAM;
+        server.extend(headers(&mut encoder, 1, end, &[(":status", "204")]));
+        server.extend(headers(&mut encoder, 3, end, &[(":status", "200")]));
+        let answered = server.len() as u64;
+        server.extend(headers(&mut encoder, 5, end, &[(":status", "200")]));
+
+        let (mut session, mut peer) = session_with(server);
+        let head = session
+            .request(
+                &mut peer,
+                REQUEST,
+                1024,
+                &mut Vec::new(),
+                &deadline(),
+                io_err,
+            )
+            .unwrap();
+        assert_eq!(head.status, 204);
+        let (mut first, mut second) = (Vec::new(), Vec::new());
+        let exchanges = vec![exchange(&mut first), exchange(&mut second)];
+        let results = session.request_all(&mut peer, exchanges, &deadline(), io_err);
+        assert!(results.iter().all(Result::is_ok));
+        // Stream 5 was opened only once stream 3 had finished.
+        let streams: Vec<u32> = sent(&peer.output)
+            .iter()
+            .filter(|f| f.kind == frame::HEADERS)
+            .map(|f| f.stream)
+            .collect();
+        assert_eq!(streams, [1, 3, 5]);
+        assert_eq!(peer.read_at_writes.last(), Some(&answered));
+    }
+
+    #[test]
+    fn a_lost_connection_fails_every_open_stream() {
+        let mut encoder = Encoder::new();
+        let mut server = headers(&mut encoder, 1, 0, &[(":status", "200")]);
+        frame::encode(&mut server, frame::DATA, 0, 1, b"ab");
+
+        let (mut session, mut peer) = session_with(server);
+        let (mut first, mut second) = (Vec::new(), Vec::new());
+        let exchanges = vec![exchange(&mut first), exchange(&mut second)];
+        let results = session.request_all(&mut peer, exchanges, &deadline(), io_err);
+        assert!(results[0]
+            .as_ref()
+            .is_err_and(|failure| !failure.replayable));
+        assert!(results[1].as_ref().is_err_and(|failure| failure.replayable));
+        assert!(!session.is_reusable());
+    }
+}
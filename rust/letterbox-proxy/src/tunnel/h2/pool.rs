@@ -0,0 +1,57 @@
+//! Idle HTTP/2 sessions kept open for reuse.
+
+use super::Session;
+use rustls::ClientConnection;
+use std::net::SocketAddr;
+
+/// Most idle sessions kept at once; each holds a TCP connection.
+const MAX_IDLE: usize = 4;
+
+/// An HTTP/2 session together with the TLS connection and the TCP connection
+/// (identified by a [`Network`](crate::tunnel::network::Network) handle `H`)
+/// it runs on.
+pub struct PooledSession<H> {
+    /// Remote endpoint of the TCP connection.
+    pub endpoint: SocketAddr,
+    /// TLS server name the connection was established for.
+    pub sni: String,
+    /// The TCP connection.
+    pub handle: H,
+    /// The TLS session state.
+    pub tls: ClientConnection,
+    /// The HTTP/2 session state.
+    pub session: Session,
+}
+
+/// Idle sessions, oldest first.
+pub struct SessionPool<H> {
+    idle: Vec<PooledSession<H>>,
+}
+
+impl<H> Default for SessionPool<H> {
+    fn default() -> Self {
+        SessionPool { idle: Vec::new() }
+    }
+}
+
+impl<H> SessionPool<H> {
+    /// Remove and return the idle session for `endpoint` and `sni`, if any.
+    pub fn take(&mut self, endpoint: SocketAddr, sni: &str) -> Option<PooledSession<H>> {
+        let index = self
+            .idle
+            .iter()
+            .position(|s| s.endpoint == endpoint && s.sni == sni)?;
+        Some(self.idle.remove(index))
+    }
+
+    /// Park `session` for reuse, returning the session evicted to make room.
+    pub fn put(&mut self, session: PooledSession<H>) -> Option<PooledSession<H>> {
+        self.idle.push(session);
+        (self.idle.len() > MAX_IDLE).then(|| self.idle.remove(0))
+    }
+
+    /// Remove and return every idle session.
+    pub fn drain(&mut self) -> Vec<PooledSession<H>> {
+        std::mem::take(&mut self.idle)
+    }
+}
@@ -0,0 +1,263 @@
+//! Hedged HTTP/1.1 requests.
+//!
+//! Image CDNs reached through the tunnel now and then sit on a request for
+//! seconds before answering. With hedging on, a request whose response has not
+//! started within the hedge delay is sent again on a second connection, and
+//! whichever connection answers first is read; the other is closed.
+//!
+//! The worker runs one request at a time over blocking streams, so the two
+//! connections are polled in turn with short reads. Bytes read from a plain
+//! connection while polling are kept for the response, and TLS records are
+//! decrypted into the session until it holds response data. Requests on
+//! HTTP/2 sessions are not hedged, as their frames cannot be polled without
+//! consuming them.
+
+use crate::error::{ProxyError, TimeoutPhase};
+use crate::timeouts::Deadline;
+use crate::tunnel::body::BodySink;
+use crate::tunnel::http1::{read_response, ResponseHead};
+use crate::tunnel::network::Network;
+use rustls::ClientConnection;
+use std::io::{self, Read};
+use std::time::{Duration, Instant};
+
+/// How long one connection is polled before turning to the other.
+const POLL_SLICE: Duration = Duration::from_millis(20);
+
+/// An HTTP/1.1 request sent on a fresh connection, whose response is still to
+/// be read.
+pub struct Sent<H> {
+    handle: H,
+    /// The TLS session, unless the connection is plain.
+    tls: Option<ClientConnection>,
+    /// Response bytes already read from a plain connection.
+    prefix: Vec<u8>,
+    /// Whether the response has started, or the server closed the connection.
+    answered: bool,
+}
+
+impl<H: Copy> Sent<H> {
+    /// A request sent on a plain connection.
+    pub fn plain(handle: H) -> Self {
+        Sent {
+            handle,
+            tls: None,
+            prefix: Vec::new(),
+            answered: false,
+        }
+    }
+
+    /// A request sent on a TLS connection.
+    pub fn tls(handle: H, connection: ClientConnection) -> Self {
+        Sent {
+            tls: Some(connection),
+            ..Self::plain(handle)
+        }
+    }
+
+    /// Wait until `until` for the response to start. Returns whether it did.
+    fn wait<N: Network<Handle = H>>(
+        &mut self,
+        tunnel: &mut N,
+        until: Instant,
+    ) -> Result<bool, ProxyError> {
+        let poll = Deadline::fixed(
+            TimeoutPhase::FirstByte,
+            until.saturating_duration_since(Instant::now()),
+        );
+        let mut stream = tunnel.stream(self.handle, &poll);
+        loop {
+            if let Some(tls) = &mut self.tls {
+                let state = tls
+                    .process_new_packets()
+                    .map_err(|e| ProxyError::TlsError {
+                        details: format!("TLS read failed: {e}"),
+                    })?;
+                self.answered |= state.plaintext_bytes_to_read() > 0 || state.peer_has_closed();
+            }
+            if self.answered {
+                return Ok(true);
+            }
+            let read = match &mut self.tls {
+                Some(tls) => tls.read_tls(&mut stream),
+                None => {
+                    let mut chunk = [0u8; 4096];
+                    stream.read(&mut chunk).inspect(|&n| {
+                        self.prefix.extend_from_slice(&chunk[..n]);
+                    })
+                }
+            };
+            match read {
+                Ok(0) => self.answered = true,
+                Ok(_) => self.answered |= self.tls.is_none(),
+                Err(e) if e.kind() == io::ErrorKind::TimedOut => return Ok(false),
+                Err(e) => return Err(read_error(self.tls.is_some(), e)),
+            }
+        }
+    }
+
+    /// Read the response, streaming a 2xx body into `body` and aborting as
+    /// soon as it exceeds `max_body`, then close the connection.
+    pub fn finish<N: Network<Handle = H>, W: BodySink>(
+        mut self,
+        tunnel: &mut N,
+        max_body: u64,
+        body: &mut W,
+        deadline: &Deadline,
+    ) -> Result<ResponseHead, ProxyError> {
+        let result = {
+            let mut stream = tunnel.stream(self.handle, deadline);
+            let tls = self.tls.is_some();
+            let read_error = |e| deadline.io_error(e, |e| read_error(tls, e));
+            match &mut self.tls {
+                Some(tls) => read_response(
+                    &mut rustls::Stream::new(tls, &mut stream),
+                    max_body,
+                    body,
+                    deadline,
+                    read_error,
+                ),
+                None => read_response(
+                    &mut self.prefix.as_slice().chain(&mut stream),
+                    max_body,
+                    body,
+                    deadline,
+                    read_error,
+                ),
+            }
+        };
+        self.close(tunnel);
+        result
+    }
+
+    /// Close the connection without reading the response.
+    pub fn close<N: Network<Handle = H>>(self, tunnel: &mut N) {
+        tunnel.close_tcp(self.handle);
+    }
+}
+
+/// A failed read of the response from a TLS or plain connection.
+fn read_error(tls: bool, e: io::Error) -> ProxyError {
+    if tls {
+        ProxyError::TlsError {
+            details: format!("TLS read failed: {e}"),
+        }
+    } else {
+        ProxyError::HttpError {
+            status_code: 0,
+            details: format!("Read failed: {e}"),
+        }
+    }
+}
+
+/// Wait for the response to `first` and, if it has not started within `delay`,
+/// send the request again on a connection opened by `second` (which returns
+/// `None` when its connection cannot carry the request as HTTP/1.1).
+///
+/// Returns the connection that answered first, along with the deadline of the
+/// second if that one won; the other connection is closed. Failures of the
+/// second connection leave the first to finish on its own.
+pub fn race<N: Network>(
+    tunnel: &mut N,
+    mut first: Sent<N::Handle>,
+    deadline: &Deadline,
+    delay: Duration,
+    second: impl FnOnce(&mut N, &Deadline) -> Result<Option<Sent<N::Handle>>, ProxyError>,
+) -> Result<(Sent<N::Handle>, Option<Deadline>), ProxyError> {
+    let hedge_at = (Instant::now() + delay).min(deadline.at());
+    match first.wait(tunnel, hedge_at) {
+        Ok(false) if Instant::now() < deadline.at() => {}
+        Ok(_) => return Ok((first, None)),
+        Err(e) => {
+            first.close(tunnel);
+            return Err(e);
+        }
+    }
+
+    let hedge_deadline = deadline.restarted();
+    let mut hedge = match second(tunnel, &hedge_deadline) {
+        Ok(Some(hedge)) => hedge,
+        Ok(None) => return Ok((first, None)),
+        Err(e) => {
+            log::debug!("Hedged request failed: {e}");
+            return Ok((first, None));
+        }
+    };
+    log::debug!("No response within {delay:?}; hedged the request on a second connection");
+
+    loop {
+        match first.wait(tunnel, Instant::now() + POLL_SLICE) {
+            Ok(false) => {}
+            Ok(true) => {
+                hedge.close(tunnel);
+                return Ok((first, None));
+            }
+            Err(e) => {
+                log::debug!("First of two hedged requests failed: {e}");
+                first.close(tunnel);
+                return Ok((hedge, Some(hedge_deadline)));
+            }
+        }
+        match hedge.wait(tunnel, Instant::now() + POLL_SLICE) {
+            Ok(false) => {}
+            Ok(true) => {
+                first.close(tunnel);
+                return Ok((hedge, Some(hedge_deadline)));
+            }
+            Err(e) => {
+                log::debug!("Hedged request failed: {e}");
+                hedge.close(tunnel);
+                return Ok((first, None));
+            }
+        }
+        if Instant::now() >= deadline.at() {
+            // Reading the first response now fails with its timeout.
+            hedge.close(tunnel);
+            return Ok((first, None));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::FetchLimits;
+    use crate::http;
+    use crate::tunnel::direct::DirectNetwork;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn a_stalled_request_is_overtaken_by_its_hedge() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = std::thread::spawn(move || {
+            let (stalled, _) = listener.accept().unwrap();
+            let (mut conn, _) = listener.accept().unwrap();
+            let mut request_line = String::new();
+            BufReader::new(&mut conn)
+                .read_line(&mut request_line)
+                .unwrap();
+            assert!(request_line.starts_with("GET /a.png "));
+            conn.write_all(
+                b"HTTP/1.1 200 OK\r\nContent-Type: image/png\r\nContent-Length: 3\r\n\
+                  Connection: close\r\n\r\nabc",
+            )
+            .unwrap();
+            std::thread::sleep(Duration::from_millis(500));
+            drop(stalled);
+        });
+
+        let mut network = DirectNetwork::default();
+        let limits = FetchLimits {
+            hedge_after: Some(Duration::from_millis(100)),
+            ..FetchLimits::default()
+        };
+        let url = format!("http://127.0.0.1:{port}/a.png");
+        let started = Instant::now();
+        let outcome = http::fetch(&mut network, &url, &[], &limits, "image/*", None).unwrap();
+        assert_eq!(&outcome.body[..], b"abc");
+        assert!(started.elapsed() < Duration::from_millis(500));
+        server.join().unwrap();
+    }
+}
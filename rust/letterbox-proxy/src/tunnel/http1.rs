@@ -1,24 +1,53 @@
 //! Minimal, allocation-conscious HTTP/1.1 wire codec.
 //!
-//! This module is pure: it builds request bytes and parses response bytes with
-//! no networking, so it is trivially unit-testable and shared by both the image
-//! fetcher and the DNS-over-HTTPS resolver. Only the small subset of HTTP/1.1
-//! needed for `GET` requests with `Connection: close` is implemented.
+//! This module builds request bytes and reads responses from any [`Read`]
+//! stream, so it is trivially unit-testable over in-memory buffers and shared
+//! by both the image fetcher and the DNS-over-HTTPS resolver. Only the small
+//! subset of HTTP/1.1 needed for `GET`/`HEAD` requests with
+//! `Connection: close` is implemented. Bodies are decoded incrementally by
+//! [`crate::tunnel::body`] and decompressed by [`crate::tunnel::encoding`].
 
-use crate::error::ProxyError;
+use crate::error::{ProxyError, TimeoutPhase};
+use crate::timeouts::Deadline;
+use crate::tunnel::body::{BodyDecoder, BodySink, Framing};
+use crate::tunnel::encoding::{ContentDecoder, ContentEncoding, ACCEPT_ENCODING};
+use std::io::{Read, Write};
 
-/// A parsed HTTP/1.1 response.
+/// Maximum size of the status line plus headers.
+const MAX_HEAD: usize = 64 * 1024;
+
+/// Response headers that set or reveal client state: cookies, credentials
+/// challenges, pinned security policy, alternative services, reporting
+/// endpoints and entity tags (which double as cookies when revalidated).
+/// They are dropped as the head is parsed, so nothing downstream — the
+/// cache, the loopback server, the app — ever sees them.
+pub const STATE_BEARING_HEADERS: &[&str] = &[
+    "set-cookie",
+    "set-cookie2",
+    "clear-site-data",
+    "www-authenticate",
+    "proxy-authenticate",
+    "authentication-info",
+    "strict-transport-security",
+    "public-key-pins",
+    "public-key-pins-report-only",
+    "alt-svc",
+    "nel",
+    "report-to",
+    "reporting-endpoints",
+    "etag",
+];
+
+/// Status line and headers of an HTTP/1.1 response.
 #[derive(Debug, Clone)]
-pub struct HttpResponse {
+pub struct ResponseHead {
     /// Status code (e.g. `200`).
     pub status: u16,
     /// Header name/value pairs, with names lowercased for case-insensitive lookup.
     pub headers: Vec<(String, String)>,
-    /// Decoded response body.
-    pub body: Vec<u8>,
 }
 
-impl HttpResponse {
+impl ResponseHead {
     /// First header value matching `name` (case-insensitive), if present.
     pub fn header(&self, name: &str) -> Option<&str> {
         let needle = name.to_ascii_lowercase();
@@ -46,9 +75,22 @@ pub fn build_get_request(
     path: &str,
     accept: &str,
     extra_headers: &[(String, String)],
+) -> Vec<u8> {
+    build_request("GET", host, path, accept, extra_headers)
+}
+
+/// Build a serialised HTTP/1.1 request for a body-less `method` (`GET` or
+/// `HEAD`), with the same header handling as [`build_get_request`].
+pub fn build_request(
+    method: &str,
+    host: &str,
+    path: &str,
+    accept: &str,
+    extra_headers: &[(String, String)],
 ) -> Vec<u8> {
     let mut request = String::with_capacity(256);
-    request.push_str("GET ");
+    request.push_str(method);
+    request.push(' ');
     request.push_str(if path.is_empty() { "/" } else { path });
     request.push_str(" HTTP/1.1\r\n");
     request.push_str("Host: ");
@@ -57,14 +99,21 @@ pub fn build_get_request(
     request.push_str("Accept: ");
     request.push_str(accept);
     request.push_str("\r\n");
-    request.push_str("Accept-Encoding: identity\r\n");
+    request.push_str("Accept-Encoding: ");
+    request.push_str(ACCEPT_ENCODING);
+    request.push_str("\r\n");
     request.push_str("Connection: close\r\n");
     for (name, value) in extra_headers {
         // Skip headers we manage ourselves to avoid duplicates / smuggling.
         let lower = name.to_ascii_lowercase();
         if matches!(
             lower.as_str(),
-            "host" | "connection" | "accept-encoding" | "content-length" | "transfer-encoding"
+            "host"
+                | "connection"
+                | "accept"
+                | "accept-encoding"
+                | "content-length"
+                | "transfer-encoding"
         ) {
             continue;
         }
@@ -77,15 +126,103 @@ pub fn build_get_request(
     request.into_bytes()
 }
 
-/// Parse a complete raw HTTP/1.1 response (headers + body).
-pub fn parse_response(raw: &[u8]) -> Result<HttpResponse, ProxyError> {
-    let split = find_header_end(raw).ok_or_else(|| ProxyError::HttpError {
-        status_code: 0,
-        details: "Malformed response: no header terminator".to_string(),
-    })?;
-    let (head, body_start) = raw.split_at(split);
-    let body_bytes = &body_start[4..]; // skip the CRLFCRLF
+/// Read a `Connection: close` response from `stream`.
+///
+/// For 2xx responses the body is decoded as it arrives and written to `body`,
+/// failing with [`ProxyError::ResponseTooLarge`] as soon as it exceeds
+/// `max_body`. A `Content-Encoding` is undone on the way, with `max_body`
+/// also applied to the decompressed bytes. Other responses (redirects, errors), and any response when
+/// `body` does not [want one](BodySink::wants_body), are returned without
+/// reading their body. `read_error` maps transport errors to the caller's variant.
+/// `deadline` moves on to the body phase once the head is in.
+pub fn read_response<S: Read, W: BodySink>(
+    stream: &mut S,
+    max_body: u64,
+    body: &mut W,
+    deadline: &Deadline,
+    read_error: impl Fn(std::io::Error) -> ProxyError,
+) -> Result<ResponseHead, ProxyError> {
+    let mut chunk = [0u8; 16 * 1024];
+    let read = |stream: &mut S, chunk: &mut [u8]| -> Result<usize, ProxyError> {
+        match stream.read(chunk) {
+            Ok(n) => Ok(n),
+            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => Ok(0),
+            Err(e) => Err(read_error(e)),
+        }
+    };
+
+    let mut buf = Vec::with_capacity(4 * 1024);
+    let head_end = loop {
+        let n = read(stream, &mut chunk)?;
+        if n == 0 {
+            return Err(ProxyError::HttpError {
+                status_code: 0,
+                details: "Malformed response: no header terminator".to_string(),
+            });
+        }
+        // Only rescan the tail that could complete a terminator.
+        let from = buf.len().saturating_sub(3);
+        buf.extend_from_slice(&chunk[..n]);
+        if let Some(end) = find_header_end(&buf[from..]) {
+            break from + end;
+        }
+        if buf.len() > MAX_HEAD {
+            return Err(ProxyError::HttpError {
+                status_code: 0,
+                details: "Response headers too large".to_string(),
+            });
+        }
+    };
+
+    let head = parse_head(&buf[..head_end])?;
+    if !(200..300).contains(&head.status) || !body.wants_body() {
+        return Ok(head);
+    }
+
+    deadline.enter(TimeoutPhase::Body);
+    let framing = Framing::of(&head);
+    let mut decoder = BodyDecoder::new(framing, max_body)?;
+    let encoding = ContentEncoding::of(&head)?;
+    // With a content coding the announced length is of the compressed body.
+    body.expect_len(
+        framing
+            .known_len()
+            .filter(|_| encoding == ContentEncoding::Identity),
+    );
+    let mut decoded = ContentDecoder::new(encoding, &mut *body, max_body)?;
+    pump(
+        stream,
+        &mut decoder,
+        &buf[head_end + 4..],
+        &mut decoded,
+        &read,
+    )?;
+    decoded.finish()?;
+    Ok(head)
+}
+
+/// Feed `rest` and then the remainder of `stream` through `decoder` into `out`.
+fn pump<S: Read, W: Write>(
+    stream: &mut S,
+    decoder: &mut BodyDecoder,
+    rest: &[u8],
+    out: &mut W,
+    read: &impl Fn(&mut S, &mut [u8]) -> Result<usize, ProxyError>,
+) -> Result<(), ProxyError> {
+    let mut chunk = [0u8; 16 * 1024];
+    decoder.feed(rest, out)?;
+    while !decoder.is_done() {
+        let n = read(stream, &mut chunk)?;
+        if n == 0 {
+            break;
+        }
+        decoder.feed(&chunk[..n], out)?;
+    }
+    decoder.finish()
+}
 
+/// Parse the status line and headers (without the terminating blank line).
+fn parse_head(head: &[u8]) -> Result<ResponseHead, ProxyError> {
     let head_str = std::str::from_utf8(head).map_err(|_| ProxyError::HttpError {
         status_code: 0,
         details: "Response headers are not valid UTF-8".to_string(),
@@ -98,22 +235,12 @@ pub fn parse_response(raw: &[u8]) -> Result<HttpResponse, ProxyError> {
     })?;
     let status = parse_status_line(status_line)?;
 
-    let mut headers = Vec::new();
-    for line in lines {
-        if line.is_empty() {
-            continue;
-        }
-        if let Some((name, value)) = line.split_once(':') {
-            headers.push((name.trim().to_ascii_lowercase(), value.trim().to_string()));
-        }
-    }
-
-    let body = decode_body(&headers, body_bytes)?;
-    Ok(HttpResponse {
-        status,
-        headers,
-        body,
-    })
+    let headers = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_ascii_lowercase(), value.trim().to_string()))
+        .filter(|(name, _)| !STATE_BEARING_HEADERS.contains(&name.as_str()))
+        .collect();
+    Ok(ResponseHead { status, headers })
 }
 
 /// Locate the `\r\n\r\n` header/body boundary, returning the index of its start.
@@ -132,65 +259,11 @@ fn parse_status_line(line: &str) -> Result<u16, ProxyError> {
         })
 }
 
-/// Decode the body honouring `Transfer-Encoding: chunked` or `Content-Length`.
-fn decode_body(headers: &[(String, String)], body: &[u8]) -> Result<Vec<u8>, ProxyError> {
-    let chunked = headers
-        .iter()
-        .any(|(k, v)| k == "transfer-encoding" && v.to_ascii_lowercase().contains("chunked"));
-    if chunked {
-        return decode_chunked(body);
-    }
-
-    if let Some((_, len)) = headers.iter().find(|(k, _)| k == "content-length") {
-        if let Ok(len) = len.parse::<usize>() {
-            let end = len.min(body.len());
-            return Ok(body[..end].to_vec());
-        }
-    }
-    // `Connection: close` framing: the remaining bytes are the whole body.
-    Ok(body.to_vec())
-}
-
-/// Decode a chunked transfer-encoded body.
-fn decode_chunked(mut body: &[u8]) -> Result<Vec<u8>, ProxyError> {
-    let mut out = Vec::with_capacity(body.len());
-    loop {
-        let line_end =
-            body.windows(2)
-                .position(|w| w == b"\r\n")
-                .ok_or_else(|| ProxyError::HttpError {
-                    status_code: 0,
-                    details: "Truncated chunk header".to_string(),
-                })?;
-        let size_str = std::str::from_utf8(&body[..line_end]).unwrap_or("");
-        let size_hex = size_str.split(';').next().unwrap_or("").trim();
-        let size = usize::from_str_radix(size_hex, 16).map_err(|_| ProxyError::HttpError {
-            status_code: 0,
-            details: format!("Invalid chunk size: {size_hex}"),
-        })?;
-        body = &body[line_end + 2..];
-        if size == 0 {
-            break;
-        }
-        if body.len() < size {
-            return Err(ProxyError::HttpError {
-                status_code: 0,
-                details: "Truncated chunk body".to_string(),
-            });
-        }
-        out.extend_from_slice(&body[..size]);
-        body = &body[size..];
-        // Skip the trailing CRLF after each chunk.
-        if body.len() >= 2 {
-            body = &body[2..];
-        }
-    }
-    Ok(out)
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::collection::vec;
+    use proptest::prelude::*;
 
     #[test]
     fn builds_minimal_get_request() {
@@ -202,6 +275,14 @@ mod tests {
         assert!(text.ends_with("\r\n\r\n"));
     }
 
+    #[test]
+    fn builds_head_request() {
+        let req = build_request("HEAD", "example.com", "", "image/*", &[]);
+        assert!(String::from_utf8(req)
+            .unwrap()
+            .starts_with("HEAD / HTTP/1.1\r\n"));
+    }
+
     #[test]
     fn skips_managed_headers() {
         let extra = vec![
@@ -214,39 +295,173 @@ mod tests {
         assert!(!req.contains("evil.com"));
     }
 
+    #[test]
+    fn sends_a_single_accept_header() {
+        let extra = vec![("accept".to_string(), "text/html".to_string())];
+        let req = String::from_utf8(build_get_request("example.com", "/", "image/*", &extra))
+            .unwrap()
+            .to_ascii_lowercase();
+        assert_eq!(req.matches("\r\naccept:").count(), 1);
+        assert!(req.contains("\r\naccept: image/*\r\n"));
+        assert!(!req.contains("text/html"));
+    }
+
+    fn deadline() -> Deadline {
+        Deadline::fixed(TimeoutPhase::Body, std::time::Duration::from_secs(30))
+    }
+
+    fn read(raw: &[u8]) -> Result<(ResponseHead, Vec<u8>), ProxyError> {
+        let mut body = Vec::new();
+        let head = read_response(&mut &raw[..], 1 << 20, &mut body, &deadline(), |e| {
+            ProxyError::HttpError {
+                status_code: 0,
+                details: e.to_string(),
+            }
+        })?;
+        Ok((head, body))
+    }
+
     #[test]
     fn parses_simple_response() {
         let raw = b"HTTP/1.1 200 OK\r\nContent-Type: image/png\r\nContent-Length: 4\r\n\r\n\x89PNG";
-        let resp = parse_response(raw).unwrap();
-        assert_eq!(resp.status, 200);
-        assert_eq!(resp.header("content-type"), Some("image/png"));
-        assert_eq!(resp.body, b"\x89PNG");
+        let (head, body) = read(raw).unwrap();
+        assert_eq!(head.status, 200);
+        assert_eq!(head.header("content-type"), Some("image/png"));
+        assert_eq!(body, b"\x89PNG");
+    }
+
+    #[test]
+    fn drops_state_bearing_headers() {
+        let raw = b"HTTP/1.1 200 OK\r\nSet-Cookie: id=1\r\nset-cookie: id=2\r\n\
+                    ETag: \"abc\"\r\nAlt-Svc: h3=\":443\"\r\nCache-Control: max-age=60\r\n\
+                    Content-Length: 0\r\n\r\n";
+        let (head, _) = read(raw).unwrap();
+        assert_eq!(head.header("set-cookie"), None);
+        assert_eq!(head.header("etag"), None);
+        assert_eq!(head.header("alt-svc"), None);
+        assert_eq!(head.header("cache-control"), Some("max-age=60"));
+        assert_eq!(head.headers.len(), 2);
     }
 
     #[test]
     fn parses_chunked_response() {
         let raw = b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n4\r\nWiki\r\n5\r\npedia\r\n0\r\n\r\n";
-        let resp = parse_response(raw).unwrap();
-        assert_eq!(resp.status, 200);
-        assert_eq!(resp.body, b"Wikipedia");
+        let (head, body) = read(raw).unwrap();
+        assert_eq!(head.status, 200);
+        assert_eq!(body, b"Wikipedia");
     }
 
     #[test]
     fn detects_redirect() {
-        let raw = b"HTTP/1.1 302 Found\r\nLocation: https://example.com/x\r\n\r\n";
-        let resp = parse_response(raw).unwrap();
-        assert_eq!(resp.redirect_location(), Some("https://example.com/x"));
+        let raw = b"HTTP/1.1 302 Found\r\nLocation: https://example.com/x\r\n\r\nignored";
+        let (head, body) = read(raw).unwrap();
+        assert_eq!(head.redirect_location(), Some("https://example.com/x"));
+        assert!(body.is_empty());
     }
 
     #[test]
     fn non_redirect_has_no_location() {
         let raw = b"HTTP/1.1 200 OK\r\nLocation: https://example.com/x\r\n\r\n";
-        let resp = parse_response(raw).unwrap();
-        assert_eq!(resp.redirect_location(), None);
+        let (head, _) = read(raw).unwrap();
+        assert_eq!(head.redirect_location(), None);
+    }
+
+    #[test]
+    fn aborts_endless_body_mid_stream() {
+        // An endless body: the reader must give up instead of reading forever.
+        let head = std::io::Cursor::new(b"HTTP/1.1 200 OK\r\n\r\n".to_vec());
+        let mut stream = head.chain(std::io::repeat(b'x'));
+        let err = read_response(
+            &mut stream,
+            100_000,
+            &mut std::io::sink(),
+            &deadline(),
+            |e| ProxyError::HttpError {
+                status_code: 0,
+                details: e.to_string(),
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(err, ProxyError::ResponseTooLarge { .. }));
+    }
+
+    #[test]
+    fn decodes_gzip_content_encoding() {
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::best());
+        encoder.write_all(&[0u8; 4096]).unwrap();
+        let gz = encoder.finish().unwrap();
+        let mut raw = format!(
+            "HTTP/1.1 200 OK\r\nContent-Encoding: gzip\r\nContent-Length: {}\r\n\r\n",
+            gz.len()
+        )
+        .into_bytes();
+        raw.extend_from_slice(&gz);
+
+        let (_, body) = read(&raw).unwrap();
+        assert_eq!(body, vec![0u8; 4096]);
+
+        // The limit applies to the decompressed size, not the wire size.
+        let err = read_response(&mut &raw[..], 1024, &mut Vec::new(), &deadline(), |e| {
+            ProxyError::HttpError {
+                status_code: 0,
+                details: e.to_string(),
+            }
+        })
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            ProxyError::ResponseTooLarge { max_size: 1024, .. }
+        ));
     }
 
     #[test]
     fn rejects_malformed_response() {
-        assert!(parse_response(b"garbage without terminator").is_err());
+        assert!(read(b"garbage without terminator").is_err());
+    }
+
+    proptest! {
+        #[test]
+        fn arbitrary_bytes_never_panic(raw in vec(any::<u8>(), 0..1024)) {
+            let _ = read(&raw);
+        }
+
+        #[test]
+        fn arbitrary_bodies_never_panic(
+            framing in prop_oneof![
+                Just("Content-Length: 16"),
+                Just("Transfer-Encoding: chunked"),
+                Just("Content-Encoding: gzip"),
+                Just("Content-Encoding: br"),
+                Just("Content-Encoding: zstd"),
+            ],
+            body in vec(any::<u8>(), 0..512),
+        ) {
+            let mut raw = format!("HTTP/1.1 200 OK\r\n{framing}\r\n\r\n").into_bytes();
+            raw.extend_from_slice(&body);
+            let _ = read(&raw);
+        }
+
+        #[test]
+        fn well_formed_responses_round_trip(
+            // 204 never has a body.
+            status in (200u16..300).prop_filter("has a body", |&s| s != 204),
+            headers in vec(("x-[a-z]{1,12}", "[!-~]([ -~]{0,30}[!-~])?"), 0..8),
+            body in vec(any::<u8>(), 0..512),
+        ) {
+            let mut raw = format!("HTTP/1.1 {status} OK\r\n");
+            for (name, value) in &headers {
+                raw.push_str(&format!("{name}: {value}\r\n"));
+            }
+            raw.push_str(&format!("Content-Length: {}\r\n\r\n", body.len()));
+            let mut raw = raw.into_bytes();
+            raw.extend_from_slice(&body);
+
+            let (head, decoded) = read(&raw).unwrap();
+            prop_assert_eq!(head.status, status);
+            prop_assert_eq!(decoded, body);
+            for (name, value) in &headers {
+                prop_assert!(head.headers.contains(&(name.clone(), value.clone())));
+            }
+        }
     }
 }
@@ -1,23 +1,43 @@
 //! Tunnel lifecycle manager.
 //!
-//! [`TunnelManager`] owns the [`WarpTunnel`] on a dedicated OS thread and
-//! exposes a synchronous, thread-safe request API over a command channel. This
+//! [`TunnelManager`] owns the [`WarpTunnel`](super::WarpTunnel) — or, in
+//! builds without the `tunnel` feature, a
+//! [`DirectNetwork`](super::direct::DirectNetwork), or with an upstream proxy
+//! set, an [`UpstreamNetwork`](super::upstream::UpstreamNetwork) — on a
+//! dedicated OS thread
+//! ([`super::worker`]) and exposes a synchronous, thread-safe request API over
+//! a command queue. This
 //! is deliberate message passing rather than shared mutable state: the tunnel —
 //! and the single-threaded smoltcp/boringtun state machine inside it — is only
 //! ever touched by its worker thread, so no `Mutex` guards the hot path.
+//!
+//! Fetch replies travel over a oneshot channel, so callers can either block on
+//! them ([`TunnelManager::fetch`]) or await them without holding a thread
+//! ([`TunnelManager::fetch_async`]). Commands wait in a [`Scheduler`] that
+//! serves them by [`FetchLimits::priority`], not strictly in arrival order.
 
 use crate::config::{FetchLimits, WarpConfig};
-use crate::error::ProxyError;
-use crate::http::{self, FetchOutcome};
-use crate::provisioning::WarpProvisioner;
-use crate::tunnel::stack::WarpTunnel;
-use crate::tunnel::transport::TunnelStats;
-use std::sync::mpsc::{channel, Receiver, Sender};
+use crate::error::{ProxyError, TimeoutPhase};
+use crate::http::{FetchOutcome, ProgressFn, StreamOutcome};
+#[cfg(feature = "tunnel")]
+use crate::protect::SocketSetup;
+use crate::tunnel::direct::DirectNetwork;
+use crate::tunnel::network::Network;
+use crate::tunnel::scheduler::{Refused, Scheduler};
+use crate::tunnel::upstream::UpstreamNetwork;
+use crate::tunnel::worker::{worker_loop, Command, StreamId};
+#[cfg(feature = "tunnel")]
+use crate::tunnel::{stack::HANDSHAKE_TIMEOUT, WarpTunnel};
+use crate::types::{Transport, UpstreamProxy};
+use std::path::PathBuf;
+use std::sync::mpsc::channel;
+use std::sync::Arc;
 use std::thread::JoinHandle;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+use tokio::sync::oneshot;
 
-/// How long to wait for the initial (and any re-)handshake to complete.
-const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(15);
+/// Most requests held while paused; further ones fail with `Paused`.
+pub const PAUSED_QUEUE_LIMIT: usize = 100;
 
 /// Whether the tunnel currently has a live WireGuard session.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -65,47 +85,78 @@ pub struct TunnelDiagnostics {
     pub rtt_ms: Option<u32>,
 }
 
-/// A unit of work for the tunnel worker thread.
-enum Command {
-    Fetch {
-        url: String,
-        headers: Vec<(String, String)>,
-        accept: String,
-        limits: FetchLimits,
-        reply: Sender<Result<FetchOutcome, ProxyError>>,
-    },
-    Diagnostics {
-        reply: Sender<TunnelDiagnostics>,
-    },
-}
-
 /// Owns the tunnel worker thread and dispatches commands to it.
 pub struct TunnelManager {
-    tx: Sender<Command>,
+    queue: Arc<Scheduler<Command>>,
     worker: Option<JoinHandle<()>>,
+    /// The path the worker's requests take.
+    transport: Transport,
 }
 
 impl TunnelManager {
     /// Start the worker thread and block until the first handshake completes.
     ///
-    /// `config` is the provisioned WARP configuration. The worker derives the
-    /// public key once and retains the config for diagnostics.
-    pub fn start(config: WarpConfig) -> Result<Self, ProxyError> {
-        let public_key = WarpProvisioner::public_key_from_private(&config.account.private_key)?;
-        let (tx, rx) = channel::<Command>();
+    /// `config` is the provisioned WARP configuration, retained by the tunnel
+    /// for diagnostics; `socket` says how to set up its WireGuard socket.
+    #[cfg(feature = "tunnel")]
+    pub fn start(config: WarpConfig, socket: SocketSetup) -> Result<Self, ProxyError> {
+        Self::spawn("warp-tunnel", move || {
+            let mut tunnel = WarpTunnel::new(&config, &socket)?;
+            tunnel.connect(HANDSHAKE_TIMEOUT)?;
+            Ok(tunnel)
+        })
+    }
+
+    /// [`Self::start`], handshaking with `endpoint` instead of the fixed WARP
+    /// anycast endpoint.
+    #[cfg(feature = "tunnel")]
+    pub fn start_at(
+        config: WarpConfig,
+        socket: SocketSetup,
+        endpoint: std::net::SocketAddr,
+    ) -> Result<Self, ProxyError> {
+        Self::spawn("warp-tunnel", move || {
+            let mut tunnel = WarpTunnel::new(&config, &socket)?;
+            tunnel.set_endpoint(endpoint)?;
+            tunnel.connect(HANDSHAKE_TIMEOUT)?;
+            Ok(tunnel)
+        })
+    }
+
+    /// Start a worker sending requests straight to the network, bypassing
+    /// WARP. The image servers see the device's own IP address.
+    pub fn direct() -> Result<Self, ProxyError> {
+        Self::spawn("letterbox-direct", || Ok(DirectNetwork::default()))
+    }
+
+    /// Start a worker sending requests through the user's own `proxy`
+    /// instead of WARP.
+    pub fn upstream(proxy: UpstreamProxy) -> Result<Self, ProxyError> {
+        Self::spawn("letterbox-upstream", move || UpstreamNetwork::new(proxy))
+    }
+
+    /// Spawn the worker thread, bring up its network with `open` and block
+    /// until that succeeds or fails.
+    fn spawn<N: Network>(
+        name: &str,
+        open: impl FnOnce() -> Result<N, ProxyError> + Send + 'static,
+    ) -> Result<Self, ProxyError> {
+        let queue = Arc::new(Scheduler::new());
         let (ready_tx, ready_rx) = channel::<Result<(), ProxyError>>();
 
+        let worker_queue = Arc::clone(&queue);
         let worker = std::thread::Builder::new()
-            .name("warp-tunnel".to_string())
-            .spawn(move || worker_loop(config, public_key, rx, ready_tx))
+            .name(name.to_string())
+            .spawn(move || worker_loop(open, &worker_queue, ready_tx))
             .map_err(|e| ProxyError::TunnelError {
                 details: format!("Failed to spawn tunnel thread: {e}"),
             })?;
 
         match ready_rx.recv() {
             Ok(Ok(())) => Ok(Self {
-                tx,
+                queue,
                 worker: Some(worker),
+                transport: N::TRANSPORT,
             }),
             Ok(Err(e)) => {
                 let _ = worker.join();
@@ -117,139 +168,295 @@ impl TunnelManager {
         }
     }
 
-    /// Fetch a URL through the tunnel.
+    /// Whether requests go through WARP or straight to the network.
+    pub fn transport(&self) -> Transport {
+        self.transport
+    }
+
+    /// Fetch a URL through the tunnel, reporting body progress to `progress`.
+    ///
+    /// Blocks the calling thread; must not be called from within an async
+    /// runtime.
     pub fn fetch(
         &self,
         url: String,
         headers: Vec<(String, String)>,
         accept: String,
         limits: FetchLimits,
+        progress: Option<ProgressFn>,
+    ) -> Result<FetchOutcome, ProxyError> {
+        self.send_fetch(url, headers, accept, limits, progress)?
+            .blocking_recv()
+            .map_err(|_| dropped())?
+    }
+
+    /// Like [`Self::fetch`], but awaits the reply instead of blocking.
+    pub async fn fetch_async(
+        &self,
+        url: String,
+        headers: Vec<(String, String)>,
+        accept: String,
+        limits: FetchLimits,
+        progress: Option<ProgressFn>,
     ) -> Result<FetchOutcome, ProxyError> {
+        let reply = self.send_fetch(url, headers, accept, limits, progress)?;
+        reply.await.map_err(|_| dropped())?
+    }
+
+    /// Queue a fetch on the worker, returning the channel its result arrives on.
+    fn send_fetch(
+        &self,
+        url: String,
+        headers: Vec<(String, String)>,
+        accept: String,
+        limits: FetchLimits,
+        progress: Option<ProgressFn>,
+    ) -> Result<oneshot::Receiver<Result<FetchOutcome, ProxyError>>, ProxyError> {
+        let (reply, reply_rx) = oneshot::channel();
+        self.send(Command::Fetch {
+            url,
+            headers,
+            accept,
+            limits,
+            progress,
+            reply,
+        })?;
+        Ok(reply_rx)
+    }
+
+    /// Fetch a URL through the tunnel, streaming the body into a new file at
+    /// `dest`. On failure the file is removed.
+    pub fn fetch_to_file(
+        &self,
+        url: String,
+        headers: Vec<(String, String)>,
+        accept: String,
+        limits: FetchLimits,
+        dest: PathBuf,
+        progress: Option<ProgressFn>,
+    ) -> Result<StreamOutcome, ProxyError> {
         let (reply, reply_rx) = channel();
-        self.tx
-            .send(Command::Fetch {
-                url,
-                headers,
-                accept,
-                limits,
-                reply,
+        self.send(Command::FetchToFile {
+            url,
+            headers,
+            accept,
+            limits,
+            dest,
+            progress,
+            reply,
+        })?;
+        reply_rx.recv().map_err(|_| dropped())?
+    }
+
+    /// Fetch only the metadata of a URL (`HEAD`, or a one-byte ranged `GET`).
+    ///
+    /// Blocks the calling thread; must not be called from within an async
+    /// runtime.
+    pub fn probe(
+        &self,
+        url: String,
+        headers: Vec<(String, String)>,
+        accept: String,
+        limits: FetchLimits,
+    ) -> Result<StreamOutcome, ProxyError> {
+        self.send_probe(url, headers, accept, limits)?
+            .blocking_recv()
+            .map_err(|_| dropped())?
+    }
+
+    /// Like [`Self::probe`], but awaits the reply instead of blocking.
+    pub async fn probe_async(
+        &self,
+        url: String,
+        headers: Vec<(String, String)>,
+        accept: String,
+        limits: FetchLimits,
+    ) -> Result<StreamOutcome, ProxyError> {
+        let reply = self.send_probe(url, headers, accept, limits)?;
+        reply.await.map_err(|_| dropped())?
+    }
+
+    /// Queue a probe on the worker, returning the channel its result arrives on.
+    fn send_probe(
+        &self,
+        url: String,
+        headers: Vec<(String, String)>,
+        accept: String,
+        limits: FetchLimits,
+    ) -> Result<oneshot::Receiver<Result<StreamOutcome, ProxyError>>, ProxyError> {
+        let (reply, reply_rx) = oneshot::channel();
+        self.send(Command::Probe {
+            url,
+            headers,
+            accept,
+            limits,
+            reply,
+        })?;
+        Ok(reply_rx)
+    }
+
+    /// Resolve `hosts` into the tunnel's DNS cache, returning how many
+    /// resolved.
+    pub fn resolve(&self, hosts: Vec<String>, limits: FetchLimits) -> Result<u32, ProxyError> {
+        let (reply, reply_rx) = channel();
+        self.send(Command::Resolve {
+            hosts,
+            limits,
+            reply,
+        })?;
+        reply_rx.recv().map_err(|_| dropped())?
+    }
+
+    /// Queue `command` for the worker according to its priority, unless its
+    /// rate limit's queue is full.
+    fn send(&self, command: Command) -> Result<(), ProxyError> {
+        let max_queued = command
+            .limits()
+            .map(|limits| limits.rate_limit.max_queued as usize)
+            .filter(|&max| max > 0);
+        self.queue
+            .push(command.priority(), command, max_queued)
+            .map_err(|refused| match refused {
+                Refused::Closed => ProxyError::TunnelError {
+                    details: "Tunnel worker is no longer running".to_string(),
+                },
+                Refused::Full => ProxyError::Paused,
+                Refused::Overflow => ProxyError::Throttled,
             })
-            .map_err(|_| ProxyError::TunnelError {
-                details: "Tunnel worker is no longer running".to_string(),
+    }
+
+    /// Hold new requests until [`Self::resume`], up to
+    /// [`PAUSED_QUEUE_LIMIT`] of them. A request already in flight completes.
+    pub fn pause(&self) {
+        self.queue.pause(PAUSED_QUEUE_LIMIT);
+    }
+
+    /// Serve the requests held while paused, then new ones as usual.
+    pub fn resume(&self) {
+        self.queue.resume();
+    }
+
+    /// Stop accepting commands and wait up to `timeout` for the queued and
+    /// in-flight ones to finish. Whatever is still queued after that is
+    /// dropped, failing its callers; a request already in flight runs to
+    /// completion. Returns whether everything finished in time.
+    ///
+    /// While paused, queued requests are dropped without waiting.
+    pub fn drain(&self, timeout: Duration) -> bool {
+        self.queue.close();
+        let drained = self.queue.wait_idle(timeout);
+        self.queue.abandon();
+        drained
+    }
+
+    /// Resolve `host` and open a TCP connection to it on `port`, for the
+    /// app to use as a raw byte stream.
+    pub fn open_stream(
+        &self,
+        host: String,
+        port: u16,
+        limits: FetchLimits,
+    ) -> Result<StreamId, ProxyError> {
+        let (reply, reply_rx) = channel();
+        self.send(Command::OpenStream {
+            host,
+            port,
+            limits,
+            reply,
+        })?;
+        reply_rx.recv().map_err(|_| dropped())?
+    }
+
+    /// Up to `max_len` bytes from stream `id`, waiting until `timeout` for
+    /// some to arrive; empty at the end of the stream.
+    pub fn read_stream(
+        &self,
+        id: StreamId,
+        max_len: usize,
+        timeout: Duration,
+    ) -> Result<Vec<u8>, ProxyError> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let (reply, reply_rx) = channel();
+            self.send(Command::ReadStream { id, max_len, reply })?;
+            if let Some(read) = reply_rx.recv().map_err(|_| dropped())?? {
+                return Ok(read);
+            }
+            if Instant::now() >= deadline {
+                return Err(stream_timed_out(timeout));
+            }
+        }
+    }
+
+    /// Write all of `data` to stream `id`, failing if it is not taken by
+    /// `timeout`.
+    pub fn write_stream(
+        &self,
+        id: StreamId,
+        mut data: Vec<u8>,
+        timeout: Duration,
+    ) -> Result<(), ProxyError> {
+        let deadline = Instant::now() + timeout;
+        while !data.is_empty() {
+            let (reply, reply_rx) = channel();
+            self.send(Command::WriteStream {
+                id,
+                data: data.clone(),
+                reply,
             })?;
-        reply_rx.recv().map_err(|_| ProxyError::TunnelError {
-            details: "Tunnel worker dropped the request".to_string(),
-        })?
+            let written = reply_rx.recv().map_err(|_| dropped())??;
+            data.drain(..written);
+            if !data.is_empty() && Instant::now() >= deadline {
+                return Err(stream_timed_out(timeout));
+            }
+        }
+        Ok(())
+    }
+
+    /// Close stream `id`. Closing a closed stream does nothing.
+    pub fn close_stream(&self, id: StreamId) {
+        let _ = self.send(Command::CloseStream { id });
+    }
+
+    /// Switch the tunnel to the peer of a refreshed WARP `config`, keeping
+    /// the worker, its queue and its caches. The next request handshakes
+    /// with the new peer.
+    pub fn update_peer(&self, config: WarpConfig) -> Result<(), ProxyError> {
+        let (reply, reply_rx) = channel();
+        self.send(Command::UpdatePeer {
+            config: Box::new(config),
+            reply,
+        })?;
+        reply_rx.recv().map_err(|_| dropped())?
     }
 
     /// Collect a diagnostics snapshot from the worker.
     pub fn diagnostics(&self) -> Result<TunnelDiagnostics, ProxyError> {
         let (reply, reply_rx) = channel();
-        self.tx
-            .send(Command::Diagnostics { reply })
-            .map_err(|_| ProxyError::TunnelError {
-                details: "Tunnel worker is no longer running".to_string(),
-            })?;
-        reply_rx.recv().map_err(|_| ProxyError::TunnelError {
-            details: "Tunnel worker dropped the request".to_string(),
-        })
+        self.send(Command::Diagnostics { reply })?;
+        reply_rx.recv().map_err(|_| dropped())?
     }
 }
 
 impl Drop for TunnelManager {
     fn drop(&mut self) {
-        // Dropping the sender closes the channel; the worker loop then exits.
+        // Closing the queue lets the worker finish what is queued, then exit.
+        self.queue.close();
         if let Some(worker) = self.worker.take() {
-            // Detach: we cannot block indefinitely in Drop, but closing the
-            // channel above guarantees the loop terminates promptly.
-            drop(std::mem::replace(&mut self.tx, channel().0));
             let _ = worker.join();
         }
     }
 }
 
-/// The worker thread body: own the tunnel and service commands until the
-/// command channel closes.
-fn worker_loop(
-    config: WarpConfig,
-    public_key: String,
-    rx: Receiver<Command>,
-    ready_tx: Sender<Result<(), ProxyError>>,
-) {
-    let mut tunnel = match WarpTunnel::new(&config) {
-        Ok(tunnel) => tunnel,
-        Err(e) => {
-            let _ = ready_tx.send(Err(e));
-            return;
-        }
-    };
-
-    match tunnel.connect(HANDSHAKE_TIMEOUT) {
-        Ok(()) => {
-            let _ = ready_tx.send(Ok(()));
-        }
-        Err(e) => {
-            let _ = ready_tx.send(Err(e));
-            return;
-        }
-    }
-
-    while let Ok(command) = rx.recv() {
-        match command {
-            Command::Fetch {
-                url,
-                headers,
-                accept,
-                limits,
-                reply,
-            } => {
-                let result = ensure_connected(&mut tunnel)
-                    .and_then(|()| http::fetch(&mut tunnel, &url, &headers, &limits, &accept));
-                let _ = reply.send(result);
-            }
-            Command::Diagnostics { reply } => {
-                let _ = reply.send(build_diagnostics(&tunnel, &config, &public_key));
-            }
-        }
-    }
-}
-
-/// Ensure a live WireGuard session, re-handshaking if it has lapsed.
-fn ensure_connected(tunnel: &mut WarpTunnel) -> Result<(), ProxyError> {
-    if tunnel.is_connected() {
-        return Ok(());
+fn stream_timed_out(timeout: Duration) -> ProxyError {
+    ProxyError::Timeout {
+        phase: TimeoutPhase::Body,
+        seconds: timeout.as_secs() as u32,
     }
-    tunnel.connect(HANDSHAKE_TIMEOUT)
 }
 
-/// Assemble a [`TunnelDiagnostics`] snapshot from live and configured state.
-fn build_diagnostics(
-    tunnel: &WarpTunnel,
-    config: &WarpConfig,
-    public_key: &str,
-) -> TunnelDiagnostics {
-    let stats: TunnelStats = tunnel.stats();
-    let endpoint = tunnel.endpoint();
-    TunnelDiagnostics {
-        connection_state: if tunnel.is_connected() {
-            ConnectionState::Connected
-        } else {
-            ConnectionState::Disconnected
-        },
-        private_key: config.account.private_key.clone(),
-        public_key: public_key.to_string(),
-        peer_public_key: config.peer.public_key.clone(),
-        endpoint_host: config.peer.endpoint_host.clone(),
-        endpoint_ipv4: endpoint.ip().to_string(),
-        endpoint_port: endpoint.port(),
-        local_address_ipv4: config.interface.address_ipv4.clone(),
-        warp_enabled: config.warp_enabled,
-        account_type: config.account_type.clone(),
-        account_id: config.account.account_id.clone(),
-        last_handshake_secs: stats.since_handshake.map(|d| d.as_secs()),
-        tx_bytes: stats.tx_bytes,
-        rx_bytes: stats.rx_bytes,
-        estimated_loss: stats.estimated_loss,
-        rtt_ms: stats.rtt_ms,
+fn dropped() -> ProxyError {
+    ProxyError::TunnelError {
+        details: "Tunnel worker dropped the request".to_string(),
     }
 }
@@ -3,21 +3,53 @@
 //! The tunnel is layered bottom-up:
 //!
 //! * [`transport`] — boringtun WireGuard over a UDP socket.
-//! * [`device`] — a smoltcp [`Device`](smoltcp::phy::Device) bridging IP packets
-//!   to the WireGuard transport.
+//! * [`device`] — a smoltcp `Device` bridging IP packets to the WireGuard
+//!   transport.
 //! * [`stack`] — the smoltcp TCP/IP interface and a blocking TCP stream adapter.
+//! * [`network`] — the TCP interface the layers above run on, implemented by
+//!   the stack and by [`direct`] sockets.
 //! * [`tls`] — rustls over the tunnelled TCP stream.
-//! * [`http1`] — a pure HTTP/1.1 request/response codec.
+//! * [`http1`] — a minimal HTTP/1.1 request/response codec.
+//! * [`h2`] — a minimal HTTP/2 client with idle-session reuse, used when the
+//!   server negotiates `h2` via ALPN.
+//! * [`hedge`] — a second copy of an HTTP/1.1 request whose response is slow
+//!   to start.
+//! * [`body`] — incremental response body decoding into any writer.
+//! * [`encoding`] — transparent gzip/deflate/brotli/zstd content decoding.
 //! * [`dns`] — DNS-over-HTTPS resolution through the tunnel.
+//! * [`ech`] — Encrypted ClientHello configurations from DNS `HTTPS` records.
+//! * [`upstream`] — connections through a SOCKS5 or HTTP proxy of the user's
+//!   own, instead of WARP.
+//! * [`scheduler`] — the priority queue of requests waiting for the worker.
 //! * [`manager`] — owns the tunnel on a worker thread and exposes a message API.
+//! * [`worker`] — the worker thread serving the manager's commands.
+//!
+//! The first three need boringtun and smoltcp and are only built with the
+//! `tunnel` feature (on by default). Without it, the manager serves requests
+//! over [`direct`] connections: the same DoH, TLS and HTTP layers, minus WARP.
 
+pub mod body;
+#[cfg(feature = "tunnel")]
 pub mod device;
+pub mod direct;
 pub mod dns;
+pub(crate) mod ech;
+pub mod encoding;
+pub mod h2;
+pub mod hedge;
 pub mod http1;
 pub mod manager;
+pub mod network;
+pub(crate) mod scheduler;
+#[cfg(feature = "tunnel")]
 pub mod stack;
 pub mod tls;
+#[cfg(feature = "tunnel")]
 pub mod transport;
+pub mod upstream;
+pub(crate) mod worker;
 
 pub use manager::{ConnectionState, TunnelDiagnostics, TunnelManager};
+pub use network::Network;
+#[cfg(feature = "tunnel")]
 pub use stack::WarpTunnel;
@@ -0,0 +1,86 @@
+//! The TCP interface the TLS, HTTP and DNS layers run over.
+//!
+//! [`Network`] is implemented by [`WarpTunnel`](super::WarpTunnel), whose
+//! connections are smoltcp sockets inside the WireGuard tunnel, and by
+//! [`DirectNetwork`](super::direct::DirectNetwork), whose connections are the
+//! host's own TCP sockets. Everything above — DoH, TLS, HTTP/1.1, HTTP/2 and
+//! the fetch loop — is generic over it.
+
+use crate::config::WarpConfig;
+use crate::error::ProxyError;
+use crate::timeouts::Deadline;
+use crate::tunnel::dns::DnsCache;
+use crate::tunnel::h2::SessionPool;
+use crate::tunnel::manager::TunnelDiagnostics;
+use crate::tunnel::tls::TlsSessionCache;
+use crate::types::Transport;
+use std::io::{Read, Write};
+use std::net::SocketAddr;
+
+/// A network that opens TCP connections and keeps idle HTTP/2 sessions.
+///
+/// Connections are identified by a copyable handle owned by the network, so a
+/// connection can outlive the stream borrowed for one request (an idle HTTP/2
+/// session keeps only its handle).
+pub trait Network {
+    /// The path connections take, as reported with each response.
+    const TRANSPORT: Transport;
+
+    /// Identifies an open connection.
+    type Handle: Copy;
+
+    /// A blocking byte stream over one connection.
+    type Stream<'a>: Read + Write
+    where
+        Self: 'a;
+
+    /// Open a TCP connection to `remote`, waiting until `deadline` for it to
+    /// be established.
+    fn open_tcp(
+        &mut self,
+        remote: SocketAddr,
+        deadline: &Deadline,
+    ) -> Result<Self::Handle, ProxyError>;
+
+    /// Close a connection and release its handle.
+    fn close_tcp(&mut self, handle: Self::Handle);
+
+    /// Borrow a connection as a stream whose reads and writes give up with
+    /// [`TimedOut`](std::io::ErrorKind::TimedOut) once `deadline` passes.
+    fn stream<'a>(&'a mut self, handle: Self::Handle, deadline: &'a Deadline) -> Self::Stream<'a>;
+
+    /// Idle HTTP/2 sessions running over this network's connections.
+    fn sessions(&mut self) -> &mut SessionPool<Self::Handle>;
+
+    /// Host names resolved over this network.
+    fn dns_cache(&mut self) -> &mut DnsCache;
+
+    /// TLS sessions to resume over this network.
+    fn tls_sessions(&mut self) -> &mut TlsSessionCache;
+
+    /// Bring the link up again if it lapsed. Returns whether a handshake was
+    /// needed.
+    fn reconnect(&mut self) -> Result<bool, ProxyError> {
+        Ok(false)
+    }
+
+    /// Switch to the peer of a refreshed WARP `config` without tearing the
+    /// link down; the next [`Self::reconnect`] handshakes with it. Direct
+    /// links have no peer.
+    fn update_peer(&mut self, _config: &WarpConfig) -> Result<(), ProxyError> {
+        Ok(())
+    }
+
+    /// Bytes sent and received over the link so far, if counted: plaintext
+    /// inside the tunnel, TCP payload on a direct link.
+    fn traffic(&self) -> Option<(u64, u64)> {
+        None
+    }
+
+    /// A diagnostics snapshot of the link.
+    fn diagnostics(&self) -> Result<TunnelDiagnostics, ProxyError> {
+        Err(ProxyError::TunnelError {
+            details: "No WARP tunnel: requests go directly to the network".to_string(),
+        })
+    }
+}
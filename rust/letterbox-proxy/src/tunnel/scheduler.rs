@@ -0,0 +1,455 @@
+//! Priority queue feeding the tunnel worker.
+//!
+//! Every request through a [`TunnelManager`](super::TunnelManager) is served
+//! by one worker thread, so whatever waits in its queue determines what the
+//! user sees next. The [`Scheduler`] keeps one FIFO lane per
+//! [`RequestPriority`] and always hands the worker the oldest command from the
+//! most urgent non-empty lane: an on-screen image queued behind fifty
+//! prefetches is fetched as soon as the current request finishes. A request
+//! already in flight is never interrupted.
+//!
+//! The scheduler also knows when the worker is idle, which lets shutdown wait
+//! for queued and in-flight requests to finish, and it can be paused: commands
+//! then accumulate, up to a limit, until it is resumed. Callers may also cap
+//! how many commands wait at any time.
+//!
+//! A command can also be queued to run no earlier than a given time, which is
+//! how the worker retries a request after a backoff without sleeping: until
+//! then it keeps serving everything else.
+//!
+//! While serving one item the worker may also [take](Scheduler::take_matching)
+//! queued items that can be served alongside it, such as fetches from the
+//! same origin that can share one connection.
+
+use crate::types::RequestPriority;
+use std::collections::VecDeque;
+use std::sync::{Condvar, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+/// Pending commands, one lane per priority.
+struct Lanes<T> {
+    /// Indexed by [`lane`]; lower index is served first.
+    lanes: [VecDeque<T>; 3],
+    /// Items that join the back of their lane once their time comes.
+    delayed: Vec<(Instant, usize, T)>,
+    /// Set once the owner is gone; no further commands are accepted.
+    closed: bool,
+    /// Set once everything queued has been dropped; not even delayed items
+    /// are accepted any more.
+    abandoned: bool,
+    /// Whether the consumer is working on the last item it took.
+    busy: bool,
+    /// While paused, nothing is handed out and at most this many items wait.
+    paused: Option<usize>,
+}
+
+impl<T> Lanes<T> {
+    fn len(&self) -> usize {
+        self.lanes.iter().map(VecDeque::len).sum::<usize>() + self.delayed.len()
+    }
+
+    /// Move every delayed item whose time has come into its lane, returning
+    /// when the next one is due.
+    fn release(&mut self, now: Instant) -> Option<Instant> {
+        let mut next = None::<Instant>;
+        let mut index = 0;
+        while index < self.delayed.len() {
+            let at = self.delayed[index].0;
+            if at <= now {
+                let (_, lane, item) = self.delayed.remove(index);
+                self.lanes[lane].push_back(item);
+            } else {
+                next = Some(next.map_or(at, |next| next.min(at)));
+                index += 1;
+            }
+        }
+        next
+    }
+
+    /// Whether the consumer has nothing to do for now.
+    fn idle(&self) -> bool {
+        !self.busy && (self.paused.is_some() || self.len() == 0)
+    }
+}
+
+/// Why [`Scheduler::push`] refused an item.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum Refused {
+    /// The scheduler has been closed.
+    Closed,
+    /// The scheduler is paused and its queue is full.
+    Full,
+    /// More items are waiting than the caller allows.
+    Overflow,
+}
+
+fn lane(priority: RequestPriority) -> usize {
+    match priority {
+        RequestPriority::Visible => 0,
+        RequestPriority::Prefetch => 1,
+        RequestPriority::Background => 2,
+    }
+}
+
+/// A blocking multi-producer, single-consumer priority queue.
+pub(crate) struct Scheduler<T> {
+    queue: Mutex<Lanes<T>>,
+    ready: Condvar,
+}
+
+impl<T> Scheduler<T> {
+    pub(crate) fn new() -> Self {
+        Scheduler {
+            queue: Mutex::new(Lanes {
+                lanes: Default::default(),
+                delayed: Vec::new(),
+                closed: false,
+                abandoned: false,
+                busy: false,
+                paused: None,
+            }),
+            ready: Condvar::new(),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Lanes<T>> {
+        self.queue
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Queue `item` behind everything of the same or higher priority, unless
+    /// the scheduler is closed, paused with a full queue, or already holds
+    /// `max_queued` items.
+    pub(crate) fn push(
+        &self,
+        priority: RequestPriority,
+        item: T,
+        max_queued: Option<usize>,
+    ) -> Result<(), Refused> {
+        let mut queue = self.lock();
+        if queue.closed {
+            return Err(Refused::Closed);
+        }
+        if queue.paused.is_some_and(|limit| queue.len() >= limit) {
+            return Err(Refused::Full);
+        }
+        if max_queued.is_some_and(|max| queue.len() >= max) {
+            return Err(Refused::Overflow);
+        }
+        queue.lanes[lane(priority)].push_back(item);
+        self.ready.notify_all();
+        Ok(())
+    }
+
+    /// Queue `item` to join its lane no earlier than `not_before`. Unlike
+    /// [`Self::push`] this is for work already accepted, such as a retry, so
+    /// it ignores the limits and is still allowed once the scheduler is
+    /// closed; only [`Self::abandon`] refuses it.
+    pub(crate) fn push_at(
+        &self,
+        priority: RequestPriority,
+        item: T,
+        not_before: Instant,
+    ) -> Result<(), Refused> {
+        let mut queue = self.lock();
+        if queue.abandoned {
+            return Err(Refused::Closed);
+        }
+        queue.delayed.push((not_before, lane(priority), item));
+        self.ready.notify_all();
+        Ok(())
+    }
+
+    /// Wait for the most urgent queued item, marking the previous one done.
+    /// Returns `None` once the scheduler is closed and everything queued
+    /// before that, delayed items included, has been taken, or immediately
+    /// if it is closed while paused.
+    pub(crate) fn pop(&self) -> Option<T> {
+        let mut queue = self.lock();
+        queue.busy = false;
+        self.ready.notify_all();
+        loop {
+            let mut next = None;
+            if queue.paused.is_none() {
+                next = queue.release(Instant::now());
+                if let Some(item) = queue.lanes.iter_mut().find_map(VecDeque::pop_front) {
+                    queue.busy = true;
+                    return Some(item);
+                }
+            }
+            if queue.closed && next.is_none() {
+                return None;
+            }
+            queue = match next {
+                Some(at) => {
+                    let timeout = at.saturating_duration_since(Instant::now());
+                    self.ready
+                        .wait_timeout(queue, timeout)
+                        .unwrap_or_else(|poisoned| poisoned.into_inner())
+                        .0
+                }
+                None => self
+                    .ready
+                    .wait(queue)
+                    .unwrap_or_else(|poisoned| poisoned.into_inner()),
+            };
+        }
+    }
+
+    /// Take up to `max` queued items that `matches` accepts, most urgent lane
+    /// first and oldest first within a lane, to be served along with the item
+    /// the consumer is working on. Takes nothing while paused.
+    pub(crate) fn take_matching(&self, max: usize, mut matches: impl FnMut(&T) -> bool) -> Vec<T> {
+        let mut queue = self.lock();
+        let mut taken = Vec::new();
+        if queue.paused.is_some() {
+            return taken;
+        }
+        queue.release(Instant::now());
+        for lane in queue.lanes.iter_mut() {
+            let mut index = 0;
+            while index < lane.len() && taken.len() < max {
+                if matches(&lane[index]) {
+                    taken.extend(lane.remove(index));
+                } else {
+                    index += 1;
+                }
+            }
+        }
+        if !taken.is_empty() {
+            self.ready.notify_all();
+        }
+        taken
+    }
+
+    /// Refuse new items and wake the consumer so it can drain and exit.
+    pub(crate) fn close(&self) {
+        self.lock().closed = true;
+        self.ready.notify_all();
+    }
+
+    /// Close the scheduler and drop everything still queued, for a consumer
+    /// that is going away without draining.
+    pub(crate) fn abandon(&self) {
+        let mut queue = self.lock();
+        queue.closed = true;
+        queue.abandoned = true;
+        queue.busy = false;
+        queue.lanes.iter_mut().for_each(VecDeque::clear);
+        queue.delayed.clear();
+        self.ready.notify_all();
+    }
+
+    /// Stop handing out items; let up to `limit` wait until [`Self::resume`].
+    /// The item being worked on, if any, is not affected.
+    pub(crate) fn pause(&self, limit: usize) {
+        self.lock().paused = Some(limit);
+    }
+
+    /// Hand out items again, starting with those queued while paused.
+    pub(crate) fn resume(&self) {
+        self.lock().paused = None;
+        self.ready.notify_all();
+    }
+
+    /// Wait up to `timeout` for the consumer to finish its current item and,
+    /// unless paused, everything queued. Returns whether it did.
+    pub(crate) fn wait_idle(&self, timeout: Duration) -> bool {
+        let (queue, _) = self
+            .ready
+            .wait_timeout_while(self.lock(), timeout, |queue| !queue.idle())
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        queue.idle()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[test]
+    fn most_urgent_lane_first_and_fifo_within_lane() {
+        let scheduler = Scheduler::new();
+        scheduler
+            .push(RequestPriority::Background, "update", None)
+            .unwrap();
+        scheduler
+            .push(RequestPriority::Prefetch, "p1", None)
+            .unwrap();
+        scheduler
+            .push(RequestPriority::Visible, "v1", None)
+            .unwrap();
+        scheduler
+            .push(RequestPriority::Prefetch, "p2", None)
+            .unwrap();
+        scheduler
+            .push(RequestPriority::Visible, "v2", None)
+            .unwrap();
+        scheduler.close();
+
+        let order: Vec<&str> = std::iter::from_fn(|| scheduler.pop()).collect();
+        assert_eq!(order, ["v1", "v2", "p1", "p2", "update"]);
+        assert_eq!(
+            scheduler.push(RequestPriority::Visible, "late", None),
+            Err(Refused::Closed)
+        );
+    }
+
+    #[test]
+    fn idle_once_the_last_item_is_done() {
+        let scheduler = Arc::new(Scheduler::new());
+        assert!(scheduler.wait_idle(Duration::ZERO));
+        scheduler.push(RequestPriority::Visible, 1, None).unwrap();
+        assert!(!scheduler.wait_idle(Duration::from_millis(10)));
+        assert_eq!(scheduler.pop(), Some(1));
+        // Taken but still being worked on.
+        assert!(!scheduler.wait_idle(Duration::from_millis(10)));
+
+        scheduler.close();
+        let consumer = {
+            let scheduler = Arc::clone(&scheduler);
+            std::thread::spawn(move || scheduler.pop())
+        };
+        assert!(scheduler.wait_idle(Duration::from_secs(5)));
+        assert_eq!(consumer.join().unwrap(), None);
+    }
+
+    #[test]
+    fn paused_queue_holds_items_up_to_the_limit() {
+        let scheduler = Arc::new(Scheduler::new());
+        scheduler.pause(2);
+        scheduler
+            .push(RequestPriority::Prefetch, "p", None)
+            .unwrap();
+        scheduler.push(RequestPriority::Visible, "v", None).unwrap();
+        assert_eq!(
+            scheduler.push(RequestPriority::Visible, "over", None),
+            Err(Refused::Full)
+        );
+        // Nothing is handed out, so a paused queue counts as idle.
+        assert!(scheduler.wait_idle(Duration::ZERO));
+
+        let consumer = {
+            let scheduler = Arc::clone(&scheduler);
+            std::thread::spawn(move || scheduler.pop())
+        };
+        std::thread::sleep(Duration::from_millis(20));
+        assert!(!consumer.is_finished());
+        scheduler.resume();
+        assert_eq!(consumer.join().unwrap(), Some("v"));
+        assert_eq!(scheduler.pop(), Some("p"));
+    }
+
+    #[test]
+    fn pushes_beyond_max_queued_overflow() {
+        let scheduler = Scheduler::new();
+        scheduler
+            .push(RequestPriority::Prefetch, 1, Some(2))
+            .unwrap();
+        scheduler
+            .push(RequestPriority::Prefetch, 2, Some(2))
+            .unwrap();
+        assert_eq!(
+            scheduler.push(RequestPriority::Visible, 3, Some(2)),
+            Err(Refused::Overflow)
+        );
+        assert_eq!(scheduler.pop(), Some(1));
+        scheduler
+            .push(RequestPriority::Visible, 3, Some(2))
+            .unwrap();
+    }
+
+    #[test]
+    fn abandon_drops_queued_items() {
+        let scheduler = Scheduler::new();
+        scheduler
+            .push(RequestPriority::Visible, "queued", None)
+            .unwrap();
+        scheduler.abandon();
+        assert_eq!(scheduler.pop(), None);
+        assert!(scheduler
+            .push(RequestPriority::Visible, "late", None)
+            .is_err());
+    }
+
+    #[test]
+    fn delayed_items_wait_for_their_time() {
+        let scheduler = Scheduler::new();
+        let start = Instant::now();
+        scheduler
+            .push_at(
+                RequestPriority::Visible,
+                "retry",
+                start + Duration::from_millis(50),
+            )
+            .unwrap();
+        scheduler
+            .push(RequestPriority::Background, "update", None)
+            .unwrap();
+        // Not due yet, so a less urgent item goes first.
+        assert_eq!(scheduler.pop(), Some("update"));
+        scheduler.close();
+        // Closing still drains the delayed item, once it is due.
+        assert_eq!(scheduler.pop(), Some("retry"));
+        assert!(start.elapsed() >= Duration::from_millis(50));
+        assert_eq!(scheduler.pop(), None);
+
+        scheduler.abandon();
+        assert_eq!(
+            scheduler.push_at(RequestPriority::Visible, "late", Instant::now()),
+            Err(Refused::Closed)
+        );
+    }
+
+    #[test]
+    fn delayed_items_count_towards_idle() {
+        let scheduler = Scheduler::new();
+        scheduler
+            .push_at(
+                RequestPriority::Prefetch,
+                1,
+                Instant::now() + Duration::from_millis(20),
+            )
+            .unwrap();
+        assert!(!scheduler.wait_idle(Duration::ZERO));
+        assert_eq!(scheduler.pop(), Some(1));
+    }
+
+    #[test]
+    fn take_matching_takes_urgent_matches_in_order() {
+        let scheduler = Scheduler::new();
+        for (priority, item) in [
+            (RequestPriority::Prefetch, 1),
+            (RequestPriority::Prefetch, 2),
+            (RequestPriority::Visible, 4),
+            (RequestPriority::Prefetch, 6),
+            (RequestPriority::Visible, 7),
+            (RequestPriority::Background, 8),
+        ] {
+            scheduler.push(priority, item, None).unwrap();
+        }
+        let even = |item: &i32| item % 2 == 0;
+        assert_eq!(scheduler.take_matching(3, even), [4, 2, 6]);
+        assert_eq!(scheduler.take_matching(3, even), [8]);
+        assert_eq!(scheduler.pop(), Some(7));
+        assert_eq!(scheduler.pop(), Some(1));
+
+        scheduler.push(RequestPriority::Visible, 10, None).unwrap();
+        scheduler.pause(4);
+        assert!(scheduler.take_matching(3, even).is_empty());
+    }
+
+    #[test]
+    fn pop_waits_for_a_push_or_close() {
+        let scheduler = Arc::new(Scheduler::new());
+        let consumer = {
+            let scheduler = Arc::clone(&scheduler);
+            std::thread::spawn(move || std::iter::from_fn(|| scheduler.pop()).collect::<Vec<_>>())
+        };
+        scheduler.push(RequestPriority::Prefetch, 1, None).unwrap();
+        scheduler.close();
+        assert_eq!(consumer.join().unwrap(), [1]);
+    }
+}
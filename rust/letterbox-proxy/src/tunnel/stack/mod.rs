@@ -3,23 +3,39 @@
 //! [`WarpTunnel`] owns every piece of the userspace network stack for the whole
 //! lifetime of the tunnel, so all storage is plain owned [`Vec`]s — there is no
 //! `Box::leak` and no `'static` smuggling. A single worker thread owns the
-//! tunnel; callers obtain a [`TunnelTcpStream`] that implements [`Read`]/[`Write`]
-//! by repeatedly driving the poll loop until the requested I/O can make progress.
+//! tunnel; callers obtain a [`TunnelTcpStream`] that implements
+//! [`Read`](std::io::Read)/[`Write`](std::io::Write) by repeatedly driving the
+//! poll loop until the requested I/O can make progress.
 //!
 //! ```text
 //! TLS / HTTP  <->  TunnelTcpStream  <->  smoltcp TCP  <->  WireGuard  <->  UDP
 //! ```
 
+mod stream;
+
 use crate::config::WarpConfig;
-use crate::error::ProxyError;
+use crate::error::{ProxyError, TimeoutPhase};
+use crate::protect::SocketSetup;
+use crate::provisioning::WarpProvisioner;
+use crate::timeouts::Deadline;
 use crate::tunnel::device::VirtualDevice;
+use crate::tunnel::dns::DnsCache;
+use crate::tunnel::h2::SessionPool;
+use crate::tunnel::manager::{ConnectionState, TunnelDiagnostics};
+use crate::tunnel::network::Network;
+use crate::tunnel::tls::TlsSessionCache;
 use crate::tunnel::transport::{TunnelStats, WireGuardTransport};
+use crate::types::Transport;
 use smoltcp::iface::{Config, Interface, SocketHandle, SocketSet};
 use smoltcp::socket::tcp::{Socket as TcpSocket, SocketBuffer, State as TcpState};
 use smoltcp::time::Instant as SmoltcpInstant;
 use smoltcp::wire::{HardwareAddress, IpAddress, IpCidr, Ipv4Address};
-use std::io::{self, Read, Write};
+use std::net::SocketAddr;
 use std::time::{Duration, Instant};
+pub use stream::TunnelTcpStream;
+
+/// How long to wait for the initial (and any re-)handshake to complete.
+pub const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(15);
 
 /// Per-direction TCP buffer size (64 KiB).
 const TCP_BUFFER_SIZE: usize = 65_535;
@@ -57,12 +73,21 @@ pub struct WarpTunnel {
     device: VirtualDevice,
     local_ipv4: [u8; 4],
     next_local_port: u16,
+    sessions: SessionPool<SocketHandle>,
+    dns: DnsCache,
+    tls: TlsSessionCache,
+    /// The WARP identity, kept for diagnostics.
+    config: WarpConfig,
+    /// Our WireGuard public key, derived once from `config`.
+    public_key: String,
 }
 
 impl WarpTunnel {
-    /// Build a tunnel from provisioned WARP configuration (no I/O yet).
-    pub fn new(config: &WarpConfig) -> Result<Self, ProxyError> {
-        let transport = WireGuardTransport::new(config)?;
+    /// Build a tunnel from provisioned WARP configuration (no I/O yet), with
+    /// its UDP socket set up as `socket` says.
+    pub fn new(config: &WarpConfig, socket: &SocketSetup) -> Result<Self, ProxyError> {
+        let public_key = WarpProvisioner::public_key_from_private(&config.account.private_key)?;
+        let transport = WireGuardTransport::new(config, socket)?;
         let local_ipv4 = parse_ipv4_octets(&config.interface.address_ipv4)?;
 
         let mut device = VirtualDevice::new();
@@ -88,6 +113,11 @@ impl WarpTunnel {
             device,
             local_ipv4,
             next_local_port: 49_152,
+            sessions: SessionPool::default(),
+            dns: DnsCache::default(),
+            tls: TlsSessionCache::default(),
+            config: config.clone(),
+            public_key,
         })
     }
 
@@ -96,6 +126,11 @@ impl WarpTunnel {
         self.transport.endpoint()
     }
 
+    /// Target `endpoint` instead; see [`WireGuardTransport::set_endpoint`].
+    pub fn set_endpoint(&mut self, endpoint: std::net::SocketAddr) -> Result<(), ProxyError> {
+        self.transport.set_endpoint(endpoint)
+    }
+
     /// Whether the WireGuard handshake has completed.
     pub fn is_connected(&self) -> bool {
         self.transport.is_connected()
@@ -131,6 +166,7 @@ impl WarpTunnel {
             }
         }
         Err(ProxyError::Timeout {
+            phase: TimeoutPhase::Tunnel,
             seconds: timeout.as_secs() as u32,
         })
     }
@@ -142,12 +178,22 @@ impl WarpTunnel {
         port
     }
 
+    /// The tunnel's local IPv4 address octets.
+    pub fn local_ipv4(&self) -> [u8; 4] {
+        self.local_ipv4
+    }
+}
+
+impl Network for WarpTunnel {
+    const TRANSPORT: Transport = Transport::Tunnel;
+    type Handle = SocketHandle;
+    type Stream<'a> = TunnelTcpStream<'a>;
+
     /// Open a TCP connection through the tunnel and wait until it is established.
-    pub fn open_tcp(
+    fn open_tcp(
         &mut self,
-        remote: IpAddress,
-        remote_port: u16,
-        timeout: Duration,
+        remote: SocketAddr,
+        deadline: &Deadline,
     ) -> Result<SocketHandle, ProxyError> {
         let rx = SocketBuffer::new(vec![0u8; TCP_BUFFER_SIZE]);
         let tx = SocketBuffer::new(vec![0u8; TCP_BUFFER_SIZE]);
@@ -158,13 +204,12 @@ impl WarpTunnel {
         {
             let socket = self.sockets.get_mut::<TcpSocket>(handle);
             socket
-                .connect(self.interface.context(), (remote, remote_port), local_port)
+                .connect(self.interface.context(), remote, local_port)
                 .map_err(|e| ProxyError::TunnelError {
                     details: format!("TCP connect failed: {e}"),
                 })?;
         }
 
-        let deadline = Instant::now() + timeout;
         loop {
             self.poll_once(POLL_SLICE)?;
             let state = self.sockets.get::<TcpSocket>(handle).state();
@@ -176,11 +221,9 @@ impl WarpTunnel {
                         details: "TCP connection refused".to_string(),
                     });
                 }
-                _ if Instant::now() >= deadline => {
+                _ if Instant::now() >= deadline.at() => {
                     self.sockets.remove(handle);
-                    return Err(ProxyError::Timeout {
-                        seconds: timeout.as_secs() as u32,
-                    });
+                    return Err(deadline.timed_out());
                 }
                 _ => {}
             }
@@ -188,7 +231,7 @@ impl WarpTunnel {
     }
 
     /// Gracefully close and drop a TCP socket.
-    pub fn close_tcp(&mut self, handle: SocketHandle) {
+    fn close_tcp(&mut self, handle: SocketHandle) {
         {
             let socket = self.sockets.get_mut::<TcpSocket>(handle);
             socket.close();
@@ -205,111 +248,96 @@ impl WarpTunnel {
         self.sockets.remove(handle);
     }
 
-    /// Borrow a TCP socket as a blocking [`Read`]/[`Write`] stream.
-    pub fn stream(&mut self, handle: SocketHandle, timeout: Duration) -> TunnelTcpStream<'_> {
+    /// Borrow a TCP socket as a blocking [`Read`](std::io::Read)/
+    /// [`Write`](std::io::Write) stream.
+    fn stream<'a>(
+        &'a mut self,
+        handle: SocketHandle,
+        deadline: &'a Deadline,
+    ) -> TunnelTcpStream<'a> {
         TunnelTcpStream {
             tunnel: self,
             handle,
-            timeout,
+            deadline,
         }
     }
 
-    /// The tunnel's local IPv4 address octets.
-    pub fn local_ipv4(&self) -> [u8; 4] {
-        self.local_ipv4
+    /// Idle HTTP/2 sessions whose sockets live in this tunnel.
+    fn sessions(&mut self) -> &mut SessionPool<SocketHandle> {
+        &mut self.sessions
     }
-}
 
-/// Blocking byte stream over a tunnelled TCP socket.
-///
-/// Each [`read`](Read::read)/[`write`](Write::write) drives the smoltcp poll loop
-/// until the socket can make progress or the per-stream timeout elapses, turning
-/// smoltcp's event model into the synchronous interface rustls expects.
-pub struct TunnelTcpStream<'t> {
-    tunnel: &'t mut WarpTunnel,
-    handle: SocketHandle,
-    timeout: Duration,
-}
+    /// Names resolved through this tunnel; kept across reconnects.
+    fn dns_cache(&mut self) -> &mut DnsCache {
+        &mut self.dns
+    }
 
-impl Read for TunnelTcpStream<'_> {
-    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
-        let deadline = Instant::now() + self.timeout;
-        loop {
-            self.tunnel
-                .poll_once(POLL_SLICE)
-                .map_err(io::Error::other)?;
+    /// Sessions with servers reached through this tunnel; also kept across
+    /// reconnects, since the identity stays the same.
+    fn tls_sessions(&mut self) -> &mut TlsSessionCache {
+        &mut self.tls
+    }
 
-            let socket = self.tunnel.sockets.get_mut::<TcpSocket>(self.handle);
-            if socket.can_recv() {
-                return socket.recv_slice(buf).map_err(io::Error::other);
-            }
-            if !socket.may_recv() {
-                // Peer closed the read half and no buffered data remains: EOF.
-                return Ok(0);
-            }
-            if Instant::now() >= deadline {
-                return Err(io::Error::new(
-                    io::ErrorKind::TimedOut,
-                    "tunnel read timed out",
-                ));
-            }
+    fn reconnect(&mut self) -> Result<bool, ProxyError> {
+        if self.is_connected() {
+            return Ok(false);
         }
+        self.connect(HANDSHAKE_TIMEOUT)?;
+        Ok(true)
     }
-}
 
-impl Write for TunnelTcpStream<'_> {
-    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
-        self.write_bytes(buf)
+    /// Idle HTTP/2 sessions are closed: they ran over the old WireGuard
+    /// session, maybe from the old address. Resolved names and TLS sessions
+    /// are kept.
+    fn update_peer(&mut self, config: &WarpConfig) -> Result<(), ProxyError> {
+        let local_ipv4 = parse_ipv4_octets(&config.interface.address_ipv4)?;
+        self.transport.set_peer(config)?;
+        for session in self.sessions.drain() {
+            self.sockets.remove(session.handle);
+        }
+        if local_ipv4 != self.local_ipv4 {
+            let [a, b, c, d] = local_ipv4;
+            self.interface.update_ip_addrs(|addrs| {
+                addrs.clear();
+                let _ = addrs.push(IpCidr::new(IpAddress::v4(a, b, c, d), 32));
+            });
+            self.local_ipv4 = local_ipv4;
+        }
+        self.config = config.clone();
+        Ok(())
     }
 
-    fn flush(&mut self) -> io::Result<()> {
-        let deadline = Instant::now() + self.timeout;
-        loop {
-            self.tunnel
-                .poll_once(POLL_SLICE)
-                .map_err(io::Error::other)?;
-            let socket = self.tunnel.sockets.get::<TcpSocket>(self.handle);
-            if socket.send_queue() == 0 {
-                return Ok(());
-            }
-            if Instant::now() >= deadline {
-                return Err(io::Error::new(
-                    io::ErrorKind::TimedOut,
-                    "tunnel flush timed out",
-                ));
-            }
-        }
+    fn traffic(&self) -> Option<(u64, u64)> {
+        let stats = self.stats();
+        Some((stats.tx_bytes, stats.rx_bytes))
     }
-}
 
-impl TunnelTcpStream<'_> {
-    fn write_bytes(&mut self, buf: &[u8]) -> io::Result<usize> {
-        let deadline = Instant::now() + self.timeout;
-        loop {
-            self.tunnel
-                .poll_once(POLL_SLICE)
-                .map_err(io::Error::other)?;
-
-            let socket = self.tunnel.sockets.get_mut::<TcpSocket>(self.handle);
-            if !socket.may_send() {
-                return Err(io::Error::new(
-                    io::ErrorKind::BrokenPipe,
-                    "tunnel connection closed",
-                ));
-            }
-            if socket.can_send() {
-                let written = socket.send_slice(buf).map_err(io::Error::other)?;
-                if written > 0 {
-                    return Ok(written);
-                }
-            }
-            if Instant::now() >= deadline {
-                return Err(io::Error::new(
-                    io::ErrorKind::TimedOut,
-                    "tunnel write timed out",
-                ));
-            }
-        }
+    fn diagnostics(&self) -> Result<TunnelDiagnostics, ProxyError> {
+        let stats: TunnelStats = self.stats();
+        let endpoint = self.endpoint();
+        let config = &self.config;
+        Ok(TunnelDiagnostics {
+            connection_state: if self.is_connected() {
+                ConnectionState::Connected
+            } else {
+                ConnectionState::Disconnected
+            },
+            private_key: config.account.private_key.clone(),
+            public_key: self.public_key.clone(),
+            peer_public_key: config.peer.public_key.clone(),
+            endpoint_host: config.peer.endpoint_host.clone(),
+            endpoint_ipv4: endpoint.ip().to_string(),
+            endpoint_port: endpoint.port(),
+            local_address_ipv4: config.interface.address_ipv4.clone(),
+            warp_enabled: config.warp_enabled,
+            account_type: config.account_type.clone(),
+            account_id: config.account.account_id.clone(),
+            last_handshake_secs: stats.since_handshake.map(|d| d.as_secs()),
+            tx_bytes: stats.tx_bytes,
+            rx_bytes: stats.rx_bytes,
+            estimated_loss: stats.estimated_loss,
+            rtt_ms: stats.rtt_ms,
+        })
     }
 }
 
@@ -341,17 +369,18 @@ mod tests {
             warp_enabled: true,
             account_type: "test".to_string(),
             last_updated: 0,
+            client_id: String::new(),
         }
     }
 
     #[test]
     fn tunnel_creation_succeeds() {
-        assert!(WarpTunnel::new(&test_config()).is_ok());
+        assert!(WarpTunnel::new(&test_config(), &SocketSetup::default()).is_ok());
     }
 
     #[test]
     fn tunnel_not_connected_initially() {
-        let tunnel = WarpTunnel::new(&test_config()).unwrap();
+        let tunnel = WarpTunnel::new(&test_config(), &SocketSetup::default()).unwrap();
         assert!(!tunnel.is_connected());
     }
 
@@ -362,9 +391,23 @@ mod tests {
         assert!(parse_ipv4_octets("not-an-ip").is_err());
     }
 
+    #[test]
+    fn peer_updates_move_the_local_address() {
+        let mut tunnel = WarpTunnel::new(&test_config(), &SocketSetup::default()).unwrap();
+        let mut refreshed = tunnel.config.clone();
+        refreshed.interface.address_ipv4 = "172.16.0.9/32".to_string();
+        tunnel.update_peer(&refreshed).unwrap();
+        assert_eq!(tunnel.local_ipv4(), [172, 16, 0, 9]);
+        assert_eq!(
+            tunnel.diagnostics().unwrap().local_address_ipv4,
+            "172.16.0.9/32"
+        );
+        assert!(!tunnel.is_connected());
+    }
+
     #[test]
     fn local_port_allocation_wraps() {
-        let mut tunnel = WarpTunnel::new(&test_config()).unwrap();
+        let mut tunnel = WarpTunnel::new(&test_config(), &SocketSetup::default()).unwrap();
         tunnel.next_local_port = 65_535;
         assert_eq!(tunnel.allocate_local_port(), 65_535);
         assert_eq!(tunnel.allocate_local_port(), 49_152);
@@ -0,0 +1,101 @@
+//! The blocking [`Read`]/[`Write`] view of a tunnelled TCP socket.
+
+use super::{WarpTunnel, POLL_SLICE};
+use crate::timeouts::Deadline;
+use smoltcp::iface::SocketHandle;
+use smoltcp::socket::tcp::Socket as TcpSocket;
+use std::io::{self, Read, Write};
+use std::time::Instant;
+
+/// Blocking byte stream over a tunnelled TCP socket.
+///
+/// Each [`read`](Read::read)/[`write`](Write::write) drives the smoltcp poll loop
+/// until the socket can make progress or the request's deadline passes, turning
+/// smoltcp's event model into the synchronous interface rustls expects.
+pub struct TunnelTcpStream<'t> {
+    pub(super) tunnel: &'t mut WarpTunnel,
+    pub(super) handle: SocketHandle,
+    pub(super) deadline: &'t Deadline,
+}
+
+impl Read for TunnelTcpStream<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let deadline = self.deadline.at();
+        loop {
+            self.tunnel
+                .poll_once(POLL_SLICE)
+                .map_err(io::Error::other)?;
+
+            let socket = self.tunnel.sockets.get_mut::<TcpSocket>(self.handle);
+            if socket.can_recv() {
+                return socket.recv_slice(buf).map_err(io::Error::other);
+            }
+            if !socket.may_recv() {
+                // Peer closed the read half and no buffered data remains: EOF.
+                return Ok(0);
+            }
+            if Instant::now() >= deadline {
+                return Err(io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    "tunnel read timed out",
+                ));
+            }
+        }
+    }
+}
+
+impl Write for TunnelTcpStream<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.write_bytes(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        let deadline = self.deadline.at();
+        loop {
+            self.tunnel
+                .poll_once(POLL_SLICE)
+                .map_err(io::Error::other)?;
+            let socket = self.tunnel.sockets.get::<TcpSocket>(self.handle);
+            if socket.send_queue() == 0 {
+                return Ok(());
+            }
+            if Instant::now() >= deadline {
+                return Err(io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    "tunnel flush timed out",
+                ));
+            }
+        }
+    }
+}
+
+impl TunnelTcpStream<'_> {
+    fn write_bytes(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let deadline = self.deadline.at();
+        loop {
+            self.tunnel
+                .poll_once(POLL_SLICE)
+                .map_err(io::Error::other)?;
+
+            let socket = self.tunnel.sockets.get_mut::<TcpSocket>(self.handle);
+            if !socket.may_send() {
+                return Err(io::Error::new(
+                    io::ErrorKind::BrokenPipe,
+                    "tunnel connection closed",
+                ));
+            }
+            if socket.can_send() {
+                let written = socket.send_slice(buf).map_err(io::Error::other)?;
+                if written > 0 {
+                    return Ok(written);
+                }
+            }
+            if Instant::now() >= deadline {
+                return Err(io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    "tunnel write timed out",
+                ));
+            }
+        }
+    }
+}
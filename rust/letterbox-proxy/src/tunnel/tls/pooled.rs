@@ -0,0 +1,92 @@
+//! Requests on idle HTTP/2 sessions parked on the network.
+//!
+//! A session is taken from the network's [`SessionPool`](crate::tunnel::h2::SessionPool)
+//! for a request, or for a batch of requests to the same server sent
+//! together, and parked again afterwards unless the connection can no longer
+//! be used.
+
+use super::{tls_io_error, ABSOLUTE_MAX_RESPONSE};
+use crate::error::TimeoutPhase;
+use crate::timeouts::Deadline;
+use crate::tunnel::body::BodySink;
+use crate::tunnel::h2::{Exchange, Failure, PooledSession};
+use crate::tunnel::http1::ResponseHead;
+use crate::tunnel::network::Network;
+use std::net::SocketAddr;
+
+/// Take the idle session to `endpoint` for `sni`, if there is one still fit
+/// for another request. An unfit one is closed.
+pub fn idle_session<N: Network>(
+    tunnel: &mut N,
+    endpoint: SocketAddr,
+    sni: &str,
+) -> Option<PooledSession<N::Handle>> {
+    let pooled = tunnel.sessions().take(endpoint, sni)?;
+    if pooled.session.is_reusable() {
+        return Some(pooled);
+    }
+    tunnel.close_tcp(pooled.handle);
+    None
+}
+
+/// Run `request` on an HTTP/2 session, then park the session for reuse or
+/// close its socket.
+pub(super) fn request_h2<N: Network, W: BodySink>(
+    tunnel: &mut N,
+    mut pooled: PooledSession<N::Handle>,
+    request: &[u8],
+    max_body: u64,
+    body: &mut W,
+    deadline: &Deadline,
+) -> Result<ResponseHead, Failure> {
+    deadline.enter(TimeoutPhase::FirstByte);
+    let result = {
+        let mut adapter = tunnel.stream(pooled.handle, deadline);
+        let mut tls = rustls::Stream::new(&mut pooled.tls, &mut adapter);
+        pooled
+            .session
+            .request(&mut tls, request, max_body, body, deadline, |e| {
+                deadline.io_error(e, tls_io_error)
+            })
+    };
+    park(tunnel, pooled);
+    result
+}
+
+/// Run `exchanges` concurrently on an HTTP/2 session, one stream each, then
+/// park the session for reuse or close its socket. Results are in the order
+/// of `exchanges`; see
+/// [`Session::request_all`](crate::tunnel::h2::Session::request_all).
+pub fn request_h2_all<N: Network, W: BodySink>(
+    tunnel: &mut N,
+    mut pooled: PooledSession<N::Handle>,
+    mut exchanges: Vec<Exchange<'_, W>>,
+    deadline: &Deadline,
+) -> Vec<Result<ResponseHead, Failure>> {
+    for exchange in &mut exchanges {
+        exchange.max_body = exchange.max_body.min(ABSOLUTE_MAX_RESPONSE);
+    }
+    deadline.enter(TimeoutPhase::FirstByte);
+    let results = {
+        let mut adapter = tunnel.stream(pooled.handle, deadline);
+        let mut tls = rustls::Stream::new(&mut pooled.tls, &mut adapter);
+        pooled
+            .session
+            .request_all(&mut tls, exchanges, deadline, |e| {
+                deadline.io_error(e, tls_io_error)
+            })
+    };
+    park(tunnel, pooled);
+    results
+}
+
+/// Park `pooled` for reuse if it can take more requests, else close it.
+fn park<N: Network>(tunnel: &mut N, pooled: PooledSession<N::Handle>) {
+    if pooled.session.is_reusable() {
+        if let Some(evicted) = tunnel.sessions().put(pooled) {
+            tunnel.close_tcp(evicted.handle);
+        }
+    } else {
+        tunnel.close_tcp(pooled.handle);
+    }
+}
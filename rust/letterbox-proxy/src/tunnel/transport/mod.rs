@@ -10,16 +10,24 @@
 //! The transport is owned exclusively by a single worker thread (see
 //! [`crate::tunnel::manager`]). Because there is no cross-thread sharing it holds
 //! the boringtun [`Tunn`] directly — no `Arc`, no `Mutex`, no lock poisoning.
+//!
+//! Key decoding and the WARP client ID live in [`session`](mod@session), the
+//! placement of the UDP socket on a network in [`socket`].
+
+mod session;
+mod socket;
 
 use crate::config::WarpConfig;
 use crate::error::ProxyError;
-use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use crate::protect::{local_address, NetworkBinding, SocketSetup};
 use boringtun::noise::{Tunn, TunnResult};
-use std::net::{SocketAddr, UdpSocket};
+use session::{decapsulate, session, stamp_reserved};
+use socket::{bind_to_network, protect};
+use std::net::{Ipv4Addr, SocketAddr, UdpSocket};
 use std::time::{Duration, Instant};
 
 /// Maximum WireGuard datagram size (IPv6 jumbo headroom).
-const MAX_DATAGRAM: usize = 65_535;
+pub const MAX_DATAGRAM: usize = 65_535;
 
 /// Fixed Cloudflare WARP UDP endpoint.
 ///
@@ -33,25 +41,9 @@ pub const WARP_ENDPOINT_IPV4: &str = "162.159.192.8";
 /// Fixed Cloudflare WARP UDP endpoint port (see [`WARP_ENDPOINT_IPV4`]).
 pub const WARP_ENDPOINT_PORT: u16 = 500;
 
-/// Persistent keepalive interval negotiated with the WARP peer (seconds).
-const PERSISTENT_KEEPALIVE_SECS: u16 = 25;
-
 /// Minimum spacing between [`tick`](WireGuardTransport::tick) timer updates.
 const TICK_INTERVAL: Duration = Duration::from_millis(100);
 
-/// Decode a base64 WireGuard key into its 32 raw bytes.
-fn decode_key(label: &str, encoded: &str) -> Result<[u8; 32], ProxyError> {
-    BASE64
-        .decode(encoded)
-        .map_err(|e| ProxyError::CryptoError {
-            details: format!("Invalid {label}: {e}"),
-        })?
-        .try_into()
-        .map_err(|_| ProxyError::CryptoError {
-            details: format!("{label} must be exactly 32 bytes"),
-        })
-}
-
 /// Live transport statistics sourced from boringtun.
 #[derive(Debug, Clone, Default)]
 pub struct TunnelStats {
@@ -75,25 +67,19 @@ pub struct WireGuardTransport {
     recv_buf: Vec<u8>,
     send_buf: Vec<u8>,
     last_tick: Instant,
+    /// Client ID stamped into the reserved bytes of every message sent.
+    reserved: [u8; 3],
 }
 
 impl WireGuardTransport {
     /// Build a transport from provisioned WARP configuration.
     ///
-    /// The UDP socket is bound to an ephemeral local port and connected to the
-    /// WARP endpoint so the OS routes replies back to us.
-    pub fn new(config: &WarpConfig) -> Result<Self, ProxyError> {
-        let private_key = decode_key("private key", &config.account.private_key)?;
-        let peer_public_key = decode_key("peer public key", &config.peer.public_key)?;
-
-        let tunnel = Tunn::new(
-            private_key.into(),
-            peer_public_key.into(),
-            None,
-            Some(PERSISTENT_KEEPALIVE_SECS),
-            0,
-            None,
-        );
+    /// The UDP socket is bound to an ephemeral local port on the network
+    /// `setup` names, handed to its protector so it can be excluded from a
+    /// VPN, and connected to the WARP endpoint so the OS routes replies back
+    /// to us.
+    pub fn new(config: &WarpConfig, setup: &SocketSetup) -> Result<Self, ProxyError> {
+        let (tunnel, reserved) = session(config)?;
 
         let endpoint: SocketAddr = format!("{WARP_ENDPOINT_IPV4}:{WARP_ENDPOINT_PORT}")
             .parse()
@@ -101,9 +87,19 @@ impl WireGuardTransport {
                 details: format!("Invalid endpoint address: {e}"),
             })?;
 
-        let socket = UdpSocket::bind("0.0.0.0:0").map_err(|e| ProxyError::TunnelError {
-            details: format!("Failed to bind UDP socket: {e}"),
+        let local = match &setup.binding {
+            NetworkBinding::LocalAddress { address } => local_address(address)?,
+            _ => Ipv4Addr::UNSPECIFIED,
+        };
+        let socket = UdpSocket::bind((local, 0)).map_err(|e| ProxyError::TunnelError {
+            details: format!("Failed to bind UDP socket to {local}: {e}"),
         })?;
+        if let NetworkBinding::AndroidNetwork { handle } = setup.binding {
+            bind_to_network(&socket, handle)?;
+        }
+        if let Some(protector) = &setup.protector {
+            protect(&socket, protector.as_ref())?;
+        }
         socket
             .connect(endpoint)
             .map_err(|e| ProxyError::TunnelError {
@@ -117,14 +113,37 @@ impl WireGuardTransport {
             recv_buf: vec![0u8; MAX_DATAGRAM],
             send_buf: vec![0u8; MAX_DATAGRAM],
             last_tick: Instant::now(),
+            reserved,
         })
     }
 
+    /// Switch to the peer key and client ID of a refreshed `config`, keeping
+    /// the socket and endpoint. The session with the old peer is dropped, so
+    /// the transport is disconnected until the next handshake.
+    pub fn set_peer(&mut self, config: &WarpConfig) -> Result<(), ProxyError> {
+        let (tunnel, reserved) = session(config)?;
+        *self.tunnel = tunnel;
+        self.reserved = reserved;
+        Ok(())
+    }
+
     /// The remote WARP endpoint this transport is bound to.
     pub fn endpoint(&self) -> SocketAddr {
         self.endpoint
     }
 
+    /// Send to `endpoint` instead of the fixed one, keeping the socket. Takes
+    /// effect with the next handshake.
+    pub fn set_endpoint(&mut self, endpoint: SocketAddr) -> Result<(), ProxyError> {
+        self.socket
+            .connect(endpoint)
+            .map_err(|e| ProxyError::TunnelError {
+                details: format!("Failed to connect UDP socket to {endpoint}: {e}"),
+            })?;
+        self.endpoint = endpoint;
+        Ok(())
+    }
+
     /// Send the first handshake initiation message to the peer.
     pub fn initiate_handshake(&mut self) -> Result<(), ProxyError> {
         match self
@@ -132,6 +151,7 @@ impl WireGuardTransport {
             .format_handshake_initiation(&mut self.send_buf, false)
         {
             TunnResult::WriteToNetwork(packet) => {
+                stamp_reserved(packet, self.reserved);
                 self.socket
                     .send(packet)
                     .map_err(|e| ProxyError::TunnelError {
@@ -192,28 +212,24 @@ impl WireGuardTransport {
     /// Decrypt a single received datagram of length `n`, appending IP payloads.
     fn decapsulate_into(&mut self, n: usize, packets: &mut Vec<Vec<u8>>) {
         // SAFETY of indices: `n` comes from a successful `recv` into `recv_buf`.
-        let datagram = self.recv_buf[..n].to_vec();
-        let mut result = self.tunnel.decapsulate(None, &datagram, &mut self.send_buf);
-        loop {
-            match result {
-                TunnResult::WriteToNetwork(packet) => {
-                    let _ = self.socket.send(packet);
-                    // boringtun requires repeated empty calls to flush its queue.
-                    result = self.tunnel.decapsulate(None, &[], &mut self.send_buf);
-                }
-                TunnResult::WriteToTunnelV4(packet, _) | TunnResult::WriteToTunnelV6(packet, _) => {
-                    packets.push(packet.to_vec());
-                    result = self.tunnel.decapsulate(None, &[], &mut self.send_buf);
-                }
-                TunnResult::Done | TunnResult::Err(_) => break,
-            }
-        }
+        let (socket, reserved) = (&self.socket, self.reserved);
+        decapsulate(
+            &mut self.tunnel,
+            &mut self.recv_buf[..n],
+            &mut self.send_buf,
+            |reply| {
+                stamp_reserved(reply, reserved);
+                let _ = socket.send(reply);
+            },
+            packets,
+        );
     }
 
     /// Encrypt one outbound IP packet and send it to the peer.
     pub fn send_ip(&mut self, packet: &[u8]) -> Result<(), ProxyError> {
         match self.tunnel.encapsulate(packet, &mut self.send_buf) {
             TunnResult::WriteToNetwork(encrypted) => {
+                stamp_reserved(encrypted, self.reserved);
                 self.socket
                     .send(encrypted)
                     .map_err(|e| ProxyError::TunnelError {
@@ -237,6 +253,7 @@ impl WireGuardTransport {
         self.last_tick = now;
 
         if let TunnResult::WriteToNetwork(packet) = self.tunnel.update_timers(&mut self.send_buf) {
+            stamp_reserved(packet, self.reserved);
             let _ = self.socket.send(packet);
         }
         Ok(())
@@ -264,7 +281,9 @@ impl WireGuardTransport {
 mod tests {
     use super::*;
     use crate::config::{WarpAccountData, WarpInterfaceConfig, WarpPeerConfig};
+    use crate::protect::SocketProtector;
     use crate::provisioning::WarpProvisioner;
+    use std::sync::{Arc, Mutex};
 
     fn test_config() -> WarpConfig {
         let (private_key, _) = WarpProvisioner::generate_keypair();
@@ -288,17 +307,18 @@ mod tests {
             warp_enabled: true,
             account_type: "test".to_string(),
             last_updated: 0,
+            client_id: String::new(),
         }
     }
 
     #[test]
     fn transport_creation_succeeds() {
-        assert!(WireGuardTransport::new(&test_config()).is_ok());
+        assert!(WireGuardTransport::new(&test_config(), &SocketSetup::default()).is_ok());
     }
 
     #[test]
     fn transport_not_connected_initially() {
-        let transport = WireGuardTransport::new(&test_config()).unwrap();
+        let transport = WireGuardTransport::new(&test_config(), &SocketSetup::default()).unwrap();
         assert!(!transport.is_connected());
         assert!(transport.stats().since_handshake.is_none());
     }
@@ -308,21 +328,118 @@ mod tests {
         let mut config = test_config();
         config.account.private_key = "not-base64!".to_string();
         assert!(matches!(
-            WireGuardTransport::new(&config),
+            WireGuardTransport::new(&config, &SocketSetup::default()),
+            Err(ProxyError::CryptoError { .. })
+        ));
+    }
+
+    #[test]
+    fn invalid_client_id_is_rejected() {
+        let mut config = test_config();
+        config.client_id = "AQIDBA==".to_string();
+        assert!(matches!(
+            WireGuardTransport::new(&config, &SocketSetup::default()),
             Err(ProxyError::CryptoError { .. })
         ));
     }
 
+    #[test]
+    fn peer_swaps_keep_the_socket() {
+        let mut transport =
+            WireGuardTransport::new(&test_config(), &SocketSetup::default()).unwrap();
+        let local = transport.socket.local_addr().unwrap();
+
+        let mut refreshed = test_config();
+        refreshed.client_id = "AQID".to_string();
+        transport.set_peer(&refreshed).unwrap();
+        assert_eq!(transport.reserved, [1, 2, 3]);
+        assert_eq!(transport.socket.local_addr().unwrap(), local);
+        assert!(!transport.is_connected());
+
+        refreshed.peer.public_key = "short".to_string();
+        assert!(transport.set_peer(&refreshed).is_err());
+        assert_eq!(transport.reserved, [1, 2, 3]);
+    }
+
+    #[test]
+    fn endpoint_moves_keep_the_socket() {
+        let mut transport =
+            WireGuardTransport::new(&test_config(), &SocketSetup::default()).unwrap();
+        let local = transport.socket.local_addr().unwrap();
+        let moved: SocketAddr = "127.0.0.1:2408".parse().unwrap();
+        transport.set_endpoint(moved).unwrap();
+        assert_eq!(transport.endpoint(), moved);
+        assert_eq!(transport.socket.peer_addr().unwrap(), moved);
+        assert_eq!(transport.socket.local_addr().unwrap().port(), local.port());
+    }
+
     #[test]
     fn invalid_peer_key_is_rejected() {
         let mut config = test_config();
         config.peer.public_key = "short".to_string();
-        assert!(WireGuardTransport::new(&config).is_err());
+        assert!(WireGuardTransport::new(&config, &SocketSetup::default()).is_err());
+    }
+
+    struct Protector {
+        allow: bool,
+        fds: Mutex<Vec<i32>>,
+    }
+
+    impl SocketProtector for Protector {
+        fn protect(&self, fd: i32) -> bool {
+            self.fds.lock().unwrap().push(fd);
+            self.allow
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn socket_is_protected_before_use_and_refusal_fails() {
+        use std::os::fd::AsRawFd;
+        for allow in [true, false] {
+            let protector = Arc::new(Protector {
+                allow,
+                fds: Mutex::default(),
+            });
+            let setup = SocketSetup {
+                protector: Some(protector.clone()),
+                ..SocketSetup::default()
+            };
+            let result = WireGuardTransport::new(&test_config(), &setup);
+            assert_eq!(result.is_ok(), allow);
+            let fds = protector.fds.lock().unwrap();
+            assert_eq!(fds.len(), 1);
+            if let Ok(transport) = result {
+                assert_eq!(fds[0], transport.socket.as_raw_fd());
+            }
+        }
+    }
+
+    #[test]
+    fn socket_is_bound_where_the_setup_says() {
+        let bound = |binding| {
+            let setup = SocketSetup {
+                binding,
+                ..SocketSetup::default()
+            };
+            WireGuardTransport::new(&test_config(), &setup)
+        };
+        let any = NetworkBinding::LocalAddress {
+            address: "0.0.0.0".to_string(),
+        };
+        assert!(bound(any).is_ok());
+        // TEST-NET-1 is never assigned to a local interface.
+        let absent = NetworkBinding::LocalAddress {
+            address: "192.0.2.1".to_string(),
+        };
+        assert!(matches!(bound(absent), Err(ProxyError::TunnelError { .. })));
+        #[cfg(not(target_os = "android"))]
+        assert!(bound(NetworkBinding::AndroidNetwork { handle: 1 }).is_err());
     }
 
     #[test]
     fn endpoint_is_fixed_warp_anycast() {
-        let transport = WireGuardTransport::new(&test_config()).unwrap();
+        let transport = WireGuardTransport::new(&test_config(), &SocketSetup::default()).unwrap();
         let endpoint = transport.endpoint();
         assert_eq!(endpoint.ip().to_string(), WARP_ENDPOINT_IPV4);
         assert_eq!(endpoint.port(), WARP_ENDPOINT_PORT);
@@ -0,0 +1,171 @@
+//! WireGuard sessions with the WARP peer and the client ID WARP expects in
+//! the reserved header bytes of every message.
+
+use crate::config::WarpConfig;
+use crate::error::ProxyError;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use boringtun::noise::{Tunn, TunnResult};
+
+/// Persistent keepalive interval negotiated with the WARP peer (seconds).
+const PERSISTENT_KEEPALIVE_SECS: u16 = 25;
+
+/// Decode a base64 WireGuard key into its 32 raw bytes.
+fn decode_key(label: &str, encoded: &str) -> Result<[u8; 32], ProxyError> {
+    BASE64
+        .decode(encoded)
+        .map_err(|e| ProxyError::CryptoError {
+            details: format!("Invalid {label}: {e}"),
+        })?
+        .try_into()
+        .map_err(|_| ProxyError::CryptoError {
+            details: format!("{label} must be exactly 32 bytes"),
+        })
+}
+
+/// Decode the base64 WARP client ID into the three reserved header bytes.
+///
+/// An empty ID leaves the reserved bytes zero, as plain WireGuard requires.
+fn decode_client_id(encoded: &str) -> Result<[u8; 3], ProxyError> {
+    if encoded.is_empty() {
+        return Ok([0; 3]);
+    }
+    BASE64
+        .decode(encoded)
+        .map_err(|e| ProxyError::CryptoError {
+            details: format!("Invalid client ID: {e}"),
+        })?
+        .try_into()
+        .map_err(|_| ProxyError::CryptoError {
+            details: "Client ID must be exactly 3 bytes".to_string(),
+        })
+}
+
+/// A WireGuard session to the peer `config` names, not yet handshaken, and
+/// the reserved bytes its messages carry.
+pub(super) fn session(config: &WarpConfig) -> Result<(Tunn, [u8; 3]), ProxyError> {
+    let private_key = decode_key("private key", &config.account.private_key)?;
+    let peer_public_key = decode_key("peer public key", &config.peer.public_key)?;
+    let reserved = decode_client_id(&config.client_id)?;
+    let tunnel = Tunn::new(
+        private_key.into(),
+        peer_public_key.into(),
+        None,
+        Some(PERSISTENT_KEEPALIVE_SECS),
+        0,
+        None,
+    );
+    Ok((tunnel, reserved))
+}
+
+/// Write `reserved` into bytes 1..4 of an outgoing WireGuard message.
+///
+/// boringtun always leaves them zero, but WARP uses them to tell clients
+/// apart and drops traffic from some accounts without them.
+pub(super) fn stamp_reserved(message: &mut [u8], reserved: [u8; 3]) {
+    if let Some(field) = message.get_mut(1..4) {
+        field.copy_from_slice(&reserved);
+    }
+}
+
+/// Zero bytes 1..4 of an incoming WireGuard message, which WARP may fill
+/// with the client ID but boringtun rejects unless zero.
+fn clear_reserved(message: &mut [u8]) {
+    if let Some(field) = message.get_mut(1..4) {
+        field.fill(0);
+    }
+}
+
+/// Decrypt one received WireGuard `datagram`, appending the IP packets it
+/// carries to `packets`.
+///
+/// Handshake, cookie and keepalive replies, and any packets boringtun queued
+/// until the session came up, are handed to `send` to go back to the peer.
+/// `scratch` must fit the largest datagram. The datagram comes straight off
+/// the network, so this must cope with any bytes at all.
+pub(super) fn decapsulate(
+    tunnel: &mut Tunn,
+    datagram: &mut [u8],
+    scratch: &mut [u8],
+    mut send: impl FnMut(&mut [u8]),
+    packets: &mut Vec<Vec<u8>>,
+) {
+    clear_reserved(datagram);
+    let mut result = tunnel.decapsulate(None, datagram, scratch);
+    loop {
+        match result {
+            TunnResult::WriteToNetwork(packet) => {
+                send(packet);
+                // boringtun requires repeated empty calls to flush its queue.
+                result = tunnel.decapsulate(None, &[], scratch);
+            }
+            TunnResult::WriteToTunnelV4(packet, _) | TunnResult::WriteToTunnelV6(packet, _) => {
+                packets.push(packet.to_vec());
+                result = tunnel.decapsulate(None, &[], scratch);
+            }
+            TunnResult::Done | TunnResult::Err(_) => break,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provisioning::WarpProvisioner;
+    use crate::tunnel::transport::MAX_DATAGRAM;
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    #[test]
+    fn client_id_fills_and_clears_reserved_bytes() {
+        let reserved = decode_client_id("AQID").unwrap();
+        assert_eq!(reserved, [1, 2, 3]);
+        assert_eq!(decode_client_id("").unwrap(), [0; 3]);
+        assert!(decode_client_id("AQIDBA==").is_err());
+
+        let mut message = [4, 0, 0, 0, 9];
+        stamp_reserved(&mut message, reserved);
+        assert_eq!(message, [4, 1, 2, 3, 9]);
+        clear_reserved(&mut message);
+        assert_eq!(message, [4, 0, 0, 0, 9]);
+    }
+
+    /// A tunnel to a peer nobody holds the key of.
+    fn test_tunnel() -> Tunn {
+        let (private_key, _) = WarpProvisioner::generate_keypair();
+        let (_, peer_public) = WarpProvisioner::generate_keypair();
+        let private_key = decode_key("private key", &private_key).unwrap();
+        let peer_public_key = decode_key("peer public key", &peer_public).unwrap();
+        Tunn::new(
+            private_key.into(),
+            peer_public_key.into(),
+            None,
+            None,
+            0,
+            None,
+        )
+    }
+
+    proptest! {
+        #[test]
+        fn decapsulation_survives_arbitrary_datagrams(
+            message_type in 0u8..6,
+            reserved in any::<[u8; 3]>(),
+            rest in vec(any::<u8>(), 0..256),
+        ) {
+            let mut datagram = [&[message_type][..], &reserved, &rest].concat();
+            let mut packets = Vec::new();
+            let mut replies = 0;
+            let mut scratch = vec![0; MAX_DATAGRAM];
+            decapsulate(
+                &mut test_tunnel(),
+                &mut datagram,
+                &mut scratch,
+                |_| replies += 1,
+                &mut packets,
+            );
+            // Without the peer's key nothing can authenticate.
+            prop_assert!(packets.is_empty());
+            prop_assert_eq!(replies, 0);
+        }
+    }
+}
@@ -0,0 +1,68 @@
+//! Placing the WireGuard UDP socket: protecting it from a VPN and binding it
+//! to a chosen Android network.
+
+use crate::error::ProxyError;
+use crate::protect::SocketProtector;
+use std::net::UdpSocket;
+
+/// Hand `socket` to `protector` before it is connected, so the route it
+/// picks already bypasses any VPN.
+#[cfg(unix)]
+pub(super) fn protect(
+    socket: &UdpSocket,
+    protector: &dyn SocketProtector,
+) -> Result<(), ProxyError> {
+    use std::os::fd::AsRawFd;
+    if protector.protect(socket.as_raw_fd()) {
+        Ok(())
+    } else {
+        Err(ProxyError::TunnelError {
+            details: "The socket protector refused the WireGuard socket".to_string(),
+        })
+    }
+}
+
+/// Socket protection hands out a Unix file descriptor; elsewhere there is
+/// nothing to hand over.
+#[cfg(not(unix))]
+pub(super) fn protect(
+    _socket: &UdpSocket,
+    _protector: &dyn SocketProtector,
+) -> Result<(), ProxyError> {
+    log::warn!("Socket protection needs a Unix file descriptor; ignoring the protector");
+    Ok(())
+}
+
+/// Route `socket` over the Android `Network` with this handle.
+#[cfg(target_os = "android")]
+pub(super) fn bind_to_network(socket: &UdpSocket, handle: u64) -> Result<(), ProxyError> {
+    use std::os::fd::AsRawFd;
+    use std::os::raw::c_int;
+
+    #[link(name = "android")]
+    extern "C" {
+        /// From `<android/multinetwork.h>`, API level 23.
+        fn android_setsocknetwork(network: u64, fd: c_int) -> c_int;
+    }
+
+    // SAFETY: `socket` owns the descriptor for the whole call, and the call
+    // only changes the socket's routing.
+    if unsafe { android_setsocknetwork(handle, socket.as_raw_fd()) } == 0 {
+        Ok(())
+    } else {
+        Err(ProxyError::TunnelError {
+            details: format!(
+                "Failed to bind the WireGuard socket to network {handle}: {}",
+                std::io::Error::last_os_error()
+            ),
+        })
+    }
+}
+
+/// Android network handles mean nothing elsewhere.
+#[cfg(not(target_os = "android"))]
+pub(super) fn bind_to_network(_socket: &UdpSocket, handle: u64) -> Result<(), ProxyError> {
+    Err(ProxyError::TunnelError {
+        details: format!("Cannot bind to Android network {handle}: not running on Android"),
+    })
+}
@@ -0,0 +1,188 @@
+//! Handing a connection to the upstream proxy over to its destination, with a
+//! SOCKS5 or an HTTP `CONNECT` handshake.
+
+use crate::types::ProxyCredentials;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use std::io::{self, Read, Write};
+use std::net::{IpAddr, SocketAddr};
+
+const SOCKS_VERSION: u8 = 5;
+
+/// SOCKS5 method: no authentication.
+const SOCKS_NO_AUTH: u8 = 0;
+
+/// SOCKS5 method: user name and password (RFC 1929).
+const SOCKS_USER_PASSWORD: u8 = 2;
+
+/// Longest `CONNECT` response head read from an HTTP proxy.
+const MAX_CONNECT_RESPONSE: usize = 8 * 1024;
+
+fn refused(reason: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::ConnectionRefused, reason.into())
+}
+
+/// What a SOCKS5 reply code means.
+fn socks_reply(code: u8) -> &'static str {
+    match code {
+        1 => "general failure",
+        2 => "connection not allowed by ruleset",
+        3 => "network unreachable",
+        4 => "host unreachable",
+        5 => "connection refused",
+        6 => "TTL expired",
+        7 => "command not supported",
+        8 => "address type not supported",
+        _ => "unknown error",
+    }
+}
+
+/// Have a SOCKS5 proxy connect `stream` to `remote`.
+pub(super) fn socks5_connect(
+    stream: &mut (impl Read + Write),
+    remote: SocketAddr,
+    credentials: Option<&ProxyCredentials>,
+) -> io::Result<()> {
+    let method = match credentials {
+        Some(_) => SOCKS_USER_PASSWORD,
+        None => SOCKS_NO_AUTH,
+    };
+    stream.write_all(&[SOCKS_VERSION, 1, method])?;
+    let mut reply = [0; 2];
+    stream.read_exact(&mut reply)?;
+    if reply[0] != SOCKS_VERSION {
+        return Err(refused("not a SOCKS5 proxy"));
+    }
+    if reply[1] != method {
+        return Err(refused("no acceptable authentication method"));
+    }
+    if let Some(credentials) = credentials {
+        // Lengths were checked to fit a byte by `validate`.
+        let mut request = vec![1, credentials.username.len() as u8];
+        request.extend_from_slice(credentials.username.as_bytes());
+        request.push(credentials.password.len() as u8);
+        request.extend_from_slice(credentials.password.as_bytes());
+        stream.write_all(&request)?;
+        stream.read_exact(&mut reply)?;
+        if reply[1] != 0 {
+            return Err(refused("user name or password rejected"));
+        }
+    }
+
+    let mut request = vec![SOCKS_VERSION, 1, 0];
+    match remote.ip() {
+        IpAddr::V4(ip) => {
+            request.push(1);
+            request.extend_from_slice(&ip.octets());
+        }
+        IpAddr::V6(ip) => {
+            request.push(4);
+            request.extend_from_slice(&ip.octets());
+        }
+    }
+    request.extend_from_slice(&remote.port().to_be_bytes());
+    stream.write_all(&request)?;
+
+    let mut head = [0; 4];
+    stream.read_exact(&mut head)?;
+    if head[0] != SOCKS_VERSION {
+        return Err(refused("malformed SOCKS5 reply"));
+    }
+    if head[1] != 0 {
+        return Err(refused(socks_reply(head[1])));
+    }
+    // The address the proxy connected from, which is of no use here.
+    let address_len = match head[3] {
+        1 => 4,
+        4 => 16,
+        3 => {
+            let mut len = [0];
+            stream.read_exact(&mut len)?;
+            usize::from(len[0])
+        }
+        _ => return Err(refused("malformed SOCKS5 reply")),
+    };
+    let mut bound = vec![0; address_len + 2];
+    stream.read_exact(&mut bound)
+}
+
+/// Have an HTTP proxy connect `stream` to `remote` with `CONNECT`.
+pub(super) fn http_connect(
+    stream: &mut (impl Read + Write),
+    remote: SocketAddr,
+    credentials: Option<&ProxyCredentials>,
+) -> io::Result<()> {
+    let mut request = format!("CONNECT {remote} HTTP/1.1\r\nHost: {remote}\r\n");
+    if let Some(credentials) = credentials {
+        let token = BASE64.encode(format!("{}:{}", credentials.username, credentials.password));
+        request.push_str(&format!("Proxy-Authorization: Basic {token}\r\n"));
+    }
+    request.push_str("\r\n");
+    stream.write_all(request.as_bytes())?;
+
+    // Read byte by byte: whatever follows the head is the server's.
+    let mut head = Vec::new();
+    while !head.ends_with(b"\r\n\r\n") {
+        if head.len() >= MAX_CONNECT_RESPONSE {
+            return Err(refused("CONNECT response head too long"));
+        }
+        let mut byte = [0];
+        stream.read_exact(&mut byte)?;
+        head.push(byte[0]);
+    }
+    let status = std::str::from_utf8(&head)
+        .ok()
+        .and_then(|head| head.split_whitespace().nth(1))
+        .and_then(|code| code.parse::<u16>().ok());
+    match status {
+        Some(200..=299) => Ok(()),
+        Some(407) => Err(refused("proxy authentication required")),
+        Some(status) => Err(refused(format!("CONNECT answered with status {status}"))),
+        None => Err(refused("malformed CONNECT response")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn http_connect_refusals_fail_the_connection() {
+        let mut stream = io::Cursor::new(Vec::new());
+        let remote: SocketAddr = "192.0.2.7:443".parse().unwrap();
+        let mut answer = |response: &[u8]| {
+            let mut duplex = Duplex {
+                input: io::Cursor::new(response.to_vec()),
+                output: &mut stream,
+            };
+            http_connect(&mut duplex, remote, None)
+        };
+        assert!(answer(b"HTTP/1.1 200 OK\r\n\r\n").is_ok());
+        let error = answer(b"HTTP/1.1 407 Proxy Authentication Required\r\n\r\n").unwrap_err();
+        assert_eq!(error.to_string(), "proxy authentication required");
+        assert!(answer(b"HTTP/1.1 403 Forbidden\r\n\r\n").is_err());
+        assert!(answer(b"garbage\r\n\r\n").is_err());
+        assert!(answer(&[b'x'; MAX_CONNECT_RESPONSE + 1]).is_err());
+    }
+
+    /// Reads from `input`, writes to `output`.
+    struct Duplex<'a> {
+        input: io::Cursor<Vec<u8>>,
+        output: &'a mut io::Cursor<Vec<u8>>,
+    }
+
+    impl Read for Duplex<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.input.read(buf)
+        }
+    }
+
+    impl Write for Duplex<'_> {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.output.write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,335 @@
+//! Connections through an upstream proxy of the user's own.
+//!
+//! With an [`UpstreamProxy`] configured, requests are carried by an
+//! [`UpstreamNetwork`] instead of the WARP tunnel: each TCP connection is
+//! opened to the proxy and handed over to its destination with a SOCKS5
+//! `CONNECT` (RFC 1928, with RFC 1929 user name and password) or an HTTP
+//! `CONNECT`. Everything above is unchanged. Names are still resolved over
+//! DoH, whose connections go through the proxy too, and TLS runs end to end
+//! with the server, so the proxy learns only the address and port of each
+//! destination.
+
+mod handshake;
+
+use crate::error::ProxyError;
+use crate::timeouts::Deadline;
+use crate::tunnel::direct::{self, ConnectionId, DirectNetwork, DirectStream};
+use crate::tunnel::dns::DnsCache;
+use crate::tunnel::h2::SessionPool;
+use crate::tunnel::network::Network;
+use crate::tunnel::tls::TlsSessionCache;
+use crate::types::{Transport, UpstreamProxy};
+use handshake::{http_connect, socks5_connect};
+use std::net::{SocketAddr, ToSocketAddrs};
+
+/// Check that `proxy` can be used before any connection is made.
+pub(crate) fn validate(proxy: &UpstreamProxy) -> Result<(), ProxyError> {
+    let invalid = |details: &str| ProxyError::TunnelError {
+        details: format!("Invalid upstream proxy: {details}"),
+    };
+    let (host, port, credentials) = match proxy {
+        UpstreamProxy::Socks5 {
+            host,
+            port,
+            credentials,
+        }
+        | UpstreamProxy::HttpConnect {
+            host,
+            port,
+            credentials,
+        } => (host, *port, credentials.as_ref()),
+    };
+    if host.trim().is_empty() || host.contains(char::is_whitespace) {
+        return Err(invalid("expected a host name or address"));
+    }
+    if port == 0 {
+        return Err(invalid("port 0"));
+    }
+    match (proxy, credentials) {
+        (UpstreamProxy::Socks5 { .. }, Some(c)) => {
+            let fits = |field: &str| (1..=255).contains(&field.len());
+            if !fits(&c.username) || !fits(&c.password) {
+                return Err(invalid(
+                    "SOCKS5 user names and passwords are 1 to 255 bytes",
+                ));
+            }
+        }
+        (UpstreamProxy::HttpConnect { .. }, Some(c)) => {
+            if c.username.contains(':') {
+                return Err(invalid("user name contains ':'"));
+            }
+            if [&c.username, &c.password]
+                .iter()
+                .any(|field| field.contains(['\r', '\n']))
+            {
+                return Err(invalid("credentials contain a line break"));
+            }
+        }
+        (_, None) => {}
+    }
+    Ok(())
+}
+
+/// TCP connections made through an [`UpstreamProxy`].
+pub struct UpstreamNetwork {
+    proxy: UpstreamProxy,
+    /// Where the proxy listens.
+    address: SocketAddr,
+    /// The connections, once handed over to their destinations.
+    inner: DirectNetwork,
+}
+
+impl UpstreamNetwork {
+    /// A network through `proxy`. Its host is resolved once, by the system
+    /// resolver: it is normally an address on the device or the local network.
+    pub fn new(proxy: UpstreamProxy) -> Result<Self, ProxyError> {
+        validate(&proxy)?;
+        let (UpstreamProxy::Socks5 { host, port, .. }
+        | UpstreamProxy::HttpConnect { host, port, .. }) = &proxy;
+        let address = (host.as_str(), *port)
+            .to_socket_addrs()
+            .ok()
+            .and_then(|mut addresses| addresses.next())
+            .ok_or_else(|| ProxyError::NetworkUnavailable {
+                details: format!("Could not resolve upstream proxy {host}"),
+            })?;
+        Ok(Self {
+            proxy,
+            address,
+            inner: DirectNetwork::default(),
+        })
+    }
+}
+
+impl Network for UpstreamNetwork {
+    const TRANSPORT: Transport = Transport::Upstream;
+
+    type Handle = ConnectionId;
+    type Stream<'a> = DirectStream<'a>;
+
+    fn open_tcp(
+        &mut self,
+        remote: SocketAddr,
+        deadline: &Deadline,
+    ) -> Result<ConnectionId, ProxyError> {
+        let handle = self.inner.adopt(direct::connect(self.address, deadline)?);
+        let handed_over = {
+            let mut stream = self.inner.stream(handle, deadline);
+            match &self.proxy {
+                UpstreamProxy::Socks5 { credentials, .. } => {
+                    socks5_connect(&mut stream, remote, credentials.as_ref())
+                }
+                UpstreamProxy::HttpConnect { credentials, .. } => {
+                    http_connect(&mut stream, remote, credentials.as_ref())
+                }
+            }
+        };
+        match handed_over {
+            Ok(()) => Ok(handle),
+            Err(e) => {
+                self.inner.close_tcp(handle);
+                Err(deadline.io_error(e, |e| ProxyError::NetworkUnavailable {
+                    details: format!("Upstream proxy could not connect to {remote}: {e}"),
+                }))
+            }
+        }
+    }
+
+    fn close_tcp(&mut self, handle: ConnectionId) {
+        self.inner.close_tcp(handle);
+    }
+
+    fn stream<'a>(&'a mut self, handle: ConnectionId, deadline: &'a Deadline) -> DirectStream<'a> {
+        self.inner.stream(handle, deadline)
+    }
+
+    fn sessions(&mut self) -> &mut SessionPool<ConnectionId> {
+        self.inner.sessions()
+    }
+
+    fn dns_cache(&mut self) -> &mut DnsCache {
+        self.inner.dns_cache()
+    }
+
+    fn tls_sessions(&mut self) -> &mut TlsSessionCache {
+        self.inner.tls_sessions()
+    }
+
+    fn traffic(&self) -> Option<(u64, u64)> {
+        self.inner.traffic()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::FetchLimits;
+    use crate::http;
+    use crate::types::{ProxyCredentials, RetryPolicy};
+    use std::io::{Read, Write};
+    use std::net::{TcpListener, TcpStream};
+
+    /// A proxy on a loopback port that runs `serve` on its first connection.
+    fn proxy(serve: impl FnOnce(TcpStream) + Send + 'static) -> (u16, std::thread::JoinHandle<()>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = std::thread::spawn(move || serve(listener.accept().unwrap().0));
+        (port, server)
+    }
+
+    /// Read up to the end of a request head.
+    fn read_head(conn: &mut TcpStream) -> String {
+        let mut head = Vec::new();
+        while !head.ends_with(b"\r\n\r\n") {
+            head.extend(read_n(conn, 1));
+        }
+        String::from_utf8(head).unwrap()
+    }
+
+    fn read_n(conn: &mut TcpStream, n: usize) -> Vec<u8> {
+        let mut buf = vec![0; n];
+        conn.read_exact(&mut buf).unwrap();
+        buf
+    }
+
+    const RESPONSE: &[u8] = b"HTTP/1.1 200 OK\r\nContent-Type: image/png\r\n\
+        Content-Length: 3\r\nConnection: close\r\n\r\nabc";
+
+    fn no_retries() -> FetchLimits {
+        FetchLimits {
+            retry: RetryPolicy {
+                max_attempts: 1,
+                ..Default::default()
+            },
+            ..FetchLimits::default()
+        }
+    }
+
+    #[test]
+    fn fetches_through_a_socks5_proxy_with_a_password() {
+        let (port, server) = proxy(|mut conn| {
+            assert_eq!(read_n(&mut conn, 3), [5, 1, 2]);
+            conn.write_all(&[5, 2]).unwrap();
+            assert_eq!(read_n(&mut conn, 7), b"\x01\x02me\x02pw");
+            conn.write_all(&[1, 0]).unwrap();
+            assert_eq!(read_n(&mut conn, 10), [5, 1, 0, 1, 192, 0, 2, 7, 0, 80]);
+            conn.write_all(&[5, 0, 0, 3, 4, b'h', b'o', b's', b't', 0, 1])
+                .unwrap();
+            assert!(read_head(&mut conn).starts_with("GET /a.png HTTP/1.1\r\n"));
+            conn.write_all(RESPONSE).unwrap();
+        });
+        let mut network = UpstreamNetwork::new(UpstreamProxy::Socks5 {
+            host: "localhost".to_string(),
+            port,
+            credentials: Some(ProxyCredentials {
+                username: "me".to_string(),
+                password: "pw".to_string(),
+            }),
+        })
+        .unwrap();
+
+        let outcome = http::fetch(
+            &mut network,
+            "http://192.0.2.7/a.png",
+            &[],
+            &no_retries(),
+            "image/*",
+            None,
+        )
+        .unwrap();
+        assert_eq!(&outcome.body[..], b"abc");
+        assert_eq!(outcome.transport, Transport::Upstream);
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn socks5_refusals_name_the_reason() {
+        let (port, server) = proxy(|mut conn| {
+            read_n(&mut conn, 3);
+            conn.write_all(&[5, 0]).unwrap();
+            read_n(&mut conn, 10);
+            conn.write_all(&[5, 5, 0, 1, 0, 0, 0, 0, 0, 0]).unwrap();
+        });
+        let mut network = UpstreamNetwork::new(UpstreamProxy::Socks5 {
+            host: "127.0.0.1".to_string(),
+            port,
+            credentials: None,
+        })
+        .unwrap();
+        let error = http::fetch(
+            &mut network,
+            "http://192.0.2.7/a.png",
+            &[],
+            &no_retries(),
+            "image/*",
+            None,
+        )
+        .unwrap_err()
+        .error;
+        assert!(error.to_string().contains("connection refused"), "{error}");
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn fetches_through_an_http_connect_proxy() {
+        let (port, server) = proxy(|mut conn| {
+            let head = read_head(&mut conn);
+            assert!(head.starts_with("CONNECT 192.0.2.7:8080 HTTP/1.1\r\n"));
+            assert!(head.contains("Proxy-Authorization: Basic bWU6cHc=\r\n"));
+            conn.write_all(b"HTTP/1.1 200 Connection established\r\n\r\n")
+                .unwrap();
+            read_head(&mut conn);
+            conn.write_all(RESPONSE).unwrap();
+        });
+        let mut network = UpstreamNetwork::new(UpstreamProxy::HttpConnect {
+            host: "127.0.0.1".to_string(),
+            port,
+            credentials: Some(ProxyCredentials {
+                username: "me".to_string(),
+                password: "pw".to_string(),
+            }),
+        })
+        .unwrap();
+
+        let outcome = http::fetch(
+            &mut network,
+            "http://192.0.2.7:8080/a.png",
+            &[],
+            &no_retries(),
+            "image/*",
+            None,
+        )
+        .unwrap();
+        assert_eq!(&outcome.body[..], b"abc");
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn validates_before_connecting() {
+        let socks = |host: &str, port, username: &str| UpstreamProxy::Socks5 {
+            host: host.to_string(),
+            port,
+            credentials: Some(ProxyCredentials {
+                username: username.to_string(),
+                password: "pw".to_string(),
+            }),
+        };
+        assert!(validate(&socks("127.0.0.1", 9050, "me")).is_ok());
+        assert!(validate(&socks("", 9050, "me")).is_err());
+        assert!(validate(&socks("127.0.0.1", 0, "me")).is_err());
+        assert!(validate(&socks("127.0.0.1", 9050, "")).is_err());
+        assert!(validate(&socks("127.0.0.1", 9050, &"u".repeat(256))).is_err());
+
+        let http = |username: &str| UpstreamProxy::HttpConnect {
+            host: "proxy.lan".to_string(),
+            port: 3128,
+            credentials: Some(ProxyCredentials {
+                username: username.to_string(),
+                password: "pw".to_string(),
+            }),
+        };
+        assert!(validate(&http("me")).is_ok());
+        assert!(validate(&http("me:you")).is_err());
+        assert!(validate(&http("me\r\nX-Injected: 1")).is_err());
+    }
+}
@@ -0,0 +1,177 @@
+//! Queued fetches served together.
+//!
+//! When the worker takes a [`Command::Fetch`] of an `https` URL, it also takes
+//! up to [`MAX_BATCH`] fetches queued for the same [`origin`](http::origin),
+//! whatever their priority, and serves them all with [`http::fetch_all`]: their
+//! requests then go out as concurrent streams on one HTTP/2 session instead of
+//! each waiting for the previous response. The batch runs under the trace id
+//! of the fetch that was taken first, which its traffic is also counted for.
+
+use super::{ready, retry_later, Command};
+use crate::http::{self, BatchFetch, Failure};
+use crate::throttle::Throttle;
+use crate::tunnel::network::Network;
+use crate::tunnel::scheduler::Scheduler;
+use crate::types::Transport;
+
+/// The most fetches served together, the one taken first included.
+pub(super) const MAX_BATCH: usize = 8;
+
+/// Take the fetches queued for the origin of `command` that can be served
+/// along with it. None unless `command` is a still wanted `https` fetch.
+pub(super) fn companions(queue: &Scheduler<Command>, command: &Command) -> Vec<Command> {
+    let Command::Fetch { url, reply, .. } = command else {
+        return Vec::new();
+    };
+    let Some(origin) = http::origin(url).filter(|_| !reply.is_closed()) else {
+        return Vec::new();
+    };
+    queue.take_matching(MAX_BATCH - 1, |queued| {
+        matches!(queued, Command::Fetch { url, .. } if http::origin(url).as_ref() == Some(&origin))
+    })
+}
+
+/// Serve `commands`, fetches to one origin, together. Each replies on its
+/// own, or goes back on `queue` to be retried like a fetch served alone.
+pub(super) fn serve<N: Network>(
+    tunnel: &mut N,
+    throttle: &mut Throttle,
+    queue: &Scheduler<Command>,
+    commands: Vec<Command>,
+) {
+    let mut wanted = Vec::with_capacity(commands.len());
+    for (index, command) in commands.into_iter().enumerate() {
+        let Command::Fetch { limits, reply, .. } = &command else {
+            continue;
+        };
+        if reply.is_closed() {
+            // The caller stopped waiting while this was queued.
+            continue;
+        }
+        if let Some(metrics) = limits
+            .metrics
+            .as_ref()
+            .filter(|_| index > 0 && N::TRANSPORT == Transport::Direct)
+        {
+            // The worker loop counts the first one.
+            metrics.record_direct_request();
+        }
+        match ready(tunnel, throttle, limits) {
+            Ok(()) => wanted.push(command),
+            Err(e) => {
+                if let Command::Fetch { reply, .. } = command {
+                    let _ = reply.send(Err(e));
+                }
+            }
+        }
+    }
+
+    let fetches: Vec<BatchFetch<'_>> = wanted
+        .iter()
+        .filter_map(|command| match command {
+            Command::Fetch {
+                url,
+                headers,
+                accept,
+                limits,
+                progress,
+                ..
+            } => Some(BatchFetch {
+                url,
+                headers,
+                limits,
+                accept,
+                progress: progress.as_ref(),
+            }),
+            _ => None,
+        })
+        .collect();
+    let results = http::fetch_all(tunnel, &fetches);
+    drop(fetches);
+
+    for (command, result) in wanted.into_iter().zip(results) {
+        match result {
+            Err(Failure {
+                retry_in: Some(delay),
+                ..
+            }) => retry_later(queue, command, delay),
+            result => {
+                if let Command::Fetch { reply, .. } = command {
+                    let _ = reply.send(result.map_err(|failure| failure.error));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::FetchLimits;
+    use crate::types::RequestPriority;
+    use tokio::sync::oneshot;
+
+    fn fetch(url: &str) -> Command {
+        // The receiver is leaked so the reply stays open.
+        let (reply, receiver) = oneshot::channel();
+        std::mem::forget(receiver);
+        Command::Fetch {
+            url: url.to_string(),
+            headers: Vec::new(),
+            accept: "image/*".to_string(),
+            limits: FetchLimits::default(),
+            progress: None,
+            reply,
+        }
+    }
+
+    fn url_of(command: &Command) -> &str {
+        command.url().unwrap_or_default()
+    }
+
+    #[test]
+    fn takes_queued_fetches_from_the_same_https_origin() {
+        let queue = Scheduler::new();
+        for url in [
+            "https://img.example/a.png",
+            "http://img.example/b.png",
+            "https://img.example:8443/c.png",
+            "https://other.example/d.png",
+            "https://img.example:443/e.png",
+        ] {
+            queue
+                .push(RequestPriority::Prefetch, fetch(url), None)
+                .unwrap();
+        }
+        let lead = fetch("https://img.example/lead.png");
+        let taken = companions(&queue, &lead);
+        let urls: Vec<&str> = taken.iter().map(url_of).collect();
+        assert_eq!(
+            urls,
+            ["https://img.example/a.png", "https://img.example:443/e.png"]
+        );
+
+        let plain = fetch("http://img.example/lead.png");
+        queue
+            .push(
+                RequestPriority::Prefetch,
+                fetch("http://img.example/f.png"),
+                None,
+            )
+            .unwrap();
+        assert!(companions(&queue, &plain).is_empty());
+    }
+
+    #[test]
+    fn takes_at_most_a_batch() {
+        let queue = Scheduler::new();
+        for index in 0..MAX_BATCH + 2 {
+            let url = format!("https://img.example/{index}.png");
+            queue
+                .push(RequestPriority::Visible, fetch(&url), None)
+                .unwrap();
+        }
+        let lead = fetch("https://img.example/lead.png");
+        assert_eq!(companions(&queue, &lead).len(), MAX_BATCH - 1);
+    }
+}
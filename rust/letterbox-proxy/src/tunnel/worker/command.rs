@@ -0,0 +1,106 @@
+//! The commands the tunnel worker serves.
+
+use super::streams::StreamId;
+use crate::config::{FetchLimits, WarpConfig};
+use crate::error::ProxyError;
+use crate::http::{FetchOutcome, ProgressFn, StreamOutcome};
+use crate::tunnel::manager::TunnelDiagnostics;
+use crate::types::RequestPriority;
+use std::path::PathBuf;
+use std::sync::mpsc::Sender;
+use tokio::sync::oneshot;
+
+/// A unit of work for the tunnel worker thread.
+pub(crate) enum Command {
+    Fetch {
+        url: String,
+        headers: Vec<(String, String)>,
+        accept: String,
+        limits: FetchLimits,
+        progress: Option<ProgressFn>,
+        reply: oneshot::Sender<Result<FetchOutcome, ProxyError>>,
+    },
+    FetchToFile {
+        url: String,
+        headers: Vec<(String, String)>,
+        accept: String,
+        limits: FetchLimits,
+        dest: PathBuf,
+        progress: Option<ProgressFn>,
+        reply: Sender<Result<StreamOutcome, ProxyError>>,
+    },
+    Probe {
+        url: String,
+        headers: Vec<(String, String)>,
+        accept: String,
+        limits: FetchLimits,
+        reply: oneshot::Sender<Result<StreamOutcome, ProxyError>>,
+    },
+    Resolve {
+        hosts: Vec<String>,
+        limits: FetchLimits,
+        reply: Sender<Result<u32, ProxyError>>,
+    },
+    Diagnostics {
+        reply: Sender<Result<TunnelDiagnostics, ProxyError>>,
+    },
+    UpdatePeer {
+        config: Box<WarpConfig>,
+        reply: Sender<Result<(), ProxyError>>,
+    },
+    OpenStream {
+        host: String,
+        port: u16,
+        limits: FetchLimits,
+        reply: Sender<Result<StreamId, ProxyError>>,
+    },
+    /// Read what arrives on a stream within [`STREAM_SLICE`](super::streams::STREAM_SLICE); `None` if
+    /// nothing did.
+    ReadStream {
+        id: StreamId,
+        max_len: usize,
+        reply: Sender<Result<Option<Vec<u8>>, ProxyError>>,
+    },
+    /// Write what fits on a stream within [`STREAM_SLICE`](super::streams::STREAM_SLICE), replying how
+    /// much did.
+    WriteStream {
+        id: StreamId,
+        data: Vec<u8>,
+        reply: Sender<Result<usize, ProxyError>>,
+    },
+    CloseStream {
+        id: StreamId,
+    },
+}
+
+impl Command {
+    pub(crate) fn limits(&self) -> Option<&FetchLimits> {
+        match self {
+            Command::Fetch { limits, .. }
+            | Command::FetchToFile { limits, .. }
+            | Command::Probe { limits, .. }
+            | Command::Resolve { limits, .. }
+            | Command::OpenStream { limits, .. } => Some(limits),
+            Command::Diagnostics { .. }
+            | Command::UpdatePeer { .. }
+            | Command::ReadStream { .. }
+            | Command::WriteStream { .. }
+            | Command::CloseStream { .. } => None,
+        }
+    }
+
+    /// The URL requested, if any.
+    pub(super) fn url(&self) -> Option<&str> {
+        match self {
+            Command::Fetch { url, .. }
+            | Command::FetchToFile { url, .. }
+            | Command::Probe { url, .. } => Some(url),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn priority(&self) -> RequestPriority {
+        self.limits()
+            .map_or(RequestPriority::Visible, |limits| limits.priority)
+    }
+}
@@ -0,0 +1,410 @@
+//! The tunnel worker thread.
+//!
+//! [`worker_loop`] owns the [`Network`] — normally the WARP tunnel — and
+//! serves the [`Command`]s that [`TunnelManager`](super::TunnelManager) queues
+//! for it, one at a time, each under the trace id of its request. Fetches
+//! queued for the same `https` origin are the exception: they are served
+//! together ([`batch`]), as concurrent streams on one HTTP/2 session. It also
+//! counts the tunnel traffic and re-handshakes of every request in the
+//! client's metrics, and the bytes it received in the requested host's
+//! bandwidth usage. Requests start no faster than their
+//! [`RateLimit`](crate::types::RateLimit) allows.
+//!
+//! The worker also keeps the connections of the app's raw streams
+//! ([`crate::stream`]). A stream read or write holds it for at most
+//! [`STREAM_SLICE`](streams::STREAM_SLICE); a caller waiting longer queues the next slice, so a
+//! stream left waiting for data never starves image fetches.
+//!
+//! A fetch that fails transiently is not retried on the spot: it goes back on
+//! the [`Scheduler`] to be taken again once its backoff has passed, and the
+//! worker serves other commands meanwhile.
+
+mod batch;
+mod command;
+mod streams;
+
+use crate::bandwidth;
+use crate::config::FetchLimits;
+use crate::error::ProxyError;
+use crate::http::{self, Failure, ProgressFn, StreamOutcome};
+use crate::throttle::Throttle;
+use crate::trace;
+use crate::tunnel::dns;
+use crate::tunnel::network::Network;
+use crate::tunnel::scheduler::Scheduler;
+use crate::types::Transport;
+pub(super) use command::Command;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::sync::mpsc::Sender;
+use std::time::{Duration, Instant};
+pub(super) use streams::StreamId;
+use streams::{open_stream, read_stream, write_stream, Streams};
+
+/// Queue a fetch `command` that failed transiently again, as its next
+/// attempt, to be taken once `delay` has passed. If the worker is going away
+/// it is dropped instead, like everything else still queued.
+fn retry_later(queue: &Scheduler<Command>, mut command: Command, delay: Duration) {
+    if let Command::Fetch { limits, .. }
+    | Command::FetchToFile { limits, .. }
+    | Command::Probe { limits, .. } = &mut command
+    {
+        limits.attempt += 1;
+    }
+    let _ = queue.push_at(command.priority(), command, Instant::now() + delay);
+}
+
+/// The worker thread body: bring up the network with `open`, then service
+/// commands until the command queue closes.
+pub(super) fn worker_loop<N: Network>(
+    open: impl FnOnce() -> Result<N, ProxyError>,
+    queue: &Scheduler<Command>,
+    ready_tx: Sender<Result<(), ProxyError>>,
+) {
+    // However the worker exits, fail whatever is still queued rather than
+    // leaving callers waiting for replies that will never come.
+    struct Abandon<'a>(&'a Scheduler<Command>);
+    impl Drop for Abandon<'_> {
+        fn drop(&mut self) {
+            self.0.abandon();
+        }
+    }
+    let _abandon = Abandon(queue);
+
+    let mut tunnel = match open() {
+        Ok(tunnel) => {
+            let _ = ready_tx.send(Ok(()));
+            tunnel
+        }
+        Err(e) => {
+            let _ = ready_tx.send(Err(e));
+            return;
+        }
+    };
+
+    let mut throttle = Throttle::default();
+    let mut streams = Streams {
+        next: 0,
+        open: HashMap::new(),
+    };
+    while let Some(command) = queue.pop() {
+        let limits = command.limits();
+        let trace_id = limits.and_then(|limits| limits.trace_id.clone());
+        let metrics = limits.and_then(|limits| limits.metrics.clone());
+        let bandwidth = limits.and_then(|limits| limits.bandwidth.clone());
+        let host = command.url().and_then(bandwidth::host_of);
+        let before = tunnel.traffic();
+        let companions = batch::companions(queue, &command);
+        trace::scope(trace_id.as_deref(), || match command {
+            command @ Command::Fetch { .. } if !companions.is_empty() => {
+                let commands = std::iter::once(command).chain(companions).collect();
+                batch::serve(&mut tunnel, &mut throttle, queue, commands);
+            }
+            Command::Fetch {
+                url,
+                headers,
+                accept,
+                limits,
+                progress,
+                reply,
+            } => {
+                if reply.is_closed() {
+                    // The caller stopped waiting (e.g. its coroutine was
+                    // cancelled) while this was queued.
+                    return;
+                }
+                let result = ready(&mut tunnel, &mut throttle, &limits)
+                    .map_err(Failure::from)
+                    .and_then(|()| {
+                        http::fetch(
+                            &mut tunnel,
+                            &url,
+                            &headers,
+                            &limits,
+                            &accept,
+                            progress.as_ref(),
+                        )
+                    });
+                match result {
+                    Err(Failure {
+                        retry_in: Some(delay),
+                        ..
+                    }) => {
+                        let command = Command::Fetch {
+                            url,
+                            headers,
+                            accept,
+                            limits,
+                            progress,
+                            reply,
+                        };
+                        retry_later(queue, command, delay);
+                    }
+                    result => {
+                        let _ = reply.send(result.map_err(|failure| failure.error));
+                    }
+                }
+            }
+            Command::FetchToFile {
+                url,
+                headers,
+                accept,
+                limits,
+                dest,
+                progress,
+                reply,
+            } => {
+                let result = ready(&mut tunnel, &mut throttle, &limits)
+                    .map_err(Failure::from)
+                    .and_then(|()| {
+                        fetch_to_file(
+                            &mut tunnel,
+                            &url,
+                            &headers,
+                            &limits,
+                            &accept,
+                            &dest,
+                            progress.as_ref(),
+                        )
+                    });
+                match result {
+                    Err(Failure {
+                        retry_in: Some(delay),
+                        ..
+                    }) => {
+                        let command = Command::FetchToFile {
+                            url,
+                            headers,
+                            accept,
+                            limits,
+                            dest,
+                            progress,
+                            reply,
+                        };
+                        retry_later(queue, command, delay);
+                    }
+                    result => {
+                        let _ = reply.send(result.map_err(|failure| failure.error));
+                    }
+                }
+            }
+            Command::Probe {
+                url,
+                headers,
+                accept,
+                limits,
+                reply,
+            } => {
+                let result = ready(&mut tunnel, &mut throttle, &limits)
+                    .map_err(Failure::from)
+                    .and_then(|()| http::probe(&mut tunnel, &url, &headers, &limits, &accept));
+                match result {
+                    Err(Failure {
+                        retry_in: Some(delay),
+                        ..
+                    }) => {
+                        let command = Command::Probe {
+                            url,
+                            headers,
+                            accept,
+                            limits,
+                            reply,
+                        };
+                        retry_later(queue, command, delay);
+                    }
+                    result => {
+                        let _ = reply.send(result.map_err(|failure| failure.error));
+                    }
+                }
+            }
+            Command::Resolve {
+                hosts,
+                limits,
+                reply,
+            } => {
+                let result = ready(&mut tunnel, &mut throttle, &limits)
+                    .map(|()| resolve_all(&mut tunnel, &hosts, &limits));
+                let _ = reply.send(result);
+            }
+            Command::Diagnostics { reply } => {
+                let _ = reply.send(tunnel.diagnostics());
+            }
+            Command::UpdatePeer { config, reply } => {
+                let _ = reply.send(tunnel.update_peer(&config));
+            }
+            Command::OpenStream {
+                host,
+                port,
+                limits,
+                reply,
+            } => {
+                let result = ready(&mut tunnel, &mut throttle, &limits)
+                    .and_then(|()| open_stream(&mut tunnel, &host, port, &limits))
+                    .map(|handle| streams.insert(handle));
+                let _ = reply.send(result);
+            }
+            Command::ReadStream { id, max_len, reply } => {
+                let result = streams
+                    .get(id)
+                    .and_then(|handle| read_stream(&mut tunnel, handle, max_len));
+                let _ = reply.send(result);
+            }
+            Command::WriteStream { id, data, reply } => {
+                let result = streams
+                    .get(id)
+                    .and_then(|handle| write_stream(&mut tunnel, handle, &data));
+                let _ = reply.send(result);
+            }
+            Command::CloseStream { id } => {
+                if let Some(handle) = streams.open.remove(&id) {
+                    tunnel.close_tcp(handle);
+                }
+            }
+        });
+        let tunnelled = N::TRANSPORT == Transport::Tunnel;
+        if let Some(metrics) = metrics
+            .as_ref()
+            .filter(|_| N::TRANSPORT == Transport::Direct)
+        {
+            metrics.record_direct_request();
+        }
+        let Some(((sent, received), (sent_after, received_after))) = before.zip(tunnel.traffic())
+        else {
+            continue;
+        };
+        let received = received_after.saturating_sub(received);
+        if let (Some(bandwidth), Some(host)) = (bandwidth, host) {
+            bandwidth.record(host, tunnelled, received);
+        }
+        // The metrics count tunnel traffic only.
+        if let Some(metrics) = metrics.filter(|_| tunnelled) {
+            metrics.record_transfer(sent_after.saturating_sub(sent), received);
+        }
+    }
+}
+
+/// Stream a fetch into a new file at `dest`, removing it again on failure.
+fn fetch_to_file<N: Network>(
+    tunnel: &mut N,
+    url: &str,
+    headers: &[(String, String)],
+    limits: &FetchLimits,
+    accept: &str,
+    dest: &Path,
+    progress: Option<&ProgressFn>,
+) -> Result<StreamOutcome, Failure> {
+    let mut file = BufWriter::new(File::create(dest).map_err(ProxyError::from)?);
+    let result = http::fetch_into(tunnel, url, headers, limits, accept, &mut file, progress)
+        .and_then(|outcome| {
+            file.flush()
+                .map(|()| outcome)
+                .map_err(|e| ProxyError::from(e).into())
+        });
+    if result.is_err() {
+        drop(file);
+        let _ = std::fs::remove_file(dest);
+    }
+    result
+}
+
+/// Resolve each of `hosts` into the network's DNS cache, returning how many
+/// resolved. Failures are only logged.
+fn resolve_all<N: Network>(tunnel: &mut N, hosts: &[String], limits: &FetchLimits) -> u32 {
+    let mut resolved = 0;
+    for host in hosts {
+        match dns::resolve(tunnel, host, limits.timeouts.dns()) {
+            Ok(_) => resolved += 1,
+            Err(e) => log::debug!("Could not resolve {host}: {e}"),
+        }
+    }
+    resolved
+}
+
+/// Wait for the request's turn under the rate limit, check that it is still
+/// wanted, then ensure a live WireGuard session, re-handshaking if it has
+/// lapsed. The handshake is counted in the metrics whether it completes or
+/// fails.
+fn ready<N: Network>(
+    tunnel: &mut N,
+    throttle: &mut Throttle,
+    limits: &FetchLimits,
+) -> Result<(), ProxyError> {
+    throttle.admit(limits.rate_limit.requests_per_minute);
+    limits.check_cancelled()?;
+    let reconnected = tunnel.reconnect();
+    if let Some(metrics) = &limits.metrics {
+        match reconnected {
+            Ok(true) => metrics.record_handshake(),
+            Ok(false) => {}
+            Err(_) => metrics.record_handshake_failure(),
+        }
+    }
+    reconnected.map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::FetchLimits;
+    use crate::tunnel::TunnelManager;
+    use crate::types::RetryPolicy;
+    use std::io::{BufRead, BufReader, Write};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[test]
+    fn other_requests_run_while_a_retry_waits() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = std::thread::spawn(move || {
+            let mut paths = Vec::new();
+            for mut conn in listener.incoming().flatten().take(3) {
+                let mut request_line = String::new();
+                BufReader::new(&mut conn)
+                    .read_line(&mut request_line)
+                    .unwrap();
+                let path = request_line.split(' ').nth(1).unwrap().to_string();
+                let status = if paths.is_empty() {
+                    "503 Service Unavailable"
+                } else {
+                    "200 OK"
+                };
+                let response = format!(
+                    "HTTP/1.1 {status}\r\nContent-Type: image/png\r\nContent-Length: 0\r\n\
+                     Connection: close\r\n\r\n"
+                );
+                conn.write_all(response.as_bytes()).unwrap();
+                paths.push(path);
+            }
+            paths
+        });
+
+        let manager = Arc::new(TunnelManager::direct().unwrap());
+        let limits = FetchLimits {
+            retry: RetryPolicy {
+                initial_backoff_ms: 500,
+                ..RetryPolicy::default()
+            },
+            ..FetchLimits::default()
+        };
+        let flaky = {
+            let manager = Arc::clone(&manager);
+            let limits = limits.clone();
+            let url = format!("http://127.0.0.1:{port}/flaky.png");
+            std::thread::spawn(move || {
+                manager.fetch(url, Vec::new(), "image/*".into(), limits, None)
+            })
+        };
+        std::thread::sleep(Duration::from_millis(100));
+        let url = format!("http://127.0.0.1:{port}/other.png");
+        manager
+            .fetch(url, Vec::new(), "image/*".into(), limits, None)
+            .unwrap();
+        assert_eq!(flaky.join().unwrap().unwrap().status, 200);
+        assert_eq!(
+            server.join().unwrap(),
+            ["/flaky.png", "/other.png", "/flaky.png"]
+        );
+    }
+}
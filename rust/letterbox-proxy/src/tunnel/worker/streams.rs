@@ -0,0 +1,99 @@
+//! The raw TCP streams the worker keeps for [`crate::stream`].
+
+use crate::config::FetchLimits;
+use crate::error::{ProxyError, TimeoutPhase};
+use crate::timeouts::Deadline;
+use crate::tunnel::dns;
+use crate::tunnel::network::Network;
+use std::collections::HashMap;
+use std::io::{self, Read, Write};
+use std::net::SocketAddr;
+use std::time::Duration;
+
+/// Identifies a stream opened with [`Command::OpenStream`](super::Command::OpenStream).
+pub(crate) type StreamId = u64;
+
+/// Longest a stream read or write holds the worker, so fetches interleave
+/// with a stream waiting for data.
+pub(super) const STREAM_SLICE: Duration = Duration::from_millis(50);
+
+/// The connections of the streams opened on a network.
+pub(super) struct Streams<H> {
+    pub(super) next: StreamId,
+    pub(super) open: HashMap<StreamId, H>,
+}
+
+impl<H: Copy> Streams<H> {
+    pub(super) fn insert(&mut self, handle: H) -> StreamId {
+        self.next += 1;
+        self.open.insert(self.next, handle);
+        self.next
+    }
+
+    pub(super) fn get(&self, id: StreamId) -> Result<H, ProxyError> {
+        self.open
+            .get(&id)
+            .copied()
+            .ok_or_else(|| ProxyError::NetworkUnavailable {
+                details: "Stream is closed".to_string(),
+            })
+    }
+}
+
+/// Resolve `host` over DoH and open a TCP connection to it.
+pub(super) fn open_stream<N: Network>(
+    tunnel: &mut N,
+    host: &str,
+    port: u16,
+    limits: &FetchLimits,
+) -> Result<N::Handle, ProxyError> {
+    let ip = dns::resolve(tunnel, host, limits.timeouts.dns())?;
+    let deadline = Deadline::phased(&limits.timeouts);
+    tunnel.open_tcp(SocketAddr::from((ip, port)), &deadline)
+}
+
+/// Up to `max_len` bytes arriving on a stream within [`STREAM_SLICE`]:
+/// `None` if none did, empty at the end of the stream.
+pub(super) fn read_stream<N: Network>(
+    tunnel: &mut N,
+    handle: N::Handle,
+    max_len: usize,
+) -> Result<Option<Vec<u8>>, ProxyError> {
+    let deadline = Deadline::fixed(TimeoutPhase::Body, STREAM_SLICE);
+    let mut buf = vec![0; max_len];
+    let read = tunnel.stream(handle, &deadline).read(&mut buf);
+    match read {
+        Ok(read) => {
+            buf.truncate(read);
+            Ok(Some(buf))
+        }
+        Err(e) if e.kind() == io::ErrorKind::TimedOut => Ok(None),
+        Err(e) => Err(stream_failed(e)),
+    }
+}
+
+/// Write what fits of `data` on a stream within [`STREAM_SLICE`] and push it
+/// out, returning how many bytes were taken.
+pub(super) fn write_stream<N: Network>(
+    tunnel: &mut N,
+    handle: N::Handle,
+    data: &[u8],
+) -> Result<usize, ProxyError> {
+    let deadline = Deadline::fixed(TimeoutPhase::Body, STREAM_SLICE);
+    let mut stream = tunnel.stream(handle, &deadline);
+    let written = match stream.write(data) {
+        Ok(written) => written,
+        Err(e) if e.kind() == io::ErrorKind::TimedOut => 0,
+        Err(e) => return Err(stream_failed(e)),
+    };
+    match stream.flush() {
+        Err(e) if e.kind() != io::ErrorKind::TimedOut => Err(stream_failed(e)),
+        _ => Ok(written),
+    }
+}
+
+fn stream_failed(e: io::Error) -> ProxyError {
+    ProxyError::NetworkUnavailable {
+        details: format!("Stream failed: {e}"),
+    }
+}
@@ -0,0 +1,279 @@
+//! FFI record types exposed to Kotlin via UniFFI.
+//!
+//! These are plain data carriers; behaviour lives in [`crate`]. They are kept in
+//! a dedicated module so `lib.rs` stays focused on the proxy logic. Records that
+//! configure requests live in [`policy`], those reporting on the proxy in
+//! [`report`].
+
+mod policy;
+mod report;
+
+pub use policy::{
+    FetchConcurrency, FetchPriority, HttpsMode, ProxyCredentials, RateLimit, RedirectPolicy,
+    RefererGrant, RequestPriority, RetryPolicy, TimeoutBudget, UpstreamProxy, WarpRefresh,
+};
+pub use report::{
+    BatchImageResult, ProxyStatus, RecentError, UpdateResult, WarpDiagnostics, WarpStoredConfig,
+};
+use std::collections::HashMap;
+
+/// Result of a successful image fetch operation.
+#[derive(Clone, Debug, uniffi::Record)]
+pub struct ImageResponse {
+    /// MIME type of the image (e.g., "image/png", "image/svg+xml").
+    pub mime_type: String,
+    /// Raw image bytes.
+    pub data: Vec<u8>,
+    /// Whether this response was served from cache.
+    pub from_cache: bool,
+    /// Final URL after redirects (if any).
+    pub final_url: String,
+    /// Every URL that answered with a redirect, in order, starting with the
+    /// requested one. Empty when the image was served without redirects.
+    pub redirect_chain: Vec<String>,
+    /// The redirects of `redirect_chain` one by one, with the host and scheme
+    /// changes the redirect policy let through.
+    pub redirect_hops: Vec<RedirectHop>,
+    /// Pixel width read from the image header, if the format is recognised.
+    pub width: Option<u32>,
+    /// Pixel height read from the image header, if the format is recognised.
+    pub height: Option<u32>,
+    /// BlurHash of the image for an instant blurred placeholder, if it is a
+    /// still image the proxy can decode.
+    pub blurhash: Option<String>,
+    /// Whether the image looks like a tracking pixel: served from a known
+    /// tracker domain, empty, or at most 2x2 pixels.
+    pub tracking_suspected: bool,
+    /// Trace id of the request, also found in its log records; quote it in
+    /// bug reports.
+    pub trace_id: String,
+    /// Selected headers of the response the image came from.
+    pub headers: ResponseHeaders,
+    /// SHA-256 of `data`, as lowercase hex.
+    pub sha256: String,
+    /// How the image was fetched, or `None` if no request was made (cache
+    /// hits and `data:` URIs).
+    pub transport: Option<Transport>,
+}
+
+/// The network path a request took.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, uniffi::Enum)]
+pub enum Transport {
+    /// Through the WARP tunnel; the server saw a Cloudflare address.
+    Tunnel,
+    /// Straight to the server, which saw the device's own IP address.
+    Direct,
+    /// Through the user's own upstream proxy; the server saw its address.
+    Upstream,
+}
+
+/// A redirect that was followed, and what it changed.
+#[derive(Clone, Debug, PartialEq, Eq, uniffi::Record)]
+pub struct RedirectHop {
+    /// URL that answered with the redirect.
+    pub from: String,
+    /// URL the redirect led to.
+    pub to: String,
+    /// Whether the redirect moved to another host.
+    pub cross_host: bool,
+    /// Whether the redirect changed the scheme.
+    pub scheme_changed: bool,
+    /// Whether the redirect went from `https` to `http`.
+    pub downgrade: bool,
+}
+
+/// Response headers passed through to the app, e.g. to name saved files or
+/// make its own caching decisions. An allowlist: cookies and other
+/// state-bearing headers are never passed through, nor cached.
+#[derive(Clone, Debug, Default, PartialEq, Eq, uniffi::Record)]
+pub struct ResponseHeaders {
+    /// File name suggested by `Content-Disposition`, without any directory
+    /// part.
+    pub filename: Option<String>,
+    /// `Last-Modified`, as sent.
+    pub last_modified: Option<String>,
+    /// `Cache-Control`, as sent.
+    pub cache_control: Option<String>,
+    /// Full resource length announced by the server: `Content-Length`, or the
+    /// `Content-Range` total of a partial response. For compressed transfers
+    /// this is the compressed length.
+    pub content_length: Option<u64>,
+}
+
+/// Result of a generic tunnelled fetch (non-image content).
+#[derive(Clone, Debug, uniffi::Record)]
+pub struct HttpFetchResponse {
+    /// HTTP status code of the final response.
+    pub status: u16,
+    /// Normalised MIME type.
+    pub mime_type: String,
+    /// Raw response body.
+    pub data: Vec<u8>,
+    /// Final URL after redirects.
+    pub final_url: String,
+    /// Trace id of the request, also found in its log records.
+    pub trace_id: String,
+}
+
+/// Image metadata obtained without downloading the body.
+#[derive(Clone, Debug, uniffi::Record)]
+pub struct ImageProbe {
+    /// MIME type the server announces.
+    pub mime_type: String,
+    /// Full size in bytes, if the server announces it.
+    pub content_length: Option<u64>,
+    /// Final URL after redirects.
+    pub final_url: String,
+    /// Whether the answer came from the cache instead of the network.
+    pub from_cache: bool,
+}
+
+/// One candidate of an `<img srcset>`: a URL with its width or pixel density
+/// descriptor.
+#[derive(Clone, Debug, PartialEq, uniffi::Record)]
+pub struct SrcsetCandidate {
+    /// Image URL, absolute.
+    pub url: String,
+    /// Intrinsic width in pixels, from a `w` descriptor (`480w`).
+    pub width: Option<u32>,
+    /// Pixel density, from an `x` descriptor (`2x`). A candidate with
+    /// neither descriptor counts as `1x`.
+    pub density: Option<f32>,
+}
+
+/// Settings for the persistent disk cache tier.
+#[derive(Clone, Debug, uniffi::Record)]
+pub struct DiskCacheOptions {
+    /// Byte budget for all cached blobs; least-recently-used entries are evicted.
+    pub max_bytes: u64,
+    /// Optional 32-byte key to encrypt cached bodies at rest.
+    pub encryption_key: Option<Vec<u8>>,
+    /// Days after which a cached image is dropped, however often it is used
+    /// (0 = kept until evicted).
+    pub max_age_days: u32,
+}
+
+/// Options for [`crate::proxy_fetch_image_to_file`].
+#[derive(Clone, Debug, Default, uniffi::Record)]
+pub struct FileFetchOptions {
+    /// Extra request headers (subject to the sensitive-header filter).
+    pub headers: Option<HashMap<String, String>>,
+    /// Replace `dest_path` if it already exists.
+    pub overwrite: bool,
+}
+
+/// Metadata of an image written to disk by [`crate::proxy_fetch_image_to_file`].
+#[derive(Clone, Debug, uniffi::Record)]
+pub struct FileFetchResult {
+    /// MIME type of the image.
+    pub mime_type: String,
+    /// Bytes written to the destination file.
+    pub size: u64,
+    /// Whether the image came from the cache instead of the network.
+    pub from_cache: bool,
+    /// Final URL after redirects.
+    pub final_url: String,
+}
+
+/// Link preview built by [`crate::proxy_resolve_preview`].
+#[derive(Clone, Debug, uniffi::Record)]
+pub struct LinkPreview {
+    /// URL of the page after redirects.
+    pub url: String,
+    /// `og:title`, `twitter:title` or the `<title>` element, if any.
+    pub title: Option<String>,
+    /// Absolute URL of the preview image the page advertises, if any.
+    pub image_url: Option<String>,
+    /// The preview image, fetched through the normal image pipeline.
+    pub image: Option<ImageResponse>,
+    /// Why the preview image could not be fetched, if it could not.
+    pub image_error: Option<String>,
+}
+
+/// Kind of non-image resource fetched by [`crate::proxy_fetch_resource`];
+/// decides the accepted content types.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, uniffi::Enum)]
+pub enum ResourceKind {
+    /// WOFF, WOFF2, TrueType, OpenType or EOT web fonts.
+    Font,
+    /// CSS stylesheets (`text/css`, UTF-8).
+    Stylesheet,
+}
+
+/// Options for [`crate::proxy_fetch_resource`].
+#[derive(Clone, Debug, uniffi::Record)]
+pub struct ResourceOptions {
+    /// What the resource is expected to be.
+    pub kind: ResourceKind,
+    /// Extra request headers (subject to the sensitive-header filter).
+    pub headers: Option<HashMap<String, String>>,
+    /// Largest accepted body in bytes (`None` = 4 MB).
+    pub max_size: Option<u64>,
+}
+
+/// Result of a successful [`crate::proxy_fetch_resource`].
+#[derive(Clone, Debug, uniffi::Record)]
+pub struct ResourceResponse {
+    /// Normalised MIME type, e.g. "font/woff2" or "text/css".
+    pub mime_type: String,
+    /// Raw resource bytes.
+    pub data: Vec<u8>,
+    /// Whether this response was served from cache.
+    pub from_cache: bool,
+    /// Final URL after redirects (if any).
+    pub final_url: String,
+    /// Every URL that answered with a redirect, in order.
+    pub redirect_chain: Vec<String>,
+}
+
+/// Downscaling and transcoding applied to an image before it is returned
+/// over FFI.
+///
+/// Images are only ever shrunk, preserving their aspect ratio, to fit within
+/// both bounds; images already inside them (and not being transcoded) are
+/// returned untouched.
+#[derive(Clone, Debug, PartialEq, Eq, uniffi::Record)]
+pub struct ImageTransform {
+    /// Maximum output width in pixels (`None` = unbounded).
+    pub max_width: Option<u32>,
+    /// Maximum output height in pixels (`None` = unbounded).
+    pub max_height: Option<u32>,
+    /// JPEG quality (1-100) used when a JPEG is encoded.
+    pub quality: u8,
+    /// Re-encode decodable images into this format, e.g. for WebViews that
+    /// cannot render JPEG XL (`None` = keep JPEG as JPEG, others as PNG when
+    /// resized).
+    pub format: Option<OutputFormat>,
+    /// Handling of animated GIF, WebP and APNG images.
+    pub animation: AnimationPolicy,
+}
+
+/// What [`ImageTransform`] does with animated images.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, uniffi::Enum)]
+pub enum AnimationPolicy {
+    /// Return animations unchanged (they are never resized or transcoded).
+    #[default]
+    PassThrough,
+    /// Return only the first frame as a still image, which is then resized
+    /// and transcoded like any other (PNG unless a format is requested).
+    FirstFrame,
+    /// Return animations within the budget unchanged; refuse others with
+    /// `ProxyError::AnimationTooLarge`.
+    Budget {
+        /// Most frames allowed.
+        max_frames: u32,
+        /// Largest encoded size allowed, in bytes.
+        max_bytes: u64,
+    },
+}
+
+/// Target format for [`ImageTransform::format`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, uniffi::Enum)]
+pub enum OutputFormat {
+    /// Lossless, keeps transparency.
+    Png,
+    /// Lossy at [`ImageTransform::quality`]; transparency is flattened.
+    Jpeg,
+    /// Lossless WebP, keeps transparency.
+    Webp,
+}
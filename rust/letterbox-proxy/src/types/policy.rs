@@ -0,0 +1,235 @@
+//! Records configuring how requests are sent: retries, timeouts, rate and
+//! concurrency caps, redirects, HTTPS upgrades, priorities and the network
+//! path.
+
+/// Automatic retry behaviour for transient fetch failures.
+///
+/// Only ever applied to GET requests, which are idempotent.
+#[derive(Clone, Debug, PartialEq, Eq, uniffi::Record)]
+pub struct RetryPolicy {
+    /// Total attempts per request, including the first (1 disables retries).
+    pub max_attempts: u32,
+    /// Delay before the first retry; doubled for each further retry.
+    pub initial_backoff_ms: u64,
+    /// Upper bound on any single delay, including a server's `Retry-After`.
+    pub max_backoff_ms: u64,
+    /// HTTP status codes worth retrying.
+    pub retryable_status_codes: Vec<u16>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff_ms: 250,
+            max_backoff_ms: 4_000,
+            retryable_status_codes: vec![408, 429, 502, 503, 504],
+        }
+    }
+}
+
+/// Time allowed for each phase of a request, in seconds.
+///
+/// Each attempt gets the full budget again when a request is retried.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, uniffi::Record)]
+pub struct TimeoutBudget {
+    /// Resolving the host over DNS-over-HTTPS, its own connection included.
+    pub dns_seconds: u32,
+    /// Opening the TCP connection.
+    pub connect_seconds: u32,
+    /// The TLS handshake.
+    pub tls_seconds: u32,
+    /// From sending the request to receiving the response head.
+    pub first_byte_seconds: u32,
+    /// Receiving the whole response body.
+    pub body_seconds: u32,
+}
+
+impl Default for TimeoutBudget {
+    fn default() -> Self {
+        Self {
+            dns_seconds: 10,
+            connect_seconds: 10,
+            tls_seconds: 10,
+            first_byte_seconds: 20,
+            body_seconds: 60,
+        }
+    }
+}
+
+/// A ceiling on the requests the proxy sends, so a message with thousands of
+/// image URLs cannot turn the device into a request cannon.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, uniffi::Record)]
+pub struct RateLimit {
+    /// Most requests started in any 60 seconds; later ones wait their turn
+    /// (0 = unlimited).
+    pub requests_per_minute: u32,
+    /// Most requests waiting for the network; further ones fail with
+    /// `Throttled` (0 = unlimited).
+    pub max_queued: u32,
+}
+
+impl Default for RateLimit {
+    fn default() -> Self {
+        Self {
+            requests_per_minute: 300,
+            max_queued: 500,
+        }
+    }
+}
+
+/// Caps on the network fetches in flight at once, across every batch and
+/// caller of one proxy.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, uniffi::Record)]
+pub struct FetchConcurrency {
+    /// Most fetches in flight in total; further ones wait for a slot
+    /// (0 = unlimited).
+    pub max_in_flight: u32,
+    /// Most fetches in flight to any one host (0 = unlimited).
+    pub max_per_host: u32,
+}
+
+impl Default for FetchConcurrency {
+    fn default() -> Self {
+        Self {
+            max_in_flight: 16,
+            max_per_host: 6,
+        }
+    }
+}
+
+/// A proxy of the user's own, e.g. Tor, carrying requests instead of WARP.
+#[derive(Clone, Debug, PartialEq, Eq, uniffi::Enum)]
+pub enum UpstreamProxy {
+    /// A SOCKS5 proxy, such as Tor's at `127.0.0.1:9050`.
+    Socks5 {
+        /// Address or name of the proxy, resolved by the system.
+        host: String,
+        port: u16,
+        /// Sent with SOCKS5 user name and password authentication; each at
+        /// most 255 bytes.
+        credentials: Option<ProxyCredentials>,
+    },
+    /// An HTTP proxy tunnelling each connection with `CONNECT`.
+    HttpConnect {
+        /// Address or name of the proxy, resolved by the system.
+        host: String,
+        port: u16,
+        /// Sent as `Proxy-Authorization: Basic`; the user name may not
+        /// contain `:`.
+        credentials: Option<ProxyCredentials>,
+    },
+}
+
+/// A user name and password for an [`UpstreamProxy`].
+#[derive(Clone, Debug, PartialEq, Eq, uniffi::Record)]
+pub struct ProxyCredentials {
+    /// User name.
+    pub username: String,
+    /// Password.
+    pub password: String,
+}
+
+/// When the WARP configuration of the stored identity is fetched again, to
+/// follow Cloudflare rotating the peer key or endpoint.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, uniffi::Record)]
+pub struct WarpRefresh {
+    /// Refresh once the configuration is this old (0 = never by age).
+    pub interval_secs: u64,
+    /// Refresh after this many WireGuard handshakes in a row failed
+    /// (0 = never on failures).
+    pub after_handshake_failures: u32,
+}
+
+impl Default for WarpRefresh {
+    fn default() -> Self {
+        Self {
+            interval_secs: 7 * 24 * 60 * 60,
+            after_handshake_failures: 3,
+        }
+    }
+}
+
+/// A `Referer` and `Origin` one host receives with the image requests of a
+/// fetch group, for CDNs that refuse hotlinked images without them.
+#[derive(Clone, Debug, PartialEq, Eq, uniffi::Record)]
+pub struct RefererGrant {
+    /// The only host the headers go to: matched exactly and
+    /// case-insensitively, not its subdomains.
+    pub host: String,
+    /// `Referer` to send: an `http(s)` URL, sent without fragment or
+    /// credentials.
+    pub referer: Option<String>,
+    /// `Origin` to send, e.g. `https://mail.example`; reduced to scheme, host
+    /// and port.
+    pub origin: Option<String>,
+}
+
+/// Rules applied to every redirect hop.
+#[derive(Clone, Debug, PartialEq, Eq, uniffi::Record)]
+pub struct RedirectPolicy {
+    /// Refuse redirects from `https` to `http`.
+    pub forbid_downgrade: bool,
+    /// Refuse redirects to loopback, private or link-local addresses.
+    pub forbid_private_targets: bool,
+    /// Refuse redirects that leave the origin of the previous URL.
+    pub forbid_cross_origin: bool,
+    /// Refuse redirects to another host, whatever the scheme and port.
+    pub forbid_cross_host: bool,
+    /// Refuse redirects that change the scheme, upgrades to `https` included.
+    pub forbid_scheme_change: bool,
+}
+
+impl Default for RedirectPolicy {
+    fn default() -> Self {
+        Self {
+            forbid_downgrade: true,
+            forbid_private_targets: true,
+            forbid_cross_origin: false,
+            forbid_cross_host: false,
+            forbid_scheme_change: false,
+        }
+    }
+}
+
+/// How plain `http://` URLs (including redirect targets) are handled.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, uniffi::Enum)]
+pub enum HttpsMode {
+    /// Fetch `http://` URLs as given.
+    #[default]
+    AllowHttp,
+    /// Rewrite `http://` to `https://`; fail if HTTPS does not work.
+    Upgrade,
+    /// Rewrite `http://` to `https://`, retrying over plain HTTP if the secure
+    /// connection cannot be established.
+    UpgradeWithFallback,
+    /// Refuse `http://` URLs with `ProxyError::InsecureUrl`.
+    HttpsOnly,
+}
+
+/// Scheduling priority for background fetches.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, uniffi::Enum)]
+pub enum FetchPriority {
+    /// Speculative work, e.g. messages further down the list.
+    Low,
+    /// Default priority.
+    Normal,
+    /// Likely to be shown next, e.g. the adjacent message.
+    High,
+}
+
+/// How urgently the tunnel should serve a request.
+///
+/// The tunnel handles one request at a time and always takes the oldest
+/// waiting request of the most urgent level next, so on-screen images are not
+/// stuck behind prefetches.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, uniffi::Enum)]
+pub enum RequestPriority {
+    /// Needed on screen now.
+    #[default]
+    Visible,
+    /// Cache warming for content the user is likely to open next.
+    Prefetch,
+    /// Work nobody is waiting on, e.g. update checks.
+    Background,
+}
@@ -1,32 +1,22 @@
-//! FFI record types exposed to Kotlin via UniFFI.
-//!
-//! These are plain data carriers; behaviour lives in [`crate`]. They are kept in
-//! a dedicated module so `lib.rs` stays focused on the proxy logic.
+//! Records reporting on the proxy: its status, WARP diagnostics, update checks,
+//! batch results and recent errors.
 
-/// Result of a successful image fetch operation.
-#[derive(Clone, Debug, uniffi::Record)]
-pub struct ImageResponse {
-    /// MIME type of the image (e.g., "image/png", "image/svg+xml").
-    pub mime_type: String,
-    /// Raw image bytes.
-    pub data: Vec<u8>,
-    /// Whether this response was served from cache.
-    pub from_cache: bool,
-    /// Final URL after redirects (if any).
-    pub final_url: String,
-}
+use super::{ImageResponse, Transport};
+use crate::error::ErrorCode;
 
-/// Result of a generic tunnelled fetch (non-image content).
-#[derive(Clone, Debug, uniffi::Record)]
-pub struct HttpFetchResponse {
-    /// HTTP status code of the final response.
-    pub status: u16,
-    /// Normalised MIME type.
-    pub mime_type: String,
-    /// Raw response body.
-    pub data: Vec<u8>,
-    /// Final URL after redirects.
-    pub final_url: String,
+/// A failed operation, as kept for in-app diagnostics.
+#[derive(Clone, Debug, PartialEq, Eq, uniffi::Record)]
+pub struct RecentError {
+    /// When it failed, in milliseconds since the Unix epoch.
+    pub timestamp_ms: i64,
+    /// Host of the URL requested, if there was one (none for `data:` URIs).
+    pub host: Option<String>,
+    /// What went wrong.
+    pub code: ErrorCode,
+    /// The error message.
+    pub message: String,
+    /// The network path in use when it failed, if the network was up.
+    pub transport: Option<Transport>,
 }
 
 /// Status of the image proxy.
@@ -44,6 +34,10 @@ pub struct ProxyStatus {
     pub last_error: Option<String>,
     /// Number of cached images.
     pub cache_size: u32,
+    /// Number of images in the disk cache (0 when disabled).
+    pub disk_cache_entries: u32,
+    /// Bytes used by the disk cache (0 when disabled).
+    pub disk_cache_bytes: u64,
 }
 
 /// Full WireGuard/WARP diagnostics for the in-app debug screen.
@@ -154,4 +148,10 @@ pub struct BatchImageResult {
     pub response: Option<ImageResponse>,
     /// Error message if failed.
     pub error: Option<String>,
+    /// Kind of error if failed, for matching without parsing `error`.
+    pub error_code: Option<ErrorCode>,
+    /// Whether a failed fetch may succeed if tried again later.
+    pub retryable: bool,
+    /// HTTP status of a failed fetch, if the server answered.
+    pub http_status: Option<u16>,
 }
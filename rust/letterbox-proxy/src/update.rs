@@ -9,6 +9,7 @@ use crate::config::FetchLimits;
 use crate::error::ProxyError;
 use crate::http::FetchOutcome;
 use crate::tunnel::TunnelManager;
+use crate::types::RequestPriority;
 use serde::Deserialize;
 
 /// Canonical GitHub repository slug for the official distribution channel.
@@ -90,6 +91,7 @@ pub fn check_for_update(
     ];
     let limits = FetchLimits {
         max_size: 1024 * 1024, // release JSON is small
+        priority: RequestPriority::Background,
         ..FetchLimits::default()
     };
 
@@ -98,6 +100,7 @@ pub fn check_for_update(
         headers,
         "application/vnd.github+json".to_string(),
         limits,
+        None,
     )?;
 
     let release: GithubRelease =
@@ -0,0 +1,46 @@
+//! An upstream proxy of the user's own in place of WARP.
+//!
+//! Some users would rather route through a proxy they run or trust — Tor,
+//! a private SOCKS5 server, a corporate HTTP proxy — than through Cloudflare.
+//! With an [`UpstreamProxy`] set, the client's requests go through it
+//! ([`UpstreamNetwork`](crate::tunnel::upstream::UpstreamNetwork)) instead of
+//! the WARP tunnel, and no WARP identity is provisioned or refreshed. Every
+//! header, redirect and content control still applies, and responses report
+//! [`Transport::Upstream`](crate::types::Transport::Upstream).
+//!
+//! The setting lives in memory; the app sets it again after each start.
+
+use crate::client::{default_client, ProxyClient};
+use crate::error::ProxyError;
+use crate::tunnel::upstream::validate;
+use crate::types::UpstreamProxy;
+
+#[uniffi::export]
+impl ProxyClient {
+    /// Carry requests through `proxy` instead of WARP, or through WARP again
+    /// with `None`. A running tunnel or proxy network is torn down and the
+    /// next request takes the new path; requests already under way finish
+    /// on the old one.
+    pub fn set_upstream_proxy(&self, proxy: Option<UpstreamProxy>) -> Result<(), ProxyError> {
+        if let Some(proxy) = &proxy {
+            validate(proxy)?;
+        }
+        let manager = {
+            let mut guard = self.lock_state();
+            let state = guard.as_mut().ok_or(ProxyError::NotInitialized)?;
+            if state.config.upstream_proxy == proxy {
+                return Ok(());
+            }
+            state.config.upstream_proxy = proxy;
+            state.manager.take()
+        };
+        self.tunnel_dropped(manager, "upstream proxy changed");
+        Ok(())
+    }
+}
+
+/// [`ProxyClient::set_upstream_proxy`] on the default proxy.
+#[uniffi::export]
+pub fn proxy_set_upstream_proxy(proxy: Option<UpstreamProxy>) -> Result<(), ProxyError> {
+    default_client().set_upstream_proxy(proxy)
+}
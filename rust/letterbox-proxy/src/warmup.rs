@@ -0,0 +1,112 @@
+//! Tunnel warm-up.
+//!
+//! Bringing up the tunnel costs seconds on first use: WARP registration if
+//! there is no identity yet, then the WireGuard handshake, then a DoH lookup
+//! for each new image host. [`ProxyClient::warm_up`] pays that in the
+//! background, e.g. when the app starts or a message list opens, so the first
+//! visible image does not. After the handshake it resolves the configured
+//! warm-up hosts — typically the image CDNs the user's mail comes from — into
+//! the tunnel's DNS cache, at background priority.
+
+use crate::client::{default_client, ProxyClient};
+use crate::config::FetchLimits;
+use crate::error::ProxyError;
+use crate::tunnel::dns::is_valid_hostname;
+use crate::types::RequestPriority;
+use std::sync::Arc;
+
+impl ProxyClient {
+    /// Bring up the tunnel and resolve the warm-up hosts.
+    fn warm(&self) -> Result<(), ProxyError> {
+        let (manager, limits) = self.acquire_manager()?;
+        let hosts = {
+            let guard = self.lock_state();
+            let state = guard.as_ref().ok_or(ProxyError::NotInitialized)?;
+            state.config.warm_up_hosts.clone()
+        };
+        if hosts.is_empty() {
+            return Ok(());
+        }
+        let count = hosts.len();
+        let limits = FetchLimits {
+            priority: RequestPriority::Background,
+            ..limits
+        };
+        let resolved = manager.resolve(hosts, limits)?;
+        log::debug!("Warm-up resolved {resolved} of {count} hosts");
+        Ok(())
+    }
+}
+
+#[uniffi::export]
+impl ProxyClient {
+    /// Provision WARP if needed, perform the WireGuard handshake and resolve
+    /// the warm-up hosts, in the background.
+    ///
+    /// Returns immediately. Failures are not reported beyond the proxy's
+    /// status and event listener; the first fetch simply tries again. Nothing
+    /// happens while the network is paused.
+    pub fn warm_up(self: Arc<Self>) -> Result<(), ProxyError> {
+        {
+            let guard = self.lock_state();
+            let state = guard.as_ref().ok_or(ProxyError::NotInitialized)?;
+            if state.paused {
+                return Ok(());
+            }
+        }
+        let client = Arc::clone(&self);
+        std::thread::Builder::new()
+            .name("letterbox-warm-up".to_string())
+            .spawn(move || {
+                if let Err(e) = client.warm() {
+                    log::debug!("Warm-up failed: {e}");
+                    client.record_error(None, &e);
+                }
+            })
+            .map_err(|e| ProxyError::InitializationFailed {
+                details: format!("Failed to spawn warm-up thread: {e}"),
+            })?;
+        Ok(())
+    }
+
+    /// Replace the hosts [`Self::warm_up`] resolves ahead of time.
+    ///
+    /// Names are lowercased; anything that is not a valid hostname or IPv4
+    /// address is dropped.
+    pub fn set_warm_up_hosts(&self, hosts: Vec<String>) -> Result<(), ProxyError> {
+        let hosts = hosts
+            .iter()
+            .map(|host| host.trim().trim_end_matches('.').to_ascii_lowercase())
+            .filter(|host| is_valid_hostname(host))
+            .collect();
+        let mut guard = self.lock_state();
+        let state = guard.as_mut().ok_or(ProxyError::NotInitialized)?;
+        state.config.warm_up_hosts = hosts;
+        Ok(())
+    }
+}
+
+/// [`ProxyClient::warm_up`] on the default proxy.
+#[uniffi::export]
+pub fn proxy_warm_up() -> Result<(), ProxyError> {
+    Arc::clone(default_client()).warm_up()
+}
+
+/// [`ProxyClient::set_warm_up_hosts`] on the default proxy.
+#[uniffi::export]
+pub fn proxy_set_warm_up_hosts(hosts: Vec<String>) -> Result<(), ProxyError> {
+    default_client().set_warm_up_hosts(hosts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tunnel::TunnelManager;
+
+    #[test]
+    fn resolve_counts_the_hosts_it_could_resolve() {
+        let manager = TunnelManager::direct().unwrap();
+        let hosts = vec!["127.0.0.1".to_string(), "bad host".to_string()];
+        assert_eq!(manager.resolve(hosts, FetchLimits::default()).unwrap(), 1);
+    }
+}
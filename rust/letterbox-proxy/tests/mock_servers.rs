@@ -0,0 +1,289 @@
+//! End-to-end tests against the in-process servers of `test_support`.
+//!
+//! Provisioning talks to a fake WARP API and images come from a local HTTPS
+//! server whose certificate only `mock` builds trust, so nothing leaves the
+//! machine. Run them with:
+//!
+//! ```bash
+//! cargo test -p letterbox-proxy --features mock --test mock_servers
+//! ```
+
+#![cfg(feature = "mock")]
+
+use letterbox_proxy::test_support::{png, use_direct_network, MockServer, Route};
+use letterbox_proxy::{
+    ErrorCode, ProxyClient, ProxyError, RedirectPolicy, RefererGrant, SrcsetCandidate, Transport,
+};
+use std::sync::Arc;
+
+/// A client connecting directly, built outside any runtime: initialising it
+/// blocks on one of its own.
+fn client(dir: &tempfile::TempDir) -> Arc<ProxyClient> {
+    let client = ProxyClient::new(dir.path().to_string_lossy().into_owned(), 16).unwrap();
+    use_direct_network(&client).unwrap();
+    client
+}
+
+fn block_on<F: std::future::Future>(future: F) -> F::Output {
+    tokio::runtime::Runtime::new().unwrap().block_on(future)
+}
+
+#[test]
+fn fetches_and_caches_an_image_over_https() {
+    let image = png(4, 3);
+    let server = MockServer::start(vec![Route::ok("/a.png", "image/png", image.clone())]).unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let client = client(&dir);
+
+    let response = block_on(client.fetch_image(server.url("/a.png"), None, None, None)).unwrap();
+    assert_eq!(response.data, image);
+    assert_eq!(response.mime_type, "image/png");
+    assert_eq!((response.width, response.height), (Some(4), Some(3)));
+    assert_eq!(response.transport, Some(Transport::Direct));
+    assert!(!response.from_cache);
+
+    let cached = block_on(client.fetch_image(server.url("/a.png"), None, None, None)).unwrap();
+    assert!(cached.from_cache);
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].target, "/a.png");
+    assert!(requests[0].header("cookie").is_none());
+}
+
+#[test]
+fn follows_redirects_and_refuses_non_images() {
+    let server = MockServer::start(vec![
+        Route::new("GET", "/old.png", 302).header("Location", "/new.png"),
+        Route::ok("/new.png", "image/png", png(2, 2)),
+        Route::ok("/page", "text/html", b"<html></html>".to_vec()),
+    ])
+    .unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let client = client(&dir);
+    client
+        .set_redirect_policy(RedirectPolicy {
+            forbid_private_targets: false,
+            ..RedirectPolicy::default()
+        })
+        .unwrap();
+
+    let response = block_on(client.fetch_image(server.url("/old.png"), None, None, None)).unwrap();
+    assert_eq!(response.final_url, server.url("/new.png"));
+    assert_eq!(response.redirect_chain, vec![server.url("/old.png")]);
+
+    let refused = block_on(client.fetch_image(server.url("/page"), None, None, None));
+    assert!(matches!(
+        refused,
+        Err(ProxyError::InvalidContentType { .. })
+    ));
+
+    let missing = block_on(client.fetch_image(server.url("/gone.png"), None, None, None));
+    assert!(matches!(
+        missing,
+        Err(ProxyError::HttpError {
+            status_code: 404,
+            ..
+        })
+    ));
+
+    let errors = client.recent_errors();
+    assert_eq!(errors.len(), 2);
+    assert_eq!(errors[0].code, ErrorCode::HttpError);
+    assert_eq!(errors[0].host.as_deref(), Some("127.0.0.1"));
+    assert_eq!(errors[0].transport, Some(Transport::Direct));
+    assert_eq!(errors[1].code, ErrorCode::InvalidContentType);
+}
+
+#[test]
+fn sends_a_granted_referer_only_within_its_group() {
+    let server = MockServer::start(vec![
+        Route::ok("/a.png", "image/png", png(1, 1)),
+        Route::ok("/b.png", "image/png", png(1, 1)),
+    ])
+    .unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let client = client(&dir);
+    client
+        .set_group_referer(
+            "m1".to_string(),
+            Some(RefererGrant {
+                host: server.addr().ip().to_string(),
+                referer: Some("https://mail.example/m1".to_string()),
+                origin: Some("https://mail.example".to_string()),
+            }),
+        )
+        .unwrap();
+
+    let headers = [("Referer".to_string(), "https://leak.example/".to_string())];
+    block_on(client.fetch_image(
+        server.url("/a.png"),
+        Some(headers.clone().into()),
+        None,
+        Some("m1".to_string()),
+    ))
+    .unwrap();
+    block_on(client.fetch_image(server.url("/b.png"), Some(headers.into()), None, None)).unwrap();
+
+    let requests = server.requests();
+    assert_eq!(
+        requests[0].header("referer"),
+        Some("https://mail.example/m1")
+    );
+    assert_eq!(requests[0].header("origin"), Some("https://mail.example"));
+    assert_eq!(requests[1].header("referer"), None);
+    assert_eq!(requests[1].header("origin"), None);
+}
+
+#[test]
+fn fetches_the_smallest_sharp_enough_srcset_candidate() {
+    let (small, large) = (png(1600, 1), png(800, 800));
+    let server = MockServer::start(vec![
+        Route::new("HEAD", "/800.png", 200)
+            .header("Content-Type", "image/png")
+            .body(large),
+        Route::new("HEAD", "/1600.png", 200)
+            .header("Content-Type", "image/png")
+            .body(small.clone()),
+        Route::ok("/1600.png", "image/png", small.clone()),
+    ])
+    .unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let client = client(&dir);
+    let candidate = |path: &str, width| SrcsetCandidate {
+        url: server.url(path),
+        width: Some(width),
+        density: None,
+    };
+    let sources = vec![
+        candidate("/400.png", 400),
+        candidate("/800.png", 800),
+        candidate("/1600.png", 1600),
+    ];
+
+    // 360 CSS pixels at 2x: 800w and 1600w are sharp enough, and 1600w is
+    // the fewer bytes; 400w does not probe at all.
+    let response = block_on(client.fetch_best_source(sources, 720, 2.0, true)).unwrap();
+    assert_eq!(response.final_url, server.url("/1600.png"));
+    assert_eq!(response.data, small);
+
+    let requests = server.requests();
+    let gets: Vec<_> = requests
+        .iter()
+        .filter(|request| request.method == "GET" && !request.target.starts_with("/400"))
+        .map(|request| request.target.as_str())
+        .collect();
+    assert_eq!(gets, ["/1600.png"]);
+}
+
+#[cfg(feature = "provisioning")]
+mod provisioning {
+    use letterbox_proxy::provisioning::WarpProvisioner;
+    use letterbox_proxy::test_support::{
+        png, route_provisioning_to, use_direct_network, MockServer, MockWarpApi, Route,
+    };
+    use letterbox_proxy::ProxyClient;
+    use std::sync::{Mutex, MutexGuard};
+
+    /// Held by tests that route provisioning, which is process-wide.
+    static PROVISIONING: Mutex<()> = Mutex::new(());
+
+    fn route_to(api: Option<&MockWarpApi>) -> MutexGuard<'static, ()> {
+        let guard = PROVISIONING.lock().unwrap_or_else(|e| e.into_inner());
+        route_provisioning_to(api);
+        guard
+    }
+
+    #[tokio::test]
+    async fn provisions_an_account_from_the_mock_api() {
+        let api = MockWarpApi::start().unwrap();
+        let provisioner = WarpProvisioner::with_api_base(&api.base_url()).unwrap();
+
+        let config = provisioner.provision_new_account().await.unwrap();
+        assert_eq!(config.account.account_id, MockWarpApi::DEVICE_ID);
+        assert_eq!(config.account.access_token, MockWarpApi::ACCESS_TOKEN);
+        assert_eq!(config.client_id, MockWarpApi::CLIENT_ID);
+        assert_eq!(config.peer.endpoint_host, "engage.cloudflareclient.com");
+        assert_eq!(config.peer.endpoint_port, 2408);
+        assert!(config.warp_enabled);
+        assert!(!config.account.private_key.is_empty());
+
+        let calls: Vec<_> = api
+            .server()
+            .requests()
+            .into_iter()
+            .map(|r| {
+                (
+                    r.method.clone(),
+                    r.header("authorization").map(str::to_string),
+                )
+            })
+            .collect();
+        let bearer = Some(format!("Bearer {}", MockWarpApi::ACCESS_TOKEN));
+        assert_eq!(
+            calls,
+            vec![
+                ("POST".to_string(), None),
+                ("GET".to_string(), bearer.clone()),
+                ("PATCH".to_string(), bearer),
+            ]
+        );
+        provisioner.tls_self_test().await.unwrap();
+    }
+
+    #[test]
+    fn identity_reset_provisions_through_the_client() {
+        let api = MockWarpApi::start().unwrap();
+        let routed = route_to(Some(&api));
+        let dir = tempfile::tempdir().unwrap();
+        let client = ProxyClient::new(dir.path().to_string_lossy().into_owned(), 16).unwrap();
+
+        let reset = client.reset_identity();
+        route_provisioning_to(None);
+        drop(routed);
+        let stored = reset.unwrap();
+        assert!(stored.has_config);
+        assert_eq!(stored.account_id, MockWarpApi::DEVICE_ID);
+        assert!(dir.path().join("warp_config.json").exists());
+    }
+
+    #[test]
+    fn refreshes_a_stale_configuration_before_fetching() {
+        let api = MockWarpApi::start().unwrap();
+        let image = MockServer::start(vec![Route::ok("/a.png", "image/png", png(2, 2))]).unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("warp_config.json");
+        let routed = route_to(Some(&api));
+
+        let client = ProxyClient::new(dir.path().to_string_lossy().into_owned(), 16).unwrap();
+        client.reset_identity().unwrap();
+        client.shutdown().unwrap();
+        let mut stored: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+        stored["last_updated"] = 0.into();
+        std::fs::write(&path, stored.to_string()).unwrap();
+
+        let client = ProxyClient::new(dir.path().to_string_lossy().into_owned(), 16).unwrap();
+        use_direct_network(&client).unwrap();
+        let requests_before = api.server().requests().len();
+        // Polled outside any tokio runtime, as Kotlin does: the refresh blocks
+        // on one of its own.
+        let fetched =
+            futures::executor::block_on(client.fetch_image(image.url("/a.png"), None, None, None));
+        route_provisioning_to(None);
+        drop(routed);
+        fetched.unwrap();
+
+        let refreshes: Vec<_> = api.server().requests()[requests_before..]
+            .iter()
+            .map(|r| (r.method.clone(), r.target.clone()))
+            .collect();
+        let device = format!("/v0a884/reg/{}", MockWarpApi::DEVICE_ID);
+        assert_eq!(refreshes, vec![("GET".to_string(), device)]);
+        let refreshed = client.stored_config().unwrap();
+        assert!(refreshed.last_updated_secs > 0);
+        let persisted: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+        assert_eq!(persisted["last_updated"], refreshed.last_updated_secs);
+    }
+}
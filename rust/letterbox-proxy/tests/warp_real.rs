@@ -14,6 +14,8 @@
 //! DNS-over-HTTPS -> TLS -> HTTP -> image validation, with the user's real IP
 //! never used for the image request.
 
+#![cfg(feature = "tunnel")]
+
 use letterbox_proxy::config::FetchLimits;
 use letterbox_proxy::provisioning::WarpProvisioner;
 use letterbox_proxy::tunnel::TunnelManager;
@@ -39,7 +41,8 @@ fn real_warp_tunnel_fetches_image() {
     // Ensure we always tear the device down, even if assertions panic.
     let result = std::panic::catch_unwind(|| {
         // 2. Bring up the real WireGuard tunnel and wait for the handshake.
-        let manager = TunnelManager::start(config.clone()).expect("start tunnel");
+        let manager =
+            TunnelManager::start(config.clone(), Default::default()).expect("start tunnel");
 
         // 3. The tunnel must report a live session.
         let diagnostics = manager.diagnostics().expect("diagnostics");
@@ -60,6 +63,7 @@ fn real_warp_tunnel_fetches_image() {
                 Vec::new(),
                 "image/*".to_string(),
                 FetchLimits::default(),
+                None,
             )
             .expect("fetch image through tunnel");
 